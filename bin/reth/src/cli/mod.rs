@@ -6,8 +6,8 @@ use crate::{
         LogArgs,
     },
     commands::{
-        config_cmd, db, debug_cmd, dump_genesis, import, init_cmd, node, node::NoArgs, p2p,
-        recover, stage, test_vectors,
+        config_cmd, db, debug_cmd, dump_genesis, export_blocks, import, import_era, init_cmd,
+        init_state, node, node::NoArgs, p2p, prune, recover, stage, static_file, test_vectors,
     },
     core::cli::runner::CliRunner,
     version::{LONG_VERSION, SHORT_VERSION},
@@ -137,18 +137,28 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
         self.logs.log_file_directory =
             self.logs.log_file_directory.join(self.chain.chain.to_string());
 
+        // keep stdout clean for the output of the command
+        if let Commands::Db(command) = &self.command {
+            self.logs.log_stdout_to_stderr = command.prints_to_stdout();
+        }
+
         let _guard = self.init_tracing()?;
 
         let runner = CliRunner::default();
         match self.command {
-            Commands::Node(command) => {
-                runner.run_command_until_exit(|ctx| command.execute(ctx, launcher))
-            }
+            Commands::Node(command) => runner
+                .with_shutdown_timeout(command.shutdown.timeout)
+                .run_command_until_exit(|ctx| command.execute(ctx, launcher)),
             Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::InitState(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::ImportEra(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::ExportBlocks(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::DumpGenesis(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Db(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Stage(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::StaticFile(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::Prune(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::P2P(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::TestVectors(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::Config(command) => runner.run_until_ctrl_c(command.execute()),
@@ -176,9 +186,18 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Initialize the database from a genesis file.
     #[command(name = "init")]
     Init(init_cmd::InitCommand),
+    /// Initialize the database from a state dump file.
+    #[command(name = "init-state")]
+    InitState(init_state::InitStateCommand),
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(import::ImportCommand),
+    /// This syncs the blocks of a directory of era1 files.
+    #[command(name = "import-era")]
+    ImportEra(import_era::ImportEraCommand),
+    /// Exports the canonical chain to a file of RLP encoded blocks.
+    #[command(name = "export-blocks")]
+    ExportBlocks(export_blocks::ExportBlocksCommand),
     /// Dumps genesis block JSON configuration to stdout.
     DumpGenesis(dump_genesis::DumpGenesisCommand),
     /// Database debugging utilities
@@ -187,6 +206,12 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Manipulate individual stages.
     #[command(name = "stage")]
     Stage(stage::Command),
+    /// Static file maintenance utilities
+    #[command(name = "static-file")]
+    StaticFile(static_file::Command),
+    /// Prune the database according to the prune configuration
+    #[command(name = "prune")]
+    Prune(prune::PruneCommand),
     /// P2P Debugging utilities
     #[command(name = "p2p")]
     P2P(p2p::Command),
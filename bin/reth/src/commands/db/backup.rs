@@ -0,0 +1,604 @@
+use crate::utils::DbTool;
+use clap::Parser;
+use eyre::WrapErr;
+use human_bytes::human_bytes;
+use reth_db::{
+    cursor::DbCursorRO,
+    database::Database,
+    mdbx::DatabaseArguments,
+    open_db_read_only,
+    static_file::iter_static_files,
+    transaction::DbTx,
+    version::{db_version_file_path, get_db_version},
+    DatabaseEnv, StageCheckpoints,
+};
+use reth_nippy_jar::NippyJar;
+use reth_primitives::{
+    stage::StageId,
+    static_file::{find_fixed_range, SegmentHeader, SegmentRangeInclusive},
+    BlockNumber, StaticFileSegment,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// The name of the data file of an MDBX environment.
+const MDBX_DATA_FILE: &str = "mdbx.dat";
+
+/// The name of the manifest file of a backup.
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// The interval at which the progress of the backup is logged.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The size of the buffer that is used to copy the files.
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// The extension of the configuration file of a static file.
+const CONFIG_FILE_EXTENSION: &str = "conf";
+
+/// The size of an offset of a static file that can still be appended to.
+const MUTABLE_OFFSET_SIZE: u64 = 8;
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db backup` command
+pub struct Command {
+    /// The directory the backup is written to, which must be empty or not exist.
+    #[arg(long, value_name = "DIR")]
+    output: PathBuf,
+
+    /// The maximum rate at which the backup is written, in megabytes per second.
+    ///
+    /// Limiting the rate avoids starving a node that's running on the database.
+    #[arg(long, value_name = "MBPS")]
+    max_mbps: Option<u64>,
+}
+
+impl Command {
+    /// Execute `db backup` command
+    pub fn execute(
+        self,
+        db_path: &Path,
+        tool: &DbTool<DatabaseEnv>,
+        db_args: DatabaseArguments,
+    ) -> eyre::Result<()> {
+        let backup = BackupPaths::new(&self.output);
+        if self.output.exists() {
+            eyre::ensure!(
+                fs::read_dir(&self.output)?.next().is_none(),
+                "The backup directory {} is not empty",
+                self.output.display()
+            );
+        }
+        fs::create_dir_all(&backup.db)?;
+        fs::create_dir_all(&backup.static_files)?;
+
+        let mut throttle = Throttle::new(self.max_mbps);
+        let db = tool.provider_factory.db_ref();
+
+        // the copy is made within a single read transaction, so it's a consistent snapshot even
+        // if the node keeps writing to the database
+        info!("Copying the database to {}.", backup.db.display());
+        copy_env(db, &backup.db.join(MDBX_DATA_FILE), &mut throttle)?;
+        let version_file = db_version_file_path(db_path);
+        if version_file.exists() {
+            fs::copy(version_file, db_version_file_path(&backup.db))?;
+        }
+
+        // the static files are snapshotted after the database is copied, so they're at least as
+        // far as the stage checkpoints of the copied database. Data above the checkpoints is
+        // removed by the consistency check of the static files when a node is started on the
+        // backup.
+        let static_file_provider = tool.provider_factory.static_file_provider();
+        let source = static_file_provider.directory();
+        let snapshots = StaticFileSnapshot::take_all(source)?;
+        info!("Copying the static files to {}.", backup.static_files.display());
+        copy_static_files(source, &snapshots, &backup.static_files, &mut throttle)?;
+
+        // the database part is read from the copy, since the copy is the snapshot of the
+        // database, and the static files part from the snapshots of the source
+        let manifest = BackupManifest::new(
+            &backup.db,
+            db_args.clone(),
+            snapshots.into_iter().filter_map(|snapshot| snapshot.manifest).collect(),
+        )?;
+        manifest.verify(&backup, db_args)?;
+        manifest.write(&self.output)?;
+
+        info!(
+            "Backed up the database at block {} ({}) to {}.",
+            manifest.tip,
+            human_bytes(throttle.bytes as f64),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+/// The paths of the database and static files of a backup or datadir.
+#[derive(Debug)]
+pub(crate) struct BackupPaths {
+    pub(crate) db: PathBuf,
+    pub(crate) static_files: PathBuf,
+}
+
+impl BackupPaths {
+    /// Returns the paths of the backup in the given directory.
+    pub(crate) fn new(dir: &Path) -> Self {
+        Self { db: dir.join("db"), static_files: dir.join("static_files") }
+    }
+}
+
+/// The manifest of a backup, which describes the copied data so it can be validated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct BackupManifest {
+    /// The version of the database, if it has a version file.
+    pub(crate) db_version: Option<u64>,
+    /// The block of the [StageId::Finish] checkpoint.
+    pub(crate) tip: BlockNumber,
+    /// The block numbers of the stage checkpoints.
+    pub(crate) stage_checkpoints: BTreeMap<String, BlockNumber>,
+    /// The static files, in order.
+    pub(crate) static_files: Vec<StaticFileManifest>,
+}
+
+/// A static file in a [BackupManifest].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct StaticFileManifest {
+    pub(crate) segment: StaticFileSegment,
+    pub(crate) block_range: SegmentRangeInclusive,
+    pub(crate) tx_range: Option<SegmentRangeInclusive>,
+    /// The sizes of the files of the static file, by file name.
+    pub(crate) files: BTreeMap<String, u64>,
+}
+
+impl BackupManifest {
+    /// Creates the manifest of the given database and static files.
+    pub(crate) fn read(
+        db_path: &Path,
+        static_files_path: &Path,
+        db_args: DatabaseArguments,
+    ) -> eyre::Result<Self> {
+        let mut static_files = Vec::new();
+        for (segment, ranges) in iter_static_files(static_files_path)? {
+            for (block_range, tx_range) in ranges {
+                let name = segment.filename(&find_fixed_range(block_range.end()));
+                let mut files = BTreeMap::new();
+                for entry in fs::read_dir(static_files_path)? {
+                    let entry = entry?;
+                    let file_name = entry.file_name().to_string_lossy().to_string();
+                    if Path::new(&file_name).file_stem().map_or(false, |stem| stem == name.as_str())
+                    {
+                        files.insert(file_name, entry.metadata()?.len());
+                    }
+                }
+                static_files.push(StaticFileManifest { segment, block_range, tx_range, files });
+            }
+        }
+        Self::new(db_path, db_args, static_files)
+    }
+
+    /// Creates the manifest of the given database and the given static files.
+    fn new(
+        db_path: &Path,
+        db_args: DatabaseArguments,
+        mut static_files: Vec<StaticFileManifest>,
+    ) -> eyre::Result<Self> {
+        let db_version = get_db_version(db_path).ok();
+
+        let db = open_db_read_only(db_path, db_args)?;
+        let stage_checkpoints = db.view(|tx| {
+            let mut cursor = tx.cursor_read::<StageCheckpoints>()?;
+            let walker = cursor.walk(None)?;
+            walker
+                .map(|entry| entry.map(|(stage, checkpoint)| (stage, checkpoint.block_number)))
+                .collect::<Result<BTreeMap<_, _>, _>>()
+        })??;
+        let tip = stage_checkpoints.get(StageId::Finish.as_str()).copied().unwrap_or_default();
+
+        static_files.sort_unstable_by_key(|static_file| {
+            (static_file.segment, static_file.block_range.start())
+        });
+
+        Ok(Self { db_version, tip, stage_checkpoints, static_files })
+    }
+
+    /// Loads the manifest of the backup in the given directory.
+    pub(crate) fn load(dir: &Path) -> eyre::Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        let file = File::open(&path)
+            .wrap_err_with(|| format!("Could not open the manifest {}", path.display()))?;
+        Ok(serde_json::from_reader(file)?)
+    }
+
+    /// Writes the manifest to the backup in the given directory.
+    fn write(&self, dir: &Path) -> eyre::Result<()> {
+        fs::write(dir.join(MANIFEST_FILE), serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Verifies that the given database and static files match the manifest.
+    pub(crate) fn verify(
+        &self,
+        paths: &BackupPaths,
+        db_args: DatabaseArguments,
+    ) -> eyre::Result<()> {
+        let actual = Self::read(&paths.db, &paths.static_files, db_args)?;
+        eyre::ensure!(
+            actual.db_version == self.db_version,
+            "Database version {:?} does not match the manifest version {:?}",
+            actual.db_version,
+            self.db_version
+        );
+        eyre::ensure!(
+            actual.stage_checkpoints == self.stage_checkpoints,
+            "Stage checkpoints {:?} do not match the manifest checkpoints {:?}",
+            actual.stage_checkpoints,
+            self.stage_checkpoints
+        );
+        eyre::ensure!(
+            actual.static_files == self.static_files,
+            "The static files in {} do not match the manifest",
+            paths.static_files.display()
+        );
+
+        // the static files that are written before the corresponding stages must not be behind
+        for (segment, stage) in [
+            (StaticFileSegment::Headers, StageId::Headers),
+            (StaticFileSegment::Transactions, StageId::Bodies),
+        ] {
+            let checkpoint =
+                self.stage_checkpoints.get(stage.as_str()).copied().unwrap_or_default();
+            let highest = self
+                .static_files
+                .iter()
+                .filter(|static_file| static_file.segment == segment)
+                .map(|static_file| static_file.block_range.end())
+                .max();
+            eyre::ensure!(
+                checkpoint == 0 || highest.is_some_and(|highest| highest >= checkpoint),
+                "The {segment} static files end at block {highest:?}, below the {stage} checkpoint \
+                 {checkpoint}"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Limits the rate at which bytes are written.
+#[derive(Debug)]
+pub(crate) struct Throttle {
+    /// The maximum number of bytes per second, if any.
+    max_bytes_per_sec: Option<u64>,
+    started_at: Instant,
+    last_logged_at: Instant,
+    /// The number of bytes written so far.
+    bytes: u64,
+}
+
+impl Throttle {
+    /// Creates a new throttle with the given maximum rate in megabytes per second.
+    pub(crate) fn new(max_mbps: Option<u64>) -> Self {
+        let now = Instant::now();
+        Self {
+            max_bytes_per_sec: max_mbps.map(|mbps| mbps.max(1) * 1024 * 1024),
+            started_at: now,
+            last_logged_at: now,
+            bytes: 0,
+        }
+    }
+
+    /// Records the given number of written bytes, and blocks until they're within the rate.
+    fn consume(&mut self, bytes: usize) {
+        self.bytes += bytes as u64;
+        if self.last_logged_at.elapsed() >= PROGRESS_INTERVAL {
+            self.last_logged_at = Instant::now();
+            info!("Copied {} so far.", human_bytes(self.bytes as f64));
+        }
+
+        let delay = self.delay(self.started_at.elapsed());
+        if !delay.is_zero() {
+            thread::sleep(delay);
+        }
+    }
+
+    /// Returns how long to wait so the written bytes are within the rate after the given time.
+    fn delay(&self, elapsed: Duration) -> Duration {
+        self.max_bytes_per_sec.map_or(Duration::ZERO, |max| {
+            Duration::from_secs_f64(self.bytes as f64 / max as f64).saturating_sub(elapsed)
+        })
+    }
+
+    /// Copies the reader into the writer.
+    fn copy(&mut self, reader: &mut impl Read, writer: &mut impl Write) -> eyre::Result<()> {
+        let mut buf = vec![0; COPY_BUFFER_SIZE];
+        loop {
+            let read = reader.read(&mut buf)?;
+            if read == 0 {
+                return Ok(())
+            }
+            writer.write_all(&buf[..read])?;
+            self.consume(read);
+        }
+    }
+}
+
+/// Copies the environment into the given data file, throttled by streaming the copy through a
+/// socket.
+#[cfg(unix)]
+fn copy_env(db: &DatabaseEnv, dest: &Path, throttle: &mut Throttle) -> eyre::Result<()> {
+    use std::os::{fd::AsRawFd, unix::net::UnixStream};
+
+    let mut file = OpenOptions::new().write(true).create_new(true).open(dest)?;
+    let (writer, mut reader) = UnixStream::pair()?;
+    thread::scope(|scope| {
+        let copy = scope.spawn(move || {
+            let res = db.copy_to_fd(writer.as_raw_fd(), true);
+            // closing the socket ends the copy on the other side
+            drop(writer);
+            res
+        });
+
+        let res = throttle.copy(&mut reader, &mut file);
+        // unblocks the copy if writing the file failed
+        drop(reader);
+        copy.join().map_err(|_| eyre::eyre!("The database copy panicked"))??;
+        res
+    })?;
+    file.sync_all()?;
+    Ok(())
+}
+
+/// Copies the environment into the given data file.
+#[cfg(not(unix))]
+fn copy_env(db: &DatabaseEnv, dest: &Path, throttle: &mut Throttle) -> eyre::Result<()> {
+    if throttle.max_bytes_per_sec.is_some() {
+        tracing::warn!("The database copy can only be throttled on unix.");
+    }
+    Ok(db.copy(dest, true)?)
+}
+
+/// A snapshot of a static file, which is taken before it's copied.
+///
+/// The node may keep appending to the highest static file of a segment while it's copied, and it
+/// writes the configuration last. So the configuration is read first, and the other files are only
+/// copied up to the lengths the configuration refers to.
+#[derive(Debug)]
+struct StaticFileSnapshot {
+    /// The name of the configuration file.
+    config_name: String,
+    /// The contents of the configuration file.
+    config: Vec<u8>,
+    /// The lengths the files of the static file are copied with, by file name.
+    files: BTreeMap<String, u64>,
+    /// The manifest of the static file, if it has a block range.
+    manifest: Option<StaticFileManifest>,
+}
+
+impl StaticFileSnapshot {
+    /// Takes the snapshots of the static files in the given directory.
+    fn take_all(dir: &Path) -> eyre::Result<Vec<Self>> {
+        let mut snapshots = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if !entry.metadata()?.is_file() {
+                continue
+            }
+            if let Some((segment, _)) =
+                StaticFileSegment::parse_filename(&entry.file_name().to_string_lossy())
+            {
+                snapshots.push(Self::take(&entry.path(), segment)?);
+            }
+        }
+        Ok(snapshots)
+    }
+
+    /// Takes the snapshot of the static file with the given data file.
+    fn take(path: &Path, segment: StaticFileSegment) -> eyre::Result<Self> {
+        let config = fs::read(path.with_extension(CONFIG_FILE_EXTENSION))?;
+        let jar = NippyJar::<SegmentHeader>::load_from_config(path, &config)?;
+
+        let offsets_path = jar.offsets_path();
+        let mut offsets = File::open(&offsets_path)?;
+        let mut offset_size = [0; 1];
+        offsets.read_exact(&mut offset_size)?;
+        let (offsets_len, data_len) = if offset_size[0] as u64 == MUTABLE_OFFSET_SIZE {
+            // one offset per column of each row, and the length of the data file
+            let offsets_len = 1 +
+                MUTABLE_OFFSET_SIZE * jar.rows() as u64 * jar.columns() as u64 +
+                MUTABLE_OFFSET_SIZE;
+            offsets.seek(SeekFrom::Start(offsets_len - MUTABLE_OFFSET_SIZE))?;
+            let mut data_len = [0; MUTABLE_OFFSET_SIZE as usize];
+            offsets.read_exact(&mut data_len)?;
+            (offsets_len, u64::from_le_bytes(data_len))
+        } else {
+            // the static file was frozen and can't be appended to anymore
+            (offsets.metadata()?.len(), fs::metadata(path)?.len())
+        };
+
+        let config_name = file_name(&jar.config_path());
+        let mut files = BTreeMap::from([
+            (file_name(path), data_len),
+            (file_name(&offsets_path), offsets_len),
+            (config_name.clone(), config.len() as u64),
+        ]);
+        let index_path = jar.index_path();
+        if index_path.exists() {
+            files.insert(file_name(&index_path), fs::metadata(&index_path)?.len());
+        }
+
+        let header = jar.user_header();
+        let manifest = header.block_range().map(|block_range| StaticFileManifest {
+            segment,
+            block_range: *block_range,
+            tx_range: header.tx_range().copied(),
+            files: files.clone(),
+        });
+        Ok(Self { config_name, config, files, manifest })
+    }
+}
+
+/// Returns the name of the file at the given path.
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+/// Copies the static files of the snapshots, and the other files of the source directory, into the
+/// destination directory.
+fn copy_static_files(
+    source: &Path,
+    snapshots: &[StaticFileSnapshot],
+    dest: &Path,
+    throttle: &mut Throttle,
+) -> eyre::Result<()> {
+    let mut copied = HashSet::new();
+    for snapshot in snapshots {
+        for (name, len) in &snapshot.files {
+            if *name == snapshot.config_name {
+                let mut writer =
+                    OpenOptions::new().write(true).create_new(true).open(dest.join(name))?;
+                throttle.copy(&mut snapshot.config.as_slice(), &mut writer)?;
+                writer.sync_all()?;
+            } else {
+                copy_file(&source.join(name), &dest.join(name), Some(*len), throttle)?;
+            }
+            copied.insert(name.clone());
+        }
+    }
+
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.metadata()?.is_file() && !copied.contains(&name) {
+            copy_file(&entry.path(), &dest.join(&name), None, throttle)?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies the files of the source directory into the destination directory.
+///
+/// The configuration files are copied first, so the copy of the configuration of a static file
+/// never refers to data that's not copied.
+pub(crate) fn copy_files(source: &Path, dest: &Path, throttle: &mut Throttle) -> eyre::Result<()> {
+    let mut files = fs::read_dir(source)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    files.retain(|path| path.is_file());
+    files.sort_unstable_by_key(|path| {
+        path.extension().map_or(true, |ext| ext != CONFIG_FILE_EXTENSION)
+    });
+
+    for path in files {
+        let Some(file_name) = path.file_name() else { continue };
+        copy_file(&path, &dest.join(file_name), None, throttle)?;
+    }
+    Ok(())
+}
+
+/// Copies the file, or only its first bytes if a length is given.
+fn copy_file(
+    source: &Path,
+    dest: &Path,
+    len: Option<u64>,
+    throttle: &mut Throttle,
+) -> eyre::Result<()> {
+    let mut reader = File::open(source)?;
+    let mut writer = OpenOptions::new().write(true).create_new(true).open(dest)?;
+    match len {
+        Some(len) => {
+            throttle.copy(&mut (&mut reader).take(len), &mut writer)?;
+            eyre::ensure!(
+                writer.metadata()?.len() == len,
+                "{} was truncated while it was copied",
+                source.display()
+            );
+        }
+        None => throttle.copy(&mut reader, &mut writer)?,
+    }
+    writer.sync_all()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_nippy_jar::NippyJarWriter;
+
+    #[test]
+    fn throttle_delay() {
+        let mut throttle = Throttle::new(Some(1));
+        throttle.bytes = 2 * 1024 * 1024;
+        assert_eq!(throttle.delay(Duration::from_millis(500)), Duration::from_millis(1500));
+        assert_eq!(throttle.delay(Duration::from_secs(3)), Duration::ZERO);
+
+        let mut unlimited = Throttle::new(None);
+        unlimited.bytes = u64::MAX;
+        assert_eq!(unlimited.delay(Duration::ZERO), Duration::ZERO);
+    }
+
+    #[test]
+    fn copy_files_config_first() {
+        let source = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        for name in ["static_file_headers_0_499999", "static_file_headers_0_499999.conf"] {
+            fs::write(source.path().join(name), name).unwrap();
+        }
+
+        let mut throttle = Throttle::new(None);
+        copy_files(source.path(), dest.path(), &mut throttle).unwrap();
+        assert_eq!(
+            fs::read(dest.path().join("static_file_headers_0_499999.conf")).unwrap(),
+            b"static_file_headers_0_499999.conf"
+        );
+        assert_eq!(throttle.bytes, 28 + 33);
+    }
+
+    #[test]
+    fn snapshot_ignores_uncommitted_append() {
+        let source = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        let path = source.path().join("static_file_headers_0_499999");
+        let header = SegmentHeader::new(
+            SegmentRangeInclusive::new(0, 499_999),
+            Some(SegmentRangeInclusive::new(0, 1)),
+            None,
+            StaticFileSegment::Headers,
+        );
+        let mut writer = NippyJarWriter::new(NippyJar::new(1, &path, header)).unwrap();
+        for value in [b"ab", b"cd"] {
+            writer.append_column(Some(Ok(value))).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        // a row that is appended while the static file is copied, before the commit
+        let mut data = OpenOptions::new().append(true).open(&path).unwrap();
+        data.write_all(b"ef").unwrap();
+        let mut offsets = OpenOptions::new().append(true).open(path.with_extension("off")).unwrap();
+        offsets.write_all(&6u64.to_le_bytes()).unwrap();
+
+        let snapshots = StaticFileSnapshot::take_all(source.path()).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        let manifest = snapshots[0].manifest.clone().unwrap();
+        assert_eq!(manifest.block_range, SegmentRangeInclusive::new(0, 1));
+        assert_eq!(manifest.files["static_file_headers_0_499999"], 4);
+        assert_eq!(manifest.files["static_file_headers_0_499999.off"], 1 + 8 * 2 + 8);
+
+        let mut throttle = Throttle::new(None);
+        copy_static_files(source.path(), &snapshots, dest.path(), &mut throttle).unwrap();
+        assert_eq!(fs::read(dest.path().join("static_file_headers_0_499999")).unwrap(), b"abcd");
+        assert_eq!(
+            fs::read(dest.path().join("static_file_headers_0_499999.conf")).unwrap(),
+            fs::read(path.with_extension("conf")).unwrap()
+        );
+    }
+}
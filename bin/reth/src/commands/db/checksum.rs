@@ -1,75 +1,1137 @@
-use crate::utils::DbTool;
+use crate::{
+    commands::db::get::{maybe_json_value_parser, table_key},
+    utils::DbTool,
+};
 use ahash::AHasher;
 use clap::Parser;
+use eyre::WrapErr;
+use rayon::prelude::*;
 use reth_db::{
-    cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx, DatabaseEnv, RawKey,
-    RawTable, RawValue, TableViewer, Tables,
+    cursor::DbCursorRO,
+    database::Database,
+    static_file::TransactionSenderMask,
+    table::{Compress, Decode, Encode, Table},
+    transaction::DbTx,
+    DatabaseEnv, RawKey, RawTable, RawValue, TableViewer, Tables,
+};
+use reth_primitives::{Address, StaticFileSegment};
+use reth_provider::{
+    providers::StaticFileProvider, HeaderProvider, ReceiptProvider, TransactionsProvider,
 };
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
+    collections::BTreeMap,
+    fmt, fs,
     hash::Hasher,
+    ops::{Bound, RangeInclusive},
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 
 #[derive(Parser, Debug)]
 /// The arguments for the `reth db checksum` command
 pub struct Command {
     /// The table name
-    table: Tables,
+    #[arg(required_unless_present = "all")]
+    table: Option<Tables>,
+
+    /// Calculate the checksum of every table in the database
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "table",
+            "start_key",
+            "end_key",
+            "limit",
+            "checkpoint_file",
+            "expected",
+        ]
+    )]
+    all: bool,
+
+    /// The start of the range to checksum
+    #[arg(long, value_parser = maybe_json_value_parser)]
+    start_key: Option<String>,
+
+    /// The end of the range to checksum
+    #[arg(long, value_parser = maybe_json_value_parser)]
+    end_key: Option<String>,
+
+    /// The maximum number of records that are queried and used to compute the checksum
+    #[arg(long)]
+    limit: Option<usize>,
+
+    /// Print one JSON object per table to stdout instead of logging the result.
+    ///
+    /// The logs for stdout are written to stderr instead, so stdout only contains the JSON
+    /// objects.
+    #[arg(long)]
+    json: bool,
+
+    /// The number of tables to checksum concurrently when `--all` is set.
+    ///
+    /// Defaults to the number of available cores.
+    #[arg(long, requires = "all")]
+    jobs: Option<usize>,
+
+    /// Periodically write the progress of the checksum to this file, so that an interrupted run
+    /// can be continued with `--resume`.
+    ///
+    /// When set, the table is hashed in chunks of `--checkpoint-interval` entries and the final
+    /// checksum is computed over the chunk digests. The result is therefore only comparable to
+    /// other runs using the same chunk size.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    checkpoint_file: Option<PathBuf>,
+
+    /// The number of entries hashed per chunk, and between checkpoint writes
+    #[arg(long, requires = "checkpoint_file", default_value_t = DEFAULT_CHECKPOINT_INTERVAL)]
+    checkpoint_interval: usize,
+
+    /// Resume from the progress stored in `--checkpoint-file`
+    #[arg(long, requires = "checkpoint_file")]
+    resume: bool,
+
+    /// Include the entries that were moved to static files.
+    ///
+    /// Applies to the `Headers`, `Transactions` and `Receipts` tables. Entries are hashed in key
+    /// order with their database encoding, so the checksum doesn't depend on how far the data was
+    /// moved to static files.
+    #[arg(long)]
+    with_static_files: bool,
+
+    /// The expected checksum of the table in hex, exits with an error if the checksum is
+    /// different.
+    #[arg(
+        long,
+        value_name = "HEX",
+        value_parser = parse_checksum,
+        conflicts_with = "expected_file"
+    )]
+    expected: Option<u64>,
+
+    /// A file with the expected checksums, exits with an error unless the checksums of all
+    /// calculated tables match.
+    ///
+    /// Every line is either `<table>=<checksum>` with the checksum of the full table, or one of
+    /// the JSON objects printed with `--json`, which also records the range and the options the
+    /// checksum was calculated with. Empty lines and lines starting with `#` are ignored.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    expected_file: Option<PathBuf>,
 }
 
+/// The default number of entries hashed per checkpoint chunk.
+const DEFAULT_CHECKPOINT_INTERVAL: usize = 1_000_000;
+
+/// The number of entries read from static files at once.
+const STATIC_FILE_BATCH_SIZE: u64 = 10_000;
+
 impl Command {
+    /// Returns `true` if the output is printed as JSON to stdout.
+    pub(crate) fn prints_json(&self) -> bool {
+        self.json
+    }
+
     /// Execute `db checksum` command
     pub fn execute(self, tool: &DbTool<DatabaseEnv>) -> eyre::Result<()> {
         warn!("This command should be run without the node running!");
-        self.table.view(&ChecksumViewer { tool })
+
+        eyre::ensure!(self.checkpoint_interval > 0, "--checkpoint-interval must not be zero");
+        let checkpoint = self.checkpoint_file.map(|path| CheckpointArgs {
+            path,
+            interval: self.checkpoint_interval,
+            resume: self.resume,
+        });
+        let viewer = ChecksumViewer {
+            tool,
+            start_key: self.start_key,
+            end_key: self.end_key,
+            limit: self.limit,
+            checkpoint,
+            with_static_files: self.with_static_files,
+        };
+        let expected = self.expected_file.as_deref().map(ExpectedChecksums::load).transpose()?;
+
+        if self.all {
+            return viewer.checksum_all(self.jobs, self.json, expected.as_ref())
+        }
+
+        let table = self.table.expect("table is required unless `--all` is set");
+        // the expected checksum of the argument is for the range of this invocation
+        let expected = match self.expected {
+            Some(checksum) => Some(ExpectedChecksums::from_iter([(
+                table.name().to_string(),
+                ExpectedChecksum { checksum, scope: viewer.scope() },
+            )])),
+            None => expected,
+        };
+
+        let result = table.view(&viewer)?;
+        result.report(self.json)?;
+        match expected {
+            Some(expected) => {
+                ChecksumReport(vec![(result.table, expected.compare(result.table, Ok(&result)))])
+                    .ensure_matches()
+            }
+            None => Ok(()),
+        }
     }
 }
 
+/// The result of checksumming a single table.
+#[derive(Debug, Serialize)]
+pub(crate) struct ChecksumResult {
+    /// The name of the table
+    pub(crate) table: &'static str,
+    /// The checksum over all enumerated raw keys and values
+    #[serde(serialize_with = "serialize_hex")]
+    pub(crate) checksum: u64,
+    /// The number of entries that were hashed
+    pub(crate) entries: usize,
+    /// The time it took to calculate the checksum
+    #[serde(rename = "elapsed_ms", serialize_with = "serialize_millis")]
+    pub(crate) elapsed: Duration,
+    /// The first key that was hashed, in the JSON encoding accepted by `--start-key`
+    pub(crate) start_key: Option<serde_json::Value>,
+    /// The last key that was hashed, in the JSON encoding accepted by `--end-key`
+    pub(crate) end_key: Option<serde_json::Value>,
+    /// The range and options the checksum was calculated with
+    #[serde(flatten)]
+    pub(crate) scope: ChecksumScope,
+}
+
+impl ChecksumResult {
+    /// Prints the result as a single JSON line to stdout, or logs it.
+    fn report(&self, json: bool) -> eyre::Result<()> {
+        if json {
+            println!("{}", serde_json::to_string(self)?);
+        } else {
+            info!(
+                "Checksum for table `{}`: {:#x} (entries: {}, elapsed: {:?})",
+                self.table, self.checksum, self.entries, self.elapsed
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The range of a table and the options that a checksum was calculated with.
+///
+/// Checksums are only comparable if they were calculated with the same scope. The scope of a full
+/// table checksum is the default, and none of its fields are serialized.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ChecksumScope {
+    /// The `--start-key` argument
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    range_start: Option<String>,
+    /// The `--end-key` argument
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    range_end: Option<String>,
+    /// The `--limit` argument
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+    /// Whether the entries in static files were included
+    #[serde(default, skip_serializing_if = "is_false")]
+    with_static_files: bool,
+    /// The number of entries per chunk, if the checksum was calculated over chunk digests
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chunk_size: Option<usize>,
+}
+
+impl fmt::Display for ChecksumScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.range_start.is_none() && self.range_end.is_none() && self.limit.is_none() {
+            f.write_str("the full table")?;
+        } else {
+            write!(
+                f,
+                "the range {}..={}",
+                self.range_start.as_deref().unwrap_or_default(),
+                self.range_end.as_deref().unwrap_or_default()
+            )?;
+            if let Some(limit) = self.limit {
+                write!(f, " limited to {limit} entries")?;
+            }
+        }
+        if self.with_static_files {
+            f.write_str(" with static files")?;
+        }
+        if let Some(chunk_size) = self.chunk_size {
+            write!(f, " in chunks of {chunk_size} entries")?;
+        }
+        Ok(())
+    }
+}
+
+/// The expected checksum of a table.
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+struct ExpectedChecksum {
+    /// The expected checksum
+    #[serde(deserialize_with = "deserialize_hex")]
+    checksum: u64,
+    /// The range and options the expected checksum was calculated with
+    #[serde(flatten)]
+    scope: ChecksumScope,
+}
+
+/// A line of the `--json` output in an expected checksums file.
+#[derive(Debug, Deserialize)]
+struct ExpectedChecksumLine {
+    /// The name of the table
+    table: String,
+    /// The expected checksum and its scope
+    #[serde(flatten)]
+    expected: ExpectedChecksum,
+}
+
+/// The expected checksums by table name, from `--expected` or `--expected-file`.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ExpectedChecksums(BTreeMap<String, ExpectedChecksum>);
+
+impl ExpectedChecksums {
+    /// Reads the expected checksums from the given file.
+    fn load(path: &Path) -> eyre::Result<Self> {
+        let contents = fs::read_to_string(path).wrap_err_with(|| {
+            format!("failed to read expected checksums file {}", path.display())
+        })?;
+        Self::parse(&contents)
+            .wrap_err_with(|| format!("invalid expected checksums file {}", path.display()))
+    }
+
+    /// Parses the expected checksums from `<table>=<checksum>` lines or the lines of the `--json`
+    /// output.
+    fn parse(contents: &str) -> eyre::Result<Self> {
+        let mut checksums = BTreeMap::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+
+            let (table, expected) = if line.starts_with('{') {
+                let ExpectedChecksumLine { table, expected } = serde_json::from_str(line)
+                    .wrap_err_with(|| format!("invalid JSON on line {}", index + 1))?;
+                (table, expected)
+            } else {
+                let (table, checksum) = line.split_once('=').ok_or_else(|| {
+                    eyre::eyre!("expected `<table>=<checksum>` on line {}", index + 1)
+                })?;
+                let checksum = parse_checksum(checksum.trim())
+                    .map_err(|err| eyre::eyre!("{err} on line {}", index + 1))?;
+                (table.trim().to_string(), ExpectedChecksum { checksum, scope: Default::default() })
+            };
+
+            table.parse::<Tables>().map_err(|err| eyre::eyre!("{err} on line {}", index + 1))?;
+            if checksums.insert(table.clone(), expected).is_some() {
+                eyre::bail!("duplicate checksum of table `{table}` on line {}", index + 1)
+            }
+        }
+
+        Ok(Self(checksums))
+    }
+
+    /// Compares the result of checksumming a table with its expected checksum.
+    fn compare(
+        &self,
+        table: &str,
+        result: Result<&ChecksumResult, &eyre::Report>,
+    ) -> ChecksumComparison {
+        let Some(expected) = self.0.get(table) else { return ChecksumComparison::Missing };
+        match result {
+            Err(err) => ChecksumComparison::Failed(err.to_string()),
+            Ok(result) if result.scope != expected.scope => ChecksumComparison::ScopeMismatch {
+                expected: expected.scope.clone(),
+                actual: result.scope.clone(),
+            },
+            Ok(result) if result.checksum != expected.checksum => ChecksumComparison::Mismatch {
+                expected: expected.checksum,
+                actual: result.checksum,
+            },
+            Ok(_) => ChecksumComparison::Match,
+        }
+    }
+}
+
+impl FromIterator<(String, ExpectedChecksum)> for ExpectedChecksums {
+    fn from_iter<I: IntoIterator<Item = (String, ExpectedChecksum)>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+/// The outcome of comparing the checksum of a table with its expected checksum.
+#[derive(Debug, PartialEq, Eq)]
+enum ChecksumComparison {
+    /// The checksum matches the expected checksum
+    Match,
+    /// The checksum is different from the expected checksum
+    Mismatch { expected: u64, actual: u64 },
+    /// The expected checksum was calculated with a different range or different options
+    ScopeMismatch { expected: ChecksumScope, actual: ChecksumScope },
+    /// There's no expected checksum of the table
+    Missing,
+    /// The checksum of the table couldn't be calculated
+    Failed(String),
+}
+
+impl fmt::Display for ChecksumComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Match => f.write_str("matches"),
+            Self::Mismatch { expected, actual } => {
+                write!(f, "expected {expected:#x}, found {actual:#x}")
+            }
+            Self::ScopeMismatch { expected, actual } => write!(
+                f,
+                "the expected checksum is for {expected}, can't compare it to the checksum of \
+                 {actual}"
+            ),
+            Self::Missing => f.write_str("no expected checksum"),
+            Self::Failed(err) => write!(f, "failed to calculate the checksum: {err}"),
+        }
+    }
+}
+
+/// The comparisons of the checksummed tables with their expected checksums.
+#[derive(Debug)]
+struct ChecksumReport(Vec<(&'static str, ChecksumComparison)>);
+
+impl ChecksumReport {
+    /// Returns an error listing every table that doesn't match its expected checksum.
+    fn ensure_matches(self) -> eyre::Result<()> {
+        let total = self.0.len();
+        let mismatches = self
+            .0
+            .into_iter()
+            .filter(|(_, comparison)| *comparison != ChecksumComparison::Match)
+            .map(|(table, comparison)| format!("\n  {table}: {comparison}"))
+            .collect::<Vec<_>>();
+        if !mismatches.is_empty() {
+            eyre::bail!(
+                "{} of {total} checksum(s) don't match the expected checksums:{}",
+                mismatches.len(),
+                mismatches.concat()
+            )
+        }
+
+        info!("All {total} checksum(s) match the expected checksums");
+        Ok(())
+    }
+}
+
+/// Parses a checksum in hex, with or without the `0x` prefix.
+fn parse_checksum(value: &str) -> Result<u64, String> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    u64::from_str_radix(digits, 16).map_err(|err| format!("invalid checksum {value:?}: {err}"))
+}
+
+fn deserialize_hex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_checksum(&value).map_err(serde::de::Error::custom)
+}
+
+const fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn serialize_hex<S: Serializer>(checksum: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{checksum:#x}"))
+}
+
+fn serialize_millis<S: Serializer>(elapsed: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u128(elapsed.as_millis())
+}
+
 pub(crate) struct ChecksumViewer<'a, DB: Database> {
     tool: &'a DbTool<DB>,
+    start_key: Option<String>,
+    end_key: Option<String>,
+    limit: Option<usize>,
+    checkpoint: Option<CheckpointArgs>,
+    with_static_files: bool,
 }
 
 impl<DB: Database> ChecksumViewer<'_, DB> {
     pub(crate) fn new(tool: &'_ DbTool<DB>) -> ChecksumViewer<'_, DB> {
-        ChecksumViewer { tool }
+        ChecksumViewer {
+            tool,
+            start_key: None,
+            end_key: None,
+            limit: None,
+            checkpoint: None,
+            with_static_files: false,
+        }
     }
 
-    pub(crate) fn get_checksum<T: Table>(&self) -> Result<(u64, Duration), eyre::Report> {
+    pub(crate) fn get_checksum<T: Table>(&self) -> Result<ChecksumResult, eyre::Report> {
         let provider =
             self.tool.provider_factory.provider()?.disable_long_read_transaction_safety();
         let tx = provider.tx_ref();
 
+        let mut checkpoint = match &self.checkpoint {
+            Some(args) if args.resume => {
+                let checkpoint = ChecksumCheckpoint::load(&args.path)?;
+                checkpoint.ensure_matches(self.expected_checkpoint::<T>(args))?;
+                info!(
+                    "Resuming checksum of table `{}` after {} entries.",
+                    T::NAME,
+                    checkpoint.entries
+                );
+                Some(checkpoint)
+            }
+            Some(args) => Some(self.expected_checkpoint::<T>(args)),
+            None => None,
+        };
+
+        let resumed_last_key =
+            checkpoint.as_ref().and_then(|checkpoint| checkpoint.last_key.clone());
+        let start = match resumed_last_key.clone() {
+            Some(last) => Bound::Excluded(RawKey::<T::Key>::new(serde_json::from_value(last)?)),
+            None => match self.start_key.as_deref() {
+                Some(start) => Bound::Included(RawKey::<T::Key>::new(table_key::<T>(start)?)),
+                None => Bound::Unbounded,
+            },
+        };
+        let end = match self.end_key.as_deref() {
+            Some(end) => Bound::Included(RawKey::<T::Key>::new(table_key::<T>(end)?)),
+            None => Bound::Unbounded,
+        };
+
         let mut cursor = tx.cursor_read::<RawTable<T>>()?;
-        let walker = cursor.walk(None)?;
+        let walker = self.entries::<T>(&mut cursor, start, end)?;
+
+        let resumed_entries = checkpoint.as_ref().map_or(0, |checkpoint| checkpoint.entries);
+        let remaining =
+            self.limit.map_or(usize::MAX, |limit| limit.saturating_sub(resumed_entries));
 
         let start_time = Instant::now();
         let mut hasher = AHasher::default();
-        for (index, entry) in walker.enumerate() {
-            let (k, v): (RawKey<T::Key>, RawValue<T::Value>) = entry?;
+        let mut entries = resumed_entries;
+        let mut chunk_entries = 0;
+        let mut first_key = checkpoint.as_ref().and_then(|checkpoint| checkpoint.first_key.clone());
+        let mut last_key = None;
+        for (index, entry) in walker.take(remaining).enumerate() {
+            let (k, v) = entry?;
 
             if index % 100_000 == 0 {
-                info!("Hashed {index} entries.");
+                info!("Hashed {} entries of table `{}`.", resumed_entries + index, T::NAME);
             }
 
             hasher.write(k.raw_key());
             hasher.write(v.raw_value());
+
+            entries += 1;
+            chunk_entries += 1;
+            if first_key.is_none() {
+                first_key = Some(encode_key::<T>(k.clone())?);
+            }
+
+            if let (Some(args), Some(checkpoint)) = (&self.checkpoint, &mut checkpoint) {
+                if chunk_entries == checkpoint.chunk_size {
+                    checkpoint.chunk_digests.push(std::mem::take(&mut hasher).finish());
+                    checkpoint.entries = entries;
+                    checkpoint.first_key = first_key.clone();
+                    checkpoint.last_key = Some(encode_key::<T>(k.clone())?);
+                    checkpoint.write(&args.path)?;
+                    chunk_entries = 0;
+                }
+            }
+
+            last_key = Some(k);
         }
 
-        let checksum = hasher.finish();
+        let checksum = match checkpoint {
+            Some(mut checkpoint) => {
+                if chunk_entries > 0 {
+                    checkpoint.chunk_digests.push(hasher.finish());
+                }
+                checkpoint.checksum()
+            }
+            None => hasher.finish(),
+        };
         let elapsed = start_time.elapsed();
 
-        Ok((checksum, elapsed))
+        // if nothing was left to hash after resuming, the last key is the one from the checkpoint
+        let end_key = last_key.map(encode_key::<T>).transpose()?.or(resumed_last_key);
+
+        Ok(ChecksumResult {
+            table: T::NAME,
+            checksum,
+            entries,
+            elapsed,
+            start_key: first_key,
+            end_key,
+            scope: self.scope(),
+        })
+    }
+
+    /// Returns the raw entries of table `T` within the range, in key order.
+    ///
+    /// If `--with-static-files` is set and the table has a static file segment, the entries up to
+    /// the highest static file block or transaction are read from the static files, and only the
+    /// entries above it from the database.
+    #[allow(clippy::type_complexity)]
+    fn entries<'c, T: Table>(
+        &self,
+        cursor: &'c mut impl DbCursorRO<RawTable<T>>,
+        start: Bound<RawKey<T::Key>>,
+        end: Bound<RawKey<T::Key>>,
+    ) -> eyre::Result<
+        Box<dyn Iterator<Item = eyre::Result<(RawKey<T::Key>, RawValue<T::Value>)>> + 'c>,
+    > {
+        let static_file_provider = self.tool.provider_factory.static_file_provider();
+        let highest = match static_file_segment(T::TABLE) {
+            Some(segment) if self.with_static_files => match segment {
+                StaticFileSegment::Headers => {
+                    static_file_provider.get_highest_static_file_block(segment)
+                }
+                StaticFileSegment::Transactions |
+                StaticFileSegment::Receipts |
+                StaticFileSegment::TransactionSenders => {
+                    static_file_provider.get_highest_static_file_tx(segment)
+                }
+            },
+            _ => None,
+        };
+
+        let Some(highest) = highest else {
+            return Ok(Box::new(
+                cursor.walk_range((start, end))?.map(|entry| entry.map_err(eyre::Report::from)),
+            ))
+        };
+
+        let first = match &start {
+            Bound::Included(key) => Some(u64::decode(key.raw_key())?),
+            Bound::Excluded(key) => u64::decode(key.raw_key())?.checked_add(1),
+            Bound::Unbounded => Some(0),
+        };
+        let last = match &end {
+            Bound::Included(key) => Some(u64::decode(key.raw_key())?.min(highest)),
+            Bound::Excluded(key) => {
+                u64::decode(key.raw_key())?.checked_sub(1).map(|last| last.min(highest))
+            }
+            Bound::Unbounded => Some(highest),
+        };
+
+        // the database range continues after the highest static file entry
+        let db_start = match first {
+            Some(first) if first > highest => start,
+            _ => Bound::Excluded(RawKey::from_vec(highest.encode().to_vec())),
+        };
+
+        // static file entries are read lazily in batches
+        let static_file_entries = first
+            .zip(last)
+            .into_iter()
+            .flat_map(|(first, last)| {
+                (first..=last).step_by(STATIC_FILE_BATCH_SIZE as usize).map(move |batch_start| {
+                    batch_start..=last.min(batch_start + STATIC_FILE_BATCH_SIZE - 1)
+                })
+            })
+            .flat_map(move |range| match static_file_entries::<T>(&static_file_provider, range) {
+                Ok(entries) => entries.into_iter().map(Ok).collect::<Vec<_>>(),
+                Err(err) => vec![Err(err)],
+            });
+
+        Ok(Box::new(static_file_entries.chain(
+            cursor.walk_range((db_start, end))?.map(|entry| entry.map_err(eyre::Report::from)),
+        )))
+    }
+
+    /// Returns the range and options of the checksums calculated by this viewer.
+    fn scope(&self) -> ChecksumScope {
+        ChecksumScope {
+            range_start: self.start_key.clone(),
+            range_end: self.end_key.clone(),
+            limit: self.limit,
+            with_static_files: self.with_static_files,
+            chunk_size: self.checkpoint.as_ref().map(|args| args.interval),
+        }
+    }
+
+    /// Returns an empty checkpoint for table `T` and the range of this viewer.
+    fn expected_checkpoint<T: Table>(&self, args: &CheckpointArgs) -> ChecksumCheckpoint {
+        ChecksumCheckpoint {
+            table: T::NAME.to_string(),
+            chunk_size: args.interval,
+            range_start: self.start_key.clone(),
+            range_end: self.end_key.clone(),
+            with_static_files: self.with_static_files,
+            entries: 0,
+            first_key: None,
+            last_key: None,
+            chunk_digests: Vec::new(),
+        }
+    }
+
+    /// Calculates the checksum of every table in [`Tables::ALL`], continuing past tables that
+    /// fail.
+    ///
+    /// Tables are hashed concurrently on a pool of `jobs` threads, each using its own read
+    /// transaction. A summary sorted by table name is logged once all tables have been processed.
+    /// Returns the first error if any of the tables failed.
+    ///
+    /// If `expected` is set, the checksums are compared with the expected checksums instead, and
+    /// an error listing the tables that failed or don't match is returned.
+    fn checksum_all(
+        &self,
+        jobs: Option<usize>,
+        json: bool,
+        expected: Option<&ExpectedChecksums>,
+    ) -> eyre::Result<()> {
+        // `0` lets rayon pick the number of available cores
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(jobs.unwrap_or_default()).build()?;
+        let results = pool.install(|| {
+            Tables::ALL
+                .par_iter()
+                .map(|table| {
+                    info!("Calculating checksum for table: {table}");
+                    let result = table.view(self);
+                    if let Ok(result) = &result {
+                        info!(
+                            "Finished checksum for table `{table}` ({} entries, elapsed: {:?})",
+                            result.entries, result.elapsed
+                        );
+                    }
+                    (table, result)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        let mut checksums = BTreeMap::new();
+        let mut failures = BTreeMap::new();
+        let mut first_error = None;
+        let mut comparisons = Vec::new();
+
+        for (table, result) in results {
+            if let Some(expected) = expected {
+                comparisons.push((table.name(), expected.compare(table.name(), result.as_ref())));
+            }
+            match result {
+                Ok(result) => {
+                    result.report(json)?;
+                    checksums.insert(table.name(), result.checksum);
+                }
+                Err(err) => {
+                    error!("Failed to calculate checksum for table `{table}`: {err:?}");
+                    failures.insert(table.name(), err.to_string());
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+
+        if !json {
+            info!("Checksum summary:");
+            for (table, checksum) in &checksums {
+                info!("{table}: {checksum:#x}");
+            }
+        }
+
+        if expected.is_some() {
+            return ChecksumReport(comparisons).ensure_matches()
+        }
+
+        if let Some(err) = first_error {
+            for (table, err) in &failures {
+                error!("{table}: {err}");
+            }
+            return Err(err
+                .wrap_err(format!("failed to calculate checksum for {} table(s)", failures.len())))
+        }
+
+        Ok(())
     }
 }
 
-impl<DB: Database> TableViewer<()> for ChecksumViewer<'_, DB> {
+impl<DB: Database> TableViewer<ChecksumResult> for ChecksumViewer<'_, DB> {
     type Error = eyre::Report;
 
-    fn view<T: Table>(&self) -> Result<(), Self::Error> {
-        let (checksum, elapsed) = self.get_checksum::<T>()?;
-        info!("Checksum for table `{}`: {:#x} (elapsed: {:?})", T::NAME, checksum, elapsed);
+    fn view<T: Table>(&self) -> Result<ChecksumResult, Self::Error> {
+        self.get_checksum::<T>()
+    }
+}
+
+/// The checkpoint arguments of the `reth db checksum` command.
+#[derive(Debug)]
+struct CheckpointArgs {
+    /// The file the checkpoint is stored in
+    path: PathBuf,
+    /// The number of entries per chunk
+    interval: usize,
+    /// Whether to continue from the stored checkpoint
+    resume: bool,
+}
+
+/// The progress of a chunked checksum run, stored as JSON in the checkpoint file.
+///
+/// The state of the hasher can't be serialized, so instead every `chunk_size` entries are hashed
+/// separately and the final checksum is derived from the ordered list of chunk digests.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct ChecksumCheckpoint {
+    /// The name of the table
+    table: String,
+    /// The number of entries hashed per chunk
+    chunk_size: usize,
+    /// The `--start-key` argument of the run
+    range_start: Option<String>,
+    /// The `--end-key` argument of the run
+    range_end: Option<String>,
+    /// Whether the run included the entries in static files
+    #[serde(default)]
+    with_static_files: bool,
+    /// The number of entries hashed so far
+    entries: usize,
+    /// The first key that was hashed
+    first_key: Option<serde_json::Value>,
+    /// The last key of the last completed chunk
+    last_key: Option<serde_json::Value>,
+    /// The digests of all completed chunks
+    chunk_digests: Vec<u64>,
+}
+
+impl ChecksumCheckpoint {
+    /// Reads a checkpoint from the given file.
+    fn load(path: &Path) -> eyre::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read checkpoint file {}", path.display()))?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Atomically writes the checkpoint to the given file.
+    fn write(&self, path: &Path) -> eyre::Result<()> {
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Returns an error if the checkpoint was written by an invocation with different arguments
+    /// than `expected`, since resuming it would produce a different checksum.
+    fn ensure_matches(&self, expected: Self) -> eyre::Result<()> {
+        if self.table != expected.table {
+            eyre::bail!(
+                "checkpoint is for table `{}`, but `{}` was requested",
+                self.table,
+                expected.table
+            )
+        }
+        if self.chunk_size != expected.chunk_size {
+            eyre::bail!(
+                "checkpoint was created with a chunk size of {}, but --checkpoint-interval is {}",
+                self.chunk_size,
+                expected.chunk_size
+            )
+        }
+        if self.range_start != expected.range_start || self.range_end != expected.range_end {
+            eyre::bail!(
+                "checkpoint was created for the range {:?}..={:?}, but {:?}..={:?} was requested",
+                self.range_start,
+                self.range_end,
+                expected.range_start,
+                expected.range_end
+            )
+        }
+        if self.with_static_files != expected.with_static_files {
+            eyre::bail!(
+                "checkpoint was created with --with-static-files set to {}",
+                self.with_static_files
+            )
+        }
 
         Ok(())
     }
+
+    /// Combines the chunk digests into the final checksum.
+    fn checksum(&self) -> u64 {
+        let mut hasher = AHasher::default();
+        for digest in &self.chunk_digests {
+            hasher.write_u64(*digest);
+        }
+        hasher.finish()
+    }
+}
+
+/// Returns the static file segment that holds the older entries of the table.
+const fn static_file_segment(table: Tables) -> Option<StaticFileSegment> {
+    match table {
+        Tables::Headers => Some(StaticFileSegment::Headers),
+        Tables::Transactions => Some(StaticFileSegment::Transactions),
+        Tables::Receipts => Some(StaticFileSegment::Receipts),
+        Tables::TransactionSenders => Some(StaticFileSegment::TransactionSenders),
+        _ => None,
+    }
+}
+
+/// Reads the entries of table `T` in the given block or transaction range from its static file
+/// segment, encoded the same way as the database entries.
+fn static_file_entries<T: Table>(
+    provider: &StaticFileProvider,
+    range: RangeInclusive<u64>,
+) -> eyre::Result<Vec<(RawKey<T::Key>, RawValue<T::Value>)>> {
+    let values: Vec<Vec<u8>> = match T::TABLE {
+        Tables::Headers => provider
+            .headers_range(range.clone())?
+            .into_iter()
+            .map(|header| header.compress().into())
+            .collect(),
+        Tables::Transactions => provider
+            .transactions_by_tx_range(range.clone())?
+            .into_iter()
+            .map(|transaction| transaction.compress().into())
+            .collect(),
+        Tables::Receipts => provider
+            .receipts_by_tx_range(range.clone())?
+            .into_iter()
+            .map(|receipt| receipt.compress().into())
+            .collect(),
+        Tables::TransactionSenders => provider
+            .fetch_range_with_predicate(
+                StaticFileSegment::TransactionSenders,
+                *range.start()..range.end() + 1,
+                |cursor, number| cursor.get_one::<TransactionSenderMask<Address>>(number.into()),
+                |_| true,
+            )?
+            .into_iter()
+            .map(|sender| sender.compress().into())
+            .collect(),
+        _ => eyre::bail!("table {} has no static file segment", T::NAME),
+    };
+
+    let expected = range.end() - range.start() + 1;
+    eyre::ensure!(
+        values.len() as u64 == expected,
+        "expected {expected} entries of table {} in static files for range {range:?}, found {}",
+        T::NAME,
+        values.len()
+    );
+
+    Ok(range
+        .zip(values)
+        .map(|(key, value)| (RawKey::from_vec(key.encode().to_vec()), RawValue::from_vec(value)))
+        .collect())
+}
+
+/// Decodes a raw key into the JSON value used for the `--start-key` and `--end-key` arguments.
+fn encode_key<T: Table>(key: RawKey<T::Key>) -> eyre::Result<serde_json::Value> {
+    Ok(serde_json::to_value(key.key()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_all_tables() {
+        let cmd = Command::try_parse_from(["reth", "--all"]).unwrap();
+        assert!(cmd.all);
+        assert!(cmd.table.is_none());
+
+        let cmd = Command::try_parse_from(["reth", "--all", "--jobs", "4"]).unwrap();
+        assert_eq!(cmd.jobs, Some(4));
+
+        let cmd = Command::try_parse_from(["reth", "Headers", "--limit", "10"]).unwrap();
+        assert_eq!(cmd.table, Some(Tables::Headers));
+        assert_eq!(cmd.limit, Some(10));
+        assert!(!cmd.with_static_files);
+
+        let cmd = Command::try_parse_from(["reth", "--all", "--with-static-files"]).unwrap();
+        assert!(cmd.with_static_files);
+    }
+
+    #[test]
+    fn reject_range_args_with_all() {
+        assert!(Command::try_parse_from(["reth"]).is_err());
+        assert!(Command::try_parse_from(["reth", "--all", "Headers"]).is_err());
+        assert!(Command::try_parse_from(["reth", "--all", "--start-key", "1"]).is_err());
+        assert!(Command::try_parse_from(["reth", "--all", "--end-key", "1"]).is_err());
+        assert!(Command::try_parse_from(["reth", "--all", "--limit", "1"]).is_err());
+        assert!(Command::try_parse_from(["reth", "Headers", "--jobs", "2"]).is_err());
+    }
+
+    #[test]
+    fn checkpoint_mismatch() {
+        let checkpoint = ChecksumCheckpoint {
+            table: "Headers".to_string(),
+            chunk_size: 10,
+            range_start: None,
+            range_end: None,
+            with_static_files: false,
+            entries: 20,
+            first_key: Some(serde_json::json!(0)),
+            last_key: Some(serde_json::json!(19)),
+            chunk_digests: vec![1, 2],
+        };
+
+        let expected = |table: &str, chunk_size, range_end: Option<&str>| ChecksumCheckpoint {
+            table: table.to_string(),
+            chunk_size,
+            range_start: None,
+            range_end: range_end.map(ToString::to_string),
+            with_static_files: false,
+            entries: 0,
+            first_key: None,
+            last_key: None,
+            chunk_digests: Vec::new(),
+        };
+
+        assert!(checkpoint.ensure_matches(expected("Headers", 10, None)).is_ok());
+        assert!(checkpoint.ensure_matches(expected("Receipts", 10, None)).is_err());
+        assert!(checkpoint.ensure_matches(expected("Headers", 11, None)).is_err());
+        assert!(checkpoint.ensure_matches(expected("Headers", 10, Some("100"))).is_err());
+    }
+
+    #[test]
+    fn checkpoint_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("checkpoint.json");
+
+        let checkpoint = ChecksumCheckpoint {
+            table: "Headers".to_string(),
+            chunk_size: 10,
+            range_start: Some("5".to_string()),
+            range_end: None,
+            with_static_files: true,
+            entries: 10,
+            first_key: Some(serde_json::json!(5)),
+            last_key: Some(serde_json::json!(14)),
+            chunk_digests: vec![0xdead],
+        };
+        checkpoint.write(&path).unwrap();
+
+        assert_eq!(ChecksumCheckpoint::load(&path).unwrap(), checkpoint);
+    }
+
+    #[test]
+    fn serialize_checksum_result() {
+        let result = ChecksumResult {
+            table: "Headers",
+            checksum: 0xdead,
+            entries: 2,
+            elapsed: Duration::from_millis(1500),
+            start_key: Some(serde_json::json!(1)),
+            end_key: Some(serde_json::json!(2)),
+            scope: ChecksumScope::default(),
+        };
+
+        assert_eq!(
+            serde_json::to_string(&result).unwrap(),
+            r#"{"table":"Headers","checksum":"0xdead","entries":2,"elapsed_ms":1500,"start_key":1,"end_key":2}"#
+        );
+        assert_eq!(table_key::<reth_db::Headers>(&result.end_key.unwrap().to_string()).unwrap(), 2);
+    }
+
+    #[test]
+    fn parse_expected_args() {
+        let cmd = Command::try_parse_from(["reth", "Headers", "--expected", "0xdead"]).unwrap();
+        assert_eq!(cmd.expected, Some(0xdead));
+
+        let cmd = Command::try_parse_from(["reth", "--all", "--expected-file", "sums"]).unwrap();
+        assert_eq!(cmd.expected_file, Some(PathBuf::from("sums")));
+
+        assert!(Command::try_parse_from(["reth", "--all", "--expected", "0xdead"]).is_err());
+        assert!(Command::try_parse_from(["reth", "Headers", "--expected", "0xnope"]).is_err());
+        assert!(Command::try_parse_from([
+            "reth",
+            "Headers",
+            "--expected",
+            "dead",
+            "--expected-file",
+            "sums"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_expected_checksums() {
+        let range = ChecksumScope {
+            range_start: Some("5".to_string()),
+            range_end: Some("10".to_string()),
+            ..Default::default()
+        };
+        let result = ChecksumResult {
+            table: "Receipts",
+            checksum: 0xbeef,
+            entries: 6,
+            elapsed: Duration::from_millis(10),
+            start_key: Some(serde_json::json!(5)),
+            end_key: Some(serde_json::json!(10)),
+            scope: range.clone(),
+        };
+        let contents = format!(
+            "# restored backup\nHeaders=0xdead\n\nTransactions = 1f\n{}\n",
+            serde_json::to_string(&result).unwrap()
+        );
+
+        let expected = ExpectedChecksums::parse(&contents).unwrap();
+        assert_eq!(
+            expected,
+            ExpectedChecksums::from_iter([
+                (
+                    "Headers".to_string(),
+                    ExpectedChecksum { checksum: 0xdead, scope: ChecksumScope::default() }
+                ),
+                (
+                    "Transactions".to_string(),
+                    ExpectedChecksum { checksum: 0x1f, scope: ChecksumScope::default() }
+                ),
+                ("Receipts".to_string(), ExpectedChecksum { checksum: 0xbeef, scope: range }),
+            ])
+        );
+
+        assert!(ExpectedChecksums::parse("Headers").is_err());
+        assert!(ExpectedChecksums::parse("Headers=0xnope").is_err());
+        assert!(ExpectedChecksums::parse("Unknown=0xdead").is_err());
+        assert!(ExpectedChecksums::parse("Headers=0x1\nHeaders=0x2").is_err());
+    }
+
+    #[test]
+    fn compare_checksums() {
+        let expected = ExpectedChecksums::parse("Headers=0xdead").unwrap();
+        let result = |checksum, scope| ChecksumResult {
+            table: "Headers",
+            checksum,
+            entries: 1,
+            elapsed: Duration::ZERO,
+            start_key: None,
+            end_key: None,
+            scope,
+        };
+        let range = ChecksumScope { limit: Some(1), ..Default::default() };
+
+        assert_eq!(
+            expected.compare("Headers", Ok(&result(0xdead, ChecksumScope::default()))),
+            ChecksumComparison::Match
+        );
+        assert_eq!(
+            expected.compare("Headers", Ok(&result(0xbeef, ChecksumScope::default()))),
+            ChecksumComparison::Mismatch { expected: 0xdead, actual: 0xbeef }
+        );
+        assert_eq!(
+            expected.compare("Headers", Ok(&result(0xdead, range.clone()))),
+            ChecksumComparison::ScopeMismatch { expected: ChecksumScope::default(), actual: range }
+        );
+        assert_eq!(
+            expected.compare("Headers", Err(&eyre::eyre!("boom"))),
+            ChecksumComparison::Failed("boom".to_string())
+        );
+        assert_eq!(
+            expected.compare("Receipts", Ok(&result(0xdead, ChecksumScope::default()))),
+            ChecksumComparison::Missing
+        );
+
+        assert!(ChecksumReport(vec![("Headers", ChecksumComparison::Match)])
+            .ensure_matches()
+            .is_ok());
+        let err = ChecksumReport(vec![
+            ("Headers", ChecksumComparison::Match),
+            ("Receipts", ChecksumComparison::Mismatch { expected: 0xdead, actual: 0xbeef }),
+        ])
+        .ensure_matches()
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "1 of 2 checksum(s) don't match the expected checksums:\n  Receipts: expected 0xdead, \
+             found 0xbeef"
+        );
+    }
 }
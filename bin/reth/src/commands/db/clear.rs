@@ -1,13 +1,20 @@
+use crate::commands::db::get::{maybe_json_value_parser, table_key};
 use clap::{Parser, Subcommand};
 use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW},
     database::Database,
     static_file::iter_static_files,
     table::Table,
     transaction::{DbTx, DbTxMut},
-    TableViewer, Tables,
+    RawKey, RawTable, TableViewer, Tables,
 };
 use reth_primitives::{static_file::find_fixed_range, StaticFileSegment};
 use reth_provider::ProviderFactory;
+use std::{
+    io::{self, IsTerminal, Write},
+    ops::Bound,
+};
+use tracing::info;
 
 /// The arguments for the `reth db clear` command
 #[derive(Parser, Debug)]
@@ -20,8 +27,24 @@ impl Command {
     /// Execute `db clear` command
     pub fn execute<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> eyre::Result<()> {
         match self.subcommand {
-            Subcommands::Mdbx { table } => {
-                table.view(&ClearViewer { db: provider_factory.db_ref() })?
+            Subcommands::Mdbx { table, start_key, end_key, commit_every, force } => {
+                if start_key.is_none() && end_key.is_none() {
+                    table.view(&ClearViewer { db: provider_factory.db_ref() })?
+                } else {
+                    eyre::ensure!(commit_every > 0, "--commit-every must be greater than zero");
+                    if !force && !confirm_range_clear(table)? {
+                        println!("Clearing table `{table}` aborted!");
+                        return Ok(())
+                    }
+
+                    let deleted = table.view(&ClearRangeViewer {
+                        db: provider_factory.db_ref(),
+                        start_key: start_key.as_deref(),
+                        end_key: end_key.as_deref(),
+                        commit_every,
+                    })?;
+                    println!("Deleted {deleted} entries from table `{table}`.");
+                }
             }
             Subcommands::StaticFile { segment } => {
                 let static_file_provider = provider_factory.static_file_provider();
@@ -42,12 +65,55 @@ impl Command {
 
 #[derive(Subcommand, Debug)]
 enum Subcommands {
-    /// Deletes all database table entries
-    Mdbx { table: Tables },
+    /// Deletes all database table entries, or only the entries within a key range
+    Mdbx {
+        table: Tables,
+
+        /// The first key to delete, inclusive
+        #[arg(long, value_parser = maybe_json_value_parser)]
+        start_key: Option<String>,
+
+        /// The last key to delete, inclusive
+        #[arg(long, value_parser = maybe_json_value_parser)]
+        end_key: Option<String>,
+
+        /// The number of entries deleted per transaction when clearing a key range
+        #[arg(long, default_value_t = DEFAULT_COMMIT_EVERY)]
+        commit_every: usize,
+
+        /// Bypasses the interactive confirmation when clearing a key range
+        #[arg(short, long)]
+        force: bool,
+    },
     /// Deletes all static file segment entries
     StaticFile { segment: StaticFileSegment },
 }
 
+/// The default number of entries deleted per transaction when clearing a key range.
+const DEFAULT_COMMIT_EVERY: usize = 100_000;
+
+/// Asks for confirmation before clearing a key range of the table.
+///
+/// Returns an error if stdin is not a terminal, because the confirmation can't be given.
+fn confirm_range_clear(table: Tables) -> eyre::Result<bool> {
+    if !io::stdin().is_terminal() {
+        eyre::bail!(
+            "Refusing to clear a key range of table `{table}` without confirmation, pass --force \
+             to skip it"
+        )
+    }
+
+    print!(
+        "Are you sure you want to delete the given key range from table `{table}`? This cannot be \
+         undone. (y/N): "
+    );
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
 struct ClearViewer<'a, DB: Database> {
     db: &'a DB,
 }
@@ -62,3 +128,82 @@ impl<DB: Database> TableViewer<()> for ClearViewer<'_, DB> {
         Ok(())
     }
 }
+
+struct ClearRangeViewer<'a, DB: Database> {
+    db: &'a DB,
+    start_key: Option<&'a str>,
+    end_key: Option<&'a str>,
+    commit_every: usize,
+}
+
+impl<DB: Database> TableViewer<usize> for ClearRangeViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        let start = match self.start_key {
+            Some(start) => Bound::Included(RawKey::<T::Key>::new(table_key::<T>(start)?)),
+            None => Bound::Unbounded,
+        };
+        let end = match self.end_key {
+            Some(end) => Bound::Included(RawKey::<T::Key>::new(table_key::<T>(end)?)),
+            None => Bound::Unbounded,
+        };
+
+        // Every batch walks the range from the start, because the entries of the previous batches
+        // are gone. Walking a DupSort table with a regular cursor visits every duplicate, so all
+        // duplicates within the range are deleted.
+        let mut total = 0;
+        loop {
+            let tx = self.db.tx_mut()?;
+            let mut cursor = tx.cursor_write::<RawTable<T>>()?;
+            let mut walker = cursor.walk_range((start.clone(), end.clone()))?;
+
+            let mut deleted = 0;
+            while deleted < self.commit_every {
+                if walker.next().transpose()?.is_none() {
+                    break
+                }
+                walker.delete_current()?;
+                deleted += 1;
+            }
+
+            drop(cursor);
+            tx.commit()?;
+            total += deleted;
+
+            if deleted < self.commit_every {
+                return Ok(total)
+            }
+            info!("Deleted {total} entries from table `{}`.", T::NAME);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_range_args() {
+        let args = Command::try_parse_from([
+            "reth",
+            "mdbx",
+            "Headers",
+            "--start-key",
+            "100",
+            "--commit-every",
+            "10",
+            "--force",
+        ])
+        .unwrap();
+        let Subcommands::Mdbx { table, start_key, end_key, commit_every, force } = args.subcommand
+        else {
+            panic!("expected mdbx subcommand")
+        };
+        assert_eq!(table, Tables::Headers);
+        assert_eq!(start_key.as_deref(), Some("100"));
+        assert_eq!(end_key, None);
+        assert_eq!(commit_every, 10);
+        assert!(force);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::{commands::db::checksum::ChecksumViewer, utils::DbTool};
+use clap::Parser;
+use eyre::WrapErr;
+use human_bytes::human_bytes;
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRW},
+    database::Database,
+    init_db,
+    mdbx::{self, DatabaseArguments},
+    open_db_read_only,
+    table::{DupSort, Table},
+    transaction::{DbTx, DbTxMut},
+    version::db_version_file_path,
+    DatabaseEnv, DatabaseError, RawDupSort, RawTable, TableViewer, Tables,
+};
+use reth_primitives::ChainSpec;
+use reth_provider::ProviderFactory;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
+use tracing::info;
+
+/// The name of the data file of an MDBX environment.
+const MDBX_DATA_FILE: &str = "mdbx.dat";
+
+/// The interval at which the progress of the copy is logged.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The number of entries that are written to the compacted database in one transaction, if only
+/// some tables are copied.
+const COMMIT_EVERY: usize = 100_000;
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db compact` command
+pub struct Command {
+    /// The directory the compacted database is written to, which must not contain a database.
+    ///
+    /// Defaults to `<DATADIR>/db.compact`.
+    #[arg(long, value_name = "DIR")]
+    output: Option<PathBuf>,
+
+    /// Only copy the given tables into a fresh database, the other tables are left empty.
+    #[arg(long, value_delimiter = ',', conflicts_with = "in_place")]
+    tables: Vec<Tables>,
+
+    /// Additionally compare the checksums of the tables of both databases, see `reth db checksum`.
+    ///
+    /// WARNING: this option will take a long time to run, as it needs to traverse and hash both
+    /// databases.
+    #[arg(long, default_value_t = false)]
+    checksum: bool,
+
+    /// Replace the database with the compacted copy once it's verified.
+    ///
+    /// The original database is kept in `<DATADIR>/db.backup`.
+    #[arg(long, default_value_t = false)]
+    in_place: bool,
+}
+
+impl Command {
+    /// Execute `db compact` command
+    pub fn execute(
+        self,
+        db_path: &Path,
+        db_args: DatabaseArguments,
+        chain: Arc<ChainSpec>,
+        static_files_path: PathBuf,
+    ) -> eyre::Result<()> {
+        let output = self.output.clone().unwrap_or_else(|| db_path.with_file_name("db.compact"));
+        let backup = db_path.with_file_name("db.backup");
+        eyre::ensure!(
+            !output.join(MDBX_DATA_FILE).exists(),
+            "{} already contains a database",
+            output.display()
+        );
+        if self.in_place {
+            eyre::ensure!(
+                !backup.exists(),
+                "The backup {} of a previous compaction already exists, remove it first",
+                backup.display()
+            );
+        }
+
+        // the exclusive mode makes sure that no node is running on the database while it's copied
+        let db = open_db_read_only(db_path, db_args.clone().with_exclusive(Some(true)))
+            .map_err(|err| in_use_error(err, db_path))?;
+
+        let tables =
+            if self.tables.is_empty() { Tables::ALL.to_vec() } else { self.tables.clone() };
+        if self.tables.is_empty() {
+            fs::create_dir_all(&output)
+                .wrap_err_with(|| format!("Could not create directory {}", output.display()))?;
+            copy_env(&db, &output.join(MDBX_DATA_FILE))?;
+
+            let version_file = db_version_file_path(db_path);
+            if version_file.exists() {
+                fs::copy(version_file, db_version_file_path(&output))?;
+            }
+        } else {
+            let compacted = init_db(&output, db_args.clone())?;
+            for table in &tables {
+                let bytes = table.view(&CopyViewer { source: &db, dest: &compacted })?;
+                info!("Copied {} of table `{table}`.", human_bytes(bytes as f64));
+            }
+        }
+
+        info!("Verifying the compacted database at {}.", output.display());
+        let compacted = open_db_read_only(&output, db_args)?;
+        let source_entries = entry_counts(&db, &tables)?;
+        let compacted_entries = entry_counts(&compacted, &tables)?;
+        for ((table, source), compacted) in tables.iter().zip(source_entries).zip(compacted_entries)
+        {
+            eyre::ensure!(
+                source == compacted,
+                "Table `{table}` has {source} entries, but {compacted} were copied"
+            );
+        }
+
+        if self.checksum {
+            let source = DbTool::new(
+                ProviderFactory::new(db, chain.clone(), static_files_path.clone())?,
+                chain.clone(),
+            )?;
+            let compacted = DbTool::new(
+                ProviderFactory::new(compacted, chain.clone(), static_files_path)?,
+                chain,
+            )?;
+            for table in &tables {
+                let expected = table.view(&ChecksumViewer::new(&source))?.checksum;
+                let checksum = table.view(&ChecksumViewer::new(&compacted))?.checksum;
+                eyre::ensure!(
+                    expected == checksum,
+                    "Checksum {checksum:x} of table `{table}` does not match {expected:x}"
+                );
+            }
+        } else {
+            drop(db);
+            drop(compacted);
+        }
+
+        info!(
+            "Compacted the database from {} to {}.",
+            human_bytes(fs::metadata(db_path.join(MDBX_DATA_FILE))?.len() as f64),
+            human_bytes(fs::metadata(output.join(MDBX_DATA_FILE))?.len() as f64)
+        );
+
+        if self.in_place {
+            replace_db(db_path, &output, &backup)?;
+            info!(
+                "Replaced the database with the compacted copy, the original database is kept in {}.",
+                backup.display()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Replaces the database with the compacted copy, and moves the original database to the backup
+/// path.
+///
+/// The original database is moved back if the compacted copy can't be moved to its path.
+fn replace_db(db_path: &Path, compacted: &Path, backup: &Path) -> eyre::Result<()> {
+    fs::rename(db_path, backup).wrap_err_with(|| {
+        format!("Could not move {} to {}", db_path.display(), backup.display())
+    })?;
+    if let Err(err) = fs::rename(compacted, db_path) {
+        let err = eyre::Report::new(err).wrap_err(format!(
+            "Could not move {} to {}",
+            compacted.display(),
+            db_path.display()
+        ));
+        return Err(match fs::rename(backup, db_path) {
+            Ok(()) => err,
+            Err(restore_err) => err.wrap_err(format!(
+                "Could not move the original database back from {} to {}: {restore_err}",
+                backup.display(),
+                db_path.display()
+            )),
+        })
+    }
+    Ok(())
+}
+
+/// Returns a more helpful error if the database couldn't be opened because it's in use.
+fn in_use_error(err: eyre::Report, db_path: &Path) -> eyre::Report {
+    let busy = err.chain().any(|err| {
+        matches!(
+            err.downcast_ref::<DatabaseError>(),
+            Some(DatabaseError::Open(info)) if info.code == mdbx::Error::Busy.to_err_code()
+        )
+    });
+    if busy {
+        eyre::eyre!(
+            "The database at {} is in use by another process, stop the node before compacting it",
+            db_path.display()
+        )
+    } else {
+        err
+    }
+}
+
+/// Copies the environment into the given data file with the compaction of MDBX, logging the
+/// progress in bytes.
+fn copy_env(db: &DatabaseEnv, dest: &Path) -> eyre::Result<()> {
+    let info = db.info()?;
+    let page_size = db.stat()?.page_size() as usize;
+    // pgno is 0 based
+    let used_bytes = (info.last_pgno() + 1).saturating_sub(db.freelist()?) * page_size;
+
+    thread::scope(|scope| {
+        let (tx, rx) = mpsc::channel();
+        scope.spawn(move || {
+            let _ = tx.send(db.copy(dest, true));
+        });
+
+        loop {
+            match rx.recv_timeout(PROGRESS_INTERVAL) {
+                Ok(res) => return res.wrap_err("Could not copy the database"),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    let copied = fs::metadata(dest).map_or(0, |metadata| metadata.len());
+                    info!(
+                        "Copied {} of about {} used by the database.",
+                        human_bytes(copied as f64),
+                        human_bytes(used_bytes as f64)
+                    );
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    eyre::bail!("The database copy was interrupted")
+                }
+            }
+        }
+    })
+}
+
+/// Returns the number of entries of the tables.
+fn entry_counts(db: &DatabaseEnv, tables: &[Tables]) -> eyre::Result<Vec<usize>> {
+    db.view(|tx| {
+        tables
+            .iter()
+            .map(|table| {
+                let table_db =
+                    tx.inner.open_db(Some(table.name())).wrap_err("Could not open db.")?;
+                let stats = tx
+                    .inner
+                    .db_stat(&table_db)
+                    .wrap_err(format!("Could not find table: {table}"))?;
+                Ok(stats.entries())
+            })
+            .collect()
+    })?
+}
+
+/// Copies the entries of a table into the compacted database, returning the number of copied
+/// bytes.
+struct CopyViewer<'a> {
+    source: &'a DatabaseEnv,
+    dest: &'a DatabaseEnv,
+}
+
+impl CopyViewer<'_> {
+    /// Logs the progress of the copy of table `T`.
+    fn log_progress<T: Table>(entries: usize, bytes: usize) {
+        info!("Copied {entries} entries ({}) of table `{}`.", human_bytes(bytes as f64), T::NAME);
+    }
+}
+
+impl TableViewer<usize> for CopyViewer<'_> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        let source = self.source.tx()?;
+        let mut cursor = source.cursor_read::<RawTable<T>>()?;
+        let mut walker = cursor.walk(None)?;
+
+        let (mut entries, mut bytes) = (0, 0);
+        let mut tx = self.dest.tx_mut()?;
+        tx.clear::<RawTable<T>>()?;
+        loop {
+            let mut writer = tx.cursor_write::<RawTable<T>>()?;
+            let mut written = 0;
+            for entry in walker.by_ref().take(COMMIT_EVERY) {
+                let (key, value) = entry?;
+                bytes += key.raw_key().len() + value.raw_value().len();
+                writer.append(key, value)?;
+                written += 1;
+            }
+
+            drop(writer);
+            tx.commit()?;
+            entries += written;
+            if written < COMMIT_EVERY {
+                return Ok(bytes)
+            }
+
+            Self::log_progress::<T>(entries, bytes);
+            tx = self.dest.tx_mut()?;
+        }
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<usize, Self::Error> {
+        let source = self.source.tx()?;
+        let mut cursor = source.cursor_read::<RawDupSort<T>>()?;
+        let mut walker = cursor.walk(None)?;
+
+        let (mut entries, mut bytes) = (0, 0);
+        let mut last_key = None;
+        let mut tx = self.dest.tx_mut()?;
+        tx.clear::<RawDupSort<T>>()?;
+        loop {
+            let mut writer = tx.cursor_dup_write::<RawDupSort<T>>()?;
+            let mut written = 0;
+            for entry in walker.by_ref().take(COMMIT_EVERY) {
+                let (key, value) = entry?;
+                bytes += key.raw_key().len() + value.raw_value().len();
+                if last_key.as_deref() == Some(key.raw_key()) {
+                    writer.append_dup(key, value)?;
+                } else {
+                    last_key = Some(key.raw_key().to_vec());
+                    writer.append(key, value)?;
+                }
+                written += 1;
+            }
+
+            drop(writer);
+            tx.commit()?;
+            entries += written;
+            if written < COMMIT_EVERY {
+                return Ok(bytes)
+            }
+
+            Self::log_progress::<T>(entries, bytes);
+            tx = self.dest.tx_mut()?;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_tables() {
+        let cmd = Command::try_parse_from([
+            "reth",
+            "--tables",
+            "PlainAccountState,PlainStorageState",
+            "--checksum",
+        ])
+        .unwrap();
+        assert_eq!(cmd.tables, vec![Tables::PlainAccountState, Tables::PlainStorageState]);
+        assert!(cmd.checksum);
+
+        // only a full copy can replace the database
+        assert!(Command::try_parse_from(["reth", "--tables", "Headers", "--in-place"]).is_err());
+    }
+
+    #[test]
+    fn replace_db_restores_original() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("db");
+        let compacted = dir.path().join("db-compacted");
+        let backup = dir.path().join("db-backup");
+        fs::create_dir(&db_path).unwrap();
+        fs::write(db_path.join(MDBX_DATA_FILE), "original").unwrap();
+
+        // the compacted copy is missing, so it can't be moved
+        assert!(replace_db(&db_path, &compacted, &backup).is_err());
+        assert_eq!(fs::read(db_path.join(MDBX_DATA_FILE)).unwrap(), b"original");
+        assert!(!backup.exists());
+
+        fs::create_dir(&compacted).unwrap();
+        fs::write(compacted.join(MDBX_DATA_FILE), "compacted").unwrap();
+        replace_db(&db_path, &compacted, &backup).unwrap();
+        assert_eq!(fs::read(db_path.join(MDBX_DATA_FILE)).unwrap(), b"compacted");
+        assert_eq!(fs::read(backup.join(MDBX_DATA_FILE)).unwrap(), b"original");
+    }
+}
@@ -0,0 +1,122 @@
+use crate::commands::db::put::{print_previous, raw_key, raw_subkey, seek_exact_dup};
+use clap::Parser;
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW},
+    database::Database,
+    table::{DupSort, Table},
+    transaction::{DbTx, DbTxMut},
+    RawDupSort, RawTable, TableViewer, Tables,
+};
+use reth_provider::ProviderFactory;
+use tracing::warn;
+
+/// The arguments for the `reth db delete-key` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The table name
+    table: Tables,
+
+    /// The key to delete, as JSON or as hex if `--raw` is set
+    key: String,
+
+    /// The subkey of the DupSort entry to delete.
+    ///
+    /// Without a subkey, all duplicates of the key are deleted.
+    #[arg(long)]
+    subkey: Option<String>,
+
+    /// Interpret the key and subkey as raw hex encoded bytes
+    #[arg(long)]
+    raw: bool,
+
+    /// Confirm that the database should be modified
+    #[arg(long)]
+    force: bool,
+}
+
+impl Command {
+    /// Execute `db delete-key` command
+    pub fn execute<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> eyre::Result<()> {
+        if !self.force {
+            eyre::bail!(
+                "`reth db delete-key` modifies the database directly, pass --force to delete"
+            )
+        }
+        warn!("This command should be run without the node running!");
+
+        self.table.view(&DeleteKeyViewer {
+            db: provider_factory.db_ref(),
+            key: &self.key,
+            subkey: self.subkey.as_deref(),
+            raw: self.raw,
+        })
+    }
+}
+
+struct DeleteKeyViewer<'a, DB: Database> {
+    db: &'a DB,
+    key: &'a str,
+    subkey: Option<&'a str>,
+    raw: bool,
+}
+
+impl<DB: Database> TableViewer<()> for DeleteKeyViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        let key = raw_key::<T>(self.key, self.raw)?;
+
+        let tx = self.db.tx_mut()?;
+        let Some(previous) = tx.get::<RawTable<T>>(key.clone())? else {
+            println!("No content for the given table key.");
+            return Ok(())
+        };
+        print_previous::<T>(&previous);
+        tx.delete::<RawTable<T>>(key, None)?;
+        tx.commit()?;
+
+        println!("Successfully deleted entry from table `{}`.", T::NAME);
+        Ok(())
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<(), Self::Error> {
+        let key = raw_key::<T>(self.key, self.raw)?;
+
+        let tx = self.db.tx_mut()?;
+        let mut cursor = tx.cursor_dup_write::<RawDupSort<T>>()?;
+
+        let deleted = match self.subkey {
+            Some(subkey) => {
+                let subkey = raw_subkey::<T>(subkey, self.raw)?;
+                match seek_exact_dup::<T>(&mut cursor, key, subkey)? {
+                    Some(previous) => {
+                        print_previous::<T>(&previous);
+                        cursor.delete_current()?;
+                        1
+                    }
+                    None => 0,
+                }
+            }
+            None => {
+                let mut deleted = 0;
+                let mut entry = cursor.seek_exact(key.clone())?;
+                while let Some((_, previous)) = entry {
+                    print_previous::<T>(&previous);
+                    cursor.delete_current()?;
+                    deleted += 1;
+                    entry = cursor.seek_exact(key.clone())?;
+                }
+                deleted
+            }
+        };
+
+        if deleted == 0 {
+            println!("No content for the given table key.");
+            return Ok(())
+        }
+        tx.commit()?;
+
+        println!("Successfully deleted {deleted} entries from table `{}`.", T::NAME);
+        Ok(())
+    }
+}
@@ -1,24 +1,25 @@
 use crate::{
     args::DatabaseArgs,
+    commands::db::get::{maybe_json_value_parser, table_key},
     dirs::{DataDirPath, PlatformPath},
     utils::DbTool,
 };
 use clap::Parser;
 use reth_db::{
-    cursor::DbCursorRO, database::Database, open_db_read_only, table::Table, transaction::DbTx,
-    AccountChangeSets, AccountsHistory, AccountsTrie, BlockBodyIndices, BlockOmmers,
-    BlockWithdrawals, Bytecodes, CanonicalHeaders, DatabaseEnv, HashedAccounts, HashedStorages,
-    HeaderNumbers, HeaderTerminalDifficulties, Headers, PlainAccountState, PlainStorageState,
-    PruneCheckpoints, Receipts, StageCheckpointProgresses, StageCheckpoints, StorageChangeSets,
-    StoragesHistory, StoragesTrie, Tables, TransactionBlocks, TransactionHashNumbers,
-    TransactionSenders, Transactions, VersionHistory,
+    cursor::DbCursorRO,
+    database::Database,
+    open_db_read_only,
+    table::{DupSort, Table},
+    transaction::DbTx,
+    DatabaseEnv, RawKey, RawTable, RawValue, TableViewer, Tables,
 };
+use serde::Serialize;
 use std::{
-    collections::HashMap,
-    fmt::Debug,
+    cell::RefCell,
+    cmp::Ordering,
     fs::{self, File},
-    hash::Hash,
-    io::Write,
+    io::{BufWriter, Write},
+    ops::Bound,
     path::{Path, PathBuf},
 };
 use tracing::{info, warn};
@@ -38,9 +39,30 @@ pub struct Command {
     #[arg(long, verbatim_doc_comment)]
     table: Option<Tables>,
 
+    /// Diff all tables. This is the default if no `--table` is given.
+    #[arg(long, conflicts_with = "table")]
+    all: bool,
+
+    /// The first key of the range to diff. Requires `--table`.
+    #[arg(long, requires = "table", value_parser = maybe_json_value_parser)]
+    start_key: Option<String>,
+
+    /// The last key of the range to diff. Requires `--table`.
+    #[arg(long, requires = "table", value_parser = maybe_json_value_parser)]
+    end_key: Option<String>,
+
+    /// The maximum number of differences to collect per table before moving on.
+    #[arg(long, default_value_t = usize::MAX, hide_default_value = true)]
+    max_diffs: usize,
+
     /// The output directory for the diff report.
     #[arg(long, verbatim_doc_comment)]
     output: PlatformPath<PathBuf>,
+
+    /// Additionally write every difference as a JSON line with the decoded key and both values
+    /// to this file.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    json_output: Option<PathBuf>,
 }
 
 impl Command {
@@ -49,11 +71,11 @@ impl Command {
     /// This first opens the `db/` folder from the secondary datadir, where the second database is
     /// opened read-only.
     ///
-    /// The tool will then iterate through all key-value pairs for the primary and secondary
-    /// databases. The value for each key will be compared with its corresponding value in the
-    /// other database. If the values are different, a discrepancy will be recorded in-memory. If
-    /// one key is present in one database but not the other, this will be recorded as an "extra
-    /// element" for that database.
+    /// The tool will then walk the raw key-value pairs of the primary and secondary databases in
+    /// lockstep. If the raw values for the same key are different, a discrepancy will be recorded
+    /// in-memory. If one key is present in one database but not the other, this will be recorded
+    /// as an "extra element" for that database. Entries of dupsort tables are matched by their key
+    /// and subkey. Collection stops after `--max-diffs` differences.
     ///
     /// The discrepancies and extra elements, along with a brief summary of the diff results are
     /// then written to a file in the output directory.
@@ -64,127 +86,125 @@ impl Command {
         let second_db = open_db_read_only(&second_db_path, self.second_db.database_args())?;
 
         let tables = match &self.table {
-            Some(table) => std::slice::from_ref(table),
-            None => Tables::ALL,
+            Some(table) if !self.all => std::slice::from_ref(table),
+            _ => Tables::ALL,
+        };
+
+        let json_output =
+            self.json_output.as_ref().map(File::create).transpose()?.map(BufWriter::new);
+        let viewer = DiffViewer {
+            primary: tool.provider_factory.db_ref(),
+            secondary: &second_db,
+            start_key: self.start_key.as_deref(),
+            end_key: self.end_key.as_deref(),
+            max_diffs: self.max_diffs,
+            output_dir: self.output.as_ref(),
+            json_output: RefCell::new(json_output),
         };
 
         for table in tables {
-            let mut primary_tx = tool.provider_factory.db_ref().tx()?;
-            let mut secondary_tx = second_db.tx()?;
-
-            // disable long read transaction safety, since this will run for a while and it's
-            // expected that the node is not running
-            primary_tx.disable_long_read_transaction_safety();
-            secondary_tx.disable_long_read_transaction_safety();
-
-            let output_dir = self.output.clone();
-            match table {
-                Tables::CanonicalHeaders => {
-                    find_diffs::<CanonicalHeaders>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::HeaderTerminalDifficulties => {
-                    find_diffs::<HeaderTerminalDifficulties>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::HeaderNumbers => {
-                    find_diffs::<HeaderNumbers>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::Headers => find_diffs::<Headers>(primary_tx, secondary_tx, output_dir)?,
-                Tables::BlockBodyIndices => {
-                    find_diffs::<BlockBodyIndices>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::BlockOmmers => {
-                    find_diffs::<BlockOmmers>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::BlockWithdrawals => {
-                    find_diffs::<BlockWithdrawals>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::TransactionBlocks => {
-                    find_diffs::<TransactionBlocks>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::Transactions => {
-                    find_diffs::<Transactions>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::TransactionHashNumbers => {
-                    find_diffs::<TransactionHashNumbers>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::Receipts => find_diffs::<Receipts>(primary_tx, secondary_tx, output_dir)?,
-                Tables::PlainAccountState => {
-                    find_diffs::<PlainAccountState>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::PlainStorageState => {
-                    find_diffs::<PlainStorageState>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::Bytecodes => find_diffs::<Bytecodes>(primary_tx, secondary_tx, output_dir)?,
-                Tables::AccountsHistory => {
-                    find_diffs::<AccountsHistory>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::StoragesHistory => {
-                    find_diffs::<StoragesHistory>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::AccountChangeSets => {
-                    find_diffs::<AccountChangeSets>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::StorageChangeSets => {
-                    find_diffs::<StorageChangeSets>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::HashedAccounts => {
-                    find_diffs::<HashedAccounts>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::HashedStorages => {
-                    find_diffs::<HashedStorages>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::AccountsTrie => {
-                    find_diffs::<AccountsTrie>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::StoragesTrie => {
-                    find_diffs::<StoragesTrie>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::TransactionSenders => {
-                    find_diffs::<TransactionSenders>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::StageCheckpoints => {
-                    find_diffs::<StageCheckpoints>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::StageCheckpointProgresses => {
-                    find_diffs::<StageCheckpointProgresses>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::PruneCheckpoints => {
-                    find_diffs::<PruneCheckpoints>(primary_tx, secondary_tx, output_dir)?
-                }
-                Tables::VersionHistory => {
-                    find_diffs::<VersionHistory>(primary_tx, secondary_tx, output_dir)?
-                }
-            };
+            table.view(&viewer)?;
+        }
+
+        if let Some(mut json_output) = viewer.json_output.into_inner() {
+            json_output.flush()?;
         }
 
         Ok(())
     }
 }
 
-/// Find diffs for a table, then analyzing the result
-fn find_diffs<T: Table>(
-    primary_tx: impl DbTx,
-    secondary_tx: impl DbTx,
-    output_dir: impl AsRef<Path>,
-) -> eyre::Result<()>
-where
-    T::Key: Hash,
-    T::Value: PartialEq,
-{
-    let table = T::TABLE;
+/// Diffs a table between the primary and secondary database.
+struct DiffViewer<'a> {
+    primary: &'a DatabaseEnv,
+    secondary: &'a DatabaseEnv,
+    start_key: Option<&'a str>,
+    end_key: Option<&'a str>,
+    max_diffs: usize,
+    output_dir: &'a Path,
+    json_output: RefCell<Option<BufWriter<File>>>,
+}
+
+impl DiffViewer<'_> {
+    /// Diffs the table, matching the entries by their key and the first `subkey_len` bytes of
+    /// their value, if set.
+    fn diff_table<T: Table>(&self, subkey_len: Option<usize>) -> eyre::Result<()> {
+        let mut primary_tx = self.primary.tx()?;
+        let mut secondary_tx = self.secondary.tx()?;
+
+        // disable long read transaction safety, since this will run for a while and it's
+        // expected that the node is not running
+        primary_tx.disable_long_read_transaction_safety();
+        secondary_tx.disable_long_read_transaction_safety();
+
+        let start = match self.start_key {
+            Some(start) => Bound::Included(RawKey::<T::Key>::new(table_key::<T>(start)?)),
+            None => Bound::Unbounded,
+        };
+        let end = match self.end_key {
+            Some(end) => Bound::Included(RawKey::<T::Key>::new(table_key::<T>(end)?)),
+            None => Bound::Unbounded,
+        };
+
+        let table = T::TABLE;
 
-    info!("Analyzing table {table}...");
-    let result = find_diffs_advanced::<T>(&primary_tx, &secondary_tx)?;
-    info!("Done analyzing table {table}!");
+        info!("Analyzing table {table}...");
+        let result = find_diffs_advanced::<T>(
+            &primary_tx,
+            &secondary_tx,
+            (start, end),
+            subkey_len,
+            self.max_diffs,
+        )?;
+        info!("Done analyzing table {table}!");
+
+        if let Some(json_output) = self.json_output.borrow_mut().as_mut() {
+            result.write_json_lines(json_output)?;
+        }
+
+        write_report(result, self.output_dir)
+    }
+}
+
+impl TableViewer<()> for DiffViewer<'_> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        self.diff_table::<T>(None)
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<(), Self::Error> {
+        self.diff_table::<T>(Some(dupsort_subkey_len(T::TABLE)))
+    }
+}
+
+/// Returns the length of the encoded subkey of a dupsort table.
+///
+/// The values of dupsort tables start with their encoded subkey, so entries with the same key and
+/// subkey are the same entry, even if their values differ.
+fn dupsort_subkey_len(table: Tables) -> usize {
+    match table {
+        Tables::PlainStorageState | Tables::StorageChangeSets | Tables::HashedStorages => 32,
+        Tables::BlockCallFromAddresses |
+        Tables::BlockCallToAddresses |
+        Tables::AccountChangeSets => 20,
+        Tables::StoragesTrie => 65,
+        _ => unreachable!("{table} is not a dupsort table"),
+    }
+}
+
+/// Write the diff report for a table, then print a summary of the result
+fn write_report<T: Table>(result: TableDiffResult<T>, output_dir: &Path) -> eyre::Result<()> {
+    let table = T::TABLE;
 
     // Pretty info summary header: newline then header
     info!("");
     info!("Diff results for {table}:");
 
     // create directory and open file
-    fs::create_dir_all(output_dir.as_ref())?;
+    fs::create_dir_all(output_dir)?;
     let file_name = format!("{table}.txt");
-    let mut file = File::create(output_dir.as_ref().join(file_name.clone()))?;
+    let mut file = File::create(output_dir.join(file_name.clone()))?;
 
     // analyze the result and print some stats
     let discrepancies = result.discrepancies.len();
@@ -193,6 +213,11 @@ where
     // Make a pretty summary header for the table
     writeln!(file, "Diff results for {table}")?;
 
+    if result.truncated {
+        writeln!(file, "Stopped after reaching the maximum number of differences")?;
+        warn!("Stopped diffing table {table} after reaching the maximum number of differences");
+    }
+
     if discrepancies > 0 {
         // write to file
         writeln!(file, "Found {discrepancies} discrepancies in table {table}")?;
@@ -226,7 +251,7 @@ where
         writeln!(file, "Discrepancies:")?;
     }
 
-    for discrepancy in result.discrepancies.values() {
+    for discrepancy in &result.discrepancies {
         writeln!(file, "{discrepancy:?}")?;
     }
 
@@ -234,79 +259,94 @@ where
         writeln!(file, "Extra elements:")?;
     }
 
-    for extra_element in result.extra_elements.values() {
+    for extra_element in &result.extra_elements {
         writeln!(file, "{extra_element:?}")?;
     }
 
-    let full_file_name = output_dir.as_ref().join(file_name);
+    let full_file_name = output_dir.join(file_name);
     info!("Done writing diff results for {table} to {}", full_file_name.display());
     Ok(())
 }
 
-/// This diff algorithm is slightly different, it will walk _each_ table, cross-checking for the
-/// element in the other table.
+/// Walks the raw entries of both tables in lockstep over the given key range.
+///
+/// Since both cursors return keys in the same order, a key that is smaller than the current key
+/// of the other table can't exist in the other table, and is recorded as an extra element.
+///
+/// For dupsort tables the entries of a key are sorted by their value, which starts with the
+/// encoded subkey of `subkey_len` bytes, so the entries are compared by key and subkey instead.
 fn find_diffs_advanced<T: Table>(
     primary_tx: &impl DbTx,
     secondary_tx: &impl DbTx,
-) -> eyre::Result<TableDiffResult<T>>
-where
-    T::Value: PartialEq,
-    T::Key: Hash,
-{
-    // initialize the zipped walker
-    let mut primary_zip_cursor =
-        primary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
-    let primary_walker = primary_zip_cursor.walk(None)?;
-
-    let mut secondary_zip_cursor =
-        secondary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
-    let secondary_walker = secondary_zip_cursor.walk(None)?;
-    let zipped_cursor = primary_walker.zip(secondary_walker);
-
-    // initialize the cursors for seeking when we are cross checking elements
-    let mut primary_cursor =
-        primary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
-
-    let mut secondary_cursor =
-        secondary_tx.cursor_read::<T>().expect("Was not able to obtain a cursor.");
+    range: (Bound<RawKey<T::Key>>, Bound<RawKey<T::Key>>),
+    subkey_len: Option<usize>,
+    max_diffs: usize,
+) -> eyre::Result<TableDiffResult<T>> {
+    let mut primary_cursor = primary_tx.cursor_read::<RawTable<T>>()?;
+    let mut primary_walker = primary_cursor.walk_range(range.clone())?.peekable();
+
+    let mut secondary_cursor = secondary_tx.cursor_read::<RawTable<T>>()?;
+    let mut secondary_walker = secondary_cursor.walk_range(range)?.peekable();
 
     let mut result = TableDiffResult::<T>::default();
 
-    // this loop will walk both tables, cross-checking for the element in the other table.
-    // it basically just loops through both tables at the same time. if the keys are different, it
-    // will check each key in the other table. if the keys are the same, it will compare the
-    // values
-    for (primary_entry, secondary_entry) in zipped_cursor {
-        let (primary_key, primary_value) = primary_entry?;
-        let (secondary_key, secondary_value) = secondary_entry?;
-
-        if primary_key != secondary_key {
-            // if the keys are different, we need to check if the key is in the other table
-            let crossed_secondary =
-                secondary_cursor.seek_exact(primary_key.clone())?.map(|(_, value)| value);
-            result.try_push_discrepancy(
-                primary_key.clone(),
-                Some(primary_value),
-                crossed_secondary,
-            );
-
-            // now do the same for the primary table
-            let crossed_primary =
-                primary_cursor.seek_exact(secondary_key.clone())?.map(|(_, value)| value);
-            result.try_push_discrepancy(
-                secondary_key.clone(),
-                crossed_primary,
-                Some(secondary_value),
-            );
-        } else {
-            // the keys are the same, so we need to compare the values
-            result.try_push_discrepancy(primary_key, Some(primary_value), Some(secondary_value));
+    loop {
+        if result.len() >= max_diffs {
+            result.truncated = true;
+            break
+        }
+
+        let ordering = match (primary_walker.peek(), secondary_walker.peek()) {
+            (
+                Some(Ok((primary_key, primary_value))),
+                Some(Ok((secondary_key, secondary_value))),
+            ) => primary_key.raw_key().cmp(secondary_key.raw_key()).then_with(|| {
+                subkey_len.map_or(Ordering::Equal, |len| {
+                    subkey(primary_value.raw_value(), len)
+                        .cmp(subkey(secondary_value.raw_value(), len))
+                })
+            }),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => break,
+            // surface the errors below
+            (Some(Err(_)), _) => Ordering::Less,
+            (_, Some(Err(_))) => Ordering::Greater,
+        };
+
+        match ordering {
+            Ordering::Equal => {
+                let (key, primary_value) = primary_walker.next().expect("peeked")?;
+                let (_, secondary_value) = secondary_walker.next().expect("peeked")?;
+                if primary_value.raw_value() != secondary_value.raw_value() {
+                    result.push_discrepancy(key, primary_value, secondary_value)?;
+                }
+            }
+            Ordering::Less => {
+                let (key, value) = primary_walker.next().expect("peeked")?;
+                result.push_extra_element(ExtraTableElement::First {
+                    key: key.key()?,
+                    value: value.value()?,
+                });
+            }
+            Ordering::Greater => {
+                let (key, value) = secondary_walker.next().expect("peeked")?;
+                result.push_extra_element(ExtraTableElement::Second {
+                    key: key.key()?,
+                    value: value.value()?,
+                });
+            }
         }
     }
 
     Ok(result)
 }
 
+/// Returns the encoded subkey at the start of a raw dupsort value.
+fn subkey(value: &[u8], len: usize) -> &[u8] {
+    &value[..len.min(value.len())]
+}
+
 /// Includes a table element between two databases with the same key, but different values
 #[derive(Debug)]
 struct TableDiffElement<T: Table> {
@@ -314,111 +354,124 @@ struct TableDiffElement<T: Table> {
     key: T::Key,
 
     /// The element from the first table
-    #[allow(dead_code)]
     first: T::Value,
 
     /// The element from the second table
-    #[allow(dead_code)]
     second: T::Value,
 }
 
 /// The diff result for an entire table. If the tables had the same number of elements, there will
 /// be no extra elements.
-struct TableDiffResult<T: Table>
-where
-    T::Key: Hash,
-{
+struct TableDiffResult<T: Table> {
     /// All elements of the database that are different
-    discrepancies: HashMap<T::Key, TableDiffElement<T>>,
+    discrepancies: Vec<TableDiffElement<T>>,
 
     /// Any extra elements, and the table they are in
-    extra_elements: HashMap<T::Key, ExtraTableElement<T>>,
+    extra_elements: Vec<ExtraTableElement<T>>,
+
+    /// Whether collection stopped early because the maximum number of differences was reached
+    truncated: bool,
 }
 
-impl<T> Default for TableDiffResult<T>
-where
-    T: Table,
-    T::Key: Hash,
-{
+impl<T: Table> Default for TableDiffResult<T> {
     fn default() -> Self {
-        Self { discrepancies: HashMap::new(), extra_elements: HashMap::new() }
+        Self { discrepancies: Vec::new(), extra_elements: Vec::new(), truncated: false }
     }
 }
 
-impl<T: Table> TableDiffResult<T>
-where
-    T::Key: Hash,
-{
-    /// Push a diff result into the discrepancies set.
-    fn push_discrepancy(&mut self, discrepancy: TableDiffElement<T>) {
-        self.discrepancies.insert(discrepancy.key.clone(), discrepancy);
+impl<T: Table> TableDiffResult<T> {
+    /// The total number of differences found.
+    fn len(&self) -> usize {
+        self.discrepancies.len() + self.extra_elements.len()
+    }
+
+    /// Decode and push a diff result into the discrepancies set.
+    fn push_discrepancy(
+        &mut self,
+        key: RawKey<T::Key>,
+        first: RawValue<T::Value>,
+        second: RawValue<T::Value>,
+    ) -> eyre::Result<()> {
+        self.discrepancies.push(TableDiffElement {
+            key: key.key()?,
+            first: first.value()?,
+            second: second.value()?,
+        });
+        Ok(())
     }
 
     /// Push an extra element into the extra elements set.
     fn push_extra_element(&mut self, element: ExtraTableElement<T>) {
-        self.extra_elements.insert(element.key().clone(), element);
+        self.extra_elements.push(element);
     }
-}
-
-impl<T> TableDiffResult<T>
-where
-    T: Table,
-    T::Key: Hash,
-    T::Value: PartialEq,
-{
-    /// Try to push a diff result into the discrepancy set, only pushing if the given elements are
-    /// different, and the discrepancy does not exist anywhere already.
-    fn try_push_discrepancy(
-        &mut self,
-        key: T::Key,
-        first: Option<T::Value>,
-        second: Option<T::Value>,
-    ) {
-        // do not bother comparing if the key is already in the discrepancies map
-        if self.discrepancies.contains_key(&key) {
-            return
-        }
 
-        // do not bother comparing if the key is already in the extra elements map
-        if self.extra_elements.contains_key(&key) {
-            return
-        }
-
-        match (first, second) {
-            (Some(first), Some(second)) => {
-                if first != second {
-                    self.push_discrepancy(TableDiffElement { key, first, second });
-                }
+    /// Write every difference as a [`JsonDiffLine`] to the given writer.
+    fn write_json_lines(&self, writer: &mut impl Write) -> eyre::Result<()> {
+        let table = T::NAME;
+
+        let discrepancies = self.discrepancies.iter().map(|element| JsonDiffLine::<T> {
+            table,
+            key: &element.key,
+            primary: Some(&element.first),
+            secondary: Some(&element.second),
+        });
+        let extra_elements = self.extra_elements.iter().map(|element| match element {
+            ExtraTableElement::First { key, value } => {
+                JsonDiffLine::<T> { table, key, primary: Some(value), secondary: None }
             }
-            (Some(first), None) => {
-                self.push_extra_element(ExtraTableElement::First { key, value: first });
+            ExtraTableElement::Second { key, value } => {
+                JsonDiffLine::<T> { table, key, primary: None, secondary: Some(value) }
             }
-            (None, Some(second)) => {
-                self.push_extra_element(ExtraTableElement::Second { key, value: second });
-            }
-            (None, None) => {}
+        });
+
+        for line in discrepancies.chain(extra_elements) {
+            serde_json::to_writer(&mut *writer, &line)?;
+            writeln!(writer)?;
         }
+
+        Ok(())
     }
 }
 
+/// A single difference, as written to the `--json-output` file.
+#[derive(Serialize)]
+#[serde(bound = "")]
+struct JsonDiffLine<'a, T: Table> {
+    /// The name of the table
+    table: &'static str,
+    /// The decoded key
+    key: &'a T::Key,
+    /// The decoded value in the primary database, if present
+    primary: Option<&'a T::Value>,
+    /// The decoded value in the secondary database, if present
+    secondary: Option<&'a T::Value>,
+}
+
 /// A single extra element from a table
 #[derive(Debug)]
 enum ExtraTableElement<T: Table> {
     /// The extra element that is in the first table
-    #[allow(dead_code)]
     First { key: T::Key, value: T::Value },
 
     /// The extra element that is in the second table
-    #[allow(dead_code)]
     Second { key: T::Key, value: T::Value },
 }
 
-impl<T: Table> ExtraTableElement<T> {
-    /// Return the key for the extra element
-    fn key(&self) -> &T::Key {
-        match self {
-            Self::First { key, .. } => key,
-            Self::Second { key, .. } => key,
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::table::Encode;
+    use reth_primitives::{trie::StoredNibblesSubKey, Address, B256};
+
+    #[test]
+    fn dupsort_subkey_lengths() {
+        assert_eq!(dupsort_subkey_len(Tables::PlainStorageState), B256::ZERO.encode().len());
+        assert_eq!(dupsort_subkey_len(Tables::AccountChangeSets), Address::ZERO.encode().len());
+        assert_eq!(
+            dupsort_subkey_len(Tables::StoragesTrie),
+            StoredNibblesSubKey::from(vec![1, 2]).encode().len()
+        );
+        assert_eq!(subkey(&[1, 2, 3], 2), &[1, 2]);
+        assert_eq!(subkey(&[1], 2), &[1]);
     }
 }
@@ -0,0 +1,279 @@
+use crate::{
+    commands::db::get::{maybe_json_value_parser, table_key},
+    utils::DbTool,
+};
+use clap::{Parser, ValueEnum};
+use reth_db::{
+    cursor::DbCursorRO,
+    database::Database,
+    table::{Decode, DupSort, Table},
+    transaction::DbTx,
+    RawKey, RawTable, TableViewer, Tables,
+};
+use serde_json::Value as JsonValue;
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{BufWriter, Write},
+    ops::Bound,
+    path::PathBuf,
+};
+use tracing::info;
+
+/// The arguments for the `reth db export` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The table name
+    table: Tables,
+
+    /// The format of the exported rows
+    #[arg(long, value_enum, default_value_t = ExportFormat::Jsonl)]
+    format: ExportFormat,
+
+    /// The file to write the exported rows to
+    #[arg(long, value_name = "FILE")]
+    output: PathBuf,
+
+    /// The first key to export
+    #[arg(long, value_parser = maybe_json_value_parser)]
+    start_key: Option<String>,
+
+    /// The last key to export
+    #[arg(long, value_parser = maybe_json_value_parser)]
+    end_key: Option<String>,
+
+    /// The maximum number of rows to export
+    #[arg(long)]
+    limit: Option<usize>,
+}
+
+/// The file format of exported table rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ExportFormat {
+    /// Comma separated values, with every key and value encoded as a JSON string
+    Csv,
+    /// One JSON object per line
+    Jsonl,
+}
+
+impl Command {
+    /// Execute `db export` command
+    pub fn execute<DB: Database>(self, tool: &DbTool<DB>) -> eyre::Result<()> {
+        let file = File::create(&self.output)?;
+        let viewer = ExportViewer {
+            tool,
+            start_key: self.start_key.as_deref(),
+            end_key: self.end_key.as_deref(),
+            limit: self.limit.unwrap_or(usize::MAX),
+            writer: RefCell::new(RowWriter::new(self.format, BufWriter::new(file))),
+        };
+
+        let rows = self.table.view(&viewer)?;
+        viewer.writer.into_inner().finish()?;
+
+        info!("Exported {rows} rows of table `{}` to {}", self.table, self.output.display());
+        Ok(())
+    }
+}
+
+struct ExportViewer<'a, DB: Database> {
+    tool: &'a DbTool<DB>,
+    start_key: Option<&'a str>,
+    end_key: Option<&'a str>,
+    limit: usize,
+    writer: RefCell<RowWriter<BufWriter<File>>>,
+}
+
+impl<DB: Database> ExportViewer<'_, DB> {
+    /// Streams the rows of the table to the writer, returning the number of exported rows.
+    ///
+    /// If a subkey decoder is given, the subkey of every raw value is exported in a separate
+    /// column.
+    fn export<T: Table>(&self, subkey: Option<SubKeyDecoder>) -> eyre::Result<usize> {
+        let provider =
+            self.tool.provider_factory.provider()?.disable_long_read_transaction_safety();
+        let tx = provider.tx_ref();
+
+        let start = match self.start_key {
+            Some(start) => Bound::Included(RawKey::<T::Key>::new(table_key::<T>(start)?)),
+            None => Bound::Unbounded,
+        };
+        let end = match self.end_key {
+            Some(end) => Bound::Included(RawKey::<T::Key>::new(table_key::<T>(end)?)),
+            None => Bound::Unbounded,
+        };
+
+        let mut cursor = tx.cursor_read::<RawTable<T>>()?;
+        let walker = cursor.walk_range((start, end))?;
+
+        let mut writer = self.writer.borrow_mut();
+        writer.write_header(subkey.is_some())?;
+
+        let mut rows = 0;
+        for (index, entry) in walker.take(self.limit).enumerate() {
+            let (k, v) = entry?;
+
+            if index % 100_000 == 0 {
+                info!("Exported {index} rows of table `{}`.", T::NAME);
+            }
+
+            let subkey = subkey.map(|decode| decode(v.raw_value())).transpose()?;
+            let key = serde_json::to_value(k.key()?)?;
+            let value = serde_json::to_value(v.value()?)?;
+            writer.write_row(key, subkey, value)?;
+            rows += 1;
+        }
+
+        Ok(rows)
+    }
+}
+
+impl<DB: Database> TableViewer<usize> for ExportViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        self.export::<T>(None)
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<usize, Self::Error> {
+        self.export::<T>(Some(decode_subkey::<T>))
+    }
+}
+
+/// Decodes the subkey of a DupSort table from a raw value.
+type SubKeyDecoder = fn(&[u8]) -> eyre::Result<JsonValue>;
+
+/// Decodes the subkey a raw DupSort value is prefixed with.
+fn decode_subkey<T: DupSort>(value: &[u8]) -> eyre::Result<JsonValue> {
+    let len = dupsort_subkey_len(T::TABLE)
+        .ok_or_else(|| eyre::eyre!("unknown subkey length for table {}", T::NAME))?;
+    let subkey = value
+        .get(..len)
+        .ok_or_else(|| eyre::eyre!("value of table {} is shorter than its subkey", T::NAME))?;
+    Ok(serde_json::to_value(T::SubKey::decode(subkey)?)?)
+}
+
+/// Returns the length of the encoded subkey of a DupSort table.
+///
+/// All DupSort subkeys are fixed size, and the values of DupSort tables start with the encoded
+/// subkey so they can be found with `seek_by_key_subkey`.
+pub(crate) const fn dupsort_subkey_len(table: Tables) -> Option<usize> {
+    match table {
+        Tables::PlainStorageState | Tables::StorageChangeSets | Tables::HashedStorages => Some(32),
+        Tables::AccountChangeSets => Some(20),
+        // 64 nibbles, padded, and the length
+        Tables::StoragesTrie => Some(65),
+        _ => None,
+    }
+}
+
+/// Writes table rows in the given [`ExportFormat`].
+pub(crate) struct RowWriter<W> {
+    format: ExportFormat,
+    writer: W,
+}
+
+impl<W: Write> RowWriter<W> {
+    /// Creates a new row writer.
+    pub(crate) const fn new(format: ExportFormat, writer: W) -> Self {
+        Self { format, writer }
+    }
+
+    /// Writes the CSV header, if the format has one.
+    pub(crate) fn write_header(&mut self, with_subkey: bool) -> eyre::Result<()> {
+        if self.format == ExportFormat::Csv {
+            if with_subkey {
+                writeln!(self.writer, "key,subkey,value")?;
+            } else {
+                writeln!(self.writer, "key,value")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a single row.
+    pub(crate) fn write_row(
+        &mut self,
+        key: JsonValue,
+        subkey: Option<JsonValue>,
+        value: JsonValue,
+    ) -> eyre::Result<()> {
+        match self.format {
+            ExportFormat::Csv => {
+                write!(self.writer, "{},", csv_field(&key))?;
+                if let Some(subkey) = &subkey {
+                    write!(self.writer, "{},", csv_field(subkey))?;
+                }
+                writeln!(self.writer, "{}", csv_field(&value))?;
+            }
+            ExportFormat::Jsonl => {
+                let mut row = serde_json::Map::new();
+                row.insert("key".to_string(), key);
+                if let Some(subkey) = subkey {
+                    row.insert("subkey".to_string(), subkey);
+                }
+                row.insert("value".to_string(), value);
+                serde_json::to_writer(&mut self.writer, &row)?;
+                writeln!(self.writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Flushes the underlying writer.
+    fn finish(mut self) -> eyre::Result<()> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Encodes the value as JSON and quotes it as a CSV field.
+fn csv_field(value: &JsonValue) -> String {
+    format!("\"{}\"", value.to_string().replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::PlainStorageState;
+    use reth_primitives::B256;
+
+    #[test]
+    fn write_csv_rows() {
+        let mut writer = RowWriter::new(ExportFormat::Csv, Vec::new());
+        writer.write_header(true).unwrap();
+        writer
+            .write_row(
+                serde_json::json!("0x01"),
+                Some(serde_json::json!(1)),
+                serde_json::json!({ "a": 2 }),
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(writer.writer).unwrap(),
+            "key,subkey,value\n\"\"\"0x01\"\"\",\"1\",\"{\"\"a\"\":2}\"\n"
+        );
+    }
+
+    #[test]
+    fn write_jsonl_rows() {
+        let mut writer = RowWriter::new(ExportFormat::Jsonl, Vec::new());
+        writer.write_header(false).unwrap();
+        writer.write_row(serde_json::json!(1), None, serde_json::json!("0x02")).unwrap();
+
+        assert_eq!(String::from_utf8(writer.writer).unwrap(), "{\"key\":1,\"value\":\"0x02\"}\n");
+    }
+
+    #[test]
+    fn decode_dupsort_subkey() {
+        let mut value = B256::repeat_byte(1).to_vec();
+        value.extend_from_slice(&[0xff, 0xff]);
+
+        assert_eq!(
+            decode_subkey::<PlainStorageState>(&value).unwrap(),
+            serde_json::to_value(B256::repeat_byte(1)).unwrap()
+        );
+        assert!(decode_subkey::<PlainStorageState>(&value[..31]).is_err());
+    }
+}
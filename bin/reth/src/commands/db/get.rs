@@ -2,11 +2,15 @@ use crate::utils::DbTool;
 use clap::Parser;
 use reth_db::{
     database::Database,
-    static_file::{ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask, TransactionMask},
-    table::{Decompress, DupSort, Table},
-    tables, RawKey, RawTable, Receipts, TableViewer, Transactions,
+    static_file::{
+        ColumnSelectorOne, ColumnSelectorTwo, HeaderMask, ReceiptMask, TransactionMask,
+        TransactionSenderMask,
+    },
+    table::{Decompress, DupSort, Table, TableRow},
+    tables, RawDupSort, RawKey, RawTable, RawValue, Receipts, TableViewer, TransactionSenders,
+    Transactions,
 };
-use reth_primitives::{BlockHash, Header, StaticFileSegment};
+use reth_primitives::{hex, BlockHash, Bytes, Header, StaticFileSegment};
 use tracing::error;
 
 /// The arguments for the `reth db get` command
@@ -23,16 +27,39 @@ enum Subcommand {
         table: tables::Tables,
 
         /// The key to get content for
-        #[arg(value_parser = maybe_json_value_parser)]
-        key: String,
+        #[arg(value_parser = maybe_json_value_parser, required_unless_present = "key_hex")]
+        key: Option<String>,
 
         /// The subkey to get content for
         #[arg(value_parser = maybe_json_value_parser)]
         subkey: Option<String>,
 
-        /// Output bytes instead of human-readable decoded value
+        /// The raw encoded key to get content for, as hex.
+        ///
+        /// Useful for entries whose decoded key can't be expressed as JSON. For DupSort tables
+        /// the first duplicate of the key is returned.
+        #[arg(long, value_name = "HEX", conflicts_with = "key")]
+        key_hex: Option<Bytes>,
+
+        /// Output the raw key and value bytes as hex instead of the human-readable decoded value.
+        ///
+        /// The value is not decompressed, so this also works for entries that fail to decode.
         #[arg(long)]
         raw: bool,
+
+        /// Walk forward from the key and print the next N entries, including the key itself.
+        ///
+        /// For DupSort tables every duplicate is printed as its own entry.
+        #[arg(long, value_name = "N", conflicts_with_all = ["raw", "key_hex"])]
+        count: Option<usize>,
+
+        /// Walk backwards from the key instead of forward
+        #[arg(long, requires = "count")]
+        reverse: bool,
+
+        /// Print the entries as line-delimited JSON records
+        #[arg(long, requires = "count")]
+        json: bool,
     },
     /// Gets the content of a static file segment for the given key
     StaticFile {
@@ -52,9 +79,8 @@ impl Command {
     /// Execute `db get` command
     pub fn execute<DB: Database>(self, tool: &DbTool<DB>) -> eyre::Result<()> {
         match self.subcommand {
-            Subcommand::Mdbx { table, key, subkey, raw } => {
-                table.view(&GetValueViewer { tool, key, subkey, raw })?
-            }
+            Subcommand::Mdbx { table, key, subkey, key_hex, raw, count, reverse, json } => table
+                .view(&GetValueViewer { tool, key, subkey, key_hex, raw, count, reverse, json })?,
             Subcommand::StaticFile { segment, key, raw } => {
                 let (key, mask): (u64, _) = match segment {
                     StaticFileSegment::Headers => {
@@ -68,6 +94,10 @@ impl Command {
                         table_key::<tables::Receipts>(&key)?,
                         <ReceiptMask<<Receipts as Table>::Value>>::MASK,
                     ),
+                    StaticFileSegment::TransactionSenders => (
+                        table_key::<tables::TransactionSenders>(&key)?,
+                        <TransactionSenderMask<<TransactionSenders as Table>::Value>>::MASK,
+                    ),
                 };
 
                 let content = tool.provider_factory.static_file_provider().find_static_file(
@@ -109,6 +139,13 @@ impl Command {
                                     )?;
                                     println!("{}", serde_json::to_string_pretty(&receipt)?);
                                 }
+                                StaticFileSegment::TransactionSenders => {
+                                    let sender =
+                                        <<TransactionSenders as Table>::Value>::decompress(
+                                            content[0].as_slice(),
+                                        )?;
+                                    println!("{}", serde_json::to_string_pretty(&sender)?);
+                                }
                             }
                         }
                     }
@@ -124,7 +161,7 @@ impl Command {
 }
 
 /// Get an instance of key for given table
-fn table_key<T: Table>(key: &str) -> Result<T::Key, eyre::Error> {
+pub(crate) fn table_key<T: Table>(key: &str) -> Result<T::Key, eyre::Error> {
     serde_json::from_str::<T::Key>(key).map_err(|e| eyre::eyre!(e))
 }
 
@@ -136,24 +173,94 @@ fn table_subkey<T: DupSort>(subkey: &Option<String>) -> Result<T::SubKey, eyre::
 
 struct GetValueViewer<'a, DB: Database> {
     tool: &'a DbTool<DB>,
-    key: String,
+    key: Option<String>,
     subkey: Option<String>,
+    key_hex: Option<Bytes>,
     raw: bool,
+    count: Option<usize>,
+    reverse: bool,
+    json: bool,
+}
+
+impl<DB: Database> GetValueViewer<'_, DB> {
+    /// Returns the JSON encoded key.
+    fn json_key(&self) -> &str {
+        self.key.as_deref().expect("key is required unless `--key-hex` is set")
+    }
+
+    /// Returns the raw key, either as passed with `--key-hex` or encoded from the JSON key.
+    fn raw_key<T: Table>(&self) -> eyre::Result<RawKey<T::Key>> {
+        match &self.key_hex {
+            Some(key) => Ok(RawKey::from_vec(key.to_vec())),
+            None => Ok(RawKey::new(table_key::<T>(self.json_key())?)),
+        }
+    }
+
+    /// Prints a value read from a [`RawTable`], either as hex together with the raw key, or
+    /// decoded.
+    fn print_raw_value<T: Table>(
+        &self,
+        key: RawKey<T::Key>,
+        value: Option<RawValue<T::Value>>,
+    ) -> eyre::Result<()> {
+        let Some(value) = value else {
+            error!(target: "reth::cli", "No content for the given table key.");
+            return Ok(())
+        };
+
+        if self.raw {
+            println!(
+                "{}\n{}",
+                hex::encode_prefixed(key.raw_key()),
+                hex::encode_prefixed(value.raw_value())
+            );
+        } else {
+            println!("{}", serde_json::to_string_pretty(&value.value()?)?);
+        }
+
+        Ok(())
+    }
+
+    /// Prints the given rows, either pretty printed or as line-delimited JSON records.
+    fn print_rows<T: Table>(&self, rows: Vec<TableRow<T>>) -> eyre::Result<()> {
+        if rows.is_empty() {
+            error!(target: "reth::cli", "No content for the given table key.");
+        }
+
+        for (key, value) in rows {
+            if self.json {
+                println!("{}", serde_json::json!({ "key": key, "value": value }));
+            } else {
+                println!(
+                    "{}\n{}",
+                    serde_json::to_string_pretty(&key)?,
+                    serde_json::to_string_pretty(&value)?
+                );
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<DB: Database> TableViewer<()> for GetValueViewer<'_, DB> {
     type Error = eyre::Report;
 
     fn view<T: Table>(&self) -> Result<(), Self::Error> {
-        let key = table_key::<T>(&self.key)?;
+        if self.raw || self.key_hex.is_some() {
+            let key = self.raw_key::<T>()?;
+            let content = self.tool.get::<RawTable<T>>(key.clone())?;
+            return self.print_raw_value::<T>(key, content)
+        }
 
-        let content = if self.raw {
-            self.tool
-                .get::<RawTable<T>>(RawKey::from(key))?
-                .map(|content| format!("{:?}", content.raw_value()))
-        } else {
-            self.tool.get::<T>(key)?.as_ref().map(serde_json::to_string_pretty).transpose()?
-        };
+        let key = table_key::<T>(self.json_key())?;
+
+        if let Some(count) = self.count {
+            return self.print_rows::<T>(self.tool.walk::<T>(key, count, self.reverse)?)
+        }
+
+        let content =
+            self.tool.get::<T>(key)?.as_ref().map(serde_json::to_string_pretty).transpose()?;
 
         match content {
             Some(content) => {
@@ -168,8 +275,30 @@ impl<DB: Database> TableViewer<()> for GetValueViewer<'_, DB> {
     }
 
     fn view_dupsort<T: DupSort>(&self) -> Result<(), Self::Error> {
+        if self.raw || self.key_hex.is_some() {
+            if self.subkey.is_none() {
+                return self.view::<T>()
+            }
+
+            let key = self.raw_key::<T>()?;
+            let subkey = RawKey::new(table_subkey::<T>(&self.subkey)?);
+            let content = self.tool.get_dup::<RawDupSort<T>>(key.clone(), subkey)?;
+            return self.print_raw_value::<T>(key, content)
+        }
+
         // get a key for given table
-        let key = table_key::<T>(&self.key)?;
+        let key = table_key::<T>(self.json_key())?;
+
+        if let Some(count) = self.count {
+            let rows = match &self.subkey {
+                Some(_) => {
+                    let subkey = table_subkey::<T>(&self.subkey)?;
+                    self.tool.walk_dup::<T>(key, subkey, count, self.reverse)?
+                }
+                None => self.tool.walk::<T>(key, count, self.reverse)?,
+            };
+            return self.print_rows::<T>(rows)
+        }
 
         // process dupsort table
         let subkey = table_subkey::<T>(&self.subkey)?;
@@ -187,7 +316,7 @@ impl<DB: Database> TableViewer<()> for GetValueViewer<'_, DB> {
 }
 
 /// Map the user input value to json
-fn maybe_json_value_parser(value: &str) -> Result<String, eyre::Error> {
+pub(crate) fn maybe_json_value_parser(value: &str) -> Result<String, eyre::Error> {
     if serde_json::from_str::<serde::de::IgnoredAny>(value).is_ok() {
         Ok(value.to_string())
     } else {
@@ -213,6 +342,44 @@ mod tests {
         args: T,
     }
 
+    #[test]
+    fn parse_count_args() {
+        let cmd = Command::try_parse_from([
+            "reth",
+            "mdbx",
+            "Headers",
+            "1",
+            "--count",
+            "5",
+            "--reverse",
+            "--json",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cmd.subcommand,
+            Subcommand::Mdbx { count: Some(5), reverse: true, json: true, .. }
+        ));
+
+        assert!(Command::try_parse_from(["reth", "mdbx", "Headers", "1", "--reverse"]).is_err());
+        assert!(Command::try_parse_from(["reth", "mdbx", "Headers", "1", "--json"]).is_err());
+    }
+
+    #[test]
+    fn parse_key_hex_args() {
+        let cmd =
+            Command::try_parse_from(["reth", "mdbx", "Headers", "--key-hex", "0x0a", "--raw"])
+                .unwrap();
+        assert!(matches!(
+            cmd.subcommand,
+            Subcommand::Mdbx { key: None, key_hex: Some(ref key), raw: true, .. } if key[..] == [0x0a]
+        ));
+
+        assert!(Command::try_parse_from(["reth", "mdbx", "Headers"]).is_err());
+        assert!(
+            Command::try_parse_from(["reth", "mdbx", "Headers", "1", "--key-hex", "0x0a"]).is_err()
+        );
+    }
+
     #[test]
     fn parse_numeric_key_args() {
         assert_eq!(table_key::<Headers>("123").unwrap(), 123);
@@ -0,0 +1,445 @@
+use crate::commands::db::export::ExportFormat;
+use clap::Parser;
+use eyre::WrapErr;
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRW},
+    database::Database,
+    table::{DupSort, Encode, Table},
+    transaction::{DbTx, DbTxMut},
+    RawDupSort, RawKey, RawTable, RawValue, TableViewer, Tables,
+};
+use reth_primitives::hex;
+use reth_provider::ProviderFactory;
+use serde_json::Value as JsonValue;
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Lines},
+    path::{Path, PathBuf},
+};
+use tracing::{info, warn};
+
+/// The arguments for the `reth db import` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The table name
+    table: Tables,
+
+    /// The format of the rows in the input file
+    #[arg(long, value_enum, default_value_t = ExportFormat::Jsonl)]
+    format: ExportFormat,
+
+    /// The file to read the rows from, as written by `reth db export`
+    #[arg(long, value_name = "FILE")]
+    input: PathBuf,
+
+    /// The number of rows written per transaction
+    #[arg(long, default_value_t = DEFAULT_BATCH_SIZE)]
+    batch_size: usize,
+
+    /// Insert the rows with `append` instead of `upsert`.
+    ///
+    /// This is a lot faster, but requires the rows to be sorted and to come after all existing
+    /// entries of the table.
+    #[arg(long)]
+    append: bool,
+
+    /// Fall back to `upsert` once an out of order key is found instead of aborting
+    #[arg(long, requires = "append")]
+    upsert_unordered: bool,
+
+    /// Only check that every row can be decoded, without writing to the database
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// The default number of rows written per transaction.
+const DEFAULT_BATCH_SIZE: usize = 10_000;
+
+impl Command {
+    /// Execute `db import` command
+    pub fn execute<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> eyre::Result<()> {
+        eyre::ensure!(self.batch_size > 0, "--batch-size must be greater than zero");
+        if !self.dry_run {
+            warn!("This command should be run without the node running!");
+        }
+
+        let rows = self.table.view(&ImportViewer {
+            db: provider_factory.db_ref(),
+            input: &self.input,
+            format: self.format,
+            batch_size: self.batch_size,
+            append: self.append,
+            upsert_unordered: self.upsert_unordered,
+            dry_run: self.dry_run,
+        })?;
+
+        if self.dry_run {
+            info!("Successfully decoded {rows} rows for table `{}`.", self.table);
+        } else {
+            info!("Imported {rows} rows into table `{}`.", self.table);
+        }
+        Ok(())
+    }
+}
+
+struct ImportViewer<'a, DB: Database> {
+    db: &'a DB,
+    input: &'a Path,
+    format: ExportFormat,
+    batch_size: usize,
+    append: bool,
+    upsert_unordered: bool,
+    dry_run: bool,
+}
+
+impl<DB: Database> ImportViewer<'_, DB> {
+    /// Opens the input file and returns an iterator over the encoded rows.
+    fn rows<T: Table>(
+        &self,
+    ) -> eyre::Result<
+        impl Iterator<Item = eyre::Result<(RawKey<T::Key>, RawValue<T::Value>, Option<JsonValue>)>>,
+    > {
+        let file = File::open(self.input)
+            .wrap_err_with(|| format!("Could not open {}", self.input.display()))?;
+        let reader = RowReader::new(self.format, BufReader::new(file));
+
+        Ok(reader.enumerate().map(|(index, row)| {
+            let row = row.and_then(|row| {
+                let key = serde_json::from_value::<T::Key>(row.key)?;
+                let value = serde_json::from_value::<T::Value>(row.value)?;
+                Ok((RawKey::new(key), RawValue::new(value), row.subkey))
+            });
+            row.wrap_err_with(|| format!("Invalid row {} in {}", index + 1, self.input.display()))
+        }))
+    }
+
+    /// Returns the order tracker for the append mode, seeded with the last entry of the table.
+    fn append_order<T: Table>(
+        &self,
+        last: Option<(RawKey<T::Key>, RawValue<T::Value>)>,
+    ) -> AppendOrder {
+        AppendOrder {
+            appending: self.append,
+            upsert_unordered: self.upsert_unordered,
+            last: last.map(|(key, value)| (key.raw_key().clone(), value.raw_value().to_vec())),
+        }
+    }
+}
+
+impl<DB: Database> TableViewer<usize> for ImportViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<usize, Self::Error> {
+        let mut rows = self.rows::<T>()?;
+        if self.dry_run {
+            return rows.try_fold(0, |count, row| row.map(|_| count + 1))
+        }
+
+        let mut order =
+            self.append_order::<T>(self.db.view(|tx| tx.cursor_read::<RawTable<T>>()?.last())??);
+        let mut total = 0;
+        loop {
+            let tx = self.db.tx_mut()?;
+            let mut cursor = tx.cursor_write::<RawTable<T>>()?;
+
+            let mut written = 0;
+            for row in rows.by_ref().take(self.batch_size) {
+                let (key, value, _) = row?;
+                if order.can_append(key.raw_key(), None)? {
+                    cursor.append(key, value)?;
+                } else {
+                    cursor.upsert(key, value)?;
+                }
+                written += 1;
+            }
+
+            drop(cursor);
+            tx.commit()?;
+            total += written;
+            info!("Imported {total} rows into table `{}`.", T::NAME);
+
+            if written < self.batch_size {
+                return Ok(total)
+            }
+        }
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<usize, Self::Error> {
+        let mut rows = self.rows::<T>().map(|rows| {
+            rows.map(|row| {
+                let (key, value, subkey) = row?;
+                if let Some(subkey) = subkey {
+                    ensure_subkey::<T>(subkey, &value)?;
+                }
+                Ok::<_, eyre::Report>((key, value))
+            })
+        })?;
+        if self.dry_run {
+            return rows.try_fold(0, |count, row| row.map(|_| count + 1))
+        }
+
+        let mut order =
+            self.append_order::<T>(self.db.view(|tx| tx.cursor_read::<RawDupSort<T>>()?.last())??);
+        let mut total = 0;
+        loop {
+            let tx = self.db.tx_mut()?;
+            let mut cursor = tx.cursor_dup_write::<RawDupSort<T>>()?;
+
+            let mut written = 0;
+            for row in rows.by_ref().take(self.batch_size) {
+                let (key, value) = row?;
+                let same_key = order.last.as_ref().is_some_and(|(last, _)| last == key.raw_key());
+                if order.can_append(key.raw_key(), Some(value.raw_value()))? {
+                    if same_key {
+                        cursor.append_dup(key, value)?;
+                    } else {
+                        cursor.append(key, value)?;
+                    }
+                } else {
+                    cursor.upsert(key, value)?;
+                }
+                written += 1;
+            }
+
+            drop(cursor);
+            tx.commit()?;
+            total += written;
+            info!("Imported {total} rows into table `{}`.", T::NAME);
+
+            if written < self.batch_size {
+                return Ok(total)
+            }
+        }
+    }
+}
+
+/// Checks that the value of a DupSort row starts with the subkey of the row.
+fn ensure_subkey<T: DupSort>(subkey: JsonValue, value: &RawValue<T::Value>) -> eyre::Result<()> {
+    let subkey = serde_json::from_value::<T::SubKey>(subkey)?.encode();
+    eyre::ensure!(
+        value.raw_value().starts_with(subkey.as_ref()),
+        "The subkey does not match the subkey of the value"
+    );
+    Ok(())
+}
+
+/// Tracks the last written entry, to decide whether the next row can be appended.
+#[derive(Debug)]
+struct AppendOrder {
+    appending: bool,
+    upsert_unordered: bool,
+    last: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+impl AppendOrder {
+    /// Returns `true` if the row comes after the last written entry and can be appended.
+    ///
+    /// For DupSort tables, rows with the same key must also have increasing values. If the row is
+    /// out of order, this either switches to upsert for all remaining rows or returns an error,
+    /// depending on `upsert_unordered`.
+    fn can_append(&mut self, key: &[u8], value: Option<&[u8]>) -> eyre::Result<bool> {
+        if !self.appending {
+            return Ok(false)
+        }
+
+        let ordered = match (&self.last, value) {
+            (None, _) => true,
+            (Some((last_key, _)), None) => key > last_key.as_slice(),
+            (Some((last_key, last_value)), Some(value)) => {
+                (key, value) > (last_key.as_slice(), last_value.as_slice())
+            }
+        };
+
+        if ordered {
+            self.last = Some((key.to_vec(), value.unwrap_or_default().to_vec()));
+            return Ok(true)
+        }
+
+        if !self.upsert_unordered {
+            eyre::bail!(
+                "Key {} is not greater than the previously written key {}, so it can't be \
+                 appended. Sort the input, or pass --upsert-unordered to fall back to upsert.",
+                hex::encode_prefixed(key),
+                hex::encode_prefixed(
+                    self.last.as_ref().map(|(key, _)| key.as_slice()).unwrap_or_default()
+                ),
+            )
+        }
+
+        warn!(
+            "Key {} is out of order, falling back to upsert for the remaining rows.",
+            hex::encode_prefixed(key)
+        );
+        self.appending = false;
+        Ok(false)
+    }
+}
+
+/// A row of an exported table, with the key, subkey and value decoded as JSON.
+#[derive(Debug, PartialEq, Eq, serde::Deserialize)]
+struct ExportedRow {
+    key: JsonValue,
+    #[serde(default)]
+    subkey: Option<JsonValue>,
+    value: JsonValue,
+}
+
+/// Reads the rows of an exported table in the given [`ExportFormat`].
+struct RowReader<R> {
+    format: ExportFormat,
+    lines: Lines<R>,
+    /// The CSV columns, read from the header.
+    columns: Option<Vec<String>>,
+}
+
+impl<R: BufRead> RowReader<R> {
+    /// Creates a new row reader.
+    fn new(format: ExportFormat, reader: R) -> Self {
+        Self { format, lines: reader.lines(), columns: None }
+    }
+
+    /// Parses a single non-empty line.
+    fn parse_line(&mut self, line: &str) -> eyre::Result<Option<ExportedRow>> {
+        match self.format {
+            ExportFormat::Jsonl => Ok(Some(serde_json::from_str(line)?)),
+            ExportFormat::Csv => {
+                let fields = parse_csv_line(line)?;
+                let Some(columns) = &self.columns else {
+                    eyre::ensure!(
+                        fields == ["key", "value"] || fields == ["key", "subkey", "value"],
+                        "Invalid CSV header, expected `key,value` or `key,subkey,value`"
+                    );
+                    self.columns = Some(fields);
+                    return Ok(None)
+                };
+                eyre::ensure!(
+                    fields.len() == columns.len(),
+                    "Expected {} fields, found {}",
+                    columns.len(),
+                    fields.len()
+                );
+
+                let mut row = serde_json::Map::new();
+                for (column, field) in columns.iter().zip(fields) {
+                    row.insert(column.clone(), serde_json::from_str(&field)?);
+                }
+                Ok(Some(serde_json::from_value(JsonValue::Object(row))?))
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for RowReader<R> {
+    type Item = eyre::Result<ExportedRow>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(err) => return Some(Err(err.into())),
+            };
+            if line.trim().is_empty() {
+                continue
+            }
+            match self.parse_line(&line) {
+                Ok(Some(row)) => return Some(Ok(row)),
+                Ok(None) => continue,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+/// Splits a CSV line into its fields, unquoting quoted fields.
+fn parse_csv_line(line: &str) -> eyre::Result<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    loop {
+        let mut field = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        field.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => field.push(c),
+                    None => eyre::bail!("Unterminated quoted CSV field"),
+                }
+            }
+            eyre::ensure!(
+                matches!(chars.peek(), None | Some(',')),
+                "Unexpected character after quoted CSV field"
+            );
+        } else {
+            while let Some(c) = chars.next_if(|c| *c != ',') {
+                field.push(c);
+            }
+        }
+        fields.push(field);
+
+        if chars.next().is_none() {
+            return Ok(fields)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::db::export::RowWriter;
+
+    #[test]
+    fn read_exported_rows() {
+        for format in [ExportFormat::Csv, ExportFormat::Jsonl] {
+            let rows = vec![
+                ExportedRow {
+                    key: serde_json::json!("0x01"),
+                    subkey: Some(serde_json::json!(1)),
+                    value: serde_json::json!({ "a": "b,\"c\"" }),
+                },
+                ExportedRow {
+                    key: serde_json::json!(2),
+                    subkey: Some(serde_json::json!(2)),
+                    value: serde_json::json!([]),
+                },
+            ];
+
+            let mut buf = Vec::new();
+            let mut writer = RowWriter::new(format, &mut buf);
+            writer.write_header(true).unwrap();
+            for row in &rows {
+                writer.write_row(row.key.clone(), row.subkey.clone(), row.value.clone()).unwrap();
+            }
+
+            let reader = RowReader::new(format, buf.as_slice());
+            let read = reader.collect::<eyre::Result<Vec<_>>>().unwrap();
+            assert_eq!(read, rows, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn parse_csv_fields() {
+        assert_eq!(parse_csv_line("key,value").unwrap(), ["key", "value"]);
+        assert_eq!(parse_csv_line("\"a,\"\"b\"\"\",,c").unwrap(), ["a,\"b\"", "", "c"]);
+        assert!(parse_csv_line("\"a").is_err());
+        assert!(parse_csv_line("\"a\"b").is_err());
+    }
+
+    #[test]
+    fn append_order() {
+        let mut order = AppendOrder { appending: true, upsert_unordered: false, last: None };
+        assert!(order.can_append(&[1], None).unwrap());
+        assert!(order.can_append(&[2], None).unwrap());
+        assert!(order.can_append(&[2], None).is_err());
+
+        let mut order = AppendOrder { appending: true, upsert_unordered: true, last: None };
+        assert!(order.can_append(&[1], Some(&[1])).unwrap());
+        assert!(order.can_append(&[1], Some(&[2])).unwrap());
+        assert!(!order.can_append(&[1], Some(&[0])).unwrap());
+        assert!(!order.can_append(&[3], Some(&[0])).unwrap());
+    }
+}
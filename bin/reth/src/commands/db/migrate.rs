@@ -0,0 +1,134 @@
+use clap::Parser;
+use eyre::WrapErr;
+use human_bytes::human_bytes;
+use reth_db::{
+    database::Database,
+    migration::{
+        visit_migrations, MigrationVisitor, Migrator, TableMigration, DEFAULT_MIGRATION_BATCH_SIZE,
+    },
+};
+use reth_provider::ProviderFactory;
+use std::collections::HashSet;
+use tracing::warn;
+
+/// The default number of entries per migration validated by a dry run.
+const DEFAULT_DRY_RUN_SAMPLE: usize = 1_000;
+
+/// The arguments for the `reth db migrate` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Validates the pending migrations over a sample of their entries, without writing to the
+    /// database
+    #[arg(long)]
+    dry_run: bool,
+
+    /// The number of entries per migration that are validated with `--dry-run`
+    #[arg(long, requires = "dry_run", default_value_t = DEFAULT_DRY_RUN_SAMPLE)]
+    sample: usize,
+
+    /// The number of entries that are migrated in a single write transaction.
+    ///
+    /// The progress is stored with every transaction, so an interrupted migration resumes after
+    /// the last committed batch.
+    #[arg(long, default_value_t = DEFAULT_MIGRATION_BATCH_SIZE)]
+    batch_size: usize,
+}
+
+impl Command {
+    /// Execute `db migrate` command
+    pub fn execute<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        eyre::ensure!(self.batch_size > 0, "--batch-size must not be zero");
+        let migrator = Migrator::new(provider_factory.db_ref(), self.batch_size);
+
+        let pending = migrator
+            .statuses()?
+            .into_iter()
+            .filter(|status| status.is_pending())
+            .inspect(|status| {
+                let progress =
+                    status.checkpoint.as_ref().map_or(0, |checkpoint| checkpoint.entries);
+                println!(
+                    "Pending migration {}: {} ({} -> {}, {progress} entries migrated)",
+                    status.version, status.description, status.source, status.target
+                );
+            })
+            .map(|status| status.version)
+            .collect::<HashSet<_>>();
+        if pending.is_empty() {
+            println!("The database has no pending migrations.");
+            return Ok(())
+        }
+
+        visit_migrations(&mut MigrateVisitor {
+            migrator: &migrator,
+            pending,
+            sample: self.dry_run.then_some(self.sample),
+        })
+    }
+}
+
+/// Runs or validates every pending migration.
+struct MigrateVisitor<'a, DB> {
+    migrator: &'a Migrator<'a, DB>,
+    /// The versions of the pending migrations
+    pending: HashSet<u64>,
+    /// The number of entries to validate, if it's a dry run
+    sample: Option<usize>,
+}
+
+impl<DB: Database> MigrationVisitor for MigrateVisitor<'_, DB> {
+    type Error = eyre::Report;
+
+    fn visit<M: TableMigration>(&mut self, migration: &M) -> Result<(), Self::Error> {
+        if !self.pending.contains(&M::VERSION) {
+            return Ok(())
+        }
+
+        match self.sample {
+            Some(sample) => {
+                let report = self
+                    .migrator
+                    .dry_run(migration, sample)
+                    .wrap_err_with(|| format!("dry run of migration {} failed", M::VERSION))?;
+                println!(
+                    "Migration {}: validated {} entries, {} would be dropped, {} -> {}",
+                    M::VERSION,
+                    report.sampled,
+                    report.dropped,
+                    human_bytes(report.source_bytes as f64),
+                    human_bytes(report.target_bytes as f64)
+                );
+            }
+            None => {
+                println!("Running migration {}: {}", M::VERSION, M::DESCRIPTION);
+                let checkpoint = self
+                    .migrator
+                    .run(migration)
+                    .wrap_err_with(|| format!("migration {} failed", M::VERSION))?;
+                println!("Finished migration {} ({} entries)", M::VERSION, checkpoint.entries);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_migrate_args() {
+        let cmd = Command::try_parse_from(["reth"]).unwrap();
+        assert!(!cmd.dry_run);
+        assert_eq!(cmd.batch_size, DEFAULT_MIGRATION_BATCH_SIZE);
+
+        let cmd = Command::try_parse_from(["reth", "--dry-run", "--sample", "10"]).unwrap();
+        assert!(cmd.dry_run);
+        assert_eq!(cmd.sample, 10);
+
+        assert!(Command::try_parse_from(["reth", "--sample", "10"]).is_err());
+    }
+}
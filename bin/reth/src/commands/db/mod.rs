@@ -5,6 +5,7 @@ use crate::{
         utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
         DatabaseArgs,
     },
+    commands::db::backup::BackupPaths,
     dirs::{DataDirPath, MaybePlatformPath},
     utils::DbTool,
 };
@@ -20,15 +21,27 @@ use std::{
     sync::Arc,
 };
 
+mod backup;
 mod checksum;
 mod clear;
+mod compact;
+mod delete_key;
 mod diff;
+mod export;
 mod get;
+mod import;
 mod list;
+mod migrate;
+mod prune_static_files;
+mod put;
+mod restore;
 mod static_files;
 mod stats;
 /// DB List TUI
 mod tui;
+mod verify;
+mod verify_static_files;
+mod verify_trie;
 
 /// `reth db` command
 #[derive(Debug, Parser)]
@@ -76,6 +89,22 @@ pub enum Subcommands {
     Diff(diff::Command),
     /// Gets the content of a table for the given key
     Get(get::Command),
+    /// Exports the decoded entries of a table to a CSV or JSONL file
+    Export(export::Command),
+    /// Imports table entries from a file written by `reth db export`
+    Import(import::Command),
+    /// Checks the referential integrity between tables
+    Verify(verify::Command),
+    /// Removes all static file rows above the given block
+    PruneStaticFilesAbove(prune_static_files::Command),
+    /// Checks the headers, offsets and block ranges of the static files
+    VerifyStaticFiles(verify_static_files::Command),
+    /// Checks the trie tables against the trie and the state root computed from the hashed state
+    VerifyTrie(verify_trie::Command),
+    /// Writes a single entry to a table, printing the value it replaces
+    Put(put::Command),
+    /// Deletes a single entry from a table, printing the deleted value
+    DeleteKey(delete_key::Command),
     /// Deletes all database entries
     Drop {
         /// Bypasses the interactive confirmation and drops the database directly
@@ -84,8 +113,16 @@ pub enum Subcommands {
     },
     /// Deletes all table entries
     Clear(clear::Command),
+    /// Runs the pending migrations of the database tables
+    Migrate(migrate::Command),
+    /// Copies the database into a compacted database, optionally replacing the database with it
+    Compact(compact::Command),
     /// Creates static files from database tables
     CreateStaticFiles(static_files::Command),
+    /// Writes a consistent backup of the database and static files, also while a node is running
+    Backup(backup::Command),
+    /// Restores a backup written by `reth db backup` into an empty data directory
+    Restore(restore::Command),
     /// Lists current and local database versions
     Version,
     /// Returns the full database path
@@ -104,6 +141,11 @@ macro_rules! db_ro_exec {
 }
 
 impl Command {
+    /// Returns `true` if the command prints its output to stdout, which must not contain any logs.
+    pub(crate) fn prints_to_stdout(&self) -> bool {
+        matches!(&self.command, Subcommands::Checksum(command) if command.prints_json())
+    }
+
     /// Execute `db` command
     pub async fn execute(self) -> eyre::Result<()> {
         // add network name to data dir
@@ -139,6 +181,52 @@ impl Command {
                     command.execute(&tool)?;
                 });
             }
+            Subcommands::Export(command) => {
+                db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
+                    command.execute(&tool)?;
+                });
+            }
+            Subcommands::Import(command) => {
+                let db = open_db(&db_path, db_args)?;
+                let provider_factory =
+                    ProviderFactory::new(db, self.chain.clone(), static_files_path)?;
+
+                command.execute(provider_factory)?;
+            }
+            Subcommands::Verify(command) => {
+                db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
+                    command.execute(&tool)?;
+                });
+            }
+            Subcommands::PruneStaticFilesAbove(command) => {
+                let db = open_db(&db_path, db_args)?;
+                let provider_factory =
+                    ProviderFactory::new(db, self.chain.clone(), static_files_path)?;
+
+                command.execute(provider_factory)?;
+            }
+            Subcommands::VerifyStaticFiles(command) => {
+                command.execute(data_dir)?;
+            }
+            Subcommands::VerifyTrie(command) => {
+                db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
+                    command.execute(&tool)?;
+                });
+            }
+            Subcommands::Put(command) => {
+                let db = open_db(&db_path, db_args)?;
+                let provider_factory =
+                    ProviderFactory::new(db, self.chain.clone(), static_files_path)?;
+
+                command.execute(provider_factory)?;
+            }
+            Subcommands::DeleteKey(command) => {
+                let db = open_db(&db_path, db_args)?;
+                let provider_factory =
+                    ProviderFactory::new(db, self.chain.clone(), static_files_path)?;
+
+                command.execute(provider_factory)?;
+            }
             Subcommands::Drop { force } => {
                 if !force {
                     // Ask for confirmation
@@ -169,9 +257,28 @@ impl Command {
 
                 command.execute(provider_factory)?;
             }
+            Subcommands::Migrate(command) => {
+                let db = open_db(&db_path, db_args)?;
+                let provider_factory =
+                    ProviderFactory::new(db, self.chain.clone(), static_files_path)?;
+
+                command.execute(provider_factory)?;
+            }
+            Subcommands::Compact(command) => {
+                command.execute(&db_path, db_args, self.chain.clone(), static_files_path)?;
+            }
             Subcommands::CreateStaticFiles(command) => {
                 command.execute(data_dir, self.db.database_args(), self.chain.clone())?;
             }
+            Subcommands::Backup(command) => {
+                db_ro_exec!(self.chain, &db_path, db_args, static_files_path, tool, {
+                    command.execute(&db_path, &tool, self.db.database_args())?;
+                });
+            }
+            Subcommands::Restore(command) => {
+                let target = BackupPaths { db: db_path, static_files: static_files_path };
+                command.execute(target, db_args)?;
+            }
             Subcommands::Version => {
                 let local_db_version = match get_db_version(&db_path) {
                     Ok(version) => Some(version),
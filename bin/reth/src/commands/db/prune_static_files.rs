@@ -0,0 +1,164 @@
+use clap::{Parser, ValueEnum};
+use reth_db::{database::Database, static_file::iter_static_files};
+use reth_primitives::{
+    stage::{StageCheckpoint, StageId},
+    static_file::find_fixed_range,
+    BlockNumber, StaticFileSegment,
+};
+use reth_provider::{
+    providers::StaticFileWriter, BlockReader, ProviderFactory, StageCheckpointReader,
+    StageCheckpointWriter,
+};
+use tracing::{info, warn};
+
+/// The arguments for the `reth db prune-static-files-above` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The last block to keep in the static files
+    block: BlockNumber,
+
+    /// Only prune the static files of these segments
+    #[arg(long = "segment", value_delimiter = ',')]
+    segments: Vec<StaticFileSegment>,
+
+    /// Only print what would be removed
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl Command {
+    /// Execute `db prune-static-files-above` command
+    ///
+    /// Rows above the block are removed with the static file writer, which deletes the files that
+    /// are entirely above the block, truncates the file containing it and updates the index of
+    /// highest static files. The static file producer derives its targets from that index.
+    ///
+    /// Once the rows of a segment are removed, the checkpoint of the stage that writes the segment
+    /// is reset to the block, so the node regenerates the pruned range.
+    pub fn execute<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> eyre::Result<()> {
+        if !self.dry_run {
+            warn!("This command should be run without the node running!");
+        }
+
+        let static_file_provider = provider_factory.static_file_provider();
+        let static_files = iter_static_files(static_file_provider.directory())?;
+        let provider = provider_factory.provider()?;
+
+        let segments = if self.segments.is_empty() {
+            StaticFileSegment::value_variants().to_vec()
+        } else {
+            self.segments.clone()
+        };
+
+        for segment in segments {
+            let Some(highest_block) = static_file_provider.get_highest_static_file_block(segment)
+            else {
+                info!("Segment {segment} has no static files.");
+                continue
+            };
+            if highest_block <= self.block {
+                info!("Segment {segment} ends at block {highest_block}, nothing to prune.");
+                continue
+            }
+
+            let rows = match segment {
+                StaticFileSegment::Headers => highest_block - self.block,
+                StaticFileSegment::Transactions |
+                StaticFileSegment::Receipts |
+                StaticFileSegment::TransactionSenders => {
+                    let next_tx = provider
+                        .block_body_indices(self.block)?
+                        .ok_or_else(|| {
+                            eyre::eyre!("Missing body indices for block {}", self.block)
+                        })?
+                        .next_tx_num();
+                    static_file_provider
+                        .get_highest_static_file_tx(segment)
+                        .map_or(0, |highest_tx| (highest_tx + 1).saturating_sub(next_tx))
+                }
+            };
+
+            let deleted_files = static_files
+                .get(&segment)
+                .into_iter()
+                .flatten()
+                .map(|(block_range, _)| find_fixed_range(block_range.start()))
+                .filter(|fixed_range| fixed_range.start() > self.block)
+                .map(|fixed_range| segment.filename(&fixed_range))
+                .collect::<Vec<_>>();
+
+            println!(
+                "Segment {segment}: removing blocks {}..={highest_block} ({rows} rows)",
+                self.block + 1
+            );
+            for file in &deleted_files {
+                println!("  deleting {file}");
+            }
+
+            let stage_id = segment_stage(segment);
+            let stage_checkpoint = provider.get_stage_checkpoint(stage_id)?;
+            let reset_checkpoint =
+                stage_checkpoint.filter(|checkpoint| checkpoint.block_number > self.block);
+            if let Some(checkpoint) = reset_checkpoint {
+                println!(
+                    "  resetting the {stage_id} stage checkpoint from block {} to block {}",
+                    checkpoint.block_number, self.block
+                );
+            }
+
+            if self.dry_run {
+                continue
+            }
+
+            let mut writer = static_file_provider.latest_writer(segment)?;
+            match segment {
+                StaticFileSegment::Headers => writer.prune_headers(rows)?,
+                StaticFileSegment::Transactions => writer.prune_transactions(rows, self.block)?,
+                StaticFileSegment::Receipts => writer.prune_receipts(rows, self.block)?,
+                StaticFileSegment::TransactionSenders => {
+                    writer.prune_transaction_senders(rows, self.block)?
+                }
+            }
+
+            // The rows are removed, so the stage has to write them again
+            if reset_checkpoint.is_some() {
+                let provider_rw = provider_factory.provider_rw()?;
+                provider_rw.save_stage_checkpoint(stage_id, StageCheckpoint::new(self.block))?;
+                provider_rw.commit()?;
+            }
+        }
+
+        if !self.dry_run {
+            warn!(
+                "Make sure the database was unwound to block {} as well, otherwise the node will \
+                 try to heal the static files on startup.",
+                self.block
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Returns the stage that writes the static files of the segment.
+const fn segment_stage(segment: StaticFileSegment) -> StageId {
+    match segment {
+        StaticFileSegment::Headers => StageId::Headers,
+        StaticFileSegment::Transactions => StageId::Bodies,
+        StaticFileSegment::Receipts => StageId::Execution,
+        StaticFileSegment::TransactionSenders => StageId::SenderRecovery,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args() {
+        let args =
+            Command::try_parse_from(["reth", "100", "--segment", "receipts", "--dry-run"]).unwrap();
+        assert_eq!(args.block, 100);
+        assert_eq!(args.segments, vec![StaticFileSegment::Receipts]);
+        assert!(args.dry_run);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::commands::db::get::{maybe_json_value_parser, table_key};
+use clap::Parser;
+use reth_db::{
+    cursor::{DbCursorRW, DbDupCursorRO},
+    database::Database,
+    table::{DupSort, Table},
+    transaction::{DbTx, DbTxMut},
+    RawDupSort, RawKey, RawTable, RawValue, TableViewer, Tables,
+};
+use reth_primitives::hex;
+use reth_provider::ProviderFactory;
+use tracing::warn;
+
+/// The arguments for the `reth db put` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The table name
+    table: Tables,
+
+    /// The key to write, as JSON or as hex if `--raw` is set
+    key: String,
+
+    /// The value to write, as JSON or as hex if `--raw` is set
+    value: String,
+
+    /// The subkey of the DupSort entry to write, required for DupSort tables.
+    ///
+    /// An existing duplicate of the key with the subkey is replaced, otherwise the value is added
+    /// as a new duplicate. The value must start with the subkey.
+    #[arg(long)]
+    subkey: Option<String>,
+
+    /// Interpret the key, subkey and value as raw hex encoded bytes and write them without
+    /// encoding
+    #[arg(long)]
+    raw: bool,
+
+    /// Confirm that the database should be modified
+    #[arg(long)]
+    force: bool,
+}
+
+impl Command {
+    /// Execute `db put` command
+    pub fn execute<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> eyre::Result<()> {
+        if !self.force {
+            eyre::bail!(
+                "`reth db put` modifies the database directly, pass --force to write the entry"
+            )
+        }
+        warn!("This command should be run without the node running!");
+
+        self.table.view(&PutViewer {
+            db: provider_factory.db_ref(),
+            key: &self.key,
+            subkey: self.subkey.as_deref(),
+            value: &self.value,
+            raw: self.raw,
+        })
+    }
+}
+
+struct PutViewer<'a, DB: Database> {
+    db: &'a DB,
+    key: &'a str,
+    subkey: Option<&'a str>,
+    value: &'a str,
+    raw: bool,
+}
+
+impl<DB: Database> PutViewer<'_, DB> {
+    /// Parses the value, either as JSON or as raw hex encoded bytes.
+    fn raw_value<T: Table>(&self) -> eyre::Result<RawValue<T::Value>> {
+        if self.raw {
+            Ok(RawValue::from_vec(hex::decode(self.value)?))
+        } else {
+            let value = serde_json::from_str::<T::Value>(&maybe_json_value_parser(self.value)?)?;
+            Ok(RawValue::new(value))
+        }
+    }
+}
+
+impl<DB: Database> TableViewer<()> for PutViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        let key = raw_key::<T>(self.key, self.raw)?;
+        let value = self.raw_value::<T>()?;
+
+        let tx = self.db.tx_mut()?;
+        match tx.get::<RawTable<T>>(key.clone())? {
+            Some(previous) => print_previous::<T>(&previous),
+            None => println!("No previous value for the given key."),
+        }
+        tx.put::<RawTable<T>>(key, value)?;
+        tx.commit()?;
+
+        println!("Successfully wrote entry to table `{}`.", T::NAME);
+        Ok(())
+    }
+
+    fn view_dupsort<T: DupSort>(&self) -> Result<(), Self::Error> {
+        let key = raw_key::<T>(self.key, self.raw)?;
+        let value = self.raw_value::<T>()?;
+
+        // Without the subkey, the duplicate the value replaces can't be found
+        let Some(subkey) = self.subkey else {
+            eyre::bail!(
+                "`{}` is a DupSort table, pass the subkey of the entry with --subkey",
+                T::NAME
+            )
+        };
+        let subkey = raw_subkey::<T>(subkey, self.raw)?;
+        eyre::ensure!(
+            value.raw_value().starts_with(subkey.raw_key()),
+            "The value doesn't start with the subkey {}",
+            hex::encode_prefixed(subkey.raw_key())
+        );
+
+        let tx = self.db.tx_mut()?;
+        let mut cursor = tx.cursor_dup_write::<RawDupSort<T>>()?;
+
+        // Delete the existing duplicate with the subkey first, so it's replaced instead of being
+        // kept next to the new value
+        match seek_exact_dup::<T>(&mut cursor, key.clone(), subkey)? {
+            Some(previous) => {
+                print_previous::<T>(&previous);
+                cursor.delete_current()?;
+            }
+            None => println!("No previous value for the given key and subkey."),
+        }
+
+        cursor.upsert(key, value)?;
+        tx.commit()?;
+
+        println!("Successfully wrote entry to table `{}`.", T::NAME);
+        Ok(())
+    }
+}
+
+/// Parses the key, either as JSON or as raw hex encoded bytes.
+pub(crate) fn raw_key<T: Table>(key: &str, raw: bool) -> eyre::Result<RawKey<T::Key>> {
+    if raw {
+        Ok(RawKey::from_vec(hex::decode(key)?))
+    } else {
+        Ok(RawKey::new(table_key::<T>(&maybe_json_value_parser(key)?)?))
+    }
+}
+
+/// Parses the subkey, either as JSON or as raw hex encoded bytes.
+pub(crate) fn raw_subkey<T: DupSort>(subkey: &str, raw: bool) -> eyre::Result<RawKey<T::SubKey>> {
+    if raw {
+        Ok(RawKey::from_vec(hex::decode(subkey)?))
+    } else {
+        let subkey = serde_json::from_str::<T::SubKey>(&maybe_json_value_parser(subkey)?)?;
+        Ok(RawKey::new(subkey))
+    }
+}
+
+/// Positions the cursor at the duplicate of `key` with exactly the given subkey, and returns its
+/// value.
+///
+/// DupSort values are prefixed with their encoded subkey, so the entry found by
+/// [`DbDupCursorRO::seek_by_key_subkey`] is an exact match if it starts with the subkey.
+pub(crate) fn seek_exact_dup<T: DupSort>(
+    cursor: &mut impl DbDupCursorRO<RawDupSort<T>>,
+    key: RawKey<T::Key>,
+    subkey: RawKey<T::SubKey>,
+) -> eyre::Result<Option<RawValue<T::Value>>> {
+    let prefix = subkey.raw_key().clone();
+    Ok(cursor
+        .seek_by_key_subkey(key, subkey)?
+        .filter(|value| value.raw_value().starts_with(&prefix)))
+}
+
+/// Prints the value that is about to be replaced or deleted, so that the change can be reverted
+/// with `reth db put --raw`.
+pub(crate) fn print_previous<T: Table>(previous: &RawValue<T::Value>) {
+    println!("Previous value: {}", hex::encode_prefixed(previous.raw_value()));
+    if let Ok(decoded) = previous.value() {
+        if let Ok(decoded) = serde_json::to_string_pretty(&decoded) {
+            println!("{decoded}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{Headers, PlainStorageState};
+    use reth_primitives::{Address, B256};
+
+    #[test]
+    fn parse_keys() {
+        assert_eq!(raw_key::<Headers>("1", false).unwrap(), RawKey::new(1));
+        assert_eq!(raw_key::<Headers>("0x0000000000000001", true).unwrap(), RawKey::new(1));
+        assert_eq!(
+            raw_key::<PlainStorageState>("0x0101010101010101010101010101010101010101", true)
+                .unwrap(),
+            RawKey::new(Address::repeat_byte(1))
+        );
+        assert_eq!(
+            raw_subkey::<PlainStorageState>(&B256::repeat_byte(2).to_string(), false).unwrap(),
+            RawKey::new(B256::repeat_byte(2))
+        );
+    }
+}
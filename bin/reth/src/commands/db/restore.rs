@@ -0,0 +1,49 @@
+use crate::commands::db::backup::{copy_files, BackupManifest, BackupPaths, Throttle};
+use clap::Parser;
+use reth_db::mdbx::DatabaseArguments;
+use std::{fs, path::PathBuf};
+use tracing::info;
+
+#[derive(Parser, Debug)]
+/// The arguments for the `reth db restore` command
+pub struct Command {
+    /// The directory of the backup written by `reth db backup`.
+    #[arg(long, value_name = "DIR")]
+    input: PathBuf,
+}
+
+impl Command {
+    /// Execute `db restore` command
+    ///
+    /// The database and static files must not exist yet.
+    pub fn execute(self, target: BackupPaths, db_args: DatabaseArguments) -> eyre::Result<()> {
+        let backup = BackupPaths::new(&self.input);
+        let manifest = BackupManifest::load(&self.input)?;
+        info!("Verifying the backup at {}.", self.input.display());
+        manifest.verify(&backup, db_args.clone())?;
+
+        for dir in [&target.db, &target.static_files] {
+            eyre::ensure!(
+                !dir.exists() || fs::read_dir(dir)?.next().is_none(),
+                "{} is not empty, move it away before restoring the backup",
+                dir.display()
+            );
+        }
+        fs::create_dir_all(&target.db)?;
+        fs::create_dir_all(&target.static_files)?;
+
+        info!("Restoring the database to {}.", target.db.display());
+        let mut throttle = Throttle::new(None);
+        copy_files(&backup.db, &target.db, &mut throttle)?;
+        info!("Restoring the static files to {}.", target.static_files.display());
+        copy_files(&backup.static_files, &target.static_files, &mut throttle)?;
+        // the lock file of the backup is recreated when the database is opened
+        let _ = fs::remove_file(target.db.join("mdbx.lck"));
+
+        info!("Verifying the restored database.");
+        manifest.verify(&target, db_args)?;
+
+        info!("Restored the backup of block {}.", manifest.tip);
+        Ok(())
+    }
+}
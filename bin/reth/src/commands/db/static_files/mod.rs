@@ -128,6 +128,10 @@ impl Command {
                             static_file_segments::Receipts,
                             SegmentConfig { filters, compression },
                         )?,
+                        StaticFileSegment::TransactionSenders => eyre::bail!(
+                            "Transaction senders static files are only written by the sender \
+                             recovery stage"
+                        ),
                     }
                 }
             }
@@ -154,6 +158,9 @@ impl Command {
                         InclusionFilter::Cuckoo,
                         phf,
                     )?,
+                    StaticFileSegment::TransactionSenders => {
+                        eyre::bail!("Transaction senders static files can't be benchmarked")
+                    }
                 }
             }
         }
@@ -1,23 +1,29 @@
 use std::time::Duration;
 
-use crate::{commands::db::checksum::ChecksumViewer, utils::DbTool};
+use crate::{
+    commands::db::checksum::{ChecksumResult, ChecksumViewer},
+    utils::DbTool,
+};
 use clap::Parser;
 use comfy_table::{Cell, Row, Table as ComfyTable};
 use eyre::WrapErr;
 use human_bytes::human_bytes;
 use itertools::Itertools;
+use rand::Rng;
 use reth_db::{
-    database::Database, mdbx, static_file::iter_static_files, AccountChangeSets, AccountsHistory,
-    AccountsTrie, BlockBodyIndices, BlockOmmers, BlockWithdrawals, Bytecodes, CanonicalHeaders,
-    DatabaseEnv, HashedAccounts, HashedStorages, HeaderNumbers, HeaderTerminalDifficulties,
-    Headers, PlainAccountState, PlainStorageState, PruneCheckpoints, Receipts,
-    StageCheckpointProgresses, StageCheckpoints, StorageChangeSets, StoragesHistory, StoragesTrie,
-    Tables, TransactionBlocks, TransactionHashNumbers, TransactionSenders, Transactions,
-    VersionHistory,
+    cursor::DbCursorRO, database::Database, mdbx, static_file::iter_static_files, table::Table,
+    transaction::DbTx, AccountChangeSets, AccountsHistory, AccountsTrie, BlockBodyIndices,
+    BlockCallFromAddresses, BlockCallToAddresses, BlockOmmers, BlockWithdrawals, Bytecodes,
+    CallFromIndex, CallToIndex, CanonicalHeaders, DatabaseEnv, HashedAccounts, HashedStorages,
+    HeaderNumbers, HeaderTerminalDifficulties, Headers, Migrations, PlainAccountState,
+    PlainStorageState, PruneCheckpoints, RawKey, RawTable, Receipts, StageCheckpointProgresses,
+    StageCheckpoints, StorageChangeSets, StoragesHistory, StoragesTrie, TableViewer, Tables,
+    TransactionBlocks, TransactionHashNumbers, TransactionSenders, Transactions, VersionHistory,
 };
 use reth_node_core::dirs::{ChainPath, DataDirPath};
 use reth_primitives::static_file::{find_fixed_range, SegmentRangeInclusive};
 use reth_provider::providers::StaticFileProvider;
+use serde::Serialize;
 use tracing::info;
 
 #[derive(Parser, Debug)]
@@ -39,6 +45,18 @@ pub struct Command {
     /// For individual table checksums, use the `reth db checksum` command.
     #[arg(long, default_value_t = false)]
     checksum: bool,
+
+    /// Additionally estimate the average value size of each table by sampling random entries.
+    #[arg(long, default_value_t = false)]
+    detailed_sizes: bool,
+
+    /// The number of entries to sample per table for `--detailed-sizes`.
+    #[arg(long, requires = "detailed_sizes", default_value_t = 1000)]
+    samples: usize,
+
+    /// Print the database table statistics as JSON. Static file statistics are not included.
+    #[arg(long, default_value_t = false, conflicts_with = "checksum")]
+    json: bool,
 }
 
 impl Command {
@@ -48,6 +66,12 @@ impl Command {
         data_dir: ChainPath<DataDirPath>,
         tool: &DbTool<DatabaseEnv>,
     ) -> eyre::Result<()> {
+        if self.json {
+            let db_stats = self.db_stats(tool)?;
+            println!("{}", serde_json::to_string_pretty(&db_stats)?);
+            return Ok(())
+        }
+
         if self.checksum {
             let checksum_report = self.checksum_report(tool)?;
             println!("{checksum_report}");
@@ -59,30 +83,19 @@ impl Command {
 
         println!("\n");
 
-        let db_stats_table = self.db_stats_table(tool)?;
-        println!("{db_stats_table}");
+        let db_stats = self.db_stats(tool)?;
+        println!("{}", self.db_stats_table(&db_stats));
 
         Ok(())
     }
 
-    fn db_stats_table(&self, tool: &DbTool<DatabaseEnv>) -> eyre::Result<ComfyTable> {
-        let mut table = ComfyTable::new();
-        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
-        table.set_header([
-            "Table Name",
-            "# Entries",
-            "Branch Pages",
-            "Leaf Pages",
-            "Overflow Pages",
-            "Total Size",
-        ]);
-
+    fn db_stats(&self, tool: &DbTool<DatabaseEnv>) -> eyre::Result<DbStats> {
         tool.provider_factory.db_ref().view(|tx| {
-            let mut db_tables = Tables::ALL.iter().map(|table| table.name()).collect::<Vec<_>>();
-            db_tables.sort();
-            let mut total_size = 0;
-            for db_table in db_tables {
-                let table_db = tx.inner.open_db(Some(db_table)).wrap_err("Could not open db.")?;
+            let mut tables = Vec::with_capacity(Tables::COUNT);
+            let mut tables_size = 0;
+            for db_table in Tables::ALL {
+                let table_db =
+                    tx.inner.open_db(Some(db_table.name())).wrap_err("Could not open db.")?;
 
                 let stats = tx
                     .inner
@@ -99,50 +112,102 @@ impl Command {
                 let num_pages = leaf_pages + branch_pages + overflow_pages;
                 let table_size = page_size * num_pages;
 
-                total_size += table_size;
-                let mut row = Row::new();
-                row.add_cell(Cell::new(db_table))
-                    .add_cell(Cell::new(stats.entries()))
-                    .add_cell(Cell::new(branch_pages))
-                    .add_cell(Cell::new(leaf_pages))
-                    .add_cell(Cell::new(overflow_pages))
-                    .add_cell(Cell::new(human_bytes(table_size as f64)));
-                table.add_row(row);
+                let avg_value_size = if self.detailed_sizes {
+                    info!("Sampling value sizes for table: {db_table}");
+                    db_table.view(&ValueSizeSampler { tx, samples: self.samples })?
+                } else {
+                    None
+                };
+
+                tables_size += table_size;
+                tables.push(TableStats {
+                    name: db_table.name(),
+                    entries: stats.entries(),
+                    branch_pages,
+                    leaf_pages,
+                    overflow_pages,
+                    size: table_size,
+                    percentage: 0.0,
+                    avg_value_size,
+                });
             }
 
-            let max_widths = table.column_max_content_widths();
-            let mut separator = Row::new();
-            for width in max_widths {
-                separator.add_cell(Cell::new("-".repeat(width as usize)));
+            let freelist_pages = tx.inner.env().freelist()?;
+            let pagesize = tx.inner.db_stat(&mdbx::Database::freelist_db())?.page_size() as usize;
+            let freelist_size = freelist_pages * pagesize;
+
+            let total_size = tables_size + freelist_size;
+            for table in &mut tables {
+                if total_size > 0 {
+                    table.percentage = table.size as f64 * 100.0 / total_size as f64;
+                }
             }
-            table.add_row(separator);
+            tables.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.name.cmp(b.name)));
 
-            let mut row = Row::new();
-            row.add_cell(Cell::new("Tables"))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(human_bytes(total_size as f64)));
-            table.add_row(row);
+            Ok::<_, eyre::Report>(DbStats { tables, tables_size, freelist_pages, freelist_size })
+        })?
+    }
 
-            let freelist = tx.inner.env().freelist()?;
-            let pagesize = tx.inner.db_stat(&mdbx::Database::freelist_db())?.page_size() as usize;
-            let freelist_size = freelist * pagesize;
+    fn db_stats_table(&self, stats: &DbStats) -> ComfyTable {
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        let mut header = vec![
+            "Table Name",
+            "# Entries",
+            "Branch Pages",
+            "Leaf Pages",
+            "Overflow Pages",
+            "Total Size",
+            "% of Total",
+        ];
+        if self.detailed_sizes {
+            header.push("Avg Value Size");
+        }
+        table.set_header(header);
 
+        for table_stats in &stats.tables {
             let mut row = Row::new();
-            row.add_cell(Cell::new("Freelist"))
-                .add_cell(Cell::new(freelist))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(""))
-                .add_cell(Cell::new(human_bytes(freelist_size as f64)));
+            row.add_cell(Cell::new(table_stats.name))
+                .add_cell(Cell::new(table_stats.entries))
+                .add_cell(Cell::new(table_stats.branch_pages))
+                .add_cell(Cell::new(table_stats.leaf_pages))
+                .add_cell(Cell::new(table_stats.overflow_pages))
+                .add_cell(Cell::new(human_bytes(table_stats.size as f64)))
+                .add_cell(Cell::new(format!("{:.2}%", table_stats.percentage)));
+            if self.detailed_sizes {
+                row.add_cell(Cell::new(
+                    table_stats.avg_value_size.map_or_else(|| "-".to_string(), human_bytes),
+                ));
+            }
             table.add_row(row);
+        }
 
-            Ok::<(), eyre::Report>(())
-        })??;
+        let max_widths = table.column_max_content_widths();
+        let mut separator = Row::new();
+        for width in max_widths {
+            separator.add_cell(Cell::new("-".repeat(width as usize)));
+        }
+        table.add_row(separator);
 
-        Ok(table)
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Tables"))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(human_bytes(stats.tables_size as f64)));
+        table.add_row(row);
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new("Freelist"))
+            .add_cell(Cell::new(stats.freelist_pages))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(""))
+            .add_cell(Cell::new(human_bytes(stats.freelist_size as f64)));
+        table.add_row(row);
+
+        table
     }
 
     fn static_files_stats_table(
@@ -325,14 +390,22 @@ impl Command {
             info!("Calculating checksum for table: {}", db_table);
 
             let viewer = ChecksumViewer::new(tool);
-            let (checksum, elapsed) = match db_table {
+            let ChecksumResult { checksum, elapsed, .. } = match db_table {
                 Tables::AccountsHistory => viewer.get_checksum::<AccountsHistory>().unwrap(),
                 Tables::AccountChangeSets => viewer.get_checksum::<AccountChangeSets>().unwrap(),
                 Tables::AccountsTrie => viewer.get_checksum::<AccountsTrie>().unwrap(),
                 Tables::BlockBodyIndices => viewer.get_checksum::<BlockBodyIndices>().unwrap(),
                 Tables::BlockOmmers => viewer.get_checksum::<BlockOmmers>().unwrap(),
                 Tables::BlockWithdrawals => viewer.get_checksum::<BlockWithdrawals>().unwrap(),
+                Tables::BlockCallFromAddresses => {
+                    viewer.get_checksum::<BlockCallFromAddresses>().unwrap()
+                }
+                Tables::BlockCallToAddresses => {
+                    viewer.get_checksum::<BlockCallToAddresses>().unwrap()
+                }
                 Tables::Bytecodes => viewer.get_checksum::<Bytecodes>().unwrap(),
+                Tables::CallFromIndex => viewer.get_checksum::<CallFromIndex>().unwrap(),
+                Tables::CallToIndex => viewer.get_checksum::<CallToIndex>().unwrap(),
                 Tables::CanonicalHeaders => viewer.get_checksum::<CanonicalHeaders>().unwrap(),
                 Tables::HashedAccounts => viewer.get_checksum::<HashedAccounts>().unwrap(),
                 Tables::HashedStorages => viewer.get_checksum::<HashedStorages>().unwrap(),
@@ -341,6 +414,7 @@ impl Command {
                     viewer.get_checksum::<HeaderTerminalDifficulties>().unwrap()
                 }
                 Tables::Headers => viewer.get_checksum::<Headers>().unwrap(),
+                Tables::Migrations => viewer.get_checksum::<Migrations>().unwrap(),
                 Tables::PlainAccountState => viewer.get_checksum::<PlainAccountState>().unwrap(),
                 Tables::PlainStorageState => viewer.get_checksum::<PlainStorageState>().unwrap(),
                 Tables::PruneCheckpoints => viewer.get_checksum::<PruneCheckpoints>().unwrap(),
@@ -390,3 +464,100 @@ impl Command {
         Ok(table)
     }
 }
+
+/// Size statistics of all database tables.
+#[derive(Debug, Serialize)]
+struct DbStats {
+    /// Statistics per table, sorted by size descending
+    tables: Vec<TableStats>,
+    /// The total size of all tables in bytes
+    tables_size: usize,
+    /// The number of pages in the freelist
+    freelist_pages: usize,
+    /// The size of the freelist in bytes
+    freelist_size: usize,
+}
+
+/// Size statistics of a single database table.
+#[derive(Debug, Serialize)]
+struct TableStats {
+    /// The table name
+    name: &'static str,
+    /// The number of entries
+    entries: usize,
+    /// The number of branch pages
+    branch_pages: usize,
+    /// The number of leaf pages
+    leaf_pages: usize,
+    /// The number of overflow pages
+    overflow_pages: usize,
+    /// The total size of all pages in bytes
+    size: usize,
+    /// The share of the total database size, including the freelist, in percent
+    percentage: f64,
+    /// The estimated average value size in bytes, if `--detailed-sizes` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    avg_value_size: Option<f64>,
+}
+
+/// Estimates the average value size of a table by seeking to random keys between the first and
+/// the last key of the table.
+struct ValueSizeSampler<'a, TX> {
+    tx: &'a TX,
+    samples: usize,
+}
+
+impl<TX: DbTx> TableViewer<Option<f64>> for ValueSizeSampler<'_, TX> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<Option<f64>, Self::Error> {
+        if self.samples == 0 {
+            return Ok(None)
+        }
+
+        let mut cursor = self.tx.cursor_read::<RawTable<T>>()?;
+        let (Some((first, _)), Some((last, last_value))) = (cursor.first()?, cursor.last()?) else {
+            return Ok(None)
+        };
+        let (low, high) = (key_prefix(first.raw_key()), key_prefix(last.raw_key()));
+
+        let mut rng = rand::thread_rng();
+        let mut total_size = 0;
+        for _ in 0..self.samples {
+            let target = RawKey::from_vec(rng.gen_range(low..=high).to_be_bytes().to_vec());
+            total_size += match cursor.seek(target)? {
+                Some((_, value)) => value.raw_value().len(),
+                None => last_value.raw_value().len(),
+            };
+        }
+
+        Ok(Some(total_size as f64 / self.samples as f64))
+    }
+}
+
+/// Returns the first 8 bytes of the key as a big-endian integer, padded with zeros.
+///
+/// This preserves the lexicographic order of keys, so seeking to any integer between the prefixes
+/// of the first and last key lands on an entry of the table.
+fn key_prefix(key: &[u8]) -> u64 {
+    let mut prefix = [0u8; 8];
+    let len = key.len().min(8);
+    prefix[..len].copy_from_slice(&key[..len]);
+    u64::from_be_bytes(prefix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_prefix_preserves_order() {
+        assert_eq!(key_prefix(&[]), 0);
+        assert_eq!(key_prefix(&[1]), 1 << 56);
+        assert_eq!(key_prefix(&1u64.to_be_bytes()), 1);
+        assert!(key_prefix(&[0, 1]) <= key_prefix(&[0, 1, 0, 0, 0, 0, 0, 0, 1]));
+        assert!(
+            key_prefix(&[0, 2]) > key_prefix(&[0, 1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff])
+        );
+    }
+}
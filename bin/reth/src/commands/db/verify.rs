@@ -0,0 +1,280 @@
+use crate::utils::DbTool;
+use clap::{Parser, ValueEnum};
+use reth_db::{database::Database, transaction::DbTx};
+use reth_primitives::{stage::StageId, BlockNumber, PruneSegment};
+use reth_provider::{
+    BlockHashReader, BlockNumReader, BlockReader, DatabaseProvider, HeaderProvider, ProviderError,
+    PruneCheckpointReader, StageCheckpointReader, TransactionsProvider,
+};
+use std::fmt;
+use tracing::info;
+
+/// The number of transactions whose senders are read at once.
+const SENDERS_CHUNK_SIZE: u64 = 100_000;
+
+/// The arguments for the `reth db verify` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The checks to run. Runs all checks if none are given.
+    #[arg(long = "check", value_enum)]
+    checks: Vec<Check>,
+
+    /// The maximum number of violations reported per check.
+    ///
+    /// A check stops after reporting this many violations.
+    #[arg(long, default_value_t = 10)]
+    max_violations: usize,
+}
+
+/// A cross-table invariant check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub(crate) enum Check {
+    /// Every canonical hash up to the `Headers` checkpoint belongs to a header with the same block
+    /// number, and is mapped back to that number in `HeaderNumbers`.
+    CanonicalHeaders,
+    /// The transaction ranges of `BlockBodyIndices` up to the `Bodies` checkpoint are contiguous,
+    /// and all of their transactions exist.
+    BodyIndices,
+    /// Every transaction up to the `SenderRecovery` checkpoint has a sender, in the database or in
+    /// static files.
+    TransactionSenders,
+    /// No stage checkpoint is ahead of the checkpoint of the stage that runs before it.
+    StageCheckpoints,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_possible_value().expect("no skipped variants").get_name().fmt(f)
+    }
+}
+
+impl Command {
+    /// Execute `db verify` command
+    pub fn execute<DB: Database>(self, tool: &DbTool<DB>) -> eyre::Result<()> {
+        let mut checks = if self.checks.is_empty() {
+            Check::value_variants().to_vec()
+        } else {
+            self.checks.clone()
+        };
+        checks.sort_unstable();
+        checks.dedup();
+
+        let provider = tool.provider_factory.provider()?.disable_long_read_transaction_safety();
+
+        let mut failed = Vec::new();
+        for check in checks {
+            info!("Running check `{check}`.");
+            let mut verifier = Verifier { provider: &provider, max: self.max_violations, found: 0 };
+            match check {
+                Check::CanonicalHeaders => verifier.canonical_headers()?,
+                Check::BodyIndices => verifier.body_indices()?,
+                Check::TransactionSenders => verifier.transaction_senders()?,
+                Check::StageCheckpoints => verifier.stage_checkpoints()?,
+            }
+
+            if verifier.found == 0 {
+                println!("{check}: PASS");
+            } else {
+                println!("{check}: FAIL ({} violations reported)", verifier.found);
+                failed.push(check);
+            }
+        }
+
+        if !failed.is_empty() {
+            let failed = failed.iter().map(ToString::to_string).collect::<Vec<_>>();
+            eyre::bail!("Database verification failed: {}", failed.join(", "))
+        }
+        Ok(())
+    }
+}
+
+/// Runs the checks and reports their violations.
+struct Verifier<'a, TX> {
+    provider: &'a DatabaseProvider<TX>,
+    /// The maximum number of violations to report.
+    max: usize,
+    /// The number of violations reported by the current check.
+    found: usize,
+}
+
+impl<TX: DbTx> Verifier<'_, TX> {
+    /// Reports a violation, returning `false` if the check should stop.
+    fn violation(&mut self, message: impl fmt::Display) -> bool {
+        println!("  {message}");
+        self.found += 1;
+        self.found < self.max
+    }
+
+    /// Returns the block number of the stage checkpoint.
+    fn checkpoint(&self, stage: StageId) -> eyre::Result<BlockNumber> {
+        Ok(self.provider.get_stage_checkpoint(stage)?.unwrap_or_default().block_number)
+    }
+
+    fn canonical_headers(&mut self) -> eyre::Result<()> {
+        let tip = self.checkpoint(StageId::Headers)?;
+        for number in 0..=tip {
+            log_progress("canonical-headers", number, tip);
+
+            let Some(hash) = self.provider.block_hash(number)? else {
+                if !self.violation(format!("block {number}: missing canonical hash")) {
+                    return Ok(())
+                }
+                continue
+            };
+
+            let message = match self.provider.header_by_number(number)? {
+                None => Some(format!("block {number}: missing header for canonical hash {hash}")),
+                Some(header) if header.hash_slow() != hash => Some(format!(
+                    "block {number}: canonical hash {hash} does not match header hash {}",
+                    header.hash_slow()
+                )),
+                Some(_) => match self.provider.block_number(hash)? {
+                    Some(mapped) if mapped == number => None,
+                    Some(mapped) => Some(format!(
+                        "block {number}: canonical hash {hash} is mapped to block {mapped}"
+                    )),
+                    None => Some(format!(
+                        "block {number}: canonical hash {hash} is not in HeaderNumbers"
+                    )),
+                },
+            };
+
+            if let Some(message) = message {
+                if !self.violation(message) {
+                    return Ok(())
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn body_indices(&mut self) -> eyre::Result<()> {
+        let tip = self.checkpoint(StageId::Bodies)?;
+        let mut next_tx_num = 0;
+        for number in 0..=tip {
+            log_progress("body-indices", number, tip);
+
+            let Some(indices) = self.provider.block_body_indices(number)? else {
+                if !self.violation(format!("block {number}: missing body indices")) {
+                    return Ok(())
+                }
+                continue
+            };
+
+            if indices.first_tx_num != next_tx_num &&
+                !self.violation(format!(
+                    "block {number}: first transaction {} does not follow the previous block, \
+                     expected {next_tx_num}",
+                    indices.first_tx_num
+                ))
+            {
+                return Ok(())
+            }
+            next_tx_num = indices.next_tx_num();
+
+            let found = match self.provider.transactions_by_tx_range(indices.tx_num_range()) {
+                Ok(transactions) => transactions.len() as u64,
+                Err(ProviderError::TransactionNotFound(_)) => 0,
+                Err(err) => return Err(err.into()),
+            };
+            if found != indices.tx_count &&
+                !self.violation(format!(
+                    "block {number}: found {found} of {} transactions in range {:?}",
+                    indices.tx_count,
+                    indices.tx_num_range()
+                ))
+            {
+                return Ok(())
+            }
+        }
+        Ok(())
+    }
+
+    fn transaction_senders(&mut self) -> eyre::Result<()> {
+        let tip = self.checkpoint(StageId::SenderRecovery)?;
+        let Some(last_tx) = self
+            .provider
+            .block_body_indices(tip)?
+            .and_then(|indices| indices.next_tx_num().checked_sub(1))
+        else {
+            return Ok(())
+        };
+
+        // Senders below the prune checkpoint are expected to be missing.
+        let first_tx = self
+            .provider
+            .get_prune_checkpoint(PruneSegment::SenderRecovery)?
+            .and_then(|checkpoint| checkpoint.tx_number)
+            .map_or(0, |tx| tx + 1);
+
+        // The senders are read through the provider, since they may have been moved to static
+        // files. Only the chunks with missing senders are checked transaction by transaction.
+        let mut start = first_tx;
+        while start <= last_tx {
+            log_progress("transaction-senders", start, last_tx);
+            let end = last_tx.min((start / SENDERS_CHUNK_SIZE + 1) * SENDERS_CHUNK_SIZE - 1);
+
+            let found = self.provider.senders_by_tx_range(start..=end)?.len() as u64;
+            if found != end - start + 1 {
+                for tx in start..=end {
+                    if self.provider.transaction_sender(tx)?.is_none() &&
+                        !self.violation(format!("transaction {tx}: missing sender"))
+                    {
+                        return Ok(())
+                    }
+                }
+            }
+            start = end + 1;
+        }
+        Ok(())
+    }
+
+    fn stage_checkpoints(&mut self) -> eyre::Result<()> {
+        // The pipeline executes the stages in this order and unwinds them in reverse, so a stage
+        // can never be ahead of the stage before it.
+        for window in StageId::ALL.windows(2) {
+            let (previous, stage) = (window[0], window[1]);
+            let (previous_checkpoint, checkpoint) =
+                (self.checkpoint(previous)?, self.checkpoint(stage)?);
+
+            if checkpoint > previous_checkpoint &&
+                !self.violation(format!(
+                    "stage {stage} is at block {checkpoint}, ahead of stage {previous} at block \
+                     {previous_checkpoint}"
+                ))
+            {
+                return Ok(())
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Logs the progress of a check every 100k items.
+fn log_progress(check: &str, current: u64, last: u64) {
+    if current % 100_000 == 0 {
+        info!("Check `{check}` at {current}/{last}.");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_checks() {
+        let args = Command::try_parse_from([
+            "reth",
+            "--check",
+            "canonical-headers",
+            "--check",
+            "stage-checkpoints",
+        ])
+        .unwrap();
+        assert_eq!(args.checks, vec![Check::CanonicalHeaders, Check::StageCheckpoints]);
+        assert_eq!(args.max_violations, 10);
+
+        assert!(Command::try_parse_from(["reth", "--check", "unknown"]).is_err());
+        assert_eq!(Check::TransactionSenders.to_string(), "transaction-senders");
+    }
+}
@@ -0,0 +1,345 @@
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use reth_nippy_jar::{NippyJar, NippyJarCursor};
+use reth_node_core::dirs::{ChainPath, DataDirPath};
+use reth_primitives::{
+    static_file::{SegmentHeader, SegmentRangeInclusive},
+    StaticFileSegment,
+};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::info;
+
+/// The arguments for the `reth db verify-static-files` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Only verify the static files of these segments
+    #[arg(long = "segment", value_delimiter = ',')]
+    segments: Vec<StaticFileSegment>,
+
+    /// Also read and decompress every row of every static file
+    #[arg(long)]
+    deep: bool,
+}
+
+impl Command {
+    /// Execute `db verify-static-files` command
+    pub fn execute(self, data_dir: ChainPath<DataDirPath>) -> eyre::Result<()> {
+        let static_files = self.static_files(&data_dir.static_files_path())?;
+
+        let mut table = ComfyTable::new();
+        table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+        table.set_header(["Segment", "Files", "Block Range", "Transaction Range", "Status"]);
+
+        let mut failures = Vec::new();
+        for (segment, files) in static_files {
+            info!("Verifying {} static files of segment {segment}.", files.len());
+            let report = verify_segment(segment, &files, self.deep);
+
+            let mut row = Row::new();
+            row.add_cell(Cell::new(segment))
+                .add_cell(Cell::new(files.len()))
+                .add_cell(Cell::new(
+                    report.block_range.map_or("N/A".to_string(), |range| range.to_string()),
+                ))
+                .add_cell(Cell::new(
+                    report.tx_range.map_or("N/A".to_string(), |range| range.to_string()),
+                ))
+                .add_cell(Cell::new(if report.errors.is_empty() { "PASS" } else { "FAIL" }));
+            table.add_row(row);
+
+            failures.extend(report.errors);
+        }
+
+        println!("{table}");
+
+        if !failures.is_empty() {
+            for failure in &failures {
+                println!("{failure}");
+            }
+            eyre::bail!("Found {} problems in the static files", failures.len())
+        }
+        Ok(())
+    }
+
+    /// Returns the data files of the selected segments in the directory, sorted by block range.
+    ///
+    /// Unlike `iter_static_files`, this doesn't load the files, so that broken files can be
+    /// reported instead of failing the whole command.
+    fn static_files(
+        &self,
+        path: &Path,
+    ) -> eyre::Result<BTreeMap<StaticFileSegment, Vec<(SegmentRangeInclusive, PathBuf)>>> {
+        let mut static_files = BTreeMap::<_, Vec<_>>::new();
+        for entry in reth_primitives::fs::read_dir(path)? {
+            let entry = entry?;
+            let Some((segment, range)) =
+                StaticFileSegment::parse_filename(&entry.file_name().to_string_lossy())
+            else {
+                continue
+            };
+
+            if self.segments.is_empty() || self.segments.contains(&segment) {
+                static_files.entry(segment).or_default().push((range, entry.path()));
+            }
+        }
+
+        for files in static_files.values_mut() {
+            files.sort_by_key(|(range, _)| range.start());
+        }
+        Ok(static_files)
+    }
+}
+
+/// The result of verifying all static files of a segment.
+#[derive(Debug, Default)]
+struct SegmentReport {
+    /// The block range covered by the valid files
+    block_range: Option<SegmentRangeInclusive>,
+    /// The transaction range covered by the valid files
+    tx_range: Option<SegmentRangeInclusive>,
+    /// The problems that were found, prefixed with the file name
+    errors: Vec<String>,
+}
+
+/// Verifies every static file of the segment, and that their block and transaction ranges are
+/// contiguous.
+fn verify_segment(
+    segment: StaticFileSegment,
+    files: &[(SegmentRangeInclusive, PathBuf)],
+    deep: bool,
+) -> SegmentReport {
+    let mut report = SegmentReport::default();
+    let mut blocks = ContiguousRanges::new("block");
+    let mut txs = ContiguousRanges::new("tx");
+
+    for (expected_range, path) in files {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let header = match verify_file(segment, expected_range, path, deep) {
+            Ok(header) => header,
+            Err(err) => {
+                report.errors.push(format!("{file_name}: {err}"));
+                // the ranges of the file are unknown, so the next file can't be checked against it
+                blocks.skip();
+                txs.skip();
+                continue
+            }
+        };
+
+        if let Err(err) = blocks.check(header.block_range()) {
+            report.errors.push(format!("{file_name}: {err}"));
+        }
+        if let Err(err) = txs.check(header.tx_range()) {
+            report.errors.push(format!("{file_name}: {err}"));
+        }
+    }
+
+    report.block_range = blocks.covered;
+    report.tx_range = txs.covered;
+    report
+}
+
+/// Checks that the ranges of consecutive files are contiguous.
+///
+/// After a gap or an overlap, the following ranges are checked against the range after it, so a
+/// single gap is only reported once.
+#[derive(Debug)]
+struct ContiguousRanges {
+    /// The kind of the ranges, used in the errors
+    kind: &'static str,
+    /// The end of the previous range, if known
+    previous_end: Option<u64>,
+    /// The range covered by the contiguous ranges before the first gap
+    covered: Option<SegmentRangeInclusive>,
+    /// Whether the covered range ended with a gap, an overlap or a skipped file
+    interrupted: bool,
+}
+
+impl ContiguousRanges {
+    const fn new(kind: &'static str) -> Self {
+        Self { kind, previous_end: None, covered: None, interrupted: false }
+    }
+
+    /// Skips a file whose range is unknown, the next range isn't checked against the previous
+    /// one.
+    fn skip(&mut self) {
+        self.previous_end = None;
+        self.interrupted |= self.covered.is_some();
+    }
+
+    /// Checks that the range starts right after the previous range, and extends the covered range.
+    fn check(&mut self, range: Option<&SegmentRangeInclusive>) -> eyre::Result<()> {
+        let Some(range) = range else { return Ok(()) };
+        let kind = self.kind;
+
+        if let Some(previous_end) = self.previous_end.replace(range.end()) {
+            let expected_start = previous_end + 1;
+            if range.start() != expected_start {
+                self.interrupted = true;
+            }
+            if range.start() > expected_start {
+                eyre::bail!(
+                    "gap in {kind} range, expected {expected_start} but file starts at {range}"
+                )
+            }
+            if range.start() < expected_start {
+                eyre::bail!(
+                    "{kind} range {range} overlaps the previous file ending at {previous_end}"
+                )
+            }
+        }
+
+        if !self.interrupted {
+            self.covered = Some(SegmentRangeInclusive::new(
+                self.covered.map_or(range.start(), |covered| covered.start()),
+                range.end(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Verifies the header, offsets and optionally the rows of a single static file, returning its
+/// segment header.
+fn verify_file(
+    segment: StaticFileSegment,
+    expected_range: &SegmentRangeInclusive,
+    path: &Path,
+    deep: bool,
+) -> eyre::Result<SegmentHeader> {
+    let jar = NippyJar::<SegmentHeader>::load(path)
+        .map_err(|err| eyre::eyre!("failed to load header: {err}"))?;
+    let header = jar.user_header().clone();
+
+    eyre::ensure!(
+        header.segment() == segment,
+        "header is for segment {}, but the file name is for segment {segment}",
+        header.segment()
+    );
+    eyre::ensure!(
+        header.expected_block_start() == expected_range.start() &&
+            header.expected_block_end() == expected_range.end(),
+        "header is for blocks {}..={}, but the file name is for blocks {expected_range}",
+        header.expected_block_start(),
+        header.expected_block_end()
+    );
+    if let Some(block_range) = header.block_range() {
+        eyre::ensure!(
+            block_range.start() >= expected_range.start() &&
+                block_range.end() <= expected_range.end(),
+            "block range {block_range} is outside of the file range {expected_range}"
+        );
+    }
+
+    let expected_rows = match segment {
+        StaticFileSegment::Headers => header.block_len(),
+        StaticFileSegment::Transactions |
+        StaticFileSegment::Receipts |
+        StaticFileSegment::TransactionSenders => header.tx_len(),
+    }
+    .unwrap_or_default() as usize;
+    eyre::ensure!(
+        jar.rows() == expected_rows,
+        "file has {} rows, but the header range has {expected_rows}",
+        jar.rows()
+    );
+    eyre::ensure!(
+        jar.columns() == segment.columns(),
+        "file has {} columns, but segment {segment} has {}",
+        jar.columns(),
+        segment.columns()
+    );
+
+    // The data reader expects the offsets file to start with the size of one offset
+    let mut offset_size = [0u8; 1];
+    File::open(jar.offsets_path())
+        .and_then(|mut file| file.read_exact(&mut offset_size))
+        .map_err(|err| eyre::eyre!("failed to read offsets file: {err}"))?;
+    eyre::ensure!(
+        (1..=8).contains(&offset_size[0]),
+        "offsets file has an invalid offset size of {}",
+        offset_size[0]
+    );
+
+    // The offsets file holds one offset per value, followed by the size of the data file
+    let reader =
+        jar.open_data_reader().map_err(|err| eyre::eyre!("failed to open data file: {err}"))?;
+    let values = jar.rows() * jar.columns();
+    let offsets = reader.offsets_count()?;
+    eyre::ensure!(
+        offsets == values + 1,
+        "offsets file has {offsets} offsets, but {values} values and the data size are expected"
+    );
+    let data_size = reader.offset(values);
+    eyre::ensure!(
+        data_size == reader.size() as u64,
+        "offsets file expects {data_size} bytes of data, but the data file has {} bytes",
+        reader.size()
+    );
+
+    if deep {
+        let mut cursor = NippyJarCursor::with_reader(&jar, Arc::new(reader))
+            .map_err(|err| eyre::eyre!("failed to open cursor: {err}"))?;
+        let mut rows = 0;
+        while cursor
+            .next_row()
+            .map_err(|err| eyre::eyre!("failed to read row {rows}: {err}"))?
+            .is_some()
+        {
+            rows += 1;
+        }
+        eyre::ensure!(rows == jar.rows(), "read {rows} rows, but the file has {}", jar.rows());
+    }
+
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contiguous_ranges() {
+        let mut ranges = ContiguousRanges::new("block");
+        let mut check =
+            |start, end| ranges.check(Some(&SegmentRangeInclusive::new(start, end))).is_ok();
+
+        assert!(check(0, 9));
+        assert!(check(10, 19));
+        assert!(!check(21, 29));
+        // the ranges after a gap are checked against the range after it
+        assert!(check(30, 39));
+        assert!(!check(35, 49));
+        assert!(check(50, 59));
+
+        assert!(ranges.check(None).is_ok());
+        assert_eq!(ranges.previous_end, Some(59));
+        assert_eq!(ranges.covered, Some(SegmentRangeInclusive::new(0, 19)));
+    }
+
+    #[test]
+    fn skipped_files() {
+        let mut ranges = ContiguousRanges::new("block");
+        let range = |start, end| Some(SegmentRangeInclusive::new(start, end));
+
+        assert!(ranges.check(range(0, 9).as_ref()).is_ok());
+        ranges.skip();
+        // the range after a skipped file isn't checked against the range before it
+        assert!(ranges.check(range(20, 29).as_ref()).is_ok());
+        assert!(ranges.check(range(30, 39).as_ref()).is_ok());
+        assert_eq!(ranges.covered, range(0, 9));
+    }
+
+    #[test]
+    fn parse_segments() {
+        let args =
+            Command::try_parse_from(["reth", "--segment", "headers,receipts", "--deep"]).unwrap();
+        assert_eq!(args.segments, vec![StaticFileSegment::Headers, StaticFileSegment::Receipts]);
+        assert!(args.deep);
+    }
+}
@@ -0,0 +1,540 @@
+use crate::utils::DbTool;
+use clap::Parser;
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    tables,
+    transaction::DbTx,
+};
+use reth_primitives::{
+    keccak256,
+    stage::StageId,
+    trie::{BranchNodeCompact, Nibbles},
+    Address, B256,
+};
+use reth_provider::{BlockNumReader, HeaderProvider, ProviderError, StageCheckpointReader};
+use reth_trie::{
+    trie_cursor::noop::NoopTrieCursorFactory,
+    updates::{TrieKey, TrieOp, TrieUpdates},
+    StateRoot, StateRootProgress, StorageRoot,
+};
+use std::{cmp::Ordering, collections::BTreeMap, fmt, iter::Fuse};
+use tracing::{info, warn};
+
+/// The number of trie nodes that are computed before they're compared with the stored nodes.
+const VERIFY_TRIE_THRESHOLD: u64 = 100_000;
+
+/// The arguments for the `reth db verify-trie` command
+///
+/// The trie is recomputed from the hashed state, ignoring the intermediate nodes of the trie
+/// tables, and its nodes are compared with the stored nodes as they're computed.
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Only verifies the storage trie of the given account.
+    ///
+    /// The storage trie is recomputed from the hashed storage of the account and compared with
+    /// the stored intermediate nodes of the account.
+    #[arg(long, value_name = "ADDRESS", verbatim_doc_comment)]
+    address: Option<Address>,
+}
+
+impl Command {
+    /// Execute `db verify-trie` command
+    pub fn execute<DB: Database>(self, tool: &DbTool<DB>) -> eyre::Result<()> {
+        let provider = tool.provider_factory.provider()?.disable_long_read_transaction_safety();
+        let tx = provider.tx_ref();
+
+        if let Some(address) = self.address {
+            return verify_storage_trie(tx, address)
+        }
+
+        // the trie tables are only up to date with the hashed state if the hashing stages didn't
+        // run past the merkle checkpoint
+        let block =
+            provider.get_stage_checkpoint(StageId::MerkleExecute)?.unwrap_or_default().block_number;
+        for stage_id in [StageId::AccountHashing, StageId::StorageHashing] {
+            let hashed_block =
+                provider.get_stage_checkpoint(stage_id)?.unwrap_or_default().block_number;
+            if hashed_block != block {
+                eyre::bail!(
+                    "The hashed state of {stage_id} is at block {hashed_block}, but the trie \
+                     tables are at block {block}. Run the pipeline up to the merkle stage first."
+                )
+            }
+        }
+        let tip = provider.best_block_number()?;
+        if block < tip {
+            warn!(target: "reth::cli", block, tip, "Verifying the trie behind the tip");
+        }
+        let header = provider
+            .header_by_number(block)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(block.into()))?;
+
+        info!(target: "reth::cli", block, "Recomputing the trie from the hashed state");
+        let verification = verify_state_trie(tx, VERIFY_TRIE_THRESHOLD)?;
+
+        let mut valid = verification.root == header.state_root;
+        if valid {
+            println!("State root {} of the hashed state matches block {block}", verification.root);
+        } else {
+            println!(
+                "State root {} of the hashed state does not match the state root {} of block \
+                 {block}",
+                verification.root, header.state_root
+            );
+        }
+        match verification.account_divergence {
+            Some(divergence) => {
+                valid = false;
+                println!("First divergent account trie node: {divergence}")
+            }
+            None => println!("Account trie nodes match the hashed state"),
+        }
+        match verification.storage_divergence {
+            Some(divergence) => {
+                valid = false;
+                println!("First divergent storage trie node: {divergence}")
+            }
+            None => println!("Storage trie nodes match the hashed state"),
+        }
+
+        if !valid {
+            eyre::bail!("Trie verification failed at block {block}")
+        }
+        Ok(())
+    }
+}
+
+/// The result of the comparison of the trie tables with the trie computed from the hashed state.
+#[derive(Debug)]
+struct TrieVerification {
+    /// The state root of the hashed state.
+    root: B256,
+    /// The first difference between the stored and the computed account trie nodes.
+    account_divergence: Option<Divergence<Nibbles>>,
+    /// The first difference between the stored and the computed storage trie nodes.
+    storage_divergence: Option<Divergence<(B256, Nibbles)>>,
+}
+
+/// Recomputes the trie from the hashed state and compares its nodes with the nodes of the trie
+/// tables.
+///
+/// The trie is computed in chunks of about `threshold` nodes, which are compared with the stored
+/// nodes before the next chunk is computed, so only the nodes of a chunk are kept in memory. The
+/// nodes are compared in the order they're computed, see [PostOrder].
+fn verify_state_trie<TX: DbTx>(tx: &TX, threshold: u64) -> eyre::Result<TrieVerification> {
+    let mut account_trie = tx.cursor_read::<tables::AccountsTrie>()?;
+    let mut accounts = NodeComparison::new(post_order(
+        account_trie.walk(None)?.map(|entry| entry.map(|(key, node)| (key.0, node.0))),
+    ))?;
+    let mut storage_trie = tx.cursor_dup_read::<tables::StoragesTrie>()?;
+    let mut storages = NodeComparison::new(post_order(storage_trie.walk(None)?.map(|entry| {
+        entry.map(|(hashed_address, entry)| ((hashed_address, entry.nibbles.0), entry.node))
+    })))?;
+
+    let mut intermediate_state = None;
+    let root = loop {
+        let progress = StateRoot::from_tx(tx)
+            .with_trie_cursor_factory(NoopTrieCursorFactory)
+            .with_threshold(threshold)
+            .with_intermediate_state(intermediate_state.take())
+            .root_with_progress()?;
+        let (root, updates) = match progress {
+            StateRootProgress::Progress(state, _, updates) => {
+                intermediate_state = Some(*state);
+                (None, updates)
+            }
+            StateRootProgress::Complete(root, _, updates) => (Some(root), updates),
+        };
+
+        let (account_nodes, storage_nodes) = expected_nodes(updates);
+        for (key, node) in account_nodes {
+            accounts.compare(key, node)?;
+        }
+        for (key, node) in storage_nodes {
+            storages.compare(key, node)?;
+        }
+
+        if let Some(root) = root {
+            break root
+        }
+    };
+
+    Ok(TrieVerification {
+        root,
+        account_divergence: accounts.finish().map(|divergence| divergence.map(|key| key.0)),
+        storage_divergence: storages.finish().map(|divergence| divergence.map(|key| key.0)),
+    })
+}
+
+/// Recomputes the storage trie of an account from its hashed storage and compares its nodes with
+/// the stored nodes of the account.
+fn verify_storage_trie<TX: DbTx>(tx: &TX, address: Address) -> eyre::Result<()> {
+    let hashed_address = keccak256(address);
+    let (root, _, updates) = StorageRoot::from_tx_hashed(tx, hashed_address)
+        .with_trie_cursor_factory(NoopTrieCursorFactory)
+        .root_with_updates()?;
+    println!("Storage root of the hashed storage of {address}: {root}");
+
+    let (_, storage_nodes) = expected_nodes(updates);
+    let mut storage_trie = tx.cursor_dup_read::<tables::StoragesTrie>()?;
+    let stored = storage_trie
+        .walk_dup(Some(hashed_address), None)?
+        .map(|entry| entry.map(|(_, entry)| ((hashed_address, entry.nibbles.0), entry.node)));
+    match first_divergence(post_order(stored), storage_nodes)? {
+        Some(divergence) => {
+            println!("First divergent storage trie node: {}", divergence.map(|key| key.0));
+            eyre::bail!("Storage trie mismatch of {address}")
+        }
+        None => println!("Storage trie nodes of {address} match the hashed storage"),
+    }
+    Ok(())
+}
+
+/// The account and storage trie nodes that are stored for a trie computed from scratch, in the
+/// order they're computed.
+type ExpectedNodes = (
+    BTreeMap<PostOrder<Nibbles>, BranchNodeCompact>,
+    BTreeMap<PostOrder<(B256, Nibbles)>, BranchNodeCompact>,
+);
+
+/// Collects the nodes of the trie updates that would be written to the trie tables.
+///
+/// The root nodes are not stored, see [TrieUpdates::flush].
+fn expected_nodes(updates: TrieUpdates) -> ExpectedNodes {
+    let mut account_nodes = BTreeMap::new();
+    let mut storage_nodes = BTreeMap::new();
+    for (key, operation) in updates {
+        let TrieOp::Update(node) = operation else { continue };
+        match key {
+            TrieKey::AccountNode(nibbles) if !nibbles.0.is_empty() => {
+                account_nodes.insert(PostOrder(nibbles.0), node);
+            }
+            TrieKey::StorageNode(hashed_address, nibbles) if !nibbles.0.is_empty() => {
+                storage_nodes.insert(PostOrder((hashed_address, nibbles.0)), node);
+            }
+            _ => {}
+        }
+    }
+    (account_nodes, storage_nodes)
+}
+
+/// The first difference between the stored trie nodes and the expected ones.
+#[derive(Debug, PartialEq, Eq)]
+enum Divergence<K> {
+    /// An expected node is not stored.
+    Missing(K),
+    /// A node is stored that's not expected.
+    Unexpected(K),
+    /// The stored node differs from the expected one.
+    Mismatch(K),
+}
+
+impl<K> Divergence<K> {
+    fn map<T>(self, f: impl FnOnce(K) -> T) -> Divergence<T> {
+        match self {
+            Self::Missing(key) => Divergence::Missing(f(key)),
+            Self::Unexpected(key) => Divergence::Unexpected(f(key)),
+            Self::Mismatch(key) => Divergence::Mismatch(f(key)),
+        }
+    }
+}
+
+impl<K: TrieNodeKey> fmt::Display for Divergence<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, key) = match self {
+            Self::Missing(key) => ("missing node", key),
+            Self::Unexpected(key) => ("unexpected node", key),
+            Self::Mismatch(key) => ("different node", key),
+        };
+        write!(f, "{kind} at ")?;
+        key.fmt_key(f)
+    }
+}
+
+/// The key of a trie node, formatted as the nibble prefix of its path.
+trait TrieNodeKey: Ord {
+    fn fmt_key(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+
+    /// Returns true if the node of the other key is part of the subtrie of this node.
+    fn is_ancestor_of(&self, other: &Self) -> bool;
+}
+
+impl TrieNodeKey for Nibbles {
+    fn fmt_key(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "prefix 0x")?;
+        self.iter().try_for_each(|nibble| write!(f, "{nibble:x}"))
+    }
+
+    fn is_ancestor_of(&self, other: &Self) -> bool {
+        other.len() > self.len() && other.starts_with(self)
+    }
+}
+
+impl TrieNodeKey for (B256, Nibbles) {
+    fn fmt_key(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hashed address {} ", self.0)?;
+        self.1.fmt_key(f)
+    }
+
+    fn is_ancestor_of(&self, other: &Self) -> bool {
+        self.0 == other.0 && self.1.is_ancestor_of(&other.1)
+    }
+}
+
+/// The key of a trie node, ordered like the nodes are computed: by key, except that a node comes
+/// after the nodes of its subtrie.
+#[derive(Debug, PartialEq, Eq)]
+struct PostOrder<K>(K);
+
+impl<K: TrieNodeKey> Ord for PostOrder<K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.0.is_ancestor_of(&other.0) {
+            Ordering::Greater
+        } else if other.0.is_ancestor_of(&self.0) {
+            Ordering::Less
+        } else {
+            self.0.cmp(&other.0)
+        }
+    }
+}
+
+impl<K: TrieNodeKey> PartialOrd for PostOrder<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Returns the stored nodes, which are walked in the order of their keys, in the [PostOrder] of
+/// their keys.
+fn post_order<K: TrieNodeKey, E>(
+    walk: impl Iterator<Item = Result<(K, BranchNodeCompact), E>>,
+) -> impl Iterator<Item = Result<(PostOrder<K>, BranchNodeCompact), E>> {
+    PostOrderNodes { walk: walk.fuse(), ancestors: Vec::new(), next: None }
+        .map(|entry| entry.map(|(key, node)| (PostOrder(key), node)))
+}
+
+/// The stored nodes in the [PostOrder] of their keys, see [post_order].
+struct PostOrderNodes<K, I: Iterator> {
+    walk: Fuse<I>,
+    /// The walked nodes whose subtries are not walked completely, each one the ancestor of the
+    /// next one.
+    ancestors: Vec<(K, BranchNodeCompact)>,
+    /// The walked node that is returned after its ancestors that are not its ancestors.
+    next: Option<(K, BranchNodeCompact)>,
+}
+
+impl<K, E, I> Iterator for PostOrderNodes<K, I>
+where
+    K: TrieNodeKey,
+    I: Iterator<Item = Result<(K, BranchNodeCompact), E>>,
+{
+    type Item = Result<(K, BranchNodeCompact), E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some((key, _)) = &self.next else {
+                match self.walk.next() {
+                    Some(Ok(entry)) => self.next = Some(entry),
+                    Some(Err(err)) => return Some(Err(err)),
+                    None => return self.ancestors.pop().map(Ok),
+                }
+                continue
+            };
+            // the subtrie of the last ancestor is walked completely once the next node is not part
+            // of it
+            if self.ancestors.last().is_some_and(|(ancestor, _)| !ancestor.is_ancestor_of(key)) {
+                return self.ancestors.pop().map(Ok)
+            }
+            self.ancestors.extend(self.next.take());
+        }
+    }
+}
+
+/// Compares the stored nodes with the expected nodes, which are compared in the order of their
+/// keys, until the first difference.
+struct NodeComparison<K, I> {
+    stored: I,
+    /// The next stored node, which is not compared yet.
+    next_stored: Option<(K, BranchNodeCompact)>,
+    divergence: Option<Divergence<K>>,
+}
+
+impl<K, E, I> NodeComparison<K, I>
+where
+    K: Ord,
+    I: Iterator<Item = Result<(K, BranchNodeCompact), E>>,
+{
+    fn new(mut stored: I) -> Result<Self, E> {
+        let next_stored = stored.next().transpose()?;
+        Ok(Self { stored, next_stored, divergence: None })
+    }
+
+    /// Compares the next expected node, which is ordered after the previously compared ones.
+    fn compare(&mut self, key: K, node: BranchNodeCompact) -> Result<(), E> {
+        if self.divergence.is_some() {
+            return Ok(())
+        }
+        self.divergence = match self.next_stored.take() {
+            // stored nodes that are ordered before the expected node are not expected
+            Some((stored_key, _)) if stored_key < key => Some(Divergence::Unexpected(stored_key)),
+            Some((stored_key, stored_node)) if stored_key == key => {
+                self.next_stored = self.stored.next().transpose()?;
+                (stored_node != node).then_some(Divergence::Mismatch(key))
+            }
+            next_stored => {
+                self.next_stored = next_stored;
+                Some(Divergence::Missing(key))
+            }
+        };
+        Ok(())
+    }
+
+    /// Returns the first difference, including the stored nodes that are ordered after all
+    /// expected nodes.
+    fn finish(self) -> Option<Divergence<K>> {
+        self.divergence.or_else(|| self.next_stored.map(|(key, _)| Divergence::Unexpected(key)))
+    }
+}
+
+/// Walks the stored nodes and the expected nodes in the order of their keys, returning the first
+/// difference.
+fn first_divergence<K: Ord, E>(
+    stored: impl Iterator<Item = Result<(K, BranchNodeCompact), E>>,
+    expected: BTreeMap<K, BranchNodeCompact>,
+) -> Result<Option<Divergence<K>>, E> {
+    let mut comparison = NodeComparison::new(stored)?;
+    for (key, node) in expected {
+        comparison.compare(key, node)?;
+    }
+    Ok(comparison.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use reth_db::{
+        cursor::{DbCursorRW, DbDupCursorRW},
+        init_db,
+        transaction::DbTxMut,
+        DatabaseEnv,
+    };
+    use reth_primitives::{
+        trie::{StoredBranchNode, StoredNibbles},
+        Account, StorageEntry, U256,
+    };
+    use std::convert::Infallible;
+
+    fn node(state_mask: u16) -> BranchNodeCompact {
+        BranchNodeCompact::new(state_mask, 0, 0, Vec::new(), None)
+    }
+
+    fn first(stored: &[(u8, u16)], expected: &[(u8, u16)]) -> Option<Divergence<Nibbles>> {
+        let key = |nibble: u8| Nibbles::from_nibbles([nibble]);
+        let stored =
+            stored.iter().map(|(nibble, mask)| Ok::<_, Infallible>((key(*nibble), node(*mask))));
+        let expected = expected.iter().map(|(nibble, mask)| (key(*nibble), node(*mask))).collect();
+        first_divergence(stored, expected).unwrap()
+    }
+
+    #[test]
+    fn find_first_divergence() {
+        let nodes = [(1, 0b11), (2, 0b11), (4, 0b11)];
+        assert_eq!(first(&nodes, &nodes), None);
+        assert_eq!(
+            first(&[(1, 0b11), (4, 0b11)], &nodes),
+            Some(Divergence::Missing(Nibbles::from_nibbles([2])))
+        );
+        assert_eq!(
+            first(&[(1, 0b11), (2, 0b11)], &nodes),
+            Some(Divergence::Missing(Nibbles::from_nibbles([4])))
+        );
+        assert_eq!(
+            first(&[(1, 0b11), (2, 0b11), (3, 0b11), (4, 0b11)], &nodes),
+            Some(Divergence::Unexpected(Nibbles::from_nibbles([3])))
+        );
+        assert_eq!(
+            first(&[(1, 0b11), (2, 0b101), (4, 0b11)], &nodes),
+            Some(Divergence::Mismatch(Nibbles::from_nibbles([2])))
+        );
+
+        let divergence = Divergence::Mismatch((B256::ZERO, Nibbles::from_nibbles([0xa, 1])));
+        assert_eq!(
+            divergence.to_string(),
+            format!("different node at hashed address {} prefix 0xa1", B256::ZERO)
+        );
+    }
+
+    /// Writes the hashed state of random accounts and one account with random storage, and the
+    /// trie tables of the state. Returns the state root and the hashed address of the account with
+    /// storage.
+    fn write_state(db: &DatabaseEnv) -> (B256, B256) {
+        let mut rng = StdRng::seed_from_u64(0);
+        let tx = db.tx_mut().unwrap();
+        let account = Account { nonce: 1, ..Default::default() };
+        for _ in 0..1000 {
+            tx.put::<tables::HashedAccounts>(B256::from(rng.gen::<[u8; 32]>()), account).unwrap();
+        }
+        let contract = B256::from(rng.gen::<[u8; 32]>());
+        tx.put::<tables::HashedAccounts>(contract, account).unwrap();
+        for _ in 0..1000 {
+            let entry =
+                StorageEntry { key: B256::from(rng.gen::<[u8; 32]>()), value: U256::from(1) };
+            tx.put::<tables::HashedStorages>(contract, entry).unwrap();
+        }
+
+        let (root, updates) = StateRoot::from_tx(&tx).root_with_updates().unwrap();
+        updates.flush(&tx).unwrap();
+        tx.commit().unwrap();
+        (root, contract)
+    }
+
+    #[test]
+    fn verify_corrupted_trie() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = init_db(dir.path(), Default::default()).unwrap();
+        let (root, contract) = write_state(&db);
+
+        // the nodes are compared in many chunks
+        let verification = verify_state_trie(&db.tx().unwrap(), 10).unwrap();
+        assert_eq!(verification.root, root);
+        assert_eq!(verification.account_divergence, None);
+        assert_eq!(verification.storage_divergence, None);
+
+        // a stored account node is changed
+        let tx = db.tx_mut().unwrap();
+        let (key, _) = tx.cursor_read::<tables::AccountsTrie>().unwrap().last().unwrap().unwrap();
+        tx.put::<tables::AccountsTrie>(key.clone(), StoredBranchNode(node(0b11))).unwrap();
+        let verification = verify_state_trie(&tx, 10).unwrap();
+        assert_eq!(verification.root, root);
+        assert_eq!(verification.account_divergence, Some(Divergence::Mismatch(key.0)));
+        assert_eq!(verification.storage_divergence, None);
+        tx.abort();
+
+        // a stored storage node is removed
+        let tx = db.tx_mut().unwrap();
+        let mut storage_trie = tx.cursor_dup_write::<tables::StoragesTrie>().unwrap();
+        let (hashed_address, entry) = storage_trie.first().unwrap().unwrap();
+        assert_eq!(hashed_address, contract);
+        storage_trie.delete_current().unwrap();
+        let verification = verify_state_trie(&tx, 10).unwrap();
+        assert_eq!(verification.account_divergence, None);
+        assert_eq!(
+            verification.storage_divergence,
+            Some(Divergence::Missing((contract, entry.nibbles.0)))
+        );
+        drop(storage_trie);
+        tx.abort();
+
+        // a node is stored below the nodes of the account trie
+        let tx = db.tx_mut().unwrap();
+        let key = Nibbles::unpack(B256::repeat_byte(0xff));
+        tx.put::<tables::AccountsTrie>(StoredNibbles(key.clone()), StoredBranchNode(node(0b11)))
+            .unwrap();
+        let verification = verify_state_trie(&tx, 10).unwrap();
+        assert_eq!(verification.account_divergence, Some(Divergence::Unexpected(key)));
+        assert_eq!(verification.storage_divergence, None);
+        tx.abort();
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::{
     args::{
-        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        utils::{chain_help, genesis_value_parser, hash_or_num_value_parser, SUPPORTED_CHAINS},
         DatabaseArgs,
     },
     core::cli::runner::CliContext,
@@ -19,7 +19,7 @@ use reth_blockchain_tree::{
     BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
 };
 use reth_db::{init_db, DatabaseEnv};
-use reth_interfaces::{consensus::Consensus, RethResult};
+use reth_interfaces::consensus::Consensus;
 use reth_node_api::PayloadBuilderAttributes;
 #[cfg(not(feature = "optimism"))]
 use reth_node_ethereum::EthEvmConfig;
@@ -29,27 +29,33 @@ use reth_primitives::{
     fs,
     revm_primitives::KzgSettings,
     stage::StageId,
-    Address, BlobTransaction, BlobTransactionSidecar, Bytes, ChainSpec, PooledTransactionsElement,
-    SealedBlock, SealedBlockWithSenders, Transaction, TransactionSigned, TxEip4844, B256, U256,
+    Address, BlobTransaction, BlobTransactionSidecar, BlockHashOrNumber, Bytes, ChainSpec, Log,
+    PooledTransactionsElement, Receipt, SealedBlock, SealedBlockWithSenders, Transaction,
+    TransactionSigned, TxEip4844, B256, U256,
 };
 use reth_provider::{
-    providers::BlockchainProvider, BlockHashReader, BlockReader, BlockWriter, ExecutorFactory,
-    ProviderFactory, StageCheckpointReader, StateProviderFactory,
+    providers::BlockchainProvider, BlockNumReader, BlockReader, BlockWriter, ExecutorFactory,
+    ProviderFactory, StageCheckpointReader, StateProviderFactory, StateRootProvider,
 };
 use reth_revm::EvmProcessorFactory;
 #[cfg(feature = "optimism")]
 use reth_rpc_types::engine::OptimismPayloadAttributes;
-use reth_rpc_types::engine::{BlobsBundleV1, PayloadAttributes};
+use reth_rpc_types::{
+    engine::{BlobsBundleV1, PayloadAttributes},
+    Withdrawal,
+};
 use reth_transaction_pool::{
     blobstore::InMemoryBlobStore, BlobStore, EthPooledTransaction, PoolConfig, TransactionOrigin,
     TransactionPool, TransactionValidationTaskExecutor,
 };
+use serde::Serialize;
 use std::{path::PathBuf, str::FromStr, sync::Arc};
 use tracing::*;
 
 /// `reth debug build-block` command
-/// This debug routine requires that the node is positioned at the block before the target.
-/// The script will then parse the block and attempt to build a similar one.
+/// This debug routine builds a block with the given transactions and attributes on top of a parent
+/// block, which defaults to the tip of the database, and prints the block with its receipts and
+/// state root.
 #[derive(Debug, Parser)]
 pub struct Command {
     /// The path to the data dir for all reth files and subdirectories.
@@ -94,22 +100,42 @@ pub struct Command {
     #[arg(long)]
     suggested_fee_recipient: Address,
 
+    /// The hash or number of the block to build on, defaults to the tip of the database.
+    ///
+    /// NOTE: the transactions are still validated by the pool against the latest state.
+    #[arg(long, value_parser = hash_or_num_value_parser)]
+    parent: Option<BlockHashOrNumber>,
+
+    /// Path to a JSON file that contains the array of withdrawals of the payload attributes.
+    #[arg(long, value_name = "PATH")]
+    withdrawals_file: Option<PathBuf>,
+
     /// Array of transactions.
     /// NOTE: 4844 transactions must be provided in the same order as they appear in the blobs
     /// bundle.
     #[arg(long, value_delimiter = ',')]
     transactions: Vec<String>,
 
+    /// Path to a file that contains raw signed transactions, hex encoded, one per line.
+    ///
+    /// They are added to the pool before the transactions of `--transactions`.
+    #[arg(long, value_name = "PATH")]
+    tx_file: Option<PathBuf>,
+
+    /// Fail if the state root of the built block differs from this one.
+    #[arg(long, value_name = "ROOT")]
+    expected_state_root: Option<B256>,
+
     /// Path to the file that contains a corresponding blobs bundle.
     #[arg(long)]
     blobs_bundle_path: Option<PathBuf>,
 }
 
 impl Command {
-    /// Fetches the best block block from the database.
+    /// Fetches the parent block from the database, which is the best block if no parent is given.
     ///
     /// If the database is empty, returns the genesis block.
-    fn lookup_best_block(&self, db: Arc<DatabaseEnv>) -> RethResult<Arc<SealedBlock>> {
+    fn lookup_parent_block(&self, db: Arc<DatabaseEnv>) -> eyre::Result<Arc<SealedBlock>> {
         let factory = ProviderFactory::new(
             db,
             self.chain.clone(),
@@ -117,20 +143,36 @@ impl Command {
         )?;
         let provider = factory.provider()?;
 
-        let best_number =
-            provider.get_stage_checkpoint(StageId::Finish)?.unwrap_or_default().block_number;
-        let best_hash = provider
-            .block_hash(best_number)?
-            .expect("the hash for the latest block is missing, database is corrupt");
+        let parent = match self.parent {
+            Some(parent) => parent,
+            None => provider
+                .get_stage_checkpoint(StageId::Finish)?
+                .unwrap_or_default()
+                .block_number
+                .into(),
+        };
 
         Ok(Arc::new(
             provider
-                .block(best_number.into())?
-                .expect("the header for the latest block is missing, database is corrupt")
-                .seal(best_hash),
+                .block(parent)?
+                .ok_or_else(|| eyre::eyre!("the parent block {parent:?} is missing"))?
+                .seal_slow(),
         ))
     }
 
+    /// Reads the raw transactions of `--tx-file`, followed by the ones of `--transactions`.
+    fn raw_transactions(&self) -> eyre::Result<Vec<String>> {
+        let mut transactions = Vec::new();
+        if let Some(path) = &self.tx_file {
+            let contents = fs::read_to_string(path)?;
+            transactions.extend(
+                contents.lines().map(str::trim).filter(|line| !line.is_empty()).map(String::from),
+            );
+        }
+        transactions.extend(self.transactions.iter().cloned());
+        Ok(transactions)
+    }
+
     /// Loads the trusted setup params from a given file path or falls back to
     /// `MAINNET_KZG_TRUSTED_SETUP`.
     fn kzg_settings(&self) -> eyre::Result<Arc<KzgSettings>> {
@@ -175,16 +217,16 @@ impl Command {
         let tree = BlockchainTree::new(tree_externals, BlockchainTreeConfig::default(), None)?;
         let blockchain_tree = ShareableBlockchainTree::new(tree);
 
-        // fetch the best block from the database
-        let best_block =
-            self.lookup_best_block(Arc::clone(&db)).wrap_err("the head block is missing")?;
+        // fetch the parent block from the database
+        let parent_block =
+            self.lookup_parent_block(Arc::clone(&db)).wrap_err("the parent block is missing")?;
 
         let blockchain_db =
             BlockchainProvider::new(provider_factory.clone(), blockchain_tree.clone())?;
         let blob_store = InMemoryBlobStore::default();
 
         let validator = TransactionValidationTaskExecutor::eth_builder(Arc::clone(&self.chain))
-            .with_head_timestamp(best_block.timestamp)
+            .with_head_timestamp(parent_block.timestamp)
             .kzg_settings(self.kzg_settings()?)
             .with_additional_tasks(1)
             .build_with_tasks(blockchain_db.clone(), ctx.task_executor.clone(), blob_store.clone());
@@ -205,7 +247,7 @@ impl Command {
             })
             .transpose()?;
 
-        for tx_bytes in self.transactions.iter() {
+        for tx_bytes in self.raw_transactions()?.iter() {
             debug!(target: "reth::cli", bytes = ?tx_bytes, "Decoding transaction");
             let transaction = TransactionSigned::decode(&mut &Bytes::from_str(tx_bytes)?[..])?
                 .into_ecrecovered()
@@ -247,20 +289,29 @@ impl Command {
                 .await?;
         }
 
+        let withdrawals = self
+            .withdrawals_file
+            .as_ref()
+            .map(|path| -> eyre::Result<Vec<Withdrawal>> {
+                let contents = fs::read_to_string(path)
+                    .wrap_err(format!("could not read {}", path.display()))?;
+                serde_json::from_str(&contents).wrap_err("failed to deserialize withdrawals")
+            })
+            .transpose()?;
+
         let payload_attrs = PayloadAttributes {
             parent_beacon_block_root: self.parent_beacon_block_root,
             prev_randao: self.prev_randao,
             timestamp: self.timestamp,
             suggested_fee_recipient: self.suggested_fee_recipient,
-            // TODO: add support for withdrawals
-            withdrawals: None,
+            withdrawals,
         };
         let payload_config = PayloadConfig::new(
-            Arc::clone(&best_block),
+            Arc::clone(&parent_block),
             Bytes::default(),
             #[cfg(feature = "optimism")]
             reth_node_optimism::OptimismPayloadBuilderAttributes::try_new(
-                best_block.hash(),
+                parent_block.hash(),
                 OptimismPayloadAttributes {
                     payload_attributes: payload_attrs,
                     transactions: None,
@@ -270,7 +321,7 @@ impl Command {
             )?,
             #[cfg(not(feature = "optimism"))]
             reth_payload_builder::EthPayloadBuilderAttributes::try_new(
-                best_block.hash(),
+                parent_block.hash(),
                 payload_attrs,
             )?,
             self.chain.clone(),
@@ -309,16 +360,20 @@ impl Command {
                     SealedBlockWithSenders::new(block.clone(), senders).unwrap();
 
                 let executor_factory = EvmProcessorFactory::new(self.chain.clone(), evm_config);
-                let mut executor = executor_factory.with_state(blockchain_db.latest()?);
+                let mut executor = executor_factory
+                    .with_state(blockchain_db.state_by_block_hash(parent_block.hash())?);
                 executor
                     .execute_and_verify_receipt(&block_with_senders.clone().unseal(), U256::MAX)?;
                 let state = executor.take_output_state();
                 debug!(target: "reth::cli", ?state, "Executed block");
 
-                let hashed_state = state.hash_state_slow();
-                let (state_root, trie_updates) = state
-                    .hash_state_slow()
-                    .state_root_with_updates(provider_factory.provider()?.tx_ref())?;
+                let (state_root, trie_updates) = blockchain_db
+                    .state_by_block_hash(parent_block.hash())?
+                    .state_root_with_updates(state.state())?;
+
+                let outcome =
+                    BuiltBlock::new(block, state.receipts_by_block(block.number), state_root);
+                println!("{}", serde_json::to_string_pretty(&outcome)?);
 
                 if state_root != block_with_senders.state_root {
                     eyre::bail!(
@@ -327,17 +382,26 @@ impl Command {
                         state_root
                     );
                 }
+                if let Some(expected) = self.expected_state_root {
+                    if state_root != expected {
+                        eyre::bail!("state root mismatch. expected: {expected}. got: {state_root}");
+                    }
+                }
 
-                // Attempt to insert new block without committing
-                let provider_rw = provider_factory.provider_rw()?;
-                provider_rw.append_blocks_with_state(
-                    Vec::from([block_with_senders]),
-                    state,
-                    hashed_state,
-                    trie_updates,
-                    None,
-                )?;
-                info!(target: "reth::cli", "Successfully appended built block");
+                // the block can only be appended to the tip of the database
+                if parent_block.number == provider_factory.provider()?.best_block_number()? {
+                    // Attempt to insert new block without committing
+                    let hashed_state = state.hash_state_slow();
+                    let provider_rw = provider_factory.provider_rw()?;
+                    provider_rw.append_blocks_with_state(
+                        Vec::from([block_with_senders]),
+                        state,
+                        hashed_state,
+                        trie_updates,
+                        None,
+                    )?;
+                    info!(target: "reth::cli", "Successfully appended built block");
+                }
             }
             _ => unreachable!("other outcomes are unreachable"),
         };
@@ -345,3 +409,133 @@ impl Command {
         Ok(())
     }
 }
+
+/// The outcome of the built block that's printed by `reth debug build-block`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BuiltBlock<'a> {
+    block: &'a SealedBlock,
+    receipts: Vec<BuiltReceipt<'a>>,
+    gas_used: u64,
+    state_root: B256,
+}
+
+impl<'a> BuiltBlock<'a> {
+    fn new(block: &'a SealedBlock, receipts: &'a [Option<Receipt>], state_root: B256) -> Self {
+        let mut cumulative_gas_used = 0;
+        let receipts = block
+            .body
+            .iter()
+            .zip(receipts.iter().flatten())
+            .map(|(transaction, receipt)| {
+                let gas_used = receipt.cumulative_gas_used - cumulative_gas_used;
+                cumulative_gas_used = receipt.cumulative_gas_used;
+                BuiltReceipt {
+                    transaction_hash: transaction.hash(),
+                    success: receipt.success,
+                    gas_used,
+                    cumulative_gas_used,
+                    logs: &receipt.logs,
+                }
+            })
+            .collect();
+        Self { block, receipts, gas_used: block.gas_used, state_root }
+    }
+}
+
+/// The receipt of a transaction of the built block.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BuiltReceipt<'a> {
+    transaction_hash: B256,
+    success: bool,
+    gas_used: u64,
+    cumulative_gas_used: u64,
+    logs: &'a [Log],
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Header;
+
+    /// The arguments that are required to build a block.
+    const REQUIRED_ARGS: [&str; 7] = [
+        "build-block",
+        "--prev-randao",
+        "0x0000000000000000000000000000000000000000000000000000000000000001",
+        "--timestamp",
+        "1",
+        "--suggested-fee-recipient",
+        "0x0000000000000000000000000000000000000001",
+    ];
+
+    #[test]
+    fn parse_parent() {
+        let command = Command::try_parse_from(REQUIRED_ARGS).unwrap();
+        assert_eq!(command.parent, None);
+
+        let command =
+            Command::try_parse_from(REQUIRED_ARGS.into_iter().chain(["--parent", "10"])).unwrap();
+        assert_eq!(command.parent, Some(BlockHashOrNumber::Number(10)));
+
+        let hash = B256::with_last_byte(1);
+        let command = Command::try_parse_from(
+            REQUIRED_ARGS.into_iter().chain(["--parent", &hash.to_string()]),
+        )
+        .unwrap();
+        assert_eq!(command.parent, Some(BlockHashOrNumber::Hash(hash)));
+    }
+
+    #[test]
+    fn tx_file_transactions_come_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let tx_file = dir.path().join("transactions.txt");
+        fs::write(&tx_file, "0x01\n\n  0x02  \n").unwrap();
+
+        let command = Command::try_parse_from(REQUIRED_ARGS.into_iter().chain([
+            "--transactions",
+            "0x03,0x04",
+            "--tx-file",
+            tx_file.to_str().unwrap(),
+        ]))
+        .unwrap();
+        assert_eq!(command.raw_transactions().unwrap(), vec!["0x01", "0x02", "0x03", "0x04"]);
+
+        let command = Command::try_parse_from(
+            REQUIRED_ARGS.into_iter().chain(["--tx-file", "missing-transactions.txt"]),
+        )
+        .unwrap();
+        assert!(command.raw_transactions().is_err());
+    }
+
+    #[test]
+    fn built_block_receipts() {
+        let transaction =
+            |hash| TransactionSigned { hash: B256::with_last_byte(hash), ..Default::default() };
+        let block = SealedBlock {
+            header: Header { gas_used: 50_000, ..Default::default() }.seal_slow(),
+            body: vec![transaction(1), transaction(2)],
+            ..Default::default()
+        };
+        let receipt = |success, cumulative_gas_used| {
+            Some(Receipt { success, cumulative_gas_used, ..Default::default() })
+        };
+        let receipts = vec![receipt(true, 21_000), receipt(false, 50_000)];
+
+        let outcome = serde_json::to_value(BuiltBlock::new(&block, &receipts, B256::ZERO)).unwrap();
+        assert_eq!(outcome["gasUsed"], 50_000);
+        assert_eq!(outcome["stateRoot"], B256::ZERO.to_string());
+
+        // the gas used of every transaction is derived from the cumulative gas used
+        let receipts = outcome["receipts"].as_array().unwrap();
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0]["transactionHash"], B256::with_last_byte(1).to_string());
+        assert_eq!(receipts[0]["success"], true);
+        assert_eq!(receipts[0]["gasUsed"], 21_000);
+        assert_eq!(receipts[1]["transactionHash"], B256::with_last_byte(2).to_string());
+        assert_eq!(receipts[1]["success"], false);
+        assert_eq!(receipts[1]["gasUsed"], 29_000);
+        assert_eq!(receipts[1]["cumulativeGasUsed"], 50_000);
+    }
+}
@@ -125,9 +125,7 @@ impl Command {
                     factory.clone(),
                     stage_conf.etl.clone(),
                 )
-                .set(SenderRecoveryStage {
-                    commit_threshold: stage_conf.sender_recovery.commit_threshold,
-                })
+                .set(SenderRecoveryStage::new_with_config(stage_conf.sender_recovery))
                 .set(ExecutionStage::new(
                     factory,
                     ExecutionStageThresholds {
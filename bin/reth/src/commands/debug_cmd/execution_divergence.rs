@@ -0,0 +1,518 @@
+//! Command for finding the first block whose execution diverges from a reference node.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    core::cli::runner::CliContext,
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::Parser;
+use eyre::Context;
+use jsonrpsee::{
+    core::{client::ClientT, params::ArrayParams},
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use reth_blockchain_tree::BundleStateDataRef;
+use reth_db::{database::Database, open_db_read_only};
+use reth_interfaces::executor::{BlockExecutionError, BlockValidationError};
+use reth_node_ethereum::EthEvmConfig;
+use reth_primitives::{
+    BlockHash, BlockNumber, BlockNumberOrTag, BlockWithSenders, ChainSpec, ForkBlock, Log, Receipt,
+    Receipts, B256, U256, U64,
+};
+use reth_provider::{
+    BlockHashReader, BlockReader, BundleStateProvider, BundleStateWithReceipts, ExecutorFactory,
+    HeaderProvider, ProviderFactory, PrunableBlockExecutor, StateRootProvider, TransactionVariant,
+};
+use reth_revm::EvmProcessorFactory;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::*;
+
+/// `reth debug execution-divergence` command
+///
+/// Re-executes the blocks of the range on top of the local state before `--from`, which is
+/// assumed to be correct, and bisects the range for the first block whose post-state root (or
+/// receipts root with `--receipts`) differs from the one of the reference node. This assumes that
+/// once diverged, the state keeps diverging in the later blocks of the range.
+///
+/// The range is executed and compared in batches of `--batch-size` blocks, and only the first
+/// batch that diverges is bisected.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[command(flatten)]
+    db: DatabaseArgs,
+
+    /// The HTTP URL of the JSON-RPC endpoint of the reference node.
+    #[arg(long, value_name = "URL")]
+    rpc: String,
+
+    /// The first block of the range.
+    #[arg(long, value_name = "BLOCK")]
+    from: BlockNumber,
+
+    /// The last block of the range.
+    #[arg(long, value_name = "BLOCK")]
+    to: BlockNumber,
+
+    /// Compare the receipts roots instead of the state roots, which is faster since no state root
+    /// has to be computed.
+    #[arg(long)]
+    receipts: bool,
+
+    /// The maximum number of blocks that are executed and compared at once.
+    #[arg(long, value_name = "BLOCKS", default_value_t = 1_000)]
+    batch_size: u64,
+
+    /// The directory the responses of the reference node are cached in.
+    ///
+    /// Defaults to `<DATADIR>/execution-divergence`.
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+}
+
+impl Command {
+    /// Execute `debug execution-divergence` command
+    pub async fn execute(self, _ctx: CliContext) -> eyre::Result<()> {
+        eyre::ensure!(self.from > 0, "The range can't start at the genesis block");
+        eyre::ensure!(self.from <= self.to, "Invalid block range {}..={}", self.from, self.to);
+        eyre::ensure!(self.batch_size > 0, "The batch size can't be zero");
+
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+        let db = open_db_read_only(&db_path, self.db.database_args())?;
+        let factory = ProviderFactory::new(db, self.chain.clone(), data_dir.static_files_path())?;
+
+        let cache_dir = self
+            .cache_dir
+            .clone()
+            .unwrap_or_else(|| data_dir.data_dir_path().join("execution-divergence"));
+        let reference = ReferenceNode::new(&self.rpc, cache_dir)?;
+
+        let parent = self.from - 1;
+        let fork = ForkBlock {
+            number: parent,
+            hash: factory
+                .block_hash(parent)?
+                .ok_or_else(|| eyre::eyre!("Block {parent} not found"))?,
+        };
+        let local = LocalExecution {
+            factory: &factory,
+            executor_factory: EvmProcessorFactory::new(self.chain.clone(), EthEvmConfig::default()),
+            fork,
+            no_hashes: BTreeMap::new(),
+        };
+
+        // the state after the blocks `self.from..search.lo`, which match the reference node
+        let mut good =
+            BundleStateWithReceipts::new(Default::default(), Receipts::default(), self.from);
+        let mut search = DivergenceSearch::new(self.from..=self.to, self.batch_size);
+        while let Some(mid) = search.next() {
+            let lo = search.lo;
+            info!(target: "reth::cli", blocks = ?lo..=mid, "Executing blocks");
+            let state = match local.execute(&good, lo..=mid)? {
+                Execution::Executed(state) => state,
+                Execution::ReceiptsDiverged(block) => {
+                    info!(target: "reth::cli", block, "Receipts diverge from the local header");
+                    search.diverged(block);
+                    continue
+                }
+            };
+
+            let mut combined = good.clone();
+            combined.extend(state);
+            let header = reference.header(mid).await?;
+            let hash = factory.block_hash(mid)?.unwrap_or_default();
+            eyre::ensure!(
+                hash == header.hash,
+                "Block {mid} with hash {hash} is not the block {} of the reference node",
+                header.hash
+            );
+            let matches = if self.receipts {
+                combined.receipts_root_slow(mid) == Some(header.receipts_root)
+            } else {
+                local.state_root(&combined)? == header.state_root
+            };
+
+            if matches {
+                info!(target: "reth::cli", block = mid, "Block matches the reference node");
+                good = compact(combined, mid + 1);
+                search.matched(mid);
+            } else {
+                info!(target: "reth::cli", block = mid, "Block diverges from the reference node");
+                search.diverged(mid);
+            }
+        }
+
+        let Some(number) = search.diverged else {
+            println!("No divergence found in blocks {}..={}", self.from, self.to);
+            return Ok(())
+        };
+        let (block, td) = local.block(number)?;
+        let receipts = local.execute_transactions(&good, &block, td)?;
+        let reference_receipts = reference.receipts(number).await?;
+
+        println!("First divergent block: {number} ({})", block.header.hash_slow());
+        for line in diff_receipts(&receipts, &reference_receipts) {
+            println!("{line}");
+        }
+        Ok(())
+    }
+}
+
+/// The search for the first block of a range whose execution diverges.
+///
+/// The range is compared in batches of at most `batch_size` blocks, until a batch diverges and is
+/// bisected.
+#[derive(Debug)]
+struct DivergenceSearch {
+    /// The first block that is not known to match.
+    lo: BlockNumber,
+    /// The last block of the range.
+    to: BlockNumber,
+    /// The maximum number of blocks that are compared at once.
+    batch_size: u64,
+    /// The first block that is known to diverge.
+    diverged: Option<BlockNumber>,
+}
+
+impl DivergenceSearch {
+    fn new(range: RangeInclusive<BlockNumber>, batch_size: u64) -> Self {
+        Self { lo: *range.start(), to: *range.end(), batch_size, diverged: None }
+    }
+
+    /// Returns the last block of the blocks `lo..` to compare next, `None` once the search is
+    /// done.
+    fn next(&self) -> Option<BlockNumber> {
+        match self.diverged {
+            Some(block) if self.lo >= block => None,
+            Some(block) => Some(self.lo + (block - self.lo) / 2),
+            None if self.lo > self.to => None,
+            None => Some(self.to.min(self.lo + self.batch_size - 1)),
+        }
+    }
+
+    /// Records that the blocks up to the given block match.
+    fn matched(&mut self, block: BlockNumber) {
+        self.lo = block + 1;
+    }
+
+    /// Records that the given block diverges.
+    fn diverged(&mut self, block: BlockNumber) {
+        self.diverged = Some(block);
+    }
+}
+
+/// Drops the reverts and the receipts of the state of blocks that match the reference node, which
+/// are not needed to execute and compare the next blocks from `next_block`.
+///
+/// This bounds the memory of the state by the touched accounts instead of the number of blocks.
+fn compact(mut state: BundleStateWithReceipts, next_block: BlockNumber) -> BundleStateWithReceipts {
+    state.state_mut().take_all_reverts();
+    *state.receipts_mut() = Receipts::default();
+    state.set_first_block(next_block);
+    state
+}
+
+/// The outcome of the execution of a range of blocks.
+enum Execution {
+    /// All blocks were executed, with the resulting state.
+    Executed(BundleStateWithReceipts),
+    /// The receipts of the block don't match the receipts root of its local header.
+    ReceiptsDiverged(BlockNumber),
+}
+
+/// Executes the blocks of the local database on top of the state before the range.
+struct LocalExecution<'a, DB> {
+    factory: &'a ProviderFactory<DB>,
+    executor_factory: EvmProcessorFactory<EthEvmConfig>,
+    /// The block before the range.
+    fork: ForkBlock,
+    /// The executed blocks are canonical, so there are no block hashes on top of the database.
+    no_hashes: BTreeMap<BlockNumber, BlockHash>,
+}
+
+impl<DB: Database> LocalExecution<'_, DB> {
+    /// Returns the block with its senders and total difficulty.
+    fn block(&self, number: BlockNumber) -> eyre::Result<(BlockWithSenders, U256)> {
+        let provider = self.factory.provider()?;
+        let block = provider
+            .block_with_senders(number.into(), TransactionVariant::WithHash)?
+            .ok_or_else(|| eyre::eyre!("Block {number} not found"))?;
+        let td = provider
+            .header_td_by_number(number)?
+            .ok_or_else(|| eyre::eyre!("Total difficulty of block {number} not found"))?;
+        Ok((block, td))
+    }
+
+    /// Returns an executor on top of the given state of the blocks since the start of the range.
+    fn executor<'a>(
+        &'a self,
+        state: &'a BundleStateWithReceipts,
+    ) -> eyre::Result<Box<dyn PrunableBlockExecutor<Error = BlockExecutionError> + 'a>> {
+        let state_provider = BundleStateProvider::new(
+            self.factory.history_by_block_number(self.fork.number)?,
+            BundleStateDataRef {
+                state,
+                sidechain_block_hashes: &self.no_hashes,
+                canonical_block_hashes: &self.no_hashes,
+                canonical_fork: self.fork,
+            },
+        );
+        Ok(self.executor_factory.with_state(state_provider))
+    }
+
+    /// Executes the blocks of the range on top of the given state of the preceding blocks.
+    fn execute(
+        &self,
+        state: &BundleStateWithReceipts,
+        range: RangeInclusive<BlockNumber>,
+    ) -> eyre::Result<Execution> {
+        let mut executor = self.executor(state)?;
+
+        for number in range {
+            let (block, td) = self.block(number)?;
+            match executor.execute_and_verify_receipt(&block, td) {
+                Ok(()) => {}
+                Err(BlockExecutionError::Validation(
+                    BlockValidationError::ReceiptRootDiff(_) |
+                    BlockValidationError::BloomLogDiff(_),
+                )) => return Ok(Execution::ReceiptsDiverged(number)),
+                Err(err) => return Err(err).wrap_err_with(|| format!("Block {number} failed")),
+            }
+        }
+        Ok(Execution::Executed(executor.take_output_state()))
+    }
+
+    /// Executes the transactions of the block on top of the given state of the preceding blocks
+    /// without verifying them, returning the receipts.
+    fn execute_transactions(
+        &self,
+        state: &BundleStateWithReceipts,
+        block: &BlockWithSenders,
+        td: U256,
+    ) -> eyre::Result<Vec<Receipt>> {
+        let mut executor = self.executor(state)?;
+        let (receipts, _) = executor.execute_transactions(block, td)?;
+        Ok(receipts)
+    }
+
+    /// Computes the state root of the state after the blocks since the start of the range.
+    fn state_root(&self, state: &BundleStateWithReceipts) -> eyre::Result<B256> {
+        Ok(self.factory.history_by_block_number(self.fork.number)?.state_root(state.state())?)
+    }
+}
+
+/// The fields of a block of the reference node that are compared.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReferenceHeader {
+    hash: B256,
+    state_root: B256,
+    receipts_root: B256,
+}
+
+/// The fields of a receipt of the reference node that are compared.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReferenceReceipt {
+    transaction_hash: B256,
+    /// Not set for receipts before Byzantium.
+    #[serde(default)]
+    status: Option<U64>,
+    gas_used: U64,
+    logs: Vec<Log>,
+}
+
+/// The JSON-RPC client of the reference node, which caches the responses on disk.
+struct ReferenceNode {
+    client: HttpClient,
+    cache_dir: PathBuf,
+}
+
+impl ReferenceNode {
+    fn new(url: &str, cache_dir: PathBuf) -> eyre::Result<Self> {
+        let client = HttpClientBuilder::default()
+            .build(url)
+            .wrap_err_with(|| format!("Invalid RPC URL {url}"))?;
+        fs::create_dir_all(&cache_dir)
+            .wrap_err_with(|| format!("Could not create directory {}", cache_dir.display()))?;
+        Ok(Self { client, cache_dir })
+    }
+
+    /// Returns the header of the block.
+    async fn header(&self, number: BlockNumber) -> eyre::Result<ReferenceHeader> {
+        self.request(
+            &format!("header-{number}.json"),
+            "eth_getBlockByNumber",
+            rpc_params![BlockNumberOrTag::Number(number), false],
+        )
+        .await
+    }
+
+    /// Returns the receipts of the block.
+    async fn receipts(&self, number: BlockNumber) -> eyre::Result<Vec<ReferenceReceipt>> {
+        self.request(
+            &format!("receipts-{number}.json"),
+            "eth_getBlockReceipts",
+            rpc_params![BlockNumberOrTag::Number(number)],
+        )
+        .await
+    }
+
+    /// Returns the cached response of the file, or sends the request and caches its response.
+    async fn request<T: Serialize + DeserializeOwned>(
+        &self,
+        file: &str,
+        method: &str,
+        params: ArrayParams,
+    ) -> eyre::Result<T> {
+        let path = self.cache_dir.join(file);
+        if let Some(response) = read_cached(&path) {
+            debug!(target: "reth::cli", path = %path.display(), "Using cached response");
+            return Ok(response)
+        }
+
+        let response: Option<T> = self
+            .client
+            .request(method, params)
+            .await
+            .wrap_err_with(|| format!("{method} request to the reference node failed"))?;
+        let response =
+            response.ok_or_else(|| eyre::eyre!("Reference node returned no result for {file}"))?;
+
+        // the response is renamed into place, so an interrupted write is never read
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(&response)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(response)
+    }
+}
+
+/// Reads a cached response, ignoring missing and invalid files.
+fn read_cached<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    serde_json::from_slice(&fs::read(path).ok()?).ok()
+}
+
+/// Returns the differences of the local receipts of a block to the receipts of the reference
+/// node, one line per difference.
+fn diff_receipts(local: &[Receipt], reference: &[ReferenceReceipt]) -> Vec<String> {
+    let mut lines = Vec::new();
+    if local.len() != reference.len() {
+        lines.push(format!("Receipts: local {}, reference {}", local.len(), reference.len()));
+    }
+
+    let mut cumulative_gas_used = 0;
+    for (index, (receipt, expected)) in local.iter().zip(reference).enumerate() {
+        let gas_used = receipt.cumulative_gas_used - cumulative_gas_used;
+        cumulative_gas_used = receipt.cumulative_gas_used;
+
+        let mut diffs = Vec::new();
+        if let Some(status) = expected.status {
+            let expected_success = status == U64::from(1);
+            if receipt.success != expected_success {
+                diffs.push(format!(
+                    "success: local {}, reference {expected_success}",
+                    receipt.success
+                ));
+            }
+        }
+        if gas_used != expected.gas_used.to::<u64>() {
+            diffs.push(format!("gas used: local {gas_used}, reference {}", expected.gas_used));
+        }
+        if receipt.logs != expected.logs {
+            diffs.push(format!("logs: local {:?}, reference {:?}", receipt.logs, expected.logs));
+        }
+
+        if !diffs.is_empty() {
+            lines.push(format!("Transaction {index} ({}):", expected.transaction_hash));
+            lines.extend(diffs.into_iter().map(|diff| format!("  {diff}")));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs the search against blocks that diverge from the given block, returning the divergent
+    /// block and the compared ranges.
+    fn search(
+        range: RangeInclusive<BlockNumber>,
+        batch_size: u64,
+        diverges_from: BlockNumber,
+    ) -> (Option<BlockNumber>, Vec<RangeInclusive<BlockNumber>>) {
+        let mut search = DivergenceSearch::new(range, batch_size);
+        let mut compared = Vec::new();
+        while let Some(mid) = search.next() {
+            compared.push(search.lo..=mid);
+            if mid < diverges_from {
+                search.matched(mid);
+            } else {
+                search.diverged(mid);
+            }
+        }
+        (search.diverged, compared)
+    }
+
+    #[test]
+    fn finds_first_divergent_block_in_batches() {
+        let (diverged, compared) = search(1..=100, 10, 37);
+        assert_eq!(diverged, Some(37));
+        assert!(compared.iter().all(|range| range.clone().count() <= 10));
+        // the batches before the divergent one are compared once
+        assert_eq!(&compared[..4], &[1..=10, 11..=20, 21..=30, 31..=40]);
+
+        assert_eq!(search(1..=100, 10, 1).0, Some(1));
+        assert_eq!(search(1..=100, 10, 100).0, Some(100));
+        assert_eq!(search(1..=100, 1_000, 64).0, Some(64));
+    }
+
+    #[test]
+    fn no_divergence() {
+        let (diverged, compared) = search(5..=25, 10, u64::MAX);
+        assert_eq!(diverged, None);
+        assert_eq!(compared, vec![5..=14, 15..=24, 25..=25]);
+    }
+
+    #[test]
+    fn compact_state_of_matching_blocks() {
+        let receipts = Receipts::from_vec(vec![vec![Some(Receipt::default())]]);
+        let state = BundleStateWithReceipts::new(Default::default(), receipts, 10);
+        let state = compact(state, 11);
+        assert_eq!(state.first_block(), 11);
+        assert!(state.receipts().is_empty());
+    }
+}
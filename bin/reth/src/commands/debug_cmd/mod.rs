@@ -4,6 +4,7 @@ use crate::core::cli::runner::CliContext;
 use clap::{Parser, Subcommand};
 mod build_block;
 mod execution;
+mod execution_divergence;
 mod in_memory_merkle;
 mod merkle;
 mod replay_engine;
@@ -20,6 +21,9 @@ pub struct Command {
 pub enum Subcommands {
     /// Debug the roundtrip execution of blocks as well as the generated data.
     Execution(execution::Command),
+    /// Find the first block whose execution diverges from a reference node by bisecting a block
+    /// range against its RPC.
+    ExecutionDivergence(execution_divergence::Command),
     /// Debug the clean & incremental state root calculations.
     Merkle(merkle::Command),
     /// Debug in-memory state root calculation.
@@ -35,6 +39,7 @@ impl Command {
     pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
         match self.command {
             Subcommands::Execution(command) => command.execute(ctx).await,
+            Subcommands::ExecutionDivergence(command) => command.execute(ctx).await,
             Subcommands::Merkle(command) => command.execute(ctx).await,
             Subcommands::InMemoryMerkle(command) => command.execute(ctx).await,
             Subcommands::BuildBlock(command) => command.execute(ctx).await,
@@ -10,7 +10,9 @@ use crate::{
 use clap::Parser;
 use eyre::Context;
 use reth_basic_payload_builder::{BasicPayloadJobGenerator, BasicPayloadJobGeneratorConfig};
-use reth_beacon_consensus::{hooks::EngineHooks, BeaconConsensus, BeaconConsensusEngine};
+use reth_beacon_consensus::{
+    hooks::EngineHooks, BeaconConsensus, BeaconConsensusEngine, ForkchoiceStatus,
+};
 use reth_blockchain_tree::{
     BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
 };
@@ -19,7 +21,9 @@ use reth_db::{init_db, DatabaseEnv};
 use reth_interfaces::consensus::Consensus;
 use reth_network::NetworkHandle;
 use reth_network_api::NetworkInfo;
-use reth_node_core::engine_api_store::{EngineApiStore, StoredEngineApiMessage};
+use reth_node_core::engine_api_store::{
+    EngineApiStore, EngineMessageJournal, JournaledEngineResponse, StoredEngineApiMessage,
+};
 #[cfg(not(feature = "optimism"))]
 use reth_node_ethereum::{EthEngineTypes, EthEvmConfig};
 use reth_payload_builder::{PayloadBuilderHandle, PayloadBuilderService};
@@ -36,7 +40,8 @@ use tracing::*;
 
 /// `reth debug replay-engine` command
 /// This script will read stored engine API messages and replay them by the timestamp.
-/// It does not require
+/// The messages are read either from an engine API store, see `--debug.engine-api-store`, or from
+/// an engine message journal, see `--engine.persist-messages`.
 #[derive(Debug, Parser)]
 pub struct Command {
     /// The path to the data dir for all reth files and subdirectories.
@@ -68,12 +73,30 @@ pub struct Command {
     network: NetworkArgs,
 
     /// The path to read engine API messages from.
-    #[arg(long = "engine-api-store", value_name = "PATH")]
-    engine_api_store: PathBuf,
+    #[arg(
+        long = "engine-api-store",
+        value_name = "PATH",
+        required_unless_present = "journal",
+        conflicts_with = "journal"
+    )]
+    engine_api_store: Option<PathBuf>,
+
+    /// The path to read an engine message journal from.
+    ///
+    /// The responses recorded in the journal are compared to the responses of the replayed
+    /// messages. The replay stops if a forkchoice update gets a different status than recorded.
+    #[arg(long = "journal", value_name = "DIR")]
+    journal: Option<PathBuf>,
 
     /// The number of milliseconds between Engine API messages.
     #[arg(long = "interval", default_value_t = 1_000)]
     interval: u64,
+
+    /// Stop before replaying the message at this position, starting at 0.
+    ///
+    /// The position of every replayed message is logged.
+    #[arg(long = "stop-at", value_name = "POSITION")]
+    stop_at: Option<usize>,
 }
 
 impl Command {
@@ -222,22 +245,79 @@ impl Command {
             let _ = tx.send(res);
         });
 
-        let engine_api_store = EngineApiStore::new(self.engine_api_store.clone());
-        for filepath in engine_api_store.engine_messages_iter()? {
-            let contents =
-                fs::read(&filepath).wrap_err(format!("failed to read: {}", filepath.display()))?;
-            let message = serde_json::from_slice(&contents)
-                .wrap_err(format!("failed to parse: {}", filepath.display()))?;
-            debug!(target: "reth::cli", filepath = %filepath.display(), ?message, "Forwarding Engine API message");
+        // the messages in their original order, with the recorded responses if any
+        let mut messages = Vec::new();
+        if let Some(journal) = &self.journal {
+            for message in EngineMessageJournal::read(journal)? {
+                messages.push((message.params, message.response));
+            }
+        } else if let Some(engine_api_store) = &self.engine_api_store {
+            let engine_api_store = EngineApiStore::new(engine_api_store.clone());
+            for filepath in engine_api_store.engine_messages_iter()? {
+                let contents = fs::read(&filepath)
+                    .wrap_err(format!("failed to read: {}", filepath.display()))?;
+                let message = serde_json::from_slice(&contents)
+                    .wrap_err(format!("failed to parse: {}", filepath.display()))?;
+                messages.push((message, None));
+            }
+        }
+
+        for (position, (message, recorded)) in messages.into_iter().enumerate() {
+            if self.stop_at == Some(position) {
+                info!(target: "reth::cli", position, "Stopping before Engine API message");
+                break
+            }
+
+            debug!(target: "reth::cli", position, ?message, "Forwarding Engine API message");
             match message {
                 StoredEngineApiMessage::ForkchoiceUpdated { state, payload_attrs } => {
                     let response =
-                        beacon_engine_handle.fork_choice_updated(state, payload_attrs).await?;
-                    debug!(target: "reth::cli", ?response, "Received for forkchoice updated");
+                        beacon_engine_handle.fork_choice_updated(state, payload_attrs).await;
+                    debug!(target: "reth::cli", position, ?response, "Received for forkchoice");
+                    let replayed = match &response {
+                        Ok(updated) => {
+                            ForkchoiceStatus::from(updated.payload_status.status.clone()).into()
+                        }
+                        Err(err) => JournaledEngineResponse::Error(err.to_string()),
+                    };
+                    match recorded {
+                        // the original node got the same response, so the replay stays on its
+                        // chain even if the forkchoice update failed
+                        Some(recorded) if recorded == replayed => {}
+                        // the following messages would be replayed on a different canonical
+                        // chain than the original node's
+                        Some(recorded) => eyre::bail!(
+                            "replayed forkchoice status {replayed:?} of message {position} \
+                             differs from the journal: {recorded:?}"
+                        ),
+                        None => {
+                            let updated = response?;
+                            if updated.is_invalid() {
+                                warn!(
+                                    target: "reth::cli",
+                                    position,
+                                    ?state,
+                                    status = ?updated.payload_status.status,
+                                    "Replayed forkchoice state is invalid"
+                                );
+                            }
+                        }
+                    }
                 }
                 StoredEngineApiMessage::NewPayload { payload, cancun_fields } => {
                     let response = beacon_engine_handle.new_payload(payload, cancun_fields).await?;
-                    debug!(target: "reth::cli", ?response, "Received for new payload");
+                    debug!(target: "reth::cli", position, ?response, "Received for new payload");
+                    if let Some(JournaledEngineResponse::PayloadStatus(recorded)) = recorded {
+                        if recorded.status != response.status {
+                            warn!(
+                                target: "reth::cli",
+                                position,
+                                ?recorded,
+                                ?response,
+                                "Replayed new payload status differs from the journal"
+                            );
+                        }
+                    }
                 }
             };
 
@@ -0,0 +1,266 @@
+//! Command that exports the canonical chain into files of RLP encoded blocks.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    dirs::{DataDirPath, MaybePlatformPath},
+    version::SHORT_VERSION,
+};
+use alloy_rlp::Encodable;
+use clap::Parser;
+use eyre::Context;
+use flate2::{write::GzEncoder, Compression};
+use reth_db::{database::Database, open_db_read_only};
+use reth_primitives::{BlockNumber, ChainSpec};
+use reth_provider::{BlockNumReader, BlockReader, ProviderFactory};
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, info};
+
+/// The number of blocks that are read from the database at once.
+const BLOCKS_PER_BATCH: u64 = 1_000;
+
+/// Exports the canonical chain to RLP encoded blocks, which can be imported with `reth import`
+/// and `geth import`.
+#[derive(Debug, Parser)]
+pub struct ExportBlocksCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[command(flatten)]
+    db: DatabaseArgs,
+
+    /// The first block to export.
+    #[arg(long, value_name = "BLOCK", default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export.
+    ///
+    /// Defaults to the tip of the database.
+    #[arg(long, value_name = "BLOCK", verbatim_doc_comment)]
+    to: Option<BlockNumber>,
+
+    /// The file the blocks are written to.
+    ///
+    /// The file is compressed with gzip if its name ends with `.gz`.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    output: PathBuf,
+
+    /// The maximum number of blocks per file.
+    ///
+    /// If set, the blocks are split into multiple files and the block range of every file is
+    /// appended to the name of the output file, e.g. `blocks-0-9999.rlp.gz`.
+    #[arg(long, value_name = "BLOCKS", verbatim_doc_comment)]
+    chunk_size: Option<u64>,
+}
+
+impl ExportBlocksCommand {
+    /// Execute `export-blocks` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = open_db_read_only(&db_path, self.db.database_args())?;
+        let provider_factory =
+            ProviderFactory::new(db, self.chain.clone(), data_dir.static_files_path())?;
+
+        let tip = provider_factory.provider()?.best_block_number()?;
+        let to = self.to.unwrap_or(tip);
+        eyre::ensure!(to <= tip, "Block {to} is above the tip {tip} of the database");
+        eyre::ensure!(self.from <= to, "Invalid block range {}..={to}", self.from);
+
+        let chunk_size = self.chunk_size.unwrap_or(u64::MAX).max(1);
+        let mut start = self.from;
+        loop {
+            let end = start.saturating_add(chunk_size - 1).min(to);
+            let path = if self.chunk_size.is_some() {
+                chunk_path(&self.output, start..=end)
+            } else {
+                self.output.clone()
+            };
+
+            info!(target: "reth::cli", path = %path.display(), blocks = ?start..=end, "Exporting blocks");
+            let mut writer = BlockWriter::create(&path)?;
+            export_range(&provider_factory, start..=end, &mut writer)?;
+            writer.finish().wrap_err_with(|| format!("Could not write {}", path.display()))?;
+
+            if end == to {
+                break
+            }
+            start = end + 1;
+        }
+
+        info!(target: "reth::cli", blocks = ?self.from..=to, "Blocks exported");
+        Ok(())
+    }
+}
+
+/// Writes the blocks of the range to the writer, reading them from the database in batches.
+fn export_range<DB: Database>(
+    provider_factory: &ProviderFactory<DB>,
+    range: RangeInclusive<BlockNumber>,
+    writer: &mut BlockWriter,
+) -> eyre::Result<()> {
+    let mut buf = Vec::new();
+    let mut start = *range.start();
+    while start <= *range.end() {
+        let end = start.saturating_add(BLOCKS_PER_BATCH - 1).min(*range.end());
+        // every batch is read in its own transaction, so the read transaction is not kept open for
+        // the whole export
+        let blocks = provider_factory.provider()?.block_range(start..=end)?;
+
+        let mut expected = start;
+        for block in blocks {
+            eyre::ensure!(block.number == expected, "Block {expected} not found");
+            buf.clear();
+            block.encode(&mut buf);
+            writer.write_all(&buf)?;
+            expected += 1;
+        }
+        eyre::ensure!(expected > end, "Block {expected} not found");
+
+        debug!(target: "reth::cli", blocks = ?start..=end, "Exported blocks");
+        start = end + 1;
+    }
+    Ok(())
+}
+
+/// Returns the path of the file of the given block range, e.g. `blocks-0-9999.rlp.gz` for
+/// `blocks.rlp.gz`.
+fn chunk_path(output: &Path, range: RangeInclusive<BlockNumber>) -> PathBuf {
+    let name = output.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+    let (name, gzip) = match name.strip_suffix(".gz") {
+        Some(name) => (name, ".gz"),
+        None => (name, ""),
+    };
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, format!(".{extension}")),
+        _ => (name, String::new()),
+    };
+    output.with_file_name(format!("{stem}-{}-{}{extension}{gzip}", range.start(), range.end()))
+}
+
+/// A buffered writer of a block file, which compresses the blocks with gzip if the name of the
+/// file ends with `.gz`.
+enum BlockWriter {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+}
+
+impl BlockWriter {
+    /// Creates the file, failing if it already exists.
+    fn create(path: &Path) -> eyre::Result<Self> {
+        let file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .wrap_err_with(|| format!("Could not create {}", path.display()))?;
+        let writer = BufWriter::new(file);
+        Ok(if path.extension().is_some_and(|extension| extension == "gz") {
+            Self::Gzip(GzEncoder::new(writer, Compression::default()))
+        } else {
+            Self::Plain(writer)
+        })
+    }
+
+    /// Flushes the blocks to the file, writing the trailer of the gzip stream if compressed.
+    fn finish(self) -> io::Result<()> {
+        let writer = match self {
+            Self::Plain(writer) => writer,
+            Self::Gzip(encoder) => encoder.finish()?,
+        };
+        writer.into_inner().map_err(|err| err.into_error())?.sync_all()
+    }
+}
+
+impl Write for BlockWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::Decodable;
+    use flate2::read::GzDecoder;
+    use reth_primitives::{Block, Header};
+    use std::io::Read;
+
+    #[test]
+    fn chunk_file_names() {
+        assert_eq!(
+            chunk_path(Path::new("out/blocks.rlp.gz"), 0..=9999),
+            PathBuf::from("out/blocks-0-9999.rlp.gz")
+        );
+        assert_eq!(chunk_path(Path::new("blocks.rlp"), 10..=19), PathBuf::from("blocks-10-19.rlp"));
+        assert_eq!(chunk_path(Path::new("blocks"), 10..=19), PathBuf::from("blocks-10-19"));
+    }
+
+    #[test]
+    fn write_compressed_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("blocks.rlp.gz");
+        let blocks = (0..3)
+            .map(|number| Block {
+                header: Header { number, ..Default::default() },
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        let mut writer = BlockWriter::create(&path).unwrap();
+        for block in &blocks {
+            writer.write_all(&alloy_rlp::encode(block)).unwrap();
+        }
+        writer.finish().unwrap();
+        // existing files are not overwritten
+        assert!(BlockWriter::create(&path).is_err());
+
+        let mut decoded = Vec::new();
+        GzDecoder::new(File::open(&path).unwrap()).read_to_end(&mut decoded).unwrap();
+        let mut buf = decoded.as_slice();
+        for block in blocks {
+            assert_eq!(Block::decode(&mut buf).unwrap(), block);
+        }
+        assert!(buf.is_empty());
+    }
+}
@@ -131,20 +131,20 @@ impl ImportCommand {
         let tip = file_client.tip().expect("file client has no tip");
         info!(target: "reth::cli", "Chain file read");
 
-        let (mut pipeline, events) = self
-            .build_import_pipeline(
-                config,
+        let (mut pipeline, events) = build_import_pipeline(
+            self.chain.clone(),
+            config,
+            provider_factory.clone(),
+            &consensus,
+            file_client,
+            StaticFileProducer::new(
                 provider_factory.clone(),
-                &consensus,
-                file_client,
-                StaticFileProducer::new(
-                    provider_factory.clone(),
-                    provider_factory.static_file_provider(),
-                    PruneModes::default(),
-                ),
-                self.disable_execution,
-            )
-            .await?;
+                provider_factory.static_file_provider(),
+                PruneModes::default(),
+            ),
+            self.disable_execution,
+        )
+        .await?;
 
         // override the tip
         pipeline.set_tip(tip);
@@ -172,60 +172,66 @@ impl ImportCommand {
         Ok(())
     }
 
-    async fn build_import_pipeline<DB, C>(
-        &self,
-        config: Config,
-        provider_factory: ProviderFactory<DB>,
-        consensus: &Arc<C>,
-        file_client: Arc<FileClient>,
-        static_file_producer: StaticFileProducer<DB>,
-        disable_execution: bool,
-    ) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)>
-    where
-        DB: Database + Clone + Unpin + 'static,
-        C: Consensus + 'static,
-    {
-        if !file_client.has_canonical_blocks() {
-            eyre::bail!("unable to import non canonical blocks");
-        }
+    /// Loads the reth config
+    fn load_config(&self, config_path: PathBuf) -> eyre::Result<Config> {
+        confy::load_path::<Config>(config_path.clone())
+            .wrap_err_with(|| format!("Could not load config file {config_path:?}"))
+    }
+}
 
-        let mut header_downloader = ReverseHeadersDownloaderBuilder::new(config.stages.headers)
-            .build(file_client.clone(), consensus.clone())
-            .into_task();
-        header_downloader.update_local_head(file_client.tip_header().unwrap());
-        header_downloader.update_sync_target(SyncTarget::Tip(file_client.start().unwrap()));
-
-        let mut body_downloader = BodiesDownloaderBuilder::new(config.stages.bodies)
-            .build(file_client.clone(), consensus.clone(), provider_factory.clone())
-            .into_task();
-        body_downloader
-            .set_download_range(file_client.min_block().unwrap()..=file_client.max_block().unwrap())
-            .expect("failed to set download range");
-
-        let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
-        let factory =
-            reth_revm::EvmProcessorFactory::new(self.chain.clone(), EthEvmConfig::default());
-
-        let max_block = file_client.max_block().unwrap_or(0);
-
-        let mut pipeline = Pipeline::builder()
-            .with_tip_sender(tip_tx)
-            // we want to sync all blocks the file client provides or 0 if empty
-            .with_max_block(max_block)
-            .add_stages(
-                DefaultStages::new(
-                    provider_factory.clone(),
-                    HeaderSyncMode::Tip(tip_rx),
-                    consensus.clone(),
-                    header_downloader,
-                    body_downloader,
-                    factory.clone(),
-                    config.stages.etl,
-                )
-                .set(SenderRecoveryStage {
-                    commit_threshold: config.stages.sender_recovery.commit_threshold,
-                })
-                .set(ExecutionStage::new(
+/// Builds a pipeline that imports the blocks of the file client, replacing the online stages.
+pub(crate) async fn build_import_pipeline<DB, C>(
+    chain: Arc<ChainSpec>,
+    config: Config,
+    provider_factory: ProviderFactory<DB>,
+    consensus: &Arc<C>,
+    file_client: Arc<FileClient>,
+    static_file_producer: StaticFileProducer<DB>,
+    disable_execution: bool,
+) -> eyre::Result<(Pipeline<DB>, impl Stream<Item = NodeEvent>)>
+where
+    DB: Database + Clone + Unpin + 'static,
+    C: Consensus + 'static,
+{
+    if !file_client.has_canonical_blocks() {
+        eyre::bail!("unable to import non canonical blocks");
+    }
+
+    let mut header_downloader = ReverseHeadersDownloaderBuilder::new(config.stages.headers)
+        .build(file_client.clone(), consensus.clone())
+        .into_task();
+    header_downloader.update_local_head(file_client.tip_header().unwrap());
+    header_downloader.update_sync_target(SyncTarget::Tip(file_client.start().unwrap()));
+
+    let mut body_downloader = BodiesDownloaderBuilder::new(config.stages.bodies)
+        .build(file_client.clone(), consensus.clone(), provider_factory.clone())
+        .into_task();
+    body_downloader
+        .set_download_range(file_client.min_block().unwrap()..=file_client.max_block().unwrap())
+        .expect("failed to set download range");
+
+    let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
+    let factory = reth_revm::EvmProcessorFactory::new(chain, EthEvmConfig::default());
+
+    let max_block = file_client.max_block().unwrap_or(0);
+
+    let mut pipeline = Pipeline::builder()
+        .with_tip_sender(tip_tx)
+        // we want to sync all blocks the file client provides or 0 if empty
+        .with_max_block(max_block)
+        .add_stages(
+            DefaultStages::new(
+                provider_factory.clone(),
+                HeaderSyncMode::Tip(tip_rx),
+                consensus.clone(),
+                header_downloader,
+                body_downloader,
+                factory.clone(),
+                config.stages.etl,
+            )
+            .set(SenderRecoveryStage::new_with_config(config.stages.sender_recovery))
+            .set(
+                ExecutionStage::new(
                     factory,
                     ExecutionStageThresholds {
                         max_blocks: config.stages.execution.max_blocks,
@@ -240,21 +246,16 @@ impl ImportCommand {
                         .max(config.stages.account_hashing.clean_threshold)
                         .max(config.stages.storage_hashing.clean_threshold),
                     config.prune.map(|prune| prune.segments).unwrap_or_default(),
-                ))
-                .disable_if(StageId::Execution, || disable_execution),
+                )
+                .with_receipts_verification(config.stages.execution.verify_receipts_from()),
             )
-            .build(provider_factory, static_file_producer);
-
-        let events = pipeline.events().map(Into::into);
+            .disable_if(StageId::Execution, || disable_execution),
+        )
+        .build(provider_factory, static_file_producer);
 
-        Ok((pipeline, events))
-    }
+    let events = pipeline.events().map(Into::into);
 
-    /// Loads the reth config
-    fn load_config(&self, config_path: PathBuf) -> eyre::Result<Config> {
-        confy::load_path::<Config>(config_path.clone())
-            .wrap_err_with(|| format!("Could not load config file {config_path:?}"))
-    }
+    Ok((pipeline, events))
 }
 
 #[cfg(test)]
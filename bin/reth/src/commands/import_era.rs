@@ -0,0 +1,330 @@
+//! Command that imports the chain from a directory of era1 files.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    commands::import::build_import_pipeline,
+    dirs::{DataDirPath, MaybePlatformPath},
+    version::SHORT_VERSION,
+};
+use clap::Parser;
+use eyre::Context;
+use reth_beacon_consensus::BeaconConsensus;
+use reth_config::Config;
+use reth_db::init_db;
+use reth_downloaders::{
+    era1::{era1_files, Era1File, TrustedAccumulators},
+    file_client::FileClient,
+};
+use reth_node_core::init::init_genesis;
+use reth_primitives::{
+    hex, stage::StageId, BlockNumber, Chain, ChainSpec, PruneModes, SealedBlock, B256, U256,
+};
+use reth_provider::{BlockHashReader, HeaderProvider, ProviderFactory, StageCheckpointReader};
+use reth_static_file::StaticFileProducer;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, info};
+
+/// Imports the blocks of a directory of era1 files.
+#[derive(Debug, Parser)]
+pub struct ImportEraCommand {
+    /// The path to the configuration file to use.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    config: Option<PathBuf>,
+
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// Disables the execution stage.
+    ///
+    /// The headers and transactions are still written to the static files, but no state is
+    /// computed.
+    #[arg(long, verbatim_doc_comment)]
+    no_exec: bool,
+
+    /// The number of era1 files that are imported in one pipeline run.
+    #[arg(long, value_name = "FILES", default_value_t = 8)]
+    batch_size: usize,
+
+    /// The path to a file of the trusted accumulator roots of the era1 files.
+    ///
+    /// The file contains one hex encoded root per line, the root of epoch `n` on line `n`. Empty
+    /// lines and lines starting with `#` are ignored. Defaults to the bundled roots of mainnet,
+    /// other chains require this file.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    trusted_accumulators: Option<PathBuf>,
+
+    #[command(flatten)]
+    db: DatabaseArgs,
+
+    /// The path to a directory of era1 files.
+    ///
+    /// The files are imported in the order of their names. Blocks up to the current tip of the
+    /// database are skipped, so an interrupted import can be continued.
+    #[arg(value_name = "IMPORT_PATH", verbatim_doc_comment)]
+    path: PathBuf,
+}
+
+impl ImportEraCommand {
+    /// Execute `import-era` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth {} starting", SHORT_VERSION);
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let config_path = self.config.clone().unwrap_or_else(|| data_dir.config_path());
+
+        let config: Config = confy::load_path(&config_path)
+            .wrap_err_with(|| format!("Could not load config file {config_path:?}"))?;
+        info!(target: "reth::cli", path = ?config_path, "Configuration loaded");
+
+        let db_path = data_dir.db_path();
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = Arc::new(init_db(db_path, self.db.database_args())?);
+        info!(target: "reth::cli", "Database opened");
+        let provider_factory =
+            ProviderFactory::new(db.clone(), self.chain.clone(), data_dir.static_files_path())?;
+
+        debug!(target: "reth::cli", chain=%self.chain.chain, genesis=?self.chain.genesis_hash(), "Initializing genesis");
+        init_genesis(provider_factory.clone())?;
+
+        let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
+
+        let trusted = match &self.trusted_accumulators {
+            Some(path) => TrustedAccumulators::open(path)
+                .wrap_err_with(|| format!("Could not read {}", path.display()))?,
+            None if self.chain.chain == Chain::mainnet() => TrustedAccumulators::mainnet(),
+            None => eyre::bail!(
+                "No trusted accumulator roots of chain {}, use --trusted-accumulators",
+                self.chain.chain
+            ),
+        };
+
+        let files = era1_files(&self.path)
+            .wrap_err_with(|| format!("Could not read directory {}", self.path.display()))?;
+        eyre::ensure!(!files.is_empty(), "No era1 files found in {}", self.path.display());
+
+        // the import continues from the current tip
+        let mut chain = {
+            let provider = provider_factory.provider()?;
+            let tip = provider.get_stage_checkpoint(StageId::Finish)?.unwrap_or_default();
+            let number = tip.block_number;
+            ChainTip {
+                number,
+                hash: provider
+                    .block_hash(number)?
+                    .ok_or_else(|| eyre::eyre!("Hash of tip {number} not found"))?,
+                total_difficulty: provider.header_td_by_number(number)?,
+            }
+        };
+        info!(target: "reth::cli", tip = chain.number, "Importing era1 files");
+
+        for batch in files.chunks(self.batch_size.max(1)) {
+            let mut blocks = Vec::new();
+            for path in batch {
+                let era1 = Era1File::open(path)
+                    .wrap_err_with(|| format!("Invalid era1 file {}", path.display()))?;
+                verify_file_name(path, era1.accumulator)?;
+                trusted
+                    .verify(&era1)
+                    .wrap_err_with(|| format!("Untrusted era1 file {}", path.display()))?;
+
+                let first = blocks.len();
+                for block in era1.blocks {
+                    if let Some(block) = chain.push(block.block, block.total_difficulty)? {
+                        blocks.push(block);
+                    }
+                }
+                debug!(target: "reth::cli", path = %path.display(), blocks = blocks.len() - first, "Read era1 file");
+            }
+            if blocks.is_empty() {
+                continue
+            }
+
+            let file_client = Arc::new(FileClient::from_blocks(blocks));
+            let tip = file_client.tip().expect("file client has no tip");
+            let (mut pipeline, events) = build_import_pipeline(
+                self.chain.clone(),
+                config.clone(),
+                provider_factory.clone(),
+                &consensus,
+                file_client.clone(),
+                StaticFileProducer::new(
+                    provider_factory.clone(),
+                    provider_factory.static_file_provider(),
+                    PruneModes::default(),
+                ),
+                self.no_exec,
+            )
+            .await?;
+            pipeline.set_tip(tip);
+
+            let latest_block_number =
+                provider_factory.provider()?.get_stage_checkpoint(StageId::Finish)?;
+            tokio::spawn(reth_node_core::events::node::handle_events(
+                None,
+                latest_block_number.map(|checkpoint| checkpoint.block_number),
+                events,
+                db.clone(),
+            ));
+
+            info!(target: "reth::cli", blocks = ?file_client.min_block()..=file_client.max_block(), "Starting sync pipeline");
+            pipeline.run().await?;
+        }
+
+        info!(target: "reth::cli", tip = chain.number, "Era1 files imported");
+        Ok(())
+    }
+}
+
+/// The last block of the chain that's imported so far.
+#[derive(Debug)]
+struct ChainTip {
+    number: BlockNumber,
+    hash: B256,
+    /// The total difficulty of the block, if it's known.
+    total_difficulty: Option<U256>,
+}
+
+impl ChainTip {
+    /// Appends a block of an era1 file to the chain.
+    ///
+    /// Returns `None` if the block is not above the tip, in which case the block must be the
+    /// block of the tip if it's the tip, or an error if the block does not extend the chain.
+    fn push(
+        &mut self,
+        block: SealedBlock,
+        total_difficulty: U256,
+    ) -> eyre::Result<Option<SealedBlock>> {
+        let number = block.number;
+        if number < self.number {
+            return Ok(None)
+        }
+        if number == self.number {
+            eyre::ensure!(
+                block.hash() == self.hash,
+                "Block {number} with hash {} does not match the block {} of the database",
+                block.hash(),
+                self.hash
+            );
+            self.total_difficulty = Some(total_difficulty);
+            return Ok(None)
+        }
+
+        eyre::ensure!(
+            number == self.number + 1,
+            "Missing blocks {}..{number} between the era1 files",
+            self.number + 1
+        );
+        eyre::ensure!(
+            block.parent_hash == self.hash,
+            "Parent hash {} of block {number} does not match the hash {} of block {}",
+            block.parent_hash,
+            self.hash,
+            self.number
+        );
+        if let Some(parent_total_difficulty) = self.total_difficulty {
+            eyre::ensure!(
+                parent_total_difficulty + block.difficulty == total_difficulty,
+                "Invalid total difficulty {total_difficulty} of block {number}"
+            );
+        }
+
+        *self = Self { number, hash: block.hash(), total_difficulty: Some(total_difficulty) };
+        Ok(Some(block))
+    }
+}
+
+/// Verifies that the accumulator root matches the short root in the file name, if the name is of
+/// the form `<network>-<epoch>-<root>.era1`.
+fn verify_file_name(path: &Path, accumulator: B256) -> eyre::Result<()> {
+    let Some(short_root) = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.rsplit('-').next())
+        .filter(|short_root| short_root.len() == 8)
+    else {
+        return Ok(())
+    };
+    let expected = hex::encode(&accumulator[..4]);
+    eyre::ensure!(
+        short_root.eq_ignore_ascii_case(&expected),
+        "Accumulator root {accumulator} of {} does not match its file name",
+        path.display()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Header;
+
+    fn block(number: BlockNumber, parent_hash: B256) -> SealedBlock {
+        let header =
+            Header { number, parent_hash, difficulty: U256::from(1), ..Default::default() };
+        SealedBlock { header: header.seal_slow(), ..Default::default() }
+    }
+
+    #[test]
+    fn extend_chain_across_files() {
+        let genesis = block(0, B256::ZERO);
+        let mut chain = ChainTip { number: 0, hash: genesis.hash(), total_difficulty: None };
+
+        // the tip itself is skipped
+        assert_eq!(chain.push(genesis.clone(), U256::from(1)).unwrap(), None);
+
+        let first = block(1, genesis.hash());
+        assert_eq!(chain.push(first.clone(), U256::from(2)).unwrap(), Some(first.clone()));
+
+        // gaps, forks and invalid total difficulties are rejected
+        assert!(chain.push(block(3, first.hash()), U256::from(3)).is_err());
+        assert!(chain.push(block(2, B256::with_last_byte(1)), U256::from(3)).is_err());
+        assert!(chain.push(block(2, first.hash()), U256::from(4)).is_err());
+        assert!(chain.push(block(2, first.hash()), U256::from(3)).unwrap().is_some());
+    }
+
+    #[test]
+    fn verify_short_root_of_file_name() {
+        let accumulator = B256::with_last_byte(1);
+        let mut root = accumulator;
+        root[..4].copy_from_slice(&[0x5e, 0xc1, 0xff, 0xb8]);
+
+        assert!(verify_file_name(Path::new("mainnet-00000-5ec1ffb8.era1"), root).is_ok());
+        assert!(verify_file_name(Path::new("mainnet-00000-5ec1ffb8.era1"), accumulator).is_err());
+        assert!(verify_file_name(Path::new("blocks.era1"), accumulator).is_ok());
+    }
+
+    #[test]
+    fn parse_import_era_command() {
+        let args = ImportEraCommand::parse_from(["reth", "--no-exec", "era"]);
+        assert!(args.no_exec);
+        assert_eq!(args.batch_size, 8);
+        assert_eq!(args.path, PathBuf::from("era"));
+        assert_eq!(args.trusted_accumulators, None);
+    }
+}
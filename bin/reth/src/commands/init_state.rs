@@ -0,0 +1,141 @@
+//! Command that initializes the node from a state dump at a block.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use alloy_rlp::Decodable;
+use clap::Parser;
+use eyre::Context;
+use reth_config::config::EtlConfig;
+use reth_db::init_db;
+use reth_node_core::init::init_from_state_dump;
+use reth_primitives::{hex, ChainSpec, Header, SealedHeader};
+use reth_provider::ProviderFactory;
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::info;
+
+/// Initializes the database with the state of a state dump at a block.
+///
+/// The blocks up to the block of the state dump must be imported first without executing them,
+/// with `reth import --disable-execution`.
+#[derive(Debug, Parser)]
+pub struct InitStateCommand {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[command(flatten)]
+    db: DatabaseArgs,
+
+    /// The JSONL state dump, with one account per line.
+    ///
+    /// Every line is an object with the `address`, `balance`, `nonce`, `code` and `storage` of an
+    /// account, like the output of `geth dump --iterative`.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    state: PathBuf,
+
+    /// The file with the RLP encoded header of the block of the state dump, either as raw bytes
+    /// or hex encoded.
+    ///
+    /// The header must be the header of the tip of the database.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    header: PathBuf,
+
+    /// The maximum size in bytes of data held in memory before being flushed to disk as a file.
+    #[arg(long)]
+    etl_file_size: Option<usize>,
+
+    /// Directory where to collect ETL files
+    #[arg(long)]
+    etl_dir: Option<PathBuf>,
+}
+
+impl InitStateCommand {
+    /// Execute the `init-state` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "reth init-state starting");
+
+        let header = read_header(&self.header)?;
+
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let etl_config = EtlConfig::new(
+            Some(
+                self.etl_dir.unwrap_or_else(|| EtlConfig::from_datadir(&data_dir.data_dir_path())),
+            ),
+            self.etl_file_size.unwrap_or(EtlConfig::default_file_size()),
+        );
+        let db_path = data_dir.db_path();
+        info!(target: "reth::cli", path = ?db_path, "Opening database");
+        let db = Arc::new(init_db(&db_path, self.db.database_args())?);
+        info!(target: "reth::cli", "Database opened");
+
+        let provider_factory = ProviderFactory::new(db, self.chain, data_dir.static_files_path())?;
+
+        info!(target: "reth::cli", block = header.number, hash = ?header.hash(), "Writing state dump");
+
+        let file = File::open(&self.state)
+            .wrap_err_with(|| format!("Could not open {}", self.state.display()))?;
+        let hash =
+            init_from_state_dump(BufReader::new(file), provider_factory, header, etl_config)?;
+
+        info!(target: "reth::cli", hash = ?hash, "State dump written");
+        Ok(())
+    }
+}
+
+/// Reads the RLP encoded header of the file, which is either raw or hex encoded.
+fn read_header(path: &Path) -> eyre::Result<SealedHeader> {
+    let bytes = fs::read(path).wrap_err_with(|| format!("Could not read {}", path.display()))?;
+    let decoded = std::str::from_utf8(&bytes).ok().and_then(|text| hex::decode(text.trim()).ok());
+    let header = Header::decode(&mut decoded.as_deref().unwrap_or(&bytes))
+        .wrap_err_with(|| format!("Invalid header in {}", path.display()))?;
+    Ok(header.seal_slow())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_raw_and_hex_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let header = Header { number: 100, ..Default::default() };
+        let rlp = alloy_rlp::encode(&header);
+
+        let raw = dir.path().join("header.rlp");
+        fs::write(&raw, &rlp).unwrap();
+        assert_eq!(read_header(&raw).unwrap(), header.clone().seal_slow());
+
+        let encoded = dir.path().join("header.hex");
+        fs::write(&encoded, format!("0x{}\n", hex::encode(&rlp))).unwrap();
+        assert_eq!(read_header(&encoded).unwrap(), header.seal_slow());
+    }
+}
@@ -4,12 +4,17 @@ pub mod config_cmd;
 pub mod db;
 pub mod debug_cmd;
 pub mod dump_genesis;
+pub mod export_blocks;
 pub mod import;
+pub mod import_era;
 
 pub mod init_cmd;
+pub mod init_state;
 
 pub mod node;
 pub mod p2p;
+pub mod prune;
 pub mod recover;
 pub mod stage;
+pub mod static_file;
 pub mod test_vectors;
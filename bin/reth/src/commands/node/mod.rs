@@ -4,7 +4,7 @@ use crate::{
     args::{
         utils::{chain_help, genesis_value_parser, parse_socket_address, SUPPORTED_CHAINS},
         DatabaseArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs, PruningArgs,
-        RpcServerArgs, TxPoolArgs,
+        RpcServerArgs, ShutdownArgs, StageArgs, StaticFilesArgs, TreeArgs, TxPoolArgs,
     },
     core::cli::runner::CliContext,
     dirs::{DataDirPath, MaybePlatformPath},
@@ -108,6 +108,25 @@ pub struct NodeCommand<Ext: clap::Args + fmt::Debug = NoArgs> {
     #[command(flatten)]
     pub pruning: PruningArgs,
 
+    /// All static files related arguments
+    #[command(flatten)]
+    pub static_files: StaticFilesArgs,
+
+    /// All stage related arguments with --stage prefix
+    #[command(flatten)]
+    pub stages: StageArgs,
+
+    /// All blockchain tree related arguments with --tree prefix
+    #[command(flatten)]
+    pub tree: TreeArgs,
+
+    /// All shutdown related arguments with --shutdown prefix
+    ///
+    /// These are applied by the [CliRunner](crate::core::cli::runner::CliRunner) that runs the
+    /// command.
+    #[command(flatten)]
+    pub shutdown: ShutdownArgs,
+
     /// Additional cli arguments
     #[command(flatten, next_help_heading = "Extension")]
     pub ext: Ext,
@@ -156,6 +175,10 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            static_files,
+            stages,
+            tree,
+            shutdown: _,
             ext,
         } = self;
 
@@ -173,6 +196,9 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            static_files,
+            stages,
+            tree,
         };
 
         // Register the prometheus recorder before creating the database,
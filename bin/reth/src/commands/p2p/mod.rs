@@ -3,21 +3,27 @@
 use crate::{
     args::{
         get_secret_key,
-        utils::{chain_help, chain_spec_value_parser, hash_or_num_value_parser, SUPPORTED_CHAINS},
+        utils::{
+            chain_help, chain_spec_value_parser, hash_or_num_value_parser,
+            parse_duration_from_secs_or_human, SUPPORTED_CHAINS,
+        },
         DatabaseArgs, DiscoveryArgs,
     },
-    dirs::{DataDirPath, MaybePlatformPath},
-    utils::get_single_header,
+    dirs::{ChainPath, DataDirPath, MaybePlatformPath},
 };
-use backon::{ConstantBuilder, Retryable};
 use clap::{Parser, Subcommand};
+use eyre::Context;
+use requests::PeerRequester;
 use reth_config::Config;
-use reth_db::create_db;
+use reth_db::{create_db, database::Database, open_db_read_only, DatabaseEnv};
 use reth_discv4::NatResolver;
-use reth_interfaces::p2p::bodies::client::BodiesClient;
-use reth_primitives::{BlockHashOrNumber, ChainSpec, NodeRecord};
-use reth_provider::ProviderFactory;
-use std::{path::PathBuf, sync::Arc};
+use reth_primitives::{
+    BlockHashOrNumber, BlockNumber, ChainSpec, NodeRecord, PeerId, SealedHeader,
+};
+use reth_provider::{BlockNumReader, HeaderProvider, ProviderFactory};
+use std::{fs, path::PathBuf, sync::Arc, time::Duration};
+
+mod requests;
 
 /// `reth p2p` command
 #[derive(Debug, Parser)]
@@ -66,6 +72,22 @@ pub struct Command {
     #[arg(long)]
     trusted_only: bool,
 
+    /// Only request the data from the peer with this id.
+    ///
+    /// By default, any connected peer is requested, and a failed request is retried with another
+    /// peer. The peer can be connected to with `--trusted-peer`.
+    #[arg(long, value_name = "PEER_ID", verbatim_doc_comment)]
+    peer: Option<PeerId>,
+
+    /// The timeout of a single request, in seconds or a human readable duration.
+    #[arg(
+        long,
+        value_name = "DURATION",
+        default_value = "10s",
+        value_parser = parse_duration_from_secs_or_human
+    )]
+    timeout: Duration,
+
     /// The number of retries per request
     #[arg(long, default_value = "5")]
     retries: usize,
@@ -95,6 +117,33 @@ pub enum Subcommands {
         #[arg(value_parser = hash_or_num_value_parser)]
         id: BlockHashOrNumber,
     },
+    /// Download the bodies of a range of blocks to files
+    ///
+    /// The bodies are validated against the headers of the database, so the blocks must be synced
+    /// first.
+    Bodies {
+        /// The first block of the range
+        #[arg(long, value_name = "BLOCK_NUMBER")]
+        from: BlockNumber,
+        /// The last block of the range, inclusive
+        #[arg(long, value_name = "BLOCK_NUMBER")]
+        to: BlockNumber,
+        /// The directory the RLP encoded bodies are written to, one `<number>.rlp` file per block
+        #[arg(long, value_name = "DIR")]
+        output: PathBuf,
+    },
+    /// Download the receipts of a block
+    ///
+    /// The receipts are validated against the header of the database, so the block must be synced
+    /// first.
+    Receipts {
+        /// The block number or hash
+        #[arg(value_parser = hash_or_num_value_parser)]
+        id: BlockHashOrNumber,
+        /// The directory the RLP encoded receipts are written to, as `<number>.receipts.rlp`
+        #[arg(long, value_name = "DIR")]
+        output: Option<PathBuf>,
+    },
 }
 impl Command {
     /// Execute `p2p` command
@@ -136,52 +185,152 @@ impl Command {
             .start_network()
             .await?;
 
-        let fetch_client = network.fetch_client().await?;
-        let retries = self.retries.max(1);
-        let backoff = ConstantBuilder::default().with_max_times(retries);
+        let mut requester =
+            PeerRequester::new(network, self.peer, self.timeout, self.retries.max(1));
 
-        match self.command {
+        match &self.command {
             Subcommands::Header { id } => {
-                let header = (move || get_single_header(fetch_client.clone(), id))
-                    .retry(&backoff)
-                    .notify(|err, _| println!("Error requesting header: {err}. Retrying..."))
-                    .await?;
+                let header = requester.header(*id).await?;
                 println!("Successfully downloaded header: {header:?}");
             }
             Subcommands::Body { id } => {
-                let hash = match id {
-                    BlockHashOrNumber::Hash(hash) => hash,
-                    BlockHashOrNumber::Number(number) => {
-                        println!("Block number provided. Downloading header first...");
-                        let client = fetch_client.clone();
-                        let header = (move || {
-                            get_single_header(client.clone(), BlockHashOrNumber::Number(number))
-                        })
-                        .retry(&backoff)
-                        .notify(|err, _| println!("Error requesting header: {err}. Retrying..."))
-                        .await?;
-                        header.hash()
+                let header = requester.header(*id).await?;
+                let mut block = None;
+                requester
+                    .bodies(&[header], |_, body, peer_id| {
+                        block = Some((body.clone(), peer_id));
+                        Ok(())
+                    })
+                    .await?;
+                let (body, peer_id) = block.expect("body was downloaded");
+                println!("Successfully downloaded body from peer {peer_id}: {body:?}")
+            }
+            Subcommands::Bodies { from, to, output } => {
+                if from > to {
+                    eyre::bail!("Invalid block range {from}..={to}")
+                }
+                let headers = local_headers(&self.provider_factory(&data_dir)?, *from, *to)?;
+                fs::create_dir_all(output)
+                    .wrap_err_with(|| format!("Could not create {}", output.display()))?;
+
+                println("Downloading bodies {from}..={to}...");
+                requester
+                    .bodies(&headers, |header, body, peer_id| {
+                        let path = output.join(format!("{}.rlp", header.number));
+                        fs::write(&path, alloy_rlp::encode(body))
+                            .wrap_err_with(|| format!("Could not write {}", path.display()))?;
+                        println!("Downloaded body of block {} from peer {peer_id}", header.number);
+                        Ok(())
+                    })
+                    .await?;
+                println!(
+                    "Successfully downloaded {} bodies to {}",
+                    headers.len(),
+                    output.display()
+                );
+            }
+            Subcommands::Receipts { id, output } => {
+                let header = local_header(&self.provider_factory(&data_dir)?, *id)?;
+                let (peer_id, receipts) = requester.receipts(&header, &self.chain).await?;
+                match output {
+                    Some(output) => {
+                        fs::create_dir_all(output)
+                            .wrap_err_with(|| format!("Could not create {}", output.display()))?;
+                        let path = output.join(format!("{}.receipts.rlp", header.number));
+                        fs::write(&path, alloy_rlp::encode(&receipts))
+                            .wrap_err_with(|| format!("Could not write {}", path.display()))?;
+                        println!(
+                            "Successfully downloaded {} receipts of block {} from peer {peer_id}",
+                            receipts.len(),
+                            header.number
+                        );
                     }
-                };
-                let (_, result) = (move || {
-                    let client = fetch_client.clone();
-                    client.get_block_bodies(vec![hash])
-                })
-                .retry(&backoff)
-                .notify(|err, _| println!("Error requesting block: {err}. Retrying..."))
-                .await?
-                .split();
-                if result.len() != 1 {
-                    eyre::bail!(
-                        "Invalid number of headers received. Expected: 1. Received: {}",
-                        result.len()
-                    )
+                    None => println!(
+                        "Successfully downloaded receipts of block {} from peer {peer_id}: \
+                         {receipts:?}",
+                        header.number
+                    ),
                 }
-                let body = result.into_iter().next().unwrap();
-                println!("Successfully downloaded body: {body:?}")
             }
         }
 
         Ok(())
     }
+
+    /// Opens the database of the node read-only, which the headers of the downloaded blocks are
+    /// read from.
+    fn provider_factory(
+        &self,
+        data_dir: &ChainPath<DataDirPath>,
+    ) -> eyre::Result<ProviderFactory<DatabaseEnv>> {
+        let db_path = data_dir.db_path();
+        let db = open_db_read_only(&db_path, self.db.database_args())
+            .wrap_err_with(|| format!("Could not open the database {}", db_path.display()))?;
+        Ok(ProviderFactory::new(db, self.chain.clone(), data_dir.static_files_path())?)
+    }
+}
+
+/// Reads the headers of the inclusive range of blocks from the database.
+///
+/// The downloaded data is validated against these headers, so they're never taken from peers.
+fn local_headers<DB: Database>(
+    factory: &ProviderFactory<DB>,
+    from: BlockNumber,
+    to: BlockNumber,
+) -> eyre::Result<Vec<SealedHeader>> {
+    let headers = factory.sealed_headers_range(from..=to)?;
+    eyre::ensure!(
+        headers.len() as u64 == to - from + 1,
+        "Headers {from}..={to} are not in the database, the blocks must be synced first"
+    );
+    Ok(headers)
+}
+
+/// Reads the header of the given block from the database, see [local_headers].
+fn local_header<DB: Database>(
+    factory: &ProviderFactory<DB>,
+    id: BlockHashOrNumber,
+) -> eyre::Result<SealedHeader> {
+    let number = match id {
+        BlockHashOrNumber::Hash(hash) => factory
+            .block_number(hash)?
+            .ok_or_else(|| eyre::eyre!("Block {hash} is not in the database"))?,
+        BlockHashOrNumber::Number(number) => number,
+    };
+    factory.sealed_header(number)?.ok_or_else(|| {
+        eyre::eyre!("Header {number} is not in the database, the block must be synced first")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Header, StaticFileSegment, MAINNET, U256};
+    use reth_provider::providers::StaticFileWriter;
+
+    #[test]
+    fn read_local_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = create_db(dir.path().join("db"), Default::default()).unwrap();
+        let factory =
+            ProviderFactory::new(db, MAINNET.clone(), dir.path().join("static_files")).unwrap();
+        let headers = (0..3)
+            .map(|number| Header { number, ..Default::default() }.seal_slow())
+            .collect::<Vec<_>>();
+        {
+            let static_file_provider = factory.static_file_provider();
+            let mut writer =
+                static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+            for header in &headers {
+                writer.append_header(header.header().clone(), U256::ZERO, header.hash()).unwrap();
+            }
+            drop(writer);
+            static_file_provider.commit().unwrap();
+        }
+
+        assert_eq!(local_headers(&factory, 1, 2).unwrap(), headers[1..]);
+        assert!(local_headers(&factory, 2, 3).is_err());
+        assert_eq!(local_header(&factory, BlockHashOrNumber::Number(2)).unwrap(), headers[2]);
+        assert!(local_header(&factory, BlockHashOrNumber::Number(3)).is_err());
+    }
 }
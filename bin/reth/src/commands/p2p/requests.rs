@@ -0,0 +1,277 @@
+//! Requests sent directly to the sessions of connected peers.
+
+use reth_eth_wire::{
+    BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders, GetReceipts, Receipts,
+};
+use reth_interfaces::p2p::{error::RequestResult, full_block::ensure_valid_body_response};
+use reth_network::{message::PeerRequest, NetworkHandle};
+use reth_network_api::{Peers, ReputationChangeKind};
+use reth_primitives::{
+    BlockBody, BlockHashOrNumber, ChainSpec, HeadersDirection, PeerId, ReceiptWithBloom,
+    SealedHeader, B256,
+};
+use std::{collections::HashSet, time::Duration};
+use tokio::sync::oneshot;
+
+/// The maximum number of bodies requested at once.
+const BODIES_PER_REQUEST: usize = 128;
+
+/// How often the connected peers are checked while waiting for a peer to request.
+const PEER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Sends requests to the connected peers and validates their responses.
+///
+/// Unlike the [FetchClient](reth_network::FetchClient), the requests are sent to the session of a
+/// known peer, so the peer that served invalid data can be reported. If the peer is not selected,
+/// a failed request is retried with another connected peer.
+#[derive(Debug)]
+pub(crate) struct PeerRequester {
+    network: NetworkHandle,
+    /// The only peer that's requested, if selected.
+    peer: Option<PeerId>,
+    /// The timeout of a single request.
+    timeout: Duration,
+    /// The number of retries of a failed request.
+    retries: usize,
+    /// The peers that failed a request, which are not requested again unless selected.
+    failed_peers: HashSet<PeerId>,
+}
+
+impl PeerRequester {
+    /// Creates a new requester of the given peer, or of any connected peer.
+    pub(crate) fn new(
+        network: NetworkHandle,
+        peer: Option<PeerId>,
+        timeout: Duration,
+        retries: usize,
+    ) -> Self {
+        Self { network, peer, timeout, retries, failed_peers: HashSet::new() }
+    }
+
+    /// Downloads the header of the given block.
+    pub(crate) async fn header(&mut self, id: BlockHashOrNumber) -> eyre::Result<SealedHeader> {
+        let request = GetBlockHeaders {
+            start_block: id,
+            limit: 1,
+            skip: 0,
+            direction: HeadersDirection::Rising,
+        };
+        let (_, header) = self
+            .request(
+                "header",
+                |response| PeerRequest::GetBlockHeaders { request, response },
+                |BlockHeaders(headers)| {
+                    let [header] = <[_; 1]>::try_from(headers).map_err(|headers| {
+                        format!("expected 1 header, received {}", headers.len())
+                    })?;
+                    let header = header.seal_slow();
+                    let valid = match id {
+                        BlockHashOrNumber::Hash(hash) => header.hash() == hash,
+                        BlockHashOrNumber::Number(number) => header.number == number,
+                    };
+                    if !valid {
+                        return Err(format!("received header {:?}", header.num_hash()))
+                    }
+                    Ok(header)
+                },
+            )
+            .await?;
+        Ok(header)
+    }
+
+    /// Downloads the bodies of the given headers, in the same order.
+    ///
+    /// Every body is validated against its header, and `on_body` is called with the peer that
+    /// served it. The headers must be trusted, like the headers of the database of the node.
+    pub(crate) async fn bodies(
+        &mut self,
+        headers: &[SealedHeader],
+        mut on_body: impl FnMut(&SealedHeader, &BlockBody, PeerId) -> eyre::Result<()>,
+    ) -> eyre::Result<()> {
+        let mut remaining = headers;
+        while !remaining.is_empty() {
+            let batch = &remaining[..remaining.len().min(BODIES_PER_REQUEST)];
+            let request = GetBlockBodies(batch.iter().map(|header| header.hash()).collect());
+            let (peer_id, bodies) = self
+                .request(
+                    "bodies",
+                    |response| PeerRequest::GetBlockBodies { request: request.clone(), response },
+                    |BlockBodies(bodies)| validate_bodies(batch, &bodies).map(|_| bodies),
+                )
+                .await?;
+            for (header, body) in batch.iter().zip(&bodies) {
+                on_body(header, body, peer_id)?;
+            }
+            remaining = &remaining[bodies.len()..];
+        }
+        Ok(())
+    }
+
+    /// Downloads the receipts of the block of the given header, validated against its receipts
+    /// root. The header must be trusted, like the headers of the database of the node.
+    pub(crate) async fn receipts(
+        &mut self,
+        header: &SealedHeader,
+        chain_spec: &ChainSpec,
+    ) -> eyre::Result<(PeerId, Vec<ReceiptWithBloom>)> {
+        let request = GetReceipts(vec![header.hash()]);
+        self.request(
+            "receipts",
+            |response| PeerRequest::GetReceipts { request: request.clone(), response },
+            |Receipts(receipts)| {
+                let [receipts] = <[_; 1]>::try_from(receipts).map_err(|receipts| {
+                    format!("expected the receipts of 1 block, received {}", receipts.len())
+                })?;
+                validate_receipts(header, &receipts, chain_spec).map(|_| receipts)
+            },
+        )
+        .await
+    }
+
+    /// Sends the request to a connected peer and validates the response with `validate`.
+    ///
+    /// If the request fails or the response is invalid, the request is retried with another
+    /// connected peer, unless a peer is selected.
+    async fn request<R, T>(
+        &mut self,
+        kind: &str,
+        request: impl Fn(oneshot::Sender<RequestResult<R>>) -> PeerRequest,
+        mut validate: impl FnMut(R) -> Result<T, String>,
+    ) -> eyre::Result<(PeerId, T)> {
+        for attempt in 0..=self.retries {
+            if attempt > 0 {
+                println!("Retrying {kind} request ({attempt}/{})...", self.retries);
+            }
+
+            let peer_id = self.connected_peer().await?;
+            let (tx, rx) = oneshot::channel();
+            self.network.send_request(peer_id, request(tx));
+            let response = match tokio::time::timeout(self.timeout, rx).await {
+                Ok(Ok(Ok(response))) => response,
+                Ok(Ok(Err(err))) => {
+                    println!("Error requesting {kind} from peer {peer_id}: {err}");
+                    self.failed_peers.insert(peer_id);
+                    continue
+                }
+                Ok(Err(_)) => {
+                    println!("Error requesting {kind} from peer {peer_id}: session closed");
+                    self.failed_peers.insert(peer_id);
+                    continue
+                }
+                Err(_) => {
+                    println!("Request of {kind} from peer {peer_id} timed out");
+                    self.failed_peers.insert(peer_id);
+                    continue
+                }
+            };
+
+            match validate(response) {
+                Ok(response) => return Ok((peer_id, response)),
+                Err(err) => {
+                    println!("Peer {peer_id} served invalid {kind}: {err}");
+                    self.network.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+                    self.failed_peers.insert(peer_id);
+                }
+            }
+        }
+        eyre::bail!("Failed to download {kind} after {} retries", self.retries)
+    }
+
+    /// Waits until a peer to request is connected, which is the selected peer if any, or else a
+    /// connected peer that didn't fail a request yet.
+    async fn connected_peer(&mut self) -> eyre::Result<PeerId> {
+        let mut waiting = false;
+        loop {
+            let peers = self.network.get_all_peers().await?;
+            let mut candidates = peers.iter().map(|peer| peer.remote_id).filter(|peer_id| {
+                self.peer.map_or(!self.failed_peers.contains(peer_id), |peer| peer == *peer_id)
+            });
+            if let Some(peer_id) = candidates.next() {
+                return Ok(peer_id)
+            }
+            if self.peer.is_none() && !peers.is_empty() && !self.failed_peers.is_empty() {
+                // every connected peer failed a request, give them another chance
+                self.failed_peers.clear();
+                continue
+            }
+
+            if !waiting {
+                match self.peer {
+                    Some(peer) => println!("Waiting for peer {peer} to connect..."),
+                    None => println!("Waiting for a peer to connect..."),
+                }
+                waiting = true;
+            }
+            tokio::time::sleep(PEER_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Validates the bodies of a response against the headers of the request, in the same order.
+///
+/// A response may contain fewer bodies than requested.
+fn validate_bodies(headers: &[SealedHeader], bodies: &[BlockBody]) -> Result<(), String> {
+    if bodies.is_empty() || bodies.len() > headers.len() {
+        return Err(format!("expected up to {} bodies, received {}", headers.len(), bodies.len()))
+    }
+    for (header, body) in headers.iter().zip(bodies) {
+        ensure_valid_body_response(header, body)
+            .map_err(|err| format!("invalid body of block {}: {err}", header.number))?;
+    }
+    Ok(())
+}
+
+/// Validates the receipts of a block against the receipts root of its header.
+fn validate_receipts(
+    header: &SealedHeader,
+    receipts: &[ReceiptWithBloom],
+    chain_spec: &ChainSpec,
+) -> Result<(), String> {
+    let root = receipts_root(receipts, chain_spec, header.timestamp);
+    if root != header.receipts_root {
+        return Err(format!(
+            "receipts root {root} does not match the receipts root {} of block {}",
+            header.receipts_root, header.number
+        ))
+    }
+    Ok(())
+}
+
+/// Computes the receipts root of the receipts of a block.
+#[cfg_attr(not(feature = "optimism"), allow(unused_variables))]
+fn receipts_root(receipts: &[ReceiptWithBloom], chain_spec: &ChainSpec, timestamp: u64) -> B256 {
+    #[cfg(feature = "optimism")]
+    {
+        reth_primitives::proofs::calculate_receipt_root_optimism(receipts, chain_spec, timestamp)
+    }
+    #[cfg(not(feature = "optimism"))]
+    {
+        reth_primitives::proofs::calculate_receipt_root(receipts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Header, MAINNET};
+
+    #[test]
+    fn validate_bodies_against_headers() {
+        let headers = [Header::default().seal_slow(), Header::default().seal_slow()];
+        assert!(validate_bodies(&headers, &[BlockBody::default()]).is_ok());
+        assert!(validate_bodies(&headers, &[]).is_err());
+        assert!(
+            validate_bodies(&headers[..1], &[BlockBody::default(), BlockBody::default()]).is_err()
+        );
+
+        let body = BlockBody { ommers: vec![Header::default()], ..Default::default() };
+        assert!(validate_bodies(&headers, &[BlockBody::default(), body]).is_err());
+    }
+
+    #[test]
+    fn validate_receipts_against_header() {
+        let header = Header::default().seal_slow();
+        assert!(validate_receipts(&header, &[], &MAINNET).is_ok());
+        assert!(validate_receipts(&header, &[ReceiptWithBloom::default()], &MAINNET).is_err());
+    }
+}
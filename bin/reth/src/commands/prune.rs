@@ -0,0 +1,344 @@
+//! Command that runs the pruner on a stopped node.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::{builder::RangedU64ValueParser, Parser};
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use eyre::Context;
+use futures::StreamExt;
+use reth_config::{Config, PruneConfig};
+use reth_db::{database::Database, open_db};
+use reth_primitives::{BlockNumber, ChainSpec, PruneMode, PruneModes, PrunePurpose, PruneSegment};
+use reth_provider::{BlockNumReader, ProviderFactory, PruneCheckpointReader};
+use reth_prune::{segments::SegmentSet, PrunerBuilder, PrunerEvent};
+use std::{collections::BTreeMap, path::PathBuf, sync::Arc, time::Instant};
+use tracing::info;
+
+/// The default maximum number of entries deleted before the changes are committed.
+const DEFAULT_BATCH_SIZE: usize = 100_000;
+
+/// Prunes the database according to the prune configuration, without running the node.
+#[derive(Debug, Parser)]
+pub struct PruneCommand {
+    /// The path to the configuration file to use.
+    #[arg(long, value_name = "FILE", verbatim_doc_comment)]
+    config: Option<PathBuf>,
+
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[command(flatten)]
+    db: DatabaseArgs,
+
+    /// Only prune these segments
+    #[arg(long = "segment", value_enum, value_delimiter = ',')]
+    segments: Vec<PruneSegment>,
+
+    /// The maximum number of entries to delete before committing the changes.
+    #[arg(
+        long = "prune.batch-size",
+        value_name = "ENTRIES",
+        default_value_t = DEFAULT_BATCH_SIZE,
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    batch_size: usize,
+
+    /// Only print the blocks every segment would be pruned up to, without pruning anything.
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Overrides the prune mode of the sender recovery segment.
+    ///
+    /// Possible values are `full`, `distance:<BLOCKS>` and `before:<BLOCK>`.
+    #[arg(long = "prune.sender-recovery", value_name = "MODE", value_parser = parse_prune_mode)]
+    sender_recovery: Option<PruneMode>,
+
+    /// Overrides the prune mode of the transaction lookup segment.
+    #[arg(long = "prune.transaction-lookup", value_name = "MODE", value_parser = parse_prune_mode)]
+    transaction_lookup: Option<PruneMode>,
+
+    /// Overrides the prune mode of the receipts segment.
+    #[arg(long = "prune.receipts", value_name = "MODE", value_parser = parse_prune_mode)]
+    receipts: Option<PruneMode>,
+
+    /// Overrides the prune mode of the account history segment.
+    #[arg(long = "prune.account-history", value_name = "MODE", value_parser = parse_prune_mode)]
+    account_history: Option<PruneMode>,
+
+    /// Overrides the prune mode of the storage history segment.
+    #[arg(long = "prune.storage-history", value_name = "MODE", value_parser = parse_prune_mode)]
+    storage_history: Option<PruneMode>,
+
+    /// Overrides the prune mode of the call trace index segment.
+    #[arg(long = "prune.call-traces", value_name = "MODE", value_parser = parse_prune_mode)]
+    call_traces: Option<PruneMode>,
+}
+
+impl PruneCommand {
+    /// Execute `prune` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        // add network name to data dir
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let config_path = self.config.clone().unwrap_or_else(|| data_dir.config_path());
+
+        let config: Config = confy::load_path(config_path.clone())
+            .wrap_err_with(|| format!("Could not load config file {config_path:?}"))?;
+        let prune_config = self.prune_config(config.prune.unwrap_or_default());
+        info!(target: "reth::cli", path = ?config_path, ?prune_config, "Configuration loaded");
+        prune_config.segments.validate().wrap_err("Invalid prune configuration")?;
+
+        // The database can only be opened exclusively if no other process uses it
+        let db_path = data_dir.db_path();
+        let db = open_db(&db_path, self.db.database_args().with_exclusive(Some(true)))
+            .wrap_err_with(|| {
+                format!("Could not open database at {db_path:?}, make sure the node isn't running")
+            })?;
+        let provider_factory =
+            ProviderFactory::new(db, self.chain.clone(), data_dir.static_files_path())?;
+
+        let tip = provider_factory.best_block_number()?;
+        if self.dry_run {
+            println!("{}", retained_blocks_table(&provider_factory, &prune_config.segments, tip)?);
+            return Ok(())
+        }
+
+        // Nothing else uses the database, so there's no need to spread the deletions over time
+        let builder = PrunerBuilder {
+            prune_delete_limit: self.batch_size,
+            timeout: None,
+            max_entries_per_second: None,
+            ..PrunerBuilder::new(prune_config)
+        };
+
+        info!(target: "reth::cli", tip, "Pruning");
+        let start = Instant::now();
+        let mut deleted = BTreeMap::<PruneSegment, usize>::new();
+        loop {
+            // The delete limit is scaled by the number of blocks since the previous run of the
+            // pruner, so every batch is pruned by a new one.
+            let mut pruner = builder.clone().build(provider_factory.clone());
+            if !self.segments.is_empty() {
+                pruner = pruner.with_only_segments(self.segments.clone());
+            }
+            let mut events = pruner.events();
+
+            let progress = pruner.run(tip)?;
+            drop(pruner);
+
+            while let Some(event) = events.next().await {
+                if let PrunerEvent::Finished { stats, .. } = event {
+                    for (segment, (_, pruned)) in stats {
+                        *deleted.entry(segment).or_default() += pruned;
+                    }
+                }
+            }
+            info!(target: "reth::cli", ?progress, deleted = deleted.values().sum::<usize>(), "Committed pruned entries");
+
+            if progress.is_finished() {
+                break
+            }
+        }
+
+        println!("{}", deleted_table(&deleted));
+        println!("Pruned up to block {tip} in {:?}", start.elapsed());
+
+        Ok(())
+    }
+
+    /// Returns the prune configuration with the prune modes overridden by the arguments, and
+    /// without the segments that weren't selected.
+    fn prune_config(&self, mut config: PruneConfig) -> PruneConfig {
+        let PruneModes {
+            sender_recovery,
+            transaction_lookup,
+            receipts,
+            account_history,
+            storage_history,
+            call_traces,
+            receipts_log_filter,
+        } = &mut config.segments;
+
+        for (segment, mode, mode_override) in [
+            (PruneSegment::SenderRecovery, sender_recovery, self.sender_recovery),
+            (PruneSegment::TransactionLookup, transaction_lookup, self.transaction_lookup),
+            (PruneSegment::Receipts, receipts, self.receipts),
+            (PruneSegment::AccountHistory, account_history, self.account_history),
+            (PruneSegment::StorageHistory, storage_history, self.storage_history),
+            (PruneSegment::CallTraces, call_traces, self.call_traces),
+        ] {
+            if mode_override.is_some() {
+                *mode = mode_override;
+            }
+            if !self.is_selected(segment) {
+                *mode = None;
+            }
+        }
+        if !self.is_selected(PruneSegment::ContractLogs) {
+            receipts_log_filter.0.clear();
+        }
+
+        config
+    }
+
+    /// Returns `true` if the segment is pruned.
+    fn is_selected(&self, segment: PruneSegment) -> bool {
+        self.segments.is_empty() || self.segments.contains(&segment)
+    }
+}
+
+/// Parses a [PruneMode] from `full`, `distance:<BLOCKS>` or `before:<BLOCK>`.
+fn parse_prune_mode(value: &str) -> eyre::Result<PruneMode> {
+    match value.split_once(':') {
+        None if value == "full" => Ok(PruneMode::Full),
+        Some(("distance", blocks)) => Ok(PruneMode::Distance(blocks.parse()?)),
+        Some(("before", block)) => Ok(PruneMode::Before(block.parse()?)),
+        _ => eyre::bail!("expected `full`, `distance:<BLOCKS>` or `before:<BLOCK>`"),
+    }
+}
+
+/// Returns a table with the lowest block every configured segment retains at the given tip.
+///
+/// Receipts containing logs of the addresses in the receipts log filter are retained from the
+/// lowest block of all filters, and all other receipts are pruned up to the minimum pruning
+/// distance.
+fn retained_blocks_table<DB: Database>(
+    provider_factory: &ProviderFactory<DB>,
+    segments: &PruneModes,
+    tip: BlockNumber,
+) -> eyre::Result<ComfyTable> {
+    let provider = provider_factory.provider()?;
+
+    let mut table = ComfyTable::new();
+    table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    table.set_header(["Segment", "Prune mode", "Pruned up to block", "Lowest retained block"]);
+
+    for segment in SegmentSet::<DB>::from_prune_modes(segments.clone()).into_vec() {
+        let checkpoint = provider.get_prune_checkpoint(segment.segment())?;
+        let pruned_block = checkpoint.and_then(|checkpoint| checkpoint.block_number);
+
+        let (prune_mode, lowest_retained_block) = match segment.mode() {
+            _ if segment.segment() == PruneSegment::ContractLogs => (
+                format!("{} addresses", segments.receipts_log_filter.0.len()),
+                segments.receipts_log_filter.lowest_retained_block(tip, pruned_block)?,
+            ),
+            Some(mode) => (
+                format!("{mode:?}"),
+                mode.prune_target_block(tip, segment.segment(), PrunePurpose::User)?
+                    .map(|(block, _)| block + 1),
+            ),
+            None => continue,
+        };
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new(segment.segment()))
+            .add_cell(Cell::new(prune_mode))
+            .add_cell(Cell::new(pruned_block.map_or("-".to_string(), |block| block.to_string())))
+            .add_cell(Cell::new(lowest_retained_block.unwrap_or_default()));
+        table.add_row(row);
+    }
+
+    Ok(table)
+}
+
+/// Returns a table with the number of deleted entries of every pruned segment.
+fn deleted_table(deleted: &BTreeMap<PruneSegment, usize>) -> ComfyTable {
+    let mut table = ComfyTable::new();
+    table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    table.set_header(["Segment", "Deleted entries"]);
+
+    for (segment, entries) in deleted {
+        let mut row = Row::new();
+        row.add_cell(Cell::new(segment)).add_cell(Cell::new(entries));
+        table.add_row(row);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::ReceiptsLogPruneConfig;
+
+    #[test]
+    fn parse_prune_modes() {
+        let command = PruneCommand::try_parse_from([
+            "reth",
+            "--prune.receipts",
+            "distance:10064",
+            "--prune.sender-recovery",
+            "full",
+            "--prune.account-history",
+            "before:100",
+        ])
+        .unwrap();
+        assert_eq!(command.receipts, Some(PruneMode::Distance(10064)));
+        assert_eq!(command.sender_recovery, Some(PruneMode::Full));
+        assert_eq!(command.account_history, Some(PruneMode::Before(100)));
+        assert_eq!(command.batch_size, DEFAULT_BATCH_SIZE);
+
+        assert!(PruneCommand::try_parse_from(["reth", "--prune.receipts", "distance"]).is_err());
+        assert!(PruneCommand::try_parse_from(["reth", "--prune.batch-size", "0"]).is_err());
+    }
+
+    #[test]
+    fn prune_config_overrides() {
+        let config = PruneConfig {
+            block_interval: 5,
+            segments: PruneModes {
+                sender_recovery: Some(PruneMode::Full),
+                receipts: Some(PruneMode::Distance(20_000)),
+                receipts_log_filter: ReceiptsLogPruneConfig(
+                    [(Default::default(), PruneMode::Full)].into(),
+                ),
+                ..PruneModes::none()
+            },
+            max_entries_per_second: None,
+        };
+
+        let command = PruneCommand::try_parse_from([
+            "reth",
+            "--segment",
+            "receipts,transaction-lookup",
+            "--prune.receipts",
+            "distance:10064",
+            "--prune.transaction-lookup",
+            "full",
+        ])
+        .unwrap();
+        assert_eq!(
+            command.prune_config(config.clone()).segments,
+            PruneModes {
+                transaction_lookup: Some(PruneMode::Full),
+                receipts: Some(PruneMode::Distance(10064)),
+                ..PruneModes::none()
+            }
+        );
+
+        let command = PruneCommand::try_parse_from(["reth"]).unwrap();
+        assert_eq!(command.prune_config(config.clone()), config);
+    }
+}
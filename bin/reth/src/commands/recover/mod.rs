@@ -3,6 +3,7 @@
 use crate::core::cli::runner::CliContext;
 use clap::{Parser, Subcommand};
 
+mod storage_trie;
 mod storage_tries;
 
 /// `reth recover` command
@@ -17,6 +18,8 @@ pub struct Command {
 pub enum Subcommands {
     /// Recover the node by deleting dangling storage tries.
     StorageTries(storage_tries::Command),
+    /// Rebuild the storage trie of a single account.
+    StorageTrie(storage_trie::Command),
 }
 
 impl Command {
@@ -24,6 +27,7 @@ impl Command {
     pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
         match self.command {
             Subcommands::StorageTries(command) => command.execute(ctx).await,
+            Subcommands::StorageTrie(command) => command.execute(ctx).await,
         }
     }
 }
@@ -0,0 +1,181 @@
+use crate::{
+    args::utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+    core::cli::runner::CliContext,
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::Parser;
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO, DbDupCursorRW},
+    init_db, tables,
+    transaction::DbTx,
+};
+use reth_node_core::args::DatabaseArgs;
+use reth_primitives::{keccak256, stage::StageId, trie::Nibbles, Address, ChainSpec, B256};
+use reth_provider::{
+    BlockNumReader, HeaderProvider, ProviderError, ProviderFactory, StageCheckpointReader,
+};
+use reth_trie::{
+    prefix_set::{PrefixSetMut, TriePrefixSets},
+    updates::{TrieKey, TrieUpdates},
+    StateRoot, StorageRoot,
+};
+use std::{fs, sync::Arc};
+use tracing::*;
+
+/// `reth recover storage-trie` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    /// All database related arguments
+    #[command(flatten)]
+    pub db: DatabaseArgs,
+
+    /// The account whose storage trie is rebuilt.
+    #[arg(long, value_name = "ADDRESS")]
+    address: Address,
+
+    /// Also rewrites the account trie nodes on the path of the account if they don't match the
+    /// rebuilt storage trie.
+    ///
+    /// Without this flag, the recovery fails instead if the account trie nodes are stale.
+    #[arg(long, verbatim_doc_comment)]
+    fix_account: bool,
+}
+
+impl Command {
+    /// Execute `storage-trie` recovery command
+    pub async fn execute(self, _ctx: CliContext) -> eyre::Result<()> {
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db_path = data_dir.db_path();
+        fs::create_dir_all(&db_path)?;
+        let db = Arc::new(init_db(db_path, self.db.database_args())?);
+
+        let factory = ProviderFactory::new(&db, self.chain.clone(), data_dir.static_files_path())?;
+        let provider = factory.provider_rw()?;
+
+        // the trie tables are only up to date with the hashed state at the merkle checkpoint
+        let block =
+            provider.get_stage_checkpoint(StageId::MerkleExecute)?.unwrap_or_default().block_number;
+        let tip = provider.best_block_number()?;
+        if block < tip {
+            warn!(target: "reth::cli", block, tip, "Recovering the storage trie behind the tip");
+        }
+        let header = provider
+            .header_by_number(block)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(block.into()))?;
+
+        let tx = provider.tx_ref();
+        let hashed_address = keccak256(self.address);
+        if tx.get::<tables::HashedAccounts>(hashed_address)?.is_none() {
+            eyre::bail!(
+                "Account {} does not exist, use `reth recover storage-tries` to delete its \
+                 storage trie",
+                self.address
+            )
+        }
+
+        let entries_before = storage_trie_entries(tx, hashed_address)?;
+        let root_before = StorageRoot::from_tx_hashed(tx, hashed_address).root()?;
+        info!(
+            target: "reth::cli",
+            address = %self.address,
+            root = %root_before,
+            entries = entries_before,
+            "Rebuilding storage trie"
+        );
+
+        // delete the stored nodes, so the storage root is recomputed from the hashed storage
+        let mut storage_trie_cursor = tx.cursor_dup_write::<tables::StoragesTrie>()?;
+        if storage_trie_cursor.seek_exact(hashed_address)?.is_some() {
+            storage_trie_cursor.delete_current_duplicates()?;
+        }
+        let (root_after, slots, updates) =
+            StorageRoot::from_tx_hashed(tx, hashed_address).root_with_updates()?;
+        updates.flush(tx)?;
+        let entries_after = storage_trie_entries(tx, hashed_address)?;
+
+        println!("Storage root of {}:", self.address);
+        println!("  before: {root_before} ({entries_before} trie nodes)");
+        println!("  after:  {root_after} ({entries_after} trie nodes, {slots} storage slots)");
+
+        // recompute the path of the account in the account trie with the rebuilt storage root
+        let mut account_prefix_set = PrefixSetMut::default();
+        account_prefix_set.insert(Nibbles::unpack(hashed_address));
+        let prefix_sets = TriePrefixSets {
+            account_prefix_set: account_prefix_set.freeze(),
+            ..Default::default()
+        };
+        let (state_root, updates) =
+            StateRoot::from_tx(tx).with_prefix_sets(prefix_sets).root_with_updates()?;
+        if state_root != header.state_root {
+            eyre::bail!(
+                "Recovery failed. The rebuilt storage trie results in the state root \
+                 {state_root}, expected the state root {} of block {block}",
+                header.state_root
+            )
+        }
+
+        // the stored account trie nodes on the path of the account hash the previous storage root
+        // if they don't result in the state root
+        let stored_state_root = StateRoot::from_tx(tx).root()?;
+        if stored_state_root != header.state_root {
+            if !self.fix_account {
+                eyre::bail!(
+                    "Recovery failed. The account trie nodes of {} don't match the rebuilt \
+                     storage root, pass `--fix-account` to rewrite them",
+                    self.address
+                )
+            }
+
+            let mut account_updates = TrieUpdates::default();
+            account_updates.extend(
+                updates.into_iter().filter(|(key, _)| matches!(key, TrieKey::AccountNode(_))),
+            );
+            info!(
+                target: "reth::cli",
+                address = %self.address,
+                nodes = account_updates.len(),
+                "Rewriting account trie nodes"
+            );
+            account_updates.flush(tx)?;
+            println!("Rewrote the account trie nodes of {}", self.address);
+        }
+
+        provider.commit()?;
+        info!(target: "reth::cli", root = %root_after, "Finished recovery");
+
+        Ok(())
+    }
+}
+
+/// Returns the number of stored nodes of the storage trie of the account.
+fn storage_trie_entries<TX: DbTx>(tx: &TX, hashed_address: B256) -> eyre::Result<usize> {
+    let mut cursor = tx.cursor_dup_read::<tables::StoragesTrie>()?;
+    let mut entries = 0;
+    for entry in cursor.walk_dup(Some(hashed_address), None)? {
+        entry?;
+        entries += 1;
+    }
+    Ok(entries)
+}
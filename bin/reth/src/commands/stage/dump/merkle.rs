@@ -148,6 +148,8 @@ async fn dry_run<DB: Database>(
     let mut stage = MerkleStage::Execution {
         // Forces updating the root instead of calculating from scratch
         clean_threshold: u64::MAX,
+        flush_interval: None,
+        parallel_storage_roots: None,
     };
 
     loop {
@@ -17,7 +17,7 @@ pub struct Command {
 /// `reth stage` subcommands
 #[derive(Subcommand, Debug)]
 pub enum Subcommands {
-    /// Run a single stage.
+    /// Run one or more stages.
     ///
     /// Note that this won't use the Pipeline and as a result runs stages
     /// assuming that all the data can be held in memory. It is not recommended
@@ -8,18 +8,25 @@ use crate::{
         utils::{chain_help, chain_spec_value_parser, SUPPORTED_CHAINS},
         DatabaseArgs, NetworkArgs, StageEnum,
     },
-    dirs::{DataDirPath, MaybePlatformPath},
+    dirs::{ChainPath, DataDirPath, MaybePlatformPath},
     prometheus_exporter,
     version::SHORT_VERSION,
 };
-use clap::Parser;
+use clap::{Parser, ValueEnum};
+use comfy_table::{Cell, Row, Table as ComfyTable};
 use reth_beacon_consensus::BeaconConsensus;
 use reth_config::{config::EtlConfig, Config};
-use reth_db::init_db;
+use reth_db::{init_db, transaction::DbTx, DatabaseEnv};
 use reth_downloaders::bodies::bodies::BodiesDownloaderBuilder;
 use reth_node_ethereum::EthEvmConfig;
-use reth_primitives::ChainSpec;
-use reth_provider::{ProviderFactory, StageCheckpointReader, StageCheckpointWriter};
+use reth_primitives::{
+    stage::{StageCheckpoint, StageId},
+    BlockNumber, ChainSpec, StaticFileSegment,
+};
+use reth_provider::{
+    DatabaseProviderRW, HeaderProvider, ProviderFactory, StageCheckpointReader,
+    StageCheckpointWriter,
+};
 use reth_stages::{
     stages::{
         AccountHashingStage, BodyStage, ExecutionStage, ExecutionStageThresholds,
@@ -28,9 +35,19 @@ use reth_stages::{
     },
     ExecInput, ExecOutput, Stage, StageExt, UnwindInput, UnwindOutput,
 };
-use std::{any::Any, net::SocketAddr, path::PathBuf, sync::Arc, time::Instant};
+use std::{
+    any::Any,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tempfile::TempDir;
 use tracing::*;
 
+/// A stage to execute, and optionally a separate stage to unwind it with.
+type StagePair = (Box<dyn Stage<Arc<DatabaseEnv>>>, Option<Box<dyn Stage<Arc<DatabaseEnv>>>>);
+
 /// `reth stage` command
 #[derive(Debug, Parser)]
 pub struct Command {
@@ -66,9 +83,17 @@ pub struct Command {
     #[arg(long, value_name = "SOCKET")]
     metrics: Option<SocketAddr>,
 
-    /// The name of the stage to run
-    #[arg(value_enum)]
-    stage: StageEnum,
+    /// The names of the stages to run, separated by commas.
+    ///
+    /// The stages are run in the order of the pipeline, regardless of the order they're given in.
+    #[arg(value_enum, value_delimiter = ',', required = true)]
+    stages: Vec<StageEnum>,
+
+    /// Run all stages of the pipeline from the given stage through this stage.
+    ///
+    /// Only a single stage may be given to start from.
+    #[arg(long, value_enum, value_name = "STAGE")]
+    through: Option<StageEnum>,
 
     /// The height to start at
     #[arg(long)]
@@ -98,6 +123,14 @@ pub struct Command {
     #[arg(long, short)]
     skip_unwind: bool,
 
+    /// Skip checking that no later stage of the pipeline is ahead of the block the stages are
+    /// unwound to, when committing.
+    ///
+    /// Unwinding stages while later stages are ahead of them leaves the database inconsistent,
+    /// so this should only be used if the later stages are unwound or run again afterwards.
+    #[arg(long)]
+    skip_unwind_check: bool,
+
     #[command(flatten)]
     network: NetworkArgs,
 
@@ -115,6 +148,14 @@ pub struct Command {
     /// Save stage checkpoints
     #[arg(long)]
     checkpoints: bool,
+
+    /// Run the stages without modifying the database or the static files.
+    ///
+    /// The stages are executed in a database transaction that is aborted afterwards, and static
+    /// files are written to a temporary copy of the static files directory that is deleted
+    /// afterwards. The checkpoints the stages would have produced are printed instead.
+    #[arg(long, conflicts_with = "commit")]
+    dry_run: bool,
 }
 
 impl Command {
@@ -124,12 +165,14 @@ impl Command {
         // Does not do anything on windows.
         let _ = fdlimit::raise_fd_limit();
 
+        let stages = self.stages()?;
+
         // add network name to data dir
         let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
         let config_path = self.config.clone().unwrap_or_else(|| data_dir.config_path());
 
         let config: Config = confy::load_path(config_path).unwrap_or_default();
-        info!(target: "reth::cli", "reth {} starting stage {:?}", SHORT_VERSION, self.stage);
+        info!(target: "reth::cli", "reth {} starting stages {:?}", SHORT_VERSION, stages);
 
         // use the overridden db path if specified
         let db_path = data_dir.db_path();
@@ -138,11 +181,19 @@ impl Command {
         let db = Arc::new(init_db(db_path, self.db.database_args())?);
         info!(target: "reth::cli", "Database opened");
 
-        let factory = ProviderFactory::new(
-            Arc::clone(&db),
-            self.chain.clone(),
-            data_dir.static_files_path(),
-        )?;
+        // Static files are written outside of the database transaction, so a dry run writes them
+        // to a copy of the static files directory instead, which is deleted when dropped.
+        let dry_run_dir = if self.dry_run {
+            info!(target: "reth::cli", "Copying static files for the dry run");
+            Some(copy_static_files(&data_dir.static_files_path(), &stages, self.from)?)
+        } else {
+            None
+        };
+        let static_files_path = dry_run_dir
+            .as_ref()
+            .map_or_else(|| data_dir.static_files_path(), |dir| dir.path().to_path_buf());
+
+        let factory = ProviderFactory::new(Arc::clone(&db), self.chain.clone(), static_files_path)?;
         let mut provider_rw = factory.provider_rw()?;
 
         if let Some(listen_addr) = self.metrics {
@@ -157,166 +208,555 @@ impl Command {
             .await?;
         }
 
+        let checkpoints = pipeline_stage_ids()
+            .map(|stage_id| {
+                let checkpoint = provider_rw.get_stage_checkpoint(stage_id)?.unwrap_or_default();
+                Ok((stage_id, checkpoint.block_number))
+            })
+            .collect::<eyre::Result<Vec<_>>>()?;
+        check_prerequisites(&checkpoints, stages[0], self.to)?;
+        if self.commit && !self.skip_unwind && !self.skip_unwind_check {
+            check_later_stages(&checkpoints, stages[stages.len() - 1], self.from)?;
+        }
+
         let batch_size = self.batch_size.unwrap_or(self.to.saturating_sub(self.from) + 1);
 
         let etl_config = EtlConfig::new(
             Some(
-                self.etl_dir.unwrap_or_else(|| EtlConfig::from_datadir(&data_dir.data_dir_path())),
+                self.etl_dir
+                    .clone()
+                    .unwrap_or_else(|| EtlConfig::from_datadir(&data_dir.data_dir_path())),
             ),
             self.etl_file_size.unwrap_or(EtlConfig::default_file_size()),
         );
 
-        let (mut exec_stage, mut unwind_stage): (Box<dyn Stage<_>>, Option<Box<dyn Stage<_>>>) =
-            match self.stage {
-                StageEnum::Bodies => {
-                    let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
-
-                    let mut config = config;
-                    config.peers.trusted_nodes_only = self.network.trusted_only;
-                    if !self.network.trusted_peers.is_empty() {
-                        self.network.trusted_peers.iter().for_each(|peer| {
-                            config.peers.trusted_nodes.insert(*peer);
-                        });
+        let mut runs = Vec::with_capacity(stages.len());
+        for stage in stages {
+            let (exec_stage, unwind_stage) = self
+                .build_stage(
+                    stage,
+                    config.clone(),
+                    &data_dir,
+                    &factory,
+                    batch_size,
+                    etl_config.clone(),
+                )
+                .await?;
+            if let Some(unwind_stage) = &unwind_stage {
+                assert_eq!((*exec_stage).type_id(), (**unwind_stage).type_id());
+            }
+            runs.push((stage, exec_stage, unwind_stage));
+        }
+
+        // Later stages depend on the data of earlier stages, so they're unwound first
+        if !self.skip_unwind {
+            for (_, exec_stage, unwind_stage) in runs.iter_mut().rev() {
+                let checkpoint =
+                    provider_rw.get_stage_checkpoint(exec_stage.id())?.unwrap_or_default();
+                let unwind_stage = unwind_stage.as_mut().unwrap_or(exec_stage);
+
+                let mut unwind = UnwindInput {
+                    checkpoint: checkpoint.with_block_number(self.to),
+                    unwind_to: self.from,
+                    bad_block: None,
+                };
+
+                while unwind.checkpoint.block_number > self.from {
+                    let UnwindOutput { checkpoint } = unwind_stage.unwind(&provider_rw, unwind)?;
+                    unwind.checkpoint = checkpoint;
+
+                    if self.checkpoints {
+                        provider_rw.save_stage_checkpoint(unwind_stage.id(), checkpoint)?;
                     }
 
-                    let network_secret_path = self
-                        .network
-                        .p2p_secret_key
-                        .clone()
-                        .unwrap_or_else(|| data_dir.p2p_secret_path());
-                    let p2p_secret_key = get_secret_key(&network_secret_path)?;
-
-                    let default_peers_path = data_dir.known_peers_path();
-
-                    let provider_factory = Arc::new(ProviderFactory::new(
-                        db.clone(),
-                        self.chain.clone(),
-                        data_dir.static_files_path(),
-                    )?);
-
-                    let network = self
-                        .network
-                        .network_config(
-                            &config,
-                            self.chain.clone(),
-                            p2p_secret_key,
-                            default_peers_path,
-                        )
-                        .build(provider_factory.clone())
-                        .start_network()
-                        .await?;
-                    let fetch_client = Arc::new(network.fetch_client().await?);
-
-                    let stage = BodyStage::new(
-                        BodiesDownloaderBuilder::default()
-                            .with_stream_batch_size(batch_size as usize)
-                            .with_request_limit(config.stages.bodies.downloader_request_limit)
-                            .with_max_buffered_blocks_size_bytes(
-                                config.stages.bodies.downloader_max_buffered_blocks_size_bytes,
-                            )
-                            .with_concurrent_requests_range(
-                                config.stages.bodies.downloader_min_concurrent_requests..=
-                                    config.stages.bodies.downloader_max_concurrent_requests,
-                            )
-                            .build(fetch_client, consensus.clone(), provider_factory),
-                    );
-                    (Box::new(stage), None)
-                }
-                StageEnum::Senders => (Box::new(SenderRecoveryStage::new(batch_size)), None),
-                StageEnum::Execution => {
-                    let factory = reth_revm::EvmProcessorFactory::new(
-                        self.chain.clone(),
-                        EthEvmConfig::default(),
-                    );
-                    (
-                        Box::new(ExecutionStage::new(
-                            factory,
-                            ExecutionStageThresholds {
-                                max_blocks: Some(batch_size),
-                                max_changes: None,
-                                max_cumulative_gas: None,
-                                max_duration: None,
-                            },
-                            config.stages.merkle.clean_threshold,
-                            config.prune.map(|prune| prune.segments).unwrap_or_default(),
-                        )),
-                        None,
-                    )
-                }
-                StageEnum::TxLookup => {
-                    (Box::new(TransactionLookupStage::new(batch_size, etl_config, None)), None)
-                }
-                StageEnum::AccountHashing => {
-                    (Box::new(AccountHashingStage::new(1, batch_size, etl_config)), None)
+                    provider_rw = self.commit_step(provider_rw, &factory)?;
                 }
-                StageEnum::StorageHashing => {
-                    (Box::new(StorageHashingStage::new(1, batch_size, etl_config)), None)
-                }
-                StageEnum::Merkle => (
-                    Box::new(MerkleStage::default_execution()),
-                    Some(Box::new(MerkleStage::default_unwind())),
-                ),
-                StageEnum::AccountHistory => (Box::<IndexAccountHistoryStage>::default(), None),
-                StageEnum::StorageHistory => (Box::<IndexStorageHistoryStage>::default(), None),
-                _ => return Ok(()),
-            };
-        if let Some(unwind_stage) = &unwind_stage {
-            assert_eq!((*exec_stage).type_id(), (**unwind_stage).type_id());
+            }
         }
 
-        let checkpoint = provider_rw.get_stage_checkpoint(exec_stage.id())?.unwrap_or_default();
+        let mut summary = Vec::with_capacity(runs.len());
+        for (stage, mut exec_stage, _) in runs {
+            let checkpoint = provider_rw.get_stage_checkpoint(exec_stage.id())?.unwrap_or_default();
 
-        let unwind_stage = unwind_stage.as_mut().unwrap_or(&mut exec_stage);
+            let mut input = ExecInput {
+                target: Some(self.to),
+                checkpoint: Some(checkpoint.with_block_number(self.from)),
+            };
 
-        let mut unwind = UnwindInput {
-            checkpoint: checkpoint.with_block_number(self.to),
-            unwind_to: self.from,
-            bad_block: None,
-        };
+            let start = Instant::now();
+            info!(target: "reth::cli", %stage, "Executing stage");
+            loop {
+                exec_stage.execute_ready(input).await?;
+                let ExecOutput { checkpoint, done } = exec_stage.execute(&provider_rw, input)?;
 
-        if !self.skip_unwind {
-            while unwind.checkpoint.block_number > self.from {
-                let UnwindOutput { checkpoint } = unwind_stage.unwind(&provider_rw, unwind)?;
-                unwind.checkpoint = checkpoint;
+                input.checkpoint = Some(checkpoint);
 
                 if self.checkpoints {
-                    provider_rw.save_stage_checkpoint(unwind_stage.id(), checkpoint)?;
+                    provider_rw.save_stage_checkpoint(exec_stage.id(), checkpoint)?;
                 }
+                provider_rw = self.commit_step(provider_rw, &factory)?;
 
-                if self.commit {
-                    provider_rw.commit()?;
-                    provider_rw = factory.provider_rw()?;
+                if done {
+                    break
+                }
+
+                if let Some(progress) = checkpoint.entities().and_then(|e| e.fmt_percentage()) {
+                    info!(target: "reth::cli", %stage, checkpoint = checkpoint.block_number, %progress, "Committed stage progress");
                 }
             }
+            let elapsed = start.elapsed();
+            info!(target: "reth::cli", %stage, time = ?elapsed, "Finished stage");
+
+            let checkpoint = input.checkpoint.unwrap_or_default();
+            summary.push(StageRun {
+                stage,
+                blocks: checkpoint.block_number.saturating_sub(self.from),
+                elapsed,
+                checkpoint,
+            });
+        }
+
+        println!("{}", summary_table(&summary));
+
+        if self.dry_run {
+            let gas = provider_rw
+                .headers_range(self.from + 1..=self.to)?
+                .iter()
+                .map(|header| header.gas_used)
+                .sum::<u64>();
+            println!(
+                "Dry run processed {gas} gas in {:?}, aborting all changes.",
+                summary.iter().map(|run| run.elapsed).sum::<Duration>()
+            );
+        }
+
+        // Without `--commit`, nothing was committed, so all changes are discarded
+        if !self.commit {
+            provider_rw.into_tx().abort();
+        }
+        drop(dry_run_dir);
+
+        Ok(())
+    }
+
+    /// Commits the changes made so far if `--commit` is set, returning the provider to continue
+    /// with.
+    ///
+    /// Otherwise the changes are kept in the same transaction, so that later steps see them, and
+    /// are aborted once all stages ran.
+    fn commit_step(
+        &self,
+        provider_rw: DatabaseProviderRW<Arc<DatabaseEnv>>,
+        factory: &ProviderFactory<Arc<DatabaseEnv>>,
+    ) -> eyre::Result<DatabaseProviderRW<Arc<DatabaseEnv>>> {
+        if !self.commit {
+            return Ok(provider_rw)
         }
 
-        let mut input = ExecInput {
-            target: Some(self.to),
-            checkpoint: Some(checkpoint.with_block_number(self.from)),
+        provider_rw.commit()?;
+        Ok(factory.provider_rw()?)
+    }
+
+    /// Returns the stages to run, in the order of the pipeline.
+    fn stages(&self) -> eyre::Result<Vec<StageEnum>> {
+        // The hashing stage consists of the account and storage hashing stages
+        let expand = |stage: StageEnum| match stage {
+            StageEnum::Hashing => vec![StageEnum::AccountHashing, StageEnum::StorageHashing],
+            stage => vec![stage],
         };
 
-        let start = Instant::now();
-        info!(target: "reth::cli", stage = %self.stage, "Executing stage");
-        loop {
-            exec_stage.execute_ready(input).await?;
-            let ExecOutput { checkpoint, done } = exec_stage.execute(&provider_rw, input)?;
+        let mut stages = self.stages.iter().copied().flat_map(expand).collect::<Vec<_>>();
+        for stage in &stages {
+            pipeline_position(*stage)?;
+        }
+
+        if let Some(through) = self.through {
+            eyre::ensure!(
+                self.stages.len() == 1,
+                "Only a single stage may be given to start from when using `--through`"
+            );
+            let start = pipeline_position(stages[0])?;
+            let end = pipeline_position(*expand(through).last().expect("not empty"))?;
+            eyre::ensure!(
+                start <= end,
+                "Stage {through} runs before stage {} in the pipeline",
+                self.stages[0]
+            );
+
+            stages = StageEnum::value_variants()
+                .iter()
+                .copied()
+                .filter(|stage| {
+                    pipeline_position(*stage)
+                        .map_or(false, |position| (start..=end).contains(&position))
+                })
+                .collect();
+        }
+
+        stages.sort_by_key(|stage| pipeline_position(*stage).expect("checked above"));
+        stages.dedup();
+        Ok(stages)
+    }
 
-            input.checkpoint = Some(checkpoint);
+    /// Builds the stage to execute, and the stage to unwind it with if it's different.
+    async fn build_stage(
+        &self,
+        stage: StageEnum,
+        mut config: Config,
+        data_dir: &ChainPath<DataDirPath>,
+        factory: &ProviderFactory<Arc<DatabaseEnv>>,
+        batch_size: u64,
+        etl_config: EtlConfig,
+    ) -> eyre::Result<StagePair> {
+        Ok(match stage {
+            StageEnum::Bodies => {
+                let consensus = Arc::new(BeaconConsensus::new(self.chain.clone()));
+
+                config.peers.trusted_nodes_only = self.network.trusted_only;
+                if !self.network.trusted_peers.is_empty() {
+                    self.network.trusted_peers.iter().for_each(|peer| {
+                        config.peers.trusted_nodes.insert(*peer);
+                    });
+                }
 
-            if self.checkpoints {
-                provider_rw.save_stage_checkpoint(exec_stage.id(), checkpoint)?;
+                let network_secret_path = self
+                    .network
+                    .p2p_secret_key
+                    .clone()
+                    .unwrap_or_else(|| data_dir.p2p_secret_path());
+                let p2p_secret_key = get_secret_key(&network_secret_path)?;
+
+                let default_peers_path = data_dir.known_peers_path();
+
+                let provider_factory = Arc::new(factory.clone());
+
+                let network = self
+                    .network
+                    .network_config(&config, self.chain.clone(), p2p_secret_key, default_peers_path)
+                    .build(provider_factory.clone())
+                    .start_network()
+                    .await?;
+                let fetch_client = Arc::new(network.fetch_client().await?);
+
+                let stage = BodyStage::new(
+                    BodiesDownloaderBuilder::default()
+                        .with_stream_batch_size(batch_size as usize)
+                        .with_request_limit(config.stages.bodies.downloader_request_limit)
+                        .with_max_buffered_blocks_size_bytes(
+                            config.stages.bodies.downloader_max_buffered_blocks_size_bytes,
+                        )
+                        .with_concurrent_requests_range(
+                            config.stages.bodies.downloader_min_concurrent_requests..=
+                                config.stages.bodies.downloader_max_concurrent_requests,
+                        )
+                        .build(fetch_client, consensus.clone(), provider_factory),
+                );
+                (Box::new(stage), None)
             }
-            if self.commit {
-                provider_rw.commit()?;
-                provider_rw = factory.provider_rw()?;
+            StageEnum::Senders => (
+                Box::new(SenderRecoveryStage {
+                    commit_threshold: batch_size,
+                    ..SenderRecoveryStage::new_with_config(config.stages.sender_recovery)
+                }),
+                None,
+            ),
+            StageEnum::Execution => {
+                let factory = reth_revm::EvmProcessorFactory::new(
+                    self.chain.clone(),
+                    EthEvmConfig::default(),
+                );
+                (
+                    Box::new(ExecutionStage::new(
+                        factory,
+                        ExecutionStageThresholds {
+                            max_blocks: Some(batch_size),
+                            max_changes: None,
+                            max_cumulative_gas: None,
+                            max_duration: None,
+                        },
+                        config.stages.merkle.clean_threshold,
+                        config.prune.map(|prune| prune.segments).unwrap_or_default(),
+                    )),
+                    None,
+                )
             }
-
-            if done {
-                break
+            StageEnum::TxLookup => {
+                (Box::new(TransactionLookupStage::new(batch_size, etl_config, None)), None)
+            }
+            StageEnum::AccountHashing => {
+                (Box::new(AccountHashingStage::new(1, batch_size, etl_config)), None)
             }
+            StageEnum::StorageHashing => {
+                (Box::new(StorageHashingStage::new(1, batch_size, etl_config)), None)
+            }
+            StageEnum::Merkle => (
+                Box::new(MerkleStage::new_execution_with_config(config.stages.merkle)),
+                Some(Box::new(MerkleStage::default_unwind())),
+            ),
+            StageEnum::AccountHistory => (Box::<IndexAccountHistoryStage>::default(), None),
+            StageEnum::StorageHistory => (Box::<IndexStorageHistoryStage>::default(), None),
+            StageEnum::Headers | StageEnum::Hashing => {
+                eyre::bail!("Stage {stage} can't be run with this command")
+            }
+        })
+    }
+}
+
+/// Returns the id of the stage, or [None] if it can't be run by this command.
+const fn stage_id(stage: StageEnum) -> Option<StageId> {
+    match stage {
+        StageEnum::Bodies => Some(StageId::Bodies),
+        StageEnum::Senders => Some(StageId::SenderRecovery),
+        StageEnum::Execution => Some(StageId::Execution),
+        StageEnum::AccountHashing => Some(StageId::AccountHashing),
+        StageEnum::StorageHashing => Some(StageId::StorageHashing),
+        StageEnum::Merkle => Some(StageId::MerkleExecute),
+        StageEnum::TxLookup => Some(StageId::TransactionLookup),
+        StageEnum::AccountHistory => Some(StageId::IndexAccountHistory),
+        StageEnum::StorageHistory => Some(StageId::IndexStorageHistory),
+        StageEnum::Headers | StageEnum::Hashing => None,
+    }
+}
+
+/// Returns the ids of the stages of the pipeline that keep a checkpoint of their own, in the
+/// order the pipeline runs them.
+///
+/// The merkle unwind stage only unwinds the trie, and the finish stage only marks the end of the
+/// pipeline, so they're skipped.
+fn pipeline_stage_ids() -> impl Iterator<Item = StageId> {
+    StageId::ALL
+        .into_iter()
+        .filter(|stage_id| !matches!(stage_id, StageId::MerkleUnwind | StageId::Finish))
+}
+
+/// Returns the position of the stage in [`pipeline_stage_ids`].
+fn pipeline_position(stage: StageEnum) -> eyre::Result<usize> {
+    stage_id(stage)
+        .and_then(|stage_id| pipeline_stage_ids().position(|id| id == stage_id))
+        .ok_or_else(|| eyre::eyre!("Stage {stage} can't be run with this command"))
+}
+
+/// Fails if the pipeline stage before the first stage to run hasn't reached the block the stages
+/// are run to, since the stages require its data.
+fn check_prerequisites(
+    checkpoints: &[(StageId, BlockNumber)],
+    first: StageEnum,
+    to: BlockNumber,
+) -> eyre::Result<()> {
+    let position = pipeline_position(first)?;
+    let Some(&(previous, checkpoint)) = position.checked_sub(1).and_then(|i| checkpoints.get(i))
+    else {
+        return Ok(())
+    };
+
+    eyre::ensure!(
+        checkpoint >= to,
+        "Stage {first} requires stage {previous} to have reached block {to}, but its checkpoint \
+         is at block {checkpoint}. Run the earlier stages first, e.g. by starting from an earlier \
+         stage with `--through {first}`."
+    );
+    Ok(())
+}
+
+/// Fails if a pipeline stage after the last stage to run is ahead of the block the stages are
+/// unwound to, since it would be left inconsistent with them.
+fn check_later_stages(
+    checkpoints: &[(StageId, BlockNumber)],
+    last: StageEnum,
+    from: BlockNumber,
+) -> eyre::Result<()> {
+    let position = pipeline_position(last)?;
+    let ahead = checkpoints
+        .iter()
+        .skip(position + 1)
+        .filter(|(_, checkpoint)| *checkpoint > from)
+        .map(|(stage_id, checkpoint)| format!("{stage_id} at block {checkpoint}"))
+        .collect::<Vec<_>>();
+
+    eyre::ensure!(
+        ahead.is_empty(),
+        "Unwinding to block {from} would leave later stages ahead: {}. Run them as well with \
+         `--through`, or pass `--skip-unwind-check`.",
+        ahead.join(", ")
+    );
+    Ok(())
+}
+
+/// Returns the static file segments the stage writes to, when executing or unwinding.
+const fn written_segments(stage: StageEnum) -> &'static [StaticFileSegment] {
+    match stage {
+        StageEnum::Bodies => &[StaticFileSegment::Transactions],
+        StageEnum::Senders => &[StaticFileSegment::TransactionSenders],
+        StageEnum::Execution => &[StaticFileSegment::Receipts],
+        _ => &[],
+    }
+}
+
+/// Copies the static files into a temporary directory next to them, so that running the stages
+/// doesn't modify the originals.
+///
+/// Only the files of the segments the stages write to that contain blocks after `from` are copied,
+/// since the stages only unwind and execute the blocks after it. All other files are only read, so
+/// they're hard linked if possible.
+fn copy_static_files(
+    static_files_path: &Path,
+    stages: &[StageEnum],
+    from: BlockNumber,
+) -> eyre::Result<TempDir> {
+    let written = stages.iter().flat_map(|stage| written_segments(*stage)).collect::<Vec<_>>();
+    let dir = tempfile::Builder::new()
+        .prefix("dry-run-static-files")
+        .tempdir_in(static_files_path.parent().unwrap_or(static_files_path))?;
+
+    for entry in reth_primitives::fs::read_dir(static_files_path)? {
+        let path = entry?.path();
+        if !path.is_file() {
+            continue
         }
-        info!(target: "reth::cli", stage = %self.stage, time = ?start.elapsed(), "Finished stage");
 
-        Ok(())
+        // The data, offsets and config files of a static file share the same file stem
+        let written = path
+            .file_stem()
+            .and_then(|stem| StaticFileSegment::parse_filename(&stem.to_string_lossy()))
+            .map_or(true, |(segment, range)| written.contains(&segment) && range.end() > from);
+        let target = dir.path().join(path.file_name().expect("is a file"));
+        if written || std::fs::hard_link(&path, &target).is_err() {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+
+    Ok(dir)
+}
+
+/// The result of executing a stage.
+#[derive(Debug)]
+struct StageRun {
+    /// The executed stage
+    stage: StageEnum,
+    /// The number of blocks the stage executed
+    blocks: u64,
+    /// The time it took to execute the stage
+    elapsed: Duration,
+    /// The checkpoint the stage finished with
+    checkpoint: StageCheckpoint,
+}
+
+/// Returns a table with the number of blocks, the elapsed time and the final checkpoint of every
+/// executed stage.
+fn summary_table(summary: &[StageRun]) -> ComfyTable {
+    let mut table = ComfyTable::new();
+    table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    table.set_header(["Stage", "Blocks", "Elapsed", "Blocks/s", "Checkpoint"]);
+
+    for run in summary {
+        let throughput = if run.elapsed.is_zero() {
+            "N/A".to_string()
+        } else {
+            format!("{:.2}", run.blocks as f64 / run.elapsed.as_secs_f64())
+        };
+
+        let mut checkpoint = format!("block {}", run.checkpoint.block_number);
+        if let Some(entities) = run.checkpoint.entities() {
+            checkpoint.push_str(&format!(" ({}/{})", entities.processed, entities.total));
+        }
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new(run.stage))
+            .add_cell(Cell::new(run.blocks))
+            .add_cell(Cell::new(format!("{:?}", run.elapsed)))
+            .add_cell(Cell::new(throughput))
+            .add_cell(Cell::new(checkpoint));
+        table.add_row(row);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> eyre::Result<Vec<StageEnum>> {
+        let args = ["reth"].iter().chain(args).chain(&["--from", "0", "--to", "10"]);
+        Command::try_parse_from(args)?.stages()
+    }
+
+    #[test]
+    fn parse_stages() {
+        assert_eq!(
+            parse(&["merkle,execution"]).unwrap(),
+            vec![StageEnum::Execution, StageEnum::Merkle]
+        );
+        assert_eq!(
+            parse(&["execution", "--through", "merkle"]).unwrap(),
+            vec![
+                StageEnum::Execution,
+                StageEnum::AccountHashing,
+                StageEnum::StorageHashing,
+                StageEnum::Merkle
+            ]
+        );
+        assert_eq!(
+            parse(&["hashing"]).unwrap(),
+            vec![StageEnum::AccountHashing, StageEnum::StorageHashing]
+        );
+
+        assert!(parse(&["headers"]).is_err());
+        assert!(parse(&["merkle", "--through", "execution"]).is_err());
+        assert!(parse(&["execution,senders", "--through", "merkle"]).is_err());
+    }
+
+    #[test]
+    fn check_checkpoints() {
+        let checkpoints = pipeline_stage_ids()
+            .map(|stage_id| (stage_id, if stage_id == StageId::MerkleExecute { 50 } else { 100 }))
+            .collect::<Vec<_>>();
+
+        assert!(check_prerequisites(&checkpoints, StageEnum::Merkle, 100).is_ok());
+        assert!(check_prerequisites(&checkpoints, StageEnum::TxLookup, 100).is_err());
+        assert!(check_prerequisites(&checkpoints, StageEnum::Bodies, 200).is_err());
+
+        assert!(check_later_stages(&checkpoints, StageEnum::Merkle, 40).is_err());
+        assert!(check_later_stages(&checkpoints, StageEnum::AccountHistory, 40).is_ok());
+        assert!(check_later_stages(&checkpoints, StageEnum::Execution, 100).is_ok());
+    }
+
+    #[test]
+    fn parse_dry_run() {
+        let args = ["reth", "execution", "--from", "0", "--to", "10", "--dry-run"];
+        assert!(Command::try_parse_from(args).unwrap().dry_run);
+        assert!(Command::try_parse_from(args.iter().chain(&["--commit"])).is_err());
+    }
+
+    #[test]
+    fn copy_static_files_for_dry_run() {
+        let static_files = tempfile::tempdir().unwrap();
+        let files = [
+            "static_file_headers_500000_999999",
+            "static_file_receipts_0_499999.off",
+            "static_file_receipts_500000_999999.off",
+            "unknown",
+        ];
+        for file in files {
+            reth_primitives::fs::write(static_files.path().join(file), file).unwrap();
+        }
+
+        let dir = copy_static_files(static_files.path(), &[StageEnum::Execution], 600_000).unwrap();
+        for file in files {
+            assert_eq!(reth_primitives::fs::read_to_string(dir.path().join(file)).unwrap(), file);
+        }
+
+        // Only the files with blocks the stages write to are copied, the others are hard links
+        let is_copy = |file: &str| {
+            reth_primitives::fs::write(dir.path().join(file), "changed").unwrap();
+            reth_primitives::fs::read_to_string(static_files.path().join(file)).unwrap() == file
+        };
+        assert!(!is_copy(files[0]));
+        assert!(!is_copy(files[1]));
+        assert!(is_copy(files[2]));
+        assert!(is_copy(files[3]));
+
+        let path = dir.path().to_path_buf();
+        drop(dir);
+        assert!(!path.exists());
     }
 }
@@ -0,0 +1,26 @@
+use clap::Parser;
+use reth_provider::providers::StaticFileProvider;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+/// The arguments for the `reth static-file index-tx-hashes` command
+#[derive(Parser, Debug)]
+pub struct Command;
+
+impl Command {
+    /// Execute `static-file index-tx-hashes` command
+    ///
+    /// Indexes the transactions of the existing static files by hash, which the node otherwise
+    /// does in the background on startup with `--static-file-tx-hash-index`. Static files that are
+    /// already indexed are only extended with the transactions that were appended since.
+    pub fn execute(self, static_files_path: PathBuf) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        let static_file_provider =
+            StaticFileProvider::new(static_files_path)?.with_transaction_hash_index();
+        let indexed = static_file_provider.update_transaction_hash_index()?;
+        info!("Indexed {indexed} transaction hashes.");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,63 @@
+//! `reth static-file` command.
+
+use crate::{
+    args::utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::{Parser, Subcommand};
+use reth_primitives::ChainSpec;
+use std::sync::Arc;
+
+mod index_tx_hashes;
+mod recompress;
+
+/// `reth static-file` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t, global = true)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser,
+        global = true,
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[command(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth static-file` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// Rewrites existing static files with a different compression.
+    Recompress(recompress::Command),
+    /// Indexes the transactions of the static files by hash.
+    IndexTxHashes(index_tx_hashes::Command),
+}
+
+impl Command {
+    /// Execute `static-file` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+
+        match self.command {
+            Subcommands::Recompress(command) => command.execute(data_dir.static_files_path()),
+            Subcommands::IndexTxHashes(command) => command.execute(data_dir.static_files_path()),
+        }
+    }
+}
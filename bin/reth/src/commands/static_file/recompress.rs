@@ -0,0 +1,188 @@
+use clap::{Parser, ValueEnum};
+use reth_db::static_file::iter_static_files;
+use reth_nippy_jar::{compression::Compressors, NippyJar, NippyJarCursor, NippyJarWriter};
+use reth_primitives::{
+    static_file::{find_fixed_range, Compression, SegmentHeader},
+    StaticFileSegment,
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{info, warn};
+
+/// The maximum number of rows of a static file used to train a zstd dictionary.
+const DICTIONARY_SAMPLE_ROWS: usize = 1000;
+
+/// The maximum size of a trained zstd dictionary.
+const MAX_DICTIONARY_SIZE: usize = 5_000_000;
+
+/// The arguments for the `reth static-file recompress` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The compression to rewrite the static files with
+    #[arg(long, value_enum)]
+    compression: Compression,
+
+    /// Only recompress the static files of these segments
+    #[arg(long = "segment", value_delimiter = ',')]
+    segments: Vec<StaticFileSegment>,
+
+    /// Also rewrite the static files that already use the compression
+    #[arg(long)]
+    force: bool,
+}
+
+impl Command {
+    /// Execute `static-file recompress` command
+    ///
+    /// Every static file is rewritten into a temporary directory first, and then moved over the
+    /// original files. The compression is stored in the configuration of each static file, so the
+    /// node reads the rewritten files without any further configuration. Static files that are
+    /// created later on use the compression configured for the node.
+    pub fn execute(self, static_files_path: PathBuf) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        let static_files = iter_static_files(&static_files_path)?;
+        let tmp_dir = static_files_path.join("recompress");
+        reth_primitives::fs::create_dir_all(&tmp_dir)?;
+
+        let segments = if self.segments.is_empty() {
+            StaticFileSegment::value_variants().to_vec()
+        } else {
+            self.segments.clone()
+        };
+
+        for segment in segments {
+            for (block_range, _) in static_files.get(&segment).into_iter().flatten() {
+                let file_name = segment.filename(&find_fixed_range(block_range.start()));
+                self.recompress_file(&static_files_path.join(&file_name), &tmp_dir)?;
+            }
+        }
+
+        reth_primitives::fs::remove_dir_all(&tmp_dir)?;
+        Ok(())
+    }
+
+    /// Rewrites the static file with the compression.
+    fn recompress_file(&self, path: &Path, tmp_dir: &Path) -> eyre::Result<()> {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let jar = NippyJar::<SegmentHeader>::load(path)?;
+
+        let current = jar_compression(&jar);
+        if current == self.compression && !self.force {
+            info!("Skipping {file_name}, it's already compressed with {}.", current.as_ref());
+            return Ok(())
+        }
+        // Filters and perfect hashing functions are stored in the index file, and can't be
+        // rebuilt from the rows alone.
+        if jar.index_path().exists() {
+            warn!("Skipping {file_name}, static files with an index file aren't supported.");
+            return Ok(())
+        }
+        if jar.rows() == 0 && self.compression == Compression::ZstdWithDictionary {
+            warn!("Skipping {file_name}, it has no rows to train the zstd dictionary on.");
+            return Ok(())
+        }
+
+        info!("Recompressing {file_name} with {}.", self.compression.as_ref());
+
+        let reader = Arc::new(jar.open_data_reader()?);
+
+        let new_jar =
+            NippyJar::new(jar.columns(), &tmp_dir.join(&file_name), jar.user_header().clone());
+        let new_jar = match self.compression {
+            Compression::Lz4 => new_jar.with_lz4(),
+            Compression::Zstd => new_jar.with_zstd(false, 0),
+            Compression::ZstdWithDictionary => {
+                // Train the dictionaries on the most recent rows, same as when creating static
+                // files from the database.
+                let mut cursor = NippyJarCursor::with_reader(&jar, reader.clone())?;
+                let mut dataset = vec![Vec::new(); jar.columns()];
+                for row in jar.rows().saturating_sub(DICTIONARY_SAMPLE_ROWS)..jar.rows() {
+                    let values = cursor
+                        .row_by_number(row)?
+                        .ok_or_else(|| eyre::eyre!("missing row {row} in {file_name}"))?;
+                    for (column, value) in values.into_iter().enumerate() {
+                        dataset[column].push(value.to_vec());
+                    }
+                }
+
+                let mut new_jar = new_jar.with_zstd(true, MAX_DICTIONARY_SIZE);
+                new_jar.prepare_compression(dataset)?;
+                new_jar
+            }
+            Compression::Uncompressed => new_jar,
+        };
+
+        // Rows are streamed from the original file, so only their offsets are kept in memory.
+        let mut writer = NippyJarWriter::new(new_jar)?;
+        let mut cursor = NippyJarCursor::with_reader(&jar, reader)?;
+        while let Some(values) = cursor.next_row()? {
+            for value in values {
+                writer.append_column(Some(Ok(value)))?;
+            }
+        }
+        writer.commit()?;
+        let new_jar = writer.into_jar();
+
+        eyre::ensure!(
+            new_jar.rows() == jar.rows(),
+            "rewrote {} rows, but {file_name} has {}",
+            new_jar.rows(),
+            jar.rows()
+        );
+
+        let size_before = reth_primitives::fs::metadata(jar.data_path())?.len();
+        let size_after = reth_primitives::fs::metadata(new_jar.data_path())?.len();
+
+        // The files are moved one by one, so interrupting the command here leaves an inconsistent
+        // static file behind.
+        for (from, to) in [
+            (new_jar.data_path().to_path_buf(), jar.data_path().to_path_buf()),
+            (new_jar.offsets_path(), jar.offsets_path()),
+            (new_jar.config_path(), jar.config_path()),
+        ] {
+            reth_primitives::fs::rename(from, to)?;
+        }
+
+        println!(
+            "{file_name}: {} -> {}, {size_before} -> {size_after} bytes",
+            current.as_ref(),
+            self.compression.as_ref()
+        );
+        Ok(())
+    }
+}
+
+/// Returns the compression the static file was created with.
+fn jar_compression(jar: &NippyJar<SegmentHeader>) -> Compression {
+    match jar.compressor() {
+        Some(Compressors::Lz4(_)) => Compression::Lz4,
+        Some(Compressors::Zstd(zstd)) if zstd.use_dict => Compression::ZstdWithDictionary,
+        Some(Compressors::Zstd(_)) => Compression::Zstd,
+        None => Compression::Uncompressed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args() {
+        let args = Command::try_parse_from([
+            "reth",
+            "--compression",
+            "none",
+            "--segment",
+            "headers,receipts",
+        ])
+        .unwrap();
+        assert_eq!(args.compression, Compression::Uncompressed);
+        assert_eq!(args.segments, vec![StaticFileSegment::Headers, StaticFileSegment::Receipts]);
+        assert!(!args.force);
+
+        assert!(Command::try_parse_from(["reth", "--compression", "brotli"]).is_err());
+    }
+}
@@ -0,0 +1,498 @@
+//! Fixtures of the encodings of the database tables and the static file segments.
+//!
+//! Every fixture holds randomly generated rows together with their exact encodings. Checking a
+//! fixture decodes the stored encodings and verifies that they decode to the stored rows and are
+//! re-encoded to the identical bytes, so a change of an encoding that would break existing data
+//! directories fails the check.
+//!
+//! The fixtures are versioned: an intended change of the encoding of a table or segment bumps its
+//! version, and the fixture of the new version is generated next to the ones of the previous
+//! versions. The fixtures are committed to `bin/reth/testdata/compat`, and every committed fixture
+//! is checked by the tests of this module.
+
+use eyre::{eyre, Result};
+use proptest::{
+    arbitrary::Arbitrary,
+    prelude::{any, ProptestConfig},
+    strategy::{Strategy, ValueTree},
+    test_runner::TestRunner,
+};
+use reth_codecs::Compact;
+use reth_db::{
+    codecs::CompactU256,
+    table::{Compress, Decode, Decompress, Encode, Table, TableRow},
+    tables,
+};
+use reth_nippy_jar::{
+    compression::{Compression, Lz4},
+    NippyJar, NippyJarCursor, NippyJarWriter,
+};
+use reth_primitives::{fs, BlockHash, Bytes, Header, Receipt, TransactionSignedNoHash};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tracing::error;
+
+/// The folder of the fixtures, relative to the manifest of the crate.
+const FIXTURES_FOLDER: &str = "testdata/compat";
+const FIXTURE_ROWS: usize = 100;
+
+/// Whether the fixtures are generated or checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FixtureMode {
+    /// Generates the missing fixtures, or all fixtures if `overwrite` is set.
+    Generate {
+        /// Whether existing fixtures are replaced.
+        overwrite: bool,
+    },
+    /// Checks the existing fixtures.
+    Check,
+}
+
+/// Generates or checks the fixtures of the specified tables and static file segments. If the list
+/// is empty, all of them are used.
+pub(crate) fn run_fixtures(mut names: Vec<String>, mode: FixtureMode) -> Result<()> {
+    let mut runner = TestRunner::new(ProptestConfig::default());
+    let mut failures = Vec::new();
+    let mut checked = 0;
+
+    macro_rules! fixtures {
+        (
+            tables: [$(($table:ident, $table_version:expr)),* $(,)?],
+            static_files: [$(($segment:ident, $row:ty, $segment_version:expr)),* $(,)?]
+        ) => {
+            if names.is_empty() {
+                names = vec![
+                    $(stringify!($table).to_string(),)*
+                    $(stringify!($segment).to_string(),)*
+                ];
+            }
+
+            for name in names {
+                let fixture = match name.as_str() {
+                    $(
+                        stringify!($table) => Fixture::<TableRowFixture>::new(
+                            fixtures_path("db", tables::$table::NAME, $table_version),
+                            tables::$table::NAME,
+                            $table_version,
+                        )
+                        .run(mode, || generate_table_rows::<tables::$table>(&mut runner), |rows| {
+                            check_table_rows::<tables::$table>(rows)
+                        }),
+                    )*
+                    $(
+                        stringify!($segment) => Fixture::<StaticFileRowFixture>::new(
+                            fixtures_path("static_files", stringify!($segment), $segment_version),
+                            stringify!($segment),
+                            $segment_version,
+                        )
+                        .run(mode, || generate_static_file_rows::<$row>(&mut runner), |rows| {
+                            check_static_file_rows::<$row>(rows)
+                        }),
+                    )*
+                    _ => {
+                        error!(target: "reth::cli", "Unknown table or segment: {}", name);
+                        continue
+                    }
+                };
+
+                checked += 1;
+                if let Err(err) = fixture {
+                    failures.push(format!("{name}: {err}"));
+                }
+            }
+        };
+    }
+
+    // Tables and segments whose encoding changes on purpose need a version bump, the Bytecodes,
+    // trie and stage checkpoint tables are not covered because their types can't be generated.
+    fixtures!(
+        tables: [
+            (CanonicalHeaders, 1),
+            (HeaderTerminalDifficulties, 1),
+            (HeaderNumbers, 1),
+            (Headers, 1),
+            (BlockBodyIndices, 1),
+            (BlockOmmers, 1),
+            (BlockWithdrawals, 1),
+            (Transactions, 1),
+            (TransactionHashNumbers, 1),
+            (TransactionBlocks, 1),
+            (Receipts, 1),
+            (PlainAccountState, 1),
+            (PlainStorageState, 1),
+            (AccountsHistory, 1),
+            (StoragesHistory, 1),
+            (CallFromIndex, 1),
+            (CallToIndex, 1),
+            (AccountChangeSets, 1),
+            (StorageChangeSets, 1),
+            (HashedAccounts, 1),
+            (HashedStorages, 1),
+            (TransactionSenders, 1),
+            (PruneCheckpoints, 1),
+            (VersionHistory, 1),
+            (Migrations, 1),
+        ],
+        static_files: [
+            (StaticFileHeaders, (Header, CompactU256, BlockHash), 1),
+            (StaticFileTransactions, TransactionSignedNoHash, 1),
+            (StaticFileReceipts, Receipt, 1),
+        ]
+    );
+
+    if !failures.is_empty() {
+        eyre::bail!(
+            "{} of {checked} fixture(s) failed:\n  {}",
+            failures.len(),
+            failures.join("\n  ")
+        )
+    }
+    Ok(())
+}
+
+/// Returns the path of the fixture of the given version.
+fn fixtures_path(kind: &str, name: &str, version: u32) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join(FIXTURES_FOLDER)
+        .join(kind)
+        .join(format!("{name}.v{version}.json"))
+}
+
+/// The rows of a table or static file segment with their encodings.
+#[derive(Debug, Serialize, Deserialize)]
+struct Fixture<Row> {
+    /// The name of the table or static file segment.
+    name: String,
+    /// The version of the encoding.
+    version: u32,
+    /// The rows with their encodings.
+    rows: Vec<Row>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl<Row: Serialize + DeserializeOwned> Fixture<Row> {
+    fn new(path: PathBuf, name: &str, version: u32) -> Self {
+        Self { name: name.to_string(), version, rows: Vec::new(), path }
+    }
+
+    /// Generates the fixture with the rows of `generate`, or checks the rows of the stored fixture
+    /// with `check`.
+    fn run(
+        mut self,
+        mode: FixtureMode,
+        generate: impl FnOnce() -> Result<Vec<Row>>,
+        check: impl FnOnce(&[Row]) -> Result<()>,
+    ) -> Result<()> {
+        match mode {
+            FixtureMode::Generate { overwrite } => {
+                if self.path.exists() && !overwrite {
+                    println!("Skipping existing fixture {}.", self.path.display());
+                    return Ok(())
+                }
+                println!("Generating fixture {}.", self.path.display());
+                self.rows = generate()?;
+                if let Some(parent) = self.path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&self.path, serde_json::to_vec_pretty(&self)?)?;
+            }
+            FixtureMode::Check => {
+                if !self.path.exists() {
+                    eyre::bail!(
+                        "fixture {} not found, generate it with `reth test-vectors fixtures`",
+                        self.path.display()
+                    )
+                }
+                let stored: Self = serde_json::from_slice(&fs::read(&self.path)?)?;
+                if (stored.name.as_str(), stored.version) != (self.name.as_str(), self.version) {
+                    eyre::bail!(
+                        "fixture {} is for {} v{}",
+                        self.path.display(),
+                        stored.name,
+                        stored.version
+                    )
+                }
+                check(&stored.rows)?;
+                println!("Checked fixture {} ({} rows).", self.path.display(), stored.rows.len());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A table row with the encoding of its key and the compressed encoding of its value.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TableRowFixture {
+    key: Value,
+    value: Value,
+    encoded_key: Bytes,
+    encoded_value: Bytes,
+}
+
+fn generate_table_rows<T>(runner: &mut TestRunner) -> Result<Vec<TableRowFixture>>
+where
+    T: Table,
+    T::Key: Arbitrary,
+    T::Value: Arbitrary,
+{
+    generate_rows::<TableRow<T>>(runner)?
+        .into_iter()
+        .map(|(key, value)| {
+            Ok(TableRowFixture {
+                key: serde_json::to_value(&key)?,
+                value: serde_json::to_value(&value)?,
+                encoded_key: Bytes::copy_from_slice(key.encode().as_ref()),
+                encoded_value: Bytes::copy_from_slice(value.compress().as_ref()),
+            })
+        })
+        .collect()
+}
+
+fn check_table_rows<T: Table>(rows: &[TableRowFixture]) -> Result<()> {
+    for (index, row) in rows.iter().enumerate() {
+        let key = T::Key::decode(&row.encoded_key)
+            .map_err(|err| eyre!("row {index}: failed to decode the key: {err}"))?;
+        let value = T::Value::decompress(&row.encoded_value)
+            .map_err(|err| eyre!("row {index}: failed to decode the value: {err}"))?;
+
+        ensure_row(index, "key", &row.key, &key, &row.encoded_key, key.clone().encode().as_ref())?;
+        let json = serde_json::to_value(&value)?;
+        ensure_row(
+            index,
+            "value",
+            &row.value,
+            &json,
+            &row.encoded_value,
+            value.compress().as_ref(),
+        )?;
+    }
+    Ok(())
+}
+
+/// A static file row with the encoding of each of its columns, as they're stored in the data file
+/// of a static file.
+#[derive(Debug, Serialize, Deserialize)]
+struct StaticFileRowFixture {
+    value: Value,
+    columns: Vec<Bytes>,
+}
+
+/// A row of a static file segment, with one column per field.
+///
+/// The columns are encoded like the static file writer encodes them before they're appended to the
+/// static file, and decoded like the static file cursor decodes them.
+trait StaticFileRow: Arbitrary + Serialize {
+    /// The number of columns of the segment.
+    const COLUMNS: usize;
+
+    fn to_columns(self) -> Vec<Vec<u8>>;
+
+    fn from_columns<C: AsRef<[u8]>>(columns: &[C]) -> Result<Self>;
+}
+
+fn encode_column<T: Compact>(column: T) -> Vec<u8> {
+    let mut buf = Vec::new();
+    column.to_compact(&mut buf);
+    buf
+}
+
+fn decode_column<T: Decompress, C: AsRef<[u8]>>(columns: &[C], index: usize) -> Result<T> {
+    let column = columns.get(index).ok_or_else(|| eyre!("missing column {index}"))?;
+    T::decompress(column).map_err(|err| eyre!("failed to decode column {index}: {err}"))
+}
+
+impl StaticFileRow for (Header, CompactU256, BlockHash) {
+    const COLUMNS: usize = 3;
+
+    fn to_columns(self) -> Vec<Vec<u8>> {
+        let (header, terminal_difficulty, hash) = self;
+        vec![encode_column(header), encode_column(terminal_difficulty), encode_column(hash)]
+    }
+
+    fn from_columns<C: AsRef<[u8]>>(columns: &[C]) -> Result<Self> {
+        Ok((decode_column(columns, 0)?, decode_column(columns, 1)?, decode_column(columns, 2)?))
+    }
+}
+
+impl StaticFileRow for TransactionSignedNoHash {
+    const COLUMNS: usize = 1;
+
+    fn to_columns(self) -> Vec<Vec<u8>> {
+        vec![encode_column(self)]
+    }
+
+    fn from_columns<C: AsRef<[u8]>>(columns: &[C]) -> Result<Self> {
+        decode_column(columns, 0)
+    }
+}
+
+impl StaticFileRow for Receipt {
+    const COLUMNS: usize = 1;
+
+    fn to_columns(self) -> Vec<Vec<u8>> {
+        vec![encode_column(self)]
+    }
+
+    fn from_columns<C: AsRef<[u8]>>(columns: &[C]) -> Result<Self> {
+        decode_column(columns, 0)
+    }
+}
+
+/// Compresses the columns with the default compression of the static file segments.
+fn compress_columns(columns: Vec<Vec<u8>>) -> Result<Vec<Bytes>> {
+    columns.into_iter().map(|column| Ok(Lz4::default().compress(&column)?.into())).collect()
+}
+
+fn generate_static_file_rows<R: StaticFileRow>(
+    runner: &mut TestRunner,
+) -> Result<Vec<StaticFileRowFixture>> {
+    generate_rows::<R>(runner)?
+        .into_iter()
+        .map(|row| {
+            Ok(StaticFileRowFixture {
+                value: serde_json::to_value(&row)?,
+                columns: compress_columns(row.to_columns())?,
+            })
+        })
+        .collect()
+}
+
+/// Checks that the stored columns decode to the stored rows and are re-encoded to the same bytes,
+/// and that the rows are stored with the same bytes in a static file and read back from it.
+fn check_static_file_rows<R: StaticFileRow>(rows: &[StaticFileRowFixture]) -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("static_file");
+    let mut writer =
+        NippyJarWriter::new(NippyJar::new_without_header(R::COLUMNS, &path).with_lz4())?;
+
+    for (index, row) in rows.iter().enumerate() {
+        let columns = row
+            .columns
+            .iter()
+            .map(|column| Lz4::default().decompress(column))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| eyre!("row {index}: failed to decompress the columns: {err}"))?;
+        let decoded = R::from_columns(&columns).map_err(|err| eyre!("row {index}: {err}"))?;
+        let json = serde_json::to_value(&decoded)?;
+        if json != row.value {
+            eyre::bail!("row {index}: the columns decode to {json}, expected {}", row.value)
+        }
+        if compress_columns(decoded.to_columns())? != row.columns {
+            eyre::bail!("row {index}: the decoded columns are re-encoded to different bytes")
+        }
+        for column in columns {
+            writer.append_column(Some(Ok(column)))?;
+        }
+    }
+    writer.commit()?;
+    drop(writer);
+
+    // the data file of a static file is made of the compressed columns of its rows
+    let stored =
+        rows.iter().flat_map(|row| row.columns.iter().flat_map(|column| column.iter().copied()));
+    if !fs::read(&path)?.into_iter().eq(stored) {
+        eyre::bail!("the rows are stored with different bytes in a static file")
+    }
+
+    let jar = NippyJar::load_without_header(&path)?;
+    let mut cursor = NippyJarCursor::new(&jar)?;
+    for (index, row) in rows.iter().enumerate() {
+        let columns = cursor
+            .row_by_number(index)?
+            .ok_or_else(|| eyre!("row {index}: missing in the static file"))?;
+        let json = serde_json::to_value(&R::from_columns(&columns)?)?;
+        if json != row.value {
+            eyre::bail!(
+                "row {index}: the static file row decodes to {json}, expected {}",
+                row.value
+            )
+        }
+    }
+    Ok(())
+}
+
+/// Generates [FIXTURE_ROWS] random rows.
+fn generate_rows<R: Arbitrary>(runner: &mut TestRunner) -> Result<Vec<R>> {
+    Ok(proptest::collection::vec(any::<R>(), FIXTURE_ROWS)
+        .no_shrink()
+        .new_tree(runner)
+        .map_err(|err| eyre!("{err}"))?
+        .current())
+}
+
+/// Ensures that the decoded part of a row matches the stored one and is re-encoded to the stored
+/// bytes.
+fn ensure_row<T: Serialize>(
+    index: usize,
+    part: &str,
+    expected: &Value,
+    decoded: &T,
+    encoded: &[u8],
+    reencoded: &[u8],
+) -> Result<()> {
+    let decoded = serde_json::to_value(decoded)?;
+    if decoded != *expected {
+        eyre::bail!("row {index}: the {part} decodes to {decoded}, expected {expected}")
+    }
+    if encoded != reencoded {
+        eyre::bail!(
+            "row {index}: the {part} is re-encoded to {}, expected {}",
+            Bytes::copy_from_slice(reencoded),
+            Bytes::copy_from_slice(encoded)
+        )
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_generated_rows() {
+        let mut runner = TestRunner::new(ProptestConfig::default());
+
+        let rows = generate_table_rows::<tables::Headers>(&mut runner).unwrap();
+        assert_eq!(rows.len(), FIXTURE_ROWS);
+        check_table_rows::<tables::Headers>(&rows).unwrap();
+
+        let rows =
+            generate_static_file_rows::<(Header, CompactU256, BlockHash)>(&mut runner).unwrap();
+        check_static_file_rows::<(Header, CompactU256, BlockHash)>(&rows).unwrap();
+    }
+
+    #[test]
+    fn check_detects_changed_encoding() {
+        let mut runner = TestRunner::new(ProptestConfig::default());
+
+        let mut rows = generate_table_rows::<tables::TransactionBlocks>(&mut runner).unwrap();
+        rows[0].encoded_value = Bytes::from_static(&[0xff; 9]);
+        assert!(check_table_rows::<tables::TransactionBlocks>(&rows).is_err());
+
+        let mut rows = generate_static_file_rows::<Receipt>(&mut runner).unwrap();
+        rows[0].value = Value::Null;
+        assert!(check_static_file_rows::<Receipt>(&rows).is_err());
+    }
+
+    #[test]
+    fn check_committed_fixtures() {
+        let mut names = Vec::new();
+        for kind in ["db", "static_files"] {
+            let folder = Path::new(env!("CARGO_MANIFEST_DIR")).join(FIXTURES_FOLDER).join(kind);
+            let Ok(entries) = std::fs::read_dir(folder) else { continue };
+            for entry in entries {
+                let file_name = entry.unwrap().file_name().into_string().unwrap();
+                let (name, _) = file_name.split_once(".v").unwrap();
+                if !names.iter().any(|existing| existing == name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+        assert!(!names.is_empty());
+
+        run_fixtures(names, FixtureMode::Check).unwrap();
+    }
+}
@@ -2,6 +2,7 @@
 
 use clap::{Parser, Subcommand};
 
+mod compat;
 mod tables;
 
 /// Generate test-vectors for different data types.
@@ -19,6 +20,20 @@ pub enum Subcommands {
         /// List of table names. Case-sensitive.
         names: Vec<String>,
     },
+    /// Generates the encoding fixtures of the specified tables and static file segments, which
+    /// are checked against the current encodings with `--check`. If no name is specified, all
+    /// tables and segments are used.
+    Fixtures {
+        /// List of table names and static file segments (`StaticFileHeaders`,
+        /// `StaticFileTransactions` and `StaticFileReceipts`). Case-sensitive.
+        names: Vec<String>,
+        /// Checks that the existing fixtures are decoded and re-encoded to the identical bytes.
+        #[arg(long, conflicts_with = "overwrite")]
+        check: bool,
+        /// Replaces the existing fixtures of the current versions.
+        #[arg(long)]
+        overwrite: bool,
+    },
 }
 
 impl Command {
@@ -28,6 +43,14 @@ impl Command {
             Subcommands::Tables { names } => {
                 tables::generate_vectors(names)?;
             }
+            Subcommands::Fixtures { names, check, overwrite } => {
+                let mode = if check {
+                    compat::FixtureMode::Check
+                } else {
+                    compat::FixtureMode::Generate { overwrite }
+                };
+                compat::run_fixtures(names, mode)?;
+            }
         }
         Ok(())
     }
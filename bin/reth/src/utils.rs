@@ -7,7 +7,7 @@ use reth_db::{
     database::Database,
     table::{Decode, Decompress, DupSort, Table, TableRow},
     transaction::{DbTx, DbTxMut},
-    DatabaseError, RawTable, TableRawRow,
+    DatabaseError, RawDupSort, RawKey, RawTable, TableRawRow,
 };
 use reth_primitives::{fs, ChainSpec};
 use reth_provider::ProviderFactory;
@@ -130,6 +130,39 @@ impl<DB: Database> DbTool<DB> {
             .map_err(|e| eyre::eyre!(e))
     }
 
+    /// Grabs up to `count` consecutive entries of the table, starting at the given key, or the
+    /// next key after it. If `reverse` is set, the walk goes backwards and starts at the given
+    /// key, or the last key before it.
+    pub fn walk<T: Table>(
+        &self,
+        key: T::Key,
+        count: usize,
+        reverse: bool,
+    ) -> Result<Vec<TableRow<T>>> {
+        self.provider_factory
+            .db_ref()
+            .view(|tx| walk_table::<T>(tx, key, count, reverse))?
+            .map_err(|e| eyre::eyre!(e))
+    }
+
+    /// Grabs up to `count` consecutive entries of the DupSort table, starting at the given key
+    /// and subkey, or the next entry after them. If `reverse` is set, the walk goes backwards and
+    /// starts at the given key and subkey, or the last entry before them.
+    ///
+    /// All duplicates of a key are returned as separate entries.
+    pub fn walk_dup<T: DupSort>(
+        &self,
+        key: T::Key,
+        subkey: T::SubKey,
+        count: usize,
+        reverse: bool,
+    ) -> Result<Vec<TableRow<T>>> {
+        self.provider_factory
+            .db_ref()
+            .view(|tx| walk_dup_table::<T>(tx, key, subkey, count, reverse))?
+            .map_err(|e| eyre::eyre!(e))
+    }
+
     /// Drops the database and the static files at the given path.
     pub fn drop(
         &mut self,
@@ -155,6 +188,85 @@ impl<DB: Database> DbTool<DB> {
     }
 }
 
+/// Walks the table for [DbTool::walk].
+fn walk_table<T: Table>(
+    tx: &impl DbTx,
+    key: T::Key,
+    count: usize,
+    reverse: bool,
+) -> Result<Vec<TableRow<T>>, DatabaseError> {
+    let mut cursor = tx.cursor_read::<T>()?;
+    let start = match cursor.seek(key.clone())? {
+        Some((found, value)) if !reverse || found == key => Some((found, value)),
+        // walking backwards starts at the last key before the given one
+        Some(_) => cursor.prev()?,
+        None if reverse => cursor.last()?,
+        None => None,
+    };
+    collect_rows(&mut cursor, start, count, reverse)
+}
+
+/// Walks the DupSort table for [DbTool::walk_dup].
+///
+/// The raw table is walked, so that an exact subkey match can be told apart from the next
+/// duplicate after it.
+fn walk_dup_table<T: DupSort>(
+    tx: &impl DbTx,
+    key: T::Key,
+    subkey: T::SubKey,
+    count: usize,
+    reverse: bool,
+) -> Result<Vec<TableRow<T>>, DatabaseError> {
+    let mut cursor = tx.cursor_dup_read::<RawDupSort<T>>()?;
+    let key = RawKey::new(key);
+    let subkey = RawKey::<T::SubKey>::new(subkey);
+    let prefix = subkey.raw_key().clone();
+    let start = match cursor.seek_by_key_subkey(key.clone(), subkey)? {
+        Some(value) if !reverse || value.raw_value().starts_with(&prefix) => Some((key, value)),
+        // walking backwards starts at the last duplicate before the subkey
+        Some(_) => cursor.prev()?,
+        None => {
+            // no duplicate at or after the subkey, find the first entry of the next key
+            let next = match cursor.seek(key.clone())? {
+                Some((found, _)) if found == key => cursor.next_no_dup()?,
+                row => row,
+            };
+            match next {
+                Some(_) if reverse => cursor.prev()?,
+                None if reverse => cursor.last()?,
+                row => row,
+            }
+        }
+    };
+    collect_rows(&mut cursor, start, count, reverse)?
+        .into_iter()
+        .map(|(key, value)| Ok((key.key()?, value.value()?)))
+        .collect()
+}
+
+/// Collects up to `count` rows from the cursor, beginning with `start` and then moving the cursor
+/// forward, or backward if `reverse` is set.
+fn collect_rows<T: Table>(
+    cursor: &mut impl DbCursorRO<T>,
+    start: Option<TableRow<T>>,
+    count: usize,
+    reverse: bool,
+) -> Result<Vec<TableRow<T>>, DatabaseError> {
+    let mut rows = Vec::new();
+    let mut row = if count > 0 { start } else { None };
+    while let Some(entry) = row {
+        rows.push(entry);
+        row = if rows.len() == count {
+            None
+        } else if reverse {
+            cursor.prev()?
+        } else {
+            cursor.next()?
+        };
+    }
+    Ok(rows)
+}
+
 /// Filters the results coming from the database.
 #[derive(Debug)]
 pub struct ListFilter {
@@ -188,3 +300,70 @@ impl ListFilter {
         self.len = len;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{test_utils::create_test_rw_db, CanonicalHeaders, PlainStorageState};
+    use reth_primitives::{Address, StorageEntry, B256, U256};
+
+    #[test]
+    fn walk_bounds() {
+        let db = create_test_rw_db();
+        db.update(|tx| {
+            for number in [1u64, 3, 5] {
+                tx.put::<CanonicalHeaders>(number, B256::with_last_byte(number as u8)).unwrap();
+            }
+        })
+        .unwrap();
+
+        let tx = db.tx().unwrap();
+        let walk = |key, reverse| {
+            walk_table::<CanonicalHeaders>(&tx, key, 10, reverse)
+                .unwrap()
+                .into_iter()
+                .map(|(number, _)| number)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(walk(3, true), vec![3, 1]);
+        assert_eq!(walk(4, true), vec![3, 1]);
+        assert_eq!(walk(6, true), vec![5, 3, 1]);
+        assert_eq!(walk(0, true), Vec::<u64>::new());
+        assert_eq!(walk(3, false), vec![3, 5]);
+        assert_eq!(walk(4, false), vec![5]);
+        assert_eq!(walk(6, false), Vec::<u64>::new());
+    }
+
+    #[test]
+    fn walk_dup_bounds() {
+        let (a, b, c) =
+            (Address::with_last_byte(1), Address::with_last_byte(2), Address::with_last_byte(3));
+        let slot = B256::with_last_byte;
+        let db = create_test_rw_db();
+        db.update(|tx| {
+            for (address, key) in [(a, slot(1)), (a, slot(3)), (b, slot(2))] {
+                tx.put::<PlainStorageState>(address, StorageEntry { key, value: U256::from(1) })
+                    .unwrap();
+            }
+        })
+        .unwrap();
+
+        let tx = db.tx().unwrap();
+        let walk = |address, subkey, reverse| {
+            walk_dup_table::<PlainStorageState>(&tx, address, subkey, 10, reverse)
+                .unwrap()
+                .into_iter()
+                .map(|(address, entry)| (address, entry.key))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(walk(a, slot(3), true), vec![(a, slot(3)), (a, slot(1))]);
+        assert_eq!(walk(a, slot(2), true), vec![(a, slot(1))]);
+        assert_eq!(walk(a, slot(4), true), vec![(a, slot(3)), (a, slot(1))]);
+        assert_eq!(walk(b, slot(1), true), vec![(a, slot(3)), (a, slot(1))]);
+        assert_eq!(walk(c, slot(0), true), vec![(b, slot(2)), (a, slot(3)), (a, slot(1))]);
+        assert_eq!(walk(a, slot(0), true), vec![]);
+        assert_eq!(walk(a, slot(2), false), vec![(a, slot(3)), (b, slot(2))]);
+        assert_eq!(walk(a, slot(4), false), vec![(b, slot(2))]);
+        assert_eq!(walk(c, slot(0), false), vec![]);
+    }
+}
@@ -1,10 +1,6 @@
 use crate::metrics::BlockBufferMetrics;
-use lru::LruCache;
 use reth_primitives::{BlockHash, BlockNumber, SealedBlockWithSenders};
-use std::{
-    collections::{btree_map, hash_map, BTreeMap, HashMap, HashSet},
-    num::NonZeroUsize,
-};
+use std::collections::{btree_map, hash_map, BTreeMap, HashMap, HashSet};
 
 /// Contains the tree of pending blocks that cannot be executed due to missing parent.
 /// It allows to store unconnected blocks for potential future inclusion.
@@ -14,9 +10,13 @@ use std::{
 /// * [BlockBuffer::remove_block_with_children] for connecting blocks if the parent gets received
 ///   and inserted.
 /// * [BlockBuffer::remove_old_blocks] to remove old blocks that precede the finalized number.
+/// * [BlockBuffer::remove_blocks_below_head] to remove the blocks that are not above the canonical
+///   head, once the buffered blocks were connected to the canonical chain.
 ///
-/// Note: Buffer is limited by number of blocks that it can contain and eviction of the block
-/// is done by last recently used block.
+/// Note: Buffer is limited by number of blocks that it can contain and by their in-memory size. If
+/// a limit is hit, the block that is the farthest from the canonical head is evicted, so the
+/// blocks that are about to be connected are kept when blocks far ahead of the head are received.
+/// Of the blocks at the same distance, the least recently inserted block is evicted.
 #[derive(Debug)]
 pub struct BlockBuffer {
     /// All blocks in the buffer stored by their block hash.
@@ -28,11 +28,22 @@ pub struct BlockBuffer {
     /// BTreeMap tracking the earliest blocks by block number.
     /// Used for removal of old blocks that precede finalization.
     pub(crate) earliest_blocks: BTreeMap<BlockNumber, HashSet<BlockHash>>,
-    /// LRU used for tracing oldest inserted blocks that are going to be
-    /// first in line for evicting if `max_blocks` limit is hit.
+    /// The buffered blocks ordered by their number and then by the order in which they were
+    /// inserted, so the block to evict is found without going through the whole buffer.
     ///
-    /// Used as counter of amount of blocks inside buffer.
-    pub(crate) lru: LruCache<BlockHash, ()>,
+    /// The farthest block from the canonical head is either the first block or the first block
+    /// of the highest number, the least recently inserted one is evicted at the same distance.
+    pub(crate) eviction_order: BTreeMap<(BlockNumber, u64), BlockHash>,
+    /// The insertion sequence number of every buffered block, its key in `eviction_order`.
+    pub(crate) insertions: HashMap<BlockHash, u64>,
+    /// The sequence number of the next inserted block.
+    pub(crate) next_insertion: u64,
+    /// The maximum number of blocks in the buffer.
+    pub(crate) limit: usize,
+    /// The maximum in-memory size of the blocks in the buffer, in bytes.
+    pub(crate) max_size: usize,
+    /// The in-memory size of the blocks in the buffer, in bytes.
+    pub(crate) size: usize,
     /// Various metrics for the block buffer.
     pub(crate) metrics: BlockBufferMetrics,
 }
@@ -44,11 +55,22 @@ impl BlockBuffer {
             blocks: Default::default(),
             parent_to_child: Default::default(),
             earliest_blocks: Default::default(),
-            lru: LruCache::new(NonZeroUsize::new(limit).unwrap()),
+            eviction_order: Default::default(),
+            insertions: Default::default(),
+            next_insertion: 0,
+            limit,
+            max_size: usize::MAX,
+            size: 0,
             metrics: Default::default(),
         }
     }
 
+    /// Set the maximum in-memory size of the blocks in the buffer, in bytes.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
     /// Return reference to buffered blocks
     pub fn blocks(&self) -> &HashMap<BlockHash, SealedBlockWithSenders> {
         &self.blocks
@@ -69,21 +91,31 @@ impl BlockBuffer {
     }
 
     /// Insert a correct block inside the buffer.
-    pub fn insert_block(&mut self, block: SealedBlockWithSenders) {
+    ///
+    /// If a limit of the buffer is hit, the blocks that are the farthest from the given canonical
+    /// head are evicted. The inserted block itself is never evicted.
+    pub fn insert_block(&mut self, block: SealedBlockWithSenders, canonical_head: BlockNumber) {
         let hash = block.hash();
 
         self.parent_to_child.entry(block.parent_hash).or_default().insert(hash);
         self.earliest_blocks.entry(block.number).or_default().insert(hash);
-        self.blocks.insert(hash, block);
+        let insertion = self.next_insertion;
+        self.next_insertion += 1;
+        if let Some(previous) = self.insertions.insert(hash, insertion) {
+            self.eviction_order.remove(&(block.number, previous));
+        }
+        self.eviction_order.insert((block.number, insertion), hash);
+        self.size += block.block.size();
+        if let Some(replaced) = self.blocks.insert(hash, block) {
+            self.size -= replaced.block.size();
+        }
 
-        if let Some((evicted_hash, _)) = self.lru.push(hash, ()).filter(|(b, _)| *b != hash) {
-            // evict the block if limit is hit
-            if let Some(evicted_block) = self.remove_block(&evicted_hash) {
-                // evict the block if limit is hit
-                self.remove_from_parent(evicted_block.parent_hash, &evicted_hash);
-            }
+        while self.blocks.len() > self.limit || self.size > self.max_size {
+            let Some(evicted_hash) = self.farthest_block(&hash, canonical_head) else { break };
+            self.remove_block(&evicted_hash);
+            self.metrics.evicted_over_capacity.increment(1);
         }
-        self.metrics.blocks.set(self.blocks.len() as f64);
+        self.update_metrics();
     }
 
     /// Removes the given block from the buffer and also all the children of the block.
@@ -100,17 +132,36 @@ impl BlockBuffer {
         let mut removed = self.remove_block(parent_hash).into_iter().collect::<Vec<_>>();
 
         removed.extend(self.remove_children(vec![*parent_hash]));
-        self.metrics.blocks.set(self.blocks.len() as f64);
+        self.update_metrics();
         removed
     }
 
     /// Discard all blocks that precede finalized block number from the buffer.
     pub fn remove_old_blocks(&mut self, finalized_number: BlockNumber) {
+        let removed = self.remove_blocks_up_to(finalized_number);
+        self.metrics.evicted_finalized.increment(removed as u64);
+        self.update_metrics();
+    }
+
+    /// Discard all blocks at or below the canonical head from the buffer, including their
+    /// descendants.
+    ///
+    /// This is used once the buffered blocks were connected to the canonical chain, so the
+    /// remaining blocks at or below the head are on forks that didn't connect.
+    pub fn remove_blocks_below_head(&mut self, canonical_head: BlockNumber) {
+        let removed = self.remove_blocks_up_to(canonical_head);
+        self.metrics.evicted_below_head.increment(removed as u64);
+        self.update_metrics();
+    }
+
+    /// Removes all blocks up to and including the given block number and their descendants,
+    /// returning the number of removed blocks.
+    fn remove_blocks_up_to(&mut self, number: BlockNumber) -> usize {
         let mut block_hashes_to_remove = Vec::new();
 
-        // discard all blocks that are before the finalized number.
+        // discard all blocks that are before the number.
         while let Some(entry) = self.earliest_blocks.first_entry() {
-            if *entry.key() > finalized_number {
+            if *entry.key() > number {
                 break
             }
             let block_hashes = entry.remove();
@@ -118,13 +169,48 @@ impl BlockBuffer {
         }
 
         // remove from other collections.
+        let mut removed = 0;
         for block_hash in &block_hashes_to_remove {
             // It's fine to call
-            self.remove_block(block_hash);
+            if self.remove_block(block_hash).is_some() {
+                removed += 1;
+            }
         }
 
-        self.remove_children(block_hashes_to_remove);
+        removed + self.remove_children(block_hashes_to_remove).len()
+    }
+
+    /// Returns the hash of the block that is the farthest from the canonical head, ignoring the
+    /// given block.
+    ///
+    /// Of the blocks at the same distance, the least recently inserted block is returned.
+    fn farthest_block(&self, except: &BlockHash, canonical_head: BlockNumber) -> Option<BlockHash> {
+        let (&(lowest, lowest_insertion), &lowest_hash) =
+            self.eviction_order.iter().find(|(_, hash)| *hash != except)?;
+        let (&(highest_number, _), _) =
+            self.eviction_order.iter().rev().find(|(_, hash)| *hash != except)?;
+        let (&(highest, highest_insertion), &highest_hash) =
+            self.eviction_order.range((highest_number, 0)..).find(|(_, hash)| *hash != except)?;
+
+        let lowest_distance = lowest.abs_diff(canonical_head);
+        let highest_distance = highest.abs_diff(canonical_head);
+        if lowest_distance > highest_distance ||
+            (lowest_distance == highest_distance && lowest_insertion < highest_insertion)
+        {
+            Some(lowest_hash)
+        } else {
+            Some(highest_hash)
+        }
+    }
+
+    /// Updates the metrics of the number, size and heights of the buffered blocks.
+    fn update_metrics(&self) {
         self.metrics.blocks.set(self.blocks.len() as f64);
+        self.metrics.size.set(self.size as f64);
+        let lowest = self.earliest_blocks.keys().next().copied().unwrap_or_default();
+        let highest = self.earliest_blocks.keys().next_back().copied().unwrap_or_default();
+        self.metrics.lowest_block_number.set(lowest as f64);
+        self.metrics.highest_block_number.set(highest as f64);
     }
 
     /// Remove block entry
@@ -155,9 +241,12 @@ impl BlockBuffer {
     /// been removed.
     fn remove_block(&mut self, hash: &BlockHash) -> Option<SealedBlockWithSenders> {
         let block = self.blocks.remove(hash)?;
+        self.size -= block.block.size();
         self.remove_from_earliest_blocks(block.number, hash);
         self.remove_from_parent(block.parent_hash, hash);
-        self.lru.pop(hash);
+        if let Some(insertion) = self.insertions.remove(hash) {
+            self.eviction_order.remove(&(block.number, insertion));
+        }
         Some(block)
     }
 
@@ -190,9 +279,12 @@ mod tests {
         generators,
         generators::{random_block, Rng},
     };
-    use reth_primitives::{BlockHash, BlockNumHash, SealedBlockWithSenders};
+    use reth_primitives::{BlockHash, BlockNumHash, BlockNumber, SealedBlockWithSenders};
     use std::collections::HashMap;
 
+    /// The number of the canonical head the blocks are buffered for.
+    const CANONICAL_HEAD: BlockNumber = 9;
+
     /// Create random block with specified number and parent hash.
     fn create_block<R: Rng>(rng: &mut R, number: u64, parent: BlockHash) -> SealedBlockWithSenders {
         let block = random_block(rng, number, Some(parent), None, None);
@@ -202,7 +294,8 @@ mod tests {
     /// Assert that all buffer collections have the same data length.
     fn assert_buffer_lengths(buffer: &BlockBuffer, expected: usize) {
         assert_eq!(buffer.blocks.len(), expected);
-        assert_eq!(buffer.lru.len(), expected);
+        assert_eq!(buffer.eviction_order.len(), expected);
+        assert_eq!(buffer.insertions.len(), expected);
         assert_eq!(
             buffer.parent_to_child.iter().fold(0, |acc, (_, hashes)| acc + hashes.len()),
             expected
@@ -235,7 +328,7 @@ mod tests {
         let block1 = create_block(&mut rng, 10, parent);
         let mut buffer = BlockBuffer::new(3);
 
-        buffer.insert_block(block1.clone());
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
         assert_buffer_lengths(&buffer, 1);
         assert_eq!(buffer.block(&block1.hash()), Some(&block1));
     }
@@ -253,10 +346,10 @@ mod tests {
 
         let mut buffer = BlockBuffer::new(5);
 
-        buffer.insert_block(block1.clone());
-        buffer.insert_block(block2.clone());
-        buffer.insert_block(block3.clone());
-        buffer.insert_block(block4.clone());
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block3.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block4.clone(), CANONICAL_HEAD);
 
         assert_buffer_lengths(&buffer, 4);
         assert_eq!(buffer.block(&block4.hash()), Some(&block4));
@@ -285,10 +378,10 @@ mod tests {
 
         let mut buffer = BlockBuffer::new(5);
 
-        buffer.insert_block(block1.clone());
-        buffer.insert_block(block2.clone());
-        buffer.insert_block(block3.clone());
-        buffer.insert_block(block4.clone());
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block3.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block4.clone(), CANONICAL_HEAD);
 
         assert_buffer_lengths(&buffer, 4);
         assert_eq!(
@@ -319,10 +412,10 @@ mod tests {
 
         let mut buffer = BlockBuffer::new(5);
 
-        buffer.insert_block(block1.clone());
-        buffer.insert_block(block2.clone());
-        buffer.insert_block(block3.clone());
-        buffer.insert_block(block4.clone());
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block3.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block4.clone(), CANONICAL_HEAD);
 
         assert_buffer_lengths(&buffer, 4);
         assert_eq!(
@@ -354,10 +447,10 @@ mod tests {
 
         let mut buffer = BlockBuffer::new(5);
 
-        buffer.insert_block(block1.clone());
-        buffer.insert_block(block2);
-        buffer.insert_block(block3);
-        buffer.insert_block(block4);
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2, CANONICAL_HEAD);
+        buffer.insert_block(block3, CANONICAL_HEAD);
+        buffer.insert_block(block4, CANONICAL_HEAD);
 
         assert_buffer_lengths(&buffer, 4);
         buffer.remove_old_blocks(block1.number);
@@ -376,16 +469,39 @@ mod tests {
 
         let mut buffer = BlockBuffer::new(5);
 
-        buffer.insert_block(block1.clone());
-        buffer.insert_block(block2);
-        buffer.insert_block(block3);
-        buffer.insert_block(block4);
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2, CANONICAL_HEAD);
+        buffer.insert_block(block3, CANONICAL_HEAD);
+        buffer.insert_block(block4, CANONICAL_HEAD);
 
         assert_buffer_lengths(&buffer, 4);
         buffer.remove_old_blocks(block1.number);
         assert_buffer_lengths(&buffer, 0);
     }
 
+    #[test]
+    fn remove_blocks_below_canonical_head() {
+        let mut rng = generators::rng();
+
+        let (parent1, parent3) = (rng.gen(), rng.gen());
+        let block1 = create_block(&mut rng, 9, parent1);
+        let block2 = create_block(&mut rng, 10, block1.hash());
+        let block3 = create_block(&mut rng, 10, parent3);
+
+        let mut buffer = BlockBuffer::new(5);
+
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block3.clone(), CANONICAL_HEAD);
+
+        // the fork at the head is removed with its descendants, the blocks above are kept
+        buffer.remove_blocks_below_head(CANONICAL_HEAD);
+        assert_buffer_lengths(&buffer, 1);
+        assert_block_removal(&buffer, &block1);
+        assert_block_removal(&buffer, &block2);
+        assert_eq!(buffer.block(&block3.hash()), Some(&block3));
+    }
+
     #[test]
     fn remove_multi_chains() {
         let mut rng = generators::rng();
@@ -404,13 +520,13 @@ mod tests {
 
         let mut buffer = BlockBuffer::new(10);
 
-        buffer.insert_block(block1.clone());
-        buffer.insert_block(block1a.clone());
-        buffer.insert_block(block2.clone());
-        buffer.insert_block(block2a.clone());
-        buffer.insert_block(random_block1.clone());
-        buffer.insert_block(random_block2.clone());
-        buffer.insert_block(random_block3.clone());
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block1a.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2a.clone(), CANONICAL_HEAD);
+        buffer.insert_block(random_block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(random_block2.clone(), CANONICAL_HEAD);
+        buffer.insert_block(random_block3.clone(), CANONICAL_HEAD);
 
         // check that random blocks are their own ancestor, and that chains have proper ancestors
         assert_eq!(buffer.lowest_ancestor(&random_block1.hash()), Some(&random_block1));
@@ -443,16 +559,17 @@ mod tests {
 
         let mut buffer = BlockBuffer::new(3);
 
-        buffer.insert_block(block1.clone());
-        buffer.insert_block(block2.clone());
-        buffer.insert_block(block3.clone());
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block3.clone(), CANONICAL_HEAD);
 
         // pre-eviction block1 is the root
         assert_eq!(buffer.lowest_ancestor(&block3.hash()), Some(&block1));
         assert_eq!(buffer.lowest_ancestor(&block2.hash()), Some(&block1));
         assert_eq!(buffer.lowest_ancestor(&block1.hash()), Some(&block1));
 
-        buffer.insert_block(block4.clone());
+        // the canonical head is above the buffered blocks, so block1 is the farthest from it
+        buffer.insert_block(block4.clone(), 13);
 
         assert_eq!(buffer.lowest_ancestor(&block4.hash()), Some(&block4));
 
@@ -480,14 +597,108 @@ mod tests {
 
         let mut buffer = BlockBuffer::new(3);
 
-        buffer.insert_block(block1.clone());
-        buffer.insert_block(block2);
-        buffer.insert_block(block3);
-        buffer.insert_block(block4);
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(block2, CANONICAL_HEAD);
+        buffer.insert_block(block3, CANONICAL_HEAD);
+        buffer.insert_block(block4, CANONICAL_HEAD);
 
-        // block3 gets evicted
-        assert_block_removal(&buffer, &block1);
+        // block3 is the farthest from the canonical head, block4 is never evicted as the inserted
+        // block
+        assert_block_removal(&buffer, &block3);
+        assert_eq!(buffer.lowest_ancestor(&block2.hash()), Some(&block1));
 
         assert_buffer_lengths(&buffer, 3);
     }
+
+    #[test]
+    fn evict_farthest_from_canonical_head() {
+        let mut rng = generators::rng();
+
+        let main_parent = BlockNumHash::new(CANONICAL_HEAD, rng.gen());
+        let block1 = create_block(&mut rng, 10, main_parent.hash);
+        let block2 = create_block(&mut rng, 11, block1.hash());
+        let far_parent1 = rng.gen();
+        let far_block1 = create_block(&mut rng, 100, far_parent1);
+        let far_parent2 = rng.gen();
+        let far_block2 = create_block(&mut rng, 101, far_parent2);
+        let far_parent3 = rng.gen();
+        let far_block3 = create_block(&mut rng, 101, far_parent3);
+
+        let mut buffer = BlockBuffer::new(3);
+
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(far_block2.clone(), CANONICAL_HEAD);
+        buffer.insert_block(far_block3.clone(), CANONICAL_HEAD);
+
+        // the far blocks are evicted before the blocks that connect to the canonical head, and of
+        // the blocks at the same distance the least recently inserted one is evicted
+        buffer.insert_block(block2.clone(), CANONICAL_HEAD);
+        assert_block_removal(&buffer, &far_block2);
+        assert_eq!(buffer.lowest_ancestor(&block2.hash()), Some(&block1));
+
+        // the inserted block is kept even if it's the farthest
+        buffer.insert_block(far_block1.clone(), CANONICAL_HEAD);
+        assert_block_removal(&buffer, &far_block3);
+        assert_eq!(buffer.block(&far_block1.hash()), Some(&far_block1));
+
+        assert_eq!(buffer.remove_block_with_children(&main_parent.hash), vec![block1, block2]);
+        assert_buffer_lengths(&buffer, 1);
+    }
+
+    #[test]
+    fn evict_least_recently_inserted_at_same_distance() {
+        let mut rng = generators::rng();
+
+        let below_parent = rng.gen();
+        let below_block = create_block(&mut rng, CANONICAL_HEAD - 5, below_parent);
+        let above_parent = rng.gen();
+        let above_block = create_block(&mut rng, CANONICAL_HEAD + 5, above_parent);
+        let near_parent = rng.gen();
+        let near_block = create_block(&mut rng, CANONICAL_HEAD + 1, near_parent);
+
+        let mut buffer = BlockBuffer::new(2);
+
+        // the blocks below and above the head are at the same distance, the one below was
+        // inserted first
+        buffer.insert_block(below_block.clone(), CANONICAL_HEAD);
+        buffer.insert_block(above_block.clone(), CANONICAL_HEAD);
+        buffer.insert_block(near_block.clone(), CANONICAL_HEAD);
+        assert_block_removal(&buffer, &below_block);
+        assert_buffer_lengths(&buffer, 2);
+
+        // re-inserting a block makes it the most recently inserted one
+        let mut buffer = BlockBuffer::new(2);
+        buffer.insert_block(below_block.clone(), CANONICAL_HEAD);
+        buffer.insert_block(above_block.clone(), CANONICAL_HEAD);
+        buffer.insert_block(below_block.clone(), CANONICAL_HEAD);
+        buffer.insert_block(near_block, CANONICAL_HEAD);
+        assert_block_removal(&buffer, &above_block);
+        assert_eq!(buffer.block(&below_block.hash()), Some(&below_block));
+        assert_buffer_lengths(&buffer, 2);
+    }
+
+    #[test]
+    fn evict_over_max_size() {
+        let mut rng = generators::rng();
+
+        let main_parent = BlockNumHash::new(CANONICAL_HEAD, rng.gen());
+        let block1 = create_block(&mut rng, 10, main_parent.hash);
+        let block2 = create_block(&mut rng, 11, block1.hash());
+        let far_parent = rng.gen();
+        let far_block = create_block(&mut rng, 100, far_parent);
+        let max_size = block1.block.size() + block2.block.size() + far_block.block.size() - 1;
+
+        let mut buffer = BlockBuffer::new(10).with_max_size(max_size);
+
+        buffer.insert_block(block1.clone(), CANONICAL_HEAD);
+        buffer.insert_block(far_block.clone(), CANONICAL_HEAD);
+        // re-inserting a block doesn't count its size twice
+        buffer.insert_block(far_block.clone(), CANONICAL_HEAD);
+        assert_buffer_lengths(&buffer, 2);
+
+        buffer.insert_block(block2.clone(), CANONICAL_HEAD);
+        assert_block_removal(&buffer, &far_block);
+        assert_buffer_lengths(&buffer, 2);
+        assert_eq!(buffer.size, block1.block.size() + block2.block.size());
+    }
 }
@@ -5,6 +5,7 @@ use crate::{
     state::{BlockChainId, TreeState},
     AppendableChain, BlockIndices, BlockchainTreeConfig, BundleStateData, TreeExternals,
 };
+use lru::LruCache;
 use reth_db::database::Database;
 use reth_interfaces::{
     blockchain_tree::{
@@ -26,13 +27,19 @@ use reth_provider::{
     CanonStateNotification, CanonStateNotificationSender, CanonStateNotifications, Chain,
     ChainSpecProvider, DisplayBlocksChain, ExecutorFactory, HeaderProvider, ProviderError,
 };
-use reth_stages::{MetricEvent, MetricEventsSender};
+use reth_stages::{BlockProcessingSource, BlockProcessingTimings, MetricEvent, MetricEventsSender};
 use std::{
     collections::{BTreeMap, HashSet},
+    num::NonZeroUsize,
     sync::Arc,
+    time::{Duration, Instant},
 };
 use tracing::{debug, error, info, instrument, trace, warn};
 
+/// The maximum number of blocks whose processing timings are kept until the blocks are made
+/// canonical.
+const MAX_BLOCK_TIMINGS: usize = 256;
+
 #[cfg_attr(doc, aquamarine::aquamarine)]
 /// A Tree of chains.
 ///
@@ -72,6 +79,9 @@ pub struct BlockchainTree<DB: Database, EVM: ExecutorFactory> {
     metrics: TreeMetrics,
     /// Metrics for sync stages.
     sync_metrics_tx: Option<MetricEventsSender>,
+    /// The time spent processing the inserted blocks, reported with
+    /// [MetricEvent::BlockProcessed] once the blocks are made canonical.
+    block_timings: LruCache<BlockHash, BlockProcessingTimings>,
     prune_modes: Option<PruneModes>,
 }
 
@@ -133,11 +143,15 @@ where
                 last_finalized_block_number,
                 last_canonical_hashes,
                 config.max_unconnected_blocks(),
+                config.max_unconnected_blocks_size(),
             ),
             config,
             canon_state_notification_sender,
             metrics: Default::default(),
             sync_metrics_tx: None,
+            block_timings: LruCache::new(
+                NonZeroUsize::new(MAX_BLOCK_TIMINGS).expect("capacity is not zero"),
+            ),
             prune_modes,
         })
     }
@@ -340,7 +354,8 @@ where
         }
 
         // insert block inside unconnected block buffer. Delaying its execution.
-        self.state.buffered_blocks.insert_block(block.clone());
+        let canonical_head = self.block_indices().canonical_tip().number;
+        self.state.buffered_blocks.insert_block(block.clone(), canonical_head);
 
         let block_hash = block.hash();
         // find the lowest ancestor of the block in the buffer to return as the missing parent
@@ -404,6 +419,7 @@ where
             BlockAttachment::HistoricalFork
         };
 
+        let mut timings = BlockProcessingTimings::default();
         let chain = AppendableChain::new_canonical_fork(
             block,
             &parent_header,
@@ -412,9 +428,11 @@ where
             &self.externals,
             block_attachment,
             block_validation_kind,
+            &mut timings,
         )?;
 
         self.insert_chain(chain);
+        self.add_block_timings(block_num_hash.hash, timings);
         self.try_connect_buffered_blocks(block_num_hash);
 
         Ok(BlockStatus::Valid(block_attachment))
@@ -448,6 +466,7 @@ where
 
         let chain_tip = parent_chain.tip().hash();
         let canonical_chain = self.state.block_indices.canonical_chain();
+        let mut timings = BlockProcessingTimings::default();
 
         // append the block if it is continuing the side chain.
         let block_attachment = if chain_tip == block.parent_hash {
@@ -469,6 +488,7 @@ where
                 canonical_fork,
                 block_attachment,
                 block_validation_kind,
+                &mut timings,
             )?;
 
             self.state.block_indices.insert_non_fork_block(block_number, block_hash, chain_id);
@@ -483,10 +503,12 @@ where
                 canonical_fork,
                 &self.externals,
                 block_validation_kind,
+                &mut timings,
             )?;
             self.insert_chain(chain);
             BlockAttachment::HistoricalFork
         };
+        self.add_block_timings(block_num_hash.hash, timings);
 
         // After we inserted the block, we try to connect any buffered blocks
         self.try_connect_buffered_blocks(block_num_hash);
@@ -634,8 +656,20 @@ where
         &mut self,
         block: SealedBlock,
     ) -> Result<InsertPayloadOk, InsertBlockError> {
+        let start = Instant::now();
         match block.try_seal_with_senders() {
-            Ok(block) => self.insert_block(block, BlockValidationKind::Exhaustive),
+            Ok(block) => {
+                let sender_recovery = start.elapsed();
+                let hash = block.hash();
+                let outcome = self.insert_block(block, BlockValidationKind::Exhaustive)?;
+                if let InsertPayloadOk::Inserted(_) = outcome {
+                    self.add_block_timings(
+                        hash,
+                        BlockProcessingTimings { sender_recovery, ..Default::default() },
+                    );
+                }
+                Ok(outcome)
+            }
             Err(block) => Err(InsertBlockError::sender_recovery_error(block)),
         }
     }
@@ -649,7 +683,8 @@ where
             return Err(InsertBlockError::consensus_error(err, block.block))
         }
 
-        self.state.buffered_blocks.insert_block(block);
+        let canonical_head = self.block_indices().canonical_tip().number;
+        self.state.buffered_blocks.insert_block(block, canonical_head);
         Ok(())
     }
 
@@ -735,16 +770,38 @@ where
         }
 
         // validate block consensus rules
+        let start = Instant::now();
         if let Err(err) = self.validate_block(&block) {
             return Err(InsertBlockError::consensus_error(err, block.block))
         }
+        let validation = start.elapsed();
 
+        let block_hash = block.hash();
         let status = self
             .try_insert_validated_block(block.clone(), block_validation_kind)
             .map_err(|kind| InsertBlockError::new(block.block, kind))?;
+        self.add_block_timings(
+            block_hash,
+            BlockProcessingTimings { validation, ..Default::default() },
+        );
         Ok(InsertPayloadOk::Inserted(status))
     }
 
+    /// Adds the time spent processing a block to its timings, which are reported once the block is
+    /// made canonical.
+    fn add_block_timings(&mut self, hash: BlockHash, timings: BlockProcessingTimings) {
+        *self.block_timings.get_or_insert_mut(hash, Default::default) += timings;
+    }
+
+    /// Records the time spent validating the execution payload of a block, which is reported with
+    /// the other timings of the block once it's made canonical.
+    pub fn on_payload_validated(&mut self, hash: BlockHash, elapsed: Duration) {
+        self.add_block_timings(
+            hash,
+            BlockProcessingTimings { payload_validation: elapsed, ..Default::default() },
+        );
+    }
+
     /// Finalize blocks up until and including `finalized_block`, and remove them from the tree.
     pub fn finalize_block(&mut self, finalized_block: BlockNumber) {
         // remove blocks
@@ -772,7 +829,8 @@ where
     /// # Note
     ///
     /// This finalizes `last_finalized_block` prior to reading the canonical hashes (using
-    /// [`BlockchainTree::finalize_block`]).
+    /// [`BlockchainTree::finalize_block`]). The buffered blocks that are not above the canonical
+    /// head and didn't connect are discarded afterwards.
     pub fn connect_buffered_blocks_to_canonical_hashes_and_finalize(
         &mut self,
         last_finalized_block: BlockNumber,
@@ -795,6 +853,10 @@ where
 
         self.connect_buffered_blocks_to_hashes(last_canonical_hashes)?;
 
+        // the buffered blocks that are not above the head didn't connect to the canonical chain
+        let canonical_head = self.state.block_indices.canonical_tip().number;
+        self.state.buffered_blocks.remove_blocks_below_head(canonical_head);
+
         Ok(())
     }
 
@@ -1027,6 +1089,12 @@ where
             "Committing new canonical chain: {}", DisplayBlocksChain(new_canon_chain.blocks())
         );
 
+        let block_timings = new_canon_chain
+            .blocks()
+            .values()
+            .map(|block| (block.number, self.block_timings.pop(&block.hash()).unwrap_or_default()))
+            .collect::<Vec<_>>();
+
         // If chain extends the tip
         let chain_notification = if new_canon_chain.fork_block().hash == old_tip.hash {
             // Commit new canonical chain to database.
@@ -1087,6 +1155,32 @@ where
             "Canonicalization finished"
         );
 
+        if let Some(metrics_tx) = self.sync_metrics_tx.as_mut() {
+            // the blocks are made canonical together, so the time of the shared phases is split
+            // evenly between them
+            let mut shared = BlockProcessingTimings::default();
+            for (action, duration) in &durations_recorder.actions {
+                match action {
+                    MakeCanonicalAction::RetrieveStateTrieUpdates => {
+                        shared.trie_updates += *duration
+                    }
+                    MakeCanonicalAction::CommitCanonicalChainToDatabase => {
+                        shared.commit += *duration
+                    }
+                    _ => shared.canonicalization += *duration,
+                }
+            }
+            let shared = shared / block_timings.len().max(1) as u32;
+            for (number, timings) in block_timings {
+                let _ = metrics_tx.send(MetricEvent::BlockProcessed {
+                    source: BlockProcessingSource::Engine,
+                    number,
+                    blocks: 1,
+                    timings: timings + shared,
+                });
+            }
+        }
+
         // Send notification about new canonical chain and return outcome of canonicalization.
         let outcome = CanonicalOutcome::Committed { head: chain_notification.tip().header.clone() };
         let _ = self.canon_state_notification_sender.send(chain_notification);
@@ -1247,6 +1341,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ShareableBlockchainTree;
     use assert_matches::assert_matches;
     use linked_hash_set::LinkedHashSet;
     use reth_db::{tables, test_utils::TempDatabase, transaction::DbTxMut, DatabaseEnv};
@@ -1260,18 +1355,19 @@ mod tests {
         constants::{EIP1559_INITIAL_BASE_FEE, EMPTY_ROOT_HASH, ETHEREUM_BLOCK_GAS_LIMIT},
         keccak256,
         proofs::{calculate_transaction_root, state_root_unhashed},
-        revm_primitives::AccountInfo,
+        revm_primitives::{AccountInfo, BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, SpecId},
         stage::StageCheckpoint,
-        Account, Address, ChainSpecBuilder, Genesis, GenesisAccount, Header, Signature,
-        Transaction, TransactionKind, TransactionSigned, TransactionSignedEcRecovered, TxEip1559,
-        Withdrawals, B256, MAINNET,
+        Account, Address, BlockId, ChainSpecBuilder, Genesis, GenesisAccount, Header, RpcBlockHash,
+        Signature, Transaction, TransactionKind, TransactionSigned, TransactionSignedEcRecovered,
+        TxEip1559, Withdrawals, B256, MAINNET,
     };
     use reth_provider::{
+        providers::BlockchainProvider,
         test_utils::{
             blocks::BlockChainTestData, create_test_provider_factory_with_chain_spec,
             TestExecutorFactory,
         },
-        ProviderFactory,
+        BlockReader, EvmEnvProvider, ProviderFactory, StateProviderFactory, TransactionVariant,
     };
     use reth_revm::EvmProcessorFactory;
     use reth_trie::StateRoot;
@@ -1776,6 +1872,65 @@ mod tests {
         assert_eq!(chain1.first_block(), block2.number);
     }
 
+    #[test]
+    fn side_chain_block_by_hash() {
+        let data = BlockChainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(vec![exec2, exec1]);
+        setup_genesis(&externals.provider_factory, data.genesis);
+        let provider_factory = externals.provider_factory.clone();
+
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2);
+        let mut tree = BlockchainTree::new(externals, config, None).expect("failed to create tree");
+        tree.make_canonical(B256::ZERO).unwrap();
+        tree.finalize_block(10);
+
+        // the blocks are only held by the tree, like blocks that were received with a new payload
+        // before the forkchoice update that makes them canonical
+        tree.insert_block(block1.clone(), BlockValidationKind::Exhaustive).unwrap();
+        tree.insert_block(block2.clone(), BlockValidationKind::Exhaustive).unwrap();
+        let provider =
+            BlockchainProvider::new(provider_factory, ShareableBlockchainTree::new(tree)).unwrap();
+        let account = Address::new([0x60; 20]);
+        let slot = B256::from(U256::from(5));
+
+        // `eth_call` resolves the state and the env of the block of the tree by hash
+        let state = provider
+            .state_by_block_id(BlockId::Hash(RpcBlockHash::from_hash(block2.hash(), Some(false))))
+            .unwrap();
+        assert_eq!(state.account_balance(account).unwrap(), Some(U256::from(20)));
+        assert_eq!(state.storage(account, slot).unwrap(), Some(U256::from(15)));
+        // the state of the earlier blocks of the tree is layered below
+        assert_eq!(state.account_balance(Address::new([0x61; 20])).unwrap(), Some(U256::from(10)));
+
+        let mut cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::LATEST);
+        let mut block_env = BlockEnv::default();
+        provider
+            .fill_env_at(&mut cfg, &mut block_env, block2.hash().into(), EthEvmConfig::default())
+            .unwrap();
+        assert_eq!(block_env.number, U256::from(block2.number));
+        assert_eq!(block_env.timestamp, U256::from(block2.timestamp));
+
+        // unless the block is required to be canonical
+        assert!(provider
+            .state_by_block_id(BlockId::Hash(RpcBlockHash::from_hash(block2.hash(), Some(true))))
+            .is_err());
+
+        // `debug_traceBlock` replays the block of the tree on top of the state of its parent
+        let block = provider
+            .block_with_senders(block2.hash().into(), TransactionVariant::WithHash)
+            .unwrap()
+            .unwrap();
+        assert_eq!(block.parent_hash, block1.hash());
+        assert_eq!(block.senders, block2.senders);
+        let parent_state = provider.state_by_block_hash(block.parent_hash).unwrap();
+        assert_eq!(parent_state.account_balance(account).unwrap(), Some(U256::from(10)));
+        assert_eq!(parent_state.storage(account, slot).unwrap(), Some(U256::from(10)));
+    }
+
     #[test]
     fn sanity_path() {
         let data = BlockChainTestData::default_from_number(11);
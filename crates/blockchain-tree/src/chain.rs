@@ -22,6 +22,7 @@ use reth_provider::{
     BundleStateDataProvider, BundleStateWithReceipts, Chain, ExecutorFactory, ProviderError,
     StateRootProvider,
 };
+use reth_stages::BlockProcessingTimings;
 use reth_trie::updates::TrieUpdates;
 use reth_trie_parallel::parallel_root::ParallelStateRoot;
 use std::{
@@ -66,6 +67,9 @@ impl AppendableChain {
     ///
     /// if [BlockValidationKind::Exhaustive] is specified, the method will verify the state root of
     /// the block.
+    ///
+    /// The time spent validating and executing the block is added to the `timings`.
+    #[allow(clippy::too_many_arguments)]
     pub fn new_canonical_fork<DB, EF>(
         block: SealedBlockWithSenders,
         parent_header: &SealedHeader,
@@ -74,6 +78,7 @@ impl AppendableChain {
         externals: &TreeExternals<DB, EF>,
         block_attachment: BlockAttachment,
         block_validation_kind: BlockValidationKind,
+        timings: &mut BlockProcessingTimings,
     ) -> Result<Self, InsertBlockErrorKind>
     where
         DB: Database + Clone,
@@ -96,6 +101,7 @@ impl AppendableChain {
             externals,
             block_attachment,
             block_validation_kind,
+            timings,
         )?;
 
         Ok(Self { chain: Chain::new(vec![block], bundle_state, trie_updates) })
@@ -104,6 +110,7 @@ impl AppendableChain {
     /// Create a new chain that forks off of an existing sidechain.
     ///
     /// This differs from [AppendableChain::new_canonical_fork] in that this starts a new fork.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new_chain_fork<DB, EF>(
         &self,
         block: SealedBlockWithSenders,
@@ -112,6 +119,7 @@ impl AppendableChain {
         canonical_fork: ForkBlock,
         externals: &TreeExternals<DB, EF>,
         block_validation_kind: BlockValidationKind,
+        timings: &mut BlockProcessingTimings,
     ) -> Result<Self, InsertBlockErrorKind>
     where
         DB: Database + Clone,
@@ -141,6 +149,7 @@ impl AppendableChain {
             externals,
             BlockAttachment::HistoricalFork,
             block_validation_kind,
+            timings,
         )?;
         // extending will also optimize few things, mostly related to selfdestruct and wiping of
         // storage.
@@ -166,6 +175,9 @@ impl AppendableChain {
     ///   - [BlockAttachment] represents if the block extends the canonical chain, and thus we can
     ///     cache the trie state updates.
     ///   - [BlockValidationKind] determines if the state root __should__ be validated.
+    ///
+    /// The time spent validating the block against its parent, executing it and computing its
+    /// state root is added to the `timings`.
     fn validate_and_execute<BSDP, DB, EVM>(
         block: SealedBlockWithSenders,
         parent_block: &SealedHeader,
@@ -173,6 +185,7 @@ impl AppendableChain {
         externals: &TreeExternals<DB, EVM>,
         block_attachment: BlockAttachment,
         block_validation_kind: BlockValidationKind,
+        timings: &mut BlockProcessingTimings,
     ) -> RethResult<(BundleStateWithReceipts, Option<TrieUpdates>)>
     where
         BSDP: BundleStateDataProvider,
@@ -180,7 +193,9 @@ impl AppendableChain {
         EVM: ExecutorFactory,
     {
         // some checks are done before blocks comes here.
+        let start = Instant::now();
         externals.consensus.validate_header_against_parent(&block, parent_block)?;
+        timings.validation += start.elapsed();
 
         // get the state provider.
         let canonical_fork = bundle_state_data_provider.canonical_fork();
@@ -206,8 +221,10 @@ impl AppendableChain {
         let mut executor = externals.executor_factory.with_state(&provider);
         let block_hash = block.hash();
         let block = block.unseal();
+        let start = Instant::now();
         executor.execute_and_verify_receipt(&block, U256::MAX)?;
         let bundle_state = executor.take_output_state();
+        timings.execution += start.elapsed();
 
         // check state root if the block extends the canonical chain __and__ if state root
         // validation was requested.
@@ -231,6 +248,7 @@ impl AppendableChain {
                 )
                 .into())
             }
+            timings.state_root += start.elapsed();
 
             tracing::debug!(
                 target: "blockchain_tree::chain",
@@ -257,6 +275,8 @@ impl AppendableChain {
     /// CAUTION: This will only perform state root check if it's possible: if the `canonical_fork`
     /// is the canonical head, or: state root check can't be performed if the given canonical is
     /// __not__ the canonical head.
+    ///
+    /// The time spent validating and executing the block is added to the `timings`.
     #[track_caller]
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn append_block<DB, EF>(
@@ -268,6 +288,7 @@ impl AppendableChain {
         canonical_fork: ForkBlock,
         block_attachment: BlockAttachment,
         block_validation_kind: BlockValidationKind,
+        timings: &mut BlockProcessingTimings,
     ) -> Result<(), InsertBlockErrorKind>
     where
         DB: Database + Clone,
@@ -289,6 +310,7 @@ impl AppendableChain {
             externals,
             block_attachment,
             block_validation_kind,
+            timings,
         )?;
         // extend the state.
         self.chain.append_block(block, block_state, trie_updates);
@@ -11,6 +11,8 @@ pub struct BlockchainTreeConfig {
     max_reorg_depth: u64,
     /// The number of unconnected blocks that we are buffering
     max_unconnected_blocks: usize,
+    /// The maximum in-memory size in bytes of the unconnected blocks that we are buffering
+    max_unconnected_blocks_size: usize,
     /// Number of additional block hashes to save in blockchain tree. For `BLOCKHASH` EVM opcode we
     /// need last 256 block hashes.
     ///
@@ -33,6 +35,8 @@ impl Default for BlockchainTreeConfig {
             num_of_additional_canonical_block_hashes: 256,
             // max unconnected blocks.
             max_unconnected_blocks: 200,
+            // max size of the unconnected blocks, 512 MiB.
+            max_unconnected_blocks_size: 512 * 1024 * 1024,
         }
     }
 }
@@ -53,6 +57,7 @@ impl BlockchainTreeConfig {
             max_reorg_depth,
             num_of_additional_canonical_block_hashes,
             max_unconnected_blocks,
+            max_unconnected_blocks_size: Self::default().max_unconnected_blocks_size,
         }
     }
 
@@ -87,4 +92,21 @@ impl BlockchainTreeConfig {
     pub fn max_unconnected_blocks(&self) -> usize {
         self.max_unconnected_blocks
     }
+
+    /// Return max in-memory size in bytes of the unconnected blocks that we are buffering
+    pub fn max_unconnected_blocks_size(&self) -> usize {
+        self.max_unconnected_blocks_size
+    }
+
+    /// Set the max in-memory size in bytes of the unconnected blocks that we are buffering.
+    pub fn with_max_unconnected_blocks_size(mut self, max_unconnected_blocks_size: usize) -> Self {
+        self.max_unconnected_blocks_size = max_unconnected_blocks_size;
+        self
+    }
+
+    /// Set the max number of unconnected blocks that we are buffering.
+    pub fn with_max_unconnected_blocks(mut self, max_unconnected_blocks: usize) -> Self {
+        self.max_unconnected_blocks = max_unconnected_blocks;
+        self
+    }
 }
@@ -31,6 +31,20 @@ pub struct TreeMetrics {
 pub struct BlockBufferMetrics {
     /// Total blocks in the block buffer
     pub blocks: Gauge,
+    /// The in-memory size of the blocks in the block buffer, in bytes
+    pub size: Gauge,
+    /// The lowest block number in the block buffer
+    pub lowest_block_number: Gauge,
+    /// The highest block number in the block buffer
+    pub highest_block_number: Gauge,
+    /// The number of blocks that were evicted because the block buffer exceeded its number or
+    /// size limit
+    pub evicted_over_capacity: Counter,
+    /// The number of blocks that were discarded because they precede the finalized block
+    pub evicted_finalized: Counter,
+    /// The number of blocks that were discarded because they are not above the canonical head
+    /// after a pipeline run, and didn't connect to the canonical chain
+    pub evicted_below_head: Counter,
 }
 
 #[derive(Debug)]
@@ -22,6 +22,7 @@ use reth_provider::{
 use std::{
     collections::{BTreeMap, HashSet},
     sync::Arc,
+    time::Duration,
 };
 use tracing::trace;
 
@@ -55,6 +56,22 @@ where
         tree.buffer_block(block)
     }
 
+    fn insert_block_without_senders(
+        &self,
+        block: SealedBlock,
+        validation_kind: BlockValidationKind,
+    ) -> Result<InsertPayloadOk, InsertBlockError> {
+        trace!(target: "blockchain_tree", hash = %block.hash(), number = block.number, parent_hash = %block.parent_hash, "Inserting block without senders");
+        let mut tree = self.tree.write();
+        let res = tree.insert_block_without_senders(block, validation_kind);
+        tree.update_chains_metrics();
+        res
+    }
+
+    fn on_payload_validated(&self, block_hash: BlockHash, elapsed: Duration) {
+        self.tree.write().on_payload_validated(block_hash, elapsed)
+    }
+
     fn insert_block(
         &self,
         block: SealedBlockWithSenders,
@@ -27,6 +27,7 @@ impl TreeState {
         last_finalized_block_number: BlockNumber,
         last_canonical_hashes: impl IntoIterator<Item = (BlockNumber, BlockHash)>,
         buffer_limit: usize,
+        buffer_max_size: usize,
     ) -> Self {
         Self {
             block_chain_id_generator: 0,
@@ -35,7 +36,7 @@ impl TreeState {
                 last_finalized_block_number,
                 BTreeMap::from_iter(last_canonical_hashes),
             ),
-            buffered_blocks: BlockBuffer::new(buffer_limit),
+            buffered_blocks: BlockBuffer::new(buffer_limit).with_max_size(buffer_max_size),
         }
     }
 
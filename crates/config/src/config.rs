@@ -6,6 +6,7 @@ use reth_primitives::PruneModes;
 use secp256k1::SecretKey;
 use serde::{Deserialize, Deserializer, Serialize};
 use std::{
+    collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
     time::Duration,
 };
@@ -24,9 +25,31 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for the transaction pool.
+    pub txpool: TxPoolConfig,
+    /// Configuration for the RPC servers.
+    pub rpc: RpcConfig,
+    /// Configuration for the execution extensions.
+    pub exex: ExExConfig,
 }
 
 impl Config {
+    /// Returns the dotted paths of the settings that differ between the two configs, e.g.
+    /// `txpool.max_local_txs`, ordered by path.
+    ///
+    /// Arrays are compared regardless of the order of their entries and reported as a whole, and
+    /// a table that's only set in one of the configs is reported by its own path.
+    pub fn changed_fields(&self, other: &Self) -> Vec<String> {
+        let mut changed = Vec::new();
+        changed_values(
+            String::new(),
+            toml::Value::try_from(self).ok().as_ref(),
+            toml::Value::try_from(other).ok().as_ref(),
+            &mut changed,
+        );
+        changed
+    }
+
     /// Initializes network config from read data
     pub fn network_config(
         &self,
@@ -37,7 +60,7 @@ impl Config {
         let peer_config = self
             .peers
             .clone()
-            .with_basic_nodes_from_file(peers_file)
+            .with_known_peers_from_file(peers_file)
             .unwrap_or_else(|_| self.peers.clone());
 
         let discv4 =
@@ -49,6 +72,36 @@ impl Config {
     }
 }
 
+/// Collects the paths of the values that differ between the two values.
+fn changed_values(
+    path: String,
+    this: Option<&toml::Value>,
+    other: Option<&toml::Value>,
+    changed: &mut Vec<String>,
+) {
+    match (this, other) {
+        (Some(toml::Value::Table(this)), Some(toml::Value::Table(other))) => {
+            let keys = this.keys().chain(other.keys()).collect::<BTreeSet<_>>();
+            for key in keys {
+                let key_path = if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+                changed_values(key_path, this.get(key), other.get(key), changed);
+            }
+        }
+        (Some(toml::Value::Array(this)), Some(toml::Value::Array(other))) => {
+            let sorted = |values: &[toml::Value]| {
+                let mut values = values.iter().map(ToString::to_string).collect::<Vec<_>>();
+                values.sort_unstable();
+                values
+            };
+            if sorted(this) != sorted(other) {
+                changed.push(path);
+            }
+        }
+        (this, other) if this != other => changed.push(path),
+        _ => {}
+    }
+}
+
 /// Configuration for each stage in the pipeline.
 #[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
@@ -73,6 +126,8 @@ pub struct StageConfig {
     pub index_account_history: IndexHistoryConfig,
     /// Index Storage History stage configuration.
     pub index_storage_history: IndexHistoryConfig,
+    /// Index Call Traces stage configuration.
+    pub index_call_traces: IndexCallTracesConfig,
     /// Common ETL related configuration.
     pub etl: EtlConfig,
 }
@@ -155,11 +210,17 @@ impl Default for BodiesConfig {
 pub struct SenderRecoveryConfig {
     /// The maximum number of transactions to process before committing progress to the database.
     pub commit_threshold: u64,
+    /// The number of transactions recovered by a single rayon worker job.
+    pub chunk_size: u64,
+    /// Whether to write the senders of final blocks to static files instead of the database.
+    ///
+    /// Ignored if the senders are pruned.
+    pub static_files: bool,
 }
 
 impl Default for SenderRecoveryConfig {
     fn default() -> Self {
-        Self { commit_threshold: 5_000_000 }
+        Self { commit_threshold: 5_000_000, chunk_size: 100, static_files: false }
     }
 }
 
@@ -179,6 +240,12 @@ pub struct ExecutionConfig {
         deserialize_with = "deserialize_duration"
     )]
     pub max_duration: Option<Duration>,
+    /// Whether the receipts root and the logs bloom of every executed block are verified against
+    /// its header. Should only be disabled for chains with non-standard receipts.
+    pub verify_receipts: bool,
+    /// The block from which the receipts are verified, if enabled. The blocks below it are
+    /// executed without the verification, which is faster.
+    pub verify_receipts_from: u64,
 }
 
 impl Default for ExecutionConfig {
@@ -190,10 +257,19 @@ impl Default for ExecutionConfig {
             max_cumulative_gas: Some(30_000_000 * 50_000),
             // 10 minutes
             max_duration: Some(Duration::from_secs(10 * 60)),
+            verify_receipts: true,
+            verify_receipts_from: 0,
         }
     }
 }
 
+impl ExecutionConfig {
+    /// Returns the block from which the receipts are verified, or `None` if they aren't.
+    pub fn verify_receipts_from(&self) -> Option<u64> {
+        self.verify_receipts.then_some(self.verify_receipts_from)
+    }
+}
+
 /// Hashing stage configuration.
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
@@ -218,11 +294,25 @@ pub struct MerkleConfig {
     /// The threshold (in number of blocks) for switching from incremental trie building of changes
     /// to whole rebuild.
     pub clean_threshold: u64,
+    /// The maximum time spent rebuilding the trie before the intermediate state is committed.
+    ///
+    /// If not set, the intermediate state is committed after a fixed number of trie updates.
+    #[serde(
+        serialize_with = "humantime_serde::serialize",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub flush_interval: Option<Duration>,
+    /// The maximum number of storage roots of changed accounts that are calculated in parallel
+    /// when the trie of the node pipeline is updated incrementally.
+    ///
+    /// Storage roots are calculated sequentially if set to 1, or if only a few accounts have
+    /// changed storage.
+    pub storage_root_parallelism: usize,
 }
 
 impl Default for MerkleConfig {
     fn default() -> Self {
-        Self { clean_threshold: 5_000 }
+        Self { clean_threshold: 5_000, flush_interval: None, storage_root_parallelism: 8 }
     }
 }
 
@@ -288,6 +378,25 @@ impl Default for IndexHistoryConfig {
     }
 }
 
+/// Call trace index stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct IndexCallTracesConfig {
+    /// Whether the stage is part of the pipeline.
+    ///
+    /// The execution stage records the addresses of the calls of the blocks it executes for the
+    /// stage, the other blocks are executed again by the stage.
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexCallTracesConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 10_000 }
+    }
+}
+
 /// Pruning configuration.
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
@@ -297,14 +406,107 @@ pub struct PruneConfig {
     /// Pruning configuration for every part of the data that can be pruned.
     #[serde(alias = "parts")]
     pub segments: PruneModes,
+    /// The maximum number of entries every segment deletes per second, spreading the deletion
+    /// of a large backlog over multiple pruner runs.
+    ///
+    /// If not set, the deletions are only limited per pruner run.
+    pub max_entries_per_second: Option<usize>,
 }
 
 impl Default for PruneConfig {
     fn default() -> Self {
-        Self { block_interval: 5, segments: PruneModes::none() }
+        Self { block_interval: 5, segments: PruneModes::none(), max_entries_per_second: None }
+    }
+}
+
+/// Transaction pool configuration.
+///
+/// The limits set on the command line take precedence over these.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct TxPoolConfig {
+    /// The maximum number of transactions of a single sender in the pool, including local
+    /// transactions.
+    pub max_txs_per_sender: Option<usize>,
+    /// The maximum number of local transactions in the pool.
+    pub max_local_txs: Option<usize>,
+    /// The maximum number of external transactions in the pool.
+    pub max_external_txs: Option<usize>,
+    /// The maximum number of private transactions in the pool.
+    pub max_private_txs: Option<usize>,
+    /// The minimum effective tip in wei a new transaction must pay at the base fee of the pending
+    /// block.
+    pub minimum_priority_fee: Option<u64>,
+}
+
+/// Configuration for the RPC servers.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct RpcConfig {
+    /// Rate limits of RPC methods, keyed by method name.
+    ///
+    /// A key may contain `*` wildcards, e.g. `eth_get*`. If several keys match a method, an exact
+    /// name takes precedence over a pattern, and a longer pattern over a shorter one.
+    #[serde(rename = "rate-limits", skip_serializing_if = "BTreeMap::is_empty")]
+    pub rate_limits: BTreeMap<String, MethodRateLimit>,
+    /// The maximum size in megabytes of the cached responses of a method, keyed by method name.
+    ///
+    /// Methods without a limit may use the entire response cache, see `--rpc.cache-size-mb`.
+    #[serde(rename = "response-cache-limits", skip_serializing_if = "BTreeMap::is_empty")]
+    pub response_cache_limits: BTreeMap<String, u64>,
+}
+
+/// Configuration for the execution extensions.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct ExExConfig {
+    /// The maximum size in megabytes of the write-ahead log of the notifications that were not
+    /// acknowledged by all ExExes yet.
+    ///
+    /// Once the limit is reached, no new notifications are accepted until the ExExes catch up.
+    pub wal_max_size_mb: u64,
+    /// The number of blocks the finished height of an ExEx may be behind the tip before a warning
+    /// is logged.
+    ///
+    /// The node never prunes blocks that an ExEx has not finished yet, so a lagging ExEx holds
+    /// back pruning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_prune_lag: Option<u64>,
+}
+
+impl Default for ExExConfig {
+    fn default() -> Self {
+        Self { wal_max_size_mb: 1024, max_prune_lag: None }
     }
 }
 
+/// The rate limit of the RPC methods matching a [`RpcConfig::rate_limits`] key.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+pub struct MethodRateLimit {
+    /// The number of requests that are allowed per second.
+    pub requests_per_second: u32,
+    /// The number of requests that are allowed in a burst, defaults to
+    /// [`MethodRateLimit::requests_per_second`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
+    /// Who the limit applies to.
+    #[serde(default)]
+    pub bucket: RateLimitBucket,
+}
+
+/// Who a [`MethodRateLimit`] applies to.
+#[derive(Debug, Clone, Copy, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RateLimitBucket {
+    /// The limit is shared by all clients.
+    #[default]
+    Global,
+    /// Each connection has its own limit.
+    Connection,
+    /// Each `X-API-Key` header value has its own limit.
+    ApiKey,
+}
+
 /// Helper type to support older versions of Duration deserialization.
 fn deserialize_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
 where
@@ -325,8 +527,9 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::Config;
-    use std::time::Duration;
+    use super::{Config, MethodRateLimit, PruneConfig, RateLimitBucket};
+    use reth_network::NodeRecord;
+    use std::{net::SocketAddr, time::Duration};
 
     const EXTENSION: &str = "toml";
 
@@ -370,6 +573,93 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_load_txpool() {
+        with_tempdir("config-load-test", |config_path| {
+            let mut config = Config::default();
+            config.txpool.max_txs_per_sender = Some(64);
+            config.txpool.max_external_txs = Some(5_000);
+            confy::store_path(config_path, &config).unwrap();
+
+            let loaded_config: Config = confy::load_path(config_path).unwrap();
+            assert_eq!(config, loaded_config);
+        })
+    }
+
+    #[test]
+    fn test_load_rpc_rate_limits() {
+        let s = r#"
+[rpc.rate-limits.eth_call]
+requests_per_second = 10
+burst = 20
+
+[rpc.rate-limits."debug_*"]
+requests_per_second = 1
+bucket = "api-key"
+"#;
+        let config: Config = toml::from_str(s).unwrap();
+        let limits = &config.rpc.rate_limits;
+        assert_eq!(
+            limits["eth_call"],
+            MethodRateLimit {
+                requests_per_second: 10,
+                burst: Some(20),
+                bucket: RateLimitBucket::Global
+            }
+        );
+        assert_eq!(
+            limits["debug_*"],
+            MethodRateLimit {
+                requests_per_second: 1,
+                burst: None,
+                bucket: RateLimitBucket::ApiKey
+            }
+        );
+
+        with_tempdir("config-load-test", |config_path| {
+            let mut config = Config::default();
+            config.rpc.rate_limits.insert(
+                "eth_*".to_string(),
+                MethodRateLimit {
+                    requests_per_second: 100,
+                    burst: None,
+                    bucket: RateLimitBucket::Connection,
+                },
+            );
+            confy::store_path(config_path, &config).unwrap();
+
+            let loaded_config: Config = confy::load_path(config_path).unwrap();
+            assert_eq!(config, loaded_config);
+        })
+    }
+
+    #[test]
+    fn test_load_rpc_response_cache_limits() {
+        let s = r#"
+[rpc.response-cache-limits]
+eth_getBlockByHash = 64
+debug_traceTransaction = 256
+"#;
+        let config: Config = toml::from_str(s).unwrap();
+        let limits = &config.rpc.response_cache_limits;
+        assert_eq!(limits.len(), 2);
+        assert_eq!(limits["eth_getBlockByHash"], 64);
+        assert_eq!(limits["debug_traceTransaction"], 256);
+    }
+
+    #[test]
+    fn test_load_merkle_stage() {
+        with_tempdir("config-load-test", |config_path| {
+            let mut config = Config::default();
+            config.stages.merkle.flush_interval = Some(Duration::from_secs(5 * 60));
+            config.stages.merkle.storage_root_parallelism = 1;
+            confy::store_path(config_path, &config).unwrap();
+
+            let loaded_config: Config = confy::load_path(config_path).unwrap();
+            assert_eq!(config, loaded_config);
+        })
+    }
+
     // ensures config deserialization is backwards compatible
     #[test]
     fn test_backwards_compatibility() {
@@ -672,6 +962,38 @@ nanos = 0
         let _conf: Config = toml::from_str(alpha_0_0_19).unwrap();
     }
 
+    #[test]
+    fn test_changed_fields() {
+        let node =
+            |port| NodeRecord::new(SocketAddr::from(([127, 0, 0, 1], port)), Default::default());
+        let mut config = Config::default();
+        config.peers.trusted_nodes.extend([node(1), node(2), node(3)]);
+        assert!(config.changed_fields(&config.clone()).is_empty());
+
+        let mut other = config.clone();
+        other.peers.trusted_nodes = [node(3), node(2), node(1)].into_iter().collect();
+        assert!(config.changed_fields(&other).is_empty());
+
+        other.peers.trusted_nodes.remove(&node(1));
+        other.peers = other.peers.with_max_inbound(10);
+        other.txpool.max_local_txs = Some(100);
+        other.prune = Some(PruneConfig::default());
+        other.rpc.rate_limits.insert(
+            "eth_call".to_string(),
+            MethodRateLimit { requests_per_second: 10, burst: None, bucket: Default::default() },
+        );
+        assert_eq!(
+            config.changed_fields(&other),
+            vec![
+                "peers.connection_info.max_inbound",
+                "peers.trusted_nodes",
+                "prune",
+                "rpc.rate-limits",
+                "txpool.max_local_txs"
+            ]
+        );
+    }
+
     #[test]
     fn test_conf_trust_nodes_only() {
         let trusted_nodes_only = r"#
@@ -77,7 +77,8 @@ impl<DB: Database + 'static> PruneHook<DB> {
     }
 
     /// This will try to spawn the pruner if it is idle:
-    /// 1. Check if pruning is needed through [Pruner::is_pruning_needed].
+    /// 1. Apply the latest config through [Pruner::apply_config_updates] and check if pruning is
+    ///    needed through [Pruner::is_pruning_needed].
     /// 2.
     ///     1. If pruning is needed, pass tip block number to the [Pruner::run] and spawn it in a
     /// separate task. Set pruner state to [PrunerState::Running].
@@ -88,6 +89,7 @@ impl<DB: Database + 'static> PruneHook<DB> {
         match &mut self.pruner_state {
             PrunerState::Idle(pruner) => {
                 let mut pruner = pruner.take()?;
+                pruner.apply_config_updates();
 
                 // Check tip for pruning
                 if pruner.is_pruning_needed(tip_block_number) {
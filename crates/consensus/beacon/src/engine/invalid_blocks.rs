@@ -0,0 +1,448 @@
+use alloy_rlp::Decodable;
+use reth_interfaces::invalid_block::{InvalidBlock, InvalidBlocksHandle};
+use reth_primitives::{fs, fs::FsPathError, Block, Bytes, Header, B256};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tracing::{debug, warn};
+
+/// The default maximum number of invalid blocks that are persisted.
+pub const DEFAULT_MAX_INVALID_BLOCKS: usize = 128;
+
+/// The default age after which a persisted invalid block is forgotten.
+pub const DEFAULT_INVALID_BLOCK_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Errors of loading the persisted invalid blocks.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidBlockStoreError {
+    /// The file of the invalid blocks couldn't be read.
+    #[error(transparent)]
+    Fs(#[from] FsPathError),
+    /// The file of the invalid blocks is malformed.
+    #[error("malformed invalid blocks file: {0}")]
+    Json(#[from] serde_json::Error),
+    /// A persisted invalid block or header can't be decoded.
+    #[error("malformed invalid block {hash}: {error}")]
+    Rlp {
+        /// The hash of the invalid block.
+        hash: B256,
+        /// The decoding error.
+        error: alloy_rlp::Error,
+    },
+}
+
+/// Persists the invalid blocks of the engine to a file, so they're still rejected after a restart.
+///
+/// At most `max_blocks` of the most recent invalid blocks are kept, and blocks are forgotten once
+/// they're older than `max_age`. Every change is published to the [InvalidBlocksHandle].
+///
+/// The file is a log with one JSON entry per line: every change appends the inserted or removed
+/// blocks to it, so the engine never rewrites the file for a single change. The log is compacted
+/// to the stored blocks when it's opened and once it holds twice as many entries as `max_blocks`.
+#[derive(Debug)]
+pub struct InvalidBlockStore {
+    /// The path of the file of the invalid blocks.
+    path: PathBuf,
+    /// The maximum number of invalid blocks that are kept.
+    max_blocks: usize,
+    /// The age after which an invalid block is forgotten.
+    max_age: Duration,
+    /// The invalid blocks, oldest first.
+    blocks: VecDeque<InvalidBlock>,
+    /// The number of entries in the file.
+    entries: usize,
+    /// The handle the invalid blocks are published to.
+    handle: InvalidBlocksHandle,
+}
+
+impl InvalidBlockStore {
+    /// Creates an empty store, replacing the file at the given path.
+    pub fn new(
+        path: impl Into<PathBuf>,
+        max_blocks: usize,
+        max_age: Duration,
+        handle: InvalidBlocksHandle,
+    ) -> Self {
+        let mut this = Self {
+            path: path.into(),
+            max_blocks,
+            max_age,
+            blocks: VecDeque::new(),
+            entries: 0,
+            handle,
+        };
+        this.compact();
+        this.publish();
+        this
+    }
+
+    /// Loads the invalid blocks from the file at the given path, if it exists.
+    ///
+    /// The blocks that are older than `max_age` are dropped, as well as the oldest blocks beyond
+    /// `max_blocks`.
+    pub fn open(
+        path: impl Into<PathBuf>,
+        max_blocks: usize,
+        max_age: Duration,
+        handle: InvalidBlocksHandle,
+    ) -> Result<Self, InvalidBlockStoreError> {
+        let path = path.into();
+        let mut blocks = VecDeque::new();
+        let mut entries = 0;
+        if path.exists() {
+            for line in fs::read_to_string(&path)?.lines().filter(|line| !line.is_empty()) {
+                entries += 1;
+                match serde_json::from_str(line)? {
+                    StoredEntry::Insert(block) => {
+                        let block = block.decode()?;
+                        let hash = block.header.hash();
+                        blocks.retain(|stored: &InvalidBlock| stored.header.hash() != hash);
+                        blocks.push_back(block);
+                    }
+                    StoredEntry::Remove { hash } => {
+                        blocks.retain(|stored| stored.header.hash() != hash);
+                    }
+                }
+            }
+        }
+
+        let mut this = Self { path, max_blocks, max_age, blocks, entries, handle };
+        this.expire_at(unix_timestamp());
+        while this.blocks.len() > this.max_blocks {
+            this.blocks.pop_front();
+        }
+        if this.blocks.len() != this.entries {
+            this.compact();
+        }
+        this.publish();
+        debug!(
+            target: "consensus::engine",
+            path = ?this.path,
+            blocks = this.blocks.len(),
+            "Loaded invalid blocks"
+        );
+
+        Ok(this)
+    }
+
+    /// Returns the invalid blocks, oldest first.
+    pub fn blocks(&self) -> impl Iterator<Item = &InvalidBlock> + '_ {
+        self.blocks.iter()
+    }
+
+    /// Returns true if no invalid blocks are stored.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Adds an invalid block, evicting the oldest block if the store is full.
+    ///
+    /// Returns false if the block is already stored.
+    pub fn insert(&mut self, block: InvalidBlock) -> bool {
+        if self.max_blocks == 0 || self.contains(&block.header.hash()) {
+            return false
+        }
+        let mut entries = Vec::new();
+        while self.blocks.len() >= self.max_blocks {
+            entries.extend(self.blocks.pop_front().map(|evicted| StoredEntry::remove(&evicted)));
+        }
+        entries.push(StoredEntry::Insert(StoredInvalidBlock::encode(&block)));
+        self.blocks.push_back(block);
+        self.append(&entries);
+        self.publish();
+        true
+    }
+
+    /// Returns true if the block with the given hash is stored.
+    pub fn contains(&self, hash: &B256) -> bool {
+        self.blocks.iter().any(|block| block.header.hash() == *hash)
+    }
+
+    /// Removes the blocks that match the predicate, e.g. because they became canonical.
+    ///
+    /// Returns the removed blocks.
+    pub fn remove_if(&mut self, mut f: impl FnMut(&InvalidBlock) -> bool) -> Vec<InvalidBlock> {
+        let (removed, kept): (Vec<_>, VecDeque<_>) =
+            std::mem::take(&mut self.blocks).into_iter().partition(|block| f(block));
+        self.blocks = kept;
+        self.on_removed(&removed);
+        removed
+    }
+
+    /// Removes the blocks that are older than the configured maximum age.
+    ///
+    /// Returns the removed blocks.
+    pub fn expire(&mut self) -> Vec<InvalidBlock> {
+        let removed = self.expire_at(unix_timestamp());
+        self.on_removed(&removed);
+        removed
+    }
+
+    /// Removes the blocks that are older than the configured maximum age at the given unix
+    /// timestamp, without persisting the change.
+    fn expire_at(&mut self, now: u64) -> Vec<InvalidBlock> {
+        let oldest = now.saturating_sub(self.max_age.as_secs());
+        let mut removed = Vec::new();
+        // the blocks are ordered by the time they were first seen
+        while self.blocks.front().is_some_and(|block| block.first_seen < oldest) {
+            removed.extend(self.blocks.pop_front());
+        }
+        removed
+    }
+
+    /// Persists and publishes the removal of the given blocks.
+    fn on_removed(&mut self, removed: &[InvalidBlock]) {
+        if !removed.is_empty() {
+            self.append(&removed.iter().map(StoredEntry::remove).collect::<Vec<_>>());
+            self.publish();
+        }
+    }
+
+    /// Appends the entries to the file, or compacts the file if it holds too many entries.
+    ///
+    /// Errors are only logged, the blocks are still tracked in memory.
+    fn append(&mut self, entries: &[StoredEntry]) {
+        if self.entries + entries.len() > self.max_blocks * 2 {
+            self.compact();
+            return
+        }
+        let result = encode_entries(entries).and_then(|contents| {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .map_err(|err| FsPathError::open(err, &self.path))?;
+            file.write_all(&contents).map_err(|err| FsPathError::write(err, &self.path))?;
+            Ok(())
+        });
+        match result {
+            Ok(()) => self.entries += entries.len(),
+            Err(err) => self.on_persist_error(err),
+        }
+    }
+
+    /// Replaces the file with one entry per stored block.
+    ///
+    /// The file is replaced atomically, so it's never left half-written. Errors are only logged,
+    /// the blocks are still tracked in memory.
+    fn compact(&mut self) {
+        let entries = self
+            .blocks
+            .iter()
+            .map(|block| StoredEntry::Insert(StoredInvalidBlock::encode(block)))
+            .collect::<Vec<_>>();
+        let result = encode_entries(&entries).and_then(|contents| {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let tmp_path = self.path.with_extension("tmp");
+            fs::write(&tmp_path, contents)?;
+            fs::rename(&tmp_path, &self.path)?;
+            Ok(())
+        });
+        match result {
+            Ok(()) => self.entries = entries.len(),
+            Err(err) => self.on_persist_error(err),
+        }
+    }
+
+    fn on_persist_error(&self, err: InvalidBlockStoreError) {
+        warn!(
+            target: "consensus::engine",
+            path = ?self.path,
+            %err,
+            "Failed to persist invalid blocks"
+        );
+    }
+
+    /// Publishes the invalid blocks to the handle.
+    fn publish(&self) {
+        self.handle.update(self.blocks.iter().cloned().collect());
+    }
+}
+
+/// Encodes the entries as JSON, one per line.
+fn encode_entries(entries: &[StoredEntry]) -> Result<Vec<u8>, InvalidBlockStoreError> {
+    let mut contents = Vec::new();
+    for entry in entries {
+        serde_json::to_writer(&mut contents, entry)?;
+        contents.push(b'\n');
+    }
+    Ok(contents)
+}
+
+/// An entry of the file of the invalid blocks.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+enum StoredEntry {
+    /// An invalid block was added.
+    Insert(StoredInvalidBlock),
+    /// An invalid block was removed.
+    Remove {
+        /// The hash of the removed block.
+        hash: B256,
+    },
+}
+
+impl StoredEntry {
+    fn remove(block: &InvalidBlock) -> Self {
+        Self::Remove { hash: block.header.hash() }
+    }
+}
+
+/// An invalid block as it's persisted, with the header and the block RLP encoded.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredInvalidBlock {
+    hash: B256,
+    header: Bytes,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    block: Option<Bytes>,
+    reason: String,
+    first_seen: u64,
+}
+
+impl StoredInvalidBlock {
+    fn encode(block: &InvalidBlock) -> Self {
+        Self {
+            hash: block.header.hash(),
+            header: alloy_rlp::encode(block.header.header()).into(),
+            block: block
+                .block
+                .as_ref()
+                .map(|block| alloy_rlp::encode(block.clone().unseal()).into()),
+            reason: block.reason.clone(),
+            first_seen: block.first_seen,
+        }
+    }
+
+    fn decode(self) -> Result<InvalidBlock, InvalidBlockStoreError> {
+        let Self { hash, header, block, reason, first_seen } = self;
+        let rlp_error = |error| InvalidBlockStoreError::Rlp { hash, error };
+        let header = Header::decode(&mut header.as_ref()).map_err(rlp_error)?.seal(hash);
+        let block = block
+            .map(|block| Block::decode(&mut block.as_ref()).map(|block| block.seal(hash)))
+            .transpose()
+            .map_err(rlp_error)?;
+        Ok(InvalidBlock { header, block, reason, first_seen })
+    }
+}
+
+/// Returns the current unix timestamp in seconds.
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid_block(number: u64, first_seen: u64, with_body: bool) -> InvalidBlock {
+        let block = Block { header: Header { number, ..Default::default() }, ..Default::default() }
+            .seal_slow();
+        InvalidBlock {
+            header: block.header.clone(),
+            block: with_body.then_some(block),
+            reason: format!("invalid block {number}"),
+            first_seen,
+        }
+    }
+
+    #[test]
+    fn persist_invalid_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invalid_blocks.jsonl");
+        let now = unix_timestamp();
+        let max_age = Duration::from_secs(60);
+
+        let handle = InvalidBlocksHandle::default();
+        let mut store = InvalidBlockStore::open(&path, 2, max_age, handle.clone()).unwrap();
+        let first = invalid_block(1, now, true);
+        let second = invalid_block(2, now, false);
+        assert!(store.insert(first.clone()));
+        assert!(!store.insert(first.clone()));
+        assert!(store.insert(second.clone()));
+        assert_eq!(handle.snapshot(), vec![first.clone(), second.clone()]);
+
+        // the oldest block is evicted
+        let third = invalid_block(3, now, true);
+        assert!(store.insert(third.clone()));
+        assert_eq!(handle.snapshot(), vec![second.clone(), third.clone()]);
+
+        let handle = InvalidBlocksHandle::default();
+        let store = InvalidBlockStore::open(&path, 2, max_age, handle.clone()).unwrap();
+        assert_eq!(store.blocks().cloned().collect::<Vec<_>>(), vec![second.clone(), third]);
+        assert_eq!(handle.snapshot().len(), 2);
+
+        // the store is truncated to the new maximum on load
+        let store = InvalidBlockStore::open(&path, 1, max_age, Default::default()).unwrap();
+        assert_eq!(store.blocks().map(|block| block.header.number).collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn expire_and_remove_invalid_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invalid_blocks.jsonl");
+        let now = unix_timestamp();
+        let max_age = Duration::from_secs(60);
+
+        let mut store = InvalidBlockStore::open(&path, 10, max_age, Default::default()).unwrap();
+        store.insert(invalid_block(1, now - 120, false));
+        store.insert(invalid_block(2, now, true));
+        store.insert(invalid_block(3, now, false));
+
+        let expired = store.expire();
+        assert_eq!(expired.iter().map(|block| block.header.number).collect::<Vec<_>>(), vec![1]);
+
+        let removed = store.remove_if(|block| block.header.number == 2);
+        assert_eq!(removed.iter().map(|block| block.header.number).collect::<Vec<_>>(), vec![2]);
+
+        let mut store = InvalidBlockStore::open(&path, 10, max_age, Default::default()).unwrap();
+        assert_eq!(store.blocks().cloned().collect::<Vec<_>>(), vec![invalid_block(3, now, false)]);
+
+        // the expired blocks are dropped on load
+        store.insert(invalid_block(4, now - 30, false));
+        let store = InvalidBlockStore::open(&path, 10, Duration::from_secs(10), Default::default())
+            .unwrap();
+        assert_eq!(store.blocks().map(|block| block.header.number).collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn compact_invalid_blocks_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invalid_blocks.jsonl");
+        let now = unix_timestamp();
+        let max_age = Duration::from_secs(60);
+
+        let mut store = InvalidBlockStore::open(&path, 2, max_age, Default::default()).unwrap();
+        for number in 0..10 {
+            store.insert(invalid_block(number, now, number % 2 == 0));
+        }
+        // the file is compacted once it holds twice as many entries as blocks are kept
+        assert!(fs::read_to_string(&path).unwrap().lines().count() <= 4);
+
+        let store = InvalidBlockStore::open(&path, 10, max_age, Default::default()).unwrap();
+        assert_eq!(store.blocks().map(|block| block.header.number).collect::<Vec<_>>(), vec![8, 9]);
+    }
+
+    #[test]
+    fn replace_corrupt_invalid_blocks_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invalid_blocks.jsonl");
+        let now = unix_timestamp();
+        let max_age = Duration::from_secs(60);
+
+        fs::write(&path, "not json").unwrap();
+        assert!(InvalidBlockStore::open(&path, 2, max_age, Default::default()).is_err());
+
+        let mut store = InvalidBlockStore::new(&path, 2, max_age, Default::default());
+        assert!(store.insert(invalid_block(1, now, true)));
+        let store = InvalidBlockStore::open(&path, 2, max_age, Default::default()).unwrap();
+        assert_eq!(store.blocks().cloned().collect::<Vec<_>>(), vec![invalid_block(1, now, true)]);
+    }
+}
@@ -64,6 +64,30 @@ impl InvalidHeaderCache {
         }
     }
 
+    /// Inserts an invalid ancestor that was rejected before, e.g. before a restart, into the map.
+    pub(crate) fn restore(&mut self, invalid_ancestor: SealedHeader) {
+        let hash = invalid_ancestor.hash();
+        if self.headers.peek(&hash).is_none() {
+            self.insert_entry(hash, Arc::new(invalid_ancestor.unseal()));
+            self.metrics.count.set(self.headers.len() as f64);
+        }
+    }
+
+    /// Removes an invalid ancestor and every header that references it as invalid ancestor from
+    /// the map, e.g. because the ancestor became canonical.
+    pub(crate) fn remove(&mut self, invalid_ancestor: &SealedHeader) {
+        let hashes = self
+            .headers
+            .iter()
+            .filter(|(_, entry)| *entry.header == *invalid_ancestor.header())
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        for hash in hashes {
+            self.headers.remove(&hash);
+        }
+        self.metrics.count.set(self.headers.len() as f64);
+    }
+
     /// Inserts an invalid ancestor into the map.
     pub(crate) fn insert(&mut self, invalid_ancestor: SealedHeader) {
         if self.get(&invalid_ancestor.hash()).is_none() {
@@ -118,4 +142,20 @@ mod tests {
 
         assert!(cache.get(&header.hash()).is_none());
     }
+
+    #[test]
+    fn test_remove_with_descendants() {
+        let mut cache = InvalidHeaderCache::new(10);
+        let header = Header::default().seal_slow();
+        let other = Header { number: 1, ..Default::default() }.seal_slow();
+        cache.restore(header.clone());
+        cache.insert(other.clone());
+        let descendant = B256::with_last_byte(1);
+        cache.insert_with_invalid_ancestor(descendant, Arc::new(header.header().clone()));
+
+        cache.remove(&header);
+        assert!(cache.get(&header.hash()).is_none());
+        assert!(cache.get(&descendant).is_none());
+        assert!(cache.get(&other.hash()).is_some());
+    }
 }
@@ -13,6 +13,11 @@ pub(crate) struct EngineMetrics {
     pub(crate) forkchoice_updated_messages: Counter,
     /// The total count of new payload messages received.
     pub(crate) new_payload_messages: Counter,
+    /// The number of new payloads that were buffered because the pipeline was running.
+    pub(crate) buffered_payloads: Counter,
+    /// The number of new payloads that were received while the pipeline was running and already
+    /// buffered.
+    pub(crate) duplicate_buffered_payloads: Counter,
     /// Latency for making canonical already canonical block
     pub(crate) make_canonical_already_canonical_latency: Histogram,
     /// Latency for making canonical committed block
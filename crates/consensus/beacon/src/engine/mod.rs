@@ -7,7 +7,7 @@ use crate::{
     hooks::{EngineContext, EngineHooksController},
     sync::{EngineSyncController, EngineSyncEvent},
 };
-use futures::{Future, StreamExt};
+use futures::{Future, FutureExt, StreamExt};
 use reth_db::database::Database;
 use reth_interfaces::{
     blockchain_tree::{
@@ -16,6 +16,7 @@ use reth_interfaces::{
     },
     consensus::ForkchoiceState,
     executor::{BlockExecutionError, BlockValidationError},
+    invalid_block::InvalidBlock,
     p2p::{bodies::client::BodiesClient, headers::client::HeadersClient},
     sync::{NetworkSyncUpdater, SyncState},
     RethError, RethResult,
@@ -34,7 +35,10 @@ use reth_rpc_types::engine::{
     CancunPayloadFields, ExecutionPayload, PayloadStatus, PayloadStatusEnum, PayloadValidationError,
 };
 use reth_stages::{ControlFlow, Pipeline, PipelineError};
-use reth_tasks::TaskSpawner;
+use reth_tasks::{
+    shutdown::{GracefulShutdown, GracefulShutdownGuard},
+    TaskSpawner,
+};
 use reth_tokio_util::EventListeners;
 use std::{
     pin::Pin,
@@ -62,6 +66,13 @@ pub use error::{
 mod invalid_headers;
 use invalid_headers::InvalidHeaderCache;
 
+mod invalid_blocks;
+use invalid_blocks::unix_timestamp;
+pub use invalid_blocks::{
+    InvalidBlockStore, InvalidBlockStoreError, DEFAULT_INVALID_BLOCK_MAX_AGE,
+    DEFAULT_MAX_INVALID_BLOCKS,
+};
+
 mod event;
 pub use event::{BeaconConsensusEngineEvent, ConsensusEngineLiveSyncProgress};
 
@@ -88,6 +99,10 @@ pub mod test_utils;
 /// The maximum number of invalid headers that can be tracked by the engine.
 const MAX_INVALID_HEADERS: u32 = 512u32;
 
+/// The reason of the invalid blocks that were detected by the pipeline, which only reports the
+/// block that it unwound.
+const PIPELINE_BAD_BLOCK_REASON: &str = "invalid block detected by the pipeline";
+
 /// The largest gap for which the tree will be used for sync. See docs for `pipeline_run_threshold`
 /// for more information.
 ///
@@ -195,6 +210,9 @@ where
     /// Tracks the header of invalid payloads that were rejected by the engine because they're
     /// invalid.
     invalid_headers: InvalidHeaderCache,
+    /// Persists the invalid blocks across restarts, see
+    /// [BeaconConsensusEngine::set_invalid_block_store].
+    invalid_blocks: Option<InvalidBlockStore>,
     /// Consensus engine metrics.
     metrics: EngineMetrics,
     /// After downloading a block corresponding to a recent forkchoice update, the engine will
@@ -210,6 +228,12 @@ where
     /// be used to download and execute the missing blocks.
     pipeline_run_threshold: u64,
     hooks: EngineHooksController,
+    /// The graceful shutdown signal of the engine, see
+    /// [BeaconConsensusEngine::set_graceful_shutdown].
+    shutdown: Option<GracefulShutdown>,
+    /// Held after the graceful shutdown signal was fired, until the engine finished its
+    /// in-flight work.
+    shutdown_guard: Option<GracefulShutdownGuard>,
 }
 
 impl<DB, BT, Client, EngineT> BeaconConsensusEngine<DB, BT, Client, EngineT>
@@ -308,9 +332,12 @@ where
             payload_builder,
             listeners,
             invalid_headers: InvalidHeaderCache::new(MAX_INVALID_HEADERS),
+            invalid_blocks: None,
             metrics: EngineMetrics::default(),
             pipeline_run_threshold,
             hooks: EngineHooksController::new(hooks),
+            shutdown: None,
+            shutdown_guard: None,
         };
 
         let maybe_pipeline_target = match target {
@@ -326,6 +353,66 @@ where
         Ok((this, handle))
     }
 
+    /// Sets the graceful shutdown signal of the engine.
+    ///
+    /// Once the signal is fired, the engine processes the messages that were already received,
+    /// waits for a running hook with database write access and then resolves. Pipeline runs and
+    /// hooks are not started anymore.
+    pub fn set_graceful_shutdown(&mut self, shutdown: GracefulShutdown) {
+        self.shutdown = Some(shutdown);
+    }
+
+    /// Sets the store that persists the invalid blocks of the engine.
+    ///
+    /// The stored blocks are rejected like the blocks that were found to be invalid since the
+    /// start, and every newly found invalid block is added to the store. Blocks are removed from
+    /// the store once they expire or become canonical.
+    pub fn set_invalid_block_store(&mut self, store: InvalidBlockStore) {
+        for block in store.blocks() {
+            self.invalid_headers.restore(block.header.clone());
+        }
+        self.invalid_blocks = Some(store);
+    }
+
+    /// Keeps track of an invalid block, together with its body if it's known.
+    fn on_invalid_block(
+        &mut self,
+        header: SealedHeader,
+        block: Option<SealedBlock>,
+        reason: String,
+    ) {
+        if let Some(store) = &mut self.invalid_blocks {
+            store.insert(InvalidBlock {
+                header: header.clone(),
+                block,
+                reason,
+                first_seen: unix_timestamp(),
+            });
+        }
+        self.invalid_headers.insert(header);
+    }
+
+    /// Removes the persisted invalid blocks that expired or became canonical with the new head.
+    ///
+    /// Only the canonical blocks that are tracked in memory by the tree are checked: a commit of
+    /// the tree can't make older blocks canonical, and those are forgotten once they expire.
+    fn purge_invalid_blocks(&mut self) {
+        let Some(store) = &mut self.invalid_blocks else { return };
+        store.expire();
+        if store.is_empty() {
+            return
+        }
+
+        let canonical_blocks = self.blockchain.canonical_blocks();
+        let canonical = store.remove_if(|block| {
+            canonical_blocks.get(&block.header.number) == Some(&block.header.hash())
+        });
+        for block in canonical {
+            debug!(target: "consensus::engine", hash=?block.header.hash(), number=block.header.number, "Invalid block became canonical");
+            self.invalid_headers.remove(&block.header);
+        }
+    }
+
     /// Called to resolve chain forks and ensure that the Execution layer is working with the latest
     /// valid chain.
     ///
@@ -400,6 +487,7 @@ where
 
                         // new VALID update that moved the canonical chain forward
                         let _ = self.update_head(head.clone());
+                        self.purge_invalid_blocks();
                         self.listeners.notify(BeaconConsensusEngineEvent::CanonicalChainCommitted(
                             Box::new(head.clone()),
                             elapsed,
@@ -1094,10 +1182,12 @@ where
         payload: ExecutionPayload,
         cancun_fields: Option<CancunPayloadFields>,
     ) -> Result<PayloadStatus, BeaconOnNewPayloadError> {
+        let start = Instant::now();
         let block = match self.ensure_well_formed_payload(payload, cancun_fields) {
             Ok(block) => block,
             Err(status) => return Ok(status),
         };
+        let payload_validation = start.elapsed();
         let block_hash = block.hash();
         let block_num_hash = block.num_hash();
 
@@ -1116,7 +1206,7 @@ where
         let res = if self.sync.is_pipeline_idle() {
             // we can only insert new payloads if the pipeline is _not_ running, because it holds
             // exclusive access to the database
-            self.try_insert_new_payload(block)
+            self.try_insert_new_payload(block, payload_validation)
         } else {
             self.try_buffer_payload(block)
         };
@@ -1279,6 +1369,8 @@ where
     /// pipeline is finished, the tree is then able to also use the buffered payloads to commit to a
     /// (newer) canonical chain.
     ///
+    /// Payloads that are already buffered are not validated again.
+    ///
     /// This will return `SYNCING` if the block was buffered successfully, and an error if an error
     /// occurred while buffering the block.
     #[instrument(level = "trace", skip_all, target = "consensus::engine", ret)]
@@ -1286,17 +1378,25 @@ where
         &mut self,
         block: SealedBlock,
     ) -> Result<PayloadStatus, InsertBlockError> {
-        self.blockchain.buffer_block_without_senders(block)?;
+        if self.blockchain.buffered_header_by_hash(block.hash()).is_some() {
+            self.metrics.duplicate_buffered_payloads.increment(1);
+        } else {
+            self.blockchain.buffer_block_without_senders(block)?;
+            self.metrics.buffered_payloads.increment(1);
+        }
         Ok(PayloadStatus::from_status(PayloadStatusEnum::Syncing))
     }
 
     /// Attempts to insert a new payload into the tree.
     ///
+    /// The time spent validating the payload is reported to the tree if the block was inserted.
+    ///
     /// Caution: This expects that the pipeline is idle.
     #[instrument(level = "trace", skip_all, target = "consensus::engine", ret)]
     fn try_insert_new_payload(
         &mut self,
         block: SealedBlock,
+        payload_validation: Duration,
     ) -> Result<PayloadStatus, InsertBlockError> {
         debug_assert!(self.sync.is_pipeline_idle(), "pipeline must be idle");
 
@@ -1312,6 +1412,7 @@ where
         let status = match status {
             InsertPayloadOk::Inserted(BlockStatus::Valid(attachment)) => {
                 latest_valid_hash = Some(block_hash);
+                self.blockchain.on_payload_validated(block_hash, payload_validation);
                 let event = if attachment.is_canonical() {
                     BeaconConsensusEngineEvent::CanonicalBlockAdded(block, elapsed)
                 } else {
@@ -1359,8 +1460,8 @@ where
             // all of these occurred if the payload is invalid
             let parent_hash = block.parent_hash;
 
-            // keep track of the invalid header
-            self.invalid_headers.insert(block.header);
+            // keep track of the invalid block
+            self.on_invalid_block(block.header.clone(), Some(block), error.to_string());
 
             let latest_valid_hash =
                 self.latest_valid_hash_for_invalid_payload(parent_hash, Some(&error));
@@ -1469,7 +1570,7 @@ where
                     let (block, err) = err.split();
                     warn!(target: "consensus::engine", invalid_number=?block.number, invalid_hash=?block.hash(), %err, "Marking block as invalid");
 
-                    self.invalid_headers.insert(block.header);
+                    self.on_invalid_block(block.header.clone(), Some(block), err.to_string());
                 }
             }
         }
@@ -1547,6 +1648,7 @@ where
             match make_canonical_result {
                 Ok(outcome) => {
                     if let CanonicalOutcome::Committed { head } = &outcome {
+                        self.purge_invalid_blocks();
                         self.listeners.notify(BeaconConsensusEngineEvent::CanonicalChainCommitted(
                             Box::new(head.clone()),
                             elapsed,
@@ -1653,7 +1755,7 @@ where
                     warn!(target: "consensus::engine", invalid_hash=?bad_block.hash(), invalid_number=?bad_block.number, "Bad block detected in unwind");
 
                     // update the `invalid_headers` cache with the new invalid headers
-                    self.invalid_headers.insert(*bad_block);
+                    self.on_invalid_block(*bad_block, None, PIPELINE_BAD_BLOCK_REASON.to_string());
                     return None
                 }
 
@@ -1834,6 +1936,14 @@ where
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
 
+        if let Some(shutdown) = this.shutdown.as_mut() {
+            if let Poll::Ready(guard) = shutdown.poll_unpin(cx) {
+                debug!(target: "consensus::engine", "Received shutdown signal");
+                this.shutdown = None;
+                this.shutdown_guard = Some(guard);
+            }
+        }
+
         // Control loop that advances the state
         'main: loop {
             // Poll a running hook with db write access (if any) and CL messages first, draining
@@ -1893,6 +2003,13 @@ where
                 break
             }
 
+            // the in-flight messages are processed, so the engine can stop once no hook is
+            // writing to the database
+            if this.shutdown_guard.is_some() && this.hooks.active_db_write_hook().is_none() {
+                debug!(target: "consensus::engine", "Shutting down consensus engine");
+                return Poll::Ready(Ok(()))
+            }
+
             // process sync events if any
             match this.sync.poll(cx) {
                 Poll::Ready(sync_event) => {
@@ -1965,6 +2082,7 @@ mod tests {
     use reth_rpc_types::engine::{ForkchoiceState, ForkchoiceUpdated, PayloadStatus};
     use reth_rpc_types_compat::engine::payload::try_block_to_payload_v1;
     use reth_stages::{ExecOutput, StageError};
+    use reth_tasks::{shutdown::ShutdownPhase, TaskManager};
     use std::{collections::VecDeque, sync::Arc};
     use tokio::sync::oneshot::error::TryRecvError;
 
@@ -2000,6 +2118,34 @@ mod tests {
         );
     }
 
+    // The engine resolves once the graceful shutdown signal is fired.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn engine_stops_on_graceful_shutdown() {
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(MAINNET.genesis.clone())
+                .paris_activated()
+                .build(),
+        );
+
+        let (mut consensus_engine, _env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+            .disable_blockchain_tree_sync()
+            .build();
+
+        let manager = TaskManager::current();
+        consensus_engine.set_graceful_shutdown(
+            manager.executor().graceful_shutdown_signal(ShutdownPhase::Engine),
+        );
+        let rx = spawn_consensus_engine(consensus_engine);
+
+        let shutdown = tokio::task::spawn_blocking(move || {
+            manager.graceful_shutdown_with_timeout(Duration::from_secs(5))
+        });
+        assert_matches!(rx.await, Ok(Ok(())));
+        assert!(shutdown.await.unwrap());
+    }
+
     // Test that the consensus engine is idle until first forkchoice updated is received.
     #[tokio::test]
     async fn is_idle_until_forkchoice_is_set() {
@@ -2454,12 +2600,37 @@ mod tests {
     mod new_payload {
         use super::*;
         use reth_db::test_utils::create_test_static_files_dir;
-        use reth_interfaces::test_utils::generators::random_block;
+        use reth_interfaces::{
+            invalid_block::InvalidBlocksHandle, test_utils::generators::random_block,
+        };
         use reth_primitives::{
             genesis::{Genesis, GenesisAllocator},
             Hardfork, U256,
         };
-        use reth_provider::test_utils::blocks::BlockChainTestData;
+        use reth_provider::{
+            bundle_state::HashedStateChanges, test_utils::blocks::BlockChainTestData,
+            OriginalValuesKnown,
+        };
+
+        /// Returns the first three blocks of the test data, converted so that they can be sent as
+        /// payloads without withdrawals.
+        fn payload_blocks(data: &BlockChainTestData) -> [SealedBlock; 3] {
+            let mut parent_hash = data.genesis.hash();
+            let blocks = data.blocks[..3]
+                .iter()
+                .map(|(block, _)| {
+                    let mut block = block.block.clone().unseal();
+                    block.withdrawals = None;
+                    block.header.parent_hash = parent_hash;
+                    block.header.base_fee_per_gas = Some(100);
+                    block.header.difficulty = U256::ZERO;
+                    let block = block.seal_slow();
+                    parent_hash = block.hash();
+                    block
+                })
+                .collect::<Vec<_>>();
+            blocks.try_into().unwrap()
+        }
 
         #[tokio::test]
         async fn new_payload_before_forkchoice() {
@@ -2672,6 +2843,218 @@ mod tests {
             assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
         }
 
+        #[tokio::test]
+        async fn payloads_out_of_order_keep_buffered_parent() {
+            let mut rng = generators::rng();
+            let data = BlockChainTestData::default();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+
+            let [block1, block2, block3] = payload_blocks(&data);
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .with_executor_results(
+                    data.blocks[..3]
+                        .iter()
+                        .rev()
+                        .map(|(_, exec_result)| exec_result.clone())
+                        .collect(),
+                )
+                .build();
+
+            let (_static_dir, static_dir_path) = create_test_static_files_dir();
+            insert_blocks(
+                ProviderFactory::new(env.db.as_ref(), chain_spec.clone(), static_dir_path)
+                    .expect("create provider factory with static_files"),
+                [&data.genesis].into_iter(),
+            );
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            // the child arrives before its parents and is buffered
+            let res = env.send_new_payload(try_block_to_payload_v1(block3.clone()), None).await;
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Syncing);
+            assert_matches!(res, Ok(result) => assert_eq!(result, expected_result));
+
+            // the blocks far ahead of the head fill the buffer, which only holds two blocks in
+            // tests, and are evicted before the buffered child
+            for number in [20, 21] {
+                let parent = rng.gen();
+                let block = random_block(&mut rng, number, Some(parent), None, Some(0));
+                let res = env.send_new_payload(try_block_to_payload_v1(block), None).await;
+                let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Syncing);
+                assert_matches!(res, Ok(result) => assert_eq!(result, expected_result));
+            }
+
+            let res = env.send_new_payload(try_block_to_payload_v1(block2.clone()), None).await;
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Syncing);
+            assert_matches!(res, Ok(result) => assert_eq!(result, expected_result));
+
+            // the missing parent connects the buffered blocks
+            let res = env.send_new_payload(try_block_to_payload_v1(block1.clone()), None).await;
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Valid)
+                .with_latest_valid_hash(block1.hash());
+            assert_matches!(res, Ok(result) => assert_eq!(result, expected_result));
+
+            let res = env
+                .send_forkchoice_updated(ForkchoiceState {
+                    head_block_hash: block3.hash(),
+                    ..Default::default()
+                })
+                .await;
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Valid)
+                .with_latest_valid_hash(block3.hash());
+            assert_matches!(res, Ok(ForkchoiceUpdated { payload_status, .. }) => assert_eq!(payload_status, expected_result));
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
+        #[tokio::test]
+        async fn payloads_buffered_during_pipeline_run() {
+            let data = BlockChainTestData::default();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+            let [block1, block2, block3] = payload_blocks(&data);
+
+            let (consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([
+                    Ok(ExecOutput { checkpoint: StageCheckpoint::new(1), done: true }),
+                    // the engine fails if the pipeline is run again instead of connecting the
+                    // buffered payloads
+                    Err(StageError::ChannelClosed),
+                ]))
+                .with_executor_results(
+                    data.blocks[1..3]
+                        .iter()
+                        .rev()
+                        .map(|(_, exec_result)| exec_result.clone())
+                        .collect(),
+                )
+                .disable_blockchain_tree_sync()
+                .build();
+
+            // the database contains the blocks and state that the pipeline backfills
+            let (_static_dir, static_dir_path) = create_test_static_files_dir();
+            let provider_factory =
+                ProviderFactory::new(env.db.as_ref(), chain_spec.clone(), static_dir_path)
+                    .expect("create provider factory with static_files");
+            let provider = provider_factory.provider_rw().unwrap();
+            for block in [&data.genesis, &block1] {
+                provider
+                    .insert_block(block.clone().try_seal_with_senders().unwrap(), None)
+                    .unwrap();
+            }
+            let exec_result1 = data.blocks[0].1.clone();
+            HashedStateChanges(exec_result1.hash_state_slow())
+                .write_to_db(provider.tx_ref())
+                .unwrap();
+            exec_result1
+                .write_to_storage(provider.tx_ref(), None, OriginalValuesKnown::Yes)
+                .unwrap();
+            provider.commit().unwrap();
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            // the unknown head triggers the pipeline
+            let forkchoice_state = ForkchoiceState {
+                head_block_hash: block3.hash(),
+                finalized_block_hash: block1.hash(),
+                ..Default::default()
+            };
+            let res = env.send_forkchoice_updated(forkchoice_state).await;
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Syncing);
+            assert_matches!(res, Ok(ForkchoiceUpdated { payload_status, .. }) => assert_eq!(payload_status, expected_result));
+
+            // the payloads that follow the tip are buffered while the pipeline is running, the
+            // engine handles the queued messages before it observes the finished pipeline
+            let (res2, res3) = tokio::join!(
+                env.send_new_payload(try_block_to_payload_v1(block2.clone()), None),
+                env.send_new_payload(try_block_to_payload_v1(block3.clone()), None)
+            );
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Syncing);
+            assert_matches!(res2, Ok(result) => assert_eq!(result, expected_result));
+            assert_matches!(res3, Ok(result) => assert_eq!(result, expected_result));
+
+            // the buffered payloads are connected to the backfilled chain
+            let res = env.send_forkchoice_retry_on_syncing(forkchoice_state).await;
+            let expected_result = PayloadStatus::from_status(PayloadStatusEnum::Valid)
+                .with_latest_valid_hash(block3.hash());
+            assert_matches!(res, Ok(ForkchoiceUpdated { payload_status, .. }) => assert_eq!(payload_status, expected_result));
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
+        #[tokio::test]
+        async fn payload_of_invalid_block_before_restart() {
+            let data = BlockChainTestData::default();
+            let chain_spec = Arc::new(
+                ChainSpecBuilder::default()
+                    .chain(MAINNET.chain)
+                    .genesis(MAINNET.genesis.clone())
+                    .paris_activated()
+                    .build(),
+            );
+            let [block1, block2, _] = payload_blocks(&data);
+
+            // the store of the previous run of the engine, which rejected the first block
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("invalid-blocks.jsonl");
+            let open_store = |handle| {
+                InvalidBlockStore::open(
+                    &path,
+                    DEFAULT_MAX_INVALID_BLOCKS,
+                    DEFAULT_INVALID_BLOCK_MAX_AGE,
+                    handle,
+                )
+                .unwrap()
+            };
+            let mut store = open_store(Default::default());
+            let invalid_block = InvalidBlock {
+                header: block1.header.clone(),
+                block: Some(block1.clone()),
+                reason: "invalid block".to_string(),
+                first_seen: unix_timestamp(),
+            };
+            assert!(store.insert(invalid_block.clone()));
+            drop(store);
+
+            let (mut consensus_engine, env) = TestConsensusEngineBuilder::new(chain_spec.clone())
+                .with_pipeline_exec_outputs(VecDeque::from([Ok(ExecOutput {
+                    checkpoint: StageCheckpoint::new(0),
+                    done: true,
+                })]))
+                .build();
+            let invalid_blocks = InvalidBlocksHandle::default();
+            consensus_engine.set_invalid_block_store(open_store(invalid_blocks.clone()));
+            assert_eq!(invalid_blocks.snapshot(), vec![invalid_block]);
+
+            let mut engine_rx = spawn_consensus_engine(consensus_engine);
+
+            // the descendant of the block that was rejected before the restart is rejected
+            let res = env.send_new_payload(try_block_to_payload_v1(block2), None).await;
+            assert_matches!(
+                res,
+                Ok(PayloadStatus { status: PayloadStatusEnum::Invalid { .. }, .. })
+            );
+            assert_eq!(invalid_blocks.snapshot().len(), 1);
+
+            assert_matches!(engine_rx.try_recv(), Err(TryRecvError::Empty));
+        }
+
         #[tokio::test]
         async fn payload_pre_merge() {
             let data = BlockChainTestData::default();
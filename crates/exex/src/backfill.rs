@@ -0,0 +1,477 @@
+use futures::{stream::BoxStream, Stream, StreamExt};
+use reth_node_api::ConfigureEvm;
+use reth_primitives::{
+    stage::StageId, BlockNumber, BlockWithSenders, ChainSpec, PruneSegment, Receipts,
+    SealedBlockWithSenders,
+};
+use reth_provider::{
+    bundle_state::{BundleStateInit, RevertsInit},
+    BlockExecutor, BlockReader, BundleStateWithReceipts, CanonStateNotification, Chain,
+    ChangeSetReader, ExecutorFactory, PruneCheckpointReader, StageCheckpointReader,
+    StateProviderFactory, TransactionVariant,
+};
+use reth_revm::EvmProcessorFactory;
+use reth_tasks::TaskExecutor;
+use reth_tracing::tracing::debug;
+use std::{
+    collections::{hash_map, HashMap},
+    ops::RangeInclusive,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
+use tokio::sync::oneshot;
+
+/// The default number of blocks that are executed in one batch.
+pub const DEFAULT_BACKFILL_BATCH_SIZE: u64 = 1_000;
+
+/// The default number of batches that are executed concurrently.
+pub const DEFAULT_BACKFILL_CONCURRENCY: usize = 1;
+
+/// A job that backfills a range of historical blocks, see [`ExExContext::backfill`].
+///
+/// The blocks are backfilled in batches of [`BackfillJob::with_batch_size`] blocks. Up to
+/// [`BackfillJob::with_concurrency`] batches are backfilled concurrently, but they're always
+/// yielded in order.
+///
+/// If the node already executed a batch and still has its receipts and changesets, the execution
+/// outcome of the batch is read from the database. Otherwise the batch is executed on top of the
+/// historical state at its parent block.
+///
+/// The bodies of the blocks and the state before the range must be available, i.e. the range must
+/// not be pruned by the sender recovery, account history or storage history prune segments.
+///
+/// [`ExExContext::backfill`]: crate::ExExContext::backfill
+#[derive(Debug, Clone)]
+pub struct BackfillJob<Provider, EvmConfig> {
+    provider: Provider,
+    executor_factory: Arc<EvmProcessorFactory<EvmConfig>>,
+    task_executor: TaskExecutor,
+    range: RangeInclusive<BlockNumber>,
+    batch_size: u64,
+    concurrency: usize,
+}
+
+impl<Provider, EvmConfig> BackfillJob<Provider, EvmConfig>
+where
+    Provider: BlockReader
+        + StateProviderFactory
+        + ChangeSetReader
+        + PruneCheckpointReader
+        + StageCheckpointReader
+        + Clone
+        + 'static,
+    EvmConfig: ConfigureEvm + 'static,
+{
+    /// Creates a new job for the given range.
+    ///
+    /// The genesis block is not executed, so a range that starts at block 0 is backfilled from
+    /// block 1.
+    pub fn new(
+        provider: Provider,
+        chain_spec: Arc<ChainSpec>,
+        evm_config: EvmConfig,
+        task_executor: TaskExecutor,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Self {
+        let range = (*range.start()).max(1)..=*range.end();
+        Self {
+            provider,
+            executor_factory: Arc::new(EvmProcessorFactory::new(chain_spec, evm_config)),
+            task_executor,
+            range,
+            batch_size: DEFAULT_BACKFILL_BATCH_SIZE,
+            concurrency: DEFAULT_BACKFILL_CONCURRENCY,
+        }
+    }
+
+    /// Sets the maximum number of blocks that are executed in one batch and yielded as one
+    /// [`Chain`].
+    pub fn with_batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Sets the maximum number of batches that are executed concurrently.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Returns the range of blocks that are backfilled.
+    pub fn range(&self) -> &RangeInclusive<BlockNumber> {
+        &self.range
+    }
+
+    /// Returns the ranges of the batches of the job.
+    fn batches(&self) -> impl Iterator<Item = RangeInclusive<BlockNumber>> {
+        let (start, end, batch_size) = (*self.range.start(), *self.range.end(), self.batch_size);
+        (start..=end).step_by(batch_size as usize).map(move |batch_start| {
+            batch_start..=batch_start.saturating_add(batch_size - 1).min(end)
+        })
+    }
+
+    /// Returns the chain of the range, read from the database if its execution outcome is stored
+    /// and executed otherwise.
+    fn backfill_range(&self, range: RangeInclusive<BlockNumber>) -> eyre::Result<Chain> {
+        if self.is_outcome_stored(&range)? {
+            if let Some(chain) = self.read_range(range.clone())? {
+                return Ok(chain)
+            }
+        }
+        self.execute_range(range)
+    }
+
+    /// Returns true if the node executed the range and none of its receipts and changesets were
+    /// pruned.
+    fn is_outcome_stored(&self, range: &RangeInclusive<BlockNumber>) -> eyre::Result<bool> {
+        let executed = self
+            .provider
+            .get_stage_checkpoint(StageId::Execution)?
+            .unwrap_or_default()
+            .block_number;
+        if executed < *range.end() {
+            return Ok(false)
+        }
+
+        for segment in [
+            PruneSegment::Receipts,
+            PruneSegment::ContractLogs,
+            PruneSegment::AccountHistory,
+            PruneSegment::StorageHistory,
+        ] {
+            let pruned = self
+                .provider
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number);
+            if pruned.is_some_and(|pruned| pruned >= *range.start()) {
+                return Ok(false)
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Reads the blocks of the range and their execution outcome from the database.
+    ///
+    /// The state is rebuilt from the changesets of the range and the historical state at the end
+    /// of the range. Returns `None` if the receipts of a block are missing, e.g. because they were
+    /// not written by the node.
+    fn read_range(&self, range: RangeInclusive<BlockNumber>) -> eyre::Result<Option<Chain>> {
+        let blocks = self
+            .blocks_with_senders(range.clone())?
+            .into_iter()
+            .map(|block| self.seal_block(block))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        let mut receipts = Vec::with_capacity(blocks.len());
+        for block in &blocks {
+            let block_receipts = self.provider.receipts_by_block(block.number.into())?;
+            match block_receipts {
+                Some(block_receipts) if block_receipts.len() == block.body.len() => {
+                    receipts.push(block_receipts.into_iter().map(Some).collect())
+                }
+                _ => return Ok(None),
+            }
+        }
+
+        let state_provider = self.provider.history_by_block_number(*range.end())?;
+        let mut state: BundleStateInit = HashMap::new();
+        let mut reverts: RevertsInit = HashMap::new();
+
+        // the account changes of all blocks are added first, the storage changes of an account
+        // that didn't change itself keep the account as it is
+        for number in range.clone() {
+            for change in self.provider.account_block_changeset(number)? {
+                // the changeset of the first change in the range has the original account
+                if let hash_map::Entry::Vacant(entry) = state.entry(change.address) {
+                    let present = state_provider.basic_account(change.address)?;
+                    entry.insert((change.info, present, HashMap::new()));
+                }
+                reverts.entry(number).or_default().entry(change.address).or_default().0 =
+                    Some(change.info);
+            }
+        }
+        for number in range.clone() {
+            for (address, storage) in self.provider.storage_block_changeset(number)? {
+                let account = match state.entry(address) {
+                    hash_map::Entry::Vacant(entry) => {
+                        let present = state_provider.basic_account(address)?;
+                        entry.insert((present, present, HashMap::new()))
+                    }
+                    hash_map::Entry::Occupied(entry) => entry.into_mut(),
+                };
+                if let hash_map::Entry::Vacant(entry) = account.2.entry(storage.key) {
+                    let present = state_provider.storage(address, storage.key)?.unwrap_or_default();
+                    entry.insert((storage.value, present));
+                }
+                reverts.entry(number).or_default().entry(address).or_default().1.push(storage);
+            }
+        }
+
+        let mut contracts = Vec::new();
+        for (original, present, _) in state.values() {
+            let code_hash = present.and_then(|account| account.bytecode_hash);
+            if let Some(code_hash) = code_hash {
+                if original.and_then(|account| account.bytecode_hash) != Some(code_hash) {
+                    if let Some(bytecode) = state_provider.bytecode_by_hash(code_hash)? {
+                        contracts.push((code_hash, bytecode));
+                    }
+                }
+            }
+        }
+
+        let outcome = BundleStateWithReceipts::new_init(
+            state,
+            reverts,
+            contracts,
+            Receipts::from_vec(receipts),
+            *range.start(),
+        );
+
+        debug!(target: "exex::backfill", ?range, "Read backfill batch");
+        Ok(Some(Chain::new(blocks, outcome, None)))
+    }
+
+    /// Returns the blocks of the range with their senders.
+    fn blocks_with_senders(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> eyre::Result<Vec<BlockWithSenders>> {
+        let blocks =
+            self.provider.blocks_with_senders_range(range.clone(), TransactionVariant::WithHash)?;
+        if blocks.len() as u64 != range.end() - range.start() + 1 {
+            eyre::bail!("blocks {range:?} are not available")
+        }
+        Ok(blocks)
+    }
+
+    /// Seals the block with its canonical hash.
+    fn seal_block(&self, block: BlockWithSenders) -> eyre::Result<SealedBlockWithSenders> {
+        let number = block.number;
+        let hash = self
+            .provider
+            .block_hash(number)?
+            .ok_or_else(|| eyre::eyre!("hash of block {number} not found"))?;
+        Ok(block.seal(hash))
+    }
+
+    /// Executes the blocks of the range and returns the resulting chain.
+    fn execute_range(&self, range: RangeInclusive<BlockNumber>) -> eyre::Result<Chain> {
+        let blocks = self.blocks_with_senders(range.clone())?;
+
+        let state = self.provider.history_by_block_number(range.start() - 1)?;
+        let mut executor = self.executor_factory.with_state(state);
+
+        let mut sealed_blocks = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            let number = block.number;
+            let td = self
+                .provider
+                .header_td_by_number(number)?
+                .ok_or_else(|| eyre::eyre!("total difficulty of block {number} not found"))?;
+            executor.execute_and_verify_receipt(&block, td)?;
+            sealed_blocks.push(self.seal_block(block)?);
+        }
+
+        debug!(target: "exex::backfill", ?range, "Executed backfill batch");
+        Ok(Chain::new(sealed_blocks, executor.take_output_state(), None))
+    }
+
+    /// Backfills the blocks of the range on a blocking task.
+    async fn spawn_backfill_range(
+        self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> eyre::Result<CanonStateNotification> {
+        let (tx, rx) = oneshot::channel();
+        let task_executor = self.task_executor.clone();
+        task_executor.spawn_blocking(Box::pin(async move {
+            let _ = tx.send(self.backfill_range(range));
+        }));
+        let chain = rx.await.map_err(|_| eyre::eyre!("backfill task was dropped"))??;
+        Ok(CanonStateNotification::Commit { new: Arc::new(chain) })
+    }
+
+    /// Returns the stream of the backfilled batches.
+    pub fn into_stream(self) -> BackfillStream {
+        let progress = BackfillProgress { range: self.range.clone(), finished_height: None };
+        let concurrency = self.concurrency;
+        let batches = self.batches().collect::<Vec<_>>();
+        let inner = futures::stream::iter(batches)
+            .map(move |range| self.clone().spawn_backfill_range(range))
+            .buffered(concurrency)
+            .boxed();
+        BackfillStream { inner, progress, failed: false }
+    }
+}
+
+/// The progress of a [`BackfillStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackfillProgress {
+    /// The range of blocks that are backfilled.
+    pub range: RangeInclusive<BlockNumber>,
+    /// The highest block that was yielded, if any.
+    pub finished_height: Option<BlockNumber>,
+}
+
+impl BackfillProgress {
+    /// Returns the number of blocks that were not yielded yet.
+    pub fn remaining(&self) -> u64 {
+        let next = self.finished_height.map_or(*self.range.start(), |height| height + 1);
+        (self.range.end() + 1).saturating_sub(next)
+    }
+
+    /// Returns true if all blocks of the range were yielded.
+    pub fn is_finished(&self) -> bool {
+        self.remaining() == 0
+    }
+}
+
+/// A stream of [`CanonStateNotification::Commit`] notifications of the backfilled batches of a
+/// [`BackfillJob`], in order.
+///
+/// The notifications have the same shape as the live notifications of the
+/// [`ExExContext`](crate::ExExContext), so both can be handled by the same code. Like for live
+/// notifications, the ExEx should emit an [`ExExEvent::FinishedHeight`](crate::ExExEvent) with the
+/// tip of every backfilled chain once it's processed. Since backfilled blocks are below the head,
+/// this only allows the node to prune blocks that the ExEx won't backfill again, and the
+/// [`BackfillProgress::finished_height`] can be used as the start of the range when the backfill
+/// is resumed.
+///
+/// The stream ends after the first error.
+#[must_use = "streams do nothing unless polled"]
+pub struct BackfillStream {
+    inner: BoxStream<'static, eyre::Result<CanonStateNotification>>,
+    progress: BackfillProgress,
+    failed: bool,
+}
+
+impl BackfillStream {
+    /// Returns the progress of the backfill.
+    pub fn progress(&self) -> &BackfillProgress {
+        &self.progress
+    }
+}
+
+impl std::fmt::Debug for BackfillStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BackfillStream").field("progress", &self.progress).finish_non_exhaustive()
+    }
+}
+
+impl Stream for BackfillStream {
+    type Item = eyre::Result<CanonStateNotification>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.failed {
+            return Poll::Ready(None)
+        }
+
+        let item = ready!(self.inner.poll_next_unpin(cx));
+        match &item {
+            Some(Ok(notification)) => {
+                self.progress.finished_height = Some(notification.tip().number);
+            }
+            Some(Err(_)) => self.failed = true,
+            None => {}
+        }
+        Poll::Ready(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_blockchain_tree::noop::NoopBlockchainTree;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{Account, PruneCheckpoint, PruneMode, MAINNET, U256};
+    use reth_provider::{
+        providers::BlockchainProvider,
+        test_utils::{create_test_provider_factory, BlockChainTestData},
+        BlockWriter, PruneCheckpointWriter,
+    };
+    use reth_tasks::TaskManager;
+
+    #[tokio::test]
+    async fn backfill_stored_blocks() {
+        let factory = create_test_provider_factory();
+        let data = BlockChainTestData::default();
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .insert_block(data.genesis.clone().try_seal_with_senders().unwrap(), None)
+            .unwrap();
+        for (block, state) in data.blocks.iter().take(2) {
+            provider_rw
+                .append_blocks_with_state(
+                    vec![block.clone()],
+                    state.clone(),
+                    Default::default(),
+                    Default::default(),
+                    None,
+                )
+                .unwrap();
+        }
+        provider_rw.commit().unwrap();
+
+        let manager = TaskManager::current();
+        let provider =
+            BlockchainProvider::new(factory.clone(), NoopBlockchainTree::default()).unwrap();
+        let job = BackfillJob::new(
+            provider,
+            MAINNET.clone(),
+            EthEvmConfig::default(),
+            manager.executor(),
+            0..=2,
+        )
+        .with_batch_size(1);
+        assert!(job.is_outcome_stored(&(1..=2)).unwrap());
+
+        let notifications = job.clone().into_stream().collect::<Vec<_>>().await;
+        assert_eq!(notifications.len(), 2);
+
+        // every batch has the state at its own tip, not the latest state
+        let account: reth_primitives::Address = [0x60; 20].into();
+        let expected = [(1, 10, 10), (3, 20, 15)];
+        for (notification, ((block, state), (nonce, balance, slot))) in
+            notifications.into_iter().zip(data.blocks.iter().zip(expected))
+        {
+            let CanonStateNotification::Commit { new } = notification.unwrap() else {
+                panic!("expected a commit notification")
+            };
+            assert_eq!(new.blocks_iter().collect::<Vec<_>>(), vec![block]);
+            assert_eq!(new.state().receipts(), state.receipts());
+            assert_eq!(
+                new.state().account(&account),
+                Some(Some(Account { nonce, balance: U256::from(balance), bytecode_hash: None }))
+            );
+            assert_eq!(new.state().storage(&account, U256::from(5)), Some(U256::from(slot)));
+        }
+
+        // the blocks are executed again once their receipts are pruned
+        let provider_rw = factory.provider_rw().unwrap();
+        provider_rw
+            .save_prune_checkpoint(
+                PruneSegment::Receipts,
+                PruneCheckpoint {
+                    block_number: Some(1),
+                    tx_number: None,
+                    prune_mode: PruneMode::Full,
+                },
+            )
+            .unwrap();
+        provider_rw.commit().unwrap();
+        assert!(!job.is_outcome_stored(&(1..=2)).unwrap());
+        assert!(job.is_outcome_stored(&(2..=2)).unwrap());
+    }
+
+    #[test]
+    fn backfill_progress() {
+        let mut progress = BackfillProgress { range: 1..=10, finished_height: None };
+        assert_eq!(progress.remaining(), 10);
+        progress.finished_height = Some(4);
+        assert_eq!(progress.remaining(), 6);
+        assert!(!progress.is_finished());
+        progress.finished_height = Some(10);
+        assert!(progress.is_finished());
+    }
+}
@@ -3,12 +3,13 @@ use reth_node_core::{
     dirs::{ChainPath, DataDirPath},
     node_config::NodeConfig,
 };
-use reth_primitives::Head;
-use reth_provider::CanonStateNotification;
+use reth_primitives::{BlockNumber, Head};
+use reth_provider::{CanonStateNotification, ChainSpecProvider};
 use reth_tasks::TaskExecutor;
+use std::ops::RangeInclusive;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
 
-use crate::ExExEvent;
+use crate::{BackfillJob, ExExEvent};
 
 /// Captures the context that an ExEx has access to.
 #[derive(Debug)]
@@ -27,6 +28,8 @@ pub struct ExExContext<Node: FullNodeComponents> {
     pub reth_config: reth_config::Config,
     /// The transaction pool of the node.
     pub pool: Node::Pool,
+    /// The EVM configuration of the node.
+    pub evm_config: Node::Evm,
     /// Channel used to send [`ExExEvent`]s to the rest of the node.
     ///
     /// # Important
@@ -44,3 +47,22 @@ pub struct ExExContext<Node: FullNodeComponents> {
     pub notifications: Receiver<CanonStateNotification>,
     // TODO(alexey): add pool, payload builder, anything else?
 }
+
+impl<Node: FullNodeComponents> ExExContext<Node> {
+    /// Returns a [`BackfillJob`] for the given range of historical blocks.
+    ///
+    /// The job yields the backfilled blocks as [`CanonStateNotification`]s, so they can be handled
+    /// like the live [`ExExContext::notifications`].
+    pub fn backfill(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> BackfillJob<Node::Provider, Node::Evm> {
+        BackfillJob::new(
+            self.provider.clone(),
+            self.provider.chain_spec(),
+            self.evm_config.clone(),
+            self.task_executor.clone(),
+            range,
+        )
+    }
+}
@@ -14,6 +14,9 @@
 //! [`ExExContext`]. A new notification is emitted whenever blocks are executed in live and
 //! historical sync.
 //!
+//! Blocks from before the ExEx was installed can be backfilled with [`ExExContext::backfill`],
+//! which yields the same notifications for historical blocks.
+//!
 //! # Pruning
 //!
 //! ExEx's **SHOULD** emit an `ExExEvent::FinishedHeight` event to signify what blocks have been
@@ -23,6 +26,13 @@
 //! To clarify: if the ExEx emits `ExExEvent::FinishedHeight(0)` it will receive notifications for
 //! any `block_number > 0`.
 //!
+//! # Persistence
+//!
+//! If the [`ExExManager`] has an [`ExExWal`], notifications are persisted until every ExEx
+//! acknowledged them with an `ExExEvent::FinishedHeight` event, and replayed in order after a
+//! restart. ExEx's should therefore emit their last processed height on startup, to not process
+//! replayed notifications twice.
+//!
 //! [`Future`]: std::future::Future
 //! [`ExExContext`]: crate::ExExContext
 //! [`CanonStateNotification`]: reth_provider::CanonStateNotification
@@ -34,6 +44,9 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod backfill;
+pub use backfill::*;
+
 mod context;
 pub use context::*;
 
@@ -42,3 +55,6 @@ pub use event::*;
 
 mod manager;
 pub use manager::*;
+
+mod wal;
+pub use wal::*;
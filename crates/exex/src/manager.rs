@@ -9,13 +9,13 @@ use std::{
     task::{Context, Poll},
 };
 
-use crate::ExExEvent;
+use crate::{ExExEvent, ExExWal};
 use futures::StreamExt;
 use metrics::Gauge;
 use reth_metrics::{metrics::Counter, Metrics};
 use reth_primitives::BlockNumber;
 use reth_provider::CanonStateNotification;
-use reth_tracing::tracing::debug;
+use reth_tracing::tracing::{debug, warn};
 use tokio::sync::{
     mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
     watch,
@@ -31,6 +31,11 @@ struct ExExMetrics {
     notifications_sent_total: Counter,
     /// The total number of events an ExEx has sent to the manager.
     events_sent_total: Counter,
+    /// The finished height of an ExEx, zero if it has not emitted a `FinishedHeight` event.
+    finished_height: Gauge,
+    /// Whether an ExEx has the lowest finished height or no finished height at all, which limits
+    /// the pruning of the node.
+    holds_pruning: Gauge,
 }
 
 /// A handle to an ExEx used by the [`ExExManager`] to communicate with ExEx's.
@@ -51,11 +56,18 @@ pub struct ExExHandle {
     receiver: UnboundedReceiver<ExExEvent>,
     /// The ID of the next notification to send to this ExEx.
     next_notification_id: usize,
+    /// The ID of the first notification that was not acknowledged by this ExEx.
+    ///
+    /// A notification is acknowledged by a `FinishedHeight` event at or above its tip that is
+    /// emitted after the notification was sent.
+    acknowledged_notification_id: usize,
 
     /// The finished block number of the ExEx.
     ///
     /// If this is `None`, the ExEx has not emitted a `FinishedHeight` event.
     finished_height: Option<BlockNumber>,
+    /// Whether the finished height of the ExEx is more than the maximum prune lag behind the tip.
+    is_lagging: bool,
 }
 
 impl ExExHandle {
@@ -74,7 +86,9 @@ impl ExExHandle {
                 sender: PollSender::new(canon_tx),
                 receiver: event_rx,
                 next_notification_id: 0,
+                acknowledged_notification_id: 0,
                 finished_height: None,
+                is_lagging: false,
             },
             event_tx,
             canon_rx,
@@ -95,6 +109,9 @@ impl ExExHandle {
         if let Some(finished_height) = self.finished_height {
             if finished_height >= notification.tip().number {
                 self.next_notification_id = event_id + 1;
+                if self.acknowledged_notification_id == *event_id {
+                    self.acknowledged_notification_id = event_id + 1;
+                }
                 return Poll::Ready(Ok(()))
             }
         }
@@ -127,6 +144,8 @@ pub struct ExExManagerMetrics {
     ///
     /// Note that this might be slightly bigger than the maximum capacity in some cases.
     buffer_size: Gauge,
+    /// Current size of the write-ahead log in bytes.
+    wal_size: Gauge,
 }
 
 /// The execution extension manager.
@@ -138,6 +157,7 @@ pub struct ExExManagerMetrics {
 /// - Backpressure
 /// - Error handling
 /// - Monitoring
+/// - Persisting notifications until they're acknowledged, if an [`ExExWal`] is configured
 #[derive(Debug)]
 pub struct ExExManager {
     /// Handles to communicate with the ExEx's.
@@ -165,6 +185,9 @@ pub struct ExExManager {
     /// Whether the manager is ready to receive new notifications.
     is_ready: watch::Sender<bool>,
 
+    /// The write-ahead log of the notifications that were not acknowledged by all ExEx's.
+    wal: Option<ExExWal>,
+
     /// The finished height of all ExEx's.
     ///
     /// This is the lowest common denominator between all ExEx's. If an ExEx has not emitted a
@@ -174,6 +197,11 @@ pub struct ExExManager {
     ///
     /// The number is inclusive, i.e. all blocks `<= finished_height` are safe to prune.
     finished_height: watch::Sender<Option<BlockNumber>>,
+    /// The highest tip of the received notifications.
+    tip: Option<BlockNumber>,
+    /// The number of blocks the finished height of an ExEx may be behind the tip before a warning
+    /// is logged, since it holds back the pruning of the node.
+    max_prune_lag: Option<u64>,
 
     /// A handle to the ExEx manager.
     handle: ExExManagerHandle,
@@ -213,7 +241,10 @@ impl ExExManager {
             current_capacity: Arc::clone(&current_capacity),
 
             is_ready: is_ready_tx,
+            wal: None,
             finished_height: finished_height_tx,
+            tip: None,
+            max_prune_lag: None,
 
             handle: ExExManagerHandle {
                 exex_tx: handle_tx,
@@ -227,18 +258,59 @@ impl ExExManager {
         }
     }
 
+    /// Sets the write-ahead log of the manager.
+    ///
+    /// The notifications that are stored in the log are sent to the ExEx's before any new
+    /// notification. Once the log reached its maximum size, the manager has no capacity until the
+    /// ExEx's acknowledged enough notifications.
+    ///
+    /// # Panics
+    ///
+    /// If the manager already received notifications.
+    pub fn with_wal(mut self, mut wal: ExExWal) -> Self {
+        assert_eq!(self.next_id, 0, "the wal must be set before notifications are received");
+        for notification in wal.take_unsent() {
+            self.push_notification(notification);
+        }
+        self.wal = Some(wal);
+        self.update_capacity();
+        self
+    }
+
+    /// Sets the number of blocks the finished height of an ExEx may be behind the tip before a
+    /// warning is logged.
+    ///
+    /// Pruning is never done above the finished height of any ExEx, so an ExEx that falls behind
+    /// holds back the pruning of the node.
+    pub fn with_max_prune_lag(mut self, max_prune_lag: Option<u64>) -> Self {
+        self.max_prune_lag = max_prune_lag;
+        self
+    }
+
     /// Returns the handle to the manager.
     pub fn handle(&self) -> ExExManagerHandle {
         self.handle.clone()
     }
 
+    /// Returns true if the write-ahead log reached its maximum size.
+    fn is_wal_full(&self) -> bool {
+        self.wal.as_ref().is_some_and(ExExWal::is_full)
+    }
+
     /// Updates the current buffer capacity and notifies all `is_ready` watchers of the manager's
     /// readiness to receive notifications.
     fn update_capacity(&mut self) {
-        let capacity = self.max_capacity.saturating_sub(self.buffer.len());
+        let capacity = if self.is_wal_full() {
+            0
+        } else {
+            self.max_capacity.saturating_sub(self.buffer.len())
+        };
         self.current_capacity.store(capacity, Ordering::Relaxed);
         self.metrics.current_capacity.set(capacity as f64);
         self.metrics.buffer_size.set(self.buffer.len() as f64);
+        if let Some(wal) = &self.wal {
+            self.metrics.wal_size.set(wal.size() as f64);
+        }
 
         // we can safely ignore if the channel is closed, since the manager always holds it open
         // internally
@@ -248,6 +320,8 @@ impl ExExManager {
     /// Pushes a new notification into the managers internal buffer, assigning the notification a
     /// unique ID.
     fn push_notification(&mut self, notification: CanonStateNotification) {
+        let tip = notification.tip().number;
+        self.tip = Some(self.tip.map_or(tip, |curr| curr.max(tip)));
         let next_id = self.next_id;
         self.buffer.push_back((next_id, notification));
         self.next_id += 1;
@@ -259,9 +333,16 @@ impl Future for ExExManager {
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         // drain handle notifications
-        while self.buffer.len() < self.max_capacity {
+        while self.buffer.len() < self.max_capacity && !self.is_wal_full() {
             if let Poll::Ready(Some(notification)) = self.handle_rx.poll_recv(cx) {
                 debug!("received new notification");
+                // the notification is persisted before it's sent to any exex
+                let next_id = self.next_id;
+                if let Some(wal) = &mut self.wal {
+                    if let Err(err) = wal.append(next_id, &notification) {
+                        return Poll::Ready(Err(err))
+                    }
+                }
                 self.push_notification(notification);
                 continue
             }
@@ -302,16 +383,54 @@ impl Future for ExExManager {
         self.update_capacity();
 
         // handle incoming exex events
-        for exex in self.exex_handles.iter_mut() {
+        let this = &mut *self;
+        for exex in this.exex_handles.iter_mut() {
             while let Poll::Ready(Some(event)) = exex.receiver.poll_recv(cx) {
                 debug!(?event, id = exex.id, "received event from exex");
                 exex.metrics.events_sent_total.increment(1);
                 match event {
-                    ExExEvent::FinishedHeight(height) => exex.finished_height = Some(height),
+                    ExExEvent::FinishedHeight(height) => {
+                        exex.finished_height = Some(height);
+
+                        // acknowledge the sent notifications up to the height, in order
+                        let acknowledged_id = exex.acknowledged_notification_id;
+                        let entries = this
+                            .wal
+                            .iter()
+                            .flat_map(ExExWal::entries)
+                            .skip_while(|entry| entry.notification_id < acknowledged_id);
+                        for entry in entries {
+                            if entry.notification_id >= exex.next_notification_id ||
+                                entry.tip > height
+                            {
+                                break
+                            }
+                            exex.acknowledged_notification_id = entry.notification_id + 1;
+                        }
+                    }
                 }
             }
         }
 
+        // remove the notifications that were acknowledged by all exexs from the wal
+        if let Some(wal) = &mut this.wal {
+            let was_full = wal.is_full();
+            let acknowledged_id = this
+                .exex_handles
+                .iter()
+                .map(|exex| exex.acknowledged_notification_id)
+                .min()
+                .unwrap_or(this.next_id);
+            if let Err(err) = wal.truncate(acknowledged_id) {
+                return Poll::Ready(Err(err))
+            }
+            if was_full && !wal.is_full() {
+                // there is capacity for new notifications again
+                this.update_capacity();
+                cx.waker().wake_by_ref();
+            }
+        }
+
         // update watch channel block number
         let finished_height = self.exex_handles.iter_mut().try_fold(u64::MAX, |curr, exex| {
             let height = match exex.finished_height {
@@ -329,6 +448,30 @@ impl Future for ExExManager {
             let _ = self.finished_height.send(Some(finished_height));
         }
 
+        // update the pruning metrics of the exexs and warn about the ones holding back pruning
+        let this = &mut *self;
+        let lowest = finished_height.ok();
+        for exex in this.exex_handles.iter_mut() {
+            // an exex without a finished height holds back pruning entirely
+            let holds_pruning = exex.finished_height.is_none() || exex.finished_height == lowest;
+            exex.metrics.finished_height.set(exex.finished_height.unwrap_or_default() as f64);
+            exex.metrics.holds_pruning.set(if holds_pruning { 1.0 } else { 0.0 });
+
+            let Some(height) = exex.finished_height else { continue };
+            let (Some(tip), Some(max_prune_lag)) = (this.tip, this.max_prune_lag) else { continue };
+            let is_lagging = tip.saturating_sub(height) > max_prune_lag;
+            if is_lagging && !exex.is_lagging {
+                warn!(
+                    exex.id,
+                    finished_height = height,
+                    tip,
+                    max_prune_lag,
+                    "ExEx is holding back pruning"
+                );
+            }
+            exex.is_lagging = is_lagging;
+        }
+
         Poll::Pending
     }
 }
@@ -414,6 +557,21 @@ impl ExExManagerHandle {
         *self.finished_height.borrow_and_update()
     }
 
+    /// Returns a receiver of whether the manager is ready for new notifications.
+    ///
+    /// The manager isn't ready while its buffer or its write-ahead log is full, i.e. while all
+    /// ExEx's are too far behind, so the node should stop advancing until it's ready again.
+    pub fn ready_receiver(&self) -> watch::Receiver<bool> {
+        self.is_ready_receiver.clone()
+    }
+
+    /// Returns a receiver of the finished height of all ExEx's, see [`Self::finished_height`].
+    ///
+    /// The pruner uses it to never prune blocks that an ExEx has not finished yet.
+    pub fn finished_height_receiver(&self) -> watch::Receiver<Option<BlockNumber>> {
+        self.finished_height.clone()
+    }
+
     /// Wait until the manager is ready for new notifications.
     pub async fn ready(&mut self) {
         poll_fn(|cx| self.poll_ready(cx)).await
@@ -449,6 +607,114 @@ impl Clone for ExExManagerHandle {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use reth_primitives::{Header, SealedBlock, SealedBlockWithSenders};
+    use reth_provider::{BundleStateWithReceipts, Chain};
+
+    fn commit(number: BlockNumber) -> CanonStateNotification {
+        let header = Header { number, ..Default::default() }.seal_slow();
+        let block = SealedBlockWithSenders {
+            block: SealedBlock { header, ..Default::default() },
+            senders: Vec::new(),
+        };
+        let chain = Chain::new([block], BundleStateWithReceipts::default(), None);
+        CanonStateNotification::Commit { new: Arc::new(chain) }
+    }
+
+    fn poll_manager(manager: &mut Pin<Box<ExExManager>>) {
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        assert!(manager.as_mut().poll(&mut cx).is_pending());
+    }
+
+    fn wal_files(dir: &std::path::Path) -> usize {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .filter(|file| file.as_ref().unwrap().path().extension().unwrap() == "wal")
+            .count()
+    }
+
+    #[test]
+    fn replays_wal_after_restart() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let (exex, events_tx, mut notifications) = ExExHandle::new("test".to_string());
+        let wal = ExExWal::open(dir.path(), u64::MAX).unwrap();
+        let mut manager = Box::pin(ExExManager::new(vec![exex], 16).with_wal(wal));
+        let handle = manager.handle();
+        handle.send(commit(1)).unwrap();
+        handle.send(commit(2)).unwrap();
+
+        // the exex channel holds a single notification
+        poll_manager(&mut manager);
+        assert_eq!(notifications.try_recv().unwrap().tip().number, 1);
+        poll_manager(&mut manager);
+        assert_eq!(notifications.try_recv().unwrap().tip().number, 2);
+        assert_eq!(wal_files(dir.path()), 2);
+
+        // only the acknowledged notification is removed from the wal
+        events_tx.send(ExExEvent::FinishedHeight(1)).unwrap();
+        poll_manager(&mut manager);
+        assert_eq!(wal_files(dir.path()), 1);
+        drop(manager);
+
+        // the unacknowledged notification is sent again after a restart
+        let (exex, events_tx, mut notifications) = ExExHandle::new("test".to_string());
+        let wal = ExExWal::open(dir.path(), u64::MAX).unwrap();
+        let mut manager = Box::pin(ExExManager::new(vec![exex], 16).with_wal(wal));
+        poll_manager(&mut manager);
+        assert_eq!(notifications.try_recv().unwrap().tip().number, 2);
+        assert!(notifications.try_recv().is_err());
+
+        events_tx.send(ExExEvent::FinishedHeight(2)).unwrap();
+        poll_manager(&mut manager);
+        assert_eq!(wal_files(dir.path()), 0);
+    }
+
+    #[test]
+    fn is_not_ready_while_wal_is_full() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let (exex, events_tx, mut notifications) = ExExHandle::new("test".to_string());
+        let wal = ExExWal::open(dir.path(), 1).unwrap();
+        let mut manager = Box::pin(ExExManager::new(vec![exex], 16).with_wal(wal));
+        let handle = manager.handle();
+        let ready = handle.ready_receiver();
+        handle.send(commit(1)).unwrap();
+
+        poll_manager(&mut manager);
+        assert_eq!(notifications.try_recv().unwrap().tip().number, 1);
+        assert!(!handle.has_capacity());
+        assert!(!*ready.borrow());
+
+        // the acknowledgement frees the wal
+        events_tx.send(ExExEvent::FinishedHeight(1)).unwrap();
+        poll_manager(&mut manager);
+        assert!(handle.has_capacity());
+        assert!(*ready.borrow());
+    }
+
+    #[test]
+    fn tracks_prune_lag() {
+        let (exex, events_tx, mut notifications) = ExExHandle::new("test".to_string());
+        let mut manager = Box::pin(ExExManager::new(vec![exex], 16).with_max_prune_lag(Some(1)));
+        let handle = manager.handle();
+        for number in 1..=3 {
+            handle.send(commit(number)).unwrap();
+        }
+        for number in 1..=3 {
+            poll_manager(&mut manager);
+            assert_eq!(notifications.try_recv().unwrap().tip().number, number);
+        }
+
+        events_tx.send(ExExEvent::FinishedHeight(1)).unwrap();
+        poll_manager(&mut manager);
+        assert!(manager.exex_handles[0].is_lagging);
+
+        events_tx.send(ExExEvent::FinishedHeight(2)).unwrap();
+        poll_manager(&mut manager);
+        assert!(!manager.exex_handles[0].is_lagging);
+    }
+
     #[tokio::test]
     async fn delivers_events() {}
 
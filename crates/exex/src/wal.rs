@@ -0,0 +1,302 @@
+use reth_primitives::{BlockNumber, Receipts, SealedBlockWithSenders};
+use reth_provider::{BundleStateWithReceipts, CanonStateNotification, Chain};
+use reth_tracing::tracing::debug;
+use revm::db::BundleState;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The file extension of the entries of the [`ExExWal`].
+const WAL_ENTRY_EXTENSION: &str = "wal";
+
+/// A write-ahead log of the notifications of the [`ExExManager`](crate::ExExManager).
+///
+/// Every notification is written to the log before it's sent to the ExExes, and removed once all
+/// ExExes acknowledged it with an [`ExExEvent::FinishedHeight`](crate::ExExEvent) that was emitted
+/// after the notification was delivered. The notifications that were not acknowledged when the
+/// node stopped are sent to the ExExes again on the next start, before any new notification.
+///
+/// Every notification is stored as a separate file in the log directory, named after its position
+/// in the log.
+#[derive(Debug)]
+pub struct ExExWal {
+    /// The directory of the log.
+    dir: PathBuf,
+    /// The entries of the log, in order.
+    entries: VecDeque<WalEntry>,
+    /// The notifications that were loaded from the log and are not sent yet.
+    unsent: Vec<CanonStateNotification>,
+    /// The position of the next entry.
+    next_position: u64,
+    /// The total size of the entries in bytes.
+    size: u64,
+    /// The maximum size of the entries in bytes.
+    max_size: u64,
+}
+
+impl ExExWal {
+    /// Opens the log in the given directory, creating it if it doesn't exist.
+    ///
+    /// The notifications of the existing entries are sent again when the log is passed to
+    /// [`ExExManager::with_wal`](crate::ExExManager::with_wal).
+    pub fn open(dir: impl Into<PathBuf>, max_size: u64) -> eyre::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        sync_dir(&dir)?;
+
+        let mut files = Vec::new();
+        for file in fs::read_dir(&dir)? {
+            let path = file?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(WAL_ENTRY_EXTENSION) {
+                continue
+            }
+            let Some(position) =
+                path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse().ok())
+            else {
+                continue
+            };
+            files.push((position, path));
+        }
+        files.sort_unstable_by_key(|(position, _)| *position);
+
+        let mut wal = Self {
+            dir,
+            entries: VecDeque::with_capacity(files.len()),
+            unsent: Vec::with_capacity(files.len()),
+            next_position: files.last().map_or(0, |(position, _)| position + 1),
+            size: 0,
+            max_size,
+        };
+        for (position, path) in files {
+            let data = fs::read(&path)?;
+            let notification: CanonStateNotification =
+                serde_json::from_slice::<WalNotification>(&data)?.into();
+            wal.size += data.len() as u64;
+            wal.entries.push_back(WalEntry {
+                position,
+                notification_id: wal.unsent.len(),
+                tip: notification.tip().number,
+                size: data.len() as u64,
+            });
+            wal.unsent.push(notification);
+        }
+
+        debug!(target: "exex::wal", entries = wal.entries.len(), size = wal.size, "Opened WAL");
+        Ok(wal)
+    }
+
+    /// Takes the notifications that were loaded from the log.
+    ///
+    /// Their notification IDs are their indexes.
+    pub(crate) fn take_unsent(&mut self) -> Vec<CanonStateNotification> {
+        std::mem::take(&mut self.unsent)
+    }
+
+    /// Writes a notification with the given notification ID of the manager to the log.
+    pub(crate) fn append(
+        &mut self,
+        notification_id: usize,
+        notification: &CanonStateNotification,
+    ) -> eyre::Result<()> {
+        let data = serde_json::to_vec(&WalNotification::from(notification))?;
+        let position = self.next_position;
+        let path = self.entry_path(position);
+
+        // write to a temporary file first, so an interrupted write does not leave a partial entry
+        let tmp_path = path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(&data)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, &path)?;
+        // the rename is only durable once the directory is synced
+        sync_dir(&self.dir)?;
+
+        self.next_position += 1;
+        self.size += data.len() as u64;
+        self.entries.push_back(WalEntry {
+            position,
+            notification_id,
+            tip: notification.tip().number,
+            size: data.len() as u64,
+        });
+        Ok(())
+    }
+
+    /// Returns the entries that are not acknowledged by all ExExes.
+    pub(crate) fn entries(&self) -> impl Iterator<Item = &WalEntry> {
+        self.entries.iter()
+    }
+
+    /// Removes all entries with a notification ID below the given one, which were acknowledged by
+    /// all ExExes.
+    pub(crate) fn truncate(&mut self, notification_id: usize) -> eyre::Result<()> {
+        let mut truncated = false;
+        while let Some(&entry) = self.entries.front() {
+            if entry.notification_id >= notification_id {
+                break
+            }
+            fs::remove_file(self.entry_path(entry.position))?;
+            self.size -= entry.size;
+            self.entries.pop_front();
+            truncated = true;
+        }
+        if truncated {
+            sync_dir(&self.dir)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the total size of the entries in bytes.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Returns true if the size of the entries reached the maximum size.
+    pub fn is_full(&self) -> bool {
+        self.size >= self.max_size
+    }
+
+    /// Returns the path of the entry at the given position.
+    fn entry_path(&self, position: u64) -> PathBuf {
+        self.dir.join(format!("{position:020}.{WAL_ENTRY_EXTENSION}"))
+    }
+}
+
+/// Syncs the given directory, so that the creation, renaming and removal of its files are durable.
+fn sync_dir(dir: &Path) -> std::io::Result<()> {
+    fs::File::open(dir)?.sync_all()
+}
+
+/// An entry of the [`ExExWal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WalEntry {
+    /// The position of the entry in the log.
+    position: u64,
+    /// The notification ID of the manager.
+    pub(crate) notification_id: usize,
+    /// The tip of the notification.
+    pub(crate) tip: BlockNumber,
+    /// The size of the entry in bytes.
+    size: u64,
+}
+
+/// The stored form of a [`CanonStateNotification`].
+#[derive(Debug, Serialize, Deserialize)]
+enum WalNotification {
+    /// A [`CanonStateNotification::Commit`].
+    Commit { new: WalChain },
+    /// A [`CanonStateNotification::Reorg`].
+    Reorg { old: WalChain, new: WalChain },
+}
+
+impl From<&CanonStateNotification> for WalNotification {
+    fn from(notification: &CanonStateNotification) -> Self {
+        match notification {
+            CanonStateNotification::Commit { new } => Self::Commit { new: new.as_ref().into() },
+            CanonStateNotification::Reorg { old, new } => {
+                Self::Reorg { old: old.as_ref().into(), new: new.as_ref().into() }
+            }
+        }
+    }
+}
+
+impl From<WalNotification> for CanonStateNotification {
+    fn from(notification: WalNotification) -> Self {
+        match notification {
+            WalNotification::Commit { new } => Self::Commit { new: Arc::new(new.into()) },
+            WalNotification::Reorg { old, new } => {
+                Self::Reorg { old: Arc::new(old.into()), new: Arc::new(new.into()) }
+            }
+        }
+    }
+}
+
+/// The stored form of a [`Chain`].
+///
+/// The trie updates of the chain are not stored.
+#[derive(Debug, Serialize, Deserialize)]
+struct WalChain {
+    blocks: Vec<SealedBlockWithSenders>,
+    bundle: BundleState,
+    receipts: Receipts,
+    first_block: BlockNumber,
+}
+
+impl From<&Chain> for WalChain {
+    fn from(chain: &Chain) -> Self {
+        let state = chain.state();
+        Self {
+            blocks: chain.blocks().values().cloned().collect(),
+            bundle: state.state().clone(),
+            receipts: state.receipts().clone(),
+            first_block: state.first_block(),
+        }
+    }
+}
+
+impl From<WalChain> for Chain {
+    fn from(chain: WalChain) -> Self {
+        let state = BundleStateWithReceipts::new(chain.bundle, chain.receipts, chain.first_block);
+        Chain::new(chain.blocks, state, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Header, SealedBlock};
+
+    fn commit(number: BlockNumber) -> CanonStateNotification {
+        let header = Header { number, ..Default::default() }.seal_slow();
+        let block = SealedBlockWithSenders {
+            block: SealedBlock { header, ..Default::default() },
+            senders: Vec::new(),
+        };
+        let chain = Chain::new([block], BundleStateWithReceipts::default(), None);
+        CanonStateNotification::Commit { new: Arc::new(chain) }
+    }
+
+    #[test]
+    fn replays_unacknowledged_entries() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut wal = ExExWal::open(dir.path(), u64::MAX).unwrap();
+        for (id, number) in [1, 2, 3].into_iter().enumerate() {
+            wal.append(id, &commit(number)).unwrap();
+        }
+        wal.truncate(1).unwrap();
+        assert_eq!(wal.entries().count(), 2);
+
+        let mut wal = ExExWal::open(dir.path(), u64::MAX).unwrap();
+        let tips = wal
+            .take_unsent()
+            .iter()
+            .map(|notification| notification.tip().number)
+            .collect::<Vec<_>>();
+        assert_eq!(tips, vec![2, 3]);
+        assert_eq!(
+            wal.entries().map(|entry| (entry.notification_id, entry.tip)).collect::<Vec<_>>(),
+            vec![(0, 2), (1, 3)]
+        );
+
+        // new entries are appended after the loaded ones
+        wal.append(2, &commit(4)).unwrap();
+        wal.truncate(3).unwrap();
+        assert_eq!(wal.size(), 0);
+        assert!(ExExWal::open(dir.path(), u64::MAX).unwrap().take_unsent().is_empty());
+    }
+
+    #[test]
+    fn is_full() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut wal = ExExWal::open(dir.path(), 1).unwrap();
+        assert!(!wal.is_full());
+        wal.append(0, &commit(1)).unwrap();
+        assert!(wal.is_full());
+    }
+}
@@ -3,7 +3,10 @@ use reth_primitives::{
     BlockHash, BlockNumHash, BlockNumber, Receipt, SealedBlock, SealedBlockWithSenders,
     SealedHeader,
 };
-use std::collections::{BTreeMap, HashSet};
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::Duration,
+};
 
 use self::error::CanonicalError;
 
@@ -43,6 +46,12 @@ pub trait BlockchainTreeEngine: BlockchainTreeViewer + Send + Sync {
         }
     }
 
+    /// Records the time spent validating the execution payload of the block with the given hash.
+    ///
+    /// This is called by the consensus engine after the block was inserted, so that the time is
+    /// reported together with the other timings of the block. Does nothing by default.
+    fn on_payload_validated(&self, _block_hash: BlockHash, _elapsed: Duration) {}
+
     /// Buffer block with senders
     fn buffer_block(&self, block: SealedBlockWithSenders) -> Result<(), InsertBlockError>;
 
@@ -72,7 +81,9 @@ pub trait BlockchainTreeEngine: BlockchainTreeViewer + Send + Sync {
     /// # Note
     ///
     /// This finalizes `last_finalized_block` prior to reading the canonical hashes (using
-    /// [`BlockchainTreeEngine::finalize_block`]).
+    /// [`BlockchainTreeEngine::finalize_block`]). The buffered blocks that are not above the
+    /// canonical head and didn't connect are discarded afterwards, since this is used once the
+    /// pipeline finished.
     fn connect_buffered_blocks_to_canonical_hashes_and_finalize(
         &self,
         last_finalized_block: BlockNumber,
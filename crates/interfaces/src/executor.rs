@@ -29,6 +29,15 @@ pub enum BlockValidationError {
     /// Error when header bloom filter doesn't match expected value
     #[error("header bloom filter mismatch: {0}")]
     BloomLogDiff(GotExpectedBoxed<Bloom>),
+    /// Error when the receipts root or the logs bloom of a block doesn't match its receipts, with
+    /// the description of the first receipt that's known to mismatch.
+    #[error("{error}; {receipt}")]
+    ReceiptsMismatch {
+        /// The mismatch of the receipts root or the logs bloom.
+        error: Box<BlockValidationError>,
+        /// The description of the first receipt that's known to mismatch.
+        receipt: String,
+    },
     /// Error when the state root does not match the expected value.
     #[error(transparent)]
     StateRoot(#[from] StateRootError),
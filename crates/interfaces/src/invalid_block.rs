@@ -0,0 +1,42 @@
+//! Types for the blocks that were rejected as invalid.
+
+use parking_lot::RwLock;
+use reth_primitives::{SealedBlock, SealedHeader};
+use std::sync::Arc;
+
+/// A block that was rejected as invalid by the engine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidBlock {
+    /// The header of the invalid block.
+    pub header: SealedHeader,
+    /// The invalid block, if its body is known.
+    ///
+    /// The body is not known for the blocks that were rejected by the pipeline.
+    pub block: Option<SealedBlock>,
+    /// Why the block was rejected.
+    pub reason: String,
+    /// The unix timestamp in seconds of when the block was first rejected.
+    pub first_seen: u64,
+}
+
+/// A shared handle to the invalid blocks that are known to the engine.
+///
+/// The engine updates the handle every time the known invalid blocks change, and the handle can be
+/// cloned to read the snapshots, e.g. to serve them over RPC.
+#[derive(Clone, Debug, Default)]
+pub struct InvalidBlocksHandle {
+    /// The known invalid blocks, oldest first.
+    blocks: Arc<RwLock<Vec<InvalidBlock>>>,
+}
+
+impl InvalidBlocksHandle {
+    /// Replaces the known invalid blocks.
+    pub fn update(&self, blocks: Vec<InvalidBlock>) {
+        *self.blocks.write() = blocks;
+    }
+
+    /// Returns the known invalid blocks, oldest first.
+    pub fn snapshot(&self) -> Vec<InvalidBlock> {
+        self.blocks.read().clone()
+    }
+}
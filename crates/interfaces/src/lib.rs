@@ -37,6 +37,12 @@ pub mod provider;
 /// Syncing related traits.
 pub mod sync;
 
+/// Configuration reload related types.
+pub mod reload;
+
+/// Invalid block related types.
+pub mod invalid_block;
+
 /// BlockchainTree related traits.
 pub mod blockchain_tree;
 
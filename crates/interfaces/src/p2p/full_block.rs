@@ -296,7 +296,7 @@ enum BodyResponse {
 ///   - ommer hash
 ///   - transaction root
 ///   - withdrawals root
-fn ensure_valid_body_response(
+pub fn ensure_valid_body_response(
     header: &SealedHeader,
     block: &BlockBody,
 ) -> Result<(), ConsensusError> {
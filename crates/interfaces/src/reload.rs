@@ -0,0 +1,67 @@
+//! Types used to reload the configuration of a running node.
+
+use reth_rpc_types::ConfigReloadReport;
+use tokio::sync::{mpsc, oneshot};
+
+/// The result of a configuration reload.
+pub type ConfigReloadResult = Result<ConfigReloadReport, ConfigReloadError>;
+
+/// A request to reload the configuration, answered on the enclosed channel.
+pub type ConfigReloadRequest = oneshot::Sender<ConfigReloadResult>;
+
+/// Errors that prevent the configuration from being reloaded at all.
+#[derive(Debug, thiserror::Error, Clone, PartialEq, Eq)]
+pub enum ConfigReloadError {
+    /// The configuration file could not be read or parsed.
+    #[error("failed to load the configuration file: {0}")]
+    Load(String),
+    /// The task that applies the configuration is not running.
+    #[error("the configuration reloader is not running")]
+    Closed,
+}
+
+/// A handle to request a reload of the configuration file of the node.
+///
+/// The requests are received by the task that owns the active configuration, which applies the
+/// changed settings that can be changed at runtime and reports the outcome of every changed
+/// setting.
+#[derive(Clone, Debug)]
+pub struct ConfigReloadHandle {
+    to_reloader: mpsc::UnboundedSender<ConfigReloadRequest>,
+}
+
+impl ConfigReloadHandle {
+    /// Creates a new handle and the receiver of its requests.
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<ConfigReloadRequest>) {
+        let (to_reloader, rx) = mpsc::unbounded_channel();
+        (Self { to_reloader }, rx)
+    }
+
+    /// Reloads the configuration file and returns the outcome of every changed setting.
+    pub async fn reload(&self) -> ConfigReloadResult {
+        let (tx, rx) = oneshot::channel();
+        self.to_reloader.send(tx).map_err(|_| ConfigReloadError::Closed)?;
+        rx.await.map_err(|_| ConfigReloadError::Closed)?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reload_request() {
+        let (handle, mut rx) = ConfigReloadHandle::new();
+        let report = ConfigReloadReport { path: "reth.toml".to_string(), fields: Vec::new() };
+
+        let expected = report.clone();
+        tokio::spawn(async move {
+            let request = rx.recv().await.unwrap();
+            request.send(Ok(expected)).unwrap();
+        });
+        assert_eq!(handle.reload().await, Ok(report));
+
+        // the reloader dropped the receiver
+        assert_eq!(handle.reload().await, Err(ConfigReloadError::Closed));
+    }
+}
@@ -1,6 +1,11 @@
 //! Traits used when interacting with the sync status of the network.
 
-use reth_primitives::Head;
+use parking_lot::RwLock;
+use reth_primitives::{
+    stage::{StageProgress, SyncStage},
+    Head,
+};
+use std::sync::Arc;
 
 /// A type that provides information about whether the node is currently syncing and the network is
 /// currently serving syncing related requests.
@@ -67,3 +72,64 @@ impl NetworkSyncUpdater for NoopSyncStateUpdater {
     fn update_sync_state(&self, _state: SyncState) {}
     fn update_status(&self, _: Head) {}
 }
+
+/// A shared handle to the latest [StageProgress] of every [SyncStage].
+///
+/// The components making progress, like the pipeline, the pruner and the static file producer,
+/// update the handle every time they commit, and the handle can be cloned to read the snapshots,
+/// e.g. to serve them over RPC.
+#[derive(Clone, Debug, Default)]
+pub struct SyncStatusHandle {
+    /// The latest progress of every stage, in the order the stages first reported progress.
+    progress: Arc<RwLock<Vec<StageProgress>>>,
+}
+
+impl SyncStatusHandle {
+    /// Replaces the latest progress of the stage.
+    pub fn update(&self, progress: StageProgress) {
+        let mut snapshots = self.progress.write();
+        match snapshots.iter_mut().find(|snapshot| snapshot.stage == progress.stage) {
+            Some(snapshot) => *snapshot = progress,
+            None => snapshots.push(progress),
+        }
+    }
+
+    /// Returns the latest progress of the stage, if it reported any.
+    pub fn stage(&self, stage: SyncStage) -> Option<StageProgress> {
+        self.progress.read().iter().find(|snapshot| snapshot.stage == stage).copied()
+    }
+
+    /// Returns the latest progress of every stage that reported any.
+    pub fn snapshot(&self) -> Vec<StageProgress> {
+        self.progress.read().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::stage::StageId;
+    use std::time::Duration;
+
+    #[test]
+    fn sync_status_keeps_latest_progress() {
+        let handle = SyncStatusHandle::default();
+        let progress = |stage, checkpoint| StageProgress {
+            stage,
+            checkpoint,
+            entities: None,
+            elapsed: Duration::from_secs(1),
+            eta: None,
+        };
+
+        handle.update(progress(SyncStage::Stage(StageId::Headers), 10));
+        handle.update(progress(SyncStage::Pruner, 5));
+        handle.clone().update(progress(SyncStage::Stage(StageId::Headers), 20));
+
+        assert_eq!(
+            handle.snapshot(),
+            vec![progress(SyncStage::Stage(StageId::Headers), 20), progress(SyncStage::Pruner, 5)]
+        );
+        assert_eq!(handle.stage(SyncStage::StaticFileProducer), None);
+    }
+}
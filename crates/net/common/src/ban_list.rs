@@ -103,6 +103,14 @@ impl BanList {
         self.banned_peers.contains_key(peer_id)
     }
 
+    /// Returns until when the given peer id is banned, if it's banned.
+    ///
+    /// The inner value is `None` if the peer is banned indefinitely.
+    #[inline]
+    pub fn banned_peer_until(&self, peer_id: &PeerId) -> Option<Option<Instant>> {
+        self.banned_peers.get(peer_id).copied()
+    }
+
     /// Unbans the ip address
     pub fn unban_ip(&mut self, ip: &IpAddr) {
         self.banned_ips.remove(ip);
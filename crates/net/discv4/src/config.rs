@@ -56,6 +56,13 @@ pub struct Discv4Config {
     pub enable_lookup: bool,
     /// Whether to enforce EIP-868 extension.
     pub enable_eip868: bool,
+    /// Whether to defer the [`DiscoveryUpdate::Added`](crate::DiscoveryUpdate::Added) of a node
+    /// that announced an ENR until its ENR was received, or the ENR request expired.
+    ///
+    /// If the ENR contains an `eth` fork id, the node is reported as
+    /// [`DiscoveryUpdate::AddedWithForkId`](crate::DiscoveryUpdate::AddedWithForkId) instead, so
+    /// the fork id is known before the node is dialed.
+    pub wait_for_enr: bool,
     /// Whether to respect expiration timestamps in messages.
     pub enforce_expiration_timestamps: bool,
     /// Additional pairs to include in The [`Enr`](enr::Enr) if EIP-868 extension is enabled <https://eips.ethereum.org/EIPS/eip-868>
@@ -132,6 +139,7 @@ impl Default for Discv4Config {
             enable_dht_random_walk: true,
             enable_lookup: true,
             enable_eip868: true,
+            wait_for_enr: false,
             enforce_expiration_timestamps: true,
             additional_eip868_rlp_pairs: Default::default(),
             external_ip_resolver: Some(Default::default()),
@@ -221,6 +229,12 @@ impl Discv4ConfigBuilder {
         self
     }
 
+    /// Whether to defer reporting nodes that announced an ENR until their ENR was received.
+    pub fn wait_for_enr(&mut self, wait_for_enr: bool) -> &mut Self {
+        self.config.wait_for_enr = wait_for_enr;
+        self
+    }
+
     /// Whether to enable EIP-868
     pub fn enforce_expiration_timestamps(
         &mut self,
@@ -370,6 +370,24 @@ impl Discv4 {
         self.send_to_service(cmd);
     }
 
+    /// Sets the udp port that's announced, e.g. the external port of a mapping on the gateway.
+    ///
+    /// This will update our [`NodeRecord`]'s udp port and the udp port of the EIP-868 [`Enr`], the
+    /// port of the socket stays the same.
+    pub fn set_udp_port(&self, port: u16) {
+        let cmd = Discv4Command::SetUdpPort(port);
+        self.send_to_service(cmd);
+    }
+
+    /// Sets the external IP
+    ///
+    /// This will update our [`NodeRecord`]'s address and the IP of the EIP-868 [`Enr`], see
+    /// [`Discv4Service::set_external_ip_addr`].
+    pub fn set_external_ip_addr(&self, external_ip: IpAddr) {
+        let cmd = Discv4Command::SetExternalIp(external_ip);
+        self.send_to_service(cmd);
+    }
+
     /// Sets the pair in the EIP-868 [`Enr`] of the node.
     ///
     /// If the key already exists, this will update it.
@@ -827,6 +845,7 @@ impl Discv4Service {
         let removed = self.kbuckets.remove(&key);
         if removed {
             trace!(target: "discv4", ?node_id, "removed node");
+            self.pending_enr_requests.remove(&node_id);
             self.notify(DiscoveryUpdate::Removed(node_id));
         }
         removed
@@ -910,12 +929,7 @@ impl Discv4Service {
                 if !old_status.is_connected() {
                     let _ = entry.update(ConnectionState::Connected, Some(old_status.direction));
                     trace!(target: "discv4", ?record, "added after successful endpoint proof");
-                    self.notify(DiscoveryUpdate::Added(record));
-
-                    if has_enr_seq {
-                        // request the ENR of the node
-                        self.send_enr_request(record);
-                    }
+                    self.on_node_added(record, has_enr_seq);
                 }
             }
             kbucket::Entry::Pending(mut entry, mut status) => {
@@ -927,12 +941,7 @@ impl Discv4Service {
                     status.state = ConnectionState::Connected;
                     let _ = entry.update(status);
                     trace!(target: "discv4", ?record, "added after successful endpoint proof");
-                    self.notify(DiscoveryUpdate::Added(record));
-
-                    if has_enr_seq {
-                        // request the ENR of the node
-                        self.send_enr_request(record);
-                    }
+                    self.on_node_added(record, has_enr_seq);
                 }
             }
             _ => {}
@@ -1155,6 +1164,26 @@ impl Discv4Service {
         echo_hash
     }
 
+    /// Notifies the listeners about a node that was added to the table, and requests its ENR if
+    /// it announced one.
+    ///
+    /// If [`Discv4Config::wait_for_enr`] is set, the notification is deferred until the ENR was
+    /// received, see [`Self::on_enr_response`], or the request expired.
+    fn on_node_added(&mut self, record: NodeRecord, has_enr_seq: bool) {
+        if has_enr_seq {
+            // request the ENR of the node
+            self.send_enr_request(record);
+
+            if self.config.wait_for_enr {
+                if let Some(request) = self.pending_enr_requests.get_mut(&record.id) {
+                    request.deferred_added = Some(record);
+                    return
+                }
+            }
+        }
+        self.notify(DiscoveryUpdate::Added(record));
+    }
+
     /// Sends an enr request message to the node's UDP address.
     ///
     /// Returns the echo hash of the ping message.
@@ -1168,8 +1197,13 @@ impl Discv4Service {
         trace!(target: "discv4", ?enr_request, "sending enr request");
         let echo_hash = self.send_packet(Message::EnrRequest(enr_request), remote_addr);
 
-        self.pending_enr_requests
-            .insert(node.id, EnrRequestState { sent_at: Instant::now(), echo_hash });
+        // keep a deferred notification of a request that's replaced
+        let deferred_added =
+            self.pending_enr_requests.remove(&node.id).and_then(|req| req.deferred_added);
+        self.pending_enr_requests.insert(
+            node.id,
+            EnrRequestState { sent_at: Instant::now(), echo_hash, deferred_added },
+        );
     }
 
     /// Message handler for an incoming `Pong`.
@@ -1235,31 +1269,45 @@ impl Discv4Service {
     /// Handler for incoming `EnrResponse` message
     fn on_enr_response(&mut self, msg: EnrResponse, remote_addr: SocketAddr, id: PeerId) {
         trace!(target: "discv4", ?remote_addr, ?msg, "received ENR response");
-        if let Some(resp) = self.pending_enr_requests.remove(&id) {
-            if resp.echo_hash == msg.request_hash {
-                let key = kad_key(id);
-                let fork_id = msg.eth_fork_id();
-                let (record, old_fork_id) = match self.kbuckets.entry(&key) {
-                    kbucket::Entry::Present(mut entry, _) => {
-                        let id = entry.value_mut().update_with_fork_id(fork_id);
-                        (entry.value().record, id)
-                    }
-                    kbucket::Entry::Pending(mut entry, _) => {
-                        let id = entry.value().update_with_fork_id(fork_id);
-                        (entry.value().record, id)
-                    }
-                    _ => return,
-                };
-                match (fork_id, old_fork_id) {
-                    (Some(new), Some(old)) => {
-                        if new != old {
-                            self.notify(DiscoveryUpdate::EnrForkId(record, new))
-                        }
-                    }
-                    (Some(new), None) => self.notify(DiscoveryUpdate::EnrForkId(record, new)),
-                    _ => {}
+        let Some(resp) = self.pending_enr_requests.remove(&id) else { return };
+        if resp.echo_hash != msg.request_hash {
+            // the ENR won't be received anymore
+            if let Some(record) = resp.deferred_added {
+                self.notify(DiscoveryUpdate::Added(record));
+            }
+            return
+        }
+
+        let key = kad_key(id);
+        let fork_id = msg.eth_fork_id();
+        let (record, old_fork_id) = match self.kbuckets.entry(&key) {
+            kbucket::Entry::Present(mut entry, _) => {
+                let id = entry.value_mut().update_with_fork_id(fork_id);
+                (entry.value().record, id)
+            }
+            kbucket::Entry::Pending(mut entry, _) => {
+                let id = entry.value().update_with_fork_id(fork_id);
+                (entry.value().record, id)
+            }
+            _ => return,
+        };
+
+        if resp.deferred_added.is_some() {
+            match fork_id {
+                Some(fork_id) => self.notify(DiscoveryUpdate::AddedWithForkId(record, fork_id)),
+                None => self.notify(DiscoveryUpdate::Added(record)),
+            }
+            return
+        }
+
+        match (fork_id, old_fork_id) {
+            (Some(new), Some(old)) => {
+                if new != old {
+                    self.notify(DiscoveryUpdate::EnrForkId(record, new))
                 }
             }
+            (Some(new), None) => self.notify(DiscoveryUpdate::EnrForkId(record, new)),
+            _ => {}
         }
     }
 
@@ -1398,10 +1446,20 @@ impl Discv4Service {
     }
 
     fn evict_expired_requests(&mut self, now: Instant) {
+        let mut deferred_added = Vec::new();
         self.pending_enr_requests.retain(|_node_id, enr_request| {
-            now.duration_since(enr_request.sent_at) < self.config.enr_expiration
+            if now.duration_since(enr_request.sent_at) < self.config.enr_expiration {
+                return true
+            }
+            deferred_added.extend(enr_request.deferred_added);
+            false
         });
 
+        // report the nodes that didn't respond with their ENR without a fork id
+        for record in deferred_added {
+            self.notify(DiscoveryUpdate::Added(record));
+        }
+
         let mut failed_pings = Vec::new();
         self.pending_pings.retain(|node_id, ping_request| {
             if now.duration_since(ping_request.sent_at) > self.config.ping_expiration {
@@ -1628,6 +1686,20 @@ impl Discv4Service {
                         } else {
                             let _ = self.local_eip_868_enr.set_tcp6(port, &self.secret_key);
                         }
+                        *self.shared_node_record.lock() = self.local_node_record;
+                    }
+                    Discv4Command::SetUdpPort(port) => {
+                        debug!(target: "discv4", %port, "Update udp port");
+                        self.local_node_record.udp_port = port;
+                        if self.local_node_record.address.is_ipv4() {
+                            let _ = self.local_eip_868_enr.set_udp4(port, &self.secret_key);
+                        } else {
+                            let _ = self.local_eip_868_enr.set_udp6(port, &self.secret_key);
+                        }
+                        *self.shared_node_record.lock() = self.local_node_record;
+                    }
+                    Discv4Command::SetExternalIp(ip) => {
+                        self.set_external_ip_addr(ip);
                     }
 
                     Discv4Command::Terminated => {
@@ -1824,6 +1896,8 @@ pub(crate) async fn receive_loop(udp: Arc<UdpSocket>, tx: IngressSender, local_i
 enum Discv4Command {
     Add(NodeRecord),
     SetTcpPort(u16),
+    SetUdpPort(u16),
+    SetExternalIp(IpAddr),
     SetEIP868RLPPair { key: Vec<u8>, rlp: Bytes },
     Ban(PeerId, IpAddr),
     BanPeer(PeerId),
@@ -2070,6 +2144,8 @@ struct EnrRequestState {
     sent_at: Instant,
     // Hash sent in the Ping request
     echo_hash: B256,
+    // The node whose `Added` notification is deferred until the ENR was received
+    deferred_added: Option<NodeRecord>,
 }
 
 /// Stored node info.
@@ -2164,6 +2240,11 @@ enum PingReason {
 pub enum DiscoveryUpdate {
     /// A new node was discovered _and_ added to the table.
     Added(NodeRecord),
+    /// A new node was discovered _and_ added to the table, and its ENR contains the given
+    /// [`ForkId`].
+    ///
+    /// Only reported if [`Discv4Config::wait_for_enr`] is set.
+    AddedWithForkId(NodeRecord, ForkId),
     /// A new node was discovered but _not_ added to the table because it is currently full.
     DiscoveredAtCapacity(NodeRecord),
     /// Received a [`ForkId`] via EIP-868 for the given [`NodeRecord`].
@@ -2671,6 +2752,35 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_wait_for_enr() {
+        reth_tracing::init_test_tracing();
+        let fork_id = ForkId { hash: ForkHash([220, 233, 108, 45]), next: 0u64 };
+
+        let config = Discv4Config::builder()
+            .external_ip_resolver(None)
+            .wait_for_enr(true)
+            .add_eip868_pair("eth", EnrForkIdEntry::from(fork_id))
+            .build();
+        let (_discv4, mut service_1) = create_discv4_with_config(config.clone()).await;
+        let (_discv4, service_2) = create_discv4_with_config(config).await;
+        let record_2 = service_2.local_node_record;
+
+        let mut updates = service_1.update_stream();
+        service_1.add_node(record_2);
+        let _handle_1 = service_1.spawn();
+        let _handle_2 = service_2.spawn();
+
+        // the node is only reported once its ENR was received
+        match updates.next().await.unwrap() {
+            DiscoveryUpdate::AddedWithForkId(record, id) => {
+                assert_eq!(record.id, record_2.id);
+                assert_eq!(id, fork_id);
+            }
+            update => unreachable!("{update:?}"),
+        }
+    }
+
     #[test]
     fn test_insert() {
         let local_node_record = rng_record(&mut rand::thread_rng());
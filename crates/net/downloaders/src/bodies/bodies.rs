@@ -1,7 +1,8 @@
-use super::queue::BodiesRequestQueue;
+use super::{concurrency::AdaptiveConcurrency, queue::BodiesRequestQueue};
 use crate::{bodies::task::TaskDownloader, metrics::BodyDownloaderMetrics};
 use futures::Stream;
 use futures_util::StreamExt;
+use parking_lot::Mutex;
 use reth_config::BodiesConfig;
 use reth_interfaces::{
     consensus::Consensus,
@@ -46,6 +47,8 @@ pub struct BodiesDownloader<B: BodiesClient, Provider> {
     stream_batch_size: usize,
     /// The allowed range for number of concurrent requests.
     concurrent_requests_range: RangeInclusive<usize>,
+    /// The concurrency adapted to the response latency of the peers, within the allowed range.
+    concurrency: Arc<Mutex<AdaptiveConcurrency>>,
     /// Maximum number of bytes of received blocks to buffer internally.
     max_buffered_blocks_size_bytes: usize,
     /// Current estimated size of buffered blocks in bytes.
@@ -136,10 +139,24 @@ where
 
     /// Max requests to handle at the same time
     ///
-    /// This depends on the number of active peers but will always be
+    /// This depends on the number of active peers and their response latency, see
+    /// [AdaptiveConcurrency], but will always be
     /// [`min_concurrent_requests`..`max_concurrent_requests`]
     #[inline]
     fn concurrent_request_limit(&self) -> usize {
+        let max_requests = self.max_concurrent_requests();
+        let limit = match self.concurrency.lock().limit() {
+            Some(limit) => limit.max(*self.concurrent_requests_range.start()).min(max_requests),
+            // no peer responded yet
+            None => max_requests,
+        };
+        self.metrics.concurrency_limit.set(limit as f64);
+        limit
+    }
+
+    /// The upper bound of the concurrent requests, depending on the number of active peers.
+    #[inline]
+    fn max_concurrent_requests(&self) -> usize {
         let num_peers = self.client.num_connected_peers();
 
         let max_requests = num_peers.max(*self.concurrent_requests_range.start());
@@ -578,7 +595,9 @@ impl BodiesDownloaderBuilder {
             max_buffered_blocks_size_bytes,
         } = self;
         let metrics = BodyDownloaderMetrics::default();
-        let in_progress_queue = BodiesRequestQueue::new(metrics.clone());
+        let concurrency =
+            Arc::new(Mutex::new(AdaptiveConcurrency::new(*concurrent_requests_range.end())));
+        let in_progress_queue = BodiesRequestQueue::new(metrics.clone(), Arc::clone(&concurrency));
         BodiesDownloader {
             client: Arc::new(client),
             consensus,
@@ -587,6 +606,7 @@ impl BodiesDownloaderBuilder {
             stream_batch_size,
             max_buffered_blocks_size_bytes,
             concurrent_requests_range,
+            concurrency,
             in_progress_queue,
             metrics,
             download_range: RangeInclusive::new(1, 0),
@@ -608,9 +628,9 @@ mod tests {
     use assert_matches::assert_matches;
     use reth_db::test_utils::{create_test_rw_db, create_test_static_files_dir};
     use reth_interfaces::test_utils::{generators, generators::random_block_range, TestConsensus};
-    use reth_primitives::{BlockBody, B256, MAINNET};
+    use reth_primitives::{BlockBody, PeerId, B256, MAINNET};
     use reth_provider::ProviderFactory;
-    use std::collections::HashMap;
+    use std::{collections::HashMap, time::Duration};
 
     // Check that the blocks are emitted in order of block number, not in order of
     // first-downloaded
@@ -818,4 +838,53 @@ mod tests {
             Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter().take(100), &mut bodies))
         );
     }
+
+    // Check that the downloader allows more concurrent requests for fast peers than for slow ones,
+    // within the configured range.
+    #[tokio::test(start_paused = true)]
+    async fn adapts_concurrency_to_peer_latency() {
+        // Generate some random blocks
+        let db = create_test_rw_db();
+        let (headers, mut bodies) = generate_bodies(0..=999);
+
+        insert_headers(db.db(), &headers);
+
+        let fast_peers = (0..15).map(|_| PeerId::random()).collect::<Vec<_>>();
+        let slow_peer = PeerId::random();
+        let peers = fast_peers
+            .iter()
+            .map(|peer_id| (*peer_id, Duration::from_millis(10)))
+            .chain([(slow_peer, Duration::from_secs(1))])
+            .collect();
+        let client =
+            Arc::new(TestBodiesClient::default().with_bodies(bodies.clone()).with_peers(peers));
+        let (_static_dir, static_dir_path) = create_test_static_files_dir();
+
+        let mut downloader = BodiesDownloaderBuilder::default()
+            .with_request_limit(10)
+            .with_stream_batch_size(1000)
+            .with_concurrent_requests_range(1..=20)
+            .build(
+                client.clone(),
+                Arc::new(TestConsensus::default()),
+                ProviderFactory::new(db, MAINNET.clone(), static_dir_path).unwrap(),
+            );
+        assert_eq!(downloader.concurrent_request_limit(), 16);
+
+        downloader.set_download_range(0..=999).expect("failed to set download range");
+        assert_matches!(
+            downloader.next().await,
+            Some(Ok(res)) => assert_eq!(res, zip_blocks(headers.iter(), &mut bodies))
+        );
+
+        let concurrency = downloader.concurrency.lock();
+        assert_eq!(concurrency.peer(&slow_peer).unwrap().concurrency, 1);
+        for peer_id in &fast_peers {
+            assert!(concurrency.peer(peer_id).unwrap().concurrency > 1);
+        }
+        drop(concurrency);
+
+        // The number of connected peers is still the upper bound
+        assert_eq!(downloader.concurrent_request_limit(), 16);
+    }
 }
@@ -0,0 +1,230 @@
+use crate::metrics::BodyConcurrencyMetrics;
+use reth_primitives::PeerId;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+/// The number of the most recent response latencies the slow latency threshold is computed from.
+const LATENCY_WINDOW: usize = 128;
+
+/// The minimum number of recent response latencies required before any response is considered
+/// slow.
+const MIN_LATENCY_SAMPLES: usize = 16;
+
+/// The percentile of the recent response latencies above which a response is considered slow.
+const SLOW_LATENCY_PERCENTILE: f64 = 0.9;
+
+/// The weight of a new response latency in the moving average of the latency of a peer.
+const LATENCY_EWMA_WEIGHT: f64 = 0.25;
+
+/// The number of responses after which a peer that didn't respond to any of them is forgotten.
+const MAX_IDLE_RESPONSES: u64 = 1024;
+
+/// Adapts the number of concurrent bodies requests to the response latency of the peers.
+///
+/// Every peer that responded to a request contributes a number of concurrent requests to the
+/// limit, which is adjusted similar to AIMD:
+///  - A response that is faster than the [`SLOW_LATENCY_PERCENTILE`] of the recent latencies
+///    increases the concurrency of the peer by one, up to the maximum.
+///  - A slower response halves the concurrency of the peer.
+///  - Timeouts can't be attributed to a peer, since the request failed without a response, so they
+///    halve the concurrency of the peer with the highest average latency instead.
+///
+/// The peers serving a request are chosen by the client, so the limit only controls how many
+/// requests are in-flight at once, and fast peers end up serving more of them.
+#[derive(Debug)]
+pub(crate) struct AdaptiveConcurrency {
+    /// The maximum number of concurrent requests, of a single peer and in total.
+    max_concurrency: usize,
+    /// The most recent response latencies of all peers.
+    latencies: VecDeque<Duration>,
+    /// The scores of the peers that recently responded.
+    peers: HashMap<PeerId, PeerScore>,
+    /// The total number of responses.
+    responses: u64,
+    /// The metrics aggregated over all peers.
+    metrics: BodyConcurrencyMetrics,
+}
+
+impl AdaptiveConcurrency {
+    /// Creates a new instance that limits the concurrency to the given maximum.
+    pub(crate) fn new(max_concurrency: usize) -> Self {
+        Self {
+            max_concurrency: max_concurrency.max(1),
+            latencies: VecDeque::with_capacity(LATENCY_WINDOW),
+            peers: HashMap::default(),
+            responses: 0,
+            metrics: BodyConcurrencyMetrics::default(),
+        }
+    }
+
+    /// Returns the number of concurrent requests allowed by the peers that recently responded,
+    /// capped by the maximum.
+    ///
+    /// Returns `None` if no peer responded yet.
+    pub(crate) fn limit(&self) -> Option<usize> {
+        if self.peers.is_empty() {
+            return None
+        }
+
+        let limit = self.peers.values().map(|peer| peer.concurrency).sum::<usize>();
+        Some(limit.min(self.max_concurrency))
+    }
+
+    /// Returns the score of the peer, if it recently responded.
+    pub(crate) fn peer(&self, peer_id: &PeerId) -> Option<&PeerScore> {
+        self.peers.get(peer_id)
+    }
+
+    /// Returns the latency above which a response is considered slow, if there are enough recent
+    /// responses.
+    fn slow_latency_threshold(&self) -> Option<Duration> {
+        if self.latencies.len() < MIN_LATENCY_SAMPLES {
+            return None
+        }
+
+        let mut latencies = self.latencies.iter().copied().collect::<Vec<_>>();
+        latencies.sort_unstable();
+        let index = ((latencies.len() - 1) as f64 * SLOW_LATENCY_PERCENTILE).round() as usize;
+        Some(latencies[index])
+    }
+
+    /// Records a response of the peer that arrived after the given latency.
+    pub(crate) fn on_response(&mut self, peer_id: PeerId, latency: Duration) {
+        let is_slow = self.slow_latency_threshold().is_some_and(|threshold| latency > threshold);
+
+        if self.latencies.len() == LATENCY_WINDOW {
+            self.latencies.pop_front();
+        }
+        self.latencies.push_back(latency);
+        self.responses += 1;
+
+        let responses = self.responses;
+        let max_concurrency = self.max_concurrency;
+        let peer = self.peers.entry(peer_id).or_insert_with(|| PeerScore::new(latency));
+        peer.last_response = responses;
+        peer.latency =
+            peer.latency.mul_f64(1. - LATENCY_EWMA_WEIGHT) + latency.mul_f64(LATENCY_EWMA_WEIGHT);
+        if is_slow {
+            self.metrics.slow_responses.increment(1);
+            peer.decrease();
+        } else {
+            peer.concurrency = (peer.concurrency + 1).min(max_concurrency);
+        }
+
+        // Forget the peers that are most likely disconnected
+        self.peers.retain(|_, peer| peer.last_response + MAX_IDLE_RESPONSES >= responses);
+        self.update_metrics();
+    }
+
+    /// Records a request that timed out.
+    pub(crate) fn on_timeout(&mut self) {
+        if let Some(peer) = self.peers.values_mut().max_by_key(|peer| peer.latency) {
+            self.metrics.timeouts.increment(1);
+            peer.decrease();
+        }
+    }
+
+    fn update_metrics(&self) {
+        self.metrics.peers.set(self.peers.len() as f64);
+        let total_latency = self.peers.values().map(|peer| peer.latency.as_secs_f64()).sum::<f64>();
+        self.metrics.average_latency_seconds.set(total_latency / self.peers.len().max(1) as f64);
+    }
+}
+
+/// The score of a peer that responded to bodies requests.
+#[derive(Debug)]
+pub(crate) struct PeerScore {
+    /// The number of concurrent requests the peer contributes to the limit.
+    pub(crate) concurrency: usize,
+    /// The moving average of the response latency.
+    latency: Duration,
+    /// The total number of responses when the peer last responded.
+    last_response: u64,
+}
+
+impl PeerScore {
+    const fn new(latency: Duration) -> Self {
+        Self { concurrency: 0, latency, last_response: 0 }
+    }
+
+    /// Halves the concurrency of the peer, keeping at least one request.
+    fn decrease(&mut self) {
+        self.concurrency = (self.concurrency / 2).max(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAST: Duration = Duration::from_millis(10);
+    const SLOW: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn increases_fast_peers() {
+        let fast_peer = PeerId::random();
+        let mut concurrency = AdaptiveConcurrency::new(8);
+        assert_eq!(concurrency.limit(), None);
+
+        concurrency.on_response(fast_peer, FAST);
+        assert_eq!(concurrency.limit(), Some(1));
+
+        for _ in 0..MIN_LATENCY_SAMPLES {
+            concurrency.on_response(fast_peer, FAST);
+        }
+        // The concurrency of a single peer is capped by the maximum
+        assert_eq!(concurrency.peer(&fast_peer).unwrap().concurrency, 8);
+        assert_eq!(concurrency.limit(), Some(8));
+
+        // The total concurrency is capped by the maximum as well
+        let other_peer = PeerId::random();
+        concurrency.on_response(other_peer, FAST);
+        assert_eq!(concurrency.limit(), Some(8));
+    }
+
+    #[test]
+    fn backs_off_slow_peers() {
+        let fast_peer = PeerId::random();
+        let slow_peer = PeerId::random();
+        let mut concurrency = AdaptiveConcurrency::new(100);
+
+        // Slow responses aren't penalized until there are enough samples
+        concurrency.on_response(slow_peer, SLOW);
+        concurrency.on_response(slow_peer, SLOW);
+        assert_eq!(concurrency.peer(&slow_peer).unwrap().concurrency, 2);
+
+        for _ in 0..MIN_LATENCY_SAMPLES {
+            concurrency.on_response(fast_peer, FAST);
+        }
+        let fast_concurrency = concurrency.peer(&fast_peer).unwrap().concurrency;
+        assert_eq!(fast_concurrency, MIN_LATENCY_SAMPLES);
+
+        // A timeout is attributed to the peer with the highest latency
+        concurrency.on_timeout();
+        assert_eq!(concurrency.peer(&slow_peer).unwrap().concurrency, 1);
+        assert_eq!(concurrency.peer(&fast_peer).unwrap().concurrency, fast_concurrency);
+
+        concurrency.on_response(slow_peer, SLOW);
+        assert_eq!(concurrency.peer(&slow_peer).unwrap().concurrency, 1);
+        assert_eq!(concurrency.limit(), Some(fast_concurrency + 1));
+    }
+
+    #[test]
+    fn forgets_idle_peers() {
+        let idle_peer = PeerId::random();
+        let peer = PeerId::random();
+        let mut concurrency = AdaptiveConcurrency::new(100);
+
+        concurrency.on_response(idle_peer, FAST);
+        for _ in 0..MAX_IDLE_RESPONSES {
+            concurrency.on_response(peer, FAST);
+        }
+        assert!(concurrency.peer(&idle_peer).is_some());
+
+        concurrency.on_response(peer, FAST);
+        assert!(concurrency.peer(&idle_peer).is_none());
+        assert_eq!(concurrency.limit(), Some(100));
+    }
+}
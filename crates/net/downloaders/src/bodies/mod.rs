@@ -5,6 +5,7 @@ pub mod bodies;
 /// A downloader implementation that spawns a downloader to a task
 pub mod task;
 
+mod concurrency;
 mod queue;
 mod request;
 
@@ -1,7 +1,8 @@
-use super::request::BodiesRequestFuture;
+use super::{concurrency::AdaptiveConcurrency, request::BodiesRequestFuture};
 use crate::metrics::BodyDownloaderMetrics;
 use futures::{stream::FuturesUnordered, Stream};
 use futures_util::StreamExt;
+use parking_lot::Mutex;
 use reth_interfaces::{
     consensus::Consensus,
     p2p::{
@@ -24,6 +25,8 @@ pub(crate) struct BodiesRequestQueue<B: BodiesClient> {
     inner: FuturesUnordered<BodiesRequestFuture<B>>,
     /// The downloader metrics.
     metrics: BodyDownloaderMetrics,
+    /// The concurrency of the downloader, shared with the requests.
+    concurrency: Arc<Mutex<AdaptiveConcurrency>>,
     /// Last requested block number.
     pub(crate) last_requested_block_number: Option<BlockNumber>,
 }
@@ -33,8 +36,11 @@ where
     B: BodiesClient + 'static,
 {
     /// Create new instance of request queue.
-    pub(crate) fn new(metrics: BodyDownloaderMetrics) -> Self {
-        Self { metrics, inner: Default::default(), last_requested_block_number: None }
+    pub(crate) fn new(
+        metrics: BodyDownloaderMetrics,
+        concurrency: Arc<Mutex<AdaptiveConcurrency>>,
+    ) -> Self {
+        Self { metrics, concurrency, inner: Default::default(), last_requested_block_number: None }
     }
 
     /// Returns `true` if the queue is empty.
@@ -71,7 +77,13 @@ where
             .or(self.last_requested_block_number);
         // Create request and push into the queue.
         self.inner.push(
-            BodiesRequestFuture::new(client, consensus, self.metrics.clone()).with_headers(request),
+            BodiesRequestFuture::new(
+                client,
+                consensus,
+                self.metrics.clone(),
+                Arc::clone(&self.concurrency),
+            )
+            .with_headers(request),
         )
     }
 }
@@ -1,10 +1,12 @@
+use super::concurrency::AdaptiveConcurrency;
 use crate::metrics::{BodyDownloaderMetrics, ResponseMetrics};
 use futures::{Future, FutureExt};
+use parking_lot::Mutex;
 use reth_interfaces::{
     consensus::{Consensus as ConsensusTrait, Consensus},
     p2p::{
         bodies::{client::BodiesClient, response::BlockResponse},
-        error::{DownloadError, DownloadResult},
+        error::{DownloadError, DownloadResult, RequestError},
         priority::Priority,
     },
 };
@@ -18,6 +20,7 @@ use std::{
     sync::Arc,
     task::{ready, Context, Poll},
 };
+use tokio::time::Instant;
 
 /// Body request implemented as a [Future].
 ///
@@ -42,6 +45,8 @@ pub(crate) struct BodiesRequestFuture<B: BodiesClient> {
     client: Arc<B>,
     consensus: Arc<dyn Consensus>,
     metrics: BodyDownloaderMetrics,
+    /// The concurrency of the downloader, adapted to the response latency of the peers.
+    concurrency: Arc<Mutex<AdaptiveConcurrency>>,
     /// Metrics for individual responses. This can be used to observe how the size (in bytes) of
     /// responses change while bodies are being downloaded.
     response_metrics: ResponseMetrics,
@@ -52,6 +57,8 @@ pub(crate) struct BodiesRequestFuture<B: BodiesClient> {
     fut: Option<B::Output>,
     /// Tracks how many bodies we requested in the last request.
     last_request_len: Option<usize>,
+    /// Tracks when the last request was submitted.
+    last_request_start: Option<Instant>,
 }
 
 impl<B> BodiesRequestFuture<B>
@@ -63,15 +70,18 @@ where
         client: Arc<B>,
         consensus: Arc<dyn Consensus>,
         metrics: BodyDownloaderMetrics,
+        concurrency: Arc<Mutex<AdaptiveConcurrency>>,
     ) -> Self {
         Self {
             client,
             consensus,
             metrics,
+            concurrency,
             response_metrics: Default::default(),
             pending_headers: Default::default(),
             buffer: Default::default(),
             last_request_len: None,
+            last_request_start: None,
             fut: None,
         }
     }
@@ -111,6 +121,7 @@ where
         tracing::trace!(target: "downloaders::bodies", request_len = req.len(), "Requesting bodies");
         let client = Arc::clone(&self.client);
         self.last_request_len = Some(req.len());
+        self.last_request_start = Some(Instant::now());
         self.fut = Some(client.get_block_bodies_with_priority(req, priority));
     }
 
@@ -120,6 +131,7 @@ where
         let (peer_id, bodies) = response.split();
         let request_len = self.last_request_len.unwrap_or_default();
         let response_len = bodies.len();
+        let latency = self.last_request_start.map(|start| start.elapsed());
 
         tracing::trace!(target: "downloaders::bodies", request_len, response_len, ?peer_id, "Received bodies");
 
@@ -144,6 +156,11 @@ where
         // Buffer block responses
         self.try_buffer_blocks(bodies)?;
 
+        // Only valid responses count towards the concurrency of the peer
+        if let Some(latency) = latency {
+            self.concurrency.lock().on_response(peer_id, latency);
+        }
+
         // Submit next request if any
         if let Some(req) = self.next_request() {
             self.submit_request(req, Priority::High);
@@ -230,6 +247,9 @@ where
                         if error.is_channel_closed() {
                             return Poll::Ready(Err(error.into()))
                         }
+                        if matches!(error, RequestError::Timeout) {
+                            this.concurrency.lock().on_timeout();
+                        }
 
                         this.on_error(error.into(), None);
                     }
@@ -265,6 +285,7 @@ mod tests {
             client.clone(),
             Arc::new(TestConsensus::default()),
             BodyDownloaderMetrics::default(),
+            Arc::new(Mutex::new(AdaptiveConcurrency::new(1))),
         )
         .with_headers(headers.clone());
 
@@ -289,6 +310,7 @@ mod tests {
             client.clone(),
             Arc::new(TestConsensus::default()),
             BodyDownloaderMetrics::default(),
+            Arc::new(Mutex::new(AdaptiveConcurrency::new(1))),
         )
         .with_headers(headers.clone());
 
@@ -0,0 +1,584 @@
+use alloy_rlp::{BufMut, Decodable, Header as RlpHeader};
+use reth_primitives::{
+    proofs::ordered_trie_root_with_encoder, BlockBody, BlockNumber, Bytes, Header, SealedBlock,
+    B256, U256,
+};
+use sha2::{Digest, Sha256};
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use thiserror::Error;
+
+/// The file extension of era1 files.
+pub const ERA1_EXTENSION: &str = "era1";
+
+/// The maximum number of blocks in an era1 file.
+pub const MAX_BLOCKS_PER_ERA1: usize = 8192;
+
+/// The entry types of an era1 file.
+mod entry_type {
+    pub(super) const VERSION: u16 = 0x3265;
+    pub(super) const COMPRESSED_HEADER: u16 = 0x03;
+    pub(super) const COMPRESSED_BODY: u16 = 0x04;
+    pub(super) const COMPRESSED_RECEIPTS: u16 = 0x05;
+    pub(super) const TOTAL_DIFFICULTY: u16 = 0x06;
+    pub(super) const ACCUMULATOR: u16 = 0x07;
+    pub(super) const BLOCK_INDEX: u16 = 0x3266;
+}
+
+/// An error that can occur when reading an era1 file.
+#[derive(Debug, Error)]
+pub enum Era1Error {
+    /// An error occurred when reading the file.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+
+    /// An error occurred when decoding a header, body or receipts.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+
+    /// The file ended within an entry.
+    #[error("unexpected end of era1 file")]
+    UnexpectedEof,
+
+    /// The file contains an entry that's not expected at its position.
+    #[error("unexpected entry of type {0:#06x} in era1 file")]
+    UnexpectedEntry(u16),
+
+    /// The file contains more blocks than an era1 file can hold.
+    #[error("era1 file contains more than {MAX_BLOCKS_PER_ERA1} blocks")]
+    TooManyBlocks,
+
+    /// The block numbers of the file are not consecutive.
+    #[error("block {got} of era1 file does not follow block {expected}")]
+    NonConsecutiveBlock {
+        /// The number of the block that was expected.
+        expected: BlockNumber,
+        /// The number of the block in the file.
+        got: BlockNumber,
+    },
+
+    /// The receipts of a block don't match the receipts root of its header.
+    #[error("receipts of block {0} do not match its receipts root")]
+    ReceiptsRootMismatch(BlockNumber),
+
+    /// The total difficulty of a block is not the total difficulty of its parent plus its
+    /// difficulty.
+    #[error("invalid total difficulty of block {0}")]
+    TotalDifficultyMismatch(BlockNumber),
+
+    /// The accumulator root computed from the blocks does not match the root of the file.
+    #[error("accumulator root {computed} of era1 file does not match {expected}")]
+    AccumulatorMismatch {
+        /// The root stored in the file.
+        expected: B256,
+        /// The root computed from the blocks of the file.
+        computed: B256,
+    },
+
+    /// The block index of the file does not match its blocks.
+    #[error("block index of era1 file does not match its blocks")]
+    BlockIndexMismatch,
+
+    /// The file does not start at the first block of an epoch.
+    #[error("era1 file starts at block {0}, which is not the first block of an epoch")]
+    UnalignedEpoch(BlockNumber),
+
+    /// There's no trusted accumulator root of the epoch of the file.
+    #[error("no trusted accumulator root of epoch {0}")]
+    UntrustedEpoch(u64),
+
+    /// The accumulator root of the file does not match the trusted root of its epoch.
+    #[error("accumulator root {got} of epoch {epoch} does not match the trusted root {expected}")]
+    UntrustedAccumulator {
+        /// The epoch of the file.
+        epoch: u64,
+        /// The trusted root of the epoch.
+        expected: B256,
+        /// The root of the file.
+        got: B256,
+    },
+}
+
+/// A block of an era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The block.
+    pub block: SealedBlock,
+    /// The consensus encodings of the receipts of the block.
+    ///
+    /// The receipts are not decoded further, because receipts before Byzantium contain an
+    /// intermediate state root instead of a status.
+    pub receipts: Vec<Bytes>,
+    /// The total difficulty of the chain up to and including the block.
+    pub total_difficulty: U256,
+}
+
+/// The contents of an era1 file, see <https://github.com/eth-clients/e2store-format-specs>.
+///
+/// An era1 file holds up to [MAX_BLOCKS_PER_ERA1] consecutive pre-merge blocks with their
+/// receipts and total difficulties, and an accumulator root over the hashes and total
+/// difficulties of the blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1File {
+    /// The blocks of the file, in order.
+    pub blocks: Vec<Era1Block>,
+    /// The accumulator root of the file.
+    pub accumulator: B256,
+}
+
+impl Era1File {
+    /// Reads the era1 file at the given path, see [Era1File::decode].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Era1Error> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    /// Decodes an era1 file.
+    ///
+    /// This verifies that the blocks are consecutive, that the receipts match the receipts roots
+    /// of the headers, that the total difficulties are consistent and that the accumulator root of
+    /// the file matches its blocks.
+    ///
+    /// The accumulator root is read from the file itself, so it must still be verified against a
+    /// trusted root, see [TrustedAccumulators::verify].
+    pub fn decode(mut data: &[u8]) -> Result<Self, Era1Error> {
+        let (ty, _) = read_entry(&mut data)?;
+        if ty != entry_type::VERSION {
+            return Err(Era1Error::UnexpectedEntry(ty))
+        }
+
+        let mut blocks = Vec::<Era1Block>::new();
+        let accumulator = loop {
+            let (ty, value) = read_entry(&mut data)?;
+            match ty {
+                entry_type::COMPRESSED_HEADER => {}
+                entry_type::ACCUMULATOR => break read_b256(value)?,
+                // other entries may be appended after the block tuples
+                _ if !blocks.is_empty() => continue,
+                ty => return Err(Era1Error::UnexpectedEntry(ty)),
+            }
+            if blocks.len() == MAX_BLOCKS_PER_ERA1 {
+                return Err(Era1Error::TooManyBlocks)
+            }
+
+            let header = Header::decode(&mut decompress(value)?.as_slice())?.seal_slow();
+            let body = BlockBody::decode(
+                &mut read_expected(&mut data, entry_type::COMPRESSED_BODY)?.as_slice(),
+            )?;
+            let receipts =
+                decode_receipts(&read_expected(&mut data, entry_type::COMPRESSED_RECEIPTS)?)?;
+            let (ty, value) = read_entry(&mut data)?;
+            if ty != entry_type::TOTAL_DIFFICULTY {
+                return Err(Era1Error::UnexpectedEntry(ty))
+            }
+            let total_difficulty = U256::from_le_bytes(read_b256(value)?.0);
+
+            let number = header.number;
+            if let Some(parent) = blocks.last() {
+                let expected = parent.block.number + 1;
+                if number != expected {
+                    return Err(Era1Error::NonConsecutiveBlock { expected, got: number })
+                }
+                if parent.total_difficulty + header.difficulty != total_difficulty {
+                    return Err(Era1Error::TotalDifficultyMismatch(number))
+                }
+            }
+            let receipts_root =
+                ordered_trie_root_with_encoder(&receipts, |receipt, buf| buf.put_slice(receipt));
+            if receipts_root != header.receipts_root {
+                return Err(Era1Error::ReceiptsRootMismatch(number))
+            }
+
+            blocks.push(Era1Block {
+                block: SealedBlock::new(header, body),
+                receipts,
+                total_difficulty,
+            });
+        };
+
+        let computed = accumulator_root(
+            blocks.iter().map(|block| (block.block.hash(), block.total_difficulty)),
+        );
+        if computed != accumulator {
+            return Err(Era1Error::AccumulatorMismatch { expected: accumulator, computed })
+        }
+
+        let (ty, index) = read_entry(&mut data)?;
+        if ty != entry_type::BLOCK_INDEX {
+            return Err(Era1Error::UnexpectedEntry(ty))
+        }
+        // starting-number | offsets | count, all as 64 bit little endian integers
+        let read_u64 = |offset: usize| {
+            index
+                .get(offset..offset + 8)
+                .map(|bytes| u64::from_le_bytes(bytes.try_into().expect("8 bytes")))
+        };
+        let start = blocks.first().map_or(0, |block| block.block.number);
+        let count = blocks.len() as u64;
+        if index.len() != 16 + blocks.len() * 8 ||
+            read_u64(0) != Some(start) ||
+            read_u64(index.len() - 8) != Some(count)
+        {
+            return Err(Era1Error::BlockIndexMismatch)
+        }
+
+        Ok(Self { blocks, accumulator })
+    }
+
+    /// Returns the number of the first block of the file, if it's not empty.
+    pub fn start_block(&self) -> Option<BlockNumber> {
+        self.blocks.first().map(|block| block.block.number)
+    }
+
+    /// Returns the number of the last block of the file, if it's not empty.
+    pub fn end_block(&self) -> Option<BlockNumber> {
+        self.blocks.last().map(|block| block.block.number)
+    }
+}
+
+/// The trusted accumulator roots of the era1 files of a chain, indexed by their epoch.
+///
+/// The roots are read from text files of one hex encoded root per line, the root of epoch `n` on
+/// line `n`. Empty lines and lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrustedAccumulators {
+    roots: Vec<B256>,
+}
+
+impl TrustedAccumulators {
+    /// Returns the bundled trusted roots of mainnet.
+    pub fn mainnet() -> Self {
+        include_str!("../res/era1/mainnet.txt").parse().expect("valid bundled accumulator roots")
+    }
+
+    /// Reads the trusted roots from the file at the given path.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    /// Returns the trusted root of the given epoch, if it's known.
+    pub fn root(&self, epoch: u64) -> Option<B256> {
+        self.roots.get(usize::try_from(epoch).ok()?).copied()
+    }
+
+    /// Verifies that the accumulator root of the file is the trusted root of its epoch.
+    ///
+    /// The epoch of a file is the number of its first block divided by [MAX_BLOCKS_PER_ERA1].
+    pub fn verify(&self, era1: &Era1File) -> Result<(), Era1Error> {
+        let start = era1.start_block().unwrap_or_default();
+        if start % MAX_BLOCKS_PER_ERA1 as u64 != 0 {
+            return Err(Era1Error::UnalignedEpoch(start))
+        }
+        let epoch = start / MAX_BLOCKS_PER_ERA1 as u64;
+        let expected = self.root(epoch).ok_or(Era1Error::UntrustedEpoch(epoch))?;
+        if expected != era1.accumulator {
+            return Err(Era1Error::UntrustedAccumulator { epoch, expected, got: era1.accumulator })
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TrustedAccumulators {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let roots = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| B256::from_str(line).map_err(|err| format!("invalid root {line}: {err}")))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { roots })
+    }
+}
+
+/// Returns the era1 files in the given directory, in order.
+///
+/// The file names of era1 files are `<network>-<epoch>-<root>.era1`, with a zero padded epoch, so
+/// they're ordered by their names.
+pub fn era1_files(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let mut files = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .filter(|path| {
+            path.as_ref().map_or(true, |path| {
+                path.extension().map_or(false, |extension| extension == ERA1_EXTENSION)
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    files.sort_unstable();
+    Ok(files)
+}
+
+/// Computes the accumulator root of the blocks of an era1 file from their hashes and total
+/// difficulties.
+///
+/// This is the SSZ hash tree root of a `List[HeaderRecord, 8192]`, where a `HeaderRecord` is a
+/// container of the block hash and the total difficulty.
+pub fn accumulator_root(records: impl IntoIterator<Item = (B256, U256)>) -> B256 {
+    let mut layer = records
+        .into_iter()
+        .map(|(hash, total_difficulty)| sha256(hash, B256::from(total_difficulty.to_le_bytes())))
+        .collect::<Vec<_>>();
+    let len = layer.len();
+
+    // merkleize the records, padded with zero hashes up to the limit of the list
+    let mut zero_hash = B256::ZERO;
+    for _ in 0..MAX_BLOCKS_PER_ERA1.trailing_zeros() {
+        if layer.len() % 2 == 1 {
+            layer.push(zero_hash);
+        }
+        layer = layer.chunks(2).map(|pair| sha256(pair[0], pair[1])).collect();
+        zero_hash = sha256(zero_hash, zero_hash);
+    }
+    let root = layer.first().copied().unwrap_or(zero_hash);
+
+    // mix in the length of the list
+    sha256(root, B256::from(U256::from(len).to_le_bytes()))
+}
+
+/// Returns the SHA-256 hash of the concatenation of both hashes.
+fn sha256(left: B256, right: B256) -> B256 {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    B256::from_slice(&hasher.finalize())
+}
+
+/// Reads the next entry of an e2store file, returning its type and value.
+///
+/// Every entry starts with a header of the type as 16 bit, the length of the value as 32 bit
+/// little endian integer, and 16 reserved bits.
+fn read_entry<'a>(data: &mut &'a [u8]) -> Result<(u16, &'a [u8]), Era1Error> {
+    if data.len() < 8 {
+        return Err(Era1Error::UnexpectedEof)
+    }
+    let ty = u16::from_le_bytes([data[0], data[1]]);
+    let len = u32::from_le_bytes([data[2], data[3], data[4], data[5]]) as usize;
+    let value = data.get(8..8 + len).ok_or(Era1Error::UnexpectedEof)?;
+    *data = &data[8 + len..];
+    Ok((ty, value))
+}
+
+/// Reads the next entry, which must have the given type, and decompresses its value.
+fn read_expected(data: &mut &[u8], expected: u16) -> Result<Vec<u8>, Era1Error> {
+    let (ty, value) = read_entry(data)?;
+    if ty != expected {
+        return Err(Era1Error::UnexpectedEntry(ty))
+    }
+    decompress(value)
+}
+
+/// Decompresses a value that's compressed with the snappy framing format.
+fn decompress(value: &[u8]) -> Result<Vec<u8>, Era1Error> {
+    let mut decompressed = Vec::new();
+    snap::read::FrameDecoder::new(value).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Reads a 32 byte value.
+fn read_b256(value: &[u8]) -> Result<B256, Era1Error> {
+    B256::try_from(value).map_err(|_| Era1Error::UnexpectedEof)
+}
+
+/// Decodes the list of receipts of a block into the consensus encodings of the receipts.
+///
+/// Legacy receipts are encoded as RLP lists, typed receipts as RLP strings of the type followed by
+/// the RLP encoding of the receipt.
+fn decode_receipts(mut data: &[u8]) -> Result<Vec<Bytes>, Era1Error> {
+    let list = RlpHeader::decode(&mut data)?;
+    if !list.list {
+        return Err(alloy_rlp::Error::UnexpectedString.into())
+    }
+    let mut payload = data.get(..list.payload_length).ok_or(alloy_rlp::Error::InputTooShort)?;
+
+    let mut receipts = Vec::new();
+    while !payload.is_empty() {
+        let item = payload;
+        let header = RlpHeader::decode(&mut payload)?;
+        let body = payload.get(..header.payload_length).ok_or(alloy_rlp::Error::InputTooShort)?;
+        if header.list {
+            let header_len = item.len() - payload.len();
+            receipts.push(Bytes::copy_from_slice(&item[..header_len + header.payload_length]));
+        } else {
+            receipts.push(Bytes::copy_from_slice(body));
+        }
+        payload = &payload[header.payload_length..];
+    }
+    Ok(receipts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::Encodable;
+    use std::io::Write;
+
+    fn write_entry(out: &mut Vec<u8>, ty: u16, value: &[u8]) {
+        out.extend_from_slice(&ty.to_le_bytes());
+        out.extend_from_slice(&(value.len() as u32).to_le_bytes());
+        out.extend_from_slice(&[0, 0]);
+        out.extend_from_slice(value);
+    }
+
+    fn compress(value: &[u8]) -> Vec<u8> {
+        let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+        encoder.write_all(value).unwrap();
+        encoder.into_inner().unwrap()
+    }
+
+    /// Encodes the blocks, all without transactions, as an era1 file.
+    fn encode_era1(headers: &[Header], total_difficulties: &[U256], accumulator: B256) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_entry(&mut out, entry_type::VERSION, &[]);
+        for (header, total_difficulty) in headers.iter().zip(total_difficulties) {
+            write_entry(
+                &mut out,
+                entry_type::COMPRESSED_HEADER,
+                &compress(&alloy_rlp::encode(header)),
+            );
+            write_entry(
+                &mut out,
+                entry_type::COMPRESSED_BODY,
+                &compress(&alloy_rlp::encode(BlockBody::default())),
+            );
+            let mut receipts = Vec::new();
+            Vec::<Bytes>::new().encode(&mut receipts);
+            write_entry(&mut out, entry_type::COMPRESSED_RECEIPTS, &compress(&receipts));
+            write_entry(
+                &mut out,
+                entry_type::TOTAL_DIFFICULTY,
+                &total_difficulty.to_le_bytes::<32>(),
+            );
+        }
+        write_entry(&mut out, entry_type::ACCUMULATOR, accumulator.as_slice());
+
+        let mut index = headers[0].number.to_le_bytes().to_vec();
+        for _ in headers {
+            index.extend_from_slice(&0u64.to_le_bytes());
+        }
+        index.extend_from_slice(&(headers.len() as u64).to_le_bytes());
+        write_entry(&mut out, entry_type::BLOCK_INDEX, &index);
+        out
+    }
+
+    fn chain(len: u64) -> (Vec<Header>, Vec<U256>) {
+        let mut headers = Vec::<Header>::new();
+        for number in 0..len {
+            headers.push(Header {
+                number,
+                parent_hash: headers.last().map(Header::hash_slow).unwrap_or_default(),
+                difficulty: U256::from(10 + number),
+                receipts_root: reth_primitives::constants::EMPTY_RECEIPTS,
+                ..Default::default()
+            });
+        }
+        let total_difficulties = headers
+            .iter()
+            .scan(U256::ZERO, |total_difficulty, header| {
+                *total_difficulty += header.difficulty;
+                Some(*total_difficulty)
+            })
+            .collect();
+        (headers, total_difficulties)
+    }
+
+    #[test]
+    fn decode_era1() {
+        let (headers, total_difficulties) = chain(3);
+        let accumulator = accumulator_root(
+            headers.iter().map(Header::hash_slow).zip(total_difficulties.iter().copied()),
+        );
+        let era1 =
+            Era1File::decode(&encode_era1(&headers, &total_difficulties, accumulator)).unwrap();
+
+        assert_eq!(era1.accumulator, accumulator);
+        assert_eq!((era1.start_block(), era1.end_block()), (Some(0), Some(2)));
+        assert_eq!(era1.blocks[2].block.hash(), headers[2].hash_slow());
+        assert_eq!(era1.blocks[2].total_difficulty, U256::from(33));
+    }
+
+    #[test]
+    fn reject_invalid_era1() {
+        let (headers, total_difficulties) = chain(3);
+        let accumulator = accumulator_root(
+            headers.iter().map(Header::hash_slow).zip(total_difficulties.iter().copied()),
+        );
+
+        let err =
+            Era1File::decode(&encode_era1(&headers, &total_difficulties, B256::ZERO)).unwrap_err();
+        assert!(matches!(
+            err,
+            Era1Error::AccumulatorMismatch { expected, computed }
+                if expected == B256::ZERO && computed == accumulator
+        ));
+
+        let mut invalid_total_difficulties = total_difficulties.clone();
+        invalid_total_difficulties[2] += U256::from(1);
+        let err =
+            Era1File::decode(&encode_era1(&headers, &invalid_total_difficulties, accumulator))
+                .unwrap_err();
+        assert!(matches!(err, Era1Error::TotalDifficultyMismatch(2)));
+
+        let data = encode_era1(&headers, &total_difficulties, accumulator);
+        assert!(matches!(
+            Era1File::decode(&data[..data.len() - 1]).unwrap_err(),
+            Era1Error::UnexpectedEof
+        ));
+    }
+
+    #[test]
+    fn verify_trusted_accumulators() {
+        let (headers, total_difficulties) = chain(3);
+        let accumulator = accumulator_root(
+            headers.iter().map(Header::hash_slow).zip(total_difficulties.iter().copied()),
+        );
+        let era1 =
+            Era1File::decode(&encode_era1(&headers, &total_difficulties, accumulator)).unwrap();
+
+        let trusted: TrustedAccumulators =
+            format!("# epoch 0\n{accumulator}\n\n{}\n", B256::ZERO).parse().unwrap();
+        assert_eq!(trusted.root(1), Some(B256::ZERO));
+        assert!(trusted.verify(&era1).is_ok());
+
+        let other: TrustedAccumulators = B256::ZERO.to_string().parse().unwrap();
+        assert!(matches!(
+            other.verify(&era1).unwrap_err(),
+            Era1Error::UntrustedAccumulator { epoch: 0, expected, got }
+                if expected == B256::ZERO && got == accumulator
+        ));
+        assert!(matches!(
+            TrustedAccumulators::default().verify(&era1).unwrap_err(),
+            Era1Error::UntrustedEpoch(0)
+        ));
+        assert!("0x01".parse::<TrustedAccumulators>().is_err());
+    }
+
+    #[test]
+    fn accumulator_root_of_empty_list() {
+        // the hash tree root of an empty list is the zero hash of the depth of the list, mixed in
+        // with the length 0
+        let mut zero_hash = B256::ZERO;
+        for _ in 0..13 {
+            zero_hash = sha256(zero_hash, zero_hash);
+        }
+        assert_eq!(accumulator_root(std::iter::empty()), sha256(zero_hash, B256::ZERO));
+    }
+
+    #[test]
+    fn decode_typed_and_legacy_receipts() {
+        let legacy = alloy_rlp::encode(vec![1u64, 2]);
+        let typed = [vec![0x02], alloy_rlp::encode(vec![3u64])].concat();
+        let payload = [legacy.clone(), alloy_rlp::encode(Bytes::from(typed.clone()))].concat();
+        let mut list = Vec::new();
+        RlpHeader { list: true, payload_length: payload.len() }.encode(&mut list);
+        list.extend_from_slice(&payload);
+
+        let receipts = decode_receipts(&list).unwrap();
+        assert_eq!(receipts, vec![Bytes::from(legacy), Bytes::from(typed)]);
+    }
+}
@@ -9,7 +9,7 @@ use reth_interfaces::p2p::{
 };
 use reth_primitives::{
     BlockBody, BlockHash, BlockHashOrNumber, BlockNumber, Header, HeadersDirection, PeerId,
-    SealedHeader, B256,
+    SealedBlock, SealedHeader, B256,
 };
 use std::{collections::HashMap, path::Path};
 use thiserror::Error;
@@ -115,9 +115,29 @@ impl FileClient {
         Ok(Self { headers, hash_to_number, bodies })
     }
 
+    /// Create a new file client from the given blocks, e.g. the blocks of
+    /// [Era1File](crate::era1::Era1File)s.
+    pub fn from_blocks(blocks: impl IntoIterator<Item = SealedBlock>) -> Self {
+        let mut headers = HashMap::new();
+        let mut hash_to_number = HashMap::new();
+        let mut bodies = HashMap::new();
+
+        for block in blocks {
+            let SealedBlock { header, body, ommers, withdrawals } = block;
+            let (header, block_hash) = header.split();
+            hash_to_number.insert(block_hash, header.number);
+            headers.insert(header.number, header);
+            bodies.insert(block_hash, BlockBody { transactions: body, ommers, withdrawals });
+        }
+
+        trace!(blocks = headers.len(), "Initialized file client");
+
+        Self { headers, hash_to_number, bodies }
+    }
+
     /// Get the tip hash of the chain.
     pub fn tip(&self) -> Option<B256> {
-        self.headers.get(&((self.headers.len() - 1) as u64)).map(|h| h.hash_slow())
+        self.headers.get(&self.max_block()?).map(|h| h.hash_slow())
     }
 
     /// Get the start hash of the chain.
@@ -32,5 +32,10 @@ pub mod file_client;
 /// Enables decoding and encoding `Block` types within file contexts.
 pub mod file_codec;
 
+/// Module for reading era1 archives of pre-merge blocks.
+///
+/// Contains [Era1File](era1::Era1File) to decode and verify era1 files.
+pub mod era1;
+
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
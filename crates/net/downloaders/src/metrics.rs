@@ -25,6 +25,9 @@ pub struct BodyDownloaderMetrics {
     pub total_downloaded: Counter,
     /// The number of requests (can contain more than 1 item) currently in-flight.
     pub in_flight_requests: Gauge,
+    /// The maximum number of requests allowed in-flight, adapted to the response latency of the
+    /// peers.
+    pub concurrency_limit: Gauge,
     /// The number of responses (can contain more than 1 item) in the internal buffer of the
     /// downloader.
     pub buffered_responses: Gauge,
@@ -75,6 +78,23 @@ pub struct ResponseMetrics {
     pub response_length: Gauge,
 }
 
+/// Metrics for the bodies responses of the peers the concurrency limit is adapted to, aggregated
+/// over all peers.
+///
+/// These metrics will be initialized with the `downloaders.bodies.concurrency` scope.
+#[derive(Clone, Metrics)]
+#[metrics(scope = "downloaders.bodies.concurrency")]
+pub struct BodyConcurrencyMetrics {
+    /// The number of peers that recently responded and contribute to the concurrency limit.
+    pub peers: Gauge,
+    /// The average of the moving average response latencies of the peers in seconds.
+    pub average_latency_seconds: Gauge,
+    /// The number of responses that were considered slow.
+    pub slow_responses: Counter,
+    /// The number of timeouts attributed to a peer.
+    pub timeouts: Counter,
+}
+
 /// Common header downloader metrics.
 ///
 /// These metrics will be initialized with the `downloaders.headers` scope.
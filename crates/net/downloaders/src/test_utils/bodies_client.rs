@@ -23,6 +23,7 @@ pub struct TestBodiesClient {
     max_batch_size: Option<usize>,
     times_requested: AtomicU64,
     empty_response_mod: Option<u64>,
+    peers: Vec<(PeerId, Duration)>,
 }
 
 impl TestBodiesClient {
@@ -43,6 +44,12 @@ impl TestBodiesClient {
         self
     }
 
+    /// Instructs the client to respond from the given peers in turn, each one after its delay.
+    pub(crate) fn with_peers(mut self, peers: Vec<(PeerId, Duration)>) -> Self {
+        self.peers = peers;
+        self
+    }
+
     pub(crate) fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
         self.max_batch_size = Some(max_batch_size);
         self
@@ -71,7 +78,7 @@ impl DownloadClient for TestBodiesClient {
     }
 
     fn num_connected_peers(&self) -> usize {
-        0
+        self.peers.len()
     }
 }
 
@@ -87,21 +94,30 @@ impl BodiesClient for TestBodiesClient {
         let bodies = self.bodies.clone();
         let max_batch_size = self.max_batch_size;
 
-        self.times_requested.fetch_add(1, Ordering::Relaxed);
+        let times_requested = self.times_requested.fetch_add(1, Ordering::Relaxed);
         let should_respond_empty = self.should_respond_empty();
+        let (peer_id, peer_delay) = if self.peers.is_empty() {
+            (PeerId::default(), None)
+        } else {
+            let (peer_id, delay) = self.peers[times_requested as usize % self.peers.len()];
+            (peer_id, Some(delay))
+        };
 
         Box::pin(async move {
             if should_respond_empty {
-                return Ok((PeerId::default(), vec![]).into())
+                return Ok((peer_id, vec![]).into())
             }
 
             if should_delay {
                 tokio::time::sleep(Duration::from_millis((hashes[0][0] % 100) as u64)).await;
             }
+            if let Some(delay) = peer_delay {
+                tokio::time::sleep(delay).await;
+            }
 
             let bodies = &mut *bodies.lock().await;
             Ok((
-                PeerId::default(),
+                peer_id,
                 hashes
                     .into_iter()
                     .take(max_batch_size.unwrap_or(usize::MAX))
@@ -1,8 +1,11 @@
 //! Implements the `GetReceipts` and `Receipts` message types.
 
-use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
+use alloy_rlp::{
+    length_of_length, Decodable, Encodable, Error as RlpError, Header, RlpDecodableWrapper,
+    RlpEncodableWrapper,
+};
 use reth_codecs::derive_arbitrary;
-use reth_primitives::{ReceiptWithBloom, B256};
+use reth_primitives::{bytes::BufMut, Log, Receipt, ReceiptWithBloom, TxType, B256};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -32,9 +35,104 @@ pub struct Receipts(
     pub Vec<Vec<ReceiptWithBloom>>,
 );
 
+impl From<Receipts69> for Receipts {
+    /// Converts the eth/69 receipts by computing the bloom of every receipt from its logs.
+    fn from(receipts: Receipts69) -> Self {
+        Receipts(
+            receipts
+                .0
+                .into_iter()
+                .map(|block| block.into_iter().map(|receipt| receipt.0.with_bloom()).collect())
+                .collect(),
+        )
+    }
+}
+
+/// The eth/69 response to [`GetReceipts`], containing receipt lists that correspond to each block
+/// requested.
+///
+/// Unlike [`Receipts`], the receipts don't include their bloom filter, which can be derived from
+/// their logs.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodableWrapper, RlpDecodableWrapper, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Receipts69(
+    /// Each receipt list should correspond to a block hash in the request.
+    pub Vec<Vec<Receipt69>>,
+);
+
+/// A [`Receipt`] encoded in the eth/69 format, without the bloom filter:
+/// `[tx-type, post-state-or-status, cumulative-gas, logs]`.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Receipt69(pub Receipt);
+
+impl Receipt69 {
+    fn payload_length(&self) -> usize {
+        u8::from(self.0.tx_type).length() +
+            self.0.success.length() +
+            self.0.cumulative_gas_used.length() +
+            self.0.logs.length()
+    }
+}
+
+impl From<Receipt> for Receipt69 {
+    fn from(receipt: Receipt) -> Self {
+        Self(receipt)
+    }
+}
+
+impl Encodable for Receipt69 {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        u8::from(self.0.tx_type).encode(out);
+        self.0.success.encode(out);
+        self.0.cumulative_gas_used.encode(out);
+        self.0.logs.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + length_of_length(payload_length)
+    }
+}
+
+impl Decodable for Receipt69 {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(RlpError::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let tx_type = TxType::try_from(u8::decode(buf)?).map_err(RlpError::Custom)?;
+        let success = bool::decode(buf)?;
+        let cumulative_gas_used = u64::decode(buf)?;
+        let logs = Vec::<Log>::decode(buf)?;
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(RlpError::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        Ok(Self(Receipt {
+            tx_type,
+            success,
+            cumulative_gas_used,
+            logs,
+            #[cfg(feature = "optimism")]
+            deposit_nonce: None,
+            #[cfg(feature = "optimism")]
+            deposit_receipt_version: None,
+        }))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{message::RequestPair, GetReceipts, Receipts};
+    use crate::{message::RequestPair, GetReceipts, Receipt69, Receipts, Receipts69};
     use alloy_rlp::{Decodable, Encodable};
     use reth_primitives::{hex, Log, Receipt, ReceiptWithBloom, TxType};
 
@@ -63,6 +161,39 @@ mod tests {
         assert_eq!(receipts, decoded);
     }
 
+    #[test]
+    fn roundtrip_receipts69() {
+        let receipt = Receipt {
+            tx_type: TxType::Legacy,
+            success: true,
+            cumulative_gas_used: 21000,
+            logs: vec![Log {
+                address: hex!("0000000000000000000000000000000000000011").into(),
+                topics: vec![hex!(
+                    "000000000000000000000000000000000000000000000000000000000000dead"
+                )
+                .into()],
+                data: hex!("0100ff")[..].into(),
+            }],
+            #[cfg(feature = "optimism")]
+            deposit_nonce: None,
+            #[cfg(feature = "optimism")]
+            deposit_receipt_version: None,
+        };
+        let receipts = Receipts69(vec![vec![Receipt69(receipt.clone())], vec![]]);
+
+        let mut out = vec![];
+        receipts.encode(&mut out);
+        assert_eq!(out.len(), receipts.length());
+
+        let decoded = Receipts69::decode(&mut out.as_slice()).unwrap();
+        assert_eq!(receipts, decoded);
+
+        // the bloom is derived from the logs
+        let receipts = Receipts::from(decoded);
+        assert_eq!(receipts, Receipts(vec![vec![receipt.with_bloom()], vec![]]));
+    }
+
     #[test]
     // Test vector from: https://eips.ethereum.org/EIPS/eip-2481
     fn encode_get_receipts() {
@@ -21,6 +21,12 @@ pub enum EthVersion {
 
     /// The `eth` protocol version 68.
     Eth68 = 68,
+
+    /// The `eth` protocol version 69.
+    ///
+    /// Note: only the eth/69 receipts format, which omits the bloom, is supported so far, so this
+    /// version isn't announced by default.
+    Eth69 = 69,
 }
 
 impl EthVersion {
@@ -31,8 +37,8 @@ impl EthVersion {
     pub const fn total_messages(&self) -> u8 {
         match self {
             EthVersion::Eth66 => 15,
-            EthVersion::Eth67 | EthVersion::Eth68 => {
-                // eth/67,68 are eth/66 minus GetNodeData and NodeData messages
+            EthVersion::Eth67 | EthVersion::Eth68 | EthVersion::Eth69 => {
+                // eth/67,68,69 are eth/66 minus GetNodeData and NodeData messages
                 13
             }
         }
@@ -52,6 +58,11 @@ impl EthVersion {
     pub const fn is_eth68(&self) -> bool {
         matches!(self, EthVersion::Eth68)
     }
+
+    /// Returns true if the version is eth/69
+    pub const fn is_eth69(&self) -> bool {
+        matches!(self, EthVersion::Eth69)
+    }
 }
 
 /// Allow for converting from a `&str` to an `EthVersion`.
@@ -72,6 +83,7 @@ impl TryFrom<&str> for EthVersion {
             "66" => Ok(EthVersion::Eth66),
             "67" => Ok(EthVersion::Eth67),
             "68" => Ok(EthVersion::Eth68),
+            "69" => Ok(EthVersion::Eth69),
             _ => Err(ParseVersionError(s.to_string())),
         }
     }
@@ -95,6 +107,7 @@ impl TryFrom<u8> for EthVersion {
             66 => Ok(EthVersion::Eth66),
             67 => Ok(EthVersion::Eth67),
             68 => Ok(EthVersion::Eth68),
+            69 => Ok(EthVersion::Eth69),
             _ => Err(ParseVersionError(u.to_string())),
         }
     }
@@ -123,6 +136,7 @@ impl From<EthVersion> for &'static str {
             EthVersion::Eth66 => "66",
             EthVersion::Eth67 => "67",
             EthVersion::Eth68 => "68",
+            EthVersion::Eth69 => "69",
         }
     }
 }
@@ -136,7 +150,8 @@ mod tests {
         assert_eq!(EthVersion::Eth66, EthVersion::try_from("66").unwrap());
         assert_eq!(EthVersion::Eth67, EthVersion::try_from("67").unwrap());
         assert_eq!(EthVersion::Eth68, EthVersion::try_from("68").unwrap());
-        assert_eq!(Err(ParseVersionError("69".to_string())), EthVersion::try_from("69"));
+        assert_eq!(EthVersion::Eth69, EthVersion::try_from("69").unwrap());
+        assert_eq!(Err(ParseVersionError("70".to_string())), EthVersion::try_from("70"));
     }
 
     #[test]
@@ -144,6 +159,7 @@ mod tests {
         assert_eq!(EthVersion::Eth66, "66".parse().unwrap());
         assert_eq!(EthVersion::Eth67, "67".parse().unwrap());
         assert_eq!(EthVersion::Eth68, "68".parse().unwrap());
-        assert_eq!(Err(ParseVersionError("69".to_string())), "69".parse::<EthVersion>());
+        assert_eq!(EthVersion::Eth69, "69".parse().unwrap());
+        assert_eq!(Err(ParseVersionError("70".to_string())), "70".parse::<EthVersion>());
     }
 }
@@ -1,4 +1,4 @@
-//! Helpers for resolving the external IP.
+//! Helpers for resolving the external IP and mapping ports on the gateway.
 //!
 //! ## Feature Flags
 //!
@@ -12,6 +12,11 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod mapping;
+mod natpmp;
+
+pub use mapping::{PortMapper, PortMappingMethod, DEFAULT_PORT_MAPPING_LEASE};
+
 use igd_next::aio::tokio::search_gateway;
 use pin_project_lite::pin_project;
 use std::{
@@ -35,8 +40,12 @@ pub enum NatResolver {
     /// Resolve with any available resolver.
     #[default]
     Any,
-    /// Resolve via Upnp
+    /// Resolve via Upnp, and map the ports via Upnp
     Upnp,
+    /// Resolve via NAT-PMP, and map the ports via NAT-PMP
+    ///
+    /// This is only supported on Linux, where the gateway is read from the routing table.
+    NatPmp,
     /// Resolve external IP via [public_ip::Resolver]
     PublicIp,
     /// Use the given [IpAddr]
@@ -52,6 +61,15 @@ impl NatResolver {
     pub async fn external_addr(self) -> Option<IpAddr> {
         external_addr_with(self).await
     }
+
+    /// Returns the method the ports are mapped on the gateway with, if the resolver maps them.
+    pub fn port_mapping_method(&self) -> Option<PortMappingMethod> {
+        match self {
+            NatResolver::Upnp => Some(PortMappingMethod::Upnp),
+            NatResolver::NatPmp => Some(PortMappingMethod::NatPmp),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for NatResolver {
@@ -59,6 +77,7 @@ impl fmt::Display for NatResolver {
         match self {
             NatResolver::Any => f.write_str("any"),
             NatResolver::Upnp => f.write_str("upnp"),
+            NatResolver::NatPmp => f.write_str("pmp"),
             NatResolver::PublicIp => f.write_str("publicip"),
             NatResolver::ExternalIp(ip) => write!(f, "extip:{ip}"),
             NatResolver::None => f.write_str("none"),
@@ -84,6 +103,7 @@ impl FromStr for NatResolver {
         let r = match s {
             "any" => NatResolver::Any,
             "upnp" => NatResolver::Upnp,
+            "pmp" | "natpmp" | "nat-pmp" => NatResolver::NatPmp,
             "none" => NatResolver::None,
             "publicip" | "public-ip" => NatResolver::PublicIp,
             s => {
@@ -191,6 +211,7 @@ pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
             .await
         }
         NatResolver::Upnp => resolve_external_ip_upnp().await,
+        NatResolver::NatPmp => resolve_external_ip_natpmp().await,
         NatResolver::PublicIp => resolve_external_ip().await,
         NatResolver::ExternalIp(ip) => Some(ip),
         NatResolver::None => None,
@@ -258,6 +279,23 @@ async fn resolve_external_ip_upnp() -> Option<IpAddr> {
         .ok()
 }
 
+async fn resolve_external_ip_natpmp() -> Option<IpAddr> {
+    let gateway = natpmp::gateway()
+        .map_err(|err| {
+            debug!(target: "net::nat", %err, "Failed to resolve external IP via NAT-PMP");
+            err
+        })
+        .ok()?;
+    natpmp::external_ip(gateway)
+        .await
+        .map(IpAddr::V4)
+        .map_err(|err| {
+            debug!(target: "net::nat", %err, "Failed to resolve external IP via NAT-PMP");
+            err
+        })
+        .ok()
+}
+
 async fn resolve_external_ip() -> Option<IpAddr> {
     public_ip::addr().await
 }
@@ -291,6 +329,8 @@ mod tests {
     fn test_from_str() {
         assert_eq!(NatResolver::Any, "any".parse().unwrap());
         assert_eq!(NatResolver::None, "none".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp, "pmp".parse().unwrap());
+        assert_eq!(NatResolver::NatPmp.to_string(), "pmp");
 
         let ip = NatResolver::ExternalIp(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
         let s = "extip:0.0.0.0";
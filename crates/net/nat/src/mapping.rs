@@ -0,0 +1,475 @@
+//! Maps the ports of the node on the gateway, so peers behind the same NAT as the node can be
+//! reached by inbound connections.
+
+use crate::natpmp::{self, NatPmpError, Protocol};
+use igd_next::{aio::tokio::search_gateway, AddPortError, PortMappingProtocol, SearchError};
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+use std::{
+    fmt,
+    future::Future,
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    pin::pin,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::net::UdpSocket;
+use tracing::{debug, info, warn};
+
+/// The default lease of the port mappings, which are refreshed after half of it.
+pub const DEFAULT_PORT_MAPPING_LEASE: Duration = Duration::from_secs(60 * 60);
+
+/// The interval at which mapping the ports is retried after it failed.
+const RETRY_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// The minimum interval at which the mappings are refreshed, in case the gateway grants very short
+/// leases.
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The description of the port mappings on UPnP gateways.
+const DESCRIPTION: &str = "reth";
+
+/// The protocol used to map the ports on the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortMappingMethod {
+    /// Map the ports via UPnP.
+    Upnp,
+    /// Map the ports via NAT-PMP.
+    NatPmp,
+}
+
+impl fmt::Display for PortMappingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Upnp => f.write_str("UPnP"),
+            Self::NatPmp => f.write_str("NAT-PMP"),
+        }
+    }
+}
+
+/// Errors of mapping a port on the gateway.
+#[derive(Debug, thiserror::Error)]
+enum PortMappingError {
+    /// No UPnP gateway was found.
+    #[error("failed to find UPnP gateway: {0}")]
+    SearchGateway(#[from] SearchError),
+    /// The UPnP gateway rejected the mapping.
+    #[error(transparent)]
+    AddPort(#[from] AddPortError),
+    /// The NAT-PMP request failed.
+    #[error(transparent)]
+    NatPmp(#[from] NatPmpError),
+    /// The local address towards the gateway couldn't be determined.
+    #[error("failed to determine the local address: {0}")]
+    LocalAddr(#[from] io::Error),
+}
+
+/// Maps the TCP port of RLPx and the UDP port of discovery on the gateway, and refreshes the
+/// mappings before their lease expires.
+///
+/// Mapping is best effort: failures are logged and retried, the node keeps running without the
+/// mappings.
+///
+/// The external IP of the gateway is resolved on every refresh, and reported to the listener set
+/// with [PortMapper::on_external_ip] whenever it changes. The same goes for the external ports of
+/// the mappings, in case the gateway doesn't grant the requested ports, see
+/// [PortMapper::on_external_ports].
+pub struct PortMapper {
+    /// The protocol used to map the ports.
+    method: PortMappingMethod,
+    /// The ports to map.
+    ports: Vec<MappedPort>,
+    /// The lease that's requested for the mappings.
+    lease: Duration,
+    /// The last external IP of the gateway.
+    external_ip: Option<IpAddr>,
+    /// Called with the external IP of the gateway whenever it changes.
+    on_external_ip: Option<Box<dyn Fn(IpAddr) + Send + Sync>>,
+    /// The last external TCP and UDP ports of the mappings, initially the local ports.
+    external_ports: (u16, u16),
+    /// Called with the external TCP and UDP ports of the mappings whenever they change.
+    on_external_ports: Option<Box<dyn Fn(u16, u16) + Send + Sync>>,
+}
+
+impl PortMapper {
+    /// Creates a new mapper of the TCP and UDP ports with the given method.
+    pub fn new(method: PortMappingMethod, tcp_port: u16, udp_port: u16) -> Self {
+        Self {
+            method,
+            ports: vec![
+                MappedPort::new(Protocol::Tcp, tcp_port),
+                MappedPort::new(Protocol::Udp, udp_port),
+            ],
+            lease: DEFAULT_PORT_MAPPING_LEASE,
+            external_ip: None,
+            on_external_ip: None,
+            external_ports: (tcp_port, udp_port),
+            on_external_ports: None,
+        }
+    }
+
+    /// Sets the lease that's requested for the mappings.
+    pub fn with_lease(mut self, lease: Duration) -> Self {
+        self.lease = lease;
+        self
+    }
+
+    /// Sets the listener that's called with the external IP of the gateway whenever it changes,
+    /// e.g. to update the address that's announced in discovery.
+    pub fn on_external_ip(mut self, listener: impl Fn(IpAddr) + Send + Sync + 'static) -> Self {
+        self.on_external_ip = Some(Box::new(listener));
+        self
+    }
+
+    /// Sets the listener that's called with the external TCP and UDP ports of the mappings
+    /// whenever the gateway maps the ports to different external ports, e.g. to update the ports
+    /// that are announced in discovery.
+    pub fn on_external_ports(
+        mut self,
+        listener: impl Fn(u16, u16) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_external_ports = Some(Box::new(listener));
+        self
+    }
+
+    /// Maps the ports and refreshes the mappings until the shutdown signal resolves, after which
+    /// the mappings are removed.
+    ///
+    /// The output of the shutdown signal, e.g. a guard of a graceful shutdown, is dropped once the
+    /// mappings are removed.
+    pub async fn run<F: Future>(mut self, shutdown: F) {
+        info!(target: "net::nat", method = %self.method, "Mapping ports on the gateway");
+
+        let mut shutdown = pin!(shutdown);
+        let guard = loop {
+            let delay = self.refresh().await;
+            tokio::select! {
+                guard = &mut shutdown => break guard,
+                _ = tokio::time::sleep(delay) => {}
+            }
+        };
+
+        self.remove().await;
+        drop(guard);
+    }
+
+    /// Maps all ports, and returns the delay until the mappings must be refreshed.
+    async fn refresh(&mut self) -> Duration {
+        let result = match self.method {
+            PortMappingMethod::Upnp => self.map_upnp().await,
+            PortMappingMethod::NatPmp => self.map_natpmp().await,
+        };
+
+        self.on_mapped_ports();
+
+        match result {
+            Ok(lease) if self.ports.iter().all(|port| port.mapped) => {
+                (lease / 2).max(MIN_REFRESH_INTERVAL)
+            }
+            Ok(_) => RETRY_INTERVAL,
+            Err(err) => {
+                for port in &mut self.ports {
+                    port.on_failure(self.method, &err);
+                }
+                RETRY_INTERVAL
+            }
+        }
+    }
+
+    /// Maps the ports on the UPnP gateway, and returns the granted lease.
+    async fn map_upnp(&mut self) -> Result<Duration, PortMappingError> {
+        let gateway = search_gateway(Default::default()).await?;
+        let local_ip = local_ip(gateway.addr).await?;
+        let lease = u32::try_from(self.lease.as_secs()).unwrap_or(u32::MAX);
+
+        for port in &mut self.ports {
+            let local_addr = SocketAddr::new(local_ip, port.port);
+            let protocol = port.protocol.into();
+            let result =
+                match gateway.add_port(protocol, port.port, local_addr, lease, DESCRIPTION).await {
+                    // a permanent mapping is still refreshed, in case the gateway restarted
+                    Err(AddPortError::OnlyPermanentLeasesSupported) => {
+                        gateway.add_port(protocol, port.port, local_addr, 0, DESCRIPTION).await
+                    }
+                    result => result,
+                };
+            match result {
+                Ok(()) => port.on_success(self.method, port.port),
+                Err(err) => port.on_failure(self.method, &err.into()),
+            }
+        }
+
+        match gateway.get_external_ip().await {
+            Ok(ip) => self.on_gateway_ip(ip),
+            Err(err) => debug!(target: "net::nat", %err, "Failed to get external IP via UPnP"),
+        }
+
+        Ok(self.lease)
+    }
+
+    /// Maps the ports on the NAT-PMP gateway, and returns the shortest granted lease.
+    async fn map_natpmp(&mut self) -> Result<Duration, PortMappingError> {
+        let gateway = natpmp::gateway()?;
+
+        let mut granted = self.lease;
+        for port in &mut self.ports {
+            match natpmp::map_port(gateway, port.protocol, port.port, port.port, self.lease).await {
+                Ok(mapping) => {
+                    granted = granted.min(mapping.lifetime);
+                    port.on_success(self.method, mapping.external_port)
+                }
+                Err(err) => port.on_failure(self.method, &err.into()),
+            }
+        }
+
+        match natpmp::external_ip(gateway).await {
+            Ok(ip) => self.on_gateway_ip(ip.into()),
+            Err(err) => debug!(target: "net::nat", %err, "Failed to get external IP via NAT-PMP"),
+        }
+
+        Ok(granted)
+    }
+
+    /// Reports the external IP of the gateway to the listener if it changed.
+    fn on_gateway_ip(&mut self, ip: IpAddr) {
+        if self.external_ip == Some(ip) {
+            return
+        }
+        // a gateway that isn't connected yet reports the unspecified IP
+        if ip.is_unspecified() {
+            debug!(target: "net::nat", %ip, "Gateway has no external IP");
+            return
+        }
+
+        info!(target: "net::nat", method = %self.method, %ip, "External IP of the gateway");
+        self.external_ip = Some(ip);
+        if let Some(listener) = &self.on_external_ip {
+            listener(ip);
+        }
+    }
+
+    /// Reports the external ports of the mappings to the listener if they changed.
+    fn on_mapped_ports(&mut self) {
+        let external_port = |protocol| {
+            self.ports
+                .iter()
+                .find(|port| port.protocol == protocol)
+                .map_or(0, |port| port.external_port)
+        };
+        let ports = (external_port(Protocol::Tcp), external_port(Protocol::Udp));
+        if self.external_ports == ports {
+            return
+        }
+
+        info!(
+            target: "net::nat",
+            method = %self.method,
+            tcp_port = ports.0,
+            udp_port = ports.1,
+            "External ports of the gateway"
+        );
+        self.external_ports = ports;
+        if let Some(listener) = &self.on_external_ports {
+            listener(ports.0, ports.1);
+        }
+    }
+
+    /// Removes the mappings from the gateway.
+    async fn remove(&mut self) {
+        let mapped = self.ports.iter().filter(|port| port.mapped).collect::<Vec<_>>();
+        if mapped.is_empty() {
+            return
+        }
+
+        match self.method {
+            PortMappingMethod::Upnp => {
+                let Ok(gateway) = search_gateway(Default::default()).await else { return };
+                for port in mapped {
+                    if let Err(err) = gateway.remove_port(port.protocol.into(), port.port).await {
+                        debug!(
+                            target: "net::nat",
+                            port = port.port,
+                            %err,
+                            "Failed to remove UPnP port mapping"
+                        );
+                    }
+                }
+            }
+            PortMappingMethod::NatPmp => {
+                let Ok(gateway) = natpmp::gateway() else { return };
+                for port in mapped {
+                    if let Err(err) =
+                        natpmp::map_port(gateway, port.protocol, port.port, 0, Duration::ZERO).await
+                    {
+                        debug!(
+                            target: "net::nat",
+                            port = port.port,
+                            %err,
+                            "Failed to remove NAT-PMP port mapping"
+                        );
+                    }
+                }
+            }
+        }
+
+        for port in &mut self.ports {
+            port.mapped = false;
+            port.metrics.mapped.set(0.0);
+        }
+        debug!(target: "net::nat", "Removed port mappings");
+    }
+}
+
+impl fmt::Debug for PortMapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PortMapper")
+            .field("method", &self.method)
+            .field("ports", &self.ports)
+            .field("lease", &self.lease)
+            .field("external_ip", &self.external_ip)
+            .field("external_ports", &self.external_ports)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A port that's mapped on the gateway.
+#[derive(Debug)]
+struct MappedPort {
+    /// The protocol of the port.
+    protocol: Protocol,
+    /// The port of this host, which is also requested as the external port.
+    port: u16,
+    /// The external port the gateway last mapped the port to, initially the port of this host.
+    external_port: u16,
+    /// Whether the port is currently mapped.
+    mapped: bool,
+    /// Whether the last attempt to map the port failed, so repeated failures are logged once.
+    failed: bool,
+    /// The metrics of the mapping of the port.
+    metrics: PortMappingMetrics,
+}
+
+impl MappedPort {
+    fn new(protocol: Protocol, port: u16) -> Self {
+        let metrics = PortMappingMetrics::new_with_labels(&[
+            ("protocol", protocol.to_string()),
+            ("port", port.to_string()),
+        ]);
+        Self { protocol, port, external_port: port, mapped: false, failed: false, metrics }
+    }
+
+    fn on_success(&mut self, method: PortMappingMethod, external_port: u16) {
+        if !self.mapped {
+            info!(
+                target: "net::nat",
+                %method,
+                protocol = %self.protocol,
+                port = self.port,
+                external_port,
+                "Mapped port on the gateway"
+            );
+        }
+        if external_port != self.external_port {
+            debug!(
+                target: "net::nat",
+                %method,
+                protocol = %self.protocol,
+                port = self.port,
+                external_port,
+                "Gateway mapped the port to a different external port"
+            );
+        }
+
+        self.external_port = external_port;
+        self.mapped = true;
+        self.failed = false;
+        self.metrics.mapped.set(1.0);
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        self.metrics.last_refresh_timestamp.set(now.as_secs() as f64);
+    }
+
+    fn on_failure(&mut self, method: PortMappingMethod, err: &PortMappingError) {
+        if self.failed {
+            debug!(
+                target: "net::nat",
+                %method,
+                protocol = %self.protocol,
+                port = self.port,
+                %err,
+                "Failed to map port on the gateway"
+            );
+        } else {
+            warn!(
+                target: "net::nat",
+                %method,
+                protocol = %self.protocol,
+                port = self.port,
+                %err,
+                "Failed to map port on the gateway, inbound peers may not be able to connect"
+            );
+        }
+
+        self.mapped = false;
+        self.failed = true;
+        self.metrics.mapped.set(0.0);
+        self.metrics.failures.increment(1);
+    }
+}
+
+impl From<Protocol> for PortMappingProtocol {
+    fn from(protocol: Protocol) -> Self {
+        match protocol {
+            Protocol::Tcp => Self::TCP,
+            Protocol::Udp => Self::UDP,
+        }
+    }
+}
+
+/// Returns the IP of the interface that routes to the gateway.
+///
+/// Connecting a UDP socket doesn't send anything, it only selects the route.
+async fn local_ip(gateway: SocketAddr) -> io::Result<IpAddr> {
+    let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0))).await?;
+    socket.connect(gateway).await?;
+    Ok(socket.local_addr()?.ip())
+}
+
+/// Metrics of the mapping of a port on the gateway.
+#[derive(Metrics)]
+#[metrics(scope = "network.nat")]
+struct PortMappingMetrics {
+    /// Whether the port is mapped on the gateway, 1 if it is and 0 otherwise
+    mapped: Gauge,
+    /// The unix timestamp of the last successful refresh of the mapping, in seconds
+    last_refresh_timestamp: Gauge,
+    /// Number of failed attempts to map the port
+    failures: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn reports_changed_external_ports() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let listener = reported.clone();
+        let mut mapper = PortMapper::new(PortMappingMethod::NatPmp, 30303, 30304)
+            .on_external_ports(move |tcp, udp| listener.lock().unwrap().push((tcp, udp)));
+
+        // the requested ports are already announced
+        for port in &mut mapper.ports {
+            port.on_success(PortMappingMethod::NatPmp, port.port);
+        }
+        mapper.on_mapped_ports();
+        assert!(reported.lock().unwrap().is_empty());
+
+        mapper.ports[1].on_success(PortMappingMethod::NatPmp, 40000);
+        mapper.on_mapped_ports();
+        mapper.on_mapped_ports();
+        assert_eq!(*reported.lock().unwrap(), vec![(30303, 40000)]);
+    }
+}
@@ -0,0 +1,248 @@
+//! A minimal client of [NAT-PMP](https://datatracker.ietf.org/doc/html/rfc6886), which is used to
+//! resolve the external IP and to map ports on gateways that don't support UPnP.
+
+use std::{
+    fmt, io,
+    net::{Ipv4Addr, SocketAddr, SocketAddrV4},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+
+/// The port the gateway listens on for NAT-PMP requests.
+const NAT_PMP_PORT: u16 = 5351;
+
+/// The delay before the first retransmission of a request, which is doubled on every retry.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// The number of times a request is sent before giving up.
+///
+/// The RFC recommends 9 attempts, which takes more than a minute, but a gateway that doesn't
+/// respond within a few seconds is treated as not supporting NAT-PMP.
+const MAX_ATTEMPTS: u32 = 4;
+
+/// Errors of NAT-PMP requests.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum NatPmpError {
+    /// The default gateway couldn't be determined.
+    #[error("failed to find the default gateway: {0}")]
+    Gateway(io::Error),
+    /// The request couldn't be sent or the gateway didn't respond.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The gateway responded with a malformed response.
+    #[error("malformed NAT-PMP response")]
+    MalformedResponse,
+    /// The gateway rejected the request with a result code.
+    #[error("NAT-PMP request failed with result code {0}")]
+    ResultCode(u16),
+}
+
+/// The protocol of a mapped port.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Protocol {
+    /// A UDP port.
+    Udp,
+    /// A TCP port.
+    Tcp,
+}
+
+impl Protocol {
+    const fn opcode(self) -> u8 {
+        match self {
+            Self::Udp => 1,
+            Self::Tcp => 2,
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Udp => f.write_str("udp"),
+            Self::Tcp => f.write_str("tcp"),
+        }
+    }
+}
+
+/// A port mapped on the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Mapping {
+    /// The external port the gateway assigned, which may differ from the requested one.
+    pub(crate) external_port: u16,
+    /// The lifetime of the mapping the gateway granted.
+    pub(crate) lifetime: Duration,
+}
+
+/// Returns the address of the NAT-PMP server of the default gateway.
+pub(crate) fn gateway() -> Result<SocketAddr, NatPmpError> {
+    let gateway = default_gateway().map_err(NatPmpError::Gateway)?;
+    Ok(SocketAddrV4::new(gateway, NAT_PMP_PORT).into())
+}
+
+/// Requests the external IP from the gateway.
+pub(crate) async fn external_ip(gateway: SocketAddr) -> Result<Ipv4Addr, NatPmpError> {
+    let response = request(gateway, &[0, 0], 12).await?;
+    let ip: [u8; 4] = response[8..12].try_into().expect("response has 12 bytes");
+    Ok(ip.into())
+}
+
+/// Maps the external port to the internal port of this host for the given lifetime.
+///
+/// A lifetime of zero deletes the mapping.
+pub(crate) async fn map_port(
+    gateway: SocketAddr,
+    protocol: Protocol,
+    internal_port: u16,
+    external_port: u16,
+    lifetime: Duration,
+) -> Result<Mapping, NatPmpError> {
+    let mut payload = [0u8; 12];
+    payload[1] = protocol.opcode();
+    payload[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    payload[6..8].copy_from_slice(&external_port.to_be_bytes());
+    let lifetime = u32::try_from(lifetime.as_secs()).unwrap_or(u32::MAX);
+    payload[8..12].copy_from_slice(&lifetime.to_be_bytes());
+
+    let response = request(gateway, &payload, 16).await?;
+    if u16::from_be_bytes([response[8], response[9]]) != internal_port {
+        return Err(NatPmpError::MalformedResponse)
+    }
+    Ok(Mapping {
+        external_port: u16::from_be_bytes([response[10], response[11]]),
+        lifetime: Duration::from_secs(u32::from_be_bytes(
+            response[12..16].try_into().expect("response has 16 bytes"),
+        ) as u64),
+    })
+}
+
+/// Sends the request to the gateway until it responds, and returns the response after checking
+/// its header.
+async fn request(
+    gateway: SocketAddr,
+    payload: &[u8],
+    response_len: usize,
+) -> Result<Vec<u8>, NatPmpError> {
+    let socket = UdpSocket::bind(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0))).await?;
+    socket.connect(gateway).await?;
+
+    let mut buf = vec![0u8; response_len];
+    let mut delay = INITIAL_RETRY_DELAY;
+    for _ in 0..MAX_ATTEMPTS {
+        socket.send(payload).await?;
+        match tokio::time::timeout(delay, socket.recv(&mut buf)).await {
+            Ok(Ok(len)) => {
+                // the response echoes the opcode of the request with the most significant bit set
+                if len != response_len || buf[0] != 0 || buf[1] != (payload[1] | 0x80) {
+                    return Err(NatPmpError::MalformedResponse)
+                }
+                return match u16::from_be_bytes([buf[2], buf[3]]) {
+                    0 => Ok(buf),
+                    code => Err(NatPmpError::ResultCode(code)),
+                }
+            }
+            Ok(Err(err)) => return Err(err.into()),
+            Err(_) => delay *= 2,
+        }
+    }
+
+    Err(io::Error::new(io::ErrorKind::TimedOut, "gateway didn't respond to NAT-PMP request").into())
+}
+
+/// Returns the default gateway from the routing table.
+#[cfg(target_os = "linux")]
+fn default_gateway() -> io::Result<Ipv4Addr> {
+    let routes = std::fs::read_to_string("/proc/net/route")?;
+    parse_default_gateway(&routes)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no default route"))
+}
+
+/// Returns the default gateway from the routing table.
+#[cfg(not(target_os = "linux"))]
+fn default_gateway() -> io::Result<Ipv4Addr> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "default gateway lookup is only supported on Linux",
+    ))
+}
+
+/// Returns the gateway of the default route in the format of `/proc/net/route`, whose addresses
+/// are hex encoded in the byte order of the host.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let destination = fields.next()?;
+        let gateway = u32::from_str_radix(fields.next()?, 16).ok()?;
+        (destination == "00000000" && gateway != 0).then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Responds to a single request on the socket of the gateway, and returns the request.
+    async fn respond(gateway: UdpSocket, response: Vec<u8>) -> Vec<u8> {
+        let mut buf = [0u8; 16];
+        let (len, peer) = gateway.recv_from(&mut buf).await.unwrap();
+        gateway.send_to(&response, peer).await.unwrap();
+        buf[..len].to_vec()
+    }
+
+    #[tokio::test]
+    async fn external_ip_request() {
+        let gateway = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = gateway.local_addr().unwrap();
+        // version, opcode, result code, seconds since the start of the epoch, external IP
+        let response = vec![0, 128, 0, 0, 0, 0, 0, 1, 203, 0, 113, 7];
+        let server = tokio::spawn(respond(gateway, response));
+
+        assert_eq!(external_ip(addr).await.unwrap(), Ipv4Addr::new(203, 0, 113, 7));
+        assert_eq!(server.await.unwrap(), [0, 0]);
+    }
+
+    #[tokio::test]
+    async fn map_port_request() {
+        let gateway = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = gateway.local_addr().unwrap();
+        // the gateway maps the port to 30304 for 1800 seconds
+        let response = vec![0, 130, 0, 0, 0, 0, 0, 1, 0x76, 0x5f, 0x76, 0x60, 0, 0, 0x07, 0x08];
+        let server = tokio::spawn(respond(gateway, response));
+
+        let mapping =
+            map_port(addr, Protocol::Tcp, 30303, 30303, Duration::from_secs(3600)).await.unwrap();
+        assert_eq!(mapping, Mapping { external_port: 30304, lifetime: Duration::from_secs(1800) });
+        // version, opcode, reserved, internal port, external port, lifetime
+        assert_eq!(server.await.unwrap(), [0, 2, 0, 0, 0x76, 0x5f, 0x76, 0x5f, 0, 0, 0x0e, 0x10]);
+    }
+
+    #[tokio::test]
+    async fn map_port_rejected() {
+        let gateway = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = gateway.local_addr().unwrap();
+        // the gateway is out of resources
+        let response = vec![0, 129, 0, 4, 0, 0, 0, 1, 0x76, 0x5f, 0, 0, 0, 0, 0, 0];
+        let server = tokio::spawn(respond(gateway, response));
+
+        let err = map_port(addr, Protocol::Udp, 30303, 30303, Duration::from_secs(3600))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, NatPmpError::ResultCode(4)));
+        assert_eq!(server.await.unwrap()[1], Protocol::Udp.opcode());
+    }
+
+    #[test]
+    #[cfg(target_endian = "little")]
+    fn parse_gateway() {
+        let routes = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0
+eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\t0\t0\t0
+";
+        assert_eq!(parse_default_gateway(routes), Some(Ipv4Addr::new(192, 168, 1, 1)));
+
+        // only the local network is routed
+        let local_routes = routes.lines().take(2).collect::<Vec<_>>().join("\n");
+        assert_eq!(parse_default_gateway(&local_routes), None);
+    }
+}
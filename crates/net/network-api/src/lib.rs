@@ -13,14 +13,14 @@
 )]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use reth_eth_wire::{DisconnectReason, EthVersion, Status};
+use reth_eth_wire::{capability::Capability, DisconnectReason, EthVersion, Status};
 use reth_primitives::{NodeRecord, PeerId};
 use std::{future::Future, net::SocketAddr, sync::Arc, time::Instant};
 
 pub use error::NetworkError;
 pub use reputation::{Reputation, ReputationChangeKind};
 use reth_eth_wire::capability::Capabilities;
-use reth_rpc_types::NetworkStatus;
+use reth_rpc_types::{NetworkStatus, PeerStats, TrustedPeerInfo};
 
 /// Network Error
 pub mod error;
@@ -120,6 +120,23 @@ pub trait Peers: PeersInfo {
         peer_ids: Vec<PeerId>,
     ) -> impl Future<Output = Result<Vec<PeerInfo>, NetworkError>> + Send;
 
+    /// Returns the [KnownPeer] info of all peers in the peer set, connected or not, as it's
+    /// persisted across restarts.
+    fn get_known_peers(&self) -> impl Future<Output = Result<Vec<KnownPeer>, NetworkError>> + Send;
+
+    /// Returns the [TrustedPeerInfo] of all trusted peers, connected or not.
+    fn get_trusted_peers(
+        &self,
+    ) -> impl Future<Output = Result<Vec<TrustedPeerInfo>, NetworkError>> + Send;
+
+    /// Returns the [PeerStats] of all connected peers.
+    ///
+    /// If `reset` is true, the counters of the messages exchanged with the peers are reset.
+    fn get_peer_stats(
+        &self,
+        reset: bool,
+    ) -> impl Future<Output = Result<Vec<PeerStats>, NetworkError>> + Send;
+
     /// Removes a peer from the peer set that corresponds to given kind.
     fn remove_peer(&self, peer: PeerId, kind: PeerKind);
 
@@ -184,6 +201,49 @@ pub struct PeerInfo {
     pub session_established: Instant,
 }
 
+/// Info about a peer of the peer set that is persisted across restarts, see
+/// [Peers::get_known_peers].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct KnownPeer {
+    /// Where to reach the peer.
+    pub record: NodeRecord,
+    /// The reputation of the peer.
+    pub reputation: i32,
+    /// The unix timestamp in seconds when the peer was last connected, if ever.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub last_seen: Option<u64>,
+    /// The unix timestamp in seconds until which the peer is banned, if it's banned.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub banned_until: Option<u64>,
+    /// The client's name and version the peer announced in its last session, if any.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub client_version: Option<String>,
+    /// The capabilities the peer announced in its last session.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub capabilities: Vec<Capability>,
+}
+
+impl KnownPeer {
+    /// Creates a new entry for the given node, without any info about past sessions.
+    pub fn new(record: NodeRecord, reputation: i32) -> Self {
+        Self {
+            record,
+            reputation,
+            last_seen: None,
+            banned_until: None,
+            client_version: None,
+            capabilities: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the peer is still banned at the given unix timestamp in seconds.
+    pub fn is_banned_at(&self, timestamp: u64) -> bool {
+        self.banned_until.is_some_and(|until| until > timestamp)
+    }
+}
+
 /// The direction of the connection.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Direction {
@@ -4,14 +4,14 @@
 //! generic over it.
 
 use crate::{
-    NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
+    KnownPeer, NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
     ReputationChangeKind,
 };
 use enr::{secp256k1::SecretKey, Enr};
 use reth_discv4::DEFAULT_DISCOVERY_PORT;
 use reth_eth_wire::{DisconnectReason, ProtocolVersion};
 use reth_primitives::{Chain, NodeRecord, PeerId};
-use reth_rpc_types::{admin::EthProtocolInfo, NetworkStatus};
+use reth_rpc_types::{admin::EthProtocolInfo, NetworkStatus, PeerStats, TrustedPeerInfo};
 use std::net::{IpAddr, SocketAddr};
 
 /// A type that implements all network trait that does nothing.
@@ -89,6 +89,18 @@ impl Peers for NoopNetwork {
         Ok(vec![])
     }
 
+    async fn get_known_peers(&self) -> Result<Vec<KnownPeer>, NetworkError> {
+        Ok(vec![])
+    }
+
+    async fn get_trusted_peers(&self) -> Result<Vec<TrustedPeerInfo>, NetworkError> {
+        Ok(vec![])
+    }
+
+    async fn get_peer_stats(&self, _reset: bool) -> Result<Vec<PeerStats>, NetworkError> {
+        Ok(vec![])
+    }
+
     fn remove_peer(&self, _peer: PeerId, _kind: PeerKind) {}
 
     fn disconnect_peer(&self, _peer: PeerId) {}
@@ -1,7 +1,7 @@
 //! Builder support for configuring the entire setup.
 
 use crate::{
-    eth_requests::EthRequestHandler,
+    eth_requests::{EthRequestHandler, EthRequestsConfig},
     transactions::{TransactionsManager, TransactionsManagerConfig},
     NetworkHandle, NetworkManager,
 };
@@ -69,12 +69,22 @@ impl<C, Tx, Eth> NetworkBuilder<C, Tx, Eth> {
     pub fn request_handler<Client>(
         self,
         client: Client,
+    ) -> NetworkBuilder<C, Tx, EthRequestHandler<Client>> {
+        self.request_handler_with_config(client, EthRequestsConfig::default())
+    }
+
+    /// Creates a new [`EthRequestHandler`] that serves the requests within the budgets of the
+    /// given config and wires it to the network.
+    pub fn request_handler_with_config<Client>(
+        self,
+        client: Client,
+        config: EthRequestsConfig,
     ) -> NetworkBuilder<C, Tx, EthRequestHandler<Client>> {
         let NetworkBuilder { mut network, transactions, .. } = self;
         let (tx, rx) = mpsc::channel(ETH_REQUEST_CHANNEL_CAPACITY);
         network.set_eth_request_handler(tx);
         let peers = network.handle().peers_handle().clone();
-        let request_handler = EthRequestHandler::new(client, peers, rx);
+        let request_handler = EthRequestHandler::with_config(client, peers, rx, config);
         NetworkBuilder { network, request_handler, transactions }
     }
 }
@@ -78,6 +78,11 @@ pub struct NetworkConfig<C> {
     pub extra_protocols: RlpxSubProtocols,
     /// Whether to disable transaction gossip
     pub tx_gossip_disabled: bool,
+    /// Whether to dial discovered peers regardless of the `eth` fork id in their ENR.
+    ///
+    /// By default, peers that announce a fork id that's incompatible with the local fork filter
+    /// are dropped before they're dialed.
+    pub fork_id_filter_disabled: bool,
     /// How to instantiate transactions manager.
     pub transactions_manager_config: TransactionsManagerConfig,
 }
@@ -211,6 +216,8 @@ pub struct NetworkConfigBuilder {
     head: Option<Head>,
     /// Whether tx gossip is disabled
     tx_gossip_disabled: bool,
+    /// Whether filtering discovered peers by their ENR fork id is disabled
+    fork_id_filter_disabled: bool,
     /// The block importer type
     #[serde(skip)]
     block_import: Option<Box<dyn BlockImport>>,
@@ -240,6 +247,7 @@ impl NetworkConfigBuilder {
             extra_protocols: Default::default(),
             head: None,
             tx_gossip_disabled: false,
+            fork_id_filter_disabled: false,
             block_import: None,
             transactions_manager_config: Default::default(),
         }
@@ -450,6 +458,14 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets whether discovered peers are dialed regardless of the fork id in their ENR.
+    ///
+    /// This is useful for devnets with unusual fork ids.
+    pub fn disable_fork_id_filter(mut self, disable_fork_id_filter: bool) -> Self {
+        self.fork_id_filter_disabled = disable_fork_id_filter;
+        self
+    }
+
     /// Sets the block import type.
     pub fn block_import(mut self, block_import: Box<dyn BlockImport>) -> Self {
         self.block_import = Some(block_import);
@@ -489,6 +505,7 @@ impl NetworkConfigBuilder {
             extra_protocols,
             head,
             tx_gossip_disabled,
+            fork_id_filter_disabled,
             block_import,
             transactions_manager_config,
         } = self;
@@ -544,6 +561,7 @@ impl NetworkConfigBuilder {
             extra_protocols,
             fork_filter,
             tx_gossip_disabled,
+            fork_id_filter_disabled,
             transactions_manager_config,
         }
     }
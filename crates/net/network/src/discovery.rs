@@ -217,6 +217,9 @@ impl Discovery {
             DiscoveryUpdate::Added(record) => {
                 self.on_node_record_update(record, None);
             }
+            DiscoveryUpdate::AddedWithForkId(record, fork_id) => {
+                self.on_node_record_update(record, Some(fork_id));
+            }
             DiscoveryUpdate::EnrForkId(node, fork_id) => {
                 self.queued_events.push_back(DiscoveryEvent::EnrForkId(node.id, fork_id))
             }
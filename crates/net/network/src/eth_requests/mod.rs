@@ -0,0 +1,729 @@
+//! Blocks/Headers management for the p2p network.
+
+use crate::{
+    budget::DEFAULT_BUDGET_TRY_DRAIN_STREAM,
+    metrics::EthRequestHandlerMetrics,
+    peers::{PeersHandle, THROTTLED_REQUESTS_REPUTATION_CHANGE},
+    poll_nested_stream_with_budget,
+};
+use alloy_rlp::Encodable;
+use futures::StreamExt;
+use reth_eth_wire::{
+    BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders, GetNodeData, GetReceipts, NodeData,
+    Receipt69, Receipts, Receipts69,
+};
+use reth_interfaces::p2p::error::RequestResult;
+use reth_network_api::ReputationChangeKind;
+use reth_primitives::{BlockBody, BlockHashOrNumber, Header, HeadersDirection, PeerId, Receipt};
+use reth_provider::{BlockReader, HeaderProvider, ReceiptProvider};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Instant,
+};
+use tokio::{
+    sync::{mpsc::Receiver, oneshot},
+    time::Sleep,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
+
+mod throttle;
+use throttle::{ByteBudget, PeerRequests};
+pub use throttle::{
+    EthRequestsConfig, DEFAULT_MAX_BYTES_PER_PEER_PER_SECOND, DEFAULT_MAX_BYTES_PER_SECOND,
+    DEFAULT_MAX_QUEUED_REQUESTS, DEFAULT_MAX_REQUESTS_PER_PEER,
+    DEFAULT_MAX_THROTTLED_REQUESTS_PER_PEER,
+};
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/b0d44338bbcefee044f1f635a84487cbbd8f0538/eth/protocols/eth/handler.go#L34-L56>
+
+/// Maximum number of receipts to serve.
+///
+/// Used to limit lookups.
+const MAX_RECEIPTS_SERVE: usize = 1024;
+
+/// Maximum number of block headers to serve.
+///
+/// Used to limit lookups.
+const MAX_HEADERS_SERVE: usize = 1024;
+
+/// Maximum number of block headers to serve.
+///
+/// Used to limit lookups. With 24KB block sizes nowadays, the practical limit will always be
+/// SOFT_RESPONSE_LIMIT.
+const MAX_BODIES_SERVE: usize = 1024;
+
+/// Maximum size of replies to data retrievals.
+const SOFT_RESPONSE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Manages eth related requests on top of the p2p network.
+///
+/// The incoming requests are queued per peer and served within the budgets of the
+/// [EthRequestsConfig], so a single peer can't saturate the disk.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct EthRequestHandler<C> {
+    /// The client type that can interact with the chain.
+    client: C,
+    /// Used for reporting peers.
+    peers: PeersHandle,
+    /// Incoming request from the [NetworkManager](crate::NetworkManager).
+    incoming_requests: ReceiverStream<IncomingEthRequest>,
+    /// The budgets of the served requests.
+    config: EthRequestsConfig,
+    /// The bytes that can be read to serve the requests of all peers.
+    read_budget: ByteBudget,
+    /// The queued requests and the budgets of the peers that recently sent requests.
+    peer_requests: HashMap<PeerId, PeerRequests>,
+    /// The number of queued requests of all peers.
+    queued_requests: usize,
+    /// Wakes up the handler when the budgets allow to serve the queued requests again.
+    budget_timer: Option<Pin<Box<Sleep>>>,
+    /// Metrics for the eth request handler.
+    metrics: EthRequestHandlerMetrics,
+}
+
+// === impl EthRequestHandler ===
+impl<C> EthRequestHandler<C> {
+    /// Create a new instance
+    pub fn new(client: C, peers: PeersHandle, incoming: Receiver<IncomingEthRequest>) -> Self {
+        Self::with_config(client, peers, incoming, EthRequestsConfig::default())
+    }
+
+    /// Create a new instance that serves the requests within the budgets of the given config.
+    pub fn with_config(
+        client: C,
+        peers: PeersHandle,
+        incoming: Receiver<IncomingEthRequest>,
+        config: EthRequestsConfig,
+    ) -> Self {
+        Self {
+            client,
+            peers,
+            incoming_requests: ReceiverStream::new(incoming),
+            config,
+            read_budget: ByteBudget::new(config.max_bytes_per_second),
+            peer_requests: HashMap::new(),
+            queued_requests: 0,
+            budget_timer: None,
+            metrics: Default::default(),
+        }
+    }
+
+    /// Queues the request of a peer, or answers it with an empty response if the peer or the
+    /// handler already queued too many requests.
+    fn on_incoming_request(&mut self, request: IncomingEthRequest) {
+        let peer_id = request.peer_id();
+        let config = &self.config;
+        let peer = self.peer_requests.entry(peer_id).or_insert_with(|| PeerRequests::new(config));
+
+        // only the limits of the peer itself count towards its penalty
+        let throttled = if peer.queued.len() >= config.max_requests_per_peer ||
+            self.queued_requests >= config.max_queued_requests
+        {
+            self.metrics.dropped_requests.increment(1);
+            trace!(target: "net::eth", ?peer_id, queued=peer.queued.len(), "Dropping request");
+            request.respond_empty();
+            peer.queued.len() >= config.max_requests_per_peer
+        } else {
+            let throttled = peer.budget.available(Instant::now()) == 0;
+            if throttled {
+                self.metrics.throttled_requests.increment(1);
+                trace!(target: "net::eth", ?peer_id, "Throttling request");
+            }
+            peer.queued.push_back(request);
+            self.queued_requests += 1;
+            throttled
+        };
+
+        if throttled {
+            peer.throttled += 1;
+            if peer.throttled >= config.max_throttled_requests_per_peer {
+                peer.throttled = 0;
+                self.metrics.penalized_peers.increment(1);
+                debug!(target: "net::eth", ?peer_id, "Penalizing peer for exceeding the budgets");
+                self.peers.reputation_change(
+                    peer_id,
+                    ReputationChangeKind::Other(THROTTLED_REQUESTS_REPUTATION_CHANGE),
+                );
+            }
+        }
+    }
+
+    /// Returns when the budgets allow to serve a queued request again, if there are any.
+    fn next_budget_refill(&self) -> Option<Instant> {
+        let peers = self
+            .peer_requests
+            .values()
+            .filter(|peer| !peer.queued.is_empty())
+            .map(|peer| peer.budget.available_at())
+            .min()?;
+        Some(peers.max(self.read_budget.available_at()))
+    }
+}
+
+impl<C> EthRequestHandler<C>
+where
+    C: BlockReader + HeaderProvider + ReceiptProvider,
+{
+    /// Returns the list of requested headers, within the given soft limit of bytes, and their
+    /// size.
+    fn get_headers_response(&self, request: GetBlockHeaders, limit: usize) -> (Vec<Header>, usize) {
+        let GetBlockHeaders { start_block, limit: count, skip, direction } = request;
+
+        let mut headers = Vec::new();
+
+        let mut block: BlockHashOrNumber = match start_block {
+            BlockHashOrNumber::Hash(start) => start.into(),
+            BlockHashOrNumber::Number(num) => {
+                let Some(hash) = self.client.block_hash(num).unwrap_or_default() else {
+                    return (headers, 0)
+                };
+                hash.into()
+            }
+        };
+
+        let skip = skip as u64;
+        let mut total_bytes = 0;
+
+        for _ in 0..count {
+            if let Some(header) = self.client.header_by_hash_or_number(block).unwrap_or_default() {
+                match direction {
+                    HeadersDirection::Rising => {
+                        if let Some(next) = (header.number + 1).checked_add(skip) {
+                            block = next.into()
+                        } else {
+                            break
+                        }
+                    }
+                    HeadersDirection::Falling => {
+                        if skip > 0 {
+                            // prevent under flows for block.number == 0 and `block.number - skip <
+                            // 0`
+                            if let Some(next) =
+                                header.number.checked_sub(1).and_then(|num| num.checked_sub(skip))
+                            {
+                                block = next.into()
+                            } else {
+                                break
+                            }
+                        } else {
+                            block = header.parent_hash.into()
+                        }
+                    }
+                }
+
+                total_bytes += header.length();
+                headers.push(header);
+
+                if headers.len() >= MAX_HEADERS_SERVE {
+                    break
+                }
+
+                if total_bytes > limit {
+                    break
+                }
+            } else {
+                break
+            }
+        }
+
+        (headers, total_bytes)
+    }
+
+    fn on_headers_request(
+        &mut self,
+        _peer_id: PeerId,
+        request: GetBlockHeaders,
+        response: oneshot::Sender<RequestResult<BlockHeaders>>,
+        limit: usize,
+    ) -> usize {
+        self.metrics.received_headers_requests.increment(1);
+        let (headers, total_bytes) = self.get_headers_response(request, limit);
+        let _ = response.send(Ok(BlockHeaders(headers)));
+        total_bytes
+    }
+
+    fn on_bodies_request(
+        &mut self,
+        _peer_id: PeerId,
+        request: GetBlockBodies,
+        response: oneshot::Sender<RequestResult<BlockBodies>>,
+        limit: usize,
+    ) -> usize {
+        self.metrics.received_bodies_requests.increment(1);
+        let mut bodies = Vec::new();
+
+        let mut total_bytes = 0;
+
+        for hash in request.0 {
+            if let Some(block) = self.client.block_by_hash(hash).unwrap_or_default() {
+                let body = BlockBody {
+                    transactions: block.body,
+                    ommers: block.ommers,
+                    withdrawals: block.withdrawals,
+                };
+
+                total_bytes += body.length();
+                bodies.push(body);
+
+                if bodies.len() >= MAX_BODIES_SERVE {
+                    break
+                }
+
+                if total_bytes > limit {
+                    break
+                }
+            } else {
+                break
+            }
+        }
+
+        let _ = response.send(Ok(BlockBodies(bodies)));
+        total_bytes
+    }
+
+    /// Returns the receipts of the requested blocks, encoded with the given function, and their
+    /// size.
+    ///
+    /// The receipts are read block by block, and the response is truncated at the first block
+    /// that would exceed the given soft limit of bytes, but it always includes the first block.
+    fn get_receipts_response<T, F>(
+        &self,
+        request: GetReceipts,
+        limit: usize,
+        mut f: F,
+    ) -> (Vec<Vec<T>>, usize)
+    where
+        T: Encodable,
+        F: FnMut(Receipt) -> T,
+    {
+        let mut receipts = Vec::new();
+
+        let mut total_bytes = 0;
+
+        for hash in request.0 {
+            let Some(receipts_by_block) =
+                self.client.receipts_by_block(BlockHashOrNumber::Hash(hash)).unwrap_or_default()
+            else {
+                break
+            };
+
+            let block_receipts = receipts_by_block.into_iter().map(&mut f).collect::<Vec<_>>();
+
+            let block_bytes = block_receipts.length();
+            if total_bytes + block_bytes > limit && !receipts.is_empty() {
+                break
+            }
+            total_bytes += block_bytes;
+            receipts.push(block_receipts);
+
+            if receipts.len() >= MAX_RECEIPTS_SERVE || total_bytes >= limit {
+                break
+            }
+        }
+
+        (receipts, total_bytes)
+    }
+
+    fn on_receipts_request(
+        &mut self,
+        _peer_id: PeerId,
+        request: GetReceipts,
+        response: oneshot::Sender<RequestResult<Receipts>>,
+        limit: usize,
+    ) -> usize {
+        let (receipts, total_bytes) =
+            self.get_receipts_response(request, limit, |receipt| receipt.with_bloom());
+        let _ = response.send(Ok(Receipts(receipts)));
+        total_bytes
+    }
+
+    /// Serves the receipts to a peer that negotiated eth/69, without computing their bloom.
+    fn on_receipts69_request(
+        &mut self,
+        _peer_id: PeerId,
+        request: GetReceipts,
+        response: oneshot::Sender<RequestResult<Receipts69>>,
+        limit: usize,
+    ) -> usize {
+        let (receipts, total_bytes) = self.get_receipts_response(request, limit, Receipt69);
+        let _ = response.send(Ok(Receipts69(receipts)));
+        total_bytes
+    }
+
+    /// Serves the request within the given soft limit of bytes and returns the size of the
+    /// response.
+    fn on_request(&mut self, request: IncomingEthRequest, limit: usize) -> usize {
+        match request {
+            IncomingEthRequest::GetBlockHeaders { peer_id, request, response } => {
+                self.on_headers_request(peer_id, request, response, limit)
+            }
+            IncomingEthRequest::GetBlockBodies { peer_id, request, response } => {
+                self.on_bodies_request(peer_id, request, response, limit)
+            }
+            IncomingEthRequest::GetNodeData { .. } => 0,
+            IncomingEthRequest::GetReceipts { peer_id, request, response } => {
+                self.on_receipts_request(peer_id, request, response, limit)
+            }
+            IncomingEthRequest::GetReceipts69 { peer_id, request, response } => {
+                self.on_receipts69_request(peer_id, request, response, limit)
+            }
+        }
+    }
+
+    /// Serves the queued requests within the budgets, one request of every peer at a time.
+    ///
+    /// Returns true if more requests can be served after [DEFAULT_BUDGET_TRY_DRAIN_STREAM]
+    /// requests were served.
+    fn serve_queued_requests(&mut self) -> bool {
+        let mut served = 0;
+        let mut peer_ids = Vec::new();
+        loop {
+            let now = Instant::now();
+            let mut progress = false;
+            peer_ids.extend(self.peer_requests.keys().copied());
+            for peer_id in peer_ids.drain(..) {
+                let available = self.read_budget.available(now);
+                let Some(peer) = self.peer_requests.get_mut(&peer_id) else { continue };
+                let limit = SOFT_RESPONSE_LIMIT.min(available).min(peer.budget.available(now));
+                if limit == 0 {
+                    continue
+                }
+                let Some(request) = peer.queued.pop_front() else { continue };
+                self.queued_requests -= 1;
+
+                let bytes = self.on_request(request, limit);
+                self.read_budget.consume(bytes);
+                if let Some(peer) = self.peer_requests.get_mut(&peer_id) {
+                    peer.budget.consume(bytes);
+                }
+
+                progress = true;
+                served += 1;
+                if served >= DEFAULT_BUDGET_TRY_DRAIN_STREAM {
+                    self.update_queue_metrics();
+                    return true
+                }
+            }
+            if !progress {
+                break
+            }
+        }
+
+        // forget the peers that don't use their budget anymore
+        let now = Instant::now();
+        self.peer_requests
+            .retain(|_, peer| !peer.queued.is_empty() || !peer.budget.is_replenished(now));
+        self.update_queue_metrics();
+
+        false
+    }
+
+    fn update_queue_metrics(&self) {
+        self.metrics.queued_requests.set(self.queued_requests as f64);
+        self.metrics
+            .queueing_peers
+            .set(self.peer_requests.values().filter(|peer| !peer.queued.is_empty()).count() as f64);
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<C> Future for EthRequestHandler<C>
+where
+    C: BlockReader + HeaderProvider + Unpin,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        let maybe_more_incoming_requests = poll_nested_stream_with_budget!(
+            "net::eth",
+            "Incoming eth requests stream",
+            DEFAULT_BUDGET_TRY_DRAIN_STREAM,
+            this.incoming_requests.poll_next_unpin(cx),
+            |incoming| {
+                match incoming {
+                    IncomingEthRequest::GetNodeData { .. } => {}
+                    incoming => this.on_incoming_request(incoming),
+                }
+            },
+        );
+
+        let maybe_more_queued_requests = this.serve_queued_requests();
+
+        // stream is fully drained and import futures pending
+        if maybe_more_incoming_requests || maybe_more_queued_requests {
+            // make sure we're woken up again
+            cx.waker().wake_by_ref();
+            return Poll::Pending
+        }
+
+        // wait until the budgets allow to serve the queued requests
+        if let Some(deadline) = this.next_budget_refill() {
+            let deadline = tokio::time::Instant::from_std(deadline);
+            let timer = this
+                .budget_timer
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline)));
+            timer.as_mut().reset(deadline);
+            if timer.as_mut().poll(cx).is_ready() {
+                cx.waker().wake_by_ref();
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+/// All `eth` request related to blocks delegated by the network.
+#[derive(Debug)]
+pub enum IncomingEthRequest {
+    /// Request Block headers from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetBlockHeaders {
+        /// The ID of the peer to request block headers from.
+        peer_id: PeerId,
+        /// The specific block headers requested.
+        request: GetBlockHeaders,
+        /// The channel sender for the response containing block headers.
+        response: oneshot::Sender<RequestResult<BlockHeaders>>,
+    },
+    /// Request Block bodies from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetBlockBodies {
+        /// The ID of the peer to request block bodies from.
+        peer_id: PeerId,
+        /// The specific block bodies requested.
+        request: GetBlockBodies,
+        /// The channel sender for the response containing block bodies.
+        response: oneshot::Sender<RequestResult<BlockBodies>>,
+    },
+    /// Request Node Data from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetNodeData {
+        /// The ID of the peer to request node data from.
+        peer_id: PeerId,
+        /// The specific node data requested.
+        request: GetNodeData,
+        /// The channel sender for the response containing node data.
+        response: oneshot::Sender<RequestResult<NodeData>>,
+    },
+    /// Request Receipts from the peer.
+    ///
+    /// The response should be sent through the channel.
+    GetReceipts {
+        /// The ID of the peer to request receipts from.
+        peer_id: PeerId,
+        /// The specific receipts requested.
+        request: GetReceipts,
+        /// The channel sender for the response containing receipts.
+        response: oneshot::Sender<RequestResult<Receipts>>,
+    },
+    /// Request Receipts without their bloom from a peer that negotiated eth/69.
+    ///
+    /// The response should be sent through the channel.
+    GetReceipts69 {
+        /// The ID of the peer to request receipts from.
+        peer_id: PeerId,
+        /// The specific receipts requested.
+        request: GetReceipts,
+        /// The channel sender for the response containing eth/69 receipts.
+        response: oneshot::Sender<RequestResult<Receipts69>>,
+    },
+}
+
+impl IncomingEthRequest {
+    /// Returns the ID of the peer that sent the request.
+    pub fn peer_id(&self) -> PeerId {
+        match self {
+            Self::GetBlockHeaders { peer_id, .. } |
+            Self::GetBlockBodies { peer_id, .. } |
+            Self::GetNodeData { peer_id, .. } |
+            Self::GetReceipts { peer_id, .. } |
+            Self::GetReceipts69 { peer_id, .. } => *peer_id,
+        }
+    }
+
+    /// Answers the request with an empty response, which the protocol allows if the requested
+    /// data is not available.
+    fn respond_empty(self) {
+        match self {
+            Self::GetBlockHeaders { response, .. } => {
+                let _ = response.send(Ok(BlockHeaders(Vec::new())));
+            }
+            Self::GetBlockBodies { response, .. } => {
+                let _ = response.send(Ok(BlockBodies(Vec::new())));
+            }
+            Self::GetNodeData { response, .. } => {
+                let _ = response.send(Ok(NodeData(Vec::new())));
+            }
+            Self::GetReceipts { response, .. } => {
+                let _ = response.send(Ok(Receipts(Vec::new())));
+            }
+            Self::GetReceipts69 { response, .. } => {
+                let _ = response.send(Ok(Receipts69(Vec::new())));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::peers::{PeersConfig, PeersManager};
+    use futures::task::noop_waker_ref;
+    use reth_interfaces::test_utils::generators::{self, random_block};
+    use reth_primitives::B256;
+    use reth_provider::test_utils::MockEthProvider;
+    use std::net::SocketAddr;
+    use tokio::sync::mpsc;
+
+    /// Returns a client with blocks of 10 transactions and the size of one of their bodies.
+    fn client_with_blocks(count: u64) -> (MockEthProvider, Vec<B256>, usize) {
+        let mut rng = generators::rng();
+        let client = MockEthProvider::default();
+        let mut hashes = Vec::new();
+        let mut body_size = 0;
+        for number in 0..count {
+            let block = random_block(&mut rng, number, None, Some(10), Some(0));
+            hashes.push(block.hash());
+            let block = block.unseal();
+            body_size = BlockBody {
+                transactions: block.body.clone(),
+                ommers: block.ommers.clone(),
+                withdrawals: block.withdrawals.clone(),
+            }
+            .length();
+            client.add_block(hashes[number as usize], block);
+        }
+        (client, hashes, body_size)
+    }
+
+    fn handler(
+        client: MockEthProvider,
+        peers: &PeersManager,
+        config: EthRequestsConfig,
+    ) -> EthRequestHandler<MockEthProvider> {
+        let (_, incoming) = mpsc::channel(1);
+        EthRequestHandler::with_config(client, peers.handle(), incoming, config)
+    }
+
+    fn bodies_request(
+        handler: &mut EthRequestHandler<MockEthProvider>,
+        peer_id: PeerId,
+        hashes: &[B256],
+    ) -> oneshot::Receiver<RequestResult<BlockBodies>> {
+        let (response, rx) = oneshot::channel();
+        handler.on_incoming_request(IncomingEthRequest::GetBlockBodies {
+            peer_id,
+            request: GetBlockBodies(hashes.to_vec()),
+            response,
+        });
+        rx
+    }
+
+    /// Returns the number of bodies of the response, or [None] if the request wasn't answered.
+    fn response_len(rx: &mut oneshot::Receiver<RequestResult<BlockBodies>>) -> Option<usize> {
+        rx.try_recv().ok().map(|response| response.unwrap().0.len())
+    }
+
+    #[tokio::test]
+    async fn queue_and_drop_requests() {
+        let (client, hashes, _) = client_with_blocks(2);
+        let peers = PeersManager::new(PeersConfig::default());
+        let config = EthRequestsConfig {
+            max_requests_per_peer: 2,
+            max_queued_requests: 3,
+            ..Default::default()
+        };
+        let mut handler = handler(client, &peers, config);
+        let (peer_a, peer_b) = (PeerId::random(), PeerId::random());
+
+        // the third request of a peer is answered with an empty response
+        let mut queued = vec![
+            bodies_request(&mut handler, peer_a, &hashes),
+            bodies_request(&mut handler, peer_a, &hashes),
+        ];
+        let mut dropped = vec![bodies_request(&mut handler, peer_a, &hashes)];
+
+        // the fourth request of all peers is answered with an empty response
+        queued.push(bodies_request(&mut handler, peer_b, &hashes));
+        dropped.push(bodies_request(&mut handler, peer_b, &hashes));
+
+        assert_eq!(handler.queued_requests, 3);
+        for rx in &mut queued {
+            assert_eq!(response_len(rx), None);
+        }
+        for rx in &mut dropped {
+            assert_eq!(response_len(rx), Some(0));
+        }
+
+        assert!(!handler.serve_queued_requests());
+        assert_eq!(handler.queued_requests, 0);
+        for rx in &mut queued {
+            assert_eq!(response_len(rx), Some(2));
+        }
+    }
+
+    #[tokio::test]
+    async fn partial_responses_within_peer_budget() {
+        let (client, hashes, body_size) = client_with_blocks(4);
+        let peers = PeersManager::new(PeersConfig::default());
+        let config = EthRequestsConfig {
+            max_bytes_per_peer_per_second: body_size * 5 / 2,
+            ..Default::default()
+        };
+        let mut handler = handler(client, &peers, config);
+        let peer_id = PeerId::random();
+
+        let mut first = bodies_request(&mut handler, peer_id, &hashes);
+        let mut second = bodies_request(&mut handler, peer_id, &hashes);
+        handler.serve_queued_requests();
+
+        // the response stops at the first body that exceeds the budget, and the next request
+        // waits for the budget to be replenished
+        assert_eq!(response_len(&mut first), Some(3));
+        assert_eq!(response_len(&mut second), None);
+        assert_eq!(handler.queued_requests, 1);
+        assert!(handler.next_budget_refill().unwrap() > Instant::now());
+    }
+
+    #[tokio::test]
+    async fn lower_reputation_of_throttled_peer() {
+        let (client, hashes, _) = client_with_blocks(1);
+        let mut peers = PeersManager::new(PeersConfig::default());
+        let peer_id = PeerId::random();
+        peers.add_peer(peer_id, SocketAddr::from(([127, 0, 0, 1], 30303)), None);
+        let config = EthRequestsConfig {
+            max_bytes_per_peer_per_second: 1,
+            max_throttled_requests_per_peer: 2,
+            ..Default::default()
+        };
+        let mut handler = handler(client, &peers, config);
+
+        // the first request exhausts the budget of the peer
+        let mut served = bodies_request(&mut handler, peer_id, &hashes);
+        handler.serve_queued_requests();
+        assert_eq!(response_len(&mut served), Some(1));
+
+        let mut cx = Context::from_waker(noop_waker_ref());
+        let _throttled = bodies_request(&mut handler, peer_id, &hashes);
+        while peers.poll(&mut cx).is_ready() {}
+        assert_eq!(peers.get_reputation(&peer_id), Some(0));
+
+        let _throttled = bodies_request(&mut handler, peer_id, &hashes);
+        while peers.poll(&mut cx).is_ready() {}
+        assert_eq!(peers.get_reputation(&peer_id), Some(THROTTLED_REQUESTS_REPUTATION_CHANGE));
+    }
+}
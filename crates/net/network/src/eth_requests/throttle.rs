@@ -0,0 +1,164 @@
+//! Budgets of the data that's read to serve the `eth` requests of peers.
+
+use super::IncomingEthRequest;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Default maximum number of requests of a peer that are queued or served at once.
+pub const DEFAULT_MAX_REQUESTS_PER_PEER: usize = 8;
+
+/// Default maximum number of bytes that are served to a peer per second.
+pub const DEFAULT_MAX_BYTES_PER_PEER_PER_SECOND: usize = 16 * 1024 * 1024;
+
+/// Default maximum number of bytes that are read per second to serve the requests of all peers.
+pub const DEFAULT_MAX_BYTES_PER_SECOND: usize = 128 * 1024 * 1024;
+
+/// Default maximum number of requests of all peers that are queued at once.
+pub const DEFAULT_MAX_QUEUED_REQUESTS: usize = 256;
+
+/// Default number of requests of a peer that hit a limit, after which the reputation of the peer
+/// is lowered.
+pub const DEFAULT_MAX_THROTTLED_REQUESTS_PER_PEER: usize = 32;
+
+/// Configuration of the budgets of the served `eth` requests.
+///
+/// Requests of a peer that exceed its byte budget are queued until the budget is replenished, and
+/// are then answered with partial responses that fit into the budget. Requests beyond the
+/// queue limits are answered with empty responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct EthRequestsConfig {
+    /// Maximum number of requests of a peer that are queued or served at once.
+    pub max_requests_per_peer: usize,
+    /// Maximum number of bytes that are served to a peer per second.
+    pub max_bytes_per_peer_per_second: usize,
+    /// Maximum number of bytes that are read per second to serve the requests of all peers.
+    ///
+    /// The budget only accounts the reads of the served requests, not the other reads of the
+    /// node.
+    pub max_bytes_per_second: usize,
+    /// Maximum number of requests of all peers that are queued at once.
+    pub max_queued_requests: usize,
+    /// Number of requests of a peer that hit a limit, after which the reputation of the peer is
+    /// lowered.
+    ///
+    /// The count is reset when the peer stops exhausting its budget.
+    pub max_throttled_requests_per_peer: usize,
+}
+
+impl Default for EthRequestsConfig {
+    fn default() -> Self {
+        Self {
+            max_requests_per_peer: DEFAULT_MAX_REQUESTS_PER_PEER,
+            max_bytes_per_peer_per_second: DEFAULT_MAX_BYTES_PER_PEER_PER_SECOND,
+            max_bytes_per_second: DEFAULT_MAX_BYTES_PER_SECOND,
+            max_queued_requests: DEFAULT_MAX_QUEUED_REQUESTS,
+            max_throttled_requests_per_peer: DEFAULT_MAX_THROTTLED_REQUESTS_PER_PEER,
+        }
+    }
+}
+
+/// A budget of bytes that's replenished over time, up to the bytes of one second.
+#[derive(Debug)]
+pub(crate) struct ByteBudget {
+    bytes_per_second: usize,
+    /// The bytes that can be read, negative if more bytes were read than available.
+    available: i64,
+    last_refill: Instant,
+}
+
+impl ByteBudget {
+    pub(crate) fn new(bytes_per_second: usize) -> Self {
+        let bytes_per_second = bytes_per_second.max(1);
+        Self { bytes_per_second, available: bytes_per_second as i64, last_refill: Instant::now() }
+    }
+
+    /// Replenishes the budget for the time that passed since the last refill.
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refill = (elapsed.as_secs_f64() * self.bytes_per_second as f64) as i64;
+        if refill > 0 || self.is_full() {
+            self.available = (self.available + refill).min(self.bytes_per_second as i64);
+            self.last_refill = now;
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.available >= self.bytes_per_second as i64
+    }
+
+    /// Returns the number of bytes that can currently be read.
+    pub(crate) fn available(&mut self, now: Instant) -> usize {
+        self.refill(now);
+        self.available.max(0) as usize
+    }
+
+    /// Returns true if the budget is fully replenished.
+    pub(crate) fn is_replenished(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.is_full()
+    }
+
+    /// Accounts the given number of read bytes, which may exceed the available bytes.
+    pub(crate) fn consume(&mut self, bytes: usize) {
+        self.available = self.available.saturating_sub(bytes as i64);
+    }
+
+    /// Returns when bytes can be read again.
+    pub(crate) fn available_at(&self) -> Instant {
+        if self.available > 0 {
+            return self.last_refill
+        }
+        let missing = (1 - self.available) as f64;
+        self.last_refill + Duration::from_secs_f64(missing / self.bytes_per_second as f64)
+    }
+}
+
+/// The queued requests and the budget of a peer.
+///
+/// Peers are forgotten once they have no queued requests and their budget is replenished.
+#[derive(Debug)]
+pub(crate) struct PeerRequests {
+    /// The queued requests of the peer, in the order they were received.
+    pub(crate) queued: VecDeque<IncomingEthRequest>,
+    /// The bytes that can be served to the peer.
+    pub(crate) budget: ByteBudget,
+    /// The number of requests that hit a limit since the peer was last penalized.
+    pub(crate) throttled: usize,
+}
+
+impl PeerRequests {
+    pub(crate) fn new(config: &EthRequestsConfig) -> Self {
+        Self {
+            queued: VecDeque::new(),
+            budget: ByteBudget::new(config.max_bytes_per_peer_per_second),
+            throttled: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_budget_refills_over_time() {
+        let mut budget = ByteBudget::new(1024);
+        let start = budget.last_refill;
+        assert_eq!(budget.available(start), 1024);
+
+        budget.consume(1535);
+        assert_eq!(budget.available(start), 0);
+        assert_eq!(budget.available_at(), start + Duration::from_millis(500));
+
+        assert_eq!(budget.available(start + Duration::from_millis(750)), 257);
+        assert!(!budget.is_replenished(start + Duration::from_millis(750)));
+
+        // the budget is capped at the bytes of one second
+        assert_eq!(budget.available(start + Duration::from_secs(10)), 1024);
+        assert!(budget.is_replenished(start + Duration::from_secs(10)));
+    }
+}
@@ -37,13 +37,14 @@ use crate::{
 };
 use futures::{pin_mut, Future, StreamExt};
 use parking_lot::Mutex;
+use reth_discv4::EnrForkIdEntry;
 use reth_eth_wire::{
     capability::{Capabilities, CapabilityMessage},
     DisconnectReason, EthVersion, Status,
 };
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_net_common::bandwidth_meter::BandwidthMeter;
-use reth_network_api::ReputationChangeKind;
+use reth_network_api::{KnownPeer, ReputationChangeKind};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use reth_provider::{BlockNumReader, BlockReader};
 use reth_rpc_types::{admin::EthProtocolInfo, NetworkStatus};
@@ -194,6 +195,7 @@ where
             dns_discovery_config,
             extra_protocols,
             tx_gossip_disabled,
+            fork_id_filter_disabled,
             transactions_manager_config: _,
         } = config;
 
@@ -208,7 +210,9 @@ where
         discovery_v4_config = discovery_v4_config.map(|mut disc_config| {
             // merge configured boot nodes
             disc_config.bootstrap_nodes.extend(boot_nodes.clone());
-            disc_config.add_eip868_pair("eth", status.forkid);
+            disc_config.add_eip868_pair("eth", EnrForkIdEntry::from(status.forkid));
+            // learn the fork id of discovered nodes before they're dialed
+            disc_config.wait_for_enr |= !fork_id_filter_disabled;
             disc_config
         });
 
@@ -241,7 +245,7 @@ where
         let state =
             NetworkState::new(client, discovery, peers_manager, Arc::clone(&num_active_peers));
 
-        let swarm = Swarm::new(incoming, sessions, state);
+        let swarm = Swarm::new(incoming, sessions, state, !fork_id_filter_disabled);
 
         let (to_manager_tx, from_handle_rx) = mpsc::unbounded_channel();
 
@@ -333,6 +337,12 @@ where
         self.swarm.state().peers().iter_peers()
     }
 
+    /// Returns the [`KnownPeer`] info of all peers in the peer set that should be persisted across
+    /// restarts.
+    pub fn known_peers(&self) -> Vec<KnownPeer> {
+        self.swarm.state().peers().known_peers()
+    }
+
     /// Returns a new [`PeersHandle`] that can be cloned and shared.
     ///
     /// The [`PeersHandle`] can be used to interact with the network's peer set.
@@ -432,6 +442,13 @@ where
                     response,
                 })
             }
+            PeerRequest::GetReceipts69 { request, response } => {
+                self.delegate_eth_request(IncomingEthRequest::GetReceipts69 {
+                    peer_id,
+                    request,
+                    response,
+                })
+            }
             PeerRequest::GetPooledTransactions { request, response } => {
                 self.notify_tx_manager(NetworkTransactionEvent::GetPooledTransactions {
                     peer_id,
@@ -574,6 +591,9 @@ where
                 // connection.
                 self.swarm.on_network_state_change(net_state);
             }
+            NetworkHandleMessage::SetPeerLimits { max_inbound, max_outbound } => {
+                self.swarm.state_mut().peers_mut().set_connection_limits(max_inbound, max_outbound);
+            }
 
             NetworkHandleMessage::Shutdown(tx) => {
                 // Set connection status to `Shutdown`. Stops node to accept
@@ -616,6 +636,15 @@ where
                 let peers = self.swarm.state().peers().peers_by_kind(kind);
                 let _ = tx.send(self.swarm.sessions().get_peer_infos_by_ids(peers));
             }
+            NetworkHandleMessage::GetKnownPeers(tx) => {
+                let _ = tx.send(self.known_peers());
+            }
+            NetworkHandleMessage::GetTrustedPeers(tx) => {
+                let _ = tx.send(self.swarm.state().peers().trusted_peers());
+            }
+            NetworkHandleMessage::GetPeerStats(reset, tx) => {
+                let _ = tx.send(self.swarm.sessions().get_peer_stats(reset));
+            }
             NetworkHandleMessage::AddRlpxSubProtocol(proto) => self.add_rlpx_sub_protocol(proto),
             NetworkHandleMessage::GetTransactionsHandle(tx) => {
                 if let Some(ref tx_inner) = self.to_transactions_manager {
@@ -687,6 +716,12 @@ where
                     self.swarm.state_mut().peers_mut().on_active_outgoing_established(peer_id);
                 }
 
+                self.swarm.state_mut().peers_mut().on_session_info(
+                    &peer_id,
+                    client_version.clone(),
+                    &capabilities,
+                );
+
                 self.update_active_connection_metrics();
 
                 self.event_listeners.notify(NetworkEvent::SessionEstablished {
@@ -7,8 +7,8 @@ use futures::FutureExt;
 use reth_eth_wire::{
     capability::RawCapabilityMessage, message::RequestPair, BlockBodies, BlockHeaders, EthMessage,
     GetBlockBodies, GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts, NewBlock,
-    NewBlockHashes, NewPooledTransactionHashes, NodeData, PooledTransactions, Receipts,
-    SharedTransactions, Transactions,
+    NewBlockHashes, NewPooledTransactionHashes, NodeData, PooledTransactions, Receipt69, Receipts,
+    Receipts69, SharedTransactions, Transactions,
 };
 use reth_interfaces::p2p::error::{RequestError, RequestResult};
 use reth_primitives::{
@@ -121,6 +121,15 @@ pub enum PeerRequest {
         /// The channel to send the response for receipts.
         response: oneshot::Sender<RequestResult<Receipts>>,
     },
+    /// Requests receipts from a peer that negotiated eth/69, without their bloom.
+    ///
+    /// The response should be sent through the channel.
+    GetReceipts69 {
+        /// The request for receipts.
+        request: GetReceipts,
+        /// The channel to send the response for receipts.
+        response: oneshot::Sender<RequestResult<Receipts69>>,
+    },
 }
 
 // === impl PeerRequest ===
@@ -139,6 +148,7 @@ impl PeerRequest {
             PeerRequest::GetPooledTransactions { response, .. } => response.send(Err(err)).ok(),
             PeerRequest::GetNodeData { response, .. } => response.send(Err(err)).ok(),
             PeerRequest::GetReceipts { response, .. } => response.send(Err(err)).ok(),
+            PeerRequest::GetReceipts69 { response, .. } => response.send(Err(err)).ok(),
         };
     }
 
@@ -160,7 +170,8 @@ impl PeerRequest {
             PeerRequest::GetNodeData { request, .. } => {
                 EthMessage::GetNodeData(RequestPair { request_id, message: request.clone() })
             }
-            PeerRequest::GetReceipts { request, .. } => {
+            PeerRequest::GetReceipts { request, .. } |
+            PeerRequest::GetReceipts69 { request, .. } => {
                 EthMessage::GetReceipts(RequestPair { request_id, message: request.clone() })
             }
         }
@@ -203,6 +214,11 @@ pub enum PeerResponse {
         /// The receiver channel for the response to a receipts request.
         response: oneshot::Receiver<RequestResult<Receipts>>,
     },
+    /// Represents a response to a request for receipts of a peer that negotiated eth/69.
+    Receipts69 {
+        /// The receiver channel for the response to a receipts request.
+        response: oneshot::Receiver<RequestResult<Receipts69>>,
+    },
 }
 
 // === impl PeerResponse ===
@@ -235,6 +251,9 @@ impl PeerResponse {
             PeerResponse::Receipts { response } => {
                 poll_request!(response, Receipts, cx)
             }
+            PeerResponse::Receipts69 { response } => {
+                poll_request!(response, Receipts69, cx)
+            }
         };
         Poll::Ready(res)
     }
@@ -253,6 +272,8 @@ pub enum PeerResponseResult {
     NodeData(RequestResult<Vec<Bytes>>),
     /// Represents a result containing receipts or an error.
     Receipts(RequestResult<Vec<Vec<ReceiptWithBloom>>>),
+    /// Represents a result containing eth/69 receipts or an error.
+    Receipts69(RequestResult<Vec<Vec<Receipt69>>>),
 }
 
 // === impl PeerResponseResult ===
@@ -287,6 +308,9 @@ impl PeerResponseResult {
             PeerResponseResult::Receipts(resp) => {
                 to_message!(resp, Receipts, id)
             }
+            PeerResponseResult::Receipts69(resp) => {
+                to_message!(resp, Receipts69, id)
+            }
         }
     }
 
@@ -298,6 +322,7 @@ impl PeerResponseResult {
             PeerResponseResult::PooledTransactions(res) => res.as_ref().err(),
             PeerResponseResult::NodeData(res) => res.as_ref().err(),
             PeerResponseResult::Receipts(res) => res.as_ref().err(),
+            PeerResponseResult::Receipts69(res) => res.as_ref().err(),
         }
     }
 
@@ -85,6 +85,44 @@ pub struct SessionManagerMetrics {
     pub(crate) total_dial_successes: Counter,
 }
 
+/// Metrics for the filtering of discovered peers by the `eth` fork id of their ENR
+#[derive(Metrics)]
+#[metrics(scope = "network")]
+pub struct ForkIdFilterMetrics {
+    /// Number of discovered peers that were not added because of an incompatible fork id
+    pub(crate) discovered_peers_fork_id_filtered: Counter,
+    /// Number of known peers that were removed because their ENR announced an incompatible fork
+    /// id
+    pub(crate) enr_fork_id_peers_removed: Counter,
+}
+
+/// Metrics for the limit of connected peers per subnet, see
+/// [`PeersConfig::max_peers_per_subnet`](crate::PeersConfig::max_peers_per_subnet)
+#[derive(Metrics)]
+#[metrics(scope = "network")]
+pub struct SubnetLimitMetrics {
+    /// Number of incoming connections that were rejected because their subnet reached the limit
+    pub(crate) subnet_limit_rejected_incoming: Counter,
+    /// Number of times an unconnected peer was skipped for an outgoing connection because its
+    /// subnet reached the limit
+    pub(crate) subnet_limit_skipped_outgoing: Counter,
+}
+
+/// Metrics for the `eth` messages of a single type exchanged with all peers, labeled by the
+/// message type
+#[derive(Metrics)]
+#[metrics(scope = "network.messages")]
+pub struct EthMessageMetrics {
+    /// Number of messages received from peers
+    pub(crate) messages_received: Counter,
+    /// Number of bytes received from peers, before compression
+    pub(crate) bytes_received: Counter,
+    /// Number of messages sent to peers
+    pub(crate) messages_sent: Counter,
+    /// Number of bytes sent to peers, before compression
+    pub(crate) bytes_sent: Counter,
+}
+
 /// Metrics for the [`TransactionsManager`](crate::transactions::TransactionsManager).
 #[derive(Metrics)]
 #[metrics(scope = "network")]
@@ -169,6 +207,12 @@ pub struct TransactionsManagerMetrics {
     ///
     /// Duration in seconds.
     pub(crate) acc_duration_poll_imported_transactions: Gauge,
+    /// Accumulated time spent streaming blob transactions with missing sidecars and buffering
+    /// them for fetching, in one call to poll the
+    /// [`TransactionsManager`](crate::transactions::TransactionsManager) future.
+    ///
+    /// Duration in seconds.
+    pub(crate) acc_duration_poll_missing_blob_sidecars: Gauge,
     /// Accumulated time spent assembling and sending requests for hashes fetching pending, in
     /// one call to poll the [`TransactionsManager`](crate::transactions::TransactionsManager)
     /// future.
@@ -315,6 +359,21 @@ pub struct EthRequestHandlerMetrics {
 
     /// Number of received bodies requests
     pub(crate) received_bodies_requests: Counter,
+
+    /// Number of requests that exceeded the byte budget of their peer and were queued
+    pub(crate) throttled_requests: Counter,
+
+    /// Number of requests that exceeded the queue limits and were answered with an empty response
+    pub(crate) dropped_requests: Counter,
+
+    /// Number of currently queued requests
+    pub(crate) queued_requests: Gauge,
+
+    /// Number of peers whose reputation was lowered for repeatedly hitting the limits
+    pub(crate) penalized_peers: Counter,
+
+    /// Number of peers with queued requests
+    pub(crate) queueing_peers: Gauge,
 }
 
 /// Eth67 announcement metrics, track entries by TxType
@@ -10,14 +10,14 @@ use reth_eth_wire::{DisconnectReason, NewBlock, NewPooledTransactionHashes, Shar
 use reth_interfaces::sync::{NetworkSyncUpdater, SyncState, SyncStateProvider};
 use reth_net_common::bandwidth_meter::BandwidthMeter;
 use reth_network_api::{
-    NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
+    KnownPeer, NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
     ReputationChangeKind,
 };
 use reth_primitives::{Head, NodeRecord, PeerId, TransactionSigned, B256};
-use reth_rpc_types::NetworkStatus;
+use reth_rpc_types::{NetworkStatus, PeerStats, TrustedPeerInfo};
 use secp256k1::SecretKey;
 use std::{
-    net::SocketAddr,
+    net::{IpAddr, SocketAddr},
     sync::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
@@ -176,11 +176,41 @@ impl NetworkHandle {
         self.set_network_conn(NetworkConnectionState::Hibernate);
     }
 
+    /// Sets the maximum number of inbound and outbound connections.
+    ///
+    /// If the new limits are lower than the current number of connections, no peers are
+    /// disconnected, but no new connections are established until the number drops below the
+    /// limits.
+    pub fn set_peer_limits(&self, max_inbound: usize, max_outbound: usize) {
+        self.send_message(NetworkHandleMessage::SetPeerLimits { max_inbound, max_outbound });
+    }
+
     /// Set network connection state.
     fn set_network_conn(&self, network_conn: NetworkConnectionState) {
         self.send_message(NetworkHandleMessage::SetNetworkState(network_conn));
     }
 
+    /// Sets the external IP that's announced in discovery, e.g. the IP of the gateway the ports
+    /// are mapped on.
+    ///
+    /// This has no effect if discv4 is disabled.
+    pub fn set_external_ip_addr(&self, external_ip: IpAddr) {
+        if let Some(discv4) = &self.inner.discv4 {
+            discv4.set_external_ip_addr(external_ip);
+        }
+    }
+
+    /// Sets the RLPx and discovery ports that are announced in discovery, e.g. the external ports
+    /// of the mappings on the gateway.
+    ///
+    /// This has no effect if discv4 is disabled.
+    pub fn set_external_ports(&self, tcp_port: u16, udp_port: u16) {
+        if let Some(discv4) = &self.inner.discv4 {
+            discv4.set_tcp_port(tcp_port);
+            discv4.set_udp_port(udp_port);
+        }
+    }
+
     /// Whether tx gossip is disabled
     pub fn tx_gossip_disabled(&self) -> bool {
         self.inner.tx_gossip_disabled
@@ -289,6 +319,24 @@ impl Peers for NetworkHandle {
         Ok(rx.await?)
     }
 
+    async fn get_known_peers(&self) -> Result<Vec<KnownPeer>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.manager().send(NetworkHandleMessage::GetKnownPeers(tx));
+        Ok(rx.await?)
+    }
+
+    async fn get_trusted_peers(&self) -> Result<Vec<TrustedPeerInfo>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.manager().send(NetworkHandleMessage::GetTrustedPeers(tx));
+        Ok(rx.await?)
+    }
+
+    async fn get_peer_stats(&self, reset: bool) -> Result<Vec<PeerStats>, NetworkError> {
+        let (tx, rx) = oneshot::channel();
+        let _ = self.manager().send(NetworkHandleMessage::GetPeerStats(reset, tx));
+        Ok(rx.await?)
+    }
+
     /// Sends a message to the [`NetworkManager`](crate::NetworkManager) to remove a peer from the
     /// set corresponding to given kind.
     fn remove_peer(&self, peer: PeerId, kind: PeerKind) {
@@ -473,6 +521,13 @@ pub(crate) enum NetworkHandleMessage {
     GetPeerInfoById(PeerId, oneshot::Sender<Option<PeerInfo>>),
     /// Gets `PeerInfo` for a specific peer kind via a oneshot sender.
     GetPeerInfosByPeerKind(PeerKind, oneshot::Sender<Vec<PeerInfo>>),
+    /// Gets the `KnownPeer` info of all peers in the peer set via a oneshot sender.
+    GetKnownPeers(oneshot::Sender<Vec<KnownPeer>>),
+    /// Gets the `TrustedPeerInfo` of all trusted peers via a oneshot sender.
+    GetTrustedPeers(oneshot::Sender<Vec<TrustedPeerInfo>>),
+    /// Gets the `PeerStats` of all connected peers via a oneshot sender, resetting their counters
+    /// if set.
+    GetPeerStats(bool, oneshot::Sender<Vec<PeerStats>>),
     /// Gets the reputation for a specific peer via a oneshot sender.
     GetReputationById(PeerId, oneshot::Sender<Option<Reputation>>),
     /// Retrieves the `TransactionsHandle` via a oneshot sender.
@@ -481,6 +536,13 @@ pub(crate) enum NetworkHandleMessage {
     Shutdown(oneshot::Sender<()>),
     /// Sets the network state between hibernation and active.
     SetNetworkState(NetworkConnectionState),
+    /// Sets the maximum number of inbound and outbound connections.
+    SetPeerLimits {
+        /// The maximum number of inbound connections.
+        max_inbound: usize,
+        /// The maximum number of outbound connections.
+        max_outbound: usize,
+    },
     /// Adds a new listener for `DiscoveryEvent`.
     DiscoveryListener(UnboundedSender<DiscoveryEvent>),
     /// Adds an additional `RlpxSubProtocol`.
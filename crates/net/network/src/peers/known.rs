@@ -0,0 +1,175 @@
+//! Persistence of the known peers across restarts.
+
+use crate::peers::reputation::DEFAULT_REPUTATION;
+use reth_network_api::KnownPeer;
+use reth_primitives::NodeRecord;
+use std::{
+    cmp::Reverse,
+    collections::HashSet,
+    fs,
+    io::{self, ErrorKind},
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The default maximum number of peers that are persisted.
+pub const DEFAULT_MAX_PERSISTED_PEERS: usize = 1_000;
+
+/// The number of temporary files created by this process, to give each write its own file.
+static TMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// The supported formats of a known peers file.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum KnownPeersFile {
+    /// The known peers with their reputation and info about their last session.
+    Peers(Vec<KnownPeer>),
+    /// The legacy format that only contains the records of the peers.
+    Records(HashSet<NodeRecord>),
+}
+
+/// Reads the known peers from the given file.
+///
+/// Files in the legacy format, that only contain the records of the peers, are supported as well.
+/// Returns an empty list if the file doesn't exist.
+pub fn read_known_peers(path: impl AsRef<Path>) -> io::Result<Vec<KnownPeer>> {
+    let reader = match fs::File::open(path.as_ref()) {
+        Ok(file) => io::BufReader::new(file),
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let peers = match serde_json::from_reader(reader)? {
+        KnownPeersFile::Peers(peers) => peers,
+        KnownPeersFile::Records(records) => {
+            records.into_iter().map(|record| KnownPeer::new(record, DEFAULT_REPUTATION)).collect()
+        }
+    };
+    Ok(peers)
+}
+
+/// Writes the known peers to the given file, keeping at most `max_entries` of the most recently
+/// seen peers.
+///
+/// The peers are written to a temporary file first, so the file isn't corrupted if the node is
+/// killed while writing. Every write uses its own temporary file, so concurrent writes don't
+/// clobber each other.
+///
+/// Note: this does blocking file I/O.
+pub fn write_known_peers(
+    path: impl AsRef<Path>,
+    mut peers: Vec<KnownPeer>,
+    max_entries: usize,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    retain_most_recent(&mut peers, max_entries);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = tmp_path(path);
+    if let Err(err) = fs::write(&tmp_path, serde_json::to_vec_pretty(&peers)?)
+        .and_then(|_| fs::rename(&tmp_path, path))
+    {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err)
+    }
+    Ok(())
+}
+
+/// Returns a unique temporary file next to the given file.
+fn tmp_path(path: &Path) -> std::path::PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    let id = TMP_FILES.fetch_add(1, Ordering::Relaxed);
+    file_name.push(format!(".{}.{id}.tmp", std::process::id()));
+    path.with_file_name(file_name)
+}
+
+/// Keeps the `max_entries` most recently seen peers, peers that were never seen are evicted
+/// first.
+fn retain_most_recent(peers: &mut Vec<KnownPeer>, max_entries: usize) {
+    if peers.len() > max_entries {
+        peers.sort_by_key(|peer| Reverse(peer.last_seen));
+        peers.truncate(max_entries);
+    }
+}
+
+/// Converts the given time to a unix timestamp in seconds.
+pub(crate) fn unix_timestamp(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// Converts the given unix timestamp in seconds to a [`SystemTime`].
+pub(crate) fn from_unix_timestamp(timestamp: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::PeerId;
+    use std::net::SocketAddr;
+
+    fn known_peer(last_seen: Option<u64>) -> KnownPeer {
+        let record = NodeRecord::new(SocketAddr::from(([127, 0, 0, 1], 30303)), PeerId::random());
+        KnownPeer { last_seen, ..KnownPeer::new(record, 100) }
+    }
+
+    #[test]
+    fn known_peers_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("peers").join("known-peers.json");
+        assert!(read_known_peers(&path).unwrap().is_empty());
+
+        let mut peer = known_peer(Some(1_700_000_000));
+        peer.banned_until = Some(1_700_001_000);
+        peer.client_version = Some("reth/v0.2.0".to_string());
+        write_known_peers(&path, vec![peer.clone()], DEFAULT_MAX_PERSISTED_PEERS).unwrap();
+
+        assert_eq!(read_known_peers(&path).unwrap(), vec![peer]);
+    }
+
+    #[test]
+    fn evicts_least_recently_seen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known-peers.json");
+
+        let never_seen = known_peer(None);
+        let old = known_peer(Some(1));
+        let recent = known_peer(Some(2));
+        write_known_peers(&path, vec![never_seen, recent.clone(), old.clone()], 2).unwrap();
+
+        assert_eq!(read_known_peers(&path).unwrap(), vec![recent, old]);
+    }
+
+    #[test]
+    fn concurrent_writes_use_own_tmp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known-peers.json");
+        assert_ne!(tmp_path(&path), tmp_path(&path));
+
+        let peers = (0..8).map(|_| vec![known_peer(Some(1)); 100]).collect::<Vec<_>>();
+        std::thread::scope(|scope| {
+            for peers in &peers {
+                let path = &path;
+                scope.spawn(move || write_known_peers(path, peers.clone(), 100).unwrap());
+            }
+        });
+
+        // the file contains one of the writes and no temporary files are left
+        assert!(peers.contains(&read_known_peers(&path).unwrap()));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn reads_legacy_records() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known-peers.json");
+
+        let record = known_peer(None).record;
+        fs::write(&path, serde_json::to_vec(&HashSet::from([record])).unwrap()).unwrap();
+
+        let peers = read_known_peers(&path).unwrap();
+        assert_eq!(peers, vec![KnownPeer::new(record, DEFAULT_REPUTATION)]);
+    }
+}
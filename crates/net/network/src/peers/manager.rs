@@ -1,9 +1,13 @@
 use crate::{
     error::{BackoffKind, SessionError},
+    metrics::SubnetLimitMetrics,
     peers::{
+        known::{self, from_unix_timestamp, unix_timestamp},
         reputation::{
-            is_banned_reputation, DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE,
+            is_banned_reputation, BANNED_REPUTATION, DEFAULT_REPUTATION,
+            MAX_TRUSTED_PEER_REPUTATION_CHANGE,
         },
+        subnet::Subnet,
         ReputationChangeWeights, DEFAULT_MAX_COUNT_CONCURRENT_OUTBOUND_DIALS,
         DEFAULT_MAX_COUNT_PEERS_INBOUND, DEFAULT_MAX_COUNT_PEERS_OUTBOUND,
     },
@@ -11,18 +15,24 @@ use crate::{
     swarm::NetworkConnectionState,
 };
 use futures::StreamExt;
-use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
+use reth_eth_wire::{
+    capability::{Capabilities, Capability},
+    errors::EthStreamError,
+    DisconnectReason,
+};
 use reth_net_common::ban_list::BanList;
-use reth_network_api::{PeerKind, ReputationChangeKind};
+use reth_network_api::{KnownPeer, PeerKind, ReputationChangeKind};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
+use reth_rpc_types::{PeerConnectionStatus, TrustedPeerInfo};
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     fmt::Display,
-    io::{self, ErrorKind},
+    io,
     net::{IpAddr, SocketAddr},
     path::Path,
+    sync::Arc,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 use thiserror::Error;
 use tokio::{
@@ -125,6 +135,10 @@ pub struct PeersManager {
     max_backoff_count: u8,
     /// Tracks the connection state of the node
     net_connection_state: NetworkConnectionState,
+    /// The maximum number of connected peers per subnet, trusted peers are exempt.
+    max_peers_per_subnet: Option<usize>,
+    /// Metrics for the subnet limit.
+    subnet_limit_metrics: SubnetLimitMetrics,
 }
 
 impl PeersManager {
@@ -140,7 +154,9 @@ impl PeersManager {
             trusted_nodes,
             trusted_nodes_only,
             basic_nodes,
+            known_peers,
             max_backoff_count,
+            max_peers_per_subnet,
         } = config;
         let (manager_tx, handle_rx) = mpsc::unbounded_channel();
         let now = Instant::now();
@@ -160,6 +176,17 @@ impl PeersManager {
             peers.entry(id).or_insert_with(|| Peer::new(SocketAddr::from((address, tcp_port))));
         }
 
+        let unix_now = unix_timestamp(SystemTime::now());
+        for known_peer in known_peers {
+            // peers that are still banned are skipped, they're not persisted again either
+            if known_peer.is_banned_at(unix_now) {
+                continue
+            }
+            if let Entry::Vacant(entry) = peers.entry(known_peer.record.id) {
+                entry.insert(Peer::from_known_peer(known_peer));
+            }
+        }
+
         Self {
             peers,
             trusted_peer_ids,
@@ -178,6 +205,8 @@ impl PeersManager {
             last_tick: Instant::now(),
             max_backoff_count,
             net_connection_state: NetworkConnectionState::default(),
+            max_peers_per_subnet,
+            subnet_limit_metrics: Default::default(),
         }
     }
 
@@ -197,6 +226,83 @@ impl PeersManager {
         self.peers.iter().map(|(peer_id, v)| NodeRecord::new(v.addr, *peer_id))
     }
 
+    /// Returns the [`KnownPeer`] info of all peers that should be persisted across restarts.
+    ///
+    /// Peers that are removed after their session was closed and peers that are banned
+    /// indefinitely, which are configured via the ban list, are skipped.
+    pub(crate) fn known_peers(&self) -> Vec<KnownPeer> {
+        let now = SystemTime::now();
+        let instant_now = std::time::Instant::now();
+
+        self.peers
+            .iter()
+            .filter(|(_, peer)| !peer.remove_after_disconnect)
+            .filter_map(|(peer_id, peer)| {
+                let banned_until = match self.ban_list.banned_peer_until(peer_id) {
+                    Some(None) => return None,
+                    Some(Some(until)) => {
+                        Some(unix_timestamp(now + until.saturating_duration_since(instant_now)))
+                    }
+                    None => None,
+                };
+                let last_seen =
+                    if matches!(peer.state, PeerConnectionState::In | PeerConnectionState::Out) {
+                        Some(now)
+                    } else {
+                        peer.last_seen
+                    };
+
+                Some(KnownPeer {
+                    record: NodeRecord::new(peer.addr, *peer_id),
+                    reputation: peer.reputation,
+                    last_seen: last_seen.map(unix_timestamp),
+                    banned_until,
+                    client_version: peer.client_version.as_deref().map(String::from),
+                    capabilities: peer.capabilities.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the [`TrustedPeerInfo`] of all trusted peers, including the ones for which we don't
+    /// have an address.
+    pub(crate) fn trusted_peers(&self) -> Vec<TrustedPeerInfo> {
+        self.trusted_peer_ids
+            .iter()
+            .map(|peer_id| {
+                let Some(peer) = self.peers.get(peer_id) else {
+                    return TrustedPeerInfo {
+                        id: *peer_id,
+                        enode: None,
+                        status: PeerConnectionStatus::Disconnected,
+                    }
+                };
+                let status = match peer.state {
+                    PeerConnectionState::In | PeerConnectionState::DisconnectingIn => {
+                        PeerConnectionStatus::Inbound
+                    }
+                    PeerConnectionState::Out | PeerConnectionState::DisconnectingOut => {
+                        PeerConnectionStatus::Outbound
+                    }
+                    PeerConnectionState::PendingOut => PeerConnectionStatus::Dialing,
+                    PeerConnectionState::Idle
+                        if peer.is_backed_off() ||
+                            peer.is_banned() ||
+                            self.ban_list.is_banned_peer(peer_id) =>
+                    {
+                        PeerConnectionStatus::BackedOff
+                    }
+                    PeerConnectionState::Idle => PeerConnectionStatus::Disconnected,
+                };
+                TrustedPeerInfo {
+                    id: *peer_id,
+                    enode: Some(NodeRecord::new(peer.addr, *peer_id)),
+                    status,
+                }
+            })
+            .collect()
+    }
+
     /// Returns an iterator over all peer ids for peers with the given kind
     pub(crate) fn peers_by_kind(&self, kind: PeerKind) -> impl Iterator<Item = PeerId> + '_ {
         self.peers.iter().filter_map(move |(peer_id, peer)| (peer.kind == kind).then_some(*peer_id))
@@ -245,10 +351,43 @@ impl PeersManager {
             return Err(InboundConnectionError::ExceedsCapacity)
         }
 
+        if let Some(max_peers_per_subnet) = self.max_peers_per_subnet {
+            // the peer id is not known yet, so trusted peers are identified by their ip
+            let is_trusted =
+                self.peers.values().any(|peer| peer.is_trusted() && peer.addr.ip() == addr);
+            if !is_trusted && self.num_peers_in_subnet(Subnet::from(addr)) >= max_peers_per_subnet {
+                self.subnet_limit_metrics.subnet_limit_rejected_incoming.increment(1);
+                return Err(InboundConnectionError::ExceedsSubnetLimit)
+            }
+        }
+
         self.connection_info.inc_pending_in();
         Ok(())
     }
 
+    /// Returns the number of connected, non-trusted peers in the given subnet.
+    fn num_peers_in_subnet(&self, subnet: Subnet) -> usize {
+        self.peers
+            .values()
+            .filter(|peer| {
+                !peer.is_trusted() &&
+                    peer.state.is_connected() &&
+                    Subnet::from(peer.addr.ip()) == subnet
+            })
+            .count()
+    }
+
+    /// Returns the number of connected, non-trusted peers per subnet.
+    fn num_peers_per_subnet(&self) -> HashMap<Subnet, usize> {
+        let mut subnets = HashMap::new();
+        for peer in
+            self.peers.values().filter(|peer| !peer.is_trusted() && peer.state.is_connected())
+        {
+            *subnets.entry(Subnet::from(peer.addr.ip())).or_default() += 1;
+        }
+        subnets
+    }
+
     /// Invoked when a previous call to [Self::on_incoming_pending_session] succeeded but it was
     /// rejected.
     pub(crate) fn on_incoming_pending_session_rejected_internally(&mut self) {
@@ -491,6 +630,7 @@ impl PeersManager {
         match self.peers.entry(peer_id) {
             Entry::Occupied(mut entry) => {
                 self.connection_info.decr_state(entry.get().state);
+                entry.get_mut().last_seen = Some(SystemTime::now());
 
                 if entry.get().remove_after_disconnect && !entry.get().is_trusted() {
                     // this peer should be removed from the set
@@ -511,6 +651,21 @@ impl PeersManager {
         self.fill_outbound_slots();
     }
 
+    /// Records the client version and capabilities the peer announced when an active session was
+    /// established.
+    pub(crate) fn on_session_info(
+        &mut self,
+        peer_id: &PeerId,
+        client_version: Arc<str>,
+        capabilities: &Capabilities,
+    ) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.last_seen = Some(SystemTime::now());
+            peer.client_version = Some(client_version);
+            peer.capabilities = capabilities.capabilities().to_vec();
+        }
+    }
+
     /// Called when a _pending_ outbound connection is successful.
     pub(crate) fn on_active_outgoing_established(&mut self, peer_id: PeerId) {
         if let Some(peer) = self.peers.get_mut(&peer_id) {
@@ -530,6 +685,9 @@ impl PeersManager {
         peer_id: &PeerId,
         err: &EthStreamError,
     ) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.last_seen = Some(SystemTime::now());
+        }
         self.on_connection_failure(remote_addr, peer_id, err, ReputationChangeKind::Dropped)
     }
 
@@ -603,7 +761,10 @@ impl PeersManager {
                 } else {
                     // If the error was not a backoff error, we reduce the peer's reputation
                     let reputation_change = self.reputation_weights.change(reputation_change);
-                    peer.reputation = peer.reputation.saturating_add(reputation_change.as_i32());
+                    peer.reputation = peer
+                        .reputation
+                        .saturating_add(reputation_change.as_i32())
+                        .max(peer.min_reputation());
                 };
 
                 self.connection_info.decr_state(peer.state);
@@ -665,9 +826,42 @@ impl PeersManager {
         self.add_peer_kind(peer_id, PeerKind::Basic, addr, fork_id)
     }
 
+    /// Sets the maximum number of inbound and outbound connections.
+    ///
+    /// Existing connections are kept if they exceed the new limits.
+    pub(crate) fn set_connection_limits(&mut self, max_inbound: usize, max_outbound: usize) {
+        self.connection_info.max_inbound = max_inbound;
+        self.connection_info.max_outbound = max_outbound;
+        self.fill_outbound_slots();
+    }
+
     /// Marks the given peer as trusted.
+    ///
+    /// If the peer is already in the set, it's dialed immediately, regardless of the outbound
+    /// capacity.
     pub(crate) fn add_trusted_peer_id(&mut self, peer_id: PeerId) {
         self.trusted_peer_ids.insert(peer_id);
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.kind = PeerKind::Trusted;
+            self.on_trusted_peer_added(peer_id);
+        }
+    }
+
+    /// Lifts a ban or backoff of a newly added trusted peer, so it's dialed immediately.
+    fn on_trusted_peer_added(&mut self, peer_id: PeerId) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.backed_off = false;
+            peer.severe_backoff_counter = 0;
+            if peer.is_banned() {
+                peer.unban();
+            }
+        }
+        self.backed_off_peers.remove(&peer_id);
+        if self.ban_list.is_banned_peer(&peer_id) {
+            self.unban_peer(peer_id);
+        }
+
+        self.fill_outbound_slots();
     }
 
     /// Called for a newly discovered trusted peer.
@@ -688,7 +882,13 @@ impl PeersManager {
         addr: SocketAddr,
         fork_id: Option<ForkId>,
     ) {
-        if self.ban_list.is_banned(&peer_id, &addr.ip()) {
+        if kind.is_trusted() {
+            // only the ip ban is enforced for trusted peers, a ban of the peer id is lifted, see
+            // `on_trusted_peer_added`
+            if self.ban_list.is_banned_ip(&addr.ip()) {
+                return
+            }
+        } else if self.ban_list.is_banned(&peer_id, &addr.ip()) {
             return
         }
 
@@ -717,6 +917,7 @@ impl PeersManager {
 
         if kind.is_trusted() {
             self.trusted_peer_ids.insert(peer_id);
+            self.on_trusted_peer_added(peer_id);
         }
     }
 
@@ -748,16 +949,24 @@ impl PeersManager {
     }
 
     /// Removes the tracked node from the trusted set.
+    ///
+    /// If only trusted peers are allowed, an existing session to the peer is disconnected.
     pub(crate) fn remove_peer_from_trusted_set(&mut self, peer_id: PeerId) {
-        let Entry::Occupied(mut entry) = self.peers.entry(peer_id) else { return };
-        if !entry.get().is_trusted() {
+        self.trusted_peer_ids.remove(&peer_id);
+
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+        if !peer.is_trusted() {
             return
         }
-
-        let peer = entry.get_mut();
         peer.kind = PeerKind::Basic;
 
-        self.trusted_peer_ids.remove(&peer_id);
+        if self.trusted_nodes_only && peer.state.is_connected() {
+            peer.state.disconnect();
+            self.queued_actions.push_back(PeerAction::Disconnect {
+                peer_id,
+                reason: Some(DisconnectReason::DisconnectRequested),
+            });
+        }
     }
 
     /// Returns the idle peer with the highest reputation.
@@ -768,13 +977,30 @@ impl PeersManager {
     /// If `trusted_nodes_only` is enabled, see [PeersConfig], then this will only consider
     /// `trusted` peers.
     ///
+    /// If `max_peers_per_subnet` is set, see [PeersConfig], then peers in subnets that already
+    /// reached the limit according to the given counts are skipped, unless they're `trusted`.
+    ///
     /// Returns `None` if no peer is available.
-    fn best_unconnected(&mut self) -> Option<(PeerId, &mut Peer)> {
+    fn best_unconnected(
+        &mut self,
+        peers_per_subnet: &HashMap<Subnet, usize>,
+    ) -> Option<(PeerId, &mut Peer)> {
+        let max_peers_per_subnet = self.max_peers_per_subnet;
         let mut unconnected = self.peers.iter_mut().filter(|(_, peer)| {
             !peer.is_backed_off() &&
                 !peer.is_banned() &&
                 peer.state.is_unconnected() &&
-                (!self.trusted_nodes_only || peer.is_trusted())
+                (!self.trusted_nodes_only || peer.is_trusted()) &&
+                (peer.is_trusted() ||
+                    max_peers_per_subnet.map_or(true, |max| {
+                        let subnet = Subnet::from(peer.addr.ip());
+                        let has_capacity =
+                            peers_per_subnet.get(&subnet).copied().unwrap_or_default() < max;
+                        if !has_capacity {
+                            self.subnet_limit_metrics.subnet_limit_skipped_outgoing.increment(1);
+                        }
+                        has_capacity
+                    }))
         });
 
         // keep track of the best peer, if there's one
@@ -811,16 +1037,23 @@ impl PeersManager {
             return
         }
 
+        let has_subnet_limit = self.max_peers_per_subnet.is_some();
+        let mut peers_per_subnet =
+            if has_subnet_limit { self.num_peers_per_subnet() } else { HashMap::new() };
+
         // as long as there a slots available fill them with the best peers
         while self.connection_info.has_out_capacity() {
             let action = {
-                let (peer_id, peer) = match self.best_unconnected() {
+                let (peer_id, peer) = match self.best_unconnected(&peers_per_subnet) {
                     Some(peer) => peer,
                     _ => break,
                 };
 
                 trace!(target: "net::peers", ?peer_id, addr=?peer.addr, "schedule outbound connection");
 
+                if has_subnet_limit && !peer.is_trusted() {
+                    *peers_per_subnet.entry(Subnet::from(peer.addr.ip())).or_default() += 1;
+                }
                 peer.state = PeerConnectionState::PendingOut;
                 PeerAction::Connect { peer_id, remote_addr: peer.addr }
             };
@@ -829,6 +1062,25 @@ impl PeersManager {
 
             self.queued_actions.push_back(action);
         }
+
+        // trusted peers are dialed regardless of the outbound capacity, but the concurrent dials
+        // are still limited
+        for (peer_id, peer) in self.peers.iter_mut().filter(|(_, peer)| {
+            peer.is_trusted() &&
+                peer.state.is_unconnected() &&
+                !peer.is_backed_off() &&
+                !peer.is_banned()
+        }) {
+            if !self.connection_info.has_dial_capacity() {
+                break
+            }
+            trace!(target: "net::peers", ?peer_id, addr=?peer.addr, "schedule trusted connection");
+
+            peer.state = PeerConnectionState::PendingOut;
+            self.connection_info.inc_pending_out();
+            self.queued_actions
+                .push_back(PeerAction::Connect { peer_id: *peer_id, remote_addr: peer.addr });
+        }
     }
 
     /// Keeps track of network state changes.
@@ -948,8 +1200,12 @@ pub struct ConnectionInfo {
 impl ConnectionInfo {
     ///  Returns `true` if there's still capacity for a new outgoing connection.
     fn has_out_capacity(&self) -> bool {
-        self.num_pending_out < self.max_concurrent_outbound_dials &&
-            self.num_outbound < self.max_outbound
+        self.has_dial_capacity() && self.num_outbound < self.max_outbound
+    }
+
+    ///  Returns `true` if there's still capacity for a new outgoing dial.
+    fn has_dial_capacity(&self) -> bool {
+        self.num_pending_out < self.max_concurrent_outbound_dials
     }
 
     ///  Returns `true` if there's still capacity for a new incoming connection.
@@ -1032,6 +1288,12 @@ pub struct Peer {
     backed_off: bool,
     /// Counts number of times the peer was backed off due to a severe [BackoffKind].
     severe_backoff_counter: u8,
+    /// When the last active session to the peer was established or closed, if ever.
+    last_seen: Option<SystemTime>,
+    /// The client version the peer announced in its last session, if any.
+    client_version: Option<Arc<str>>,
+    /// The capabilities the peer announced in its last session.
+    capabilities: Vec<Capability>,
 }
 
 // === impl Peer ===
@@ -1060,6 +1322,27 @@ impl Peer {
             kind: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            last_seen: None,
+            client_version: None,
+            capabilities: Vec::new(),
+        }
+    }
+
+    /// Restores a peer that was persisted across restarts.
+    ///
+    /// A banned reputation is reset, since the ban of the peer expired.
+    fn from_known_peer(known_peer: KnownPeer) -> Self {
+        let KnownPeer { record, reputation, last_seen, client_version, capabilities, .. } =
+            known_peer;
+        let reputation =
+            if is_banned_reputation(reputation) { DEFAULT_REPUTATION } else { reputation };
+
+        Self {
+            reputation,
+            last_seen: last_seen.map(from_unix_timestamp),
+            client_version: client_version.map(Into::into),
+            capabilities,
+            ..Self::new(record.tcp_addr())
         }
     }
 
@@ -1079,7 +1362,7 @@ impl Peer {
     fn apply_reputation(&mut self, reputation: i32) -> ReputationChangeOutcome {
         let previous = self.reputation;
         // we add reputation since negative reputation change decrease total reputation
-        self.reputation = previous.saturating_add(reputation);
+        self.reputation = previous.saturating_add(reputation).max(self.min_reputation());
 
         trace!(target: "net::peers", reputation=%self.reputation, banned=%self.is_banned(), "applied reputation change");
 
@@ -1121,6 +1404,18 @@ impl Peer {
     fn is_trusted(&self) -> bool {
         matches!(self.kind, PeerKind::Trusted)
     }
+
+    /// Returns the lowest reputation the peer can have.
+    ///
+    /// Trusted peers are never banned because of their reputation, so they're always redialed.
+    #[inline]
+    fn min_reputation(&self) -> i32 {
+        if self.is_trusted() {
+            BANNED_REPUTATION
+        } else {
+            i32::MIN
+        }
+    }
 }
 
 /// Outcomes when a reputation change is applied to a peer
@@ -1293,6 +1588,11 @@ pub struct PeersConfig {
     /// Basic nodes to connect to.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub basic_nodes: HashSet<NodeRecord>,
+    /// Peers that were persisted by a previous run, see [`KnownPeer`].
+    ///
+    /// Peers that are still banned are skipped.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub known_peers: Vec<KnownPeer>,
     /// How long to ban bad peers.
     #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
     pub ban_duration: Duration,
@@ -1308,6 +1608,13 @@ pub struct PeersConfig {
     ///
     /// The backoff duration increases with number of backoff attempts.
     pub backoff_durations: PeerBackoffDurations,
+    /// The maximum number of connected peers per `/24` IPv4 or `/48` IPv6 subnet, if any.
+    ///
+    /// This applies to dialed and incoming peers, so the node doesn't end up connected to peers
+    /// that are all hosted in the same network.
+    ///
+    /// Note: this does not apply to trusted peers.
+    pub max_peers_per_subnet: Option<usize>,
 }
 
 impl Default for PeersConfig {
@@ -1323,7 +1630,9 @@ impl Default for PeersConfig {
             trusted_nodes: Default::default(),
             trusted_nodes_only: false,
             basic_nodes: Default::default(),
+            known_peers: Default::default(),
             max_backoff_count: 5,
+            max_peers_per_subnet: None,
         }
     }
 }
@@ -1405,6 +1714,12 @@ impl PeersConfig {
         self
     }
 
+    /// Peers that were persisted by a previous run.
+    pub fn with_known_peers(mut self, known_peers: Vec<KnownPeer>) -> Self {
+        self.known_peers = known_peers;
+        self
+    }
+
     /// Configures the max allowed backoff count.
     pub fn with_max_backoff_count(mut self, max_backoff_count: u8) -> Self {
         self.max_backoff_count = max_backoff_count;
@@ -1423,25 +1738,37 @@ impl PeersConfig {
         self
     }
 
+    /// Configures the maximum number of connected peers per subnet, trusted peers are exempt.
+    pub fn with_max_peers_per_subnet(mut self, max_peers_per_subnet: Option<usize>) -> Self {
+        self.max_peers_per_subnet = max_peers_per_subnet;
+        self
+    }
+
     /// Returns the maximum number of peers, inbound and outbound.
     pub const fn max_peers(&self) -> usize {
         self.connection_info.max_outbound + self.connection_info.max_inbound
     }
 
-    /// Read from file nodes available at launch. Ignored if None.
-    pub fn with_basic_nodes_from_file(
+    /// Returns the maximum number of inbound connections.
+    pub const fn max_inbound(&self) -> usize {
+        self.connection_info.max_inbound
+    }
+
+    /// Returns the maximum number of outbound connections.
+    pub const fn max_outbound(&self) -> usize {
+        self.connection_info.max_outbound
+    }
+
+    /// Read from file the peers that were persisted by a previous run, see
+    /// [`read_known_peers`](crate::peers::read_known_peers). Ignored if None.
+    pub fn with_known_peers_from_file(
         self,
         optional_file: Option<impl AsRef<Path>>,
     ) -> Result<Self, io::Error> {
         let Some(file_path) = optional_file else { return Ok(self) };
-        let reader = match std::fs::File::open(file_path.as_ref()) {
-            Ok(file) => io::BufReader::new(file),
-            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(self),
-            Err(e) => Err(e)?,
-        };
         info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved peers");
-        let nodes: HashSet<NodeRecord> = serde_json::from_reader(reader)?;
-        Ok(self.with_basic_nodes(nodes))
+        let known_peers = known::read_known_peers(file_path)?;
+        Ok(self.with_known_peers(known_peers))
     }
 
     /// Returns settings for testing
@@ -1530,6 +1857,8 @@ pub enum InboundConnectionError {
     IpBanned,
     /// No capacity for new inbound connections
     ExceedsCapacity,
+    /// The subnet of the remote's ip address reached the maximum number of connected peers
+    ExceedsSubnetLimit,
 }
 
 impl Display for InboundConnectionError {
@@ -1544,8 +1873,9 @@ mod tests {
     use crate::{
         error::BackoffKind,
         peers::{
+            known::unix_timestamp,
             manager::{ConnectionInfo, PeerBackoffDurations, PeerConnectionState},
-            reputation::DEFAULT_REPUTATION,
+            reputation::{BANNED_REPUTATION, DEFAULT_REPUTATION},
             InboundConnectionError, PeerAction,
         },
         session::PendingSessionHandshakeError,
@@ -1557,16 +1887,17 @@ mod tests {
         DisconnectReason,
     };
     use reth_net_common::ban_list::BanList;
-    use reth_network_api::{Direction, ReputationChangeKind};
+    use reth_network_api::{Direction, KnownPeer, ReputationChangeKind};
     use reth_primitives::{PeerId, B512};
+    use reth_rpc_types::{PeerConnectionStatus, TrustedPeerInfo};
     use std::{
-        collections::HashSet,
+        collections::{HashMap, HashSet},
         future::{poll_fn, Future},
         io,
         net::{IpAddr, Ipv4Addr, SocketAddr},
         pin::Pin,
         task::{Context, Poll},
-        time::Duration,
+        time::{Duration, SystemTime},
     };
 
     struct PeerActionFuture<'a> {
@@ -2230,20 +2561,109 @@ mod tests {
             assert!(!p.is_banned());
         }
 
-        // ensure peer is banned eventually
-        loop {
+        // ensure peer is never banned
+        for _ in 0..100 {
             peers.apply_reputation_change(&peer, ReputationChangeKind::BadMessage);
+        }
 
-            let p = peers.peers.get(&peer).unwrap();
-            if p.is_banned() {
-                break
-            }
+        let p = peers.peers.get(&peer).unwrap();
+        assert_eq!(p.state, PeerConnectionState::Out);
+        assert!(!p.is_banned());
+        assert!(peers.queued_actions.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_trusted_peer_dials_respect_max_concurrent_dials() {
+        let mut peers = PeersManager::new(
+            PeersConfig::test().with_max_outbound(1).with_max_concurrent_dials(2),
+        );
+
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        for _ in 0..4 {
+            peers.add_trusted_peer(PeerId::random(), socket_addr);
         }
 
-        match event!(peers) {
-            PeerAction::Disconnect { peer_id, .. } => {
-                assert_eq!(peer_id, peer);
-            }
+        let dials = peers
+            .queued_actions
+            .drain(..)
+            .filter(|action| matches!(action, PeerAction::Connect { .. }))
+            .count();
+        assert_eq!(dials, 2);
+        assert_eq!(peers.connection_info.num_pending_out, 2);
+    }
+
+    #[tokio::test]
+    async fn test_trusted_peers_bypass_outbound_limit() {
+        let mut peers = PeersManager::new(PeersConfig::test().with_max_outbound(1));
+
+        let basic_peer = PeerId::random();
+        let basic_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        peers.add_peer(basic_peer, basic_sock, None);
+        peers.fill_outbound_slots();
+        peers.on_active_outgoing_established(basic_peer);
+        assert!(!peers.connection_info.has_out_capacity());
+
+        let trusted_peer = PeerId::random();
+        let trusted_sock = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8009);
+        peers.add_trusted_peer(trusted_peer, trusted_sock);
+
+        let actions = peers.queued_actions.drain(..).collect::<Vec<_>>();
+        assert!(actions.iter().any(|action| matches!(
+            action,
+            PeerAction::Connect { peer_id, remote_addr }
+                if *peer_id == trusted_peer && *remote_addr == trusted_sock
+        )));
+        assert_eq!(
+            peers.trusted_peers(),
+            vec![TrustedPeerInfo {
+                id: trusted_peer,
+                enode: Some(NodeRecord::new(trusted_sock, trusted_peer)),
+                status: PeerConnectionStatus::Dialing,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_set_connection_limits() {
+        let mut peers = PeersManager::new(PeersConfig::test().with_max_outbound(0));
+
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        peers.add_peer(peer, socket_addr, None);
+        peers.fill_outbound_slots();
+        assert_eq!(peers.peers.get(&peer).unwrap().state, PeerConnectionState::Idle);
+
+        // raising the limit dials the known peers right away
+        peers.set_connection_limits(0, 1);
+        assert_eq!(peers.connection_info.max_inbound, 0);
+        assert_eq!(peers.peers.get(&peer).unwrap().state, PeerConnectionState::PendingOut);
+        assert!(peers.queued_actions.drain(..).any(|action| matches!(
+            action,
+            PeerAction::Connect { peer_id, .. } if peer_id == peer
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_add_trusted_peer_id_with_trusted_nodes_only() {
+        let mut peers = PeersManager::new(PeersConfig::test().with_trusted_nodes_only(true));
+
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        peers.add_peer(peer, socket_addr, None);
+        peers.fill_outbound_slots();
+        assert_eq!(peers.peers.get(&peer).unwrap().state, PeerConnectionState::Idle);
+
+        // the known peer becomes dialable once it's trusted
+        peers.add_trusted_peer_id(peer);
+        assert_eq!(peers.peers.get(&peer).unwrap().state, PeerConnectionState::PendingOut);
+        peers.on_active_outgoing_established(peer);
+
+        // and is disconnected once it's no longer trusted
+        peers.queued_actions.clear();
+        peers.remove_peer_from_trusted_set(peer);
+        assert!(peers.trusted_peers().is_empty());
+        match peers.queued_actions.pop_front() {
+            Some(PeerAction::Disconnect { peer_id, .. }) => assert_eq!(peer_id, peer),
             _ => unreachable!(),
         }
     }
@@ -2839,4 +3259,115 @@ mod tests {
         // no more pending outbound connections
         assert_eq!(peer_manager.connection_info.num_pending_out, 0);
     }
+    #[tokio::test]
+    async fn test_restore_known_peers() {
+        let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let unix_now = unix_timestamp(SystemTime::now());
+
+        let mut known = KnownPeer::new(NodeRecord::new(addr, PeerId::random()), -100);
+        known.last_seen = Some(unix_now - 60);
+        known.client_version = Some("reth/v0.2.0".to_string());
+        let mut still_banned =
+            KnownPeer::new(NodeRecord::new(addr, PeerId::random()), BANNED_REPUTATION - 1);
+        still_banned.banned_until = Some(unix_now + 60);
+        let mut ban_expired =
+            KnownPeer::new(NodeRecord::new(addr, PeerId::random()), BANNED_REPUTATION - 1);
+        ban_expired.banned_until = Some(unix_now - 60);
+
+        let config = PeersConfig::test().with_known_peers(vec![
+            known.clone(),
+            still_banned.clone(),
+            ban_expired.clone(),
+        ]);
+        let mut peers = PeersManager::new(config);
+        assert_eq!(peers.num_known_peers(), 2);
+        assert!(!peers.peers.contains_key(&still_banned.record.id));
+        assert_eq!(peers.get_reputation(&known.record.id), Some(-100));
+        assert_eq!(peers.get_reputation(&ban_expired.record.id), Some(DEFAULT_REPUTATION));
+
+        // the banned peer is persisted with the expiry of its ban
+        peers.ban_peer(ban_expired.record.id);
+        let mut persisted = peers.known_peers();
+        persisted.sort_by_key(|peer| peer.last_seen);
+        assert_eq!(persisted.len(), 2);
+        assert_eq!(persisted[0].record.id, ban_expired.record.id);
+        assert!(persisted[0].is_banned_at(unix_now));
+        assert_eq!(persisted[1], known);
+    }
+
+    /// Returns the number of dials per subnet, keyed by the third octet of `10.0.x.y`.
+    fn dials_per_subnet(peers: &mut PeersManager) -> HashMap<u8, usize> {
+        let mut dials = HashMap::new();
+        for action in peers.queued_actions.drain(..) {
+            if let PeerAction::Connect { remote_addr: SocketAddr::V4(addr), .. } = action {
+                *dials.entry(addr.ip().octets()[2]).or_default() += 1;
+            }
+        }
+        dials
+    }
+
+    #[tokio::test]
+    async fn test_max_peers_per_subnet_dials() {
+        let config = PeersConfig::test().with_max_peers_per_subnet(Some(2));
+        let mut peers = PeersManager::new(config);
+
+        // most peers are hosted in the same subnet, diverse peers are scarce
+        for host in 1..=10 {
+            peers.add_peer(PeerId::random(), SocketAddr::from(([10, 0, 1, host], 30303)), None);
+        }
+        peers.add_peer(PeerId::random(), SocketAddr::from(([10, 0, 2, 1], 30303)), None);
+        peers.add_peer(PeerId::random(), SocketAddr::from(([10, 0, 3, 1], 30303)), None);
+        let trusted_peer = PeerId::random();
+        peers.add_trusted_peer(trusted_peer, SocketAddr::from(([10, 0, 1, 100], 30303)));
+
+        peers.fill_outbound_slots();
+        let dials = dials_per_subnet(&mut peers);
+        // the crowded subnet is capped, the trusted peer is exempt
+        assert_eq!(dials, HashMap::from([(1, 3), (2, 1), (3, 1)]));
+        assert_eq!(peers.peers.get(&trusted_peer).unwrap().state, PeerConnectionState::PendingOut);
+
+        // the remaining slots are filled with peers of diverse subnets once they're discovered
+        for subnet in 4..=6 {
+            peers.add_peer(PeerId::random(), SocketAddr::from(([10, 0, subnet, 1], 30303)), None);
+        }
+        peers.fill_outbound_slots();
+        assert_eq!(dials_per_subnet(&mut peers), HashMap::from([(4, 1), (5, 1), (6, 1)]));
+
+        // a slot in the crowded subnet is released if a session is closed
+        let (&dialed, _) = peers
+            .peers
+            .iter()
+            .find(|(peer_id, peer)| {
+                **peer_id != trusted_peer &&
+                    peer.state == PeerConnectionState::PendingOut &&
+                    peer.addr.ip().to_string().starts_with("10.0.1.")
+            })
+            .unwrap();
+        peers.on_outgoing_pending_session_gracefully_closed(&dialed);
+        peers.fill_outbound_slots();
+        assert_eq!(dials_per_subnet(&mut peers), HashMap::from([(1, 1)]));
+    }
+
+    #[tokio::test]
+    async fn test_max_peers_per_subnet_incoming() {
+        let config = PeersConfig::test().with_max_peers_per_subnet(Some(1));
+        let mut peers = PeersManager::new(config);
+
+        let peer = PeerId::random();
+        let addr = SocketAddr::from(([10, 0, 1, 1], 30303));
+        assert!(peers.on_incoming_pending_session(addr.ip()).is_ok());
+        peers.on_incoming_session_established(peer, addr);
+
+        // another peer of the same subnet is rejected
+        let same_subnet = IpAddr::from([10, 0, 1, 2]);
+        assert_eq!(
+            peers.on_incoming_pending_session(same_subnet),
+            Err(InboundConnectionError::ExceedsSubnetLimit)
+        );
+        assert!(peers.on_incoming_pending_session(IpAddr::from([10, 0, 2, 1])).is_ok());
+
+        // unless it's trusted
+        peers.add_trusted_peer(PeerId::random(), SocketAddr::new(same_subnet, 30303));
+        assert!(peers.on_incoming_pending_session(same_subnet).is_ok());
+    }
 }
@@ -1,11 +1,15 @@
 //! Peer related implementations
 
+mod known;
 mod manager;
 mod reputation;
+mod subnet;
 
+pub use known::{read_known_peers, write_known_peers, DEFAULT_MAX_PERSISTED_PEERS};
 pub(crate) use manager::InboundConnectionError;
 pub use manager::{ConnectionInfo, Peer, PeerAction, PeersConfig, PeersHandle, PeersManager};
 pub use reputation::ReputationChangeWeights;
+pub(crate) use reputation::THROTTLED_REQUESTS_REPUTATION_CHANGE;
 pub use reth_network_api::PeerKind;
 
 /// Maximum number of available slots for outbound sessions.
@@ -37,6 +37,10 @@ const BAD_PROTOCOL_REPUTATION_CHANGE: i32 = i32::MIN;
 // todo: current value is a hint, needs to be set properly
 const BAD_ANNOUNCEMENT_REPUTATION_CHANGE: i32 = REPUTATION_UNIT;
 
+/// The reputation change to apply to a peer whose requests consistently exceed the budgets of the
+/// [`EthRequestHandler`](crate::eth_requests::EthRequestHandler).
+pub(crate) const THROTTLED_REQUESTS_REPUTATION_CHANGE: i32 = 4 * REPUTATION_UNIT;
+
 /// The maximum reputation change that can be applied to a trusted peer.
 /// This is used to prevent a single bad message from a trusted peer to cause a significant change.
 /// This gives a trusted peer more leeway when interacting with the node, which is useful for in
@@ -0,0 +1,54 @@
+//! Grouping of peers by the subnet of their ip address.
+
+use std::net::IpAddr;
+
+/// The subnet of an ip address, a `/24` for IPv4 and a `/48` for IPv6 addresses.
+///
+/// This is used to limit the number of connected peers that are hosted in the same network, see
+/// [`PeersConfig::max_peers_per_subnet`](crate::PeersConfig::max_peers_per_subnet).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Subnet {
+    /// The first three octets of an IPv4 address.
+    V4([u8; 3]),
+    /// The first three segments of an IPv6 address.
+    V6([u16; 3]),
+}
+
+impl From<IpAddr> for Subnet {
+    fn from(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => {
+                let [a, b, c, _] = ip.octets();
+                Subnet::V4([a, b, c])
+            }
+            IpAddr::V6(ip) => {
+                // IPv4-mapped addresses belong to the subnet of the IPv4 address
+                if let Some(ip) = ip.to_ipv4_mapped() {
+                    return IpAddr::V4(ip).into()
+                }
+                let [a, b, c, ..] = ip.segments();
+                Subnet::V6([a, b, c])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn groups_by_prefix() {
+        let subnet = |ip: &str| Subnet::from(ip.parse::<IpAddr>().unwrap());
+
+        assert_eq!(subnet("10.0.1.1"), subnet("10.0.1.255"));
+        assert_ne!(subnet("10.0.1.1"), subnet("10.0.2.1"));
+
+        assert_eq!(subnet("2001:db8:1::1"), subnet("2001:db8:1:ffff::1"));
+        assert_ne!(subnet("2001:db8:1::1"), subnet("2001:db8:2::1"));
+
+        let mapped = IpAddr::V6(Ipv4Addr::new(10, 0, 1, 7).to_ipv6_mapped());
+        assert_eq!(Subnet::from(mapped), subnet("10.0.1.1"));
+    }
+}
@@ -6,9 +6,11 @@ use crate::{
         config::INITIAL_REQUEST_TIMEOUT,
         conn::EthRlpxConnection,
         handle::{ActiveSessionMessage, SessionCommand},
+        stats::SessionStats,
         SessionId,
     },
 };
+use alloy_rlp::Encodable;
 use core::sync::atomic::Ordering;
 use fnv::FnvHashMap;
 use futures::{stream::Fuse, SinkExt, StreamExt};
@@ -16,7 +18,7 @@ use reth_eth_wire::{
     capability::Capabilities,
     errors::{EthHandshakeError, EthStreamError, P2PStreamError},
     message::{EthBroadcastMessage, RequestPair},
-    DisconnectP2P, DisconnectReason, EthMessage,
+    DisconnectP2P, DisconnectReason, EthMessage, EthVersion, Receipts69,
 };
 use reth_interfaces::p2p::error::RequestError;
 use reth_metrics::common::mpsc::MeteredPollSender;
@@ -95,6 +97,8 @@ pub(crate) struct ActiveSession {
     pub(crate) protocol_breach_request_timeout: Duration,
     /// Used to reserve a slot to guarantee that the termination message is delivered
     pub(crate) terminate_message: Option<(PollSender<ActiveSessionMessage>, ActiveSessionMessage)>,
+    /// The counters of the messages exchanged with the peer, shared with the session handle.
+    pub(crate) stats: Arc<SessionStats>,
 }
 
 impl ActiveSession {
@@ -226,14 +230,54 @@ impl ActiveSession {
                 on_response!(resp, GetNodeData)
             }
             EthMessage::GetReceipts(req) => {
-                on_request!(req, Receipts, GetReceipts)
+                if self.conn.version() >= EthVersion::Eth69 {
+                    on_request!(req, Receipts69, GetReceipts69)
+                } else {
+                    on_request!(req, Receipts, GetReceipts)
+                }
             }
             EthMessage::Receipts(resp) => {
                 on_response!(resp, GetReceipts)
             }
+            EthMessage::Receipts69(resp) => self.on_receipts69_response(resp),
         }
     }
 
+    /// Handles eth/69 receipts received from the peer.
+    ///
+    /// If the request expects receipts with their bloom, the bloom is derived from the logs.
+    fn on_receipts69_response(
+        &mut self,
+        resp: RequestPair<Receipts69>,
+    ) -> OnIncomingMessageOutcome {
+        let RequestPair { request_id, message } = resp;
+        let Some(req) = self.inflight_requests.remove(&request_id) else {
+            // we received a response to a request we never sent
+            self.on_bad_message();
+            return OnIncomingMessageOutcome::Ok
+        };
+
+        match req.request {
+            RequestState::Waiting(PeerRequest::GetReceipts69 { response, .. }) => {
+                let _ = response.send(Ok(message));
+                self.update_request_timeout(req.timestamp, Instant::now());
+            }
+            RequestState::Waiting(PeerRequest::GetReceipts { response, .. }) => {
+                let _ = response.send(Ok(message.into()));
+                self.update_request_timeout(req.timestamp, Instant::now());
+            }
+            RequestState::Waiting(request) => {
+                request.send_bad_response();
+            }
+            RequestState::TimedOut => {
+                // request was already timed out internally
+                self.update_request_timeout(req.timestamp, Instant::now());
+            }
+        }
+
+        OnIncomingMessageOutcome::Ok
+    }
+
     /// Handle an internal peer request that will be sent to the remote.
     fn on_internal_peer_request(&mut self, request: PeerRequest, deadline: Instant) {
         let request_id = self.next_id();
@@ -289,6 +333,7 @@ impl ActiveSession {
     fn handle_outgoing_response(&mut self, id: u64, resp: PeerResponseResult) {
         match resp.try_into_message(id) {
             Ok(msg) => {
+                self.stats.on_request_served();
                 self.queued_outgoing.push_back(msg.into());
             }
             Err(err) => {
@@ -428,6 +473,7 @@ impl ActiveSession {
                 if req.is_waiting() {
                     debug!(target: "net::session", ?id, remote_peer_id=?self.remote_peer_id, "timed out outgoing request");
                     req.timeout();
+                    self.stats.on_request_timed_out();
                 } else if now - req.timestamp > self.protocol_breach_request_timeout {
                     return true
                 }
@@ -437,9 +483,10 @@ impl ActiveSession {
         false
     }
 
-    /// Updates the request timeout with a request's timestamps
+    /// Updates the request timeout with a request's timestamps and records the response latency.
     fn update_request_timeout(&mut self, sent: Instant, received: Instant) {
         let elapsed = received.saturating_duration_since(sent);
+        self.stats.on_response(elapsed);
 
         let current = Duration::from_millis(self.internal_request_timeout.load(Ordering::Relaxed));
         let request_timeout = calculate_new_timeout(current, elapsed);
@@ -552,8 +599,14 @@ impl Future for ActiveSession {
                 if let Some(msg) = this.queued_outgoing.pop_front() {
                     progress = true;
                     let res = match msg {
-                        OutgoingMessage::Eth(msg) => this.conn.start_send_unpin(msg),
-                        OutgoingMessage::Broadcast(msg) => this.conn.start_send_broadcast(msg),
+                        OutgoingMessage::Eth(msg) => {
+                            this.stats.on_message_out(msg.message_id(), msg.length());
+                            this.conn.start_send_unpin(msg)
+                        }
+                        OutgoingMessage::Broadcast(msg) => {
+                            this.stats.on_message_out(msg.message_id(), msg.length());
+                            this.conn.start_send_broadcast(msg)
+                        }
                     };
                     if let Err(err) = res {
                         debug!(target: "net::session", %err, remote_peer_id=?this.remote_peer_id, "failed to send message");
@@ -606,6 +659,7 @@ impl Future for ActiveSession {
                         match res {
                             Ok(msg) => {
                                 trace!(target: "net::session", msg_id=?msg.message_id(), remote_peer_id=?this.remote_peer_id, "received eth message");
+                                this.stats.on_message_in(msg.message_id(), msg.length());
                                 // decode and handle message
                                 match this.on_incoming_message(msg) {
                                     OnIncomingMessageOutcome::Ok => {
@@ -895,6 +949,7 @@ mod tests {
                         )),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
                         terminate_message: None,
+                        stats: Default::default(),
                     }
                 }
                 ev => {
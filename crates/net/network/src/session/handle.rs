@@ -2,7 +2,7 @@
 
 use crate::{
     message::PeerMessage,
-    session::{conn::EthRlpxConnection, Direction, SessionId},
+    session::{conn::EthRlpxConnection, stats::SessionStats, Direction, SessionId},
     PendingSessionHandshakeError,
 };
 use reth_ecies::ECIESError;
@@ -13,6 +13,7 @@ use reth_eth_wire::{
 };
 use reth_network_api::PeerInfo;
 use reth_primitives::PeerId;
+use reth_rpc_types::PeerStats;
 use std::{io, net::SocketAddr, sync::Arc, time::Instant};
 use tokio::sync::{
     mpsc::{self, error::SendError},
@@ -75,6 +76,8 @@ pub struct ActiveSessionHandle {
     pub(crate) local_addr: Option<SocketAddr>,
     /// The Status message the peer sent for the `eth` handshake
     pub(crate) status: Arc<Status>,
+    /// The counters of the messages exchanged with the peer, updated by the session.
+    pub(crate) stats: Arc<SessionStats>,
 }
 
 // === impl ActiveSessionHandle ===
@@ -149,6 +152,11 @@ impl ActiveSessionHandle {
             session_established: self.established,
         }
     }
+
+    /// Returns the [PeerStats] of the session, resetting the counters if `reset` is true.
+    pub(crate) fn peer_stats(&self, reset: bool) -> PeerStats {
+        self.stats.peer_stats(self.remote_id, self.client_version.to_string(), reset)
+    }
 }
 
 /// Events a pending session can produce.
@@ -3,7 +3,11 @@
 use crate::{
     message::PeerMessage,
     metrics::SessionManagerMetrics,
-    session::{active::ActiveSession, config::SessionCounter},
+    session::{
+        active::ActiveSession,
+        config::SessionCounter,
+        stats::{MessageMetrics, SessionStats},
+    },
 };
 use fnv::FnvHashMap;
 use futures::{future::Either, io, FutureExt, StreamExt};
@@ -20,6 +24,7 @@ use reth_net_common::{
     stream::HasRemoteAddr,
 };
 use reth_primitives::{ForkFilter, ForkId, ForkTransition, Head, PeerId};
+use reth_rpc_types::PeerStats;
 use reth_tasks::TaskSpawner;
 use secp256k1::SecretKey;
 use std::{
@@ -43,6 +48,7 @@ mod active;
 mod config;
 mod conn;
 mod handle;
+mod stats;
 pub use crate::message::PeerRequestSender;
 use crate::protocol::{IntoRlpxSubProtocol, RlpxSubProtocolHandlers, RlpxSubProtocols};
 pub use config::{SessionLimits, SessionsConfig};
@@ -112,6 +118,8 @@ pub struct SessionManager {
     bandwidth_meter: BandwidthMeter,
     /// Metrics for the session manager.
     metrics: SessionManagerMetrics,
+    /// Metrics for the messages exchanged in all sessions.
+    message_metrics: Arc<MessageMetrics>,
 }
 
 // === impl SessionManager ===
@@ -154,6 +162,7 @@ impl SessionManager {
             bandwidth_meter,
             extra_protocols,
             metrics: Default::default(),
+            message_metrics: Default::default(),
         }
     }
 
@@ -473,6 +482,8 @@ impl SessionManager {
                 // negotiated version
                 let version = conn.version();
 
+                let stats = Arc::new(SessionStats::new(Arc::clone(&self.message_metrics)));
+
                 let session = ActiveSession {
                     next_id: 0,
                     remote_peer_id: peer_id,
@@ -493,6 +504,7 @@ impl SessionManager {
                     internal_request_timeout: Arc::clone(&timeout),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
                     terminate_message: None,
+                    stats: Arc::clone(&stats),
                 };
 
                 self.spawn(session);
@@ -510,6 +522,7 @@ impl SessionManager {
                     client_version: Arc::clone(&client_version),
                     remote_addr,
                     local_addr,
+                    stats,
                 };
 
                 self.active_sessions.insert(peer_id, handle);
@@ -606,6 +619,11 @@ impl SessionManager {
         self.active_sessions.values().map(ActiveSessionHandle::peer_info).collect()
     }
 
+    /// Returns [`PeerStats`] for all connected peers, resetting their counters if `reset` is true.
+    pub(crate) fn get_peer_stats(&self, reset: bool) -> Vec<PeerStats> {
+        self.active_sessions.values().map(|session| session.peer_stats(reset)).collect()
+    }
+
     /// Returns [`PeerInfo`] for a given peer.
     ///
     /// Returns `None` if there's no active session to the peer.
@@ -0,0 +1,212 @@
+//! Accounting of the `eth` messages exchanged in a session.
+
+use crate::metrics::EthMessageMetrics;
+use reth_eth_wire::EthMessageID;
+use reth_primitives::PeerId;
+use reth_rpc_types::{MessageStats, PeerStats};
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// The number of possible `eth` message ids.
+const MESSAGE_IDS: usize = EthMessageID::max() as usize + 1;
+
+/// The [`EthMessageMetrics`] of every `eth` message type, shared by all sessions.
+#[derive(Debug)]
+pub(crate) struct MessageMetrics {
+    /// The metrics indexed by message id, `None` for the unassigned ids.
+    by_id: Vec<Option<EthMessageMetrics>>,
+}
+
+impl MessageMetrics {
+    fn get(&self, id: EthMessageID) -> Option<&EthMessageMetrics> {
+        self.by_id.get(id as usize)?.as_ref()
+    }
+}
+
+impl Default for MessageMetrics {
+    fn default() -> Self {
+        let by_id = (0..MESSAGE_IDS)
+            .map(|id| {
+                let id = EthMessageID::try_from(id).ok()?;
+                Some(EthMessageMetrics::new_with_labels(&[("message", format!("{id:?}"))]))
+            })
+            .collect();
+        Self { by_id }
+    }
+}
+
+/// The counters of the messages of a single type.
+#[derive(Debug, Default)]
+struct MessageCounters {
+    messages_in: AtomicU64,
+    bytes_in: AtomicU64,
+    messages_out: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
+/// The counters of the messages exchanged in a session.
+///
+/// These are updated by the [`ActiveSession`](super::active::ActiveSession) for every message and
+/// read through its [`ActiveSessionHandle`](super::ActiveSessionHandle). The byte counts are the
+/// sizes of the RLP encoded messages, before compression.
+#[derive(Debug, Default)]
+pub(crate) struct SessionStats {
+    /// The counters indexed by message id.
+    messages: [MessageCounters; MESSAGE_IDS],
+    /// The number of requests of the peer that were answered.
+    requests_served: AtomicU64,
+    /// The number of requests to the peer that timed out.
+    requests_timed_out: AtomicU64,
+    /// The number of responses of the peer.
+    responses: AtomicU64,
+    /// The total time the peer took to respond, in microseconds.
+    response_latency_micros: AtomicU64,
+    /// The aggregated metrics of all sessions.
+    metrics: Arc<MessageMetrics>,
+}
+
+impl SessionStats {
+    /// Creates new counters that also update the given aggregated metrics.
+    pub(crate) fn new(metrics: Arc<MessageMetrics>) -> Self {
+        Self {
+            messages: Default::default(),
+            requests_served: AtomicU64::new(0),
+            requests_timed_out: AtomicU64::new(0),
+            responses: AtomicU64::new(0),
+            response_latency_micros: AtomicU64::new(0),
+            metrics,
+        }
+    }
+
+    /// Records a message received from the peer.
+    pub(crate) fn on_message_in(&self, id: EthMessageID, bytes: usize) {
+        let counters = &self.messages[id as usize];
+        counters.messages_in.fetch_add(1, Ordering::Relaxed);
+        counters.bytes_in.fetch_add(bytes as u64, Ordering::Relaxed);
+        if let Some(metrics) = self.metrics.get(id) {
+            metrics.messages_received.increment(1);
+            metrics.bytes_received.increment(bytes as u64);
+        }
+    }
+
+    /// Records a message sent to the peer.
+    pub(crate) fn on_message_out(&self, id: EthMessageID, bytes: usize) {
+        let counters = &self.messages[id as usize];
+        counters.messages_out.fetch_add(1, Ordering::Relaxed);
+        counters.bytes_out.fetch_add(bytes as u64, Ordering::Relaxed);
+        if let Some(metrics) = self.metrics.get(id) {
+            metrics.messages_sent.increment(1);
+            metrics.bytes_sent.increment(bytes as u64);
+        }
+    }
+
+    /// Records a request of the peer that was answered.
+    pub(crate) fn on_request_served(&self) {
+        self.requests_served.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a request to the peer that timed out.
+    pub(crate) fn on_request_timed_out(&self) {
+        self.requests_timed_out.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a response of the peer that arrived after the given latency.
+    pub(crate) fn on_response(&self, latency: Duration) {
+        self.responses.fetch_add(1, Ordering::Relaxed);
+        self.response_latency_micros.fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Returns the [`PeerStats`] of the session.
+    ///
+    /// If `reset` is true, all counters are reset to zero.
+    pub(crate) fn peer_stats(&self, id: PeerId, name: String, reset: bool) -> PeerStats {
+        let read = |counter: &AtomicU64| {
+            if reset {
+                counter.swap(0, Ordering::Relaxed)
+            } else {
+                counter.load(Ordering::Relaxed)
+            }
+        };
+
+        let mut messages = BTreeMap::new();
+        let (mut bytes_in, mut bytes_out) = (0, 0);
+        for (id, counters) in self.messages.iter().enumerate() {
+            let stats = MessageStats {
+                messages_in: read(&counters.messages_in),
+                bytes_in: read(&counters.bytes_in),
+                messages_out: read(&counters.messages_out),
+                bytes_out: read(&counters.bytes_out),
+            };
+            if stats == MessageStats::default() {
+                continue
+            }
+            let Ok(id) = EthMessageID::try_from(id) else { continue };
+            bytes_in += stats.bytes_in;
+            bytes_out += stats.bytes_out;
+            messages.insert(format!("{id:?}"), stats);
+        }
+
+        let responses = read(&self.responses);
+        let response_latency_micros = read(&self.response_latency_micros);
+        PeerStats {
+            id,
+            name,
+            bytes_in,
+            bytes_out,
+            requests_served: read(&self.requests_served),
+            requests_timed_out: read(&self.requests_timed_out),
+            avg_response_latency_ms: (responses > 0)
+                .then(|| response_latency_micros / responses / 1_000),
+            messages,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peer_stats_reset() {
+        let stats = SessionStats::default();
+        stats.on_message_in(EthMessageID::GetBlockHeaders, 10);
+        stats.on_message_out(EthMessageID::BlockHeaders, 500);
+        stats.on_message_out(EthMessageID::BlockHeaders, 300);
+        stats.on_request_served();
+        stats.on_request_timed_out();
+        stats.on_response(Duration::from_millis(100));
+        stats.on_response(Duration::from_millis(300));
+
+        let id = PeerId::random();
+        let peer_stats = stats.peer_stats(id, "reth".to_string(), true);
+        assert_eq!(peer_stats.bytes_in, 10);
+        assert_eq!(peer_stats.bytes_out, 800);
+        assert_eq!(peer_stats.requests_served, 1);
+        assert_eq!(peer_stats.requests_timed_out, 1);
+        assert_eq!(peer_stats.avg_response_latency_ms, Some(200));
+        assert_eq!(
+            peer_stats.messages,
+            BTreeMap::from([
+                (
+                    "GetBlockHeaders".to_string(),
+                    MessageStats { messages_in: 1, bytes_in: 10, ..Default::default() }
+                ),
+                (
+                    "BlockHeaders".to_string(),
+                    MessageStats { messages_out: 2, bytes_out: 800, ..Default::default() }
+                ),
+            ])
+        );
+
+        let peer_stats = stats.peer_stats(id, "reth".to_string(), false);
+        assert_eq!(peer_stats.bytes_out, 0);
+        assert_eq!(peer_stats.avg_response_latency_ms, None);
+        assert!(peer_stats.messages.is_empty());
+    }
+}
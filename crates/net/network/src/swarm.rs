@@ -1,6 +1,7 @@
 use crate::{
     listener::{ConnectionListener, ListenerEvent},
     message::{PeerMessage, PeerRequestSender},
+    metrics::ForkIdFilterMetrics,
     peers::InboundConnectionError,
     protocol::IntoRlpxSubProtocol,
     session::{Direction, PendingSessionHandshakeError, SessionEvent, SessionId, SessionManager},
@@ -12,7 +13,7 @@ use reth_eth_wire::{
     errors::EthStreamError,
     EthVersion, Status,
 };
-use reth_primitives::PeerId;
+use reth_primitives::{ForkId, PeerId};
 use reth_provider::{BlockNumReader, BlockReader};
 use std::{
     io,
@@ -57,6 +58,10 @@ pub(crate) struct Swarm<C> {
     sessions: SessionManager,
     /// Tracks the entire state of the network and handles events received from the sessions.
     state: NetworkState<C>,
+    /// Whether discovered peers with an incompatible fork id are dropped before they're dialed.
+    fork_id_filter: bool,
+    /// Metrics for the fork id filter.
+    fork_id_filter_metrics: ForkIdFilterMetrics,
 }
 
 // === impl Swarm ===
@@ -67,8 +72,15 @@ impl<C> Swarm<C> {
         incoming: ConnectionListener,
         sessions: SessionManager,
         state: NetworkState<C>,
+        fork_id_filter: bool,
     ) -> Self {
-        Self { incoming, sessions, state }
+        Self {
+            incoming,
+            sessions,
+            state,
+            fork_id_filter,
+            fork_id_filter_metrics: Default::default(),
+        }
     }
 
     /// Adds an additional protocol handler to the RLPx sub-protocol list.
@@ -206,6 +218,9 @@ where
                         InboundConnectionError::ExceedsCapacity => {
                             trace!(target: "net", ?remote_addr, "No capacity for incoming connection");
                         }
+                        InboundConnectionError::ExceedsSubnetLimit => {
+                            trace!(target: "net", ?remote_addr, "Incoming connection exceeds the subnet limit");
+                        }
                     }
                     return None
                 }
@@ -253,14 +268,19 @@ where
                     return None
                 }
                 // Insert peer only if no fork id or a valid fork id
-                if fork_id.map_or_else(|| true, |f| self.sessions.is_valid_fork_id(f)) {
+                if fork_id.map_or(true, |fork_id| self.is_compatible_fork_id(fork_id)) {
                     self.state_mut().peers_mut().add_peer(peer_id, socket_addr, fork_id);
+                } else {
+                    trace!(target: "net", ?peer_id, ?fork_id, "dropping incompatible peer");
+                    self.fork_id_filter_metrics.discovered_peers_fork_id_filtered.increment(1);
                 }
             }
             StateAction::DiscoveredEnrForkId { peer_id, fork_id } => {
-                if self.sessions.is_valid_fork_id(fork_id) {
+                if self.is_compatible_fork_id(fork_id) {
                     self.state_mut().peers_mut().set_discovered_fork_id(peer_id, fork_id);
                 } else {
+                    trace!(target: "net", ?peer_id, ?fork_id, "removing incompatible peer");
+                    self.fork_id_filter_metrics.enr_fork_id_peers_removed.increment(1);
                     self.state_mut().peers_mut().remove_peer(peer_id);
                 }
             }
@@ -268,6 +288,12 @@ where
         None
     }
 
+    /// Returns `true` if the fork id of a discovered peer is compatible with the local fork filter,
+    /// or if the fork id filter is disabled.
+    fn is_compatible_fork_id(&self, fork_id: ForkId) -> bool {
+        !self.fork_id_filter || self.sessions.is_valid_fork_id(fork_id)
+    }
+
     /// Set network connection state to `ShuttingDown`
     pub(crate) fn on_shutdown_requested(&mut self) {
         self.state_mut().peers_mut().on_shutdown();
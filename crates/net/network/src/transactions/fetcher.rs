@@ -411,6 +411,45 @@ impl TransactionFetcher {
         self.remove_hashes_from_transaction_fetcher(max_retried_and_evicted_hashes);
     }
 
+    /// Buffers the hash of a transaction that is missing from the pool, to be fetched from one of
+    /// the given peers that have seen the transaction. This is used for reorged blob transactions
+    /// whose sidecars are no longer in the blob store.
+    ///
+    /// If the hash is already in the fetcher, the peers are only added as fallback peers.
+    pub fn buffer_missing_hash(
+        &mut self,
+        hash: TxHash,
+        peers_seen_hash: impl IntoIterator<Item = PeerId>,
+    ) {
+        let is_new = self.hashes_fetch_inflight_and_pending_fetch.peek(&hash).is_none();
+
+        let limit = NonZeroUsize::new(DEFAULT_MAX_COUNT_FALLBACK_PEERS.into())
+            .expect("MAX_ALTERNATIVE_PEERS_PER_TX should be non-zero");
+        let Some(TxFetchMetadata { fallback_peers, .. }) =
+            self.hashes_fetch_inflight_and_pending_fetch.get_or_insert(hash, || TxFetchMetadata {
+                retries: 0,
+                fallback_peers: LruCache::new(limit),
+                tx_encoded_length: None,
+            })
+        else {
+            debug!(target: "net::tx",
+                %hash,
+                "failed to cache missing hash in schnellru::LruMap, dropping hash"
+            );
+            return
+        };
+        for peer_id in peers_seen_hash {
+            fallback_peers.insert(peer_id);
+        }
+
+        if is_new {
+            if let (_, Some(evicted_hash)) = self.hashes_pending_fetch.insert_and_get_evicted(hash)
+            {
+                self.remove_hashes_from_transaction_fetcher([evicted_hash]);
+            }
+        }
+    }
+
     /// Tries to request hashes pending fetch.
     ///
     /// Finds the first buffered hash with a fallback peer that is idle, if any. Fills the rest of
@@ -1555,4 +1594,22 @@ mod test {
         assert_eq!(1, verified_payload.len());
         assert!(verified_payload.contains(&signed_tx_1));
     }
+
+    #[test]
+    fn buffer_missing_hash() {
+        let mut tx_fetcher = TransactionFetcher::default();
+        let hash = B256::random();
+        let peer_1 = PeerId::random();
+        let peer_2 = PeerId::random();
+
+        tx_fetcher.buffer_missing_hash(hash, [peer_1]);
+        assert!(tx_fetcher.hashes_pending_fetch.contains(&hash));
+
+        // buffering the hash again only adds the fallback peers
+        tx_fetcher.buffer_missing_hash(hash, [peer_2]);
+        assert_eq!(tx_fetcher.hashes_pending_fetch.len(), 1);
+        let metadata = tx_fetcher.hashes_fetch_inflight_and_pending_fetch.get(&hash).unwrap();
+        assert!(metadata.fallback_peers_mut().contains(&peer_1));
+        assert!(metadata.fallback_peers_mut().contains(&peer_2));
+    }
 }
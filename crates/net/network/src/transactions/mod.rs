@@ -242,6 +242,9 @@ pub struct TransactionsManager<Pool> {
     ///   - all dynamic fee requirements are (currently) met
     ///   - account has enough balance to cover the transaction's gas
     pending_transactions: ReceiverStream<TxHash>,
+    /// A stream that yields hashes of blob transactions that are missing from the pool, because
+    /// their sidecars are unavailable after a reorg.
+    missing_blob_sidecars: ReceiverStream<TxHash>,
     /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
     transaction_events: UnboundedMeteredReceiver<NetworkTransactionEvent>,
     /// TransactionsManager metrics
@@ -269,6 +272,8 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
         // install a listener for new __pending__ transactions that are allowed to be propagated
         // over the network
         let pending = pool.pending_transactions_listener();
+        // install a listener for reorged blob transactions that need to be fetched from peers
+        let missing_blob_sidecars = pool.missing_blob_sidecars_listener();
         let pending_pool_imports_info = PendingPoolImportsInfo::default();
         let metrics = TransactionsManagerMetrics::default();
         metrics
@@ -292,6 +297,7 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
             command_tx,
             command_rx: UnboundedReceiverStream::new(command_rx),
             pending_transactions: ReceiverStream::new(pending),
+            missing_blob_sidecars: ReceiverStream::new(missing_blob_sidecars),
             transaction_events: UnboundedMeteredReceiver::new(
                 from_network,
                 NETWORK_POOL_TRANSACTIONS_SCOPE,
@@ -326,6 +332,7 @@ where
             acc_pending_imports,
             acc_tx_events,
             acc_imported_txns,
+            acc_missing_blob_sidecars,
             acc_fetch_events,
             acc_pending_fetch,
             acc_cmds,
@@ -338,6 +345,9 @@ where
         metrics.acc_duration_poll_pending_pool_imports.set(acc_pending_imports.as_secs_f64());
         metrics.acc_duration_poll_transaction_events.set(acc_tx_events.as_secs_f64());
         metrics.acc_duration_poll_imported_transactions.set(acc_imported_txns.as_secs_f64());
+        metrics
+            .acc_duration_poll_missing_blob_sidecars
+            .set(acc_missing_blob_sidecars.as_secs_f64());
         metrics.acc_duration_poll_fetch_events.set(acc_fetch_events.as_secs_f64());
         metrics.acc_duration_fetch_pending_hashes.set(acc_pending_fetch.as_secs_f64());
         metrics.acc_duration_poll_commands.set(acc_cmds.as_secs_f64());
@@ -1102,6 +1112,38 @@ where
         }
     }
 
+    /// Buffers the given blob transactions, whose sidecars are missing from the pool after a
+    /// reorg, to be fetched from the peers that have seen them.
+    ///
+    /// Transactions no peer has seen can't be fetched, the pool requests them again on the next
+    /// block.
+    fn on_missing_blob_sidecars(&mut self, hashes: Vec<TxHash>) {
+        for hash in hashes {
+            let peers_seen_hash = self
+                .peers
+                .iter()
+                .filter(|(_, peer)| peer.seen_transactions.contains(&hash))
+                .map(|(peer_id, _)| *peer_id)
+                .collect::<Vec<_>>();
+
+            if peers_seen_hash.is_empty() {
+                trace!(target: "net::tx",
+                    %hash,
+                    "no peer has seen blob transaction with missing sidecar"
+                );
+                continue
+            }
+
+            trace!(target: "net::tx",
+                %hash,
+                peers_len=peers_seen_hash.len(),
+                "buffering blob transaction with missing sidecar"
+            );
+
+            self.transaction_fetcher.buffer_missing_hash(hash, peers_seen_hash);
+        }
+    }
+
     /// Runs an operation to fetch hashes that are cached in [`TransactionFetcher`].
     fn on_fetch_hashes_pending_fetch(&mut self) {
         // try drain transaction hashes pending fetch
@@ -1277,6 +1319,22 @@ where
             this.on_new_pending_transactions(new_txs);
         }
 
+        // Advances blob transactions whose sidecars are missing from the pool after a reorg, and
+        // buffers them to be fetched from peers that have seen them.
+        let mut missing_blob_txs = Vec::new();
+        let acc = &mut poll_durations.acc_missing_blob_sidecars;
+        let maybe_more_missing_blob_sidecars = metered_poll_nested_stream_with_budget!(
+            acc,
+            "net::tx",
+            "Missing blob sidecars stream",
+            DEFAULT_BUDGET_TRY_DRAIN_STREAM,
+            this.missing_blob_sidecars.poll_next_unpin(cx),
+            |hash| missing_blob_txs.push(hash)
+        );
+        if !missing_blob_txs.is_empty() {
+            this.on_missing_blob_sidecars(missing_blob_txs);
+        }
+
         // Advance inflight fetch requests (flush transaction fetcher and queue for
         // import to pool).
         //
@@ -1354,7 +1412,8 @@ where
             maybe_more_tx_events ||
             maybe_more_tx_fetch_events ||
             maybe_more_pool_imports ||
-            maybe_more_pending_txns
+            maybe_more_pending_txns ||
+            maybe_more_missing_blob_sidecars
         {
             // make sure we're woken up again
             cx.waker().wake_by_ref();
@@ -1466,7 +1525,9 @@ impl PooledTransactionsHashesBuilder {
             EthVersion::Eth66 | EthVersion::Eth67 => {
                 PooledTransactionsHashesBuilder::Eth66(Default::default())
             }
-            EthVersion::Eth68 => PooledTransactionsHashesBuilder::Eth68(Default::default()),
+            EthVersion::Eth68 | EthVersion::Eth69 => {
+                PooledTransactionsHashesBuilder::Eth68(Default::default())
+            }
         }
     }
 
@@ -1612,6 +1673,7 @@ struct TxManagerPollDurations {
     acc_pending_imports: Duration,
     acc_tx_events: Duration,
     acc_imported_txns: Duration,
+    acc_missing_blob_sidecars: Duration,
     acc_fetch_events: Duration,
     acc_pending_fetch: Duration,
     acc_cmds: Duration,
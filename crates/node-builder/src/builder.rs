@@ -4,9 +4,10 @@
 
 use crate::{
     components::{ComponentsBuilder, NodeComponents, NodeComponentsBuilder, PoolBuilder},
-    exex::BoxedLaunchExEx,
+    exex::{BoxedLaunchExEx, ExExBackpressureHook},
     hooks::NodeHooks,
     node::FullNode,
+    reload::ConfigReloader,
     rpc::{RethRpcServerHandles, RpcContext, RpcHooks},
     Node, NodeHandle,
 };
@@ -15,7 +16,8 @@ use futures::{future, future::Either, stream, stream_select, Future, StreamExt};
 use rayon::ThreadPoolBuilder;
 use reth_beacon_consensus::{
     hooks::{EngineHooks, PruneHook, StaticFileHook},
-    BeaconConsensusEngine,
+    BeaconConsensusEngine, InvalidBlockStore, DEFAULT_INVALID_BLOCK_MAX_AGE,
+    DEFAULT_MAX_INVALID_BLOCKS,
 };
 use reth_blockchain_tree::{
     BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree, TreeExternals,
@@ -27,36 +29,52 @@ use reth_db::{
     test_utils::{create_test_rw_db, TempDatabase},
     DatabaseEnv,
 };
-use reth_exex::{ExExContext, ExExHandle, ExExManager};
-use reth_interfaces::p2p::either::EitherDownloader;
+use reth_exex::{ExExContext, ExExHandle, ExExManager, ExExWal};
+use reth_interfaces::{
+    invalid_block::InvalidBlocksHandle, p2p::either::EitherDownloader, reload::ConfigReloadHandle,
+    sync::SyncStatusHandle,
+};
+use reth_net_nat::PortMapper;
 use reth_network::{NetworkBuilder, NetworkConfig, NetworkEvents, NetworkHandle};
+use reth_network_api::PeersInfo;
 use reth_node_api::{
     FullNodeComponents, FullNodeComponentsAdapter, FullNodeTypes, FullNodeTypesAdapter, NodeTypes,
 };
 use reth_node_core::{
-    cli::config::{PayloadBuilderConfig, RethRpcConfig, RethTransactionPoolConfig},
+    cli::config::{PayloadBuilderConfig, RethRpcConfig},
     dirs::{ChainPath, DataDirPath, MaybePlatformPath},
-    engine_api_store::EngineApiStore,
+    engine_api_store::{EngineApiStore, EngineMessageJournal, DEFAULT_ENGINE_JOURNAL_MAX_MESSAGES},
     events::cl::ConsensusLayerHealthEvents,
     exit::NodeExitFuture,
     init::init_genesis,
     node_config::NodeConfig,
     primitives::{kzg::KzgSettings, Head},
-    utils::write_peers_to_file,
+    rpc_consensus::RpcConsensusClient,
+    utils::{write_peers_to_file, write_peers_to_file_periodically},
 };
 use reth_primitives::{constants::eip4844::MAINNET_KZG_TRUSTED_SETUP, format_ether, ChainSpec};
 use reth_provider::{
-    providers::BlockchainProvider, CanonStateSubscriptions, ChainSpecProvider, ProviderFactory,
+    providers::{BlockchainProvider, StaticFileWriter},
+    CanonStateSubscriptions, ChainSpecProvider, ProviderFactory,
 };
 use reth_prune::PrunerBuilder;
 use reth_revm::EvmProcessorFactory;
 use reth_rpc_engine_api::EngineApi;
 use reth_static_file::StaticFileProducer;
-use reth_tasks::TaskExecutor;
-use reth_tracing::tracing::{debug, error, info};
-use reth_transaction_pool::{PoolConfig, TransactionPool};
-use std::{cmp::max, str::FromStr, sync::Arc, thread::available_parallelism};
-use tokio::sync::{mpsc::unbounded_channel, oneshot};
+use reth_tasks::{shutdown::ShutdownPhase, TaskExecutor};
+use reth_tracing::tracing::{debug, error, info, warn};
+use reth_transaction_pool::{
+    maintain::{LocalTransactionBackupConfig, MaintainPoolConfig},
+    PoolConfig, TransactionPool,
+};
+use std::{
+    cmp::max,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    thread::available_parallelism,
+};
+use tokio::sync::{broadcast::error::RecvError, mpsc::unbounded_channel, oneshot, watch};
 
 /// The builtin provider type of the reth node.
 // Note: we need to hardcode this because custom components might depend on it in associated types.
@@ -174,27 +192,38 @@ impl<DB, State> NodeBuilder<DB, State> {
         &self.config
     }
 
+    /// Returns the path of the reth config with the given datadir root
+    fn config_path(&self, data_dir: &ChainPath<DataDirPath>) -> PathBuf {
+        self.config.config.clone().unwrap_or_else(|| data_dir.config_path())
+    }
+
     /// Loads the reth config with the given datadir root
     fn load_config(&self, data_dir: &ChainPath<DataDirPath>) -> eyre::Result<reth_config::Config> {
-        let config_path = self.config.config.clone().unwrap_or_else(|| data_dir.config_path());
+        load_config_file(&self.config, &self.config_path(data_dir))
+    }
+}
 
-        let mut config = confy::load_path::<reth_config::Config>(&config_path)
-            .wrap_err_with(|| format!("Could not load config file {config_path:?}"))?;
+/// Loads the reth config from the given path and updates it with the command line arguments.
+pub(crate) fn load_config_file(
+    node_config: &NodeConfig,
+    config_path: &Path,
+) -> eyre::Result<reth_config::Config> {
+    let mut config = confy::load_path::<reth_config::Config>(config_path)
+        .wrap_err_with(|| format!("Could not load config file {config_path:?}"))?;
 
-        info!(target: "reth::cli", path = ?config_path, "Configuration loaded");
+    info!(target: "reth::cli", path = ?config_path, "Configuration loaded");
 
-        // Update the config with the command line arguments
-        config.peers.trusted_nodes_only = self.config.network.trusted_only;
+    // Update the config with the command line arguments
+    config.peers.trusted_nodes_only = node_config.network.trusted_only;
 
-        if !self.config.network.trusted_peers.is_empty() {
-            info!(target: "reth::cli", "Adding trusted nodes");
-            self.config.network.trusted_peers.iter().for_each(|peer| {
-                config.peers.trusted_nodes.insert(*peer);
-            });
-        }
-
-        Ok(config)
+    if !node_config.network.trusted_peers.is_empty() {
+        info!(target: "reth::cli", "Adding trusted nodes");
+        node_config.network.trusted_peers.iter().for_each(|peer| {
+            config.peers.trusted_nodes.insert(*peer);
+        });
     }
+
+    Ok(config)
 }
 
 impl NodeBuilder<(), InitState> {
@@ -479,7 +508,10 @@ where
         >,
     > {
         // get config from file
+        let config_path = self.config_path(&data_dir);
         let reth_config = self.load_config(&data_dir)?;
+        // the config as it was loaded, which is compared with the reloaded config
+        let file_config = reth_config.clone();
 
         let Self {
             config,
@@ -504,7 +536,10 @@ where
             Arc::clone(&config.chain),
             data_dir.static_files_path(),
         )?
-        .with_static_files_metrics();
+        .with_static_files_metrics()
+        .with_static_files_compression(config.static_files.segment_compression()?)
+        .with_static_files_access_pattern(config.static_files.access_pattern)
+        .with_static_files_transaction_hash_index(config.static_files.tx_hash_index);
         info!(target: "reth::cli", "Database opened");
 
         let prometheus_handle = config.install_prometheus_recorder()?;
@@ -526,14 +561,26 @@ where
 
         debug!(target: "reth::cli", "Spawning stages metrics listener task");
         let (sync_metrics_tx, sync_metrics_rx) = unbounded_channel();
-        let sync_metrics_listener = reth_stages::MetricsListener::new(sync_metrics_rx);
+        let sync_metrics_listener = reth_stages::MetricsListener::new(sync_metrics_rx)
+            .with_slow_block_threshold(config.debug.slow_block_threshold);
         executor.spawn_critical("stages metrics listener task", sync_metrics_listener);
+        let sync_status_metrics_tx = sync_metrics_tx.clone();
 
         let prune_config = config.prune_config()?.or_else(|| reth_config.prune.clone());
+        if let Some(prune_config) = &prune_config {
+            prune_config.segments.validate().wrap_err("Invalid prune configuration")?;
+        }
 
         // Configure the blockchain tree for the node
         let evm_config = types.evm_config();
-        let tree_config = BlockchainTreeConfig::default();
+        let mut tree_config = BlockchainTreeConfig::default();
+        if let Some(max_buffered_blocks) = config.tree.max_buffered_blocks {
+            tree_config = tree_config.with_max_unconnected_blocks(max_buffered_blocks.get());
+        }
+        if let Some(max_buffered_blocks_size) = config.tree.max_buffered_blocks_size {
+            tree_config = tree_config
+                .with_max_unconnected_blocks_size(max_buffered_blocks_size.get() * 1024 * 1024);
+        }
         let tree_externals = TreeExternals::new(
             provider_factory.clone(),
             consensus.clone(),
@@ -611,6 +658,7 @@ where
                 config: config.clone(),
                 reth_config: reth_config.clone(),
                 pool: transaction_pool.clone(),
+                evm_config: evm_config.clone(),
                 events,
                 notifications,
             };
@@ -635,19 +683,43 @@ where
         future::join_all(exexs).await;
 
         // spawn exex manager
+        let mut exex_finished_height = None;
+        let mut exex_manager = None;
         if !exex_handles.is_empty() {
             debug!(target: "reth::cli", "spawning exex manager");
             // todo(onbjerg): rm magic number
-            let exex_manager = ExExManager::new(exex_handles, 1024);
-            let mut exex_manager_handle = exex_manager.handle();
+            let exex_wal = ExExWal::open(
+                data_dir.exex_wal_path(),
+                reth_config.exex.wal_max_size_mb.saturating_mul(1024 * 1024),
+            )?;
+            let manager = ExExManager::new(exex_handles, 1024)
+                .with_wal(exex_wal)
+                .with_max_prune_lag(reth_config.exex.max_prune_lag);
+            let mut exex_manager_handle = manager.handle();
+            exex_finished_height = Some(exex_manager_handle.finished_height_receiver());
+            exex_manager = Some(exex_manager_handle.clone());
             executor.spawn_critical("exex manager", async move {
-                exex_manager.await.expect("exex manager crashed");
+                manager.await.expect("exex manager crashed");
             });
 
             // send notifications from the blockchain tree to exex manager
             let mut canon_state_notifications = blockchain_tree.subscribe_to_canonical_state();
             executor.spawn_critical("exex manager blockchain tree notifications", async move {
-                while let Ok(notification) = canon_state_notifications.recv().await {
+                loop {
+                    let notification = match canon_state_notifications.recv().await {
+                        Ok(notification) => notification,
+                        Err(RecvError::Lagged(skipped)) => {
+                            // the engine is held back while the manager isn't ready, so this
+                            // only happens if the tree commits faster than the manager receives
+                            error!(
+                                target: "reth::cli",
+                                skipped,
+                                "ExEx manager lagged behind the canonical state notifications"
+                            );
+                            continue
+                        }
+                        Err(RecvError::Closed) => break,
+                    };
                     exex_manager_handle
                         .send_async(notification)
                         .await
@@ -673,6 +745,23 @@ where
             consensus_engine_rx = engine_intercept_rx;
         };
 
+        if let Some(journal_path) = config.debug.engine_persist_messages.clone() {
+            debug!(target: "reth::cli", "spawning engine message journal");
+            let (engine_intercept_tx, engine_intercept_rx) = unbounded_channel();
+            let journal = EngineMessageJournal::new(
+                journal_path,
+                config
+                    .debug
+                    .engine_persist_messages_max
+                    .unwrap_or(DEFAULT_ENGINE_JOURNAL_MAX_MESSAGES),
+            )?;
+            executor.spawn_critical(
+                "engine message journal",
+                journal.intercept(consensus_engine_rx, engine_intercept_tx),
+            );
+            consensus_engine_rx = engine_intercept_rx;
+        }
+
         let max_block = config.max_block(&network_client, provider_factory.clone()).await?;
         let mut hooks = EngineHooks::new();
 
@@ -680,16 +769,46 @@ where
             provider_factory.clone(),
             provider_factory.static_file_provider(),
             prune_config.clone().unwrap_or_default().segments,
+        )
+        .with_compression_pool(
+            ThreadPoolBuilder::new()
+                .num_threads(config.static_files.compression_workers())
+                .thread_name(|i| format!("static-file-compression-{i}"))
+                .build()?,
         );
         let static_file_producer_events = static_file_producer.lock().events();
+        let static_file_producer_progress_events = static_file_producer.lock().events();
         hooks.add(StaticFileHook::new(static_file_producer.clone(), Box::new(executor.clone())));
+        if let Some(exex_manager) = &exex_manager {
+            // don't make new blocks canonical while all exexs are too far behind
+            hooks.add(ExExBackpressureHook::new(exex_manager));
+        }
         info!(target: "reth::cli", "StaticFileProducer initialized");
 
+        if config.static_files.tx_hash_index {
+            // Index the transactions of the existing static files, which can take a while on the
+            // first startup with the index enabled.
+            let static_file_provider = provider_factory.static_file_provider();
+            executor.spawn_blocking(async move {
+                match static_file_provider.update_transaction_hash_index() {
+                    Ok(indexed) => {
+                        debug!(target: "reth::cli", indexed, "Indexed transaction hashes")
+                    }
+                    Err(err) => {
+                        error!(target: "reth::cli", %err, "Failed to index transaction hashes")
+                    }
+                }
+            });
+        }
+
         // Make sure ETL doesn't default to /tmp/, but to whatever datadir is set to
         if reth_config.stages.etl.dir.is_none() {
             reth_config.stages.etl.dir = Some(EtlConfig::from_datadir(&data_dir.data_dir_path()));
         }
 
+        // Apply the stage thresholds that were set on the command line
+        config.stages.apply(&mut reth_config.stages);
+
         // Configure the pipeline
         let (mut pipeline, client) = if config.dev.dev {
             info!(target: "reth::cli", "Starting Reth in dev mode");
@@ -723,6 +842,7 @@ where
                 max_block,
                 static_file_producer,
                 evm_config,
+                exex_manager.clone(),
             )
             .await?;
 
@@ -745,6 +865,7 @@ where
                 max_block,
                 static_file_producer,
                 evm_config,
+                exex_manager.clone(),
             )
             .await?;
 
@@ -761,34 +882,103 @@ where
             .prune_delete_limit(config.chain.prune_delete_limit)
             .timeout(PrunerBuilder::DEFAULT_TIMEOUT)
             .build(provider_factory.clone());
+        // the prune config is replaced when the config file is reloaded
+        let (prune_config_tx, prune_config_rx) = watch::channel(prune_config.clone());
+        pruner = pruner.with_config_updates(prune_config_rx);
+        if let Some(finished_exex_height) = exex_finished_height {
+            // never prune blocks that an exex has not finished yet
+            pruner = pruner.with_finished_exex_height(finished_exex_height);
+        }
 
         let pruner_events = pruner.events();
+        let pruner_progress_events = pruner.events();
         hooks.add(PruneHook::new(pruner, Box::new(executor.clone())));
         info!(target: "reth::cli", ?prune_config, "Pruner initialized");
 
+        // Keep track of the progress of the stages, the pruner and the static file producer, which
+        // is served by the `reth_syncStatus` RPC method
+        let sync_status = SyncStatusHandle::default();
+        let progress_events = stream_select!(
+            pipeline.events().map(Into::into),
+            pruner_progress_events.map(Into::into),
+            static_file_producer_progress_events.map(Into::into)
+        );
+        executor.spawn_critical(
+            "sync status task",
+            reth_node_core::events::sync_status::handle_sync_status_events(
+                progress_events,
+                sync_status.clone(),
+                Some(sync_status_metrics_tx),
+            ),
+        );
+
         // Configure the consensus engine
-        let (beacon_consensus_engine, beacon_engine_handle) = BeaconConsensusEngine::with_channel(
-            client,
-            pipeline,
-            blockchain_db.clone(),
-            Box::new(executor.clone()),
-            Box::new(network.clone()),
-            max_block,
-            config.debug.continuous,
-            payload_builder.clone(),
-            initial_target,
-            reth_beacon_consensus::MIN_BLOCKS_FOR_PIPELINE_RUN,
-            consensus_engine_tx,
-            consensus_engine_rx,
-            hooks,
-        )?;
+        let (mut beacon_consensus_engine, beacon_engine_handle) =
+            BeaconConsensusEngine::with_channel(
+                client,
+                pipeline,
+                blockchain_db.clone(),
+                Box::new(executor.clone()),
+                Box::new(network.clone()),
+                max_block,
+                config.debug.continuous,
+                payload_builder.clone(),
+                initial_target,
+                reth_beacon_consensus::MIN_BLOCKS_FOR_PIPELINE_RUN,
+                consensus_engine_tx,
+                consensus_engine_rx,
+                hooks,
+            )?;
+        // let the engine process the in-flight messages when the node shuts down
+        beacon_consensus_engine
+            .set_graceful_shutdown(executor.graceful_shutdown_signal(ShutdownPhase::Engine));
+
+        // Persist the invalid blocks across restarts, which are served by the
+        // `debug_getBadBlocks` RPC method
+        let invalid_blocks = InvalidBlocksHandle::default();
+        let max_invalid_blocks =
+            config.debug.engine_invalid_blocks_max.unwrap_or(DEFAULT_MAX_INVALID_BLOCKS);
+        if max_invalid_blocks > 0 {
+            let path = data_dir.invalid_blocks_path();
+            let max_age =
+                config.debug.engine_invalid_blocks_max_age.unwrap_or(DEFAULT_INVALID_BLOCK_MAX_AGE);
+            let store =
+                InvalidBlockStore::open(&path, max_invalid_blocks, max_age, invalid_blocks.clone())
+                    .unwrap_or_else(|err| {
+                        warn!(
+                            target: "reth::cli",
+                            ?path,
+                            %err,
+                            "Failed to load the invalid blocks, starting with none"
+                        );
+                        InvalidBlockStore::new(
+                            &path,
+                            max_invalid_blocks,
+                            max_age,
+                            invalid_blocks.clone(),
+                        )
+                    });
+            beacon_consensus_engine.set_invalid_block_store(store);
+        }
         info!(target: "reth::cli", "Consensus engine initialized");
 
+        // Follow the chain of a reference node instead of a consensus layer
+        if let Some(url) = config.debug.rpc_consensus_url.clone() {
+            let rpc_consensus =
+                RpcConsensusClient::new(url, config.debug.follow, beacon_engine_handle.clone())?;
+            executor.spawn_critical_with_shutdown_signal("rpc consensus client", |shutdown| {
+                rpc_consensus.run(shutdown)
+            });
+        }
+
         let events = stream_select!(
             network.event_listener().map(Into::into),
             beacon_engine_handle.event_listener().map(Into::into),
             pipeline_events.map(Into::into),
-            if config.debug.tip.is_none() && !config.dev.dev {
+            if config.debug.tip.is_none() &&
+                config.debug.rpc_consensus_url.is_none() &&
+                !config.dev.dev
+            {
                 Either::Left(
                     ConsensusLayerHealthEvents::new(Box::new(blockchain_db.clone()))
                         .map(Into::into),
@@ -827,20 +1017,41 @@ where
 
         // Start RPC servers
 
-        let (rpc_server_handles, mut rpc_registry) = crate::rpc::launch_rpc_servers(
-            node_components.clone(),
-            engine_api,
-            &config,
-            jwt_secret,
-            rpc,
-        )
-        .await?;
+        let (config_reload, config_reload_requests) = ConfigReloadHandle::new();
+        let (rpc_server_handles, mut rpc_registry, rpc_rate_limits) =
+            crate::rpc::launch_rpc_servers(
+                node_components.clone(),
+                engine_api,
+                &config,
+                &reth_config.rpc,
+                jwt_secret,
+                sync_status,
+                invalid_blocks,
+                prune_config.segments.clone(),
+                config_reload,
+                rpc,
+            )
+            .await?;
+
+        // Reload the config file on SIGHUP or `admin_reloadConfig`
+        let config_reloader = ConfigReloader::new(
+            config.clone(),
+            config_path,
+            file_config,
+            transaction_pool.clone(),
+            network.clone(),
+            rpc_rate_limits,
+            prune_config_tx,
+        )?;
+        executor.spawn_critical("config reload task", config_reloader.run(config_reload_requests));
 
         // in dev mode we generate 20 random dev-signer accounts
         if config.dev.dev {
             rpc_registry.eth_api().with_dev_accounts();
         }
 
+        spawn_shutdown_tasks(&executor, rpc_server_handles.clone(), &provider_factory);
+
         // Run consensus engine to completion
         let (tx, rx) = oneshot::channel();
         info!(target: "reth::cli", "Starting consensus engine");
@@ -889,6 +1100,46 @@ where
     }
 }
 
+/// Spawns the tasks that shut down the parts of the node in their [ShutdownPhase].
+///
+/// The consensus engine, the pipeline and the components of the node are shut down by their own
+/// graceful shutdown signals. The database is closed once the last task that holds it is dropped,
+/// after the last phase.
+fn spawn_shutdown_tasks<DB>(
+    executor: &TaskExecutor,
+    rpc_server_handles: RethRpcServerHandles,
+    provider_factory: &ProviderFactory<DB>,
+) where
+    DB: Database + 'static,
+{
+    // stop accepting new requests and engine API messages first
+    executor.spawn_critical_with_graceful_shutdown_phase(
+        "rpc shutdown",
+        ShutdownPhase::Rpc,
+        |shutdown| async move {
+            let _guard = shutdown.await;
+            info!(target: "reth::cli", "Stopping RPC servers");
+            let RethRpcServerHandles { rpc, auth } = rpc_server_handles;
+            let _ = rpc.stop();
+            let _ = auth.stop();
+        },
+    );
+
+    // commit the static file writers after the pipeline stopped
+    let static_file_provider = provider_factory.static_file_provider();
+    executor.spawn_critical_with_graceful_shutdown_phase(
+        "static files shutdown",
+        ShutdownPhase::StaticFiles,
+        |shutdown| async move {
+            let _guard = shutdown.await;
+            info!(target: "reth::cli", "Committing static files");
+            if let Err(err) = static_file_provider.commit() {
+                error!(target: "reth::cli", %err, "Failed to commit static files");
+            }
+        },
+    );
+}
+
 /// A [NodeBuilder] with it's launch context already configured.
 ///
 /// This exposes the same methods as [NodeBuilder] but with the launch context already configured,
@@ -1259,7 +1510,17 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
 
     /// Returns the transaction pool config of the node.
     pub fn pool_config(&self) -> PoolConfig {
-        self.config().txpool.pool_config()
+        self.config().txpool.pool_config_with(&self.reth_config.txpool)
+    }
+
+    /// Returns the config of the transaction pool maintenance task.
+    pub fn maintain_pool_config(&self) -> MaintainPoolConfig {
+        self.config().txpool.maintain_pool_config()
+    }
+
+    /// Returns the config for the journal of the local and pending transactions of the pool.
+    pub fn transactions_backup_config(&self) -> LocalTransactionBackupConfig {
+        self.config().txpool.transactions_backup_config(self.data_dir().txpool_transactions_path())
     }
 
     /// Loads `MAINNET_KZG_TRUSTED_SETUP`.
@@ -1310,23 +1571,51 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     {
         let (handle, network, txpool, eth) = builder
             .transactions(pool, Default::default())
-            .request_handler(self.provider().clone())
+            .request_handler_with_config(
+                self.provider().clone(),
+                self.config.network.eth_requests_config(),
+            )
             .split_with_handle();
 
         self.executor.spawn_critical("p2p txpool", txpool);
         self.executor.spawn_critical("p2p eth request handler", eth);
 
-        let default_peers_path = self.data_dir().known_peers_path();
-        let known_peers_file = self.config.network.persistent_peers_file(default_peers_path);
+        let peers_file =
+            self.config.network.peers_file.clone().unwrap_or(self.data_dir().known_peers_path());
+        let known_peers_file = self.config.network.persistent_peers_file(peers_file);
+        let max_known_peers = self.config.network.peers_file_max_entries;
+        let peers_file_interval = self.config.network.peers_file_interval;
+
+        if let Some(file_path) = known_peers_file.clone().filter(|_| !peers_file_interval.is_zero())
+        {
+            self.executor.spawn(write_peers_to_file_periodically(
+                handle.clone(),
+                file_path,
+                peers_file_interval,
+                max_known_peers,
+            ));
+        }
+
         self.executor.spawn_critical_with_graceful_shutdown_signal(
             "p2p network task",
             |shutdown| {
                 network.run_until_graceful_shutdown(shutdown, |network| {
-                    write_peers_to_file(network, known_peers_file)
+                    write_peers_to_file(network, known_peers_file, max_known_peers)
                 })
             },
         );
 
+        if let Some(method) = self.config.network.nat.port_mapping_method() {
+            let local_node = handle.local_node_record();
+            let (network, ports_network) = (handle.clone(), handle.clone());
+            let port_mapper = PortMapper::new(method, local_node.tcp_port, local_node.udp_port)
+                .on_external_ip(move |ip| network.set_external_ip_addr(ip))
+                .on_external_ports(move |tcp_port, udp_port| {
+                    ports_network.set_external_ports(tcp_port, udp_port)
+                });
+            self.executor.spawn_with_graceful_shutdown_signal(|shutdown| port_mapper.run(shutdown));
+        }
+
         handle
     }
 }
@@ -1,8 +1,16 @@
 //! Types for launching execution extensions (ExEx).
-use futures::{future::BoxFuture, FutureExt};
-use reth_exex::ExExContext;
+use futures::{future::BoxFuture, FutureExt, StreamExt};
+use reth_beacon_consensus::hooks::{
+    EngineContext, EngineHook, EngineHookDBAccessLevel, EngineHookEvent,
+};
+use reth_exex::{ExExContext, ExExManagerHandle};
+use reth_interfaces::RethResult;
 use reth_node_api::FullNodeComponents;
-use std::future::Future;
+use std::{
+    future::Future,
+    task::{Context, Poll},
+};
+use tokio_stream::wrappers::WatchStream;
 
 /// A trait for launching an ExEx.
 trait LaunchExEx<Node: FullNodeComponents>: Send {
@@ -61,3 +69,65 @@ where
         self(ctx)
     }
 }
+
+/// An engine hook that holds back the engine while the ExEx manager isn't ready for new
+/// notifications, i.e. while all ExExes are too far behind the node.
+///
+/// The hook is running while the manager isn't ready. Since it needs write access to the database,
+/// the engine doesn't make new blocks canonical while it's running.
+#[derive(Debug)]
+pub(crate) struct ExExBackpressureHook {
+    /// Whether the manager is ready for new notifications.
+    ready: WatchStream<bool>,
+    /// The last readiness of the manager.
+    is_ready: bool,
+    /// Whether the hook is running.
+    is_running: bool,
+}
+
+impl ExExBackpressureHook {
+    /// Creates the hook for the manager of the given handle.
+    pub(crate) fn new(handle: &ExExManagerHandle) -> Self {
+        Self { ready: WatchStream::new(handle.ready_receiver()), is_ready: true, is_running: false }
+    }
+}
+
+impl EngineHook for ExExBackpressureHook {
+    fn name(&self) -> &'static str {
+        "ExExBackpressure"
+    }
+
+    fn poll(
+        &mut self,
+        cx: &mut Context<'_>,
+        _ctx: EngineContext,
+    ) -> Poll<RethResult<EngineHookEvent>> {
+        loop {
+            match self.ready.poll_next_unpin(cx) {
+                Poll::Ready(Some(is_ready)) => self.is_ready = is_ready,
+                // the manager is gone, so nothing holds back the engine anymore
+                Poll::Ready(None) => {
+                    self.is_ready = true;
+                    break
+                }
+                Poll::Pending => break,
+            }
+        }
+
+        match (self.is_running, self.is_ready) {
+            (false, false) => {
+                self.is_running = true;
+                Poll::Ready(Ok(EngineHookEvent::Started))
+            }
+            (true, true) => {
+                self.is_running = false;
+                Poll::Ready(Ok(EngineHookEvent::Finished(Ok(()))))
+            }
+            _ => Poll::Pending,
+        }
+    }
+
+    fn db_access_level(&self) -> EngineHookDBAccessLevel {
+        EngineHookDBAccessLevel::ReadWrite
+    }
+}
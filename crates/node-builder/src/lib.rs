@@ -24,6 +24,8 @@ pub use builder::*;
 mod handle;
 pub use handle::NodeHandle;
 
+mod reload;
+
 pub mod rpc;
 
 pub mod setup;
@@ -3,7 +3,7 @@ use crate::{
     rpc::{RethRpcServerHandles, RpcRegistry},
 };
 use reth_network::NetworkHandle;
-use reth_node_api::FullNodeComponents;
+use reth_node_api::{ConfigureEvm, FullNodeComponents};
 use reth_node_core::{
     dirs::{ChainPath, DataDirPath},
     node_config::NodeConfig,
@@ -38,6 +38,69 @@ pub trait Node<N>: NodeTypes + Clone {
     ) -> ComponentsBuilder<N, Self::PoolBuilder, Self::PayloadBuilder, Self::NetworkBuilder>;
 }
 
+/// A [Node] that replaces the EVM configuration of another [Node], but keeps its types and
+/// components.
+///
+/// The EVM configuration of the [NodeTypes] is the single place that defines how blocks and calls
+/// are executed. The same configuration is used by the pipeline and the blockchain tree to execute
+/// blocks, by the payload builder to build payloads and by the RPC to execute calls and traces, so
+/// replacing it changes the execution of all of them consistently.
+///
+/// # Example
+///
+/// ```ignore
+/// let handle = NodeBuilder::new(config)
+///     .with_database(db)
+///     .with_launch_context(executor, data_dir)
+///     .node(WithEvmConfig::new(EthereumNode::default(), MyEvmConfig::default()))
+///     .launch()
+///     .await?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct WithEvmConfig<Types, Evm> {
+    /// The node whose EVM configuration is replaced.
+    types: Types,
+    /// The EVM configuration of the node.
+    evm_config: Evm,
+}
+
+impl<Types, Evm> WithEvmConfig<Types, Evm> {
+    /// Replaces the EVM configuration of the given node.
+    pub const fn new(types: Types, evm_config: Evm) -> Self {
+        Self { types, evm_config }
+    }
+}
+
+impl<Types, Evm> NodeTypes for WithEvmConfig<Types, Evm>
+where
+    Types: NodeTypes,
+    Evm: ConfigureEvm + 'static,
+{
+    type Primitives = Types::Primitives;
+    type Engine = Types::Engine;
+    type Evm = Evm;
+
+    fn evm_config(&self) -> Self::Evm {
+        self.evm_config.clone()
+    }
+}
+
+impl<N, Types, Evm> Node<N> for WithEvmConfig<Types, Evm>
+where
+    Types: Node<N>,
+    Evm: ConfigureEvm + 'static,
+{
+    type PoolBuilder = Types::PoolBuilder;
+    type NetworkBuilder = Types::NetworkBuilder;
+    type PayloadBuilder = Types::PayloadBuilder;
+
+    fn components(
+        self,
+    ) -> ComponentsBuilder<N, Self::PoolBuilder, Self::PayloadBuilder, Self::NetworkBuilder> {
+        self.types.components()
+    }
+}
+
 /// The launched node with all components including RPC handlers.
 ///
 /// This can be used to interact with the launched node.
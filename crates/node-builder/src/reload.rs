@@ -0,0 +1,388 @@
+//! Reloading of the configuration file of a running node.
+//!
+//! The configuration file is reloaded on `SIGHUP` or with the `admin_reloadConfig` RPC method. Only
+//! the settings of the file that the node can change at runtime are applied, the others are
+//! reported as rejected until the node is restarted:
+//!
+//! - the pruning configuration, unless pruning is configured on the command line
+//! - the transaction limits and the minimum priority fee of the transaction pool
+//! - the peer limits and the trusted peers of the network
+//! - the rate limits of the RPC methods
+
+use crate::builder::load_config_file;
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use reth_config::{Config, PruneConfig};
+use reth_interfaces::reload::{ConfigReloadError, ConfigReloadRequest, ConfigReloadResult};
+use reth_network::NetworkHandle;
+use reth_network_api::{PeerKind, Peers};
+use reth_node_core::{
+    node_config::NodeConfig,
+    rpc::{
+        builder::rate_limit::RpcRateLimits,
+        types::{ConfigFieldChange, ConfigReloadReport},
+    },
+};
+use reth_primitives::PruneModes;
+use reth_tracing::tracing::{info, warn};
+use reth_transaction_pool::{PoolConfigUpdate, TransactionPool};
+use std::path::PathBuf;
+use tokio::sync::{mpsc, watch};
+
+/// The reason of a rejected setting that can't be changed at runtime.
+const REQUIRES_RESTART: &str = "requires a restart";
+
+/// The reason of a rejected setting that's overridden by a command line argument.
+const OVERRIDDEN: &str = "overridden by a command line argument";
+
+/// Reloads the configuration file and applies the changed settings to the running node.
+pub(crate) struct ConfigReloader<Pool> {
+    /// The configuration file and its active settings.
+    file: ConfigFile,
+    pool: Pool,
+    network: NetworkHandle,
+    rate_limits: RpcRateLimits,
+    prune_config: watch::Sender<PruneConfig>,
+}
+
+impl<Pool> ConfigReloader<Pool>
+where
+    Pool: TransactionPool,
+{
+    /// Creates a new reloader of the configuration file at the given path, which was loaded as
+    /// `active` on launch.
+    pub(crate) fn new(
+        config: NodeConfig,
+        path: PathBuf,
+        active: Config,
+        pool: Pool,
+        network: NetworkHandle,
+        rate_limits: RpcRateLimits,
+        prune_config: watch::Sender<PruneConfig>,
+    ) -> eyre::Result<Self> {
+        let cli_pruning = config.prune_config()?.is_some();
+        let file = ConfigFile { config, path, active, cli_pruning };
+        Ok(Self { file, pool, network, rate_limits, prune_config })
+    }
+
+    /// Reloads the configuration on every request and `SIGHUP`, until both the requests and the
+    /// signals end.
+    pub(crate) async fn run(mut self, mut requests: mpsc::UnboundedReceiver<ConfigReloadRequest>) {
+        let mut hangups = hangup_signals();
+        loop {
+            tokio::select! {
+                Some(request) = requests.recv() => {
+                    let _ = request.send(self.reload());
+                }
+                Some(()) = hangups.next() => {
+                    info!(target: "reth::cli", "Received SIGHUP");
+                    let _ = self.reload();
+                }
+                else => break,
+            }
+        }
+    }
+
+    /// Reloads the configuration file and applies the settings that changed since the last
+    /// reload.
+    ///
+    /// Rejected settings are not applied and reported again on the next reload.
+    fn reload(&mut self) -> ConfigReloadResult {
+        let (active, report) = self.file.reload()?;
+        self.apply(active);
+        Ok(report)
+    }
+
+    /// Applies the accepted settings to the subsystems of the node and makes them the active
+    /// configuration.
+    fn apply(&mut self, active: Config) {
+        let current = &self.file.active;
+        if active.prune != current.prune {
+            self.prune_config.send_replace(active.prune.clone().unwrap_or_default());
+        }
+
+        if active.txpool != current.txpool {
+            let pool_config = self.file.config.txpool.pool_config_with(&active.txpool);
+            self.pool.update_config(PoolConfigUpdate::from(&pool_config));
+        }
+
+        let (old_peers, new_peers) = (&current.peers, &active.peers);
+        if (old_peers.max_inbound(), old_peers.max_outbound()) !=
+            (new_peers.max_inbound(), new_peers.max_outbound())
+        {
+            let network_config = &self.file.config.network;
+            let peers = new_peers
+                .clone()
+                .with_max_inbound_opt(network_config.max_inbound_peers)
+                .with_max_outbound_opt(network_config.max_outbound_peers);
+            self.network.set_peer_limits(peers.max_inbound(), peers.max_outbound());
+        }
+        for node in old_peers.trusted_nodes.difference(&new_peers.trusted_nodes) {
+            if !new_peers.trusted_nodes.iter().any(|new| new.id == node.id) {
+                self.network.remove_peer(node.id, PeerKind::Trusted);
+            }
+        }
+        for node in new_peers.trusted_nodes.difference(&old_peers.trusted_nodes) {
+            self.network.add_trusted_peer(node.id, node.tcp_addr());
+        }
+
+        if active.rpc.rate_limits != current.rpc.rate_limits {
+            self.rate_limits.set_rules(active.rpc.rate_limits.clone());
+        }
+
+        self.file.active = active;
+    }
+}
+
+/// The configuration file of a running node.
+struct ConfigFile {
+    /// The command line configuration of the node, which takes precedence over the file.
+    config: NodeConfig,
+    /// The path of the configuration file.
+    path: PathBuf,
+    /// The settings of the configuration file that are applied to the node.
+    active: Config,
+    /// Whether the pruning is configured on the command line.
+    cli_pruning: bool,
+}
+
+impl ConfigFile {
+    /// Loads the configuration file and checks the settings that changed since the active
+    /// configuration.
+    ///
+    /// Returns the new active configuration with the accepted settings, and the report of all
+    /// changed settings.
+    fn reload(&self) -> Result<(Config, ConfigReloadReport), ConfigReloadError> {
+        let new = self.load().map_err(|err| {
+            warn!(target: "reth::cli", path = ?self.path, %err, "Failed to reload the config");
+            ConfigReloadError::Load(err.to_string())
+        })?;
+
+        let changed = self.active.changed_fields(&new);
+        let prune =
+            changed.iter().any(|field| in_section(field, "prune")).then(|| self.check_prune(&new));
+
+        let mut active = self.active.clone();
+        let mut fields = Vec::with_capacity(changed.len());
+        for field in changed {
+            let change = if self.is_overridden(&field) {
+                Err(OVERRIDDEN.to_string())
+            } else {
+                apply_field(&field, &new, &mut active, prune.as_ref())
+            };
+            let change = match change {
+                Ok(()) => {
+                    info!(target: "reth::cli", %field, "Configuration setting applied");
+                    ConfigFieldChange::accepted(field)
+                }
+                Err(reason) => {
+                    warn!(target: "reth::cli", %field, %reason, "Configuration setting rejected");
+                    ConfigFieldChange::rejected(field, reason)
+                }
+            };
+            fields.push(change);
+        }
+
+        info!(target: "reth::cli", path = ?self.path, fields = fields.len(), "Config reloaded");
+        Ok((active, ConfigReloadReport { path: self.path.display().to_string(), fields }))
+    }
+
+    /// Loads the configuration file.
+    ///
+    /// Unlike on launch, a missing file is an error. Loading it would create a default file and
+    /// apply its settings.
+    fn load(&self) -> eyre::Result<Config> {
+        if !self.path.is_file() {
+            eyre::bail!("config file {:?} does not exist", self.path)
+        }
+        load_config_file(&self.config, &self.path)
+    }
+
+    /// Returns true if the setting is overridden by a command line argument.
+    fn is_overridden(&self, field: &str) -> bool {
+        let txpool = &self.config.txpool;
+        let network = &self.config.network;
+        match field {
+            field if in_section(field, "prune") => self.cli_pruning,
+            "txpool.max_txs_per_sender" => txpool.max_per_sender.is_some(),
+            "txpool.max_local_txs" => txpool.max_local.is_some(),
+            "txpool.max_external_txs" => txpool.max_external.is_some(),
+            "txpool.max_private_txs" => txpool.max_private.is_some(),
+            "txpool.minimum_priority_fee" => txpool.minimum_priority_fee.is_some(),
+            "peers.connection_info.max_inbound" => network.max_inbound_peers.is_some(),
+            "peers.connection_info.max_outbound" => network.max_outbound_peers.is_some(),
+            _ => false,
+        }
+    }
+
+    /// Checks whether the pruning configuration of the file can be applied, which is accepted or
+    /// rejected as a whole.
+    ///
+    /// The stages and the RPC keep the segments the node was launched with, so only the
+    /// distances of the pruned segments can change.
+    fn check_prune(&self, new: &Config) -> Result<(), String> {
+        let current = self.active.prune.clone().unwrap_or_default().segments;
+        let segments = new.prune.clone().unwrap_or_default().segments;
+        segments.validate().map_err(|err| format!("invalid prune configuration: {err}"))?;
+
+        if segments.receipts_log_filter != current.receipts_log_filter {
+            return Err(format!("receipts_log_filter {REQUIRES_RESTART}"))
+        }
+        let kinds = |modes: &PruneModes| {
+            [
+                modes.sender_recovery,
+                modes.transaction_lookup,
+                modes.receipts,
+                modes.account_history,
+                modes.storage_history,
+                modes.call_traces,
+            ]
+            .map(|mode| mode.map(|mode| mode.is_full()))
+        };
+        let (kinds, current_kinds) = (kinds(&segments), kinds(&current));
+        if kinds.iter().zip(&current_kinds).any(|(new, current)| new.is_some() != current.is_some())
+        {
+            return Err(format!("adding or removing a pruned segment {REQUIRES_RESTART}"))
+        }
+        if kinds != current_kinds {
+            return Err(format!("switching a segment to or from full pruning {REQUIRES_RESTART}"))
+        }
+        Ok(())
+    }
+}
+
+/// Copies a changed setting of the new configuration to the active configuration if it can be
+/// changed at runtime.
+fn apply_field(
+    field: &str,
+    new: &Config,
+    active: &mut Config,
+    prune: Option<&Result<(), String>>,
+) -> Result<(), String> {
+    match field {
+        field if in_section(field, "prune") => {
+            prune.cloned().unwrap_or(Ok(()))?;
+            active.prune = new.prune.clone();
+        }
+        "txpool.max_txs_per_sender" => {
+            active.txpool.max_txs_per_sender = new.txpool.max_txs_per_sender
+        }
+        "txpool.max_local_txs" => active.txpool.max_local_txs = new.txpool.max_local_txs,
+        "txpool.max_external_txs" => active.txpool.max_external_txs = new.txpool.max_external_txs,
+        "txpool.max_private_txs" => active.txpool.max_private_txs = new.txpool.max_private_txs,
+        "txpool.minimum_priority_fee" => {
+            active.txpool.minimum_priority_fee = new.txpool.minimum_priority_fee
+        }
+        "peers.connection_info.max_inbound" => {
+            active.peers =
+                std::mem::take(&mut active.peers).with_max_inbound(new.peers.max_inbound())
+        }
+        "peers.connection_info.max_outbound" => {
+            active.peers =
+                std::mem::take(&mut active.peers).with_max_outbound(new.peers.max_outbound())
+        }
+        "peers.trusted_nodes" => active.peers.trusted_nodes = new.peers.trusted_nodes.clone(),
+        field if in_section(field, "rpc.rate-limits") => {
+            active.rpc.rate_limits = new.rpc.rate_limits.clone()
+        }
+        _ => return Err(REQUIRES_RESTART.to_string()),
+    }
+    Ok(())
+}
+
+/// Returns true if the dotted path of the setting is the section or one of its settings.
+fn in_section(field: &str, section: &str) -> bool {
+    field.strip_prefix(section).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// Returns the `SIGHUP` signals received by the process.
+#[cfg(unix)]
+fn hangup_signals() -> BoxStream<'static, ()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::hangup()) {
+        Ok(signal) => stream::unfold(signal, |mut signal| async move {
+            signal.recv().await.map(|()| ((), signal))
+        })
+        .boxed(),
+        Err(err) => {
+            warn!(target: "reth::cli", %err, "Failed to listen for SIGHUP");
+            stream::empty().boxed()
+        }
+    }
+}
+
+/// Returns the `SIGHUP` signals received by the process, which are not supported on this
+/// platform.
+#[cfg(not(unix))]
+fn hangup_signals() -> BoxStream<'static, ()> {
+    stream::empty().boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::PruneMode;
+
+    fn config_file(dir: &tempfile::TempDir, active: Config) -> ConfigFile {
+        let path = dir.path().join("reth.toml");
+        ConfigFile { config: NodeConfig::default(), path, active, cli_pruning: false }
+    }
+
+    fn with_prune(segments: PruneModes) -> Config {
+        Config { prune: Some(PruneConfig { segments, ..Default::default() }), ..Default::default() }
+    }
+
+    #[test]
+    fn reload_fails_without_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = config_file(&dir, Config::default());
+        assert!(matches!(file.reload(), Err(ConfigReloadError::Load(_))));
+        // no default config file was written
+        assert!(!file.path.exists());
+    }
+
+    #[test]
+    fn reload_applies_runtime_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = config_file(&dir, Config::default());
+
+        let mut new = Config::default();
+        new.txpool.max_local_txs = Some(10);
+        new.stages.headers.commit_threshold += 1;
+        confy::store_path(&file.path, &new).unwrap();
+
+        let (active, report) = file.reload().unwrap();
+        assert_eq!(active.txpool, new.txpool);
+        assert_eq!(active.stages, Config::default().stages);
+        assert_eq!(
+            report.fields,
+            vec![
+                ConfigFieldChange::rejected("stages.headers.commit_threshold", REQUIRES_RESTART),
+                ConfigFieldChange::accepted("txpool.max_local_txs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn prune_segments_are_kept() {
+        let dir = tempfile::tempdir().unwrap();
+        let modes = PruneModes { receipts: Some(PruneMode::Distance(100)), ..PruneModes::none() };
+        let file = config_file(&dir, with_prune(modes.clone()));
+
+        // the distance of a pruned segment can change
+        let new = PruneModes { receipts: Some(PruneMode::Distance(200)), ..modes.clone() };
+        assert_eq!(file.check_prune(&with_prune(new)), Ok(()));
+
+        let added = PruneModes { sender_recovery: Some(PruneMode::Distance(100)), ..modes.clone() };
+        assert!(file.check_prune(&with_prune(added)).unwrap_err().contains("adding or removing"));
+        let removed = PruneModes { receipts: None, ..modes.clone() };
+        assert!(file.check_prune(&with_prune(removed)).unwrap_err().contains("adding or removing"));
+        assert!(file.check_prune(&Config::default()).unwrap_err().contains("adding or removing"));
+
+        let full = PruneModes { receipts: Some(PruneMode::Full), ..modes };
+        assert!(file.check_prune(&with_prune(full)).unwrap_err().contains("full pruning"));
+    }
+}
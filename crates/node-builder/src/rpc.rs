@@ -1,21 +1,29 @@
 //! Builder support for rpc components.
 
 use futures::TryFutureExt;
+use reth_config::config::RpcConfig;
+use reth_interfaces::{
+    invalid_block::InvalidBlocksHandle, reload::ConfigReloadHandle, sync::SyncStatusHandle,
+};
 use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
 use reth_node_core::{
     cli::config::RethRpcConfig,
     node_config::NodeConfig,
     rpc::{
-        api::EngineApiServer,
+        api::{AdminConfigApiServer, EngineApiServer},
         builder::{
             auth::{AuthRpcModule, AuthServerHandle},
-            RethModuleRegistry, RpcModuleBuilder, RpcServerHandle, TransportRpcModules,
+            rate_limit::RpcRateLimits,
+            response_cache::ResponseCache,
+            RethModuleRegistry, RethRpcModule, RpcModuleBuilder, RpcServerHandle,
+            TransportRpcModules,
         },
     },
 };
 use reth_payload_builder::PayloadBuilderHandle;
-use reth_rpc::JwtSecret;
+use reth_primitives::PruneModes;
+use reth_rpc::{AdminConfigApi, JwtSecret};
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, info};
 use std::{
@@ -206,7 +214,9 @@ pub struct RpcContext<'a, Node: FullNodeComponents> {
     /// Holds installed modules per transport type.
     ///
     /// This can be used to merge additional modules into the configured transports (http, ipc,
-    /// ws). See [TransportRpcModules::merge_configured]
+    /// ws). See [TransportRpcModules::merge_configured], or
+    /// [TransportRpcModules::merge_if_module_configured] for custom namespaces that are only
+    /// installed if they're selected, e.g. with `--http.api`.
     pub modules: &'a mut TransportRpcModules,
     /// Holds jwt authenticated rpc module.
     ///
@@ -244,16 +254,30 @@ impl<'a, Node: FullNodeComponents> RpcContext<'a, Node> {
     pub fn payload_builder(&self) -> &PayloadBuilderHandle<Node::Engine> {
         self.node.payload_builder()
     }
+
+    /// Returns the task executor of the node.
+    pub fn task_executor(&self) -> &TaskExecutor {
+        self.node.task_executor()
+    }
 }
 
 /// Launch the rpc servers.
+///
+/// Returns the rate limits of the RPC methods, which can be replaced when the config file is
+/// reloaded with the `admin_reloadConfig` method of the given handle.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn launch_rpc_servers<Node, Engine>(
     node: Node,
     engine_api: Engine,
     config: &NodeConfig,
+    rpc_config: &RpcConfig,
     jwt_secret: JwtSecret,
+    sync_status: SyncStatusHandle,
+    invalid_blocks: InvalidBlocksHandle,
+    prune_modes: PruneModes,
+    config_reload: ConfigReloadHandle,
     hooks: RpcHooks<Node>,
-) -> eyre::Result<(RethRpcServerHandles, RpcRegistry<Node>)>
+) -> eyre::Result<(RethRpcServerHandles, RpcRegistry<Node>, RpcRateLimits)>
 where
     Node: FullNodeComponents + Clone,
     Engine: EngineApiServer<Node::Engine>,
@@ -271,8 +295,17 @@ where
         .with_events(node.provider().clone())
         .with_executor(node.task_executor().clone())
         .with_evm_config(node.evm_config())
+        .with_sync_status(sync_status)
+        .with_invalid_blocks(invalid_blocks)
+        .with_prune_modes(prune_modes)
         .build_with_auth_server(module_config, engine_api);
 
+    // reloading the config changes the node, so it's only available with the `admin` namespace
+    modules.merge_if_module_configured(
+        RethRpcModule::Admin,
+        AdminConfigApi::new(config_reload).into_rpc(),
+    )?;
+
     let mut registry = RpcRegistry { registry };
     let ctx = RpcContext {
         node: node.clone(),
@@ -284,7 +317,32 @@ where
 
     extend_rpc_modules.extend_rpc_modules(ctx)?;
 
-    let server_config = config.rpc.rpc_server_config();
+    // a selected custom module that no extension installed is most likely misspelled
+    let unknown_modules = modules.unknown_modules();
+    if !unknown_modules.is_empty() {
+        eyre::bail!(
+            "unknown RPC modules: {}",
+            unknown_modules.iter().map(|module| module.as_str()).collect::<Vec<_>>().join(", ")
+        )
+    }
+
+    let mut server_config = config
+        .rpc
+        .rpc_server_config()
+        .with_rate_limits(rpc_config.rate_limits.clone())
+        .with_resumable_unsubscribes(registry.eth_handlers().pubsub.resumable_unsubscribes());
+    if let Some(cache_config) = config.rpc.response_cache_config() {
+        let method_limits = rpc_config
+            .response_cache_limits
+            .iter()
+            .map(|(method, mb)| (method.clone(), mb.saturating_mul(1024 * 1024) as usize));
+        let cache = ResponseCache::new(
+            cache_config.with_method_limits(method_limits),
+            node.provider().clone(),
+        );
+        server_config = server_config.with_response_cache(cache);
+    }
+    let rate_limits = server_config.rate_limits();
     let launch_rpc = modules.clone().start_server(server_config).map_ok(|handle| {
         if let Some(url) = handle.ipc_endpoint() {
             info!(target: "reth::cli", url=%url, "RPC IPC server started");
@@ -323,5 +381,5 @@ where
 
     on_rpc_started.on_rpc_started(ctx, handles.clone())?;
 
-    Ok((handles, registry))
+    Ok((handles, registry, rate_limits))
 }
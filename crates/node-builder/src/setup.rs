@@ -7,6 +7,7 @@ use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
+use reth_exex::ExExManagerHandle;
 use reth_interfaces::{
     consensus::Consensus,
     p2p::{
@@ -16,7 +17,7 @@ use reth_interfaces::{
 };
 use reth_node_core::{
     node_config::NodeConfig,
-    primitives::{BlockNumber, B256},
+    primitives::{stage::StageId, BlockNumber, B256},
 };
 use reth_provider::{HeaderSyncMode, ProviderFactory};
 use reth_revm::stack::{Hook, InspectorStackConfig};
@@ -24,13 +25,13 @@ use reth_stages::{
     prelude::DefaultStages,
     stages::{
         AccountHashingStage, ExecutionStage, ExecutionStageThresholds, IndexAccountHistoryStage,
-        IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage, StorageHashingStage,
-        TransactionLookupStage,
+        IndexCallTracesStage, IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage,
+        StorageHashingStage, TransactionLookupStage,
     },
     Pipeline, StageSet,
 };
 use reth_static_file::StaticFileProducer;
-use reth_tasks::TaskExecutor;
+use reth_tasks::{shutdown::ShutdownPhase, TaskExecutor};
 use reth_tracing::tracing::debug;
 use std::sync::Arc;
 use tokio::sync::watch;
@@ -49,6 +50,7 @@ pub async fn build_networked_pipeline<DB, Client, EvmConfig>(
     max_block: Option<BlockNumber>,
     static_file_producer: StaticFileProducer<DB>,
     evm_config: EvmConfig,
+    exex_manager: Option<ExExManagerHandle>,
 ) -> eyre::Result<Pipeline<DB>>
 where
     DB: Database + Unpin + Clone + 'static,
@@ -64,7 +66,7 @@ where
         .build(client, Arc::clone(&consensus), provider_factory.clone())
         .into_task_with(task_executor);
 
-    let pipeline = build_pipeline(
+    let mut pipeline = build_pipeline(
         node_config,
         provider_factory,
         config,
@@ -76,9 +78,13 @@ where
         prune_config,
         static_file_producer,
         evm_config,
+        exex_manager,
     )
     .await?;
 
+    // stop the pipeline at the next stage boundary when the node shuts down
+    pipeline.set_graceful_shutdown(task_executor.graceful_shutdown_signal(ShutdownPhase::Pipeline));
+
     Ok(pipeline)
 }
 
@@ -96,6 +102,7 @@ pub async fn build_pipeline<DB, H, B, EvmConfig>(
     prune_config: Option<PruneConfig>,
     static_file_producer: StaticFileProducer<DB>,
     evm_config: EvmConfig,
+    exex_manager: Option<ExExManagerHandle>,
 ) -> eyre::Result<Pipeline<DB>>
 where
     DB: Database + Clone + 'static,
@@ -111,7 +118,8 @@ where
     }
 
     let (tip_tx, tip_rx) = watch::channel(B256::ZERO);
-    let factory = reth_revm::EvmProcessorFactory::new(node_config.chain.clone(), evm_config);
+    let factory =
+        reth_revm::EvmProcessorFactory::new(node_config.chain.clone(), evm_config.clone());
 
     let stack_config = InspectorStackConfig {
         use_printer_tracer: node_config.debug.print_inspector,
@@ -135,6 +143,30 @@ where
     } else {
         HeaderSyncMode::Tip(tip_rx)
     };
+
+    let mut execution_stage = ExecutionStage::new(
+        factory.clone(),
+        ExecutionStageThresholds {
+            max_blocks: stage_config.execution.max_blocks,
+            max_changes: stage_config.execution.max_changes,
+            max_cumulative_gas: stage_config.execution.max_cumulative_gas,
+            max_duration: stage_config.execution.max_duration,
+        },
+        stage_config
+            .merkle
+            .clean_threshold
+            .max(stage_config.account_hashing.clean_threshold)
+            .max(stage_config.storage_hashing.clean_threshold),
+        prune_modes.clone(),
+    )
+    .with_receipts_verification(stage_config.execution.verify_receipts_from())
+    .with_call_addresses(stage_config.index_call_traces.enabled)
+    .with_metrics_tx(metrics_tx);
+    if let Some(exex_manager) = exex_manager {
+        // wait for the exexs to catch up before executing more blocks
+        execution_stage = execution_stage.with_exex_manager_ready(exex_manager.ready_receiver());
+    }
+
     let pipeline = builder
         .with_tip_sender(tip_tx)
         .with_metrics_tx(metrics_tx.clone())
@@ -145,30 +177,16 @@ where
                 Arc::clone(&consensus),
                 header_downloader,
                 body_downloader,
-                factory.clone(),
+                factory,
                 stage_config.etl.clone(),
             )
             .set(SenderRecoveryStage {
-                commit_threshold: stage_config.sender_recovery.commit_threshold,
+                // Pruned senders are removed from the database, but not from static files
+                static_files: stage_config.sender_recovery.static_files &&
+                    prune_modes.sender_recovery.is_none(),
+                ..SenderRecoveryStage::new_with_config(stage_config.sender_recovery)
             })
-            .set(
-                ExecutionStage::new(
-                    factory,
-                    ExecutionStageThresholds {
-                        max_blocks: stage_config.execution.max_blocks,
-                        max_changes: stage_config.execution.max_changes,
-                        max_cumulative_gas: stage_config.execution.max_cumulative_gas,
-                        max_duration: stage_config.execution.max_duration,
-                    },
-                    stage_config
-                        .merkle
-                        .clean_threshold
-                        .max(stage_config.account_hashing.clean_threshold)
-                        .max(stage_config.storage_hashing.clean_threshold),
-                    prune_modes.clone(),
-                )
-                .with_metrics_tx(metrics_tx),
-            )
+            .set(execution_stage)
             .set(AccountHashingStage::new(
                 stage_config.account_hashing.clean_threshold,
                 stage_config.account_hashing.commit_threshold,
@@ -179,7 +197,10 @@ where
                 stage_config.storage_hashing.commit_threshold,
                 stage_config.etl.clone(),
             ))
-            .set(MerkleStage::new_execution(stage_config.merkle.clean_threshold))
+            .set(MerkleStage::new_execution_with_parallel_storage_roots(
+                stage_config.merkle,
+                provider_factory.clone(),
+            ))
             .set(TransactionLookupStage::new(
                 stage_config.transaction_lookup.chunk_size,
                 stage_config.etl.clone(),
@@ -194,7 +215,17 @@ where
                 stage_config.index_storage_history.commit_threshold,
                 prune_modes.storage_history,
                 stage_config.etl.clone(),
-            )),
+            ))
+            .add_before(
+                IndexCallTracesStage::new(
+                    node_config.chain.clone(),
+                    evm_config,
+                    stage_config.index_call_traces.commit_threshold,
+                    prune_modes.call_traces,
+                ),
+                StageId::Finish,
+            )
+            .disable_if(StageId::IndexCallTraces, || !stage_config.index_call_traces.enabled),
         )
         .build(provider_factory, static_file_producer);
 
@@ -6,6 +6,27 @@ use reth_interfaces::db::LogLevel;
 use crate::version::default_client_version;
 
 /// Parameters for database configuration
+///
+/// The latency and value sizes of the database operations are recorded per table with
+/// `--db.metrics`:
+///
+/// ```
+/// use clap::Parser;
+/// use reth_node_core::args::DatabaseArgs;
+///
+/// #[derive(Parser)]
+/// struct Cli {
+///     #[command(flatten)]
+///     db: DatabaseArgs,
+/// }
+///
+/// let cli = Cli::parse_from(["reth", "--db.metrics"]);
+/// assert!(cli.db.metrics);
+///
+/// // the database opened with these arguments records the histograms once its metrics are
+/// // enabled with `DatabaseEnv::with_metrics`
+/// let args = cli.db.database_args();
+/// ```
 #[derive(Debug, Args, PartialEq, Eq, Default, Clone, Copy)]
 #[command(next_help_heading = "Database")]
 pub struct DatabaseArgs {
@@ -16,6 +37,12 @@ pub struct DatabaseArgs {
     /// NFS volume.
     #[arg(long = "db.exclusive")]
     pub exclusive: Option<bool>,
+    /// Record histograms of the latency and value sizes of the database operations per table.
+    ///
+    /// The histograms are exported by the metrics endpoint, at the cost of a small overhead on
+    /// every database operation.
+    #[arg(long = "db.metrics", verbatim_doc_comment)]
+    pub metrics: bool,
 }
 
 impl DatabaseArgs {
@@ -24,6 +51,7 @@ impl DatabaseArgs {
         reth_db::mdbx::DatabaseArguments::new(default_client_version())
             .with_log_level(self.log_level)
             .with_exclusive(self.exclusive)
+            .with_operation_latency_metrics(self.metrics)
     }
 }
 
@@ -45,4 +73,10 @@ mod tests {
         let args = CommandParser::<DatabaseArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn test_parse_database_metrics() {
+        let args = CommandParser::<DatabaseArgs>::parse_from(["reth", "--db.metrics"]).args;
+        assert!(args.metrics);
+    }
 }
@@ -1,8 +1,9 @@
 //! clap [Args](clap::Args) for debugging purposes
 
-use clap::Args;
+use clap::{Args, ValueEnum};
+use humantime::parse_duration;
 use reth_primitives::{TxHash, B256};
-use std::path::PathBuf;
+use std::{path::PathBuf, time::Duration};
 
 /// Parameters for debugging purposes
 #[derive(Debug, Clone, Args, PartialEq, Eq, Default)]
@@ -24,6 +25,30 @@ pub struct DebugArgs {
     #[arg(long = "debug.tip", help_heading = "Debug", conflicts_with = "continuous")]
     pub tip: Option<B256>,
 
+    /// The URL of a reference node whose chain is followed instead of a consensus layer.
+    ///
+    /// The head of the reference node is polled over RPC and sent to the engine as forkchoice
+    /// updates, so the node keeps following the reference node, including its reorgs.
+    ///
+    /// NOTE: This is for testing purposes only.
+    #[arg(
+        long = "debug.rpc-consensus-url",
+        help_heading = "Debug",
+        value_name = "URL",
+        conflicts_with_all = ["tip", "continuous"]
+    )]
+    pub rpc_consensus_url: Option<String>,
+
+    /// The block of the reference node that's followed with `--debug.rpc-consensus-url`.
+    #[arg(
+        long = "debug.follow",
+        help_heading = "Debug",
+        value_enum,
+        default_value_t = FollowBlock::Latest,
+        requires = "rpc_consensus_url"
+    )]
+    pub follow: FollowBlock,
+
     /// Runs the sync only up to the specified block.
     #[arg(long = "debug.max-block", help_heading = "Debug")]
     pub max_block: Option<u64>,
@@ -64,6 +89,69 @@ pub struct DebugArgs {
     /// will be written to specified location.
     #[arg(long = "debug.engine-api-store", help_heading = "Debug", value_name = "PATH")]
     pub engine_api_store: Option<PathBuf>,
+
+    /// The directory to journal engine API messages to.
+    ///
+    /// If specified, every `engine_newPayload` and `engine_forkchoiceUpdated` call is appended
+    /// to rotating files in this directory, together with the response of the engine. The
+    /// journal can be replayed with `reth debug replay-engine --journal`.
+    #[arg(long = "engine.persist-messages", help_heading = "Debug", value_name = "DIR")]
+    pub engine_persist_messages: Option<PathBuf>,
+
+    /// The maximum number of messages per engine message journal file.
+    ///
+    /// At least this many of the most recent messages are kept. Defaults to 10000.
+    #[arg(
+        long = "engine.persist-messages-max",
+        help_heading = "Debug",
+        value_name = "COUNT",
+        requires = "engine_persist_messages"
+    )]
+    pub engine_persist_messages_max: Option<usize>,
+
+    /// The maximum number of invalid blocks that are persisted, so they're still rejected and
+    /// served by `debug_getBadBlocks` after a restart.
+    ///
+    /// The oldest blocks are forgotten first. `0` disables the persistence. Defaults to 128.
+    #[arg(long = "engine.invalid-blocks-max", help_heading = "Debug", value_name = "COUNT")]
+    pub engine_invalid_blocks_max: Option<usize>,
+
+    /// The age after which a persisted invalid block is forgotten. Defaults to 7 days.
+    ///
+    /// Parses strings using [humantime::parse_duration]
+    /// --engine.invalid-blocks-max-age 24h
+    #[arg(
+        long = "engine.invalid-blocks-max-age",
+        help_heading = "Debug",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        verbatim_doc_comment
+    )]
+    pub engine_invalid_blocks_max_age: Option<Duration>,
+
+    /// The processing time above which a block is reported as slow, together with the slowest
+    /// of the recently processed blocks and the time spent in each of its processing phases.
+    ///
+    /// Parses strings using [humantime::parse_duration]
+    /// --debug.slow-block-threshold 500ms
+    #[arg(
+        long = "debug.slow-block-threshold",
+        help_heading = "Debug",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        verbatim_doc_comment
+    )]
+    pub slow_block_threshold: Option<Duration>,
+}
+
+/// The block of the reference node that's followed with `--debug.rpc-consensus-url`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum FollowBlock {
+    /// The latest block, which may be reorged.
+    #[default]
+    Latest,
+    /// The finalized block.
+    Finalized,
 }
 
 #[cfg(test)]
@@ -84,4 +172,79 @@ mod tests {
         let args = CommandParser::<DebugArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn test_parse_engine_persist_messages_args() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--engine.persist-messages",
+            "engine-messages",
+            "--engine.persist-messages-max",
+            "100",
+        ])
+        .args;
+        assert_eq!(args.engine_persist_messages, Some(PathBuf::from("engine-messages")));
+        assert_eq!(args.engine_persist_messages_max, Some(100));
+
+        assert!(CommandParser::<DebugArgs>::try_parse_from([
+            "reth",
+            "--engine.persist-messages-max",
+            "100"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_rpc_consensus_args() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.rpc-consensus-url",
+            "http://localhost:8545",
+            "--debug.follow",
+            "finalized",
+        ])
+        .args;
+        assert_eq!(args.rpc_consensus_url.as_deref(), Some("http://localhost:8545"));
+        assert_eq!(args.follow, FollowBlock::Finalized);
+
+        assert!(CommandParser::<DebugArgs>::try_parse_from([
+            "reth",
+            "--debug.follow",
+            "finalized"
+        ])
+        .is_err());
+        assert!(CommandParser::<DebugArgs>::try_parse_from([
+            "reth",
+            "--debug.rpc-consensus-url",
+            "http://localhost:8545",
+            "--debug.tip",
+            "0x0000000000000000000000000000000000000000000000000000000000000001"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_slow_block_threshold_args() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--debug.slow-block-threshold",
+            "500ms",
+        ])
+        .args;
+        assert_eq!(args.slow_block_threshold, Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_parse_engine_invalid_blocks_args() {
+        let args = CommandParser::<DebugArgs>::parse_from([
+            "reth",
+            "--engine.invalid-blocks-max",
+            "16",
+            "--engine.invalid-blocks-max-age",
+            "24h",
+        ])
+        .args;
+        assert_eq!(args.engine_invalid_blocks_max, Some(16));
+        assert_eq!(args.engine_invalid_blocks_max_age, Some(Duration::from_secs(24 * 60 * 60)));
+    }
 }
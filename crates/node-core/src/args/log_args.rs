@@ -69,6 +69,12 @@ pub struct LogArgs {
     /// The verbosity settings for the tracer.
     #[command(flatten)]
     pub verbosity: Verbosity,
+
+    /// Whether the logs for stdout are written to stderr instead.
+    ///
+    /// Set by commands that print their output to stdout, so the logs don't interleave with it.
+    #[arg(skip)]
+    pub log_stdout_to_stderr: bool,
 }
 
 impl LogArgs {
@@ -98,6 +104,10 @@ impl LogArgs {
         let stdout = self.layer(self.log_stdout_format, self.log_stdout_filter.clone(), true);
         tracer = tracer.with_stdout(stdout);
 
+        if self.log_stdout_to_stderr {
+            tracer = tracer.with_stdout_to_stderr();
+        }
+
         if self.journald {
             tracer = tracer.with_journald(self.journald_filter.clone());
         }
@@ -14,7 +14,7 @@ pub use rpc_state_cache_args::RpcStateCacheArgs;
 
 /// DebugArgs struct for debugging purposes
 mod debug_args;
-pub use debug_args::DebugArgs;
+pub use debug_args::{DebugArgs, FollowBlock};
 
 /// DatabaseArgs struct for configuring the database
 mod database_args;
@@ -33,7 +33,7 @@ pub use payload_builder_args::PayloadBuilderArgs;
 
 /// Stage related arguments
 mod stage_args;
-pub use stage_args::StageEnum;
+pub use stage_args::{StageArgs, StageEnum, VerifyReceipts};
 
 /// Gas price oracle related arguments
 mod gas_price_oracle_args;
@@ -51,6 +51,18 @@ pub use dev_args::DevArgs;
 mod pruning_args;
 pub use pruning_args::PruningArgs;
 
+/// StaticFilesArgs for configuring the static file producer
+mod static_files_args;
+pub use static_files_args::StaticFilesArgs;
+
+/// TreeArgs for configuring the blockchain tree
+mod tree_args;
+pub use tree_args::TreeArgs;
+
+/// ShutdownArgs for configuring the graceful shutdown
+mod shutdown_args;
+pub use shutdown_args::ShutdownArgs;
+
 pub mod utils;
 
 pub mod types;
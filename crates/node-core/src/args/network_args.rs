@@ -2,6 +2,7 @@
 
 use crate::version::P2P_CLIENT_VERSION;
 use clap::Args;
+use humantime::parse_duration;
 use reth_config::Config;
 use reth_discv4::{
     DEFAULT_DISCOVERY_ADDR, DEFAULT_DISCOVERY_PORT, DEFAULT_DISCOVERY_V5_ADDR,
@@ -9,6 +10,11 @@ use reth_discv4::{
 };
 use reth_net_nat::NatResolver;
 use reth_network::{
+    eth_requests::{
+        EthRequestsConfig, DEFAULT_MAX_BYTES_PER_PEER_PER_SECOND, DEFAULT_MAX_BYTES_PER_SECOND,
+        DEFAULT_MAX_QUEUED_REQUESTS, DEFAULT_MAX_REQUESTS_PER_PEER,
+    },
+    peers::DEFAULT_MAX_PERSISTED_PEERS,
     transactions::{
         TransactionFetcherConfig, TransactionsManagerConfig,
         DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
@@ -18,7 +24,8 @@ use reth_network::{
 };
 use reth_primitives::{mainnet_nodes, ChainSpec, NodeRecord};
 use secp256k1::SecretKey;
-use std::{net::IpAddr, path::PathBuf, sync::Arc};
+use std::{net::IpAddr, path::PathBuf, sync::Arc, time::Duration};
+use tracing::warn;
 
 /// Parameters for configuring the network more granularity via CLI
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
@@ -44,11 +51,31 @@ pub struct NetworkArgs {
     #[arg(long, value_delimiter = ',')]
     pub bootnodes: Option<Vec<NodeRecord>>,
 
-    /// The path to the known peers file. Connected peers are dumped to this file on nodes
-    /// shutdown, and read on startup. Cannot be used with `--no-persist-peers`.
+    /// The path to the known peers file. Known peers, with their reputation and info about their
+    /// last session, are dumped to this file on nodes shutdown and periodically, and read on
+    /// startup. Cannot be used with `--no-persist-peers`.
     #[arg(long, value_name = "FILE", verbatim_doc_comment, conflicts_with = "no_persist_peers")]
     pub peers_file: Option<PathBuf>,
 
+    /// The maximum number of peers to write to the known peers file. The least recently seen
+    /// peers are evicted first.
+    #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_MAX_PERSISTED_PEERS)]
+    pub peers_file_max_entries: usize,
+
+    /// How often to write the known peers file while the node is running. `0s` only writes the
+    /// file on shutdown.
+    ///
+    /// Parses strings using [humantime::parse_duration]
+    /// --peers-file-interval 10m
+    #[arg(
+        long,
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        default_value = "10m",
+        verbatim_doc_comment
+    )]
+    pub peers_file_interval: Duration,
+
     /// Custom node identity
     #[arg(long, value_name = "IDENTITY", default_value = P2P_CLIENT_VERSION)]
     pub identity: String,
@@ -64,7 +91,10 @@ pub struct NetworkArgs {
     #[arg(long, verbatim_doc_comment)]
     pub no_persist_peers: bool,
 
-    /// NAT resolution method (any|none|upnp|publicip|extip:\<IP\>)
+    /// NAT resolution method (any|none|upnp|pmp|publicip|extip:\<IP\>)
+    ///
+    /// With upnp or pmp, the RLPx and discovery ports are also mapped on the gateway. pmp finds
+    /// the gateway only on Linux.
     #[arg(long, default_value = "any")]
     pub nat: NatResolver,
 
@@ -84,6 +114,18 @@ pub struct NetworkArgs {
     #[arg(long)]
     pub max_inbound_peers: Option<usize>,
 
+    /// Maximum number of connected peers per /24 IPv4 or /48 IPv6 subnet.
+    ///
+    /// This applies to both outbound and inbound connections, trusted peers are exempt.
+    /// Unlimited by default.
+    #[arg(
+        id = "network.max-peers-per-subnet",
+        long = "network.max-peers-per-subnet",
+        value_name = "COUNT",
+        verbatim_doc_comment
+    )]
+    pub max_peers_per_subnet: Option<usize>,
+
     /// Soft limit for the byte size of a `PooledTransactions` response on assembling a
     /// `GetPooledTransactions` request. Spec'd at 2 MiB.
     ///
@@ -98,6 +140,46 @@ pub struct NetworkArgs {
     /// is 128 KiB.
     #[arg(long = "pooled-tx-pack-soft-limit", value_name = "BYTES", default_value_t = DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ)]
     pub soft_limit_byte_size_pooled_transactions_response_on_pack_request: usize,
+
+    /// Maximum number of `eth` requests of a peer that are queued or served at once.
+    ///
+    /// Requests beyond the limit are answered with an empty response.
+    #[arg(
+        id = "eth-requests.max-per-peer",
+        long = "eth-requests.max-per-peer",
+        value_name = "COUNT",
+        default_value_t = DEFAULT_MAX_REQUESTS_PER_PEER
+    )]
+    pub max_eth_requests_per_peer: usize,
+
+    /// Maximum number of bytes that are served to a peer per second.
+    ///
+    /// Requests beyond the budget are queued and answered with partial responses.
+    #[arg(
+        id = "eth-requests.max-peer-bytes-per-second",
+        long = "eth-requests.max-peer-bytes-per-second",
+        value_name = "BYTES",
+        default_value_t = DEFAULT_MAX_BYTES_PER_PEER_PER_SECOND
+    )]
+    pub max_eth_request_bytes_per_peer_per_second: usize,
+
+    /// Maximum number of bytes that are read per second to serve the `eth` requests of all peers.
+    #[arg(
+        id = "eth-requests.max-bytes-per-second",
+        long = "eth-requests.max-bytes-per-second",
+        value_name = "BYTES",
+        default_value_t = DEFAULT_MAX_BYTES_PER_SECOND
+    )]
+    pub max_eth_request_bytes_per_second: usize,
+
+    /// Maximum number of `eth` requests of all peers that are queued at once.
+    #[arg(
+        id = "eth-requests.max-queued",
+        long = "eth-requests.max-queued",
+        value_name = "COUNT",
+        default_value_t = DEFAULT_MAX_QUEUED_REQUESTS
+    )]
+    pub max_queued_eth_requests: usize,
 }
 
 impl NetworkArgs {
@@ -116,12 +198,20 @@ impl NetworkArgs {
         let chain_bootnodes = chain_spec.bootnodes().unwrap_or_else(mainnet_nodes);
         let peers_file = self.peers_file.clone().unwrap_or(default_peers_file);
 
-        // Configure peer connections
+        // Configure peer connections, seeded with the peers persisted by the previous run
         let peers_config = config
             .peers
             .clone()
+            .with_known_peers_from_file(self.persistent_peers_file(peers_file))
+            .unwrap_or_else(|err| {
+                warn!(target: "reth::cli", %err, "Failed to load the known peers file");
+                config.peers.clone()
+            })
             .with_max_inbound_opt(self.max_inbound_peers)
-            .with_max_outbound_opt(self.max_outbound_peers);
+            .with_max_outbound_opt(self.max_outbound_peers)
+            .with_max_peers_per_subnet(
+                self.max_peers_per_subnet.or(config.peers.max_peers_per_subnet),
+            );
 
         // Configure transactions manager
         let transactions_manager_config = TransactionsManagerConfig {
@@ -133,7 +223,7 @@ impl NetworkArgs {
 
         // Configure basic network stack
         let mut network_config_builder = config
-            .network_config(self.nat, self.persistent_peers_file(peers_file), secret_key)
+            .network_config(self.nat, None, secret_key)
             .sessions_config(
                 SessionsConfig::default().with_upscaled_event_buffer(peers_config.max_peers()),
             )
@@ -151,6 +241,17 @@ impl NetworkArgs {
         self.discovery.apply_to_builder(network_config_builder)
     }
 
+    /// Returns the budgets of the served `eth` requests.
+    pub fn eth_requests_config(&self) -> EthRequestsConfig {
+        EthRequestsConfig {
+            max_requests_per_peer: self.max_eth_requests_per_peer,
+            max_bytes_per_peer_per_second: self.max_eth_request_bytes_per_peer_per_second,
+            max_bytes_per_second: self.max_eth_request_bytes_per_second,
+            max_queued_requests: self.max_queued_eth_requests,
+            ..Default::default()
+        }
+    }
+
     /// If `no_persist_peers` is true then this returns the path to the persistent peers file path.
     pub fn persistent_peers_file(&self, peers_file: PathBuf) -> Option<PathBuf> {
         if self.no_persist_peers {
@@ -184,6 +285,8 @@ impl Default for NetworkArgs {
             trusted_only: false,
             bootnodes: None,
             peers_file: None,
+            peers_file_max_entries: DEFAULT_MAX_PERSISTED_PEERS,
+            peers_file_interval: Duration::from_secs(10 * 60),
             identity: P2P_CLIENT_VERSION.to_string(),
             p2p_secret_key: None,
             no_persist_peers: false,
@@ -192,9 +295,14 @@ impl Default for NetworkArgs {
             port: DEFAULT_DISCOVERY_PORT,
             max_outbound_peers: None,
             max_inbound_peers: None,
+            max_peers_per_subnet: None,
             soft_limit_byte_size_pooled_transactions_response:
                 SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
             soft_limit_byte_size_pooled_transactions_response_on_pack_request: DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
+            max_eth_requests_per_peer: DEFAULT_MAX_REQUESTS_PER_PEER,
+            max_eth_request_bytes_per_peer_per_second: DEFAULT_MAX_BYTES_PER_PEER_PER_SECOND,
+            max_eth_request_bytes_per_second: DEFAULT_MAX_BYTES_PER_SECOND,
+            max_queued_eth_requests: DEFAULT_MAX_QUEUED_REQUESTS,
         }
     }
 }
@@ -218,6 +326,13 @@ pub struct DiscoveryArgs {
     #[arg(long, conflicts_with = "disable_discovery")]
     pub enable_discv5_discovery: bool,
 
+    /// Dial discovered peers even if the `eth` fork id in their ENR is incompatible with the local
+    /// chain.
+    ///
+    /// Useful for devnets with unusual fork ids.
+    #[arg(long)]
+    pub disable_fork_id_filter: bool,
+
     /// The UDP address to use for devp2p peer discovery version 4.
     #[arg(id = "discovery.addr", long = "discovery.addr", value_name = "DISCOVERY_ADDR", default_value_t = DEFAULT_DISCOVERY_ADDR)]
     pub addr: IpAddr,
@@ -255,7 +370,7 @@ impl DiscoveryArgs {
             network_config_builder = network_config_builder.enable_discv5_discovery();
         }
 
-        network_config_builder
+        network_config_builder.disable_fork_id_filter(self.disable_fork_id_filter)
     }
 
     /// Set the discovery port to zero, to allow the OS to assign a random unused port when
@@ -273,6 +388,7 @@ impl Default for DiscoveryArgs {
             disable_dns_discovery: false,
             disable_discv4_discovery: false,
             enable_discv5_discovery: cfg!(feature = "optimism"),
+            disable_fork_id_filter: false,
             addr: DEFAULT_DISCOVERY_ADDR,
             port: DEFAULT_DISCOVERY_PORT,
             discv5_addr: DEFAULT_DISCOVERY_V5_ADDR,
@@ -300,6 +416,9 @@ mod tests {
         let args =
             CommandParser::<NetworkArgs>::parse_from(["reth", "--nat", "extip:0.0.0.0"]).args;
         assert_eq!(args.nat, NatResolver::ExternalIp("0.0.0.0".parse().unwrap()));
+
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth", "--nat", "pmp"]).args;
+        assert_eq!(args.nat, NatResolver::NatPmp);
     }
 
     #[test]
@@ -319,6 +438,48 @@ mod tests {
         .args;
         assert_eq!(args.max_outbound_peers, Some(75));
         assert_eq!(args.max_inbound_peers, Some(15));
+        assert_eq!(args.max_peers_per_subnet, None);
+
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--network.max-peers-per-subnet",
+            "4",
+        ])
+        .args;
+        assert_eq!(args.max_peers_per_subnet, Some(4));
+    }
+
+    #[test]
+    fn parse_eth_requests_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.eth_requests_config(), EthRequestsConfig::default());
+
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--eth-requests.max-per-peer",
+            "2",
+            "--eth-requests.max-peer-bytes-per-second",
+            "1048576",
+        ])
+        .args;
+        let config = args.eth_requests_config();
+        assert_eq!(config.max_requests_per_peer, 2);
+        assert_eq!(config.max_bytes_per_peer_per_second, 1024 * 1024);
+        assert_eq!(config.max_bytes_per_second, DEFAULT_MAX_BYTES_PER_SECOND);
+    }
+
+    #[test]
+    fn parse_peers_file_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--peers-file-max-entries",
+            "100",
+            "--peers-file-interval",
+            "0s",
+        ])
+        .args;
+        assert_eq!(args.peers_file_max_entries, 100);
+        assert!(args.peers_file_interval.is_zero());
     }
 
     #[test]
@@ -1,5 +1,5 @@
 use crate::{
-    args::utils::parse_duration_from_secs, cli::config::PayloadBuilderConfig,
+    args::utils::parse_duration_from_secs_or_human, cli::config::PayloadBuilderConfig,
     version::default_extradata,
 };
 use clap::{
@@ -23,14 +23,29 @@ pub struct PayloadBuilderArgs {
     #[arg(long = "builder.gaslimit", default_value = "30000000", value_name = "GAS_LIMIT")]
     pub max_gas_limit: u64,
 
-    /// The interval at which the job should build a new payload after the last (in seconds).
-    #[arg(long = "builder.interval", value_parser = parse_duration_from_secs, default_value = "1", value_name = "SECONDS")]
+    /// The interval at which the job should build a new payload after the last.
+    ///
+    /// Accepts seconds or a duration like `500ms`.
+    #[arg(long = "builder.interval", value_parser = parse_duration_from_secs_or_human, default_value = "1", value_name = "DURATION")]
     pub interval: Duration,
 
     /// The deadline for when the payload builder job should resolve.
-    #[arg(long = "builder.deadline", value_parser = parse_duration_from_secs, default_value = "12", value_name = "SECONDS")]
+    ///
+    /// Accepts seconds or a duration like `500ms`.
+    #[arg(long = "builder.deadline", value_parser = parse_duration_from_secs_or_human, default_value = "12", value_name = "DURATION")]
     pub deadline: Duration,
 
+    /// Immediately re-build the payload when a new pending transaction pays a higher tip than the
+    /// lowest tip included in the best payload.
+    #[arg(long = "builder.rebuild-on-tx")]
+    pub rebuild_on_tx: bool,
+
+    /// The minimum time between two re-builds triggered by new transactions.
+    ///
+    /// Only used with `--builder.rebuild-on-tx`.
+    #[arg(long = "builder.rebuild-debounce", value_parser = humantime::parse_duration, default_value = "100ms", value_name = "DURATION")]
+    pub rebuild_debounce: Duration,
+
     /// Maximum number of tasks to spawn for building a payload.
     #[arg(long = "builder.max-tasks", default_value = "3", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
     pub max_payload_tasks: usize,
@@ -43,6 +58,8 @@ impl Default for PayloadBuilderArgs {
             max_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             interval: Duration::from_secs(1),
             deadline: SLOT_DURATION,
+            rebuild_on_tx: false,
+            rebuild_debounce: Duration::from_millis(100),
             max_payload_tasks: 3,
         }
     }
@@ -61,6 +78,10 @@ impl PayloadBuilderConfig for PayloadBuilderArgs {
         self.deadline
     }
 
+    fn rebuild_on_tx(&self) -> Option<Duration> {
+        self.rebuild_on_tx.then_some(self.rebuild_debounce)
+    }
+
     fn max_gas_limit(&self) -> u64 {
         self.max_gas_limit
     }
@@ -150,6 +171,24 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_args_with_durations() {
+        let args = CommandParser::<PayloadBuilderArgs>::parse_from([
+            "reth",
+            "--builder.interval",
+            "500ms",
+            "--builder.deadline",
+            "4",
+            "--builder.rebuild-on-tx",
+            "--builder.rebuild-debounce",
+            "50ms",
+        ])
+        .args;
+        assert_eq!(args.interval, Duration::from_millis(500));
+        assert_eq!(args.deadline, Duration::from_secs(4));
+        assert_eq!(args.rebuild_on_tx(), Some(Duration::from_millis(50)));
+    }
+
     #[test]
     fn payload_builder_args_default_sanity_check() {
         let default_args = PayloadBuilderArgs::default();
@@ -3,6 +3,7 @@
 use crate::{
     args::{
         types::{MaxU32, ZeroAsNoneU64},
+        utils::parse_duration_from_secs_or_human,
         GasPriceOracleArgs, RpcStateCacheArgs,
     },
     cli::config::RethRpcConfig,
@@ -16,17 +17,23 @@ use rand::Rng;
 use reth_network_api::{NetworkInfo, Peers};
 use reth_node_api::{ConfigureEvm, EngineTypes};
 use reth_provider::{
-    AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, HeaderProvider, StateProviderFactory,
+    AccountReader, BlockReaderIdExt, CallTraceIndexReader, CanonStateSubscriptions,
+    ChainSpecProvider, ChangeSetReader, DatabaseMetadataReader, EvmEnvProvider, HeaderProvider,
+    PruneCheckpointReader, StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory,
 };
 use reth_rpc::{
-    eth::{cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, RPC_DEFAULT_GAS_CAP},
+    eth::{
+        cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, DEFAULT_ETH_PROOF_WINDOW,
+        DEFAULT_MAX_DORMANT_SUBSCRIPTIONS, DEFAULT_RESUMABLE_BUFFER_DEPTH,
+        DEFAULT_RESUMABLE_EXPIRY, RPC_DEFAULT_GAS_CAP,
+    },
     JwtError, JwtSecret,
 };
 use reth_rpc_builder::{
     auth::{AuthServerConfig, AuthServerHandle},
     constants,
     error::RpcError,
+    response_cache::{ResponseCacheConfig, DEFAULT_FINALITY_DEPTH},
     EthConfig, Identity, IpcServerBuilder, RethRpcModule, RpcModuleConfig, RpcModuleSelection,
     RpcServerConfig, RpcServerHandle, ServerBuilder, TransportRpcModuleConfig,
 };
@@ -37,6 +44,7 @@ use std::{
     ffi::OsStr,
     net::{IpAddr, Ipv4Addr, SocketAddr},
     path::PathBuf,
+    time::Duration,
 };
 use tracing::debug;
 
@@ -71,6 +79,8 @@ pub struct RpcServerArgs {
     pub http_port: u16,
 
     /// Rpc Modules to be configured for the HTTP server
+    ///
+    /// The `miner` module is only served over IPC.
     #[arg(long = "http.api", value_parser = RpcModuleSelectionValueParser::default())]
     pub http_api: Option<RpcModuleSelection>,
 
@@ -95,6 +105,8 @@ pub struct RpcServerArgs {
     pub ws_allowed_origins: Option<String>,
 
     /// Rpc Modules to be configured for the WS server
+    ///
+    /// The `miner` module is only served over IPC.
     #[arg(long = "ws.api", value_parser = RpcModuleSelectionValueParser::default())]
     pub ws_api: Option<RpcModuleSelection>,
 
@@ -152,11 +164,11 @@ pub struct RpcServerArgs {
     pub rpc_max_tracing_requests: usize,
 
     /// Maximum number of blocks that could be scanned per filter request. (0 = entire chain)
-    #[arg(long, value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_BLOCKS_PER_FILTER))]
+    #[arg(long, alias = "rpc.max-blocks-per-filter", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_BLOCKS_PER_FILTER))]
     pub rpc_max_blocks_per_filter: ZeroAsNoneU64,
 
     /// Maximum number of logs that can be returned in a single response. (0 = no limit)
-    #[arg(long, value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64))]
+    #[arg(long, alias = "rpc.max-logs-per-response", value_name = "COUNT", default_value_t = ZeroAsNoneU64::new(constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64))]
     pub rpc_max_logs_per_response: ZeroAsNoneU64,
 
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
@@ -169,6 +181,88 @@ pub struct RpcServerArgs {
     )]
     pub rpc_gas_cap: u64,
 
+    /// Enable `eth_sendRawTransactionConditional`, accepting transactions that may only be
+    /// included in blocks satisfying the given conditions.
+    #[arg(long = "rpc.tx-conditional")]
+    pub rpc_tx_conditional: bool,
+
+    /// Maximum number of transactions that can be returned in a single `txpool` response.
+    #[arg(
+        long = "rpc.txpool-max-entries",
+        value_name = "COUNT",
+        default_value_t = constants::DEFAULT_MAX_TXPOOL_ENTRIES
+    )]
+    pub rpc_txpool_max_entries: usize,
+
+    /// Maximum number of blocks that can be traced by a single `trace_filter` request.
+    #[arg(
+        long = "rpc.max-trace-filter-blocks",
+        value_name = "COUNT",
+        default_value_t = constants::DEFAULT_MAX_TRACE_FILTER_BLOCKS
+    )]
+    pub rpc_max_trace_filter_blocks: u64,
+
+    /// Maximum number of blocks that can be traced by a single `traceChain` subscription.
+    #[arg(
+        long = "rpc.max-trace-chain-blocks",
+        value_name = "COUNT",
+        default_value_t = constants::DEFAULT_MAX_TRACE_CHAIN_BLOCKS
+    )]
+    pub rpc_max_trace_chain_blocks: u64,
+
+    /// Maximum number of blocks below the tip for which `eth_getProof` is served.
+    ///
+    /// Proofs of historical blocks revert the state changes of all later blocks in memory, so
+    /// larger windows allow more expensive requests.
+    #[arg(
+        long = "rpc.eth-proof-window",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_ETH_PROOF_WINDOW
+    )]
+    pub rpc_eth_proof_window: u64,
+
+    /// Maximum number of notifications that are buffered for a resumable subscription whose
+    /// connection was lost.
+    #[arg(
+        long = "rpc.resumable-subscription-buffer",
+        value_name = "COUNT",
+        default_value_t = DEFAULT_RESUMABLE_BUFFER_DEPTH
+    )]
+    pub rpc_resumable_subscription_buffer: usize,
+
+    /// How long a resumable subscription can be resumed after its connection was lost.
+    ///
+    /// Accepts seconds or a duration like `90s`.
+    #[arg(long = "rpc.resumable-subscription-expiry", value_parser = parse_duration_from_secs_or_human, default_value = "60", value_name = "DURATION")]
+    pub rpc_resumable_subscription_expiry: Duration,
+
+    /// Maximum number of resumable subscriptions whose connection was lost.
+    #[arg(
+        long = "rpc.max-dormant-subscriptions",
+        value_name = "COUNT",
+        default_value_t = DEFAULT_MAX_DORMANT_SUBSCRIPTIONS
+    )]
+    pub rpc_max_dormant_subscriptions: usize,
+
+    /// Maximum size of the cache of responses for immutable data, e.g. blocks, receipts and
+    /// traces of finalized blocks, in megabytes. (0 = disabled)
+    #[arg(long = "rpc.cache-size-mb", value_name = "MB", default_value_t = 0)]
+    pub rpc_cache_size_mb: u64,
+
+    /// Number of blocks below the tip after which responses are considered immutable and can be
+    /// cached.
+    #[arg(
+        long = "rpc.cache-finality-depth",
+        value_name = "BLOCKS",
+        default_value_t = DEFAULT_FINALITY_DEPTH
+    )]
+    pub rpc_cache_finality_depth: u64,
+
+    /// Create a span for every RPC call that is linked to the W3C trace context of the HTTP or WS
+    /// request, taken from its `traceparent` and `tracestate` headers.
+    #[arg(long = "rpc.tracing-propagation")]
+    pub rpc_tracing_propagation: bool,
+
     /// State cache configuration.
     #[command(flatten)]
     pub rpc_state_cache: RpcStateCacheArgs,
@@ -295,6 +389,11 @@ impl RpcServerArgs {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + PruneCheckpointReader
+            + CallTraceIndexReader
+            + StaticFileProviderFactory
+            + StageCheckpointReader
+            + DatabaseMetadataReader
             + Clone
             + Unpin
             + 'static,
@@ -334,6 +433,8 @@ impl RpcServerArgs {
             + ChainSpecProvider
             + EvmEnvProvider
             + HeaderProvider
+            + PruneCheckpointReader
+            + CallTraceIndexReader
             + StateProviderFactory
             + Clone
             + Unpin
@@ -376,6 +477,14 @@ impl RethRpcConfig for RpcServerArgs {
             .max_blocks_per_filter(self.rpc_max_blocks_per_filter.unwrap_or_max())
             .max_logs_per_response(self.rpc_max_logs_per_response.unwrap_or_max() as usize)
             .rpc_gas_cap(self.rpc_gas_cap)
+            .conditional_transactions(self.rpc_tx_conditional)
+            .txpool_max_entries(self.rpc_txpool_max_entries)
+            .max_trace_filter_blocks(self.rpc_max_trace_filter_blocks)
+            .max_trace_chain_blocks(self.rpc_max_trace_chain_blocks)
+            .eth_proof_window(self.rpc_eth_proof_window)
+            .resumable_buffer_depth(self.rpc_resumable_subscription_buffer)
+            .resumable_expiry(self.rpc_resumable_subscription_expiry)
+            .max_dormant_subscriptions(self.rpc_max_dormant_subscriptions)
             .state_cache(self.state_cache_config())
             .gpo_config(self.gas_price_oracle_config())
     }
@@ -422,7 +531,8 @@ impl RethRpcConfig for RpcServerArgs {
         }
 
         if self.is_ipc_enabled() {
-            config = config.with_ipc(RpcModuleSelection::default_ipc_modules());
+            // all modules are available on IPC, including custom ones
+            config = config.with_ipc(RpcModuleSelection::All);
         }
 
         config
@@ -445,7 +555,9 @@ impl RethRpcConfig for RpcServerArgs {
     }
 
     fn rpc_server_config(&self) -> RpcServerConfig {
-        let mut config = RpcServerConfig::default().with_jwt_secret(self.rpc_secret_key());
+        let mut config = RpcServerConfig::default()
+            .with_jwt_secret(self.rpc_secret_key())
+            .with_tracing_propagation(self.rpc_tracing_propagation);
 
         if self.http {
             let socket_address = SocketAddr::new(self.http_addr, self.http_port);
@@ -469,6 +581,14 @@ impl RethRpcConfig for RpcServerArgs {
         config
     }
 
+    fn response_cache_config(&self) -> Option<ResponseCacheConfig> {
+        if self.rpc_cache_size_mb == 0 {
+            return None
+        }
+        let max_bytes = self.rpc_cache_size_mb.saturating_mul(1024 * 1024) as usize;
+        Some(ResponseCacheConfig::new(max_bytes).with_finality_depth(self.rpc_cache_finality_depth))
+    }
+
     fn auth_server_config(&self, jwt_secret: JwtSecret) -> Result<AuthServerConfig, RpcError> {
         let address = SocketAddr::new(self.auth_addr, self.auth_port);
 
@@ -523,6 +643,17 @@ impl Default for RpcServerArgs {
             rpc_max_blocks_per_filter: constants::DEFAULT_MAX_BLOCKS_PER_FILTER.into(),
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            rpc_tx_conditional: false,
+            rpc_txpool_max_entries: constants::DEFAULT_MAX_TXPOOL_ENTRIES,
+            rpc_max_trace_filter_blocks: constants::DEFAULT_MAX_TRACE_FILTER_BLOCKS,
+            rpc_max_trace_chain_blocks: constants::DEFAULT_MAX_TRACE_CHAIN_BLOCKS,
+            rpc_eth_proof_window: DEFAULT_ETH_PROOF_WINDOW,
+            rpc_resumable_subscription_buffer: DEFAULT_RESUMABLE_BUFFER_DEPTH,
+            rpc_resumable_subscription_expiry: DEFAULT_RESUMABLE_EXPIRY,
+            rpc_max_dormant_subscriptions: DEFAULT_MAX_DORMANT_SUBSCRIPTIONS,
+            rpc_cache_size_mb: 0,
+            rpc_cache_finality_depth: DEFAULT_FINALITY_DEPTH,
+            rpc_tracing_propagation: false,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
         }
@@ -590,6 +721,38 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_rpc_tx_conditional() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert!(!args.eth_config().conditional_transactions);
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.tx-conditional"]).args;
+        assert!(args.eth_config().conditional_transactions);
+    }
+
+    #[test]
+    fn test_rpc_eth_proof_window() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.eth_config().eth_proof_window, DEFAULT_ETH_PROOF_WINDOW);
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.eth-proof-window", "0"])
+                .args;
+        assert_eq!(args.eth_config().eth_proof_window, 0);
+    }
+
+    #[test]
+    fn test_rpc_response_cache() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        assert!(args.response_cache_config().is_none());
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc.cache-size-mb", "256"]).args;
+        assert!(args.response_cache_config().is_some());
+        assert_eq!(args.rpc_cache_finality_depth, DEFAULT_FINALITY_DEPTH);
+    }
+
     #[test]
     fn test_rpc_server_args_parser() {
         let args =
@@ -745,6 +908,22 @@ mod tests {
         assert_eq!(config.max_logs_per_response, Some(200));
     }
 
+    #[test]
+    fn test_custom_filter_limits_aliases() {
+        let args = CommandParser::<RpcServerArgs>::parse_from([
+            "reth",
+            "--rpc.max-blocks-per-filter",
+            "100",
+            "--rpc.max-logs-per-response",
+            "200",
+        ])
+        .args;
+
+        let config = args.eth_config().filter_config();
+        assert_eq!(config.max_blocks_per_filter, Some(100));
+        assert_eq!(config.max_logs_per_response, Some(200));
+    }
+
     #[test]
     fn rpc_server_args_default_sanity_test() {
         let default_args = RpcServerArgs::default();
@@ -0,0 +1,52 @@
+//! clap [Args](clap::Args) for shutdown configuration
+
+use crate::cli::runner::DEFAULT_SHUTDOWN_TIMEOUT;
+use clap::Args;
+use humantime::parse_duration;
+use std::time::Duration;
+
+/// Parameters for the graceful shutdown of the node
+#[derive(Debug, Args, PartialEq, Eq, Clone, Copy)]
+#[command(next_help_heading = "Shutdown")]
+pub struct ShutdownArgs {
+    /// The maximum time the node waits for the ordered shutdown of its components after it
+    /// received a shutdown signal.
+    ///
+    /// The remaining tasks are aborted once it elapsed.
+    #[arg(
+        long = "shutdown.timeout",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        default_value = "10s"
+    )]
+    pub timeout: Duration,
+}
+
+impl Default for ShutdownArgs {
+    fn default() -> Self {
+        Self { timeout: DEFAULT_SHUTDOWN_TIMEOUT }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_shutdown_args() {
+        let args = CommandParser::<ShutdownArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, ShutdownArgs::default());
+
+        let args =
+            CommandParser::<ShutdownArgs>::parse_from(["reth", "--shutdown.timeout", "1m"]).args;
+        assert_eq!(args.timeout, Duration::from_secs(60));
+    }
+}
@@ -1,5 +1,10 @@
 //! Shared arguments related to stages
+use clap::{builder::RangedU64ValueParser, Args};
 use derive_more::Display;
+use humantime::parse_duration;
+use reth_config::config::StageConfig;
+use reth_primitives::BlockNumber;
+use std::{num::ParseIntError, str::FromStr, time::Duration};
 
 /// Represents a specific stage within the data pipeline.
 ///
@@ -51,3 +56,238 @@ pub enum StageEnum {
     /// Manages historical data related to storage.
     StorageHistory,
 }
+
+/// Whether, and from which block on, the execution stage verifies the receipts of blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyReceipts {
+    /// The receipts aren't verified.
+    Off,
+    /// The receipts of the blocks from this block on are verified.
+    From(BlockNumber),
+}
+
+impl FromStr for VerifyReceipts {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("off") {
+            return Ok(Self::Off)
+        }
+        s.parse().map(Self::From)
+    }
+}
+
+/// Parameters for configuring the stages of the pipeline
+///
+/// Every parameter overrides the corresponding value of the `[stages]` section of the
+/// configuration file.
+#[derive(Debug, Args, PartialEq, Eq, Default, Clone, Copy)]
+#[command(next_help_heading = "Stages")]
+pub struct StageArgs {
+    /// The maximum cumulative amount of gas to execute before the execution stage commits.
+    ///
+    /// Defaults to 1.5T gas, 50k full blocks of 30M gas.
+    #[arg(long = "stage.execution.max-gas", value_name = "GAS")]
+    pub execution_max_gas: Option<u64>,
+
+    /// The maximum number of state changes to keep in memory before the execution stage commits.
+    ///
+    /// Defaults to 5M changes.
+    #[arg(long = "stage.execution.max-changes", value_name = "CHANGES")]
+    pub execution_max_changes: Option<u64>,
+
+    /// The maximum time to spend executing blocks before the execution stage commits.
+    ///
+    /// Defaults to 10 minutes. Parses strings using [humantime::parse_duration]
+    /// --stage.execution.max-duration 5m
+    #[arg(
+        long = "stage.execution.max-duration",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        verbatim_doc_comment
+    )]
+    pub execution_max_duration: Option<Duration>,
+
+    /// The block from which the execution stage verifies the receipts root and the logs bloom of
+    /// every block against its header, or `off` for chains with non-standard receipts.
+    ///
+    /// The blocks below it are executed without the verification, which is faster. Defaults to
+    /// verifying all blocks.
+    #[arg(
+        long = "stage.execution.verify-receipts",
+        value_name = "BLOCK|off",
+        value_parser = VerifyReceipts::from_str
+    )]
+    pub execution_verify_receipts: Option<VerifyReceipts>,
+
+    /// The maximum time the merkle stage spends rebuilding the trie before it commits the
+    /// intermediate state, limiting the trie work lost on a crash.
+    ///
+    /// Parses strings using [humantime::parse_duration]
+    /// --stage.merkle.flush-interval 5m
+    #[arg(
+        long = "stage.merkle.flush-interval",
+        value_name = "DURATION",
+        value_parser = parse_duration,
+        verbatim_doc_comment
+    )]
+    pub merkle_flush_interval: Option<Duration>,
+
+    /// The maximum number of storage roots of changed accounts that the merkle stage of the node
+    /// pipeline calculates in parallel.
+    ///
+    /// Defaults to 8. Storage roots are calculated sequentially if set to 1.
+    #[arg(
+        long = "stage.merkle.storage-root-parallelism",
+        value_name = "THREADS",
+        value_parser = RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub merkle_storage_root_parallelism: Option<usize>,
+
+    /// The number of transactions the sender recovery stage recovers in a single parallel job.
+    ///
+    /// Defaults to 100 transactions.
+    #[arg(
+        long = "stage.sender-recovery.chunk-size",
+        value_name = "TRANSACTIONS",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..)
+    )]
+    pub sender_recovery_chunk_size: Option<u64>,
+
+    /// Write the senders of final blocks to static files instead of the database.
+    ///
+    /// Ignored if the senders are pruned.
+    #[arg(long = "stage.sender-recovery.static-files")]
+    pub sender_recovery_static_files: bool,
+}
+
+impl StageArgs {
+    /// Overrides the stage configuration with the parameters that are set.
+    ///
+    /// The execution stage commits as soon as any of its thresholds is hit.
+    pub fn apply(&self, config: &mut StageConfig) {
+        if let Some(max_gas) = self.execution_max_gas {
+            config.execution.max_cumulative_gas = Some(max_gas);
+        }
+        if let Some(max_changes) = self.execution_max_changes {
+            config.execution.max_changes = Some(max_changes);
+        }
+        if let Some(max_duration) = self.execution_max_duration {
+            config.execution.max_duration = Some(max_duration);
+        }
+        match self.execution_verify_receipts {
+            Some(VerifyReceipts::Off) => config.execution.verify_receipts = false,
+            Some(VerifyReceipts::From(block)) => {
+                config.execution.verify_receipts = true;
+                config.execution.verify_receipts_from = block;
+            }
+            None => {}
+        }
+        if let Some(flush_interval) = self.merkle_flush_interval {
+            config.merkle.flush_interval = Some(flush_interval);
+        }
+        if let Some(parallelism) = self.merkle_storage_root_parallelism {
+            config.merkle.storage_root_parallelism = parallelism;
+        }
+        if let Some(chunk_size) = self.sender_recovery_chunk_size {
+            config.sender_recovery.chunk_size = chunk_size;
+        }
+        if self.sender_recovery_static_files {
+            config.sender_recovery.static_files = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_stage_args() {
+        let args = CommandParser::<StageArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, StageArgs::default());
+
+        let mut config = StageConfig::default();
+        args.apply(&mut config);
+        assert_eq!(config, StageConfig::default());
+
+        let args = CommandParser::<StageArgs>::parse_from([
+            "reth",
+            "--stage.execution.max-gas",
+            "1000000",
+            "--stage.execution.max-duration",
+            "30s",
+            "--stage.execution.verify-receipts",
+            "15000000",
+            "--stage.merkle.flush-interval",
+            "5m",
+            "--stage.merkle.storage-root-parallelism",
+            "16",
+            "--stage.sender-recovery.chunk-size",
+            "500",
+            "--stage.sender-recovery.static-files",
+        ])
+        .args;
+        assert_eq!(
+            args,
+            StageArgs {
+                execution_max_gas: Some(1_000_000),
+                execution_max_changes: None,
+                execution_max_duration: Some(Duration::from_secs(30)),
+                execution_verify_receipts: Some(VerifyReceipts::From(15_000_000)),
+                merkle_flush_interval: Some(Duration::from_secs(5 * 60)),
+                merkle_storage_root_parallelism: Some(16),
+                sender_recovery_chunk_size: Some(500),
+                sender_recovery_static_files: true,
+            }
+        );
+
+        args.apply(&mut config);
+        assert_eq!(config.execution.max_cumulative_gas, Some(1_000_000));
+        assert_eq!(config.execution.max_changes, StageConfig::default().execution.max_changes);
+        assert_eq!(config.execution.max_duration, Some(Duration::from_secs(30)));
+        assert_eq!(config.execution.verify_receipts_from(), Some(15_000_000));
+        assert_eq!(config.merkle.flush_interval, Some(Duration::from_secs(5 * 60)));
+        assert_eq!(config.merkle.storage_root_parallelism, 16);
+        assert_eq!(config.sender_recovery.chunk_size, 500);
+        assert!(config.sender_recovery.static_files);
+
+        assert!(CommandParser::<StageArgs>::try_parse_from([
+            "reth",
+            "--stage.sender-recovery.chunk-size",
+            "0"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_verify_receipts() {
+        let args = CommandParser::<StageArgs>::parse_from([
+            "reth",
+            "--stage.execution.verify-receipts",
+            "off",
+        ])
+        .args;
+        assert_eq!(args.execution_verify_receipts, Some(VerifyReceipts::Off));
+
+        let mut config = StageConfig::default();
+        assert_eq!(config.execution.verify_receipts_from(), Some(0));
+        args.apply(&mut config);
+        assert_eq!(config.execution.verify_receipts_from(), None);
+
+        assert!(CommandParser::<StageArgs>::try_parse_from([
+            "reth",
+            "--stage.execution.verify-receipts",
+            "latest"
+        ])
+        .is_err());
+    }
+}
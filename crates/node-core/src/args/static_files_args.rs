@@ -0,0 +1,184 @@
+//! clap [Args](clap::Args) for static files configuration
+
+use clap::Args;
+use reth_primitives::{
+    static_file::{AccessPattern, Compression},
+    StaticFileSegment,
+};
+use std::{num::NonZeroUsize, thread::available_parallelism};
+
+/// Parameters for static files configuration
+#[derive(Debug, Args, PartialEq, Eq, Default, Clone, Copy)]
+#[command(next_help_heading = "Static Files")]
+pub struct StaticFilesArgs {
+    /// The number of threads used to compress rows when producing static files.
+    ///
+    /// Defaults to half of the available cores.
+    #[arg(long = "static-file-compression-workers", value_name = "WORKERS")]
+    pub compression_workers: Option<NonZeroUsize>,
+
+    /// The compression of new header static files. Defaults to lz4.
+    ///
+    /// Existing static files keep their compression.
+    #[arg(long = "static-file-compression.headers", value_enum, value_name = "COMPRESSION")]
+    pub headers_compression: Option<Compression>,
+
+    /// The compression of new transaction static files. Defaults to none.
+    ///
+    /// Transactions are always compressed with zstd by their encoding, and this compression is
+    /// applied on top of it. Existing static files keep their compression.
+    #[arg(long = "static-file-compression.transactions", value_enum, value_name = "COMPRESSION")]
+    pub transactions_compression: Option<Compression>,
+
+    /// The compression of new receipt static files. Defaults to none.
+    ///
+    /// Receipts are always compressed with zstd by their encoding, and this compression is applied
+    /// on top of it. Existing static files keep their compression.
+    #[arg(long = "static-file-compression.receipts", value_enum, value_name = "COMPRESSION")]
+    pub receipts_compression: Option<Compression>,
+
+    /// The access pattern the OS is advised of for the memory-mapped static files.
+    ///
+    /// By default, the OS reads ahead with its defaults. `random` avoids reading ahead on point
+    /// lookups, e.g. for nodes serving many lookups by hash, and only reads the rows of range
+    /// queries ahead.
+    #[arg(long = "static-file-access-pattern", value_enum, value_name = "PATTERN")]
+    pub access_pattern: Option<AccessPattern>,
+
+    /// Indexes the transactions of the static files by hash.
+    ///
+    /// The index is used to look up transactions by hash once the `TransactionHashNumbers` table
+    /// is pruned. Existing static files are indexed in the background on startup, or with
+    /// `reth static-file index-tx-hashes`.
+    #[arg(long = "static-file-tx-hash-index")]
+    pub tx_hash_index: bool,
+}
+
+impl StaticFilesArgs {
+    /// Returns the number of threads used to compress rows when producing static files.
+    pub fn compression_workers(&self) -> usize {
+        self.compression_workers
+            .map(NonZeroUsize::get)
+            .unwrap_or_else(|| available_parallelism().map_or(1, |cpus| (cpus.get() / 2).max(1)))
+    }
+
+    /// Returns the configured compression of new static files per segment.
+    ///
+    /// Fails if a segment is configured with [`Compression::ZstdWithDictionary`], which can only be
+    /// used by `reth static-file recompress`, since new static files are created without any rows
+    /// to train the dictionary on.
+    pub fn segment_compression(&self) -> eyre::Result<Vec<(StaticFileSegment, Compression)>> {
+        [
+            (StaticFileSegment::Headers, self.headers_compression),
+            (StaticFileSegment::Transactions, self.transactions_compression),
+            (StaticFileSegment::Receipts, self.receipts_compression),
+        ]
+        .into_iter()
+        .filter_map(|(segment, compression)| compression.map(|compression| (segment, compression)))
+        .map(|(segment, compression)| {
+            if compression == Compression::ZstdWithDictionary {
+                eyre::bail!(
+                    "{segment} static files can't be created with zstd-with-dictionary \
+                     compression, use `reth static-file recompress` instead"
+                )
+            }
+            Ok((segment, compression))
+        })
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_static_files_args() {
+        let default_args = StaticFilesArgs::default();
+        let args = CommandParser::<StaticFilesArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, default_args);
+        assert!(args.compression_workers() >= 1);
+
+        let args = CommandParser::<StaticFilesArgs>::parse_from([
+            "reth",
+            "--static-file-compression-workers",
+            "4",
+        ])
+        .args;
+        assert_eq!(args.compression_workers(), 4);
+
+        assert!(CommandParser::<StaticFilesArgs>::try_parse_from([
+            "reth",
+            "--static-file-compression-workers",
+            "0",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_segment_compression() {
+        let args = CommandParser::<StaticFilesArgs>::parse_from([
+            "reth",
+            "--static-file-compression.headers",
+            "none",
+            "--static-file-compression.receipts",
+            "lz4",
+        ])
+        .args;
+        assert_eq!(
+            args.segment_compression().unwrap(),
+            vec![
+                (StaticFileSegment::Headers, Compression::Uncompressed),
+                (StaticFileSegment::Receipts, Compression::Lz4)
+            ]
+        );
+
+        let args = CommandParser::<StaticFilesArgs>::parse_from([
+            "reth",
+            "--static-file-compression.transactions",
+            "zstd-with-dictionary",
+        ])
+        .args;
+        assert!(args.segment_compression().is_err());
+    }
+
+    #[test]
+    fn test_parse_access_pattern() {
+        let args = CommandParser::<StaticFilesArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.access_pattern, None);
+
+        let args = CommandParser::<StaticFilesArgs>::parse_from([
+            "reth",
+            "--static-file-access-pattern",
+            "sequential",
+        ])
+        .args;
+        assert_eq!(args.access_pattern, Some(AccessPattern::Sequential));
+
+        assert!(CommandParser::<StaticFilesArgs>::try_parse_from([
+            "reth",
+            "--static-file-access-pattern",
+            "willneed",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_tx_hash_index() {
+        let args = CommandParser::<StaticFilesArgs>::parse_from(["reth"]).args;
+        assert!(!args.tx_hash_index);
+
+        let args =
+            CommandParser::<StaticFilesArgs>::parse_from(["reth", "--static-file-tx-hash-index"])
+                .args;
+        assert!(args.tx_hash_index);
+    }
+}
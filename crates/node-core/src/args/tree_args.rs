@@ -0,0 +1,63 @@
+//! clap [Args](clap::Args) for blockchain tree configuration
+
+use clap::Args;
+use std::num::NonZeroUsize;
+
+/// Parameters for the blockchain tree
+#[derive(Debug, Args, PartialEq, Eq, Default, Clone, Copy)]
+#[command(next_help_heading = "Blockchain Tree")]
+pub struct TreeArgs {
+    /// The maximum number of blocks that are buffered until their parent is received.
+    ///
+    /// If the buffer is full, the blocks that are the farthest from the canonical head are
+    /// evicted first. Defaults to 200.
+    #[arg(long = "tree.max-buffered-blocks", value_name = "COUNT")]
+    pub max_buffered_blocks: Option<NonZeroUsize>,
+
+    /// The maximum in-memory size in MB of the blocks that are buffered until their parent is
+    /// received.
+    ///
+    /// If the buffer exceeds the size, the blocks that are the farthest from the canonical head
+    /// are evicted first. Defaults to 512 MB.
+    #[arg(long = "tree.max-buffered-blocks-size", value_name = "MB")]
+    pub max_buffered_blocks_size: Option<NonZeroUsize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_tree_args() {
+        let args = CommandParser::<TreeArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, TreeArgs::default());
+
+        let args =
+            CommandParser::<TreeArgs>::parse_from(["reth", "--tree.max-buffered-blocks", "1000"])
+                .args;
+        assert_eq!(args.max_buffered_blocks, NonZeroUsize::new(1000));
+
+        let args = CommandParser::<TreeArgs>::parse_from([
+            "reth",
+            "--tree.max-buffered-blocks-size",
+            "256",
+        ])
+        .args;
+        assert_eq!(args.max_buffered_blocks_size, NonZeroUsize::new(256));
+
+        assert!(CommandParser::<TreeArgs>::try_parse_from([
+            "reth",
+            "--tree.max-buffered-blocks",
+            "0"
+        ])
+        .is_err());
+    }
+}
@@ -2,13 +2,21 @@
 
 use crate::cli::config::RethTransactionPoolConfig;
 use clap::Args;
+use humantime::parse_duration;
+use reth_config::config::TxPoolConfig;
 use reth_primitives::Address;
 use reth_transaction_pool::{
-    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS, validate::DEFAULT_MAX_TX_INPUT_BYTES,
-    LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-    REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    blobstore::disk::DEFAULT_MAX_CACHED_BLOBS,
+    maintain::{
+        LocalTransactionBackupConfig, MaintainPoolConfig, PriorityFeeFloorConfig,
+        DEFAULT_MAX_TXS_BACKUP_SIZE, DEFAULT_TXS_BACKUP_FLUSH_INTERVAL,
+    },
+    validate::DEFAULT_MAX_TX_INPUT_BYTES,
+    LocalTransactionConfig, OriginLimits, PoolConfig, PriceBumpConfig, SubPoolLimit,
+    DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
     TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
+use std::{path::PathBuf, time::Duration};
 /// Parameters for debugging purposes
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 #[command(next_help_heading = "TxPool")]
@@ -38,6 +46,19 @@ pub struct TxPoolArgs {
     #[arg(long = "txpool.max_account_slots", default_value_t = TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER)]
     pub max_account_slots: usize,
 
+    /// Max number of transactions of a single sender in the pool, including local transactions.
+    #[arg(long = "txpool.max-per-sender", value_name = "COUNT")]
+    pub max_per_sender: Option<usize>,
+    /// Max number of local transactions in the pool.
+    #[arg(long = "txpool.max-local", value_name = "COUNT")]
+    pub max_local: Option<usize>,
+    /// Max number of external transactions in the pool.
+    #[arg(long = "txpool.max-external", value_name = "COUNT")]
+    pub max_external: Option<usize>,
+    /// Max number of private transactions in the pool.
+    #[arg(long = "txpool.max-private", value_name = "COUNT")]
+    pub max_private: Option<usize>,
+
     /// Price bump (in %) for the transaction pool underpriced check.
     #[arg(long = "txpool.pricebump", default_value_t = DEFAULT_PRICE_BUMP)]
     pub price_bump: u128,
@@ -46,6 +67,30 @@ pub struct TxPoolArgs {
     #[arg(long = "blobpool.pricebump", default_value_t = REPLACE_BLOB_PRICE_BUMP)]
     pub blob_transaction_price_bump: u128,
 
+    /// Minimum effective tip in wei a new transaction must pay at the current base fee to be
+    /// accepted into the pool.
+    #[arg(long = "txpool.minimum-priority-fee", value_name = "WEI")]
+    pub minimum_priority_fee: Option<u128>,
+    /// Also enforce the minimum priority fee for local transactions, which are exempt by default.
+    #[arg(long = "txpool.minimum-priority-fee.include-locals", requires = "minimum_priority_fee")]
+    pub minimum_priority_fee_include_locals: bool,
+
+    /// Enables the dynamic floor of the effective tip of new transactions, which is raised
+    /// towards the lowest included tip while recent blocks are full, and lowered while they're
+    /// mostly empty, up to this value in wei.
+    ///
+    /// The floor can be overridden with `miner_setGasPrice`.
+    #[arg(long = "txpool.dynamic-priority-fee.max", value_name = "WEI")]
+    pub dynamic_priority_fee_max: Option<u128>,
+    /// The lowest dynamic floor of the effective tip of new transactions in wei.
+    #[arg(
+        long = "txpool.dynamic-priority-fee.min",
+        value_name = "WEI",
+        default_value_t = 0,
+        requires = "dynamic_priority_fee_max"
+    )]
+    pub dynamic_priority_fee_min: u128,
+
     /// Max size in bytes of a single transaction allowed to enter the pool
     #[arg(long = "txpool.max_tx_input_bytes", default_value_t = DEFAULT_MAX_TX_INPUT_BYTES)]
     pub max_tx_input_bytes: usize,
@@ -63,6 +108,34 @@ pub struct TxPoolArgs {
     /// Flag to toggle local transaction propagation.
     #[arg(long = "txpool.no-local-transactions-propagation")]
     pub no_local_transactions_propagation: bool,
+
+    /// Path to the journal the local and pending transactions are saved to, and restored from on
+    /// startup.
+    ///
+    /// Defaults to `<DATADIR>/txpool-transactions-journal.bin`.
+    #[arg(long = "txpool.journal", value_name = "PATH")]
+    pub journal: Option<PathBuf>,
+    /// Disables the journal of the local and pending transactions.
+    #[arg(long = "txpool.journal.disable", conflicts_with = "journal")]
+    pub disable_journal: bool,
+    /// Interval at which the transactions journal is written, in addition to the graceful
+    /// shutdown. A zero interval only writes the journal on graceful shutdown.
+    ///
+    /// Parses strings using [humantime::parse_duration]
+    /// --txpool.journal.interval 1h
+    #[arg(
+        long = "txpool.journal.interval",
+        value_parser = parse_duration,
+        default_value = "1h",
+        verbatim_doc_comment
+    )]
+    pub journal_interval: Duration,
+    /// Max size of the transactions journal in megabytes.
+    #[arg(
+        long = "txpool.journal.max_size",
+        default_value_t = DEFAULT_MAX_TXS_BACKUP_SIZE / (1024 * 1024)
+    )]
+    pub journal_max_size: usize,
 }
 
 impl Default for TxPoolArgs {
@@ -75,17 +148,76 @@ impl Default for TxPoolArgs {
             queued_max_count: TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
             queued_max_size: TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_per_sender: None,
+            max_local: None,
+            max_external: None,
+            max_private: None,
             price_bump: DEFAULT_PRICE_BUMP,
             blob_transaction_price_bump: REPLACE_BLOB_PRICE_BUMP,
+            minimum_priority_fee: None,
+            minimum_priority_fee_include_locals: false,
+            dynamic_priority_fee_max: None,
+            dynamic_priority_fee_min: 0,
             max_tx_input_bytes: DEFAULT_MAX_TX_INPUT_BYTES,
             max_cached_entries: DEFAULT_MAX_CACHED_BLOBS,
             no_locals: false,
             locals: Default::default(),
             no_local_transactions_propagation: false,
+            journal: None,
+            disable_journal: false,
+            journal_interval: DEFAULT_TXS_BACKUP_FLUSH_INTERVAL,
+            journal_max_size: DEFAULT_MAX_TXS_BACKUP_SIZE / (1024 * 1024),
+        }
+    }
+}
+
+impl TxPoolArgs {
+    /// Returns the configuration of the transactions journal, written to the given default path
+    /// unless configured otherwise.
+    pub fn transactions_backup_config(
+        &self,
+        default_path: PathBuf,
+    ) -> LocalTransactionBackupConfig {
+        if self.disable_journal {
+            return LocalTransactionBackupConfig::default()
+        }
+
+        let flush_interval = (!self.journal_interval.is_zero()).then_some(self.journal_interval);
+        LocalTransactionBackupConfig::with_local_txs_backup(
+            self.journal.clone().unwrap_or(default_path),
+        )
+        .with_flush_interval(flush_interval)
+        .with_max_size(self.journal_max_size * 1024 * 1024)
+    }
+
+    /// Returns the configuration of the pool maintenance task.
+    pub fn maintain_pool_config(&self) -> MaintainPoolConfig {
+        MaintainPoolConfig {
+            priority_fee_floor: self
+                .dynamic_priority_fee_max
+                .map(|max| PriorityFeeFloorConfig::new(self.dynamic_priority_fee_min, max)),
+            ..Default::default()
         }
     }
 }
 
+impl TxPoolArgs {
+    /// Returns the transaction pool configuration, falling back to the limits of the config file
+    /// for the limits that aren't set on the command line.
+    pub fn pool_config_with(&self, config: &TxPoolConfig) -> PoolConfig {
+        let mut pool_config = self.pool_config();
+        pool_config.max_txs_per_sender =
+            pool_config.max_txs_per_sender.or(config.max_txs_per_sender);
+        let limits = &mut pool_config.origin_limits;
+        limits.local = limits.local.or(config.max_local_txs);
+        limits.external = limits.external.or(config.max_external_txs);
+        limits.private = limits.private.or(config.max_private_txs);
+        pool_config.minimum_priority_fee =
+            pool_config.minimum_priority_fee.or(config.minimum_priority_fee.map(u128::from));
+        pool_config
+    }
+}
+
 impl RethTransactionPoolConfig for TxPoolArgs {
     /// Returns transaction pool configuration.
     fn pool_config(&self) -> PoolConfig {
@@ -94,6 +226,7 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 no_exemptions: self.no_locals,
                 local_addresses: self.locals.clone().into_iter().collect(),
                 propagate_local_transactions: !self.no_local_transactions_propagation,
+                enforce_minimum_priority_fee: self.minimum_priority_fee_include_locals,
             },
             pending_limit: SubPoolLimit {
                 max_txs: self.pending_max_count,
@@ -112,10 +245,17 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 max_size: self.queued_max_size * 1024 * 1024,
             },
             max_account_slots: self.max_account_slots,
+            max_txs_per_sender: self.max_per_sender,
+            origin_limits: OriginLimits {
+                local: self.max_local,
+                external: self.max_external,
+                private: self.max_private,
+            },
             price_bumps: PriceBumpConfig {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
             },
+            minimum_priority_fee: self.minimum_priority_fee,
         }
     }
 }
@@ -138,4 +278,102 @@ mod tests {
         let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
         assert_eq!(args, default_args);
     }
+
+    #[test]
+    fn txpool_journal_args() {
+        let default_path = PathBuf::from("default");
+
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.journal",
+            "journal.bin",
+            "--txpool.journal.interval",
+            "0s",
+        ])
+        .args;
+        let config = args.transactions_backup_config(default_path.clone());
+        assert_eq!(config.transactions_path, Some(PathBuf::from("journal.bin")));
+        assert_eq!(config.flush_interval, None);
+
+        let args =
+            CommandParser::<TxPoolArgs>::parse_from(["reth", "--txpool.journal.disable"]).args;
+        assert_eq!(args.transactions_backup_config(default_path).transactions_path, None);
+    }
+
+    #[test]
+    fn txpool_limit_args() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.max-per-sender",
+            "32",
+            "--txpool.max-external",
+            "1000",
+        ])
+        .args;
+        let file = TxPoolConfig {
+            max_txs_per_sender: Some(64),
+            max_local_txs: Some(100),
+            ..Default::default()
+        };
+        let config = args.pool_config_with(&file);
+        assert_eq!(config.max_txs_per_sender, Some(32));
+        assert_eq!(
+            config.origin_limits,
+            OriginLimits { local: Some(100), external: Some(1000), private: None }
+        );
+    }
+
+    #[test]
+    fn txpool_minimum_priority_fee_args() {
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.minimum-priority-fee",
+            "100000000",
+        ])
+        .args;
+        let config = args.pool_config();
+        assert_eq!(config.minimum_priority_fee, Some(100_000_000));
+        assert!(!config.local_transactions_config.enforce_minimum_priority_fee);
+
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.minimum-priority-fee",
+            "100000000",
+            "--txpool.minimum-priority-fee.include-locals",
+        ])
+        .args;
+        assert!(args.pool_config().local_transactions_config.enforce_minimum_priority_fee);
+
+        assert!(CommandParser::<TxPoolArgs>::try_parse_from([
+            "reth",
+            "--txpool.minimum-priority-fee.include-locals",
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn txpool_dynamic_priority_fee_args() {
+        let args = CommandParser::<TxPoolArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.maintain_pool_config().priority_fee_floor, None);
+
+        let args = CommandParser::<TxPoolArgs>::parse_from([
+            "reth",
+            "--txpool.dynamic-priority-fee.max",
+            "10000000000",
+            "--txpool.dynamic-priority-fee.min",
+            "1000000",
+        ])
+        .args;
+        assert_eq!(
+            args.maintain_pool_config().priority_fee_floor,
+            Some(PriorityFeeFloorConfig::new(1_000_000, 10_000_000_000))
+        );
+
+        assert!(CommandParser::<TxPoolArgs>::try_parse_from([
+            "reth",
+            "--txpool.dynamic-priority-fee.min",
+            "1000000",
+        ])
+        .is_err());
+    }
 }
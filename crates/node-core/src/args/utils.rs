@@ -28,6 +28,15 @@ pub fn parse_duration_from_secs(arg: &str) -> eyre::Result<Duration, std::num::P
     Ok(Duration::from_secs(seconds))
 }
 
+/// Helper to parse a [Duration] from either plain seconds or a human readable duration like
+/// `500ms`.
+pub fn parse_duration_from_secs_or_human(arg: &str) -> eyre::Result<Duration> {
+    if let Ok(seconds) = arg.parse() {
+        return Ok(Duration::from_secs(seconds))
+    }
+    Ok(humantime::parse_duration(arg)?)
+}
+
 /// Clap value parser for [ChainSpec]s that takes either a built-in chainspec or the path
 /// to a custom one.
 pub fn chain_spec_value_parser(s: &str) -> eyre::Result<Arc<ChainSpec>, eyre::Error> {
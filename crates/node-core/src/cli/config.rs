@@ -7,8 +7,8 @@ use reth_rpc::{
     JwtError, JwtSecret,
 };
 use reth_rpc_builder::{
-    auth::AuthServerConfig, error::RpcError, EthConfig, Identity, IpcServerBuilder,
-    RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
+    auth::AuthServerConfig, error::RpcError, response_cache::ResponseCacheConfig, EthConfig,
+    Identity, IpcServerBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
 };
 use reth_transaction_pool::PoolConfig;
 use std::{borrow::Cow, path::PathBuf, time::Duration};
@@ -54,6 +54,9 @@ pub trait RethRpcConfig {
     /// Creates the [RpcServerConfig] from cli args.
     fn rpc_server_config(&self) -> RpcServerConfig;
 
+    /// Creates the [ResponseCacheConfig] from cli args, if response caching is enabled.
+    fn response_cache_config(&self) -> Option<ResponseCacheConfig>;
+
     /// Creates the [AuthServerConfig] from cli args.
     fn auth_server_config(&self, jwt_secret: JwtSecret) -> Result<AuthServerConfig, RpcError>;
 
@@ -97,6 +100,10 @@ pub trait PayloadBuilderConfig {
     /// The deadline for when the payload builder job should resolve.
     fn deadline(&self) -> Duration;
 
+    /// The minimum time between two re-builds of a payload that are triggered by new pending
+    /// transactions, or `None` if payloads are only re-built at the [interval](Self::interval).
+    fn rebuild_on_tx(&self) -> Option<Duration>;
+
     /// Target gas ceiling for built blocks.
     fn max_gas_limit(&self) -> u64;
 
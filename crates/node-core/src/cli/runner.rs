@@ -2,22 +2,42 @@
 
 use futures::pin_mut;
 use reth_tasks::{TaskExecutor, TaskManager};
-use std::future::Future;
+use std::{future::Future, time::Duration};
 use tracing::{debug, error, trace};
 
+/// The default time to wait for the graceful shutdown of the spawned tasks.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Executes CLI commands.
-#[derive(Clone, Debug, Default)]
-#[non_exhaustive]
-pub struct CliRunner;
+#[derive(Clone, Debug)]
+pub struct CliRunner {
+    /// The maximum time to wait for the graceful shutdown of the spawned tasks.
+    shutdown_timeout: Duration,
+}
+
+impl Default for CliRunner {
+    fn default() -> Self {
+        Self { shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT }
+    }
+}
 
 // === impl CliRunner ===
 
 impl CliRunner {
+    /// Sets the maximum time to wait for the graceful shutdown of the tasks spawned by a command,
+    /// see [CliRunner::run_command_until_exit].
+    pub fn with_shutdown_timeout(mut self, shutdown_timeout: Duration) -> Self {
+        self.shutdown_timeout = shutdown_timeout;
+        self
+    }
+
     /// Executes the given _async_ command on the tokio runtime until the command future resolves or
     /// until the process receives a `SIGINT` or `SIGTERM` signal.
     ///
     /// Tasks spawned by the command via the [TaskExecutor] are shut down and an attempt is made to
-    /// drive their shutdown to completion after the command has finished.
+    /// drive their shutdown to completion after the command has finished. The shutdown is done in
+    /// the order of the [ShutdownPhase](reth_tasks::shutdown::ShutdownPhase)s, and the remaining
+    /// tasks are aborted once the shutdown timeout elapsed.
     pub fn run_command_until_exit<F, E>(
         self,
         command: impl FnOnce(CliContext) -> F,
@@ -41,7 +61,7 @@ impl CliRunner {
             // after the command has finished or exit signal was received we shutdown the task
             // manager which fires the shutdown signal to all tasks spawned via the task
             // executor and awaiting on tasks spawned with graceful shutdown
-            task_manager.graceful_shutdown_with_timeout(std::time::Duration::from_secs(10));
+            task_manager.graceful_shutdown_with_timeout(self.shutdown_timeout);
         }
 
         // drop the tokio runtime on a separate thread because drop blocks until its pools
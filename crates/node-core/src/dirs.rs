@@ -309,11 +309,25 @@ impl<D> ChainPath<D> {
         self.0.join("blobstore").into()
     }
 
-    /// Returns the path to the local transactions backup file
+    /// Returns the path to the journal of the local and pending transactions
     ///
-    /// `<DIR>/<CHAIN_ID>/txpool-transactions-backup.rlp`
+    /// `<DIR>/<CHAIN_ID>/txpool-transactions-journal.bin`
     pub fn txpool_transactions_path(&self) -> PathBuf {
-        self.0.join("txpool-transactions-backup.rlp").into()
+        self.0.join("txpool-transactions-journal.bin").into()
+    }
+
+    /// Returns the path to the file of the invalid blocks that were rejected by the engine.
+    ///
+    /// `<DIR>/<CHAIN_ID>/invalid-blocks.jsonl`
+    pub fn invalid_blocks_path(&self) -> PathBuf {
+        self.0.join("invalid-blocks.jsonl").into()
+    }
+
+    /// Returns the path to the write-ahead log of the ExEx notifications.
+    ///
+    /// `<DIR>/<CHAIN_ID>/exex/wal`
+    pub fn exex_wal_path(&self) -> PathBuf {
+        self.0.join("exex").join("wal").into()
     }
 
     /// Returns the path to the config file for this chain.
@@ -1,17 +1,37 @@
 //! Stores engine API messages to disk for later inspection and replay.
 
-use reth_beacon_consensus::BeaconEngineMessage;
-use reth_node_api::EngineTypes;
+use eyre::WrapErr;
+use futures::{future::BoxFuture, stream::FuturesOrdered, FutureExt, StreamExt};
+use reth_beacon_consensus::{BeaconEngineMessage, ForkchoiceStatus};
+use reth_node_api::{EngineTypes, PayloadAttributes};
 use reth_primitives::fs::{self};
 use reth_rpc_types::{
-    engine::{CancunPayloadFields, ForkchoiceState},
+    engine::{CancunPayloadFields, ForkchoiceState, PayloadStatus},
     ExecutionPayload,
 };
-use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, path::PathBuf, time::SystemTime};
-use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tokio::sync::{
+    mpsc::{UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
 use tracing::*;
 
+/// The default maximum number of messages per engine message journal file.
+pub const DEFAULT_ENGINE_JOURNAL_MAX_MESSAGES: usize = 10_000;
+
+/// The prefix of the engine message journal files, which are followed by the index of the file.
+const JOURNAL_FILE_PREFIX: &str = "engine-messages-";
+
+/// The extension of the engine message journal files.
+const JOURNAL_FILE_EXTENSION: &str = "jsonl";
+
 /// A message from the engine API that has been stored to disk.
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -127,3 +147,353 @@ impl EngineApiStore {
         }
     }
 }
+
+/// The response of the engine to an engine API message, as recorded in the
+/// [EngineMessageJournal].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JournaledEngineResponse {
+    /// The [PayloadStatus] returned for an `engine_newPayload` call.
+    PayloadStatus(PayloadStatus),
+    /// The status of the forkchoice state of an `engine_forkchoiceUpdated` call, one of `VALID`,
+    /// `INVALID` or `SYNCING`.
+    ForkchoiceStatus(String),
+    /// The error returned by the engine.
+    Error(String),
+}
+
+impl From<ForkchoiceStatus> for JournaledEngineResponse {
+    fn from(status: ForkchoiceStatus) -> Self {
+        let status = match status {
+            ForkchoiceStatus::Valid => "VALID",
+            ForkchoiceStatus::Invalid => "INVALID",
+            ForkchoiceStatus::Syncing => "SYNCING",
+        };
+        Self::ForkchoiceStatus(status.to_string())
+    }
+}
+
+/// An engine API message and the response of the engine, as recorded in the
+/// [EngineMessageJournal].
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournaledEngineMessage<Attributes> {
+    /// When the message was received, in milliseconds since the unix epoch.
+    pub timestamp: u64,
+    /// The engine API method that was called.
+    ///
+    /// The engine doesn't know which version of the method was called, so it's derived from the
+    /// params. A `forkchoiceUpdated` without payload attributes is recorded without a version.
+    pub method: String,
+    /// The params of the call.
+    pub params: StoredEngineApiMessage<Attributes>,
+    /// The response of the engine, if it responded.
+    pub response: Option<JournaledEngineResponse>,
+}
+
+/// An append-only journal of the `engine_newPayload` and `engine_forkchoiceUpdated` messages,
+/// including the responses of the engine.
+///
+/// The messages are appended to rotating files in a directory, one JSON object per line. Once a
+/// file holds the configured maximum number of messages, a new file is started and all files but
+/// the full one are removed, so at least the last `max_messages` messages are kept.
+#[derive(Debug)]
+pub struct EngineMessageJournal {
+    /// The path to the directory that stores the journal files.
+    path: PathBuf,
+    /// The maximum number of messages per file.
+    max_messages: usize,
+    /// The index of the current file.
+    file_index: u64,
+    /// The current file and the number of messages it holds, if it was opened.
+    file: Option<(File, usize)>,
+}
+
+impl EngineMessageJournal {
+    /// Creates a new [EngineMessageJournal] in the given directory.
+    ///
+    /// Existing journal files are kept, new messages are appended to a new file.
+    pub fn new(path: PathBuf, max_messages: usize) -> eyre::Result<Self> {
+        fs::create_dir_all(&path)?;
+        let file_index = Self::journal_files(&path)?.last().map_or(0, |(index, _)| index + 1);
+        Ok(Self { path, max_messages: max_messages.max(1), file_index, file: None })
+    }
+
+    /// Returns the journal files in the given directory, ordered by their index.
+    fn journal_files(path: &Path) -> eyre::Result<Vec<(u64, PathBuf)>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let path = entry.path();
+            let index = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(JOURNAL_FILE_PREFIX))
+                .and_then(|name| name.strip_suffix(JOURNAL_FILE_EXTENSION))
+                .and_then(|name| name.strip_suffix('.'))
+                .and_then(|index| index.parse::<u64>().ok());
+            if let Some(index) = index {
+                files.push((index, path));
+            }
+        }
+        files.sort_unstable();
+        Ok(files)
+    }
+
+    /// Appends the message to the journal, rotating the files if necessary.
+    ///
+    /// Every message is written to the file immediately, so it's not lost if the node crashes.
+    pub fn append<Attributes: Serialize>(
+        &mut self,
+        message: &JournaledEngineMessage<Attributes>,
+    ) -> eyre::Result<()> {
+        if self.file.as_ref().map_or(true, |(_, messages)| *messages >= self.max_messages) {
+            self.rotate()?;
+        }
+        let Some((file, messages)) = self.file.as_mut() else { unreachable!("file was opened") };
+
+        let mut line = serde_json::to_vec(message)?;
+        line.push(b'\n');
+        file.write_all(&line)?;
+        *messages += 1;
+        Ok(())
+    }
+
+    /// Starts a new journal file.
+    ///
+    /// If the current file is full, all files but the current one are removed.
+    fn rotate(&mut self) -> eyre::Result<()> {
+        let is_full = self.file.take().is_some();
+        if is_full {
+            self.file_index += 1;
+        }
+
+        let filename =
+            format!("{JOURNAL_FILE_PREFIX}{:08}.{JOURNAL_FILE_EXTENSION}", self.file_index);
+        let path = self.path.join(filename);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .wrap_err_with(|| format!("failed to open: {}", path.display()))?;
+        self.file = Some((file, 0));
+
+        if is_full {
+            for (index, path) in Self::journal_files(&self.path)? {
+                if index + 1 < self.file_index {
+                    fs::remove_file(path)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads all messages of the journal in the given directory, in the order they were received.
+    pub fn read<Attributes: DeserializeOwned>(
+        path: &Path,
+    ) -> eyre::Result<Vec<JournaledEngineMessage<Attributes>>> {
+        let mut messages = Vec::new();
+        for (_, path) in Self::journal_files(path)? {
+            let file = File::open(&path)?;
+            for line in BufReader::new(file).lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue
+                }
+                let message = serde_json::from_str(&line)
+                    .wrap_err_with(|| format!("failed to parse: {}", path.display()))?;
+                messages.push(message);
+            }
+        }
+        Ok(messages)
+    }
+
+    /// Intercepts the incoming engine API messages, forwarding them to the engine channel and
+    /// appending them to the journal together with the response of the engine.
+    ///
+    /// The responses are forwarded to the callers as soon as the engine responds, the messages
+    /// are journaled in the order they were received.
+    pub async fn intercept<Engine>(
+        mut self,
+        mut rx: UnboundedReceiver<BeaconEngineMessage<Engine>>,
+        to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
+    ) where
+        Engine: EngineTypes,
+    {
+        let mut pending = FuturesOrdered::new();
+        loop {
+            tokio::select! {
+                biased;
+                Some(message) = pending.next() => self.on_journaled_message(message),
+                msg = rx.recv() => {
+                    let Some(msg) = msg else { break };
+                    if let Some(response) = Self::forward(msg, &to_engine) {
+                        pending.push_back(response);
+                    }
+                }
+            }
+        }
+
+        // journal the messages that are still awaiting a response
+        while let Some(message) = pending.next().await {
+            self.on_journaled_message(message);
+        }
+    }
+
+    fn on_journaled_message<Attributes: Serialize>(
+        &mut self,
+        message: JournaledEngineMessage<Attributes>,
+    ) {
+        if let Err(error) = self.append(&message) {
+            error!(target: "engine::journal", method = %message.method, %error, "Error journaling Engine API message");
+        }
+    }
+
+    /// Forwards the message to the engine.
+    ///
+    /// For `newPayload` and `forkchoiceUpdated` messages, this returns a future that forwards the
+    /// response of the engine to the caller and resolves to the message to journal.
+    fn forward<Engine>(
+        msg: BeaconEngineMessage<Engine>,
+        to_engine: &UnboundedSender<BeaconEngineMessage<Engine>>,
+    ) -> Option<BoxFuture<'static, JournaledEngineMessage<Engine::PayloadAttributes>>>
+    where
+        Engine: EngineTypes,
+    {
+        let timestamp =
+            SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_millis() as u64;
+        match msg {
+            BeaconEngineMessage::NewPayload { payload, cancun_fields, tx } => {
+                let method = match payload {
+                    ExecutionPayload::V1(_) => "engine_newPayloadV1",
+                    ExecutionPayload::V2(_) => "engine_newPayloadV2",
+                    ExecutionPayload::V3(_) => "engine_newPayloadV3",
+                };
+                let params = StoredEngineApiMessage::NewPayload {
+                    payload: payload.clone(),
+                    cancun_fields: cancun_fields.clone(),
+                };
+
+                let (engine_tx, engine_rx) = oneshot::channel();
+                let _ = to_engine.send(BeaconEngineMessage::NewPayload {
+                    payload,
+                    cancun_fields,
+                    tx: engine_tx,
+                });
+
+                let response = async move {
+                    let result = engine_rx.await.ok();
+                    let response = result.as_ref().map(|result| match result {
+                        Ok(status) => JournaledEngineResponse::PayloadStatus(status.clone()),
+                        Err(err) => JournaledEngineResponse::Error(err.to_string()),
+                    });
+                    if let Some(result) = result {
+                        let _ = tx.send(result);
+                    }
+                    JournaledEngineMessage {
+                        timestamp,
+                        method: method.to_string(),
+                        params,
+                        response,
+                    }
+                };
+                Some(response.boxed())
+            }
+            BeaconEngineMessage::ForkchoiceUpdated { state, payload_attrs, tx } => {
+                let method = match &payload_attrs {
+                    Some(attrs) if attrs.parent_beacon_block_root().is_some() => {
+                        "engine_forkchoiceUpdatedV3"
+                    }
+                    Some(attrs) if attrs.withdrawals().is_some() => "engine_forkchoiceUpdatedV2",
+                    Some(_) => "engine_forkchoiceUpdatedV1",
+                    None => "engine_forkchoiceUpdated",
+                };
+                let params = StoredEngineApiMessage::ForkchoiceUpdated {
+                    state,
+                    payload_attrs: payload_attrs.clone(),
+                };
+
+                let (engine_tx, engine_rx) = oneshot::channel();
+                let _ = to_engine.send(BeaconEngineMessage::ForkchoiceUpdated {
+                    state,
+                    payload_attrs,
+                    tx: engine_tx,
+                });
+
+                let response = async move {
+                    let result = engine_rx.await.ok();
+                    let response = result.as_ref().map(|result| match result {
+                        Ok(updated) => updated.forkchoice_status().into(),
+                        Err(err) => JournaledEngineResponse::Error(err.to_string()),
+                    });
+                    if let Some(result) = result {
+                        let _ = tx.send(result);
+                    }
+                    JournaledEngineMessage {
+                        timestamp,
+                        method: method.to_string(),
+                        params,
+                        response,
+                    }
+                };
+                Some(response.boxed())
+            }
+            msg => {
+                let _ = to_engine.send(msg);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+    use reth_rpc_types::engine::PayloadStatusEnum;
+
+    fn forkchoice_updated(timestamp: u64) -> JournaledEngineMessage<()> {
+        JournaledEngineMessage {
+            timestamp,
+            method: "engine_forkchoiceUpdated".to_string(),
+            params: StoredEngineApiMessage::ForkchoiceUpdated {
+                state: ForkchoiceState {
+                    head_block_hash: B256::with_last_byte(timestamp as u8),
+                    safe_block_hash: B256::ZERO,
+                    finalized_block_hash: B256::ZERO,
+                },
+                payload_attrs: None,
+            },
+            response: Some(JournaledEngineResponse::PayloadStatus(PayloadStatus::from_status(
+                PayloadStatusEnum::Syncing,
+            ))),
+        }
+    }
+
+    #[test]
+    fn journal_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_path_buf();
+
+        let mut journal = EngineMessageJournal::new(path.clone(), 2).unwrap();
+        for timestamp in 0..5 {
+            journal.append(&forkchoice_updated(timestamp)).unwrap();
+        }
+        // the messages of the current and the previous file are kept
+        let timestamps = |messages: Vec<JournaledEngineMessage<()>>| {
+            messages.into_iter().map(|message| message.timestamp).collect::<Vec<_>>()
+        };
+        assert_eq!(timestamps(EngineMessageJournal::read(&path).unwrap()), vec![2, 3, 4]);
+
+        // a restarted journal appends to a new file
+        let mut journal = EngineMessageJournal::new(path.clone(), 2).unwrap();
+        journal.append(&forkchoice_updated(5)).unwrap();
+        assert_eq!(timestamps(EngineMessageJournal::read(&path).unwrap()), vec![2, 3, 4, 5]);
+
+        let messages = EngineMessageJournal::read::<()>(&path).unwrap();
+        let StoredEngineApiMessage::ForkchoiceUpdated { state, .. } = &messages[3].params else {
+            panic!("expected forkchoice updated")
+        };
+        assert_eq!(state.head_block_hash, B256::with_last_byte(5));
+    }
+}
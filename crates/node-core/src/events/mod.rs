@@ -2,3 +2,4 @@
 
 pub mod cl;
 pub mod node;
+pub mod sync_status;
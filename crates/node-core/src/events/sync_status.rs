@@ -0,0 +1,118 @@
+//! Support for tracking the progress of the sync.
+
+use crate::events::node::NodeEvent;
+use futures::{Stream, StreamExt};
+use reth_interfaces::sync::SyncStatusHandle;
+use reth_primitives::stage::{StageProgress, SyncStage};
+use reth_prune::PrunerEvent;
+use reth_stages::{MetricEvent, MetricEventsSender, PipelineEvent};
+use reth_static_file::StaticFileProducerEvent;
+
+/// Keeps the [SyncStatusHandle] up to date with the progress reported by the components of the
+/// node.
+///
+/// The stages of the pipeline are reported every time they commit. The pruner and the static file
+/// producer are reported as pseudo-stages every time they finish a run, and their progress is
+/// also sent to the metrics listener, which receives the progress of the stages from the pipeline
+/// directly.
+pub async fn handle_sync_status_events<E>(
+    mut events: E,
+    sync_status: SyncStatusHandle,
+    metrics_tx: Option<MetricEventsSender>,
+) where
+    E: Stream<Item = NodeEvent> + Unpin,
+{
+    while let Some(event) = events.next().await {
+        let Some(progress) = stage_progress(&sync_status, event) else { continue };
+
+        if let Some(metrics_tx) = &metrics_tx {
+            if !matches!(progress.stage, SyncStage::Stage(_)) {
+                let _ = metrics_tx.send(MetricEvent::StageProgress { progress });
+            }
+        }
+        sync_status.update(progress);
+    }
+}
+
+/// Returns the progress reported by the event, if any.
+fn stage_progress(sync_status: &SyncStatusHandle, event: NodeEvent) -> Option<StageProgress> {
+    match event {
+        NodeEvent::Pipeline(PipelineEvent::Progress { progress }) => Some(progress),
+        NodeEvent::Pruner(PrunerEvent::Finished { tip_block_number, elapsed, .. }) => {
+            Some(StageProgress {
+                stage: SyncStage::Pruner,
+                checkpoint: tip_block_number,
+                entities: None,
+                elapsed,
+                eta: None,
+            })
+        }
+        NodeEvent::StaticFileProducer(StaticFileProducerEvent::Finished { targets, elapsed }) => {
+            // Without any targets, the static files didn't move
+            let checkpoint = targets.highest_block().or_else(|| {
+                sync_status.stage(SyncStage::StaticFileProducer).map(|progress| progress.checkpoint)
+            })?;
+
+            Some(StageProgress {
+                stage: SyncStage::StaticFileProducer,
+                checkpoint,
+                entities: None,
+                elapsed,
+                eta: None,
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::stage::StageId;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn tracks_pseudo_stages() {
+        let sync_status = SyncStatusHandle::default();
+        let (metrics_tx, mut metrics_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let execution = StageProgress {
+            stage: SyncStage::Stage(StageId::Execution),
+            checkpoint: 100,
+            entities: None,
+            elapsed: Duration::from_secs(2),
+            eta: Some(Duration::from_secs(10)),
+        };
+        let pruner = StageProgress {
+            stage: SyncStage::Pruner,
+            checkpoint: 90,
+            entities: None,
+            elapsed: Duration::from_secs(1),
+            eta: None,
+        };
+        let events = futures::stream::iter([
+            PipelineEvent::Progress { progress: execution }.into(),
+            PrunerEvent::Finished {
+                tip_block_number: 90,
+                elapsed: Duration::from_secs(1),
+                stats: Default::default(),
+            }
+            .into(),
+            // The static file producer didn't report any progress yet
+            StaticFileProducerEvent::Finished {
+                targets: Default::default(),
+                elapsed: Duration::from_secs(1),
+            }
+            .into(),
+        ]);
+
+        handle_sync_status_events(events, sync_status.clone(), Some(metrics_tx)).await;
+
+        assert_eq!(sync_status.snapshot(), vec![execution, pruner]);
+        assert_matches::assert_matches!(
+            metrics_rx.try_recv(),
+            Ok(MetricEvent::StageProgress { progress }) if progress == pruner
+        );
+        assert!(metrics_rx.try_recv().is_err());
+    }
+}
@@ -1,26 +1,42 @@
 //! Reth genesis initialization utility functions.
 
+use eyre::WrapErr;
+use reth_config::config::EtlConfig;
 use reth_db::{
+    cursor::DbCursorRW,
     database::Database,
+    models::AddressStorageKey,
+    table::{Decode, Decompress},
     tables,
     transaction::{DbTx, DbTxMut},
+    RawKey, RawTable, RawValue,
 };
+use reth_etl::Collector;
 use reth_interfaces::{db::DatabaseError, provider::ProviderResult};
 use reth_primitives::{
-    stage::StageId, Account, Bytecode, ChainSpec, Receipts, StaticFileSegment, StorageEntry, B256,
-    U256,
+    keccak256,
+    stage::{StageCheckpoint, StageId},
+    Account, Address, Bytecode, ChainSpec, GenesisAccount, Receipts, SealedHeader,
+    StaticFileSegment, StorageEntry, B256, U256,
 };
 use reth_provider::{
     bundle_state::{BundleStateInit, RevertsInit},
     providers::{StaticFileProvider, StaticFileWriter},
-    BlockHashReader, BundleStateWithReceipts, ChainSpecProvider, DatabaseProviderRW, HashingWriter,
-    HistoryWriter, OriginalValuesKnown, ProviderError, ProviderFactory,
+    BlockHashReader, BlockNumReader, BundleStateWithReceipts, ChainSpecProvider,
+    DatabaseProviderRW, HashingWriter, HeaderProvider, HistoryWriter, OriginalValuesKnown,
+    ProviderError, ProviderFactory, StageCheckpointReader,
 };
+use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress};
+use serde::Deserialize;
 use std::{
     collections::{BTreeMap, HashMap},
+    io::BufRead,
     sync::Arc,
 };
-use tracing::debug;
+use tracing::{debug, info};
+
+/// The number of accounts of a state dump after which the progress is logged.
+const STATE_DUMP_PROGRESS_INTERVAL: usize = 1_000_000;
 
 /// Database initialization error type.
 #[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
@@ -35,6 +51,15 @@ pub enum InitDatabaseError {
         database_hash: B256,
     },
 
+    /// The state root computed from a state dump does not match the state root of its header.
+    #[error("state root of the state dump does not match the header: header is {expected}, computed is {computed}")]
+    StateRootMismatch {
+        /// Expected state root.
+        expected: B256,
+        /// Actual state root.
+        computed: B256,
+    },
+
     /// Provider error.
     #[error(transparent)]
     Provider(#[from] ProviderError),
@@ -233,6 +258,257 @@ pub fn insert_genesis_header<DB: Database>(
     Ok(())
 }
 
+/// A line of a state dump.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StateDumpLine {
+    /// An account with its storage.
+    Account {
+        address: Address,
+        #[serde(flatten)]
+        account: GenesisAccount,
+    },
+    /// The state root of the dump, written as the first line by `geth dump`.
+    Root { root: B256 },
+}
+
+/// Initializes the database with the state of a state dump at the block of the given header, so
+/// that the node syncs forward from this block.
+///
+/// The headers and bodies up to the block must already be imported without executing them, like
+/// with `reth import --disable-execution`, and the block must be the tip of the database. The
+/// genesis state of the database is replaced by the state dump. The receipts up to the block are
+/// not part of a state dump, so they're not written.
+///
+/// The state dump is a JSONL file with one account per line, like
+/// `{"address":"0x..","balance":"0x..","nonce":"0x1","code":"0x..","storage":{"0x..":"0x.."}}`. The
+/// accounts don't need to be sorted; they're sorted by ETL collectors, so the memory usage is
+/// bounded by the file size of the ETL config rather than the size of the dump.
+///
+/// The state is written in a single transaction, which is only committed if the computed state
+/// root matches the header.
+pub fn init_from_state_dump<DB: Database>(
+    dump: impl BufRead,
+    factory: ProviderFactory<DB>,
+    header: SealedHeader,
+    etl_config: EtlConfig,
+) -> eyre::Result<B256> {
+    let provider_rw = factory.provider_rw()?;
+    let block = header.number;
+    let tip = provider_rw.last_block_number()?;
+    eyre::ensure!(
+        tip >= block,
+        "The database ends at block {tip}, the blocks up to the block {block} of the state dump \
+         must be imported first"
+    );
+    eyre::ensure!(
+        tip == block,
+        "The database ends at block {tip}, after the block {block} of the state dump"
+    );
+    let local =
+        provider_rw.sealed_header(block)?.ok_or(ProviderError::HeaderNotFound(block.into()))?;
+    eyre::ensure!(
+        local.hash() == header.hash(),
+        "Header {} of the state dump does not match the header {} of block {block}",
+        header.hash(),
+        local.hash()
+    );
+    let executed =
+        provider_rw.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+    eyre::ensure!(executed == 0, "The database already contains the state of block {executed}");
+
+    let tx = provider_rw.tx_ref();
+    clear_state(tx)?;
+    let accounts = insert_state_dump(dump, tx, header.state_root, &etl_config)?;
+    info!(accounts, "Inserted the state dump, computing the state root.");
+
+    let computed = compute_state_root::<DB>(tx)?;
+    if computed != header.state_root {
+        return Err(
+            InitDatabaseError::StateRootMismatch { expected: header.state_root, computed }.into()
+        )
+    }
+
+    // the node continues to sync from the block of the state dump
+    for stage in StageId::ALL.iter() {
+        tx.put::<tables::StageCheckpoints>(stage.to_string(), StageCheckpoint::new(block))?;
+    }
+
+    provider_rw.commit()?;
+
+    Ok(header.hash())
+}
+
+/// Removes the state, its history and the tries from the database.
+fn clear_state(tx: &impl DbTxMut) -> ProviderResult<()> {
+    tx.clear::<tables::PlainAccountState>()?;
+    tx.clear::<tables::PlainStorageState>()?;
+    tx.clear::<tables::Bytecodes>()?;
+    tx.clear::<tables::HashedAccounts>()?;
+    tx.clear::<tables::HashedStorages>()?;
+    tx.clear::<tables::AccountsTrie>()?;
+    tx.clear::<tables::StoragesTrie>()?;
+    tx.clear::<tables::AccountChangeSets>()?;
+    tx.clear::<tables::StorageChangeSets>()?;
+    tx.clear::<tables::AccountsHistory>()?;
+    tx.clear::<tables::StoragesHistory>()?;
+    Ok(())
+}
+
+/// Inserts the accounts of a state dump into the plain and hashed state tables, returning the
+/// number of accounts.
+///
+/// The accounts are collected by ETL collectors first, so that every table is written in sorted
+/// order.
+fn insert_state_dump(
+    dump: impl BufRead,
+    tx: &(impl DbTx + DbTxMut),
+    state_root: B256,
+    etl_config: &EtlConfig,
+) -> eyre::Result<usize> {
+    // the collectors share the memory of the ETL config
+    let file_size = etl_config.file_size / 5;
+    let mut plain_accounts: Collector<Address, Account> =
+        Collector::new(file_size, etl_config.dir.clone());
+    let mut plain_storages: Collector<AddressStorageKey, StorageEntry> =
+        Collector::new(file_size, etl_config.dir.clone());
+    let mut bytecodes: Collector<B256, Bytecode> =
+        Collector::new(file_size, etl_config.dir.clone());
+    let mut hashed_accounts: Collector<B256, Account> =
+        Collector::new(file_size, etl_config.dir.clone());
+    // keyed by the hashed address followed by the hashed storage key
+    let mut hashed_storages: Collector<Vec<u8>, StorageEntry> =
+        Collector::new(file_size, etl_config.dir.clone());
+
+    let mut accounts = 0;
+    for (index, line) in dump.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue
+        }
+        let (address, genesis_account) = match serde_json::from_str(&line)
+            .wrap_err_with(|| format!("Invalid line {} of the state dump", index + 1))?
+        {
+            StateDumpLine::Account { address, account } => (address, account),
+            StateDumpLine::Root { root } => {
+                eyre::ensure!(
+                    root == state_root,
+                    "State root {root} of the state dump does not match the header {state_root}"
+                );
+                continue
+            }
+        };
+
+        let bytecode_hash = match genesis_account.code.filter(|code| !code.is_empty()) {
+            Some(code) => {
+                let bytecode = Bytecode::new_raw(code).into_analysed();
+                let hash = bytecode.hash_slow();
+                bytecodes.insert(hash, bytecode)?;
+                Some(hash)
+            }
+            None => None,
+        };
+        let account = Account {
+            nonce: genesis_account.nonce.unwrap_or_default(),
+            balance: genesis_account.balance,
+            bytecode_hash,
+        };
+        let hashed_address = keccak256(address);
+        plain_accounts.insert(address, account)?;
+        hashed_accounts.insert(hashed_address, account)?;
+
+        for (key, value) in genesis_account.storage.unwrap_or_default() {
+            let value = U256::from_be_bytes(value.0);
+            if value.is_zero() {
+                continue
+            }
+            plain_storages
+                .insert(AddressStorageKey((address, key)), StorageEntry { key, value })?;
+            let hashed_key = keccak256(key);
+            hashed_storages.insert(
+                [hashed_address.as_slice(), hashed_key.as_slice()].concat(),
+                StorageEntry { key: hashed_key, value },
+            )?;
+        }
+
+        accounts += 1;
+        if accounts % STATE_DUMP_PROGRESS_INTERVAL == 0 {
+            info!(accounts, "Read accounts of the state dump.");
+        }
+    }
+
+    let mut cursor = tx.cursor_write::<RawTable<tables::PlainAccountState>>()?;
+    let mut last = None;
+    for entry in plain_accounts.iter()? {
+        let (address, account) = entry?;
+        if last.as_ref() == Some(&address) {
+            eyre::bail!(
+                "Account {} appears more than once in the state dump",
+                Address::decode(&address)?
+            )
+        }
+        cursor.append(RawKey::from_vec(address.clone()), RawValue::from_vec(account))?;
+        last = Some(address);
+    }
+
+    // contracts with the same code share their bytecode
+    let mut cursor = tx.cursor_write::<RawTable<tables::Bytecodes>>()?;
+    let mut last = None;
+    for entry in bytecodes.iter()? {
+        let (hash, bytecode) = entry?;
+        if last.as_ref() != Some(&hash) {
+            cursor.append(RawKey::from_vec(hash.clone()), RawValue::from_vec(bytecode))?;
+            last = Some(hash);
+        }
+    }
+
+    let mut cursor = tx.cursor_write::<RawTable<tables::HashedAccounts>>()?;
+    for entry in hashed_accounts.iter()? {
+        let (hashed_address, account) = entry?;
+        cursor.append(RawKey::from_vec(hashed_address), RawValue::from_vec(account))?;
+    }
+
+    let mut cursor = tx.cursor_dup_write::<tables::PlainStorageState>()?;
+    for entry in plain_storages.iter()? {
+        let (key, storage_entry) = entry?;
+        let AddressStorageKey((address, _)) = AddressStorageKey::decode(key)?;
+        cursor.upsert(address, StorageEntry::decompress(storage_entry)?)?;
+    }
+
+    let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+    for entry in hashed_storages.iter()? {
+        let (key, storage_entry) = entry?;
+        let hashed_address = B256::from_slice(&key[..32]);
+        cursor.upsert(hashed_address, StorageEntry::decompress(storage_entry)?)?;
+    }
+
+    Ok(accounts)
+}
+
+/// Computes the state root from the hashed state tables, flushing the trie updates to the
+/// transaction whenever the intermediate progress is returned.
+fn compute_state_root<DB: Database>(tx: &<DB as Database>::TXMut) -> eyre::Result<B256> {
+    let mut intermediate_state: Option<IntermediateStateRootState> = None;
+    let mut entries_walked = 0;
+    loop {
+        match StateRoot::from_tx(tx)
+            .with_intermediate_state(intermediate_state.take())
+            .root_with_progress()?
+        {
+            StateRootProgress::Progress(state, walked, updates) => {
+                updates.flush(tx)?;
+                entries_walked += walked;
+                info!(entries_walked, "Computing the state root.");
+                intermediate_state = Some(*state);
+            }
+            StateRootProgress::Complete(root, _, updates) => {
+                updates.flush(tx)?;
+                return Ok(root)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -244,10 +520,14 @@ mod tests {
         DatabaseEnv,
     };
     use reth_primitives::{
-        Address, Chain, ForkTimestamps, Genesis, GenesisAccount, IntegerList, GOERLI,
-        GOERLI_GENESIS_HASH, MAINNET, MAINNET_GENESIS_HASH, SEPOLIA, SEPOLIA_GENESIS_HASH,
+        proofs::state_root_ref_unhashed, Bytes, Chain, ForkTimestamps, Genesis, Header,
+        IntegerList, EMPTY_ROOT_HASH, GOERLI, GOERLI_GENESIS_HASH, MAINNET, MAINNET_GENESIS_HASH,
+        SEPOLIA, SEPOLIA_GENESIS_HASH,
+    };
+    use reth_provider::{
+        test_utils::create_test_provider_factory_with_chain_spec, AccountReader, StateProvider,
+        StateProviderFactory,
     };
-    use reth_provider::test_utils::create_test_provider_factory_with_chain_spec;
 
     fn collect_table_entries<DB, T>(
         tx: &<DB as Database>::TX,
@@ -367,4 +647,137 @@ mod tests {
             )],
         );
     }
+
+    /// Writes the headers up to the given header to the static files, like an import without
+    /// execution.
+    fn import_headers<DB: Database>(factory: &ProviderFactory<DB>, last: &SealedHeader) {
+        let static_file_provider = factory.static_file_provider();
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        for number in 1..last.number {
+            let header = Header { number, ..Default::default() }.seal_slow();
+            writer.append_header(header.header().clone(), U256::ZERO, header.hash()).unwrap();
+        }
+        writer.append_header(last.header().clone(), U256::ZERO, last.hash()).unwrap();
+        drop(writer);
+        static_file_provider.commit().unwrap();
+    }
+
+    #[test]
+    fn init_from_state_dump_at_block() {
+        let code = Bytes::from_static(&[0x60, 0x00]);
+        let alloc = BTreeMap::from([
+            (
+                Address::with_last_byte(1),
+                GenesisAccount { balance: U256::from(1), ..Default::default() },
+            ),
+            (
+                Address::with_last_byte(2),
+                GenesisAccount {
+                    nonce: Some(1),
+                    code: Some(code.clone()),
+                    storage: Some(BTreeMap::from([(
+                        B256::with_last_byte(1),
+                        B256::with_last_byte(2),
+                    )])),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let state_root = state_root_ref_unhashed(&alloc);
+        // the accounts don't need to be sorted
+        let dump = [
+            serde_json::json!({ "root": state_root }),
+            serde_json::json!({
+                "address": Address::with_last_byte(2),
+                "nonce": "0x1",
+                "balance": "0x0",
+                "code": code,
+                "storage": { B256::with_last_byte(1).to_string(): B256::with_last_byte(2) },
+            }),
+            serde_json::json!({ "address": Address::with_last_byte(1), "balance": "0x1" }),
+        ]
+        .map(|line| line.to_string())
+        .join("\n");
+        let genesis_address = *MAINNET.genesis.alloc.keys().next().unwrap();
+
+        // the computed state root must match the header, otherwise nothing is written
+        let accounts = dump.split_once('\n').unwrap().1;
+        let header = Header { number: 5, ..Default::default() }.seal_slow();
+        let factory = create_test_provider_factory_with_chain_spec(MAINNET.clone());
+        init_genesis(factory.clone()).unwrap();
+        import_headers(&factory, &header);
+        let err = init_from_state_dump(
+            accounts.as_bytes(),
+            factory.clone(),
+            header,
+            EtlConfig::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<InitDatabaseError>(),
+            Some(&InitDatabaseError::StateRootMismatch {
+                expected: EMPTY_ROOT_HASH,
+                computed: state_root
+            })
+        );
+        let provider = factory.provider().unwrap();
+        assert!(provider.basic_account(genesis_address).unwrap().is_some());
+        assert_eq!(provider.basic_account(Address::with_last_byte(1)).unwrap(), None);
+        assert_eq!(provider.best_block_number().unwrap(), 0);
+
+        // the blocks up to the header must be imported first
+        let header = Header { number: 5, state_root, ..Default::default() }.seal_slow();
+        let factory = create_test_provider_factory_with_chain_spec(MAINNET.clone());
+        init_genesis(factory.clone()).unwrap();
+        assert!(init_from_state_dump(
+            dump.as_bytes(),
+            factory.clone(),
+            header.clone(),
+            EtlConfig::default()
+        )
+        .is_err());
+
+        import_headers(&factory, &header);
+        let other = Header { number: 5, state_root, gas_limit: 1, ..Default::default() };
+        assert!(init_from_state_dump(
+            dump.as_bytes(),
+            factory.clone(),
+            other.seal_slow(),
+            EtlConfig::default()
+        )
+        .is_err());
+
+        let hash = init_from_state_dump(
+            dump.as_bytes(),
+            factory.clone(),
+            header.clone(),
+            EtlConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(hash, header.hash());
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.block_hash(0).unwrap(), Some(MAINNET_GENESIS_HASH));
+        assert_eq!(provider.block_hash(5).unwrap(), Some(header.hash()));
+        assert_eq!(provider.best_block_number().unwrap(), 5);
+        // the genesis state is replaced
+        assert_eq!(provider.basic_account(genesis_address).unwrap(), None);
+        assert_eq!(
+            provider.basic_account(Address::with_last_byte(2)).unwrap(),
+            Some(Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(keccak256(&code)) })
+        );
+        assert_eq!(
+            factory
+                .latest()
+                .unwrap()
+                .storage(Address::with_last_byte(2), B256::with_last_byte(1))
+                .unwrap(),
+            Some(U256::from(2))
+        );
+
+        // the state can't be initialized twice
+        let err = init_from_state_dump(dump.as_bytes(), factory, header, EtlConfig::default())
+            .unwrap_err();
+        assert!(err.to_string().contains("already contains the state"), "{err}");
+    }
 }
@@ -17,6 +17,7 @@ pub mod exit;
 pub mod init;
 pub mod metrics;
 pub mod node_config;
+pub mod rpc_consensus;
 pub mod utils;
 pub mod version;
 
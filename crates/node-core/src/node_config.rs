@@ -3,7 +3,8 @@
 use crate::{
     args::{
         get_secret_key, DatabaseArgs, DebugArgs, DevArgs, DiscoveryArgs, NetworkArgs,
-        PayloadBuilderArgs, PruningArgs, RpcServerArgs, TxPoolArgs,
+        PayloadBuilderArgs, PruningArgs, RpcServerArgs, StageArgs, StaticFilesArgs, TreeArgs,
+        TxPoolArgs,
     },
     dirs::{ChainPath, DataDirPath},
     metrics::prometheus_exporter,
@@ -146,6 +147,15 @@ pub struct NodeConfig {
 
     /// All pruning related arguments
     pub pruning: PruningArgs,
+
+    /// All static files related arguments
+    pub static_files: StaticFilesArgs,
+
+    /// All stage related arguments with --stage prefix
+    pub stages: StageArgs,
+
+    /// All blockchain tree related arguments with --tree prefix
+    pub tree: TreeArgs,
 }
 
 impl NodeConfig {
@@ -234,6 +244,24 @@ impl NodeConfig {
         self
     }
 
+    /// Set the static files args for the node
+    pub fn with_static_files(mut self, static_files: StaticFilesArgs) -> Self {
+        self.static_files = static_files;
+        self
+    }
+
+    /// Set the stage args for the node
+    pub fn with_stages(mut self, stages: StageArgs) -> Self {
+        self.stages = stages;
+        self
+    }
+
+    /// Set the blockchain tree args for the node
+    pub fn with_tree(mut self, tree: TreeArgs) -> Self {
+        self.tree = tree;
+        self
+    }
+
     /// Get the network secret from the given data dir
     pub fn network_secret(&self, data_dir: &ChainPath<DataDirPath>) -> eyre::Result<SecretKey> {
         let network_secret_path =
@@ -540,6 +568,9 @@ impl Default for NodeConfig {
             db: DatabaseArgs::default(),
             dev: DevArgs::default(),
             pruning: PruningArgs::default(),
+            static_files: StaticFilesArgs::default(),
+            stages: StageArgs::default(),
+            tree: TreeArgs::default(),
         }
     }
 }
@@ -0,0 +1,334 @@
+//! Follows the chain of a reference node over RPC instead of a consensus layer.
+//!
+//! This is meant for shadow-syncing an execution client without a consensus layer: the head of
+//! the reference node is polled and sent to the engine as forkchoice updates, so the node keeps
+//! following the reference node indefinitely, including its reorgs.
+
+use crate::args::FollowBlock;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use reth_beacon_consensus::{BeaconConsensusEngineHandle, BeaconForkChoiceUpdateError};
+use reth_node_api::EngineTypes;
+use reth_primitives::{BlockNumberOrTag, B256};
+use reth_rpc_api::EthApiClient;
+use reth_rpc_types::engine::{ForkchoiceState, PayloadStatusEnum};
+use reth_tasks::shutdown::Shutdown;
+use std::time::Duration;
+use tracing::*;
+
+/// The default interval at which the head of the reference node is polled.
+pub const DEFAULT_RPC_CONSENSUS_POLL_INTERVAL: Duration = Duration::from_secs(4);
+
+/// The maximum interval at which the reference node is polled after consecutive errors.
+pub const MAX_RPC_CONSENSUS_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Sends the head of a reference node, polled over RPC, to the engine as forkchoice updates.
+#[derive(Debug)]
+pub struct RpcConsensusClient<Engine: EngineTypes> {
+    /// The URL of the reference node.
+    url: String,
+    /// The client of the reference node.
+    client: HttpClient,
+    /// The block of the reference node that's sent as the head.
+    follow: FollowBlock,
+    /// The handle of the engine the forkchoice updates are sent to.
+    engine: BeaconConsensusEngineHandle<Engine>,
+    /// The interval at which the reference node is polled.
+    poll_interval: Duration,
+}
+
+impl<Engine: EngineTypes> RpcConsensusClient<Engine> {
+    /// Creates a new client that follows the given block of the reference node at the URL.
+    pub fn new(
+        url: impl Into<String>,
+        follow: FollowBlock,
+        engine: BeaconConsensusEngineHandle<Engine>,
+    ) -> eyre::Result<Self> {
+        let url = url.into();
+        let client = HttpClientBuilder::default().build(&url)?;
+        Ok(Self { url, client, follow, engine, poll_interval: DEFAULT_RPC_CONSENSUS_POLL_INTERVAL })
+    }
+
+    /// Sets the interval at which the reference node is polled.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Polls the reference node and sends every change of its forkchoice state to the engine,
+    /// until the node shuts down or the engine stops.
+    ///
+    /// The polling backs off while the reference node or the engine return errors. A shutdown
+    /// also interrupts a poll that's in progress.
+    pub async fn run(self, mut shutdown: Shutdown) {
+        info!(
+            target: "reth::cli",
+            url = %self.url,
+            follow = ?self.follow,
+            "Following the chain of the reference node"
+        );
+
+        let mut last_state = None;
+        let mut delay = Duration::ZERO;
+        loop {
+            let poll = async {
+                tokio::time::sleep(delay).await;
+                self.poll(&mut last_state).await
+            };
+            let outcome = tokio::select! {
+                _ = &mut shutdown => break,
+                outcome = poll => outcome,
+            };
+
+            delay = match outcome {
+                PollOutcome::Polled => self.poll_interval,
+                PollOutcome::Failed => {
+                    let delay = next_backoff(delay, self.poll_interval);
+                    debug!(target: "reth::cli", ?delay, "Backing off polling the reference node");
+                    delay
+                }
+                PollOutcome::EngineStopped => break,
+            };
+        }
+    }
+
+    /// Polls the reference node once and sends its forkchoice state to the engine if it changed
+    /// since the last successful update.
+    async fn poll(&self, last_state: &mut Option<ForkchoiceState>) -> PollOutcome {
+        let state = match self.fetch_forkchoice_state().await {
+            Ok(state) => state,
+            Err(err) => {
+                warn!(
+                    target: "reth::cli",
+                    url = %self.url,
+                    %err,
+                    "Failed to fetch the head of the reference node"
+                );
+                return PollOutcome::Failed
+            }
+        };
+
+        let Some(state) = state else {
+            debug!(
+                target: "reth::cli",
+                follow = ?self.follow,
+                "Reference node has no block to follow yet"
+            );
+            return PollOutcome::Polled
+        };
+        if *last_state == Some(state) {
+            return PollOutcome::Polled
+        }
+
+        // the remote head is always sent as the head, so a reorg of the reference node is
+        // followed as well
+        match self.engine.fork_choice_updated(state, None).await {
+            Ok(response) => {
+                let status = response.payload_status.status;
+                if matches!(status, PayloadStatusEnum::Invalid { .. }) {
+                    warn!(
+                        target: "reth::cli",
+                        head = %state.head_block_hash,
+                        ?status,
+                        "Forkchoice update of the reference node is invalid"
+                    );
+                } else {
+                    debug!(
+                        target: "reth::cli",
+                        head = %state.head_block_hash,
+                        ?status,
+                        "Sent forkchoice update of the reference node"
+                    );
+                }
+                *last_state = Some(state);
+                PollOutcome::Polled
+            }
+            Err(BeaconForkChoiceUpdateError::EngineUnavailable) => {
+                debug!(
+                    target: "reth::cli",
+                    "Consensus engine stopped, no longer following the reference node"
+                );
+                PollOutcome::EngineStopped
+            }
+            Err(err) => {
+                warn!(
+                    target: "reth::cli",
+                    head = %state.head_block_hash,
+                    %err,
+                    "Failed to send forkchoice update of the reference node"
+                );
+                PollOutcome::Failed
+            }
+        }
+    }
+
+    /// Returns the forkchoice state of the reference node, or `None` if the followed block is not
+    /// known yet.
+    ///
+    /// The safe and finalized blocks are zero if the reference node doesn't know them.
+    async fn fetch_forkchoice_state(&self) -> eyre::Result<Option<ForkchoiceState>> {
+        let finalized = self.fetch_block_hash(BlockNumberOrTag::Finalized).await?;
+        let (head, safe) = match self.follow {
+            FollowBlock::Latest => (
+                self.fetch_block_hash(BlockNumberOrTag::Latest).await?,
+                self.fetch_block_hash(BlockNumberOrTag::Safe).await?,
+            ),
+            FollowBlock::Finalized => (finalized, finalized),
+        };
+
+        Ok(head.map(|head_block_hash| ForkchoiceState {
+            head_block_hash,
+            safe_block_hash: safe.unwrap_or_default(),
+            finalized_block_hash: finalized.unwrap_or_default(),
+        }))
+    }
+
+    /// Returns the hash of the block of the reference node with the given tag.
+    async fn fetch_block_hash(&self, tag: BlockNumberOrTag) -> eyre::Result<Option<B256>> {
+        let block = EthApiClient::block_by_number(&self.client, tag, false).await?;
+        Ok(block.and_then(|block| block.header.hash))
+    }
+}
+
+/// The outcome of polling the reference node once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PollOutcome {
+    /// The reference node was polled and its forkchoice state, if it changed, was sent to the
+    /// engine.
+    Polled,
+    /// The reference node or the engine returned an error.
+    Failed,
+    /// The engine stopped.
+    EngineStopped,
+}
+
+/// Returns the delay before the next poll after an error, which doubles on every consecutive
+/// error until [MAX_RPC_CONSENSUS_BACKOFF].
+fn next_backoff(delay: Duration, poll_interval: Duration) -> Duration {
+    delay.saturating_mul(2).max(poll_interval).min(MAX_RPC_CONSENSUS_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpsee::{
+        server::{ServerBuilder, ServerHandle},
+        types::ErrorObjectOwned,
+        RpcModule,
+    };
+    use reth_beacon_consensus::BeaconEngineMessage;
+    use reth_ethereum_engine_primitives::EthEngineTypes;
+    use reth_interfaces::RethError;
+    use reth_primitives::{BlockWithSenders, U256};
+    use reth_rpc_types_compat::block::from_block_with_tx_hashes;
+    use reth_tasks::shutdown::signal;
+    use std::{net::SocketAddr, time::Instant};
+    use tokio::sync::mpsc;
+
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// Starts a reference node that returns the same block with the given hash for every tag, after
+    /// the given delay.
+    async fn launch_reference_node(hash: B256, delay: Duration) -> (String, ServerHandle) {
+        let server = ServerBuilder::default()
+            .build("127.0.0.1:0".parse::<SocketAddr>().unwrap())
+            .await
+            .unwrap();
+        let url = format!("http://{}", server.local_addr().unwrap());
+
+        let block = from_block_with_tx_hashes(BlockWithSenders::default(), U256::ZERO, Some(hash));
+        let mut module = RpcModule::new(());
+        module
+            .register_async_method("eth_getBlockByNumber", move |_, _| {
+                let block = block.clone();
+                async move {
+                    tokio::time::sleep(delay).await;
+                    Ok::<_, ErrorObjectOwned>(block)
+                }
+            })
+            .unwrap();
+
+        (url, server.start(module))
+    }
+
+    fn client(
+        url: String,
+    ) -> (
+        RpcConsensusClient<EthEngineTypes>,
+        mpsc::UnboundedReceiver<BeaconEngineMessage<EthEngineTypes>>,
+    ) {
+        let (to_engine, from_client) = mpsc::unbounded_channel();
+        let client = RpcConsensusClient::new(
+            url,
+            FollowBlock::Latest,
+            BeaconConsensusEngineHandle::new(to_engine),
+        )
+        .unwrap()
+        .with_poll_interval(POLL_INTERVAL);
+        (client, from_client)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn backs_off_on_engine_errors_and_stops_with_engine() {
+        let hash = B256::with_last_byte(1);
+        let (url, _server) = launch_reference_node(hash, Duration::ZERO).await;
+        let (client, mut from_client) = client(url);
+
+        let (_signal, shutdown) = signal();
+        let run = tokio::spawn(client.run(shutdown));
+
+        // the failed updates are retried with a growing delay, the last one stops the engine
+        let mut received = Vec::new();
+        for i in 0..3 {
+            let Some(BeaconEngineMessage::ForkchoiceUpdated { state, tx, .. }) =
+                from_client.recv().await
+            else {
+                panic!("expected a forkchoice update")
+            };
+            assert_eq!(
+                state,
+                ForkchoiceState {
+                    head_block_hash: hash,
+                    safe_block_hash: hash,
+                    finalized_block_hash: hash
+                }
+            );
+            received.push(Instant::now());
+            if i < 2 {
+                tx.send(Err(RethError::Custom("engine error".to_string()))).unwrap();
+            } else {
+                drop(tx);
+            }
+        }
+
+        assert!(received[1] - received[0] >= POLL_INTERVAL);
+        assert!(received[2] - received[1] >= POLL_INTERVAL * 2);
+        tokio::time::timeout(Duration::from_secs(5), run).await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let poll_interval = Duration::from_secs(4);
+        let mut delay = Duration::ZERO;
+        let mut delays = Vec::new();
+        for _ in 0..6 {
+            delay = next_backoff(delay, poll_interval);
+            delays.push(delay.as_secs());
+        }
+        assert_eq!(delays, vec![4, 8, 16, 32, 60, 60]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn shutdown_interrupts_in_flight_poll() {
+        let (url, _server) =
+            launch_reference_node(B256::with_last_byte(1), Duration::from_secs(30)).await;
+        let (client, _from_client) = client(url);
+
+        let (signal, shutdown) = signal();
+        let run = tokio::spawn(client.run(shutdown));
+
+        // let the first poll reach the reference node
+        tokio::time::sleep(POLL_INTERVAL).await;
+        signal.fire();
+        tokio::time::timeout(Duration::from_secs(5), run).await.unwrap().unwrap();
+    }
+}
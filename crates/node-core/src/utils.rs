@@ -8,16 +8,16 @@ use reth_interfaces::p2p::{
     headers::client::{HeadersClient, HeadersRequest},
     priority::Priority,
 };
-use reth_network::NetworkManager;
-use reth_primitives::{
-    fs, BlockHashOrNumber, ChainSpec, HeadersDirection, SealedBlock, SealedHeader,
-};
+use reth_network::{peers::write_known_peers, NetworkManager};
+use reth_network_api::{KnownPeer, Peers};
+use reth_primitives::{BlockHashOrNumber, ChainSpec, HeadersDirection, SealedBlock, SealedHeader};
 use reth_provider::BlockReader;
 use reth_rpc::{JwtError, JwtSecret};
 use std::{
     env::VarError,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 use tracing::{debug, info, trace, warn};
 
@@ -38,25 +38,53 @@ pub fn get_or_create_jwt_secret_from_path(path: &Path) -> Result<JwtSecret, JwtE
     }
 }
 
-/// Collect the peers from the [NetworkManager] and write them to the given `persistent_peers_file`,
-/// if configured.
-pub fn write_peers_to_file<C>(network: &NetworkManager<C>, persistent_peers_file: Option<PathBuf>)
-where
+/// Collect the known peers from the [NetworkManager] and write them to the given
+/// `persistent_peers_file`, if configured, keeping at most `max_entries` of the most recently seen
+/// peers.
+pub fn write_peers_to_file<C>(
+    network: &NetworkManager<C>,
+    persistent_peers_file: Option<PathBuf>,
+    max_entries: usize,
+) where
     C: BlockReader + Unpin,
 {
     if let Some(file_path) = persistent_peers_file {
-        let known_peers = network.all_peers().collect::<Vec<_>>();
-        if let Ok(known_peers) = serde_json::to_string_pretty(&known_peers) {
-            trace!(target: "reth::cli", peers_file =?file_path, num_peers=%known_peers.len(), "Saving current peers");
-            let parent_dir = file_path.parent().map(fs::create_dir_all).transpose();
-            match parent_dir.and_then(|_| fs::write(&file_path, known_peers)) {
-                Ok(_) => {
-                    info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
-                }
-                Err(err) => {
-                    warn!(target: "reth::cli", %err, peers_file=?file_path, "Failed to write network peers to file");
-                }
-            }
+        write_known_peers_to_file(&file_path, network.known_peers(), max_entries);
+    }
+}
+
+/// Periodically collect the known peers from the network and write them to the given
+/// `persistent_peers_file`, keeping at most `max_entries` of the most recently seen peers.
+///
+/// The peers are written on a blocking task. Returns once the network was shut down.
+pub async fn write_peers_to_file_periodically<N: Peers>(
+    network: N,
+    persistent_peers_file: PathBuf,
+    period: Duration,
+    max_entries: usize,
+) {
+    let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+    loop {
+        interval.tick().await;
+        let Ok(known_peers) = network.get_known_peers().await else { return };
+        let file_path = persistent_peers_file.clone();
+        let write = tokio::task::spawn_blocking(move || {
+            write_known_peers_to_file(&file_path, known_peers, max_entries)
+        });
+        if let Err(err) = write.await {
+            warn!(target: "reth::cli", %err, peers_file=?persistent_peers_file, "Failed to write network peers to file");
+        }
+    }
+}
+
+fn write_known_peers_to_file(file_path: &Path, known_peers: Vec<KnownPeer>, max_entries: usize) {
+    trace!(target: "reth::cli", peers_file=?file_path, num_peers=%known_peers.len(), "Saving current peers");
+    match write_known_peers(file_path, known_peers, max_entries) {
+        Ok(_) => {
+            info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
+        }
+        Err(err) => {
+            warn!(target: "reth::cli", %err, peers_file=?file_path, "Failed to write network peers to file");
         }
     }
 }
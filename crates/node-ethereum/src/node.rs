@@ -84,8 +84,11 @@ where
     async fn build_pool(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::Pool> {
         let data_dir = ctx.data_dir();
         let blob_store = DiskFileBlobStore::open(data_dir.blobstore_path(), Default::default())?;
+        let pool_config = ctx.pool_config();
         let validator = TransactionValidationTaskExecutor::eth_builder(ctx.chain_spec())
             .with_head_timestamp(ctx.head().timestamp)
+            .set_minimum_priority_fee(pool_config.minimum_priority_fee)
+            .set_local_transactions_config(pool_config.local_transactions_config.clone())
             .kzg_settings(ctx.kzg_settings()?)
             .with_additional_tasks(1)
             .build_with_tasks(
@@ -95,17 +98,15 @@ where
             );
 
         let transaction_pool =
-            reth_transaction_pool::Pool::eth_pool(validator, blob_store, ctx.pool_config());
+            reth_transaction_pool::Pool::eth_pool(validator, blob_store, pool_config);
         info!(target: "reth::cli", "Transaction pool initialized");
-        let transactions_path = data_dir.txpool_transactions_path();
 
         // spawn txpool maintenance task
         {
             let pool = transaction_pool.clone();
             let chain_events = ctx.provider().canonical_state_stream();
             let client = ctx.provider().clone();
-            let transactions_backup_config =
-                reth_transaction_pool::maintain::LocalTransactionBackupConfig::with_local_txs_backup(transactions_path);
+            let transactions_backup_config = ctx.transactions_backup_config();
 
             ctx.task_executor().spawn_critical_with_graceful_shutdown_signal(
                 "local transactions backup task",
@@ -126,7 +127,7 @@ where
                     pool,
                     chain_events,
                     ctx.task_executor().clone(),
-                    Default::default(),
+                    ctx.maintain_pool_config(),
                 ),
             );
             debug!(target: "reth::cli", "Spawned txpool maintenance task");
@@ -151,12 +152,15 @@ where
         ctx: &BuilderContext<Node>,
         pool: Pool,
     ) -> eyre::Result<PayloadBuilderHandle<Node::Engine>> {
-        let payload_builder = reth_ethereum_payload_builder::EthereumPayloadBuilder::default();
+        // execute the payload transactions with the same evm as the blocks of the node
+        let payload_builder =
+            reth_ethereum_payload_builder::EthereumPayloadBuilder::new(ctx.evm_config().clone());
         let conf = ctx.payload_builder_config();
 
         let payload_job_config = BasicPayloadJobGeneratorConfig::default()
             .interval(conf.interval())
             .deadline(conf.deadline())
+            .rebuild_on_tx(conf.rebuild_on_tx())
             .max_payload_tasks(conf.max_payload_tasks())
             .extradata(conf.extradata_bytes())
             .max_gas_limit(conf.max_gas_limit());
@@ -185,6 +185,10 @@ where
         self.inner.on_new_head_block(new_tip_block);
         self.update_l1_block_info(&new_tip_block.clone().unseal());
     }
+
+    fn on_new_minimum_priority_fee(&self, minimum_priority_fee: Option<u128>) {
+        self.inner.on_new_minimum_priority_fee(minimum_priority_fee)
+    }
 }
 
 /// Tracks additional infos for the current block.
@@ -8,11 +8,11 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-use crate::metrics::PayloadBuilderMetrics;
+use crate::metrics::{PayloadBuilderMetrics, PayloadStrategyMetrics};
 use futures_core::ready;
-use futures_util::FutureExt;
+use futures_util::{FutureExt, StreamExt};
 use reth_interfaces::RethResult;
-use reth_node_api::{BuiltPayload, PayloadBuilderAttributes};
+use reth_node_api::{BuiltPayload, ConfigureEvm, PayloadBuilderAttributes};
 use reth_payload_builder::{
     database::CachedReads, error::PayloadBuilderError, KeepPayloadJobAlive, PayloadId, PayloadJob,
     PayloadJobGenerator,
@@ -28,12 +28,13 @@ use reth_revm::state_change::{
     apply_beacon_root_contract_call, post_block_withdrawals_balance_increments,
 };
 use reth_tasks::TaskSpawner;
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{NewSubpoolTransactionStream, PoolTransaction, TransactionPool};
 use revm::{
     primitives::{BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg},
-    Database, DatabaseCommit, Evm, State,
+    Database, DatabaseCommit, State,
 };
 use std::{
+    fmt,
     future::Future,
     pin::Pin,
     sync::{atomic::AtomicBool, Arc},
@@ -177,6 +178,15 @@ where
 
         let cached_reads = self.maybe_pre_cached(config.parent_block.hash());
 
+        let rebuild = self.config.rebuild_on_tx.map(|debounce| {
+            TransactionRebuild::new(self.pool.new_pending_pool_transactions_listener(), debounce)
+        });
+
+        let mut strategies = self.builder.strategies(&config);
+        if strategies.is_empty() {
+            strategies.push(PayloadStrategy::DEFAULT);
+        }
+
         Ok(BasicPayloadJob {
             config,
             client: self.client.clone(),
@@ -184,8 +194,12 @@ where
             executor: self.executor.clone(),
             deadline,
             interval: tokio::time::interval(self.config.interval),
+            strategies,
             best_payload: None,
-            pending_block: None,
+            best_strategy: None,
+            best_lowest_tip: None,
+            rebuild,
+            pending_blocks: Vec::new(),
             cached_reads,
             payload_task_guard: self.payload_task_guard.clone(),
             metrics: Default::default(),
@@ -249,6 +263,10 @@ pub struct BasicPayloadJobGeneratorConfig {
     ///
     /// By default this is [SLOT_DURATION]: 12s
     deadline: Duration,
+    /// The minimum time between two re-builds that are triggered by new pending transactions.
+    ///
+    /// By default this is `None` and payloads are only re-built at the interval.
+    rebuild_on_tx: Option<Duration>,
     /// Maximum number of tasks to spawn for building a payload.
     max_payload_tasks: usize,
 }
@@ -268,6 +286,17 @@ impl BasicPayloadJobGeneratorConfig {
         self
     }
 
+    /// Sets whether the job should immediately re-build the payload when a new pending transaction
+    /// pays a higher tip than the lowest tip included in the best payload.
+    ///
+    /// The given duration is the minimum time between two such re-builds, `None` disables them.
+    /// Builds that are in progress are never cancelled for a re-build, it starts once they
+    /// finished.
+    pub fn rebuild_on_tx(mut self, debounce: Option<Duration>) -> Self {
+        self.rebuild_on_tx = debounce;
+        self
+    }
+
     /// Sets the maximum number of tasks to spawn for building a payload(s).
     ///
     /// # Panics
@@ -304,6 +333,7 @@ impl Default for BasicPayloadJobGeneratorConfig {
             interval: Duration::from_secs(1),
             // 12s slot time
             deadline: SLOT_DURATION,
+            rebuild_on_tx: None,
             max_payload_tasks: 3,
         }
     }
@@ -313,6 +343,7 @@ impl Default for BasicPayloadJobGeneratorConfig {
 #[derive(Debug)]
 pub struct BasicPayloadJob<Client, Pool, Tasks, Builder>
 where
+    Pool: TransactionPool,
     Builder: PayloadBuilder<Pool, Client>,
 {
     /// The configuration for how the payload will be created.
@@ -327,10 +358,18 @@ where
     deadline: Pin<Box<Sleep>>,
     /// The interval at which the job should build a new payload after the last.
     interval: Interval,
+    /// The strategies to build competing payloads with, see [PayloadBuilder::strategies].
+    strategies: Vec<PayloadStrategy>,
     /// The best payload so far.
     best_payload: Option<Builder::BuiltPayload>,
-    /// Receiver for the block that is currently being built.
-    pending_block: Option<PendingPayload<Builder::BuiltPayload>>,
+    /// The strategy the best payload was built with.
+    best_strategy: Option<PayloadStrategy>,
+    /// The lowest effective tip of the transactions in the best payload.
+    best_lowest_tip: Option<u128>,
+    /// Triggers re-builds on new pending transactions, if enabled.
+    rebuild: Option<TransactionRebuild<Pool::Transaction>>,
+    /// Receivers for the blocks that are currently being built, one per strategy.
+    pending_blocks: Vec<PendingPayload<Builder::BuiltPayload>>,
     /// Restricts how many generator tasks can be executed at once.
     payload_task_guard: PayloadTaskGuard,
     /// Caches all disk reads for the state the new payloads builds on
@@ -346,6 +385,66 @@ where
     builder: Builder,
 }
 
+impl<Client, Pool, Tasks, Builder> BasicPayloadJob<Client, Pool, Tasks, Builder>
+where
+    Client: StateProviderFactory + Clone + Unpin + 'static,
+    Pool: TransactionPool + Unpin + 'static,
+    Tasks: TaskSpawner + Clone + 'static,
+    Builder: PayloadBuilder<Pool, Client> + Unpin + 'static,
+    <Builder as PayloadBuilder<Pool, Client>>::Attributes: Unpin + Clone,
+    <Builder as PayloadBuilder<Pool, Client>>::BuiltPayload: Unpin + Clone,
+{
+    /// Spawns a new payload build task for every strategy.
+    fn spawn_builds(&mut self) {
+        // the new builds include all transactions that are currently pending
+        if let Some(rebuild) = self.rebuild.as_mut() {
+            rebuild.pending = false;
+        }
+
+        let mut reads = self.cached_reads.take().unwrap_or_default();
+        for (idx, &strategy) in self.strategies.iter().enumerate() {
+            trace!(target: "payload_builder", %strategy, "spawn new payload build task");
+            // only the builds of the additional strategies need their own copy of the cached reads
+            let cached_reads = if idx + 1 < self.strategies.len() {
+                reads.clone()
+            } else {
+                std::mem::take(&mut reads)
+            };
+            let (tx, rx) = oneshot::channel();
+            let client = self.client.clone();
+            let pool = self.pool.clone();
+            let cancel = Cancelled::default();
+            let _cancel = cancel.clone();
+            let guard = self.payload_task_guard.clone();
+            let payload_config = self.config.clone();
+            let best_payload = self.best_payload.clone();
+            self.metrics.inc_initiated_payload_builds();
+            let builder = self.builder.clone();
+            self.executor.spawn_blocking(Box::pin(async move {
+                // acquire the permit for executing the task
+                let _permit = guard.0.acquire().await;
+                if cancel.is_cancelled() {
+                    // the build was cancelled while waiting for the permit
+                    return
+                }
+                let args = BuildArguments {
+                    client,
+                    pool,
+                    cached_reads,
+                    config: payload_config,
+                    cancel,
+                    best_payload,
+                    strategy,
+                };
+                let result = builder.try_build(args);
+                let _ = tx.send(result);
+            }));
+
+            self.pending_blocks.push(PendingPayload { _cancel, strategy, payload: rx });
+        }
+    }
+}
+
 impl<Client, Pool, Tasks, Builder> Future for BasicPayloadJob<Client, Pool, Tasks, Builder>
 where
     Client: StateProviderFactory + Clone + Unpin + 'static,
@@ -362,74 +461,74 @@ where
 
         // check if the deadline is reached
         if this.deadline.as_mut().poll(cx).is_ready() {
-            trace!(target: "payload_builder", "payload building deadline reached");
+            trace!(target: "payload_builder", pending = this.pending_blocks.len(), "payload building deadline reached");
+            // dropping the pending builds cancels them
+            this.pending_blocks.clear();
             return Poll::Ready(Ok(()))
         }
 
+        // check if a new pending transaction warrants an immediate re-build, which waits for the
+        // pending builds so that they can finish on a busy pool
+        let base_fee = this.config.initialized_block_env.basefee.to::<u64>();
+        if let Some(rebuild) = this.rebuild.as_mut() {
+            let is_idle = this.pending_blocks.is_empty();
+            if rebuild.poll_rebuild(cx, this.best_lowest_tip, base_fee, is_idle) {
+                trace!(target: "payload_builder", "re-build on new pending transaction");
+                this.spawn_builds();
+                this.interval.reset();
+            }
+        }
+
         // check if the interval is reached
         while this.interval.poll_tick(cx).is_ready() {
-            // start a new job if there is no pending block and we haven't reached the deadline
-            if this.pending_block.is_none() {
-                trace!(target: "payload_builder", "spawn new payload build task");
-                let (tx, rx) = oneshot::channel();
-                let client = this.client.clone();
-                let pool = this.pool.clone();
-                let cancel = Cancelled::default();
-                let _cancel = cancel.clone();
-                let guard = this.payload_task_guard.clone();
-                let payload_config = this.config.clone();
-                let best_payload = this.best_payload.clone();
-                this.metrics.inc_initiated_payload_builds();
-                let cached_reads = this.cached_reads.take().unwrap_or_default();
-                let builder = this.builder.clone();
-                this.executor.spawn_blocking(Box::pin(async move {
-                    // acquire the permit for executing the task
-                    let _permit = guard.0.acquire().await;
-                    let args = BuildArguments {
-                        client,
-                        pool,
-                        cached_reads,
-                        config: payload_config,
-                        cancel,
-                        best_payload,
-                    };
-                    let result = builder.try_build(args);
-                    let _ = tx.send(result);
-                }));
-
-                this.pending_block = Some(PendingPayload { _cancel, payload: rx });
+            // start new jobs if there are no pending blocks and we haven't reached the deadline
+            if this.pending_blocks.is_empty() {
+                this.spawn_builds();
             }
         }
 
-        // poll the pending block
-        if let Some(mut fut) = this.pending_block.take() {
-            match fut.poll_unpin(cx) {
-                Poll::Ready(Ok(outcome)) => {
-                    this.interval.reset();
-                    match outcome {
-                        BuildOutcome::Better { payload, cached_reads } => {
-                            this.cached_reads = Some(cached_reads);
-                            debug!(target: "payload_builder", value = %payload.fees(), "built better payload");
-                            let payload = payload;
-                            this.best_payload = Some(payload);
-                        }
-                        BuildOutcome::Aborted { fees, cached_reads } => {
-                            this.cached_reads = Some(cached_reads);
-                            trace!(target: "payload_builder", worse_fees = %fees, "skipped payload build of worse block");
-                        }
-                        BuildOutcome::Cancelled => {
-                            unreachable!("the cancel signal never fired")
-                        }
+        // poll the pending blocks
+        let mut idx = 0;
+        while idx < this.pending_blocks.len() {
+            let Poll::Ready(res) = this.pending_blocks[idx].poll_unpin(cx) else {
+                idx += 1;
+                continue
+            };
+            let strategy = this.pending_blocks.swap_remove(idx).strategy;
+            if this.pending_blocks.is_empty() {
+                this.interval.reset();
+                if this.rebuild.as_ref().is_some_and(|rebuild| rebuild.pending) {
+                    // poll again to start the re-build that waited for the pending builds
+                    cx.waker().wake_by_ref();
+                }
+            }
+
+            match res {
+                Ok(BuildOutcome::Better { payload, cached_reads }) => {
+                    this.cached_reads = Some(cached_reads);
+                    // the payloads of the strategies are built concurrently, so this is only
+                    // better than the best payload at the time the build was started
+                    if is_better_payload(this.best_payload.as_ref(), payload.fees()) {
+                        debug!(target: "payload_builder", value = %payload.fees(), %strategy, "built better payload");
+                        this.best_lowest_tip = Some(lowest_tip(payload.block(), base_fee));
+                        this.best_payload = Some(payload);
+                        this.best_strategy = Some(strategy);
+                    } else {
+                        trace!(target: "payload_builder", worse_fees = %payload.fees(), %strategy, "skipped payload outbid by another strategy");
                     }
                 }
-                Poll::Ready(Err(error)) => {
+                Ok(BuildOutcome::Aborted { fees, cached_reads }) => {
+                    this.cached_reads = Some(cached_reads);
+                    trace!(target: "payload_builder", worse_fees = %fees, %strategy, "skipped payload build of worse block");
+                }
+                Ok(BuildOutcome::Cancelled) => {
+                    unreachable!("the cancel signal never fired")
+                }
+                Err(error) => {
                     // job failed, but we simply try again next interval
-                    debug!(target: "payload_builder", %error, "payload build attempt failed");
+                    debug!(target: "payload_builder", %error, %strategy, "payload build attempt failed");
                     this.metrics.inc_failed_payload_builds();
                 }
-                Poll::Pending => {
-                    this.pending_block = Some(fut);
-                }
             }
         }
 
@@ -461,7 +560,7 @@ where
         // away and the first full block should have been built by the time CL is requesting the
         // payload.
         self.metrics.inc_requested_empty_payload();
-        Builder::build_empty_payload(&self.client, self.config.clone())
+        self.builder.build_empty_payload(&self.client, self.config.clone())
     }
 
     fn payload_attributes(&self) -> Result<Self::PayloadAttributes, PayloadBuilderError> {
@@ -470,7 +569,8 @@ where
 
     fn resolve(&mut self) -> (Self::ResolvePayloadFuture, KeepPayloadJobAlive) {
         let best_payload = self.best_payload.take();
-        let maybe_better = self.pending_block.take();
+        let best_strategy = self.best_strategy.take();
+        let maybe_better = std::mem::take(&mut self.pending_blocks);
         let mut empty_payload = None;
 
         if best_payload.is_none() {
@@ -483,6 +583,7 @@ where
                 config: self.config.clone(),
                 cancel: Cancelled::default(),
                 best_payload: None,
+                strategy: PayloadStrategy::DEFAULT,
             };
 
             // TODO: create optimism payload job, that wraps this type, that implements PayloadJob
@@ -491,7 +592,12 @@ where
             if let Some(payload) = self.builder.on_missing_payload(args) {
                 debug!(target: "payload_builder", id=%self.config.payload_id(), "resolving fallback payload as best payload");
                 return (
-                    ResolveBestPayload { best_payload: Some(payload), maybe_better, empty_payload },
+                    ResolveBestPayload {
+                        best_payload: Some(payload),
+                        best_strategy: None,
+                        maybe_better,
+                        empty_payload,
+                    },
                     KeepPayloadJobAlive::Yes,
                 )
             }
@@ -500,17 +606,18 @@ where
             self.metrics.inc_requested_empty_payload();
             // no payload built yet, so we need to return an empty payload
             let (tx, rx) = oneshot::channel();
+            let builder = self.builder.clone();
             let client = self.client.clone();
             let config = self.config.clone();
             self.executor.spawn_blocking(Box::pin(async move {
-                let res = Builder::build_empty_payload(&client, config);
+                let res = builder.build_empty_payload(&client, config);
                 let _ = tx.send(res);
             }));
 
             empty_payload = Some(rx);
         }
 
-        let fut = ResolveBestPayload { best_payload, maybe_better, empty_payload };
+        let fut = ResolveBestPayload { best_payload, best_strategy, maybe_better, empty_payload };
 
         (fut, KeepPayloadJobAlive::No)
     }
@@ -521,23 +628,26 @@ where
 /// This returns the payload that's supposed to be sent to the CL.
 ///
 /// If payload has been built so far, it will return that, but it will check if there's a better
-/// payload available from an in progress build job. If so it will return that.
+/// payload available from the in progress build jobs of the strategies. If so it will return that.
+/// Build jobs that haven't finished yet are cancelled.
 ///
 /// If no payload has been built so far, it will either return an empty payload or the result of the
-/// in progress build job, whatever finishes first.
+/// in progress build jobs, whatever finishes first.
 #[derive(Debug)]
 pub struct ResolveBestPayload<Payload> {
     /// Best payload so far.
     best_payload: Option<Payload>,
-    /// Regular payload job that's currently running that might produce a better payload.
-    maybe_better: Option<PendingPayload<Payload>>,
+    /// The strategy the best payload was built with.
+    best_strategy: Option<PayloadStrategy>,
+    /// Regular payload jobs that are currently running that might produce a better payload.
+    maybe_better: Vec<PendingPayload<Payload>>,
     /// The empty payload building job in progress.
     empty_payload: Option<oneshot::Receiver<Result<Payload, PayloadBuilderError>>>,
 }
 
 impl<Payload> Future for ResolveBestPayload<Payload>
 where
-    Payload: Unpin,
+    Payload: BuiltPayload + Unpin,
 {
     type Output = Result<Payload, PayloadBuilderError>;
 
@@ -545,18 +655,33 @@ where
         let this = self.get_mut();
 
         // check if there is a better payload before returning the best payload
-        if let Some(fut) = Pin::new(&mut this.maybe_better).as_pin_mut() {
-            if let Poll::Ready(res) = fut.poll(cx) {
-                this.maybe_better = None;
-                if let Ok(BuildOutcome::Better { payload, .. }) = res {
-                    debug!(target: "payload_builder", "resolving better payload");
-                    return Poll::Ready(Ok(payload))
+        let mut idx = 0;
+        while idx < this.maybe_better.len() {
+            let Poll::Ready(res) = this.maybe_better[idx].poll_unpin(cx) else {
+                idx += 1;
+                continue
+            };
+            let strategy = this.maybe_better.swap_remove(idx).strategy;
+            if let Ok(BuildOutcome::Better { payload, .. }) = res {
+                if is_better_payload(this.best_payload.as_ref(), payload.fees()) {
+                    debug!(target: "payload_builder", %strategy, "found better payload");
+                    this.best_payload = Some(payload);
+                    this.best_strategy = Some(strategy);
                 }
             }
         }
 
         if let Some(best) = this.best_payload.take() {
-            debug!(target: "payload_builder", "resolving best payload");
+            if let Some(strategy) = this.best_strategy.take() {
+                debug!(target: "payload_builder", %strategy, "resolving best payload");
+                PayloadStrategyMetrics::new_with_labels(&[("strategy", strategy.name())])
+                    .resolved_payloads
+                    .increment(1);
+            } else {
+                debug!(target: "payload_builder", "resolving best payload");
+            }
+            // dropping the remaining build jobs cancels them
+            this.maybe_better.clear();
             return Poll::Ready(Ok(best))
         }
 
@@ -584,6 +709,8 @@ where
 struct PendingPayload<P> {
     /// The marker to cancel the job on drop
     _cancel: Cancelled,
+    /// The strategy the payload is built with.
+    strategy: PayloadStrategy,
     /// The channel to send the result to.
     payload: oneshot::Receiver<Result<BuildOutcome<P>, PayloadBuilderError>>,
 }
@@ -618,6 +745,70 @@ impl Drop for Cancelled {
     }
 }
 
+/// Triggers a re-build of the payload when a new pending transaction pays a higher tip than the
+/// lowest tip included in the best payload.
+#[derive(Debug)]
+struct TransactionRebuild<T: PoolTransaction> {
+    /// The new transactions of the pending subpool.
+    transactions: NewSubpoolTransactionStream<T>,
+    /// Whether a transaction that warrants a re-build was received since the last build.
+    pending: bool,
+    /// The minimum time between two re-builds.
+    debounce: Duration,
+    /// Fires when the next re-build is allowed.
+    delay: Pin<Box<Sleep>>,
+}
+
+impl<T: PoolTransaction> TransactionRebuild<T> {
+    fn new(transactions: NewSubpoolTransactionStream<T>, debounce: Duration) -> Self {
+        Self {
+            transactions,
+            pending: false,
+            debounce,
+            delay: Box::pin(tokio::time::sleep(Duration::ZERO)),
+        }
+    }
+
+    /// Drains the new transactions and returns true if the payload should be re-built now.
+    ///
+    /// Transactions are only compared against the `lowest_tip` of the best payload, so no
+    /// re-builds are triggered before the first payload was built. A re-build is only started if
+    /// the job `is_idle`, i.e. has no pending builds.
+    fn poll_rebuild(
+        &mut self,
+        cx: &mut Context<'_>,
+        lowest_tip: Option<u128>,
+        base_fee: u64,
+        is_idle: bool,
+    ) -> bool {
+        while let Poll::Ready(Some(event)) = self.transactions.poll_next_unpin(cx) {
+            let Some(lowest_tip) = lowest_tip else { continue };
+            if event.transaction.effective_tip_per_gas(base_fee) > Some(lowest_tip) {
+                self.pending = true;
+            }
+        }
+
+        if !self.pending || !is_idle || self.delay.as_mut().poll(cx).is_pending() {
+            return false
+        }
+
+        let next = tokio::time::Instant::now() + self.debounce;
+        self.delay.as_mut().reset(next);
+        true
+    }
+}
+
+/// Returns the lowest effective tip of the transactions in the given block, `0` if the block has no
+/// transactions that pay a tip.
+fn lowest_tip(block: &SealedBlock, base_fee: u64) -> u128 {
+    block
+        .body
+        .iter()
+        .filter_map(|tx| tx.effective_tip_per_gas(Some(base_fee)))
+        .min()
+        .unwrap_or_default()
+}
+
 /// Static config for how to build a payload.
 #[derive(Clone, Debug)]
 pub struct PayloadConfig<Attributes> {
@@ -673,6 +864,41 @@ where
     }
 }
 
+/// A strategy to build payloads with, identified by its name.
+///
+/// On every attempt, a payload job builds one payload per strategy returned by
+/// [PayloadBuilder::strategies] and keeps the one with the most fees. The strategy of an attempt
+/// is passed to the builder in [BuildArguments::strategy].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PayloadStrategy(&'static str);
+
+impl PayloadStrategy {
+    /// The default strategy, that includes the best transactions of the pool by their priority.
+    pub const DEFAULT: Self = Self::new("default");
+
+    /// Creates a new strategy with the given name.
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    /// Returns the name of the strategy.
+    pub const fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Default for PayloadStrategy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl fmt::Display for PayloadStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
 /// The possible outcomes of a payload building attempt.
 #[derive(Debug)]
 pub enum BuildOutcome<Payload> {
@@ -713,6 +939,8 @@ pub struct BuildArguments<Pool, Client, Attributes, Payload> {
     pub cancel: Cancelled,
     /// The best payload achieved so far.
     pub best_payload: Option<Payload>,
+    /// The strategy to build the payload with.
+    pub strategy: PayloadStrategy,
 }
 
 impl<Pool, Client, Attributes, Payload> BuildArguments<Pool, Client, Attributes, Payload> {
@@ -725,7 +953,21 @@ impl<Pool, Client, Attributes, Payload> BuildArguments<Pool, Client, Attributes,
         cancel: Cancelled,
         best_payload: Option<Payload>,
     ) -> Self {
-        Self { client, pool, cached_reads, config, cancel, best_payload }
+        Self {
+            client,
+            pool,
+            cached_reads,
+            config,
+            cancel,
+            best_payload,
+            strategy: PayloadStrategy::DEFAULT,
+        }
+    }
+
+    /// Sets the strategy to build the payload with.
+    pub fn with_strategy(mut self, strategy: PayloadStrategy) -> Self {
+        self.strategy = strategy;
+        self
     }
 }
 
@@ -760,6 +1002,14 @@ pub trait PayloadBuilder<Pool, Client>: Send + Sync + Clone {
         args: BuildArguments<Pool, Client, Self::Attributes, Self::BuiltPayload>,
     ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError>;
 
+    /// Returns the strategies to build competing payloads with for the given config.
+    ///
+    /// On every attempt, the payload job spawns one build per strategy and resolves the payload
+    /// with the most fees, see [PayloadStrategy]. Defaults to [PayloadStrategy::DEFAULT] only.
+    fn strategies(&self, _config: &PayloadConfig<Self::Attributes>) -> Vec<PayloadStrategy> {
+        vec![PayloadStrategy::DEFAULT]
+    }
+
     /// Invoked when the payload job is being resolved and there is no payload yet.
     ///
     /// If this returns a payload, it will be used as the final payload for the job.
@@ -775,6 +1025,7 @@ pub trait PayloadBuilder<Pool, Client>: Send + Sync + Clone {
 
     /// Builds an empty payload without any transaction.
     fn build_empty_payload(
+        &self,
         client: &Client,
         config: PayloadConfig<Self::Attributes>,
     ) -> Result<Self::BuiltPayload, PayloadBuilderError>;
@@ -840,16 +1091,17 @@ pub fn commit_withdrawals<DB: Database<Error = ProviderError>>(
 
 /// Apply the [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) pre block contract call.
 ///
-/// This constructs a new [Evm] with the given DB, and environment
-/// ([CfgEnvWithHandlerCfg] and [BlockEnv]) to execute the pre block contract call.
+/// This constructs a new [Evm](revm::Evm) of the given [ConfigureEvm] with the given DB, and
+/// environment ([CfgEnvWithHandlerCfg] and [BlockEnv]) to execute the pre block contract call.
 ///
 /// The parent beacon block root used for the call is gathered from the given
 /// [PayloadBuilderAttributes].
 ///
 /// This uses [apply_beacon_root_contract_call] to ultimately apply the beacon root contract state
 /// change.
-pub fn pre_block_beacon_root_contract_call<DB: Database + DatabaseCommit, Attributes>(
+pub fn pre_block_beacon_root_contract_call<DB: Database + DatabaseCommit, EvmConfig, Attributes>(
     db: &mut DB,
+    evm_config: &EvmConfig,
     chain_spec: &ChainSpec,
     block_number: u64,
     initialized_cfg: &CfgEnvWithHandlerCfg,
@@ -858,17 +1110,18 @@ pub fn pre_block_beacon_root_contract_call<DB: Database + DatabaseCommit, Attrib
 ) -> Result<(), PayloadBuilderError>
 where
     DB::Error: std::fmt::Display,
+    EvmConfig: ConfigureEvm,
     Attributes: PayloadBuilderAttributes,
 {
     // apply pre-block EIP-4788 contract call
-    let mut evm_pre_block = Evm::builder()
-        .with_db(db)
-        .with_env_with_handler_cfg(EnvWithHandlerCfg::new_with_cfg_env(
+    let mut evm_pre_block = evm_config.evm_with_env(
+        db,
+        EnvWithHandlerCfg::new_with_cfg_env(
             initialized_cfg.clone(),
             initialized_block_env.clone(),
             Default::default(),
-        ))
-        .build();
+        ),
+    );
 
     // initialize a block from the env, because the pre block call needs the block itself
     apply_beacon_root_contract_call(
@@ -901,3 +1154,200 @@ fn duration_until(unix_timestamp_secs: u64) -> Duration {
     let timestamp = Duration::from_secs(unix_timestamp_secs);
     timestamp.saturating_sub(unix_now)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_payload_builder::{EthBuiltPayload, EthPayloadBuilderAttributes};
+    use reth_primitives::{Address, MAINNET};
+    use reth_provider::test_utils::NoopProvider;
+    use reth_tasks::TokioTaskExecutor;
+    use reth_transaction_pool::{noop::NoopTransactionPool, SubPool};
+
+    const BLOBS: PayloadStrategy = PayloadStrategy::new("blobs");
+    const EXTRA: PayloadStrategy = PayloadStrategy::new("extra");
+
+    /// A builder that never builds anything, the builds of the tests are injected and the builds
+    /// spawned by the job are aborted.
+    #[derive(Debug, Clone)]
+    struct TestPayloadBuilder;
+
+    impl<Pool, Client> PayloadBuilder<Pool, Client> for TestPayloadBuilder {
+        type Attributes = EthPayloadBuilderAttributes;
+        type BuiltPayload = EthBuiltPayload;
+
+        fn try_build(
+            &self,
+            args: BuildArguments<Pool, Client, Self::Attributes, Self::BuiltPayload>,
+        ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
+            Ok(BuildOutcome::Aborted { fees: U256::ZERO, cached_reads: args.cached_reads })
+        }
+
+        fn build_empty_payload(
+            &self,
+            _client: &Client,
+            _config: PayloadConfig<Self::Attributes>,
+        ) -> Result<Self::BuiltPayload, PayloadBuilderError> {
+            unreachable!("the tests always have a best payload")
+        }
+    }
+
+    type TestJob =
+        BasicPayloadJob<NoopProvider, NoopTransactionPool, TokioTaskExecutor, TestPayloadBuilder>;
+
+    fn payload(fees: u64) -> EthBuiltPayload {
+        EthBuiltPayload::new(PayloadId::new([0; 8]), SealedBlock::default(), U256::from(fees))
+    }
+
+    /// Returns a build of the strategy that already finished with a payload of the given fees.
+    fn finished(strategy: PayloadStrategy, fees: u64) -> PendingPayload<EthBuiltPayload> {
+        let (tx, rx) = oneshot::channel();
+        let outcome =
+            BuildOutcome::Better { payload: payload(fees), cached_reads: Default::default() };
+        tx.send(Ok(outcome)).unwrap();
+        PendingPayload { _cancel: Cancelled::default(), strategy, payload: rx }
+    }
+
+    /// Returns a build of the strategy that is still in progress, together with its cancel marker
+    /// and the sender of its result.
+    fn in_progress(
+        strategy: PayloadStrategy,
+    ) -> (
+        PendingPayload<EthBuiltPayload>,
+        Cancelled,
+        oneshot::Sender<Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>>,
+    ) {
+        let (tx, rx) = oneshot::channel();
+        let cancel = Cancelled::default();
+        (PendingPayload { _cancel: cancel.clone(), strategy, payload: rx }, cancel, tx)
+    }
+
+    fn job(pending_blocks: Vec<PendingPayload<EthBuiltPayload>>, deadline: Duration) -> TestJob {
+        let attributes = EthPayloadBuilderAttributes {
+            id: PayloadId::new([0; 8]),
+            parent: B256::ZERO,
+            timestamp: 0,
+            suggested_fee_recipient: Address::ZERO,
+            prev_randao: B256::ZERO,
+            withdrawals: Withdrawals::default(),
+            parent_beacon_block_root: None,
+        };
+        BasicPayloadJob {
+            config: PayloadConfig::new(
+                Arc::new(SealedBlock::default()),
+                Bytes::default(),
+                attributes,
+                MAINNET.clone(),
+            ),
+            client: NoopProvider::default(),
+            pool: NoopTransactionPool::default(),
+            executor: TokioTaskExecutor::default(),
+            deadline: Box::pin(tokio::time::sleep(deadline)),
+            interval: tokio::time::interval(Duration::from_secs(1)),
+            strategies: vec![PayloadStrategy::DEFAULT, BLOBS, EXTRA],
+            best_payload: None,
+            best_strategy: None,
+            best_lowest_tip: None,
+            rebuild: None,
+            pending_blocks,
+            payload_task_guard: PayloadTaskGuard::new(1),
+            cached_reads: None,
+            metrics: Default::default(),
+            builder: TestPayloadBuilder,
+        }
+    }
+
+    #[tokio::test]
+    async fn job_keeps_best_payload_of_strategies() {
+        let pending =
+            vec![finished(PayloadStrategy::DEFAULT, 1), finished(BLOBS, 3), finished(EXTRA, 2)];
+        let mut job = job(pending, Duration::from_secs(60));
+
+        let poll = std::future::poll_fn(|cx| Poll::Ready(job.poll_unpin(cx))).await;
+        assert!(poll.is_pending());
+        assert!(job.pending_blocks.is_empty());
+        assert_eq!(job.best_payload.as_ref().map(|payload| payload.fees()), Some(U256::from(3)));
+        assert_eq!(job.best_strategy, Some(BLOBS));
+    }
+
+    #[tokio::test]
+    async fn job_cancels_pending_builds_at_deadline() {
+        let (pending, cancel, tx) = in_progress(PayloadStrategy::DEFAULT);
+        let mut job = job(vec![pending], Duration::ZERO);
+        assert!(!cancel.is_cancelled());
+
+        (&mut job).await.unwrap();
+        assert!(job.pending_blocks.is_empty());
+        assert!(cancel.is_cancelled());
+        assert!(tx.is_closed());
+    }
+
+    #[tokio::test]
+    async fn job_rebuilds_after_pending_builds_finished() {
+        let (pending, cancel, tx) = in_progress(PayloadStrategy::DEFAULT);
+        let mut job = job(vec![pending], Duration::from_secs(60));
+        let (_transactions_tx, transactions_rx) = tokio::sync::mpsc::channel(1);
+        let transactions = NewSubpoolTransactionStream::new(transactions_rx, SubPool::Pending);
+        job.rebuild = Some(TransactionRebuild::new(transactions, Duration::ZERO));
+        job.best_lowest_tip = Some(0);
+
+        // a new transaction warrants a re-build, but the build in progress is not cancelled
+        job.rebuild.as_mut().unwrap().pending = true;
+        let poll = std::future::poll_fn(|cx| Poll::Ready(job.poll_unpin(cx))).await;
+        assert!(poll.is_pending());
+        assert_eq!(job.pending_blocks.len(), 1);
+        assert!(!cancel.is_cancelled());
+
+        // the build finishes while transactions keep arriving
+        let outcome =
+            BuildOutcome::Better { payload: payload(5), cached_reads: Default::default() };
+        tx.send(Ok(outcome)).unwrap();
+        job.rebuild.as_mut().unwrap().pending = true;
+        let poll = std::future::poll_fn(|cx| Poll::Ready(job.poll_unpin(cx))).await;
+        assert!(poll.is_pending());
+        assert_eq!(job.best_payload.as_ref().map(|payload| payload.fees()), Some(U256::from(5)));
+
+        // the re-build starts on the next poll, holding the permit keeps its builds pending
+        let _permit = job.payload_task_guard.0.clone().try_acquire_owned().unwrap();
+        let poll = std::future::poll_fn(|cx| Poll::Ready(job.poll_unpin(cx))).await;
+        assert!(poll.is_pending());
+        assert_eq!(job.pending_blocks.len(), job.strategies.len());
+        assert!(!job.rebuild.as_ref().unwrap().pending);
+    }
+
+    #[tokio::test]
+    async fn resolve_best_payload_of_strategies() {
+        let resolve = ResolveBestPayload {
+            best_payload: Some(payload(2)),
+            best_strategy: Some(PayloadStrategy::DEFAULT),
+            maybe_better: vec![finished(BLOBS, 5), finished(EXTRA, 1)],
+            empty_payload: None,
+        };
+        assert_eq!(resolve.await.unwrap().fees(), U256::from(5));
+
+        // a worse payload of another strategy does not replace the best payload
+        let resolve = ResolveBestPayload {
+            best_payload: Some(payload(2)),
+            best_strategy: Some(PayloadStrategy::DEFAULT),
+            maybe_better: vec![finished(BLOBS, 1)],
+            empty_payload: None,
+        };
+        assert_eq!(resolve.await.unwrap().fees(), U256::from(2));
+    }
+
+    #[tokio::test]
+    async fn resolve_cancels_unfinished_builds() {
+        let (pending, cancel, tx) = in_progress(BLOBS);
+        let resolve = ResolveBestPayload {
+            best_payload: Some(payload(2)),
+            best_strategy: Some(PayloadStrategy::DEFAULT),
+            maybe_better: vec![finished(EXTRA, 3), pending],
+            empty_payload: None,
+        };
+        assert!(!cancel.is_cancelled());
+
+        assert_eq!(resolve.await.unwrap().fees(), U256::from(3));
+        assert!(cancel.is_cancelled());
+        assert!(tx.is_closed());
+    }
+}
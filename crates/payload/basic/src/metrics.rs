@@ -14,6 +14,14 @@ pub(crate) struct PayloadBuilderMetrics {
     pub(crate) failed_payload_builds: Counter,
 }
 
+/// Metrics of the payloads built with a [PayloadStrategy](crate::PayloadStrategy)
+#[derive(Metrics)]
+#[metrics(scope = "payloads.strategy")]
+pub(crate) struct PayloadStrategyMetrics {
+    /// Number of resolved payloads that were built with the strategy
+    pub(crate) resolved_payloads: Counter,
+}
+
 impl PayloadBuilderMetrics {
     pub(crate) fn inc_requested_empty_payload(&self) {
         self.requested_empty_payload.increment(1);
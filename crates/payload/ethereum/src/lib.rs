@@ -11,8 +11,10 @@
 
 use reth_basic_payload_builder::{
     commit_withdrawals, is_better_payload, pre_block_beacon_root_contract_call, BuildArguments,
-    BuildOutcome, PayloadBuilder, PayloadConfig, WithdrawalsOutcome,
+    BuildOutcome, PayloadBuilder, PayloadConfig, PayloadStrategy, WithdrawalsOutcome,
 };
+use reth_evm::ConfigureEvm;
+use reth_evm_ethereum::EthEvmConfig;
 use reth_payload_builder::{
     error::PayloadBuilderError, EthBuiltPayload, EthPayloadBuilderAttributes,
 };
@@ -23,28 +25,59 @@ use reth_primitives::{
     eip4844::calculate_excess_blob_gas,
     proofs,
     revm::env::tx_env_with_recovered,
-    Block, Header, IntoRecoveredTransaction, Receipt, Receipts, EMPTY_OMMER_ROOT_HASH, U256,
+    Block, Header, IntoRecoveredTransaction, Receipt, Receipts, TxHash, EMPTY_OMMER_ROOT_HASH,
+    U256,
 };
 use reth_provider::{BundleStateWithReceipts, StateProviderFactory};
 use reth_revm::database::StateProviderDatabase;
-use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
+use reth_transaction_pool::{
+    BestTransactions, BestTransactionsAttributes, PoolTransaction, TransactionPool,
+    ValidPoolTransaction,
+};
 use revm::{
     db::states::bundle_state::BundleRetention,
     primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
     DatabaseCommit, State,
 };
+use std::{collections::HashSet, sync::Arc};
 use tracing::{debug, trace, warn};
 
+/// The [PayloadStrategy] that includes the blob transactions of the pool first, and all other
+/// transactions afterwards.
+///
+/// The ethereum payload builder races this strategy against the [PayloadStrategy::DEFAULT] one
+/// once cancun is active.
+pub const BLOBS_FIRST_STRATEGY: PayloadStrategy = PayloadStrategy::new("blobs-first");
+
 /// Ethereum payload builder
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-#[non_exhaustive]
-pub struct EthereumPayloadBuilder;
+///
+/// The transactions of the payloads are executed with the EVM of the given [ConfigureEvm], which
+/// should be the same as the one that executes the blocks of the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig> {
+    /// The type responsible for creating the evm.
+    evm_config: EvmConfig,
+}
 
-// Default implementation of [PayloadBuilder] for unit type
-impl<Pool, Client> PayloadBuilder<Pool, Client> for EthereumPayloadBuilder
+impl<EvmConfig> EthereumPayloadBuilder<EvmConfig> {
+    /// Creates a new payload builder that executes transactions with the given EVM config.
+    pub const fn new(evm_config: EvmConfig) -> Self {
+        Self { evm_config }
+    }
+}
+
+impl Default for EthereumPayloadBuilder {
+    fn default() -> Self {
+        Self::new(EthEvmConfig::default())
+    }
+}
+
+// Default implementation of [PayloadBuilder] for the ethereum payload builder
+impl<Pool, Client, EvmConfig> PayloadBuilder<Pool, Client> for EthereumPayloadBuilder<EvmConfig>
 where
     Client: StateProviderFactory,
     Pool: TransactionPool,
+    EvmConfig: ConfigureEvm,
 {
     type Attributes = EthPayloadBuilderAttributes;
     type BuiltPayload = EthBuiltPayload;
@@ -53,10 +86,19 @@ where
         &self,
         args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
     ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
-        default_ethereum_payload_builder(args)
+        default_ethereum_payload_builder(self.evm_config.clone(), args)
+    }
+
+    fn strategies(&self, config: &PayloadConfig<Self::Attributes>) -> Vec<PayloadStrategy> {
+        if config.chain_spec.is_cancun_active_at_timestamp(config.attributes.timestamp) {
+            vec![PayloadStrategy::DEFAULT, BLOBS_FIRST_STRATEGY]
+        } else {
+            vec![PayloadStrategy::DEFAULT]
+        }
     }
 
     fn build_empty_payload(
+        &self,
         client: &Client,
         config: PayloadConfig<Self::Attributes>,
     ) -> Result<EthBuiltPayload, PayloadBuilderError> {
@@ -88,6 +130,7 @@ where
         // apply eip-4788 pre block contract call
         pre_block_beacon_root_contract_call(
                 &mut db,
+                &self.evm_config,
                 &chain_spec,
                 block_number,
                 &initialized_cfg,
@@ -168,14 +211,17 @@ where
 /// and configuration, this function creates a transaction payload. Returns
 /// a result indicating success with the payload or an error in case of failure.
 #[inline]
-pub fn default_ethereum_payload_builder<Pool, Client>(
+pub fn default_ethereum_payload_builder<EvmConfig, Pool, Client>(
+    evm_config: EvmConfig,
     args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
 ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
 where
+    EvmConfig: ConfigureEvm,
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
-    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload } = args;
+    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload, strategy } =
+        args;
 
     let state_provider = client.state_by_block_hash(config.parent_block.hash())?;
     let state = StateProviderDatabase::new(&state_provider);
@@ -191,7 +237,7 @@ where
         ..
     } = config;
 
-    debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash(), parent_number = parent_block.number, "building new payload");
+    debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash(), parent_number = parent_block.number, %strategy, "building new payload");
     let mut cumulative_gas_used = 0;
     let mut sum_blob_gas_used = 0;
     let block_gas_limit: u64 = initialized_block_env.gas_limit.try_into().unwrap_or(u64::MAX);
@@ -199,10 +245,17 @@ where
 
     let mut executed_txs = Vec::new();
 
-    let mut best_txs = pool.best_transactions_with_attributes(BestTransactionsAttributes::new(
+    let best_txs_attributes = BestTransactionsAttributes::new(
         base_fee,
         initialized_block_env.get_blob_gasprice().map(|gasprice| gasprice as u64),
-    ));
+    );
+    let mut best_txs: Box<
+        dyn BestTransactions<Item = Arc<ValidPoolTransaction<Pool::Transaction>>> + '_,
+    > = if strategy == BLOBS_FIRST_STRATEGY {
+        Box::new(BlobsFirst::new(&pool, best_txs_attributes))
+    } else {
+        pool.best_transactions_with_attributes(best_txs_attributes)
+    };
 
     let mut total_fees = U256::ZERO;
 
@@ -211,6 +264,7 @@ where
     // apply eip-4788 pre block contract call
     pre_block_beacon_root_contract_call(
         &mut db,
+        &evm_config,
         &chain_spec,
         block_number,
         &initialized_cfg,
@@ -229,6 +283,14 @@ where
             continue
         }
 
+        // skip transactions whose conditions aren't satisfied by this block, which also skips
+        // their dependent transactions
+        if !pool_tx.is_conditional_satisfied(block_number, attributes.timestamp, &state_provider)? {
+            trace!(target: "payload_builder", tx=?pool_tx.hash(), "skipping transaction with unsatisfied conditions");
+            best_txs.mark_invalid(&pool_tx);
+            continue
+        }
+
         // check if the job was cancelled, if so we can exit early
         if cancel.is_cancelled() {
             return Ok(BuildOutcome::Cancelled)
@@ -253,14 +315,12 @@ where
         }
 
         // Configure the environment for the block.
-        let mut evm = revm::Evm::builder()
-            .with_db(&mut db)
-            .with_env_with_handler_cfg(EnvWithHandlerCfg::new_with_cfg_env(
-                initialized_cfg.clone(),
-                initialized_block_env.clone(),
-                tx_env_with_recovered(&tx),
-            ))
-            .build();
+        let env = EnvWithHandlerCfg::new_with_cfg_env(
+            initialized_cfg.clone(),
+            initialized_block_env.clone(),
+            tx_env_with_recovered(&tx),
+        );
+        let mut evm = evm_config.evm_with_env(&mut db, env);
 
         let ResultAndState { result, state } = match evm.transact() {
             Ok(res) => res,
@@ -415,3 +475,170 @@ where
 
     Ok(BuildOutcome::Better { payload, cached_reads })
 }
+
+/// The best transactions of a pool.
+type BestPoolTransactions<T> = Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<T>>>>;
+
+/// A [BestTransactions] iterator for the [BLOBS_FIRST_STRATEGY], that yields the best blob
+/// transactions of the pool first, and the best of all remaining transactions afterwards.
+struct BlobsFirst<T: PoolTransaction> {
+    /// The best blob transactions.
+    blobs: BestPoolTransactions<T>,
+    /// The best transactions, yielded once all blob transactions were yielded.
+    rest: BestPoolTransactions<T>,
+    /// Whether all blob transactions were yielded.
+    blobs_done: bool,
+    /// The blob transactions that were yielded and not marked as invalid.
+    yielded_blobs: HashSet<TxHash>,
+    /// The blob transactions that were marked as invalid, these and their descendants are skipped
+    /// by the remaining transactions.
+    invalid_blobs: HashSet<TxHash>,
+}
+
+impl<T: PoolTransaction> BlobsFirst<T> {
+    fn new<Pool>(pool: &Pool, attributes: BestTransactionsAttributes) -> Self
+    where
+        Pool: TransactionPool<Transaction = T>,
+    {
+        Self {
+            blobs: pool.best_transactions_with_attributes(attributes),
+            rest: pool.best_transactions_with_attributes(attributes),
+            blobs_done: false,
+            yielded_blobs: HashSet::new(),
+            invalid_blobs: HashSet::new(),
+        }
+    }
+}
+
+impl<T: PoolTransaction> Iterator for BlobsFirst<T> {
+    type Item = Arc<ValidPoolTransaction<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.blobs_done {
+            let Some(tx) = self.blobs.next() else {
+                self.blobs_done = true;
+                break
+            };
+            if tx.is_eip4844() {
+                self.yielded_blobs.insert(*tx.hash());
+                return Some(tx)
+            }
+            // this also skips the descendants of this transaction, which are not blob transactions
+            // either, because the transactions of a sender are either all blob transactions or none
+            self.blobs.mark_invalid(&tx);
+        }
+        loop {
+            let tx = self.rest.next()?;
+            if self.invalid_blobs.contains(tx.hash()) {
+                // also skips the descendants of the invalid blob transaction
+                self.rest.mark_invalid(&tx);
+                continue
+            }
+            if !self.yielded_blobs.contains(tx.hash()) {
+                return Some(tx)
+            }
+        }
+    }
+}
+
+impl<T: PoolTransaction> BestTransactions for BlobsFirst<T> {
+    fn mark_invalid(&mut self, tx: &Self::Item) {
+        if self.blobs_done {
+            self.rest.mark_invalid(tx)
+        } else {
+            self.yielded_blobs.remove(tx.hash());
+            self.invalid_blobs.insert(*tx.hash());
+            self.blobs.mark_invalid(tx)
+        }
+    }
+
+    fn no_updates(&mut self) {
+        self.blobs.no_updates();
+        self.rest.no_updates();
+    }
+
+    fn skip_blobs(&mut self) {
+        self.set_skip_blobs(true)
+    }
+
+    fn set_skip_blobs(&mut self, skip_blobs: bool) {
+        self.blobs.set_skip_blobs(skip_blobs);
+        self.rest.set_skip_blobs(skip_blobs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction, TestPool},
+        TransactionOrigin,
+    };
+
+    async fn add(pool: &TestPool, tx: &MockTransaction) {
+        pool.add_transaction(TransactionOrigin::External, tx.clone()).await.unwrap();
+    }
+
+    fn blobs_first(pool: &TestPool) -> BlobsFirst<MockTransaction> {
+        let base_fee = pool.block_info().pending_basefee;
+        BlobsFirst::new(pool, BestTransactionsAttributes::base_fee(base_fee))
+    }
+
+    fn hashes(
+        best: impl Iterator<Item = Arc<ValidPoolTransaction<MockTransaction>>>,
+    ) -> Vec<TxHash> {
+        best.map(|tx| *tx.hash()).collect()
+    }
+
+    #[tokio::test]
+    async fn blobs_first_yields_blob_transactions_first() {
+        let pool = testing_pool();
+        let tx = MockTransaction::eip1559().with_max_fee(1_000).with_priority_fee(100);
+        let blob = MockTransaction::eip4844().with_max_fee(1_000).with_priority_fee(10);
+        let other_blob = MockTransaction::eip4844().with_max_fee(1_000).with_priority_fee(20);
+        for tx in [&tx, &blob, &other_blob] {
+            add(&pool, tx).await;
+        }
+
+        assert_eq!(
+            hashes(blobs_first(&pool)),
+            vec![other_blob.get_hash(), blob.get_hash(), tx.get_hash()]
+        );
+    }
+
+    #[tokio::test]
+    async fn blobs_first_yields_dependent_blob_transactions() {
+        let pool = testing_pool();
+        let tx = MockTransaction::eip1559().with_max_fee(1_000).with_priority_fee(100);
+        let blob = MockTransaction::eip4844().with_max_fee(1_000).with_priority_fee(10);
+        let next_blob = blob.next();
+        for tx in [&tx, &blob, &next_blob] {
+            add(&pool, tx).await;
+        }
+
+        assert_eq!(
+            hashes(blobs_first(&pool)),
+            vec![blob.get_hash(), next_blob.get_hash(), tx.get_hash()]
+        );
+    }
+
+    #[tokio::test]
+    async fn blobs_first_skips_invalid_blob_transactions() {
+        let pool = testing_pool();
+        let tx = MockTransaction::eip1559().with_max_fee(1_000).with_priority_fee(100);
+        let blob = MockTransaction::eip4844().with_max_fee(1_000).with_priority_fee(20);
+        let next_blob = blob.next();
+        let other_blob = MockTransaction::eip4844().with_max_fee(1_000).with_priority_fee(10);
+        for tx in [&tx, &blob, &next_blob, &other_blob] {
+            add(&pool, tx).await;
+        }
+
+        let mut best = blobs_first(&pool);
+        let invalid = best.next().unwrap();
+        assert_eq!(*invalid.hash(), blob.get_hash());
+        best.mark_invalid(&invalid);
+
+        // neither the invalid blob transaction nor its descendant are yielded again
+        assert_eq!(hashes(best), vec![other_blob.get_hash(), tx.get_hash()]);
+    }
+}
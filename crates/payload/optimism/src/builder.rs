@@ -103,6 +103,7 @@ where
     }
 
     fn build_empty_payload(
+        &self,
         client: &Client,
         config: PayloadConfig<Self::Attributes>,
     ) -> Result<OptimismBuiltPayload, PayloadBuilderError> {
@@ -134,6 +135,7 @@ where
         // apply eip-4788 pre block contract call
         pre_block_beacon_root_contract_call(
                 &mut db,
+                &self.evm_config,
                 &chain_spec,
                 block_number,
                 &initialized_cfg,
@@ -233,7 +235,7 @@ where
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
-    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload } = args;
+    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload, .. } = args;
 
     let state_provider = client.state_by_block_hash(config.parent_block.hash())?;
     let state = StateProviderDatabase::new(&state_provider);
@@ -272,6 +274,7 @@ where
     // apply eip-4788 pre block contract call
     pre_block_beacon_root_contract_call(
         &mut db,
+        &evm_config,
         &chain_spec,
         block_number,
         &initialized_cfg,
@@ -405,6 +408,18 @@ where
                 ))
             }
 
+            // skip transactions whose conditions aren't satisfied by this block, which also skips
+            // their dependent transactions
+            if !pool_tx.is_conditional_satisfied(
+                block_number,
+                attributes.payload_attributes.timestamp,
+                &state_provider,
+            )? {
+                trace!(target: "payload_builder", tx=?pool_tx.hash(), "skipping transaction with unsatisfied conditions");
+                best_txs.mark_invalid(&pool_tx);
+                continue
+            }
+
             // check if the job was cancelled, if so we can exit early
             if cancel.is_cancelled() {
                 return Ok(BuildOutcome::Cancelled)
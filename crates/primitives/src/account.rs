@@ -65,6 +65,14 @@ impl Bytecode {
     pub fn new_raw(bytes: Bytes) -> Self {
         Self(RevmBytecode::new_raw(bytes))
     }
+
+    /// Analyses the valid jump destinations of the bytecode, unless it's analysed already.
+    ///
+    /// The bytecodes are stored analysed, so they're not analysed again when they're loaded for
+    /// execution.
+    pub fn into_analysed(self) -> Self {
+        Self(revm::interpreter::analysis::to_analysed(self.0))
+    }
 }
 
 impl Deref for Bytecode {
@@ -192,4 +200,20 @@ mod tests {
         assert_eq!(decoded, bytecode);
         assert!(remainder.is_empty());
     }
+
+    #[test]
+    fn analyse_bytecode() {
+        // PUSH1 0x5b JUMPDEST
+        let code = Bytes::from(hex!("605b5b").as_ref());
+        let analysed = Bytecode::new_raw(code.clone()).into_analysed();
+        assert_eq!(analysed.original_bytes(), code);
+        assert_eq!(analysed.hash_slow(), keccak256(&code));
+        let BytecodeState::Analysed { jump_map, .. } = analysed.state() else {
+            panic!("the bytecode is not analysed")
+        };
+        assert!(!jump_map.is_valid(1) && jump_map.is_valid(2));
+
+        // the analysed bytecode is kept as is
+        assert_eq!(analysed.clone().into_analysed(), analysed);
+    }
 }
@@ -143,6 +143,23 @@ impl<'a> Arbitrary<'a> for IntegerList {
     }
 }
 
+#[cfg(any(test, feature = "arbitrary"))]
+impl proptest::arbitrary::Arbitrary for IntegerList {
+    type Parameters = ();
+    type Strategy = proptest::strategy::Map<
+        proptest::collection::BTreeSetStrategy<<u64 as proptest::arbitrary::Arbitrary>::Strategy>,
+        fn(std::collections::BTreeSet<u64>) -> Self,
+    >;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+
+        // the set is sorted, so the list can be created without sorting it again
+        proptest::collection::btree_set(proptest::arbitrary::any::<u64>(), 1..100)
+            .prop_map(|nums| Self::new_pre_sorted(nums.into_iter().collect::<Vec<_>>()))
+    }
+}
+
 /// Primitives error type.
 #[derive(Debug, thiserror::Error)]
 pub enum RoaringBitmapError {
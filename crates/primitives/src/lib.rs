@@ -32,6 +32,7 @@ mod header;
 mod integer_list;
 mod log;
 mod net;
+mod node_status;
 mod peer;
 pub mod proofs;
 mod prune;
@@ -75,10 +76,14 @@ pub use net::{
     NodeRecordParseError, GOERLI_BOOTNODES, HOLESKY_BOOTNODES, MAINNET_BOOTNODES,
     SEPOLIA_BOOTNODES,
 };
+pub use node_status::{
+    ChainStatus, DatabaseStatus, NodeStatus, PeerCountStatus, StageCheckpointStatus,
+    StaticFileSegmentStatus,
+};
 pub use peer::{id2pk, pk2id, AnyNode, PeerId, WithPeerId};
 pub use prune::{
     PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneModes, PruneProgress,
-    PrunePurpose, PruneSegment, PruneSegmentError, ReceiptsLogPruneConfig,
+    PrunePurpose, PruneSegment, PruneSegmentError, PruneSegmentStatus, ReceiptsLogPruneConfig,
     MINIMUM_PRUNING_DISTANCE,
 };
 pub use receipt::{Receipt, ReceiptWithBloom, ReceiptWithBloomRef, Receipts};
@@ -0,0 +1,116 @@
+use crate::{BlockNumber, ForkId, Hardfork, PruneSegmentStatus, StaticFileSegment, B256};
+use serde::{Deserialize, Serialize};
+
+/// The state of a node: the progress of its sync, the data it has and the chain it follows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStatus {
+    /// The checkpoints of the stages of the sync, as committed to the database.
+    pub stages: Vec<StageCheckpointStatus>,
+    /// The highest block of every static file segment.
+    pub static_files: Vec<StaticFileSegmentStatus>,
+    /// The pruning status of every segment.
+    pub prune: Vec<PruneSegmentStatus>,
+    /// The size of the database.
+    pub database: DatabaseStatus,
+    /// The number of connected peers.
+    pub peers: PeerCountStatus,
+    /// The chain the node follows, at its head.
+    pub chain: ChainStatus,
+}
+
+/// The checkpoint of a stage of the sync.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageCheckpointStatus {
+    /// The name of the stage.
+    pub stage: String,
+    /// The block the stage reached, if it ran.
+    pub block_number: Option<BlockNumber>,
+}
+
+/// The highest block of a static file segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StaticFileSegmentStatus {
+    /// The segment of the data.
+    pub segment: StaticFileSegment,
+    /// The highest block in the static files of the segment, if there is any.
+    pub highest_block: Option<BlockNumber>,
+}
+
+/// The size of the database.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStatus {
+    /// The size of the used pages of the database, in bytes.
+    pub size: Option<u64>,
+    /// The number of pages on the freelist, which are reused before the database grows.
+    pub freelist_pages: Option<u64>,
+}
+
+/// The number of connected peers by the direction of their connection.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerCountStatus {
+    /// The number of peers that connected to the node.
+    pub inbound: usize,
+    /// The number of peers the node connected to.
+    pub outbound: usize,
+}
+
+/// The chain the node follows, at its head.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainStatus {
+    /// The chain ID.
+    pub chain_id: u64,
+    /// The hash of the genesis block.
+    pub genesis_hash: B256,
+    /// The number of the head block.
+    pub head_number: BlockNumber,
+    /// The hash of the head block.
+    pub head_hash: B256,
+    /// The [EIP-2124](https://eips.ethereum.org/EIPS/eip-2124) fork identifier at the head, whose
+    /// hash identifies the genesis and the activated hardforks of the chain specification.
+    pub fork_id: ForkId,
+    /// The hardforks that are activated at the head.
+    pub hardforks: Vec<Hardfork>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_node_status() {
+        let status = NodeStatus {
+            stages: vec![StageCheckpointStatus {
+                stage: "Execution".to_string(),
+                block_number: Some(100),
+            }],
+            static_files: vec![StaticFileSegmentStatus {
+                segment: StaticFileSegment::Headers,
+                highest_block: None,
+            }],
+            prune: vec![],
+            database: DatabaseStatus { size: Some(4096), freelist_pages: Some(1) },
+            peers: PeerCountStatus { inbound: 1, outbound: 2 },
+            chain: ChainStatus {
+                chain_id: 1,
+                genesis_hash: B256::ZERO,
+                head_number: 100,
+                head_hash: B256::ZERO,
+                fork_id: ForkId { hash: crate::ForkHash([0xfc, 0x64, 0xec, 0x04]), next: 0 },
+                hardforks: vec![Hardfork::Frontier],
+            },
+        };
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["stages"][0]["blockNumber"], 100);
+        assert_eq!(json["staticFiles"][0]["highestBlock"], serde_json::Value::Null);
+        assert_eq!(json["database"]["freelistPages"], 1);
+        assert_eq!(json["chain"]["headNumber"], 100);
+        assert_eq!(serde_json::from_value::<NodeStatus>(json).unwrap(), status);
+    }
+}
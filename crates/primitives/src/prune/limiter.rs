@@ -62,6 +62,21 @@ impl PruneLimiter {
         self
     }
 
+    /// Caps the number of entries (rows in the database) left to delete, keeping the number of
+    /// the entries that have already been deleted.
+    ///
+    /// If the limit wasn't set, it's set to the given number of entries.
+    pub fn cap_deleted_entries_left(mut self, left: usize) -> Self {
+        if let Some(deleted_entries_limit) = self.deleted_entries_limit.as_mut() {
+            deleted_entries_limit.limit =
+                deleted_entries_limit.limit.min(deleted_entries_limit.deleted + left);
+        } else {
+            self.deleted_entries_limit = Some(PruneDeletedEntriesLimit::new(left));
+        }
+
+        self
+    }
+
     /// Sets the limit on the number of deleted entries (rows in the database) to a biggest
     /// multiple of the given denominator that is smaller than the existing limit.
     ///
@@ -2,6 +2,7 @@ mod checkpoint;
 mod limiter;
 mod mode;
 mod segment;
+mod status;
 mod target;
 
 use crate::{Address, BlockNumber};
@@ -10,6 +11,7 @@ pub use limiter::PruneLimiter;
 pub use mode::PruneMode;
 pub use segment::{PrunePurpose, PruneSegment, PruneSegmentError};
 use serde::{Deserialize, Serialize};
+pub use status::PruneSegmentStatus;
 use std::collections::BTreeMap;
 pub use target::{PruneModes, MINIMUM_PRUNING_DISTANCE};
 
@@ -66,6 +68,43 @@ impl ReceiptsLogPruneConfig {
         Ok(map)
     }
 
+    /// Returns the lowest block from which the receipts containing logs of any of the addresses
+    /// are retained. All receipts below it are pruned.
+    ///
+    /// Returns `None` if the configuration is empty.
+    pub fn lowest_retained_block(
+        &self,
+        tip: BlockNumber,
+        pruned_block: Option<BlockNumber>,
+    ) -> Result<Option<BlockNumber>, PruneSegmentError> {
+        Ok(self.group_by_block(tip, pruned_block)?.into_keys().next())
+    }
+
+    /// Validates that none of the addresses retains more blocks than the `receipts` prune mode,
+    /// which takes priority over the configuration.
+    ///
+    /// A [`PruneMode::Distance`] and a [`PruneMode::Before`] can't be compared without the tip,
+    /// so they're always considered valid.
+    pub fn validate(&self, receipts: Option<PruneMode>) -> Result<(), PruneSegmentError> {
+        let Some(receipts) = receipts else { return Ok(()) };
+
+        for (address, mode) in self.0.iter() {
+            let exceeds_receipts = match (receipts, mode) {
+                (PruneMode::Full, _) => true,
+                (PruneMode::Distance(receipts), PruneMode::Distance(distance)) => {
+                    *distance > receipts
+                }
+                (PruneMode::Before(receipts), PruneMode::Before(block)) => *block < receipts,
+                _ => false,
+            };
+            if exceeds_receipts {
+                return Err(PruneSegmentError::ReceiptsLogFilterExceedsReceipts(*address))
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns the lowest block where we start filtering logs which use `PruneMode::Distance(_)`.
     pub fn lowest_block_with_distance(
         &self,
@@ -151,3 +190,40 @@ impl PruneProgress {
         matches!(self, Self::Finished)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receipts_log_prune_config() {
+        let (address_a, address_b, address_c) =
+            (Address::random(), Address::random(), Address::random());
+        let config = ReceiptsLogPruneConfig(BTreeMap::from([
+            (address_a, PruneMode::Before(8_000)),
+            (address_b, PruneMode::Before(5_000)),
+            (address_c, PruneMode::Distance(MINIMUM_PRUNING_DISTANCE + 100)),
+        ]));
+
+        let tip = 20_000;
+        assert_eq!(config.lowest_retained_block(tip, None), Ok(Some(5_000)));
+        // Blocks that were already pruned aren't retained
+        assert_eq!(config.lowest_retained_block(tip, Some(6_000)), Ok(Some(6_001)));
+        assert_eq!(ReceiptsLogPruneConfig::default().lowest_retained_block(tip, None), Ok(None));
+
+        assert_eq!(config.validate(None), Ok(()));
+        assert_eq!(
+            config.validate(Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE + 100))),
+            Ok(())
+        );
+        assert_eq!(
+            config.validate(Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE))),
+            Err(PruneSegmentError::ReceiptsLogFilterExceedsReceipts(address_c))
+        );
+        assert_eq!(
+            config.validate(Some(PruneMode::Before(6_000))),
+            Err(PruneSegmentError::ReceiptsLogFilterExceedsReceipts(address_b))
+        );
+        assert!(config.validate(Some(PruneMode::Full)).is_err());
+    }
+}
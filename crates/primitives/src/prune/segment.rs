@@ -1,4 +1,4 @@
-use crate::MINIMUM_PRUNING_DISTANCE;
+use crate::{Address, MINIMUM_PRUNING_DISTANCE};
 use derive_more::Display;
 use reth_codecs::{main_codec, Compact};
 use thiserror::Error;
@@ -6,6 +6,7 @@ use thiserror::Error;
 /// Segment of the data that can be pruned.
 #[main_codec]
 #[derive(Debug, Display, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
 pub enum PruneSegment {
     /// Prune segment responsible for the `TransactionSenders` table.
     SenderRecovery,
@@ -24,6 +25,8 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for the `CallFromIndex` and `CallToIndex` tables.
+    CallTraces,
 }
 
 impl PruneSegment {
@@ -34,7 +37,7 @@ impl PruneSegment {
                 0
             }
             Self::Receipts if purpose.is_static_file() => 0,
-            Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
+            Self::ContractLogs | Self::AccountHistory | Self::StorageHistory | Self::CallTraces => {
                 MINIMUM_PRUNING_DISTANCE
             }
             Self::Receipts => MINIMUM_PRUNING_DISTANCE,
@@ -72,6 +75,10 @@ pub enum PruneSegmentError {
     /// Receipts have been pruned
     #[error("receipts have been pruned")]
     ReceiptsPruned,
+    /// The receipts containing logs of the address are retained for longer than all receipts,
+    /// which take priority.
+    #[error("receipts log filter of {0} retains more blocks than the receipts prune mode")]
+    ReceiptsLogFilterExceedsReceipts(Address),
 }
 
 #[cfg(test)]
@@ -0,0 +1,20 @@
+use crate::{BlockNumber, PruneMode, PruneSegment};
+use serde::{Deserialize, Serialize};
+
+/// The pruning status of a [`PruneSegment`], describing the lowest block its data is available
+/// for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneSegmentStatus {
+    /// The segment of the data.
+    pub segment: PruneSegment,
+    /// The configured prune mode of the segment, if the segment is pruned by the prune
+    /// configuration.
+    pub prune_mode: Option<PruneMode>,
+    /// The highest block pruned from the database, according to the prune checkpoint of the
+    /// segment.
+    pub pruned_block: Option<BlockNumber>,
+    /// The lowest block the data of the segment is available for, either in the database or in
+    /// the static files.
+    pub lowest_available_block: BlockNumber,
+}
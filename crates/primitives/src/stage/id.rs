@@ -30,6 +30,10 @@ pub enum StageId {
     IndexStorageHistory,
     /// Index account history stage in the process.
     IndexAccountHistory,
+    /// Index call traces stage in the process.
+    ///
+    /// The stage is optional, so it's not part of [`StageId::ALL`].
+    IndexCallTraces,
     /// Finish stage in the process.
     Finish,
     /// Other custom stage with a provided string identifier.
@@ -69,6 +73,7 @@ impl StageId {
             StageId::TransactionLookup => "TransactionLookup",
             StageId::IndexAccountHistory => "IndexAccountHistory",
             StageId::IndexStorageHistory => "IndexStorageHistory",
+            StageId::IndexCallTraces => "IndexCallTraces",
             StageId::Finish => "Finish",
             StageId::Other(s) => s,
         }
@@ -107,6 +112,7 @@ mod tests {
         assert_eq!(StageId::MerkleExecute.to_string(), "MerkleExecute");
         assert_eq!(StageId::IndexAccountHistory.to_string(), "IndexAccountHistory");
         assert_eq!(StageId::IndexStorageHistory.to_string(), "IndexStorageHistory");
+        assert_eq!(StageId::IndexCallTraces.to_string(), "IndexCallTraces");
         assert_eq!(StageId::TransactionLookup.to_string(), "TransactionLookup");
         assert_eq!(StageId::Finish.to_string(), "Finish");
 
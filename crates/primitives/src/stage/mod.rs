@@ -9,3 +9,6 @@ pub use checkpoints::{
     HeadersCheckpoint, IndexHistoryCheckpoint, MerkleCheckpoint, StageCheckpoint,
     StageUnitCheckpoint, StorageHashingCheckpoint,
 };
+
+mod progress;
+pub use progress::{StageProgress, SyncStage};
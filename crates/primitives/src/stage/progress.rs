@@ -0,0 +1,102 @@
+use super::{EntitiesCheckpoint, StageId};
+use crate::BlockNumber;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+/// A stage of the pipeline, or a component running alongside the pipeline whose progress is
+/// reported like the progress of a stage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SyncStage {
+    /// A stage of the pipeline.
+    Stage(StageId),
+    /// The pruner, removing data that is no longer needed from the database and static files.
+    Pruner,
+    /// The static file producer, moving data from the database to static files.
+    StaticFileProducer,
+}
+
+impl Display for SyncStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncStage::Stage(stage_id) => Display::fmt(stage_id, f),
+            SyncStage::Pruner => f.write_str("Pruner"),
+            SyncStage::StaticFileProducer => f.write_str("StaticFileProducer"),
+        }
+    }
+}
+
+impl FromStr for SyncStage {
+    type Err = String;
+
+    /// Parses the name of a known stage.
+    ///
+    /// Custom stages can't be parsed, since [`StageId::Other`] requires a static name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Pruner" => Ok(SyncStage::Pruner),
+            "StaticFileProducer" => Ok(SyncStage::StaticFileProducer),
+            _ => StageId::ALL
+                .into_iter()
+                .find(|stage_id| stage_id.as_str() == s)
+                .map(SyncStage::Stage)
+                .ok_or_else(|| format!("unknown stage: {s}")),
+        }
+    }
+}
+
+impl From<StageId> for SyncStage {
+    fn from(stage_id: StageId) -> Self {
+        SyncStage::Stage(stage_id)
+    }
+}
+
+impl Serialize for SyncStage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SyncStage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A snapshot of the progress of a [`SyncStage`], taken every time it commits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StageProgress {
+    /// The stage that made progress.
+    pub stage: SyncStage,
+    /// The block number the stage reached.
+    pub checkpoint: BlockNumber,
+    /// The number of processed and total entities of the stage, if known.
+    pub entities: Option<EntitiesCheckpoint>,
+    /// The time it took to run the batch that was committed.
+    pub elapsed: Duration,
+    /// The estimated time until the stage processed all entities, if known.
+    pub eta: Option<Duration>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_stage_roundtrip() {
+        for stage in StageId::ALL
+            .into_iter()
+            .map(SyncStage::Stage)
+            .chain([SyncStage::Pruner, SyncStage::StaticFileProducer])
+        {
+            assert_eq!(stage.to_string().parse::<SyncStage>(), Ok(stage));
+        }
+
+        assert_eq!(
+            serde_json::to_string(&SyncStage::Stage(StageId::Execution)).unwrap(),
+            "\"Execution\""
+        );
+        assert!("Foo".parse::<SyncStage>().is_err());
+    }
+}
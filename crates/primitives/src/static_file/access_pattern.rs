@@ -0,0 +1,18 @@
+use strum::AsRefStr;
+
+/// The expected access pattern of the memory-mapped static files, which is passed to the OS as a
+/// hint of how much of a file to read ahead.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, AsRefStr)]
+#[cfg_attr(feature = "clap", derive(clap::ValueEnum))]
+pub enum AccessPattern {
+    /// No hint, the OS reads ahead with its defaults.
+    #[strum(serialize = "normal")]
+    #[default]
+    Normal,
+    /// Rows are read at random, e.g. by hash lookups, so nothing is read ahead.
+    #[strum(serialize = "random")]
+    Random,
+    /// Rows are read in order, e.g. by range walks, so the OS reads ahead aggressively.
+    #[strum(serialize = "sequential")]
+    Sequential,
+}
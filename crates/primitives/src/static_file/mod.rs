@@ -1,9 +1,11 @@
 //! StaticFile primitives.
 
+mod access_pattern;
 mod compression;
 mod filters;
 mod segment;
 
+pub use access_pattern::AccessPattern;
 use alloy_primitives::BlockNumber;
 pub use compression::Compression;
 pub use filters::{Filters, InclusionFilter, PerfectHashingFunction};
@@ -24,6 +26,9 @@ pub struct HighestStaticFiles {
     /// Highest static file block of transactions, inclusive.
     /// If [`None`], no static file is available.
     pub transactions: Option<BlockNumber>,
+    /// Highest static file block of transaction senders, inclusive.
+    /// If [`None`], no static file is available.
+    pub transaction_senders: Option<BlockNumber>,
 }
 
 impl HighestStaticFiles {
@@ -33,6 +38,7 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => self.headers,
             StaticFileSegment::Transactions => self.transactions,
             StaticFileSegment::Receipts => self.receipts,
+            StaticFileSegment::TransactionSenders => self.transaction_senders,
         }
     }
 
@@ -42,12 +48,16 @@ impl HighestStaticFiles {
             StaticFileSegment::Headers => &mut self.headers,
             StaticFileSegment::Transactions => &mut self.transactions,
             StaticFileSegment::Receipts => &mut self.receipts,
+            StaticFileSegment::TransactionSenders => &mut self.transaction_senders,
         }
     }
 
     /// Returns the maximum block of all segments.
     pub fn max(&self) -> Option<u64> {
-        [self.headers, self.transactions, self.receipts].iter().filter_map(|&option| option).max()
+        [self.headers, self.transactions, self.receipts, self.transaction_senders]
+            .iter()
+            .filter_map(|&option| option)
+            .max()
     }
 }
 
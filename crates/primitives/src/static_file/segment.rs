@@ -36,6 +36,9 @@ pub enum StaticFileSegment {
     #[strum(serialize = "receipts")]
     /// Static File segment responsible for the `Receipts` table.
     Receipts,
+    #[strum(serialize = "transaction-senders")]
+    /// Static File segment responsible for the `TransactionSenders` table.
+    TransactionSenders,
 }
 
 impl StaticFileSegment {
@@ -45,6 +48,7 @@ impl StaticFileSegment {
             StaticFileSegment::Headers => "headers",
             StaticFileSegment::Transactions => "transactions",
             StaticFileSegment::Receipts => "receipts",
+            StaticFileSegment::TransactionSenders => "transaction-senders",
         }
     }
 
@@ -62,6 +66,7 @@ impl StaticFileSegment {
             StaticFileSegment::Headers => default_config,
             StaticFileSegment::Transactions => default_config,
             StaticFileSegment::Receipts => default_config,
+            StaticFileSegment::TransactionSenders => default_config,
         }
     }
 
@@ -71,6 +76,7 @@ impl StaticFileSegment {
             StaticFileSegment::Headers => 3,
             StaticFileSegment::Transactions => 1,
             StaticFileSegment::Receipts => 1,
+            StaticFileSegment::TransactionSenders => 1,
         }
     }
 
@@ -240,7 +246,9 @@ impl SegmentHeader {
     pub fn increment_tx(&mut self) {
         match self.segment {
             StaticFileSegment::Headers => (),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::TransactionSenders => {
                 if let Some(tx_range) = &mut self.tx_range {
                     tx_range.end += 1;
                 } else {
@@ -262,7 +270,9 @@ impl SegmentHeader {
                     }
                 };
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::TransactionSenders => {
                 if let Some(range) = &mut self.tx_range {
                     if num > range.end {
                         self.tx_range = None;
@@ -298,7 +308,9 @@ impl SegmentHeader {
     pub fn start(&self) -> Option<u64> {
         match self.segment {
             StaticFileSegment::Headers => self.block_start(),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => self.tx_start(),
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::TransactionSenders => self.tx_start(),
         }
     }
 }
@@ -371,6 +383,12 @@ mod tests {
         let test_vectors = [
             (StaticFileSegment::Headers, 2..=30, "static_file_headers_2_30", None),
             (StaticFileSegment::Receipts, 30..=300, "static_file_receipts_30_300", None),
+            (
+                StaticFileSegment::TransactionSenders,
+                30..=300,
+                "static_file_transaction-senders_30_300",
+                None,
+            ),
             (
                 StaticFileSegment::Transactions,
                 1_123_233..=11_223_233,
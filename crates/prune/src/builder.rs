@@ -21,6 +21,8 @@ pub struct PrunerBuilder {
     pub prune_delete_limit: usize,
     /// Time a pruner job can run before timing out.
     pub timeout: Option<Duration>,
+    /// The maximum number of entries every segment deletes per second.
+    pub max_entries_per_second: Option<usize>,
 }
 
 impl PrunerBuilder {
@@ -32,6 +34,7 @@ impl PrunerBuilder {
         PrunerBuilder::default()
             .block_interval(pruner_config.block_interval)
             .segments(pruner_config.segments)
+            .max_entries_per_second(pruner_config.max_entries_per_second)
     }
 
     /// Sets the minimum pruning interval measured in blocks.
@@ -67,6 +70,12 @@ impl PrunerBuilder {
         self
     }
 
+    /// Sets the maximum number of entries every segment deletes per second.
+    pub fn max_entries_per_second(mut self, max_entries_per_second: Option<usize>) -> Self {
+        self.max_entries_per_second = max_entries_per_second;
+        self
+    }
+
     /// Builds a [Pruner] from the current configuration.
     pub fn build<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> Pruner<DB> {
         let segments = SegmentSet::<DB>::from_prune_modes(self.segments);
@@ -79,6 +88,7 @@ impl PrunerBuilder {
             self.max_reorg_depth,
             self.timeout,
         )
+        .with_max_entries_per_second(self.max_entries_per_second)
     }
 }
 
@@ -90,6 +100,7 @@ impl Default for PrunerBuilder {
             max_reorg_depth: 64,
             prune_delete_limit: MAINNET.prune_delete_limit,
             timeout: Some(Self::DEFAULT_TIMEOUT),
+            max_entries_per_second: None,
         }
     }
 }
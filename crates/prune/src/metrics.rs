@@ -10,6 +10,9 @@ use std::collections::HashMap;
 pub(crate) struct Metrics {
     /// Pruning duration
     pub(crate) duration_seconds: Histogram,
+    /// The block number that the segments were last pruned relative to, which is the tip or the
+    /// finished height of the ExExes if it's lower
+    pub(crate) prune_ceiling: Gauge,
     #[metric(skip)]
     prune_segments: HashMap<PruneSegment, PrunerSegmentMetrics>,
 }
@@ -34,4 +37,6 @@ pub(crate) struct PrunerSegmentMetrics {
     pub(crate) duration_seconds: Histogram,
     /// Highest pruned block per segment
     pub(crate) highest_pruned_block: Gauge,
+    /// Number of blocks left to prune per segment
+    pub(crate) backlog_blocks: Gauge,
 }
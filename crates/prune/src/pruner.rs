@@ -2,20 +2,22 @@
 
 use crate::{
     segments,
-    segments::{PruneInput, Segment},
+    segments::{PruneInput, Segment, SegmentSet},
     Metrics, PrunerError, PrunerEvent,
 };
+use reth_config::PruneConfig;
 use reth_db::database::Database;
 use reth_primitives::{
-    BlockNumber, PruneLimiter, PruneMode, PruneProgress, PrunePurpose, PruneSegment,
-    StaticFileSegment,
+    BlockNumber, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress, PrunePurpose,
+    PruneSegment, StaticFileSegment,
 };
 use reth_provider::{DatabaseProviderRW, ProviderFactory, PruneCheckpointReader};
 use reth_tokio_util::EventListeners;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     time::{Duration, Instant},
 };
+use tokio::sync::watch;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::debug;
 
@@ -27,6 +29,10 @@ pub type PrunerWithResult<DB> = (Pruner<DB>, PrunerResult);
 
 type PrunerStats = BTreeMap<PruneSegment, (PruneProgress, usize)>;
 
+/// The maximum duration the deletion budget of a rate limited segment is accrued for, so the
+/// budget doesn't pile up while the pruner isn't running, e.g. during the pipeline sync.
+const MAX_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
 /// Pruning routine. Main pruning logic happens in [Pruner::run].
 #[derive(Debug)]
 pub struct Pruner<DB> {
@@ -46,6 +52,20 @@ pub struct Pruner<DB> {
     prune_max_blocks_per_run: usize,
     /// Maximum time for a one pruner run.
     timeout: Option<Duration>,
+    /// If set, only these segments are pruned.
+    only_segments: Option<Vec<PruneSegment>>,
+    /// Maximum entries (rows in the database) every segment deletes per second.
+    max_entries_per_second: Option<usize>,
+    /// Time from which the deletion budget of the segments is accrued.
+    created_at: Instant,
+    /// Time up to which the deletion budget of every rate limited segment has been spent.
+    segments_spent_until: HashMap<PruneSegment, Instant>,
+    /// The lowest finished height of all ExExes, if ExExes are installed.
+    ///
+    /// `None` if not all ExExes reported a finished height yet.
+    finished_exex_height: Option<watch::Receiver<Option<BlockNumber>>>,
+    /// The latest config, if the config can be changed while the pruner is running.
+    config_updates: Option<watch::Receiver<PruneConfig>>,
     #[doc(hidden)]
     metrics: Metrics,
     listeners: EventListeners<PrunerEvent>,
@@ -69,11 +89,71 @@ impl<DB: Database> Pruner<DB> {
             delete_limit_per_block: delete_limit,
             prune_max_blocks_per_run,
             timeout,
+            only_segments: None,
+            max_entries_per_second: None,
+            created_at: Instant::now(),
+            segments_spent_until: HashMap::default(),
+            finished_exex_height: None,
+            config_updates: None,
             metrics: Metrics::default(),
             listeners: Default::default(),
         }
     }
 
+    /// Only prune the given segments, including the segments that are pruned according to the
+    /// highest static files.
+    pub fn with_only_segments(mut self, segments: Vec<PruneSegment>) -> Self {
+        self.only_segments = Some(segments);
+        self
+    }
+
+    /// Limits the number of entries (rows in the database) every segment deletes per second,
+    /// spreading the deletion of a large backlog over multiple runs.
+    pub fn with_max_entries_per_second(mut self, max_entries_per_second: Option<usize>) -> Self {
+        self.max_entries_per_second = max_entries_per_second;
+        self
+    }
+
+    /// Limits the pruning to the blocks that all ExExes finished processing.
+    ///
+    /// The lowest finished height of the ExExes is treated like the tip of the chain, so every
+    /// segment is pruned up to its configured distance below that height. Nothing is pruned as
+    /// long as not all ExExes reported a finished height.
+    pub fn with_finished_exex_height(
+        mut self,
+        finished_exex_height: watch::Receiver<Option<BlockNumber>>,
+    ) -> Self {
+        self.finished_exex_height = Some(finished_exex_height);
+        self
+    }
+
+    /// Applies the latest config of the receiver with [Pruner::apply_config_updates].
+    pub fn with_config_updates(mut self, config_updates: watch::Receiver<PruneConfig>) -> Self {
+        self.config_updates = Some(config_updates);
+        self
+    }
+
+    /// Replaces the prune modes of the segments, the minimum pruning interval and the maximum
+    /// entries every segment deletes per second.
+    ///
+    /// Data that was already pruned is not restored if a segment keeps more blocks than before.
+    pub fn set_config(&mut self, config: PruneConfig) {
+        debug!(target: "pruner", ?config, "Applying new prune config");
+        self.segments = SegmentSet::from_prune_modes(config.segments).into_vec();
+        self.min_block_interval = config.block_interval;
+        self.max_entries_per_second = config.max_entries_per_second;
+    }
+
+    /// Applies the latest config of the receiver set with [Pruner::with_config_updates], if it
+    /// changed since it was last applied.
+    pub fn apply_config_updates(&mut self) {
+        let Some(config_updates) = &mut self.config_updates else { return };
+        if config_updates.has_changed().unwrap_or_default() {
+            let config = config_updates.borrow_and_update().clone();
+            self.set_config(config);
+        }
+    }
+
     /// Listen for events on the pruner.
     pub fn events(&mut self) -> UnboundedReceiverStream<PrunerEvent> {
         self.listeners.new_listener()
@@ -81,7 +161,14 @@ impl<DB: Database> Pruner<DB> {
 
     /// Run the pruner
     pub fn run(&mut self, tip_block_number: BlockNumber) -> PrunerResult {
-        if tip_block_number == 0 {
+        let Some(prune_tip_block_number) = self.prune_tip_block_number(tip_block_number) else {
+            self.previous_tip_block_number = Some(tip_block_number);
+
+            debug!(target: "pruner", %tip_block_number, "Not all ExExes finished a block yet, nothing to prune");
+            return Ok(PruneProgress::Finished)
+        };
+
+        if prune_tip_block_number == 0 {
             self.previous_tip_block_number = Some(tip_block_number);
 
             debug!(target: "pruner", %tip_block_number, "Nothing to prune yet");
@@ -116,9 +203,14 @@ impl<DB: Database> Pruner<DB> {
             limiter = limiter.set_time_limit(timeout);
         };
 
+        if prune_tip_block_number < tip_block_number {
+            debug!(target: "pruner", %tip_block_number, %prune_tip_block_number, "Pruning is held back by ExExes");
+        }
+        self.metrics.prune_ceiling.set(prune_tip_block_number as f64);
+
         let provider = self.provider_factory.provider_rw()?;
         let (stats, deleted_entries, progress) =
-            self.prune_segments(&provider, tip_block_number, &mut limiter)?;
+            self.prune_segments(&provider, prune_tip_block_number, &mut limiter)?;
         provider.commit()?;
 
         self.previous_tip_block_number = Some(tip_block_number);
@@ -147,6 +239,18 @@ impl<DB: Database> Pruner<DB> {
         Ok(progress)
     }
 
+    /// Returns the block number that the segments are pruned relative to, which is the lower of
+    /// the tip and the finished height of the ExExes.
+    ///
+    /// Returns `None` if not all ExExes reported a finished height yet.
+    fn prune_tip_block_number(&self, tip_block_number: BlockNumber) -> Option<BlockNumber> {
+        let Some(finished_exex_height) = &self.finished_exex_height else {
+            return Some(tip_block_number)
+        };
+        let finished_exex_height = (*finished_exex_height.borrow())?;
+        Some(tip_block_number.min(finished_exex_height))
+    }
+
     /// Prunes the segments that the [Pruner] was initialized with, and the segments that needs to
     /// be pruned according to the highest static_files. Segments are parts of the database that
     /// represent one or more tables.
@@ -162,7 +266,10 @@ impl<DB: Database> Pruner<DB> {
         let segments = static_file_segments
             .iter()
             .map(|segment| (segment, PrunePurpose::StaticFile))
-            .chain(self.segments.iter().map(|segment| (segment, PrunePurpose::User)));
+            .chain(self.segments.iter().map(|segment| (segment, PrunePurpose::User)))
+            .filter(|(segment, _)| {
+                self.only_segments.as_ref().map_or(true, |only| only.contains(&segment.segment()))
+            });
 
         let mut stats = PrunerStats::new();
         let mut pruned = 0;
@@ -190,10 +297,45 @@ impl<DB: Database> Pruner<DB> {
 
                 let segment_start = Instant::now();
                 let previous_checkpoint = provider.get_prune_checkpoint(segment.segment())?;
+                let previous_pruned_block =
+                    previous_checkpoint.and_then(|checkpoint| checkpoint.block_number);
+
+                let mut segment_limiter = limiter.clone();
+                if let Some(max_entries_per_second) = self.max_entries_per_second {
+                    let spent_until = self
+                        .segments_spent_until
+                        .entry(segment.segment())
+                        .or_insert(self.created_at);
+                    let budget = rate_limit_budget(spent_until, max_entries_per_second);
+
+                    if budget == 0 && previous_pruned_block.map_or(true, |block| block < to_block) {
+                        debug!(
+                            target: "pruner",
+                            segment = ?segment.segment(),
+                            ?purpose,
+                            %to_block,
+                            "Segment deletion rate limit reached"
+                        );
+                        progress = PruneProgress::HasMoreData(
+                            PruneInterruptReason::DeletedEntriesLimitReached,
+                        );
+                        continue
+                    }
+                    segment_limiter = segment_limiter.cap_deleted_entries_left(budget);
+                }
+
                 let output = segment.prune(
                     provider,
-                    PruneInput { previous_checkpoint, to_block, limiter: limiter.clone() },
+                    PruneInput { previous_checkpoint, to_block, limiter: segment_limiter },
                 )?;
+                if let Some(max_entries_per_second) = self.max_entries_per_second {
+                    if let Some(spent_until) = self.segments_spent_until.get_mut(&segment.segment())
+                    {
+                        *spent_until += Duration::from_secs_f64(
+                            output.pruned as f64 / max_entries_per_second as f64,
+                        );
+                    }
+                }
                 if let Some(checkpoint) = output.checkpoint {
                     segment
                         .save_checkpoint(provider, checkpoint.as_prune_checkpoint(prune_mode))?;
@@ -210,8 +352,21 @@ impl<DB: Database> Pruner<DB> {
                         .highest_pruned_block
                         .set(highest_pruned_block as f64);
                 }
+                let backlog_blocks = output
+                    .checkpoint
+                    .and_then(|checkpoint| checkpoint.block_number)
+                    .or(previous_pruned_block)
+                    .map_or(to_block + 1, |pruned_block| to_block.saturating_sub(pruned_block));
+                self.metrics
+                    .get_prune_segment_metrics(segment.segment())
+                    .backlog_blocks
+                    .set(backlog_blocks as f64);
 
-                progress = output.progress;
+                // A segment that has more data to prune, e.g. because of its rate limit, keeps the
+                // run from being finished
+                if progress.is_finished() {
+                    progress = output.progress;
+                }
 
                 debug!(
                     target: "pruner",
@@ -230,6 +385,7 @@ impl<DB: Database> Pruner<DB> {
                 }
             } else {
                 debug!(target: "pruner", segment = ?segment.segment(), ?purpose, "Nothing to prune for the segment");
+                self.metrics.get_prune_segment_metrics(segment.segment()).backlog_blocks.set(0.);
             }
         }
 
@@ -288,13 +444,37 @@ impl<DB: Database> Pruner<DB> {
     }
 }
 
+/// Returns the number of entries the rate limited segment can delete, given the time up to which
+/// its budget has been spent.
+///
+/// The budget is accrued for at most [`MAX_RATE_LIMIT_WINDOW`].
+fn rate_limit_budget(spent_until: &mut Instant, max_entries_per_second: usize) -> usize {
+    let now = Instant::now();
+    if let Some(window_start) = now.checked_sub(MAX_RATE_LIMIT_WINDOW) {
+        *spent_until = (*spent_until).max(window_start);
+    }
+
+    (now.saturating_duration_since(*spent_until).as_secs_f64() * max_entries_per_second as f64)
+        as usize
+}
+
 #[cfg(test)]
 mod tests {
 
-    use crate::Pruner;
-    use reth_db::test_utils::{create_test_rw_db, create_test_static_files_dir};
-    use reth_primitives::MAINNET;
-    use reth_provider::ProviderFactory;
+    use crate::{segments, Pruner};
+    use reth_config::PruneConfig;
+    use reth_db::{
+        tables,
+        test_utils::{create_test_rw_db, create_test_static_files_dir},
+    };
+    use reth_interfaces::test_utils::{generators, generators::random_block_range};
+    use reth_primitives::{
+        PruneCheckpoint, PruneInterruptReason, PruneMode, PruneModes, PruneProgress, PruneSegment,
+        B256, MAINNET,
+    };
+    use reth_provider::{ProviderFactory, PruneCheckpointReader, PruneCheckpointWriter};
+    use reth_stages::test_utils::{StorageKind, TestStageDB};
+    use std::time::{Duration, Instant};
 
     #[test]
     fn is_pruning_needed() {
@@ -318,4 +498,178 @@ mod tests {
         let third_block_number = second_block_number;
         assert!(!pruner.is_pruning_needed(third_block_number));
     }
+
+    #[test]
+    fn apply_config_updates() {
+        let db = create_test_rw_db();
+        let (_static_dir, static_dir_path) = create_test_static_files_dir();
+        let provider_factory = ProviderFactory::new(db, MAINNET.clone(), static_dir_path)
+            .expect("create provide factory with static_files");
+        let (tx, rx) = tokio::sync::watch::channel(PruneConfig::default());
+        let mut pruner =
+            Pruner::new(provider_factory, vec![], 5, 0, 5, None).with_config_updates(rx);
+
+        pruner.apply_config_updates();
+        assert!(pruner.segments.is_empty());
+
+        tx.send_replace(PruneConfig {
+            block_interval: 10,
+            segments: PruneModes { receipts: Some(PruneMode::Distance(100)), ..PruneModes::none() },
+            max_entries_per_second: Some(1_000),
+        });
+        pruner.apply_config_updates();
+        assert_eq!(pruner.min_block_interval, 10);
+        assert_eq!(pruner.max_entries_per_second, Some(1_000));
+        assert_eq!(
+            pruner
+                .segments
+                .iter()
+                .map(|segment| (segment.segment(), segment.mode()))
+                .collect::<Vec<_>>(),
+            vec![(PruneSegment::Receipts, Some(PruneMode::Distance(100)))]
+        );
+    }
+
+    #[test]
+    fn prune_tip_block_number_is_limited_by_exexs() {
+        let db = create_test_rw_db();
+        let (_static_dir, static_dir_path) = create_test_static_files_dir();
+        let provider_factory = ProviderFactory::new(db, MAINNET.clone(), static_dir_path)
+            .expect("create provide factory with static_files");
+        let pruner = Pruner::new(provider_factory, vec![], 5, 0, 5, None);
+        assert_eq!(pruner.prune_tip_block_number(100), Some(100));
+
+        let (finished_exex_height_tx, finished_exex_height_rx) = tokio::sync::watch::channel(None);
+        let pruner = pruner.with_finished_exex_height(finished_exex_height_rx);
+        assert_eq!(pruner.prune_tip_block_number(100), None);
+
+        finished_exex_height_tx.send(Some(90)).unwrap();
+        assert_eq!(pruner.prune_tip_block_number(100), Some(90));
+
+        finished_exex_height_tx.send(Some(110)).unwrap();
+        assert_eq!(pruner.prune_tip_block_number(100), Some(100));
+    }
+
+    #[test]
+    fn keeps_progress_of_rate_limited_segments() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=20, B256::ZERO, 5..10);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+        let tip = blocks.last().unwrap().number;
+        let transactions = blocks.iter().map(|block| block.body.len() as u64).sum::<u64>();
+
+        // The transaction lookup segment is already pruned up to the tip
+        let provider = db.factory.provider_rw().unwrap();
+        provider
+            .save_prune_checkpoint(
+                PruneSegment::TransactionLookup,
+                PruneCheckpoint {
+                    block_number: Some(tip),
+                    tx_number: Some(transactions - 1),
+                    prune_mode: PruneMode::Full,
+                },
+            )
+            .unwrap();
+        provider.commit().unwrap();
+
+        let mut pruner = Pruner::new(
+            db.factory.clone(),
+            vec![
+                Box::new(segments::SenderRecovery::new(PruneMode::Full)),
+                Box::new(segments::TransactionLookup::new(PruneMode::Full)),
+            ],
+            1,
+            1000,
+            5,
+            None,
+        )
+        .with_only_segments(vec![PruneSegment::SenderRecovery, PruneSegment::TransactionLookup])
+        .with_max_entries_per_second(Some(5));
+        // The sender recovery segment has no budget left
+        pruner
+            .segments_spent_until
+            .insert(PruneSegment::SenderRecovery, Instant::now() + Duration::from_secs(60));
+
+        // The finished transaction lookup segment doesn't overwrite the progress of the rate
+        // limited sender recovery segment
+        assert_eq!(
+            pruner.run(tip).unwrap(),
+            PruneProgress::HasMoreData(PruneInterruptReason::DeletedEntriesLimitReached)
+        );
+    }
+
+    #[test]
+    fn rate_limits_segments() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let blocks = random_block_range(&mut rng, 1..=20, B256::ZERO, 5..10);
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        let mut transaction_senders = Vec::new();
+        for block in &blocks {
+            for transaction in &block.body {
+                transaction_senders.push((
+                    transaction_senders.len() as u64,
+                    transaction.recover_signer().expect("recover signer"),
+                ));
+            }
+        }
+        db.insert_transaction_senders(transaction_senders.clone())
+            .expect("insert transaction senders");
+
+        let max_entries_per_second = 5;
+        let mut pruner = Pruner::new(
+            db.factory.clone(),
+            vec![Box::new(segments::SenderRecovery::new(PruneMode::Full))],
+            1,
+            1000,
+            5,
+            None,
+        )
+        .with_only_segments(vec![PruneSegment::SenderRecovery])
+        .with_max_entries_per_second(Some(max_entries_per_second));
+
+        let tip = blocks.last().unwrap().number;
+        let mut previously_deleted = 0;
+        for elapsed in [Duration::from_secs(4), Duration::from_secs(2)] {
+            let started_at = Instant::now() - elapsed;
+            pruner.created_at = started_at;
+            pruner.segments_spent_until.insert(PruneSegment::SenderRecovery, started_at);
+            // Don't limit the run by the number of blocks since the previous one
+            pruner.previous_tip_block_number = None;
+
+            let progress = pruner.run(tip).unwrap();
+            assert!(!progress.is_finished());
+
+            // The budget is accrued for the duration of the run as well
+            let max_deleted =
+                (started_at.elapsed().as_secs_f64() * max_entries_per_second as f64) as usize;
+            let deleted = transaction_senders.len() -
+                db.table::<tables::TransactionSenders>().unwrap().len() -
+                previously_deleted;
+            assert!(deleted > 0 && deleted <= max_deleted);
+            previously_deleted += deleted;
+
+            let checkpoint = db
+                .factory
+                .provider()
+                .unwrap()
+                .get_prune_checkpoint(PruneSegment::SenderRecovery)
+                .unwrap()
+                .unwrap();
+            assert_eq!(checkpoint.tx_number, Some(previously_deleted as u64 - 1));
+        }
+
+        // Without any time passed, there's no budget left
+        pruner.previous_tip_block_number = None;
+        let progress = pruner.run(tip).unwrap();
+        assert!(!progress.is_finished());
+        assert_eq!(
+            db.table::<tables::TransactionSenders>().unwrap().len(),
+            transaction_senders.len() - previously_deleted
+        );
+    }
 }
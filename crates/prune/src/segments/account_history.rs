@@ -81,7 +81,6 @@ impl<DB: Database> Segment<DB> for AccountHistory {
         let (processed, pruned_indices) = prune_history_indices::<DB, tables::AccountsHistory, _>(
             provider,
             last_changeset_pruned_block,
-            |a, b| a.key == b.key,
             |key| ShardedKey::last(key.key),
         )?;
         trace!(target: "pruner", %processed, pruned = %pruned_indices, %done, "Pruned account history (history)");
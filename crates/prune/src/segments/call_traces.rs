@@ -0,0 +1,138 @@
+use crate::{
+    segments::{
+        history::prune_history_indices, PruneInput, PruneOutput, PruneOutputCheckpoint, Segment,
+    },
+    PrunerError,
+};
+use reth_db::{database::Database, models::ShardedKey, tables};
+use reth_primitives::{PruneMode, PruneProgress, PruneSegment};
+use reth_provider::DatabaseProviderRW;
+use tracing::{instrument, trace};
+
+/// Prunes the call trace index, i.e. the [tables::CallFromIndex] and [tables::CallToIndex] tables
+/// written by the `IndexCallTraces` stage.
+#[derive(Debug)]
+pub struct CallTraces {
+    mode: PruneMode,
+}
+
+impl CallTraces {
+    pub fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<DB: Database> Segment<DB> for CallTraces {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::CallTraces
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<PruneOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No call traces to prune");
+                return Ok(PruneOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        // The indices don't have changesets to limit the number of pruned entries by, so the
+        // whole range is always pruned at once.
+        let (processed_from, pruned_from) =
+            prune_history_indices::<DB, tables::CallFromIndex, _>(provider, range_end, |key| {
+                ShardedKey::last(key.key)
+            })?;
+        let (processed_to, pruned_to) =
+            prune_history_indices::<DB, tables::CallToIndex, _>(provider, range_end, |key| {
+                ShardedKey::last(key.key)
+            })?;
+        trace!(
+            target: "pruner",
+            processed = %(processed_from + processed_to),
+            pruned = %(pruned_from + pruned_to),
+            "Pruned call trace indices"
+        );
+
+        Ok(PruneOutput {
+            progress: PruneProgress::Finished,
+            pruned: pruned_from + pruned_to,
+            checkpoint: Some(PruneOutputCheckpoint {
+                block_number: Some(range_end),
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{CallTraces, PruneInput, PruneOutput, Segment};
+    use assert_matches::assert_matches;
+    use reth_db::{models::ShardedKey, tables, transaction::DbTxMut, BlockNumberList};
+    use reth_primitives::{
+        Address, PruneCheckpoint, PruneLimiter, PruneMode, PruneProgress, PruneSegment,
+    };
+    use reth_provider::PruneCheckpointReader;
+    use reth_stages::test_utils::TestStageDB;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+        let (from, to) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        db.commit(|tx| {
+            tx.put::<tables::CallFromIndex>(
+                ShardedKey::new(from, 20),
+                BlockNumberList::new_pre_sorted([10, 20]),
+            )?;
+            tx.put::<tables::CallFromIndex>(
+                ShardedKey::last(from),
+                BlockNumberList::new_pre_sorted([30, 40]),
+            )?;
+            tx.put::<tables::CallToIndex>(
+                ShardedKey::last(to),
+                BlockNumberList::new_pre_sorted([10, 20, 30]),
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let prune_mode = PruneMode::Before(31);
+        let input = PruneInput {
+            previous_checkpoint: None,
+            to_block: 30,
+            limiter: PruneLimiter::default(),
+        };
+        let segment = CallTraces::new(prune_mode);
+
+        let provider = db.factory.provider_rw().unwrap();
+        let result = segment.prune(&provider, input).unwrap();
+        assert_matches!(
+            result,
+            PruneOutput { progress: PruneProgress::Finished, pruned: 2, checkpoint: Some(_) }
+        );
+        segment
+            .save_checkpoint(&provider, result.checkpoint.unwrap().as_prune_checkpoint(prune_mode))
+            .unwrap();
+        provider.commit().expect("commit");
+
+        assert_eq!(
+            db.table::<tables::CallFromIndex>().unwrap(),
+            vec![(ShardedKey::last(from), BlockNumberList::new_pre_sorted([40]))]
+        );
+        assert_eq!(db.table::<tables::CallToIndex>().unwrap(), vec![]);
+        assert_eq!(
+            db.factory.provider().unwrap().get_prune_checkpoint(PruneSegment::CallTraces).unwrap(),
+            Some(PruneCheckpoint { block_number: Some(30), tx_number: None, prune_mode })
+        );
+    }
+}
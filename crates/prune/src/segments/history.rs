@@ -12,11 +12,16 @@ use reth_provider::DatabaseProviderRW;
 
 /// Prune history indices up to the provided block, inclusive.
 ///
+/// For every sharded key, the shards that only contain block numbers up to the target block are
+/// deleted, and the boundary shard, i.e. the first shard with block numbers above the target
+/// block, is rewritten without the pruned block numbers. The shards after the boundary shard only
+/// contain higher block numbers and are left untouched, and the last shard of the key keeps the
+/// `u64::MAX` highest block number.
+///
 /// Returns total number of processed (walked) and deleted entities.
 pub(crate) fn prune_history_indices<DB, T, SK>(
     provider: &DatabaseProviderRW<DB>,
     to_block: BlockNumber,
-    key_matches: impl Fn(&T::Key, &T::Key) -> bool,
     last_key: impl Fn(&T::Key) -> T::Key,
 ) -> Result<(usize, usize), DatabaseError>
 where
@@ -28,17 +33,12 @@ where
     let mut deleted = 0;
     let mut cursor = provider.tx_ref().cursor_write::<T>()?;
 
-    // Prune history table:
-    // 1. If the shard has `highest_block_number` less than or equal to the target block number
-    // for pruning, delete the shard completely.
-    // 2. If the shard has `highest_block_number` greater than the target block number for
-    // pruning, filter block numbers inside the shard which are less than the target
-    // block number for pruning.
     while let Some(result) = cursor.next()? {
         let (key, blocks): (T::Key, BlockNumberList) = result;
+        processed += 1;
 
-        // If shard consists only of block numbers less than the target one, delete shard
-        // completely.
+        // If shard consists only of block numbers less than or equal to the target one, delete
+        // shard completely.
         if key.as_ref().highest_block_number <= to_block {
             cursor.delete_current()?;
             deleted += 1;
@@ -48,65 +48,117 @@ where
                 // sharded key will not contain the target block number, as it's in this shard.
                 cursor.seek_exact(last_key(&key))?;
             }
+            continue
         }
-        // Shard contains block numbers that are higher than the target one, so we need to
-        // filter it. It is guaranteed that further shards for this sharded key will not
-        // contain the target block number, as it's in this shard.
-        else {
-            let higher_blocks =
-                blocks.iter().skip_while(|block| *block <= to_block).collect::<Vec<_>>();
-
-            // If there were blocks less than or equal to the target one
-            // (so the shard has changed), update the shard.
-            if blocks.len() as usize != higher_blocks.len() {
-                // If there will be no more blocks in the shard after pruning blocks below target
-                // block, we need to remove it, as empty shards are not allowed.
-                if higher_blocks.is_empty() {
-                    if key.as_ref().highest_block_number == u64::MAX {
-                        let prev_row = cursor.prev()?;
-                        match prev_row {
-                            // If current shard is the last shard for the sharded key that
-                            // has previous shards, replace it with the previous shard.
-                            Some((prev_key, prev_value)) if key_matches(&prev_key, &key) => {
-                                cursor.delete_current()?;
-                                deleted += 1;
-                                // Upsert will replace the last shard for this sharded key with
-                                // the previous value.
-                                cursor.upsert(key.clone(), prev_value)?;
-                            }
-                            // If there's no previous shard for this sharded key,
-                            // just delete last shard completely.
-                            _ => {
-                                // If we successfully moved the cursor to a previous row,
-                                // jump to the original last shard.
-                                if prev_row.is_some() {
-                                    cursor.next()?;
-                                }
-                                // Delete shard.
-                                cursor.delete_current()?;
-                                deleted += 1;
-                            }
-                        }
-                    }
-                    // If current shard is not the last shard for this sharded key,
-                    // just delete it.
-                    else {
-                        cursor.delete_current()?;
-                        deleted += 1;
-                    }
-                } else {
-                    cursor.upsert(key.clone(), BlockNumberList::new_pre_sorted(higher_blocks))?;
-                }
-            }
 
-            // Jump to the last shard for this key, if current key isn't already the last shard.
-            if key.as_ref().highest_block_number != u64::MAX {
-                cursor.seek_exact(last_key(&key))?;
+        // Shard contains block numbers that are higher than the target one, so it's the boundary
+        // shard of this key and we need to filter it. It is guaranteed that further shards for
+        // this sharded key will not contain the target block number, as it's in this shard.
+        if let Some(higher_blocks) = prune_shard(&blocks, to_block) {
+            if higher_blocks.is_empty() {
+                // Only the last shard can become empty, as other shards contain their highest
+                // block number. All previous shards of the key were deleted, so the key has no
+                // shards left and there is no shard to take over the last shard.
+                cursor.delete_current()?;
+                deleted += 1;
+            } else {
+                cursor.upsert(key.clone(), BlockNumberList::new_pre_sorted(higher_blocks))?;
             }
         }
 
-        processed += 1;
+        // Jump to the last shard for this key, if current key isn't already the last shard.
+        if key.as_ref().highest_block_number != u64::MAX {
+            cursor.seek_exact(last_key(&key))?;
+        }
     }
 
     Ok((processed, deleted))
 }
+
+/// Returns the block numbers of the shard that are higher than the target block, or `None` if the
+/// shard doesn't contain any block numbers up to the target block.
+fn prune_shard(blocks: &BlockNumberList, to_block: BlockNumber) -> Option<Vec<u64>> {
+    let higher_blocks = blocks.iter().skip_while(|block| *block <= to_block).collect::<Vec<_>>();
+    (blocks.len() as usize != higher_blocks.len()).then_some(higher_blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{tables, transaction::DbTxMut};
+    use reth_primitives::Address;
+    use reth_stages::test_utils::TestStageDB;
+
+    /// Prunes the shards of two addresses up to the target block and returns the remaining ones.
+    ///
+    /// The first address has the shards `[1, 2, 3]`, `[5, 6, 7]` and `[9, 10]`, the second one
+    /// only has the last shard `[2, 8]`.
+    fn prune(to_block: BlockNumber) -> Vec<(ShardedKey<Address>, Vec<u64>)> {
+        let (first, second) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let shards = [
+            (ShardedKey::new(first, 3), vec![1, 2, 3]),
+            (ShardedKey::new(first, 7), vec![5, 6, 7]),
+            (ShardedKey::last(first), vec![9, 10]),
+            (ShardedKey::last(second), vec![2, 8]),
+        ];
+
+        let db = TestStageDB::default();
+        db.commit(|tx| {
+            for (key, blocks) in shards {
+                tx.put::<tables::AccountsHistory>(key, BlockNumberList::new_pre_sorted(blocks))?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let provider = db.factory.provider_rw().unwrap();
+        prune_history_indices::<_, tables::AccountsHistory, _>(&provider, to_block, |key| {
+            ShardedKey::last(key.key)
+        })
+        .unwrap();
+        provider.commit().unwrap();
+
+        db.table::<tables::AccountsHistory>()
+            .unwrap()
+            .into_iter()
+            .map(|(key, blocks)| (key, blocks.iter().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn prune_inside_shard() {
+        let (first, second) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        assert_eq!(
+            prune(5),
+            vec![
+                (ShardedKey::new(first, 7), vec![6, 7]),
+                (ShardedKey::last(first), vec![9, 10]),
+                (ShardedKey::last(second), vec![8]),
+            ]
+        );
+    }
+
+    #[test]
+    fn prune_at_shard_boundary() {
+        let (first, second) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        assert_eq!(
+            prune(7),
+            vec![(ShardedKey::last(first), vec![9, 10]), (ShardedKey::last(second), vec![8])]
+        );
+        assert_eq!(
+            prune(3),
+            vec![
+                (ShardedKey::new(first, 7), vec![5, 6, 7]),
+                (ShardedKey::last(first), vec![9, 10]),
+                (ShardedKey::last(second), vec![8]),
+            ]
+        );
+    }
+
+    #[test]
+    fn prune_beyond_all_shards() {
+        let first = Address::with_last_byte(1);
+        assert_eq!(prune(8), vec![(ShardedKey::last(first), vec![9, 10])]);
+        assert_eq!(prune(10), vec![]);
+    }
+}
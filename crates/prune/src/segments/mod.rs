@@ -1,4 +1,5 @@
 mod account_history;
+mod call_traces;
 mod headers;
 pub(super) mod history;
 mod receipts;
@@ -10,6 +11,7 @@ mod transaction_lookup;
 mod transactions;
 
 pub use account_history::AccountHistory;
+pub use call_traces::CallTraces;
 pub use headers::Headers;
 pub use receipts::Receipts;
 pub use receipts_by_logs::ReceiptsByLogs;
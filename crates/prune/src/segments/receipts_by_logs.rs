@@ -4,10 +4,11 @@ use crate::{
 };
 use reth_db::{database::Database, tables};
 use reth_primitives::{
-    PruneCheckpoint, PruneMode, PruneProgress, PrunePurpose, PruneSegment, ReceiptsLogPruneConfig,
+    PruneCheckpoint, PruneMode, PruneProgress, PruneSegment, ReceiptsLogPruneConfig,
     MINIMUM_PRUNING_DISTANCE,
 };
 use reth_provider::{BlockReader, DatabaseProviderRW, PruneCheckpointWriter, TransactionsProvider};
+use std::collections::HashMap;
 use tracing::{instrument, trace};
 
 #[derive(Debug)]
@@ -27,7 +28,10 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
     }
 
     fn mode(&self) -> Option<PruneMode> {
-        None
+        // Contract log filtering removes every receipt possible except the ones in the list. So,
+        // for the other receipts it's as if they had a `PruneMode::Distance()` of
+        // `MINIMUM_PRUNING_DISTANCE`.
+        Some(PruneMode::Distance(MINIMUM_PRUNING_DISTANCE))
     }
 
     #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
@@ -36,13 +40,10 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
         provider: &DatabaseProviderRW<DB>,
         input: PruneInput,
     ) -> Result<PruneOutput, PrunerError> {
-        // Contract log filtering removes every receipt possible except the ones in the list. So,
-        // for the other receipts it's as if they had a `PruneMode::Distance()` of
-        // `MINIMUM_PRUNING_DISTANCE`.
-        let to_block = PruneMode::Distance(MINIMUM_PRUNING_DISTANCE)
-            .prune_target_block(input.to_block, PruneSegment::ContractLogs, PrunePurpose::User)?
-            .map(|(bn, _)| bn)
-            .unwrap_or_default();
+        // The target block is set according to the mode of the segment, so the tip the addresses
+        // are retained at is `MINIMUM_PRUNING_DISTANCE` blocks ahead of it.
+        let to_block = input.to_block;
+        let tip = to_block + MINIMUM_PRUNING_DISTANCE;
 
         // Get status checkpoint from latest run
         let mut last_pruned_block =
@@ -50,7 +51,7 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
 
         let initial_last_pruned_block = last_pruned_block;
 
-        let mut from_tx_number = match initial_last_pruned_block {
+        let from_tx_number = match initial_last_pruned_block {
             Some(block) => provider
                 .block_body_indices(block)?
                 .map(|block| block.last_tx_num() + 1)
@@ -60,57 +61,36 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
 
         // Figure out what receipts have already been pruned, so we can have an accurate
         // `address_filter`
-        let address_filter = self.config.group_by_block(input.to_block, last_pruned_block)?;
+        let address_filter = self.config.group_by_block(tip, last_pruned_block)?;
 
-        // Splits all transactions in different block ranges. Each block range will have its own
-        // filter address list and will check it while going through the table
+        // Every address is retained from the first transaction of the block it's grouped by, so
+        // all receipts can be pruned in a single walk over the table, checking the logs of every
+        // receipt against all addresses at once.
         //
         // Example:
         // For an `address_filter` such as:
         // { block9: [a1, a2], block20: [a3, a4, a5] }
         //
-        // The following structures will be created in the exact order as showed:
-        // `block_ranges`: [
-        //    (block0, block8, 0 addresses),
-        //    (block9, block19, 2 addresses),
-        //    (block20, to_block, 5 addresses)
-        //  ]
-        // `filtered_addresses`: [a1, a2, a3, a4, a5]
+        // The following map will be created:
+        // `retained_from_tx`: {
+        //    a1: first tx of block9, a2: first tx of block9,
+        //    a3: first tx of block20, a4: first tx of block20, a5: first tx of block20
+        // }
         //
-        // The first range will delete all receipts between block0 - block8
-        // The second range will delete all receipts between block9 - 19, except the ones with
-        //     emitter logs from these addresses: [a1, a2].
-        // The third range will delete all receipts between block20 - to_block, except the ones with
-        //     emitter logs from these addresses: [a1, a2, a3, a4, a5]
-        let mut block_ranges = vec![];
-        let mut blocks_iter = address_filter.iter().peekable();
-        let mut filtered_addresses = vec![];
-
-        while let Some((start_block, addresses)) = blocks_iter.next() {
-            filtered_addresses.extend_from_slice(addresses);
-
-            // This will clear all receipts before the first  appearance of a contract log or since
-            // the block after the last pruned one.
-            if block_ranges.is_empty() {
-                let init = last_pruned_block.map(|b| b + 1).unwrap_or_default();
-                if init < *start_block {
-                    block_ranges.push((init, *start_block - 1, 0));
-                }
-            }
-
-            let end_block =
-                blocks_iter.peek().map(|(next_block, _)| *next_block - 1).unwrap_or(to_block);
-
-            // Addresses in lower block ranges, are still included in the inclusion list for future
-            // ranges.
-            block_ranges.push((*start_block, end_block, filtered_addresses.len()));
+        // All receipts up to `to_block` are deleted, except the ones with emitter logs from an
+        // address whose first retained transaction is not higher than the receipt's one.
+        let mut retained_from_tx = HashMap::new();
+        for (block, addresses) in address_filter {
+            // Without the block, no receipts of the addresses are retained in this run
+            let Some(body) = provider.block_body_indices(block)? else { continue };
+            retained_from_tx
+                .extend(addresses.into_iter().map(|address| (*address, body.first_tx_num)));
         }
 
         trace!(
             target: "pruner",
-            ?block_ranges,
-            ?filtered_addresses,
-            "Calculated block ranges and filtered addresses",
+            ?retained_from_tx,
+            "Calculated first retained transactions of the filtered addresses",
         );
 
         let mut limiter = input.limiter;
@@ -118,69 +98,51 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
         let mut done = true;
         let mut pruned = 0;
         let mut last_pruned_transaction = None;
-        for (start_block, end_block, num_addresses) in block_ranges {
-            let block_range = start_block..=end_block;
-
-            // Calculate the transaction range from this block range
-            let tx_range_end = match provider.block_body_indices(end_block)? {
-                Some(body) => body.last_tx_num(),
-                None => {
-                    trace!(
-                        target: "pruner",
-                        ?block_range,
-                        "No receipts to prune."
-                    );
-                    continue
-                }
-            };
-            let tx_range = from_tx_number..=tx_range_end;
-
-            // Delete receipts, except the ones in the inclusion list
-            let mut last_skipped_transaction = 0;
-            let deleted;
-            (deleted, done) = provider.prune_table_with_range::<tables::Receipts>(
-                tx_range,
-                &mut limiter,
-                |(tx_num, receipt)| {
-                    let skip = num_addresses > 0 &&
-                        receipt.logs.iter().any(|log| {
-                            filtered_addresses[..num_addresses].contains(&&log.address)
+        match provider
+            .block_body_indices(to_block)?
+            .map(|body| from_tx_number..=body.last_tx_num())
+            .filter(|tx_range| !tx_range.is_empty())
+        {
+            Some(tx_range) => {
+                // Delete receipts, except the ones with logs of the retained addresses
+                let mut last_skipped_transaction = 0;
+                (pruned, done) = provider.prune_table_with_range::<tables::Receipts>(
+                    tx_range,
+                    &mut limiter,
+                    |(tx_num, receipt)| {
+                        let skip = receipt.logs.iter().any(|log| {
+                            retained_from_tx
+                                .get(&log.address)
+                                .map_or(false, |retained_from_tx| tx_num >= retained_from_tx)
                         });
 
-                    if skip {
-                        last_skipped_transaction = *tx_num;
-                    }
-                    skip
-                },
-                |row| last_pruned_transaction = Some(row.0),
-            )?;
-
-            trace!(target: "pruner", %deleted, %done, ?block_range, "Pruned receipts");
-
-            pruned += deleted;
-
-            // For accurate checkpoints we need to know that we have checked every transaction.
-            // Example: we reached the end of the range, and the last receipt is supposed to skip
-            // its deletion.
-            let last_pruned_transaction = *last_pruned_transaction
-                .insert(last_pruned_transaction.unwrap_or_default().max(last_skipped_transaction));
-
-            last_pruned_block = Some(
-                provider
-                    .transaction_block(last_pruned_transaction)?
-                    .ok_or(PrunerError::InconsistentData("Block for transaction is not found"))?
-                    // If there's more receipts to prune, set the checkpoint block number to
-                    // previous, so we could finish pruning its receipts on the
-                    // next run.
-                    .saturating_sub(if done { 0 } else { 1 }),
-            );
-
-            if limiter.is_limit_reached() {
-                done &= end_block == to_block;
-                break
+                        if skip {
+                            last_skipped_transaction = *tx_num;
+                        }
+                        skip
+                    },
+                    |row| last_pruned_transaction = Some(row.0),
+                )?;
+
+                trace!(target: "pruner", %pruned, %done, "Pruned receipts");
+
+                // For accurate checkpoints we need to know that we have checked every
+                // transaction. Example: we reached the end of the range, and the last receipt is
+                // supposed to skip its deletion.
+                let last_pruned_transaction = *last_pruned_transaction.insert(
+                    last_pruned_transaction.unwrap_or_default().max(last_skipped_transaction),
+                );
+
+                last_pruned_block = Some(
+                    provider
+                        .transaction_block(last_pruned_transaction)?
+                        .ok_or(PrunerError::InconsistentData("Block for transaction is not found"))?
+                        // If there's more receipts to prune, set the checkpoint block number to
+                        // previous, so we could finish pruning its receipts on the next run.
+                        .saturating_sub(if done { 0 } else { 1 }),
+                );
             }
-
-            from_tx_number = last_pruned_transaction + 1;
+            None => trace!(target: "pruner", %to_block, "No receipts to prune"),
         }
 
         // If there are contracts using `PruneMode::Distance(_)` there will be receipts before
@@ -194,7 +156,7 @@ impl<DB: Database> Segment<DB> for ReceiptsByLogs {
         // checkpoint is the `last_pruned_block`.
         let prune_mode_block = self
             .config
-            .lowest_block_with_distance(input.to_block, initial_last_pruned_block)?
+            .lowest_block_with_distance(tip, initial_last_pruned_block)?
             .unwrap_or(to_block);
 
         provider.save_prune_checkpoint(
@@ -221,7 +183,10 @@ mod tests {
         generators,
         generators::{random_block_range, random_eoa_account, random_log, random_receipt},
     };
-    use reth_primitives::{PruneLimiter, PruneMode, PruneSegment, ReceiptsLogPruneConfig, B256};
+    use reth_primitives::{
+        PruneLimiter, PruneMode, PruneSegment, ReceiptsLogPruneConfig, B256,
+        MINIMUM_PRUNING_DISTANCE,
+    };
     use reth_provider::{PruneCheckpointReader, TransactionsProvider};
     use reth_stages::test_utils::{StorageKind, TestStageDB};
     use std::collections::BTreeMap;
@@ -286,7 +251,7 @@ mod tests {
                         .unwrap()
                         .get_prune_checkpoint(PruneSegment::ContractLogs)
                         .unwrap(),
-                    to_block: tip,
+                    to_block: tip - MINIMUM_PRUNING_DISTANCE,
                     limiter,
                 },
             );
@@ -332,4 +297,82 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn prune_receipts_by_multiple_logs() {
+        let db = TestStageDB::default();
+        let mut rng = generators::rng();
+
+        let tip = 20000;
+        let blocks = [
+            random_block_range(&mut rng, 0..=100, B256::ZERO, 1..5),
+            random_block_range(&mut rng, (100 + 1)..=(tip - 100), B256::ZERO, 0..1),
+            random_block_range(&mut rng, (tip - 100 + 1)..=tip, B256::ZERO, 1..5),
+        ]
+        .concat();
+        db.insert_blocks(blocks.iter(), StorageKind::Database(None)).expect("insert blocks");
+
+        // Every address is retained from a different block
+        let addresses = (0..3).map(|_| random_eoa_account(&mut rng).0).collect::<Vec<_>>();
+        let receipts_log_filter = ReceiptsLogPruneConfig(BTreeMap::from([
+            (addresses[0], PruneMode::Before(20)),
+            (addresses[1], PruneMode::Before(60)),
+            (addresses[2], PruneMode::Distance(tip - 40)),
+        ]));
+        let retained_from_blocks = [20, 60, 41];
+
+        let mut receipts = Vec::new();
+        for block in &blocks {
+            for (txi, transaction) in block.body.iter().enumerate() {
+                let mut receipt = random_receipt(&mut rng, transaction, Some(1));
+                receipt.logs.push(random_log(
+                    &mut rng,
+                    Some(addresses[(block.number as usize + txi) % addresses.len()]),
+                    Some(1),
+                ));
+                receipts.push((receipts.len() as u64, receipt));
+            }
+        }
+        db.insert_receipts(receipts).expect("insert receipts");
+
+        loop {
+            let provider = db.factory.provider_rw().unwrap();
+            let output = ReceiptsByLogs::new(receipts_log_filter.clone())
+                .prune(
+                    &provider,
+                    PruneInput {
+                        previous_checkpoint: provider
+                            .get_prune_checkpoint(PruneSegment::ContractLogs)
+                            .unwrap(),
+                        to_block: tip - MINIMUM_PRUNING_DISTANCE,
+                        limiter: PruneLimiter::default().set_deleted_entries_limit(10),
+                    },
+                )
+                .unwrap();
+            provider.commit().expect("commit");
+
+            if output.progress.is_finished() {
+                break
+            }
+        }
+
+        let provider = db.factory.provider().unwrap();
+        let mut cursor = provider.tx_ref().cursor_read::<tables::Receipts>().unwrap();
+        let mut retained = vec![0; addresses.len()];
+        for receipt in cursor.walk(None).unwrap() {
+            let (tx_num, receipt) = receipt.unwrap();
+            let block = provider.transaction_block(tx_num).unwrap().unwrap();
+
+            // Either the receipt is retained by the address of its log, or it's part of the
+            // unprunable receipts set by tip - 128
+            let index = addresses.iter().position(|address| *address == receipt.logs[1].address);
+            match index {
+                Some(index) if block >= retained_from_blocks[index] => retained[index] += 1,
+                _ => assert!(block > tip - 128),
+            }
+        }
+
+        // Block receipts of every address were retained
+        assert!(retained.iter().all(|retained| *retained > 0));
+    }
 }
@@ -1,5 +1,5 @@
 use crate::segments::{
-    AccountHistory, Receipts, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory,
+    AccountHistory, CallTraces, Receipts, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory,
     TransactionLookup,
 };
 use reth_db::database::Database;
@@ -44,6 +44,7 @@ impl<DB: Database> SegmentSet<DB> {
             receipts,
             account_history,
             storage_history,
+            call_traces,
             receipts_log_filter,
         } = prune_modes;
 
@@ -63,6 +64,8 @@ impl<DB: Database> SegmentSet<DB> {
             .segment_opt(account_history.map(AccountHistory::new))
             // Storage history
             .segment_opt(storage_history.map(StorageHistory::new))
+            // Call traces
+            .segment_opt(call_traces.map(CallTraces::new))
     }
 }
 
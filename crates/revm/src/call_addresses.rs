@@ -0,0 +1,70 @@
+//! An inspector that records the addresses of the calls of the executed transactions.
+
+use revm::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+    primitives::{Address, U256},
+    Database, EvmContext, Inspector,
+};
+use std::collections::HashSet;
+
+/// Records the `from` and `to` addresses of the call, create and self-destruct actions of the
+/// executed transactions, as they're reported by the parity call traces.
+///
+/// The addresses are a superset of the addresses of the traces, e.g. both the address and the code
+/// address of a delegate call are recorded as recipients, so they can be used to find the blocks
+/// that contain the traces of an address.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallAddresses {
+    /// The senders of calls, the creators of contracts and the self-destructed contracts.
+    pub from: HashSet<Address>,
+    /// The recipients of calls, the created contracts and the refund addresses of self-destructs.
+    pub to: HashSet<Address>,
+}
+
+impl CallAddresses {
+    /// Returns `true` if no addresses were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.from.is_empty() && self.to.is_empty()
+    }
+}
+
+impl<DB: Database> Inspector<DB> for CallAddresses {
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.from.extend([inputs.context.caller, inputs.transfer.source]);
+        self.to.extend([
+            inputs.contract,
+            inputs.context.address,
+            inputs.context.code_address,
+            inputs.transfer.target,
+        ]);
+        None
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.from.insert(inputs.caller);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.to.extend(outcome.address);
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, _value: U256) {
+        self.from.insert(contract);
+        self.to.insert(target);
+    }
+}
@@ -31,6 +31,9 @@ pub mod eth_dao_fork;
 /// used in the main Reth executor.
 pub mod stack;
 
+/// An inspector recording the addresses of the calls of the executed transactions.
+pub mod call_addresses;
+
 /// Optimism-specific implementation and utilities for the executor
 #[cfg(feature = "optimism")]
 pub mod optimism;
@@ -52,27 +52,48 @@ where
     ) -> Result<(), BlockExecutionError> {
         // execute block
         let receipts = self.execute_inner(block, total_difficulty)?;
+        self.verify_receipts(block, &receipts)?;
+        self.save_receipts(receipts)
+    }
+
+    fn execute_block(
+        &mut self,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+    ) -> Result<Vec<Receipt>, BlockExecutionError> {
+        self.execute_inner(block, total_difficulty)
+    }
 
+    fn save_receipts(&mut self, receipts: Vec<Receipt>) -> Result<(), BlockExecutionError> {
+        EVMProcessor::save_receipts(self, receipts)
+    }
+
+    fn verify_receipts(
+        &mut self,
+        block: &BlockWithSenders,
+        receipts: &[Receipt],
+    ) -> Result<bool, BlockExecutionError> {
         // TODO Before Byzantium, receipts contained state root that would mean that expensive
         // operation as hashing that is needed for state root got calculated in every
         // transaction This was replaced with is_success flag.
         // See more about EIP here: https://eips.ethereum.org/EIPS/eip-658
-        if self.chain_spec.fork(Hardfork::Byzantium).active_at_block(block.header.number) {
-            let time = Instant::now();
-            if let Err(error) = verify_receipt_optimism(
-                block.header.receipts_root,
-                block.header.logs_bloom,
-                receipts.iter(),
-                self.chain_spec.as_ref(),
-                block.timestamp,
-            ) {
-                debug!(target: "evm", %error, ?receipts, "receipts verification failed");
-                return Err(error)
-            };
-            self.stats.receipt_root_duration += time.elapsed();
+        if !self.chain_spec.fork(Hardfork::Byzantium).active_at_block(block.header.number) {
+            return Ok(false)
         }
 
-        self.save_receipts(receipts)
+        let time = Instant::now();
+        if let Err(error) = verify_receipt_optimism(
+            block.header.receipts_root,
+            block.header.logs_bloom,
+            receipts.iter(),
+            self.chain_spec.as_ref(),
+            block.timestamp,
+        ) {
+            debug!(target: "evm", %error, ?receipts, "receipts verification failed");
+            return Err(error)
+        };
+        self.stats.receipt_root_duration += time.elapsed();
+        Ok(true)
     }
 
     fn execute_transactions(
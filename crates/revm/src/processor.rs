@@ -7,7 +7,7 @@ use revm::{
     primitives::{CfgEnvWithHandlerCfg, ResultAndState},
     Evm, State,
 };
-use std::{sync::Arc, time::Instant};
+use std::{collections::HashSet, sync::Arc, time::Instant};
 #[cfg(not(feature = "optimism"))]
 use tracing::{debug, trace};
 
@@ -20,7 +20,7 @@ use reth_primitives::revm::env::fill_tx_env;
 use reth_primitives::{
     Address, Block, BlockNumber, BlockWithSenders, Bloom, ChainSpec, GotExpected, Hardfork, Header,
     PruneMode, PruneModes, PruneSegmentError, Receipt, ReceiptWithBloom, Receipts,
-    TransactionSigned, Withdrawals, B256, MINIMUM_PRUNING_DISTANCE, U256,
+    TransactionSigned, B256, MINIMUM_PRUNING_DISTANCE, U256,
 };
 #[cfg(not(feature = "optimism"))]
 use reth_provider::BundleStateWithReceipts;
@@ -29,10 +29,10 @@ use reth_provider::{
 };
 
 use crate::{
+    call_addresses::CallAddresses,
     database::StateProviderDatabase,
-    eth_dao_fork::{DAO_HARDFORK_BENEFICIARY, DAO_HARDKFORK_ACCOUNTS},
     stack::{InspectorStack, InspectorStackConfig},
-    state_change::{apply_beacon_root_contract_call, post_block_balance_increments},
+    state_change::{apply_beacon_root_contract_call, post_block_balance_changes},
 };
 
 /// EVMProcessor is a block executor that uses revm to execute blocks or multiple blocks.
@@ -128,6 +128,11 @@ where
         self.evm.context.external = stack;
     }
 
+    /// Returns a mutable reference to the configured inspectors.
+    pub fn stack_mut(&mut self) -> &mut InspectorStack {
+        &mut self.evm.context.external
+    }
+
     /// Configure the executor with the given block.
     pub fn set_first_block(&mut self, num: BlockNumber) {
         self.first_block = Some(num);
@@ -186,30 +191,13 @@ where
         block: &Block,
         total_difficulty: U256,
     ) -> Result<(), BlockExecutionError> {
-        let mut balance_increments = post_block_balance_increments(
-            &self.chain_spec,
-            block.number,
-            block.difficulty,
-            block.beneficiary,
-            block.timestamp,
-            total_difficulty,
-            &block.ommers,
-            block.withdrawals.as_ref().map(Withdrawals::as_ref),
-        );
+        let chain_spec = Arc::clone(&self.chain_spec);
+        let balance_increments =
+            post_block_balance_changes(&chain_spec, block, total_difficulty, |accounts| {
+                self.db_mut().drain_balances(accounts.iter().copied())
+            })
+            .map_err(|_| BlockValidationError::IncrementBalanceFailed)?;
 
-        // Irregular state change at Ethereum DAO hardfork
-        if self.chain_spec.fork(Hardfork::Dao).transitions_at_block(block.number) {
-            // drain balances from hardcoded addresses.
-            let drained_balance: u128 = self
-                .db_mut()
-                .drain_balances(DAO_HARDKFORK_ACCOUNTS)
-                .map_err(|_| BlockValidationError::IncrementBalanceFailed)?
-                .into_iter()
-                .sum();
-
-            // return balance to DAO beneficiary.
-            *balance_increments.entry(DAO_HARDFORK_BENEFICIARY).or_default() += drained_balance;
-        }
         // increment balances
         self.db_mut()
             .increment_balances(balance_increments)
@@ -391,23 +379,44 @@ where
     ) -> Result<(), BlockExecutionError> {
         // execute block
         let receipts = self.execute_inner(block, total_difficulty)?;
+        self.verify_receipts(block, &receipts)?;
+        self.save_receipts(receipts)
+    }
+
+    fn execute_block(
+        &mut self,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+    ) -> Result<Vec<Receipt>, BlockExecutionError> {
+        self.execute_inner(block, total_difficulty)
+    }
+
+    fn save_receipts(&mut self, receipts: Vec<Receipt>) -> Result<(), BlockExecutionError> {
+        EVMProcessor::save_receipts(self, receipts)
+    }
 
+    fn verify_receipts(
+        &mut self,
+        block: &BlockWithSenders,
+        receipts: &[Receipt],
+    ) -> Result<bool, BlockExecutionError> {
         // TODO Before Byzantium, receipts contained state root that would mean that expensive
         // operation as hashing that is needed for state root got calculated in every
         // transaction This was replaced with is_success flag.
         // See more about EIP here: https://eips.ethereum.org/EIPS/eip-658
-        if self.chain_spec.fork(Hardfork::Byzantium).active_at_block(block.header.number) {
-            let time = Instant::now();
-            if let Err(error) =
-                verify_receipt(block.header.receipts_root, block.header.logs_bloom, receipts.iter())
-            {
-                debug!(target: "evm", %error, ?receipts, "receipts verification failed");
-                return Err(error)
-            };
-            self.stats.receipt_root_duration += time.elapsed();
+        if !self.chain_spec.fork(Hardfork::Byzantium).active_at_block(block.header.number) {
+            return Ok(false)
         }
 
-        self.save_receipts(receipts)
+        let time = Instant::now();
+        if let Err(error) =
+            verify_receipt(block.header.receipts_root, block.header.logs_bloom, receipts.iter())
+        {
+            debug!(target: "evm", %error, ?receipts, "receipts verification failed");
+            return Err(error)
+        };
+        self.stats.receipt_root_duration += time.elapsed();
+        Ok(true)
     }
 
     fn execute_transactions(
@@ -494,6 +503,16 @@ where
     fn set_prune_modes(&mut self, prune_modes: PruneModes) {
         self.prune_modes = prune_modes;
     }
+
+    fn record_call_addresses(&mut self) {
+        self.stack_mut().call_addresses.get_or_insert_with(CallAddresses::default);
+    }
+
+    fn take_call_addresses(&mut self) -> Option<(HashSet<Address>, HashSet<Address>)> {
+        let CallAddresses { from, to } =
+            self.stack_mut().call_addresses.as_mut().map(std::mem::take)?;
+        Some((from, to))
+    }
 }
 
 /// Calculate the receipts root, and copmare it against against the expected receipts root and logs
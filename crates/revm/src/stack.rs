@@ -1,3 +1,4 @@
+use crate::call_addresses::CallAddresses;
 use revm::{
     inspectors::CustomPrintTracer,
     interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
@@ -38,6 +39,9 @@ impl Hook {
 pub struct InspectorStack {
     /// An inspector that prints the opcode traces to the console.
     pub custom_print_tracer: Option<CustomPrintTracer>,
+    /// An inspector that records the addresses of the calls of every transaction, regardless of
+    /// the hook.
+    pub call_addresses: Option<CallAddresses>,
     /// The provided hook
     pub hook: Hook,
 }
@@ -46,6 +50,7 @@ impl Debug for InspectorStack {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InspectorStack")
             .field("custom_print_tracer", &self.custom_print_tracer.is_some())
+            .field("call_addresses", &self.call_addresses.is_some())
             .field("hook", &self.hook)
             .finish()
     }
@@ -58,14 +63,16 @@ impl InspectorStack {
         Self {
             hook: config.hook,
             custom_print_tracer: config.use_printer_tracer.then(Default::default),
+            call_addresses: None,
         }
     }
 
     /// Returns `true` if this inspector should be used.
     #[inline]
     pub fn should_inspect(&self, env: &Env, tx_hash: &B256) -> bool {
-        self.custom_print_tracer.is_some() &&
-            self.hook.is_enabled(env.block.number.saturating_to(), tx_hash)
+        self.call_addresses.is_some() ||
+            (self.custom_print_tracer.is_some() &&
+                self.hook.is_enabled(env.block.number.saturating_to(), tx_hash))
     }
 }
 
@@ -129,7 +136,7 @@ where
         context: &mut EvmContext<DB>,
         inputs: &mut CallInputs,
     ) -> Option<CallOutcome> {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!([&mut self.call_addresses, &mut self.custom_print_tracer], |inspector| {
             if let Some(outcome) = inspector.call(context, inputs) {
                 return Some(outcome)
             }
@@ -164,7 +171,7 @@ where
         context: &mut EvmContext<DB>,
         inputs: &mut CreateInputs,
     ) -> Option<CreateOutcome> {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!([&mut self.call_addresses, &mut self.custom_print_tracer], |inspector| {
             if let Some(out) = inspector.create(context, inputs) {
                 return Some(out)
             }
@@ -180,7 +187,7 @@ where
         inputs: &CreateInputs,
         outcome: CreateOutcome,
     ) -> CreateOutcome {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!([&mut self.call_addresses, &mut self.custom_print_tracer], |inspector| {
             let new_ret = inspector.create_end(context, inputs, outcome.clone());
 
             // If the inspector returns a different ret or a revert with a non-empty message,
@@ -195,7 +202,7 @@ where
 
     #[inline]
     fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
-        call_inspectors!([&mut self.custom_print_tracer], |inspector| {
+        call_inspectors!([&mut self.call_addresses, &mut self.custom_print_tracer], |inspector| {
             Inspector::<DB>::selfdestruct(inspector, contract, target, value);
         });
     }
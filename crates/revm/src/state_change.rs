@@ -1,8 +1,9 @@
+use crate::eth_dao_fork::{DAO_HARDFORK_BENEFICIARY, DAO_HARDKFORK_ACCOUNTS};
 use reth_consensus_common::calc;
 use reth_interfaces::executor::{BlockExecutionError, BlockValidationError};
 use reth_primitives::{
     constants::SYSTEM_ADDRESS, revm::env::fill_tx_env_with_beacon_root_contract_call, Address,
-    ChainSpec, Header, Withdrawal, B256, U256,
+    Block, ChainSpec, Hardfork, Header, Withdrawal, Withdrawals, B256, U256,
 };
 use revm::{interpreter::Host, Database, DatabaseCommit, Evm};
 use std::collections::HashMap;
@@ -51,6 +52,42 @@ pub fn post_block_balance_increments(
     balance_increments
 }
 
+/// Collect all balance changes at the end of the block, including the irregular state change of
+/// the DAO hardfork.
+///
+/// At the DAO hardfork, the balances of the DAO accounts are drained with `drain_balances`, which
+/// returns the drained balances, and added to the balance of the DAO beneficiary. This must be
+/// called after the transactions of the block are executed.
+#[inline]
+pub fn post_block_balance_changes<E>(
+    chain_spec: &ChainSpec,
+    block: &Block,
+    total_difficulty: U256,
+    drain_balances: impl FnOnce(&[Address]) -> Result<Vec<u128>, E>,
+) -> Result<HashMap<Address, u128>, E> {
+    let mut balance_increments = post_block_balance_increments(
+        chain_spec,
+        block.number,
+        block.difficulty,
+        block.beneficiary,
+        block.timestamp,
+        total_difficulty,
+        &block.ommers,
+        block.withdrawals.as_ref().map(Withdrawals::as_ref),
+    );
+
+    // Irregular state change at Ethereum DAO hardfork
+    if chain_spec.fork(Hardfork::Dao).transitions_at_block(block.number) {
+        // drain balances from hardcoded addresses.
+        let drained_balance: u128 = drain_balances(&DAO_HARDKFORK_ACCOUNTS)?.into_iter().sum();
+
+        // return balance to DAO beneficiary.
+        *balance_increments.entry(DAO_HARDFORK_BENEFICIARY).or_default() += drained_balance;
+    }
+
+    Ok(balance_increments)
+}
+
 /// Applies the pre-block call to the EIP-4788 beacon block root contract, using the given block,
 /// [ChainSpec], EVM.
 ///
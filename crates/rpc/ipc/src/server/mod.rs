@@ -21,7 +21,7 @@ use tokio::{
     io::{AsyncRead, AsyncWrite},
     sync::{oneshot, watch, OwnedSemaphorePermit},
 };
-use tower::{layer::util::Identity, Service};
+use tower::{layer::util::Identity, Layer, Service};
 use tracing::{debug, trace, warn};
 
 // re-export so can be used during builder setup
@@ -45,12 +45,20 @@ pub struct IpcServer<B = Identity> {
     service_builder: tower::ServiceBuilder<B>,
 }
 
-impl IpcServer<Identity> {
+impl<B> IpcServer<B> {
     /// Returns the configured [Endpoint]
     pub fn endpoint(&self) -> &Endpoint {
         &self.endpoint
     }
+}
 
+impl<B> IpcServer<B>
+where
+    B: Layer<TowerService> + Send + 'static,
+    B::Service: Service<String, Response = Option<String>> + Send + 'static,
+    <B::Service as Service<String>>::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    <B::Service as Service<String>>::Future: Send + Unpin,
+{
     /// Start responding to connections requests.
     ///
     /// This will run on the tokio runtime until the server is stopped or the ServerHandle is
@@ -193,7 +201,7 @@ impl IpcServer<Identity> {
     }
 }
 
-impl std::fmt::Debug for IpcServer {
+impl<B> std::fmt::Debug for IpcServer<B> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("IpcServer")
             .field("endpoint", &self.endpoint.path())
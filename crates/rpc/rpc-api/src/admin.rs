@@ -1,6 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{AnyNode, NodeRecord};
-use reth_rpc_types::{admin::NodeInfo, PeerInfo};
+use reth_rpc_types::{admin::NodeInfo, ConfigReloadReport, PeerInfo, TrustedPeerInfo};
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
@@ -27,6 +27,11 @@ pub trait AdminApi {
     #[method(name = "removeTrustedPeer")]
     fn remove_trusted_peer(&self, record: AnyNode) -> RpcResult<bool>;
 
+    /// Returns all trusted peers, including the ones that are currently not connected, with the
+    /// state of the connection to them.
+    #[method(name = "trustedPeers")]
+    async fn trusted_peers(&self) -> RpcResult<Vec<TrustedPeerInfo>>;
+
     /// The peers administrative property can be queried for all the information known about the
     /// connected remote nodes at the networking granularity. These include general information
     /// about the nodes themselves as participants of the devp2p P2P overlay protocol, as well as
@@ -34,6 +39,13 @@ pub trait AdminApi {
     #[method(name = "peers")]
     async fn peers(&self) -> RpcResult<Vec<PeerInfo>>;
 
+    /// Returns all peers known to the node, connected or not, as they're persisted across
+    /// restarts, in the format of `admin_peers`.
+    ///
+    /// The name and the capabilities are the ones the peer announced in its last session, if any.
+    #[method(name = "knownPeers")]
+    async fn known_peers(&self) -> RpcResult<Vec<PeerInfo>>;
+
     /// Creates an RPC subscription which serves events received from the network.
     #[subscription(
         name = "peerEvents",
@@ -46,3 +58,15 @@ pub trait AdminApi {
     #[method(name = "nodeInfo")]
     async fn node_info(&self) -> RpcResult<NodeInfo>;
 }
+
+/// Admin namespace rpc interface for reloading the configuration of a running node.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "admin"))]
+pub trait AdminConfigApi {
+    /// Reloads the configuration file and applies the changed settings that can be changed at
+    /// runtime.
+    ///
+    /// Returns whether every changed setting was applied, and why it was not.
+    #[method(name = "reloadConfig")]
+    async fn reload_config(&self) -> RpcResult<ConfigReloadReport>;
+}
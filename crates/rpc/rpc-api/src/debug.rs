@@ -1,11 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256};
 use reth_rpc_types::{
-    trace::geth::{
-        BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
-        TraceResult,
-    },
-    Bundle, RichBlock, StateContext, TransactionRequest,
+    debug::{BadBlock, DebugSubscriptionKind, ExecutionWitness, TraceChainResult},
+    trace::geth::{GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, TraceResult},
+    Bundle, StateContext, TransactionRequest,
 };
 
 /// Debug rpc interface.
@@ -36,16 +34,27 @@ pub trait DebugApi {
 
     /// Returns an array of recent bad blocks that the client has seen on the network.
     #[method(name = "getBadBlocks")]
-    async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>>;
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock>>;
 
-    /// Returns the structured logs created during the execution of EVM between two blocks
-    /// (excluding start) as a JSON object.
-    #[method(name = "traceChain")]
-    async fn debug_trace_chain(
+    /// Creates a subscription of the given kind, which is only `traceChain`, the subscription of
+    /// `debug_traceChain` of geth.
+    ///
+    /// `traceChain` traces the blocks after `start_block` up to and including `end_block`, and
+    /// sends a notification with the traces of the transactions of every block, followed by a
+    /// [TraceChainResult::Completed] notification once all blocks are traced. The subscription is
+    /// only available over WebSocket and IPC.
+    #[subscription(
+        name = "subscribe" => "subscription",
+        unsubscribe = "unsubscribe",
+        item = TraceChainResult
+    )]
+    async fn subscribe(
         &self,
-        start_exclusive: BlockNumberOrTag,
-        end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>>;
+        kind: DebugSubscriptionKind,
+        start_block: BlockNumberOrTag,
+        end_block: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult;
 
     /// The `debug_traceBlock` method will return a full stack trace of all invoked opcodes of all
     /// transaction that were included in this block.
@@ -120,17 +129,28 @@ pub trait DebugApi {
     /// replayed.
     /// The trace can be configured similar to `debug_traceTransaction`.
     /// State override apply to all bundles.
+    /// The optional last argument selects the tracer of individual transactions, by bundle and
+    /// transaction index, the transactions without one are traced with the tracer of the options.
     ///
     /// This methods is similar to many `eth_callMany`, hence this returns nested lists of traces.
     /// Where the length of the outer list is the number of bundles and the length of the inner list
-    /// (`Vec<GethTrace>`) is the number of transactions in the bundle.
+    /// (`Vec<TraceResult>`) is the number of transactions in the bundle.
+    /// If a transaction fails, the lists end with the error of that transaction.
     #[method(name = "traceCallMany")]
     async fn debug_trace_call_many(
         &self,
         bundles: Vec<Bundle>,
         state_context: Option<StateContext>,
         opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<Vec<Vec<GethTrace>>>;
+        call_opts: Option<Vec<Vec<Option<GethDebugTracingOptions>>>>,
+    ) -> RpcResult<Vec<Vec<TraceResult>>>;
+
+    /// Re-executes the given block on top of the state of its parent block and returns the
+    /// [ExecutionWitness] of the block: the accessed accounts, storage slots and bytecodes together
+    /// with the trie nodes proving them against the state root of the parent block.
+    #[method(name = "executionWitness")]
+    async fn debug_execution_witness(&self, block: BlockNumberOrTag)
+        -> RpcResult<ExecutionWitness>;
 
     /// Sets the logging backtrace location. When a backtrace location is set and a log message is
     /// emitted at that location, the stack of the goroutine executing the log statement will
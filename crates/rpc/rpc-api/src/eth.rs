@@ -4,7 +4,9 @@ use reth_primitives::{
     Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64,
 };
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
+    simulate::{SimulatePayload, SimulatedBlock, SimulatedCall},
+    state::StateOverride,
+    AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
     EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index, RichBlock,
     StateContext, SyncStatus, Transaction, TransactionRequest, Work,
 };
@@ -194,6 +196,30 @@ pub trait EthApi {
         state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<EthCallResponse>>;
 
+    /// Simulates a bundle of calls like `eth_callMany`, optionally after the first
+    /// `transactionIndex` transactions of the block, and returns the gas used, the return data
+    /// and the revert reason of every call.
+    ///
+    /// The gas of all calls of the bundle is limited by the configured gas cap.
+    #[method(name = "simulateBundle")]
+    async fn simulate_bundle(
+        &self,
+        bundle: Bundle,
+        state_context: Option<StateContext>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Vec<SimulatedCall>>;
+
+    /// Simulates blocks of calls on top of the given block, with optional block and state
+    /// overrides for every block.
+    ///
+    /// See also: <https://github.com/ethereum/execution-apis/pull/484>
+    #[method(name = "simulateV1")]
+    async fn simulate_v1(
+        &self,
+        opts: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<SimulatedBlock>>;
+
     /// Generates an access list for a transaction.
     ///
     /// This method creates an [EIP2930](https://eips.ethereum.org/EIPS/eip-2930) type accessList based on a given Transaction.
@@ -0,0 +1,22 @@
+//! Additional `eth_` functions for transactions with validity conditions.
+
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::{Bytes, B256};
+use reth_rpc_types::TransactionConditional;
+
+/// Eth rpc interface for transactions that may only be included in blocks satisfying the given
+/// conditions.
+///
+/// See also <https://notes.ethereum.org/@yoav/SkaX2lS9j>
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "eth"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "eth"))]
+pub trait EthConditionalApi {
+    /// Sends a signed transaction that may only be included in a block satisfying the given
+    /// conditions, returning its hash.
+    #[method(name = "sendRawTransactionConditional")]
+    async fn send_raw_transaction_conditional(
+        &self,
+        bytes: Bytes,
+        conditional: TransactionConditional,
+    ) -> RpcResult<B256>;
+}
@@ -1,10 +1,19 @@
 use jsonrpsee::proc_macros::rpc;
-use reth_rpc_types::pubsub::{Params, SubscriptionKind};
+use reth_rpc_types::{
+    pubsub::Params,
+    subscription::{AnySubscriptionKind, SubscriptionOptions},
+};
 
 /// Ethereum pub-sub rpc interface.
 #[rpc(server, namespace = "eth")]
 pub trait EthPubSubApi {
     /// Create an ethereum subscription for the given params
+    ///
+    /// Besides the standard subscriptions, this supports the reth specific
+    /// [`RethSubscriptionKind`](reth_rpc_types::subscription::RethSubscriptionKind)s.
+    ///
+    /// `newHeads` and `logs` subscriptions can be made resumable with the reth specific
+    /// [`SubscriptionOptions`].
     #[subscription(
         name = "subscribe" => "subscription",
         unsubscribe = "unsubscribe",
@@ -12,7 +21,22 @@ pub trait EthPubSubApi {
     )]
     async fn subscribe(
         &self,
-        kind: SubscriptionKind,
+        kind: AnySubscriptionKind,
         params: Option<Params>,
+        options: Option<SubscriptionOptions>,
     ) -> jsonrpsee::core::SubscriptionResult;
+
+    /// Resumes a resumable subscription whose connection was lost.
+    ///
+    /// The token is the id of the lost subscription. The notifications that were missed are sent
+    /// before the new ones, the resumed subscription is resumable again with its new id.
+    ///
+    /// Fails if the token is unknown or expired, or if more notifications were missed than could
+    /// be buffered.
+    #[subscription(
+        name = "resubscribe" => "subscription",
+        unsubscribe = "unresubscribe",
+        item = reth_rpc_types::pubsub::SubscriptionResult
+    )]
+    async fn resubscribe(&self, token: String) -> jsonrpsee::core::SubscriptionResult;
 }
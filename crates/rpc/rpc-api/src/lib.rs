@@ -19,9 +19,11 @@ mod bundle;
 mod debug;
 mod engine;
 mod eth;
+mod eth_conditional;
 mod eth_filter;
 mod eth_pubsub;
 mod mev;
+mod miner;
 mod net;
 mod otterscan;
 mod reth;
@@ -37,14 +39,16 @@ pub use servers::*;
 /// Aggregates all server traits.
 pub mod servers {
     pub use crate::{
-        admin::AdminApiServer,
+        admin::{AdminApiServer, AdminConfigApiServer},
         bundle::{EthBundleApiServer, EthCallBundleApiServer},
         debug::DebugApiServer,
         engine::{EngineApiServer, EngineEthApiServer},
         eth::EthApiServer,
+        eth_conditional::EthConditionalApiServer,
         eth_filter::EthFilterApiServer,
         eth_pubsub::EthPubSubApiServer,
         mev::MevApiServer,
+        miner::MinerApiServer,
         net::NetApiServer,
         otterscan::OtterscanServer,
         reth::RethApiServer,
@@ -64,13 +68,15 @@ pub use clients::*;
 #[cfg(feature = "client")]
 pub mod clients {
     pub use crate::{
-        admin::AdminApiClient,
+        admin::{AdminApiClient, AdminConfigApiClient},
         bundle::{EthBundleApiClient, EthCallBundleApiClient},
         debug::DebugApiClient,
         engine::{EngineApiClient, EngineEthApiClient},
         eth::EthApiClient,
+        eth_conditional::EthConditionalApiClient,
         eth_filter::EthFilterApiClient,
         mev::MevApiClient,
+        miner::MinerApiClient,
         net::NetApiClient,
         otterscan::OtterscanClient,
         rpc::RpcApiServer,
@@ -0,0 +1,17 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_primitives::U128;
+
+/// Miner rpc interface.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "miner"))]
+#[cfg_attr(feature = "client", rpc(server, client, namespace = "miner"))]
+pub trait MinerApi {
+    /// Sets the minimum priority fee per gas of the transactions admitted to the pool, overriding
+    /// the configured and the dynamic priority fee floors.
+    #[method(name = "setGasPrice")]
+    fn set_gas_price(&self, gas_price: U128) -> RpcResult<bool>;
+
+    /// Clears the priority fee floor set with `miner_setGasPrice`, restoring the configured and
+    /// the dynamic priority fee floors.
+    #[method(name = "clearGasPrice")]
+    fn clear_gas_price(&self) -> RpcResult<bool>;
+}
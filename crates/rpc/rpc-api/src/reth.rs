@@ -1,5 +1,8 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, U256};
+use reth_primitives::{
+    stage::StageProgress, Address, BlockId, NodeStatus, PruneSegmentStatus, U256,
+};
+use reth_rpc_types::PeerStats;
 use std::collections::HashMap;
 
 /// Reth API namespace for reth-specific methods
@@ -12,4 +15,28 @@ pub trait RethApi {
         &self,
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
+
+    /// Returns the latest progress of every stage of the sync, including the pruner and the static
+    /// file producer.
+    #[method(name = "syncStatus")]
+    async fn reth_sync_status(&self) -> RpcResult<Vec<StageProgress>>;
+
+    /// Returns the pruning status of every segment, including the lowest block its data is still
+    /// available for.
+    #[method(name = "getPruneStatus")]
+    async fn reth_get_prune_status(&self) -> RpcResult<Vec<PruneSegmentStatus>>;
+
+    /// Returns the status of the node: the checkpoints of the stages as committed to the database,
+    /// the highest block of every static file segment, the pruning status, the size of the
+    /// database, the number of inbound and outbound peers, and the fork identifier and the
+    /// hardforks activated at the head.
+    #[method(name = "nodeStatus")]
+    async fn reth_node_status(&self) -> RpcResult<NodeStatus>;
+
+    /// Returns the bytes and messages exchanged with every connected peer, by message type, and
+    /// how many of their requests were served and of our requests timed out.
+    ///
+    /// If `reset` is true, the counters are reset after they're returned.
+    #[method(name = "peerStats")]
+    async fn reth_peer_stats(&self, reset: Option<bool>) -> RpcResult<Vec<PeerStats>>;
 }
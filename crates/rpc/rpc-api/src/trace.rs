@@ -29,11 +29,16 @@ pub trait TraceApi {
     /// Performs multiple call traces on top of the same block. i.e. transaction n will be executed
     /// on top of a pending block with all n-1 transactions applied (traced) first. Allows to trace
     /// dependent transactions.
+    ///
+    /// The state overrides are applied before the first call. If a call fails, the error data
+    /// contains the index of the failed call and the traces of the calls before it.
     #[method(name = "callMany")]
     async fn trace_call_many(
         &self,
         calls: Vec<(TransactionRequest, HashSet<TraceType>)>,
         block_id: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
     ) -> RpcResult<Vec<TraceResults>>;
 
     /// Traces a call to `eth_sendRawTransaction` without making the call, returning the traces.
@@ -1,6 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::Address;
-use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus};
+use reth_rpc_types::{
+    txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus},
+    TxpoolPage, TxpoolPriorityFeeFloor, TxpoolQuery,
+};
 
 /// Txpool rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpool"))]
@@ -16,6 +19,9 @@ pub trait TxPoolApi {
     /// Returns a summary of all the transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     ///
+    /// Fails if the txpool has more transactions than the server returns at once, see
+    /// `txpool_inspectPage`.
+    ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_inspect) for more details
     #[method(name = "inspect")]
     async fn txpool_inspect(&self) -> RpcResult<TxpoolInspect>;
@@ -30,7 +36,31 @@ pub trait TxPoolApi {
     /// Returns the details of all transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     ///
+    /// Fails if the txpool has more transactions than the server returns at once, see
+    /// `txpool_contentPage`.
+    ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     #[method(name = "content")]
     async fn txpool_content(&self) -> RpcResult<TxpoolContent>;
+
+    /// Returns a page of the details of the transactions currently in the txpool that match the
+    /// filters of the query, ordered by sender and nonce.
+    ///
+    /// Transactions of all sub-pools other than the pending one are returned as queued.
+    #[method(name = "contentPage")]
+    async fn txpool_content_page(&self, query: TxpoolQuery)
+        -> RpcResult<TxpoolPage<TxpoolContent>>;
+
+    /// Returns a page of the summaries of the transactions currently in the txpool that match the
+    /// filters of the query, ordered by sender and nonce.
+    ///
+    /// Transactions of all sub-pools other than the pending one are returned as queued.
+    #[method(name = "inspectPage")]
+    async fn txpool_inspect_page(&self, query: TxpoolQuery)
+        -> RpcResult<TxpoolPage<TxpoolInspect>>;
+
+    /// Returns the minimum effective tip a new transaction must pay at the base fee of the pending
+    /// block to be accepted into the txpool, and the floors it's derived from.
+    #[method(name = "priorityFeeFloor")]
+    async fn txpool_priority_fee_floor(&self) -> RpcResult<TxpoolPriorityFeeFloor>;
 }
@@ -19,8 +19,8 @@ pub use reth_ipc::server::{Builder as IpcServerBuilder, Endpoint};
 use reth_network_api::{NetworkInfo, Peers};
 use reth_node_api::{ConfigureEvm, EngineTypes};
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, ReceiptProviderIdExt,
-    StateProviderFactory,
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
+    ReceiptProviderIdExt, StateProviderFactory,
 };
 use reth_rpc::{
     eth::{
@@ -57,6 +57,7 @@ where
         + ChainSpecProvider
         + EvmEnvProvider
         + HeaderProvider
+        + PruneCheckpointReader
         + ReceiptProviderIdExt
         + StateProviderFactory
         + Clone
@@ -88,6 +89,7 @@ where
         eth_cache.clone(),
         gas_oracle,
         EthConfig::default().rpc_gas_cap,
+        EthConfig::default().eth_proof_window,
         Box::new(executor.clone()),
         BlockingTaskPool::build().expect("failed to build tracing pool"),
         fee_history_cache,
@@ -115,6 +117,7 @@ where
         + ChainSpecProvider
         + EvmEnvProvider
         + HeaderProvider
+        + PruneCheckpointReader
         + StateProviderFactory
         + Clone
         + Unpin
@@ -396,7 +399,12 @@ impl AuthServerHandle {
     }
 
     /// Tell the server to stop without waiting for the server to stop.
+    ///
+    /// This also stops the IPC server, if started.
     pub fn stop(self) -> Result<(), AlreadyStoppedError> {
+        if let Some(handle) = self.ipc_handle {
+            handle.stop()?
+        }
         self.handle.stop()
     }
 
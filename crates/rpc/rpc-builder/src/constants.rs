@@ -3,6 +3,9 @@ pub use reth_rpc::eth::gas_oracle::{
     DEFAULT_GAS_PRICE_BLOCKS, DEFAULT_GAS_PRICE_PERCENTILE, DEFAULT_IGNORE_GAS_PRICE,
     DEFAULT_MAX_GAS_PRICE,
 };
+pub use reth_rpc::{
+    DEFAULT_MAX_TRACE_CHAIN_BLOCKS, DEFAULT_MAX_TRACE_FILTER_BLOCKS, DEFAULT_MAX_TXPOOL_ENTRIES,
+};
 use std::cmp::max;
 
 /// The default port for the http server
@@ -1,11 +1,14 @@
 use crate::constants::{
     default_max_tracing_requests, DEFAULT_MAX_BLOCKS_PER_FILTER, DEFAULT_MAX_LOGS_PER_RESPONSE,
+    DEFAULT_MAX_TRACE_CHAIN_BLOCKS, DEFAULT_MAX_TRACE_FILTER_BLOCKS, DEFAULT_MAX_TXPOOL_ENTRIES,
 };
 use reth_rpc::{
     eth::{
         cache::{EthStateCache, EthStateCacheConfig},
         gas_oracle::GasPriceOracleConfig,
-        EthFilterConfig, FeeHistoryCacheConfig, RPC_DEFAULT_GAS_CAP,
+        EthFilterConfig, FeeHistoryCacheConfig, ResumableSubscriptionsConfig,
+        DEFAULT_ETH_PROOF_WINDOW, DEFAULT_MAX_DORMANT_SUBSCRIPTIONS,
+        DEFAULT_RESUMABLE_BUFFER_DEPTH, DEFAULT_RESUMABLE_EXPIRY, RPC_DEFAULT_GAS_CAP,
     },
     EthApi, EthFilter, EthPubSub,
 };
@@ -49,6 +52,26 @@ pub struct EthConfig {
     pub stale_filter_ttl: std::time::Duration,
     /// Settings for the fee history cache
     pub fee_history_cache: FeeHistoryCacheConfig,
+    /// Whether to serve `eth_sendRawTransactionConditional`.
+    pub conditional_transactions: bool,
+    /// Maximum number of transactions that can be returned in a single response in `txpool`
+    /// calls.
+    pub txpool_max_entries: usize,
+    /// Maximum number of blocks that can be traced by a single `trace_filter` request.
+    pub max_trace_filter_blocks: u64,
+    /// Maximum number of blocks that can be traced by a single `traceChain` subscription.
+    pub max_trace_chain_blocks: u64,
+    /// Maximum number of blocks below the tip for which `eth_getProof` is served.
+    ///
+    /// Defaults to [DEFAULT_ETH_PROOF_WINDOW]
+    pub eth_proof_window: u64,
+    /// Maximum number of notifications that are buffered for a resumable subscription whose
+    /// connection was lost.
+    pub resumable_buffer_depth: usize,
+    /// How long a resumable subscription can be resumed after its connection was lost.
+    pub resumable_expiry: std::time::Duration,
+    /// Maximum number of resumable subscriptions whose connection was lost.
+    pub max_dormant_subscriptions: usize,
 }
 
 impl EthConfig {
@@ -59,6 +82,14 @@ impl EthConfig {
             .max_logs_per_response(self.max_logs_per_response)
             .stale_filter_ttl(self.stale_filter_ttl)
     }
+
+    /// Returns the config of resumable subscriptions for the `eth_pubsub` handler.
+    pub fn resumable_subscriptions_config(&self) -> ResumableSubscriptionsConfig {
+        ResumableSubscriptionsConfig::default()
+            .buffer_depth(self.resumable_buffer_depth)
+            .expiry(self.resumable_expiry)
+            .max_dormant(self.max_dormant_subscriptions)
+    }
 }
 
 /// Default value for stale filter ttl
@@ -75,6 +106,14 @@ impl Default for EthConfig {
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
             fee_history_cache: FeeHistoryCacheConfig::default(),
+            conditional_transactions: false,
+            txpool_max_entries: DEFAULT_MAX_TXPOOL_ENTRIES,
+            max_trace_filter_blocks: DEFAULT_MAX_TRACE_FILTER_BLOCKS,
+            max_trace_chain_blocks: DEFAULT_MAX_TRACE_CHAIN_BLOCKS,
+            eth_proof_window: DEFAULT_ETH_PROOF_WINDOW,
+            resumable_buffer_depth: DEFAULT_RESUMABLE_BUFFER_DEPTH,
+            resumable_expiry: DEFAULT_RESUMABLE_EXPIRY,
+            max_dormant_subscriptions: DEFAULT_MAX_DORMANT_SUBSCRIPTIONS,
         }
     }
 }
@@ -115,4 +154,52 @@ impl EthConfig {
         self.rpc_gas_cap = rpc_gas_cap;
         self
     }
+
+    /// Configures whether to serve `eth_sendRawTransactionConditional`
+    pub fn conditional_transactions(mut self, enabled: bool) -> Self {
+        self.conditional_transactions = enabled;
+        self
+    }
+
+    /// Configures the maximum number of transactions per `txpool` response
+    pub fn txpool_max_entries(mut self, max_entries: usize) -> Self {
+        self.txpool_max_entries = max_entries;
+        self
+    }
+
+    /// Configures the maximum number of blocks traced per `trace_filter` request
+    pub fn max_trace_filter_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_trace_filter_blocks = max_blocks;
+        self
+    }
+
+    /// Configures the maximum number of blocks traced per `traceChain` subscription
+    pub fn max_trace_chain_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_trace_chain_blocks = max_blocks;
+        self
+    }
+
+    /// Configures the maximum number of blocks below the tip for which `eth_getProof` is served
+    pub fn eth_proof_window(mut self, window: u64) -> Self {
+        self.eth_proof_window = window;
+        self
+    }
+
+    /// Configures the maximum number of buffered notifications per dormant resumable subscription
+    pub fn resumable_buffer_depth(mut self, depth: usize) -> Self {
+        self.resumable_buffer_depth = depth;
+        self
+    }
+
+    /// Configures how long a resumable subscription can be resumed after its connection was lost
+    pub fn resumable_expiry(mut self, expiry: std::time::Duration) -> Self {
+        self.resumable_expiry = expiry;
+        self
+    }
+
+    /// Configures the maximum number of dormant resumable subscriptions
+    pub fn max_dormant_subscriptions(mut self, max_dormant: usize) -> Self {
+        self.max_dormant_subscriptions = max_dormant;
+        self
+    }
 }
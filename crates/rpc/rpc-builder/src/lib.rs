@@ -20,8 +20,10 @@
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_node_api::ConfigureEvm;
 //! use reth_provider::{
-//!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     AccountReader, BlockReaderIdExt, CallTraceIndexReader, CanonStateSubscriptions,
+//!     ChainSpecProvider, ChangeSetReader, DatabaseMetadataReader, EvmEnvProvider,
+//!     PruneCheckpointReader, StageCheckpointReader, StateProviderFactory,
+//!     StaticFileProviderFactory,
 //! };
 //! use reth_rpc_builder::{
 //!     RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
@@ -41,6 +43,11 @@
 //!         + ChangeSetReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
+//!         + PruneCheckpointReader
+//!         + CallTraceIndexReader
+//!         + StaticFileProviderFactory
+//!         + StageCheckpointReader
+//!         + DatabaseMetadataReader
 //!         + Clone
 //!         + Unpin
 //!         + 'static,
@@ -80,8 +87,10 @@
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_node_api::{ConfigureEvm, EngineTypes};
 //! use reth_provider::{
-//!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     AccountReader, BlockReaderIdExt, CallTraceIndexReader, CanonStateSubscriptions,
+//!     ChainSpecProvider, ChangeSetReader, DatabaseMetadataReader, EvmEnvProvider,
+//!     PruneCheckpointReader, StageCheckpointReader, StateProviderFactory,
+//!     StaticFileProviderFactory,
 //! };
 //! use reth_rpc::JwtSecret;
 //! use reth_rpc_api::EngineApiServer;
@@ -106,6 +115,11 @@
 //!         + ChangeSetReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
+//!         + PruneCheckpointReader
+//!         + CallTraceIndexReader
+//!         + StaticFileProviderFactory
+//!         + StageCheckpointReader
+//!         + DatabaseMetadataReader
 //!         + Clone
 //!         + Unpin
 //!         + 'static,
@@ -155,7 +169,13 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use crate::{
-    auth::AuthRpcModule, error::WsHttpSamePortError, metrics::RpcRequestMetrics,
+    auth::AuthRpcModule,
+    error::WsHttpSamePortError,
+    metrics::RpcRequestMetrics,
+    rate_limit::{ApiKeyLayer, IpcRateLimitLayer, RateLimitLayer, RpcRateLimits},
+    response_cache::{IpcResponseCacheLayer, ResponseCache, ResponseCacheLayer},
+    trace_context::{RpcTracingLayer, TraceContextLayer},
+    unsubscribe::{IpcUnsubscribeLayer, UnsubscribeLayer},
     RpcModuleSelection::Selection,
 };
 use constants::*;
@@ -167,13 +187,17 @@ use jsonrpsee::{
     server::{AlreadyStoppedError, IdProvider, RpcServiceBuilder, Server, ServerHandle},
     Methods, RpcModule,
 };
+use reth_config::config::MethodRateLimit;
+use reth_interfaces::{invalid_block::InvalidBlocksHandle, sync::SyncStatusHandle};
 use reth_ipc::server::IpcServer;
 pub use reth_ipc::server::{Builder as IpcServerBuilder, Endpoint};
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_node_api::{ConfigureEvm, EngineTypes};
+use reth_primitives::PruneModes;
 use reth_provider::{
-    AccountReader, BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-    ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+    AccountReader, BlockReader, BlockReaderIdExt, CallTraceIndexReader, CanonStateSubscriptions,
+    ChainSpecProvider, ChangeSetReader, DatabaseMetadataReader, EvmEnvProvider,
+    PruneCheckpointReader, StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory,
 };
 use reth_rpc::{
     eth::{
@@ -181,11 +205,11 @@ use reth_rpc::{
         fee_history_cache_new_blocks_task,
         gas_oracle::GasPriceOracle,
         traits::RawTransactionForwarder,
-        EthBundle, FeeHistoryCache,
+        EthBundle, FeeHistoryCache, ResumableUnsubscribes,
     },
     AdminApi, AuthLayer, Claims, DebugApi, EngineEthApi, EthApi, EthFilter, EthPubSub,
-    EthSubscriptionIdProvider, JwtAuthValidator, JwtSecret, NetApi, OtterscanApi, RPCApi, RethApi,
-    TraceApi, TxPoolApi, Web3Api,
+    EthSubscriptionIdProvider, JwtAuthValidator, JwtSecret, MinerApi, NetApi, OtterscanApi, RPCApi,
+    RethApi, TraceApi, TxPoolApi, Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_tasks::{
@@ -193,19 +217,19 @@ use reth_tasks::{
     TaskSpawner, TokioTaskExecutor,
 };
 use reth_transaction_pool::{noop::NoopTransactionPool, TransactionPool};
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt,
     net::{Ipv4Addr, SocketAddr, SocketAddrV4},
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, Mutex, PoisonError},
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use strum::{AsRefStr, EnumIter, IntoStaticStr, ParseError, VariantArray, VariantNames};
+use strum::ParseError;
 pub use tower::layer::util::{Identity, Stack};
 use tower_http::cors::CorsLayer;
-use tracing::{instrument, trace};
+use tracing::{instrument, trace, warn};
 
 // re-export for convenience
 pub use crate::eth::{EthConfig, EthHandlers};
@@ -228,6 +252,18 @@ pub mod constants;
 // Rpc server metrics
 mod metrics;
 
+/// Rate limiting of RPC methods.
+pub mod rate_limit;
+
+/// Caching of RPC responses for immutable data.
+pub mod response_cache;
+
+/// Propagation of W3C trace context to the spans of RPC calls.
+pub mod trace_context;
+
+/// Reporting of `eth_unsubscribe` calls to the resumable subscriptions.
+mod unsubscribe;
+
 /// Convenience function for starting a server in one step.
 #[allow(clippy::too_many_arguments)]
 pub async fn launch<Provider, Pool, Network, Tasks, Events, EvmConfig>(
@@ -247,6 +283,11 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + PruneCheckpointReader
+        + CallTraceIndexReader
+        + StaticFileProviderFactory
+        + StageCheckpointReader
+        + DatabaseMetadataReader
         + Clone
         + Unpin
         + 'static,
@@ -281,6 +322,12 @@ pub struct RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig> {
     events: Events,
     /// Defines how the EVM should be configured before execution.
     evm_config: EvmConfig,
+    /// The latest progress of the stages of the sync, served by the `reth` namespace.
+    sync_status: SyncStatusHandle,
+    /// The invalid blocks known to the engine, served by `debug_getBadBlocks`.
+    invalid_blocks: InvalidBlocksHandle,
+    /// The configured prune modes of the node, served by the `reth` namespace.
+    prune_modes: PruneModes,
 }
 
 // === impl RpcBuilder ===
@@ -297,7 +344,17 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
         events: Events,
         evm_config: EvmConfig,
     ) -> Self {
-        Self { provider, pool, network, executor, events, evm_config }
+        Self {
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            sync_status: SyncStatusHandle::default(),
+            invalid_blocks: InvalidBlocksHandle::default(),
+            prune_modes: PruneModes::none(),
+        }
     }
 
     /// Configure the provider instance.
@@ -308,8 +365,28 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     where
         P: BlockReader + StateProviderFactory + EvmEnvProvider + 'static,
     {
-        let Self { pool, network, executor, events, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+        }
     }
 
     /// Configure the transaction pool instance.
@@ -320,8 +397,28 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     where
         P: TransactionPool + 'static,
     {
-        let Self { provider, network, executor, events, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            provider,
+            network,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+        }
     }
 
     /// Configure a [NoopTransactionPool] instance.
@@ -332,7 +429,17 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     pub fn with_noop_pool(
         self,
     ) -> RpcModuleBuilder<Provider, NoopTransactionPool, Network, Tasks, Events, EvmConfig> {
-        let Self { provider, executor, events, network, evm_config, .. } = self;
+        let Self {
+            provider,
+            executor,
+            events,
+            network,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+            ..
+        } = self;
         RpcModuleBuilder {
             provider,
             executor,
@@ -340,6 +447,9 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
             network,
             evm_config,
             pool: NoopTransactionPool::default(),
+            sync_status,
+            invalid_blocks,
+            prune_modes,
         }
     }
 
@@ -351,8 +461,28 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     where
         N: NetworkInfo + Peers + 'static,
     {
-        let Self { provider, pool, executor, events, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            provider,
+            pool,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+        }
     }
 
     /// Configure a [NoopNetwork] instance.
@@ -363,7 +493,17 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     pub fn with_noop_network(
         self,
     ) -> RpcModuleBuilder<Provider, Pool, NoopNetwork, Tasks, Events, EvmConfig> {
-        let Self { provider, pool, executor, events, evm_config, .. } = self;
+        let Self {
+            provider,
+            pool,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+            ..
+        } = self;
         RpcModuleBuilder {
             provider,
             pool,
@@ -371,6 +511,9 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
             events,
             network: NoopNetwork::default(),
             evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
         }
     }
 
@@ -382,8 +525,28 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     where
         T: TaskSpawner + 'static,
     {
-        let Self { pool, network, provider, events, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            pool,
+            network,
+            provider,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+        }
     }
 
     /// Configure [TokioTaskExecutor] as the task executor to use for additional tasks.
@@ -393,7 +556,17 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     pub fn with_tokio_executor(
         self,
     ) -> RpcModuleBuilder<Provider, Pool, Network, TokioTaskExecutor, Events, EvmConfig> {
-        let Self { pool, network, provider, events, evm_config, .. } = self;
+        let Self {
+            pool,
+            network,
+            provider,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+            ..
+        } = self;
         RpcModuleBuilder {
             provider,
             network,
@@ -401,6 +574,9 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
             events,
             executor: TokioTaskExecutor::default(),
             evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
         }
     }
 
@@ -412,8 +588,28 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     where
         E: CanonStateSubscriptions + 'static,
     {
-        let Self { provider, pool, executor, network, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            provider,
+            pool,
+            executor,
+            network,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+        }
     }
 
     /// Configure the evm configuration type
@@ -424,8 +620,47 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     where
         E: ConfigureEvm + 'static,
     {
-        let Self { provider, pool, executor, network, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            provider,
+            pool,
+            executor,
+            network,
+            events,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+        }
+    }
+
+    /// Configure the handle to the progress of the sync, served by `reth_syncStatus`.
+    pub fn with_sync_status(mut self, sync_status: SyncStatusHandle) -> Self {
+        self.sync_status = sync_status;
+        self
+    }
+
+    /// Configure the handle to the invalid blocks known to the engine, served by
+    /// `debug_getBadBlocks`.
+    pub fn with_invalid_blocks(mut self, invalid_blocks: InvalidBlocksHandle) -> Self {
+        self.invalid_blocks = invalid_blocks;
+        self
+    }
+
+    /// Configure the prune modes of the node, served by `reth_getPruneStatus`.
+    pub fn with_prune_modes(mut self, prune_modes: PruneModes) -> Self {
+        self.prune_modes = prune_modes;
+        self
     }
 }
 
@@ -438,6 +673,11 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + PruneCheckpointReader
+        + CallTraceIndexReader
+        + StaticFileProviderFactory
+        + StageCheckpointReader
+        + DatabaseMetadataReader
         + Clone
         + Unpin
         + 'static,
@@ -467,7 +707,17 @@ where
     {
         let mut modules = TransportRpcModules::default();
 
-        let Self { provider, pool, network, executor, events, evm_config } = self;
+        let Self {
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+        } = self;
 
         let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
 
@@ -480,10 +730,13 @@ where
             config.unwrap_or_default(),
             evm_config,
         );
+        registry.set_sync_status(sync_status);
+        registry.set_invalid_blocks(invalid_blocks);
+        registry.set_prune_modes(prune_modes);
 
         modules.config = module_config;
-        modules.http = registry.maybe_module(http.as_ref());
-        modules.ws = registry.maybe_module(ws.as_ref());
+        modules.http = registry.maybe_public_module("http", http.as_ref());
+        modules.ws = registry.maybe_public_module("ws", ws.as_ref());
         modules.ipc = registry.maybe_module(ipc.as_ref());
 
         let auth_module = registry.create_auth_module(engine);
@@ -522,8 +775,23 @@ where
         self,
         config: RpcModuleConfig,
     ) -> RethModuleRegistry<Provider, Pool, Network, Tasks, Events, EvmConfig> {
-        let Self { provider, pool, network, executor, events, evm_config } = self;
-        RethModuleRegistry::new(provider, pool, network, executor, events, config, evm_config)
+        let Self {
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+        } = self;
+        let mut registry =
+            RethModuleRegistry::new(provider, pool, network, executor, events, config, evm_config);
+        registry.set_sync_status(sync_status);
+        registry.set_invalid_blocks(invalid_blocks);
+        registry.set_prune_modes(prune_modes);
+        registry
     }
 
     /// Configures all [RpcModule]s specific to the given [TransportRpcModuleConfig] which can be
@@ -533,7 +801,17 @@ where
     pub fn build(self, module_config: TransportRpcModuleConfig) -> TransportRpcModules<()> {
         let mut modules = TransportRpcModules::default();
 
-        let Self { provider, pool, network, executor, events, evm_config } = self;
+        let Self {
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            sync_status,
+            invalid_blocks,
+            prune_modes,
+        } = self;
 
         if !module_config.is_empty() {
             let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
@@ -547,10 +825,13 @@ where
                 config.unwrap_or_default(),
                 evm_config,
             );
+            registry.set_sync_status(sync_status);
+            registry.set_invalid_blocks(invalid_blocks);
+            registry.set_prune_modes(prune_modes);
 
             modules.config = module_config;
-            modules.http = registry.maybe_module(http.as_ref());
-            modules.ws = registry.maybe_module(ws.as_ref());
+            modules.http = registry.maybe_public_module("http", http.as_ref());
+            modules.ws = registry.maybe_public_module("ws", ws.as_ref());
             modules.ipc = registry.maybe_module(ipc.as_ref());
         }
 
@@ -711,6 +992,18 @@ impl RpcModuleSelection {
         }
     }
 
+    /// Returns true if the given module is selected.
+    ///
+    /// [RpcModuleSelection::All] contains every module, including custom
+    /// [RethRpcModule::Other] modules.
+    pub fn contains(&self, module: &RethRpcModule) -> bool {
+        match self {
+            RpcModuleSelection::All => true,
+            RpcModuleSelection::Standard => Self::STANDARD_MODULES.contains(module),
+            RpcModuleSelection::Selection(s) => s.contains(module),
+        }
+    }
+
     /// Creates a new [RpcModule] based on the configured reth modules.
     ///
     /// Note: This will always create new instance of the module handlers and is therefor only
@@ -734,6 +1027,11 @@ impl RpcModuleSelection {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + PruneCheckpointReader
+            + CallTraceIndexReader
+            + StaticFileProviderFactory
+            + StageCheckpointReader
+            + DatabaseMetadataReader
             + Clone
             + Unpin
             + 'static,
@@ -820,22 +1118,7 @@ impl fmt::Display for RpcModuleSelection {
 }
 
 /// Represents RPC modules that are supported by reth
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    Eq,
-    PartialEq,
-    Hash,
-    AsRefStr,
-    IntoStaticStr,
-    VariantNames,
-    VariantArray,
-    EnumIter,
-    Deserialize,
-)]
-#[serde(rename_all = "snake_case")]
-#[strum(serialize_all = "kebab-case")]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum RethRpcModule {
     /// `admin_` module
     Admin,
@@ -849,6 +1132,11 @@ pub enum RethRpcModule {
     Trace,
     /// `txpool_` module
     Txpool,
+    /// `miner_` module
+    ///
+    /// This module changes the settings of the node, so it's only served over IPC, see
+    /// [RethRpcModule::is_ipc_only].
+    Miner,
     /// `web3_` module
     Web3,
     /// `rpc_` module
@@ -862,31 +1150,97 @@ pub enum RethRpcModule {
     /// This is separate from [RethRpcModule::Eth] because it is a non standardized call that
     /// should be opt-in.
     EthCallBundle,
+    /// A custom namespace, e.g. `myapp_`, whose methods are installed by the node.
+    ///
+    /// Custom modules have no methods on their own, the methods are merged into the transports
+    /// that select the module with [TransportRpcModules::merge_if_module_configured].
+    Other(&'static str),
 }
 
 // === impl RethRpcModule ===
 
 impl RethRpcModule {
-    /// Returns all variant names of the enum
+    /// All modules that are provided by reth.
+    const VARIANTS: &'static [Self] = &[
+        RethRpcModule::Admin,
+        RethRpcModule::Debug,
+        RethRpcModule::Eth,
+        RethRpcModule::Net,
+        RethRpcModule::Trace,
+        RethRpcModule::Txpool,
+        RethRpcModule::Miner,
+        RethRpcModule::Web3,
+        RethRpcModule::Rpc,
+        RethRpcModule::Reth,
+        RethRpcModule::Ots,
+        RethRpcModule::EthCallBundle,
+    ];
+
+    /// Returns the names of all modules that are provided by reth.
     pub const fn all_variant_names() -> &'static [&'static str] {
-        <Self as VariantNames>::VARIANTS
-    }
-
-    /// Returns all variants of the enum
+        &[
+            "admin",
+            "debug",
+            "eth",
+            "net",
+            "trace",
+            "txpool",
+            "miner",
+            "web3",
+            "rpc",
+            "reth",
+            "ots",
+            "eth-call-bundle",
+        ]
+    }
+
+    /// Returns all modules that are provided by reth, without custom modules.
     pub const fn all_variants() -> &'static [Self] {
-        <Self as VariantArray>::VARIANTS
+        Self::VARIANTS
     }
 
-    /// Returns all variants of the enum
+    /// Returns all modules that are provided by reth, without custom modules.
     pub fn modules() -> impl IntoIterator<Item = RethRpcModule> {
-        use strum::IntoEnumIterator;
-        Self::iter()
+        Self::VARIANTS.iter().copied()
     }
 
     /// Returns the string representation of the module.
     #[inline]
     pub fn as_str(&self) -> &'static str {
-        self.into()
+        match self {
+            RethRpcModule::Admin => "admin",
+            RethRpcModule::Debug => "debug",
+            RethRpcModule::Eth => "eth",
+            RethRpcModule::Net => "net",
+            RethRpcModule::Trace => "trace",
+            RethRpcModule::Txpool => "txpool",
+            RethRpcModule::Miner => "miner",
+            RethRpcModule::Web3 => "web3",
+            RethRpcModule::Rpc => "rpc",
+            RethRpcModule::Reth => "reth",
+            RethRpcModule::Ots => "ots",
+            RethRpcModule::EthCallBundle => "eth-call-bundle",
+            RethRpcModule::Other(name) => name,
+        }
+    }
+
+    /// Returns true if the module is only served over IPC.
+    ///
+    /// These modules are removed from the selection of the HTTP and WS servers, which may be
+    /// reachable by anyone.
+    pub const fn is_ipc_only(&self) -> bool {
+        matches!(self, RethRpcModule::Miner)
+    }
+
+    /// Returns true if this is a custom module.
+    pub fn is_other(&self) -> bool {
+        matches!(self, RethRpcModule::Other(_))
+    }
+}
+
+impl AsRef<str> for RethRpcModule {
+    fn as_ref(&self) -> &str {
+        self.as_str()
     }
 }
 
@@ -901,11 +1255,16 @@ impl FromStr for RethRpcModule {
             "net" => RethRpcModule::Net,
             "trace" => RethRpcModule::Trace,
             "txpool" => RethRpcModule::Txpool,
+            "miner" => RethRpcModule::Miner,
             "web3" => RethRpcModule::Web3,
             "rpc" => RethRpcModule::Rpc,
             "reth" => RethRpcModule::Reth,
             "ots" => RethRpcModule::Ots,
-            "eth-call-bundle" | "eth_callBundle" => RethRpcModule::EthCallBundle,
+            "eth-call-bundle" | "eth_call_bundle" | "eth_callBundle" => {
+                RethRpcModule::EthCallBundle
+            }
+            // custom namespaces are lowercase identifiers, like the namespace of their methods
+            other if is_namespace(other) => RethRpcModule::Other(intern_namespace(other)),
             _ => return Err(ParseError::VariantNotFound),
         })
     }
@@ -918,9 +1277,30 @@ impl TryFrom<&str> for RethRpcModule {
     }
 }
 
+/// Returns true if the name is a valid namespace of a custom [RethRpcModule::Other] module.
+fn is_namespace(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase()) &&
+        name.bytes().all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+}
+
+/// Returns the static name of a custom namespace.
+///
+/// Every distinct namespace is leaked once, there are only as many as the node is configured with.
+fn intern_namespace(name: &str) -> &'static str {
+    static NAMESPACES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+    let mut namespaces = NAMESPACES.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(namespace) = namespaces.get(name) {
+        return namespace
+    }
+    let namespace: &'static str = Box::leak(name.into());
+    namespaces.insert(namespace);
+    namespace
+}
+
 impl fmt::Display for RethRpcModule {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.pad(self.as_ref())
+        f.pad(self.as_str())
     }
 }
 
@@ -929,7 +1309,17 @@ impl Serialize for RethRpcModule {
     where
         S: Serializer,
     {
-        s.serialize_str(self.as_ref())
+        s.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for RethRpcModule {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
     }
 }
 
@@ -954,6 +1344,12 @@ pub struct RethModuleRegistry<Provider, Pool, Network, Tasks, Events, EvmConfig>
     /// Optional forwarder for `eth_sendRawTransaction`
     // TODO(mattsse): find a more ergonomic way to configure eth/rpc customizations
     eth_raw_transaction_forwarder: Option<Arc<dyn RawTransactionForwarder>>,
+    /// The latest progress of the stages of the sync, served by the `reth` namespace.
+    sync_status: SyncStatusHandle,
+    /// The invalid blocks known to the engine, served by `debug_getBadBlocks`.
+    invalid_blocks: InvalidBlocksHandle,
+    /// The configured prune modes of the node, served by the `reth` namespace.
+    prune_modes: PruneModes,
 }
 
 // === impl RethModuleRegistry ===
@@ -983,6 +1379,9 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
             config,
             events,
             eth_raw_transaction_forwarder: None,
+            sync_status: SyncStatusHandle::default(),
+            invalid_blocks: InvalidBlocksHandle::default(),
+            prune_modes: PruneModes::none(),
         }
     }
 
@@ -996,6 +1395,21 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
         self.eth_raw_transaction_forwarder = Some(forwarder);
     }
 
+    /// Sets the handle to the progress of the sync, served by `reth_syncStatus`.
+    pub fn set_sync_status(&mut self, sync_status: SyncStatusHandle) {
+        self.sync_status = sync_status;
+    }
+
+    /// Sets the handle to the invalid blocks known to the engine, served by `debug_getBadBlocks`.
+    pub fn set_invalid_blocks(&mut self, invalid_blocks: InvalidBlocksHandle) {
+        self.invalid_blocks = invalid_blocks;
+    }
+
+    /// Sets the prune modes of the node, served by `reth_getPruneStatus`.
+    pub fn set_prune_modes(&mut self, prune_modes: PruneModes) {
+        self.prune_modes = prune_modes;
+    }
+
     /// Returns a reference to the pool
     pub fn pool(&self) -> &Pool {
         &self.pool
@@ -1070,6 +1484,11 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + PruneCheckpointReader
+        + CallTraceIndexReader
+        + StaticFileProviderFactory
+        + StageCheckpointReader
+        + DatabaseMetadataReader
         + Clone
         + Unpin
         + 'static,
@@ -1086,7 +1505,13 @@ where
     /// If called outside of the tokio runtime. See also [Self::eth_api]
     pub fn register_eth(&mut self) -> &mut Self {
         let eth_api = self.eth_api();
-        self.modules.insert(RethRpcModule::Eth, eth_api.into_rpc().into());
+        let mut module = EthApiServer::into_rpc(eth_api.clone());
+        if self.config.eth.conditional_transactions {
+            module
+                .merge(EthConditionalApiServer::into_rpc(eth_api))
+                .expect("No conflicting methods");
+        }
+        self.modules.insert(RethRpcModule::Eth, module.into());
         self
     }
 
@@ -1178,6 +1603,32 @@ where
         Some(module)
     }
 
+    /// Helper function to create a [RpcModule] for the HTTP or WS server if it's not `None`.
+    ///
+    /// The modules that are only served over IPC are removed from the selection, see
+    /// [RethRpcModule::is_ipc_only].
+    fn maybe_public_module(
+        &mut self,
+        transport: &'static str,
+        config: Option<&RpcModuleSelection>,
+    ) -> Option<RpcModule<()>> {
+        let config = config?;
+        let (ipc_only, public): (Vec<_>, Vec<_>) =
+            config.iter_selection().partition(RethRpcModule::is_ipc_only);
+        if ipc_only.is_empty() {
+            return Some(self.module_for(config))
+        }
+        if !matches!(config, RpcModuleSelection::All) {
+            warn!(
+                target: "rpc",
+                transport,
+                modules = ?ipc_only,
+                "Modules are only served over IPC"
+            );
+        }
+        Some(self.module_for(&RpcModuleSelection::Selection(public)))
+    }
+
     /// Populates a new [RpcModule] based on the selected [RethRpcModule]s in the given
     /// [RpcModuleSelection]
     pub fn module_for(&mut self, config: &RpcModuleSelection) -> RpcModule<()> {
@@ -1213,6 +1664,8 @@ where
         let namespaces: Vec<_> = namespaces.collect();
         namespaces
             .iter()
+            // the methods of custom modules are merged by the node
+            .filter(|namespace| !namespace.is_other())
             .copied()
             .map(|namespace| {
                 self.modules
@@ -1227,14 +1680,21 @@ where
                             self.provider.clone(),
                             eth_api.clone(),
                             self.blocking_pool_guard.clone(),
+                            self.invalid_blocks.clone(),
                         )
+                        .with_max_trace_chain_blocks(self.config.eth.max_trace_chain_blocks)
                         .into_rpc()
                         .into(),
                         RethRpcModule::Eth => {
                             // merge all eth handlers
-                            let mut module = eth_api.clone().into_rpc();
+                            let mut module = EthApiServer::into_rpc(eth_api.clone());
                             module.merge(eth_filter.clone().into_rpc()).expect("No conflicts");
                             module.merge(eth_pubsub.clone().into_rpc()).expect("No conflicts");
+                            if self.config.eth.conditional_transactions {
+                                module
+                                    .merge(EthConditionalApiServer::into_rpc(eth_api.clone()))
+                                    .expect("No conflicts");
+                            }
 
                             module.into()
                         }
@@ -1246,12 +1706,15 @@ where
                             eth_api.clone(),
                             self.blocking_pool_guard.clone(),
                         )
+                        .with_max_trace_filter_blocks(self.config.eth.max_trace_filter_blocks)
                         .into_rpc()
                         .into(),
                         RethRpcModule::Web3 => Web3Api::new(self.network.clone()).into_rpc().into(),
-                        RethRpcModule::Txpool => {
-                            TxPoolApi::new(self.pool.clone()).into_rpc().into()
-                        }
+                        RethRpcModule::Txpool => TxPoolApi::new(self.pool.clone())
+                            .with_max_entries(self.config.eth.txpool_max_entries)
+                            .into_rpc()
+                            .into(),
+                        RethRpcModule::Miner => MinerApi::new(self.pool.clone()).into_rpc().into(),
                         RethRpcModule::Rpc => RPCApi::new(
                             namespaces
                                 .iter()
@@ -1261,16 +1724,21 @@ where
                         .into_rpc()
                         .into(),
                         RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
-                        RethRpcModule::Reth => {
-                            RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
-                                .into_rpc()
-                                .into()
-                        }
+                        RethRpcModule::Reth => RethApi::new(
+                            self.provider.clone(),
+                            self.network.clone(),
+                            Box::new(self.executor.clone()),
+                            self.sync_status.clone(),
+                            self.prune_modes.clone(),
+                        )
+                        .into_rpc()
+                        .into(),
                         RethRpcModule::EthCallBundle => {
                             EthBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
                                 .into_rpc()
                                 .into()
                         }
+                        RethRpcModule::Other(_) => Methods::new(),
                     })
                     .clone()
             })
@@ -1343,6 +1811,7 @@ where
             cache.clone(),
             gas_oracle,
             self.config.eth.rpc_gas_cap,
+            self.config.eth.eth_proof_window,
             executor.clone(),
             blocking_task_pool.clone(),
             fee_history_cache,
@@ -1353,7 +1822,10 @@ where
             self.provider.clone(),
             self.pool.clone(),
             cache.clone(),
-            self.config.eth.filter_config(),
+            self.config
+                .eth
+                .filter_config()
+                .receipts_log_filter(self.prune_modes.receipts_log_filter.clone()),
             executor.clone(),
         );
 
@@ -1363,7 +1835,8 @@ where
             self.events.clone(),
             self.network.clone(),
             executor,
-        );
+        )
+        .with_resumable_subscriptions(self.config.eth.resumable_subscriptions_config());
 
         EthHandlers { api, cache, filter, pubsub, blocking_task_pool }
     }
@@ -1396,6 +1869,7 @@ where
     pub fn trace_api(&mut self) -> TraceApi<Provider, EthApi<Provider, Pool, Network, EvmConfig>> {
         let eth = self.eth_handlers();
         TraceApi::new(self.provider.clone(), eth.api, self.blocking_pool_guard.clone())
+            .with_max_trace_filter_blocks(self.config.eth.max_trace_filter_blocks)
     }
 
     /// Instantiates [EthBundle] Api
@@ -1425,7 +1899,13 @@ where
     /// If called outside of the tokio runtime. See also [Self::eth_api]
     pub fn debug_api(&mut self) -> DebugApi<Provider, EthApi<Provider, Pool, Network, EvmConfig>> {
         let eth_api = self.eth_api();
-        DebugApi::new(self.provider.clone(), eth_api, self.blocking_pool_guard.clone())
+        DebugApi::new(
+            self.provider.clone(),
+            eth_api,
+            self.blocking_pool_guard.clone(),
+            self.invalid_blocks.clone(),
+        )
+        .with_max_trace_chain_blocks(self.config.eth.max_trace_chain_blocks)
     }
 
     /// Instantiates NetApi
@@ -1439,8 +1919,14 @@ where
     }
 
     /// Instantiates RethApi
-    pub fn reth_api(&mut self) -> RethApi<Provider> {
-        RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
+    pub fn reth_api(&mut self) -> RethApi<Provider, Network> {
+        RethApi::new(
+            self.provider.clone(),
+            self.network.clone(),
+            Box::new(self.executor.clone()),
+            self.sync_status.clone(),
+            self.prune_modes.clone(),
+        )
     }
 }
 
@@ -1475,6 +1961,14 @@ pub struct RpcServerConfig {
     ipc_endpoint: Option<Endpoint>,
     /// JWT secret for authentication
     jwt_secret: Option<JwtSecret>,
+    /// Rate limits of the RPC methods
+    rate_limits: RpcRateLimits,
+    /// The cache of the responses of the http and ws servers
+    response_cache: Option<ResponseCache>,
+    /// The tracker the `eth_unsubscribe` calls of resumable subscriptions are reported to
+    resumable_unsubscribes: Option<ResumableUnsubscribes>,
+    /// Whether the calls of the http and ws servers are traced
+    tracing_propagation: bool,
 }
 
 impl fmt::Debug for RpcServerConfig {
@@ -1488,6 +1982,10 @@ impl fmt::Debug for RpcServerConfig {
             .field("ipc_server_config", &self.ipc_server_config)
             .field("ipc_endpoint", &self.ipc_endpoint.as_ref().map(|endpoint| endpoint.path()))
             .field("jwt_secret", &self.jwt_secret)
+            .field("rate_limits", &self.rate_limits)
+            .field("response_cache", &self.response_cache)
+            .field("resumable_unsubscribes", &self.resumable_unsubscribes)
+            .field("tracing_propagation", &self.tracing_propagation)
             .finish()
     }
 }
@@ -1605,6 +2103,47 @@ impl RpcServerConfig {
         self
     }
 
+    /// Configures the rate limits of the RPC methods of all transports, keyed by method name or
+    /// pattern.
+    ///
+    /// See also [RpcConfig::rate_limits](reth_config::config::RpcConfig::rate_limits).
+    pub fn with_rate_limits(mut self, rate_limits: BTreeMap<String, MethodRateLimit>) -> Self {
+        self.rate_limits = RpcRateLimits::new(rate_limits);
+        self
+    }
+
+    /// Returns the rate limits of the RPC methods, which can replace the limits of the servers
+    /// once they're started.
+    pub fn rate_limits(&self) -> RpcRateLimits {
+        self.rate_limits.clone()
+    }
+
+    /// Configures the cache of the responses of the servers.
+    ///
+    /// See also [ResponseCache].
+    pub fn with_response_cache(mut self, response_cache: ResponseCache) -> Self {
+        self.response_cache = Some(response_cache);
+        self
+    }
+
+    /// Configures the tracker the `eth_unsubscribe` calls are reported to, so that unsubscribed
+    /// resumable subscriptions are not kept dormant.
+    ///
+    /// See also [EthPubSub::resumable_unsubscribes].
+    pub fn with_resumable_unsubscribes(mut self, unsubscribes: ResumableUnsubscribes) -> Self {
+        self.resumable_unsubscribes = Some(unsubscribes);
+        self
+    }
+
+    /// Configures whether a span is created for every call of the http and ws servers, which is
+    /// linked to the trace context of the request.
+    ///
+    /// See also [trace_context].
+    pub fn with_tracing_propagation(mut self, enabled: bool) -> Self {
+        self.tracing_propagation = enabled;
+        self
+    }
+
     /// Returns true if any server is configured.
     ///
     /// If no server is configured, no server will be be launched on [RpcServerConfig::start].
@@ -1691,6 +2230,10 @@ impl RpcServerConfig {
                     .or(modules.ws.as_ref())
                     .map(RpcRequestMetrics::same_port)
                     .unwrap_or_default(),
+                self.rate_limits.layer(modules.http.as_ref().or(modules.ws.as_ref())),
+                ResponseCacheLayer::new(self.response_cache.clone()),
+                UnsubscribeLayer::new(self.resumable_unsubscribes.clone()),
+                self.tracing_propagation,
             )
             .await?;
             return Ok(WsHttpServer {
@@ -1715,6 +2258,10 @@ impl RpcServerConfig {
                 self.jwt_secret.clone(),
                 ServerKind::WS(ws_socket_addr),
                 modules.ws.as_ref().map(RpcRequestMetrics::ws).unwrap_or_default(),
+                self.rate_limits.layer(modules.ws.as_ref()),
+                ResponseCacheLayer::new(self.response_cache.clone()),
+                UnsubscribeLayer::new(self.resumable_unsubscribes.clone()),
+                self.tracing_propagation,
             )
             .await?;
             ws_local_addr = Some(addr);
@@ -1730,6 +2277,10 @@ impl RpcServerConfig {
                 self.jwt_secret.clone(),
                 ServerKind::Http(http_socket_addr),
                 modules.http.as_ref().map(RpcRequestMetrics::http).unwrap_or_default(),
+                self.rate_limits.layer(modules.http.as_ref()),
+                ResponseCacheLayer::new(self.response_cache.clone()),
+                UnsubscribeLayer::new(self.resumable_unsubscribes.clone()),
+                self.tracing_propagation,
             )
             .await?;
             http_local_addr = Some(addr);
@@ -1761,6 +2312,12 @@ impl RpcServerConfig {
             let ipc = builder
                 // TODO(mattsse): add metrics middleware for IPC
                 // .set_middleware(metrics)
+                .set_middleware(
+                    tower::ServiceBuilder::new()
+                        .layer(self.rate_limits.ipc_layer(modules.ipc.as_ref()))
+                        .layer(IpcResponseCacheLayer::new(self.response_cache.clone()))
+                        .layer(IpcUnsubscribeLayer::new(self.resumable_unsubscribes.clone())),
+                )
                 .build(ipc_path.path());
             server.ipc = Some(ipc);
         }
@@ -1880,6 +2437,9 @@ pub struct TransportRpcModules<Context = ()> {
     ws: Option<RpcModule<Context>>,
     /// rpcs module for ipc
     ipc: Option<RpcModule<Context>>,
+    /// The custom modules that were merged with
+    /// [TransportRpcModules::merge_if_module_configured].
+    merged_modules: HashSet<RethRpcModule>,
 }
 
 // === impl TransportRpcModules ===
@@ -1926,6 +2486,54 @@ impl TransportRpcModules {
         Ok(false)
     }
 
+    /// Merge the given [Methods] of the module in the methods of every transport that selects the
+    /// module.
+    ///
+    /// This is intended for custom [RethRpcModule::Other] modules, which are only installed if
+    /// they're selected, e.g. with `--http.api myapp`, or if all modules are selected.
+    ///
+    /// Fails if any of the methods in other is present already.
+    pub fn merge_if_module_configured(
+        &mut self,
+        module: RethRpcModule,
+        other: impl Into<Methods>,
+    ) -> Result<(), RegisterMethodError> {
+        self.merged_modules.insert(module);
+        let other = other.into();
+        if self.config.http().is_some_and(|http| http.contains(&module)) {
+            self.merge_http(other.clone())?;
+        }
+        if self.config.ws().is_some_and(|ws| ws.contains(&module)) {
+            self.merge_ws(other.clone())?;
+        }
+        if self.config.ipc().is_some_and(|ipc| ipc.contains(&module)) {
+            self.merge_ipc(other)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the selected custom [RethRpcModule::Other] modules that weren't merged with
+    /// [TransportRpcModules::merge_if_module_configured].
+    ///
+    /// Any lowercase name is parsed as a custom module, so these are most likely misspelled, e.g.
+    /// `--http.api ethh`.
+    pub fn unknown_modules(&self) -> Vec<RethRpcModule> {
+        let mut unknown = Vec::new();
+        for selection in
+            [self.config.http(), self.config.ws(), self.config.ipc()].into_iter().flatten()
+        {
+            for module in selection.iter_selection() {
+                if module.is_other() &&
+                    !self.merged_modules.contains(&module) &&
+                    !unknown.contains(&module)
+                {
+                    unknown.push(module);
+                }
+            }
+        }
+        unknown
+    }
+
     /// Merge the given [Methods] in all configured methods.
     ///
     /// Fails if any of the methods in other is present already.
@@ -2015,22 +2623,32 @@ impl Default for WsHttpServers {
     }
 }
 
+/// The rpc middleware of the http and ws servers.
+type RpcMiddleware = Stack<
+    UnsubscribeLayer,
+    Stack<
+        ResponseCacheLayer,
+        Stack<RateLimitLayer, Stack<RpcRequestMetrics, Stack<RpcTracingLayer, Identity>>>,
+    >,
+>;
+
+/// The http middleware that is applied innermost, after cors and auth.
+type HttpMiddleware<L> = Stack<TraceContextLayer, Stack<ApiKeyLayer, L>>;
+
 /// Http Servers Enum
 #[allow(clippy::type_complexity)]
 enum WsHttpServerKind {
     /// Http server
-    Plain(Server<Identity, Stack<RpcRequestMetrics, Identity>>),
+    Plain(Server<HttpMiddleware<Identity>, RpcMiddleware>),
     /// Http server with cors
-    WithCors(Server<Stack<CorsLayer, Identity>, Stack<RpcRequestMetrics, Identity>>),
+    WithCors(Server<HttpMiddleware<Stack<CorsLayer, Identity>>, RpcMiddleware>),
     /// Http server with auth
-    WithAuth(
-        Server<Stack<AuthLayer<JwtAuthValidator>, Identity>, Stack<RpcRequestMetrics, Identity>>,
-    ),
+    WithAuth(Server<HttpMiddleware<Stack<AuthLayer<JwtAuthValidator>, Identity>>, RpcMiddleware>),
     /// Http server with cors and auth
     WithCorsAuth(
         Server<
-            Stack<AuthLayer<JwtAuthValidator>, Stack<CorsLayer, Identity>>,
-            Stack<RpcRequestMetrics, Identity>,
+            HttpMiddleware<Stack<AuthLayer<JwtAuthValidator>, Stack<CorsLayer, Identity>>>,
+            RpcMiddleware,
         >,
     ),
 }
@@ -2051,6 +2669,7 @@ impl WsHttpServerKind {
     /// Builds the server according to the given config parameters.
     ///
     /// Returns the address of the started server.
+    #[allow(clippy::too_many_arguments)]
     async fn build(
         builder: ServerBuilder<Identity, Identity>,
         socket_addr: SocketAddr,
@@ -2058,7 +2677,19 @@ impl WsHttpServerKind {
         jwt_secret: Option<JwtSecret>,
         server_kind: ServerKind,
         metrics: RpcRequestMetrics,
+        rate_limits: RateLimitLayer,
+        response_cache: ResponseCacheLayer,
+        unsubscribes: UnsubscribeLayer,
+        tracing_propagation: bool,
     ) -> Result<(Self, SocketAddr), RpcError> {
+        let rpc_middleware = RpcServiceBuilder::new()
+            .layer(RpcTracingLayer::new(tracing_propagation))
+            .layer(metrics)
+            .layer(rate_limits)
+            .layer(response_cache)
+            .layer(unsubscribes);
+        let trace_context = TraceContextLayer::new(tracing_propagation);
+
         if let Some(cors) = cors_domains.as_deref().map(cors::create_cors_layer) {
             let cors = cors.map_err(|err| RpcError::Custom(err.to_string()))?;
 
@@ -2066,11 +2697,13 @@ impl WsHttpServerKind {
                 // stack cors and auth layers
                 let middleware = tower::ServiceBuilder::new()
                     .layer(cors)
-                    .layer(AuthLayer::new(JwtAuthValidator::new(secret.clone())));
+                    .layer(AuthLayer::new(JwtAuthValidator::new(secret.clone())))
+                    .layer(ApiKeyLayer)
+                    .layer(trace_context);
 
                 let server = builder
                     .set_http_middleware(middleware)
-                    .set_rpc_middleware(RpcServiceBuilder::new().layer(metrics))
+                    .set_rpc_middleware(rpc_middleware)
                     .build(socket_addr)
                     .await
                     .map_err(|err| RpcError::server_error(err, server_kind))?;
@@ -2079,10 +2712,13 @@ impl WsHttpServerKind {
                 let server = WsHttpServerKind::WithCorsAuth(server);
                 Ok((server, local_addr))
             } else {
-                let middleware = tower::ServiceBuilder::new().layer(cors);
+                let middleware = tower::ServiceBuilder::new()
+                    .layer(cors)
+                    .layer(ApiKeyLayer)
+                    .layer(trace_context);
                 let server = builder
                     .set_http_middleware(middleware)
-                    .set_rpc_middleware(RpcServiceBuilder::new().layer(metrics))
+                    .set_rpc_middleware(rpc_middleware)
                     .build(socket_addr)
                     .await
                     .map_err(|err| RpcError::server_error(err, server_kind))?;
@@ -2094,10 +2730,12 @@ impl WsHttpServerKind {
         } else if let Some(secret) = jwt_secret {
             // jwt auth layered service
             let middleware = tower::ServiceBuilder::new()
-                .layer(AuthLayer::new(JwtAuthValidator::new(secret.clone())));
+                .layer(AuthLayer::new(JwtAuthValidator::new(secret.clone())))
+                .layer(ApiKeyLayer)
+                .layer(trace_context);
             let server = builder
                 .set_http_middleware(middleware)
-                .set_rpc_middleware(RpcServiceBuilder::new().layer(metrics))
+                .set_rpc_middleware(rpc_middleware)
                 .build(socket_addr)
                 .await
                 .map_err(|err| RpcError::server_error(err, ServerKind::Auth(socket_addr)))?;
@@ -2108,7 +2746,10 @@ impl WsHttpServerKind {
         } else {
             // plain server without any middleware
             let server = builder
-                .set_rpc_middleware(RpcServiceBuilder::new().layer(metrics))
+                .set_http_middleware(
+                    tower::ServiceBuilder::new().layer(ApiKeyLayer).layer(trace_context),
+                )
+                .set_rpc_middleware(rpc_middleware)
                 .build(socket_addr)
                 .await
                 .map_err(|err| RpcError::server_error(err, server_kind))?;
@@ -2125,7 +2766,7 @@ pub struct RpcServer {
     /// Configured ws,http servers
     ws_http: WsHttpServer,
     /// ipc server
-    ipc: Option<IpcServer>,
+    ipc: Option<IpcServer<Stack<IpcUnsubscribeLayer, Stack<IpcRateLimitLayer, Identity>>>>,
 }
 
 // === impl RpcServer ===
@@ -2162,7 +2803,7 @@ impl RpcServer {
     pub async fn start(self, modules: TransportRpcModules) -> Result<RpcServerHandle, RpcError> {
         trace!(target: "rpc", "staring RPC server");
         let Self { ws_http, ipc: ipc_server } = self;
-        let TransportRpcModules { config, http, ws, ipc } = modules;
+        let TransportRpcModules { config, http, ws, ipc, .. } = modules;
         let mut handle = RpcServerHandle {
             http_local_addr: ws_http.http_local_addr,
             ws_local_addr: ws_http.ws_local_addr,
@@ -2302,6 +2943,17 @@ impl RpcServerHandle {
         let client = builder.build(url).await.expect("failed to create ws client");
         Some(client)
     }
+
+    /// Returns an ipc client connected to the server.
+    #[cfg(unix)]
+    pub async fn ipc_client(&self) -> Option<jsonrpsee::async_client::Client> {
+        let endpoint = self.ipc_endpoint()?;
+        let client = reth_ipc::client::IpcClientBuilder::default()
+            .build(Endpoint::new(endpoint).path())
+            .await
+            .expect("failed to create ipc client");
+        Some(client)
+    }
 }
 
 impl fmt::Debug for RpcServerHandle {
@@ -2340,6 +2992,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_custom_module_selection() {
+        let selection = "eth,myapp".parse::<RpcModuleSelection>().unwrap();
+        let custom = RethRpcModule::Other("myapp");
+        assert_eq!(selection, RpcModuleSelection::Selection(vec![RethRpcModule::Eth, custom]));
+        assert_eq!(custom.to_string(), "myapp");
+        assert!(selection.contains(&custom));
+        assert!(RpcModuleSelection::All.contains(&custom));
+        assert!(!RpcModuleSelection::Standard.contains(&custom));
+
+        assert!("my-app".parse::<RethRpcModule>().is_err());
+        assert!("MyApp".parse::<RethRpcModule>().is_err());
+    }
+
+    #[test]
+    fn unknown_custom_modules() {
+        let config = TransportRpcModuleConfig::default()
+            .with_http("eth,myapp,ethh".parse::<RpcModuleSelection>().unwrap())
+            .with_ipc(RpcModuleSelection::All);
+        let mut modules = TransportRpcModules { config, ..Default::default() };
+        assert_eq!(
+            modules.unknown_modules(),
+            vec![RethRpcModule::Other("myapp"), RethRpcModule::Other("ethh")]
+        );
+
+        modules.merge_if_module_configured(RethRpcModule::Other("myapp"), Methods::new()).unwrap();
+        assert_eq!(modules.unknown_modules(), vec![RethRpcModule::Other("ethh")]);
+    }
+
     #[test]
     fn parse_rpc_module_selection() {
         let selection = "all".parse::<RpcModuleSelection>().unwrap();
@@ -2400,6 +3081,8 @@ mod tests {
                 "net" =>  RethRpcModule::Net,
                 "trace" =>  RethRpcModule::Trace,
                 "web3" =>  RethRpcModule::Web3,
+                "txpool" =>  RethRpcModule::Txpool,
+                "miner" =>  RethRpcModule::Miner,
                 "rpc" => RethRpcModule::Rpc,
                 "ots" => RethRpcModule::Ots,
                 "reth" => RethRpcModule::Reth,
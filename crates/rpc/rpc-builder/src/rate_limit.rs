@@ -0,0 +1,748 @@
+//! Rate limiting of RPC methods.
+//!
+//! The limits are configured per method, see [`RpcConfig`](reth_config::config::RpcConfig), and
+//! enforced with token buckets. Depending on its [`RateLimitBucket`], a limit is either shared
+//! by all clients, tracked per connection or tracked per `X-API-Key` header value.
+//!
+//! Note: the `X-API-Key` header is taken from the HTTP request, or from the upgrade request of a
+//! WS connection. Requests over IPC are treated as requests without an API key, which share a
+//! single bucket. The per connection limits of HTTP requests are tracked per keep-alive
+//! connection.
+//!
+//! The limits can be replaced while the servers are running, see [RpcRateLimits::set_rules].
+
+use hyper::Request as HttpRequest;
+use jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{ErrorObject, ErrorObjectOwned, Request},
+    MethodResponse, RpcModule,
+};
+use parking_lot::{Mutex, RwLock};
+use reth_config::config::{MethodRateLimit, RateLimitBucket};
+use reth_metrics::{metrics::Counter, Metrics};
+use schnellru::{ByLength, LruMap};
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::{ready, Future},
+    pin::Pin,
+    sync::{Arc, Weak},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+use tokio::task::futures::TaskLocalFuture;
+use tower::{Layer, Service};
+
+/// The error code of requests that exceeded their rate limit.
+pub const RATE_LIMIT_EXCEEDED_CODE: i32 = -32005;
+
+/// The header that holds the API key of a HTTP request.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// The maximum number of shared buckets, the least recently used ones are evicted.
+const MAX_SHARED_BUCKETS: u32 = 10_000;
+
+tokio::task_local! {
+    /// The HTTP connection whose request is currently handled.
+    static HTTP_CONNECTION: HttpConnection;
+}
+
+/// The API key and the buckets of the HTTP connection a request was received on.
+///
+/// This is made available to the [RateLimitService] when it's created for a request, or for the
+/// WS connection the request upgrades to.
+#[derive(Debug, Clone, Default)]
+struct HttpConnection {
+    /// The `X-API-Key` header of the request.
+    api_key: Option<String>,
+    /// The per connection buckets.
+    buckets: Arc<ConnectionBuckets>,
+}
+
+/// The configured rate limits of the RPC servers.
+///
+/// The buckets of globally shared and API key limits are shared by all transports. Clones share
+/// the limits, so a clone that's taken before the servers are started can replace the limits of
+/// the running servers.
+#[derive(Debug, Clone, Default)]
+pub struct RpcRateLimits {
+    /// The configured limits, keyed by method name or pattern.
+    rules: Arc<RwLock<BTreeMap<String, MethodRateLimit>>>,
+    /// The buckets that are not tracked per connection.
+    shared_buckets: Arc<Mutex<SharedBuckets>>,
+    /// The limiters of the modules the servers were started with.
+    limiters: Arc<Mutex<Vec<Weak<RateLimiter>>>>,
+}
+
+impl RpcRateLimits {
+    /// Creates the rate limits from the configured limits.
+    pub(crate) fn new(rules: BTreeMap<String, MethodRateLimit>) -> Self {
+        Self { rules: Arc::new(RwLock::new(rules)), ..Default::default() }
+    }
+
+    /// Replaces the configured limits, keyed by method name or pattern.
+    ///
+    /// The new limits apply to the next call of a method. The buckets of methods whose limit
+    /// changed are kept, but hold at most the burst of the new limit.
+    pub fn set_rules(&self, rules: BTreeMap<String, MethodRateLimit>) {
+        *self.rules.write() = rules;
+        self.limiters.lock().retain(|limiter| {
+            let Some(limiter) = limiter.upgrade() else { return false };
+            *limiter.methods.write() = self.method_limiters(&limiter.module_methods);
+            true
+        });
+    }
+
+    /// Returns the limit of the given method.
+    ///
+    /// An exact match takes precedence over patterns, and a longer pattern over a shorter one.
+    fn find(&self, method: &str) -> Option<MethodRateLimit> {
+        let rules = self.rules.read();
+        if let Some(limit) = rules.get(method) {
+            return Some(*limit)
+        }
+        rules
+            .iter()
+            .filter(|(pattern, _)| pattern.contains('*') && matches_pattern(pattern, method))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, limit)| *limit)
+    }
+
+    /// Returns the limits of the given methods that are rate limited.
+    fn method_limiters(&self, methods: &[&'static str]) -> HashMap<&'static str, MethodLimiter> {
+        methods
+            .iter()
+            .filter_map(|method| {
+                let limit = self.find(method)?;
+                let metrics = RateLimitMetrics::new_with_labels(&[("method", *method)]);
+                Some((*method, MethodLimiter { limit, metrics }))
+            })
+            .collect()
+    }
+
+    /// Returns the limiter for the methods of the given module.
+    fn limiter(&self, module: Option<&RpcModule<()>>) -> Arc<RateLimiter> {
+        let module_methods =
+            module.into_iter().flat_map(|module| module.method_names()).collect::<Vec<_>>();
+        let limiter = Arc::new(RateLimiter {
+            methods: RwLock::new(self.method_limiters(&module_methods)),
+            module_methods,
+            shared_buckets: self.shared_buckets.clone(),
+        });
+        self.limiters.lock().push(Arc::downgrade(&limiter));
+        limiter
+    }
+
+    /// Returns the [RpcServiceT] middleware for the methods of the given HTTP or WS module.
+    pub(crate) fn layer(&self, module: Option<&RpcModule<()>>) -> RateLimitLayer {
+        RateLimitLayer { limiter: self.limiter(module) }
+    }
+
+    /// Returns the middleware for the methods of the given IPC module.
+    pub(crate) fn ipc_layer(&self, module: Option<&RpcModule<()>>) -> IpcRateLimitLayer {
+        IpcRateLimitLayer { limiter: self.limiter(module) }
+    }
+}
+
+/// Returns true if the method matches the pattern, where `*` matches any sequence of characters.
+fn matches_pattern(pattern: &str, method: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = parts.next().and_then(|prefix| method.strip_prefix(prefix)) else {
+        return false
+    };
+    let mut parts = parts.collect::<Vec<_>>();
+    let Some(suffix) = parts.pop() else { return rest.is_empty() };
+    for part in parts {
+        match rest.find(part) {
+            Some(idx) => rest = &rest[idx + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(suffix)
+}
+
+/// The key of a bucket that is not tracked per connection: the method and the API key, if any.
+type SharedBucket = (&'static str, Option<String>);
+
+/// The buckets that are not tracked per connection.
+///
+/// Since API keys are not authenticated, the number of buckets is bounded and the least recently
+/// used bucket is evicted. Buckets are not expired otherwise, a bucket that is full again is
+/// equivalent to a new one.
+struct SharedBuckets(LruMap<SharedBucket, TokenBucket>);
+
+impl SharedBuckets {
+    /// Takes a token from the bucket with the given key, creating a full bucket if it doesn't
+    /// exist.
+    fn try_acquire(
+        &mut self,
+        key: SharedBucket,
+        limit: &MethodRateLimit,
+        now: Instant,
+    ) -> Result<(), RetryAfter> {
+        self.0
+            .get_or_insert(key, || TokenBucket::full(limit, now))
+            .expect("the buckets are limited by length only")
+            .try_acquire(limit, now)
+    }
+
+    /// Returns the number of buckets.
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl Default for SharedBuckets {
+    fn default() -> Self {
+        Self(LruMap::new(ByLength::new(MAX_SHARED_BUCKETS)))
+    }
+}
+
+impl std::fmt::Debug for SharedBuckets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedBuckets").field("len", &self.0.len()).finish()
+    }
+}
+
+/// The per connection buckets, keyed by method.
+type ConnectionBuckets = Mutex<HashMap<&'static str, TokenBucket>>;
+
+/// The rate limits of the methods of a module.
+#[derive(Debug)]
+struct RateLimiter {
+    /// The limits of all rate limited methods.
+    methods: RwLock<HashMap<&'static str, MethodLimiter>>,
+    /// All methods of the module, which are checked against new limits.
+    module_methods: Vec<&'static str>,
+    /// The buckets that are not tracked per connection.
+    shared_buckets: Arc<Mutex<SharedBuckets>>,
+}
+
+impl RateLimiter {
+    /// Takes a token for a call of the given method on a connection with the given buckets and API
+    /// key.
+    ///
+    /// Returns the time after which the call can be retried if the limit is exceeded.
+    fn check(
+        &self,
+        method: &str,
+        connection: &ConnectionBuckets,
+        api_key: Option<&str>,
+    ) -> Result<(), RetryAfter> {
+        let methods = self.methods.read();
+        let Some((method, limiter)) = methods.get_key_value(method) else { return Ok(()) };
+        let now = Instant::now();
+
+        let res = match limiter.limit.bucket {
+            RateLimitBucket::Connection => connection
+                .lock()
+                .entry(method)
+                .or_insert_with(|| TokenBucket::full(&limiter.limit, now))
+                .try_acquire(&limiter.limit, now),
+            bucket => {
+                let api_key = if bucket == RateLimitBucket::ApiKey {
+                    api_key.map(str::to_string)
+                } else {
+                    None
+                };
+                self.shared_buckets.lock().try_acquire((method, api_key), &limiter.limit, now)
+            }
+        };
+
+        if res.is_err() {
+            limiter.metrics.throttled_requests.increment(1);
+        }
+        res
+    }
+
+    /// Returns true if no method is rate limited.
+    fn is_empty(&self) -> bool {
+        self.methods.read().is_empty()
+    }
+}
+
+/// The limit of a single method.
+#[derive(Debug)]
+struct MethodLimiter {
+    limit: MethodRateLimit,
+    metrics: RateLimitMetrics,
+}
+
+/// The time after which a throttled call can be retried, `None` if the method doesn't refill.
+type RetryAfter = Option<Duration>;
+
+/// A token bucket that refills continuously.
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    /// The available tokens.
+    tokens: f64,
+    /// When the tokens were last updated.
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    /// Returns a full bucket.
+    fn full(limit: &MethodRateLimit, now: Instant) -> Self {
+        Self { tokens: burst(limit), updated_at: now }
+    }
+
+    /// Refills the bucket and takes a token if one is available.
+    fn try_acquire(&mut self, limit: &MethodRateLimit, now: Instant) -> Result<(), RetryAfter> {
+        let rate = limit.requests_per_second as f64;
+        self.tokens = self.tokens_at(limit, now);
+        self.updated_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else if rate > 0.0 {
+            Err(Some(Duration::from_secs_f64((1.0 - self.tokens) / rate)))
+        } else {
+            Err(None)
+        }
+    }
+
+    /// Returns the available tokens at the given time.
+    fn tokens_at(&self, limit: &MethodRateLimit, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        (self.tokens + elapsed * limit.requests_per_second as f64).min(burst(limit))
+    }
+}
+
+/// Returns the capacity of the buckets of the limit.
+fn burst(limit: &MethodRateLimit) -> f64 {
+    limit.burst.unwrap_or(limit.requests_per_second) as f64
+}
+
+/// Returns the error of a throttled call.
+///
+/// The error data holds the number of seconds after which the call can be retried.
+fn rate_limit_error(retry_after: RetryAfter) -> ErrorObjectOwned {
+    ErrorObject::owned(
+        RATE_LIMIT_EXCEEDED_CODE,
+        "rate limit exceeded",
+        retry_after.map(|retry_after| json!({ "retryAfter": retry_after.as_secs_f64().ceil() })),
+    )
+}
+
+/// A [RpcServiceT] middleware that rejects calls that exceed their rate limit.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let connection = HTTP_CONNECTION.try_with(Clone::clone).unwrap_or_default();
+        RateLimitService { inner, limiter: self.limiter.clone(), connection }
+    }
+}
+
+/// The [RpcServiceT] of the [RateLimitLayer].
+///
+/// This is created per HTTP request and per WS connection.
+#[derive(Debug, Clone)]
+pub(crate) struct RateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+    /// The HTTP connection the service was created for.
+    connection: HttpConnection,
+}
+
+impl<'a, S> RpcServiceT<'a> for RateLimitService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = RateLimitFuture<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let HttpConnection { api_key, buckets } = &self.connection;
+        match self.limiter.check(req.method_name(), buckets, api_key.as_deref()) {
+            Ok(()) => RateLimitFuture::Call(self.inner.call(req)),
+            Err(retry_after) => RateLimitFuture::Throttled(Some(MethodResponse::error(
+                req.id,
+                rate_limit_error(retry_after),
+            ))),
+        }
+    }
+}
+
+/// Response future of the [RateLimitService].
+#[pin_project::pin_project(project = RateLimitFutureProj)]
+pub(crate) enum RateLimitFuture<F> {
+    /// The call was rejected.
+    Throttled(Option<MethodResponse>),
+    /// The call was forwarded.
+    Call(#[pin] F),
+}
+
+impl<F> std::fmt::Debug for RateLimitFuture<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RateLimitFuture")
+    }
+}
+
+impl<F: Future<Output = MethodResponse>> Future for RateLimitFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.project() {
+            RateLimitFutureProj::Throttled(response) => {
+                Poll::Ready(response.take().expect("polled after completion"))
+            }
+            RateLimitFutureProj::Call(fut) => fut.poll(cx),
+        }
+    }
+}
+
+/// A HTTP middleware that makes the `X-API-Key` header of a request and the buckets of its
+/// connection available to the [RateLimitService].
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ApiKeyLayer;
+
+impl<S> Layer<S> for ApiKeyLayer {
+    type Service = ApiKeyService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ApiKeyService { inner, buckets: Default::default() }
+    }
+}
+
+/// The HTTP service of the [ApiKeyLayer].
+///
+/// This is created per connection.
+#[derive(Debug, Clone)]
+pub(crate) struct ApiKeyService<S> {
+    inner: S,
+    /// The buckets of the connection.
+    buckets: Arc<ConnectionBuckets>,
+}
+
+impl<S, B> Service<HttpRequest<B>> for ApiKeyService<S>
+where
+    S: Service<HttpRequest<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<HttpConnection, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        let api_key = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let connection = HttpConnection { api_key, buckets: self.buckets.clone() };
+
+        // the rpc service of a WS connection is created when the upgrade request is handled, and
+        // the one of a HTTP request may be created when the request is handled or polled
+        let fut = HTTP_CONNECTION.sync_scope(connection.clone(), || self.inner.call(req));
+        HTTP_CONNECTION.scope(connection, fut)
+    }
+}
+
+/// The IPC middleware that rejects calls that exceed their rate limit.
+#[derive(Debug, Clone)]
+pub(crate) struct IpcRateLimitLayer {
+    limiter: Arc<RateLimiter>,
+}
+
+impl<S> Layer<S> for IpcRateLimitLayer {
+    type Service = IpcRateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IpcRateLimitService { inner, limiter: self.limiter.clone(), connection: Default::default() }
+    }
+}
+
+/// The service of the [IpcRateLimitLayer].
+///
+/// This is created per connection and handles the raw requests, which may be batches.
+#[derive(Debug)]
+pub(crate) struct IpcRateLimitService<S> {
+    inner: S,
+    limiter: Arc<RateLimiter>,
+    /// The buckets of the connection.
+    connection: ConnectionBuckets,
+}
+
+impl<S> IpcRateLimitService<S> {
+    /// Checks the rate limit of a single call.
+    ///
+    /// Returns the error response of a throttled call, or `None` if the throttled call is a
+    /// notification.
+    fn check(&self, call: &Value) -> Result<(), Option<Value>> {
+        let Some(method) = call.get("method").and_then(Value::as_str) else { return Ok(()) };
+        self.limiter.check(method, &self.connection, None).map_err(|retry_after| {
+            call.get("id").map(
+                |id| json!({ "jsonrpc": "2.0", "id": id, "error": rate_limit_error(retry_after) }),
+            )
+        })
+    }
+}
+
+impl<S> Service<String> for IpcRateLimitService<S>
+where
+    S: Service<String, Response = Option<String>>,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Option<String>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: String) -> Self::Future {
+        if self.limiter.is_empty() {
+            return Box::pin(self.inner.call(request))
+        }
+
+        // invalid requests are left to the server
+        let calls = match serde_json::from_str::<Value>(&request) {
+            Ok(Value::Array(calls)) => calls,
+            Ok(call) => {
+                return match self.check(&call) {
+                    Ok(()) => Box::pin(self.inner.call(request)),
+                    Err(response) => Box::pin(ready(Ok(response.map(|resp| resp.to_string())))),
+                }
+            }
+            Err(_) => return Box::pin(self.inner.call(request)),
+        };
+
+        let mut throttled = false;
+        let mut responses = Vec::new();
+        let mut allowed = Vec::with_capacity(calls.len());
+        for call in calls {
+            match self.check(&call) {
+                Ok(()) => allowed.push(call),
+                Err(response) => {
+                    throttled = true;
+                    responses.extend(response);
+                }
+            }
+        }
+
+        if !throttled {
+            return Box::pin(self.inner.call(request))
+        }
+        if allowed.is_empty() {
+            return Box::pin(ready(Ok(batch_response(responses))))
+        }
+
+        // forward the remaining calls and merge their responses with the errors
+        let fut = self.inner.call(Value::Array(allowed).to_string());
+        Box::pin(async move {
+            let Some(response) = fut.await? else { return Ok(batch_response(responses)) };
+            match serde_json::from_str::<Value>(&response) {
+                Ok(Value::Array(forwarded)) => responses.extend(forwarded),
+                Ok(forwarded) => responses.push(forwarded),
+                Err(_) => return Ok(Some(response)),
+            }
+            Ok(batch_response(responses))
+        })
+    }
+}
+
+/// Returns the response of a batch, `None` if the batch only contained notifications.
+fn batch_response(responses: Vec<Value>) -> Option<String> {
+    (!responses.is_empty()).then(|| Value::Array(responses).to_string())
+}
+
+/// Metrics of rate limited RPC methods.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "rpc_server.rate_limits")]
+struct RateLimitMetrics {
+    /// The number of requests that exceeded the rate limit of the method.
+    throttled_requests: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(requests_per_second: u32, burst: Option<u32>) -> MethodRateLimit {
+        MethodRateLimit { requests_per_second, burst, bucket: RateLimitBucket::Global }
+    }
+
+    #[test]
+    fn match_patterns() {
+        assert!(matches_pattern("eth_*", "eth_call"));
+        assert!(matches_pattern("*", "eth_call"));
+        assert!(matches_pattern("*_call", "eth_call"));
+        assert!(matches_pattern("eth_get*By*", "eth_getBlockByNumber"));
+        assert!(matches_pattern("eth_call", "eth_call"));
+        assert!(!matches_pattern("eth_call", "eth_callMany"));
+        assert!(!matches_pattern("debug_*", "eth_call"));
+        assert!(!matches_pattern("eth_get*By*Hash", "eth_getBlockByNumber"));
+        assert!(!matches_pattern("eth_*call", "eth_call"));
+    }
+
+    #[test]
+    fn most_specific_rule() {
+        let limits = RpcRateLimits::new(BTreeMap::from([
+            ("*".to_string(), limit(1, None)),
+            ("eth_*".to_string(), limit(2, None)),
+            ("eth_call".to_string(), limit(3, None)),
+        ]));
+        assert_eq!(limits.find("eth_call"), Some(limit(3, None)));
+        assert_eq!(limits.find("eth_getBalance"), Some(limit(2, None)));
+        assert_eq!(limits.find("debug_traceCall"), Some(limit(1, None)));
+
+        let limits = RpcRateLimits::new(BTreeMap::from([("eth_*".to_string(), limit(2, None))]));
+        assert_eq!(limits.find("debug_traceCall"), None);
+    }
+
+    #[test]
+    fn replace_rules() {
+        let mut module = RpcModule::new(());
+        module.register_method("eth_call", |_, _| "").unwrap();
+        module.register_method("eth_chainId", |_, _| "").unwrap();
+
+        let limits =
+            RpcRateLimits::new(BTreeMap::from([("eth_call".to_string(), limit(0, Some(1)))]));
+        let limiter = limits.limiter(Some(&module));
+        let connection = ConnectionBuckets::default();
+        assert_eq!(limiter.check("eth_call", &connection, None), Ok(()));
+        assert_eq!(limiter.check("eth_call", &connection, None), Err(None));
+        assert_eq!(limiter.check("eth_chainId", &connection, None), Ok(()));
+
+        limits.clone().set_rules(BTreeMap::from([("eth_chainId".to_string(), limit(0, Some(1)))]));
+        assert_eq!(limiter.check("eth_call", &connection, None), Ok(()));
+        assert_eq!(limiter.check("eth_chainId", &connection, None), Ok(()));
+        assert_eq!(limiter.check("eth_chainId", &connection, None), Err(None));
+
+        // limiters that were dropped are not updated anymore
+        drop(limiter);
+        limits.set_rules(BTreeMap::new());
+        assert!(limits.limiters.lock().is_empty());
+    }
+
+    #[test]
+    fn api_key_buckets_are_bounded() {
+        let mut module = RpcModule::new(());
+        module.register_method("eth_call", |_, _| "").unwrap();
+        let limit = MethodRateLimit {
+            requests_per_second: 0,
+            burst: Some(1),
+            bucket: RateLimitBucket::ApiKey,
+        };
+        let limits = RpcRateLimits::new(BTreeMap::from([("eth_call".to_string(), limit)]));
+        let limiter = limits.limiter(Some(&module));
+        let connection = ConnectionBuckets::default();
+        assert_eq!(limiter.check("eth_call", &connection, Some("a")), Ok(()));
+        assert_eq!(limiter.check("eth_call", &connection, Some("a")), Err(None));
+        assert_eq!(limiter.check("eth_call", &connection, Some("b")), Ok(()));
+
+        // rotating keys evicts the least recently used buckets
+        for key in 0..MAX_SHARED_BUCKETS {
+            assert_eq!(limiter.check("eth_call", &connection, Some(&key.to_string())), Ok(()));
+        }
+        assert_eq!(limits.shared_buckets.lock().len(), MAX_SHARED_BUCKETS as usize);
+        assert_eq!(limiter.check("eth_call", &connection, Some("a")), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn http_connection_of_requests() {
+        let limits = RpcRateLimits::default();
+        let layer = limits.layer(None);
+        // creates the rpc service like the server does when it handles a request
+        let inner = tower::service_fn(move |_: HttpRequest<()>| {
+            ready(Ok::<_, std::convert::Infallible>(layer.layer(()).connection))
+        });
+        let request = |api_key: Option<&str>| {
+            let mut builder = HttpRequest::builder();
+            if let Some(api_key) = api_key {
+                builder = builder.header(API_KEY_HEADER, api_key);
+            }
+            builder.body(()).unwrap()
+        };
+
+        let mut http = ApiKeyLayer.layer(inner.clone());
+        let first = http.call(request(Some("key"))).await.unwrap();
+        assert_eq!(first.api_key.as_deref(), Some("key"));
+        let second = http.call(request(None)).await.unwrap();
+        assert_eq!(second.api_key, None);
+        assert!(Arc::ptr_eq(&first.buckets, &second.buckets));
+
+        // a new connection has its own buckets
+        let mut http = ApiKeyLayer.layer(inner);
+        let other = http.call(request(Some("key"))).await.unwrap();
+        assert!(!Arc::ptr_eq(&first.buckets, &other.buckets));
+    }
+
+    #[tokio::test]
+    async fn ipc_rejects_throttled_calls() {
+        let mut module = RpcModule::new(());
+        module.register_method("eth_call", |_, _| "").unwrap();
+        module.register_method("eth_chainId", |_, _| "").unwrap();
+        let limit = MethodRateLimit {
+            requests_per_second: 0,
+            burst: Some(1),
+            bucket: RateLimitBucket::Connection,
+        };
+        let limits = RpcRateLimits::new(BTreeMap::from([("eth_call".to_string(), limit)]));
+
+        // answers every forwarded call
+        let result = |call: &Value| json!({ "jsonrpc": "2.0", "id": call["id"], "result": "ok" });
+        let inner = tower::service_fn(move |request: String| {
+            let response = match serde_json::from_str::<Value>(&request).unwrap() {
+                Value::Array(calls) => Value::Array(calls.iter().map(result).collect()),
+                call => result(&call),
+            };
+            ready(Ok::<_, std::convert::Infallible>(Some(response.to_string())))
+        });
+        let mut ipc = limits.ipc_layer(Some(&module)).layer(inner);
+        let mut call = |request: Value| {
+            let response = ipc.call(request.to_string());
+            async move { serde_json::from_str::<Value>(&response.await.unwrap().unwrap()).unwrap() }
+        };
+
+        let response = call(json!({ "jsonrpc": "2.0", "id": 1, "method": "eth_call" })).await;
+        assert_eq!(response["result"], "ok");
+
+        let response = call(json!([
+            { "jsonrpc": "2.0", "id": 2, "method": "eth_call" },
+            { "jsonrpc": "2.0", "id": 3, "method": "eth_chainId" },
+            { "jsonrpc": "2.0", "method": "eth_call" },
+        ]))
+        .await;
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        let response = |id: u64| responses.iter().find(|response| response["id"] == id).unwrap();
+        assert_eq!(response(2)["error"]["code"], RATE_LIMIT_EXCEEDED_CODE);
+        assert_eq!(response(2)["error"].get("data"), None);
+        assert_eq!(response(3)["result"], "ok");
+    }
+
+    #[test]
+    fn token_bucket() {
+        let limit = limit(2, Some(4));
+        let now = Instant::now();
+        let mut bucket = TokenBucket::full(&limit, now);
+        for _ in 0..4 {
+            assert_eq!(bucket.try_acquire(&limit, now), Ok(()));
+        }
+        assert_eq!(bucket.try_acquire(&limit, now), Err(Some(Duration::from_millis(500))));
+        assert!(bucket.tokens_at(&limit, now) < burst(&limit));
+
+        let now = now + Duration::from_millis(500);
+        assert_eq!(bucket.try_acquire(&limit, now), Ok(()));
+        assert!(bucket.try_acquire(&limit, now).is_err());
+        assert_eq!(bucket.tokens_at(&limit, now + Duration::from_secs(2)), burst(&limit));
+
+        let limit = MethodRateLimit { requests_per_second: 0, burst: Some(1), ..limit };
+        let mut bucket = TokenBucket::full(&limit, now);
+        assert_eq!(bucket.try_acquire(&limit, now), Ok(()));
+        assert_eq!(bucket.try_acquire(&limit, now + Duration::from_secs(10)), Err(None));
+    }
+}
@@ -0,0 +1,713 @@
+//! Caching of RPC responses for immutable data.
+//!
+//! The responses of methods that return the data of a single block, like blocks, receipts and
+//! traces, are cached once that block is at least [`ResponseCacheConfig::finality_depth`] blocks
+//! below the tip and therefore considered final. Responses for blocks closer to the tip are never
+//! cached, so a reorg can not invalidate a cached response.
+//!
+//! Responses are cached in their serialized form, keyed by method and parameters. On a cache hit
+//! only the id of the request is set, the result is not serialized again. Whether a response is
+//! final is decided on a blocking task after the response was sent, since it reads the database.
+//!
+//! Note: the IPC server only caches the responses of single calls, calls in batches are always
+//! forwarded.
+
+use jsonrpsee::{
+    server::middleware::rpc::RpcServiceT,
+    types::{Request, ResponsePayload},
+    MethodResponse,
+};
+use parking_lot::Mutex;
+use reth_interfaces::provider::ProviderResult;
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
+use reth_primitives::{BlockNumber, TxHash, B256, U64};
+use reth_provider::TransactionsProvider;
+use schnellru::{LruMap, Unlimited};
+use serde::Deserialize;
+use serde_json::{value::RawValue, Value};
+use std::{
+    collections::HashMap,
+    future::{ready, Future},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tower::{Layer, Service};
+
+/// The default number of blocks a block must be below the tip to be considered final.
+pub const DEFAULT_FINALITY_DEPTH: u64 = 64;
+
+/// The memory that is accounted for a cached response in addition to its key and result.
+const ENTRY_OVERHEAD: usize = 64;
+
+/// How the block of a cacheable response is determined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockSource {
+    /// The parameter at the given index is the number or hash of the block.
+    BlockParam(usize),
+    /// The parameter at the given index is the hash of a transaction of the block.
+    TransactionParam(usize),
+    /// The result has a `number` or `blockNumber` field.
+    Result,
+}
+
+/// The methods whose responses are cached.
+const CACHEABLE_METHODS: &[(&str, BlockSource)] = &[
+    ("eth_getBlockByHash", BlockSource::Result),
+    ("eth_getBlockByNumber", BlockSource::BlockParam(0)),
+    ("eth_getBlockReceipts", BlockSource::BlockParam(0)),
+    ("eth_getTransactionByBlockHashAndIndex", BlockSource::BlockParam(0)),
+    ("eth_getTransactionByBlockNumberAndIndex", BlockSource::BlockParam(0)),
+    ("eth_getTransactionByHash", BlockSource::Result),
+    ("eth_getTransactionReceipt", BlockSource::Result),
+    ("debug_getRawBlock", BlockSource::BlockParam(0)),
+    ("debug_getRawHeader", BlockSource::BlockParam(0)),
+    ("debug_getRawReceipts", BlockSource::BlockParam(0)),
+    ("debug_traceBlockByHash", BlockSource::BlockParam(0)),
+    ("debug_traceBlockByNumber", BlockSource::BlockParam(0)),
+    ("debug_traceTransaction", BlockSource::TransactionParam(0)),
+    ("trace_block", BlockSource::BlockParam(0)),
+    ("trace_replayBlockTransactions", BlockSource::BlockParam(0)),
+    ("trace_replayTransaction", BlockSource::TransactionParam(0)),
+    ("trace_transaction", BlockSource::TransactionParam(0)),
+];
+
+/// Returns the name and block source of the method if its responses are cached.
+fn cacheable_method(method: &str) -> Option<(&'static str, BlockSource)> {
+    CACHEABLE_METHODS.iter().find(|(name, _)| *name == method).copied()
+}
+
+/// Configuration of the [ResponseCache].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseCacheConfig {
+    /// The maximum total size of the cached responses in bytes.
+    pub max_bytes: usize,
+    /// The maximum size of the cached responses of a method in bytes, keyed by method name.
+    ///
+    /// Methods without a limit may use the entire cache.
+    pub method_max_bytes: HashMap<String, usize>,
+    /// The number of blocks a block must be below the tip to be considered final.
+    pub finality_depth: u64,
+}
+
+impl ResponseCacheConfig {
+    /// Creates a new config with the given total size in bytes.
+    pub fn new(max_bytes: usize) -> Self {
+        Self { max_bytes, method_max_bytes: HashMap::new(), finality_depth: DEFAULT_FINALITY_DEPTH }
+    }
+
+    /// Sets the number of blocks a block must be below the tip to be considered final.
+    pub fn with_finality_depth(mut self, finality_depth: u64) -> Self {
+        self.finality_depth = finality_depth;
+        self
+    }
+
+    /// Sets the maximum sizes in bytes of the cached responses of the given methods.
+    pub fn with_method_limits(mut self, limits: impl IntoIterator<Item = (String, usize)>) -> Self {
+        self.method_max_bytes.extend(limits);
+        self
+    }
+
+    /// Returns the maximum size of the cached responses of the given method.
+    fn method_limit(&self, method: &str) -> usize {
+        self.method_max_bytes
+            .get(method)
+            .map_or(self.max_bytes, |limit| *limit.min(&self.max_bytes))
+    }
+}
+
+/// The canonical chain as far as the [ResponseCache] needs to know it.
+trait CanonicalChain: Send + Sync {
+    /// Returns the number of the tip.
+    fn tip(&self) -> ProviderResult<BlockNumber>;
+
+    /// Returns the number of the canonical block with the given hash.
+    fn canonical_block_number(&self, hash: B256) -> ProviderResult<Option<BlockNumber>>;
+
+    /// Returns the number of the canonical block that includes the given transaction.
+    fn transaction_block_number(&self, hash: TxHash) -> ProviderResult<Option<BlockNumber>>;
+}
+
+impl<T> CanonicalChain for T
+where
+    T: TransactionsProvider,
+{
+    fn tip(&self) -> ProviderResult<BlockNumber> {
+        self.best_block_number()
+    }
+
+    fn canonical_block_number(&self, hash: B256) -> ProviderResult<Option<BlockNumber>> {
+        self.block_number(hash)
+    }
+
+    fn transaction_block_number(&self, hash: TxHash) -> ProviderResult<Option<BlockNumber>> {
+        match self.transaction_id(hash)? {
+            Some(id) => self.transaction_block(id),
+            None => Ok(None),
+        }
+    }
+}
+
+/// An in-process LRU cache of the responses of RPC methods that return immutable data.
+///
+/// See the [module docs](self) for more info.
+#[derive(Clone)]
+pub struct ResponseCache {
+    inner: Arc<ResponseCacheInner>,
+}
+
+impl ResponseCache {
+    /// Creates a new cache that uses the given provider to decide whether a response is final.
+    pub fn new<Provider>(config: ResponseCacheConfig, provider: Provider) -> Self
+    where
+        Provider: TransactionsProvider + 'static,
+    {
+        let metrics = CACHEABLE_METHODS
+            .iter()
+            .map(|(method, _)| {
+                (*method, ResponseCacheMetrics::new_with_labels(&[("method", *method)]))
+            })
+            .collect();
+        Self {
+            inner: Arc::new(ResponseCacheInner {
+                config,
+                chain: Box::new(provider),
+                responses: Default::default(),
+                metrics,
+            }),
+        }
+    }
+
+    /// Returns the cached result of the call.
+    fn get(&self, method: &'static str, key: &str) -> Option<Box<RawValue>> {
+        let result = self
+            .inner
+            .responses
+            .lock()
+            .methods
+            .get_mut(method)
+            .and_then(|responses| responses.entries.get(key).cloned());
+        let metrics = &self.inner.metrics[method];
+        if result.is_some() {
+            metrics.hits.increment(1);
+        } else {
+            metrics.misses.increment(1);
+        }
+        result
+    }
+
+    /// Caches the result of the successful response of the call if it belongs to a final block.
+    ///
+    /// The block is looked up on a blocking task.
+    fn on_response(
+        &self,
+        method: &'static str,
+        source: BlockSource,
+        params: Vec<Value>,
+        key: String,
+        response: &str,
+    ) {
+        let Ok(ResponseResult { result }) = serde_json::from_str(response) else { return };
+        // the data may not be available yet
+        if result.get() == "null" {
+            return
+        }
+
+        let cache = self.clone();
+        let result = result.to_owned();
+        tokio::task::spawn_blocking(move || {
+            cache.insert_if_final(method, source, &params, key, result)
+        });
+    }
+
+    /// Caches the result of the call if it belongs to a final block.
+    fn insert_if_final(
+        &self,
+        method: &'static str,
+        source: BlockSource,
+        params: &[Value],
+        key: String,
+        result: Box<RawValue>,
+    ) {
+        let block = match source {
+            BlockSource::BlockParam(index) => {
+                params.get(index).and_then(|block| match block_param(block)? {
+                    BlockParam::Number(number) => Some(number),
+                    BlockParam::Hash(hash) => {
+                        self.inner.chain.canonical_block_number(hash).ok().flatten()
+                    }
+                })
+            }
+            BlockSource::TransactionParam(index) => params
+                .get(index)
+                .and_then(|hash| serde_json::from_value::<TxHash>(hash.clone()).ok())
+                .and_then(|hash| self.inner.chain.transaction_block_number(hash).ok().flatten()),
+            BlockSource::Result => serde_json::from_str::<ResultBlock>(result.get())
+                .ok()
+                .and_then(|block| block.number.or(block.block_number))
+                .map(|number| number.to::<u64>()),
+        };
+        let Some(block) = block else { return };
+        let Ok(tip) = self.inner.chain.tip() else { return };
+        if block.saturating_add(self.inner.config.finality_depth) > tip {
+            return
+        }
+
+        self.insert(method, key, result);
+    }
+
+    /// Inserts the result of the call and evicts the least recently used results until the cache
+    /// is within its limits again.
+    fn insert(&self, method: &'static str, key: String, result: Box<RawValue>) {
+        let config = &self.inner.config;
+        let method_limit = config.method_limit(method);
+        let size = key.len() + result.get().len() + ENTRY_OVERHEAD;
+        if size > method_limit {
+            return
+        }
+
+        let mut responses = self.inner.responses.lock();
+        let CachedResponses { methods, size: total_size } = &mut *responses;
+        let cache = methods.entry(method).or_insert_with(MethodResponses::new);
+        if let Some(previous) = cache.entries.remove(&key) {
+            let previous = entry_size(&key, &previous);
+            cache.size -= previous;
+            *total_size -= previous;
+        }
+        cache.entries.insert(key, result);
+        cache.size += size;
+        *total_size += size;
+
+        while cache.size > method_limit {
+            let Some(evicted) = cache.pop_oldest() else { break };
+            *total_size -= evicted;
+        }
+        self.inner.metrics[method].cached_bytes.set(cache.size as f64);
+
+        // the largest cache gives way first
+        while *total_size > config.max_bytes {
+            let Some((method, cache)) = methods.iter_mut().max_by_key(|(_, cache)| cache.size)
+            else {
+                break
+            };
+            let Some(evicted) = cache.pop_oldest() else { break };
+            *total_size -= evicted;
+            self.inner.metrics[method].cached_bytes.set(cache.size as f64);
+        }
+    }
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("config", &self.inner.config)
+            .field("size", &self.inner.responses.lock().size)
+            .finish_non_exhaustive()
+    }
+}
+
+struct ResponseCacheInner {
+    config: ResponseCacheConfig,
+    chain: Box<dyn CanonicalChain>,
+    responses: Mutex<CachedResponses>,
+    metrics: HashMap<&'static str, ResponseCacheMetrics>,
+}
+
+/// The cached responses of all methods.
+#[derive(Default)]
+struct CachedResponses {
+    methods: HashMap<&'static str, MethodResponses>,
+    /// The total size of the cached responses.
+    size: usize,
+}
+
+/// The cached results of a method, keyed by the canonicalized parameters of the call.
+struct MethodResponses {
+    entries: LruMap<String, Box<RawValue>, Unlimited>,
+    /// The size of the cached results.
+    size: usize,
+}
+
+impl MethodResponses {
+    fn new() -> Self {
+        Self { entries: LruMap::new(Unlimited), size: 0 }
+    }
+
+    /// Removes the least recently used result and returns its size.
+    fn pop_oldest(&mut self) -> Option<usize> {
+        let (key, result) = self.entries.pop_oldest()?;
+        let size = entry_size(&key, &result);
+        self.size -= size;
+        Some(size)
+    }
+}
+
+/// Returns the accounted size of a cached result.
+fn entry_size(key: &str, result: &RawValue) -> usize {
+    key.len() + result.get().len() + ENTRY_OVERHEAD
+}
+
+/// The result of a successful response.
+#[derive(Deserialize)]
+struct ResponseResult<'a> {
+    #[serde(borrow)]
+    result: &'a RawValue,
+}
+
+/// The block number fields of a result.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResultBlock {
+    number: Option<U64>,
+    block_number: Option<U64>,
+}
+
+/// A block parameter that refers to an exact block.
+#[derive(Debug, PartialEq, Eq)]
+enum BlockParam {
+    Number(BlockNumber),
+    Hash(B256),
+}
+
+/// Parses a block parameter, block tags are ignored.
+///
+/// Accepts block numbers, block hashes and `{"blockNumber": ..}` or `{"blockHash": ..}` objects.
+fn block_param(param: &Value) -> Option<BlockParam> {
+    match param {
+        Value::Number(number) => number.as_u64().map(BlockParam::Number),
+        Value::String(s) if s.len() == 66 => s.parse().ok().map(BlockParam::Hash),
+        Value::String(s) => {
+            u64::from_str_radix(s.strip_prefix("0x")?, 16).ok().map(BlockParam::Number)
+        }
+        Value::Object(object) => {
+            object.get("blockNumber").or_else(|| object.get("blockHash")).and_then(block_param)
+        }
+        _ => None,
+    }
+}
+
+/// Parses the positional parameters of a call and returns them together with their canonical
+/// form that is used as cache key.
+///
+/// Trailing `null` parameters are removed and hex strings are lowercased. Returns `None` for
+/// named parameters.
+fn canonicalize_params(params: Option<&str>) -> Option<(Vec<Value>, String)> {
+    let params = match params {
+        Some(params) => serde_json::from_str::<Vec<Value>>(params).ok()?,
+        None => Vec::new(),
+    };
+    canonicalize_param_values(params)
+}
+
+/// Like [canonicalize_params], for parsed parameters.
+fn canonicalize_param_values(mut params: Vec<Value>) -> Option<(Vec<Value>, String)> {
+    while params.last().map_or(false, Value::is_null) {
+        params.pop();
+    }
+    params.iter_mut().for_each(canonicalize);
+    let key = serde_json::to_string(&params).ok()?;
+    Some((params, key))
+}
+
+fn canonicalize(value: &mut Value) {
+    match value {
+        Value::String(s) if s.starts_with("0x") => s.make_ascii_lowercase(),
+        Value::Array(values) => values.iter_mut().for_each(canonicalize),
+        Value::Object(object) => object.values_mut().for_each(canonicalize),
+        _ => {}
+    }
+}
+
+/// A [RpcServiceT] middleware that answers calls from the [ResponseCache].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ResponseCacheLayer {
+    cache: Option<ResponseCache>,
+}
+
+impl ResponseCacheLayer {
+    /// Creates a new layer, responses are not cached if no cache is given.
+    pub(crate) fn new(cache: Option<ResponseCache>) -> Self {
+        Self { cache }
+    }
+}
+
+impl<S> Layer<S> for ResponseCacheLayer {
+    type Service = ResponseCacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ResponseCacheService { inner, cache: self.cache.clone() }
+    }
+}
+
+/// The [RpcServiceT] of the [ResponseCacheLayer].
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCacheService<S> {
+    inner: S,
+    cache: Option<ResponseCache>,
+}
+
+impl<'a, S> RpcServiceT<'a> for ResponseCacheService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+    S::Future: Send + 'a,
+{
+    type Future = Pin<Box<dyn Future<Output = MethodResponse> + Send + 'a>>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        let Some(((method, source), cache)) =
+            cacheable_method(req.method_name()).zip(self.cache.clone())
+        else {
+            return Box::pin(self.inner.call(req))
+        };
+        let Some((params, key)) = canonicalize_params(req.params().as_str()) else {
+            return Box::pin(self.inner.call(req))
+        };
+
+        if let Some(result) = cache.get(method, &key) {
+            // the response did not exceed the maximum response size when it was cached
+            let response =
+                MethodResponse::response(req.id, ResponsePayload::result(&*result), usize::MAX);
+            return Box::pin(ready(response))
+        }
+
+        let call = self.inner.call(req);
+        Box::pin(async move {
+            let response = call.await;
+            if response.is_success() {
+                cache.on_response(method, source, params, key, response.as_result());
+            }
+            response
+        })
+    }
+}
+
+/// A middleware of the IPC server that answers single calls from the [ResponseCache].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IpcResponseCacheLayer {
+    cache: Option<ResponseCache>,
+}
+
+impl IpcResponseCacheLayer {
+    /// Creates a new layer, responses are not cached if no cache is given.
+    pub(crate) fn new(cache: Option<ResponseCache>) -> Self {
+        Self { cache }
+    }
+}
+
+impl<S> Layer<S> for IpcResponseCacheLayer {
+    type Service = IpcResponseCacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IpcResponseCacheService { inner, cache: self.cache.clone() }
+    }
+}
+
+/// The service of the [IpcResponseCacheLayer].
+///
+/// This handles the raw requests, batches are forwarded.
+#[derive(Debug)]
+pub(crate) struct IpcResponseCacheService<S> {
+    inner: S,
+    cache: Option<ResponseCache>,
+}
+
+impl<S> Service<String> for IpcResponseCacheService<S>
+where
+    S: Service<String, Response = Option<String>>,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Option<String>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: String) -> Self::Future {
+        let Some(cache) = self.cache.clone() else { return Box::pin(self.inner.call(request)) };
+        // invalid requests, batches and notifications are left to the server
+        let Ok(Value::Object(mut call)) = serde_json::from_str::<Value>(&request) else {
+            return Box::pin(self.inner.call(request))
+        };
+        let Some(id) = call.remove("id") else { return Box::pin(self.inner.call(request)) };
+        let Some((method, source)) =
+            call.get("method").and_then(Value::as_str).and_then(cacheable_method)
+        else {
+            return Box::pin(self.inner.call(request))
+        };
+        let params = match call.remove("params") {
+            Some(Value::Array(params)) => Some(params),
+            None | Some(Value::Null) => Some(Vec::new()),
+            Some(_) => None,
+        };
+        let Some((params, key)) = params.and_then(canonicalize_param_values) else {
+            return Box::pin(self.inner.call(request))
+        };
+
+        if let Some(result) = cache.get(method, &key) {
+            let response = format!(r#"{{"jsonrpc":"2.0","id":{id},"result":{}}}"#, result.get());
+            return Box::pin(ready(Ok(Some(response))))
+        }
+
+        let call = self.inner.call(request);
+        Box::pin(async move {
+            let response = call.await?;
+            if let Some(response) = &response {
+                cache.on_response(method, source, params, key, response);
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Metrics of the [ResponseCache], per method.
+#[derive(Metrics, Clone)]
+#[metrics(scope = "rpc_server.response_cache")]
+struct ResponseCacheMetrics {
+    /// The number of calls that were answered from the cache.
+    hits: Counter,
+    /// The number of cacheable calls that were not cached.
+    misses: Counter,
+    /// The size of the cached responses in bytes.
+    cached_bytes: Gauge,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Block, Header};
+    use reth_provider::test_utils::{MockEthProvider, NoopProvider};
+    use serde_json::json;
+
+    fn raw(s: &str) -> Box<RawValue> {
+        RawValue::from_string(s.to_string()).unwrap()
+    }
+
+    #[test]
+    fn canonical_params() {
+        let (params, key) =
+            canonicalize_params(Some(r#"["0xABCD", {"tracer": "callTracer"}, null]"#)).unwrap();
+        assert_eq!(params.len(), 2);
+        assert_eq!(key, r#"["0xabcd",{"tracer":"callTracer"}]"#);
+
+        assert_eq!(canonicalize_params(None).unwrap().1, "[]");
+        assert!(canonicalize_params(Some(r#"{"hash": "0x01"}"#)).is_none());
+    }
+
+    #[test]
+    fn parse_block_param() {
+        assert_eq!(block_param(&Value::from("0x10")), Some(BlockParam::Number(16)));
+        assert_eq!(block_param(&Value::from(16)), Some(BlockParam::Number(16)));
+        assert_eq!(block_param(&Value::from("latest")), None);
+        let hash = B256::with_last_byte(1);
+        assert_eq!(block_param(&Value::from(hash.to_string())), Some(BlockParam::Hash(hash)));
+        assert_eq!(
+            block_param(&serde_json::json!({ "blockNumber": "0x1" })),
+            Some(BlockParam::Number(1))
+        );
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let key = |i: u8| format!("[{i}]");
+        let size = entry_size(&key(0), &raw("1"));
+        let config = ResponseCacheConfig::new(3 * size)
+            .with_method_limits([("eth_getBlockByNumber".to_string(), 2 * size)]);
+        let cache = ResponseCache::new(config, NoopProvider::default());
+
+        // the method limit applies
+        for i in 0..3 {
+            cache.insert("eth_getBlockByNumber", key(i), raw("1"));
+        }
+        assert!(cache.get("eth_getBlockByNumber", &key(0)).is_none());
+        assert!(cache.get("eth_getBlockByNumber", &key(1)).is_some());
+        assert!(cache.get("eth_getBlockByNumber", &key(2)).is_some());
+
+        // the total limit evicts from the largest cache
+        cache.insert("eth_getTransactionReceipt", key(0), raw("22"));
+        assert_eq!(cache.inner.responses.lock().size, 2 * size + 1);
+        assert!(cache.get("eth_getBlockByNumber", &key(1)).is_none());
+        assert!(cache.get("eth_getBlockByNumber", &key(2)).is_some());
+        assert!(cache.get("eth_getTransactionReceipt", &key(0)).is_some());
+
+        // too large for the method
+        cache.insert("eth_getBlockByNumber", key(3), raw(&"1".repeat(2 * size)));
+        assert!(cache.get("eth_getBlockByNumber", &key(3)).is_none());
+    }
+
+    #[test]
+    fn caches_final_blocks() {
+        let provider = MockEthProvider::default();
+        let tip =
+            |number| (B256::with_last_byte(number as u8), Header { number, ..Default::default() });
+        let (hash, header) = tip(100);
+        provider.add_header(hash, header);
+        let block_hash = B256::with_last_byte(1);
+        provider.add_block(
+            block_hash,
+            Block { header: Header { number: 30, ..Default::default() }, ..Default::default() },
+        );
+        let cache = ResponseCache::new(ResponseCacheConfig::new(1 << 20), provider.clone());
+
+        let insert = |method, params: Value| {
+            let (method, source) = cacheable_method(method).unwrap();
+            let (params, key) = canonicalize_param_values(vec![params]).unwrap();
+            cache.insert_if_final(
+                method,
+                source,
+                &params,
+                key.clone(),
+                raw(r#"{"number":"0x24"}"#),
+            );
+            cache.get(method, &key).is_some()
+        };
+
+        // blocks at least the finality depth below the tip are final
+        assert!(insert("eth_getBlockByNumber", json!("0x24")));
+        assert!(!insert("eth_getBlockByNumber", json!("0x25")));
+        assert!(insert("eth_getBlockReceipts", json!(block_hash)));
+        // the block of an unknown hash is not known to be canonical
+        assert!(!insert("eth_getBlockReceipts", json!(B256::with_last_byte(2))));
+        assert!(insert("eth_getBlockByHash", json!(B256::with_last_byte(3))));
+
+        // the block becomes final once the tip advances
+        let (hash, header) = tip(101);
+        provider.add_header(hash, header);
+        assert!(insert("eth_getBlockByNumber", json!("0x25")));
+    }
+
+    #[tokio::test]
+    async fn ipc_answers_from_cache() {
+        let cache = ResponseCache::new(ResponseCacheConfig::new(1 << 20), NoopProvider::default());
+        cache.insert("eth_getBlockByNumber", r#"["0x1"]"#.to_string(), raw(r#"{"number":"0x1"}"#));
+
+        // answers every forwarded call
+        let inner = tower::service_fn(|request: String| {
+            let call = serde_json::from_str::<Value>(&request).unwrap();
+            let response = json!({ "jsonrpc": "2.0", "id": call["id"], "result": "forwarded" });
+            ready(Ok::<_, std::convert::Infallible>(Some(response.to_string())))
+        });
+        let mut ipc = IpcResponseCacheLayer::new(Some(cache)).layer(inner);
+        let mut call = |request: Value| {
+            let response = ipc.call(request.to_string());
+            async move { serde_json::from_str::<Value>(&response.await.unwrap().unwrap()).unwrap() }
+        };
+
+        let response = call(json!({
+            "jsonrpc": "2.0", "id": 7, "method": "eth_getBlockByNumber", "params": ["0x1", null]
+        }))
+        .await;
+        assert_eq!(response, json!({ "jsonrpc": "2.0", "id": 7, "result": { "number": "0x1" } }));
+
+        let response = call(json!({
+            "jsonrpc": "2.0", "id": 8, "method": "eth_getBlockByNumber", "params": ["0x2"]
+        }))
+        .await;
+        assert_eq!(response["result"], "forwarded");
+    }
+}
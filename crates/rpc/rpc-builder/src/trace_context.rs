@@ -0,0 +1,406 @@
+//! Propagation of W3C trace context to the spans of RPC calls.
+//!
+//! If enabled, an `rpc.request` span is created for every HTTP request and WS connection. The span
+//! is linked to the trace of the client with the `traceparent` and `tracestate` headers of the
+//! request, see <https://www.w3.org/TR/trace-context/>.
+//!
+//! Every JSON-RPC call, including every call of a batch, gets an `rpc.call` child span of that
+//! span, which records the method, the size of the params, the index of the call, the status of
+//! the response and the duration of the call.
+//!
+//! The spans are regular [tracing] spans with the `rpc::tracing` target, they are exported by
+//! whichever subscriber is installed.
+//!
+//! Note: calls over IPC are not traced.
+
+use hyper::{HeaderMap, Request as HttpRequest};
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request, MethodResponse};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Instant,
+};
+use tower::{Layer, Service};
+use tracing::{field::Empty, instrument::Instrumented, Instrument, Span};
+
+/// The header that holds the trace context of a request.
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+/// The header that holds the vendor specific trace state of a request.
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// The trace context of a `traceparent` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    /// The id of the trace the request belongs to.
+    pub trace_id: u128,
+    /// The id of the span of the client that sent the request.
+    pub parent_id: u64,
+    /// Whether the client sampled the trace.
+    pub sampled: bool,
+}
+
+impl TraceContext {
+    /// Parses the value of a `traceparent` header.
+    ///
+    /// Returns `None` if the value is invalid, in which case the header must be ignored.
+    pub fn parse(traceparent: &str) -> Option<Self> {
+        let mut fields = traceparent.trim().split('-');
+        let version = fields.next().filter(|field| is_hex(field, 2) && *field != "ff")?;
+        let trace_id = fields.next().filter(|field| is_hex(field, 32))?;
+        let parent_id = fields.next().filter(|field| is_hex(field, 16))?;
+        let flags = fields.next().filter(|field| is_hex(field, 2))?;
+        // later versions may append fields, but version 00 consists of exactly four fields
+        if version == "00" && fields.next().is_some() {
+            return None
+        }
+
+        let trace_id = u128::from_str_radix(trace_id, 16).ok().filter(|id| *id != 0)?;
+        let parent_id = u64::from_str_radix(parent_id, 16).ok().filter(|id| *id != 0)?;
+        let flags = u8::from_str_radix(flags, 16).ok()?;
+        Some(Self { trace_id, parent_id, sampled: flags & 1 == 1 })
+    }
+}
+
+/// Returns true if the field consists of exactly `len` lowercase hex digits.
+fn is_hex(field: &str, len: usize) -> bool {
+    field.len() == len && field.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Returns the span of a HTTP request or WS connection with the given headers.
+fn request_span(headers: &HeaderMap) -> Span {
+    let header = |name| headers.get(name).and_then(|value| value.to_str().ok());
+    let span = tracing::info_span!(
+        target: "rpc::tracing",
+        "rpc.request",
+        trace_id = Empty,
+        parent_id = Empty,
+        sampled = Empty,
+        tracestate = Empty,
+    );
+    if let Some(context) = header(TRACEPARENT_HEADER).and_then(TraceContext::parse) {
+        span.record("trace_id", format!("{:032x}", context.trace_id).as_str());
+        span.record("parent_id", format!("{:016x}", context.parent_id).as_str());
+        span.record("sampled", context.sampled);
+        // the trace state is only meaningful together with a valid trace context
+        if let Some(tracestate) = header(TRACESTATE_HEADER) {
+            span.record("tracestate", tracestate);
+        }
+    }
+    span
+}
+
+/// A HTTP middleware that creates the span of a request from its trace context headers.
+///
+/// The [RpcTracingService] of the request is created in that span, which makes it the parent of
+/// the spans of the calls.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct TraceContextLayer {
+    enabled: bool,
+}
+
+impl TraceContextLayer {
+    /// Creates a new layer, requests are not traced if it's not enabled.
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+impl<S> Layer<S> for TraceContextLayer {
+    type Service = TraceContextService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TraceContextService { inner, enabled: self.enabled }
+    }
+}
+
+/// The HTTP service of the [TraceContextLayer].
+#[derive(Debug, Clone)]
+pub(crate) struct TraceContextService<S> {
+    inner: S,
+    enabled: bool,
+}
+
+impl<S, B> Service<HttpRequest<B>> for TraceContextService<S>
+where
+    S: Service<HttpRequest<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = Instrumented<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: HttpRequest<B>) -> Self::Future {
+        let span = if self.enabled { request_span(req.headers()) } else { Span::none() };
+        span.in_scope(|| self.inner.call(req)).instrument(span)
+    }
+}
+
+/// A [RpcServiceT] middleware that creates a span for every call.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RpcTracingLayer {
+    enabled: bool,
+}
+
+impl RpcTracingLayer {
+    /// Creates a new layer, calls are not traced if it's not enabled.
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+impl<S> Layer<S> for RpcTracingLayer {
+    type Service = RpcTracingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcTracingService {
+            inner,
+            enabled: self.enabled,
+            parent: if self.enabled { Span::current() } else { Span::none() },
+            calls: Default::default(),
+        }
+    }
+}
+
+/// The [RpcServiceT] of the [RpcTracingLayer].
+///
+/// This is created per HTTP request or WS connection, so the index of a call is its index in the
+/// batch of a HTTP request, or in the calls of a WS connection.
+#[derive(Debug, Clone)]
+pub(crate) struct RpcTracingService<S> {
+    inner: S,
+    enabled: bool,
+    /// The span of the HTTP request or WS connection.
+    parent: Span,
+    /// The number of calls so far.
+    calls: Arc<AtomicUsize>,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcTracingService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = TracedCallFuture<S::Future>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if !self.enabled {
+            return TracedCallFuture { fut: self.inner.call(req), span: None, started_at: None }
+        }
+
+        let span = tracing::info_span!(
+            target: "rpc::tracing",
+            parent: &self.parent,
+            "rpc.call",
+            method = %req.method_name(),
+            params_size = req.params().as_str().map_or(0, str::len),
+            index = self.calls.fetch_add(1, Ordering::Relaxed),
+            status = Empty,
+            error_code = Empty,
+            duration_ms = Empty,
+        );
+        let fut = span.in_scope(|| self.inner.call(req));
+        TracedCallFuture { fut, span: Some(span), started_at: Some(Instant::now()) }
+    }
+}
+
+/// Response future of the [RpcTracingService], which records the response in the span of the
+/// call.
+#[pin_project::pin_project]
+pub(crate) struct TracedCallFuture<F> {
+    #[pin]
+    fut: F,
+    /// The span of the call, if it's traced.
+    span: Option<Span>,
+    /// When the call started, if it's traced.
+    started_at: Option<Instant>,
+}
+
+impl<F> std::fmt::Debug for TracedCallFuture<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("TracedCallFuture")
+    }
+}
+
+impl<F: Future<Output = MethodResponse>> Future for TracedCallFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let Some(span) = this.span.as_ref() else { return this.fut.poll(cx) };
+
+        let _enter = span.enter();
+        let res = this.fut.poll(cx);
+        if let Poll::Ready(resp) = &res {
+            if resp.is_success() {
+                span.record("status", "ok");
+            } else {
+                span.record("status", "error");
+                if let Some(code) = resp.as_error_code() {
+                    span.record("error_code", code);
+                }
+            }
+            if let Some(started_at) = this.started_at {
+                span.record("duration_ms", started_at.elapsed().as_secs_f64() * 1000.0);
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::header::HeaderValue;
+    use jsonrpsee::{
+        core::client::ClientT,
+        http_client::HttpClientBuilder,
+        rpc_params,
+        server::{RpcServiceBuilder, Server},
+        RpcModule,
+    };
+    use parking_lot::Mutex;
+    use reth_tracing::tracing_subscriber::{
+        layer::{Context as LayerContext, SubscriberExt},
+        registry::LookupSpan,
+        Layer as SubscriberLayer,
+    };
+    use std::collections::HashMap;
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Subscriber,
+    };
+
+    /// A span recorded by [CapturedSpans].
+    #[derive(Debug)]
+    struct CapturedSpan {
+        id: Id,
+        name: &'static str,
+        parent: Option<Id>,
+        fields: HashMap<&'static str, String>,
+    }
+
+    impl Visit for CapturedSpan {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.fields.insert(field.name(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.fields.insert(field.name(), format!("{value:?}"));
+        }
+    }
+
+    /// A subscriber layer that records all spans with their parents and fields.
+    #[derive(Debug, Clone, Default)]
+    struct CapturedSpans(Arc<Mutex<Vec<CapturedSpan>>>);
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> SubscriberLayer<S> for CapturedSpans {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: LayerContext<'_, S>) {
+            let parent = ctx.span(id).and_then(|span| span.parent()).map(|parent| parent.id());
+            let mut span = CapturedSpan {
+                id: id.clone(),
+                name: attrs.metadata().name(),
+                parent,
+                fields: HashMap::new(),
+            };
+            attrs.record(&mut span);
+            self.0.lock().push(span);
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: LayerContext<'_, S>) {
+            if let Some(span) = self.0.lock().iter_mut().rev().find(|span| span.id == *id) {
+                values.record(span);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_traceparent() {
+        let context =
+            TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01").unwrap();
+        assert_eq!(context.trace_id, 0x4bf92f3577b34da6a3ce929d0e0e4736);
+        assert_eq!(context.parent_id, 0x00f067aa0ba902b7);
+        assert!(context.sampled);
+
+        let context =
+            TraceContext::parse("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00").unwrap();
+        assert!(!context.sampled);
+
+        // later versions may append fields
+        assert!(TraceContext::parse(
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-future"
+        )
+        .is_some());
+    }
+
+    #[test]
+    fn parse_invalid_traceparent() {
+        for traceparent in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-future",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-+bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e473-00f067aa0ba902b7-01",
+        ] {
+            assert_eq!(TraceContext::parse(traceparent), None, "{traceparent}");
+        }
+    }
+
+    #[tokio::test]
+    async fn calls_are_traced_in_the_trace_context_of_the_request() {
+        // the server runs on the current thread, so its spans are recorded by this subscriber
+        let spans = CapturedSpans::default();
+        let _guard = tracing::subscriber::set_default(
+            reth_tracing::tracing_subscriber::registry().with(spans.clone()),
+        );
+
+        let server = Server::builder()
+            .set_http_middleware(tower::ServiceBuilder::new().layer(TraceContextLayer::new(true)))
+            .set_rpc_middleware(RpcServiceBuilder::new().layer(RpcTracingLayer::new(true)))
+            .build("127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = server.local_addr().unwrap();
+        let mut module = RpcModule::new(());
+        module.register_method("eth_chainId", |_, _| "0x1").unwrap();
+        let handle = server.start(module);
+
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let client = HttpClientBuilder::default()
+            .set_headers(HeaderMap::from_iter([
+                (TRACEPARENT_HEADER.parse().unwrap(), HeaderValue::from_static(traceparent)),
+                (TRACESTATE_HEADER.parse().unwrap(), HeaderValue::from_static("congo=t61rcWkgMzE")),
+            ]))
+            .build(format!("http://{addr}"))
+            .unwrap();
+        let chain_id: String = client.request("eth_chainId", rpc_params![]).await.unwrap();
+        assert_eq!(chain_id, "0x1");
+        handle.stop().unwrap();
+
+        let spans = spans.0.lock();
+        let request = spans.iter().find(|span| span.name == "rpc.request").unwrap();
+        assert_eq!(request.fields["trace_id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+        assert_eq!(request.fields["parent_id"], "00f067aa0ba902b7");
+        assert_eq!(request.fields["sampled"], "true");
+        assert_eq!(request.fields["tracestate"], "congo=t61rcWkgMzE");
+
+        // the span of the call is a child of the span of the request
+        let call = spans.iter().find(|span| span.name == "rpc.call").unwrap();
+        assert_eq!(call.parent.as_ref(), Some(&request.id));
+        assert_eq!(call.fields["method"], "eth_chainId");
+        assert_eq!(call.fields["index"], "0");
+        assert_eq!(call.fields["status"], "ok");
+    }
+}
@@ -0,0 +1,158 @@
+//! Reporting of `eth_unsubscribe` calls to the resumable subscriptions.
+//!
+//! A resumable subscription can't tell whether it was closed by an `eth_unsubscribe` call or by a
+//! lost connection, so the servers report the unsubscribe calls before they're handled. Only
+//! subscriptions whose connection was lost are kept dormant.
+//!
+//! See also [ResumableUnsubscribes].
+
+use jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request};
+use reth_rpc::eth::ResumableUnsubscribes;
+use serde_json::Value;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+
+/// The method that unsubscribes `eth_subscribe` subscriptions.
+const UNSUBSCRIBE_METHOD: &str = "eth_unsubscribe";
+
+/// Returns the subscription id of the params of an `eth_unsubscribe` call.
+fn subscription_id(params: &Value) -> Option<String> {
+    match params.get(0)? {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// A [RpcServiceT] middleware that reports the `eth_unsubscribe` calls.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UnsubscribeLayer {
+    unsubscribes: Option<ResumableUnsubscribes>,
+}
+
+impl UnsubscribeLayer {
+    /// Creates a new layer, calls are not reported if no tracker is given.
+    pub(crate) fn new(unsubscribes: Option<ResumableUnsubscribes>) -> Self {
+        Self { unsubscribes }
+    }
+}
+
+impl<S> Layer<S> for UnsubscribeLayer {
+    type Service = UnsubscribeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        UnsubscribeService { inner, unsubscribes: self.unsubscribes.clone() }
+    }
+}
+
+/// The [RpcServiceT] of the [UnsubscribeLayer].
+#[derive(Debug, Clone)]
+pub(crate) struct UnsubscribeService<S> {
+    inner: S,
+    unsubscribes: Option<ResumableUnsubscribes>,
+}
+
+impl<'a, S> RpcServiceT<'a> for UnsubscribeService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync + Clone + 'static,
+{
+    type Future = S::Future;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        if let Some(unsubscribes) = &self.unsubscribes {
+            if req.method_name() == UNSUBSCRIBE_METHOD {
+                let id =
+                    req.params().parse::<Value>().ok().and_then(|params| subscription_id(&params));
+                if let Some(id) = id {
+                    unsubscribes.on_unsubscribe(&id);
+                }
+            }
+        }
+        self.inner.call(req)
+    }
+}
+
+/// A middleware of the IPC server that reports the `eth_unsubscribe` calls.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct IpcUnsubscribeLayer {
+    unsubscribes: Option<ResumableUnsubscribes>,
+}
+
+impl IpcUnsubscribeLayer {
+    /// Creates a new layer, calls are not reported if no tracker is given.
+    pub(crate) fn new(unsubscribes: Option<ResumableUnsubscribes>) -> Self {
+        Self { unsubscribes }
+    }
+}
+
+impl<S> Layer<S> for IpcUnsubscribeLayer {
+    type Service = IpcUnsubscribeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IpcUnsubscribeService { inner, unsubscribes: self.unsubscribes.clone() }
+    }
+}
+
+/// The service of the [IpcUnsubscribeLayer].
+///
+/// This handles the raw requests, which may be batches.
+#[derive(Debug)]
+pub(crate) struct IpcUnsubscribeService<S> {
+    inner: S,
+    unsubscribes: Option<ResumableUnsubscribes>,
+}
+
+impl<S> IpcUnsubscribeService<S> {
+    /// Reports a single call if it's an `eth_unsubscribe` call.
+    fn on_call(unsubscribes: &ResumableUnsubscribes, call: &Value) {
+        if call.get("method").and_then(Value::as_str) != Some(UNSUBSCRIBE_METHOD) {
+            return
+        }
+        if let Some(id) = call.get("params").and_then(subscription_id) {
+            unsubscribes.on_unsubscribe(&id);
+        }
+    }
+}
+
+impl<S> Service<String> for IpcUnsubscribeService<S>
+where
+    S: Service<String>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: String) -> Self::Future {
+        if let Some(unsubscribes) = &self.unsubscribes {
+            // invalid requests are left to the server
+            if request.contains(UNSUBSCRIBE_METHOD) {
+                match serde_json::from_str::<Value>(&request) {
+                    Ok(Value::Array(calls)) => {
+                        calls.iter().for_each(|call| Self::on_call(unsubscribes, call))
+                    }
+                    Ok(call) => Self::on_call(unsubscribes, &call),
+                    Err(_) => {}
+                }
+            }
+        }
+        self.inner.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn unsubscribe_params() {
+        assert_eq!(subscription_id(&json!(["0x1"])), Some("0x1".to_string()));
+        assert_eq!(subscription_id(&json!([1])), Some("1".to_string()));
+        assert_eq!(subscription_id(&json!([])), None);
+        assert_eq!(subscription_id(&json!({ "id": "0x1" })), None);
+    }
+}
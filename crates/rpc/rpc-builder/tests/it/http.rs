@@ -1,6 +1,8 @@
 #![allow(unreachable_pub)]
 //! Standalone http tests
 
+#[cfg(unix)]
+use crate::utils::launch_ipc;
 use crate::utils::{launch_http, launch_http_ws, launch_ws};
 use jsonrpsee::{
     core::{
@@ -13,14 +15,14 @@ use jsonrpsee::{
 };
 use reth_primitives::{
     hex_literal::hex, Address, BlockId, BlockNumberOrTag, Bytes, NodeRecord, TxHash, B256, B64,
-    U256, U64,
+    U128, U256, U64,
 };
 use reth_rpc_api::{
     clients::{AdminApiClient, EthApiClient},
-    DebugApiClient, EthFilterApiClient, NetApiClient, OtterscanClient, TraceApiClient,
-    Web3ApiClient,
+    DebugApiClient, EthFilterApiClient, MinerApiClient, NetApiClient, OtterscanClient,
+    TraceApiClient, TxPoolApiClient, Web3ApiClient,
 };
-use reth_rpc_builder::RethRpcModule;
+use reth_rpc_builder::{RethRpcModule, RpcModuleSelection};
 use reth_rpc_types::{
     trace::filter::TraceFilter, Filter, Index, Log, PendingTransactionFilterKind, RichBlock,
     SyncStatus, Transaction, TransactionReceipt, TransactionRequest,
@@ -39,6 +41,15 @@ fn is_unimplemented(err: jsonrpsee::core::client::Error) -> bool {
     }
 }
 
+fn is_method_not_found(err: jsonrpsee::core::client::Error) -> bool {
+    match err {
+        jsonrpsee::core::client::Error::Call(error_obj) => {
+            error_obj.code() == ErrorCode::MethodNotFound.code()
+        }
+        _ => false,
+    }
+}
+
 async fn test_rpc_call_ok<R>(client: &HttpClient, method_name: &str, params: ArrayParams)
 where
     R: DeserializeOwned,
@@ -143,7 +154,9 @@ where
     AdminApiClient::remove_peer(client, node.into()).await.unwrap();
     AdminApiClient::add_trusted_peer(client, node.into()).await.unwrap();
     AdminApiClient::remove_trusted_peer(client, node.into()).await.unwrap();
+    AdminApiClient::trusted_peers(client).await.unwrap();
     AdminApiClient::node_info(client).await.unwrap();
+    AdminApiClient::known_peers(client).await.unwrap();
 }
 
 async fn test_basic_eth_calls<C>(client: &C)
@@ -228,7 +241,7 @@ where
     DebugApiClient::raw_block(client, block_id).await.unwrap();
     DebugApiClient::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
-    assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
+    assert!(DebugApiClient::bad_blocks(client).await.unwrap().is_empty());
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -258,9 +271,15 @@ where
     TraceApiClient::trace_raw_transaction(client, Bytes::default(), HashSet::default(), None)
         .await
         .unwrap_err();
-    TraceApiClient::trace_call_many(client, vec![], Some(BlockNumberOrTag::Latest.into()))
-        .await
-        .unwrap();
+    TraceApiClient::trace_call_many(
+        client,
+        vec![],
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
     TraceApiClient::replay_transaction(client, B256::default(), HashSet::default())
         .await
         .err()
@@ -534,6 +553,47 @@ async fn test_call_otterscan_functions_http_and_ws() {
     test_basic_otterscan_calls(&client).await;
 }
 
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_call_miner_functions_ipc() {
+    reth_tracing::init_test_tracing();
+
+    let handle = launch_ipc(vec![RethRpcModule::Miner, RethRpcModule::Txpool]).await;
+    let client = handle.ipc_client().await.unwrap();
+
+    let floor = U128::from(1_000_000_000u64);
+    assert!(MinerApiClient::set_gas_price(&client, floor).await.unwrap());
+    let current = TxPoolApiClient::txpool_priority_fee_floor(&client).await.unwrap();
+    assert_eq!(current.floor, Some(floor));
+    assert_eq!(current.manual, Some(floor));
+
+    assert!(MinerApiClient::clear_gas_price(&client).await.unwrap());
+    let current = TxPoolApiClient::txpool_priority_fee_floor(&client).await.unwrap();
+    assert_eq!(current.manual, None);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_miner_functions_not_served_over_http() {
+    reth_tracing::init_test_tracing();
+
+    for modules in [
+        RpcModuleSelection::from([RethRpcModule::Miner, RethRpcModule::Txpool]),
+        RpcModuleSelection::All,
+    ] {
+        let handle = launch_http_ws(modules).await;
+        let floor = U128::from(1_000_000_000u64);
+
+        let client = handle.http_client().unwrap();
+        let err = MinerApiClient::set_gas_price(&client, floor).await.unwrap_err();
+        assert!(is_method_not_found(err));
+        assert!(TxPoolApiClient::txpool_priority_fee_floor(&client).await.is_ok());
+
+        let client = handle.ws_client().await.unwrap();
+        let err = MinerApiClient::clear_gas_price(&client).await.unwrap_err();
+        assert!(is_method_not_found(err));
+    }
+}
+
 // <https://github.com/paradigmxyz/reth/issues/5830>
 #[tokio::test(flavor = "multi_thread")]
 async fn test_eth_logs_args() {
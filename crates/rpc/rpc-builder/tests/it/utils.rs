@@ -57,6 +57,19 @@ pub async fn launch_ws(modules: impl Into<RpcModuleSelection>) -> RpcServerHandl
         .unwrap()
 }
 
+/// Launches a new server with ipc only with the given modules
+pub async fn launch_ipc(modules: impl Into<RpcModuleSelection>) -> RpcServerHandle {
+    let builder = test_rpc_builder();
+    let server = builder.build(TransportRpcModuleConfig::set_ipc(modules));
+    let endpoint = std::env::temp_dir().join(format!("reth-rpc-test-{}.ipc", std::process::id()));
+    server
+        .start_server(
+            RpcServerConfig::ipc(Default::default()).with_ipc_endpoint(endpoint.to_string_lossy()),
+        )
+        .await
+        .unwrap()
+}
+
 /// Launches a new server with http and ws and with the given modules
 pub async fn launch_http_ws(modules: impl Into<RpcModuleSelection>) -> RpcServerHandle {
     let builder = test_rpc_builder();
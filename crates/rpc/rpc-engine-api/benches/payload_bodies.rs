@@ -0,0 +1,67 @@
+#![allow(missing_docs)]
+use criterion::{criterion_group, criterion_main, Criterion};
+use reth_interfaces::test_utils::generators::{self, random_block_range};
+use reth_primitives::{BlockHashOrNumber, StaticFileSegment, B256};
+use reth_provider::{
+    providers::StaticFileWriter, test_utils::create_test_provider_factory, BlockReader,
+};
+use reth_rpc_types_compat::engine::payload::{
+    convert_block_body_to_payload_body_v1, convert_to_payload_body_v1,
+};
+
+/// The number of blocks of the largest allowed `engine_getPayloadBodiesByRange` request.
+const RANGE: u64 = 1024;
+
+/// Compares assembling the payload bodies of a range from full blocks against assembling them
+/// from the transactions and withdrawals only.
+fn payload_bodies_by_range(c: &mut Criterion) {
+    let factory = create_test_provider_factory();
+    let mut rng = generators::rng();
+    let blocks = random_block_range(&mut rng, 0..=RANGE, B256::ZERO, 0..50);
+
+    let provider = factory.provider_rw().unwrap();
+    for block in blocks {
+        provider.insert_historical_block(block.try_seal_with_senders().unwrap(), None).unwrap();
+    }
+    provider
+        .static_file_provider()
+        .latest_writer(StaticFileSegment::Headers)
+        .unwrap()
+        .commit()
+        .unwrap();
+    provider.commit().unwrap();
+
+    let mut group = c.benchmark_group("Payload bodies by range");
+    group.sample_size(10);
+
+    group.bench_function("full blocks", |b| {
+        b.iter(|| {
+            let provider = factory.provider().unwrap();
+            (1..=RANGE)
+                .map(|number| {
+                    provider
+                        .block(BlockHashOrNumber::Number(number))
+                        .unwrap()
+                        .map(convert_to_payload_body_v1)
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("bodies", |b| {
+        b.iter(|| {
+            factory
+                .provider()
+                .unwrap()
+                .block_bodies_range(1..=RANGE, |_, transactions, withdrawals| {
+                    convert_block_body_to_payload_body_v1(transactions, withdrawals)
+                })
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, payload_bodies_by_range);
+criterion_main!(benches);
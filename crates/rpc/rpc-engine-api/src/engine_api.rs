@@ -17,7 +17,8 @@ use reth_rpc_types::engine::{
     TransitionConfiguration, CAPABILITIES,
 };
 use reth_rpc_types_compat::engine::payload::{
-    convert_payload_input_v2_to_payload, convert_to_payload_body_v1,
+    convert_block_body_to_payload_body_v1, convert_payload_input_v2_to_payload,
+    convert_to_payload_body_v1,
 };
 use reth_tasks::TaskSpawner;
 use std::{sync::Arc, time::Instant};
@@ -301,22 +302,18 @@ where
         start: BlockNumber,
         count: u64,
     ) -> EngineApiResult<ExecutionPayloadBodiesV1> {
+        if count > MAX_PAYLOAD_BODIES_LIMIT {
+            return Err(EngineApiError::PayloadRequestTooLarge { len: count })
+        }
+
+        if start == 0 || count == 0 {
+            return Err(EngineApiError::InvalidBodiesRange { start, count })
+        }
+
         let (tx, rx) = oneshot::channel();
         let inner = self.inner.clone();
 
         self.inner.task_spawner.spawn_blocking(Box::pin(async move {
-            if count > MAX_PAYLOAD_BODIES_LIMIT {
-                tx.send(Err(EngineApiError::PayloadRequestTooLarge { len: count })).ok();
-                return;
-            }
-
-            if start == 0 || count == 0 {
-                tx.send(Err(EngineApiError::InvalidBodiesRange { start, count })).ok();
-                return;
-            }
-
-            let mut result = Vec::with_capacity(count as usize);
-
             // -1 so range is inclusive
             let mut end = start.saturating_add(count - 1);
 
@@ -328,18 +325,31 @@ where
                 }
             }
 
-            for num in start..=end {
-                let block_result = inner.provider.block(BlockHashOrNumber::Number(num));
-                match block_result {
-                    Ok(block) => {
-                        result.push(block.map(convert_to_payload_body_v1));
-                    }
-                    Err(err) => {
-                        tx.send(Err(EngineApiError::Internal(Box::new(err)))).ok();
-                        return;
-                    }
-                };
+            // only the transactions and withdrawals of the blocks are read, and converted into
+            // payload bodies one block at a time
+            let bodies = match inner.provider.block_bodies_range(
+                start..=end,
+                |number, transactions, withdrawals| {
+                    (number, convert_block_body_to_payload_body_v1(transactions, withdrawals))
+                },
+            ) {
+                Ok(bodies) => bodies,
+                Err(err) => {
+                    tx.send(Err(EngineApiError::Internal(Box::new(err)))).ok();
+                    return;
+                }
+            };
+
+            // the bodies of unavailable blocks are returned as `null`
+            let mut result = Vec::with_capacity(count as usize);
+            let mut next = start;
+            for (number, body) in bodies {
+                result.extend((next..number).map(|_| None));
+                result.push(Some(body));
+                next = number + 1;
             }
+            result.extend((next..=end).map(|_| None));
+
             tx.send(Ok(result)).ok();
         }));
 
@@ -804,6 +814,27 @@ mod tests {
             assert_eq!(res, expected);
         }
 
+        #[tokio::test]
+        async fn returns_payload_bodies_past_tip() {
+            let mut rng = generators::rng();
+            let (handle, api) = setup_engine_api();
+
+            let blocks = random_block_range(&mut rng, 1..=10, B256::default(), 0..2);
+            handle.provider.extend_blocks(blocks.iter().cloned().map(|b| (b.hash(), b.unseal())));
+
+            // the range is truncated at the tip, without trailing `null`s
+            let expected = blocks[4..]
+                .iter()
+                .cloned()
+                .map(|b| Some(convert_to_payload_body_v1(b.unseal())))
+                .collect::<Vec<_>>();
+            let res = api.get_payload_bodies_by_range(5, 20).await.unwrap();
+            assert_eq!(res, expected);
+
+            let res = api.get_payload_bodies_by_range(11, 5).await.unwrap();
+            assert!(res.is_empty());
+        }
+
         #[tokio::test]
         async fn returns_payload_bodies_with_gaps() {
             let mut rng = generators::rng();
@@ -318,7 +318,7 @@ impl<T: TraceApiClient + Sync> TraceApiExt for T {
     {
         let call_set = calls.into_iter().collect::<Vec<_>>();
         let stream = futures::stream::once(async move {
-            match self.trace_call_many(call_set.clone(), block_id).await {
+            match self.trace_call_many(call_set.clone(), block_id, None, None).await {
                 Ok(results) => Ok((results, call_set)),
                 Err(err) => Err((err, call_set)),
             }
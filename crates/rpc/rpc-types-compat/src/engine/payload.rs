@@ -4,7 +4,8 @@
 use reth_primitives::{
     constants::{EMPTY_OMMER_ROOT_HASH, MAXIMUM_EXTRA_DATA_SIZE, MIN_PROTOCOL_BASE_FEE_U256},
     proofs::{self},
-    Block, Header, SealedBlock, TransactionSigned, UintTryTo, Withdrawal, Withdrawals, B256, U256,
+    Block, Header, SealedBlock, TransactionSigned, TransactionSignedNoHash, UintTryTo, Withdrawal,
+    Withdrawals, B256, U256,
 };
 use reth_rpc_types::engine::{
     payload::{ExecutionPayloadBodyV1, ExecutionPayloadFieldV2, ExecutionPayloadInputV2},
@@ -332,6 +333,22 @@ pub fn convert_to_payload_body_v1(value: Block) -> ExecutionPayloadBodyV1 {
     ExecutionPayloadBodyV1 { transactions: transactions.collect(), withdrawals: withdraw }
 }
 
+/// Converts the transactions and withdrawals of a block to [ExecutionPayloadBodyV1]
+pub fn convert_block_body_to_payload_body_v1(
+    transactions: Vec<TransactionSignedNoHash>,
+    withdrawals: Option<Withdrawals>,
+) -> ExecutionPayloadBodyV1 {
+    let transactions = transactions.into_iter().map(|tx| {
+        let mut out = Vec::new();
+        tx.transaction.encode_with_signature(&tx.signature, &mut out, false);
+        out.into()
+    });
+    let withdraw: Option<Vec<reth_rpc_types::Withdrawal>> = withdrawals.map(|withdrawals| {
+        withdrawals.into_iter().map(convert_withdrawal_to_standalone_withdraw).collect::<Vec<_>>()
+    });
+    ExecutionPayloadBodyV1 { transactions: transactions.collect(), withdrawals: withdraw }
+}
+
 /// Transforms a [SealedBlock] into a [ExecutionPayloadV1]
 pub fn execution_payload_from_sealed_block(value: SealedBlock) -> ExecutionPayloadV1 {
     let transactions = value.raw_transactions();
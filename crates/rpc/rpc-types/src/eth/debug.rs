@@ -0,0 +1,138 @@
+//! Types for the `debug` namespace that are not covered by the geth tracing types.
+
+use alloy_primitives::{Bytes, B256, U64};
+use alloy_rpc_types::RichBlock;
+use alloy_rpc_types_trace::geth::BlockTraceResult;
+use serde::{Deserialize, Serialize};
+
+/// The witness of a block returned by `debug_executionWitness`.
+///
+/// Contains all state that is accessed when executing the block, together with the trie nodes that
+/// prove it against the state root of the parent block, so that the block can be re-executed by a
+/// stateless client.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionWitness {
+    /// The RLP encoded nodes of the state trie and the storage tries that are needed to prove the
+    /// accessed accounts and storage slots, deduplicated.
+    pub state: Vec<Bytes>,
+    /// The bytecodes of the accessed contracts.
+    pub codes: Vec<Bytes>,
+    /// The preimages of the accessed trie keys: each accessed address followed by its accessed
+    /// storage slots.
+    pub keys: Vec<Bytes>,
+    /// The RLP encoded headers of the parent block and of all ancestors down to the oldest block
+    /// whose hash is accessed, starting with the parent.
+    pub headers: Vec<Bytes>,
+}
+
+/// A block that was rejected as invalid by the node, returned by `debug_getBadBlocks`.
+///
+/// The `hash`, `block` and `rlp` fields match the response of geth, the other fields describe why
+/// and when the block was rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BadBlock {
+    /// The hash of the block.
+    pub hash: B256,
+    /// The hash of the parent of the block.
+    pub parent_hash: B256,
+    /// The block, if its body is known and the senders of its transactions can be recovered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block: Option<RichBlock>,
+    /// The RLP encoded block, if its body is known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rlp: Option<Bytes>,
+    /// Why the block was rejected.
+    pub reason: String,
+    /// The unix timestamp in seconds of when the block was first rejected.
+    pub first_seen: u64,
+}
+
+/// The kind of a `debug_subscribe` subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DebugSubscriptionKind {
+    /// Traces the blocks of a range and sends the traces of every block, like `traceChain` of
+    /// geth.
+    TraceChain,
+}
+
+/// A notification of a `traceChain` subscription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TraceChainResult {
+    /// The traces of the transactions of a block, in the format of geth.
+    Block(BlockTraceResult),
+    /// The last notification, which is sent once all blocks of the range are traced.
+    Completed(TraceChainCompleted),
+}
+
+/// The notification that ends a `traceChain` subscription after all blocks are traced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceChainCompleted {
+    /// The block the range starts after, on whose state the first block was traced.
+    pub start_block: U64,
+    /// The last traced block.
+    pub end_block: U64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_execution_witness() {
+        let witness = ExecutionWitness {
+            state: vec![Bytes::from_static(&[0xc0])],
+            codes: vec![Bytes::from_static(&[0x60, 0x00])],
+            keys: vec![Bytes::from_static(&[0x01; 20])],
+            headers: Vec::new(),
+        };
+        let s = serde_json::to_string(&witness).unwrap();
+        assert_eq!(
+            s,
+            r#"{"state":["0xc0"],"codes":["0x6000"],"keys":["0x0101010101010101010101010101010101010101"],"headers":[]}"#
+        );
+        assert_eq!(serde_json::from_str::<ExecutionWitness>(&s).unwrap(), witness);
+    }
+
+    #[test]
+    fn serde_bad_block() {
+        let bad_block = BadBlock {
+            hash: B256::with_last_byte(1),
+            parent_hash: B256::ZERO,
+            block: None,
+            rlp: Some(Bytes::from_static(&[0xc0])),
+            reason: "invalid state root".to_string(),
+            first_seen: 1700000000,
+        };
+        let s = serde_json::to_string(&bad_block).unwrap();
+        assert_eq!(
+            s,
+            r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000001","parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000","rlp":"0xc0","reason":"invalid state root","firstSeen":1700000000}"#
+        );
+        assert_eq!(serde_json::from_str::<BadBlock>(&s).unwrap(), bad_block);
+    }
+
+    #[test]
+    fn serde_trace_chain_result() {
+        let completed = TraceChainResult::Completed(TraceChainCompleted {
+            start_block: U64::from(1),
+            end_block: U64::from(16),
+        });
+        let s = serde_json::to_string(&completed).unwrap();
+        assert_eq!(s, r#"{"startBlock":"0x1","endBlock":"0x10"}"#);
+        let TraceChainResult::Completed(completed) = serde_json::from_str(&s).unwrap() else {
+            panic!("expected completion")
+        };
+        assert_eq!(completed.end_block, U64::from(16));
+
+        let block = r#"{"block":"0x2","hash":"0x0000000000000000000000000000000000000000000000000000000000000002","traces":[]}"#;
+        let TraceChainResult::Block(block) = serde_json::from_str(block).unwrap() else {
+            panic!("expected block traces")
+        };
+        assert_eq!(block.hash, B256::with_last_byte(2));
+        assert!(block.traces.is_empty());
+    }
+}
@@ -1,6 +1,12 @@
 //! Ethereum related types
 
+pub mod debug;
+pub mod simulate;
+pub mod subscription;
 pub mod transaction;
+mod txpool;
+
+pub use txpool::{TxpoolPage, TxpoolPriorityFeeFloor, TxpoolQuery, TxpoolSubPool};
 
 // re-export
 pub use alloy_rpc_types_engine as engine;
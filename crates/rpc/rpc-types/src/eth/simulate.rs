@@ -0,0 +1,165 @@
+//! Types for the `eth_simulateV1` endpoint, see
+//! <https://github.com/ethereum/execution-apis/pull/484>, and the `eth_simulateBundle` endpoint.
+
+use crate::{state::StateOverride, Block, BlockOverrides, Log, TransactionRequest};
+use alloy_primitives::{address, b256, Address, Bytes, B256, U64};
+use serde::{Deserialize, Serialize};
+
+/// The address that emits the synthetic logs of ether transfers if
+/// [`SimulatePayload::trace_transfers`] is set.
+pub const TRANSFER_LOG_EMITTER: Address = address!("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee");
+
+/// The topic of the synthetic logs of ether transfers, the signature of the ERC-20
+/// `Transfer(address,address,uint256)` event.
+pub const TRANSFER_LOG_TOPIC: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+/// The request of `eth_simulateV1`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, in order.
+    pub block_state_calls: Vec<SimBlock>,
+    /// Whether to add synthetic logs for all ether transfers.
+    #[serde(default)]
+    pub trace_transfers: bool,
+    /// Whether to validate the calls like transactions of a real block: nonces, balances and the
+    /// base fee are checked.
+    #[serde(default)]
+    pub validation: bool,
+    /// Whether to return the full transactions of the simulated blocks instead of their hashes.
+    #[serde(default)]
+    pub return_full_transactions: bool,
+}
+
+/// A block of `eth_simulateV1` calls.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBlock {
+    /// Overrides of the block environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// Overrides of the state, applied before the first call of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+    /// The calls to execute in the block, in order.
+    #[serde(default)]
+    pub calls: Vec<TransactionRequest>,
+}
+
+/// A block simulated by `eth_simulateV1` together with the results of its calls.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedBlock {
+    /// The simulated block.
+    #[serde(flatten)]
+    pub inner: Block,
+    /// The results of the calls of the block, in order.
+    pub calls: Vec<SimCallResult>,
+}
+
+/// The result of a call of `eth_simulateV1`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimCallResult {
+    /// The data returned by the call.
+    pub return_data: Bytes,
+    /// The logs emitted by the call, empty if the call failed.
+    pub logs: Vec<Log>,
+    /// The gas used by the call.
+    pub gas_used: U64,
+    /// `1` if the call succeeded, `0` otherwise.
+    pub status: U64,
+    /// The error of the call if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulateError>,
+}
+
+/// The error of a failed `eth_simulateV1` call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulateError {
+    /// The error code.
+    pub code: i32,
+    /// The error message.
+    pub message: String,
+}
+
+/// The result of a call of `eth_simulateBundle`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCall {
+    /// The data returned by the call, or the revert data if the call reverted.
+    pub return_data: Bytes,
+    /// The gas used by the call.
+    pub gas_used: U64,
+    /// `1` if the call succeeded, `0` otherwise.
+    pub status: U64,
+    /// The reason string of the revert, if the call reverted with one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    /// The error of the call if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+
+    #[test]
+    fn serde_simulate_payload() {
+        let s = r#"{
+            "blockStateCalls": [
+                {
+                    "blockOverrides": { "number": "0x10", "baseFee": "0x1" },
+                    "stateOverrides": {
+                        "0xc000000000000000000000000000000000000000": { "balance": "0x3e8" }
+                    },
+                    "calls": [
+                        {
+                            "from": "0xc000000000000000000000000000000000000000",
+                            "to": "0xc100000000000000000000000000000000000000",
+                            "value": "0x3e8"
+                        }
+                    ]
+                },
+                {}
+            ],
+            "traceTransfers": true
+        }"#;
+        let payload = serde_json::from_str::<SimulatePayload>(s).unwrap();
+        assert_eq!(payload.block_state_calls.len(), 2);
+        assert!(payload.trace_transfers);
+        assert!(!payload.validation);
+        assert!(!payload.return_full_transactions);
+
+        let block = &payload.block_state_calls[0];
+        assert_eq!(block.block_overrides.as_ref().unwrap().base_fee, Some(U256::from(1)));
+        assert_eq!(block.state_overrides.as_ref().unwrap().len(), 1);
+        assert_eq!(block.calls.len(), 1);
+        assert_eq!(payload.block_state_calls[1], SimBlock::default());
+
+        let s = serde_json::to_string(&payload).unwrap();
+        assert_eq!(serde_json::from_str::<SimulatePayload>(&s).unwrap(), payload);
+    }
+
+    #[test]
+    fn serde_simulated_call() {
+        let call = SimulatedCall {
+            return_data: Bytes::from_static(&[0xab]),
+            gas_used: U64::from(21_000),
+            status: U64::ZERO,
+            revert_reason: Some("not allowed".to_string()),
+            error: Some("execution reverted: not allowed".to_string()),
+        };
+        let s = serde_json::to_string(&call).unwrap();
+        assert_eq!(
+            s,
+            r#"{"returnData":"0xab","gasUsed":"0x5208","status":"0x0","revertReason":"not allowed","error":"execution reverted: not allowed"}"#
+        );
+        assert_eq!(serde_json::from_str::<SimulatedCall>(&s).unwrap(), call);
+
+        let s = r#"{"returnData":"0x","gasUsed":"0x5208","status":"0x1"}"#;
+        assert_eq!(serde_json::from_str::<SimulatedCall>(s).unwrap().error, None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Reth specific `eth_subscribe` subscriptions.
+
+use crate::pubsub::SubscriptionKind;
+use alloy_primitives::{TxHash, B256};
+use serde::{Deserialize, Serialize};
+
+/// Subscription kinds that are only supported by reth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RethSubscriptionKind {
+    /// Transactions that were dropped from the pool or whose block was reorged out.
+    ///
+    /// Yields [`DroppedTransaction`]s.
+    DroppedTransactions,
+}
+
+/// The kind of an `eth_subscribe` subscription, either a standard or a reth specific one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AnySubscriptionKind {
+    /// A standard subscription.
+    Eth(SubscriptionKind),
+    /// A reth specific subscription.
+    Reth(RethSubscriptionKind),
+}
+
+impl From<SubscriptionKind> for AnySubscriptionKind {
+    fn from(kind: SubscriptionKind) -> Self {
+        Self::Eth(kind)
+    }
+}
+
+impl From<RethSubscriptionKind> for AnySubscriptionKind {
+    fn from(kind: RethSubscriptionKind) -> Self {
+        Self::Reth(kind)
+    }
+}
+
+/// Reth specific options of an `eth_subscribe` subscription.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionOptions {
+    /// Whether the subscription can be resumed with `eth_resubscribe` after the connection was
+    /// lost.
+    ///
+    /// The notifications of a resumable subscription that couldn't be delivered are buffered
+    /// until it's resumed, the subscription id is the resumption token.
+    #[serde(default)]
+    pub resumable: bool,
+}
+
+/// A transaction that was dropped, yielded by the
+/// [`RethSubscriptionKind::DroppedTransactions`] subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DroppedTransaction {
+    /// The hash of the transaction.
+    pub transaction_hash: TxHash,
+    /// Why the transaction was dropped.
+    pub reason: DropReason,
+    /// The hash of the block that included the transaction, if it was reorged out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_hash: Option<B256>,
+}
+
+/// The reason a transaction was dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DropReason {
+    /// The transaction was replaced by another transaction with the same sender and nonce.
+    Replaced,
+    /// The transaction was evicted because the pool reached its limits.
+    Underpriced,
+    /// The conditions of the transaction can no longer be satisfied.
+    Expired,
+    /// The transaction became invalid, e.g. its nonce was used by another transaction.
+    Invalid,
+    /// The block that included the transaction is no longer canonical.
+    Reorged,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_subscription_kind() {
+        let kind: AnySubscriptionKind = serde_json::from_str(r#""newHeads""#).unwrap();
+        assert_eq!(kind, SubscriptionKind::NewHeads.into());
+
+        let kind: AnySubscriptionKind = serde_json::from_str(r#""droppedTransactions""#).unwrap();
+        assert_eq!(kind, RethSubscriptionKind::DroppedTransactions.into());
+        assert_eq!(serde_json::to_string(&kind).unwrap(), r#""droppedTransactions""#);
+    }
+
+    #[test]
+    fn serde_subscription_options() {
+        let options: SubscriptionOptions = serde_json::from_str(r#"{"resumable":true}"#).unwrap();
+        assert!(options.resumable);
+        let options: SubscriptionOptions = serde_json::from_str("{}").unwrap();
+        assert!(!options.resumable);
+    }
+
+    #[test]
+    fn serde_dropped_transaction() {
+        let dropped = DroppedTransaction {
+            transaction_hash: TxHash::with_last_byte(1),
+            reason: DropReason::Reorged,
+            block_hash: Some(B256::with_last_byte(2)),
+        };
+        let s = serde_json::to_string(&dropped).unwrap();
+        assert_eq!(
+            s,
+            r#"{"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000001","reason":"reorged","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000002"}"#
+        );
+        assert_eq!(serde_json::from_str::<DroppedTransaction>(&s).unwrap(), dropped);
+    }
+}
@@ -0,0 +1,75 @@
+//! Conditions of transactions submitted via `eth_sendRawTransactionConditional`.
+
+use alloy_primitives::{Address, B256, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The conditions a block must satisfy to include a transaction submitted via
+/// `eth_sendRawTransactionConditional`.
+///
+/// See also <https://notes.ethereum.org/@yoav/SkaX2lS9j>
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionConditional {
+    /// The expected storage of accounts in the parent state of the block.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub known_accounts: HashMap<Address, KnownAccount>,
+    /// The minimum number of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_min: Option<U64>,
+    /// The maximum number of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number_max: Option<U64>,
+    /// The minimum timestamp of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_min: Option<U64>,
+    /// The maximum timestamp of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_max: Option<U64>,
+}
+
+/// The expected storage of an account, either its storage root or the values of storage slots.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KnownAccount {
+    /// The expected root of the storage trie of the account.
+    StorageRoot(B256),
+    /// The expected values of storage slots of the account.
+    StorageSlots(HashMap<B256, B256>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256};
+
+    #[test]
+    fn serde_transaction_conditional() {
+        let s = r#"{"knownAccounts":{"0x000000000000000000000000000000000000dead":{"0x0000000000000000000000000000000000000000000000000000000000000001":"0x0000000000000000000000000000000000000000000000000000000000000002"}},"blockNumberMax":"0x64","timestampMin":"0x10"}"#;
+        let conditional = serde_json::from_str::<TransactionConditional>(s).unwrap();
+        assert_eq!(
+            conditional.known_accounts[&address!("000000000000000000000000000000000000dead")],
+            KnownAccount::StorageSlots(HashMap::from([(
+                b256!("0000000000000000000000000000000000000000000000000000000000000001"),
+                b256!("0000000000000000000000000000000000000000000000000000000000000002")
+            )]))
+        );
+        assert_eq!(conditional.block_number_max, Some(U64::from(100)));
+        assert_eq!(conditional.timestamp_min, Some(U64::from(16)));
+        assert_eq!(conditional.block_number_min, None);
+        assert_eq!(serde_json::to_string(&conditional).unwrap(), s);
+    }
+
+    #[test]
+    fn serde_known_account_storage_root() {
+        let s = r#"{"knownAccounts":{"0x000000000000000000000000000000000000dead":"0x0000000000000000000000000000000000000000000000000000000000000001"}}"#;
+        let conditional = serde_json::from_str::<TransactionConditional>(s).unwrap();
+        assert_eq!(
+            conditional.known_accounts[&address!("000000000000000000000000000000000000dead")],
+            KnownAccount::StorageRoot(b256!(
+                "0000000000000000000000000000000000000000000000000000000000000001"
+            ))
+        );
+        assert_eq!(serde_json::to_string(&conditional).unwrap(), s);
+    }
+}
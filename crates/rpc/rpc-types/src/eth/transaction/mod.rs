@@ -1,5 +1,7 @@
 //! RPC types for transactions
+mod conditional;
 mod request;
 mod typed;
+pub use conditional::{KnownAccount, TransactionConditional};
 pub use request::TransactionRequest;
 pub use typed::*;
@@ -0,0 +1,88 @@
+//! Types for the paginated and the priority fee floor `txpool` namespace endpoints.
+
+use alloy_primitives::{U128, U64};
+use serde::{Deserialize, Serialize};
+
+/// A sub-pool of the transaction pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxpoolSubPool {
+    /// Transactions that are ready for inclusion in the next block.
+    Pending,
+    /// Transactions that don't meet the base fee of the next block.
+    BaseFee,
+    /// Blob transactions that are not pending.
+    Blob,
+    /// Transactions with a nonce gap or insufficient funds.
+    Queued,
+}
+
+/// Filters and pagination of the transactions returned by the paginated `txpool` endpoints.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolQuery {
+    /// Continue after the last transaction of a previous page, see [`TxpoolPage::next_cursor`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+    /// The maximum number of transactions in the page, capped by the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<U64>,
+    /// Only include transactions whose effective tip at the next block's base fee is at least
+    /// this value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_tip: Option<U128>,
+    /// Only include transactions of this type.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_type: Option<U64>,
+    /// Only include transactions in this sub-pool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub_pool: Option<TxpoolSubPool>,
+}
+
+/// A page of the transactions returned by the paginated `txpool` endpoints.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolPage<T> {
+    /// The transactions of the page.
+    pub content: T,
+    /// The cursor to query the next page with, or `None` if this is the last page.
+    pub next_cursor: Option<String>,
+}
+
+/// The minimum effective tip in wei a new transaction must pay at the base fee of the pending
+/// block to be accepted into the pool, returned by `txpool_priorityFeeFloor`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolPriorityFeeFloor {
+    /// The floor that's enforced, or `None` if any tip is accepted.
+    pub floor: Option<U128>,
+    /// The configured minimum priority fee.
+    pub minimum: Option<U128>,
+    /// The floor adjusted to the fullness of recent blocks, if enabled.
+    pub dynamic: Option<U128>,
+    /// The floor set with `miner_setGasPrice`, which replaces the other floors.
+    pub manual: Option<U128>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_txpool_query() {
+        let s = r#"{"cursor":"3-7","limit":"0x64","minTip":"0x3b9aca00","subPool":"baseFee"}"#;
+        let query = serde_json::from_str::<TxpoolQuery>(s).unwrap();
+        assert_eq!(
+            query,
+            TxpoolQuery {
+                cursor: Some("3-7".to_string()),
+                limit: Some(U64::from(100)),
+                min_tip: Some(U128::from(1_000_000_000)),
+                tx_type: None,
+                sub_pool: Some(TxpoolSubPool::BaseFee),
+            }
+        );
+        assert_eq!(serde_json::to_string(&query).unwrap(), s);
+        assert_eq!(serde_json::from_str::<TxpoolQuery>("{}").unwrap(), TxpoolQuery::default());
+    }
+}
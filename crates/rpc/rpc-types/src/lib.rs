@@ -15,6 +15,7 @@ mod mev;
 mod net;
 mod peer;
 pub mod relay;
+mod reload;
 mod rpc;
 
 // re-export for convenience
@@ -29,14 +30,19 @@ pub mod trace {
 }
 // Ethereum specific rpc types related to typed transaction requests and the engine API.
 pub use eth::{
-    engine,
+    debug, engine,
     engine::{
         ExecutionPayload, ExecutionPayloadV1, ExecutionPayloadV2, ExecutionPayloadV3, PayloadError,
     },
-    transaction::{self, TransactionKind, TransactionRequest, TypedTransactionRequest},
+    simulate, subscription,
+    transaction::{
+        self, TransactionConditional, TransactionKind, TransactionRequest, TypedTransactionRequest,
+    },
+    TxpoolPage, TxpoolPriorityFeeFloor, TxpoolQuery, TxpoolSubPool,
 };
 
 pub use mev::*;
 pub use net::*;
 pub use peer::*;
+pub use reload::*;
 pub use rpc::*;
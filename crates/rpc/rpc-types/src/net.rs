@@ -6,6 +6,7 @@ use secp256k1::{SecretKey, SECP256K1};
 use serde::{Deserialize, Serialize};
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
+    collections::BTreeMap,
     fmt,
     fmt::Write,
     net::{IpAddr, Ipv4Addr, SocketAddr},
@@ -26,6 +27,74 @@ pub struct NetworkStatus {
     pub eth_protocol_info: EthProtocolInfo,
 }
 
+/// The state of the connection to a trusted peer, see [`TrustedPeerInfo`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PeerConnectionStatus {
+    /// There's no connection to the peer.
+    Disconnected,
+    /// An outgoing connection to the peer is being established.
+    Dialing,
+    /// Connected via a connection the peer initiated.
+    Inbound,
+    /// Connected via a connection to the peer.
+    Outbound,
+    /// The peer is banned or backed off, it's dialed again once the ban or backoff expired.
+    BackedOff,
+}
+
+/// Represents a trusted peer in the `admin_trustedPeers` response.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrustedPeerInfo {
+    /// The ID of the peer.
+    pub id: PeerId,
+    /// The enode URL of the peer, if its address is known.
+    pub enode: Option<NodeRecord>,
+    /// The state of the connection to the peer.
+    pub status: PeerConnectionStatus,
+}
+
+/// The number of messages of a single type exchanged with a peer, see [`PeerStats`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageStats {
+    /// The number of messages received from the peer.
+    pub messages_in: u64,
+    /// The number of bytes received from the peer.
+    pub bytes_in: u64,
+    /// The number of messages sent to the peer.
+    pub messages_out: u64,
+    /// The number of bytes sent to the peer.
+    pub bytes_out: u64,
+}
+
+/// Represents a connected peer in the `reth_peerStats` response.
+///
+/// The byte counts are the sizes of the RLP encoded `eth` messages, before compression.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerStats {
+    /// The ID of the peer.
+    pub id: PeerId,
+    /// The client's name and version.
+    pub name: String,
+    /// The number of bytes received from the peer.
+    pub bytes_in: u64,
+    /// The number of bytes sent to the peer.
+    pub bytes_out: u64,
+    /// The number of requests of the peer that were answered.
+    pub requests_served: u64,
+    /// The number of requests to the peer that timed out.
+    pub requests_timed_out: u64,
+    /// The average time the peer took to respond to a request, in milliseconds.
+    ///
+    /// This is `None` if the peer didn't respond to any request yet.
+    pub avg_response_latency_ms: Option<u64>,
+    /// The messages exchanged with the peer by message type.
+    pub messages: BTreeMap<String, MessageStats>,
+}
+
 /// Represents a ENR in discovery.
 ///
 /// Note: this is only an excerpt of the [`NodeRecord`] data structure.
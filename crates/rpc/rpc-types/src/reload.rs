@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// The outcome of reloading the configuration file of the node.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReloadReport {
+    /// The path of the configuration file that was reloaded.
+    pub path: String,
+    /// Every setting that differs from the active configuration.
+    pub fields: Vec<ConfigFieldChange>,
+}
+
+impl ConfigReloadReport {
+    /// Returns true if every changed setting was applied.
+    pub fn is_fully_applied(&self) -> bool {
+        self.fields.iter().all(|field| field.accepted)
+    }
+}
+
+/// A setting of the configuration file that differs from the active configuration.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFieldChange {
+    /// The dotted path of the setting in the configuration file, e.g. `txpool.max_local_txs`.
+    pub field: String,
+    /// Whether the new value was applied to the running node.
+    pub accepted: bool,
+    /// Why the new value was not applied.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl ConfigFieldChange {
+    /// Creates an applied change of the setting.
+    pub fn accepted(field: impl Into<String>) -> Self {
+        Self { field: field.into(), accepted: true, reason: None }
+    }
+
+    /// Creates a change of the setting that was not applied for the given reason.
+    pub fn rejected(field: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self { field: field.into(), accepted: false, reason: Some(reason.into()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_config_reload_report() {
+        let report = ConfigReloadReport {
+            path: "reth.toml".to_string(),
+            fields: vec![
+                ConfigFieldChange::accepted("txpool.max_local_txs"),
+                ConfigFieldChange::rejected("stages.headers", "requires a restart"),
+            ],
+        };
+        let json = serde_json::to_string(&report).unwrap();
+        assert_eq!(
+            json,
+            r#"{"path":"reth.toml","fields":[{"field":"txpool.max_local_txs","accepted":true},{"field":"stages.headers","accepted":false,"reason":"requires a restart"}]}"#
+        );
+        assert_eq!(serde_json::from_str::<ConfigReloadReport>(&json).unwrap(), report);
+        assert!(!report.is_fully_applied());
+    }
+}
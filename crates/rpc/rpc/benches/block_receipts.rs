@@ -0,0 +1,114 @@
+#![allow(missing_docs)]
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use reth_blockchain_tree::noop::NoopBlockchainTree;
+use reth_evm_ethereum::EthEvmConfig;
+use reth_interfaces::test_utils::generators::{
+    self, random_block, random_receipt, random_signed_tx,
+};
+use reth_network_api::noop::NoopNetwork;
+use reth_primitives::{
+    constants::ETHEREUM_BLOCK_GAS_LIMIT, BlockId, BlockNumberOrTag, Receipts, StaticFileSegment,
+};
+use reth_provider::{
+    providers::{BlockchainProvider, StaticFileWriter},
+    test_utils::create_test_provider_factory,
+    BundleStateWithReceipts, OriginalValuesKnown,
+};
+use reth_rpc::{
+    eth::{
+        cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache, FeeHistoryCacheConfig,
+        DEFAULT_ETH_PROOF_WINDOW,
+    },
+    EthApi,
+};
+use reth_rpc_api::EthApiServer;
+use reth_tasks::pool::BlockingTaskPool;
+use reth_transaction_pool::test_utils::testing_pool;
+use revm::db::BundleState;
+
+/// The numbers of transactions of the benchmarked blocks.
+const TX_COUNTS: [usize; 3] = [50, 300, 1000];
+
+/// Compares fetching all receipts of a block with `eth_getBlockReceipts` against fetching them
+/// one by one with `eth_getTransactionReceipt`.
+fn block_receipts(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("Block receipts");
+    group.sample_size(10);
+
+    for tx_count in TX_COUNTS {
+        let factory = create_test_provider_factory();
+        let mut rng = generators::rng();
+
+        let genesis = random_block(&mut rng, 0, None, Some(0), Some(0));
+        let mut block = random_block(&mut rng, 1, Some(genesis.hash()), Some(0), Some(0));
+        block.body = (0..tx_count).map(|_| random_signed_tx(&mut rng)).collect();
+        let hashes = block.body.iter().map(|tx| tx.hash()).collect::<Vec<_>>();
+        let receipts =
+            block.body.iter().map(|tx| random_receipt(&mut rng, tx, Some(2))).collect::<Vec<_>>();
+
+        let provider = factory.provider_rw().unwrap();
+        for block in [genesis, block] {
+            provider.insert_historical_block(block.try_seal_with_senders().unwrap(), None).unwrap();
+        }
+        BundleStateWithReceipts::new(
+            BundleState::default(),
+            Receipts::from_block_receipt(receipts),
+            1,
+        )
+        .write_to_storage(provider.tx_ref(), None, OriginalValuesKnown::Yes)
+        .unwrap();
+        provider
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+        provider.commit().unwrap();
+
+        let eth_api = runtime.block_on(async {
+            let provider = BlockchainProvider::new(factory, NoopBlockchainTree::default()).unwrap();
+            let evm_config = EthEvmConfig::default();
+            let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+            let fee_history_cache =
+                FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
+            EthApi::new(
+                provider.clone(),
+                testing_pool(),
+                NoopNetwork::default(),
+                cache.clone(),
+                GasPriceOracle::new(provider, Default::default(), cache),
+                ETHEREUM_BLOCK_GAS_LIMIT,
+                DEFAULT_ETH_PROOF_WINDOW,
+                BlockingTaskPool::build().unwrap(),
+                fee_history_cache,
+                evm_config,
+                None,
+            )
+        });
+        let block_id = BlockId::Number(BlockNumberOrTag::Number(1));
+
+        group.bench_with_input(BenchmarkId::new("block", tx_count), &block_id, |b, block_id| {
+            b.iter(|| runtime.block_on(EthApiServer::block_receipts(&eth_api, *block_id)).unwrap())
+        });
+
+        group.bench_with_input(BenchmarkId::new("transactions", tx_count), &hashes, |b, hashes| {
+            b.iter(|| {
+                runtime.block_on(async {
+                    let mut receipts = Vec::with_capacity(hashes.len());
+                    for hash in hashes {
+                        receipts.push(
+                            EthApiServer::transaction_receipt(&eth_api, *hash).await.unwrap(),
+                        );
+                    }
+                    receipts
+                })
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, block_receipts);
+criterion_main!(benches);
@@ -2,12 +2,14 @@ use crate::result::ToRpcResult;
 use alloy_primitives::B256;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
+use reth_interfaces::reload::ConfigReloadHandle;
 use reth_network_api::{NetworkInfo, PeerKind, Peers};
 use reth_primitives::{AnyNode, ChainSpec, NodeRecord};
-use reth_rpc_api::AdminApiServer;
+use reth_rpc_api::{AdminApiServer, AdminConfigApiServer};
 use reth_rpc_types::{
     admin::{EthProtocolInfo, NodeInfo, Ports, ProtocolInfo},
-    PeerEthProtocolInfo, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
+    ConfigReloadReport, PeerEthProtocolInfo, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
+    TrustedPeerInfo,
 };
 use std::sync::Arc;
 
@@ -60,6 +62,11 @@ where
         Ok(true)
     }
 
+    /// Handler for `admin_trustedPeers`
+    async fn trusted_peers(&self) -> RpcResult<Vec<TrustedPeerInfo>> {
+        self.network.get_trusted_peers().await.to_rpc_result()
+    }
+
     async fn peers(&self) -> RpcResult<Vec<PeerInfo>> {
         let peers = self.network.get_all_peers().await.to_rpc_result()?;
         let peers = peers
@@ -89,6 +96,27 @@ where
         Ok(peers)
     }
 
+    /// Handler for `admin_knownPeers`
+    async fn known_peers(&self) -> RpcResult<Vec<PeerInfo>> {
+        let peers = self.network.get_known_peers().await.to_rpc_result()?;
+        let local_address = self.network.local_addr().to_string();
+        let peers = peers
+            .into_iter()
+            .map(|peer| PeerInfo {
+                id: Some(peer.record.id.to_string()),
+                name: peer.client_version.unwrap_or_default(),
+                caps: peer.capabilities.iter().map(|cap| cap.to_string()).collect(),
+                network: PeerNetworkInfo {
+                    remote_address: peer.record.tcp_addr().to_string(),
+                    local_address: local_address.clone(),
+                },
+                protocols: PeerProtocolsInfo { eth: None, pip: None },
+            })
+            .collect();
+
+        Ok(peers)
+    }
+
     /// Handler for `admin_nodeInfo`
     async fn node_info(&self) -> RpcResult<NodeInfo> {
         let enode = self.network.local_node_record();
@@ -132,3 +160,27 @@ impl<N> std::fmt::Debug for AdminApi<N> {
         f.debug_struct("AdminApi").finish_non_exhaustive()
     }
 }
+
+/// `admin` API implementation for reloading the configuration of the node.
+///
+/// The reload is performed by the task that owns the active configuration, see
+/// [ConfigReloadHandle].
+#[derive(Debug, Clone)]
+pub struct AdminConfigApi {
+    reload: ConfigReloadHandle,
+}
+
+impl AdminConfigApi {
+    /// Creates a new instance of `AdminConfigApi`.
+    pub fn new(reload: ConfigReloadHandle) -> Self {
+        Self { reload }
+    }
+}
+
+#[async_trait]
+impl AdminConfigApiServer for AdminConfigApi {
+    /// Handler for `admin_reloadConfig`
+    async fn reload_config(&self) -> RpcResult<ConfigReloadReport> {
+        self.reload.reload().await.to_rpc_result()
+    }
+}
@@ -5,53 +5,86 @@ use crate::{
         EthTransactions,
     },
     result::{internal_rpc_err, ToRpcResult},
+    witness::{AccessedState, RecordingStateProvider},
     EthApiSpec,
 };
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::{core::RpcResult, server::SubscriptionMessage, PendingSubscriptionSink};
+use parking_lot::Mutex;
+use reth_interfaces::invalid_block::InvalidBlocksHandle;
 use reth_primitives::{
     revm::env::tx_env_with_recovered, Address, Block, BlockId, BlockNumberOrTag, Bytes,
-    TransactionSignedEcRecovered, Withdrawals, B256, U256,
+    TransactionSignedEcRecovered, Withdrawals, B256, U256, U64,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, HeaderProvider, StateProviderBox, TransactionVariant,
+    BlockReaderIdExt, ChainSpecProvider, HeaderProvider, StateProvider, StateProviderBox,
+    TransactionVariant,
+};
+use reth_revm::{
+    database::{StateProviderDatabase, SubState},
+    state_change::post_block_balance_changes,
 };
-use reth_revm::database::{StateProviderDatabase, SubState};
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_types::{
+    debug::{
+        BadBlock, DebugSubscriptionKind, ExecutionWitness, TraceChainCompleted, TraceChainResult,
+    },
     trace::geth::{
         BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
         GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
     },
-    BlockError, Bundle, RichBlock, StateContext, TransactionRequest,
+    BlockError, BlockTransactionsKind, Bundle, StateContext, TransactionRequest,
 };
+use reth_rpc_types_compat::block::from_block;
 use reth_tasks::pool::BlockingTaskGuard;
 use revm::{
     db::CacheDB,
-    primitives::{db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg},
+    primitives::{
+        db::DatabaseCommit, Account, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg,
+        HashMap,
+    },
+    Database,
 };
 use revm_inspectors::tracing::{
     js::{JsInspector, TransactionContext},
     FourByteInspector, MuxInspector, TracingInspector, TracingInspectorConfig,
 };
-use std::sync::Arc;
+use std::{collections::BTreeSet, sync::Arc};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
+/// The default maximum number of blocks that can be traced by a single `traceChain` subscription.
+pub const DEFAULT_MAX_TRACE_CHAIN_BLOCKS: u64 = 1000;
+
 /// `debug` API implementation.
 ///
 /// This type provides the functionality for handling `debug` related requests.
 pub struct DebugApi<Provider, Eth> {
     inner: Arc<DebugApiInner<Provider, Eth>>,
+    /// The maximum number of blocks that can be traced by a single `traceChain` subscription.
+    max_trace_chain_blocks: u64,
 }
 
 // === impl DebugApi ===
 
 impl<Provider, Eth> DebugApi<Provider, Eth> {
     /// Create a new instance of the [DebugApi]
-    pub fn new(provider: Provider, eth: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        let inner = Arc::new(DebugApiInner { provider, eth_api: eth, blocking_task_guard });
-        Self { inner }
+    pub fn new(
+        provider: Provider,
+        eth: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        invalid_blocks: InvalidBlocksHandle,
+    ) -> Self {
+        let inner =
+            Arc::new(DebugApiInner { provider, eth_api: eth, blocking_task_guard, invalid_blocks });
+        Self { inner, max_trace_chain_blocks: DEFAULT_MAX_TRACE_CHAIN_BLOCKS }
+    }
+
+    /// Sets the maximum number of blocks that can be traced by a single `traceChain`
+    /// subscription.
+    pub fn with_max_trace_chain_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_trace_chain_blocks = max_blocks;
+        self
     }
 
     /// Access the underlying `Eth` API.
@@ -207,6 +240,258 @@ where
         .await
     }
 
+    /// Re-executes the block on top of the state of its parent block and returns the
+    /// [ExecutionWitness] of the block.
+    ///
+    /// All state that is read during execution is recorded and then proven against the state root
+    /// of the parent block.
+    pub async fn debug_execution_witness(
+        &self,
+        block_id: BlockNumberOrTag,
+    ) -> EthResult<ExecutionWitness> {
+        let block = self
+            .inner
+            .eth_api
+            .block_by_id_with_senders(block_id.into())
+            .await?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let total_difficulty = self
+            .inner
+            .provider
+            .header_td_by_number(block.number)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(block.parent_hash.into(), move |state| {
+                let parent_number = block.number.saturating_sub(1);
+                let accessed = Mutex::new(AccessedState::default());
+                this.eth_api().execute_block(
+                    RecordingStateProvider::new(&*state, &accessed),
+                    &block.unseal(),
+                    total_difficulty,
+                )?;
+                let accessed = accessed.into_inner();
+
+                let targets = accessed
+                    .accounts
+                    .into_iter()
+                    .map(|(address, slots)| (address, slots.into_iter().collect::<Vec<_>>()))
+                    .collect::<Vec<_>>();
+                let mut nodes = BTreeSet::new();
+                for proof in state.proofs(&targets)? {
+                    nodes.extend(proof.proof);
+                    for storage_proof in proof.storage_proofs {
+                        nodes.extend(storage_proof.proof);
+                    }
+                }
+
+                let mut keys = Vec::new();
+                for (address, slots) in targets {
+                    keys.push(Bytes::copy_from_slice(address.as_slice()));
+                    keys.extend(slots.iter().map(|slot| Bytes::copy_from_slice(slot.as_slice())));
+                }
+
+                let mut codes = Vec::with_capacity(accessed.codes.len());
+                for code_hash in accessed.codes {
+                    if let Some(code) = state.bytecode_by_hash(code_hash)? {
+                        codes.push(code.original_bytes());
+                    }
+                }
+
+                // the parent header commits to the pre-state root and the headers of older blocks
+                // are needed to verify the accessed block hashes
+                let oldest = accessed
+                    .oldest_block_hash
+                    .map_or(parent_number, |oldest| oldest.min(parent_number));
+                let headers = this
+                    .inner
+                    .provider
+                    .headers_range(oldest..=parent_number)?
+                    .iter()
+                    .rev()
+                    .map(|header| alloy_rlp::encode(header).into())
+                    .collect();
+
+                Ok(ExecutionWitness { state: nodes.into_iter().collect(), codes, keys, headers })
+            })
+            .await
+    }
+
+    /// Traces the blocks after `start_block` up to and including `end_block`, and sends the traces
+    /// of every block to the subscription, followed by a [TraceChainResult::Completed]
+    /// notification.
+    ///
+    /// The blocks are executed one after another, every block on top of the post-state of the
+    /// previous block, so the state that's loaded by a block is reused by the following blocks.
+    /// Tracing stops once the client unsubscribes or disconnects.
+    async fn trace_chain(
+        &self,
+        pending: PendingSubscriptionSink,
+        start_block: BlockNumberOrTag,
+        end_block: BlockNumberOrTag,
+        opts: GethDebugTracingOptions,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let (start, end) = match self.trace_chain_range(start_block, end_block) {
+            Ok(range) => range,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(())
+            }
+        };
+
+        let sink = pending.accept().await?;
+
+        let mut db = None;
+        for number in start + 1..=end {
+            // the permit is only held while the block is traced and released before its traces are
+            // sent, so a slow subscriber doesn't hold up other tracing requests
+            let permit = self.acquire_trace_permit().await?;
+            let (traces, state) = tokio::select! {
+                _ = sink.closed() => {
+                    // connection dropped
+                    return Ok(())
+                }
+                res = self.trace_chain_block(number, opts.clone(), db.take()) => res?,
+            };
+            drop(permit);
+            db = Some(state);
+
+            let msg = SubscriptionMessage::from_json(&TraceChainResult::Block(traces))?;
+            if sink.send(msg).await.is_err() {
+                return Ok(())
+            }
+        }
+
+        let completed =
+            TraceChainCompleted { start_block: U64::from(start), end_block: U64::from(end) };
+        let msg = SubscriptionMessage::from_json(&TraceChainResult::Completed(completed))?;
+        let _ = sink.send(msg).await;
+        Ok(())
+    }
+
+    /// Returns the numbers of the block the range of a `traceChain` subscription starts after and
+    /// of the last block of the range.
+    fn trace_chain_range(
+        &self,
+        start_block: BlockNumberOrTag,
+        end_block: BlockNumberOrTag,
+    ) -> EthResult<(u64, u64)> {
+        let start = self
+            .inner
+            .provider
+            .convert_block_number(start_block)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let end = self
+            .inner
+            .provider
+            .convert_block_number(end_block)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+
+        if start >= end {
+            return Err(EthApiError::InvalidParams(format!(
+                "end block ({end}) needs to come after start block ({start})"
+            )))
+        }
+        // ensure that the range is not too large, since all blocks of the range are executed
+        if end - start > self.max_trace_chain_blocks {
+            return Err(EthApiError::InvalidParams(format!(
+                "Block range too large; currently limited to {} blocks",
+                self.max_trace_chain_blocks
+            )))
+        }
+        Ok((start, end))
+    }
+
+    /// Traces all transactions of the block on top of the given post-state of its parent block,
+    /// and returns the traces together with the post-state of the block.
+    ///
+    /// If there's no post-state of the parent block yet, the block is executed on the state of the
+    /// parent block from the database.
+    async fn trace_chain_block(
+        &self,
+        number: u64,
+        opts: GethDebugTracingOptions,
+        db: Option<SubState<StateProviderBox>>,
+    ) -> EthResult<(BlockTraceResult, SubState<StateProviderBox>)> {
+        let block = self
+            .inner
+            .eth_api
+            .block_by_id_with_senders(number.into())
+            .await?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let block_hash = block.hash();
+        let block = block.unseal();
+        let (cfg, block_env, _) = self.inner.eth_api.evm_env_at(block_hash.into()).await?;
+        let total_difficulty = self
+            .inner
+            .provider
+            .header_td_by_number(number)?
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_blocking(move || {
+                // the cached post-state of the parent block is kept, only what's not cached yet is
+                // read from the database, so no read transaction is held open across blocks
+                let state = this.eth_api().state_at(block.parent_hash.into())?;
+                let mut db = match db {
+                    Some(mut db) => {
+                        db.db = StateProviderDatabase::new(state);
+                        db
+                    }
+                    None => CacheDB::new(StateProviderDatabase::new(state)),
+                };
+
+                this.eth_api().pre_block_beacon_root_contract_call(
+                    &mut db,
+                    &cfg,
+                    &block_env,
+                    block.parent_beacon_block_root,
+                )?;
+
+                let mut traces = Vec::with_capacity(block.body.len());
+                for (index, (sender, tx)) in block.transactions_with_sender().enumerate() {
+                    let tx = tx.clone().with_signer(*sender);
+                    let tx_hash = tx.hash;
+                    let env = EnvWithHandlerCfg {
+                        env: Env::boxed(
+                            cfg.cfg_env.clone(),
+                            block_env.clone(),
+                            tx_env_with_recovered(&tx),
+                        ),
+                        handler_cfg: cfg.handler_cfg,
+                    };
+                    let (result, state_changes) = this.trace_transaction(
+                        opts.clone(),
+                        env,
+                        &mut db,
+                        Some(TransactionContext {
+                            block_hash: Some(block_hash),
+                            tx_hash: Some(tx_hash),
+                            tx_index: Some(index),
+                        }),
+                    )?;
+                    db.commit(state_changes);
+                    traces.push(TraceResult::Success { result, tx_hash: Some(tx_hash) });
+                }
+
+                // the same post-block changes as the block executor, applied to the cached state
+                let chain_spec = this.inner.provider.chain_spec();
+                let balance_increments = post_block_balance_changes(
+                    &chain_spec,
+                    &block.block,
+                    total_difficulty,
+                    |accounts| drain_balances(&mut db, accounts),
+                )?;
+                increment_balances(&mut db, balance_increments)?;
+
+                let traces = BlockTraceResult { block: number.into(), hash: block_hash, traces };
+                Ok((traces, db))
+            })
+            .await
+    }
+
     /// Trace the transaction according to the provided options.
     ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
@@ -415,12 +700,23 @@ where
     /// The debug_traceCallMany method lets you run an `eth_callMany` within the context of the
     /// given block execution using the first n transactions in the given block as base.
     /// Each following bundle increments block number by 1 and block timestamp by 12 seconds
+    ///
+    /// The state overrides of the `opts` are applied before the first transaction, the block
+    /// overrides of the `opts` apply to all bundles that don't override the block themselves.
+    ///
+    /// The `call_opts` select the tracer of individual transactions, by bundle and transaction
+    /// index. Transactions without tracing options of their own are traced with the tracing
+    /// options of the `opts`.
+    ///
+    /// If a transaction fails, the traces of all previous transactions are returned together with
+    /// the error of the failed transaction, the remaining transactions are not executed.
     pub async fn debug_trace_call_many(
         &self,
         bundles: Vec<Bundle>,
         state_context: Option<StateContext>,
         opts: Option<GethDebugTracingCallOptions>,
-    ) -> EthResult<Vec<Vec<GethTrace>>> {
+        call_opts: Option<Vec<Vec<Option<GethDebugTracingOptions>>>>,
+    ) -> EthResult<Vec<Vec<TraceResult>>> {
         if bundles.is_empty() {
             return Err(EthApiError::InvalidParams(String::from("bundles are empty.")))
         }
@@ -436,7 +732,9 @@ where
 
         let opts = opts.unwrap_or_default();
         let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
-        let GethDebugTracingCallOptions { tracing_options, mut state_overrides, .. } = opts;
+        let GethDebugTracingCallOptions { tracing_options, mut state_overrides, block_overrides } =
+            opts;
+        let mut call_opts = call_opts.unwrap_or_default().into_iter();
         let gas_limit = self.inner.eth_api.call_gas_limit();
 
         // we're essentially replaying the transactions in the block here, hence we need the state
@@ -484,33 +782,51 @@ where
                 while let Some(bundle) = bundles.next() {
                     let mut results = Vec::with_capacity(bundle.transactions.len());
                     let Bundle { transactions, block_override } = bundle;
+                    let mut bundle_opts = call_opts.next().unwrap_or_default().into_iter();
 
-                    let block_overrides = block_override.map(Box::new);
+                    let block_overrides =
+                        block_override.or_else(|| block_overrides.clone()).map(Box::new);
 
                     let mut transactions = transactions.into_iter().peekable();
                     while let Some(tx) = transactions.next() {
                         // apply state overrides only once, before the first transaction
                         let state_overrides = state_overrides.take();
                         let overrides = EvmOverrides::new(state_overrides, block_overrides.clone());
+                        let tracing_options =
+                            bundle_opts.next().flatten().unwrap_or_else(|| tracing_options.clone());
 
-                        let env = prepare_call_env(
+                        let traced = prepare_call_env(
                             cfg.clone(),
                             block_env.clone(),
                             tx,
                             gas_limit,
                             &mut db,
                             overrides,
-                        )?;
-
-                        let (trace, state) =
-                            this.trace_transaction(tracing_options.clone(), env, &mut db, None)?;
+                        )
+                        .and_then(|env| {
+                            this.trace_transaction(tracing_options, env, &mut db, None)
+                        });
+
+                        let (trace, state) = match traced {
+                            Ok(traced) => traced,
+                            Err(err) => {
+                                // return the traces so far, the following transactions depend on
+                                // the failed one
+                                results.push(TraceResult::Error {
+                                    error: err.to_string(),
+                                    tx_hash: None,
+                                });
+                                all_bundles.push(results);
+                                return Ok(all_bundles)
+                            }
+                        };
 
                         // If there is more transactions, commit the database
                         // If there is no transactions, but more bundles, commit to the database too
                         if transactions.peek().is_some() || bundles.peek().is_some() {
                             db.commit(state);
                         }
-                        results.push(trace);
+                        results.push(TraceResult::Success { result: trace, tx_hash: None });
                     }
                     // Increment block_env number and timestamp for the next bundle
                     block_env.number += U256::from(1);
@@ -710,17 +1026,63 @@ where
     }
 
     /// Handler for `debug_getBadBlocks`
-    async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+    ///
+    /// Returns the invalid blocks known to the engine, most recent first.
+    async fn bad_blocks(&self) -> RpcResult<Vec<BadBlock>> {
+        let invalid_blocks = self.inner.invalid_blocks.snapshot();
+        let this = self.clone();
+        // recovering the senders of the blocks is CPU bound
+        let bad_blocks = self
+            .eth_api()
+            .spawn_blocking(move || {
+                let bad_blocks = invalid_blocks.into_iter().rev().map(|invalid| {
+                    let hash = invalid.header.hash();
+                    let parent_hash = invalid.header.parent_hash;
+                    let block = invalid.block.map(|block| block.unseal());
+                    let rlp = block.as_ref().map(|block| alloy_rlp::encode(block).into());
+                    let block = block.and_then(|block| {
+                        let block = block.with_recovered_senders()?;
+                        let parent_td = this
+                            .inner
+                            .provider
+                            .header_td(&parent_hash)
+                            .ok()
+                            .flatten()
+                            .unwrap_or_default();
+                        let total_difficulty = parent_td + block.header.difficulty;
+                        from_block(block, total_difficulty, BlockTransactionsKind::Full, Some(hash))
+                            .ok()
+                            .map(Into::into)
+                    });
+                    BadBlock {
+                        hash,
+                        parent_hash,
+                        block,
+                        rlp,
+                        reason: invalid.reason,
+                        first_seen: invalid.first_seen,
+                    }
+                });
+                Ok(bad_blocks.collect::<Vec<_>>())
+            })
+            .await?;
+        Ok(bad_blocks)
     }
 
-    /// Handler for `debug_traceChain`
-    async fn debug_trace_chain(
+    /// Handler for `debug_subscribe`
+    async fn subscribe(
         &self,
-        _start_exclusive: BlockNumberOrTag,
-        _end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>> {
-        Err(internal_rpc_err("unimplemented"))
+        pending: PendingSubscriptionSink,
+        kind: DebugSubscriptionKind,
+        start_block: BlockNumberOrTag,
+        end_block: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        match kind {
+            DebugSubscriptionKind::TraceChain => {
+                self.trace_chain(pending, start_block, end_block, opts.unwrap_or_default()).await
+            }
+        }
     }
 
     /// Handler for `debug_traceBlock`
@@ -780,9 +1142,19 @@ where
         bundles: Vec<Bundle>,
         state_context: Option<StateContext>,
         opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<Vec<Vec<GethTrace>>> {
+        call_opts: Option<Vec<Vec<Option<GethDebugTracingOptions>>>>,
+    ) -> RpcResult<Vec<Vec<TraceResult>>> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(DebugApi::debug_trace_call_many(self, bundles, state_context, opts, call_opts).await?)
+    }
+
+    /// Handler for `debug_executionWitness`
+    async fn debug_execution_witness(
+        &self,
+        block: BlockNumberOrTag,
+    ) -> RpcResult<ExecutionWitness> {
         let _permit = self.acquire_trace_permit().await;
-        Ok(DebugApi::debug_trace_call_many(self, bundles, state_context, opts).await?)
+        Ok(DebugApi::debug_execution_witness(self, block).await?)
     }
 
     async fn debug_backtrace_at(&self, _location: &str) -> RpcResult<()> {
@@ -1005,7 +1377,7 @@ impl<Provider, Eth> std::fmt::Debug for DebugApi<Provider, Eth> {
 
 impl<Provider, Eth> Clone for DebugApi<Provider, Eth> {
     fn clone(&self) -> Self {
-        Self { inner: Arc::clone(&self.inner) }
+        Self { inner: Arc::clone(&self.inner), max_trace_chain_blocks: self.max_trace_chain_blocks }
     }
 }
 
@@ -1016,4 +1388,303 @@ struct DebugApiInner<Provider, Eth> {
     eth_api: Eth,
     // restrict the number of concurrent calls to blocking calls
     blocking_task_guard: BlockingTaskGuard,
+    /// The invalid blocks known to the engine.
+    invalid_blocks: InvalidBlocksHandle,
+}
+
+/// Drains the balances of the accounts and returns them, like [State::drain_balances].
+///
+/// [State::drain_balances]: revm::State::drain_balances
+fn drain_balances(
+    db: &mut SubState<StateProviderBox>,
+    accounts: &[Address],
+) -> EthResult<Vec<u128>> {
+    let mut balances = Vec::with_capacity(accounts.len());
+    let mut drained = HashMap::with_capacity(accounts.len());
+    for address in accounts {
+        let mut info = db.basic(*address)?.unwrap_or_default();
+        balances.push(info.balance.to::<u128>());
+        info.balance = U256::ZERO;
+        drained.insert(*address, touched_account(info));
+    }
+    db.commit(drained);
+    Ok(balances)
+}
+
+/// Increments the balances of the accounts, like [State::increment_balances].
+///
+/// [State::increment_balances]: revm::State::increment_balances
+fn increment_balances(
+    db: &mut SubState<StateProviderBox>,
+    balance_increments: std::collections::HashMap<Address, u128>,
+) -> EthResult<()> {
+    let mut increments = HashMap::with_capacity(balance_increments.len());
+    for (address, increment) in balance_increments {
+        if increment == 0 {
+            continue
+        }
+        let mut info = db.basic(address)?.unwrap_or_default();
+        info.balance = info.balance.saturating_add(U256::from(increment));
+        increments.insert(address, touched_account(info));
+    }
+    db.commit(increments);
+    Ok(())
+}
+
+/// Returns the account with the given info, marked as touched so it's committed.
+fn touched_account(info: revm::primitives::AccountInfo) -> Account {
+    let mut account = Account::from(info);
+    account.mark_touch();
+    account
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::test_utils::build_test_eth_api;
+    use jsonrpsee::rpc_params;
+    use reth_blockchain_tree::noop::NoopBlockchainTree;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_interfaces::test_utils::generators::{self, generate_keys, sign_tx_with_key_pair};
+    use reth_primitives::{
+        constants::ETH_TO_WEI, public_key_to_address, Account, Chain, ChainSpecBuilder,
+        ForkCondition, Genesis, Hardfork, Header, Receipts, StaticFileSegment, Transaction,
+        TransactionKind, TxLegacy,
+    };
+    use reth_provider::{
+        bundle_state::{BundleStateInit, RevertsInit},
+        providers::{BlockchainProvider, StaticFileWriter},
+        test_utils::{create_test_provider_factory_with_chain_spec, MockEthProvider},
+        BlockExecutor, BundleStateWithReceipts, HistoryWriter, OriginalValuesKnown,
+    };
+    use reth_revm::{
+        eth_dao_fork::{DAO_HARDFORK_BENEFICIARY, DAO_HARDKFORK_ACCOUNTS},
+        processor::EVMProcessor,
+    };
+    use reth_rpc_types::state::{AccountOverride, StateOverride};
+
+    /// Returns a transfer of 60 wei between two accounts without a balance.
+    fn transfer() -> TransactionRequest {
+        TransactionRequest {
+            from: Some(Address::with_last_byte(1)),
+            to: Some(Address::with_last_byte(2)),
+            value: Some(U256::from(60)),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn debug_trace_call_many_carries_state_across_calls() {
+        let provider = MockEthProvider::default();
+        provider.add_block(B256::ZERO, Block::default());
+        let debug_api = DebugApi::new(
+            provider.clone(),
+            build_test_eth_api(provider),
+            BlockingTaskGuard::new(1),
+            InvalidBlocksHandle::default(),
+        );
+
+        let bundle = |transactions| Bundle { transactions, block_override: None };
+        let bundles =
+            vec![bundle(vec![transfer(), transfer(), transfer()]), bundle(vec![transfer()])];
+        let state_context =
+            StateContext { block_number: Some(B256::ZERO.into()), transaction_index: None };
+        // the balance is only enough for two transfers
+        let account = AccountOverride { balance: Some(U256::from(150)), ..Default::default() };
+        let opts = GethDebugTracingCallOptions {
+            state_overrides: Some(StateOverride::from([(Address::with_last_byte(1), account)])),
+            ..Default::default()
+        };
+        // the second transfer is traced with the call tracer
+        let call_tracer = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::CallTracer,
+            )),
+            ..Default::default()
+        };
+        let call_opts = vec![vec![None, Some(call_tracer)]];
+
+        let results = debug_api
+            .debug_trace_call_many(bundles, Some(state_context), Some(opts), Some(call_opts))
+            .await
+            .unwrap();
+
+        // the third transfer fails and the next bundle isn't executed
+        assert_eq!(results.len(), 1);
+        let results = &results[0];
+        assert_eq!(results.len(), 3);
+        assert!(matches!(results[0], TraceResult::Success { result: GethTrace::Default(_), .. }));
+        assert!(matches!(
+            results[1],
+            TraceResult::Success { result: GethTrace::CallTracer(_), .. }
+        ));
+        assert!(matches!(results[2], TraceResult::Error { .. }));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn trace_chain_matches_trace_block_by_number() {
+        let key_pair = generate_keys(&mut generators::rng(), 1)[0];
+        let sender = public_key_to_address(key_pair.public_key());
+        let miner = Address::with_last_byte(0xaa);
+        let dao_account = DAO_HARDKFORK_ACCOUNTS[0];
+
+        // the DAO hardfork drains the DAO account after the transactions of block 2
+        let chain_spec = Arc::new(
+            ChainSpecBuilder::default()
+                .chain(Chain::mainnet())
+                .genesis(Genesis::default())
+                .frontier_activated()
+                .with_fork(Hardfork::Dao, ForkCondition::Block(2))
+                .build(),
+        );
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+
+        // every block sends transfers to the miner, the DAO account, and the DAO beneficiary, so
+        // the prestate of a block shows the rewards, the drain, and the transfers of the previous
+        // blocks
+        let recipients = [miner, dao_account, DAO_HARDFORK_BENEFICIARY];
+        let mut blocks = Vec::new();
+        let mut parent_hash = B256::ZERO;
+        let mut nonce = 0;
+        for number in 0..=3u64 {
+            let body = if number == 0 {
+                Vec::new()
+            } else {
+                recipients
+                    .iter()
+                    .map(|to| {
+                        let tx = Transaction::Legacy(TxLegacy {
+                            nonce,
+                            gas_price: 1,
+                            gas_limit: 21_000,
+                            to: TransactionKind::Call(*to),
+                            value: U256::from(number),
+                            ..Default::default()
+                        });
+                        nonce += 1;
+                        sign_tx_with_key_pair(key_pair, tx)
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let header = Header {
+                parent_hash,
+                number,
+                beneficiary: miner,
+                difficulty: U256::from(1),
+                gas_limit: 1_000_000,
+                gas_used: 21_000 * body.len() as u64,
+                timestamp: number * 12,
+                ..Default::default()
+            };
+            let block = Block { header, body, ..Default::default() }
+                .seal_slow()
+                .try_seal_with_senders()
+                .unwrap();
+            parent_hash = block.hash();
+            blocks.push(block);
+        }
+
+        // write the genesis block and its state
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_historical_block(blocks[0].clone(), None).unwrap();
+        let accounts = [(sender, ETH_TO_WEI), (dao_account, 1000)].map(|(address, balance)| {
+            (address, Account { balance: U256::from(balance), ..Default::default() })
+        });
+        let state_init: BundleStateInit = accounts
+            .iter()
+            .map(|(address, account)| (*address, (None, Some(*account), Default::default())))
+            .collect();
+        let reverts_init: RevertsInit = std::collections::HashMap::from([(
+            0,
+            accounts.iter().map(|(address, _)| (*address, (Some(None), Vec::new()))).collect(),
+        )]);
+        BundleStateWithReceipts::new_init(state_init, reverts_init, Vec::new(), Receipts::new(), 0)
+            .write_to_storage(provider.tx_ref(), None, OriginalValuesKnown::Yes)
+            .unwrap();
+        provider
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+        provider.commit().unwrap();
+
+        // execute the blocks and write them with their state and history
+        let mut executor = EVMProcessor::new_with_db(
+            chain_spec,
+            StateProviderDatabase::new(factory.latest().unwrap()),
+            EthEvmConfig::default(),
+        );
+        for block in &blocks[1..] {
+            executor
+                .execute_and_verify_receipt(&block.clone().unseal(), U256::from(block.number + 1))
+                .unwrap();
+        }
+        let state = executor.take_output_state();
+        drop(executor);
+
+        let provider = factory.provider_rw().unwrap();
+        for block in &blocks[1..] {
+            provider.insert_historical_block(block.clone(), None).unwrap();
+        }
+        state.write_to_storage(provider.tx_ref(), None, OriginalValuesKnown::Yes).unwrap();
+        provider.update_history_indices(0..=3).unwrap();
+        provider
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+        provider.commit().unwrap();
+
+        let provider = BlockchainProvider::new(factory, NoopBlockchainTree::default()).unwrap();
+        let debug_api = DebugApi::new(
+            provider.clone(),
+            build_test_eth_api(provider),
+            BlockingTaskGuard::new(1),
+            InvalidBlocksHandle::default(),
+        );
+        let module = debug_api.into_rpc();
+
+        let prestate_tracer = GethDebugTracingOptions {
+            tracer: Some(GethDebugTracerType::BuiltInTracer(
+                GethDebugBuiltInTracerType::PreStateTracer,
+            )),
+            ..Default::default()
+        };
+        for opts in [GethDebugTracingOptions::default(), prestate_tracer] {
+            let start = BlockNumberOrTag::Number(0);
+            let end = BlockNumberOrTag::Number(3);
+            let mut subscription = module
+                .subscribe_unbounded(
+                    "debug_subscribe",
+                    rpc_params!["traceChain", start, end, &opts],
+                )
+                .await
+                .unwrap();
+
+            // every block is traced on the post-state of the previous block that's kept by the
+            // subscription, and needs to match the block traced on the state from the database
+            for (number, block) in blocks.iter().enumerate().skip(1) {
+                let (traces, _) = subscription.next::<serde_json::Value>().await.unwrap().unwrap();
+                let expected: serde_json::Value = module
+                    .call(
+                        "debug_traceBlockByNumber",
+                        rpc_params![BlockNumberOrTag::Number(number as u64), &opts],
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(traces["block"], serde_json::json!(U64::from(number)));
+                assert_eq!(traces["hash"], serde_json::json!(block.hash()));
+                assert_eq!(traces["traces"].as_array().unwrap().len(), recipients.len());
+                assert_eq!(traces["traces"], expected);
+            }
+
+            let (completed, _) = subscription.next::<TraceChainCompleted>().await.unwrap().unwrap();
+            assert_eq!(
+                completed,
+                TraceChainCompleted { start_block: U64::from(0), end_block: U64::from(3) }
+            );
+        }
+    }
 }
@@ -2,19 +2,23 @@
 
 use crate::{
     eth::{
-        api::transactions::build_transaction_receipt_with_block_receipts,
+        api::transactions::build_transaction_receipt,
         error::{EthApiError, EthResult},
     },
     EthApi,
 };
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{BlockId, TransactionMeta};
-use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
+use reth_primitives::{
+    Address, BlockId, BlockNumber, Receipt, SealedBlock, TransactionMeta, TransactionSigned,
+};
+use reth_provider::{
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, PruneCheckpointReader,
+    StateProviderFactory,
+};
 use reth_rpc_types::{AnyTransactionReceipt, Header, Index, RichBlock};
 use reth_rpc_types_compat::block::{from_block, uncle_block_from_header};
 use reth_transaction_pool::TransactionPool;
-use std::sync::Arc;
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
 where
@@ -56,74 +60,6 @@ where
         Ok(uncle)
     }
 
-    /// Returns all transaction receipts in the block.
-    ///
-    /// Returns `None` if the block wasn't found.
-    pub(crate) async fn block_receipts(
-        &self,
-        block_id: BlockId,
-    ) -> EthResult<Option<Vec<AnyTransactionReceipt>>> {
-        let mut block_and_receipts = None;
-
-        if block_id.is_pending() {
-            block_and_receipts = self
-                .provider()
-                .pending_block_and_receipts()?
-                .map(|(sb, receipts)| (sb, Arc::new(receipts)));
-        } else if let Some(block_hash) = self.provider().block_hash_for_id(block_id)? {
-            block_and_receipts = self.cache().get_block_and_receipts(block_hash).await?;
-        }
-
-        if let Some((block, receipts)) = block_and_receipts {
-            let block_number = block.number;
-            let base_fee = block.base_fee_per_gas;
-            let block_hash = block.hash();
-            let excess_blob_gas = block.excess_blob_gas;
-            let timestamp = block.timestamp;
-            let block = block.unseal();
-
-            #[cfg(feature = "optimism")]
-            let (block_timestamp, l1_block_info) = {
-                let body = reth_revm::optimism::extract_l1_info(&block);
-                (block.timestamp, body.ok())
-            };
-
-            let receipts = block
-                .body
-                .into_iter()
-                .zip(receipts.iter())
-                .enumerate()
-                .map(|(idx, (tx, receipt))| {
-                    let meta = TransactionMeta {
-                        tx_hash: tx.hash,
-                        index: idx as u64,
-                        block_hash,
-                        block_number,
-                        base_fee,
-                        excess_blob_gas,
-                        timestamp,
-                    };
-
-                    #[cfg(feature = "optimism")]
-                    let op_tx_meta =
-                        self.build_op_tx_meta(&tx, l1_block_info.clone(), block_timestamp)?;
-
-                    build_transaction_receipt_with_block_receipts(
-                        tx,
-                        meta,
-                        receipt.clone(),
-                        &receipts,
-                        #[cfg(feature = "optimism")]
-                        op_tx_meta,
-                    )
-                })
-                .collect::<EthResult<Vec<_>>>();
-            return receipts.map(Some)
-        }
-
-        Ok(None)
-    }
-
     /// Returns the number transactions in the given block.
     ///
     /// Returns `None` if the block does not exist
@@ -212,3 +148,306 @@ where
         Ok(header)
     }
 }
+
+impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
+where
+    Provider: BlockReaderIdExt
+        + ChainSpecProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + PruneCheckpointReader
+        + 'static,
+    Pool: TransactionPool + Clone + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+    EvmConfig: ConfigureEvm + 'static,
+{
+    /// Returns all transaction receipts in the block.
+    ///
+    /// Returns `None` if the block wasn't found.
+    pub(crate) async fn block_receipts(
+        &self,
+        block_id: BlockId,
+    ) -> EthResult<Option<Vec<AnyTransactionReceipt>>> {
+        if block_id.is_pending() {
+            // Pending block can be fetched directly without need for caching
+            let Some((block, receipts)) = self.provider().pending_block_and_receipts()? else {
+                return Ok(None)
+            };
+            return self.build_block_receipts(block, None, receipts).map(Some)
+        }
+
+        if let Some(block_number) = self.provider().block_number_for_id(block_id)? {
+            let receipts = self
+                .on_blocking_task(|this| async move { this.canonical_block_receipts(block_number) })
+                .await?;
+            if receipts.is_some() {
+                return Ok(receipts)
+            }
+        }
+
+        // the block isn't stored in the database, e.g. a block of a side chain
+        if let Some(block_hash) = self.provider().block_hash_for_id(block_id)? {
+            if let Some((block, receipts)) = self.cache().get_block_and_receipts(block_hash).await?
+            {
+                return self.build_block_receipts(block, None, receipts.to_vec()).map(Some)
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the receipts of the block with the given number, read from the database and
+    /// static files.
+    ///
+    /// The header, transactions, senders and receipts of the block are read together with a
+    /// single provider, see `BlockReader::block_with_senders_and_receipts`.
+    ///
+    /// Returns `None` if the block isn't stored.
+    fn canonical_block_receipts(
+        &self,
+        block_number: BlockNumber,
+    ) -> EthResult<Option<Vec<AnyTransactionReceipt>>> {
+        let Some((block, senders, receipts)) =
+            self.provider().block_with_senders_and_receipts(block_number)?
+        else {
+            return Ok(None)
+        };
+        if receipts.len() < block.body.len() {
+            self.ensure_receipts_not_pruned(block_number)?;
+        }
+
+        // the senders are missing if they're pruned
+        self.build_block_receipts(block, Some(senders), receipts).map(Some)
+    }
+
+    /// Builds the receipts of all transactions of the block.
+    ///
+    /// The senders of the transactions are recovered if they're not given.
+    fn build_block_receipts(
+        &self,
+        block: SealedBlock,
+        senders: Option<Vec<Address>>,
+        receipts: Vec<Receipt>,
+    ) -> EthResult<Vec<AnyTransactionReceipt>> {
+        let senders = match senders {
+            Some(senders) if senders.len() == block.body.len() => senders,
+            _ => TransactionSigned::recover_signers_unchecked(&block.body, block.body.len())
+                .ok_or(EthApiError::InvalidTransactionSignature)?,
+        };
+
+        let block_number = block.number;
+        let base_fee = block.base_fee_per_gas;
+        let block_hash = block.hash();
+        let excess_blob_gas = block.excess_blob_gas;
+        let timestamp = block.timestamp;
+        let block = block.unseal();
+
+        #[cfg(feature = "optimism")]
+        let l1_block_info = reth_revm::optimism::extract_l1_info(&block).ok();
+
+        let mut cumulative_gas_used = 0;
+        let mut num_logs = 0;
+        block
+            .body
+            .into_iter()
+            .zip(senders)
+            .zip(receipts)
+            .enumerate()
+            .map(|(idx, ((tx, from), receipt))| {
+                let meta = TransactionMeta {
+                    tx_hash: tx.hash,
+                    index: idx as u64,
+                    block_hash,
+                    block_number,
+                    base_fee,
+                    excess_blob_gas,
+                    timestamp,
+                };
+
+                #[cfg(feature = "optimism")]
+                let op_tx_meta = self.build_op_tx_meta(&tx, l1_block_info.clone(), timestamp)?;
+
+                let gas_used = receipt.cumulative_gas_used.saturating_sub(cumulative_gas_used);
+                cumulative_gas_used = receipt.cumulative_gas_used;
+                let tx_num_logs = receipt.logs.len();
+
+                let receipt = build_transaction_receipt(
+                    tx,
+                    from,
+                    meta,
+                    receipt,
+                    gas_used,
+                    num_logs,
+                    #[cfg(feature = "optimism")]
+                    op_tx_meta,
+                );
+                num_logs += tx_num_logs;
+                Ok(receipt)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::{
+        api::transactions::build_transaction_receipt_with_block_receipts,
+        test_utils::build_test_eth_api,
+    };
+    use reth_interfaces::test_utils::generators::{self, generate_keys, sign_tx_with_key_pair};
+    use reth_primitives::{
+        constants::eip4844::DATA_GAS_PER_BLOB, eip4844::calc_blob_gasprice, Block, Bytes, Header,
+        Log, Transaction, TransactionKind, TxEip1559, TxEip4844, TxLegacy, TxType, B256, U256,
+    };
+    use reth_provider::test_utils::MockEthProvider;
+
+    /// Stores the block and its receipts and returns the receipts of the block built from the
+    /// batched range reads along with the receipts built one by one.
+    fn block_receipts_both_ways(
+        block: Block,
+        receipts: Vec<Receipt>,
+    ) -> (Vec<AnyTransactionReceipt>, Vec<AnyTransactionReceipt>) {
+        let provider = MockEthProvider::default();
+        let block_hash = block.header.hash_slow();
+        provider.add_block(block_hash, block.clone());
+        provider.add_receipts(block_hash, receipts.clone());
+        let eth_api = build_test_eth_api(provider);
+
+        let batched = eth_api.canonical_block_receipts(block.number).unwrap().unwrap();
+
+        let one_by_one = block
+            .body
+            .iter()
+            .zip(&receipts)
+            .enumerate()
+            .map(|(idx, (tx, receipt))| {
+                let meta = TransactionMeta {
+                    tx_hash: tx.hash,
+                    index: idx as u64,
+                    block_hash,
+                    block_number: block.number,
+                    base_fee: block.base_fee_per_gas,
+                    excess_blob_gas: block.excess_blob_gas,
+                    timestamp: block.timestamp,
+                };
+                build_transaction_receipt_with_block_receipts(
+                    tx.clone(),
+                    meta,
+                    receipt.clone(),
+                    &receipts,
+                    #[cfg(feature = "optimism")]
+                    Default::default(),
+                )
+                .unwrap()
+            })
+            .collect();
+
+        (batched, one_by_one)
+    }
+
+    fn receipt(tx_type: TxType, success: bool, cumulative_gas_used: u64, logs: usize) -> Receipt {
+        let log = Log { address: Address::with_last_byte(0xaa), ..Default::default() };
+        Receipt {
+            tx_type,
+            success,
+            cumulative_gas_used,
+            logs: vec![log; logs],
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn block_receipts_match_transaction_receipts_before_byzantium() {
+        let mut rng = generators::rng();
+        let key_pair = generate_keys(&mut rng, 1)[0];
+        let body = (0..3)
+            .map(|nonce| {
+                let tx = TxLegacy {
+                    nonce,
+                    gas_price: 1,
+                    gas_limit: 50_000,
+                    to: TransactionKind::Call(Address::with_last_byte(2)),
+                    input: Bytes::from_static(&[1]),
+                    ..Default::default()
+                };
+                sign_tx_with_key_pair(key_pair, Transaction::Legacy(tx))
+            })
+            .collect();
+        let receipts = vec![
+            receipt(TxType::Legacy, true, 21_016, 2),
+            receipt(TxType::Legacy, false, 50_000, 0),
+            receipt(TxType::Legacy, true, 71_016, 1),
+        ];
+        let header = Header { number: 1_000_000, timestamp: 1_455_404_053, ..Default::default() };
+
+        let (batched, one_by_one) =
+            block_receipts_both_ways(Block { header, body, ..Default::default() }, receipts);
+        assert_eq!(batched, one_by_one);
+
+        let gas_used = batched.iter().map(|receipt| receipt.gas_used).collect::<Vec<_>>();
+        assert_eq!(gas_used, vec![21_016, 28_984, 21_016]);
+        let log_indices = batched
+            .iter()
+            .flat_map(|receipt| receipt.inner.inner.inner.receipt.logs.iter())
+            .map(|log| log.log_index)
+            .collect::<Vec<_>>();
+        assert_eq!(log_indices, vec![Some(0), Some(1), Some(2)]);
+        assert!(batched.iter().all(|receipt| receipt.blob_gas_used.is_none()));
+    }
+
+    #[tokio::test]
+    async fn block_receipts_match_transaction_receipts_after_cancun() {
+        let mut rng = generators::rng();
+        let key_pair = generate_keys(&mut rng, 1)[0];
+        let to = TransactionKind::Call(Address::with_last_byte(2));
+        let dynamic_fee = TxEip1559 {
+            chain_id: 1,
+            gas_limit: 21_000,
+            max_fee_per_gas: 20,
+            max_priority_fee_per_gas: 1,
+            to,
+            ..Default::default()
+        };
+        let blob = TxEip4844 {
+            chain_id: 1,
+            nonce: 1,
+            gas_limit: 21_000,
+            max_fee_per_gas: 20,
+            max_priority_fee_per_gas: 1,
+            to,
+            blob_versioned_hashes: vec![B256::with_last_byte(1), B256::with_last_byte(2)],
+            max_fee_per_blob_gas: 10,
+            ..Default::default()
+        };
+        let body = vec![
+            sign_tx_with_key_pair(key_pair, Transaction::Eip1559(dynamic_fee)),
+            sign_tx_with_key_pair(key_pair, Transaction::Eip4844(blob)),
+        ];
+        let receipts = vec![
+            receipt(TxType::Eip1559, true, 21_000, 1),
+            receipt(TxType::Eip4844, true, 42_000, 1),
+        ];
+        let excess_blob_gas = 10 * DATA_GAS_PER_BLOB;
+        let header = Header {
+            number: 19_500_000,
+            timestamp: 1_710_338_135,
+            base_fee_per_gas: Some(10),
+            blob_gas_used: Some(2 * DATA_GAS_PER_BLOB),
+            excess_blob_gas: Some(excess_blob_gas),
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        };
+
+        let (batched, one_by_one) =
+            block_receipts_both_ways(Block { header, body, ..Default::default() }, receipts);
+        assert_eq!(batched, one_by_one);
+
+        // only the blob transaction has blob gas fields
+        assert_eq!(batched[0].blob_gas_used, None);
+        assert_eq!(batched[0].blob_gas_price, None);
+        assert_eq!(batched[1].blob_gas_used, Some(2 * DATA_GAS_PER_BLOB as u128));
+        assert_eq!(batched[1].blob_gas_price, Some(calc_blob_gasprice(excess_blob_gas)));
+        assert_eq!(batched[1].effective_gas_price, 11);
+    }
+}
@@ -2,7 +2,10 @@
 
 use crate::{
     eth::{
-        error::{ensure_success, EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
+        error::{
+            ensure_success, EthApiError, EthResult, EthSimulateError, RevertError,
+            RpcInvalidTransactionError,
+        },
         revm_utils::{
             apply_state_overrides, build_call_evm_env, caller_gas_allowance,
             cap_tx_gas_limit_with_caller_allowance, get_precompiles, prepare_call_env,
@@ -12,25 +15,31 @@ use crate::{
     },
     EthApi,
 };
+use alloy_sol_types::decode_revert_reason;
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{revm::env::tx_env_with_recovered, BlockId, BlockNumberOrTag, Bytes, U256};
+use reth_primitives::{
+    revm::env::tx_env_with_recovered, Address, BlockId, BlockNumberOrTag, Bytes, B256, U256, U64,
+};
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderBox,
+    StateProviderFactory,
 };
 use reth_revm::{access_list::AccessListInspector, database::StateProviderDatabase};
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, Bundle, EthCallResponse, StateContext,
-    TransactionRequest,
+    simulate::SimulatedCall, state::StateOverride, AccessList, AccessListItem,
+    AccessListWithGasUsed, Bundle, EthCallResponse, StateContext, TransactionRequest,
 };
 use reth_transaction_pool::TransactionPool;
 use revm::{
     db::{CacheDB, DatabaseRef},
     primitives::{
-        BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, HaltReason, TransactTo,
+        BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, HaltReason,
+        ResultAndState, TransactTo,
     },
     DatabaseCommit,
 };
+use std::collections::{BTreeMap, BTreeSet};
 use tracing::trace;
 
 // Gas per transaction not creating a contract.
@@ -39,6 +48,9 @@ const MIN_TRANSACTION_GAS: u64 = 21_000u64;
 /// Taken from Geth's implementation in order to pass the hive tests
 /// <https://github.com/ethereum/go-ethereum/blob/a5a4fa7032bb248f5a7c40f4e8df2b131c4186a4/internal/ethapi/api.go#L56>
 const ESTIMATE_GAS_ERROR_RATIO: f64 = 0.015;
+/// The maximum number of times a call is executed by `eth_createAccessList` until its access list
+/// converges.
+const MAX_ACCESS_LIST_ITERATIONS: usize = 3;
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
 where
@@ -95,6 +107,146 @@ where
             return Err(EthApiError::InvalidParams(String::from("transactions are empty.")))
         }
 
+        let gas_limit = self.inner.gas_cap;
+        self.spawn_with_state_at_context(state_context, move |this, cfg, block_env, db| {
+            let mut results = Vec::with_capacity(transactions.len());
+            let block_overrides = block_override.map(Box::new);
+
+            let mut transactions = transactions.into_iter().peekable();
+            while let Some(tx) = transactions.next() {
+                // apply state overrides only once, before the first transaction
+                let state_overrides = state_override.take();
+                let overrides = EvmOverrides::new(state_overrides, block_overrides.clone());
+
+                let env =
+                    prepare_call_env(cfg.clone(), block_env.clone(), tx, gas_limit, db, overrides)?;
+                let (res, _) = this.transact(&mut *db, env)?;
+
+                match ensure_success(res.result) {
+                    Ok(output) => {
+                        results.push(EthCallResponse { value: Some(output), error: None });
+                    }
+                    Err(err) => {
+                        results.push(EthCallResponse { value: None, error: Some(err.to_string()) });
+                    }
+                }
+
+                if transactions.peek().is_some() {
+                    // need to apply the state changes of this call before executing the next call
+                    db.commit(res.state);
+                }
+            }
+
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Simulates a bundle of calls at an arbitrary blockchain index like [Self::call_many], and
+    /// returns the gas used, the return data and the revert reason of every call.
+    ///
+    /// Every call sees the state changes of the previous calls. Calls that are not valid
+    /// transactions are reported as failed and don't change the state. The gas of all calls is
+    /// limited by the configured gas cap.
+    pub async fn simulate_bundle(
+        &self,
+        bundle: Bundle,
+        state_context: Option<StateContext>,
+        mut state_override: Option<StateOverride>,
+    ) -> EthResult<Vec<SimulatedCall>> {
+        let Bundle { transactions, block_override } = bundle;
+        if transactions.is_empty() {
+            return Err(EthApiError::InvalidParams(String::from("transactions are empty.")))
+        }
+
+        let gas_cap = self.inner.gas_cap;
+        self.spawn_with_state_at_context(state_context, move |this, cfg, block_env, db| {
+            let mut results = Vec::with_capacity(transactions.len());
+            let block_overrides = block_override.map(Box::new);
+            let mut gas_remaining = gas_cap;
+
+            for tx in transactions {
+                if gas_remaining == 0 || tx.gas.is_some_and(|gas| gas > gas_remaining as u128) {
+                    return Err(EthSimulateError::GasCapExceeded(gas_cap).into())
+                }
+
+                // apply state overrides only once, before the first transaction
+                let overrides = EvmOverrides::new(state_override.take(), block_overrides.clone());
+                let mut env = prepare_call_env(
+                    cfg.clone(),
+                    block_env.clone(),
+                    tx,
+                    gas_remaining,
+                    db,
+                    overrides,
+                )?;
+                // the gas limit of a call with a gas price is capped by the allowance of the caller
+                // instead of the remaining gas
+                env.tx.gas_limit = env.tx.gas_limit.min(gas_remaining);
+
+                let ResultAndState { result, state } = match this.transact(&mut *db, env) {
+                    Ok((res, _)) => res,
+                    Err(EthApiError::InvalidTransaction(err)) => {
+                        results.push(SimulatedCall {
+                            error: Some(err.to_string()),
+                            ..Default::default()
+                        });
+                        continue
+                    }
+                    Err(err) => return Err(err),
+                };
+                db.commit(state);
+
+                let gas_used = result.gas_used();
+                gas_remaining = gas_remaining.saturating_sub(gas_used);
+
+                let (return_data, revert_reason, error) = match result {
+                    ExecutionResult::Success { output, .. } => (output.into_data(), None, None),
+                    ExecutionResult::Revert { output, .. } => {
+                        let reason = decode_revert_reason(&output);
+                        let error = RevertError::new(output.clone()).to_string();
+                        (output, reason, Some(error))
+                    }
+                    ExecutionResult::Halt { reason, gas_used } => {
+                        let error = RpcInvalidTransactionError::halt(reason, gas_used).to_string();
+                        (Bytes::new(), None, Some(error))
+                    }
+                };
+                results.push(SimulatedCall {
+                    return_data,
+                    gas_used: U64::from(gas_used),
+                    status: U64::from(error.is_none() as u8),
+                    revert_reason,
+                    error,
+                });
+            }
+
+            Ok(results)
+        })
+        .await
+    }
+
+    /// Executes the closure on the tracing pool with the state of the given [StateContext].
+    ///
+    /// This is the state at the start of the context's block with the first `transactionIndex`
+    /// transactions of the block executed on top of it, or the state of the block itself if all of
+    /// its transactions are included. The closure is called with the environment of the block.
+    async fn spawn_with_state_at_context<F, T>(
+        &self,
+        state_context: Option<StateContext>,
+        f: F,
+    ) -> EthResult<T>
+    where
+        F: FnOnce(
+                &Self,
+                CfgEnvWithHandlerCfg,
+                BlockEnv,
+                &mut CacheDB<StateProviderDatabase<StateProviderBox>>,
+            ) -> EthResult<T>
+            + Send
+            + 'static,
+        T: Send + 'static,
+    {
         let StateContext { transaction_index, block_number } = state_context.unwrap_or_default();
         let transaction_index = transaction_index.unwrap_or_default();
 
@@ -107,7 +259,6 @@ where
         )?;
 
         let Some(block) = block else { return Err(EthApiError::UnknownBlockNumber) };
-        let gas_limit = self.inner.gas_cap;
 
         // we're essentially replaying the transactions in the block here, hence we need the state
         // that points to the beginning of the block, which is the state at the parent block
@@ -125,7 +276,6 @@ where
 
         let this = self.clone();
         self.spawn_with_state_at_block(at.into(), move |state| {
-            let mut results = Vec::with_capacity(transactions.len());
             let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
             if replay_block_txs {
@@ -141,40 +291,7 @@ where
                 }
             }
 
-            let block_overrides = block_override.map(Box::new);
-
-            let mut transactions = transactions.into_iter().peekable();
-            while let Some(tx) = transactions.next() {
-                // apply state overrides only once, before the first transaction
-                let state_overrides = state_override.take();
-                let overrides = EvmOverrides::new(state_overrides, block_overrides.clone());
-
-                let env = prepare_call_env(
-                    cfg.clone(),
-                    block_env.clone(),
-                    tx,
-                    gas_limit,
-                    &mut db,
-                    overrides,
-                )?;
-                let (res, _) = this.transact(&mut db, env)?;
-
-                match ensure_success(res.result) {
-                    Ok(output) => {
-                        results.push(EthCallResponse { value: Some(output), error: None });
-                    }
-                    Err(err) => {
-                        results.push(EthCallResponse { value: None, error: Some(err.to_string()) });
-                    }
-                }
-
-                if transactions.peek().is_some() {
-                    // need to apply the state changes of this call before executing the next call
-                    db.commit(res.state);
-                }
-            }
-
-            Ok(results)
+            f(&this, cfg, block_env, &mut db)
         })
         .await
     }
@@ -418,25 +535,37 @@ where
             from.create(nonce)
         };
 
-        // can consume the list since we're not using the request anymore
-        let initial = request.access_list.take().unwrap_or_default();
-
-        let precompiles = get_precompiles(env.handler_cfg.spec_id);
-        let mut inspector = AccessListInspector::new(initial, from, to, precompiles);
-        let (result, env) = self.inspect(&mut db, env, &mut inspector)?;
+        let precompiles = get_precompiles(env.handler_cfg.spec_id).into_iter().collect::<Vec<_>>();
+
+        // applying the access list changes the gas costs of the accesses, which can change the
+        // code path of gas sensitive calls, so the call is executed with the previously collected
+        // access list applied until the accessed slots don't change anymore
+        let mut access_list = sorted_access_list(request.access_list.take().unwrap_or_default());
+        for _ in 0..MAX_ACCESS_LIST_ITERATIONS {
+            let mut env = env.clone();
+            env.tx.access_list = access_list.clone().into_flattened();
+
+            let mut inspector =
+                AccessListInspector::new(access_list.clone(), from, to, precompiles.clone());
+            let (result, _) = self.inspect(&mut db, env, &mut inspector)?;
+
+            match result.result {
+                ExecutionResult::Halt { reason, .. } => Err(match reason {
+                    HaltReason::NonceOverflow => RpcInvalidTransactionError::NonceMaxValue,
+                    halt => RpcInvalidTransactionError::EvmHalt(halt),
+                }),
+                ExecutionResult::Revert { output, .. } => {
+                    Err(RpcInvalidTransactionError::Revert(RevertError::new(output)))
+                }
+                ExecutionResult::Success { .. } => Ok(()),
+            }?;
 
-        match result.result {
-            ExecutionResult::Halt { reason, .. } => Err(match reason {
-                HaltReason::NonceOverflow => RpcInvalidTransactionError::NonceMaxValue,
-                halt => RpcInvalidTransactionError::EvmHalt(halt),
-            }),
-            ExecutionResult::Revert { output, .. } => {
-                Err(RpcInvalidTransactionError::Revert(RevertError::new(output)))
+            let accessed = sorted_access_list(inspector.into_access_list());
+            if accessed == access_list {
+                break
             }
-            ExecutionResult::Success { .. } => Ok(()),
-        }?;
-
-        let access_list = inspector.into_access_list();
+            access_list = accessed;
+        }
 
         let cfg_with_spec_id =
             CfgEnvWithHandlerCfg { cfg_env: env.cfg.clone(), handler_cfg: env.handler_cfg };
@@ -448,6 +577,7 @@ where
 
         Ok(AccessListWithGasUsed { access_list, gas_used })
     }
+
     /// Executes the requests again after an out of gas error to check if the error is gas related
     /// or not
     #[inline]
@@ -483,6 +613,24 @@ where
     }
 }
 
+/// Returns the access list with its addresses and storage keys sorted and deduplicated, so that
+/// access lists with the same entries are equal.
+fn sorted_access_list(access_list: AccessList) -> AccessList {
+    let mut entries = BTreeMap::<Address, BTreeSet<B256>>::new();
+    for item in access_list.0 {
+        entries.entry(item.address).or_default().extend(item.storage_keys);
+    }
+    AccessList(
+        entries
+            .into_iter()
+            .map(|(address, storage_keys)| AccessListItem {
+                address,
+                storage_keys: storage_keys.into_iter().collect(),
+            })
+            .collect(),
+    )
+}
+
 /// Updates the highest and lowest gas limits for binary search
 ///  based on the result of the execution
 #[inline]
@@ -521,3 +669,230 @@ fn update_estimated_gas_range(
     };
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::{
+        cache::EthStateCache, gas_oracle::GasPriceOracle, test_utils::build_test_eth_api,
+        FeeHistoryCache, FeeHistoryCacheConfig, DEFAULT_ETH_PROOF_WINDOW,
+    };
+    use alloy_sol_types::{Revert, SolError};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_interfaces::test_utils::generators::{self, generate_keys, sign_tx_with_key_pair};
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{
+        constants::ETHEREUM_BLOCK_GAS_LIMIT, hex_literal::hex, Block, Transaction, TransactionKind,
+        TxLegacy,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types::TransactionIndex;
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_transaction_pool::test_utils::testing_pool;
+
+    #[tokio::test]
+    async fn create_access_list_converges() {
+        // loads slot 0, then loads slot 1 if less than 75000 gas is left and slot 2 otherwise
+        let code = hex!("600054505a620124f81160125760025450005b6001545000");
+        let contract = Address::with_last_byte(0xaa);
+
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::copy_from_slice(&code)),
+        );
+
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let eth_api = EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            NoopNetwork::default(),
+            cache.clone(),
+            GasPriceOracle::new(provider, Default::default(), cache.clone()),
+            ETHEREUM_BLOCK_GAS_LIMIT,
+            DEFAULT_ETH_PROOF_WINDOW,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+        );
+
+        let request = TransactionRequest {
+            from: Some(Address::with_last_byte(1)),
+            to: Some(contract),
+            gas: Some(100_000),
+            ..Default::default()
+        };
+        let AccessListWithGasUsed { access_list, gas_used } =
+            eth_api.create_access_list_at(request, Some(B256::ZERO.into())).await.unwrap();
+
+        // without an access list slot 2 is loaded, with slots 0 and 2 in the access list the
+        // intrinsic gas is higher and slot 1 is loaded instead, which changes the list once more
+        assert_eq!(
+            access_list,
+            AccessList(vec![AccessListItem {
+                address: contract,
+                storage_keys: vec![
+                    B256::with_last_byte(0),
+                    B256::with_last_byte(1),
+                    B256::with_last_byte(2)
+                ],
+            }])
+        );
+        // the intrinsic gas of the call with the final access list
+        assert!(gas_used > U256::from(21_000 + 2_400 + 3 * 1_900));
+    }
+
+    /// A contract that loops until it runs out of gas.
+    const LOOP: Address = Address::with_last_byte(0xaa);
+
+    fn simulate_call(from: Address, to: Address, gas: Option<u64>) -> TransactionRequest {
+        TransactionRequest {
+            from: Some(from),
+            to: Some(to),
+            gas: gas.map(u128::from),
+            ..Default::default()
+        }
+    }
+
+    fn bundle(transactions: Vec<TransactionRequest>) -> Bundle {
+        Bundle { transactions, block_override: None }
+    }
+
+    /// Returns a provider with the looping contract and a block at the zero hash.
+    fn simulate_provider() -> MockEthProvider {
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            LOOP,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(Bytes::from_static(&hex!("5b600056"))),
+        );
+        provider.add_block(B256::ZERO, Block::default());
+        provider
+    }
+
+    #[tokio::test]
+    async fn simulate_bundle_enforces_gas_cap() {
+        let eth_api = build_test_eth_api(simulate_provider());
+        let from = Address::with_last_byte(1);
+        let state_context =
+            StateContext { block_number: Some(B256::ZERO.into()), ..Default::default() };
+        let gas = ETHEREUM_BLOCK_GAS_LIMIT * 2 / 3;
+
+        // the second call gets the gas left by the first one
+        let results = eth_api
+            .simulate_bundle(
+                bundle(vec![simulate_call(from, LOOP, Some(gas)), simulate_call(from, LOOP, None)]),
+                Some(state_context.clone()),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].gas_used, U64::from(gas));
+        assert_eq!(results[0].status, U64::ZERO);
+        assert!(results[0].error.is_some());
+        assert_eq!(results[1].gas_used, U64::from(ETHEREUM_BLOCK_GAS_LIMIT - gas));
+
+        // the gas of the second call exceeds the remaining gas of the bundle
+        let err = eth_api
+            .simulate_bundle(
+                bundle(vec![
+                    simulate_call(from, LOOP, Some(gas)),
+                    simulate_call(from, LOOP, Some(gas)),
+                ]),
+                Some(state_context),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            EthApiError::Simulate(EthSimulateError::GasCapExceeded(ETHEREUM_BLOCK_GAS_LIMIT))
+        ));
+    }
+
+    #[tokio::test]
+    async fn simulate_bundle_replays_block_until_transaction_index() {
+        let mut rng = generators::rng();
+        let key_pair = generate_keys(&mut rng, 1)[0];
+        let recipient = Address::with_last_byte(2);
+        let transfers = (0..2)
+            .map(|nonce| {
+                sign_tx_with_key_pair(
+                    key_pair,
+                    Transaction::Legacy(TxLegacy {
+                        chain_id: Some(1),
+                        nonce,
+                        gas_limit: 21_000,
+                        to: TransactionKind::Call(recipient),
+                        value: U256::from(100),
+                        ..Default::default()
+                    }),
+                )
+            })
+            .collect::<Vec<_>>();
+        let sender = transfers[0].recover_signer().unwrap();
+
+        // the state of the block is only replayed if not all of its transactions are included
+        let provider = MockEthProvider::default();
+        provider.add_account(sender, ExtendedAccount::new(0, U256::from(200)));
+        provider.add_block(B256::ZERO, Block { body: transfers, ..Default::default() });
+        let eth_api = build_test_eth_api(provider);
+
+        // the recipient can only pass on the value if the transfer of the block was replayed
+        let pass_on = TransactionRequest {
+            value: Some(U256::from(100)),
+            ..simulate_call(recipient, Address::with_last_byte(3), None)
+        };
+        let simulate = |transaction_index| {
+            let state_context = StateContext {
+                block_number: Some(B256::ZERO.into()),
+                transaction_index: Some(transaction_index),
+            };
+            eth_api.simulate_bundle(bundle(vec![pass_on.clone()]), Some(state_context), None)
+        };
+
+        let results = simulate(TransactionIndex::Index(1)).await.unwrap();
+        assert_eq!(results[0].status, U64::from(1));
+        assert_eq!(results[0].gas_used, U64::from(21_000));
+
+        let results = simulate(TransactionIndex::Index(0)).await.unwrap();
+        assert_eq!(results[0].status, U64::ZERO);
+        assert_eq!(results[0].gas_used, U64::ZERO);
+        assert!(results[0].error.is_some());
+    }
+
+    #[tokio::test]
+    async fn simulate_bundle_decodes_revert_reason() {
+        // copies the revert data that follows the code to memory and reverts with it
+        let revert_data = Revert { reason: "not allowed".to_string() }.abi_encode();
+        let len = revert_data.len() as u8;
+        let mut code = vec![0x60, len, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, len, 0x60, 0x00, 0xfd];
+        code.extend_from_slice(&revert_data);
+        let contract = Address::with_last_byte(0xbb);
+
+        let provider = simulate_provider();
+        provider.add_account(
+            contract,
+            ExtendedAccount::new(0, U256::ZERO).with_bytecode(Bytes::from(code)),
+        );
+        let eth_api = build_test_eth_api(provider);
+        let state_context =
+            StateContext { block_number: Some(B256::ZERO.into()), ..Default::default() };
+
+        let results = eth_api
+            .simulate_bundle(
+                bundle(vec![simulate_call(Address::with_last_byte(1), contract, None)]),
+                Some(state_context),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(results[0].status, U64::ZERO);
+        assert_eq!(results[0].return_data, Bytes::from(revert_data));
+        assert!(results[0].revert_reason.as_ref().unwrap().contains("not allowed"));
+        assert!(results[0].error.as_ref().unwrap().contains("not allowed"));
+    }
+}
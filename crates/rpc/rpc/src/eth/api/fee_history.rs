@@ -1,11 +1,12 @@
 //! Consist of types adjacent to the fee history cache and its configs
 
-use crate::eth::{cache::EthStateCache, error::EthApiError, gas_oracle::MAX_HEADER_HISTORY};
+use crate::eth::{cache::EthStateCache, gas_oracle::MAX_HEADER_HISTORY};
 use futures::{
     future::{Fuse, FusedFuture},
     FutureExt, Stream, StreamExt,
 };
 use metrics::atomics::AtomicU64;
+use parking_lot::Mutex;
 use reth_primitives::{
     basefee::calculate_next_block_base_fee,
     eip4844::{calc_blob_gasprice, calculate_excess_blob_gas},
@@ -13,10 +14,11 @@ use reth_primitives::{
 };
 use reth_provider::{BlockReaderIdExt, CanonStateNotification, ChainSpecProvider};
 use reth_rpc_types::TxGasAndReward;
+use schnellru::{ByLength, LruMap};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, VecDeque},
-    fmt::Debug,
+    fmt::{self, Debug, Formatter},
     sync::{atomic::Ordering::SeqCst, Arc},
 };
 use tracing::trace;
@@ -35,6 +37,7 @@ impl FeeHistoryCache {
         let inner = FeeHistoryCacheInner {
             lower_bound: Default::default(),
             upper_bound: Default::default(),
+            block_tips: BlockTipsLru::new(config.max_blocks),
             config,
             entries: Default::default(),
             eth_cache,
@@ -48,12 +51,6 @@ impl FeeHistoryCache {
         &self.inner.config
     }
 
-    /// Returns the configured resolution for percentile approximation.
-    #[inline]
-    pub fn resolution(&self) -> u64 {
-        self.config().resolution
-    }
-
     /// Returns all blocks that are missing in the cache in the [lower_bound, upper_bound] range.
     ///
     /// This function is used to populate the cache with missing blocks, which can happen if the
@@ -73,18 +70,16 @@ impl FeeHistoryCache {
     {
         let mut entries = self.inner.entries.write().await;
 
-        let percentiles = self.predefined_percentiles();
-        // Insert all new blocks and calculate approximated rewards
+        // Insert all new blocks and cache the sorted tips of their transactions
         for (block, receipts) in blocks {
-            let mut fee_history_entry = FeeHistoryEntry::new(&block);
-            fee_history_entry.rewards = calculate_reward_percentiles_for_block(
-                &percentiles,
+            let fee_history_entry = FeeHistoryEntry::new(&block);
+            let tips = BlockTips::new(
                 fee_history_entry.gas_used,
                 fee_history_entry.base_fee_per_gas,
                 &block.body,
                 &receipts,
-            )
-            .unwrap_or_default();
+            );
+            self.insert_block_tips(fee_history_entry.header_hash, Arc::new(tips));
             entries.insert(block.number, fee_history_entry);
         }
 
@@ -93,6 +88,31 @@ impl FeeHistoryCache {
             entries.pop_first();
         }
 
+        self.update_bounds(&mut entries);
+    }
+
+    /// Removes the blocks of a reorged chain from the cache.
+    ///
+    /// Only the entries of the given blocks are removed, the entries of the blocks that already
+    /// replaced them are kept.
+    async fn remove_blocks<'a, I>(&self, blocks: I)
+    where
+        I: IntoIterator<Item = &'a SealedBlock>,
+    {
+        let mut entries = self.inner.entries.write().await;
+
+        for block in blocks {
+            if entries.get(&block.number).is_some_and(|entry| entry.header_hash == block.hash()) {
+                entries.remove(&block.number);
+            }
+            self.inner.block_tips.remove(&block.hash());
+        }
+
+        self.update_bounds(&mut entries);
+    }
+
+    /// Updates the bounds of the cache to its first and last entries.
+    fn update_bounds(&self, entries: &mut BTreeMap<u64, FeeHistoryEntry>) {
         if entries.len() == 0 {
             self.inner.upper_bound.store(0, SeqCst);
             self.inner.lower_bound.store(0, SeqCst);
@@ -122,11 +142,21 @@ impl FeeHistoryCache {
         self.inner.lower_bound.load(SeqCst)
     }
 
+    /// Returns the cached sorted tips of the transactions of the block with the given hash.
+    pub fn block_tips(&self, block_hash: B256) -> Option<Arc<BlockTips>> {
+        self.inner.block_tips.get(&block_hash)
+    }
+
+    /// Caches the sorted tips of the transactions of the block with the given hash.
+    pub fn insert_block_tips(&self, block_hash: B256, tips: Arc<BlockTips>) {
+        self.inner.block_tips.insert(block_hash, tips);
+    }
+
     /// Collect fee history for given range.
     ///
     /// This function retrieves fee history entries from the cache for the specified range.
-    /// If the requested range (start_block to end_block) is within the cache bounds,
-    /// it returns the corresponding entries.
+    /// If the requested range (start_block to end_block) is within the cache bounds and the cache
+    /// contains all of its blocks, it returns the corresponding entries.
     /// Otherwise it returns None.
     pub async fn get_history(
         &self,
@@ -142,7 +172,8 @@ impl FeeHistoryCache {
                 .map(|(_, fee_entry)| fee_entry.clone())
                 .collect::<Vec<_>>();
 
-            if result.is_empty() {
+            // the cache may have gaps after a reorg
+            if result.len() as u64 != end_block.saturating_sub(start_block) + 1 {
                 return None
             }
 
@@ -151,14 +182,6 @@ impl FeeHistoryCache {
             None
         }
     }
-
-    /// Generates predefined set of percentiles
-    ///
-    /// This returns 100 * resolution points
-    pub fn predefined_percentiles(&self) -> Vec<f64> {
-        let res = self.resolution() as f64;
-        (0..=100 * self.resolution()).map(|p| p as f64 / res).collect()
-    }
 }
 
 /// Settings for the [FeeHistoryCache].
@@ -167,18 +190,17 @@ impl FeeHistoryCache {
 pub struct FeeHistoryCacheConfig {
     /// Max number of blocks in cache.
     ///
+    /// This also limits the number of blocks with cached transaction tips, which includes older
+    /// blocks requested with reward percentiles.
+    ///
     /// Default is [MAX_HEADER_HISTORY] plus some change to also serve slightly older blocks from
     /// cache, since fee_history supports the entire range
     pub max_blocks: u64,
-    /// Percentile approximation resolution
-    ///
-    /// Default is 4 which means 0.25
-    pub resolution: u64,
 }
 
 impl Default for FeeHistoryCacheConfig {
     fn default() -> Self {
-        FeeHistoryCacheConfig { max_blocks: MAX_HEADER_HISTORY + 100, resolution: 4 }
+        FeeHistoryCacheConfig { max_blocks: MAX_HEADER_HISTORY + 100 }
     }
 }
 
@@ -189,14 +211,42 @@ struct FeeHistoryCacheInner {
     lower_bound: AtomicU64,
     /// Stores the upper bound of the cache
     upper_bound: AtomicU64,
-    /// Config for FeeHistoryCache, consists of resolution for percentile approximation
-    /// and max number of blocks
+    /// Config for FeeHistoryCache, consists of the max number of blocks
     config: FeeHistoryCacheConfig,
     /// Stores the entries of the cache
     entries: tokio::sync::RwLock<BTreeMap<u64, FeeHistoryEntry>>,
+    /// Stores the sorted transaction tips of the recently requested blocks by their hash
+    block_tips: BlockTipsLru,
     eth_cache: EthStateCache,
 }
 
+/// LRU cache of the [BlockTips] of blocks by their hash.
+struct BlockTipsLru(Mutex<LruMap<B256, Arc<BlockTips>, ByLength>>);
+
+impl BlockTipsLru {
+    fn new(max_blocks: u64) -> Self {
+        Self(Mutex::new(LruMap::new(ByLength::new(max_blocks.try_into().unwrap_or(u32::MAX)))))
+    }
+
+    fn get(&self, block_hash: &B256) -> Option<Arc<BlockTips>> {
+        self.0.lock().get(block_hash).cloned()
+    }
+
+    fn insert(&self, block_hash: B256, tips: Arc<BlockTips>) {
+        self.0.lock().insert(block_hash, tips);
+    }
+
+    fn remove(&self, block_hash: &B256) {
+        self.0.lock().remove(block_hash);
+    }
+}
+
+impl Debug for BlockTipsLru {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockTipsLru").field("len", &self.0.lock().len()).finish()
+    }
+}
+
 /// Awaits for new chain events and directly inserts them into the cache so they're available
 /// immediately before they need to be fetched from disk.
 pub async fn fee_history_cache_new_blocks_task<St, Provider>(
@@ -239,6 +289,11 @@ pub async fn fee_history_cache_new_blocks_task<St, Provider>(
                      // the stream ended, we are done
                     break;
                 };
+                if let Some(reverted) = event.reverted() {
+                    fee_history_cache
+                        .remove_blocks(reverted.blocks_iter().map(|block| &block.block))
+                        .await;
+                }
                 let (blocks, receipts): (Vec<_>, Vec<_>) = event
                     .committed()
                     .blocks_and_receipts()
@@ -255,64 +310,77 @@ pub async fn fee_history_cache_new_blocks_task<St, Provider>(
     }
 }
 
-/// Calculates reward percentiles for transactions in a block header.
-/// Given a list of percentiles and a sealed block header, this function computes
-/// the corresponding rewards for the transactions at each percentile.
+/// The transactions of a block sorted by their effective tip, which is the reward of the block's
+/// author per gas used by the transaction.
 ///
-/// The results are returned as a vector of U256 values.
-pub(crate) fn calculate_reward_percentiles_for_block(
-    percentiles: &[f64],
+/// The reward at a percentile of the block's gas used is found with a binary search.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockTips {
+    /// Gas used by the block.
     gas_used: u64,
-    base_fee_per_gas: u64,
-    transactions: &[TransactionSigned],
-    receipts: &[Receipt],
-) -> Result<Vec<u128>, EthApiError> {
-    let mut transactions = transactions
-        .iter()
-        .zip(receipts)
-        .scan(0, |previous_gas, (tx, receipt)| {
-            // Convert the cumulative gas used in the receipts
-            // to the gas usage by the transaction
-            //
-            // While we will sum up the gas again later, it is worth
-            // noting that the order of the transactions will be different,
-            // so the sum will also be different for each receipt.
-            let gas_used = receipt.cumulative_gas_used - *previous_gas;
-            *previous_gas = receipt.cumulative_gas_used;
-
-            Some(TxGasAndReward {
-                gas_used,
-                reward: tx.effective_tip_per_gas(Some(base_fee_per_gas)).unwrap_or_default(),
+    /// The effective tips of the transactions in ascending order, with the gas used by the
+    /// transactions up to and including the transaction in this order.
+    tips: Vec<(u64, u128)>,
+}
+
+impl BlockTips {
+    /// Sorts the effective tips of the transactions of a block with the given gas used and base
+    /// fee.
+    pub fn new(
+        gas_used: u64,
+        base_fee_per_gas: u64,
+        transactions: &[TransactionSigned],
+        receipts: &[Receipt],
+    ) -> Self {
+        let mut transactions = transactions
+            .iter()
+            .zip(receipts)
+            .scan(0, |previous_gas, (tx, receipt)| {
+                // Convert the cumulative gas used in the receipts
+                // to the gas usage by the transaction
+                //
+                // While we will sum up the gas again later, it is worth
+                // noting that the order of the transactions will be different,
+                // so the sum will also be different for each receipt.
+                let gas_used = receipt.cumulative_gas_used - *previous_gas;
+                *previous_gas = receipt.cumulative_gas_used;
+
+                Some(TxGasAndReward {
+                    gas_used,
+                    reward: tx.effective_tip_per_gas(Some(base_fee_per_gas)).unwrap_or_default(),
+                })
             })
-        })
-        .collect::<Vec<_>>();
-
-    // Sort the transactions by their rewards in ascending order
-    transactions.sort_by_key(|tx| tx.reward);
-
-    // Find the transaction that corresponds to the given percentile
-    //
-    // We use a `tx_index` here that is shared across all percentiles, since we know
-    // the percentiles are monotonically increasing.
-    let mut tx_index = 0;
-    let mut cumulative_gas_used = transactions.first().map(|tx| tx.gas_used).unwrap_or_default();
-    let mut rewards_in_block = Vec::new();
-    for percentile in percentiles {
-        // Empty blocks should return in a zero row
-        if transactions.is_empty() {
-            rewards_in_block.push(0);
-            continue
-        }
+            .collect::<Vec<_>>();
 
-        let threshold = (gas_used as f64 * percentile / 100.) as u64;
-        while cumulative_gas_used < threshold && tx_index < transactions.len() - 1 {
-            tx_index += 1;
-            cumulative_gas_used += transactions[tx_index].gas_used;
-        }
-        rewards_in_block.push(transactions[tx_index].reward);
+        // Sort the transactions by their rewards in ascending order
+        transactions.sort_by_key(|tx| tx.reward);
+
+        let tips = transactions
+            .into_iter()
+            .scan(0, |cumulative_gas_used, tx| {
+                *cumulative_gas_used += tx.gas_used;
+                Some((*cumulative_gas_used, tx.reward))
+            })
+            .collect();
+
+        Self { gas_used, tips }
+    }
+
+    /// Returns the reward of the transaction that corresponds to the given percentile of the
+    /// block's gas used.
+    ///
+    /// Empty blocks have a zero reward.
+    pub fn reward_percentile(&self, percentile: f64) -> u128 {
+        let threshold = (self.gas_used as f64 * percentile / 100.) as u64;
+        let index =
+            self.tips.partition_point(|(cumulative_gas_used, _)| *cumulative_gas_used < threshold);
+        self.tips.get(index).or(self.tips.last()).map(|(_, reward)| *reward).unwrap_or_default()
     }
 
-    Ok(rewards_in_block)
+    /// Returns the rewards at the given percentiles of the block's gas used.
+    pub fn reward_percentiles(&self, percentiles: &[f64]) -> Vec<u128> {
+        percentiles.iter().map(|&percentile| self.reward_percentile(percentile)).collect()
+    }
 }
 
 /// A cached entry for a block's fee history.
@@ -341,8 +409,6 @@ pub struct FeeHistoryEntry {
     pub gas_limit: u64,
     /// Hash of the block.
     pub header_hash: B256,
-    /// Approximated rewards for the configured percentiles.
-    pub rewards: Vec<u128>,
     /// The timestamp of the block.
     pub timestamp: u64,
 }
@@ -350,7 +416,7 @@ pub struct FeeHistoryEntry {
 impl FeeHistoryEntry {
     /// Creates a new entry from a sealed block.
     ///
-    /// Note: This does not calculate the rewards for the block.
+    /// Note: This does not sort the tips of the block's transactions, see [BlockTips].
     pub fn new(block: &SealedBlock) -> Self {
         FeeHistoryEntry {
             base_fee_per_gas: block.base_fee_per_gas.unwrap_or_default(),
@@ -363,7 +429,6 @@ impl FeeHistoryEntry {
             gas_used: block.gas_used,
             header_hash: block.hash(),
             gas_limit: block.gas_limit,
-            rewards: Vec::new(),
             timestamp: block.timestamp,
         }
     }
@@ -394,3 +459,114 @@ impl FeeHistoryEntry {
         Some(calculate_excess_blob_gas(self.excess_blob_gas?, self.blob_gas_used?))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        constants::eip4844::MAX_DATA_GAS_PER_BLOCK, Block, Header, Transaction, TxEip1559,
+    };
+    use reth_provider::test_utils::NoopProvider;
+
+    fn fee_history_cache() -> FeeHistoryCache {
+        let eth_cache = EthStateCache::spawn(
+            NoopProvider::default(),
+            Default::default(),
+            EthEvmConfig::default(),
+        );
+        FeeHistoryCache::new(eth_cache, FeeHistoryCacheConfig::default())
+    }
+
+    fn block(number: u64, gas_used: u64) -> SealedBlock {
+        block_with_header(Header { number, gas_used, gas_limit: 1000, ..Default::default() })
+    }
+
+    fn block_with_header(header: Header) -> SealedBlock {
+        Block { header, ..Default::default() }.seal_slow()
+    }
+
+    fn transaction(tip: u128) -> TransactionSigned {
+        TransactionSigned {
+            transaction: Transaction::Eip1559(TxEip1559 {
+                max_priority_fee_per_gas: tip,
+                max_fee_per_gas: tip,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn block_tips_reward_percentiles() {
+        let transactions = [transaction(30), transaction(10), transaction(20)];
+        let receipts = [10, 30, 100]
+            .map(|cumulative_gas_used| Receipt { cumulative_gas_used, ..Default::default() });
+        let tips = BlockTips::new(100, 0, &transactions, &receipts);
+
+        // sorted by tip, the transactions use 20, 70 and 10 gas
+        assert_eq!(
+            tips.reward_percentiles(&[0., 20., 25., 90., 95., 100.]),
+            vec![10, 10, 20, 20, 30, 30]
+        );
+        assert_eq!(BlockTips::new(0, 0, &[], &[]).reward_percentiles(&[0., 50.]), vec![0, 0]);
+    }
+
+    #[test]
+    fn fee_history_entry_blob_gas() {
+        let block = block_with_header(Header {
+            number: 1,
+            blob_gas_used: Some(MAX_DATA_GAS_PER_BLOCK / 2),
+            excess_blob_gas: Some(10_000_000),
+            ..Default::default()
+        });
+        let entry = FeeHistoryEntry::new(&block);
+        assert_eq!(entry.base_fee_per_blob_gas, Some(calc_blob_gasprice(10_000_000)));
+        assert_eq!(entry.blob_gas_used_ratio, 0.5);
+
+        // pre EIP-4844
+        let entry = FeeHistoryEntry::new(&self::block(1, 500));
+        assert_eq!(entry.base_fee_per_blob_gas, None);
+        assert_eq!(entry.blob_gas_used_ratio, 0.);
+    }
+
+    #[tokio::test]
+    async fn reorg_removes_blocks() {
+        let cache = fee_history_cache();
+        let blocks = (1..=3).map(|number| block(number, 100)).collect::<Vec<_>>();
+        cache.insert_blocks(blocks.iter().map(|block| (block.clone(), Arc::default()))).await;
+        assert_eq!(cache.get_history(1, 3).await.map(|entries| entries.len()), Some(3));
+        assert!(cache.block_tips(blocks[2].hash()).is_some());
+
+        // blocks 2 and 3 are replaced by a single block 2
+        let new_block = block(2, 200);
+        cache.remove_blocks(&blocks[1..]).await;
+        cache.insert_blocks([(new_block.clone(), Arc::default())]).await;
+
+        assert_eq!(cache.upper_bound(), 2);
+        assert!(cache.get_history(1, 3).await.is_none());
+        let entries = cache.get_history(1, 2).await.unwrap();
+        assert_eq!(
+            entries.iter().map(|entry| entry.header_hash).collect::<Vec<_>>(),
+            vec![blocks[0].hash(), new_block.hash()]
+        );
+        assert!(cache.block_tips(blocks[1].hash()).is_none());
+        assert!(cache.block_tips(blocks[2].hash()).is_none());
+        assert!(cache.block_tips(new_block.hash()).is_some());
+
+        // removing a reorged block keeps the block that replaced it
+        cache.remove_blocks(&blocks[1..2]).await;
+        assert_eq!(cache.get_history(2, 2).await.unwrap()[0].header_hash, new_block.hash());
+    }
+
+    #[tokio::test]
+    async fn gap_is_not_served() {
+        let cache = fee_history_cache();
+        let blocks = (1..=3).map(|number| block(number, 100)).collect::<Vec<_>>();
+        cache.insert_blocks(blocks.iter().map(|block| (block.clone(), Arc::default()))).await;
+
+        cache.remove_blocks(&blocks[1..2]).await;
+        assert!(cache.get_history(1, 3).await.is_none());
+        assert_eq!(cache.get_history(3, 3).await.map(|entries| entries.len()), Some(1));
+    }
+}
@@ -2,17 +2,18 @@
 
 use crate::{
     eth::{
-        api::fee_history::{calculate_reward_percentiles_for_block, FeeHistoryEntry},
+        api::fee_history::BlockTips,
         error::{EthApiError, EthResult},
     },
     EthApi,
 };
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{basefee::calculate_next_block_base_fee, BlockNumberOrTag, U256};
+use reth_primitives::{basefee::calculate_next_block_base_fee, BlockNumberOrTag, B256, U256};
 use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
 use reth_rpc_types::FeeHistory;
 use reth_transaction_pool::TransactionPool;
+use std::sync::Arc;
 use tracing::debug;
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
@@ -50,8 +51,8 @@ where
 
     /// Reports the fee history, for the given amount of blocks, up until the given newest block.
     ///
-    /// If `reward_percentiles` are provided the [FeeHistory] will include the _approximated_
-    /// rewards for the requested range.
+    /// If `reward_percentiles` are provided the [FeeHistory] will include the rewards for the
+    /// requested range.
     pub(crate) async fn fee_history(
         &self,
         mut block_count: u64,
@@ -130,11 +131,15 @@ where
                 blob_gas_used_ratio.push(entry.blob_gas_used_ratio);
 
                 if let Some(percentiles) = &reward_percentiles {
-                    let mut block_rewards = Vec::with_capacity(percentiles.len());
-                    for &percentile in percentiles.iter() {
-                        block_rewards.push(self.approximate_percentile(entry, percentile));
-                    }
-                    rewards.push(block_rewards);
+                    rewards.push(
+                        self.block_reward_percentiles(
+                            entry.header_hash,
+                            entry.gas_used,
+                            entry.base_fee_per_gas,
+                            percentiles,
+                        )
+                        .await?,
+                    );
                 }
             }
             let last_entry = fee_entries.last().expect("is not empty");
@@ -163,20 +168,14 @@ where
 
                 // Percentiles were specified, so we need to collect reward percentile ino
                 if let Some(percentiles) = &reward_percentiles {
-                    let (transactions, receipts) = self
-                        .cache()
-                        .get_transactions_and_receipts(header.hash())
-                        .await?
-                        .ok_or(EthApiError::InvalidBlockRange)?;
                     rewards.push(
-                        calculate_reward_percentiles_for_block(
-                            percentiles,
+                        self.block_reward_percentiles(
+                            header.hash(),
                             header.gas_used,
                             header.base_fee_per_gas.unwrap_or_default(),
-                            &transactions,
-                            &receipts,
+                            percentiles,
                         )
-                        .unwrap_or_default(),
+                        .await?,
                     );
                 }
             }
@@ -210,17 +209,31 @@ where
         })
     }
 
-    /// Approximates reward at a given percentile for a specific block
-    /// Based on the configured resolution
-    fn approximate_percentile(&self, entry: &FeeHistoryEntry, requested_percentile: f64) -> u128 {
-        let resolution = self.fee_history_cache().resolution();
-        let rounded_percentile =
-            (requested_percentile * resolution as f64).round() / resolution as f64;
-        let clamped_percentile = rounded_percentile.clamp(0.0, 100.0);
-
-        // Calculate the index in the precomputed rewards array
-        let index = (clamped_percentile / (1.0 / resolution as f64)).round() as usize;
-        // Fetch the reward from the FeeHistoryEntry
-        entry.rewards.get(index).cloned().unwrap_or_default()
+    /// Returns the rewards at the given percentiles of the gas used by the block.
+    ///
+    /// The sorted tips of the block's transactions are cached, so the transactions and receipts of
+    /// the block are only read if the block is not cached yet.
+    async fn block_reward_percentiles(
+        &self,
+        block_hash: B256,
+        gas_used: u64,
+        base_fee_per_gas: u64,
+        percentiles: &[f64],
+    ) -> EthResult<Vec<u128>> {
+        let tips = match self.fee_history_cache().block_tips(block_hash) {
+            Some(tips) => tips,
+            None => {
+                let (transactions, receipts) = self
+                    .cache()
+                    .get_transactions_and_receipts(block_hash)
+                    .await?
+                    .ok_or(EthApiError::InvalidBlockRange)?;
+                let tips =
+                    Arc::new(BlockTips::new(gas_used, base_fee_per_gas, &transactions, &receipts));
+                self.fee_history_cache().insert_block_tips(block_hash, tips.clone());
+                tips
+            }
+        };
+        Ok(tips.reward_percentiles(percentiles))
     }
 }
@@ -18,11 +18,12 @@ use reth_interfaces::RethResult;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
     revm_primitives::{BlockEnv, CfgEnvWithHandlerCfg},
-    Address, BlockId, BlockNumberOrTag, ChainInfo, SealedBlockWithSenders, SealedHeader, B256,
-    U256, U64,
+    Address, BlockId, BlockNumber, BlockNumberOrTag, ChainInfo, PruneSegment,
+    SealedBlockWithSenders, SealedHeader, B256, U256, U64,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, PruneCheckpointReader, StateProviderBox,
+    StateProviderFactory,
 };
 use reth_rpc_types::{SyncInfo, SyncStatus};
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner, TokioTaskExecutor};
@@ -46,6 +47,7 @@ mod optimism;
 mod pending_block;
 mod server;
 mod sign;
+mod simulate;
 mod state;
 mod transactions;
 
@@ -102,6 +104,7 @@ where
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
         gas_cap: impl Into<GasCap>,
+        eth_proof_window: u64,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
         evm_config: EvmConfig,
@@ -114,6 +117,7 @@ where
             eth_cache,
             gas_oracle,
             gas_cap.into().into(),
+            eth_proof_window,
             Box::<TokioTaskExecutor>::default(),
             blocking_task_pool,
             fee_history_cache,
@@ -131,6 +135,7 @@ where
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
         gas_cap: u64,
+        eth_proof_window: u64,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
@@ -153,6 +158,7 @@ where
             eth_cache,
             gas_oracle,
             gas_cap,
+            eth_proof_window,
             starting_block: U256::from(latest_block),
             task_spawner,
             pending_block: Default::default(),
@@ -202,6 +208,11 @@ where
         self.inner.gas_cap
     }
 
+    /// Returns the maximum number of blocks below the tip for which `eth_getProof` is served
+    pub fn eth_proof_window(&self) -> u64 {
+        self.inner.eth_proof_window
+    }
+
     /// Returns the inner `Provider`
     pub fn provider(&self) -> &Provider {
         &self.inner.provider
@@ -223,6 +234,38 @@ where
     }
 }
 
+// === Pruning helpers ===
+
+impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
+where
+    Provider: BlockReaderIdExt + ChainSpecProvider + PruneCheckpointReader + 'static,
+{
+    /// Returns [EthApiError::PrunedHistory] if the receipts of the given block were pruned,
+    /// either entirely or by the receipts log filter.
+    pub(crate) fn ensure_receipts_not_pruned(&self, block_number: BlockNumber) -> EthResult<()> {
+        for segment in [PruneSegment::Receipts, PruneSegment::ContractLogs] {
+            let pruned_block = self
+                .provider()
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number);
+            if pruned_block.is_some_and(|pruned_block| block_number <= pruned_block) {
+                return Err(EthApiError::PrunedHistory(block_number))
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns [EthApiError::PrunedHistory] if the transaction exists, but its receipt was
+    /// pruned.
+    pub(crate) fn ensure_transaction_receipt_not_pruned(&self, hash: B256) -> EthResult<()> {
+        match self.provider().transaction_by_hash_with_meta(hash)? {
+            Some((_, meta)) => self.ensure_receipts_not_pruned(meta.block_number),
+            None => Ok(()),
+        }
+    }
+}
+
 // === State access helpers ===
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
@@ -232,7 +275,11 @@ where
 {
     /// Returns the state at the given [BlockId] enum.
     ///
-    /// Note: if not [BlockNumberOrTag::Pending] then this will only return canonical state. See also <https://github.com/paradigmxyz/reth/issues/4515>
+    /// Note: if a block number other than [BlockNumberOrTag::Pending] is given then this will only
+    /// return canonical state. See also <https://github.com/paradigmxyz/reth/issues/4515>
+    ///
+    /// A block hash also resolves to a non-canonical block of the tree, unless `requireCanonical`
+    /// is set.
     pub fn state_at_block_id(&self, at: BlockId) -> EthResult<StateProviderBox> {
         Ok(self.provider().state_by_block_id(at)?)
     }
@@ -343,13 +390,14 @@ where
             }
 
             // we rebuild the block
-            let pending_block = match pending.build_block(this.provider(), this.pool()) {
-                Ok(block) => block,
-                Err(err) => {
-                    tracing::debug!(target: "rpc", "Failed to build pending block: {:?}", err);
-                    return Ok(None)
-                }
-            };
+            let pending_block =
+                match pending.build_block(this.provider(), this.pool(), &this.inner.evm_config) {
+                    Ok(block) => block,
+                    Err(err) => {
+                        tracing::debug!(target: "rpc", "Failed to build pending block: {:?}", err);
+                        return Ok(None)
+                    }
+                };
 
             let now = Instant::now();
             *lock = Some(PendingBlock {
@@ -439,6 +487,12 @@ where
 /// more complex calls.
 pub const RPC_DEFAULT_GAS_CAP: GasCap = GasCap(50_000_000);
 
+/// The default maximum number of blocks below the tip for which `eth_getProof` is served.
+///
+/// Proofs of historical blocks are generated by reverting the changesets of all later blocks in
+/// memory, so the cost of a proof grows with the distance of its block to the tip.
+pub const DEFAULT_ETH_PROOF_WINDOW: u64 = 10_000;
+
 /// The wrapper type for gas limit
 #[derive(Debug, Clone, Copy)]
 pub struct GasCap(u64);
@@ -477,6 +531,8 @@ struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     gas_oracle: GasPriceOracle<Provider>,
     /// Maximum gas limit for `eth_call` and call tracing RPC methods.
     gas_cap: u64,
+    /// Maximum number of blocks below the tip for which `eth_getProof` is served.
+    eth_proof_window: u64,
     /// The block number at which the node started
     starting_block: U256,
     /// The type that can spawn tasks which would otherwise block.
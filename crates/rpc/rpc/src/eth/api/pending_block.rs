@@ -1,12 +1,13 @@
 //! Support for building a pending block via local txpool.
 
 use crate::eth::error::{EthApiError, EthResult};
+use reth_evm::ConfigureEvm;
 use reth_primitives::{
     constants::{eip4844::MAX_DATA_GAS_PER_BLOCK, BEACON_NONCE},
     proofs,
     revm::env::tx_env_with_recovered,
     revm_primitives::{
-        BlockEnv, CfgEnvWithHandlerCfg, EVMError, Env, InvalidTransaction, ResultAndState, SpecId,
+        BlockEnv, CfgEnvWithHandlerCfg, EVMError, InvalidTransaction, ResultAndState, SpecId,
     },
     Block, BlockId, BlockNumberOrTag, ChainSpec, Header, IntoRecoveredTransaction, Receipt,
     Receipts, SealedBlockWithSenders, SealedHeader, B256, EMPTY_OMMER_ROOT_HASH, U256,
@@ -39,14 +40,18 @@ impl PendingBlockEnv {
     ///
     /// After Cancun, if the origin is the actual pending block, the block includes the EIP-4788 pre
     /// block contract call using the parent beacon block root received from the CL.
-    pub(crate) fn build_block<Client, Pool>(
+    ///
+    /// The transactions are executed with the EVM of the given [ConfigureEvm].
+    pub(crate) fn build_block<Client, Pool, EvmConfig>(
         self,
         client: &Client,
         pool: &Pool,
+        evm_config: &EvmConfig,
     ) -> EthResult<SealedBlockWithSenders>
     where
         Client: StateProviderFactory + ChainSpecProvider,
         Pool: TransactionPool,
+        EvmConfig: ConfigureEvm,
     {
         let Self { cfg, block_env, origin } = self;
 
@@ -82,6 +87,7 @@ impl PendingBlockEnv {
             // parent beacon block root
             pre_block_beacon_root_contract_call(
                 &mut db,
+                evm_config,
                 chain_spec.as_ref(),
                 block_number,
                 &cfg,
@@ -131,10 +137,13 @@ impl PendingBlockEnv {
             }
 
             // Configure the environment for the block.
-            let env =
-                Env::boxed(cfg.cfg_env.clone(), block_env.clone(), tx_env_with_recovered(&tx));
+            let env = EnvWithHandlerCfg::new_with_cfg_env(
+                cfg.clone(),
+                block_env.clone(),
+                tx_env_with_recovered(&tx),
+            );
 
-            let mut evm = revm::Evm::builder().with_env(env).with_db(&mut db).build();
+            let mut evm = evm_config.evm_with_env(&mut db, env);
 
             let ResultAndState { result, state } = match evm.transact() {
                 Ok(res) => res,
@@ -270,13 +279,14 @@ impl PendingBlockEnv {
 
 /// Apply the [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) pre block contract call.
 ///
-/// This constructs a new [Evm](revm::Evm) with the given DB, and environment [CfgEnvWithHandlerCfg]
-/// and [BlockEnv]) to execute the pre block contract call.
+/// This constructs a new [Evm](revm::Evm) of the given [ConfigureEvm] with the given DB, and
+/// environment ([CfgEnvWithHandlerCfg] and [BlockEnv]) to execute the pre block contract call.
 ///
 /// This uses [apply_beacon_root_contract_call] to ultimately apply the beacon root contract state
 /// change.
-fn pre_block_beacon_root_contract_call<DB: Database + DatabaseCommit>(
+pub(crate) fn pre_block_beacon_root_contract_call<DB: Database + DatabaseCommit, EvmConfig>(
     db: &mut DB,
+    evm_config: &EvmConfig,
     chain_spec: &ChainSpec,
     block_number: u64,
     initialized_cfg: &CfgEnvWithHandlerCfg,
@@ -285,16 +295,17 @@ fn pre_block_beacon_root_contract_call<DB: Database + DatabaseCommit>(
 ) -> EthResult<()>
 where
     DB::Error: std::fmt::Display,
+    EvmConfig: ConfigureEvm,
 {
     // apply pre-block EIP-4788 contract call
-    let mut evm_pre_block = revm::Evm::builder()
-        .with_db(db)
-        .with_env_with_handler_cfg(EnvWithHandlerCfg::new_with_cfg_env(
+    let mut evm_pre_block = evm_config.evm_with_env(
+        db,
+        EnvWithHandlerCfg::new_with_cfg_env(
             initialized_cfg.clone(),
             initialized_block_env.clone(),
             Default::default(),
-        ))
-        .build();
+        ),
+    );
 
     // initialize a block from the env, because the pre block call needs the block itself
     apply_beacon_root_contract_call(
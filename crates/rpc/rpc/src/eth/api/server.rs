@@ -19,13 +19,15 @@ use reth_primitives::{
 };
 use reth_provider::{
     BlockIdReader, BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider,
-    HeaderProvider, StateProviderFactory,
+    HeaderProvider, PruneCheckpointReader, StateProviderFactory,
 };
-use reth_rpc_api::EthApiServer;
+use reth_rpc_api::{EthApiServer, EthConditionalApiServer};
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
+    simulate::{SimulatePayload, SimulatedBlock, SimulatedCall},
+    state::StateOverride,
+    AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
     EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index, RichBlock,
-    StateContext, SyncStatus, TransactionRequest, Work,
+    StateContext, SyncStatus, TransactionConditional, TransactionRequest, Work,
 };
 use reth_transaction_pool::TransactionPool;
 use serde_json::Value;
@@ -43,6 +45,7 @@ where
         + HeaderProvider
         + StateProviderFactory
         + EvmEnvProvider
+        + PruneCheckpointReader
         + 'static,
     Network: NetworkInfo + Send + Sync + 'static,
     EvmConfig: ConfigureEvm + 'static,
@@ -211,7 +214,14 @@ where
     /// Handler for: `eth_getTransactionReceipt`
     async fn transaction_receipt(&self, hash: B256) -> Result<Option<AnyTransactionReceipt>> {
         trace!(target: "rpc::eth", ?hash, "Serving eth_getTransactionReceipt");
-        Ok(EthTransactions::transaction_receipt(self, hash).await?)
+        let receipt = EthTransactions::transaction_receipt(self, hash).await?;
+        if receipt.is_none() {
+            self.on_blocking_task(|this| async move {
+                this.ensure_transaction_receipt_not_pruned(hash)
+            })
+            .await?;
+        }
+        Ok(receipt)
     }
 
     /// Handler for: `eth_getBalance`
@@ -292,6 +302,27 @@ where
         Ok(EthApi::call_many(self, bundle, state_context, state_override).await?)
     }
 
+    /// Handler for: `eth_simulateBundle`
+    async fn simulate_bundle(
+        &self,
+        bundle: Bundle,
+        state_context: Option<StateContext>,
+        state_override: Option<StateOverride>,
+    ) -> Result<Vec<SimulatedCall>> {
+        trace!(target: "rpc::eth", ?bundle, ?state_context, ?state_override, "Serving eth_simulateBundle");
+        Ok(EthApi::simulate_bundle(self, bundle, state_context, state_override).await?)
+    }
+
+    /// Handler for: `eth_simulateV1`
+    async fn simulate_v1(
+        &self,
+        opts: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> Result<Vec<SimulatedBlock>> {
+        trace!(target: "rpc::eth", ?block_number, "Serving eth_simulateV1");
+        Ok(EthApi::simulate_v1(self, opts, block_number).await?)
+    }
+
     /// Handler for: `eth_createAccessList`
     async fn create_access_list(
         &self,
@@ -433,13 +464,32 @@ where
     }
 }
 
+#[async_trait::async_trait]
+impl<Provider, Pool, Network, EvmConfig> EthConditionalApiServer
+    for EthApi<Provider, Pool, Network, EvmConfig>
+where
+    Self: EthTransactions,
+    Pool: TransactionPool + 'static,
+    Provider:
+        BlockReaderIdExt + ChainSpecProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+    EvmConfig: ConfigureEvm + 'static,
+{
+    /// Handler for: `eth_sendRawTransactionConditional`
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> Result<B256> {
+        trace!(target: "rpc::eth", ?tx, ?conditional, "Serving eth_sendRawTransactionConditional");
+        Ok(EthTransactions::send_raw_transaction_conditional(self, tx, conditional).await?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        eth::{
-            cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache,
-            FeeHistoryCacheConfig,
-        },
+        eth::{error::PRUNED_HISTORY_UNAVAILABLE_CODE, test_utils::build_test_eth_api},
         EthApi,
     };
     use jsonrpsee::types::error::INVALID_PARAMS_CODE;
@@ -447,48 +497,13 @@ mod tests {
     use reth_interfaces::test_utils::{generators, generators::Rng};
     use reth_network_api::noop::NoopNetwork;
     use reth_primitives::{
-        basefee::calculate_next_block_base_fee, constants::ETHEREUM_BLOCK_GAS_LIMIT, BaseFeeParams,
-        Block, BlockNumberOrTag, Header, TransactionSigned, B256,
-    };
-    use reth_provider::{
-        test_utils::{MockEthProvider, NoopProvider},
-        BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory,
+        basefee::calculate_next_block_base_fee, BaseFeeParams, Block, BlockNumberOrTag, Header,
+        PruneCheckpoint, PruneMode, PruneSegment, TransactionSigned, B256,
     };
+    use reth_provider::test_utils::{MockEthProvider, NoopProvider};
     use reth_rpc_api::EthApiServer;
     use reth_rpc_types::FeeHistory;
-    use reth_tasks::pool::BlockingTaskPool;
-    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
-
-    fn build_test_eth_api<
-        P: BlockReaderIdExt
-            + BlockReader
-            + ChainSpecProvider
-            + EvmEnvProvider
-            + StateProviderFactory
-            + Unpin
-            + Clone
-            + 'static,
-    >(
-        provider: P,
-    ) -> EthApi<P, TestPool, NoopNetwork, EthEvmConfig> {
-        let evm_config = EthEvmConfig::default();
-        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
-        let fee_history_cache =
-            FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
-
-        EthApi::new(
-            provider.clone(),
-            testing_pool(),
-            NoopNetwork::default(),
-            cache.clone(),
-            GasPriceOracle::new(provider, Default::default(), cache),
-            ETHEREUM_BLOCK_GAS_LIMIT,
-            BlockingTaskPool::build().expect("failed to build tracing pool"),
-            fee_history_cache,
-            evm_config,
-            None,
-        )
-    }
+    use reth_transaction_pool::test_utils::TestPool;
 
     // Function to prepare the EthApi with mock data
     fn prepare_eth_api(
@@ -732,4 +747,53 @@ mod tests {
             "all: no percentiles were requested, so there should be no rewards result"
         );
     }
+
+    /// Receipts below the prune checkpoint are reported as pruned history
+    #[tokio::test]
+    async fn test_pruned_receipts() {
+        let mut rng = generators::rng();
+        let mock_provider = MockEthProvider::default();
+
+        let mut transactions = vec![];
+        for number in 1..=2 {
+            let transaction = generators::random_signed_tx(&mut rng);
+            let header = Header { number, ..Default::default() };
+            mock_provider.add_block(
+                header.hash_slow(),
+                Block { header, body: vec![transaction.clone()], ..Default::default() },
+            );
+            transactions.push(transaction);
+        }
+        mock_provider.add_prune_checkpoint(
+            PruneSegment::Receipts,
+            PruneCheckpoint {
+                block_number: Some(1),
+                tx_number: None,
+                prune_mode: PruneMode::Before(2),
+            },
+        );
+        let eth_api = build_test_eth_api(mock_provider);
+
+        let error = <EthApi<_, _, _, _> as EthApiServer>::transaction_receipt(
+            &eth_api,
+            transactions[0].hash(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.code(), PRUNED_HISTORY_UNAVAILABLE_CODE);
+
+        let error = <EthApi<_, _, _, _> as EthApiServer>::block_receipts(&eth_api, 1.into())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), PRUNED_HISTORY_UNAVAILABLE_CODE);
+
+        // the receipt of a block above the prune checkpoint is merely not found
+        let receipt = <EthApi<_, _, _, _> as EthApiServer>::transaction_receipt(
+            &eth_api,
+            transactions[1].hash(),
+        )
+        .await
+        .unwrap();
+        assert!(receipt.is_none());
+    }
 }
@@ -0,0 +1,687 @@
+//! Contains the `eth_simulateV1` implementation, see
+//! <https://github.com/ethereum/execution-apis/pull/484>
+
+use crate::{
+    eth::{
+        error::{
+            EthApiError, EthResult, EthSimulateError, RevertError, RpcInvalidTransactionError,
+        },
+        revm_utils::{apply_block_overrides, apply_state_overrides, build_call_evm_env},
+        EthTransactions,
+    },
+    EthApi,
+};
+use reth_evm::ConfigureEvm;
+use reth_network_api::NetworkInfo;
+use reth_primitives::{
+    constants::{BEACON_NONCE, EMPTY_WITHDRAWALS},
+    logs_bloom, proofs, Address, Block, BlockId, BlockNumberOrTag, BlockWithSenders, Bytes,
+    ChainSpec, Header, Log, Receipt, SealedHeader, Signature, Transaction, TransactionKind,
+    TransactionSigned, TxEip1559, TxLegacy, Withdrawals, B256, EMPTY_OMMER_ROOT_HASH, U256, U64,
+};
+use reth_provider::{
+    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProviderBox,
+    StateProviderFactory,
+};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_types::{
+    simulate::{
+        SimBlock, SimCallResult, SimulateError, SimulatePayload, SimulatedBlock,
+        TRANSFER_LOG_EMITTER, TRANSFER_LOG_TOPIC,
+    },
+    BlockTransactionsKind, TransactionRequest,
+};
+use reth_rpc_types_compat::block::from_block;
+use reth_transaction_pool::TransactionPool;
+use revm::{
+    db::{AccountState, BundleState, CacheDB, DatabaseRef},
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+    primitives::{
+        BlockEnv, CfgEnvWithHandlerCfg, ExecutionResult, ResultAndState, SpecId, TransactTo, TxEnv,
+    },
+    Database, DatabaseCommit, EvmContext, Inspector,
+};
+use std::sync::Arc;
+
+/// The maximum number of blocks, including the blocks that fill gaps, that are simulated in one
+/// request.
+const MAX_SIMULATE_BLOCKS: u64 = 256;
+
+/// The time between two simulated blocks if their timestamps are not overridden.
+const SIMULATE_BLOCK_TIME: u64 = 12;
+
+/// Error code of a reverted call.
+const REVERTED_CODE: i32 = -32000;
+
+/// Error code of a call that halted.
+const VM_ERROR_CODE: i32 = -32015;
+
+/// The database the blocks are simulated on.
+type SimulateDb = CacheDB<StateProviderDatabase<StateProviderBox>>;
+
+impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Provider: BlockReaderIdExt
+        + ChainSpecProvider
+        + HeaderProvider
+        + StateProviderFactory
+        + EvmEnvProvider
+        + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+    EvmConfig: ConfigureEvm + 'static,
+{
+    /// Simulates the blocks of calls of an `eth_simulateV1` request on top of the given block.
+    ///
+    /// Each call sees the state changes of all previous calls. Gaps between the numbers of the
+    /// requested blocks are filled with empty blocks. The gas of the calls of all blocks is limited
+    /// by the configured gas cap.
+    pub async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> EthResult<Vec<SimulatedBlock>> {
+        let SimulatePayload {
+            block_state_calls,
+            trace_transfers,
+            validation,
+            return_full_transactions,
+        } = payload;
+        if block_state_calls.is_empty() {
+            return Err(EthApiError::InvalidParams(String::from("blockStateCalls are empty.")))
+        }
+
+        let target_block = block_number.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let ((cfg, block_env, at), block) =
+            futures::try_join!(self.evm_env_at(target_block), self.block_by_id(target_block))?;
+        let block = block.ok_or(EthApiError::UnknownBlockNumber)?;
+        let total_difficulty =
+            self.provider().header_td_by_number(block.number)?.unwrap_or_default();
+
+        let this = self.clone();
+        self.spawn_with_state_at_block(at, move |state| {
+            let base = block.header;
+            let mut simulator = BlockSimulator {
+                eth_api: &this,
+                cfg,
+                block_env,
+                chain_spec: this.provider().chain_spec(),
+                db: CacheDB::new(StateProviderDatabase::new(state)),
+                gas_cap: this.inner.gas_cap,
+                gas_remaining: this.inner.gas_cap,
+                state_changed: false,
+                trace_transfers,
+                validation,
+                kind: return_full_transactions.into(),
+                total_difficulty,
+            };
+
+            let mut blocks = Vec::with_capacity(block_state_calls.len());
+            let mut parent = base.clone();
+            for SimBlock { block_overrides, state_overrides, calls } in block_state_calls {
+                let number = block_overrides
+                    .as_ref()
+                    .and_then(|overrides| overrides.number)
+                    .unwrap_or(U256::from(parent.number + 1));
+                if number <= U256::from(parent.number) {
+                    return Err(EthSimulateError::BlockNumberInvalid(
+                        number,
+                        U256::from(parent.number),
+                    )
+                    .into())
+                }
+                if number > U256::from(base.number + MAX_SIMULATE_BLOCKS) {
+                    return Err(EthSimulateError::TooManyBlocks.into())
+                }
+
+                // fill the gap to the requested block with empty blocks
+                while U256::from(parent.number + 1) < number {
+                    let block_env = simulator.next_block_env(&parent);
+                    let (block, header) = simulator.simulate_block(&parent, block_env, vec![])?;
+                    blocks.push(block);
+                    parent = header;
+                }
+
+                let mut block_env = simulator.next_block_env(&parent);
+                if let Some(mut block_overrides) = block_overrides {
+                    if let Some(block_hashes) = block_overrides.block_hash.take() {
+                        simulator.db.block_hashes.extend(
+                            block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)),
+                        )
+                    }
+                    apply_block_overrides(block_overrides, &mut block_env);
+                }
+                if block_env.timestamp <= U256::from(parent.timestamp) {
+                    return Err(EthSimulateError::BlockTimestampInvalid(
+                        block_env.timestamp,
+                        U256::from(parent.timestamp),
+                    )
+                    .into())
+                }
+
+                if let Some(state_overrides) = state_overrides {
+                    apply_state_overrides(state_overrides, &mut simulator.db)?;
+                    simulator.state_changed = true;
+                }
+
+                let (block, header) = simulator.simulate_block(&parent, block_env, calls)?;
+                blocks.push(block);
+                parent = header;
+            }
+
+            Ok(blocks)
+        })
+        .await
+    }
+}
+
+/// Executes the calls of simulated blocks on top of each other.
+struct BlockSimulator<'a, Eth> {
+    /// The eth api to execute the calls with.
+    eth_api: &'a Eth,
+    /// The configuration of the block the simulation is based on.
+    cfg: CfgEnvWithHandlerCfg,
+    /// The environment of the block the simulation is based on.
+    block_env: BlockEnv,
+    /// The chain spec.
+    chain_spec: Arc<ChainSpec>,
+    /// The state the calls are executed on.
+    db: SimulateDb,
+    /// The gas that can be used by the calls of all blocks.
+    gas_cap: u64,
+    /// The gas that can still be used by the calls of all blocks.
+    gas_remaining: u64,
+    /// Whether the state changed since the state root of the last block was calculated.
+    state_changed: bool,
+    /// Whether to add synthetic logs for ether transfers.
+    trace_transfers: bool,
+    /// Whether to validate the calls like transactions.
+    validation: bool,
+    /// Whether to return full transactions or their hashes.
+    kind: BlockTransactionsKind,
+    /// The total difficulty of the simulated blocks.
+    total_difficulty: U256,
+}
+
+impl<'a, Eth> BlockSimulator<'a, Eth>
+where
+    Eth: EthTransactions,
+{
+    /// Returns the default environment of the block that follows the given parent.
+    fn next_block_env(&self, parent: &SealedHeader) -> BlockEnv {
+        let mut block_env = self.block_env.clone();
+        block_env.number = U256::from(parent.number + 1);
+        block_env.timestamp = U256::from(parent.timestamp + SIMULATE_BLOCK_TIME);
+        // the base fee is only checked if the calls are validated
+        block_env.basefee = if self.validation {
+            let base_fee_params = self.chain_spec.base_fee_params(parent.timestamp);
+            U256::from(parent.next_block_base_fee(base_fee_params).unwrap_or_default())
+        } else {
+            U256::ZERO
+        };
+        block_env
+    }
+
+    /// Executes the calls in a new block on top of the given parent.
+    ///
+    /// Returns the simulated block and its header.
+    fn simulate_block(
+        &mut self,
+        parent: &SealedHeader,
+        block_env: BlockEnv,
+        calls: Vec<TransactionRequest>,
+    ) -> EthResult<(SimulatedBlock, SealedHeader)> {
+        let block_gas_limit: u64 = block_env.gas_limit.saturating_to();
+        let mut gas_used = 0u64;
+        let mut transactions = Vec::with_capacity(calls.len());
+        let mut senders = Vec::with_capacity(calls.len());
+        let mut receipts = Vec::with_capacity(calls.len());
+        let mut results = Vec::with_capacity(calls.len());
+
+        for call in calls {
+            let gas_limit = call.gas;
+            let mut cfg = self.cfg.clone();
+            cfg.disable_base_fee = !self.validation;
+            cfg.disable_eip3607 = !self.validation;
+            cfg.disable_block_gas_limit = true;
+            let mut env = build_call_evm_env(cfg, block_env.clone(), call)?;
+
+            let block_gas_remaining = block_gas_limit.saturating_sub(gas_used);
+            // a call without a gas limit that gets less gas than the block has left is limited by
+            // the gas cap
+            let capped = gas_limit.is_none() && self.gas_remaining < block_gas_remaining;
+            match gas_limit {
+                Some(gas_limit) if gas_limit > U256::from(self.gas_remaining) => {
+                    return Err(EthSimulateError::GasCapExceeded(self.gas_cap).into())
+                }
+                Some(gas_limit) if gas_limit > U256::from(block_gas_remaining) => {
+                    return Err(EthSimulateError::BlockGasLimitExceeded.into())
+                }
+                Some(_) => {}
+                None => env.tx.gas_limit = block_gas_remaining.min(self.gas_remaining),
+            }
+
+            let sender = env.tx.caller;
+            let nonce = self.db.basic_ref(sender)?.map(|acc| acc.nonce).unwrap_or_default();
+            let nonce = if self.validation {
+                env.tx.nonce.unwrap_or(nonce)
+            } else {
+                // the nonce is not checked without validation
+                env.tx.nonce = None;
+                nonce
+            };
+            let transaction = simulated_transaction(&env.tx, nonce);
+
+            let gas_cap = self.gas_cap;
+            let map_err = |err: EthApiError| match err {
+                // the gas left by the gas cap doesn't cover the intrinsic gas of the call
+                EthApiError::InvalidTransaction(
+                    RpcInvalidTransactionError::GasTooLow | RpcInvalidTransactionError::GasTooHigh,
+                ) if capped => EthApiError::from(EthSimulateError::GasCapExceeded(gas_cap)),
+                err => into_simulate_error(err),
+            };
+            let (ResultAndState { result, state }, logs) = if self.trace_transfers {
+                let mut inspector = TransferInspector::default();
+                let (res, _) =
+                    self.eth_api.inspect(&mut self.db, env, &mut inspector).map_err(map_err)?;
+                (res, inspector.logs)
+            } else {
+                let (res, _) = self.eth_api.transact(&mut self.db, env).map_err(map_err)?;
+                let logs = res.result.logs().to_vec();
+                (res, logs)
+            };
+            self.db.commit(state);
+            self.state_changed = true;
+
+            let call_gas_used = result.gas_used();
+            gas_used += call_gas_used;
+            self.gas_remaining = self.gas_remaining.saturating_sub(call_gas_used);
+
+            let (return_data, logs, error) = match result {
+                ExecutionResult::Success { output, .. } => (output.into_data(), logs, None),
+                ExecutionResult::Revert { output, .. } => {
+                    let message = RevertError::new(output.clone()).to_string();
+                    (output, vec![], Some(SimulateError { code: REVERTED_CODE, message }))
+                }
+                ExecutionResult::Halt { reason, gas_used } => {
+                    let message = RpcInvalidTransactionError::halt(reason, gas_used).to_string();
+                    (Bytes::new(), vec![], Some(SimulateError { code: VM_ERROR_CODE, message }))
+                }
+            };
+
+            receipts.push(Receipt {
+                tx_type: transaction.tx_type(),
+                success: error.is_none(),
+                cumulative_gas_used: gas_used,
+                logs,
+                #[cfg(feature = "optimism")]
+                deposit_nonce: None,
+                #[cfg(feature = "optimism")]
+                deposit_receipt_version: None,
+            });
+            results.push(SimCallResult {
+                return_data,
+                logs: vec![],
+                gas_used: U64::from(call_gas_used),
+                status: U64::from(error.is_none() as u8),
+                error,
+            });
+            transactions.push(transaction);
+            senders.push(sender);
+        }
+
+        let spec_id = self.cfg.handler_cfg.spec_id;
+        let timestamp = block_env.timestamp.saturating_to::<u64>();
+
+        // blocks without state changes, like the blocks that fill gaps, keep the root of the parent
+        let state_root = if self.state_changed { self.state_root()? } else { parent.state_root };
+        self.state_changed = false;
+
+        #[cfg(feature = "optimism")]
+        let receipts_root = proofs::calculate_receipt_root_ref_optimism(
+            &receipts.iter().collect::<Vec<_>>(),
+            &self.chain_spec,
+            timestamp,
+        );
+        #[cfg(not(feature = "optimism"))]
+        let receipts_root =
+            proofs::calculate_receipt_root_ref(&receipts.iter().collect::<Vec<_>>());
+
+        let header = Header {
+            parent_hash: parent.hash(),
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            beneficiary: block_env.coinbase,
+            state_root,
+            transactions_root: proofs::calculate_transaction_root(&transactions),
+            receipts_root,
+            withdrawals_root: (spec_id >= SpecId::SHANGHAI).then_some(EMPTY_WITHDRAWALS),
+            logs_bloom: logs_bloom(receipts.iter().flat_map(|receipt| &receipt.logs)),
+            timestamp,
+            mix_hash: block_env.prevrandao.unwrap_or_default(),
+            nonce: BEACON_NONCE,
+            base_fee_per_gas: (spec_id >= SpecId::LONDON)
+                .then(|| block_env.basefee.saturating_to()),
+            number: block_env.number.saturating_to(),
+            gas_limit: block_gas_limit,
+            difficulty: block_env.difficulty,
+            gas_used,
+            blob_gas_used: (spec_id >= SpecId::CANCUN).then_some(0),
+            excess_blob_gas: block_env.get_blob_excess_gas(),
+            extra_data: Default::default(),
+            parent_beacon_block_root: (spec_id >= SpecId::CANCUN).then_some(B256::ZERO),
+        }
+        .seal_slow();
+        // the following blocks can access the hash of this block
+        self.db.block_hashes.insert(U256::from(header.number), header.hash());
+
+        // the logs can only be completed once the block hash is known
+        let mut log_index = 0;
+        for (tx_index, (result, receipt)) in results.iter_mut().zip(receipts).enumerate() {
+            result.logs = receipt
+                .logs
+                .into_iter()
+                .map(|log| {
+                    let log = reth_rpc_types::Log {
+                        inner: log,
+                        block_hash: Some(header.hash()),
+                        block_number: Some(header.number),
+                        block_timestamp: Some(header.timestamp),
+                        transaction_hash: Some(transactions[tx_index].hash()),
+                        transaction_index: Some(tx_index as u64),
+                        log_index: Some(log_index),
+                        removed: false,
+                    };
+                    log_index += 1;
+                    log
+                })
+                .collect();
+        }
+
+        let block = Block {
+            header: header.clone().unseal(),
+            body: transactions,
+            ommers: vec![],
+            withdrawals: (spec_id >= SpecId::SHANGHAI).then(Withdrawals::default),
+        };
+        let inner = from_block(
+            BlockWithSenders { block, senders },
+            self.total_difficulty,
+            self.kind,
+            Some(header.hash()),
+        )?;
+
+        Ok((SimulatedBlock { inner, calls: results }, header))
+    }
+
+    /// Returns the state root of the state after all executed calls.
+    fn state_root(&self) -> EthResult<B256> {
+        let mut bundle = BundleState::builder(0..=0);
+        for (address, account) in &self.db.accounts {
+            let original = self.db.db.basic_ref(*address)?;
+            if account.account_state == AccountState::NotExisting && original.is_none() {
+                continue
+            }
+            if let Some(original) = original {
+                bundle = bundle.state_original_account_info(*address, original);
+            }
+            let storage = account
+                .storage
+                .iter()
+                .map(|(slot, value)| {
+                    Ok((*slot, (self.db.db.storage_ref(*address, *slot)?, *value)))
+                })
+                .collect::<EthResult<_>>()?;
+            bundle = bundle
+                .state_present_account_info(*address, account.info.clone())
+                .state_storage(*address, storage);
+        }
+        Ok(self.db.db.0.state_root(&bundle.build())?)
+    }
+}
+
+/// Maps the errors of invalid calls to the `eth_simulateV1` error codes.
+fn into_simulate_error(err: EthApiError) -> EthApiError {
+    match err {
+        EthApiError::InvalidTransaction(err) => EthSimulateError::InvalidTransaction(err).into(),
+        err => err,
+    }
+}
+
+/// Creates the unsigned transaction of a simulated call.
+fn simulated_transaction(tx: &TxEnv, nonce: u64) -> TransactionSigned {
+    let to = match tx.transact_to {
+        TransactTo::Call(to) => TransactionKind::Call(to),
+        TransactTo::Create(_) => TransactionKind::Create,
+    };
+    let transaction = match tx.gas_priority_fee {
+        Some(max_priority_fee_per_gas) => Transaction::Eip1559(TxEip1559 {
+            chain_id: tx.chain_id.unwrap_or_default(),
+            nonce,
+            gas_limit: tx.gas_limit,
+            max_fee_per_gas: tx.gas_price.saturating_to(),
+            max_priority_fee_per_gas: max_priority_fee_per_gas.saturating_to(),
+            to,
+            value: tx.value,
+            access_list: reth_primitives::AccessList(
+                tx.access_list
+                    .iter()
+                    .map(|(address, slots)| reth_primitives::AccessListItem {
+                        address: *address,
+                        storage_keys: slots.iter().map(|slot| B256::from(*slot)).collect(),
+                    })
+                    .collect(),
+            ),
+            input: tx.data.clone(),
+        }),
+        None => Transaction::Legacy(TxLegacy {
+            chain_id: tx.chain_id,
+            nonce,
+            gas_price: tx.gas_price.saturating_to(),
+            gas_limit: tx.gas_limit,
+            to,
+            value: tx.value,
+            input: tx.data.clone(),
+        }),
+    };
+    TransactionSigned::from_transaction_and_signature(transaction, Signature::default())
+}
+
+/// Returns the synthetic log of an ether transfer.
+fn transfer_log(from: Address, to: Address, value: U256) -> Log {
+    Log::new_unchecked(
+        TRANSFER_LOG_EMITTER,
+        vec![TRANSFER_LOG_TOPIC, from.into_word(), to.into_word()],
+        Bytes::copy_from_slice(&value.to_be_bytes::<32>()),
+    )
+}
+
+/// An [Inspector] that records the logs of a call together with synthetic logs of all ether
+/// transfers, in the order of execution.
+#[derive(Debug, Default)]
+struct TransferInspector {
+    /// The logs of the call.
+    logs: Vec<Log>,
+    /// The number of logs before each of the currently executed call frames.
+    frames: Vec<usize>,
+}
+
+impl TransferInspector {
+    /// Records the start of a call frame.
+    fn enter(&mut self) {
+        self.frames.push(self.logs.len());
+    }
+
+    /// Records the end of a call frame.
+    ///
+    /// The logs of a reverted frame are discarded, the logs of a successful frame are preceded by
+    /// the log of its transfer.
+    fn exit(&mut self, success: bool, transfer: Option<(Address, Address, U256)>) {
+        let start = self.frames.pop().unwrap_or_default();
+        if !success {
+            self.logs.truncate(start);
+            return
+        }
+        if let Some((from, to, value)) = transfer {
+            if from != to && !value.is_zero() {
+                self.logs.insert(start, transfer_log(from, to, value));
+            }
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for TransferInspector {
+    fn log(&mut self, _context: &mut EvmContext<DB>, log: &Log) {
+        self.logs.push(log.clone());
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.enter();
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        let transfer = &inputs.transfer;
+        self.exit(
+            outcome.result.result.is_ok(),
+            Some((transfer.source, transfer.target, transfer.value)),
+        );
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.enter();
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        let transfer = outcome.address.map(|address| (inputs.caller, address, inputs.value));
+        self.exit(outcome.result.result.is_ok(), transfer);
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if contract != target && !value.is_zero() {
+            self.logs.push(transfer_log(contract, target, value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::test_utils::build_test_eth_api;
+    use jsonrpsee::types::ErrorObject;
+    use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, hex_literal::hex};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use serde_json::json;
+
+    /// A contract that loops until it runs out of gas.
+    const LOOP: Address = Address::with_last_byte(0xaa);
+
+    /// The state root of the block the blocks are simulated on.
+    const BASE_STATE_ROOT: B256 = B256::with_last_byte(1);
+
+    /// Returns a provider with the looping contract and a block at the zero hash.
+    fn simulate_provider() -> MockEthProvider {
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            LOOP,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(Bytes::from_static(&hex!("5b600056"))),
+        );
+        let header = Header { state_root: BASE_STATE_ROOT, ..Default::default() };
+        provider.add_block(B256::ZERO, Block { header, ..Default::default() });
+        provider
+    }
+
+    fn payload(block_state_calls: serde_json::Value) -> SimulatePayload {
+        serde_json::from_value(json!({ "blockStateCalls": block_state_calls })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn simulate_enforces_gas_cap() {
+        let eth_api = build_test_eth_api(simulate_provider());
+        let from = Address::with_last_byte(1);
+        let gas = ETHEREUM_BLOCK_GAS_LIMIT - 10_000;
+
+        // the call of the second block only gets the gas left by the first block, which doesn't
+        // cover its intrinsic gas
+        let err = eth_api
+            .simulate_v1(
+                payload(json!([
+                    { "calls": [{ "from": from, "to": LOOP, "gas": U64::from(gas) }] },
+                    { "calls": [{ "from": from, "to": LOOP }] },
+                ])),
+                Some(B256::ZERO.into()),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            EthApiError::Simulate(EthSimulateError::GasCapExceeded(ETHEREUM_BLOCK_GAS_LIMIT))
+        ));
+        assert_eq!(ErrorObject::from(err).code(), -38026);
+
+        // a call with a gas limit above the remaining gas is rejected as well
+        let err = eth_api
+            .simulate_v1(
+                payload(json!([
+                    { "calls": [{ "from": from, "to": LOOP, "gas": U64::from(gas) }] },
+                    { "calls": [{ "from": from, "to": LOOP, "gas": U64::from(21_000) }] },
+                ])),
+                Some(B256::ZERO.into()),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(ErrorObject::from(err).code(), -38026);
+    }
+
+    #[tokio::test]
+    async fn simulate_fills_gaps_with_empty_blocks() {
+        let eth_api = build_test_eth_api(simulate_provider());
+        let call = json!({ "from": Address::with_last_byte(1), "to": Address::with_last_byte(2) });
+
+        let blocks = eth_api
+            .simulate_v1(
+                payload(json!([
+                    { "blockOverrides": { "number": "0x3" }, "calls": [call] },
+                    { "calls": [] },
+                ])),
+                Some(B256::ZERO.into()),
+            )
+            .await
+            .unwrap();
+
+        let numbers = blocks.iter().map(|block| block.inner.header.number).collect::<Vec<_>>();
+        assert_eq!(numbers, vec![Some(1), Some(2), Some(3), Some(4)]);
+        let calls = blocks.iter().map(|block| block.calls.len()).collect::<Vec<_>>();
+        assert_eq!(calls, vec![0, 0, 1, 0]);
+        for block in blocks.windows(2) {
+            assert_eq!(block[1].inner.header.parent_hash, block[0].inner.header.hash.unwrap());
+        }
+
+        // the state root is only calculated for the block that changes the state, the mock
+        // provider returns the zero hash as the calculated root
+        let roots = blocks.iter().map(|block| block.inner.header.state_root).collect::<Vec<_>>();
+        assert_eq!(roots, vec![BASE_STATE_ROOT, BASE_STATE_ROOT, B256::ZERO, B256::ZERO]);
+        assert_eq!(blocks[2].calls[0].status, U64::from(1));
+    }
+}
@@ -1,7 +1,7 @@
 //! Contains RPC handler implementations specific to transactions
 use crate::{
     eth::{
-        api::pending_block::PendingBlockEnv,
+        api::pending_block::{pre_block_beacon_root_contract_call, PendingBlockEnv},
         error::{EthApiError, EthResult, RpcInvalidTransactionError, SignError},
         revm_utils::{prepare_call_env, EvmOverrides},
         utils::recover_raw_transaction,
@@ -14,16 +14,18 @@ use reth_network_api::NetworkInfo;
 use reth_primitives::{
     eip4844::calc_blob_gasprice,
     revm::env::{fill_block_env_with_coinbase, tx_env_with_recovered},
-    Address, BlockId, BlockNumberOrTag, Bytes, FromRecoveredPooledTransaction, Header,
-    IntoRecoveredTransaction, Receipt, SealedBlock, SealedBlockWithSenders,
+    Address, BlockId, BlockNumberOrTag, BlockWithSenders, Bytes, FromRecoveredPooledTransaction,
+    Header, IntoRecoveredTransaction, Receipt, SealedBlock, SealedBlockWithSenders,
     TransactionKind::{Call, Create},
     TransactionMeta, TransactionSigned, TransactionSignedEcRecovered, B256, U256,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+    BlockExecutor, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider,
+    StateProviderBox, StateProviderFactory,
 };
 use reth_revm::{
     database::StateProviderDatabase,
+    processor::EVMProcessor,
     tracing::{TracingInspector, TracingInspectorConfig},
 };
 use reth_rpc_types::{
@@ -31,9 +33,9 @@ use reth_rpc_types::{
         EIP1559TransactionRequest, EIP2930TransactionRequest, EIP4844TransactionRequest,
         LegacyTransactionRequest,
     },
-    AnyReceiptEnvelope, AnyTransactionReceipt, Index, Log, ReceiptWithBloom, Transaction,
-    TransactionInfo, TransactionKind as RpcTransactionKind, TransactionReceipt, TransactionRequest,
-    TypedTransactionRequest, WithOtherFields,
+    AnyReceiptEnvelope, AnyTransactionReceipt, Index, KnownAccount, Log, ReceiptWithBloom,
+    Transaction, TransactionConditional, TransactionInfo, TransactionKind as RpcTransactionKind,
+    TransactionReceipt, TransactionRequest, TypedTransactionRequest, WithOtherFields,
 };
 use reth_rpc_types_compat::transaction::from_recovered_with_block_context;
 use reth_transaction_pool::{TransactionOrigin, TransactionPool};
@@ -60,6 +62,9 @@ use reth_rpc_types::OptimismTransactionReceiptFields;
 use revm::L1BlockInfo;
 use revm_primitives::db::{Database, DatabaseRef};
 
+/// The maximum number of storage slots the conditions of a transaction can reference.
+const MAX_CONDITIONAL_STORAGE_SLOTS: usize = 1000;
+
 /// Helper alias type for the state's [CacheDB]
 pub(crate) type StateCacheDB = CacheDB<StateProviderDatabase<StateProviderBox>>;
 
@@ -148,6 +153,19 @@ pub trait EthTransactions: Send + Sync {
         I: IntoIterator<Item = Tx>,
         Tx: FillableTransaction;
 
+    /// Applies the [EIP-4788](https://eips.ethereum.org/EIPS/eip-4788) pre block contract call of
+    /// the block with the given environment to the given database.
+    fn pre_block_beacon_root_contract_call<DB>(
+        &self,
+        db: &mut DB,
+        cfg: &CfgEnvWithHandlerCfg,
+        block_env: &BlockEnv,
+        parent_beacon_block_root: Option<B256>,
+    ) -> EthResult<()>
+    where
+        DB: Database + DatabaseCommit,
+        <DB as Database>::Error: std::fmt::Display;
+
     /// Returns default gas limit to use for `eth_call` and tracing RPC methods.
     fn call_gas_limit(&self) -> u64;
 
@@ -183,6 +201,19 @@ pub trait EthTransactions: Send + Sync {
         F: FnOnce(StateProviderBox) -> EthResult<T> + Send + 'static,
         T: Send + 'static;
 
+    /// Executes the entire block on top of the given state, including the pre-block and
+    /// post-block state changes, and verifies the receipts of the block.
+    ///
+    /// The given state is expected to be the state of the parent block.
+    fn execute_block<SP>(
+        &self,
+        state: SP,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+    ) -> EthResult<()>
+    where
+        SP: StateProvider;
+
     /// Returns the revm evm env for the requested [BlockId]
     ///
     /// If the [BlockId] this will return the [BlockId] of the block the env was configured
@@ -268,6 +299,18 @@ pub trait EthTransactions: Send + Sync {
     /// Returns the hash of the transaction.
     async fn send_raw_transaction(&self, tx: Bytes) -> EthResult<B256>;
 
+    /// Decodes and recovers the transaction and submits it to the pool, to be included only if the
+    /// given conditions are satisfied.
+    ///
+    /// Rejects the transaction if the conditions aren't satisfied by the latest block and state.
+    ///
+    /// Returns the hash of the transaction.
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> EthResult<B256>;
+
     /// Signs transaction with a matching signer, if any and submits the transaction to the pool.
     /// Returns the hash of the signed transaction.
     async fn send_transaction(&self, request: TransactionRequest) -> EthResult<B256>;
@@ -623,6 +666,28 @@ where
         Ok(index)
     }
 
+    fn pre_block_beacon_root_contract_call<DB>(
+        &self,
+        db: &mut DB,
+        cfg: &CfgEnvWithHandlerCfg,
+        block_env: &BlockEnv,
+        parent_beacon_block_root: Option<B256>,
+    ) -> EthResult<()>
+    where
+        DB: Database + DatabaseCommit,
+        <DB as Database>::Error: std::fmt::Display,
+    {
+        pre_block_beacon_root_contract_call(
+            db,
+            &self.inner.evm_config,
+            self.provider().chain_spec().as_ref(),
+            block_env.number.to::<u64>(),
+            cfg,
+            block_env,
+            parent_beacon_block_root,
+        )
+    }
+
     fn call_gas_limit(&self) -> u64 {
         self.inner.gas_cap
     }
@@ -667,6 +732,25 @@ where
         .await
     }
 
+    fn execute_block<SP>(
+        &self,
+        state: SP,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+    ) -> EthResult<()>
+    where
+        SP: StateProvider,
+    {
+        let mut executor = EVMProcessor::new_with_db(
+            self.provider().chain_spec(),
+            StateProviderDatabase::new(state),
+            self.inner.evm_config.clone(),
+        );
+        executor
+            .execute_and_verify_receipt(block, total_difficulty)
+            .map_err(|err| EthApiError::Internal(err.into()))
+    }
+
     async fn evm_env_at(
         &self,
         at: BlockId,
@@ -862,6 +946,44 @@ where
         Ok(hash)
     }
 
+    async fn send_raw_transaction_conditional(
+        &self,
+        tx: Bytes,
+        conditional: TransactionConditional,
+    ) -> EthResult<B256> {
+        // Forwarded transactions are included by the sequencer, which can't enforce the conditions
+        if self.inner.raw_transaction_forwarder.is_some() {
+            return Err(EthApiError::Unsupported(
+                "conditional transactions are not supported with a sequencer",
+            ))
+        }
+
+        let conditional = pool_conditional(conditional);
+        if conditional.storage_slots() > MAX_CONDITIONAL_STORAGE_SLOTS {
+            return Err(EthApiError::TransactionConditionalRejected("too many storage slots"))
+        }
+
+        let latest = self.provider().latest_header()?.ok_or(EthApiError::UnknownBlockNumber)?;
+        if conditional.has_exceeded_block_attributes(latest.number, latest.timestamp) {
+            return Err(EthApiError::TransactionConditionalRejected("block attributes exceeded"))
+        }
+        if !conditional.known_accounts_match(&self.provider().latest()?)? {
+            return Err(EthApiError::TransactionConditionalRejected("storage mismatch"))
+        }
+
+        let recovered = recover_raw_transaction(tx)?;
+        let pool_transaction = <Pool::Transaction>::from_recovered_pooled_transaction(recovered);
+
+        // submit the transaction with a `Private` origin, so that it isn't propagated to peers that
+        // would include it regardless of the conditions
+        let hash = self
+            .pool()
+            .add_conditional_transaction(TransactionOrigin::Private, pool_transaction, conditional)
+            .await?;
+
+        Ok(hash)
+    }
+
     async fn send_transaction(&self, mut request: TransactionRequest) -> EthResult<B256> {
         let from = match request.from {
             Some(from) => from,
@@ -1666,6 +1788,36 @@ impl From<TransactionSource> for Transaction {
     }
 }
 
+/// Converts the conditions of an `eth_sendRawTransactionConditional` request into the conditions
+/// the pool enforces.
+fn pool_conditional(
+    conditional: TransactionConditional,
+) -> reth_transaction_pool::TransactionConditional {
+    let known_accounts = conditional
+        .known_accounts
+        .into_iter()
+        .map(|(address, account)| {
+            let account = match account {
+                KnownAccount::StorageRoot(root) => {
+                    reth_transaction_pool::KnownAccount::StorageRoot(root)
+                }
+                KnownAccount::StorageSlots(slots) => {
+                    reth_transaction_pool::KnownAccount::StorageSlots(slots)
+                }
+            };
+            (address, account)
+        })
+        .collect();
+
+    reth_transaction_pool::TransactionConditional {
+        known_accounts,
+        block_number_min: conditional.block_number_min.map(|number| number.to()),
+        block_number_max: conditional.block_number_max.map(|number| number.to()),
+        timestamp_min: conditional.timestamp_min.map(|timestamp| timestamp.to()),
+        timestamp_max: conditional.timestamp_max.map(|timestamp| timestamp.to()),
+    }
+}
+
 /// Helper function to construct a transaction receipt
 ///
 /// Note: This requires _all_ block receipts because we need to calculate the gas used by the
@@ -1693,17 +1845,40 @@ pub(crate) fn build_transaction_receipt_with_block_receipts(
             .unwrap_or_default()
     };
 
-    let blob_gas_used = transaction.transaction.blob_gas_used();
-    // Blob gas price should only be present if the transaction is a blob transaction
-    let blob_gas_price = blob_gas_used.and_then(|_| meta.excess_blob_gas.map(calc_blob_gasprice));
-    let logs_bloom = receipt.bloom_slow();
-
     // get number of logs in the block
     let mut num_logs = 0;
     for prev_receipt in all_receipts.iter().take(meta.index as usize) {
         num_logs += prev_receipt.logs.len();
     }
 
+    Ok(build_transaction_receipt(
+        transaction,
+        from,
+        meta,
+        receipt,
+        gas_used,
+        num_logs,
+        #[cfg(feature = "optimism")]
+        optimism_tx_meta,
+    ))
+}
+
+/// Helper function to construct a transaction receipt from its sender, the gas used by the
+/// transaction and the number of logs of the previous transactions in the block.
+pub(crate) fn build_transaction_receipt(
+    transaction: TransactionSigned,
+    from: Address,
+    meta: TransactionMeta,
+    receipt: Receipt,
+    gas_used: u64,
+    num_logs: usize,
+    #[cfg(feature = "optimism")] optimism_tx_meta: OptimismTxMeta,
+) -> AnyTransactionReceipt {
+    let blob_gas_used = transaction.transaction.blob_gas_used();
+    // Blob gas price should only be present if the transaction is a blob transaction
+    let blob_gas_price = blob_gas_used.and_then(|_| meta.excess_blob_gas.map(calc_blob_gasprice));
+    let logs_bloom = receipt.bloom_slow();
+
     let mut logs = Vec::with_capacity(receipt.logs.len());
     for (tx_log_idx, log) in receipt.logs.into_iter().enumerate() {
         let rpclog = Log {
@@ -1778,7 +1953,7 @@ pub(crate) fn build_transaction_receipt_with_block_receipts(
         }
     }
 
-    Ok(res_receipt)
+    res_receipt
 }
 
 #[cfg(test)]
@@ -1786,6 +1961,7 @@ mod tests {
     use super::*;
     use crate::eth::{
         cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache, FeeHistoryCacheConfig,
+        DEFAULT_ETH_PROOF_WINDOW,
     };
     use reth_evm_ethereum::EthEvmConfig;
     use reth_network_api::noop::NoopNetwork;
@@ -1812,6 +1988,7 @@ mod tests {
             cache.clone(),
             GasPriceOracle::new(noop_provider, Default::default(), cache.clone()),
             ETHEREUM_BLOCK_GAS_LIMIT,
+            DEFAULT_ETH_PROOF_WINDOW,
             BlockingTaskPool::build().expect("failed to build tracing pool"),
             fee_history_cache,
             evm_config,
@@ -99,13 +99,14 @@ where
         // use the block number of the request
         block_env.number = U256::from(block_number);
 
+        let this = self.clone();
         self.inner
             .eth_api
             .spawn_with_state_at_block(at, move |state| {
                 let coinbase = block_env.coinbase;
                 let basefee = Some(block_env.basefee.to::<u64>());
                 let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, TxEnv::default());
-                let db = CacheDB::new(StateProviderDatabase::new(state));
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
 
                 let initial_coinbase = DatabaseRef::basic_ref(&db, coinbase)?
                     .map(|acc| acc.balance)
@@ -116,9 +117,6 @@ where
                 let mut total_gas_fess = U256::ZERO;
                 let mut hash_bytes = Vec::with_capacity(32 * transactions.len());
 
-                let mut evm =
-                    revm::Evm::builder().with_db(db).with_env_with_handler_cfg(env).build();
-
                 let mut results = Vec::with_capacity(transactions.len());
                 let mut transactions = transactions.into_iter().peekable();
 
@@ -136,8 +134,11 @@ where
                     let gas_price = tx
                         .effective_tip_per_gas(basefee)
                         .ok_or_else(|| RpcInvalidTransactionError::FeeCapTooLow)?;
-                    tx.try_fill_tx_env(evm.tx_mut())?;
-                    let ResultAndState { result, state } = evm.transact()?;
+                    // the transactions are executed with the evm of the node
+                    let mut env = env.clone();
+                    tx.try_fill_tx_env(&mut env.tx)?;
+                    let (ResultAndState { result, state }, _) =
+                        this.inner.eth_api.transact(&mut db, env)?;
 
                     let gas_used = result.gas_used();
                     total_gas_used += gas_used;
@@ -183,7 +184,7 @@ where
                     if transactions.peek().is_some() {
                         // need to apply the state changes of this call before executing
                         // the next call
-                        evm.context.evm.db.commit(state)
+                        db.commit(state)
                     }
                 }
 
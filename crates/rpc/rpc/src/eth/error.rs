@@ -4,7 +4,7 @@ use crate::result::{internal_rpc_err, invalid_params_rpc_err, rpc_err, rpc_error
 use alloy_sol_types::decode_revert_reason;
 use jsonrpsee::types::{error::CALL_EXECUTION_FAILED_CODE, ErrorObject};
 use reth_interfaces::RethError;
-use reth_primitives::{revm_primitives::InvalidHeader, Address, Bytes, U256};
+use reth_primitives::{revm_primitives::InvalidHeader, Address, BlockNumber, Bytes, U256};
 use reth_revm::tracing::{js::JsInspectorError, MuxError};
 use reth_rpc_types::{error::EthRpcErrorCode, request::TransactionInputError, BlockError};
 use reth_transaction_pool::error::{
@@ -17,6 +17,13 @@ use std::time::Duration;
 /// Result alias
 pub type EthResult<T> = Result<T, EthApiError>;
 
+/// Pruned history unavailable error code.
+pub const PRUNED_HISTORY_UNAVAILABLE_CODE: i32 = 4444;
+
+/// Error code of a rejected `eth_sendRawTransactionConditional` request, see also
+/// <https://notes.ethereum.org/@yoav/SkaX2lS9j>
+pub const TRANSACTION_CONDITIONAL_REJECTED_CODE: i32 = -32003;
+
 /// A tait for custom rpc errors used by [EthApiError::Other].
 pub trait ToRpcError: std::error::Error + Send + Sync + 'static {
     /// Converts the error to a JSON-RPC error object.
@@ -48,9 +55,16 @@ pub enum EthApiError {
     /// Thrown when an unknown block or transaction index is encountered
     #[error("unknown block or tx index")]
     UnknownBlockOrTxIndex,
+    /// Thrown when the requested data of the block was pruned by the node
+    #[error("pruned history unavailable for block {0}")]
+    PrunedHistory(BlockNumber),
     /// When an invalid block range is provided
     #[error("invalid block range")]
     InvalidBlockRange,
+    /// Thrown when the target block of `eth_getProof` is further below the tip than the
+    /// configured proof window
+    #[error("distance to target block exceeds maximum proof window")]
+    ExceedsMaxProofWindow,
     /// An internal error where prevrandao is not set in the evm's environment
     #[error("prevrandao not in the EVM's environment after merge")]
     PrevrandaoNotSet,
@@ -82,6 +96,10 @@ pub enum EthApiError {
     /// Some feature is unsupported
     #[error("unsupported")]
     Unsupported(&'static str),
+    /// Thrown when the conditions of a transaction submitted via
+    /// `eth_sendRawTransactionConditional` can't be satisfied
+    #[error("transaction conditional rejected: {0}")]
+    TransactionConditionalRejected(&'static str),
     /// General purpose error for invalid params
     #[error("{0}")]
     InvalidParams(String),
@@ -118,6 +136,9 @@ pub enum EthApiError {
     /// Error thrown when tracing with a muxTracer fails
     #[error(transparent)]
     MuxTracerError(#[from] MuxError),
+    /// Errors of `eth_simulateV1`
+    #[error(transparent)]
+    Simulate(#[from] EthSimulateError),
     /// Any other error
     #[error("0")]
     Other(Box<dyn ToRpcError>),
@@ -137,6 +158,7 @@ impl From<EthApiError> for ErrorObject<'static> {
             EthApiError::InvalidTransactionSignature |
             EthApiError::EmptyRawTransactionData |
             EthApiError::InvalidBlockRange |
+            EthApiError::ExceedsMaxProofWindow |
             EthApiError::ConflictingFeeFieldsInRequest |
             EthApiError::Signing(_) |
             EthApiError::BothStateAndStateDiffInOverride(_) |
@@ -156,7 +178,13 @@ impl From<EthApiError> for ErrorObject<'static> {
             EthApiError::UnknownSafeOrFinalizedBlock => {
                 rpc_error_with_code(EthRpcErrorCode::UnknownBlock.code(), error.to_string())
             }
+            EthApiError::PrunedHistory(_) => {
+                rpc_error_with_code(PRUNED_HISTORY_UNAVAILABLE_CODE, error.to_string())
+            }
             EthApiError::Unsupported(msg) => internal_rpc_err(msg),
+            EthApiError::TransactionConditionalRejected(_) => {
+                rpc_error_with_code(TRANSACTION_CONDITIONAL_REJECTED_CODE, error.to_string())
+            }
             EthApiError::InternalJsTracerError(msg) => internal_rpc_err(msg),
             EthApiError::InvalidParams(msg) => invalid_params_rpc_err(msg),
             EthApiError::InvalidRewardPercentiles => internal_rpc_err(error.to_string()),
@@ -168,6 +196,7 @@ impl From<EthApiError> for ErrorObject<'static> {
             err @ EthApiError::TransactionInputError(_) => invalid_params_rpc_err(err.to_string()),
             EthApiError::Other(err) => err.to_rpc_error(),
             EthApiError::MuxTracerError(msg) => internal_rpc_err(msg.to_string()),
+            EthApiError::Simulate(err) => err.into(),
         }
     }
 }
@@ -205,6 +234,7 @@ impl From<reth_interfaces::provider::ProviderError> for EthApiError {
             ProviderError::FinalizedBlockNotFound | ProviderError::SafeBlockNotFound => {
                 EthApiError::UnknownSafeOrFinalizedBlock
             }
+            ProviderError::StateAtBlockPruned(block) => EthApiError::PrunedHistory(block),
             err => EthApiError::Internal(err.into()),
         }
     }
@@ -568,9 +598,15 @@ pub enum RpcPoolError {
     /// When the transaction is underpriced
     #[error("transaction underpriced")]
     Underpriced,
+    /// When the effective tip of the transaction is below the configured minimum priority fee
+    #[error("transaction underpriced: effective tip below minimum priority fee {0}")]
+    PriorityFeeBelowMinimum(u128),
     /// When the transaction pool is full
     #[error("txpool is full")]
     TxPoolOverflow,
+    /// When the sender already has the maximum number of transactions in the pool
+    #[error("account limit exceeded")]
+    AccountLimitExceeded,
     /// When the replacement transaction is underpriced
     #[error("replacement transaction underpriced")]
     ReplaceUnderpriced,
@@ -621,6 +657,8 @@ impl From<PoolError> for RpcPoolError {
             PoolErrorKind::ReplacementUnderpriced => RpcPoolError::ReplaceUnderpriced,
             PoolErrorKind::FeeCapBelowMinimumProtocolFeeCap(_) => RpcPoolError::Underpriced,
             PoolErrorKind::SpammerExceededCapacity(_) => RpcPoolError::TxPoolOverflow,
+            PoolErrorKind::SenderTransactionsLimitExceeded(_) => RpcPoolError::AccountLimitExceeded,
+            PoolErrorKind::OriginLimitExceeded(_) => RpcPoolError::TxPoolOverflow,
             PoolErrorKind::DiscardedOnInsert => RpcPoolError::TxPoolOverflow,
             PoolErrorKind::InvalidTransaction(err) => err.into(),
             PoolErrorKind::Other(err) => RpcPoolError::Other(err),
@@ -645,6 +683,9 @@ impl From<InvalidPoolTransactionError> for RpcPoolError {
             }
             InvalidPoolTransactionError::OversizedData(_, _) => RpcPoolError::OversizedData,
             InvalidPoolTransactionError::Underpriced => RpcPoolError::Underpriced,
+            InvalidPoolTransactionError::PriorityFeeBelowMinimum { minimum_priority_fee } => {
+                RpcPoolError::PriorityFeeBelowMinimum(minimum_priority_fee)
+            }
             InvalidPoolTransactionError::Other(err) => RpcPoolError::PoolTransactionError(err),
             InvalidPoolTransactionError::Eip4844(err) => RpcPoolError::Eip4844(err),
             InvalidPoolTransactionError::Overdraft => {
@@ -680,6 +721,59 @@ pub enum SignError {
     NoChainId,
 }
 
+/// Errors of `eth_simulateV1` that fail the entire request, see
+/// <https://github.com/ethereum/execution-apis/pull/484>
+#[derive(Debug, thiserror::Error)]
+pub enum EthSimulateError {
+    /// The calls of a block use more gas than the gas limit of the block.
+    #[error("block gas limit reached")]
+    BlockGasLimitExceeded,
+    /// The number of a block is not greater than the number of the previous block.
+    #[error("block numbers must be in order: {0} <= {1}")]
+    BlockNumberInvalid(U256, U256),
+    /// The timestamp of a block is not greater than the timestamp of the previous block.
+    #[error("block timestamps must be in order: {0} <= {1}")]
+    BlockTimestampInvalid(U256, U256),
+    /// More blocks than allowed are simulated.
+    #[error("too many blocks")]
+    TooManyBlocks,
+    /// The calls of all blocks use more gas than the configured gas cap.
+    #[error("gas cap of {0} reached")]
+    GasCapExceeded(u64),
+    /// A call is not a valid transaction, only returned if validation is enabled.
+    #[error(transparent)]
+    InvalidTransaction(RpcInvalidTransactionError),
+}
+
+impl EthSimulateError {
+    /// Returns the rpc error code for this error.
+    fn error_code(&self) -> i32 {
+        match self {
+            EthSimulateError::BlockGasLimitExceeded => -38015,
+            EthSimulateError::BlockNumberInvalid(..) => -38020,
+            EthSimulateError::BlockTimestampInvalid(..) => -38021,
+            EthSimulateError::TooManyBlocks | EthSimulateError::GasCapExceeded(_) => -38026,
+            EthSimulateError::InvalidTransaction(err) => match err {
+                RpcInvalidTransactionError::NonceTooLow => -38010,
+                RpcInvalidTransactionError::NonceTooHigh => -38011,
+                RpcInvalidTransactionError::FeeCapTooLow => -38012,
+                RpcInvalidTransactionError::GasTooLow => -38013,
+                RpcInvalidTransactionError::InsufficientFunds |
+                RpcInvalidTransactionError::InsufficientFundsForTransfer => -38014,
+                RpcInvalidTransactionError::SenderNoEOA => -38024,
+                RpcInvalidTransactionError::MaxInitCodeSizeExceeded => -38025,
+                err => err.error_code(),
+            },
+        }
+    }
+}
+
+impl From<EthSimulateError> for ErrorObject<'static> {
+    fn from(error: EthSimulateError) -> Self {
+        rpc_error_with_code(error.error_code(), error.to_string())
+    }
+}
+
 /// Converts the evm [ExecutionResult] into a result where `Ok` variant is the output bytes if it is
 /// [ExecutionResult::Success].
 pub(crate) fn ensure_success(result: ExecutionResult) -> EthResult<Bytes> {
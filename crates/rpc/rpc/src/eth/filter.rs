@@ -10,9 +10,13 @@ use crate::{
 use core::fmt;
 
 use async_trait::async_trait;
-use jsonrpsee::{core::RpcResult, server::IdProvider};
-use reth_primitives::{ChainInfo, IntoRecoveredTransaction, TxHash};
-use reth_provider::{BlockIdReader, BlockReader, EvmEnvProvider, ProviderError};
+use jsonrpsee::{core::RpcResult, server::IdProvider, types::ErrorObject};
+use reth_primitives::{
+    ChainInfo, IntoRecoveredTransaction, PruneSegment, ReceiptsLogPruneConfig, TxHash, U64,
+};
+use reth_provider::{
+    BlockIdReader, BlockReader, EvmEnvProvider, ProviderError, PruneCheckpointReader,
+};
 use reth_rpc_api::EthFilterApiServer;
 use reth_rpc_types::{
     BlockNumHash, Filter, FilterBlockOption, FilterChanges, FilterId, FilteredParams, Log,
@@ -63,8 +67,12 @@ where
         config: EthFilterConfig,
         task_spawner: Box<dyn TaskSpawner>,
     ) -> Self {
-        let EthFilterConfig { max_blocks_per_filter, max_logs_per_response, stale_filter_ttl } =
-            config;
+        let EthFilterConfig {
+            max_blocks_per_filter,
+            max_logs_per_response,
+            stale_filter_ttl,
+            receipts_log_filter,
+        } = config;
         let inner = EthFilterInner {
             provider,
             active_filters: Default::default(),
@@ -77,6 +85,7 @@ where
             // if not set, use the max value, which is effectively no limit
             max_blocks_per_filter: max_blocks_per_filter.unwrap_or(u64::MAX),
             max_logs_per_response: max_logs_per_response.unwrap_or(usize::MAX),
+            receipts_log_filter,
         };
 
         let eth_filter = Self { inner: Arc::new(inner) };
@@ -126,7 +135,7 @@ where
 
 impl<Provider, Pool> EthFilter<Provider, Pool>
 where
-    Provider: BlockReader + BlockIdReader + EvmEnvProvider + 'static,
+    Provider: BlockReader + BlockIdReader + EvmEnvProvider + PruneCheckpointReader + 'static,
     Pool: TransactionPool + 'static,
     <Pool as TransactionPool>::Transaction: 'static,
 {
@@ -223,7 +232,7 @@ where
 #[async_trait]
 impl<Provider, Pool> EthFilterApiServer for EthFilter<Provider, Pool>
 where
-    Provider: BlockReader + BlockIdReader + EvmEnvProvider + 'static,
+    Provider: BlockReader + BlockIdReader + EvmEnvProvider + PruneCheckpointReader + 'static,
     Pool: TransactionPool + 'static,
 {
     /// Handler for `eth_newFilter`
@@ -330,6 +339,8 @@ struct EthFilterInner<Provider, Pool> {
     max_blocks_per_filter: u64,
     /// Maximum number of logs that can be returned in a response
     max_logs_per_response: usize,
+    /// The addresses whose logs are retained by the [PruneSegment::ContractLogs] pruning.
+    receipts_log_filter: ReceiptsLogPruneConfig,
     /// The async cache frontend for eth related data
     eth_cache: EthStateCache,
     /// maximum number of headers to read at once for range filter
@@ -342,7 +353,7 @@ struct EthFilterInner<Provider, Pool> {
 
 impl<Provider, Pool> EthFilterInner<Provider, Pool>
 where
-    Provider: BlockReader + BlockIdReader + EvmEnvProvider + 'static,
+    Provider: BlockReader + BlockIdReader + EvmEnvProvider + PruneCheckpointReader + 'static,
     Pool: TransactionPool + 'static,
 {
     /// Returns logs matching given filter object.
@@ -416,10 +427,43 @@ where
         Ok(id)
     }
 
+    /// Returns the highest block whose receipts with logs that match the filter were pruned, see
+    /// [logs_utils::highest_pruned_block].
+    fn highest_pruned_block(&self, filter: &Filter, tip: u64) -> Result<Option<u64>, FilterError> {
+        let receipts_pruned = self
+            .provider
+            .get_prune_checkpoint(PruneSegment::Receipts)?
+            .and_then(|checkpoint| checkpoint.block_number);
+
+        // the block number of the checkpoint is the lowest block retained for the configured
+        // addresses, the progress of the pruning of all other receipts is the transaction number
+        let contract_logs_pruned =
+            match self.provider.get_prune_checkpoint(PruneSegment::ContractLogs)? {
+                Some(checkpoint) => match checkpoint.tx_number {
+                    Some(tx_number) => self.provider.transaction_block(tx_number)?,
+                    None => checkpoint.block_number,
+                },
+                None => None,
+            };
+
+        Ok(logs_utils::highest_pruned_block(
+            &filter.address,
+            &self.receipts_log_filter,
+            tip,
+            receipts_pruned,
+            contract_logs_pruned,
+        ))
+    }
+
     /// Returns all logs in the given _inclusive_ range that match the filter
     ///
+    /// The headers of the range are read in chunks of `max_headers_range`, and the receipts are
+    /// only read for blocks whose logs bloom matches the filter.
+    ///
     /// Returns an error if:
     ///  - underlying database error
+    ///  - the range exceeds the configured limit
+    ///  - the receipts of blocks in the range that match the filter were pruned
     ///  - amount of matches exceeds configured limit
     async fn get_logs_in_block_range(
         &self,
@@ -435,6 +479,9 @@ where
             return Err(FilterError::QueryExceedsMaxBlocks(self.max_blocks_per_filter))
         }
 
+        // without receipts, blocks would silently be reported as having no matching logs
+        let pruned_block = self.highest_pruned_block(filter, best_number)?;
+
         let mut all_logs = Vec::new();
         let filter_params = FilteredParams::new(Some(filter.clone()));
 
@@ -475,6 +522,10 @@ where
                 if FilteredParams::matches_address(header.logs_bloom, &address_filter) &&
                     FilteredParams::matches_topics(header.logs_bloom, &topics_filter)
                 {
+                    // only blocks that could contain matching logs need their receipts, so pruned
+                    // blocks are only rejected if their bloom matches
+                    logs_utils::ensure_range_not_pruned(header.number, to_block, pruned_block)?;
+
                     // these are consecutive headers, so we can use the parent hash of the next
                     // block to get the current header's hash
                     let block_hash = match headers.get(idx + 1) {
@@ -500,9 +551,13 @@ where
                         // logs of a single block
                         let is_multi_block_range = from_block != to_block;
                         if is_multi_block_range && all_logs.len() > self.max_logs_per_response {
-                            return Err(FilterError::QueryExceedsMaxResults(
-                                self.max_logs_per_response,
-                            ))
+                            let (from_block, to_block) =
+                                logs_utils::max_results_retry_range(from_block, header.number);
+                            return Err(FilterError::QueryExceedsMaxResults {
+                                max_logs: self.max_logs_per_response,
+                                from_block,
+                                to_block,
+                            })
                         }
                     }
                 }
@@ -529,6 +584,12 @@ pub struct EthFilterConfig {
     /// A filter is considered stale if it has not been polled for longer than this duration and
     /// will be removed.
     pub stale_filter_ttl: Duration,
+    /// The addresses whose logs are retained if receipts are pruned, see
+    /// [PruneModes::receipts_log_filter](reth_primitives::PruneModes::receipts_log_filter).
+    ///
+    /// Queries for the logs of these addresses are served for the blocks their receipts are
+    /// retained for.
+    pub receipts_log_filter: ReceiptsLogPruneConfig,
 }
 
 impl EthFilterConfig {
@@ -550,6 +611,12 @@ impl EthFilterConfig {
         self.stale_filter_ttl = duration;
         self
     }
+
+    /// Sets the addresses whose logs are retained if receipts are pruned.
+    pub fn receipts_log_filter(mut self, receipts_log_filter: ReceiptsLogPruneConfig) -> Self {
+        self.receipts_log_filter = receipts_log_filter;
+        self
+    }
 }
 
 impl Default for EthFilterConfig {
@@ -559,6 +626,7 @@ impl Default for EthFilterConfig {
             max_logs_per_response: None,
             // 5min
             stale_filter_ttl: Duration::from_secs(5 * 60),
+            receipts_log_filter: Default::default(),
         }
     }
 }
@@ -684,8 +752,30 @@ pub enum FilterError {
     FilterNotFound(FilterId),
     #[error("query exceeds max block range {0}")]
     QueryExceedsMaxBlocks(u64),
-    #[error("query exceeds max results {0}")]
-    QueryExceedsMaxResults(usize),
+    /// The query matched more logs than allowed, the query can be retried with the range of
+    /// blocks whose logs were within the limit, or with the first block alone if its logs
+    /// already exceeded the limit.
+    #[error("query exceeds max results {max_logs}, retry with the range {from_block}-{to_block}")]
+    QueryExceedsMaxResults {
+        /// The maximum number of logs per response.
+        max_logs: usize,
+        /// The first block of the range to retry with.
+        from_block: u64,
+        /// The last block of the range to retry with.
+        to_block: u64,
+    },
+    /// The range includes blocks whose receipts were pruned, the query can be retried with the
+    /// range of blocks whose receipts are available.
+    #[error(
+        "query includes pruned blocks up to {pruned_block}, retry with the range {}-{to_block}",
+        pruned_block + 1
+    )]
+    QueryIncludesPrunedBlocks {
+        /// The highest block whose receipts were pruned.
+        pruned_block: u64,
+        /// The last block of the queried range.
+        to_block: u64,
+    },
     #[error(transparent)]
     EthAPIError(#[from] EthApiError),
     /// Error thrown when a spawned task failed to deliver a response.
@@ -708,13 +798,26 @@ impl From<FilterError> for jsonrpsee::types::error::ErrorObject<'static> {
             err @ FilterError::QueryExceedsMaxBlocks(_) => {
                 rpc_error_with_code(jsonrpsee::types::error::INVALID_PARAMS_CODE, err.to_string())
             }
-            err @ FilterError::QueryExceedsMaxResults(_) => {
-                rpc_error_with_code(jsonrpsee::types::error::INVALID_PARAMS_CODE, err.to_string())
+            err @ FilterError::QueryExceedsMaxResults { from_block, to_block, .. } => {
+                retry_range_error(err.to_string(), from_block, to_block)
+            }
+            err @ FilterError::QueryIncludesPrunedBlocks { pruned_block, to_block } => {
+                retry_range_error(err.to_string(), pruned_block + 1, to_block)
             }
         }
     }
 }
 
+/// Returns an [InvalidParams](jsonrpsee::types::error::INVALID_PARAMS_CODE) error with the
+/// _inclusive_ block range the query can be retried with as data.
+fn retry_range_error(msg: String, from_block: u64, to_block: u64) -> ErrorObject<'static> {
+    ErrorObject::owned(
+        jsonrpsee::types::error::INVALID_PARAMS_CODE,
+        msg,
+        Some(serde_json::json!({ "from": U64::from(from_block), "to": U64::from(to_block) })),
+    )
+}
+
 impl From<ProviderError> for FilterError {
     fn from(err: ProviderError) -> Self {
         FilterError::EthAPIError(err.into())
@@ -753,6 +856,24 @@ mod tests {
     use super::*;
     use rand::{thread_rng, Rng};
 
+    #[test]
+    fn test_retry_range_error() {
+        let err: ErrorObject<'static> =
+            FilterError::QueryExceedsMaxResults { max_logs: 10, from_block: 1, to_block: 16 }
+                .into();
+        assert_eq!(err.code(), jsonrpsee::types::error::INVALID_PARAMS_CODE);
+        assert_eq!(err.message(), "query exceeds max results 10, retry with the range 1-16");
+        assert_eq!(err.data().unwrap().get(), r#"{"from":"0x1","to":"0x10"}"#);
+
+        let err: ErrorObject<'static> =
+            FilterError::QueryIncludesPrunedBlocks { pruned_block: 9, to_block: 20 }.into();
+        assert_eq!(
+            err.message(),
+            "query includes pruned blocks up to 9, retry with the range 10-20"
+        );
+        assert_eq!(err.data().unwrap().get(), r#"{"from":"0xa","to":"0x14"}"#);
+    }
+
     #[test]
     fn test_block_range_iter() {
         for _ in 0..100 {
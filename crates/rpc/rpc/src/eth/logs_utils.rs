@@ -1,8 +1,10 @@
-use super::filter::FilterError;
-use alloy_primitives::TxHash;
-use reth_primitives::{BlockNumHash, ChainInfo, Receipt};
+use super::{error::EthApiError, filter::FilterError};
+use alloy_primitives::{Address, TxHash};
+use reth_primitives::{
+    BlockNumHash, ChainInfo, PrunePurpose, PruneSegment, Receipt, ReceiptsLogPruneConfig,
+};
 use reth_provider::{BlockReader, ProviderError};
-use reth_rpc_types::{FilteredParams, Log};
+use reth_rpc_types::{FilterSet, FilteredParams, Log, ValueOrArray};
 
 /// Returns all matching of a block's receipts when the transaction hashes are known.
 pub(crate) fn matching_block_logs_with_tx_hashes<'a, I>(
@@ -41,7 +43,8 @@ where
 }
 
 /// Appends all matching logs of a block's receipts.
-/// If the log matches, look up the corresponding transaction hash.
+/// If the log matches, look up the corresponding transaction hash. The transactions of the block
+/// are read at once, on the first match.
 pub(crate) fn append_matching_block_logs(
     all_logs: &mut Vec<Log>,
     provider: impl BlockReader,
@@ -54,10 +57,10 @@ pub(crate) fn append_matching_block_logs(
     // Tracks the index of a log in the entire block.
     let mut log_index: u64 = 0;
 
-    // Lazy loaded number of the first transaction in the block.
+    // Lazy loaded number of the first transaction in the block and the block's transactions.
     // This is useful for blocks with multiple matching logs because it prevents
-    // re-querying the block body indices.
-    let mut loaded_first_tx_num = None;
+    // re-querying the block body indices and the transactions.
+    let mut loaded_transactions = None;
 
     // Iterate over receipts and append matching logs.
     for (receipt_idx, receipt) in receipts.iter().enumerate() {
@@ -66,25 +69,25 @@ pub(crate) fn append_matching_block_logs(
 
         for log in receipt.logs.iter() {
             if log_matches_filter(block_num_hash, log, filter) {
-                let first_tx_num = match loaded_first_tx_num {
-                    Some(num) => num,
-                    None => {
+                // if this is the first match in the receipt's logs, look up the transaction hash
+                if transaction_hash.is_none() {
+                    if loaded_transactions.is_none() {
                         let block_body_indices =
                             provider.block_body_indices(block_num_hash.number)?.ok_or(
                                 ProviderError::BlockBodyIndicesNotFound(block_num_hash.number),
                             )?;
-                        loaded_first_tx_num = Some(block_body_indices.first_tx_num);
-                        block_body_indices.first_tx_num
+                        let transactions =
+                            provider.transactions_by_tx_range(block_body_indices.tx_num_range())?;
+                        loaded_transactions = Some((block_body_indices.first_tx_num, transactions));
                     }
-                };
+                    let (first_tx_num, transactions) =
+                        loaded_transactions.as_ref().expect("transactions are loaded");
 
-                // if this is the first match in the receipt's logs, look up the transaction hash
-                if transaction_hash.is_none() {
                     // This is safe because Transactions and Receipts have the same keys.
-                    let transaction_id = first_tx_num + receipt_idx as u64;
-                    let transaction = provider
-                        .transaction_by_id(transaction_id)?
-                        .ok_or(ProviderError::TransactionNotFound(transaction_id.into()))?;
+                    let transaction = transactions.get(receipt_idx).ok_or_else(|| {
+                        let transaction_id = first_tx_num + receipt_idx as u64;
+                        ProviderError::TransactionNotFound(transaction_id.into())
+                    })?;
 
                     transaction_hash = Some(transaction.hash());
                 }
@@ -125,6 +128,81 @@ pub(crate) fn log_matches_filter(
     true
 }
 
+/// Returns the highest block whose receipts with logs of the given addresses were pruned. An empty
+/// set of addresses matches the logs of all addresses.
+///
+/// All receipts up to `receipts_pruned` were pruned. If `receipts_log_filter` is configured, the
+/// [PruneSegment::ContractLogs] pruning also removed all receipts up to `contract_logs_pruned`,
+/// except the receipts with logs of the configured addresses, which are only pruned according to
+/// the prune mode of the address.
+pub(crate) fn highest_pruned_block(
+    addresses: &FilterSet<Address>,
+    receipts_log_filter: &ReceiptsLogPruneConfig,
+    tip: u64,
+    receipts_pruned: Option<u64>,
+    contract_logs_pruned: Option<u64>,
+) -> Option<u64> {
+    if receipts_log_filter.is_empty() {
+        return receipts_pruned
+    }
+
+    // the highest pruned block of a single address
+    let pruned_for_address = |address: &Address| match receipts_log_filter.0.get(address) {
+        Some(mode) => {
+            let target = mode
+                .prune_target_block(tip, PruneSegment::ContractLogs, PrunePurpose::User)
+                .ok()
+                .flatten()
+                .map(|(block, _)| block);
+            // the pruning can't be ahead of its checkpoint
+            target.zip(contract_logs_pruned).map(|(target, pruned)| target.min(pruned))
+        }
+        None => contract_logs_pruned,
+    };
+
+    let contract_logs_pruned = match addresses.to_value_or_array() {
+        // all addresses match, including the ones that aren't configured
+        None => contract_logs_pruned,
+        Some(ValueOrArray::Value(address)) => pruned_for_address(&address),
+        Some(ValueOrArray::Array(addresses)) => {
+            addresses.iter().map(pruned_for_address).max().flatten()
+        }
+    };
+
+    receipts_pruned.max(contract_logs_pruned)
+}
+
+/// Returns an error if the receipts of blocks in the given _inclusive_ range were pruned, where
+/// `pruned_block` is the highest block whose receipts were pruned.
+pub(crate) fn ensure_range_not_pruned(
+    from_block: u64,
+    to_block: u64,
+    pruned_block: Option<u64>,
+) -> Result<(), FilterError> {
+    match pruned_block {
+        Some(pruned_block) if to_block <= pruned_block => {
+            Err(EthApiError::PrunedHistory(to_block).into())
+        }
+        Some(pruned_block) if from_block <= pruned_block => {
+            Err(FilterError::QueryIncludesPrunedBlocks { pruned_block, to_block })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns the _inclusive_ range to retry a query for the range starting at `from_block` with,
+/// after the logs up to `block` exceeded the limit of logs per response.
+///
+/// These are all blocks before `block`, or `block` alone if it's the first block of the range: the
+/// logs of a single block are always returned in full, so the retry never exceeds the limit again.
+pub(crate) fn max_results_retry_range(from_block: u64, block: u64) -> (u64, u64) {
+    if block <= from_block {
+        (from_block, from_block)
+    } else {
+        (from_block, block - 1)
+    }
+}
+
 /// Computes the block range based on the filter range and current block numbers
 pub(crate) fn get_filter_block_range(
     from_block: Option<u64>,
@@ -153,6 +231,7 @@ pub(crate) fn get_filter_block_range(
 
 #[cfg(test)]
 mod tests {
+    use reth_primitives::PruneMode;
     use reth_rpc_types::Filter;
 
     use super::*;
@@ -200,6 +279,67 @@ mod tests {
         assert_eq!(range, (info.best_number, info.best_number));
     }
 
+    #[test]
+    fn test_range_not_pruned() {
+        assert!(ensure_range_not_pruned(10, 20, None).is_ok());
+        assert!(ensure_range_not_pruned(10, 20, Some(9)).is_ok());
+
+        // the range straddles pruned history
+        assert!(matches!(
+            ensure_range_not_pruned(10, 20, Some(10)),
+            Err(FilterError::QueryIncludesPrunedBlocks { pruned_block: 10, to_block: 20 })
+        ));
+        assert!(matches!(
+            ensure_range_not_pruned(10, 20, Some(19)),
+            Err(FilterError::QueryIncludesPrunedBlocks { pruned_block: 19, to_block: 20 })
+        ));
+
+        // the entire range is pruned
+        assert!(matches!(
+            ensure_range_not_pruned(10, 20, Some(20)),
+            Err(FilterError::EthAPIError(EthApiError::PrunedHistory(20)))
+        ));
+    }
+
+    #[test]
+    fn test_highest_pruned_block() {
+        let deposit_contract = Address::random();
+        let receipts_log_filter =
+            ReceiptsLogPruneConfig([(deposit_contract, PruneMode::Before(100))].into());
+        let all = FilterSet::default();
+        let deposits = FilterSet::from(deposit_contract);
+        let other = FilterSet::from(Address::random());
+        let both = FilterSet::from(vec![deposit_contract, Address::random()]);
+
+        // without contract log pruning, only the receipts checkpoint counts
+        let none = ReceiptsLogPruneConfig::default();
+        assert_eq!(highest_pruned_block(&deposits, &none, 1_000, Some(50), Some(900)), Some(50));
+
+        // the logs of the configured address are retained from its prune target on
+        let pruned = |addresses| {
+            highest_pruned_block(addresses, &receipts_log_filter, 1_000, Some(50), Some(800))
+        };
+        assert_eq!(pruned(&deposits), Some(99));
+        assert_eq!(pruned(&other), Some(800));
+        assert_eq!(pruned(&both), Some(800));
+        assert_eq!(pruned(&all), Some(800));
+
+        // the pruning of the configured address didn't reach its target yet
+        assert_eq!(
+            highest_pruned_block(&deposits, &receipts_log_filter, 1_000, None, Some(60)),
+            Some(60)
+        );
+        assert_eq!(highest_pruned_block(&deposits, &receipts_log_filter, 1_000, None, None), None);
+    }
+
+    #[test]
+    fn test_max_results_retry_range() {
+        assert_eq!(max_results_retry_range(10, 15), (10, 14));
+        assert_eq!(max_results_retry_range(10, 11), (10, 10));
+        // the first block alone exceeds the limit, but a single block is always returned in full
+        assert_eq!(max_results_retry_range(10, 10), (10, 10));
+    }
+
     #[test]
     fn parse_log_from_only() {
         let s = r#"{"fromBlock":"0xf47a42","address":["0x7de93682b9b5d80d45cd371f7a14f74d49b0914c","0x0f00392fcb466c0e4e4310d81b941e07b4d5a079","0xebf67ab8cff336d3f609127e8bbf8bd6dd93cd81"],"topics":["0x0559884fd3a460db3073b7fc896cc77986f16e378210ded43186175bf646fc5f"]}"#;
@@ -9,20 +9,31 @@ pub mod gas_oracle;
 mod id_provider;
 mod logs_utils;
 mod pubsub;
+mod resumable;
 pub mod revm_utils;
 mod signer;
 pub mod traits;
 pub(crate) mod utils;
 
+#[cfg(test)]
+pub(crate) mod test_utils;
+
 #[cfg(feature = "optimism")]
 pub mod optimism;
 
 pub use api::{
-    fee_history::{fee_history_cache_new_blocks_task, FeeHistoryCache, FeeHistoryCacheConfig},
-    EthApi, EthApiSpec, EthTransactions, TransactionSource, RPC_DEFAULT_GAS_CAP,
+    fee_history::{
+        fee_history_cache_new_blocks_task, BlockTips, FeeHistoryCache, FeeHistoryCacheConfig,
+    },
+    EthApi, EthApiSpec, EthTransactions, TransactionSource, DEFAULT_ETH_PROOF_WINDOW,
+    RPC_DEFAULT_GAS_CAP,
 };
 
 pub use bundle::EthBundle;
 pub use filter::{EthFilter, EthFilterConfig};
 pub use id_provider::EthSubscriptionIdProvider;
 pub use pubsub::EthPubSub;
+pub use resumable::{
+    ResumableSubscriptionsConfig, ResumableUnsubscribes, DEFAULT_MAX_DORMANT_SUBSCRIPTIONS,
+    DEFAULT_RESUMABLE_BUFFER_DEPTH, DEFAULT_RESUMABLE_EXPIRY,
+};
@@ -1,7 +1,13 @@
 //! `eth_` PubSub RPC handler implementation
 
 use crate::{
-    eth::logs_utils,
+    eth::{
+        logs_utils,
+        resumable::{
+            NotificationStream, ResumableSubscriptions, ResumableSubscriptionsConfig,
+            ResumableUnsubscribes,
+        },
+    },
     result::{internal_rpc_err, invalid_params_rpc_err},
 };
 use futures::StreamExt;
@@ -10,33 +16,44 @@ use jsonrpsee::{
 };
 use reth_network_api::NetworkInfo;
 use reth_primitives::{IntoRecoveredTransaction, TxHash};
-use reth_provider::{BlockReader, CanonStateSubscriptions, EvmEnvProvider};
+use reth_provider::{BlockReader, CanonStateNotification, CanonStateSubscriptions, EvmEnvProvider};
 use reth_rpc_api::EthPubSubApiServer;
 use reth_rpc_types::{
     pubsub::{
         Params, PubSubSyncStatus, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
         SyncStatusMetadata,
     },
+    subscription::{
+        AnySubscriptionKind, DropReason, DroppedTransaction, RethSubscriptionKind,
+        SubscriptionOptions,
+    },
     FilteredParams, Header, Log,
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
-use reth_transaction_pool::{NewTransactionEvent, TransactionPool};
+use reth_transaction_pool::{
+    FullTransactionEvent, NewTransactionEvent, PoolTransaction, TransactionPool,
+};
 use serde::Serialize;
-use std::sync::Arc;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+};
 use tokio_stream::{
-    wrappers::{BroadcastStream, ReceiverStream},
+    wrappers::{errors::BroadcastStreamRecvError, BroadcastStream, ReceiverStream},
     Stream,
 };
 
 /// `Eth` pubsub RPC implementation.
 ///
-/// This handles `eth_subscribe` RPC calls.
+/// This handles `eth_subscribe` and `eth_resubscribe` RPC calls.
 #[derive(Clone)]
 pub struct EthPubSub<Provider, Pool, Events, Network> {
     /// All nested fields bundled together.
     inner: Arc<EthPubSubInner<Provider, Pool, Events, Network>>,
     /// The type that's used to spawn subscription tasks.
     subscription_task_spawner: Box<dyn TaskSpawner>,
+    /// The resumable subscriptions whose connection was lost.
+    resumable: ResumableSubscriptions,
 }
 
 // === impl EthPubSub ===
@@ -64,7 +81,30 @@ impl<Provider, Pool, Events, Network> EthPubSub<Provider, Pool, Events, Network>
         subscription_task_spawner: Box<dyn TaskSpawner>,
     ) -> Self {
         let inner = EthPubSubInner { provider, pool, chain_events, network };
-        Self { inner: Arc::new(inner), subscription_task_spawner }
+        let resumable = ResumableSubscriptions::new(
+            Default::default(),
+            subscription_task_spawner.clone(),
+            Default::default(),
+        );
+        Self { inner: Arc::new(inner), subscription_task_spawner, resumable }
+    }
+
+    /// Configures the buffering of the notifications of resumable subscriptions.
+    pub fn with_resumable_subscriptions(mut self, config: ResumableSubscriptionsConfig) -> Self {
+        self.resumable = ResumableSubscriptions::new(
+            config,
+            self.subscription_task_spawner.clone(),
+            self.resumable.unsubscribes().clone(),
+        );
+        self
+    }
+
+    /// Returns the tracker that the server must report the `eth_unsubscribe` calls to.
+    ///
+    /// Otherwise an unsubscribed resumable subscription is kept dormant like one whose connection
+    /// was lost.
+    pub fn resumable_unsubscribes(&self) -> ResumableUnsubscribes {
+        self.resumable.unsubscribes().clone()
     }
 }
 
@@ -81,9 +121,56 @@ where
     async fn subscribe(
         &self,
         pending: PendingSubscriptionSink,
-        kind: SubscriptionKind,
+        kind: AnySubscriptionKind,
         params: Option<Params>,
+        options: Option<SubscriptionOptions>,
     ) -> jsonrpsee::core::SubscriptionResult {
+        if options.unwrap_or_default().resumable {
+            let stream: NotificationStream = match kind {
+                AnySubscriptionKind::Eth(SubscriptionKind::NewHeads) => Box::pin(
+                    self.inner
+                        .new_headers_stream()
+                        .map(|block| EthSubscriptionResult::Header(Box::new(block.into()))),
+                ),
+                AnySubscriptionKind::Eth(SubscriptionKind::Logs) => {
+                    let filter = match logs_filter(params) {
+                        Ok(filter) => filter,
+                        Err(err) => {
+                            pending.reject(err).await;
+                            return Ok(())
+                        }
+                    };
+                    Box::pin(
+                        self.inner
+                            .log_stream(filter)
+                            .map(|log| EthSubscriptionResult::Log(Box::new(log))),
+                    )
+                }
+                _ => return Err("Only newHeads and logs subscriptions are resumable".into()),
+            };
+
+            let sink = pending.accept().await?;
+            let resumable = self.resumable.clone();
+            self.subscription_task_spawner.spawn(Box::pin(async move {
+                let _ = resumable.pipe(sink, VecDeque::new(), stream).await;
+            }));
+            return Ok(())
+        }
+
+        let kind = match kind {
+            AnySubscriptionKind::Eth(kind) => kind,
+            AnySubscriptionKind::Reth(RethSubscriptionKind::DroppedTransactions) => {
+                if params.is_some() {
+                    return Err("Invalid params for droppedTransactions".into())
+                }
+                let sink = pending.accept().await?;
+                // awaited in place, so that a subscription that can't keep up is closed with an
+                // error notification
+                return pipe_without_backpressure(sink, self.inner.dropped_transactions_stream())
+                    .await
+            }
+        };
+
         let sink = pending.accept().await?;
         let pubsub = self.inner.clone();
         self.subscription_task_spawner.spawn(Box::pin(async move {
@@ -92,6 +179,39 @@ where
 
         Ok(())
     }
+
+    /// Handler for `eth_resubscribe`
+    async fn resubscribe(
+        &self,
+        pending: PendingSubscriptionSink,
+        token: String,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let (missed, stream) = match self.resumable.resume(&token).await {
+            Ok(resumed) => resumed,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(())
+            }
+        };
+
+        let sink = pending.accept().await?;
+        let resumable = self.resumable.clone();
+        self.subscription_task_spawner.spawn(Box::pin(async move {
+            let _ = resumable.pipe(sink, missed, stream).await;
+        }));
+
+        Ok(())
+    }
+}
+
+/// Returns the filter of a `logs` subscription.
+fn logs_filter(params: Option<Params>) -> Result<FilteredParams, ErrorObject<'static>> {
+    // if no params are provided, used default filter params
+    match params {
+        Some(Params::Logs(filter)) => Ok(FilteredParams::new(Some(*filter))),
+        Some(Params::Bool(_)) => Err(invalid_params_rpc_err("Invalid params for logs")),
+        _ => Ok(FilteredParams::default()),
+    }
 }
 
 /// The actual handler for an accepted [`EthPubSub::subscribe`] call.
@@ -115,14 +235,7 @@ where
             pipe_from_stream(accepted_sink, stream).await
         }
         SubscriptionKind::Logs => {
-            // if no params are provided, used default filter params
-            let filter = match params {
-                Some(Params::Logs(filter)) => FilteredParams::new(Some(*filter)),
-                Some(Params::Bool(_)) => {
-                    return Err(invalid_params_rpc_err("Invalid params for logs"))
-                }
-                _ => FilteredParams::default(),
-            };
+            let filter = logs_filter(params)?;
             let stream =
                 pubsub.log_stream(filter).map(|log| EthSubscriptionResult::Log(Box::new(log)));
             pipe_from_stream(accepted_sink, stream).await
@@ -243,6 +356,54 @@ where
     }
 }
 
+/// Error of a subscription that was closed because it couldn't keep up with its notifications.
+#[derive(Debug, thiserror::Error)]
+#[error("Subscription lagged behind and was closed")]
+pub(crate) struct SubscriptionLaggedError;
+
+/// Pipes all stream items to the subscription sink without waiting for the sink to have capacity.
+///
+/// Unlike [pipe_from_stream], the notifications aren't buffered if the client can't keep up,
+/// instead the subscription is closed with a [SubscriptionLaggedError], as it is if the stream
+/// lagged behind.
+async fn pipe_without_backpressure<T, St>(
+    sink: SubscriptionSink,
+    mut stream: St,
+) -> jsonrpsee::core::SubscriptionResult
+where
+    St: Stream<Item = Result<T, BroadcastStreamRecvError>> + Unpin,
+    T: Serialize,
+{
+    loop {
+        tokio::select! {
+            _ = sink.closed() => {
+                // connection dropped
+                break Ok(())
+            },
+            maybe_item = stream.next() => {
+                let item = match maybe_item {
+                    Some(Ok(item)) => item,
+                    Some(Err(BroadcastStreamRecvError::Lagged(_))) => {
+                        break Err(SubscriptionLaggedError.into())
+                    }
+                    None => {
+                        // stream ended
+                        break Ok(())
+                    },
+                };
+                let msg = SubscriptionMessage::from_json(&item)
+                    .map_err(SubscriptionSerializeError::new)?;
+                if sink.try_send(msg).is_err() {
+                    if sink.is_closed() {
+                        break Ok(())
+                    }
+                    break Err(SubscriptionLaggedError.into())
+                }
+            }
+        }
+    }
+}
+
 impl<Provider, Pool, Events, Network> std::fmt::Debug
     for EthPubSub<Provider, Pool, Events, Network>
 {
@@ -339,3 +500,176 @@ where
             })
     }
 }
+
+impl<Provider, Pool, Events, Network> EthPubSubInner<Provider, Pool, Events, Network>
+where
+    Pool: TransactionPool + 'static,
+    Events: CanonStateSubscriptions + 'static,
+{
+    /// Returns a stream that yields all transactions that were dropped from the txpool and all
+    /// transactions of blocks that were reorged out.
+    ///
+    /// Yields an error if the stream lagged behind the pool events or the canonical state
+    /// notifications.
+    fn dropped_transactions_stream(
+        &self,
+    ) -> impl Stream<Item = Result<DroppedTransaction, BroadcastStreamRecvError>> {
+        let events = self.pool.all_transactions_event_listener();
+        // the dropped transaction of the event that was received together with a lag is yielded
+        // after the lag error
+        let dropped =
+            Box::pin(futures::stream::unfold((events, None), |(mut events, pending)| async move {
+                if let Some(dropped) = pending {
+                    return Some((Ok(dropped), (events, None)))
+                }
+                loop {
+                    let dropped = dropped_from_pool(events.next().await?);
+                    let missed = events.take_missed();
+                    if missed > 0 {
+                        return Some((
+                            Err(BroadcastStreamRecvError::Lagged(missed)),
+                            (events, dropped),
+                        ))
+                    }
+                    if let Some(dropped) = dropped {
+                        return Some((Ok(dropped), (events, None)))
+                    }
+                }
+            }));
+        let reorged = BroadcastStream::new(self.chain_events.subscribe_to_canonical_state())
+            .flat_map(|notification| {
+                let items = match notification {
+                    Ok(notification) => {
+                        reorged_transactions(&notification).into_iter().map(Ok).collect()
+                    }
+                    Err(err) => vec![Err(err)],
+                };
+                futures::stream::iter(items)
+            });
+        futures::stream::select(dropped, reorged)
+    }
+}
+
+/// Returns the [DroppedTransaction] if the event removed the transaction from the pool for another
+/// reason than being mined.
+fn dropped_from_pool<T: PoolTransaction>(
+    event: FullTransactionEvent<T>,
+) -> Option<DroppedTransaction> {
+    let (transaction_hash, reason) = match event {
+        FullTransactionEvent::Replaced { transaction, .. } => {
+            (*transaction.hash(), DropReason::Replaced)
+        }
+        FullTransactionEvent::Discarded(hash) => (hash, DropReason::Underpriced),
+        FullTransactionEvent::Expired(hash) => (hash, DropReason::Expired),
+        FullTransactionEvent::Invalid(hash) => (hash, DropReason::Invalid),
+        FullTransactionEvent::Pending(_) |
+        FullTransactionEvent::Queued(_) |
+        FullTransactionEvent::Mined { .. } |
+        FullTransactionEvent::Propagated(_) => return None,
+    };
+    Some(DroppedTransaction { transaction_hash, reason, block_hash: None })
+}
+
+/// Returns the transactions of the blocks that were removed from the canonical chain and aren't
+/// included in the new canonical chain.
+fn reorged_transactions(notification: &CanonStateNotification) -> Vec<DroppedTransaction> {
+    let Some(reverted) = notification.reverted() else { return Vec::new() };
+    let committed = notification
+        .committed()
+        .blocks_iter()
+        .flat_map(|block| block.body.iter().map(|tx| tx.hash()))
+        .collect::<HashSet<_>>();
+    reverted
+        .blocks_iter()
+        .flat_map(|block| {
+            block.body.iter().filter(|tx| !committed.contains(&tx.hash())).map(|tx| {
+                DroppedTransaction {
+                    transaction_hash: tx.hash(),
+                    reason: DropReason::Reorged,
+                    block_hash: Some(block.hash()),
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_interfaces::test_utils::generators::{self, random_block};
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::SealedBlock;
+    use reth_provider::{test_utils::TestCanonStateSubscriptions, BundleStateWithReceipts, Chain};
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction, TestPool},
+        TransactionOrigin,
+    };
+
+    fn pubsub(
+        chain_events: TestCanonStateSubscriptions,
+    ) -> EthPubSubInner<(), TestPool, TestCanonStateSubscriptions, NoopNetwork> {
+        EthPubSubInner { pool: testing_pool(), provider: (), chain_events, network: NoopNetwork }
+    }
+
+    #[tokio::test]
+    async fn dropped_replaced_transaction() {
+        let pubsub = pubsub(Default::default());
+        let mut stream = pubsub.dropped_transactions_stream();
+
+        let transaction = MockTransaction::eip1559();
+        pubsub
+            .pool
+            .add_transaction(TransactionOrigin::External, transaction.clone())
+            .await
+            .unwrap();
+        let replacement = transaction.clone().rng_hash().inc_price_by(1_000_000);
+        pubsub.pool.add_transaction(TransactionOrigin::External, replacement).await.unwrap();
+
+        let dropped = stream.next().await.unwrap().unwrap();
+        assert_eq!(dropped.transaction_hash, transaction.get_hash());
+        assert_eq!(dropped.reason, DropReason::Replaced);
+        assert_eq!(dropped.block_hash, None);
+    }
+
+    #[tokio::test]
+    async fn dropped_transactions_lagged() {
+        let pubsub = pubsub(Default::default());
+        let mut stream = pubsub.dropped_transactions_stream();
+
+        for _ in 0..1100 {
+            pubsub
+                .pool
+                .add_transaction(TransactionOrigin::External, MockTransaction::eip1559())
+                .await
+                .unwrap();
+        }
+
+        assert_matches!(stream.next().await, Some(Err(BroadcastStreamRecvError::Lagged(_))));
+    }
+
+    #[tokio::test]
+    async fn dropped_reorged_transactions() {
+        let mut rng = generators::rng();
+        let mut chain_events = TestCanonStateSubscriptions::default();
+        let pubsub = pubsub(chain_events.clone());
+        let mut stream = pubsub.dropped_transactions_stream();
+
+        let old = random_block(&mut rng, 1, None, Some(2), Some(0));
+        let mut new = random_block(&mut rng, 1, None, Some(0), Some(0));
+        new.body.push(old.body[1].clone());
+        let chain = |block: SealedBlock| {
+            Arc::new(Chain::from_block(
+                block.seal_with_senders().unwrap(),
+                BundleStateWithReceipts::default(),
+                None,
+            ))
+        };
+        chain_events.add_next_reorg(chain(old.clone()), chain(new));
+
+        let dropped = stream.next().await.unwrap().unwrap();
+        assert_eq!(dropped.transaction_hash, old.body[0].hash());
+        assert_eq!(dropped.reason, DropReason::Reorged);
+        assert_eq!(dropped.block_hash, Some(old.hash()));
+    }
+}
@@ -0,0 +1,481 @@
+//! Support for `eth_subscribe` subscriptions that can be resumed after the connection was lost.
+//!
+//! The notifications of a resumable subscription whose connection was lost are buffered by a
+//! dormant task until the subscription is resumed with `eth_resubscribe`, its buffer overflows or
+//! it expires.
+
+use crate::{eth::pubsub::SubscriptionSerializeError, result::invalid_params_rpc_err};
+use futures::{Stream, StreamExt};
+use jsonrpsee::{
+    server::SubscriptionMessage,
+    types::{ErrorObject, SubscriptionId},
+    SubscriptionSink,
+};
+use parking_lot::Mutex;
+use reth_rpc_types::pubsub::SubscriptionResult as EthSubscriptionResult;
+use reth_tasks::TaskSpawner;
+use std::{
+    collections::{HashMap, VecDeque},
+    pin::Pin,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::oneshot;
+use tracing::trace;
+
+/// The default maximum number of notifications that are buffered for a dormant subscription.
+pub const DEFAULT_RESUMABLE_BUFFER_DEPTH: usize = 256;
+
+/// The default duration for which a dormant subscription can be resumed.
+pub const DEFAULT_RESUMABLE_EXPIRY: Duration = Duration::from_secs(60);
+
+/// The default maximum number of dormant subscriptions.
+pub const DEFAULT_MAX_DORMANT_SUBSCRIPTIONS: usize = 1_000;
+
+/// Config for resumable subscriptions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumableSubscriptionsConfig {
+    /// Maximum number of notifications that are buffered for a dormant subscription.
+    ///
+    /// A subscription that missed more notifications can't be resumed.
+    pub buffer_depth: usize,
+    /// How long a dormant subscription can be resumed after its connection was lost.
+    pub expiry: Duration,
+    /// Maximum number of dormant subscriptions.
+    ///
+    /// A subscription that loses its connection while this many subscriptions are dormant can't be
+    /// resumed.
+    pub max_dormant: usize,
+}
+
+impl ResumableSubscriptionsConfig {
+    /// Sets the maximum number of notifications that are buffered for a dormant subscription.
+    pub fn buffer_depth(mut self, depth: usize) -> Self {
+        self.buffer_depth = depth;
+        self
+    }
+
+    /// Sets how long a dormant subscription can be resumed after its connection was lost.
+    pub fn expiry(mut self, expiry: Duration) -> Self {
+        self.expiry = expiry;
+        self
+    }
+
+    /// Sets the maximum number of dormant subscriptions.
+    pub fn max_dormant(mut self, max_dormant: usize) -> Self {
+        self.max_dormant = max_dormant;
+        self
+    }
+}
+
+impl Default for ResumableSubscriptionsConfig {
+    fn default() -> Self {
+        Self {
+            buffer_depth: DEFAULT_RESUMABLE_BUFFER_DEPTH,
+            expiry: DEFAULT_RESUMABLE_EXPIRY,
+            max_dormant: DEFAULT_MAX_DORMANT_SUBSCRIPTIONS,
+        }
+    }
+}
+
+/// Keeps track of the `eth_unsubscribe` calls of the connected resumable subscriptions.
+///
+/// A subscription is closed by both, a lost connection and an `eth_unsubscribe` call. The server
+/// reports the unsubscribe calls with [`ResumableUnsubscribes::on_unsubscribe`], so that only the
+/// subscriptions whose connection was lost become dormant.
+#[derive(Debug, Clone, Default)]
+pub struct ResumableUnsubscribes {
+    /// The tokens of the connected resumable subscriptions, and whether they were unsubscribed.
+    connected: Arc<Mutex<HashMap<String, bool>>>,
+}
+
+impl ResumableUnsubscribes {
+    /// Records an `eth_unsubscribe` call for the subscription with the given id.
+    ///
+    /// This must be called before the call is handled by the server.
+    pub fn on_unsubscribe(&self, id: &str) {
+        if let Some(unsubscribed) = self.connected.lock().get_mut(id) {
+            *unsubscribed = true;
+        }
+    }
+
+    /// Tracks the subscription with the given token while it's connected.
+    fn connect(&self, token: String) {
+        self.connected.lock().insert(token, false);
+    }
+
+    /// Stops tracking the subscription with the given token.
+    ///
+    /// Returns true if the subscription was unsubscribed.
+    fn disconnect(&self, token: &str) -> bool {
+        self.connected.lock().remove(token).unwrap_or_default()
+    }
+}
+
+/// The notifications of a resumable subscription.
+pub(crate) type NotificationStream = Pin<Box<dyn Stream<Item = EthSubscriptionResult> + Send>>;
+
+/// Keeps track of the resumable subscriptions whose connection was lost.
+#[derive(Clone)]
+pub(crate) struct ResumableSubscriptions {
+    inner: Arc<ResumableSubscriptionsInner>,
+}
+
+impl ResumableSubscriptions {
+    /// Creates a new instance that spawns the dormant subscription tasks with the given spawner.
+    ///
+    /// The given [`ResumableUnsubscribes`] must receive the `eth_unsubscribe` calls.
+    pub(crate) fn new(
+        config: ResumableSubscriptionsConfig,
+        task_spawner: Box<dyn TaskSpawner>,
+        unsubscribes: ResumableUnsubscribes,
+    ) -> Self {
+        let inner = ResumableSubscriptionsInner {
+            config,
+            task_spawner,
+            unsubscribes,
+            dormant: Default::default(),
+        };
+        Self { inner: Arc::new(inner) }
+    }
+
+    /// Returns the tracker of the `eth_unsubscribe` calls.
+    pub(crate) fn unsubscribes(&self) -> &ResumableUnsubscribes {
+        &self.inner.unsubscribes
+    }
+
+    /// Sends the missed notifications and then all notifications of the stream to the sink.
+    ///
+    /// If the connection of the sink is lost, the subscription becomes dormant and can be resumed
+    /// with the subscription id as token. An unsubscribed subscription ends.
+    pub(crate) async fn pipe(
+        &self,
+        sink: SubscriptionSink,
+        missed: VecDeque<EthSubscriptionResult>,
+        stream: NotificationStream,
+    ) -> Result<(), ErrorObject<'static>> {
+        let token = resumption_token(sink.subscription_id());
+        self.inner.unsubscribes.connect(token.clone());
+        let closed = pipe_until_closed(&sink, missed, stream).await;
+        let unsubscribed = self.inner.unsubscribes.disconnect(&token);
+
+        if let Some((missed, stream)) = closed? {
+            if unsubscribed {
+                trace!(target: "rpc::eth", %token, "Subscription was unsubscribed");
+            } else {
+                self.park(token, missed, stream);
+            }
+        }
+        Ok(())
+    }
+
+    /// Resumes the dormant subscription with the given token.
+    ///
+    /// Returns the missed notifications and the stream of the subscription.
+    pub(crate) async fn resume(
+        &self,
+        token: &str,
+    ) -> Result<(VecDeque<EthSubscriptionResult>, NotificationStream), ResumeSubscriptionError>
+    {
+        let resume =
+            self.inner.dormant.lock().remove(token).ok_or(ResumeSubscriptionError::UnknownToken)?;
+        let (tx, rx) = oneshot::channel();
+        // the dormant task may have expired in the meantime
+        resume.send(tx).map_err(|_| ResumeSubscriptionError::UnknownToken)?;
+        let Dormant { missed, stream } =
+            rx.await.map_err(|_| ResumeSubscriptionError::UnknownToken)?;
+
+        match stream {
+            DormantStream::Active(stream) => Ok((missed, stream)),
+            DormantStream::Ended => Ok((missed, Box::pin(futures::stream::empty()))),
+            DormantStream::Overflowed => {
+                Err(ResumeSubscriptionError::BufferExceeded(self.inner.config.buffer_depth))
+            }
+        }
+    }
+
+    /// Spawns the dormant task of the subscription with the given token, unless there are too
+    /// many dormant subscriptions.
+    fn park(
+        &self,
+        token: String,
+        missed: VecDeque<EthSubscriptionResult>,
+        stream: NotificationStream,
+    ) {
+        let (resume_tx, resume_rx) = oneshot::channel();
+        {
+            let mut dormant = self.inner.dormant.lock();
+            if dormant.len() >= self.inner.config.max_dormant {
+                trace!(target: "rpc::eth", %token, "Too many dormant subscriptions, dropping subscription");
+                return
+            }
+            dormant.insert(token.clone(), resume_tx);
+        }
+
+        trace!(target: "rpc::eth", %token, "Subscription is dormant");
+        let this = self.clone();
+        self.inner.task_spawner.spawn(Box::pin(async move {
+            this.buffer_until_resumed(token, missed, DormantStream::Active(stream), resume_rx).await
+        }));
+    }
+
+    /// Buffers the notifications of a dormant subscription until it's resumed or expires.
+    async fn buffer_until_resumed(
+        self,
+        token: String,
+        mut missed: VecDeque<EthSubscriptionResult>,
+        mut stream: DormantStream,
+        mut resume: oneshot::Receiver<oneshot::Sender<Dormant>>,
+    ) {
+        let expired = tokio::time::sleep(self.inner.config.expiry);
+        tokio::pin!(expired);
+
+        loop {
+            tokio::select! {
+                // buffer the notifications that are ready before handling a resume request, the
+                // buffer is bounded so this can't starve the other branches
+                biased;
+
+                maybe_notification = stream.next() => {
+                    match maybe_notification {
+                        Some(notification) if missed.len() < self.inner.config.buffer_depth => {
+                            missed.push_back(notification);
+                        }
+                        Some(_) => {
+                            trace!(target: "rpc::eth", %token, "Dormant subscription overflowed");
+                            // the subscription can't be resumed anymore, so there's no need to
+                            // keep the notifications
+                            missed = VecDeque::new();
+                            stream = DormantStream::Overflowed;
+                        }
+                        None => stream = DormantStream::Ended,
+                    }
+                },
+                _ = &mut expired => {
+                    trace!(target: "rpc::eth", %token, "Dormant subscription expired");
+                    self.inner.dormant.lock().remove(&token);
+                    break
+                },
+                resumed = &mut resume => {
+                    if let Ok(resumed) = resumed {
+                        let _ = resumed.send(Dormant { missed, stream });
+                    }
+                    break
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for ResumableSubscriptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResumableSubscriptions")
+            .field("config", &self.inner.config)
+            .field("dormant", &self.inner.dormant.lock().len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Container type for [ResumableSubscriptions].
+struct ResumableSubscriptionsInner {
+    /// The config of resumable subscriptions.
+    config: ResumableSubscriptionsConfig,
+    /// The type that's used to spawn the dormant subscription tasks.
+    task_spawner: Box<dyn TaskSpawner>,
+    /// The `eth_unsubscribe` calls of the connected subscriptions.
+    unsubscribes: ResumableUnsubscribes,
+    /// The dormant subscriptions by token, with the channel to resume them.
+    dormant: Mutex<HashMap<String, oneshot::Sender<oneshot::Sender<Dormant>>>>,
+}
+
+/// A dormant subscription that is handed over when it's resumed.
+struct Dormant {
+    /// The notifications that were missed.
+    missed: VecDeque<EthSubscriptionResult>,
+    /// The notifications of the subscription.
+    stream: DormantStream,
+}
+
+/// The notification stream of a dormant subscription.
+enum DormantStream {
+    /// The stream is still active.
+    Active(NotificationStream),
+    /// The stream ended, only the missed notifications are left.
+    Ended,
+    /// More notifications were missed than could be buffered.
+    Overflowed,
+}
+
+impl DormantStream {
+    /// Returns the next notification of an active stream, never resolves otherwise.
+    async fn next(&mut self) -> Option<EthSubscriptionResult> {
+        match self {
+            Self::Active(stream) => stream.next().await,
+            Self::Ended | Self::Overflowed => futures::future::pending().await,
+        }
+    }
+}
+
+/// Errors of resuming a subscription.
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum ResumeSubscriptionError {
+    /// There's no dormant subscription with the given token.
+    #[error("Unknown or expired resumption token")]
+    UnknownToken,
+    /// The subscription missed more notifications than could be buffered.
+    #[error("Subscription missed more than {0} notifications and can't be resumed")]
+    BufferExceeded(usize),
+}
+
+impl From<ResumeSubscriptionError> for ErrorObject<'static> {
+    fn from(err: ResumeSubscriptionError) -> Self {
+        invalid_params_rpc_err(err.to_string())
+    }
+}
+
+/// Sends the missed notifications and then all notifications of the stream to the sink.
+///
+/// Returns the notifications that weren't sent and the stream if the sink was closed before the
+/// stream ended.
+async fn pipe_until_closed(
+    sink: &SubscriptionSink,
+    mut missed: VecDeque<EthSubscriptionResult>,
+    mut stream: NotificationStream,
+) -> Result<Option<(VecDeque<EthSubscriptionResult>, NotificationStream)>, ErrorObject<'static>> {
+    while let Some(notification) = missed.pop_front() {
+        let msg = SubscriptionMessage::from_json(&notification)
+            .map_err(SubscriptionSerializeError::new)?;
+        if sink.send(msg).await.is_err() {
+            missed.push_front(notification);
+            return Ok(Some((missed, stream)))
+        }
+    }
+
+    loop {
+        tokio::select! {
+            _ = sink.closed() => {
+                break Ok(Some((VecDeque::new(), stream)))
+            },
+            maybe_notification = stream.next() => {
+                let Some(notification) = maybe_notification else {
+                    // stream ended
+                    break Ok(None)
+                };
+                let msg = SubscriptionMessage::from_json(&notification)
+                    .map_err(SubscriptionSerializeError::new)?;
+                if sink.send(msg).await.is_err() {
+                    break Ok(Some((VecDeque::from([notification]), stream)))
+                }
+            }
+        }
+    }
+}
+
+/// Returns the resumption token of a subscription, which is its id.
+fn resumption_token(id: SubscriptionId<'_>) -> String {
+    match id {
+        SubscriptionId::Num(num) => num.to_string(),
+        SubscriptionId::Str(id) => id.into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+    use reth_tasks::TokioTaskExecutor;
+
+    fn subscriptions(config: ResumableSubscriptionsConfig) -> ResumableSubscriptions {
+        ResumableSubscriptions::new(config, Box::<TokioTaskExecutor>::default(), Default::default())
+    }
+
+    fn notification(byte: u8) -> EthSubscriptionResult {
+        EthSubscriptionResult::TransactionHash(B256::with_last_byte(byte))
+    }
+
+    fn hashes(notifications: VecDeque<EthSubscriptionResult>) -> Vec<B256> {
+        notifications
+            .into_iter()
+            .map(|notification| match notification {
+                EthSubscriptionResult::TransactionHash(hash) => hash,
+                _ => unreachable!(),
+            })
+            .collect()
+    }
+
+    /// Spawns the dormant task of a subscription that missed a notification and whose stream
+    /// yields the given notifications.
+    fn park(
+        subscriptions: &ResumableSubscriptions,
+        token: &str,
+        notifications: Vec<EthSubscriptionResult>,
+    ) -> tokio::task::JoinHandle<()> {
+        let (resume_tx, resume_rx) = oneshot::channel();
+        subscriptions.inner.dormant.lock().insert(token.to_string(), resume_tx);
+        let stream =
+            Box::pin(futures::stream::iter(notifications).chain(futures::stream::pending()));
+        tokio::spawn(subscriptions.clone().buffer_until_resumed(
+            token.to_string(),
+            VecDeque::from([notification(0)]),
+            DormantStream::Active(stream),
+            resume_rx,
+        ))
+    }
+
+    #[tokio::test]
+    async fn resume_with_missed_notifications() {
+        let subscriptions = subscriptions(Default::default());
+        park(&subscriptions, "0x1", vec![notification(1), notification(2)]);
+
+        let (missed, _) = subscriptions.resume("0x1").await.unwrap();
+        assert_eq!(
+            hashes(missed),
+            vec![B256::with_last_byte(0), B256::with_last_byte(1), B256::with_last_byte(2)]
+        );
+
+        // a subscription can only be resumed once
+        assert!(matches!(
+            subscriptions.resume("0x1").await,
+            Err(ResumeSubscriptionError::UnknownToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn resume_overflowed() {
+        let subscriptions = subscriptions(ResumableSubscriptionsConfig::default().buffer_depth(2));
+        park(&subscriptions, "0x1", vec![notification(1), notification(2)]);
+
+        assert!(matches!(
+            subscriptions.resume("0x1").await,
+            Err(ResumeSubscriptionError::BufferExceeded(2))
+        ));
+    }
+
+    #[test]
+    fn track_unsubscribes() {
+        let unsubscribes = ResumableUnsubscribes::default();
+        // subscriptions that aren't connected are not tracked
+        unsubscribes.on_unsubscribe("0x1");
+        assert!(!unsubscribes.disconnect("0x1"));
+
+        unsubscribes.connect("0x1".to_string());
+        unsubscribes.connect("0x2".to_string());
+        unsubscribes.on_unsubscribe("0x1");
+        assert!(unsubscribes.disconnect("0x1"));
+        assert!(!unsubscribes.disconnect("0x2"));
+        assert!(unsubscribes.connected.lock().is_empty());
+    }
+
+    #[tokio::test]
+    async fn dormant_subscription_expires() {
+        let config = ResumableSubscriptionsConfig::default().expiry(Duration::from_millis(10));
+        let subscriptions = subscriptions(config);
+        park(&subscriptions, "0x1", vec![]).await.unwrap();
+
+        assert!(subscriptions.inner.dormant.lock().is_empty());
+        assert!(matches!(
+            subscriptions.resume("0x1").await,
+            Err(ResumeSubscriptionError::UnknownToken)
+        ));
+    }
+}
@@ -0,0 +1,49 @@
+//! Helpers for testing the handlers that are built on top of the [EthApi].
+
+use crate::{
+    eth::{
+        cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache, FeeHistoryCacheConfig,
+        DEFAULT_ETH_PROOF_WINDOW,
+    },
+    EthApi,
+};
+use reth_evm_ethereum::EthEvmConfig;
+use reth_network_api::noop::NoopNetwork;
+use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
+use reth_provider::{
+    BlockReader, BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory,
+};
+use reth_tasks::pool::BlockingTaskPool;
+use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+
+/// Returns an [EthApi] on top of the given provider, with a testing pool and no network.
+pub(crate) fn build_test_eth_api<
+    P: BlockReaderIdExt
+        + BlockReader
+        + ChainSpecProvider
+        + EvmEnvProvider
+        + StateProviderFactory
+        + Unpin
+        + Clone
+        + 'static,
+>(
+    provider: P,
+) -> EthApi<P, TestPool, NoopNetwork, EthEvmConfig> {
+    let evm_config = EthEvmConfig::default();
+    let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+    let fee_history_cache = FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
+
+    EthApi::new(
+        provider.clone(),
+        testing_pool(),
+        NoopNetwork::default(),
+        cache.clone(),
+        GasPriceOracle::new(provider, Default::default(), cache),
+        ETHEREUM_BLOCK_GAS_LIMIT,
+        DEFAULT_ETH_PROOF_WINDOW,
+        BlockingTaskPool::build().expect("failed to build tracing pool"),
+        fee_history_cache,
+        evm_config,
+        None,
+    )
+}
@@ -30,6 +30,7 @@ mod debug;
 mod engine;
 pub mod eth;
 mod layers;
+mod miner;
 mod net;
 mod otterscan;
 mod reth;
@@ -37,16 +38,18 @@ mod rpc;
 mod trace;
 mod txpool;
 mod web3;
-pub use admin::AdminApi;
-pub use debug::DebugApi;
+mod witness;
+pub use admin::{AdminApi, AdminConfigApi};
+pub use debug::{DebugApi, DEFAULT_MAX_TRACE_CHAIN_BLOCKS};
 pub use engine::{EngineApi, EngineEthApi};
 pub use eth::{EthApi, EthApiSpec, EthFilter, EthPubSub, EthSubscriptionIdProvider};
 pub use layers::{AuthLayer, AuthValidator, Claims, JwtAuthValidator, JwtError, JwtSecret};
+pub use miner::MinerApi;
 pub use net::NetApi;
 pub use otterscan::OtterscanApi;
 pub use reth::RethApi;
 pub use rpc::RPCApi;
-pub use trace::TraceApi;
-pub use txpool::TxPoolApi;
+pub use trace::{TraceApi, TraceCallManyError, DEFAULT_MAX_TRACE_FILTER_BLOCKS};
+pub use txpool::{TxPoolApi, DEFAULT_MAX_TXPOOL_ENTRIES};
 pub use web3::Web3Api;
 pub mod result;
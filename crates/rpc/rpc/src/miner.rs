@@ -0,0 +1,46 @@
+use jsonrpsee::core::RpcResult;
+use reth_primitives::U128;
+use reth_rpc_api::MinerApiServer;
+use reth_transaction_pool::TransactionPool;
+use tracing::info;
+
+/// `miner` API implementation.
+///
+/// This type provides the functionality for handling `miner` related requests.
+pub struct MinerApi<Pool> {
+    /// An interface to interact with the pool
+    pool: Pool,
+}
+
+impl<Pool> MinerApi<Pool> {
+    /// Creates a new instance of `MinerApi`.
+    pub fn new(pool: Pool) -> Self {
+        MinerApi { pool }
+    }
+}
+
+impl<Pool> MinerApiServer for MinerApi<Pool>
+where
+    Pool: TransactionPool + 'static,
+{
+    /// Handler for `miner_setGasPrice`
+    fn set_gas_price(&self, gas_price: U128) -> RpcResult<bool> {
+        let floor = gas_price.to::<u128>();
+        info!(target: "rpc::miner", floor, "Overriding the priority fee floor");
+        self.pool.set_priority_fee_floor_override(Some(floor));
+        Ok(true)
+    }
+
+    /// Handler for `miner_clearGasPrice`
+    fn clear_gas_price(&self) -> RpcResult<bool> {
+        info!(target: "rpc::miner", "Clearing the priority fee floor override");
+        self.pool.set_priority_fee_floor_override(None);
+        Ok(true)
+    }
+}
+
+impl<Pool> std::fmt::Debug for MinerApi<Pool> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MinerApi").finish_non_exhaustive()
+    }
+}
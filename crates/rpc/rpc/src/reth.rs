@@ -1,39 +1,79 @@
-use crate::eth::error::{EthApiError, EthResult};
+use crate::{
+    eth::error::{EthApiError, EthResult},
+    result::ToRpcResult,
+};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
-use reth_interfaces::RethResult;
-use reth_primitives::{Address, BlockId, U256};
-use reth_provider::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
+use reth_interfaces::{sync::SyncStatusHandle, RethResult};
+use reth_network_api::Peers;
+use reth_primitives::{
+    stage::{StageId, StageProgress},
+    static_file::StaticFileSegment,
+    Address, BlockId, BlockNumber, ChainStatus, DatabaseStatus, Head, NodeStatus, PeerCountStatus,
+    PruneMode, PruneModes, PruneSegment, PruneSegmentStatus, StageCheckpointStatus,
+    StaticFileSegmentStatus, U256,
+};
+use reth_provider::{
+    BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, DatabaseMetadataReader,
+    PruneCheckpointReader, StageCheckpointReader, StateProviderFactory, StaticFileProviderFactory,
+};
 use reth_rpc_api::RethApiServer;
+use reth_rpc_types::PeerStats;
 use reth_tasks::TaskSpawner;
-use std::{collections::HashMap, future::Future, sync::Arc};
+use std::{collections::HashMap, future::Future, ops::RangeInclusive, sync::Arc};
 use tokio::sync::oneshot;
 
 /// `reth` API implementation.
 ///
 /// This type provides the functionality for handling `reth` prototype RPC requests.
-pub struct RethApi<Provider> {
-    inner: Arc<RethApiInner<Provider>>,
+pub struct RethApi<Provider, Network> {
+    inner: Arc<RethApiInner<Provider, Network>>,
 }
 
 // === impl RethApi ===
 
-impl<Provider> RethApi<Provider> {
+impl<Provider, Network> RethApi<Provider, Network> {
     /// The provider that can interact with the chain.
     pub fn provider(&self) -> &Provider {
         &self.inner.provider
     }
 
+    /// The network that the node is connected to.
+    pub fn network(&self) -> &Network {
+        &self.inner.network
+    }
+
     /// Create a new instance of the [RethApi]
-    pub fn new(provider: Provider, task_spawner: Box<dyn TaskSpawner>) -> Self {
-        let inner = Arc::new(RethApiInner { provider, task_spawner });
+    pub fn new(
+        provider: Provider,
+        network: Network,
+        task_spawner: Box<dyn TaskSpawner>,
+        sync_status: SyncStatusHandle,
+        prune_modes: PruneModes,
+    ) -> Self {
+        let inner =
+            Arc::new(RethApiInner { provider, network, task_spawner, sync_status, prune_modes });
         Self { inner }
     }
+
+    /// Returns the latest progress of every stage of the sync.
+    pub fn sync_status(&self) -> Vec<StageProgress> {
+        self.inner.sync_status.snapshot()
+    }
 }
 
-impl<Provider> RethApi<Provider>
+impl<Provider, Network> RethApi<Provider, Network>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider: BlockReaderIdExt
+        + ChainSpecProvider
+        + ChangeSetReader
+        + StateProviderFactory
+        + PruneCheckpointReader
+        + StageCheckpointReader
+        + StaticFileProviderFactory
+        + DatabaseMetadataReader
+        + 'static,
+    Network: Send + Sync + 'static,
 {
     /// Executes the future on a new blocking task.
     async fn on_blocking_task<C, F, R>(&self, c: C) -> EthResult<R>
@@ -81,12 +121,188 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the pruning status of every segment, with the lowest block its data is available
+    /// for.
+    pub async fn prune_status(&self) -> EthResult<Vec<PruneSegmentStatus>> {
+        self.on_blocking_task(|this| async move { this.try_prune_status() }).await
+    }
+
+    fn try_prune_status(&self) -> EthResult<Vec<PruneSegmentStatus>> {
+        let prune_modes = &self.inner.prune_modes;
+        let static_file_provider = self.provider().static_file_provider();
+        let tip = self.provider().best_block_number()?;
+
+        [
+            PruneSegment::SenderRecovery,
+            PruneSegment::TransactionLookup,
+            PruneSegment::Receipts,
+            PruneSegment::ContractLogs,
+            PruneSegment::AccountHistory,
+            PruneSegment::StorageHistory,
+            PruneSegment::Headers,
+            PruneSegment::Transactions,
+            PruneSegment::CallTraces,
+        ]
+        .into_iter()
+        .map(|segment| {
+            let pruned_block = self
+                .provider()
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number);
+            let prune_mode = match segment {
+                PruneSegment::SenderRecovery => prune_modes.sender_recovery,
+                PruneSegment::TransactionLookup => prune_modes.transaction_lookup,
+                PruneSegment::Receipts => prune_modes.receipts,
+                PruneSegment::AccountHistory => prune_modes.account_history,
+                PruneSegment::StorageHistory => prune_modes.storage_history,
+                PruneSegment::CallTraces => prune_modes.call_traces,
+                // The receipts with logs of the filtered addresses are retained from the lowest
+                // block of the filter at the tip, the filter itself is validated on startup.
+                PruneSegment::ContractLogs => prune_modes
+                    .receipts_log_filter
+                    .lowest_retained_block(tip, pruned_block)
+                    .ok()
+                    .flatten()
+                    .map(PruneMode::Before),
+                PruneSegment::Headers | PruneSegment::Transactions => None,
+            };
+
+            let db_lowest_block = pruned_block.map_or(0, |block| block + 1);
+            let static_file_segment = match segment {
+                PruneSegment::Headers => Some(StaticFileSegment::Headers),
+                PruneSegment::Transactions => Some(StaticFileSegment::Transactions),
+                PruneSegment::Receipts => Some(StaticFileSegment::Receipts),
+                PruneSegment::SenderRecovery => Some(StaticFileSegment::TransactionSenders),
+                _ => None,
+            };
+            let static_file_blocks = static_file_segment.and_then(|segment| {
+                let lowest = static_file_provider.get_lowest_static_file_block(segment)?;
+                let highest = static_file_provider.get_highest_static_file_block(segment)?;
+                Some(lowest..=highest)
+            });
+            let lowest_available_block =
+                lowest_available_block(db_lowest_block, static_file_blocks);
+
+            Ok(PruneSegmentStatus { segment, prune_mode, pruned_block, lowest_available_block })
+        })
+        .collect()
+    }
+
+    /// Returns the status of the node: the checkpoints of the stages, the static files, the
+    /// pruning, the database, the connected peers and the chain at the head.
+    pub async fn node_status(&self) -> EthResult<NodeStatus>
+    where
+        Network: Peers,
+    {
+        let peers = self.network().get_all_peers().await;
+        let inbound = peers.iter().filter(|peer| peer.direction.is_incoming()).count();
+        let peers = PeerCountStatus { inbound, outbound: peers.len() - inbound };
+
+        let mut status =
+            self.on_blocking_task(|this| async move { this.try_node_status() }).await?;
+        status.peers = peers;
+        Ok(status)
+    }
+
+    /// Returns the status of the node read from the database and the static files, without the
+    /// connected peers.
+    fn try_node_status(&self) -> EthResult<NodeStatus> {
+        let stages = StageId::ALL
+            .into_iter()
+            .map(|stage| {
+                let block_number = self
+                    .provider()
+                    .get_stage_checkpoint(stage)?
+                    .map(|checkpoint| checkpoint.block_number);
+                Ok(StageCheckpointStatus { stage: stage.to_string(), block_number })
+            })
+            .collect::<EthResult<_>>()?;
+
+        let static_file_provider = self.provider().static_file_provider();
+        let static_files = [
+            StaticFileSegment::Headers,
+            StaticFileSegment::Transactions,
+            StaticFileSegment::Receipts,
+            StaticFileSegment::TransactionSenders,
+        ]
+        .into_iter()
+        .map(|segment| StaticFileSegmentStatus {
+            segment,
+            highest_block: static_file_provider.get_highest_static_file_block(segment),
+        })
+        .collect();
+
+        let metadata = self.provider().database_metadata();
+        let database = DatabaseStatus {
+            size: metadata.size().map(|size| size as u64),
+            freelist_pages: metadata.freelist_size().map(|pages| pages as u64),
+        };
+
+        let chain_spec = self.provider().chain_spec();
+        let head_number = self.provider().best_block_number()?;
+        let head =
+            self.provider().sealed_header(head_number)?.ok_or(EthApiError::UnknownBlockNumber)?;
+        let total_difficulty =
+            self.provider().header_td_by_number(head_number)?.unwrap_or_default();
+        let head = Head {
+            number: head.number,
+            hash: head.hash(),
+            difficulty: head.difficulty,
+            total_difficulty,
+            timestamp: head.timestamp,
+        };
+        let chain = ChainStatus {
+            chain_id: chain_spec.chain().id(),
+            genesis_hash: chain_spec.genesis_hash(),
+            head_number: head.number,
+            head_hash: head.hash,
+            fork_id: chain_spec.fork_id(&head),
+            hardforks: chain_spec
+                .forks_iter()
+                .filter(|(_, condition)| condition.active_at_head(&head))
+                .map(|(fork, _)| fork)
+                .collect(),
+        };
+
+        Ok(NodeStatus {
+            stages,
+            static_files,
+            prune: self.try_prune_status()?,
+            database,
+            peers: PeerCountStatus::default(),
+            chain,
+        })
+    }
+}
+
+/// Returns the lowest block the data of a segment is available for, given the lowest block that
+/// isn't pruned from the database and the blocks of the static files of the segment.
+///
+/// Data that was moved to static files is never pruned from the database, so the static files
+/// extend the available range only if they're contiguous with it.
+fn lowest_available_block(
+    db_lowest_block: BlockNumber,
+    static_file_blocks: Option<RangeInclusive<BlockNumber>>,
+) -> BlockNumber {
+    static_file_blocks
+        .filter(|blocks| blocks.end() + 1 >= db_lowest_block)
+        .map_or(db_lowest_block, |blocks| (*blocks.start()).min(db_lowest_block))
 }
 
 #[async_trait]
-impl<Provider> RethApiServer for RethApi<Provider>
+impl<Provider, Network> RethApiServer for RethApi<Provider, Network>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider: BlockReaderIdExt
+        + ChainSpecProvider
+        + ChangeSetReader
+        + StateProviderFactory
+        + PruneCheckpointReader
+        + StageCheckpointReader
+        + StaticFileProviderFactory
+        + DatabaseMetadataReader
+        + 'static,
+    Network: Peers + 'static,
 {
     /// Handler for `reth_getBalanceChangesInBlock`
     async fn reth_get_balance_changes_in_block(
@@ -95,23 +311,76 @@ where
     ) -> RpcResult<HashMap<Address, U256>> {
         Ok(RethApi::balance_changes_in_block(self, block_id).await?)
     }
+
+    /// Handler for `reth_syncStatus`
+    async fn reth_sync_status(&self) -> RpcResult<Vec<StageProgress>> {
+        Ok(RethApi::sync_status(self))
+    }
+
+    /// Handler for `reth_getPruneStatus`
+    async fn reth_get_prune_status(&self) -> RpcResult<Vec<PruneSegmentStatus>> {
+        Ok(RethApi::prune_status(self).await?)
+    }
+
+    /// Handler for `reth_nodeStatus`
+    async fn reth_node_status(&self) -> RpcResult<NodeStatus> {
+        Ok(RethApi::node_status(self).await?)
+    }
+
+    /// Handler for `reth_peerStats`
+    async fn reth_peer_stats(&self, reset: Option<bool>) -> RpcResult<Vec<PeerStats>> {
+        self.network().get_peer_stats(reset.unwrap_or_default()).await.to_rpc_result()
+    }
 }
 
-impl<Provider> std::fmt::Debug for RethApi<Provider> {
+impl<Provider, Network> std::fmt::Debug for RethApi<Provider, Network> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RethApi").finish_non_exhaustive()
     }
 }
 
-impl<Provider> Clone for RethApi<Provider> {
+impl<Provider, Network> Clone for RethApi<Provider, Network> {
     fn clone(&self) -> Self {
         Self { inner: Arc::clone(&self.inner) }
     }
 }
 
-struct RethApiInner<Provider> {
+struct RethApiInner<Provider, Network> {
     /// The provider that can interact with the chain.
     provider: Provider,
+    /// The network that the node is connected to.
+    network: Network,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
+    /// The latest progress of the stages of the sync.
+    sync_status: SyncStatusHandle,
+    /// The configured prune modes of the node.
+    prune_modes: PruneModes,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lowest_available_block_without_static_files() {
+        assert_eq!(lowest_available_block(0, None), 0);
+        assert_eq!(lowest_available_block(101, None), 101);
+    }
+
+    #[test]
+    fn lowest_available_block_with_contiguous_static_files() {
+        // the static files end right below the database
+        assert_eq!(lowest_available_block(101, Some(0..=100)), 0);
+        // the static files overlap with the database
+        assert_eq!(lowest_available_block(101, Some(50..=200)), 50);
+        // nothing was pruned from the database
+        assert_eq!(lowest_available_block(0, Some(0..=100)), 0);
+    }
+
+    #[test]
+    fn lowest_available_block_with_gap_to_static_files() {
+        // the blocks between the static files and the database were pruned
+        assert_eq!(lowest_available_block(101, Some(0..=50)), 101);
+    }
 }
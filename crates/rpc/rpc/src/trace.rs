@@ -1,16 +1,19 @@
 use crate::eth::{
-    error::{EthApiError, EthResult},
+    error::{EthApiError, EthResult, ToRpcError},
     revm_utils::{prepare_call_env, EvmOverrides},
     utils::recover_raw_transaction,
     EthTransactions,
 };
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::{core::RpcResult as Result, types::ErrorObject};
 use reth_consensus_common::calc::{base_block_reward, block_reward};
 use reth_primitives::{
-    revm::env::tx_env_with_recovered, BlockId, BlockNumberOrTag, Bytes, SealedHeader, B256, U256,
+    revm::env::tx_env_with_recovered, Address, BlockId, BlockNumber, BlockNumberOrTag, Bytes,
+    SealedHeader, B256, U256,
+};
+use reth_provider::{
+    BlockReader, CallTraceIndexReader, ChainSpecProvider, EvmEnvProvider, StateProviderFactory,
 };
-use reth_provider::{BlockReader, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
 use reth_revm::{
     database::StateProviderDatabase,
     tracing::{parity::populate_state_diff, TracingInspector, TracingInspectorConfig},
@@ -19,12 +22,12 @@ use reth_rpc_api::TraceApiServer;
 use reth_rpc_types::{
     state::StateOverride,
     trace::{
-        filter::TraceFilter,
+        filter::{TraceFilter, TraceFilterMatcher, TraceFilterMode},
         opcode::{BlockOpcodeGas, TransactionOpcodeGas},
         parity::*,
         tracerequest::TraceCallRequest,
     },
-    BlockError, BlockOverrides, Index, TransactionRequest,
+    BlockOverrides, Index, TransactionRequest,
 };
 use reth_tasks::pool::BlockingTaskGuard;
 use revm::{
@@ -32,14 +35,23 @@ use revm::{
     primitives::EnvWithHandlerCfg,
 };
 use revm_inspectors::opcode::OpcodeGasInspector;
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, ops::RangeInclusive, sync::Arc};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
+/// The default maximum number of blocks that can be traced by a single `trace_filter` request.
+pub const DEFAULT_MAX_TRACE_FILTER_BLOCKS: u64 = 100;
+
+/// The number of blocks that are traced concurrently by a `trace_filter` request, every block with
+/// its own permit.
+const TRACE_FILTER_CHUNK_SIZE: usize = 10;
+
 /// `trace` API implementation.
 ///
 /// This type provides the functionality for handling `trace` related requests.
 pub struct TraceApi<Provider, Eth> {
     inner: Arc<TraceApiInner<Provider, Eth>>,
+    /// The maximum number of blocks that can be traced by a single `trace_filter` request.
+    max_trace_filter_blocks: u64,
 }
 
 // === impl TraceApi ===
@@ -53,7 +65,13 @@ impl<Provider, Eth> TraceApi<Provider, Eth> {
     /// Create a new instance of the [TraceApi]
     pub fn new(provider: Provider, eth_api: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
         let inner = Arc::new(TraceApiInner { provider, eth_api, blocking_task_guard });
-        Self { inner }
+        Self { inner, max_trace_filter_blocks: DEFAULT_MAX_TRACE_FILTER_BLOCKS }
+    }
+
+    /// Sets the maximum number of blocks that can be traced by a single `trace_filter` request.
+    pub fn with_max_trace_filter_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_trace_filter_blocks = max_blocks;
+        self
     }
 
     /// Acquires a permit to execute a tracing call.
@@ -73,7 +91,12 @@ impl<Provider, Eth> TraceApi<Provider, Eth> {
 
 impl<Provider, Eth> TraceApi<Provider, Eth>
 where
-    Provider: BlockReader + StateProviderFactory + EvmEnvProvider + ChainSpecProvider + 'static,
+    Provider: BlockReader
+        + StateProviderFactory
+        + EvmEnvProvider
+        + ChainSpecProvider
+        + CallTraceIndexReader
+        + 'static,
     Eth: EthTransactions + 'static,
 {
     /// Executes the given call and returns a number of possible traces for it.
@@ -131,11 +154,16 @@ where
     /// Performs multiple call traces on top of the same block. i.e. transaction n will be executed
     /// on top of a pending block with all n-1 transactions applied (traced) first.
     ///
+    /// The state overrides are applied before the first call, the block overrides apply to all
+    /// calls.
+    ///
     /// Note: Allows tracing dependent transactions, hence all transactions are traced in sequence
+    /// and a failed call returns a [TraceCallManyError] with the traces of the calls before it.
     pub async fn trace_call_many(
         &self,
         calls: Vec<(TransactionRequest, HashSet<TraceType>)>,
         block_id: Option<BlockId>,
+        overrides: EvmOverrides,
     ) -> EthResult<Vec<TraceResults>> {
         let at = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Pending));
         let (cfg, block_env, at) = self.inner.eth_api.evm_env_at(at).await?;
@@ -147,36 +175,51 @@ where
             .spawn_with_state_at_block(at, move |state| {
                 let mut results = Vec::with_capacity(calls.len());
                 let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                let EvmOverrides { state: mut state_overrides, block: block_overrides } = overrides;
 
                 let mut calls = calls.into_iter().peekable();
 
                 while let Some((call, trace_types)) = calls.next() {
-                    let env = prepare_call_env(
+                    // apply state overrides only once, before the first call
+                    let overrides =
+                        EvmOverrides::new(state_overrides.take(), block_overrides.clone());
+                    let traced = prepare_call_env(
                         cfg.clone(),
                         block_env.clone(),
                         call,
                         gas_limit,
                         &mut db,
-                        Default::default(),
-                    )?;
-                    let config = TracingInspectorConfig::from_parity_config(&trace_types);
-                    let mut inspector = TracingInspector::new(config);
-                    let (res, _) = this.eth_api().inspect(&mut db, env, &mut inspector)?;
-
-                    let trace_res = inspector.into_parity_builder().into_trace_results_with_state(
-                        &res,
-                        &trace_types,
-                        &db,
-                    )?;
+                        overrides,
+                    )
+                    .and_then(|env| {
+                        let config = TracingInspectorConfig::from_parity_config(&trace_types);
+                        let mut inspector = TracingInspector::new(config);
+                        let (res, _) = this.eth_api().inspect(&mut db, env, &mut inspector)?;
+
+                        let trace_res = inspector
+                            .into_parity_builder()
+                            .into_trace_results_with_state(&res, &trace_types, &db)?;
+                        Ok((trace_res, res.state))
+                    });
+
+                    let (trace_res, state) = match traced {
+                        Ok(traced) => traced,
+                        Err(err) => {
+                            // the following calls depend on the failed one
+                            return Err(EthApiError::other(TraceCallManyError {
+                                index: results.len(),
+                                error: err.into(),
+                                results,
+                            }))
+                        }
+                    };
 
                     results.push(trace_res);
 
-                    // need to apply the state changes of this call before executing the
-                    // next call
                     if calls.peek().is_some() {
                         // need to apply the state changes of this call before executing
                         // the next call
-                        db.commit(res.state)
+                        db.commit(state)
                     }
                 }
 
@@ -236,16 +279,28 @@ where
         Ok(self.trace_transaction(hash).await?.and_then(|traces| traces.into_iter().nth(index)))
     }
 
-    /// Returns all transaction traces that match the given filter.
+    /// Returns all traces that match the given filter.
+    ///
+    /// This traces the relevant transactions of the range like [Self::trace_block], including the
+    /// block reward traces, and only returns the traces whose addresses match the filter. The
+    /// `after` and `count` fields of the filter are applied to the matching traces of the entire
+    /// range.
     ///
-    /// This is similar to [Self::trace_block] but only returns traces for transactions that match
-    /// the filter.
+    /// If the call traces are indexed, all transactions of the blocks with matching calls are
+    /// traced. Otherwise only the transactions whose sender or recipient match the filter are
+    /// traced, so traces of internal calls are only found in those transactions.
+    ///
+    /// The blocks are traced in chunks, every block with its own permit, and no more chunks are
+    /// traced once enough traces were found.
     pub async fn trace_filter(
         &self,
         filter: TraceFilter,
     ) -> EthResult<Vec<LocalizedTransactionTrace>> {
         let matcher = filter.matcher();
-        let TraceFilter { from_block, to_block, after: _after, count: _count, .. } = filter;
+        let match_rewards = filter.from_address.is_empty();
+        let TraceFilter {
+            from_block, to_block, from_address, to_address, mode, after, count, ..
+        } = filter;
         let start = from_block.unwrap_or(0);
         let end = if let Some(to_block) = to_block {
             to_block
@@ -253,68 +308,145 @@ where
             self.provider().best_block_number()?
         };
 
-        // ensure that the range is not too large, since we need to fetch all blocks in the range
+        // ensure that the range is not too large, since we need to trace all blocks in the range
         let distance = end.saturating_sub(start);
-        if distance > 100 {
-            return Err(EthApiError::InvalidParams(
-                "Block range too large; currently limited to 100 blocks".to_string(),
-            ))
+        if distance > self.max_trace_filter_blocks {
+            return Err(EthApiError::InvalidParams(format!(
+                "Block range too large; currently limited to {} blocks",
+                self.max_trace_filter_blocks
+            )))
         }
 
+        let indexed_blocks = self
+            .call_trace_blocks(
+                mode,
+                from_address.into_iter().collect(),
+                to_address.into_iter().collect(),
+                start..=end,
+            )?
+            .map(|blocks| blocks.into_iter().collect::<HashSet<_>>());
+
         // fetch all blocks in that range
-        let blocks = self.provider().block_range(start..=end)?;
+        let blocks = self.provider().block_with_senders_range(start..=end)?;
+        let headers = self.provider().sealed_headers_range(start..=end)?;
 
-        // find relevant blocks to trace
+        // find relevant transactions to trace, `None` if all transactions are traced
         let mut target_blocks = Vec::new();
-        for block in blocks {
-            let mut transaction_indices = HashSet::new();
-            let mut highest_matching_index = 0;
-            for (tx_idx, tx) in block.body.iter().enumerate() {
-                let from = tx.recover_signer().ok_or(BlockError::InvalidSignature)?;
-                let to = tx.to();
-                if matcher.matches(from, to) {
-                    let idx = tx_idx as u64;
-                    transaction_indices.insert(idx);
-                    highest_matching_index = idx;
-                }
+        for (block, header) in blocks.into_iter().zip(headers) {
+            let transaction_indices = match &indexed_blocks {
+                Some(indexed_blocks) if indexed_blocks.contains(&block.number) => None,
+                Some(_) => Some(HashSet::new()),
+                None => Some(
+                    block
+                        .body
+                        .iter()
+                        .zip(&block.senders)
+                        .enumerate()
+                        .filter(|(_, (tx, from))| matcher.matches(**from, tx.to()))
+                        .map(|(tx_idx, _)| tx_idx as u64)
+                        .collect(),
+                ),
+            };
+            if !match_rewards && transaction_indices.as_ref().is_some_and(HashSet::is_empty) {
+                continue
             }
-            if !transaction_indices.is_empty() {
-                target_blocks.push((block.number, transaction_indices, highest_matching_index));
+            target_blocks.push((header, block.ommers.len(), transaction_indices));
+        }
+
+        let after = after.unwrap_or_default() as usize;
+        let count = count.map_or(usize::MAX, |count| count as usize);
+        let mut traces = Vec::new();
+        for chunk in target_blocks.chunks(TRACE_FILTER_CHUNK_SIZE) {
+            let block_traces = chunk.iter().map(|(header, ommers, transaction_indices)| {
+                self.trace_filter_block(header, *ommers, transaction_indices.clone(), match_rewards)
+            });
+            let block_traces = futures::future::try_join_all(block_traces).await?;
+            traces.extend(
+                block_traces
+                    .into_iter()
+                    .flatten()
+                    .filter(|trace| trace_matches(&matcher, match_rewards, &trace.trace)),
+            );
+
+            if traces.len() >= after.saturating_add(count) {
+                break
             }
         }
 
-        // trace all relevant blocks
-        let mut block_traces = Vec::with_capacity(target_blocks.len());
-        for (num, indices, highest_idx) in target_blocks {
-            let traces = self.inner.eth_api.trace_block_until(
-                num.into(),
-                Some(highest_idx),
-                TracingInspectorConfig::default_parity(),
-                move |tx_info, inspector, res, _, _| {
-                    if let Some(idx) = tx_info.index {
-                        if !indices.contains(&idx) {
-                            // only record traces for relevant transactions
-                            return Ok(None)
+        Ok(traces.into_iter().skip(after).take(count).collect())
+    }
+
+    /// Returns the traces of the given transactions of the block for `trace_filter`, followed by
+    /// the block reward traces if `with_rewards` is set.
+    ///
+    /// All transactions are traced if no indices are given. The transactions are traced with their
+    /// own permit.
+    async fn trace_filter_block(
+        &self,
+        header: &SealedHeader,
+        ommers: usize,
+        transaction_indices: Option<HashSet<u64>>,
+        with_rewards: bool,
+    ) -> EthResult<Vec<LocalizedTransactionTrace>> {
+        let highest_index =
+            transaction_indices.as_ref().and_then(|indices| indices.iter().max().copied());
+
+        let mut traces = Vec::new();
+        if transaction_indices.as_ref().map_or(true, |indices| !indices.is_empty()) {
+            let _permit = self.acquire_trace_permit().await;
+            let block_traces = self
+                .inner
+                .eth_api
+                .trace_block_until(
+                    header.number.into(),
+                    highest_index,
+                    TracingInspectorConfig::default_parity(),
+                    move |tx_info, inspector, res, _, _| {
+                        if let (Some(indices), Some(idx)) = (&transaction_indices, tx_info.index) {
+                            if !indices.contains(&idx) {
+                                // only record traces for relevant transactions
+                                return Ok(None)
+                            }
                         }
-                    }
-                    let traces = inspector
-                        .with_transaction_gas_used(res.gas_used())
-                        .into_parity_builder()
-                        .into_localized_transaction_traces(tx_info);
-                    Ok(Some(traces))
-                },
-            );
-            block_traces.push(traces);
+                        let traces = inspector
+                            .with_transaction_gas_used(res.gas_used())
+                            .into_parity_builder()
+                            .into_localized_transaction_traces(tx_info);
+                        Ok(Some(traces))
+                    },
+                )
+                .await?;
+            traces.extend(block_traces.into_iter().flatten().flatten().flatten());
         }
 
-        let block_traces = futures::future::try_join_all(block_traces).await?;
-        let all_traces = block_traces
-            .into_iter()
-            .flatten()
-            .flat_map(|traces| traces.into_iter().flatten().flat_map(|traces| traces.into_iter()))
-            .collect();
+        if with_rewards {
+            traces.extend(self.block_reward_traces(header, ommers)?);
+        }
+
+        Ok(traces)
+    }
+
+    /// Returns the blocks of the range that may contain traces matching the filter according to
+    /// the call trace index, or `None` if the blocks are not indexed or the filter doesn't
+    /// restrict the addresses of the traces.
+    fn call_trace_blocks(
+        &self,
+        mode: TraceFilterMode,
+        from: HashSet<Address>,
+        to: HashSet<Address>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> EthResult<Option<Vec<BlockNumber>>> {
+        // An empty set of addresses matches any address, so the index can only be used if every
+        // matching trace is from one of the `from` addresses or to one of the `to` addresses.
+        let restricted = match mode {
+            TraceFilterMode::Union => !from.is_empty() && !to.is_empty(),
+            TraceFilterMode::Intersection => !from.is_empty() || !to.is_empty(),
+        };
+        if !restricted {
+            return Ok(None)
+        }
 
-        Ok(all_traces)
+        Ok(self.provider().call_trace_blocks(range, &from, &to)?)
     }
 
     /// Returns all traces for the given transaction hash
@@ -362,40 +494,50 @@ where
             maybe_traces.map(|traces| traces.into_iter().flatten().collect::<Vec<_>>());
 
         if let (Some(block), Some(traces)) = (maybe_block, maybe_traces.as_mut()) {
-            if let Some(header_td) = self.provider().header_td(&block.header.hash())? {
-                if let Some(base_block_reward) = base_block_reward(
-                    self.provider().chain_spec().as_ref(),
-                    block.header.number,
-                    block.header.difficulty,
-                    header_td,
-                ) {
+            traces.extend(self.block_reward_traces(&block.header, block.ommers.len())?);
+        }
+
+        Ok(maybe_traces)
+    }
+
+    /// Returns the reward traces of the block with the given header and number of ommers.
+    fn block_reward_traces(
+        &self,
+        header: &SealedHeader,
+        ommers: usize,
+    ) -> EthResult<Vec<LocalizedTransactionTrace>> {
+        let mut traces = Vec::new();
+        if let Some(header_td) = self.provider().header_td(&header.hash())? {
+            if let Some(base_block_reward) = base_block_reward(
+                self.provider().chain_spec().as_ref(),
+                header.number,
+                header.difficulty,
+                header_td,
+            ) {
+                traces.push(reward_trace(
+                    header,
+                    RewardAction {
+                        author: header.beneficiary,
+                        reward_type: RewardType::Block,
+                        value: U256::from(base_block_reward),
+                    },
+                ));
+
+                if ommers > 0 {
                     traces.push(reward_trace(
-                        &block.header,
+                        header,
                         RewardAction {
-                            author: block.header.beneficiary,
-                            reward_type: RewardType::Block,
-                            value: U256::from(base_block_reward),
+                            author: header.beneficiary,
+                            reward_type: RewardType::Uncle,
+                            value: U256::from(
+                                block_reward(base_block_reward, ommers) - base_block_reward,
+                            ),
                         },
                     ));
-
-                    if !block.ommers.is_empty() {
-                        traces.push(reward_trace(
-                            &block.header,
-                            RewardAction {
-                                author: block.header.beneficiary,
-                                reward_type: RewardType::Uncle,
-                                value: U256::from(
-                                    block_reward(base_block_reward, block.ommers.len()) -
-                                        base_block_reward,
-                                ),
-                            },
-                        ));
-                    }
                 }
             }
         }
-
-        Ok(maybe_traces)
+        Ok(traces)
     }
 
     /// Replays all transactions in a block
@@ -490,7 +632,12 @@ where
 #[async_trait]
 impl<Provider, Eth> TraceApiServer for TraceApi<Provider, Eth>
 where
-    Provider: BlockReader + StateProviderFactory + EvmEnvProvider + ChainSpecProvider + 'static,
+    Provider: BlockReader
+        + StateProviderFactory
+        + EvmEnvProvider
+        + ChainSpecProvider
+        + CallTraceIndexReader
+        + 'static,
     Eth: EthTransactions + 'static,
 {
     /// Executes the given call and returns a number of possible traces for it.
@@ -515,9 +662,12 @@ where
         &self,
         calls: Vec<(TransactionRequest, HashSet<TraceType>)>,
         block_id: Option<BlockId>,
+        state_overrides: Option<StateOverride>,
+        block_overrides: Option<Box<BlockOverrides>>,
     ) -> Result<Vec<TraceResults>> {
         let _permit = self.acquire_trace_permit().await;
-        Ok(TraceApi::trace_call_many(self, calls, block_id).await?)
+        let overrides = EvmOverrides::new(state_overrides, block_overrides);
+        Ok(TraceApi::trace_call_many(self, calls, block_id, overrides).await?)
     }
 
     /// Handler for `trace_rawTransaction`
@@ -566,6 +716,7 @@ where
     ///
     /// # Limitations
     /// This currently requires block filter fields, since reth does not have address indices yet.
+    /// The blocks in the range are traced, so the range is limited.
     async fn trace_filter(&self, filter: TraceFilter) -> Result<Vec<LocalizedTransactionTrace>> {
         Ok(TraceApi::trace_filter(self, filter).await?)
     }
@@ -613,7 +764,10 @@ impl<Provider, Eth> std::fmt::Debug for TraceApi<Provider, Eth> {
 }
 impl<Provider, Eth> Clone for TraceApi<Provider, Eth> {
     fn clone(&self) -> Self {
-        Self { inner: Arc::clone(&self.inner) }
+        Self {
+            inner: Arc::clone(&self.inner),
+            max_trace_filter_blocks: self.max_trace_filter_blocks,
+        }
     }
 }
 
@@ -626,6 +780,32 @@ struct TraceApiInner<Provider, Eth> {
     blocking_task_guard: BlockingTaskGuard,
 }
 
+/// The error of a `trace_callMany` request with a failed call.
+///
+/// The error data contains the index of the failed call, the traces of the calls before it and
+/// the data of the call's error.
+#[derive(Debug, thiserror::Error)]
+#[error("call {index} failed: {}", error.message())]
+pub struct TraceCallManyError {
+    /// The index of the failed call.
+    index: usize,
+    /// The error of the failed call.
+    error: ErrorObject<'static>,
+    /// The traces of the calls before the failed call.
+    results: Vec<TraceResults>,
+}
+
+impl ToRpcError for TraceCallManyError {
+    fn to_rpc_error(&self) -> ErrorObject<'static> {
+        let data = serde_json::json!({
+            "index": self.index,
+            "results": self.results,
+            "data": self.error.data(),
+        });
+        ErrorObject::owned(self.error.code(), self.error.message().to_string(), Some(data))
+    }
+}
+
 /// Helper to construct a [`LocalizedTransactionTrace`] that describes a reward to the block
 /// beneficiary.
 fn reward_trace(header: &SealedHeader, reward: RewardAction) -> LocalizedTransactionTrace {
@@ -643,3 +823,169 @@ fn reward_trace(header: &SealedHeader, reward: RewardAction) -> LocalizedTransac
         },
     }
 }
+
+/// Returns true if the addresses of the trace match the filter.
+///
+/// Like OpenEthereum, this matches the `from` and `to` addresses of the trace's action: the
+/// address of a self-destructed contract and its refund address are its `from` and `to`, the
+/// address of a created contract is the `to` of a create. A reward has no `from` address, so it
+/// only matches if the filter has no `fromAddress`.
+fn trace_matches(
+    matcher: &TraceFilterMatcher,
+    match_rewards: bool,
+    trace: &TransactionTrace,
+) -> bool {
+    match &trace.action {
+        Action::Call(call) => matcher.matches(call.from, Some(call.to)),
+        Action::Create(create) => {
+            let to = match &trace.result {
+                Some(TraceOutput::Create(output)) => Some(output.address),
+                _ => None,
+            };
+            matcher.matches(create.from, to)
+        }
+        Action::Selfdestruct(selfdestruct) => {
+            matcher.matches(selfdestruct.address, Some(selfdestruct.refund_address))
+        }
+        Action::Reward(reward) => {
+            match_rewards && matcher.matches(reward.author, Some(reward.author))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::test_utils::build_test_eth_api;
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_rpc_types::state::AccountOverride;
+
+    fn trace(action: Action) -> TransactionTrace {
+        TransactionTrace { trace_address: vec![], subtraces: 0, action, error: None, result: None }
+    }
+
+    fn filter(from_address: Vec<Address>, to_address: Vec<Address>) -> TraceFilter {
+        TraceFilter {
+            from_block: None,
+            to_block: None,
+            from_address,
+            to_address,
+            mode: Default::default(),
+            after: None,
+            count: None,
+        }
+    }
+
+    fn matches(filter: TraceFilter, trace: &TransactionTrace) -> bool {
+        trace_matches(&filter.matcher(), filter.from_address.is_empty(), trace)
+    }
+
+    #[test]
+    fn filter_reward_traces() {
+        let author = Address::with_last_byte(1);
+        let reward = trace(Action::Reward(RewardAction {
+            author,
+            reward_type: RewardType::Block,
+            value: U256::from(2),
+        }));
+
+        assert!(matches(filter(vec![], vec![]), &reward));
+        assert!(matches(filter(vec![], vec![author]), &reward));
+        assert!(!matches(filter(vec![], vec![Address::with_last_byte(2)]), &reward));
+        // rewards have no sender
+        assert!(!matches(filter(vec![author], vec![]), &reward));
+        assert!(!matches(filter(vec![author], vec![author]), &reward));
+    }
+
+    #[test]
+    fn filter_selfdestruct_traces() {
+        let address = Address::with_last_byte(1);
+        let refund_address = Address::with_last_byte(2);
+        let selfdestruct = trace(Action::Selfdestruct(SelfdestructAction {
+            address,
+            refund_address,
+            balance: U256::from(3),
+        }));
+
+        assert!(matches(filter(vec![address], vec![]), &selfdestruct));
+        assert!(matches(filter(vec![], vec![refund_address]), &selfdestruct));
+        assert!(!matches(filter(vec![refund_address], vec![]), &selfdestruct));
+        assert!(!matches(filter(vec![], vec![address]), &selfdestruct));
+    }
+
+    /// Returns a transfer of 60 wei between two accounts without a balance.
+    fn transfer() -> TransactionRequest {
+        TransactionRequest {
+            from: Some(Address::with_last_byte(1)),
+            to: Some(Address::with_last_byte(2)),
+            value: Some(U256::from(60)),
+            ..Default::default()
+        }
+    }
+
+    /// Overrides the balance of the sender of the transfers to 150 wei.
+    fn balance_override() -> StateOverride {
+        let account = AccountOverride { balance: Some(U256::from(150)), ..Default::default() };
+        StateOverride::from([(Address::with_last_byte(1), account)])
+    }
+
+    #[tokio::test]
+    async fn trace_call_many_carries_state_across_calls() {
+        let provider = MockEthProvider::default();
+        let trace_api = TraceApi::new(
+            provider.clone(),
+            build_test_eth_api(provider),
+            BlockingTaskGuard::new(1),
+        );
+        let calls = vec![
+            (transfer(), HashSet::from([TraceType::Trace])),
+            (transfer(), HashSet::from([TraceType::StateDiff])),
+            (transfer(), HashSet::from([TraceType::Trace])),
+            (transfer(), HashSet::from([TraceType::Trace])),
+        ];
+
+        let err = trace_api
+            .trace_call_many(
+                calls.clone(),
+                Some(B256::ZERO.into()),
+                EvmOverrides::new(Some(balance_override()), None),
+            )
+            .await
+            .unwrap_err();
+
+        // the overridden balance is only enough for two transfers, the third call fails and the
+        // last call isn't executed
+        let EthApiError::Other(err) = err else { panic!("{err:?}") };
+        let data: serde_json::Value =
+            serde_json::from_str(err.to_rpc_error().data().unwrap().get()).unwrap();
+        assert_eq!(data["index"], 2);
+        let results: Vec<TraceResults> = serde_json::from_value(data["results"].clone()).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(!results[0].trace.is_empty());
+        assert!(results[0].state_diff.is_none());
+        assert!(results[1].trace.is_empty());
+        assert!(results[1].state_diff.is_some());
+
+        // two transfers are covered by the overridden balance
+        let results = trace_api
+            .trace_call_many(
+                calls[..2].to_vec(),
+                Some(B256::ZERO.into()),
+                EvmOverrides::new(Some(balance_override()), None),
+            )
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        // without the override, the first transfer fails already
+        let err = trace_api
+            .trace_call_many(calls, Some(B256::ZERO.into()), EvmOverrides::default())
+            .await
+            .unwrap_err();
+        let EthApiError::Other(err) = err else { panic!("{err:?}") };
+        let data: serde_json::Value =
+            serde_json::from_str(err.to_rpc_error().data().unwrap().get()).unwrap();
+        assert_eq!(data["index"], 0);
+        assert_eq!(data["results"], serde_json::json!([]));
+    }
+}
@@ -1,15 +1,22 @@
+use crate::result::invalid_params_rpc_err;
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult as Result;
-use reth_primitives::{Address, U256, U64};
+use jsonrpsee::{core::RpcResult as Result, types::ErrorObject};
+use reth_primitives::{Address, U128, U256, U64};
 use reth_rpc_api::TxPoolApiServer;
 use reth_rpc_types::{
     txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
-    Transaction,
+    Transaction, TxpoolPage, TxpoolPriorityFeeFloor, TxpoolQuery, TxpoolSubPool,
 };
-use reth_transaction_pool::{AllPoolTransactions, PoolTransaction, TransactionPool};
-use std::collections::BTreeMap;
+use reth_transaction_pool::{
+    PoolTransaction, SubPool, TransactionPool, TransactionsCursor, TransactionsQuery,
+    ValidPoolTransaction,
+};
+use std::{collections::BTreeMap, sync::Arc};
 use tracing::trace;
 
+/// The default maximum number of transactions returned by a single `txpool` request.
+pub const DEFAULT_MAX_TXPOOL_ENTRIES: usize = 10_000;
+
 /// `txpool` API implementation.
 ///
 /// This type provides the functionality for handling `txpool` related requests.
@@ -17,12 +24,23 @@ use tracing::trace;
 pub struct TxPoolApi<Pool> {
     /// An interface to interact with the pool
     pool: Pool,
+    /// The maximum number of transactions returned by a single request.
+    max_entries: usize,
 }
 
 impl<Pool> TxPoolApi<Pool> {
     /// Creates a new instance of `TxpoolApi`.
     pub fn new(pool: Pool) -> Self {
-        TxPoolApi { pool }
+        TxPoolApi { pool, max_entries: DEFAULT_MAX_TXPOOL_ENTRIES }
+    }
+
+    /// Sets the maximum number of transactions returned by a single request.
+    ///
+    /// The unpaginated `txpool_content` and `txpool_inspect` requests fail if the pool has more
+    /// transactions, and the limit of the paginated ones is capped by it.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
     }
 }
 
@@ -30,32 +48,131 @@ impl<Pool> TxPoolApi<Pool>
 where
     Pool: TransactionPool + 'static,
 {
-    fn content(&self) -> TxpoolContent {
-        #[inline]
-        fn insert<T: PoolTransaction>(
-            tx: &T,
-            content: &mut BTreeMap<Address, BTreeMap<String, Transaction>>,
-        ) {
-            content.entry(tx.sender()).or_default().insert(
-                tx.nonce().to_string(),
-                reth_rpc_types_compat::transaction::from_recovered(tx.to_recovered_transaction()),
-            );
-        }
+    /// Walks the transactions of the pool that match the query in pages, passing every
+    /// transaction to `f` until the limit of the query is reached.
+    ///
+    /// Transactions of the blob sub-pool are skipped unless the query filters by sub-pool, since
+    /// they aren't part of the unpaginated responses either.
+    ///
+    /// Returns the cursor to continue after the last visited transaction, if there are more.
+    fn walk<F>(&self, mut query: TransactionsQuery, mut f: F) -> Option<TransactionsCursor>
+    where
+        F: FnMut(SubPool, &Arc<ValidPoolTransaction<Pool::Transaction>>),
+    {
+        let skip_blobs = query.sub_pool.is_none();
+        let mut remaining = query.limit;
+        loop {
+            query.limit = remaining;
+            let page = self.pool.transactions_page(query.clone());
+            for (sub_pool, tx) in &page.transactions {
+                if skip_blobs && *sub_pool == SubPool::Blob {
+                    continue
+                }
+                f(*sub_pool, tx);
+                remaining -= 1;
+            }
 
-        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+            // Skipped blob transactions leave room for more transactions in the next page
+            if remaining == 0 || page.next_cursor.is_none() {
+                return page.next_cursor
+            }
+            query.cursor = page.next_cursor;
+        }
+    }
 
+    fn content(&self, query: TransactionsQuery) -> (TxpoolContent, Option<TransactionsCursor>) {
         let mut content = TxpoolContent::default();
-        for pending in pending {
-            insert(&pending.transaction, &mut content.pending);
-        }
-        for queued in queued {
-            insert(&queued.transaction, &mut content.queued);
-        }
+        let next_cursor = self.walk(query, |sub_pool, tx| {
+            let entries = if sub_pool == SubPool::Pending {
+                &mut content.pending
+            } else {
+                &mut content.queued
+            };
+            insert_content(&tx.transaction, entries);
+        });
+        (content, next_cursor)
+    }
 
-        content
+    fn inspect(&self, query: TransactionsQuery) -> (TxpoolInspect, Option<TransactionsCursor>) {
+        let mut inspect = TxpoolInspect::default();
+        let next_cursor = self.walk(query, |sub_pool, tx| {
+            let entries = if sub_pool == SubPool::Pending {
+                &mut inspect.pending
+            } else {
+                &mut inspect.queued
+            };
+            insert_inspect(&tx.transaction, entries);
+        });
+        (inspect, next_cursor)
+    }
+
+    /// Converts the query of a paginated request, capping its limit.
+    fn page_query(&self, query: TxpoolQuery) -> Result<TransactionsQuery> {
+        let cursor = query
+            .cursor
+            .map(|cursor| cursor.parse::<TransactionsCursor>())
+            .transpose()
+            .map_err(|_| invalid_params_rpc_err("invalid cursor"))?;
+        let limit = query
+            .limit
+            .map_or(self.max_entries, |limit| limit.saturating_to::<usize>().min(self.max_entries));
+        let sub_pool = query.sub_pool.map(|sub_pool| match sub_pool {
+            TxpoolSubPool::Pending => SubPool::Pending,
+            TxpoolSubPool::BaseFee => SubPool::BaseFee,
+            TxpoolSubPool::Blob => SubPool::Blob,
+            TxpoolSubPool::Queued => SubPool::Queued,
+        });
+
+        Ok(TransactionsQuery {
+            sender: None,
+            sub_pool,
+            tx_type: query.tx_type.map(|tx_type| tx_type.saturating_to()),
+            min_tip: query.min_tip.map(|min_tip| min_tip.to()),
+            cursor,
+            limit,
+        })
+    }
+
+    /// Returns the error of an unpaginated request for a pool with more than the maximum number of
+    /// transactions, pointing to the paginated method.
+    fn max_entries_exceeded(&self, page_method: &str) -> ErrorObject<'static> {
+        invalid_params_rpc_err(format!(
+            "txpool exceeds max results {}, use {page_method} instead",
+            self.max_entries
+        ))
     }
 }
 
+#[inline]
+fn insert_content<T: PoolTransaction>(
+    tx: &T,
+    content: &mut BTreeMap<Address, BTreeMap<String, Transaction>>,
+) {
+    content.entry(tx.sender()).or_default().insert(
+        tx.nonce().to_string(),
+        reth_rpc_types_compat::transaction::from_recovered(tx.to_recovered_transaction()),
+    );
+}
+
+#[inline]
+fn insert_inspect<T: PoolTransaction>(
+    tx: &T,
+    inspect: &mut BTreeMap<Address, BTreeMap<String, TxpoolInspectSummary>>,
+) {
+    let entry = inspect.entry(tx.sender()).or_default();
+    let key = tx.nonce().to_string();
+    let tx = tx.to_recovered_transaction();
+    entry.insert(
+        key,
+        TxpoolInspectSummary {
+            to: tx.to(),
+            value: tx.value(),
+            gas: U256::from(tx.gas_limit()),
+            gas_price: U256::from(tx.transaction.max_fee_per_gas()),
+        },
+    );
+}
+
 #[async_trait]
 impl<Pool> TxPoolApiServer for TxPoolApi<Pool>
 where
@@ -78,42 +195,18 @@ where
     /// Returns a summary of all the transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     ///
+    /// Fails if the pool has more than the configured maximum number of entries.
+    ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_inspect) for more details
     ///
     /// Handler for `txpool_inspect`
     async fn txpool_inspect(&self) -> Result<TxpoolInspect> {
         trace!(target: "rpc::eth", "Serving txpool_inspect");
-
-        #[inline]
-        fn insert<T: PoolTransaction>(
-            tx: &T,
-            inspect: &mut BTreeMap<Address, BTreeMap<String, TxpoolInspectSummary>>,
-        ) {
-            let entry = inspect.entry(tx.sender()).or_default();
-            let key = tx.nonce().to_string();
-            let tx = tx.to_recovered_transaction();
-            entry.insert(
-                key,
-                TxpoolInspectSummary {
-                    to: tx.to(),
-                    value: tx.value(),
-                    gas: U256::from(tx.gas_limit()),
-                    gas_price: U256::from(tx.transaction.max_fee_per_gas()),
-                },
-            );
+        let query = TransactionsQuery { limit: self.max_entries, ..Default::default() };
+        match self.inspect(query) {
+            (inspect, None) => Ok(inspect),
+            (_, Some(_)) => Err(self.max_entries_exceeded("txpool_inspectPage")),
         }
-
-        let mut inspect = TxpoolInspect::default();
-        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
-
-        for pending in pending {
-            insert(&pending.transaction, &mut inspect.pending);
-        }
-        for queued in queued {
-            insert(&queued.transaction, &mut inspect.queued);
-        }
-
-        Ok(inspect)
     }
 
     /// Retrieves the transactions contained within the txpool, returning pending as well as queued
@@ -123,22 +216,94 @@ where
     /// Handler for `txpool_contentFrom`
     async fn txpool_content_from(&self, from: Address) -> Result<TxpoolContentFrom> {
         trace!(target: "rpc::eth", ?from, "Serving txpool_contentFrom");
-        Ok(self.content().remove_from(&from))
+        // Only the transactions of the sender are visited, which are bounded by the per-sender
+        // limit of the pool
+        let query =
+            TransactionsQuery { sender: Some(from), limit: usize::MAX, ..Default::default() };
+        Ok(self.content(query).0.remove_from(&from))
     }
 
     /// Returns the details of all transactions currently pending for inclusion in the next
     /// block(s), as well as the ones that are being scheduled for future execution only.
     ///
+    /// Fails if the pool has more than the configured maximum number of entries.
+    ///
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
-    /// Handler for `txpool_inspect`
+    /// Handler for `txpool_content`
     async fn txpool_content(&self) -> Result<TxpoolContent> {
-        trace!(target: "rpc::eth", "Serving txpool_inspect");
-        Ok(self.content())
+        trace!(target: "rpc::eth", "Serving txpool_content");
+        let query = TransactionsQuery { limit: self.max_entries, ..Default::default() };
+        match self.content(query) {
+            (content, None) => Ok(content),
+            (_, Some(_)) => Err(self.max_entries_exceeded("txpool_contentPage")),
+        }
+    }
+
+    /// Handler for `txpool_contentPage`
+    async fn txpool_content_page(&self, query: TxpoolQuery) -> Result<TxpoolPage<TxpoolContent>> {
+        trace!(target: "rpc::eth", ?query, "Serving txpool_contentPage");
+        let (content, next_cursor) = self.content(self.page_query(query)?);
+        Ok(TxpoolPage { content, next_cursor: next_cursor.map(|cursor| cursor.to_string()) })
+    }
+
+    /// Handler for `txpool_inspectPage`
+    async fn txpool_inspect_page(&self, query: TxpoolQuery) -> Result<TxpoolPage<TxpoolInspect>> {
+        trace!(target: "rpc::eth", ?query, "Serving txpool_inspectPage");
+        let (content, next_cursor) = self.inspect(self.page_query(query)?);
+        Ok(TxpoolPage { content, next_cursor: next_cursor.map(|cursor| cursor.to_string()) })
+    }
+
+    /// Handler for `txpool_priorityFeeFloor`
+    async fn txpool_priority_fee_floor(&self) -> Result<TxpoolPriorityFeeFloor> {
+        trace!(target: "rpc::eth", "Serving txpool_priorityFeeFloor");
+        let floor = self.pool.priority_fee_floor();
+        Ok(TxpoolPriorityFeeFloor {
+            floor: floor.effective().map(U128::from),
+            minimum: floor.minimum.map(U128::from),
+            dynamic: floor.dynamic.map(U128::from),
+            manual: floor.manual.map(U128::from),
+        })
     }
 }
 
 impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("TxpoolApi").finish_non_exhaustive()
+        f.debug_struct("TxpoolApi").field("max_entries", &self.max_entries).finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction},
+        TransactionOrigin,
+    };
+
+    #[tokio::test]
+    async fn unpaginated_requests_fail_above_max_entries() {
+        let pool = testing_pool();
+        for _ in 0..3 {
+            pool.add_transaction(TransactionOrigin::External, MockTransaction::eip1559())
+                .await
+                .unwrap();
+        }
+
+        let api = TxPoolApi::new(pool.clone()).with_max_entries(3);
+        let content = api.txpool_content().await.unwrap();
+        assert_eq!(content.pending.len() + content.queued.len(), 3);
+        let inspect = api.txpool_inspect().await.unwrap();
+        assert_eq!(inspect.pending.len() + inspect.queued.len(), 3);
+
+        let api = TxPoolApi::new(pool).with_max_entries(2);
+        let err = api.txpool_content().await.unwrap_err();
+        assert_eq!(err.message(), "txpool exceeds max results 2, use txpool_contentPage instead");
+        let err = api.txpool_inspect().await.unwrap_err();
+        assert_eq!(err.message(), "txpool exceeds max results 2, use txpool_inspectPage instead");
+
+        // the paginated requests return the first page instead
+        let page = api.txpool_content_page(TxpoolQuery::default()).await.unwrap();
+        assert_eq!(page.content.pending.len() + page.content.queued.len(), 2);
+        assert!(page.next_cursor.is_some());
     }
 }
@@ -0,0 +1,127 @@
+//! Recording of the state accessed during block execution, used by `debug_executionWitness`.
+
+use parking_lot::Mutex;
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{
+    trie::AccountProof, Account, Address, BlockNumber, Bytecode, StorageKey, StorageValue, B256,
+};
+use reth_provider::{AccountReader, BlockHashReader, StateProvider, StateRootProvider};
+use reth_trie::updates::TrieUpdates;
+use revm::db::BundleState;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The state that was read through a [RecordingStateProvider].
+#[derive(Debug, Default)]
+pub(crate) struct AccessedState {
+    /// The accessed accounts with their accessed storage slots.
+    pub(crate) accounts: BTreeMap<Address, BTreeSet<B256>>,
+    /// The hashes of the accessed bytecodes.
+    pub(crate) codes: BTreeSet<B256>,
+    /// The number of the oldest block whose hash was accessed.
+    pub(crate) oldest_block_hash: Option<BlockNumber>,
+}
+
+/// A [StateProvider] that records all accounts, storage slots, bytecodes and block hashes that are
+/// read from the wrapped state provider.
+pub(crate) struct RecordingStateProvider<'a> {
+    state: &'a dyn StateProvider,
+    accessed: &'a Mutex<AccessedState>,
+}
+
+impl<'a> RecordingStateProvider<'a> {
+    /// Creates a new [RecordingStateProvider] that records all reads of `state` into `accessed`.
+    pub(crate) fn new(state: &'a dyn StateProvider, accessed: &'a Mutex<AccessedState>) -> Self {
+        Self { state, accessed }
+    }
+}
+
+impl<'a> AccountReader for RecordingStateProvider<'a> {
+    fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
+        self.accessed.lock().accounts.entry(address).or_default();
+        self.state.basic_account(address)
+    }
+}
+
+impl<'a> BlockHashReader for RecordingStateProvider<'a> {
+    fn block_hash(&self, number: u64) -> ProviderResult<Option<B256>> {
+        let mut accessed = self.accessed.lock();
+        accessed.oldest_block_hash =
+            Some(accessed.oldest_block_hash.map_or(number, |oldest| oldest.min(number)));
+        drop(accessed);
+        self.state.block_hash(number)
+    }
+
+    fn canonical_hashes_range(
+        &self,
+        start: BlockNumber,
+        end: BlockNumber,
+    ) -> ProviderResult<Vec<B256>> {
+        self.state.canonical_hashes_range(start, end)
+    }
+}
+
+impl<'a> StateRootProvider for RecordingStateProvider<'a> {
+    fn state_root(&self, state: &BundleState) -> ProviderResult<B256> {
+        self.state.state_root(state)
+    }
+
+    fn state_root_with_updates(&self, state: &BundleState) -> ProviderResult<(B256, TrieUpdates)> {
+        self.state.state_root_with_updates(state)
+    }
+}
+
+impl<'a> StateProvider for RecordingStateProvider<'a> {
+    fn storage(
+        &self,
+        account: Address,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        self.accessed.lock().accounts.entry(account).or_default().insert(storage_key);
+        self.state.storage(account, storage_key)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> ProviderResult<Option<Bytecode>> {
+        self.accessed.lock().codes.insert(code_hash);
+        self.state.bytecode_by_hash(code_hash)
+    }
+
+    fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof> {
+        self.state.proof(address, keys)
+    }
+
+    fn proofs(&self, targets: &[(Address, Vec<B256>)]) -> ProviderResult<Vec<AccountProof>> {
+        self.state.proofs(targets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_provider::test_utils::NoopProvider;
+
+    #[test]
+    fn records_accessed_state() {
+        let state = NoopProvider::default();
+        let accessed = Mutex::new(AccessedState::default());
+        let provider = RecordingStateProvider::new(&state, &accessed);
+
+        let address = Address::with_last_byte(1);
+        let slot = B256::with_last_byte(2);
+        provider.basic_account(Address::with_last_byte(3)).unwrap();
+        provider.storage(address, slot).unwrap();
+        provider.bytecode_by_hash(B256::with_last_byte(4)).unwrap();
+        provider.block_hash(10).unwrap();
+        provider.block_hash(5).unwrap();
+
+        let accessed = accessed.into_inner();
+        assert_eq!(
+            accessed.accounts,
+            BTreeMap::from([
+                (address, BTreeSet::from([slot])),
+                (Address::with_last_byte(3), BTreeSet::new())
+            ])
+        );
+        assert_eq!(accessed.codes, BTreeSet::from([B256::with_last_byte(4)]));
+        assert_eq!(accessed.oldest_block_hash, Some(5));
+    }
+}
@@ -57,11 +57,36 @@ fn senders(c: &mut Criterion) {
     let db = setup::txs_testdata(DEFAULT_NUM_BLOCKS);
 
     for batch in [1000usize, 10_000, 100_000, 250_000] {
-        let stage = SenderRecoveryStage { commit_threshold: DEFAULT_NUM_BLOCKS };
+        let stage =
+            SenderRecoveryStage { commit_threshold: DEFAULT_NUM_BLOCKS, ..Default::default() };
         let label = format!("SendersRecovery-batch-{batch}");
 
         measure_stage(&mut group, &db, setup::stage_unwind, stage, 0..=DEFAULT_NUM_BLOCKS, label);
     }
+
+    // Writing the senders of final blocks to static files skips the database writes, which get
+    // copied to static files later on otherwise
+    for static_files in [false, true] {
+        let stage = SenderRecoveryStage {
+            commit_threshold: DEFAULT_NUM_BLOCKS,
+            static_files,
+            ..Default::default()
+        };
+        let label =
+            format!("SendersRecovery-{}", if static_files { "static-files" } else { "database" });
+
+        measure_stage(
+            &mut group,
+            &db,
+            setup::stage_unwind,
+            stage,
+            0..=DEFAULT_NUM_BLOCKS,
+            label.clone(),
+        );
+
+        let (db_size, static_files_size) = setup::senders_size(&db);
+        println!("{label}: {db_size} bytes of senders in the database, {static_files_size} bytes in static files");
+    }
 }
 
 fn transaction_lookup(c: &mut Criterion) {
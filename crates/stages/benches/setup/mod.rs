@@ -2,6 +2,8 @@
 use itertools::concat;
 use reth_db::{
     cursor::DbCursorRO,
+    database::Database,
+    table::Table,
     tables,
     test_utils::TempDatabase,
     transaction::{DbTx, DbTxMut},
@@ -10,11 +12,11 @@ use reth_db::{
 use reth_interfaces::test_utils::{
     generators,
     generators::{
-        random_block_range, random_changeset_range, random_contract_account_range,
+        random_block, random_block_range, random_changeset_range, random_contract_account_range,
         random_eoa_accounts,
     },
 };
-use reth_primitives::{fs, Account, Address, SealedBlock, B256, U256};
+use reth_primitives::{fs, Account, Address, SealedBlock, StaticFileSegment, U256};
 use reth_stages::{
     stages::{AccountHashingStage, StorageHashingStage},
     test_utils::{StorageKind, TestStageDB},
@@ -78,6 +80,30 @@ pub(crate) fn unwind_hashes<S: Clone + Stage<Arc<TempDatabase<DatabaseEnv>>>>(
     provider.commit().unwrap();
 }
 
+/// Returns the number of bytes the senders take up in the database and in static files.
+pub(crate) fn senders_size(db: &TestStageDB) -> (usize, u64) {
+    let db_size = db
+        .factory
+        .db_ref()
+        .view(|tx| {
+            let table_db = tx.inner.open_db(Some(tables::TransactionSenders::NAME)).unwrap();
+            let stats = tx.inner.db_stat(&table_db).unwrap();
+            stats.page_size() as usize *
+                (stats.leaf_pages() + stats.branch_pages() + stats.overflow_pages())
+        })
+        .unwrap();
+
+    let prefix = format!("static_file_{}_", StaticFileSegment::TransactionSenders.as_str());
+    let static_files_size = fs::read_dir(db.factory.static_file_provider().directory())
+        .unwrap()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .map(|entry| entry.metadata().unwrap().len())
+        .sum();
+
+    (db_size, static_files_size)
+}
+
 // Helper for generating testdata for the benchmarks.
 // Returns the path to the database file.
 pub(crate) fn txs_testdata(num_blocks: u64) -> TestStageDB {
@@ -112,7 +138,11 @@ pub(crate) fn txs_testdata(num_blocks: u64) -> TestStageDB {
         .into_iter()
         .collect();
 
-        let mut blocks = random_block_range(&mut rng, 0..=num_blocks, B256::ZERO, txs_range);
+        // The genesis block has no transactions, so that the senders of all blocks can be
+        // written to static files
+        let genesis = random_block(&mut rng, 0, None, Some(0), None);
+        let mut blocks = random_block_range(&mut rng, 1..=num_blocks, genesis.hash(), txs_range);
+        blocks.insert(0, genesis);
 
         let (transitions, start_state) = random_changeset_range(
             &mut rng,
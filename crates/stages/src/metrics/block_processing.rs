@@ -0,0 +1,308 @@
+use reth_metrics::{metrics::Histogram, Metrics};
+use reth_primitives::BlockNumber;
+use std::{collections::VecDeque, time::Duration};
+use tracing::{debug, warn};
+
+/// The number of the most recently processed blocks of which the slowest one is reported when a
+/// block exceeds the slow block threshold.
+const SLOW_BLOCK_WINDOW: usize = 64;
+
+/// The component that processed a block, or a batch of blocks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlockProcessingSource {
+    /// A block that was inserted into the blockchain tree and made canonical, i.e. a block that
+    /// was received by the consensus engine during live sync.
+    Engine,
+    /// A batch of blocks that was executed by the pipeline.
+    Pipeline,
+}
+
+impl BlockProcessingSource {
+    /// Returns the source as a string.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Engine => "engine",
+            Self::Pipeline => "pipeline",
+        }
+    }
+}
+
+/// The time spent in the phases of processing a block, or a batch of blocks.
+///
+/// Phases that are not measured by the [BlockProcessingSource] are zero, e.g. the pipeline only
+/// reports the execution and the write of the state.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockProcessingTimings {
+    /// Validation of the execution payload and its conversion into a block.
+    pub payload_validation: Duration,
+    /// Validation of the block against the consensus rules before its execution.
+    pub validation: Duration,
+    /// Recovery of the transaction senders.
+    pub sender_recovery: Duration,
+    /// Execution of the block, including the validation of the receipts.
+    pub execution: Duration,
+    /// Computation and validation of the state root.
+    pub state_root: Duration,
+    /// Retrieval of the trie updates, recomputing them if they were not cached.
+    pub trie_updates: Duration,
+    /// Write of the blocks and their state to the database.
+    pub commit: Duration,
+    /// The remaining work of making the blocks canonical, e.g. reverting the old canonical
+    /// chain from the database.
+    pub canonicalization: Duration,
+}
+
+impl BlockProcessingTimings {
+    /// Returns the total time of all phases.
+    pub fn total(&self) -> Duration {
+        self.payload_validation +
+            self.validation +
+            self.sender_recovery +
+            self.execution +
+            self.state_root +
+            self.trie_updates +
+            self.commit +
+            self.canonicalization
+    }
+}
+
+impl std::ops::Add for BlockProcessingTimings {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl std::ops::Div<u32> for BlockProcessingTimings {
+    type Output = Self;
+
+    /// Divides the time of each phase, e.g. to share the time of a batch between its blocks.
+    fn div(self, rhs: u32) -> Self {
+        Self {
+            payload_validation: self.payload_validation / rhs,
+            validation: self.validation / rhs,
+            sender_recovery: self.sender_recovery / rhs,
+            execution: self.execution / rhs,
+            state_root: self.state_root / rhs,
+            trie_updates: self.trie_updates / rhs,
+            commit: self.commit / rhs,
+            canonicalization: self.canonicalization / rhs,
+        }
+    }
+}
+
+impl std::ops::AddAssign for BlockProcessingTimings {
+    fn add_assign(&mut self, other: Self) {
+        self.payload_validation += other.payload_validation;
+        self.validation += other.validation;
+        self.sender_recovery += other.sender_recovery;
+        self.execution += other.execution;
+        self.state_root += other.state_root;
+        self.trie_updates += other.trie_updates;
+        self.commit += other.commit;
+        self.canonicalization += other.canonicalization;
+    }
+}
+
+/// A processed block, or batch of blocks, reported with [MetricEvent::BlockProcessed].
+///
+/// [MetricEvent::BlockProcessed]: crate::MetricEvent::BlockProcessed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ProcessedBlock {
+    source: BlockProcessingSource,
+    number: BlockNumber,
+    blocks: u64,
+    timings: BlockProcessingTimings,
+}
+
+/// Block processing metrics, labeled by the [BlockProcessingSource].
+#[derive(Metrics)]
+#[metrics(scope = "sync.block_processing")]
+pub(crate) struct BlockProcessingMetrics {
+    /// The time it took to validate the execution payload of a block
+    payload_validation_duration_seconds: Histogram,
+    /// The time it took to validate a block before its execution
+    validation_duration_seconds: Histogram,
+    /// The time it took to recover the transaction senders of a block
+    sender_recovery_duration_seconds: Histogram,
+    /// The time it took to execute a block
+    execution_duration_seconds: Histogram,
+    /// The time it took to compute the state root of a block
+    state_root_duration_seconds: Histogram,
+    /// The time it took to retrieve the trie updates of the committed blocks
+    trie_updates_duration_seconds: Histogram,
+    /// The time it took to write the committed blocks to the database
+    commit_duration_seconds: Histogram,
+    /// The time it took to make the committed blocks canonical, excluding their commit
+    canonicalization_duration_seconds: Histogram,
+    /// The total time it took to process a block
+    duration_seconds: Histogram,
+}
+
+impl BlockProcessingMetrics {
+    /// Records the non-zero phases of the timings.
+    fn record(&self, timings: &BlockProcessingTimings) {
+        for (histogram, duration) in [
+            (&self.payload_validation_duration_seconds, timings.payload_validation),
+            (&self.validation_duration_seconds, timings.validation),
+            (&self.sender_recovery_duration_seconds, timings.sender_recovery),
+            (&self.execution_duration_seconds, timings.execution),
+            (&self.state_root_duration_seconds, timings.state_root),
+            (&self.trie_updates_duration_seconds, timings.trie_updates),
+            (&self.commit_duration_seconds, timings.commit),
+            (&self.canonicalization_duration_seconds, timings.canonicalization),
+        ] {
+            if !duration.is_zero() {
+                histogram.record(duration);
+            }
+        }
+        self.duration_seconds.record(timings.total());
+    }
+}
+
+/// Records the processed blocks and reports the slowest of the recently processed blocks if a
+/// block exceeds the slow block threshold.
+#[derive(Debug, Default)]
+pub(crate) struct BlockProcessingTracker {
+    /// If set, the slowest recent block is reported when a block takes longer than this.
+    slow_block_threshold: Option<Duration>,
+    /// The most recently processed blocks, at most [SLOW_BLOCK_WINDOW].
+    recent: VecDeque<ProcessedBlock>,
+}
+
+impl BlockProcessingTracker {
+    /// Sets the slow block threshold.
+    pub(crate) fn set_slow_block_threshold(&mut self, threshold: Option<Duration>) {
+        self.slow_block_threshold = threshold;
+    }
+
+    /// Logs the timings of a processed block, and the slowest recent block if it exceeds the slow
+    /// block threshold.
+    ///
+    /// Returns the reported slowest recent block, if any.
+    pub(crate) fn on_block_processed(
+        &mut self,
+        metrics: &BlockProcessingMetrics,
+        source: BlockProcessingSource,
+        number: BlockNumber,
+        blocks: u64,
+        timings: BlockProcessingTimings,
+    ) -> Option<ProcessedBlock> {
+        metrics.record(&timings);
+
+        let block = ProcessedBlock { source, number, blocks, timings };
+        log_block(&block, "Block processed", false);
+
+        if self.recent.len() == SLOW_BLOCK_WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(block);
+
+        let threshold = self.slow_block_threshold?;
+        if timings.total() <= threshold {
+            return None
+        }
+
+        let slowest = *self
+            .recent
+            .iter()
+            .max_by_key(|block| block.timings.total())
+            .expect("block was just pushed");
+        warn!(
+            target: "sync::metrics",
+            ?threshold,
+            window = self.recent.len(),
+            "Block processing exceeded the slow block threshold"
+        );
+        log_block(&slowest, "Slowest recently processed block", true);
+        Some(slowest)
+    }
+}
+
+/// Logs the timings of a processed block, at warn level if the block is slow and at debug level
+/// otherwise.
+fn log_block(block: &ProcessedBlock, message: &'static str, slow: bool) {
+    let ProcessedBlock { source, number, blocks, timings } = block;
+    macro_rules! log_at {
+        ($level:ident) => {
+            $level!(
+                target: "sync::metrics",
+                source = source.as_str(),
+                number,
+                blocks,
+                total = ?timings.total(),
+                payload_validation = ?timings.payload_validation,
+                validation = ?timings.validation,
+                sender_recovery = ?timings.sender_recovery,
+                execution = ?timings.execution,
+                state_root = ?timings.state_root,
+                trie_updates = ?timings.trie_updates,
+                commit = ?timings.commit,
+                canonicalization = ?timings.canonicalization,
+                "{message}"
+            )
+        };
+    }
+    if slow {
+        log_at!(warn)
+    } else {
+        log_at!(debug)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timings(execution_millis: u64) -> BlockProcessingTimings {
+        BlockProcessingTimings {
+            execution: Duration::from_millis(execution_millis),
+            commit: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn timings_total_and_division() {
+        let total = timings(10) + timings(20);
+        assert_eq!(total.total(), Duration::from_millis(32));
+        assert_eq!(
+            total / 2,
+            BlockProcessingTimings {
+                execution: Duration::from_millis(15),
+                commit: Duration::from_millis(1),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn reports_slowest_recent_block_above_threshold() {
+        let metrics = BlockProcessingMetrics::default();
+        let mut tracker = BlockProcessingTracker::default();
+        let source = BlockProcessingSource::Engine;
+
+        // without a threshold nothing is reported
+        assert_eq!(tracker.on_block_processed(&metrics, source, 1, 1, timings(500)), None);
+
+        tracker.set_slow_block_threshold(Some(Duration::from_millis(100)));
+        assert_eq!(tracker.on_block_processed(&metrics, source, 2, 1, timings(50)), None);
+
+        // the slowest block of the window is reported, not the one that exceeded the threshold
+        let slowest = tracker.on_block_processed(&metrics, source, 3, 1, timings(200)).unwrap();
+        assert_eq!(slowest.number, 1);
+        assert_eq!(slowest.timings, timings(500));
+
+        // the window only keeps the most recent blocks
+        for number in 4..4 + SLOW_BLOCK_WINDOW as u64 {
+            tracker.on_block_processed(&metrics, source, number, 1, timings(10));
+        }
+        assert_eq!(tracker.recent.len(), SLOW_BLOCK_WINDOW);
+        let number = 4 + SLOW_BLOCK_WINDOW as u64;
+        let slowest =
+            tracker.on_block_processed(&metrics, source, number, 1, timings(150)).unwrap();
+        assert_eq!(slowest.number, number);
+    }
+}
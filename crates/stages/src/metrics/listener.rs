@@ -1,13 +1,17 @@
-use crate::metrics::SyncMetrics;
+use crate::metrics::{
+    block_processing::BlockProcessingTracker, BlockProcessingSource, BlockProcessingTimings,
+    SyncMetrics,
+};
 use reth_primitives::{
     constants::MGAS_TO_GAS,
-    stage::{StageCheckpoint, StageId},
+    stage::{StageCheckpoint, StageId, StageProgress},
     BlockNumber,
 };
 use std::{
     future::Future,
     pin::Pin,
     task::{ready, Context, Poll},
+    time::Duration,
 };
 use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tracing::trace;
@@ -33,11 +37,32 @@ pub enum MetricEvent {
         /// If specified, `entities_total` metric is updated.
         max_block_number: Option<BlockNumber>,
     },
+    /// Stage, or a component reported like a stage, committed a batch.
+    StageProgress {
+        /// Progress of the stage.
+        progress: StageProgress,
+    },
     /// Execution stage processed some amount of gas.
     ExecutionStageGas {
         /// Gas processed.
         gas: u64,
     },
+    /// Execution stage verified the receipts of some blocks against their headers.
+    ExecutionStageReceiptsVerified {
+        /// The number of blocks whose receipts were verified.
+        blocks: u64,
+    },
+    /// A block, or a batch of blocks, was processed.
+    BlockProcessed {
+        /// The component that processed the blocks.
+        source: BlockProcessingSource,
+        /// The number of the highest processed block.
+        number: BlockNumber,
+        /// The number of processed blocks.
+        blocks: u64,
+        /// The time spent in the phases of processing the blocks.
+        timings: BlockProcessingTimings,
+    },
 }
 
 /// Metrics routine that listens to new metric events on the `events_rx` receiver.
@@ -46,12 +71,24 @@ pub enum MetricEvent {
 pub struct MetricsListener {
     events_rx: UnboundedReceiver<MetricEvent>,
     pub(crate) sync_metrics: SyncMetrics,
+    block_processing: BlockProcessingTracker,
 }
 
 impl MetricsListener {
     /// Creates a new [MetricsListener] with the provided receiver of [MetricEvent].
     pub fn new(events_rx: UnboundedReceiver<MetricEvent>) -> Self {
-        Self { events_rx, sync_metrics: SyncMetrics::default() }
+        Self {
+            events_rx,
+            sync_metrics: SyncMetrics::default(),
+            block_processing: BlockProcessingTracker::default(),
+        }
+    }
+
+    /// Sets the threshold above which the processing of a block is reported as slow, together
+    /// with the slowest of the recently processed blocks.
+    pub fn with_slow_block_threshold(mut self, threshold: Option<Duration>) -> Self {
+        self.block_processing.set_slow_block_threshold(threshold);
+        self
     }
 
     fn handle_event(&mut self, event: MetricEvent) {
@@ -85,9 +122,24 @@ impl MetricsListener {
                     stage_metrics.entities_total.set(total as f64);
                 }
             }
+            MetricEvent::StageProgress { progress } => {
+                let progress_metrics = self.sync_metrics.get_progress_metrics(progress.stage);
+
+                progress_metrics.batch_duration_seconds.set(progress.elapsed.as_secs_f64());
+                if let Some(eta) = progress.eta {
+                    progress_metrics.eta_seconds.set(eta.as_secs_f64());
+                }
+            }
             MetricEvent::ExecutionStageGas { gas } => {
                 self.sync_metrics.execution_stage.mgas_processed_total.increment(gas / MGAS_TO_GAS)
             }
+            MetricEvent::ExecutionStageReceiptsVerified { blocks } => {
+                self.sync_metrics.execution_stage.receipts_verified_blocks_total.increment(blocks)
+            }
+            MetricEvent::BlockProcessed { source, number, blocks, timings } => {
+                let metrics = self.sync_metrics.get_block_processing_metrics(source);
+                self.block_processing.on_block_processed(metrics, source, number, blocks, timings);
+            }
         }
     }
 }
@@ -1,5 +1,7 @@
+mod block_processing;
 mod listener;
 mod sync_metrics;
 
+pub use block_processing::{BlockProcessingSource, BlockProcessingTimings};
 pub use listener::{MetricEvent, MetricEventsSender, MetricsListener};
 use sync_metrics::*;
@@ -1,14 +1,17 @@
+use super::block_processing::{BlockProcessingMetrics, BlockProcessingSource};
 use reth_metrics::{
     metrics::{Counter, Gauge},
     Metrics,
 };
-use reth_primitives::stage::StageId;
+use reth_primitives::stage::{StageId, SyncStage};
 use std::collections::HashMap;
 
 #[derive(Debug, Default)]
 pub(crate) struct SyncMetrics {
     pub(crate) stages: HashMap<StageId, StageMetrics>,
+    pub(crate) progress: HashMap<SyncStage, StageProgressMetrics>,
     pub(crate) execution_stage: ExecutionStageMetrics,
+    pub(crate) block_processing: HashMap<BlockProcessingSource, BlockProcessingMetrics>,
 }
 
 impl SyncMetrics {
@@ -18,6 +21,25 @@ impl SyncMetrics {
             .entry(stage_id)
             .or_insert_with(|| StageMetrics::new_with_labels(&[("stage", stage_id.to_string())]))
     }
+
+    /// Returns existing or initializes a new instance of [StageProgressMetrics] for the provided
+    /// [SyncStage].
+    pub(crate) fn get_progress_metrics(&mut self, stage: SyncStage) -> &mut StageProgressMetrics {
+        self.progress.entry(stage).or_insert_with(|| {
+            StageProgressMetrics::new_with_labels(&[("stage", stage.to_string())])
+        })
+    }
+
+    /// Returns existing or initializes a new instance of [BlockProcessingMetrics] for the provided
+    /// [BlockProcessingSource].
+    pub(crate) fn get_block_processing_metrics(
+        &mut self,
+        source: BlockProcessingSource,
+    ) -> &mut BlockProcessingMetrics {
+        self.block_processing.entry(source).or_insert_with(|| {
+            BlockProcessingMetrics::new_with_labels(&[("source", source.as_str())])
+        })
+    }
 }
 
 #[derive(Metrics)]
@@ -31,10 +53,23 @@ pub(crate) struct StageMetrics {
     pub(crate) entities_total: Gauge,
 }
 
+/// Progress metrics of a stage, or of a component reported like a stage.
+#[derive(Metrics)]
+#[metrics(scope = "sync")]
+pub(crate) struct StageProgressMetrics {
+    /// The duration of the last committed batch of a stage, in seconds.
+    pub(crate) batch_duration_seconds: Gauge,
+    /// The estimated time until a stage processed all entities, in seconds, if known.
+    pub(crate) eta_seconds: Gauge,
+}
+
 /// Execution stage metrics.
 #[derive(Metrics)]
 #[metrics(scope = "sync.execution")]
 pub(crate) struct ExecutionStageMetrics {
     /// The total amount of gas processed (in millions)
     pub(crate) mgas_processed_total: Counter,
+    /// The total number of blocks whose receipts root and logs bloom were verified against their
+    /// headers
+    pub(crate) receipts_verified_blocks_total: Counter,
 }
@@ -82,7 +82,9 @@ where
             tip_tx,
             listeners: Default::default(),
             progress: Default::default(),
+            stage_progress: Default::default(),
             metrics_tx,
+            shutdown: None,
         }
     }
 }
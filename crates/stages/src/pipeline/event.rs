@@ -1,6 +1,6 @@
 use crate::stage::{ExecOutput, UnwindInput, UnwindOutput};
 use reth_primitives::{
-    stage::{StageCheckpoint, StageId},
+    stage::{StageCheckpoint, StageId, StageProgress},
     BlockNumber,
 };
 use std::fmt::{Display, Formatter};
@@ -45,6 +45,12 @@ pub enum PipelineEvent {
         /// The result of executing the stage.
         result: ExecOutput,
     },
+    /// Emitted when a stage has committed the result of running a single time.
+    Progress {
+        /// The progress of the stage, including the elapsed time of the run and the estimated
+        /// time until the stage reaches its target.
+        progress: StageProgress,
+    },
     /// Emitted when a stage is about to be unwound.
     Unwind {
         /// The stage that is about to be unwound.
@@ -14,8 +14,9 @@ use reth_provider::{
     providers::StaticFileWriter, ProviderFactory, StageCheckpointReader, StageCheckpointWriter,
 };
 use reth_static_file::StaticFileProducer;
+use reth_tasks::shutdown::GracefulShutdown;
 use reth_tokio_util::EventListeners;
-use std::pin::Pin;
+use std::{collections::HashMap, pin::Pin, time::Instant};
 use tokio::sync::watch;
 use tokio_stream::wrappers::UnboundedReceiverStream;
 use tracing::*;
@@ -76,9 +77,13 @@ pub struct Pipeline<DB: Database> {
     listeners: EventListeners<PipelineEvent>,
     /// Keeps track of the progress of the pipeline.
     progress: PipelineProgress,
+    /// Keeps track of the throughput of each stage.
+    stage_progress: HashMap<StageId, StageProgressTracker>,
     /// A receiver for the current chain tip to sync to.
     tip_tx: Option<watch::Sender<B256>>,
     metrics_tx: Option<MetricEventsSender>,
+    /// Stops the pipeline at the next stage boundary once it's fired.
+    shutdown: Option<GracefulShutdown>,
 }
 
 impl<DB> Pipeline<DB>
@@ -104,6 +109,20 @@ where
         });
     }
 
+    /// Sets the graceful shutdown signal of the pipeline.
+    ///
+    /// Once the signal is fired, the pipeline stops before it executes the next batch of a stage,
+    /// so the progress of all stages is committed. The signal is only released once the pipeline is
+    /// dropped.
+    pub fn set_graceful_shutdown(&mut self, shutdown: GracefulShutdown) {
+        self.shutdown = Some(shutdown);
+    }
+
+    /// Returns true if the graceful shutdown signal of the pipeline was fired.
+    fn is_shutting_down(&self) -> bool {
+        self.shutdown.as_ref().map_or(false, GracefulShutdown::is_fired)
+    }
+
     /// Listen for events on the pipeline.
     pub fn events(&mut self) -> UnboundedReceiverStream<PipelineEvent> {
         self.listeners.new_listener()
@@ -151,6 +170,11 @@ where
         loop {
             let next_action = self.run_loop().await?;
 
+            if self.is_shutting_down() {
+                trace!(target: "sync::pipeline", "Pipeline is shutting down.");
+                return Ok(())
+            }
+
             // Terminate the loop early if it's reached the maximum user
             // configured block.
             if next_action.should_continue() &&
@@ -187,6 +211,11 @@ where
 
         let mut previous_stage = None;
         for stage_index in 0..self.stages.len() {
+            if self.is_shutting_down() {
+                debug!(target: "sync::pipeline", "Stopping pipeline at stage boundary due to shutdown");
+                break
+            }
+
             let stage = &self.stages[stage_index];
             let stage_id = stage.id();
 
@@ -246,6 +275,8 @@ where
             transactions: provider
                 .get_stage_checkpoint(StageId::Bodies)?
                 .map(|checkpoint| checkpoint.block_number),
+            // Transaction senders are written to static files by the sender recovery stage
+            transaction_senders: None,
         })?;
         static_file_producer.run(targets)?;
 
@@ -350,6 +381,13 @@ where
         loop {
             let prev_checkpoint = self.provider_factory.get_stage_checkpoint(stage_id)?;
 
+            // all previous batches of the stage are committed, so the stage can be stopped here
+            if self.shutdown.as_ref().map_or(false, GracefulShutdown::is_fired) {
+                return Ok(ControlFlow::NoProgress {
+                    block_number: prev_checkpoint.map(|progress| progress.block_number),
+                })
+            }
+
             let stage_reached_max_block = prev_checkpoint
                 .zip(self.max_block)
                 .map_or(false, |(prev_progress, target)| prev_progress.block_number >= target);
@@ -400,6 +438,7 @@ where
             });
 
             let provider_rw = self.provider_factory.provider_rw()?;
+            let started_at = Instant::now();
             match stage.execute(&provider_rw, exec_input) {
                 Ok(out @ ExecOutput { checkpoint, done }) => {
                     made_progress |=
@@ -426,6 +465,17 @@ where
                     self.provider_factory.static_file_provider().commit()?;
                     provider_rw.commit()?;
 
+                    let progress = self.stage_progress.entry(stage_id).or_default().update(
+                        stage_id,
+                        checkpoint,
+                        target,
+                        started_at.elapsed(),
+                    );
+                    if let Some(metrics_tx) = &mut self.metrics_tx {
+                        let _ = metrics_tx.send(MetricEvent::StageProgress { progress });
+                    }
+                    self.listeners.notify(PipelineEvent::Progress { progress });
+
                     if done {
                         let block_number = checkpoint.block_number;
                         return Ok(if made_progress {
@@ -556,10 +606,19 @@ mod tests {
         provider::ProviderError,
         test_utils::{generators, generators::random_header},
     };
-    use reth_primitives::PruneModes;
+    use reth_primitives::{stage::EntitiesCheckpoint, PruneModes};
     use reth_provider::test_utils::create_test_provider_factory;
+    use std::time::Duration;
     use tokio_stream::StreamExt;
 
+    const SECOND: Duration = Duration::from_secs(1);
+
+    /// Collects the events of the pipeline, except for the progress events, since their elapsed
+    /// time isn't deterministic.
+    async fn collect_events(events: UnboundedReceiverStream<PipelineEvent>) -> Vec<PipelineEvent> {
+        events.filter(|event| !matches!(event, PipelineEvent::Progress { .. })).collect().await
+    }
+
     #[test]
     fn record_progress_calculates_outliers() {
         let mut progress = PipelineProgress::default();
@@ -587,6 +646,34 @@ mod tests {
         assert_eq!(progress.next_ctrl(), ControlFlow::Continue { block_number: 1 });
     }
 
+    #[test]
+    fn stage_progress_eta() {
+        let mut tracker = StageProgressTracker::default();
+        let stage_id = StageId::Other("A");
+
+        // The throughput isn't known until a batch was measured
+        let progress = tracker.update(stage_id, StageCheckpoint::new(10), Some(100), SECOND);
+        assert_eq!(progress.entities, Some(EntitiesCheckpoint { processed: 10, total: 100 }));
+        assert_eq!(progress.eta, None);
+
+        let progress = tracker.update(stage_id, StageCheckpoint::new(20), Some(100), SECOND);
+        assert_eq!(progress.eta, Some(8 * SECOND));
+
+        // Only the most recent batches are averaged
+        for block in 3..=THROUGHPUT_WINDOW as u64 + 2 {
+            tracker.update(stage_id, StageCheckpoint::new(block * 10), Some(200), 4 * SECOND);
+        }
+        let progress = tracker.update(stage_id, StageCheckpoint::new(130), Some(200), 4 * SECOND);
+        assert_eq!(progress.eta, Some(28 * SECOND));
+
+        // Unwinding resets the throughput
+        let progress = tracker.update(stage_id, StageCheckpoint::new(50), Some(200), SECOND);
+        assert_eq!(progress.eta, None);
+
+        let progress = tracker.update(stage_id, StageCheckpoint::new(200), Some(200), SECOND);
+        assert_eq!(progress.eta, Some(Duration::ZERO));
+    }
+
     /// Runs a simple pipeline.
     #[tokio::test]
     async fn run_pipeline() {
@@ -619,7 +706,7 @@ mod tests {
 
         // Check that the stages were run in order
         assert_eq!(
-            events.collect::<Vec<PipelineEvent>>().await,
+            collect_events(events).await,
             vec![
                 PipelineEvent::Prepare {
                     pipeline_stages_progress: PipelineStagesProgress { current: 1, total: 2 },
@@ -702,7 +789,7 @@ mod tests {
 
         // Check that the stages were unwound in reverse order
         assert_eq!(
-            events.collect::<Vec<PipelineEvent>>().await,
+            collect_events(events).await,
             vec![
                 // Executing
                 PipelineEvent::Prepare {
@@ -834,7 +921,7 @@ mod tests {
 
         // Check that the stages were unwound in reverse order
         assert_eq!(
-            events.collect::<Vec<PipelineEvent>>().await,
+            collect_events(events).await,
             vec![
                 // Executing
                 PipelineEvent::Prepare {
@@ -946,7 +1033,7 @@ mod tests {
 
         // Check that the stages were unwound in reverse order
         assert_eq!(
-            events.collect::<Vec<PipelineEvent>>().await,
+            collect_events(events).await,
             vec![
                 PipelineEvent::Prepare {
                     pipeline_stages_progress: PipelineStagesProgress { current: 1, total: 2 },
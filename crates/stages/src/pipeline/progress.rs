@@ -1,6 +1,13 @@
 use super::ctrl::ControlFlow;
 use crate::util::opt;
-use reth_primitives::BlockNumber;
+use reth_primitives::{
+    stage::{EntitiesCheckpoint, StageCheckpoint, StageId, StageProgress, SyncStage},
+    BlockNumber,
+};
+use std::{collections::VecDeque, time::Duration};
+
+/// The number of most recent batches of a stage [StageProgressTracker] averages the throughput of.
+pub(crate) const THROUGHPUT_WINDOW: usize = 10;
 
 #[derive(Debug, Default)]
 pub(crate) struct PipelineProgress {
@@ -27,3 +34,76 @@ impl PipelineProgress {
         }
     }
 }
+
+/// Tracks the throughput of the most recent batches of a stage, to estimate the time until the
+/// stage processed all entities.
+///
+/// The moving average reacts to the throughput changing over the course of the sync, e.g. the
+/// execution stage slowing down as the blocks get fuller near the tip, unlike an overall average.
+#[derive(Debug, Default)]
+pub(crate) struct StageProgressTracker {
+    /// The number of processed entities as of the last committed batch.
+    processed: Option<u64>,
+    /// The number of entities processed by and the duration of the most recent batches.
+    batches: VecDeque<(u64, Duration)>,
+}
+
+impl StageProgressTracker {
+    /// Records a committed batch of the stage, returning the progress of the stage.
+    ///
+    /// Stages that don't report entities are measured in blocks up to the target.
+    pub(crate) fn update(
+        &mut self,
+        stage_id: StageId,
+        checkpoint: StageCheckpoint,
+        target: Option<BlockNumber>,
+        elapsed: Duration,
+    ) -> StageProgress {
+        let entities = checkpoint.entities().or_else(|| {
+            target.map(|total| EntitiesCheckpoint { processed: checkpoint.block_number, total })
+        });
+        let processed = entities.map_or(checkpoint.block_number, |entities| entities.processed);
+
+        match self.processed {
+            Some(previous) if processed >= previous => {
+                self.batches.push_back((processed - previous, elapsed));
+                if self.batches.len() > THROUGHPUT_WINDOW {
+                    self.batches.pop_front();
+                }
+            }
+            // The stage was unwound, so the previous throughput doesn't apply anymore
+            _ => self.batches.clear(),
+        }
+        self.processed = Some(processed);
+
+        StageProgress {
+            stage: SyncStage::Stage(stage_id),
+            checkpoint: checkpoint.block_number,
+            entities,
+            elapsed,
+            eta: entities.and_then(|entities| self.eta(entities)),
+        }
+    }
+
+    /// Estimates the time until all entities are processed from the throughput of the most recent
+    /// batches.
+    fn eta(&self, entities: EntitiesCheckpoint) -> Option<Duration> {
+        let remaining = entities.total.saturating_sub(entities.processed);
+        if remaining == 0 {
+            return Some(Duration::ZERO)
+        }
+
+        let (processed, elapsed) = self.batches.iter().fold(
+            (0, Duration::ZERO),
+            |(processed, elapsed), (batch_processed, batch_elapsed)| {
+                (processed + batch_processed, elapsed + *batch_elapsed)
+            },
+        );
+        if processed == 0 {
+            return None
+        }
+
+        Duration::try_from_secs_f64(elapsed.as_secs_f64() * remaining as f64 / processed as f64)
+            .ok()
+    }
+}
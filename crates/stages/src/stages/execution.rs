@@ -1,7 +1,10 @@
+use super::index_call_traces::BlockCallAddresses;
 use crate::{
-    stages::MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD, BlockErrorKind, ExecInput, ExecOutput,
-    MetricEvent, MetricEventsSender, Stage, StageError, UnwindInput, UnwindOutput,
+    stages::MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD, BlockErrorKind, BlockProcessingSource,
+    BlockProcessingTimings, ExecInput, ExecOutput, MetricEvent, MetricEventsSender, Stage,
+    StageError, UnwindInput, UnwindOutput,
 };
+use futures_util::StreamExt;
 use num_traits::Zero;
 use reth_db::{
     cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
@@ -11,11 +14,13 @@ use reth_db::{
     tables,
     transaction::{DbTx, DbTxMut},
 };
+use reth_interfaces::executor::{BlockExecutionError, BlockValidationError};
 use reth_primitives::{
     stage::{
         CheckpointBlockRange, EntitiesCheckpoint, ExecutionCheckpoint, StageCheckpoint, StageId,
     },
-    BlockNumber, Header, PruneModes, StaticFileSegment, U256,
+    static_file::find_fixed_range,
+    BlockNumber, BlockWithSenders, Header, PruneModes, Receipt, StaticFileSegment, U256,
 };
 use reth_provider::{
     providers::{StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter},
@@ -25,10 +30,16 @@ use reth_provider::{
 use std::{
     cmp::Ordering,
     ops::RangeInclusive,
+    task::{Context, Poll},
     time::{Duration, Instant},
 };
+use tokio::sync::watch;
+use tokio_stream::wrappers::WatchStream;
 use tracing::*;
 
+/// The number of blocks that are read from the database at once for execution.
+const FETCH_BLOCKS_BATCH_SIZE: u64 = 100;
+
 /// The execution stage executes all transactions and
 /// update history indexes.
 ///
@@ -51,6 +62,8 @@ use tracing::*;
 /// - [tables::Bytecodes]
 /// - [tables::AccountChangeSets]
 /// - [tables::StorageChangeSets]
+/// - [tables::BlockCallFromAddresses] and [tables::BlockCallToAddresses] if the call addresses are
+///   [recorded](ExecutionStage::with_call_addresses)
 ///
 /// For unwinds we are accessing:
 /// - [tables::BlockBodyIndices] get tx index to know what needs to be unwinded
@@ -73,6 +86,17 @@ pub struct ExecutionStage<EF: ExecutorFactory> {
     external_clean_threshold: u64,
     /// Pruning configuration.
     prune_modes: PruneModes,
+    /// The block from which the receipts of the executed blocks are verified against their
+    /// headers. [None] if they aren't verified.
+    verify_receipts_from: Option<BlockNumber>,
+    /// Whether the ExEx manager can receive the notifications of new blocks. The stage waits
+    /// before executing blocks while it can't.
+    exex_manager_ready: Option<WatchStream<bool>>,
+    /// The last readiness of the ExEx manager.
+    is_exex_manager_ready: bool,
+    /// Whether the addresses of the call traces of the executed blocks are recorded for the
+    /// [`IndexCallTracesStage`](super::IndexCallTracesStage).
+    record_call_addresses: bool,
 }
 
 impl<EF: ExecutorFactory> ExecutionStage<EF> {
@@ -89,6 +113,10 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
             executor_factory,
             thresholds,
             prune_modes,
+            verify_receipts_from: Some(0),
+            exex_manager_ready: None,
+            is_exex_manager_ready: true,
+            record_call_addresses: false,
         }
     }
 
@@ -104,12 +132,40 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
         )
     }
 
+    /// Sets the block from which the receipts root and the logs bloom of the executed blocks are
+    /// verified against their headers. The receipts aren't verified if [None], which is meant for
+    /// chains with non-standard receipts.
+    ///
+    /// The receipts of all blocks are verified by default.
+    pub fn with_receipts_verification(mut self, verify_receipts_from: Option<BlockNumber>) -> Self {
+        self.verify_receipts_from = verify_receipts_from;
+        self
+    }
+
     /// Set the metric events sender.
     pub fn with_metrics_tx(mut self, metrics_tx: MetricEventsSender) -> Self {
         self.metrics_tx = Some(metrics_tx);
         self
     }
 
+    /// Sets the readiness of the ExEx manager to receive new notifications.
+    ///
+    /// The stage doesn't execute blocks while the manager isn't ready, i.e. while all ExExes are
+    /// too far behind, so that the node doesn't advance further ahead of them.
+    pub fn with_exex_manager_ready(mut self, ready: watch::Receiver<bool>) -> Self {
+        self.exex_manager_ready = Some(WatchStream::new(ready));
+        self
+    }
+
+    /// Sets whether the `from` and `to` addresses of the call traces of the executed blocks are
+    /// recorded in [tables::BlockCallFromAddresses] and [tables::BlockCallToAddresses], from
+    /// which the [`IndexCallTracesStage`](super::IndexCallTracesStage) builds its index without
+    /// executing the blocks again.
+    pub fn with_call_addresses(mut self, record_call_addresses: bool) -> Self {
+        self.record_call_addresses = record_call_addresses;
+        self
+    }
+
     /// Execute the stage.
     pub fn execute_inner<DB: Database>(
         &mut self,
@@ -141,6 +197,10 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
         ));
         executor.set_prune_modes(prune_modes);
         executor.set_tip(max_block);
+        if self.record_call_addresses {
+            executor.record_call_addresses();
+        }
+        let mut call_addresses = BlockCallAddresses::default();
 
         // Progress tracking
         let mut stage_progress = start_block;
@@ -153,8 +213,11 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
 
         // Execute block range
         let mut cumulative_gas = 0;
+        let mut verified_blocks = 0;
+        let mut batch_trigger = None;
         let batch_start = Instant::now();
 
+        let mut blocks = Vec::<BlockWithSenders>::new().into_iter();
         for block_number in start_block..=max_block {
             // Fetch the block
             let fetch_block_start = Instant::now();
@@ -163,9 +226,19 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
                 .header_td_by_number(block_number)?
                 .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
 
-            // we need the block's transactions but we don't need the transaction hashes
-            let block = provider
-                .block_with_senders(block_number.into(), TransactionVariant::NoHash)?
+            // the blocks are read in batches, we need their transactions but we don't need the
+            // transaction hashes
+            if blocks.as_slice().is_empty() {
+                blocks = provider
+                    .blocks_with_senders_range(
+                        block_number..=fetch_batch_end(block_number, max_block),
+                        TransactionVariant::NoHash,
+                    )?
+                    .into_iter();
+            }
+            let block = blocks
+                .next()
+                .filter(|block| block.number == block_number)
                 .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
 
             fetch_block_duration += fetch_block_start.elapsed();
@@ -177,10 +250,39 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
 
             // Execute the block
             let execute_start = Instant::now();
-            executor.execute_and_verify_receipt(&block, td).map_err(|error| StageError::Block {
+            let block_error = |error| StageError::Block {
                 block: Box::new(block.header.clone().seal_slow()),
                 error: BlockErrorKind::Execution(error),
-            })?;
+            };
+            let receipts = executor.execute_block(&block, td).map_err(block_error)?;
+            if self.verify_receipts_from.is_some_and(|from| block_number >= from) {
+                match executor.verify_receipts(&block, &receipts) {
+                    Ok(verified) => verified_blocks += u64::from(verified),
+                    Err(BlockExecutionError::Validation(error)) => {
+                        let receipt = describe_receipts_mismatch(&block, &receipts);
+                        error!(
+                            target: "sync::stages::execution",
+                            number = block_number,
+                            %error,
+                            %receipt,
+                            "Receipts don't match the header"
+                        );
+                        return Err(block_error(
+                            BlockValidationError::ReceiptsMismatch {
+                                error: Box::new(error),
+                                receipt,
+                            }
+                            .into(),
+                        ))
+                    }
+                    Err(error) => return Err(block_error(error)),
+                }
+            }
+            // the receipts are pruned once they're saved, so they're verified before
+            executor.save_receipts(receipts).map_err(block_error)?;
+            if let Some((from, to)) = executor.take_call_addresses() {
+                call_addresses.push(&block, from, to);
+            }
             execution_duration += execute_start.elapsed();
 
             // Gas metrics
@@ -195,15 +297,25 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
 
             // Check if we should commit now
             let bundle_size_hint = executor.size_hint().unwrap_or_default() as u64;
-            if self.thresholds.is_end_of_batch(
+            batch_trigger = self.thresholds.end_of_batch_trigger(
                 block_number - start_block,
                 bundle_size_hint,
                 cumulative_gas,
                 batch_start.elapsed(),
-            ) {
+            );
+            if batch_trigger.is_some() {
                 break
             }
         }
+        debug!(
+            target: "sync::stages::execution",
+            start = start_block,
+            end = stage_progress,
+            gas = cumulative_gas,
+            elapsed = ?batch_start.elapsed(),
+            trigger = ?batch_trigger,
+            "Finished execution batch"
+        );
         let time = Instant::now();
         let state = executor.take_output_state();
         let write_preparation_duration = time.elapsed();
@@ -215,6 +327,7 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
             static_file_producer,
             OriginalValuesKnown::Yes,
         )?;
+        call_addresses.write(provider.tx_ref())?;
         let db_write_duration = time.elapsed();
         debug!(
             target: "sync::stages::execution",
@@ -224,6 +337,22 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
             write = ?db_write_duration,
             "Execution time"
         );
+        if let Some(metrics_tx) = &mut self.metrics_tx {
+            let _ = metrics_tx.send(MetricEvent::BlockProcessed {
+                source: BlockProcessingSource::Pipeline,
+                number: stage_progress,
+                blocks: stage_progress - start_block + 1,
+                timings: BlockProcessingTimings {
+                    execution: execution_duration,
+                    commit: write_preparation_duration + db_write_duration,
+                    ..Default::default()
+                },
+            });
+            if verified_blocks > 0 {
+                let _ = metrics_tx
+                    .send(MetricEvent::ExecutionStageReceiptsVerified { blocks: verified_blocks });
+            }
+        }
 
         let done = stage_progress == max_block;
         Ok(ExecOutput {
@@ -263,6 +392,46 @@ impl<EF: ExecutorFactory> ExecutionStage<EF> {
     }
 }
 
+/// Returns the last block of the batch of blocks that are read at once for execution, starting at
+/// the given block.
+///
+/// A batch doesn't cross the boundary of a static file, so the transactions of a batch are read
+/// from a single static file or from the database.
+fn fetch_batch_end(block_number: BlockNumber, max_block: BlockNumber) -> BlockNumber {
+    block_number
+        .saturating_add(FETCH_BLOCKS_BATCH_SIZE - 1)
+        .min(find_fixed_range(block_number).end())
+        .min(max_block)
+}
+
+/// Describes the first receipt of the block that's known to mismatch its header.
+///
+/// Only the receipts root and the logs bloom of the header are known, so a receipt is known to
+/// mismatch if its logs set bits that aren't set in the logs bloom of the header.
+fn describe_receipts_mismatch(block: &BlockWithSenders, receipts: &[Receipt]) -> String {
+    let header_bloom = block.header.logs_bloom;
+    let mismatching = receipts
+        .iter()
+        .enumerate()
+        .find(|(_, receipt)| receipt.bloom_slow() | header_bloom != header_bloom);
+
+    match mismatching {
+        Some((index, receipt)) => {
+            let hash = block.body.get(index).map(|tx| tx.recalculate_hash()).unwrap_or_default();
+            format!(
+                "receipt {index} of transaction {hash} has logs that aren't in the logs bloom of \
+                 the header: {receipt:?}"
+            )
+        }
+        None => format!(
+            "no receipt is known to mismatch, the {} receipts have {} logs and use {} gas",
+            receipts.len(),
+            receipts.iter().map(|receipt| receipt.logs.len()).sum::<usize>(),
+            receipts.last().map_or(0, |receipt| receipt.cumulative_gas_used)
+        ),
+    }
+}
+
 fn execution_checkpoint(
     provider: &StaticFileProvider,
     start_block: BlockNumber,
@@ -359,6 +528,33 @@ impl<EF: ExecutorFactory, DB: Database> Stage<DB> for ExecutionStage<EF> {
         StageId::Execution
     }
 
+    fn poll_execute_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+        _input: ExecInput,
+    ) -> Poll<Result<(), StageError>> {
+        if let Some(ready) = &mut self.exex_manager_ready {
+            loop {
+                match ready.poll_next_unpin(cx) {
+                    Poll::Ready(Some(is_ready)) => self.is_exex_manager_ready = is_ready,
+                    // the manager is gone, so nothing holds back the execution anymore
+                    Poll::Ready(None) => {
+                        self.exex_manager_ready = None;
+                        self.is_exex_manager_ready = true;
+                        break
+                    }
+                    Poll::Pending => break,
+                }
+            }
+        }
+
+        if !self.is_exex_manager_ready {
+            trace!(target: "sync::stages::execution", "Waiting for the ExEx manager to catch up");
+            return Poll::Pending
+        }
+        Poll::Ready(Ok(()))
+    }
+
     /// Execute the stage
     fn execute(
         &mut self,
@@ -425,6 +621,10 @@ impl<EF: ExecutorFactory, DB: Database> Stage<DB> for ExecutionStage<EF> {
         // Discard unwinded changesets
         provider.unwind_table_by_num::<tables::AccountChangeSets>(unwind_to)?;
 
+        // Discard the call addresses of the unwound blocks that weren't indexed yet
+        provider.unwind_table_by_num::<tables::BlockCallFromAddresses>(unwind_to)?;
+        provider.unwind_table_by_num::<tables::BlockCallToAddresses>(unwind_to)?;
+
         let mut rev_storage_changeset_walker = storage_changeset.walk_back(None)?;
         while let Some((key, _)) = rev_storage_changeset_walker.next().transpose()? {
             if key.block_number() < *range.start() {
@@ -490,11 +690,9 @@ impl<EF: ExecutorFactory, DB: Database> Stage<DB> for ExecutionStage<EF> {
 
 /// The thresholds at which the execution stage writes state changes to the database.
 ///
-/// If either of the thresholds (`max_blocks` and `max_changes`) are hit, then the execution stage
+/// All thresholds are checked after every executed block. If any of them (`max_blocks`,
+/// `max_changes`, `max_cumulative_gas` and `max_duration`) is hit, then the execution stage
 /// commits all pending changes to the database.
-///
-/// A third threshold, `max_changesets`, can be set to periodically write changesets to the
-/// current database transaction, which frees up memory.
 #[derive(Debug, Clone)]
 pub struct ExecutionStageThresholds {
     /// The maximum number of blocks to execute before the execution stage commits.
@@ -530,13 +728,49 @@ impl ExecutionStageThresholds {
         cumulative_gas_used: u64,
         elapsed: Duration,
     ) -> bool {
-        blocks_processed >= self.max_blocks.unwrap_or(u64::MAX) ||
-            changes_processed >= self.max_changes.unwrap_or(u64::MAX) ||
-            cumulative_gas_used >= self.max_cumulative_gas.unwrap_or(u64::MAX) ||
-            elapsed >= self.max_duration.unwrap_or(Duration::MAX)
+        self.end_of_batch_trigger(blocks_processed, changes_processed, cumulative_gas_used, elapsed)
+            .is_some()
+    }
+
+    /// Returns the first threshold that has been hit, if any.
+    ///
+    /// All thresholds are checked, so the batch ends as soon as any of them is hit. Thresholds that
+    /// aren't set are never hit.
+    #[inline]
+    pub fn end_of_batch_trigger(
+        &self,
+        blocks_processed: u64,
+        changes_processed: u64,
+        cumulative_gas_used: u64,
+        elapsed: Duration,
+    ) -> Option<ExecutionBatchTrigger> {
+        if self.max_blocks.is_some_and(|max| blocks_processed >= max) {
+            Some(ExecutionBatchTrigger::Blocks)
+        } else if self.max_changes.is_some_and(|max| changes_processed >= max) {
+            Some(ExecutionBatchTrigger::Changes)
+        } else if self.max_cumulative_gas.is_some_and(|max| cumulative_gas_used >= max) {
+            Some(ExecutionBatchTrigger::CumulativeGas)
+        } else if self.max_duration.is_some_and(|max| elapsed >= max) {
+            Some(ExecutionBatchTrigger::Duration)
+        } else {
+            None
+        }
     }
 }
 
+/// The threshold of [`ExecutionStageThresholds`] that ended a batch of the execution stage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionBatchTrigger {
+    /// The maximum number of blocks was executed.
+    Blocks,
+    /// The maximum number of state changes was kept in memory.
+    Changes,
+    /// The maximum cumulative amount of gas was processed.
+    CumulativeGas,
+    /// The maximum time was spent on blocks processing.
+    Duration,
+}
+
 /// Returns a `StaticFileProviderRWRefMut` static file producer after performing a consistency
 /// check.
 ///
@@ -620,13 +854,12 @@ mod tests {
     use crate::test_utils::TestStageDB;
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
-    use reth_db::models::AccountBeforeTx;
-    use reth_interfaces::executor::BlockValidationError;
+    use reth_db::{models::AccountBeforeTx, DatabaseEnv};
     use reth_node_ethereum::EthEvmConfig;
     use reth_primitives::{
         address, hex_literal::hex, keccak256, stage::StageUnitCheckpoint, Account, Address,
-        Bytecode, ChainSpecBuilder, PruneMode, ReceiptsLogPruneConfig, SealedBlock, StorageEntry,
-        B256,
+        Bytecode, ChainSpecBuilder, Log, PruneMode, ReceiptsLogPruneConfig, SealedBlock,
+        StorageEntry, B256,
     };
     use reth_provider::{test_utils::create_test_provider_factory, AccountReader, ReceiptProvider};
     use reth_revm::EvmProcessorFactory;
@@ -650,6 +883,130 @@ mod tests {
         )
     }
 
+    #[test]
+    fn end_of_batch_triggers() {
+        let unlimited = ExecutionStageThresholds {
+            max_blocks: None,
+            max_changes: None,
+            max_cumulative_gas: None,
+            max_duration: None,
+        };
+        let below = (9, 9, 9, Duration::from_secs(9));
+        let trigger = |thresholds: &ExecutionStageThresholds, (blocks, changes, gas, elapsed)| {
+            thresholds.end_of_batch_trigger(blocks, changes, gas, elapsed)
+        };
+
+        assert_eq!(trigger(&unlimited, (u64::MAX, u64::MAX, u64::MAX, Duration::MAX)), None);
+
+        let thresholds = ExecutionStageThresholds { max_blocks: Some(10), ..unlimited.clone() };
+        assert_eq!(trigger(&thresholds, below), None);
+        assert_eq!(
+            trigger(&thresholds, (10, u64::MAX, u64::MAX, Duration::MAX)),
+            Some(ExecutionBatchTrigger::Blocks)
+        );
+
+        let thresholds = ExecutionStageThresholds { max_changes: Some(10), ..unlimited.clone() };
+        assert_eq!(trigger(&thresholds, below), None);
+        assert_eq!(
+            trigger(&thresholds, (u64::MAX, 10, u64::MAX, Duration::MAX)),
+            Some(ExecutionBatchTrigger::Changes)
+        );
+
+        let thresholds =
+            ExecutionStageThresholds { max_cumulative_gas: Some(10), ..unlimited.clone() };
+        assert_eq!(trigger(&thresholds, below), None);
+        assert_eq!(
+            trigger(&thresholds, (u64::MAX, u64::MAX, 10, Duration::MAX)),
+            Some(ExecutionBatchTrigger::CumulativeGas)
+        );
+
+        let thresholds = ExecutionStageThresholds {
+            max_duration: Some(Duration::from_secs(10)),
+            ..unlimited.clone()
+        };
+        assert_eq!(trigger(&thresholds, below), None);
+        assert_eq!(
+            trigger(&thresholds, (u64::MAX, u64::MAX, u64::MAX, Duration::from_secs(10))),
+            Some(ExecutionBatchTrigger::Duration)
+        );
+
+        // All thresholds are checked at once, and the batch ends on the first one that is hit
+        let thresholds = ExecutionStageThresholds {
+            max_blocks: Some(10),
+            max_changes: Some(10),
+            max_cumulative_gas: Some(10),
+            max_duration: Some(Duration::from_secs(10)),
+        };
+        assert_eq!(trigger(&thresholds, below), None);
+        assert_eq!(
+            trigger(&thresholds, (9, 9, 10, Duration::from_secs(9))),
+            Some(ExecutionBatchTrigger::CumulativeGas)
+        );
+        assert!(thresholds.is_end_of_batch(9, 9, 9, Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn waits_for_exex_manager() {
+        let (ready_tx, ready_rx) = watch::channel(true);
+        let mut stage = stage().with_exex_manager_ready(ready_rx);
+        let mut cx = Context::from_waker(futures_util::task::noop_waker_ref());
+        let mut poll = |stage: &mut ExecutionStage<_>| {
+            Stage::<DatabaseEnv>::poll_execute_ready(stage, &mut cx, ExecInput::default())
+        };
+        assert_matches!(poll(&mut stage), Poll::Ready(Ok(())));
+
+        // all exexs are too far behind
+        ready_tx.send(false).unwrap();
+        assert_matches!(poll(&mut stage), Poll::Pending);
+
+        ready_tx.send(true).unwrap();
+        assert_matches!(poll(&mut stage), Poll::Ready(Ok(())));
+
+        // the manager is gone
+        ready_tx.send(false).unwrap();
+        drop(ready_tx);
+        assert_matches!(poll(&mut stage), Poll::Ready(Ok(())));
+    }
+
+    #[test]
+    fn fetch_batches_within_static_files() {
+        assert_eq!(fetch_batch_end(0, 1_000), FETCH_BLOCKS_BATCH_SIZE - 1);
+        assert_eq!(fetch_batch_end(10, 50), 50);
+
+        let file_end = find_fixed_range(0).end();
+        assert_eq!(fetch_batch_end(file_end - 10, file_end + 1_000), file_end);
+        assert_eq!(fetch_batch_end(file_end, file_end + 1_000), file_end);
+        assert_eq!(
+            fetch_batch_end(file_end + 1, file_end + 1_000),
+            file_end + FETCH_BLOCKS_BATCH_SIZE
+        );
+    }
+
+    #[test]
+    fn describe_mismatching_receipt() {
+        let log = || Log {
+            address: Address::random(),
+            topics: vec![B256::random()],
+            ..Default::default()
+        };
+        let receipts = vec![
+            Receipt { cumulative_gas_used: 21_000, logs: vec![log()], ..Default::default() },
+            Receipt { cumulative_gas_used: 42_000, logs: vec![log()], ..Default::default() },
+        ];
+
+        // the logs bloom of the header only has the logs of the first receipt
+        let mut block = BlockWithSenders::default();
+        block.block.header.logs_bloom = receipts[0].bloom_slow();
+        let description = describe_receipts_mismatch(&block, &receipts);
+        assert!(description.starts_with("receipt 1 of transaction"), "{description}");
+
+        block.block.header.logs_bloom = receipts[0].bloom_slow() | receipts[1].bloom_slow();
+        assert_eq!(
+            describe_receipts_mismatch(&block, &receipts),
+            "no receipt is known to mismatch, the 2 receipts have 2 logs and use 42000 gas"
+        );
+    }
+
     #[test]
     fn execution_checkpoint_matches() {
         let factory = create_test_provider_factory();
@@ -1219,4 +1576,78 @@ mod tests {
             ]
         );
     }
+
+    #[tokio::test]
+    async fn receipts_mismatch_fails_execution() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let input = ExecInput { target: Some(1), checkpoint: None };
+        let mut genesis_rlp = hex!("f901faf901f5a00000000000000000000000000000000000000000000000000000000000000000a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa045571b40ae66ca7480791bbb2887286e4e4c4b1b298b191c889d6959023a32eda056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000808502540be400808000a00000000000000000000000000000000000000000000000000000000000000000880000000000000000c0c0").as_slice();
+        let genesis = SealedBlock::decode(&mut genesis_rlp).unwrap();
+        let mut block_rlp = hex!("f90262f901f9a075c371ba45999d87f4542326910a11af515897aebce5265d3f6acd1f1161f82fa01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa098f2dcd87c8ae4083e7017a05456c14eea4b1db2032126e27b3b1563d57d7cc0a08151d548273f6683169524b66ca9fe338b9ce42bc3540046c828fd939ae23bcba03f4e5c2ec5b2170b711d97ee755c160457bb58d8daa338e835ec02ae6860bbabb901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000018502540be40082a8798203e800a00000000000000000000000000000000000000000000000000000000000000000880000000000000000f863f861800a8405f5e10094100000000000000000000000000000000000000080801ba07e09e26678ed4fac08a249ebe8ed680bf9051a5e14ad223e4b2b9d26e0208f37a05f6e3f188e3e6eab7d7d3b6568f5eac7d687b08d307d3154ccd8c87b4630509bc0").as_slice();
+        // the receipts root of the header doesn't match the receipts of the execution
+        let mut block = SealedBlock::decode(&mut block_rlp).unwrap().unseal();
+        block.header.receipts_root = B256::random();
+        let block = block.seal_slow();
+        provider.insert_historical_block(genesis.try_seal_with_senders().unwrap(), None).unwrap();
+        provider.insert_historical_block(block.try_seal_with_senders().unwrap(), None).unwrap();
+        provider
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+        {
+            let mut receipts_writer =
+                provider.static_file_provider().latest_writer(StaticFileSegment::Receipts).unwrap();
+            receipts_writer.increment_block(StaticFileSegment::Receipts, 0).unwrap();
+            receipts_writer.commit().unwrap();
+        }
+        provider.commit().unwrap();
+
+        // insert pre state
+        let provider = factory.provider_rw().unwrap();
+        let db_tx = provider.tx_ref();
+        let code = hex!("5a465a905090036002900360015500");
+        let code_hash = keccak256(code);
+        db_tx
+            .put::<tables::PlainAccountState>(
+                address!("1000000000000000000000000000000000000000"),
+                Account { nonce: 0, balance: U256::ZERO, bytecode_hash: Some(code_hash) },
+            )
+            .unwrap();
+        db_tx
+            .put::<tables::PlainAccountState>(
+                address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b"),
+                Account {
+                    nonce: 0,
+                    balance: U256::from(0x3635c9adc5dea00000u128),
+                    bytecode_hash: None,
+                },
+            )
+            .unwrap();
+        db_tx.put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(code.to_vec().into())).unwrap();
+        provider.commit().unwrap();
+
+        let provider = factory.provider_rw().unwrap();
+        let result = stage().execute(&provider, input);
+        assert_matches!(
+            result,
+            Err(StageError::Block {
+                error: BlockErrorKind::Execution(BlockExecutionError::Validation(
+                    BlockValidationError::ReceiptsMismatch { error, .. }
+                )),
+                ..
+            }) if matches!(*error, BlockValidationError::ReceiptRootDiff(_))
+        );
+        drop(provider);
+
+        // the receipts of blocks before the first verified block aren't verified
+        let provider = factory.provider_rw().unwrap();
+        let output = stage().with_receipts_verification(Some(2)).execute(&provider, input).unwrap();
+        assert_eq!(output.checkpoint.block_number, 1);
+        assert!(output.done);
+        provider.commit().unwrap();
+        assert!(factory.provider().unwrap().receipt(0).unwrap().is_some());
+    }
 }
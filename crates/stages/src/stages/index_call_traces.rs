@@ -0,0 +1,497 @@
+use crate::{ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRW},
+    database::Database,
+    table::{DupSort, Table},
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_evm::ConfigureEvm;
+use reth_primitives::{
+    stage::{StageCheckpoint, StageId},
+    Address, BlockNumber, BlockWithSenders, ChainSpec, PruneCheckpoint, PruneMode, PruneModes,
+    PrunePurpose, PruneSegment,
+};
+use reth_provider::{
+    BlockExecutor, BlockReader, DatabaseProviderRW, HeaderProvider, HistoricalStateProviderRef,
+    HistoryWriter, ProviderError, PrunableBlockExecutor, PruneCheckpointReader,
+    PruneCheckpointWriter, TransactionVariant,
+};
+use reth_revm::{
+    call_addresses::CallAddresses, database::StateProviderDatabase, processor::EVMProcessor,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    ops::RangeInclusive,
+    sync::Arc,
+};
+use tracing::info;
+
+/// The number of blocks that are read from the database at once for execution.
+const FETCH_BLOCKS_BATCH_SIZE: u64 = 100;
+
+/// Stage is indexing the blocks that contain the call traces of every address, from the addresses
+/// of the calls that the [`ExecutionStage`](super::ExecutionStage) recorded while executing the
+/// blocks.
+///
+/// The blocks are indexed by the `from` addresses in [`tables::CallFromIndex`] and by the `to`
+/// addresses in [`tables::CallToIndex`], sharded like [`tables::AccountsHistory`]. The index is
+/// used by `trace_filter` to only trace the blocks that may contain matching traces. The recorded
+/// addresses of [`tables::BlockCallFromAddresses`] and [`tables::BlockCallToAddresses`] are
+/// removed once they're indexed.
+///
+/// The blocks that weren't executed by the execution stage with the recording enabled, e.g. the
+/// blocks that were made canonical by the blockchain tree, are executed again on top of their
+/// historical state with an inspector that records the addresses of their calls. So are the
+/// unwound blocks. The blocks with pruned account or storage history can't be executed and are
+/// not indexed.
+///
+/// The stage is optional, so it's not part of the default stages.
+#[derive(Debug)]
+pub struct IndexCallTracesStage<EvmConfig> {
+    /// The chain spec of the executed blocks.
+    chain_spec: Arc<ChainSpec>,
+    /// The EVM configuration of the executed blocks.
+    evm_config: EvmConfig,
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    pub commit_threshold: u64,
+    /// Pruning configuration.
+    pub prune_mode: Option<PruneMode>,
+}
+
+impl<EvmConfig> IndexCallTracesStage<EvmConfig> {
+    /// Create new instance of [IndexCallTracesStage].
+    pub fn new(
+        chain_spec: Arc<ChainSpec>,
+        evm_config: EvmConfig,
+        commit_threshold: u64,
+        prune_mode: Option<PruneMode>,
+    ) -> Self {
+        Self { chain_spec, evm_config, commit_threshold, prune_mode }
+    }
+}
+
+/// The blocks of the call traces of the addresses, by their `from` and `to` addresses.
+#[derive(Debug, Default)]
+struct CallTraceIndices {
+    from: BTreeMap<Address, Vec<BlockNumber>>,
+    to: BTreeMap<Address, Vec<BlockNumber>>,
+}
+
+impl CallTraceIndices {
+    /// Adds the addresses of the call traces of the block.
+    fn insert(&mut self, block_number: BlockNumber, from: HashSet<Address>, to: HashSet<Address>) {
+        for address in from {
+            self.from.entry(address).or_default().push(block_number);
+        }
+        for address in to {
+            self.to.entry(address).or_default().push(block_number);
+        }
+    }
+}
+
+/// The `from` and `to` addresses of the call traces of the blocks that are executed by the
+/// [`ExecutionStage`](super::ExecutionStage), which are written to
+/// [`tables::BlockCallFromAddresses`] and [`tables::BlockCallToAddresses`] for the
+/// [IndexCallTracesStage].
+#[derive(Debug, Default)]
+pub(crate) struct BlockCallAddresses(Vec<(BlockNumber, HashSet<Address>, HashSet<Address>)>);
+
+impl BlockCallAddresses {
+    /// Adds the addresses of the call traces of the executed block.
+    pub(crate) fn push(
+        &mut self,
+        block: &BlockWithSenders,
+        from: HashSet<Address>,
+        mut to: HashSet<Address>,
+    ) {
+        insert_reward_beneficiaries(block, &mut to);
+        self.0.push((block.number, from, to));
+    }
+
+    /// Writes the addresses of the blocks to the database.
+    pub(crate) fn write<TX: DbTxMut>(self, tx: &TX) -> Result<(), DatabaseError> {
+        for (block_number, from, to) in self.0 {
+            for address in from {
+                tx.put::<tables::BlockCallFromAddresses>(block_number, address)?;
+            }
+            for address in to {
+                tx.put::<tables::BlockCallToAddresses>(block_number, address)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<EvmConfig> IndexCallTracesStage<EvmConfig>
+where
+    EvmConfig: ConfigureEvm + Clone,
+{
+    /// Collects the blocks of the call traces of every address in the range, from the addresses
+    /// recorded by the execution stage, executing the blocks without recorded addresses again.
+    fn collect_indices<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<CallTraceIndices, StageError> {
+        let tx = provider.tx_ref();
+        let mut indices = CallTraceIndices::default();
+
+        // Every recorded block has at least its beneficiary as a `to` address.
+        let mut recorded_blocks = BTreeSet::new();
+        for entry in
+            tx.cursor_dup_read::<tables::BlockCallToAddresses>()?.walk_range(range.clone())?
+        {
+            let (block_number, address) = entry?;
+            recorded_blocks.insert(block_number);
+            indices.to.entry(address).or_default().push(block_number);
+        }
+        for entry in
+            tx.cursor_dup_read::<tables::BlockCallFromAddresses>()?.walk_range(range.clone())?
+        {
+            let (block_number, address) = entry?;
+            indices.from.entry(address).or_default().push(block_number);
+        }
+
+        // The state of a block is only known at the start of a run of consecutive blocks, so every
+        // run of blocks without recorded addresses is executed from its start.
+        let mut unrecorded_blocks =
+            range.filter(|block_number| !recorded_blocks.contains(block_number)).peekable();
+        while let Some(start) = unrecorded_blocks.next() {
+            let mut end = start;
+            while unrecorded_blocks.next_if_eq(&(end + 1)).is_some() {
+                end += 1;
+            }
+            self.execute_indices(provider, start..=end, &mut indices)?;
+        }
+        if !recorded_blocks.is_empty() {
+            for blocks in indices.from.values_mut().chain(indices.to.values_mut()) {
+                blocks.sort_unstable();
+            }
+        }
+
+        Ok(indices)
+    }
+
+    /// Executes the blocks of the range again and adds the blocks of the call traces of every
+    /// address to the indices.
+    fn execute_indices<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        range: RangeInclusive<BlockNumber>,
+        indices: &mut CallTraceIndices,
+    ) -> Result<(), StageError> {
+        let mut executor = EVMProcessor::new_with_db(
+            self.chain_spec.clone(),
+            StateProviderDatabase::new(HistoricalStateProviderRef::new(
+                provider.tx_ref(),
+                *range.start(),
+                provider.static_file_provider().clone(),
+            )),
+            self.evm_config.clone(),
+        );
+        // Only the addresses of the calls are used, so neither the receipts nor the reverts of the
+        // state are retained.
+        executor.set_prune_modes(PruneModes {
+            receipts: Some(PruneMode::Full),
+            account_history: Some(PruneMode::Full),
+            storage_history: Some(PruneMode::Full),
+            ..PruneModes::none()
+        });
+        executor.set_tip(*range.end());
+        executor.stack_mut().call_addresses = Some(CallAddresses::default());
+
+        for batch_start in range.clone().step_by(FETCH_BLOCKS_BATCH_SIZE as usize) {
+            let batch_end =
+                batch_start.saturating_add(FETCH_BLOCKS_BATCH_SIZE - 1).min(*range.end());
+            let blocks = provider
+                .blocks_with_senders_range(batch_start..=batch_end, TransactionVariant::NoHash)?;
+            for block in blocks {
+                let td = provider
+                    .header_td_by_number(block.number)?
+                    .ok_or_else(|| ProviderError::HeaderNotFound(block.number.into()))?;
+
+                // The blocks were already executed by the execution stage, so an error is an
+                // inconsistency of the database rather than an invalid block.
+                executor
+                    .execute_and_verify_receipt(&block, td)
+                    .map_err(|error| StageError::Fatal(Box::new(error)))?;
+
+                let CallAddresses { from, mut to } = executor
+                    .stack_mut()
+                    .call_addresses
+                    .replace(CallAddresses::default())
+                    .unwrap_or_default();
+                insert_reward_beneficiaries(&block, &mut to);
+                indices.insert(block.number, from, to);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<DB, EvmConfig> Stage<DB> for IndexCallTracesStage<EvmConfig>
+where
+    DB: Database,
+    EvmConfig: ConfigureEvm + Clone + 'static,
+{
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::IndexCallTraces
+    }
+
+    /// Execute the stage.
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        mut input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        if let Some((target_prunable_block, prune_mode)) = self
+            .prune_mode
+            .map(|mode| {
+                mode.prune_target_block(
+                    input.target(),
+                    PruneSegment::CallTraces,
+                    PrunePurpose::User,
+                )
+            })
+            .transpose()?
+            .flatten()
+        {
+            if target_prunable_block > input.checkpoint().block_number {
+                input.checkpoint = Some(StageCheckpoint::new(target_prunable_block));
+
+                // Save prune checkpoint only if we don't have one already.
+                // Otherwise, pruner may skip the unpruned range of blocks.
+                if provider.get_prune_checkpoint(PruneSegment::CallTraces)?.is_none() {
+                    provider.save_prune_checkpoint(
+                        PruneSegment::CallTraces,
+                        PruneCheckpoint {
+                            block_number: Some(target_prunable_block),
+                            tx_number: None,
+                            prune_mode,
+                        },
+                    )?;
+                }
+            }
+        }
+
+        // The blocks with pruned history can't be executed, they're skipped and marked as pruned,
+        // so their traces are not looked up in the index.
+        let lowest_executable_block = lowest_executable_block(provider)?;
+        if lowest_executable_block > input.next_block() {
+            let skipped_block = (lowest_executable_block - 1).min(input.target());
+            input.checkpoint = Some(StageCheckpoint::new(skipped_block));
+
+            let pruned_block = provider
+                .get_prune_checkpoint(PruneSegment::CallTraces)?
+                .and_then(|checkpoint| checkpoint.block_number);
+            if pruned_block.map_or(true, |block_number| block_number < skipped_block) {
+                provider.save_prune_checkpoint(
+                    PruneSegment::CallTraces,
+                    PruneCheckpoint {
+                        block_number: Some(skipped_block),
+                        tx_number: None,
+                        prune_mode: self
+                            .prune_mode
+                            .unwrap_or(PruneMode::Before(lowest_executable_block)),
+                    },
+                )?;
+            }
+        }
+
+        // The recorded addresses of the blocks up to the checkpoint are indexed or skipped.
+        remove_block_call_addresses(provider.tx_ref(), input.checkpoint().block_number)?;
+
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let (range, is_final_range) = input.next_block_range_with_threshold(self.commit_threshold);
+
+        info!(target: "sync::stages::index_call_traces::exec", ?range, "Collecting indices");
+        let indices = self.collect_indices(provider, range.clone())?;
+
+        // The range may have been indexed before the checkpoint was lowered by a reorg, so the
+        // existing indices of the range are removed first.
+        info!(target: "sync::stages::index_call_traces::exec", "Loading indices into database");
+        provider.unwind_call_trace_indices(
+            *range.start(),
+            &indices.from.keys().copied().collect(),
+            &indices.to.keys().copied().collect(),
+        )?;
+        provider.insert_call_trace_index(indices.from, indices.to)?;
+        remove_block_call_addresses(provider.tx_ref(), *range.end())?;
+
+        Ok(ExecOutput { checkpoint: StageCheckpoint::new(*range.end()), done: is_final_range })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (range, unwind_progress, _) =
+            input.unwind_block_range_with_threshold(self.commit_threshold);
+
+        // The addresses of the unwound blocks are only known by executing them again. If their
+        // history is pruned, their indices are left in place, which only adds blocks without
+        // matching traces to the lookups once the blocks are indexed again.
+        if lowest_executable_block(provider)? <= *range.start() {
+            let start = *range.start();
+            let indices = self.collect_indices(provider, range)?;
+            provider.unwind_call_trace_indices(
+                start,
+                &indices.from.into_keys().collect(),
+                &indices.to.into_keys().collect(),
+            )?;
+        }
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(unwind_progress) })
+    }
+}
+
+/// Adds the beneficiaries of the block and its ommers to the `to` addresses, the rewards are traced
+/// with them as the author.
+fn insert_reward_beneficiaries(block: &BlockWithSenders, to: &mut HashSet<Address>) {
+    to.insert(block.beneficiary);
+    to.extend(block.ommers.iter().map(|ommer| ommer.beneficiary));
+}
+
+/// Removes the recorded call addresses of the blocks up to the given block.
+fn remove_block_call_addresses<TX: DbTxMut + DbTx>(
+    tx: &TX,
+    last_block: BlockNumber,
+) -> Result<(), DatabaseError> {
+    remove_up_to::<_, tables::BlockCallFromAddresses>(tx, last_block)?;
+    remove_up_to::<_, tables::BlockCallToAddresses>(tx, last_block)
+}
+
+/// Removes all entries of the table up to the given block.
+fn remove_up_to<TX, T>(tx: &TX, last_block: BlockNumber) -> Result<(), DatabaseError>
+where
+    TX: DbTxMut + DbTx,
+    T: Table<Key = BlockNumber> + DupSort,
+{
+    let mut cursor = tx.cursor_dup_write::<T>()?;
+    while let Some((block_number, _)) = cursor.first()? {
+        if block_number > last_block {
+            break
+        }
+        cursor.delete_current_duplicates()?;
+    }
+    Ok(())
+}
+
+/// Returns the lowest block that can be executed on top of its historical state, which requires
+/// the account and storage history from the block onwards.
+fn lowest_executable_block<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+) -> Result<BlockNumber, StageError> {
+    let mut lowest_block = 0;
+    for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+        if let Some(block_number) =
+            provider.get_prune_checkpoint(segment)?.and_then(|checkpoint| checkpoint.block_number)
+        {
+            lowest_block = lowest_block.max(block_number + 1);
+        }
+    }
+    Ok(lowest_block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StorageKind, TestStageDB};
+    use reth_db::{models::ShardedKey, BlockNumberList};
+    use reth_interfaces::test_utils::generators::{self, random_block};
+    use reth_node_ethereum::EthEvmConfig;
+    use reth_primitives::{address, SealedBlock, B256, MAINNET};
+
+    const FROM: Address = address!("0000000000000000000000000000000000000001");
+    const TO: Address = address!("0000000000000000000000000000000000000002");
+
+    /// Inserts empty blocks up to the given block, their beneficiary is the zero address.
+    fn insert_blocks(db: &TestStageDB, last_block: BlockNumber) -> Vec<SealedBlock> {
+        let mut rng = generators::rng();
+        let mut parent = B256::ZERO;
+        let blocks = (0..=last_block)
+            .map(|number| {
+                let block = random_block(&mut rng, number, Some(parent), Some(0), Some(0));
+                parent = block.hash();
+                block
+            })
+            .collect::<Vec<_>>();
+        db.insert_blocks(blocks.iter(), StorageKind::Static).unwrap();
+        blocks
+    }
+
+    fn stage() -> IndexCallTracesStage<EthEvmConfig> {
+        IndexCallTracesStage::new(MAINNET.clone(), EthEvmConfig::default(), 10_000, None)
+    }
+
+    fn cast(
+        table: Vec<(ShardedKey<Address>, BlockNumberList)>,
+    ) -> Vec<(Address, Vec<BlockNumber>)> {
+        table.into_iter().map(|(k, v)| (k.key, v.iter().collect())).collect()
+    }
+
+    fn run(db: &TestStageDB, run_to: u64) {
+        let input = ExecInput { target: Some(run_to), checkpoint: Some(StageCheckpoint::new(0)) };
+        let provider = db.factory.provider_rw().unwrap();
+        let out = stage().execute(&provider, input).unwrap();
+        assert_eq!(out, ExecOutput { checkpoint: StageCheckpoint::new(run_to), done: true });
+        provider.commit().unwrap();
+    }
+
+    #[test]
+    fn index_recorded_and_executed_blocks() {
+        let db = TestStageDB::default();
+        let blocks = insert_blocks(&db, 4);
+
+        // The addresses of blocks 1 and 2 are recorded, blocks 3 and 4 are executed again.
+        let mut recorded = BlockCallAddresses::default();
+        for (block, from, to) in [
+            (&blocks[1], HashSet::from([FROM]), HashSet::from([TO])),
+            (&blocks[2], HashSet::new(), HashSet::new()),
+        ] {
+            let block = block.clone().unseal().with_recovered_senders().unwrap();
+            recorded.push(&block, from, to);
+        }
+        db.commit(|tx| Ok(recorded.write(tx)?)).unwrap();
+
+        run(&db, 4);
+
+        assert_eq!(cast(db.table::<tables::CallFromIndex>().unwrap()), vec![(FROM, vec![1])]);
+        assert_eq!(
+            cast(db.table::<tables::CallToIndex>().unwrap()),
+            vec![(Address::ZERO, vec![1, 2, 3, 4]), (TO, vec![1])]
+        );
+        assert!(db.table::<tables::BlockCallFromAddresses>().unwrap().is_empty());
+        assert!(db.table::<tables::BlockCallToAddresses>().unwrap().is_empty());
+    }
+
+    #[test]
+    fn unwind_executed_blocks() {
+        let db = TestStageDB::default();
+        insert_blocks(&db, 4);
+        run(&db, 4);
+
+        let input =
+            UnwindInput { checkpoint: StageCheckpoint::new(4), unwind_to: 2, ..Default::default() };
+        let provider = db.factory.provider_rw().unwrap();
+        let out = stage().unwind(&provider, input).unwrap();
+        assert_eq!(out, UnwindOutput { checkpoint: StageCheckpoint::new(2) });
+        provider.commit().unwrap();
+
+        assert!(db.table::<tables::CallFromIndex>().unwrap().is_empty());
+        assert_eq!(
+            cast(db.table::<tables::CallToIndex>().unwrap()),
+            vec![(Address::ZERO, vec![1, 2])]
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use crate::{BlockErrorKind, ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use reth_codecs::Compact;
+use reth_config::config::MerkleConfig;
 use reth_db::{
     database::Database,
     tables,
@@ -12,17 +14,30 @@ use reth_primitives::{
     BlockNumber, GotExpected, SealedHeader, B256,
 };
 use reth_provider::{
-    DatabaseProviderRW, HeaderProvider, ProviderError, StageCheckpointReader,
-    StageCheckpointWriter, StatsReader,
+    providers::ConsistentDbView, DatabaseProviderRW, HeaderProvider, ProviderError,
+    ProviderFactory, StageCheckpointReader, StageCheckpointWriter, StatsReader,
+};
+use reth_trie::{
+    prefix_set::{PrefixSetLoader, TriePrefixSets},
+    updates::TrieUpdates,
+    HashedPostState, IntermediateStateRootState, StateRoot, StateRootProgress,
+};
+use reth_trie_parallel::parallel_root::{ParallelStateRoot, ParallelStateRootError};
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+    time::Duration,
 };
-use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress};
-use std::fmt::Debug;
 use tracing::*;
 
 /// The default threshold (in number of blocks) for switching from incremental trie building
 /// of changes to whole rebuild.
 pub const MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD: u64 = 5_000;
 
+/// The default minimum number of accounts with changed storage for which the storage roots are
+/// calculated in parallel, below it the thread overhead dominates.
+pub const MERKLE_STAGE_DEFAULT_PARALLEL_STORAGE_ROOT_THRESHOLD: usize = 64;
+
 /// The merkle hashing stage uses input from
 /// [`AccountHashingStage`][crate::stages::AccountHashingStage] and
 /// [`StorageHashingStage`][crate::stages::AccountHashingStage] to calculate intermediate hashes
@@ -38,6 +53,11 @@ pub const MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD: u64 = 5_000;
 /// stages. The order of these two variants is important. The unwind variant should be added to the
 /// pipeline before the execution variant.
 ///
+/// Rebuilding the trie from scratch is split into multiple executions, each of them committing
+/// the intermediate state as a [`MerkleCheckpoint`], so that the rebuild resumes from the last
+/// hashed account key after a restart. The number of processed hashed accounts and storage slots
+/// is reported in the entities checkpoint of the stage.
+///
 /// An example pipeline to only hash state would be:
 ///
 /// - [`MerkleStage::Unwind`]
@@ -51,6 +71,12 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// The maximum time spent rebuilding the trie before the intermediate state is
+        /// committed, in addition to the threshold on the number of trie updates.
+        flush_interval: Option<Duration>,
+        /// Calculates the storage roots of changed accounts in parallel when the trie is updated
+        /// incrementally, or sequentially if not set.
+        parallel_storage_roots: Option<ParallelStorageRoots>,
     },
     /// The unwind portion of the merkle stage.
     Unwind,
@@ -66,7 +92,11 @@ pub enum MerkleStage {
 impl MerkleStage {
     /// Stage default for the [MerkleStage::Execution].
     pub fn default_execution() -> Self {
-        Self::Execution { clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD }
+        Self::Execution {
+            clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
+            flush_interval: None,
+            parallel_storage_roots: None,
+        }
     }
 
     /// Stage default for the [MerkleStage::Unwind].
@@ -76,7 +106,33 @@ impl MerkleStage {
 
     /// Create new instance of [MerkleStage::Execution].
     pub fn new_execution(clean_threshold: u64) -> Self {
-        Self::Execution { clean_threshold }
+        Self::Execution { clean_threshold, flush_interval: None, parallel_storage_roots: None }
+    }
+
+    /// Create new instance of [MerkleStage::Execution] from the stage configuration.
+    pub fn new_execution_with_config(config: MerkleConfig) -> Self {
+        Self::Execution {
+            clean_threshold: config.clean_threshold,
+            flush_interval: config.flush_interval,
+            parallel_storage_roots: None,
+        }
+    }
+
+    /// Create new instance of [MerkleStage::Execution] from the stage configuration, which
+    /// calculates the storage roots of changed accounts on read-only transactions of the given
+    /// factory if the configured parallelism is greater than 1.
+    ///
+    /// See [ParallelStorageRoots] for the caveats.
+    pub fn new_execution_with_parallel_storage_roots<DB: Database + Clone + 'static>(
+        config: MerkleConfig,
+        factory: ProviderFactory<DB>,
+    ) -> Self {
+        Self::Execution {
+            clean_threshold: config.clean_threshold,
+            flush_interval: config.flush_interval,
+            parallel_storage_roots: (config.storage_root_parallelism > 1)
+                .then(|| ParallelStorageRoots::new(factory, config.storage_root_parallelism)),
+        }
     }
 
     /// Gets the hashing progress
@@ -131,14 +187,16 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         provider: &DatabaseProviderRW<DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
-        let threshold = match self {
+        let (threshold, flush_interval, parallel_storage_roots) = match self {
             MerkleStage::Unwind => {
                 info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
                 return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
             }
-            MerkleStage::Execution { clean_threshold } => *clean_threshold,
+            MerkleStage::Execution { clean_threshold, flush_interval, parallel_storage_roots } => {
+                (*clean_threshold, *flush_interval, parallel_storage_roots.clone())
+            }
             #[cfg(any(test, feature = "test-utils"))]
-            MerkleStage::Both { clean_threshold } => *clean_threshold,
+            MerkleStage::Both { clean_threshold } => (*clean_threshold, None, None),
         };
 
         let range = input.next_block_range();
@@ -155,6 +213,9 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             (target_block_root, input.checkpoint().entities_stage_checkpoint().unwrap_or_default())
         } else if to_block - from_block > threshold || from_block == 1 {
             // if there are more blocks than threshold it is faster to rebuild the trie
+            let total_hashed_entries = (provider.count_entries::<tables::HashedAccounts>()? +
+                provider.count_entries::<tables::HashedStorages>()?)
+                as u64;
             let mut entities_checkpoint = if let Some(checkpoint) =
                 checkpoint.as_ref().filter(|c| c.target_block == to_block)
             {
@@ -166,7 +227,17 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                     "Continuing inner merkle checkpoint"
                 );
 
-                input.checkpoint().entities_stage_checkpoint()
+                // The stage checkpoint isn't necessarily saved along with the inner checkpoint,
+                // e.g. when the stage is run with `reth stage run` without `--checkpoints`.
+                input.checkpoint().entities_stage_checkpoint().unwrap_or_else(|| {
+                    EntitiesCheckpoint {
+                        processed: estimate_processed_entries(
+                            checkpoint.last_account_key,
+                            total_hashed_entries,
+                        ),
+                        total: total_hashed_entries,
+                    }
+                })
             } else {
                 debug!(
                     target: "sync::stages::merkle::exec",
@@ -181,18 +252,13 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 provider.tx_ref().clear::<tables::AccountsTrie>()?;
                 provider.tx_ref().clear::<tables::StoragesTrie>()?;
 
-                None
-            }
-            .unwrap_or(EntitiesCheckpoint {
-                processed: 0,
-                total: (provider.count_entries::<tables::HashedAccounts>()? +
-                    provider.count_entries::<tables::HashedStorages>()?)
-                    as u64,
-            });
+                EntitiesCheckpoint { processed: 0, total: total_hashed_entries }
+            };
 
             let tx = provider.tx_ref();
             let progress = StateRoot::from_tx(tx)
                 .with_intermediate_state(checkpoint.map(IntermediateStateRootState::from))
+                .with_duration_threshold(flush_interval)
                 .root_with_progress()
                 .map_err(|e| StageError::Fatal(Box::new(e)))?;
             match progress {
@@ -226,9 +292,18 @@ impl<DB: Database> Stage<DB> for MerkleStage {
             }
         } else {
             debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie");
-            let (root, updates) =
-                StateRoot::incremental_root_with_updates(provider.tx_ref(), range)
-                    .map_err(|e| StageError::Fatal(Box::new(e)))?;
+            let prefix_sets = PrefixSetLoader::new(provider.tx_ref()).load(range)?;
+            let (root, updates) = match parallel_storage_roots
+                .filter(|roots| prefix_sets.storage_prefix_sets.len() >= roots.threshold)
+            {
+                Some(roots) => roots
+                    .root_with_updates(prefix_sets)
+                    .map_err(|e| StageError::Fatal(Box::new(e)))?,
+                None => StateRoot::from_tx(provider.tx_ref())
+                    .with_prefix_sets(prefix_sets)
+                    .root_with_updates()
+                    .map_err(|e| StageError::Fatal(Box::new(e)))?,
+            };
             updates.flush(provider.tx_ref())?;
 
             let total_hashed_entries = (provider.count_entries::<tables::HashedAccounts>()? +
@@ -313,6 +388,14 @@ impl<DB: Database> Stage<DB> for MerkleStage {
     }
 }
 
+/// Estimates the number of hashed entries processed before the given hashed account key, assuming
+/// that the hashed keys are uniformly distributed.
+fn estimate_processed_entries(last_account_key: B256, total: u64) -> u64 {
+    // The most significant 8 bytes of the key are precise enough for an estimate
+    let position = u64::from_be_bytes(last_account_key[..8].try_into().expect("32 bytes"));
+    ((total as u128 * position as u128) >> 64) as u64
+}
+
 /// Check that the computed state root matches the root in the expected header.
 #[inline]
 fn validate_state_root(
@@ -333,6 +416,75 @@ fn validate_state_root(
     }
 }
 
+/// Calculates the storage roots of changed accounts in parallel when the trie is updated
+/// incrementally, each of them on its own read-only transaction with [ParallelStateRoot].
+///
+/// The read-only transactions only see committed changes, so the hashing stages have to be
+/// committed before the [MerkleStage] is executed, as the [Pipeline](crate::Pipeline) does.
+#[derive(Clone)]
+pub struct ParallelStorageRoots {
+    /// The pool the storage roots are calculated on.
+    pool: Arc<ThreadPool>,
+    /// The minimum number of accounts with changed storage for which the storage roots are
+    /// calculated in parallel.
+    threshold: usize,
+    /// Calculates the state root of the changes of the prefix sets on read-only transactions.
+    #[allow(clippy::type_complexity)]
+    state_root: Arc<
+        dyn Fn(TriePrefixSets) -> Result<(B256, TrieUpdates), ParallelStateRootError> + Send + Sync,
+    >,
+}
+
+impl ParallelStorageRoots {
+    /// Creates the calculation of the storage roots on at most `parallelism` threads, on the
+    /// read-only transactions of the given factory.
+    pub fn new<DB: Database + Clone + 'static>(
+        factory: ProviderFactory<DB>,
+        parallelism: usize,
+    ) -> Self {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(parallelism)
+            .thread_name(|i| format!("merkle-storage-root-{i}"))
+            .build()
+            .expect("failed to build the storage root thread pool");
+        let state_root = move |prefix_sets| {
+            let view = ConsistentDbView::new(factory.clone(), None);
+            ParallelStateRoot::new(view, HashedPostState::default())
+                .with_prefix_sets(prefix_sets)
+                .incremental_root_with_updates()
+        };
+        Self {
+            pool: Arc::new(pool),
+            threshold: MERKLE_STAGE_DEFAULT_PARALLEL_STORAGE_ROOT_THRESHOLD,
+            state_root: Arc::new(state_root),
+        }
+    }
+
+    /// Set the minimum number of accounts with changed storage for which the storage roots are
+    /// calculated in parallel.
+    pub fn with_threshold(mut self, threshold: usize) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Calculates the state root of the changes of the given prefix sets with the trie updates.
+    fn root_with_updates(
+        &self,
+        prefix_sets: TriePrefixSets,
+    ) -> Result<(B256, TrieUpdates), ParallelStateRootError> {
+        self.pool.install(|| (self.state_root)(prefix_sets))
+    }
+}
+
+impl Debug for ParallelStorageRoots {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParallelStorageRoots")
+            .field("pool", &self.pool)
+            .field("threshold", &self.threshold)
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,6 +590,109 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    /// Update small trie with the storage roots calculated in parallel
+    #[tokio::test]
+    async fn execute_small_merkle_with_parallel_storage_roots() {
+        let (previous_stage, stage_progress) = (2, 1);
+
+        // Set up the runner
+        let mut runner = MerkleTestRunner::default();
+        let input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+
+        runner.seed_execution(input).expect("failed to seed execution");
+
+        let mut stage = MerkleStage::Execution {
+            clean_threshold: runner.clean_threshold,
+            flush_interval: None,
+            parallel_storage_roots: Some(
+                ParallelStorageRoots::new(runner.db.factory.clone(), 4).with_threshold(1),
+            ),
+        };
+        let provider = runner.db.factory.provider_rw().unwrap();
+        let result = stage.execute(&provider, input);
+        provider.commit().unwrap();
+
+        assert_matches!(
+            result,
+            Ok(ExecOutput { checkpoint: StageCheckpoint { block_number, .. }, done: true })
+                if block_number == previous_stage
+        );
+
+        // Validate the stage execution
+        assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
+    }
+
+    /// Rebuild the trie over multiple executions, restarting the stage after every commit
+    #[tokio::test]
+    async fn execute_clean_merkle_resumes_after_restart() {
+        let (previous_stage, stage_progress) = (500, 0);
+
+        // Set up the runner
+        let mut runner = MerkleTestRunner::default();
+        let input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+
+        runner.seed_execution(input).expect("failed to seed execution");
+
+        let hashed_accounts = runner.db.table::<tables::HashedAccounts>().unwrap();
+        let total = (hashed_accounts.len() +
+            runner.db.table::<tables::HashedStorages>().unwrap().len()) as u64;
+
+        let mut checkpoint = input.checkpoint();
+        let mut last_account_keys = Vec::new();
+        loop {
+            // Only the committed state carries over to the new stage
+            let mut stage = MerkleStage::Execution {
+                clean_threshold: runner.clean_threshold,
+                flush_interval: Some(Duration::ZERO),
+                parallel_storage_roots: None,
+            };
+            let provider = runner.db.factory.provider_rw().unwrap();
+            let output = stage
+                .execute(&provider, ExecInput { checkpoint: Some(checkpoint), ..input })
+                .unwrap();
+            let inner_checkpoint = stage.get_execution_checkpoint(&provider).unwrap();
+            provider.commit().unwrap();
+
+            checkpoint = output.checkpoint;
+            let entities = checkpoint.entities().expect("entities checkpoint");
+            assert_eq!(entities.total, total);
+
+            if output.done {
+                assert_eq!(checkpoint.block_number, previous_stage);
+                assert_eq!(entities.processed, total);
+                assert_eq!(inner_checkpoint, None);
+                break
+            }
+
+            assert!(entities.processed < total);
+            last_account_keys.push(inner_checkpoint.expect("inner checkpoint").last_account_key);
+        }
+
+        // Without any time to spare, the intermediate state is committed after every account, and
+        // every execution resumes with the account after the persisted one.
+        assert_eq!(
+            last_account_keys,
+            hashed_accounts.into_iter().map(|(key, _)| key).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn estimate_processed_entries_from_key() {
+        assert_eq!(estimate_processed_entries(B256::ZERO, 1000), 0);
+        assert_eq!(estimate_processed_entries(B256::with_last_byte(1), 1000), 0);
+
+        let mut key = B256::ZERO;
+        key[0] = 0x80;
+        assert_eq!(estimate_processed_entries(key, 1000), 500);
+        assert_eq!(estimate_processed_entries(B256::repeat_byte(0xff), 1000), 999);
+    }
+
     struct MerkleTestRunner {
         db: TestStageDB,
         clean_threshold: u64,
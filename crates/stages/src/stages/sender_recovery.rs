@@ -1,7 +1,9 @@
 use crate::{BlockErrorKind, ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
+use reth_config::config::SenderRecoveryConfig;
 use reth_db::{
-    cursor::DbCursorRW,
+    cursor::{DbCursorRO, DbCursorRW},
     database::Database,
+    models::StoredBlockBodyIndices,
     static_file::TransactionMask,
     tables,
     transaction::{DbTx, DbTxMut},
@@ -9,45 +11,80 @@ use reth_db::{
 };
 use reth_interfaces::consensus;
 use reth_primitives::{
+    constants::EPOCH_SLOTS,
     stage::{EntitiesCheckpoint, StageCheckpoint, StageId},
-    Address, PruneSegment, StaticFileSegment, TransactionSignedNoHash, TxNumber,
+    Address, BlockNumber, PruneSegment, StaticFileSegment, TransactionSignedNoHash, TxNumber,
 };
 use reth_provider::{
+    providers::{StaticFileProviderRWRefMut, StaticFileWriter},
     BlockReader, DatabaseProviderRW, HeaderProvider, ProviderError, PruneCheckpointReader,
     StatsReader,
 };
-use std::{fmt::Debug, ops::Range, sync::mpsc};
+use std::{
+    fmt::Debug,
+    iter::Peekable,
+    ops::{Range, RangeInclusive},
+    sync::mpsc,
+};
 use thiserror::Error;
 use tracing::*;
 
 /// Maximum amount of transactions to read from disk at one time before we flush their senders to
-/// disk. Since each rayon worker will hold at most 100 transactions (WORKER_CHUNK_SIZE), we
-/// effectively max limit each batch to 1000 channels in memory.
+/// disk. Since each rayon worker will hold at most 100 transactions by default
+/// ([`SenderRecoveryStage::chunk_size`]), we effectively max limit each batch to 1000 channels in
+/// memory.
 const BATCH_SIZE: usize = 100_000;
 
-/// Maximum number of senders to recover per rayon worker job.
-const WORKER_CHUNK_SIZE: usize = 100;
+/// Default maximum number of senders to recover per rayon worker job.
+const WORKER_CHUNK_SIZE: u64 = 100;
+
+/// The number of blocks below the target of the stage after which blocks are considered final,
+/// since blocks are finalized by the beacon chain after two epochs.
+const FINALIZATION_DISTANCE: u64 = 2 * EPOCH_SLOTS;
 
 /// The sender recovery stage iterates over existing transactions,
 /// recovers the transaction signer and stores them
 /// in [`TransactionSenders`][reth_db::tables::TransactionSenders] table.
+///
+/// If [`SenderRecoveryStage::static_files`] is enabled, the senders of final blocks are appended
+/// to the [`StaticFileSegment::TransactionSenders`] static files instead, which skips writing
+/// them to the database.
 #[derive(Clone, Debug)]
 pub struct SenderRecoveryStage {
     /// The size of inserted items after which the control
     /// flow will be returned to the pipeline for commit
     pub commit_threshold: u64,
+    /// The maximum number of senders to recover per rayon worker job.
+    pub chunk_size: u64,
+    /// Whether to write the senders of final blocks to static files instead of the database.
+    ///
+    /// The finalized block isn't known to the pipeline, so blocks that are at least
+    /// [`FINALIZATION_DISTANCE`] blocks below the target of the stage are considered final. The
+    /// senders are only appended if the static files contain all senders before them, so the
+    /// blocks following senders that were written to the database are written to the database
+    /// as well.
+    pub static_files: bool,
 }
 
 impl SenderRecoveryStage {
     /// Create new instance of [SenderRecoveryStage].
     pub fn new(commit_threshold: u64) -> Self {
-        Self { commit_threshold }
+        Self { commit_threshold, ..Default::default() }
+    }
+
+    /// Create new instance of [SenderRecoveryStage] from the stage configuration.
+    pub fn new_with_config(config: SenderRecoveryConfig) -> Self {
+        Self {
+            commit_threshold: config.commit_threshold,
+            chunk_size: config.chunk_size,
+            static_files: config.static_files,
+        }
     }
 }
 
 impl Default for SenderRecoveryStage {
     fn default() -> Self {
-        Self { commit_threshold: 5_000_000 }
+        Self { commit_threshold: 5_000_000, chunk_size: WORKER_CHUNK_SIZE, static_files: false }
     }
 }
 
@@ -60,7 +97,8 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
     /// Retrieve the range of transactions to iterate over by querying
     /// [`BlockBodyIndices`][reth_db::tables::BlockBodyIndices],
     /// collect transactions within that range, recover signer for each transaction and store
-    /// entries in the [`TransactionSenders`][reth_db::tables::TransactionSenders] table.
+    /// entries in the [`TransactionSenders`][reth_db::tables::TransactionSenders] table or static
+    /// files.
     fn execute(
         &mut self,
         provider: &DatabaseProviderRW<DB>,
@@ -74,6 +112,10 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
             input.next_block_range_with_transaction_threshold(provider, self.commit_threshold)?;
         let end_block = *block_range.end();
 
+        // If the static files are ahead of the checkpoint, a previous execution committed them
+        // without committing the database, so the senders are recovered again.
+        prune_static_files_ahead(provider, *block_range.start() - 1, tx_range.start)?;
+
         // No transactions to walk over
         if tx_range.is_empty() {
             info!(target: "sync::stages::sender_recovery", ?tx_range, "Target transaction already reached");
@@ -89,16 +131,40 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
         // Acquire the cursor for inserting elements
         let mut senders_cursor = tx.cursor_write::<tables::TransactionSenders>()?;
 
+        let mut static_file_output = if self.static_files {
+            StaticFileOutput::new(
+                provider,
+                *block_range.start()..=
+                    end_block.min(input.target().saturating_sub(FINALIZATION_DISTANCE)),
+                tx_range.start,
+            )?
+        } else {
+            None
+        };
+
         // Iterate over transactions in chunks
-        info!(target: "sync::stages::sender_recovery", ?tx_range, "Recovering senders");
+        info!(
+            target: "sync::stages::sender_recovery",
+            ?tx_range,
+            static_files_tx_end = static_file_output.as_ref().map(|output| output.tx_end),
+            "Recovering senders"
+        );
 
         let batch = (tx_range.start..tx_range.end)
             .step_by(BATCH_SIZE)
             .map(|start| start..std::cmp::min(start + BATCH_SIZE as u64, tx_range.end))
             .collect::<Vec<Range<u64>>>();
 
+        let mut append_sender = |tx_id, sender| match &mut static_file_output {
+            Some(output) if tx_id < output.tx_end => output.append(tx_id, sender),
+            _ => Ok(senders_cursor.append(tx_id, sender)?),
+        };
         for range in batch {
-            recover_range(range, provider, tx, &mut senders_cursor)?;
+            recover_range(range, self.chunk_size, provider, tx, &mut append_sender)?;
+        }
+
+        if let Some(output) = static_file_output {
+            output.finish()?;
         }
 
         Ok(ExecOutput {
@@ -117,11 +183,11 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
         let (_, unwind_to, _) = input.unwind_block_range_with_threshold(self.commit_threshold);
 
         // Lookup latest tx id that we should unwind to
-        let latest_tx_id = provider
+        let body = provider
             .block_body_indices(unwind_to)?
-            .ok_or(ProviderError::BlockBodyIndicesNotFound(unwind_to))?
-            .last_tx_num();
-        provider.unwind_table_by_num::<tables::TransactionSenders>(latest_tx_id)?;
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(unwind_to))?;
+        provider.unwind_table_by_num::<tables::TransactionSenders>(body.last_tx_num())?;
+        prune_static_files_ahead(provider, unwind_to, body.next_tx_num())?;
 
         Ok(UnwindOutput {
             checkpoint: StageCheckpoint::new(unwind_to)
@@ -130,21 +196,121 @@ impl<DB: Database> Stage<DB> for SenderRecoveryStage {
     }
 }
 
+/// Removes the senders of the blocks above `last_block` from the static files, starting with the
+/// sender of transaction `next_tx`.
+fn prune_static_files_ahead<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    last_block: BlockNumber,
+    next_tx: TxNumber,
+) -> Result<(), StageError> {
+    let segment = StaticFileSegment::TransactionSenders;
+    let static_file_provider = provider.static_file_provider();
+
+    if static_file_provider
+        .get_highest_static_file_block(segment)
+        .is_some_and(|highest_block| highest_block > last_block)
+    {
+        let rows = static_file_provider
+            .get_highest_static_file_tx(segment)
+            .map_or(0, |highest_tx| (highest_tx + 1).saturating_sub(next_tx));
+        debug!(target: "sync::stages::sender_recovery", last_block, rows, "Pruning senders from static files");
+        static_file_provider.latest_writer(segment)?.prune_transaction_senders(rows, last_block)?;
+    }
+
+    Ok(())
+}
+
+/// Appends the recovered senders of final blocks to the
+/// [`StaticFileSegment::TransactionSenders`] static files.
+struct StaticFileOutput<'a> {
+    writer: StaticFileProviderRWRefMut<'a>,
+    /// The body indices of the blocks that are yet to be appended.
+    blocks: Peekable<std::vec::IntoIter<(BlockNumber, StoredBlockBodyIndices)>>,
+    /// The first transaction whose sender is written to the database instead.
+    tx_end: TxNumber,
+}
+
+impl<'a> StaticFileOutput<'a> {
+    /// Returns the output for the senders of the blocks in the range, if the static files contain
+    /// all senders before transaction `tx_start`.
+    ///
+    /// Blocks between the highest static file block and the range must have no transactions,
+    /// and are appended as well.
+    fn new<DB: Database>(
+        provider: &'a DatabaseProviderRW<DB>,
+        block_range: RangeInclusive<BlockNumber>,
+        tx_start: TxNumber,
+    ) -> Result<Option<Self>, StageError> {
+        let segment = StaticFileSegment::TransactionSenders;
+        let static_file_provider = provider.static_file_provider();
+
+        let next_static_file_block = static_file_provider
+            .get_highest_static_file_block(segment)
+            .map_or(0, |block| block + 1);
+        let next_static_file_tx =
+            static_file_provider.get_highest_static_file_tx(segment).map_or(0, |tx| tx + 1);
+
+        if block_range.is_empty() ||
+            next_static_file_block > *block_range.start() ||
+            next_static_file_tx != tx_start
+        {
+            return Ok(None)
+        }
+
+        let blocks = provider
+            .tx_ref()
+            .cursor_read::<tables::BlockBodyIndices>()?
+            .walk_range(next_static_file_block..=*block_range.end())?
+            .collect::<Result<Vec<_>, _>>()?;
+        if blocks.first().map(|(block, _)| *block) != Some(next_static_file_block) {
+            return Ok(None)
+        }
+        let tx_end = blocks.last().map(|(_, body)| body.next_tx_num()).expect("not empty");
+
+        Ok(Some(Self {
+            writer: static_file_provider.get_writer(next_static_file_block, segment)?,
+            blocks: blocks.into_iter().peekable(),
+            tx_end,
+        }))
+    }
+
+    /// Appends the sender, after the blocks up to the one of the transaction.
+    fn append(&mut self, tx_id: TxNumber, sender: Address) -> Result<(), StageError> {
+        while let Some((block, _)) = self.blocks.next_if(|(_, body)| body.first_tx_num <= tx_id) {
+            self.writer.increment_block(StaticFileSegment::TransactionSenders, block)?;
+        }
+        self.writer.append_transaction_sender(tx_id, sender)?;
+        Ok(())
+    }
+
+    /// Appends the remaining blocks, and commits the static files.
+    ///
+    /// The static files are committed right away, so that the checkpoint of the stage counts the
+    /// appended senders. If the database isn't committed afterwards, the next execution prunes
+    /// them.
+    fn finish(mut self) -> Result<(), StageError> {
+        for (block, _) in self.blocks {
+            self.writer.increment_block(StaticFileSegment::TransactionSenders, block)?;
+        }
+        self.writer.commit()?;
+        Ok(())
+    }
+}
+
 fn recover_range<DB: Database>(
     tx_range: Range<u64>,
+    chunk_size: u64,
     provider: &DatabaseProviderRW<DB>,
     tx: &<DB as Database>::TXMut,
-    senders_cursor: &mut <<DB as Database>::TXMut as DbTxMut>::CursorMut<
-        tables::TransactionSenders,
-    >,
+    append_sender: &mut impl FnMut(TxNumber, Address) -> Result<(), StageError>,
 ) -> Result<(), StageError> {
     debug!(target: "sync::stages::sender_recovery", ?tx_range, "Recovering senders batch");
 
     // Preallocate channels
     let (chunks, receivers): (Vec<_>, Vec<_>) = (tx_range.start..tx_range.end)
-        .step_by(WORKER_CHUNK_SIZE)
+        .step_by(chunk_size as usize)
         .map(|start| {
-            let range = start..std::cmp::min(start + WORKER_CHUNK_SIZE as u64, tx_range.end);
+            let range = start..std::cmp::min(start + chunk_size, tx_range.end);
             let (tx, rx) = mpsc::channel();
             // Range and channel sender will be sent to rayon worker
             ((range, tx), rx)
@@ -186,7 +352,7 @@ fn recover_range<DB: Database>(
         }
     });
 
-    debug!(target: "sync::stages::sender_recovery", ?tx_range, "Appending recovered senders to the database");
+    debug!(target: "sync::stages::sender_recovery", ?tx_range, "Appending recovered senders");
     for channel in receivers {
         while let Ok(recovered) = channel.recv() {
             let (tx_id, sender) = match recovered {
@@ -215,7 +381,7 @@ fn recover_range<DB: Database>(
                     }
                 }
             };
-            senders_cursor.append(tx_id, sender)?;
+            append_sender(tx_id, sender)?;
         }
     }
     debug!(target: "sync::stages::sender_recovery", ?tx_range, "Finished recovering senders batch");
@@ -281,16 +447,15 @@ struct FailedSenderRecoveryError {
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
-    use reth_db::cursor::DbCursorRO;
     use reth_interfaces::test_utils::{
         generators,
         generators::{random_block, random_block_range},
     };
     use reth_primitives::{
-        stage::StageUnitCheckpoint, BlockNumber, PruneCheckpoint, PruneMode, SealedBlock,
-        TransactionSigned, B256,
+        stage::StageUnitCheckpoint, PruneCheckpoint, PruneMode, SealedBlock, TransactionSigned,
+        B256,
     };
-    use reth_provider::{providers::StaticFileWriter, PruneCheckpointWriter, TransactionsProvider};
+    use reth_provider::{PruneCheckpointWriter, TransactionsProvider};
 
     use super::*;
     use crate::test_utils::{
@@ -482,6 +647,95 @@ mod tests {
         );
     }
 
+    /// Execute the stage writing the senders of final blocks to static files, and unwind it
+    #[tokio::test]
+    async fn execute_and_unwind_static_files() {
+        let mut rng = generators::rng();
+        let db = TestStageDB::default();
+        let segment = StaticFileSegment::TransactionSenders;
+        let static_file_provider = db.factory.static_file_provider();
+
+        let target = 200;
+        let final_block = target - FINALIZATION_DISTANCE;
+        let genesis = random_block(&mut rng, 0, None, Some(0), None);
+        let mut blocks = random_block_range(&mut rng, 1..=target, genesis.hash(), 0..4);
+        blocks.insert(0, genesis);
+        db.insert_blocks(blocks.iter(), StorageKind::Static).expect("insert blocks");
+        let body = |block: BlockNumber| {
+            db.factory.provider().unwrap().block_body_indices(block).unwrap().unwrap()
+        };
+
+        let mut stage = SenderRecoveryStage { static_files: true, ..Default::default() };
+        let execute = |stage: &mut SenderRecoveryStage, checkpoint: BlockNumber| {
+            let provider = db.factory.provider_rw().unwrap();
+            let input = ExecInput {
+                target: Some(target),
+                checkpoint: Some(StageCheckpoint::new(checkpoint)),
+            };
+            let output = stage.execute(&provider, input).unwrap();
+            provider.commit().unwrap();
+            output
+        };
+
+        let output = execute(&mut stage, 0);
+        assert!(output.done);
+        assert_eq!(
+            output.checkpoint.entities(),
+            Some(EntitiesCheckpoint {
+                processed: body(target).next_tx_num(),
+                total: body(target).next_tx_num()
+            })
+        );
+
+        // Only the senders of the blocks that are not final are written to the database
+        assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(final_block));
+        assert_eq!(
+            static_file_provider.get_highest_static_file_tx(segment),
+            Some(body(final_block).last_tx_num())
+        );
+        assert!(db
+            .table::<tables::TransactionSenders>()
+            .unwrap()
+            .iter()
+            .all(|(tx_id, _)| *tx_id >= body(final_block).next_tx_num()));
+
+        let provider = db.factory.provider().unwrap();
+        for (tx_id, transaction) in blocks.iter().flat_map(|block| &block.body).enumerate() {
+            assert_eq!(
+                provider.transaction_sender(tx_id as u64).unwrap(),
+                transaction.recover_signer()
+            );
+        }
+        assert_eq!(
+            provider.senders_by_tx_range(..).unwrap(),
+            blocks
+                .iter()
+                .flat_map(|block| &block.body)
+                .map(|transaction| transaction.recover_signer().unwrap())
+                .collect::<Vec<_>>()
+        );
+        drop(provider);
+
+        // Unwinding removes the senders from static files as well
+        let unwind_to = 100;
+        let provider = db.factory.provider_rw().unwrap();
+        let input = UnwindInput { checkpoint: output.checkpoint, unwind_to, bad_block: None };
+        stage.unwind(&provider, input).unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(unwind_to));
+        assert_eq!(
+            static_file_provider.get_highest_static_file_tx(segment),
+            Some(body(unwind_to).last_tx_num())
+        );
+        assert!(db.table_is_empty::<tables::TransactionSenders>().unwrap());
+
+        // Executing again appends to the static files
+        let output = execute(&mut stage, unwind_to);
+        assert!(output.done);
+        assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(final_block));
+    }
+
     struct SenderRecoveryTestRunner {
         db: TestStageDB,
         threshold: u64,
@@ -534,7 +788,7 @@ mod tests {
         }
 
         fn stage(&self) -> Self::S {
-            SenderRecoveryStage { commit_threshold: self.threshold }
+            SenderRecoveryStage { commit_threshold: self.threshold, ..Default::default() }
         }
     }
 
@@ -9,6 +9,8 @@ pub use headers::Headers;
 mod receipts;
 pub use receipts::Receipts;
 
+use rayon::prelude::*;
+use reth_codecs::Compact;
 use reth_db::{
     cursor::DbCursorRO, database::Database, table::Table, transaction::DbTx, RawKey, RawTable,
 };
@@ -19,15 +21,23 @@ use reth_primitives::{
         find_fixed_range, Compression, Filters, InclusionFilter, PerfectHashingFunction,
         SegmentConfig, SegmentHeader,
     },
-    BlockNumber, StaticFileSegment,
+    BlockNumber, StaticFileSegment, TxNumber,
 };
 use reth_provider::{
-    providers::StaticFileProvider, DatabaseProviderRO, ProviderError, TransactionsProviderExt,
+    providers::{StaticFileProvider, StaticFileWriter},
+    BlockReader, DatabaseProviderRO, ProviderError, TransactionsProviderExt,
+};
+use std::{
+    ops::{Range, RangeInclusive},
+    path::Path,
 };
-use std::{ops::RangeInclusive, path::Path};
 
 pub(crate) type Rows<const COLUMNS: usize> = [Vec<Vec<u8>>; COLUMNS];
 
+/// The number of transactions that are read from the database and compressed in parallel at
+/// once, before being appended to the static file.
+const COMPRESSION_BATCH_SIZE: u64 = 10_000;
+
 /// A segment represents moving some portion of the data to static files.
 pub trait Segment<DB: Database>: Send + Sync {
     /// Returns the [`StaticFileSegment`].
@@ -66,7 +76,9 @@ pub(crate) fn prepare_jar<DB: Database, const COLUMNS: usize>(
 ) -> ProviderResult<NippyJar<SegmentHeader>> {
     let tx_range = match segment {
         StaticFileSegment::Headers => None,
-        StaticFileSegment::Receipts | StaticFileSegment::Transactions => {
+        StaticFileSegment::Receipts |
+        StaticFileSegment::Transactions |
+        StaticFileSegment::TransactionSenders => {
             Some(provider.transaction_range_by_block_range(block_range.clone())?.into())
         }
     };
@@ -118,3 +130,68 @@ pub(crate) fn dataset_for_compression<DB: Database, T: Table<Key = u64>>(
         .map(|row| row.map(|(_key, value)| value.into_value()).expect("should exist"))
         .collect::<Vec<_>>())
 }
+
+/// Copies the rows of a tx number-based table to the static files of the segment for the provided
+/// block range.
+///
+/// Blocks are grouped into batches of at least [`COMPRESSION_BATCH_SIZE`] transactions. The rows of
+/// a batch are encoded and compressed in parallel on the current rayon thread pool, and then
+/// appended in order by the calling thread, so the produced files are the same as when appending
+/// the rows one by one. At most one batch of rows is kept in memory.
+pub(crate) fn copy_tx_based_table<DB, T>(
+    provider: &DatabaseProviderRO<DB>,
+    static_file_provider: &StaticFileProvider,
+    segment: StaticFileSegment,
+    block_range: RangeInclusive<BlockNumber>,
+) -> ProviderResult<()>
+where
+    DB: Database,
+    T: Table<Key = TxNumber>,
+    T::Value: Compact + Send,
+{
+    let mut static_file_writer = static_file_provider.get_writer(*block_range.start(), segment)?;
+    let mut cursor = provider.tx_ref().cursor_read::<T>()?;
+
+    let mut batch = Vec::<(BlockNumber, Range<TxNumber>)>::new();
+    let mut batch_transactions = 0;
+    let last_block = *block_range.end();
+
+    for block in block_range {
+        let block_body_indices = provider
+            .block_body_indices(block)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(block))?;
+        batch.push((block, block_body_indices.tx_num_range()));
+        batch_transactions += block_body_indices.tx_count;
+
+        if batch_transactions < COMPRESSION_BATCH_SIZE && block != last_block {
+            continue
+        }
+
+        let tx_range = batch[0].1.start..batch[batch.len() - 1].1.end;
+        let rows = cursor
+            .walk_range(tx_range)?
+            .collect::<Result<Vec<_>, _>>()?
+            .into_par_iter()
+            .map(|(tx_number, value)| {
+                let mut buf = Vec::new();
+                value.to_compact(&mut buf);
+                (tx_number, buf)
+            })
+            .collect::<Vec<_>>();
+        let mut rows = rows.into_iter().peekable();
+
+        for (block, tx_range) in batch.drain(..) {
+            let _static_file_block = static_file_writer.increment_block(segment, block)?;
+            debug_assert_eq!(_static_file_block, block);
+
+            while let Some((tx_number, value)) =
+                rows.next_if(|(tx_number, _)| tx_range.contains(tx_number))
+            {
+                static_file_writer.append_encoded_with_tx_number(segment, tx_number, &value)?;
+            }
+        }
+        batch_transactions = 0;
+    }
+
+    Ok(())
+}
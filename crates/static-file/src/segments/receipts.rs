@@ -1,17 +1,11 @@
-use crate::segments::{dataset_for_compression, prepare_jar, Segment};
-use reth_db::{
-    cursor::DbCursorRO, database::Database, static_file::create_static_file_T1, tables,
-    transaction::DbTx,
-};
-use reth_interfaces::provider::{ProviderError, ProviderResult};
+use crate::segments::{copy_tx_based_table, dataset_for_compression, prepare_jar, Segment};
+use reth_db::{database::Database, static_file::create_static_file_T1, tables};
+use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
     static_file::{SegmentConfig, SegmentHeader},
     BlockNumber, StaticFileSegment, TxNumber,
 };
-use reth_provider::{
-    providers::{StaticFileProvider, StaticFileWriter},
-    BlockReader, DatabaseProviderRO, TransactionsProviderExt,
-};
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRO, TransactionsProviderExt};
 use std::{ops::RangeInclusive, path::Path};
 
 /// Static File segment responsible for [StaticFileSegment::Receipts] part of data.
@@ -29,29 +23,12 @@ impl<DB: Database> Segment<DB> for Receipts {
         static_file_provider: StaticFileProvider,
         block_range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<()> {
-        let mut static_file_writer =
-            static_file_provider.get_writer(*block_range.start(), StaticFileSegment::Receipts)?;
-
-        for block in block_range {
-            let _static_file_block =
-                static_file_writer.increment_block(StaticFileSegment::Receipts, block)?;
-            debug_assert_eq!(_static_file_block, block);
-
-            let block_body_indices = provider
-                .block_body_indices(block)?
-                .ok_or(ProviderError::BlockBodyIndicesNotFound(block))?;
-
-            let mut receipts_cursor = provider.tx_ref().cursor_read::<tables::Receipts>()?;
-            let receipts_walker = receipts_cursor.walk_range(block_body_indices.tx_num_range())?;
-
-            for entry in receipts_walker {
-                let (tx_number, receipt) = entry?;
-
-                static_file_writer.append_receipt(tx_number, receipt)?;
-            }
-        }
-
-        Ok(())
+        copy_tx_based_table::<DB, tables::Receipts>(
+            &provider,
+            &static_file_provider,
+            StaticFileSegment::Receipts,
+            block_range,
+        )
     }
 
     fn create_static_file_file(
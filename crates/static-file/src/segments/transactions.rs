@@ -1,17 +1,11 @@
-use crate::segments::{dataset_for_compression, prepare_jar, Segment};
-use reth_db::{
-    cursor::DbCursorRO, database::Database, static_file::create_static_file_T1, tables,
-    transaction::DbTx,
-};
-use reth_interfaces::provider::{ProviderError, ProviderResult};
+use crate::segments::{copy_tx_based_table, dataset_for_compression, prepare_jar, Segment};
+use reth_db::{database::Database, static_file::create_static_file_T1, tables};
+use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
     static_file::{SegmentConfig, SegmentHeader},
     BlockNumber, StaticFileSegment, TxNumber,
 };
-use reth_provider::{
-    providers::{StaticFileProvider, StaticFileWriter},
-    BlockReader, DatabaseProviderRO, TransactionsProviderExt,
-};
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRO, TransactionsProviderExt};
 use std::{ops::RangeInclusive, path::Path};
 
 /// Static File segment responsible for [StaticFileSegment::Transactions] part of data.
@@ -31,31 +25,12 @@ impl<DB: Database> Segment<DB> for Transactions {
         static_file_provider: StaticFileProvider,
         block_range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<()> {
-        let mut static_file_writer = static_file_provider
-            .get_writer(*block_range.start(), StaticFileSegment::Transactions)?;
-
-        for block in block_range {
-            let _static_file_block =
-                static_file_writer.increment_block(StaticFileSegment::Transactions, block)?;
-            debug_assert_eq!(_static_file_block, block);
-
-            let block_body_indices = provider
-                .block_body_indices(block)?
-                .ok_or(ProviderError::BlockBodyIndicesNotFound(block))?;
-
-            let mut transactions_cursor =
-                provider.tx_ref().cursor_read::<tables::Transactions>()?;
-            let transactions_walker =
-                transactions_cursor.walk_range(block_body_indices.tx_num_range())?;
-
-            for entry in transactions_walker {
-                let (tx_number, transaction) = entry?;
-
-                static_file_writer.append_transaction(tx_number, transaction)?;
-            }
-        }
-
-        Ok(())
+        copy_tx_based_table::<DB, tables::Transactions>(
+            &provider,
+            &static_file_provider,
+            StaticFileSegment::Transactions,
+            block_range,
+        )
     }
 
     fn create_static_file_file(
@@ -43,6 +43,14 @@ impl<DB: Database> StaticFileProducer<DB> {
             prune_modes,
         ))))
     }
+
+    /// Sets the thread pool that compresses the rows of static files in parallel.
+    ///
+    /// By default, the global rayon thread pool is used.
+    pub fn with_compression_pool(self, pool: rayon::ThreadPool) -> Self {
+        self.0.lock().compression_pool = Some(Arc::new(pool));
+        self
+    }
 }
 
 impl<DB> Deref for StaticFileProducer<DB> {
@@ -64,11 +72,14 @@ pub struct StaticFileProducerInner<DB> {
     /// needed in [StaticFileProducerInner] to prevent attempting to move prunable data to static
     /// files. See [StaticFileProducerInner::get_static_file_targets].
     prune_modes: PruneModes,
+    /// Thread pool that runs the segments and compresses their rows in parallel. If [None], the
+    /// global rayon thread pool is used.
+    compression_pool: Option<Arc<rayon::ThreadPool>>,
     listeners: EventListeners<StaticFileProducerEvent>,
 }
 
 /// Static File targets, per data part, measured in [`BlockNumber`].
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
 pub struct StaticFileTargets {
     headers: Option<RangeInclusive<BlockNumber>>,
     receipts: Option<RangeInclusive<BlockNumber>>,
@@ -81,6 +92,15 @@ impl StaticFileTargets {
         self.headers.is_some() || self.receipts.is_some() || self.transactions.is_some()
     }
 
+    /// Returns the highest block of all targets, if any.
+    pub fn highest_block(&self) -> Option<BlockNumber> {
+        [&self.headers, &self.receipts, &self.transactions]
+            .into_iter()
+            .flatten()
+            .map(|block_range| *block_range.end())
+            .max()
+    }
+
     // Returns `true` if all targets are either [`None`] or has beginning of the range equal to the
     // highest static_file.
     fn is_contiguous_to_highest_static_files(&self, static_files: HighestStaticFiles) -> bool {
@@ -107,7 +127,13 @@ impl<DB: Database> StaticFileProducerInner<DB> {
         static_file_provider: StaticFileProvider,
         prune_modes: PruneModes,
     ) -> Self {
-        Self { provider_factory, static_file_provider, prune_modes, listeners: Default::default() }
+        Self {
+            provider_factory,
+            static_file_provider,
+            prune_modes,
+            compression_pool: None,
+            listeners: Default::default(),
+        }
     }
 
     /// Listen for events on the static_file_producer.
@@ -119,7 +145,8 @@ impl<DB: Database> StaticFileProducerInner<DB> {
     ///
     /// For each [Some] target in [StaticFileTargets], initializes a corresponding [Segment] and
     /// runs it with the provided block range using [StaticFileProvider] and a read-only
-    /// database transaction from [ProviderFactory]. All segments are run in parallel.
+    /// database transaction from [ProviderFactory]. All segments are run in parallel, and the rows
+    /// of transaction based segments are compressed in parallel batches on the compression pool.
     ///
     /// NOTE: it doesn't delete the data from database, and the actual deleting (aka pruning) logic
     /// lives in the `prune` crate.
@@ -150,25 +177,35 @@ impl<DB: Database> StaticFileProducerInner<DB> {
             segments.push((Box::new(segments::Receipts), block_range));
         }
 
-        segments.par_iter().try_for_each(|(segment, block_range)| -> RethResult<()> {
-            debug!(target: "static_file", segment = %segment.segment(), ?block_range, "StaticFileProducer segment");
-            let start = Instant::now();
+        let run_segments = || {
+            segments.par_iter().try_for_each(|(segment, block_range)| -> RethResult<()> {
+                debug!(target: "static_file", segment = %segment.segment(), ?block_range, "StaticFileProducer segment");
+                let start = Instant::now();
 
-            // Create a new database transaction on every segment to prevent long-lived read-only
-            // transactions
-            let provider = self.provider_factory.provider()?.disable_long_read_transaction_safety();
-            segment.copy_to_static_files(provider, self.static_file_provider.clone(), block_range.clone())?;
+                // Create a new database transaction on every segment to prevent long-lived
+                // read-only transactions
+                let provider = self.provider_factory.provider()?.disable_long_read_transaction_safety();
+                segment.copy_to_static_files(provider, self.static_file_provider.clone(), block_range.clone())?;
 
-            let elapsed = start.elapsed(); // TODO(alexey): track in metrics
-            debug!(target: "static_file", segment = %segment.segment(), ?block_range, ?elapsed, "Finished StaticFileProducer segment");
+                let elapsed = start.elapsed(); // TODO(alexey): track in metrics
+                debug!(target: "static_file", segment = %segment.segment(), ?block_range, ?elapsed, "Finished StaticFileProducer segment");
 
-            Ok(())
-        })?;
+                Ok(())
+            })
+        };
+        match &self.compression_pool {
+            Some(pool) => pool.install(run_segments)?,
+            None => run_segments()?,
+        }
 
         self.static_file_provider.commit()?;
         for (segment, block_range) in segments {
             self.static_file_provider.update_index(segment.segment(), Some(*block_range.end()))?;
         }
+        if targets.transactions.is_some() {
+            let indexed = self.static_file_provider.update_transaction_hash_index()?;
+            debug!(target: "static_file", indexed, "Indexed transaction hashes");
+        }
 
         let elapsed = start.elapsed(); // TODO(alexey): track in metrics
         debug!(target: "static_file", ?targets, ?elapsed, "StaticFileProducer finished");
@@ -314,6 +351,7 @@ mod tests {
                 headers: Some(1),
                 receipts: Some(1),
                 transactions: Some(1),
+                transaction_senders: None,
             })
             .expect("get static file targets");
         assert_eq!(
@@ -327,7 +365,12 @@ mod tests {
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             static_file_provider.get_highest_static_files(),
-            HighestStaticFiles { headers: Some(1), receipts: Some(1), transactions: Some(1) }
+            HighestStaticFiles {
+                headers: Some(1),
+                receipts: Some(1),
+                transactions: Some(1),
+                transaction_senders: None
+            }
         );
 
         let targets = static_file_producer
@@ -335,6 +378,7 @@ mod tests {
                 headers: Some(3),
                 receipts: Some(3),
                 transactions: Some(3),
+                transaction_senders: None,
             })
             .expect("get static file targets");
         assert_eq!(
@@ -348,7 +392,12 @@ mod tests {
         assert_matches!(static_file_producer.run(targets), Ok(_));
         assert_eq!(
             static_file_provider.get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                transaction_senders: None
+            }
         );
 
         let targets = static_file_producer
@@ -356,6 +405,7 @@ mod tests {
                 headers: Some(4),
                 receipts: Some(4),
                 transactions: Some(4),
+                transaction_senders: None,
             })
             .expect("get static file targets");
         assert_eq!(
@@ -372,7 +422,12 @@ mod tests {
         );
         assert_eq!(
             static_file_provider.get_highest_static_files(),
-            HighestStaticFiles { headers: Some(3), receipts: Some(3), transactions: Some(3) }
+            HighestStaticFiles {
+                headers: Some(3),
+                receipts: Some(3),
+                transactions: Some(3),
+                transaction_senders: None
+            }
         );
     }
 
@@ -401,6 +456,7 @@ mod tests {
                         headers: Some(1),
                         receipts: Some(1),
                         transactions: Some(1),
+                        transaction_senders: None,
                     })
                     .expect("get static file targets");
                 assert_matches!(locked_producer.run(targets.clone()), Ok(_));
@@ -1,5 +1,6 @@
 use crate::{
     abstraction::common::Sealed,
+    database_metrics::DatabaseMetadata,
     table::TableImporter,
     transaction::{DbTx, DbTxMut},
     DatabaseError,
@@ -9,7 +10,7 @@ use std::{fmt::Debug, sync::Arc};
 /// Main Database trait that can open read-only and read-write transactions.
 ///
 /// Sealed trait which cannot be implemented by 3rd parties, exposed only for consumption.
-pub trait Database: Send + Sync + Sealed {
+pub trait Database: DatabaseMetadata + Send + Sync + Sealed {
     /// Read-Only database transaction
     type TX: DbTx + Send + Sync + Debug + 'static;
     /// Read-Write database transaction
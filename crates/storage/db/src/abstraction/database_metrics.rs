@@ -46,18 +46,31 @@ impl<DB: DatabaseMetrics> DatabaseMetrics for Arc<DB> {
 pub struct DatabaseMetadataValue {
     /// The freelist size
     freelist_size: Option<usize>,
+    /// The size of the used pages, in bytes
+    size: Option<usize>,
 }
 
 impl DatabaseMetadataValue {
     /// Creates a new [DatabaseMetadataValue] with the given freelist size.
     pub fn new(freelist_size: Option<usize>) -> Self {
-        Self { freelist_size }
+        Self { freelist_size, size: None }
+    }
+
+    /// Sets the size of the used pages of the database, in bytes.
+    pub fn with_size(mut self, size: Option<usize>) -> Self {
+        self.size = size;
+        self
     }
 
     /// Returns the freelist size, if available.
     pub fn freelist_size(&self) -> Option<usize> {
         self.freelist_size
     }
+
+    /// Returns the size of the used pages of the database in bytes, if available.
+    pub fn size(&self) -> Option<usize> {
+        self.size
+    }
 }
 
 /// Includes a method to return a [DatabaseMetadataValue] type, which can be used to dynamically
@@ -72,3 +85,9 @@ impl<DB: DatabaseMetadata> DatabaseMetadata for Arc<DB> {
         <DB as DatabaseMetadata>::metadata(self)
     }
 }
+
+impl<DB: DatabaseMetadata> DatabaseMetadata for &DB {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        <DB as DatabaseMetadata>::metadata(self)
+    }
+}
@@ -7,6 +7,7 @@ use crate::{
         ReverseWalker, Walker,
     },
     database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue},
     table::{DupSort, Table, TableImporter},
     transaction::{DbTx, DbTxMut},
     DatabaseError,
@@ -34,6 +35,12 @@ impl Database for DatabaseMock {
     }
 }
 
+impl DatabaseMetadata for DatabaseMock {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        DatabaseMetadataValue::default()
+    }
+}
+
 /// Mock read only tx
 #[derive(Debug, Clone, Default)]
 pub struct TxMock {
@@ -68,6 +75,10 @@ impl DbTx for TxMock {
     }
 
     fn disable_long_read_transaction_safety(&mut self) {}
+
+    fn snapshot_id(&self) -> Option<u64> {
+        None
+    }
 }
 
 impl DbTxMut for TxMock {
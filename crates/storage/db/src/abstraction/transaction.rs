@@ -26,6 +26,10 @@ pub trait DbTx: Send + Sync {
     fn entries<T: Table>(&self) -> Result<usize, DatabaseError>;
     /// Disables long-lived read transaction safety guarantees.
     fn disable_long_read_transaction_safety(&mut self);
+    /// Returns the id of the transaction, which for a read only transaction identifies the
+    /// snapshot of the database it reads, and changes with every committed read-write
+    /// transaction. Returns `None` if the id is unknown.
+    fn snapshot_id(&self) -> Option<u64>;
 }
 
 /// Read write transaction that allows writing to database
@@ -57,6 +57,22 @@ impl<K: TransactionKind, T: Table> Cursor<K, T> {
             f(self)
         }
     }
+
+    /// If `self.metrics` is `Some(...)` and records the latency of operations, record a metric
+    /// with the provided read operation and the size of the read value returned by the closure.
+    ///
+    /// Otherwise, just execute the closure.
+    #[inline]
+    fn execute_with_read_operation_metric<R>(
+        &mut self,
+        operation: Operation,
+        f: impl FnOnce(&mut Self) -> (R, Option<usize>),
+    ) -> R {
+        match self.metrics.as_ref().filter(|metrics| metrics.records_operation_latency()).cloned() {
+            Some(metrics) => metrics.record_read_operation(T::TABLE, operation, || f(self)),
+            None => f(self).0,
+        }
+    }
 }
 
 /// Decodes a `(key, value)` pair from the database.
@@ -72,6 +88,27 @@ where
     res.map_err(|e| DatabaseError::Read(e.into()))?.map(decoder::<T>).transpose()
 }
 
+/// Decodes a `(key, value)` pair from the database with [decode], along with the size of the
+/// value.
+fn decode_with_size<T>(
+    res: Result<Option<(Cow<'_, [u8]>, Cow<'_, [u8]>)>, MDBXError>,
+) -> (PairResult<T>, Option<usize>)
+where
+    T: Table,
+    T::Key: Decode,
+    T::Value: Decompress,
+{
+    let value_size = pair_value_size(&res);
+    (decode::<T>(res), value_size)
+}
+
+/// Returns the size of the value of a `(key, value)` pair read from the database.
+fn pair_value_size(
+    res: &Result<Option<(Cow<'_, [u8]>, Cow<'_, [u8]>)>, MDBXError>,
+) -> Option<usize> {
+    res.as_ref().ok().and_then(Option::as_ref).map(|(_, value)| value.len())
+}
+
 /// Some types don't support compression (eg. B256), and we don't want to be copying them to the
 /// allocated buffer when we can just use their reference.
 macro_rules! compress_to_buf_or_ref {
@@ -88,27 +125,39 @@ macro_rules! compress_to_buf_or_ref {
 
 impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
     fn first(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.first())
+        self.execute_with_read_operation_metric(Operation::CursorSeek, |cursor| {
+            decode_with_size::<T>(cursor.inner.first())
+        })
     }
 
     fn seek_exact(&mut self, key: <T as Table>::Key) -> PairResult<T> {
-        decode::<T>(self.inner.set_key(key.encode().as_ref()))
+        self.execute_with_read_operation_metric(Operation::CursorSeek, |cursor| {
+            decode_with_size::<T>(cursor.inner.set_key(key.encode().as_ref()))
+        })
     }
 
     fn seek(&mut self, key: <T as Table>::Key) -> PairResult<T> {
-        decode::<T>(self.inner.set_range(key.encode().as_ref()))
+        self.execute_with_read_operation_metric(Operation::CursorSeek, |cursor| {
+            decode_with_size::<T>(cursor.inner.set_range(key.encode().as_ref()))
+        })
     }
 
     fn next(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.next())
+        self.execute_with_read_operation_metric(Operation::CursorNext, |cursor| {
+            decode_with_size::<T>(cursor.inner.next())
+        })
     }
 
     fn prev(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.prev())
+        self.execute_with_read_operation_metric(Operation::CursorNext, |cursor| {
+            decode_with_size::<T>(cursor.inner.prev())
+        })
     }
 
     fn last(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.last())
+        self.execute_with_read_operation_metric(Operation::CursorSeek, |cursor| {
+            decode_with_size::<T>(cursor.inner.last())
+        })
     }
 
     fn current(&mut self) -> PairResult<T> {
@@ -117,7 +166,7 @@ impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
 
     fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
         let start = if let Some(start_key) = start_key {
-            decode::<T>(self.inner.set_range(start_key.encode().as_ref())).transpose()
+            self.seek(start_key).transpose()
         } else {
             self.first().transpose()
         };
@@ -130,13 +179,13 @@ impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
         range: impl RangeBounds<T::Key>,
     ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
         let start = match range.start_bound().cloned() {
-            Bound::Included(key) => self.inner.set_range(key.encode().as_ref()),
+            Bound::Included(key) => self.seek(key),
             Bound::Excluded(_key) => {
                 unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
             }
-            Bound::Unbounded => self.inner.first(),
-        };
-        let start = decode::<T>(start).transpose();
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
         Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
     }
 
@@ -145,11 +194,10 @@ impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
         start_key: Option<T::Key>,
     ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
         let start = if let Some(start_key) = start_key {
-            decode::<T>(self.inner.set_range(start_key.encode().as_ref()))
+            self.seek(start_key).transpose()
         } else {
-            self.last()
-        }
-        .transpose();
+            self.last().transpose()
+        };
 
         Ok(ReverseWalker::new(self, start))
     }
@@ -158,21 +206,28 @@ impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
 impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
     /// Returns the next `(key, value)` pair of a DUPSORT table.
     fn next_dup(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.next_dup())
+        self.execute_with_read_operation_metric(Operation::CursorNext, |cursor| {
+            decode_with_size::<T>(cursor.inner.next_dup())
+        })
     }
 
     /// Returns the next `(key, value)` pair skipping the duplicates.
     fn next_no_dup(&mut self) -> PairResult<T> {
-        decode::<T>(self.inner.next_nodup())
+        self.execute_with_read_operation_metric(Operation::CursorNext, |cursor| {
+            decode_with_size::<T>(cursor.inner.next_nodup())
+        })
     }
 
     /// Returns the next `value` of a duplicate `key`.
     fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
-        self.inner
-            .next_dup()
-            .map_err(|e| DatabaseError::Read(e.into()))?
-            .map(decode_value::<T>)
-            .transpose()
+        self.execute_with_read_operation_metric(Operation::CursorNext, |cursor| {
+            let res = cursor.inner.next_dup();
+            let value_size = pair_value_size(&res);
+            let value = res
+                .map_err(|e| DatabaseError::Read(e.into()))
+                .and_then(|kv| kv.map(decode_value::<T>).transpose());
+            (value, value_size)
+        })
     }
 
     fn seek_by_key_subkey(
@@ -180,11 +235,16 @@ impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
         key: <T as Table>::Key,
         subkey: <T as DupSort>::SubKey,
     ) -> ValueOnlyResult<T> {
-        self.inner
-            .get_both_range(key.encode().as_ref(), subkey.encode().as_ref())
-            .map_err(|e| DatabaseError::Read(e.into()))?
-            .map(decode_one::<T>)
-            .transpose()
+        self.execute_with_read_operation_metric(Operation::CursorSeek, |cursor| {
+            let res = cursor
+                .inner
+                .get_both_range::<Cow<'_, [u8]>>(key.encode().as_ref(), subkey.encode().as_ref());
+            let value_size = res.as_ref().ok().and_then(Option::as_ref).map(|value| value.len());
+            let value = res
+                .map_err(|e| DatabaseError::Read(e.into()))
+                .and_then(|value| value.map(decode_one::<T>).transpose());
+            (value, value_size)
+        })
     }
 
     /// Depending on its arguments, returns an iterator starting at:
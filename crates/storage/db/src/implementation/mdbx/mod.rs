@@ -87,6 +87,9 @@ pub struct DatabaseArguments {
     ///
     /// This flag affects only at environment opening but can't be changed after.
     exclusive: Option<bool>,
+    /// Record the latency and value sizes of all database operations per table, if metrics are
+    /// enabled with [DatabaseEnv::with_metrics].
+    operation_latency_metrics: bool,
 }
 
 impl DatabaseArguments {
@@ -97,6 +100,7 @@ impl DatabaseArguments {
             log_level: None,
             max_read_transaction_duration: None,
             exclusive: None,
+            operation_latency_metrics: false,
         }
     }
 
@@ -121,6 +125,12 @@ impl DatabaseArguments {
         self
     }
 
+    /// Set whether the latency and value sizes of all database operations are recorded.
+    pub fn with_operation_latency_metrics(mut self, operation_latency_metrics: bool) -> Self {
+        self.operation_latency_metrics = operation_latency_metrics;
+        self
+    }
+
     /// Returns the client version if any.
     pub fn client_version(&self) -> &ClientVersion {
         &self.client_version
@@ -134,6 +144,9 @@ pub struct DatabaseEnv {
     inner: Environment,
     /// Cache for metric handles. If `None`, metrics are not recorded.
     metrics: Option<Arc<DatabaseEnvMetrics>>,
+    /// Whether the latency and value sizes of all operations are recorded when metrics are
+    /// enabled.
+    operation_latency_metrics: bool,
 }
 
 impl Database for DatabaseEnv {
@@ -238,7 +251,13 @@ impl DatabaseMetrics for DatabaseEnv {
 
 impl DatabaseMetadata for DatabaseEnv {
     fn metadata(&self) -> DatabaseMetadataValue {
-        DatabaseMetadataValue::new(self.freelist().ok())
+        // pgno is 0 based
+        let size = self
+            .info()
+            .ok()
+            .zip(self.stat().ok())
+            .map(|(info, stat)| (info.last_pgno() + 1) * stat.page_size() as usize);
+        DatabaseMetadataValue::new(self.freelist().ok()).with_size(size)
     }
 }
 
@@ -382,14 +401,18 @@ impl DatabaseEnv {
         let env = DatabaseEnv {
             inner: inner_env.open(path).map_err(|e| DatabaseError::Open(e.into()))?,
             metrics: None,
+            operation_latency_metrics: args.operation_latency_metrics,
         };
 
         Ok(env)
     }
 
     /// Enables metrics on the database.
+    ///
+    /// The latency and value sizes of all operations are only recorded if enabled with
+    /// [DatabaseArguments::with_operation_latency_metrics].
     pub fn with_metrics(mut self) -> Self {
-        self.metrics = Some(DatabaseEnvMetrics::new().into());
+        self.metrics = Some(DatabaseEnvMetrics::new(self.operation_latency_metrics).into());
         self
     }
 
@@ -446,7 +469,7 @@ impl Deref for DatabaseEnv {
 mod tests {
     use super::*;
     use crate::{
-        abstraction::table::{Encode, Table},
+        abstraction::table::{Compress, Encode, Table},
         cursor::{DbDupCursorRO, DbDupCursorRW, ReverseWalker, Walker},
         models::{AccountBeforeTx, ShardedKey},
         tables::{
@@ -455,6 +478,7 @@ mod tests {
         test_utils::*,
         AccountChangeSets,
     };
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder, Snapshotter};
     use reth_interfaces::db::{DatabaseWriteError, DatabaseWriteOperation};
     use reth_libmdbx::Error;
     use reth_primitives::{Account, Address, Header, IntegerList, StorageEntry, B256, U256};
@@ -536,6 +560,91 @@ mod tests {
         assert_eq!(first.1, value, "First next should be put value");
     }
 
+    #[test]
+    fn db_operation_latency_metrics() {
+        // the metrics of this thread are recorded, this is the only test that installs a recorder
+        DebuggingRecorder::per_thread().install().unwrap();
+
+        let path = tempfile::TempDir::new().expect(ERROR_TEMPDIR).into_path();
+        let args =
+            DatabaseArguments::new(ClientVersion::default()).with_operation_latency_metrics(true);
+        let env = DatabaseEnv::open(&path, DatabaseEnvKind::RW, args)
+            .expect(ERROR_DB_CREATION)
+            .with_metrics();
+        env.create_tables().expect(ERROR_TABLE_CREATION);
+        assert!(env.metrics.as_ref().unwrap().records_operation_latency());
+
+        let address = Address::with_last_byte(1);
+        let entries = [
+            StorageEntry { key: B256::with_last_byte(1), value: U256::from(1) },
+            StorageEntry { key: B256::with_last_byte(2), value: U256::from(2) },
+        ];
+        let tx = env.tx_mut().expect(ERROR_INIT_TX);
+        tx.put::<CanonicalHeaders>(1, B256::ZERO).expect(ERROR_PUT);
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        for entry in entries {
+            cursor.upsert(address, entry).expect(ERROR_UPSERT);
+        }
+        drop(cursor);
+        tx.commit().expect(ERROR_COMMIT);
+
+        // the instrumented reads return the same results
+        let tx = env.tx().expect(ERROR_INIT_TX);
+        assert_eq!(tx.get::<CanonicalHeaders>(1).expect(ERROR_GET), Some(B256::ZERO));
+        assert_eq!(tx.get::<CanonicalHeaders>(2).expect(ERROR_GET), None);
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        assert_eq!(cursor.seek_exact(1), Ok(Some((1, B256::ZERO))));
+        assert_eq!(cursor.next(), Ok(None));
+        let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+        assert_eq!(cursor.seek_by_key_subkey(address, entries[0].key), Ok(Some(entries[0])));
+        assert_eq!(cursor.next_dup_val(), Ok(Some(entries[1])));
+        assert_eq!(cursor.next_dup_val(), Ok(None));
+
+        let snapshot = Snapshotter::current_thread_snapshot().unwrap().into_vec();
+        let histogram = |name: &str, table: &str, operation: &str| {
+            snapshot
+                .iter()
+                .find_map(|(key, _, _, value)| {
+                    let key = key.key();
+                    let labels =
+                        key.labels().map(|label| (label.key(), label.value())).collect::<Vec<_>>();
+                    let matches = key.name() == format!("database.operation.{name}") &&
+                        labels.contains(&("table", table)) &&
+                        labels.contains(&("operation", operation));
+                    match value {
+                        DebugValue::Histogram(values) if matches => {
+                            Some(values.iter().map(|value| value.into_inner()).collect::<Vec<_>>())
+                        }
+                        _ => None,
+                    }
+                })
+                .unwrap_or_default()
+        };
+        let durations = |table, operation| histogram("duration_seconds", table, operation).len();
+        let value_sizes = |table, operation| histogram("value_size_bytes", table, operation);
+
+        // every operation records its latency, and the size of the value if there's one
+        assert_eq!(durations(CanonicalHeaders::NAME, "put"), 1);
+        assert_eq!(value_sizes(CanonicalHeaders::NAME, "put"), vec![32.0]);
+        assert_eq!(durations(CanonicalHeaders::NAME, "get"), 2);
+        assert_eq!(value_sizes(CanonicalHeaders::NAME, "get"), vec![32.0]);
+        assert_eq!(durations(CanonicalHeaders::NAME, "cursor-seek"), 1);
+        assert_eq!(value_sizes(CanonicalHeaders::NAME, "cursor-seek"), vec![32.0]);
+        assert_eq!(durations(CanonicalHeaders::NAME, "cursor-next"), 1);
+        assert_eq!(value_sizes(CanonicalHeaders::NAME, "cursor-next"), Vec::<f64>::new());
+
+        let entry_sizes = entries.map(|entry| entry.compress().as_ref().len() as f64).to_vec();
+        assert_eq!(durations(PlainStorageState::NAME, "cursor-upsert"), 2);
+        assert_eq!(value_sizes(PlainStorageState::NAME, "cursor-upsert"), entry_sizes);
+        assert_eq!(durations(PlainStorageState::NAME, "cursor-seek"), 1);
+        assert_eq!(value_sizes(PlainStorageState::NAME, "cursor-seek"), entry_sizes[..1].to_vec());
+        assert_eq!(durations(PlainStorageState::NAME, "cursor-next"), 2);
+        assert_eq!(value_sizes(PlainStorageState::NAME, "cursor-next"), entry_sizes[1..].to_vec());
+
+        // nothing is recorded for the operations that weren't made
+        assert_eq!(durations(CanonicalHeaders::NAME, "delete"), 0);
+    }
+
     #[test]
     fn db_cursor_walk_range() {
         let db: Arc<DatabaseEnv> = create_test_db(DatabaseEnvKind::RW);
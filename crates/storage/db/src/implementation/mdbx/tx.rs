@@ -14,6 +14,7 @@ use reth_libmdbx::{ffi::DBI, CommitLatency, Transaction, TransactionKind, WriteF
 use reth_tracing::tracing::{debug, trace, warn};
 use std::{
     backtrace::Backtrace,
+    borrow::Cow,
     marker::PhantomData,
     sync::{
         atomic::{AtomicBool, Ordering},
@@ -175,6 +176,27 @@ impl<K: TransactionKind> Tx<K> {
             f(&self.inner)
         }
     }
+
+    /// If `self.metrics_handler == Some(_)`, record a metric with the provided read operation and
+    /// the size of the read value returned by the closure.
+    ///
+    /// Otherwise, just execute the closure.
+    fn execute_with_read_operation_metric<T: Table, R>(
+        &self,
+        operation: Operation,
+        f: impl FnOnce(&Transaction<K>) -> (R, Option<usize>),
+    ) -> R {
+        if let Some(metrics_handler) = &self.metrics_handler {
+            metrics_handler.log_backtrace_on_long_read_transaction();
+            metrics_handler.env_metrics.record_read_operation(
+                T::TABLE,
+                operation,
+                || f(&self.inner),
+            )
+        } else {
+            f(&self.inner).0
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -279,11 +301,13 @@ impl<K: TransactionKind> DbTx for Tx<K> {
     type DupCursor<T: DupSort> = Cursor<K, T>;
 
     fn get<T: Table>(&self, key: T::Key) -> Result<Option<<T as Table>::Value>, DatabaseError> {
-        self.execute_with_operation_metric::<T, _>(Operation::Get, None, |tx| {
-            tx.get(self.get_dbi::<T>()?, key.encode().as_ref())
-                .map_err(|e| DatabaseError::Read(e.into()))?
-                .map(decode_one::<T>)
-                .transpose()
+        self.execute_with_read_operation_metric::<T, _>(Operation::Get, |tx| {
+            let value = self.get_dbi::<T>().and_then(|dbi| {
+                tx.get::<Cow<'_, [u8]>>(dbi, key.encode().as_ref())
+                    .map_err(|e| DatabaseError::Read(e.into()))
+            });
+            let value_size = value.as_ref().ok().and_then(Option::as_ref).map(|value| value.len());
+            (value.and_then(|value| value.map(decode_one::<T>).transpose()), value_size)
         })
     }
 
@@ -330,6 +354,10 @@ impl<K: TransactionKind> DbTx for Tx<K> {
 
         self.inner.disable_timeout();
     }
+
+    fn snapshot_id(&self) -> Option<u64> {
+        self.id().ok()
+    }
 }
 
 impl DbTxMut for Tx<RW> {
@@ -68,6 +68,7 @@ pub mod abstraction;
 
 mod implementation;
 mod metrics;
+pub mod migration;
 pub mod static_file;
 pub mod tables;
 mod utils;
@@ -28,22 +28,36 @@ pub struct DatabaseEnvMetrics {
     /// outcome. Can only be updated at tx close, as outcome is only known at that point.
     transaction_outcomes:
         FxHashMap<(TransactionMode, TransactionOutcome), TransactionOutcomeMetrics>,
+    /// Caches OperationLatencyMetrics handles for each table and operation tuple. If `None`, the
+    /// latency and value sizes of operations are not recorded.
+    operation_latencies: Option<FxHashMap<(Tables, Operation), OperationLatencyMetrics>>,
 }
 
 impl DatabaseEnvMetrics {
-    pub(crate) fn new() -> Self {
+    /// Creates the metric handles. If `operation_latency` is `true`, the latency and value sizes
+    /// of all operations are recorded as well.
+    pub(crate) fn new(operation_latency: bool) -> Self {
         // Pre-populate metric handle maps with all possible combinations of labels
         // to avoid runtime locks on the map when recording metrics.
         Self {
-            operations: Self::generate_operation_handles(),
+            operations: Self::generate_operation_handles(|labels| {
+                OperationMetrics::new_with_labels(labels)
+            }),
             transactions: Self::generate_transaction_handles(),
             transaction_outcomes: Self::generate_transaction_outcome_handles(),
+            operation_latencies: operation_latency.then(|| {
+                Self::generate_operation_handles(|labels| {
+                    OperationLatencyMetrics::new_with_labels(labels)
+                })
+            }),
         }
     }
 
     /// Generate a map of all possible operation handles for each table and operation tuple.
     /// Used for tracking all operation metrics.
-    fn generate_operation_handles() -> FxHashMap<(Tables, Operation), OperationMetrics> {
+    fn generate_operation_handles<M>(
+        new_with_labels: impl Fn(&[(&'static str, &'static str); 2]) -> M,
+    ) -> FxHashMap<(Tables, Operation), M> {
         let mut operations = FxHashMap::with_capacity_and_hasher(
             Tables::COUNT * Operation::COUNT,
             BuildHasherDefault::<FxHasher>::default(),
@@ -52,7 +66,7 @@ impl DatabaseEnvMetrics {
             for operation in Operation::iter() {
                 operations.insert(
                     (*table, operation),
-                    OperationMetrics::new_with_labels(&[
+                    new_with_labels(&[
                         (Labels::Table.as_str(), table.name()),
                         (Labels::Operation.as_str(), operation.as_str()),
                     ]),
@@ -109,10 +123,63 @@ impl DatabaseEnvMetrics {
         value_size: Option<usize>,
         f: impl FnOnce() -> R,
     ) -> R {
-        self.operations
+        let metrics = self
+            .operations
             .get(&(table, operation))
-            .expect("operation & table metric handle not found")
-            .record(value_size, f)
+            .expect("operation & table metric handle not found");
+        match self.operation_latency_metrics(table, operation) {
+            Some(latency_metrics) => {
+                let start = Instant::now();
+                let result = metrics.record(value_size, f);
+                latency_metrics.record(start.elapsed(), value_size);
+                result
+            }
+            None => metrics.record(value_size, f),
+        }
+    }
+
+    /// Record a metric for database read operation executed in `f`, which returns the size of the
+    /// read value along with its result.
+    /// Panics if a metric recorder is not found for the given table and operation.
+    pub(crate) fn record_read_operation<R>(
+        &self,
+        table: Tables,
+        operation: Operation,
+        f: impl FnOnce() -> (R, Option<usize>),
+    ) -> R {
+        let metrics = self
+            .operations
+            .get(&(table, operation))
+            .expect("operation & table metric handle not found");
+        match self.operation_latency_metrics(table, operation) {
+            Some(latency_metrics) => {
+                let start = Instant::now();
+                let (result, value_size) = metrics.record(None, f);
+                latency_metrics.record(start.elapsed(), value_size);
+                result
+            }
+            None => metrics.record(None, f).0,
+        }
+    }
+
+    /// Returns `true` if the latency and value sizes of operations are recorded.
+    pub(crate) fn records_operation_latency(&self) -> bool {
+        self.operation_latencies.is_some()
+    }
+
+    /// Returns the latency metric handles of the given table and operation, if the latency of
+    /// operations is recorded.
+    /// Panics if a metric recorder is not found for the given table and operation.
+    fn operation_latency_metrics(
+        &self,
+        table: Tables,
+        operation: Operation,
+    ) -> Option<&OperationLatencyMetrics> {
+        self.operation_latencies.as_ref().map(|operation_latencies| {
+            operation_latencies
+                .get(&(table, operation))
+                .expect("operation & table latency metric handle not found")
+        })
     }
 
     /// Record metrics for opening a database transaction.
@@ -215,6 +282,10 @@ pub(crate) enum Operation {
     CursorDeleteCurrent,
     /// Database cursor delete current duplicates operation.
     CursorDeleteCurrentDuplicates,
+    /// Database cursor seek operation: first, last, seek, seek exact and seek by key and subkey.
+    CursorSeek,
+    /// Database cursor next operation: next, prev, next dup, next no dup and next dup value.
+    CursorNext,
 }
 
 impl Operation {
@@ -230,6 +301,8 @@ impl Operation {
             Operation::CursorAppendDup => "cursor-append-dup",
             Operation::CursorDeleteCurrent => "cursor-delete-current",
             Operation::CursorDeleteCurrentDuplicates => "cursor-delete-current-duplicates",
+            Operation::CursorSeek => "cursor-seek",
+            Operation::CursorNext => "cursor-next",
         }
     }
 }
@@ -359,3 +432,22 @@ impl OperationMetrics {
         }
     }
 }
+
+#[derive(Metrics, Clone)]
+#[metrics(scope = "database.operation")]
+pub(crate) struct OperationLatencyMetrics {
+    /// The time it took to execute a database operation
+    duration_seconds: Histogram,
+    /// The size of the value that was written or read by a database operation
+    value_size_bytes: Histogram,
+}
+
+impl OperationLatencyMetrics {
+    /// Record the duration of an operation and the size of its value, if any.
+    pub(crate) fn record(&self, duration: Duration, value_size: Option<usize>) {
+        self.duration_seconds.record(duration);
+        if let Some(value_size) = value_size {
+            self.value_size_bytes.record(value_size as f64);
+        }
+    }
+}
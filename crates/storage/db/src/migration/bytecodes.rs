@@ -0,0 +1,33 @@
+use super::{MigrationError, TableMigration};
+use crate::tables;
+use reth_primitives::{keccak256, Bytecode, B256};
+
+/// Stores the bytecodes of [`tables::Bytecodes`] together with their analysis.
+///
+/// The bytecodes that were created during execution are stored analysed, padded and together with
+/// the jump table of their valid jump destinations, but the bytecodes of the genesis and of state
+/// dumps were stored raw and analysed again whenever they were loaded for execution. The migration
+/// analyses the raw bytecodes, which are written analysed since.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnalyseBytecodes;
+
+impl TableMigration for AnalyseBytecodes {
+    const VERSION: u64 = 1;
+    const DESCRIPTION: &'static str = "store the bytecodes together with their analysis";
+
+    type Source = tables::Bytecodes;
+    type Target = tables::Bytecodes;
+
+    fn transform(
+        &self,
+        code_hash: B256,
+        bytecode: Bytecode,
+    ) -> Result<Option<(B256, Bytecode)>, MigrationError> {
+        if keccak256(bytecode.original_bytes()) != code_hash {
+            return Err(MigrationError::Transform(format!(
+                "the bytecode of {code_hash} doesn't match its code hash"
+            )))
+        }
+        Ok(Some((code_hash, bytecode.into_analysed())))
+    }
+}
@@ -0,0 +1,498 @@
+//! Versioned migrations of the database tables.
+//!
+//! A [`TableMigration`] transforms the decoded entries of a source table into the entries of a
+//! target table. Every migration has a unique version, and the migrations are run in the order of
+//! their versions by [`visit_migrations`].
+//!
+//! The progress of a migration is stored in [`tables::Migrations`] under its version. Entries are
+//! migrated in batches, each committed in its own write transaction together with the progress,
+//! so an interrupted migration resumes after the last committed batch. Finished migrations are
+//! recorded and never run again.
+
+use crate::{
+    cursor::DbCursorRO,
+    database::Database,
+    models::migration::MigrationCheckpoint,
+    table::{Compress, Table},
+    tables,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError, RawKey, RawTable, RawValue, Tables,
+};
+use reth_tracing::tracing::info;
+use std::ops::Bound;
+
+mod bytecodes;
+pub use bytecodes::AnalyseBytecodes;
+
+/// The default number of entries migrated in a single write transaction.
+pub const DEFAULT_MIGRATION_BATCH_SIZE: usize = 10_000;
+
+/// A migration transforming the entries of a table.
+pub trait TableMigration {
+    /// The version of the migration, increasing with every new migration.
+    const VERSION: u64;
+
+    /// A short description of the migration.
+    const DESCRIPTION: &'static str;
+
+    /// The table whose entries are migrated. It must not be a `DUPSORT` table.
+    type Source: Table;
+
+    /// The table the migrated entries are written to.
+    ///
+    /// If it's the source table, the entries are rewritten in place and must keep their keys.
+    /// Otherwise, the source table is cleared once all of its entries were migrated.
+    type Target: Table;
+
+    /// Transforms an entry of the source table into an entry of the target table, or returns
+    /// `None` to drop the entry.
+    #[allow(clippy::type_complexity)]
+    fn transform(
+        &self,
+        key: <Self::Source as Table>::Key,
+        value: <Self::Source as Table>::Value,
+    ) -> Result<
+        Option<(<Self::Target as Table>::Key, <Self::Target as Table>::Value)>,
+        MigrationError,
+    >;
+}
+
+/// Operates on the migrations of the database in a generic way, see [`visit_migrations`].
+pub trait MigrationVisitor {
+    /// The error type returned by the visitor.
+    type Error;
+
+    /// Operate on the migration in a generic way.
+    fn visit<M: TableMigration>(&mut self, migration: &M) -> Result<(), Self::Error>;
+}
+
+/// Visits all migrations of the database in the order of their versions, stopping at the first
+/// error.
+pub fn visit_migrations<V: MigrationVisitor>(visitor: &mut V) -> Result<(), V::Error> {
+    visitor.visit(&AnalyseBytecodes)
+}
+
+/// An error of a migration.
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    /// The database failed.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    /// The source table of the migration is a `DUPSORT` table.
+    #[error("migration {version} can't migrate the DUPSORT table {table}")]
+    DupSortSource {
+        /// The version of the migration.
+        version: u64,
+        /// The source table of the migration.
+        table: Tables,
+    },
+    /// The transform of an in-place migration changed the key of an entry.
+    #[error("migration {version} changed the key {key:?} of an entry of table {table}")]
+    KeyChanged {
+        /// The version of the migration.
+        version: u64,
+        /// The table that's migrated in place.
+        table: Tables,
+        /// The raw key of the entry.
+        key: Vec<u8>,
+    },
+    /// A migrated entry doesn't decode to the same entry once it's written.
+    #[error("migration {version} wrote an entry of table {table} that can't be read back")]
+    Roundtrip {
+        /// The version of the migration.
+        version: u64,
+        /// The target table of the migration.
+        table: Tables,
+    },
+    /// The transform rejected an entry.
+    #[error("{0}")]
+    Transform(String),
+}
+
+/// The status of a migration of the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// The version of the migration.
+    pub version: u64,
+    /// The description of the migration.
+    pub description: &'static str,
+    /// The table whose entries are migrated.
+    pub source: Tables,
+    /// The table the migrated entries are written to.
+    pub target: Tables,
+    /// The stored progress of the migration, if it was started.
+    pub checkpoint: Option<MigrationCheckpoint>,
+}
+
+impl MigrationStatus {
+    /// Returns `true` if the migration didn't finish yet.
+    pub fn is_pending(&self) -> bool {
+        !self.checkpoint.as_ref().is_some_and(|checkpoint| checkpoint.finished)
+    }
+}
+
+/// The result of validating a migration over a sample of entries, see [`Migrator::dry_run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DryRunReport {
+    /// The number of sampled entries of the source table.
+    pub sampled: usize,
+    /// The number of sampled entries that would be dropped.
+    pub dropped: usize,
+    /// The encoded size of the sampled entries.
+    pub source_bytes: usize,
+    /// The encoded size of the migrated sampled entries.
+    pub target_bytes: usize,
+}
+
+/// Runs the migrations of a database in batched write transactions.
+#[derive(Debug)]
+pub struct Migrator<'a, DB> {
+    db: &'a DB,
+    batch_size: usize,
+}
+
+impl<'a, DB: Database> Migrator<'a, DB> {
+    /// Creates a new migrator of the database, migrating `batch_size` entries per transaction.
+    pub fn new(db: &'a DB, batch_size: usize) -> Self {
+        Self { db, batch_size: batch_size.max(1) }
+    }
+
+    /// Returns the status of all migrations of the database, in the order of their versions.
+    pub fn statuses(&self) -> Result<Vec<MigrationStatus>, DatabaseError> {
+        let tx = self.db.tx()?;
+        let mut visitor = StatusVisitor { tx: &tx, statuses: Vec::new() };
+        visit_migrations(&mut visitor)?;
+        Ok(visitor.statuses)
+    }
+
+    /// Runs the migration until all entries are migrated, resuming from its stored progress.
+    ///
+    /// Returns the final progress of the migration, which is returned right away if the migration
+    /// finished before.
+    pub fn run<M: TableMigration>(
+        &self,
+        migration: &M,
+    ) -> Result<MigrationCheckpoint, MigrationError> {
+        ensure_migratable::<M>()?;
+
+        loop {
+            let tx = self.db.tx_mut()?;
+            let mut checkpoint = tx.get::<tables::Migrations>(M::VERSION)?.unwrap_or_default();
+            if checkpoint.finished {
+                return Ok(checkpoint)
+            }
+
+            let batch = read_batch::<M, _>(&tx, migration, &checkpoint, self.batch_size)?;
+            let finished = batch.len() < self.batch_size;
+            write_batch::<M, _>(&tx, batch, &mut checkpoint)?;
+
+            if finished {
+                if M::Source::TABLE != M::Target::TABLE {
+                    tx.clear::<M::Source>()?;
+                }
+                checkpoint.finished = true;
+            }
+            tx.put::<tables::Migrations>(M::VERSION, checkpoint.clone())?;
+            tx.commit()?;
+
+            info!(
+                target: "db::migration",
+                version = M::VERSION,
+                entries = checkpoint.entries,
+                finished,
+                "Migrated batch"
+            );
+            if finished {
+                return Ok(checkpoint)
+            }
+        }
+    }
+
+    /// Validates the migration over up to `sample` of the entries that are not migrated yet,
+    /// without writing to the database.
+    ///
+    /// Every sampled entry is transformed and its encoding is checked to decode to the same
+    /// entry.
+    pub fn dry_run<M: TableMigration>(
+        &self,
+        migration: &M,
+        sample: usize,
+    ) -> Result<DryRunReport, MigrationError> {
+        ensure_migratable::<M>()?;
+
+        let tx = self.db.tx()?;
+        let checkpoint = tx.get::<tables::Migrations>(M::VERSION)?.unwrap_or_default();
+        let mut report = DryRunReport::default();
+        if checkpoint.finished {
+            return Ok(report)
+        }
+
+        for (source_key, source_size, entry) in
+            read_batch::<M, _>(&tx, migration, &checkpoint, sample)?
+        {
+            report.sampled += 1;
+            report.source_bytes += source_size;
+
+            let Some((key, value)) = entry else {
+                report.dropped += 1;
+                continue
+            };
+            let key = RawKey::<<M::Target as Table>::Key>::new(key);
+            ensure_same_key::<M>(&source_key, &key)?;
+            let value = RawValue::<<M::Target as Table>::Value>::new(value);
+            let roundtrip = value.value()?.compress();
+            if roundtrip.as_ref() != value.raw_value() || key.key().is_err() {
+                return Err(MigrationError::Roundtrip {
+                    version: M::VERSION,
+                    table: M::Target::TABLE,
+                })
+            }
+            report.target_bytes += key.raw_key().len() + value.raw_value().len();
+        }
+
+        Ok(report)
+    }
+}
+
+/// A transformed entry of a migration, with the raw key and the encoded size of its source entry.
+type MigratedEntry<M> = (
+    RawKey<<<M as TableMigration>::Source as Table>::Key>,
+    usize,
+    Option<(
+        <<M as TableMigration>::Target as Table>::Key,
+        <<M as TableMigration>::Target as Table>::Value,
+    )>,
+);
+
+/// Returns an error if the migration can't be run by the [`Migrator`].
+fn ensure_migratable<M: TableMigration>() -> Result<(), MigrationError> {
+    if M::Source::TABLE.is_dupsort() {
+        return Err(MigrationError::DupSortSource { version: M::VERSION, table: M::Source::TABLE })
+    }
+    Ok(())
+}
+
+/// Returns an error if the migration is in place and the key was changed.
+fn ensure_same_key<M: TableMigration>(
+    source: &RawKey<<M::Source as Table>::Key>,
+    target: &RawKey<<M::Target as Table>::Key>,
+) -> Result<(), MigrationError> {
+    if M::Source::TABLE == M::Target::TABLE && source.raw_key() != target.raw_key() {
+        return Err(MigrationError::KeyChanged {
+            version: M::VERSION,
+            table: M::Source::TABLE,
+            key: source.raw_key().clone(),
+        })
+    }
+    Ok(())
+}
+
+/// Reads and transforms up to `limit` entries of the source table after the last migrated entry.
+///
+/// The entries are collected before they're written, so the source table isn't modified while
+/// it's walked.
+fn read_batch<M: TableMigration, TX: DbTx>(
+    tx: &TX,
+    migration: &M,
+    checkpoint: &MigrationCheckpoint,
+    limit: usize,
+) -> Result<Vec<MigratedEntry<M>>, MigrationError> {
+    let start = match &checkpoint.last_key {
+        Some(key) => Bound::Excluded(RawKey::from_vec(key.to_vec())),
+        None => Bound::Unbounded,
+    };
+
+    let mut cursor = tx.cursor_read::<RawTable<M::Source>>()?;
+    let mut batch = Vec::new();
+    for entry in cursor.walk_range((start, Bound::Unbounded))?.take(limit) {
+        let (key, value) = entry?;
+        let size = key.raw_key().len() + value.raw_value().len();
+        let entry = migration.transform(key.key()?, value.value()?)?;
+        batch.push((key, size, entry));
+    }
+    Ok(batch)
+}
+
+/// Writes the transformed entries to the target table and advances the checkpoint past them.
+fn write_batch<M: TableMigration, TX: DbTxMut>(
+    tx: &TX,
+    batch: Vec<MigratedEntry<M>>,
+    checkpoint: &mut MigrationCheckpoint,
+) -> Result<(), MigrationError> {
+    let in_place = M::Source::TABLE == M::Target::TABLE;
+    for (source_key, _, entry) in batch {
+        match entry {
+            Some((key, value)) => {
+                let key = RawKey::new(key);
+                ensure_same_key::<M>(&source_key, &key)?;
+                tx.put::<RawTable<M::Target>>(key, RawValue::new(value))?;
+            }
+            None if in_place => {
+                tx.delete::<RawTable<M::Source>>(source_key.clone(), None)?;
+            }
+            None => {}
+        }
+        checkpoint.entries += 1;
+        checkpoint.last_key = Some(source_key.into_key().into());
+    }
+    Ok(())
+}
+
+/// Collects the status of every visited migration.
+struct StatusVisitor<'a, TX> {
+    tx: &'a TX,
+    statuses: Vec<MigrationStatus>,
+}
+
+impl<TX: DbTx> MigrationVisitor for StatusVisitor<'_, TX> {
+    type Error = DatabaseError;
+
+    fn visit<M: TableMigration>(&mut self, _: &M) -> Result<(), Self::Error> {
+        self.statuses.push(MigrationStatus {
+            version: M::VERSION,
+            description: M::DESCRIPTION,
+            source: M::Source::TABLE,
+            target: M::Target::TABLE,
+            checkpoint: self.tx.get::<tables::Migrations>(M::VERSION)?,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::create_test_rw_db;
+    use reth_primitives::{
+        keccak256, revm_primitives::BytecodeState, BlockNumber, Bytecode, Bytes, B256,
+    };
+
+    /// Inserts raw bytecodes and returns their sorted code hashes.
+    fn insert_bytecodes<DB: Database>(db: &DB, count: u8) -> Vec<B256> {
+        let tx = db.tx_mut().unwrap();
+        let mut hashes = Vec::new();
+        for i in 0..count {
+            let code = [0x60, i, 0x5b];
+            hashes.push(keccak256(code));
+            tx.put::<tables::Bytecodes>(
+                keccak256(code),
+                Bytecode::new_raw(Bytes::copy_from_slice(&code)),
+            )
+            .unwrap();
+        }
+        tx.commit().unwrap();
+        hashes.sort();
+        hashes
+    }
+
+    fn is_analysed<DB: Database>(db: &DB, hash: B256) -> bool {
+        let bytecode = db.tx().unwrap().get::<tables::Bytecodes>(hash).unwrap().unwrap();
+        assert_eq!(keccak256(bytecode.original_bytes()), hash);
+        matches!(bytecode.state(), BytecodeState::Analysed { .. })
+    }
+
+    /// Moves the canonical block hashes into the block numbers by hash.
+    struct MoveCanonicalHashes;
+
+    impl TableMigration for MoveCanonicalHashes {
+        const VERSION: u64 = u64::MAX;
+        const DESCRIPTION: &'static str = "move the canonical hashes";
+
+        type Source = tables::CanonicalHeaders;
+        type Target = tables::HeaderNumbers;
+
+        fn transform(
+            &self,
+            number: BlockNumber,
+            hash: B256,
+        ) -> Result<Option<(B256, BlockNumber)>, MigrationError> {
+            Ok((number % 2 == 0).then_some((hash, number)))
+        }
+    }
+
+    #[test]
+    fn analyse_bytecodes_in_batches() {
+        let db = create_test_rw_db();
+        let hashes = insert_bytecodes(&db, 5);
+        let migrator = Migrator::new(&db, 2);
+        assert!(migrator.statuses().unwrap()[0].is_pending());
+
+        let checkpoint = migrator.run(&AnalyseBytecodes).unwrap();
+        assert_eq!(
+            checkpoint,
+            MigrationCheckpoint {
+                entries: 5,
+                finished: true,
+                last_key: Some(hashes[4].to_vec().into())
+            }
+        );
+        assert!(hashes.iter().all(|hash| is_analysed(&db, *hash)));
+        assert!(!migrator.statuses().unwrap()[0].is_pending());
+
+        // finished migrations are not run again
+        insert_bytecodes(&db, 6);
+        assert_eq!(migrator.run(&AnalyseBytecodes).unwrap(), checkpoint);
+    }
+
+    #[test]
+    fn resume_migration() {
+        let db = create_test_rw_db();
+        let hashes = insert_bytecodes(&db, 4);
+        let tx = db.tx_mut().unwrap();
+        let checkpoint = MigrationCheckpoint {
+            entries: 2,
+            finished: false,
+            last_key: Some(hashes[1].to_vec().into()),
+        };
+        tx.put::<tables::Migrations>(AnalyseBytecodes::VERSION, checkpoint).unwrap();
+        tx.commit().unwrap();
+
+        let checkpoint = Migrator::new(&db, 10).run(&AnalyseBytecodes).unwrap();
+        assert_eq!(checkpoint.entries, 4);
+        assert!(checkpoint.finished);
+        assert!(!is_analysed(&db, hashes[0]) && !is_analysed(&db, hashes[1]));
+        assert!(is_analysed(&db, hashes[2]) && is_analysed(&db, hashes[3]));
+    }
+
+    #[test]
+    fn migrate_into_other_table() {
+        let db = create_test_rw_db();
+        let tx = db.tx_mut().unwrap();
+        for number in 0..5 {
+            tx.put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8)).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let checkpoint = Migrator::new(&db, 3).run(&MoveCanonicalHashes).unwrap();
+        assert_eq!(checkpoint.entries, 5);
+
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.entries::<tables::CanonicalHeaders>().unwrap(), 0);
+        let mut cursor = tx.cursor_read::<tables::HeaderNumbers>().unwrap();
+        let numbers = cursor.walk(None).unwrap().map(|entry| entry.unwrap().1).collect::<Vec<_>>();
+        assert_eq!(numbers, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn dry_run_validates_sample() {
+        let db = create_test_rw_db();
+        let hashes = insert_bytecodes(&db, 3);
+        let migrator = Migrator::new(&db, 10);
+
+        let report = migrator.dry_run(&AnalyseBytecodes, 2).unwrap();
+        assert_eq!(report.sampled, 2);
+        assert_eq!(report.dropped, 0);
+        assert!(report.target_bytes > report.source_bytes);
+        assert!(hashes.iter().all(|hash| !is_analysed(&db, *hash)));
+        assert!(migrator.statuses().unwrap()[0].checkpoint.is_none());
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<tables::Bytecodes>(B256::ZERO, Bytecode::new_raw(Bytes::from_static(&[0x00])))
+            .unwrap();
+        tx.commit().unwrap();
+        assert!(matches!(
+            migrator.dry_run(&AnalyseBytecodes, 1),
+            Err(MigrationError::Transform(_))
+        ));
+    }
+}
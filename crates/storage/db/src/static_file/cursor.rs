@@ -4,7 +4,7 @@ use derive_more::{Deref, DerefMut};
 use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_nippy_jar::{DataReader, NippyJar, NippyJarCursor};
 use reth_primitives::{static_file::SegmentHeader, B256};
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
 /// Cursor of a static file segment.
 #[derive(Debug, Deref, DerefMut)]
@@ -25,6 +25,15 @@ impl<'a> StaticFileCursor<'a> {
         self.jar().user_header().start().map(|start| self.row_index() + start)
     }
 
+    /// Hints the OS to read ahead the rows of the given range of `BlockNumber` or `TxNumber`
+    /// depending on the kind of static file segment, because they're about to be read in order.
+    pub fn prefetch(&self, range: Range<u64>) -> ProviderResult<()> {
+        let Some(start) = self.jar().user_header().start() else { return Ok(()) };
+        let rows =
+            range.start.saturating_sub(start) as usize..range.end.saturating_sub(start) as usize;
+        self.prefetch_rows(rows).map_err(|err| ProviderError::NippyJar(err.to_string()))
+    }
+
     /// Gets a row of values.
     pub fn get(
         &mut self,
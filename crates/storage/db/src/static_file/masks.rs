@@ -1,9 +1,9 @@
-use super::{ReceiptMask, TransactionMask};
+use super::{ReceiptMask, TransactionMask, TransactionSenderMask};
 use crate::{
     add_static_file_mask,
     static_file::mask::{ColumnSelectorOne, ColumnSelectorTwo, HeaderMask},
     table::Table,
-    HeaderTerminalDifficulties, RawValue, Receipts, Transactions,
+    HeaderTerminalDifficulties, RawValue, Receipts, TransactionSenders, Transactions,
 };
 use reth_primitives::{BlockHash, Header};
 
@@ -20,3 +20,6 @@ add_static_file_mask!(ReceiptMask, <Receipts as Table>::Value, 0b1);
 // TRANSACTION MASKS
 add_static_file_mask!(TransactionMask, <Transactions as Table>::Value, 0b1);
 add_static_file_mask!(TransactionMask, RawValue<<Transactions as Table>::Value>, 0b1);
+
+// TRANSACTION SENDER MASKS
+add_static_file_mask!(TransactionSenderMask, <TransactionSenders as Table>::Value, 0b1);
@@ -1,5 +1,5 @@
 use crate::{
-    models::client_version::ClientVersion,
+    models::{client_version::ClientVersion, migration::MigrationCheckpoint},
     table::{Compress, Decompress},
     tables::models::*,
 };
@@ -50,7 +50,8 @@ impl_compression_for_compact!(
     CompactU256,
     StageCheckpoint,
     PruneCheckpoint,
-    ClientVersion
+    ClientVersion,
+    MigrationCheckpoint
 );
 
 macro_rules! impl_compression_fixed_compact {
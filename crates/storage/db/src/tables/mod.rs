@@ -32,6 +32,7 @@ use crate::{
             accounts::{AccountBeforeTx, BlockNumberAddress},
             blocks::{HeaderHash, StoredBlockOmmers},
             client_version::ClientVersion,
+            migration::MigrationCheckpoint,
             storage_sharded_key::StorageShardedKey,
             ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals,
         },
@@ -332,6 +333,33 @@ tables! {
     /// Code example can be found in `reth_provider::HistoricalStateProviderRef`
     table StoragesHistory<Key = StorageShardedKey, Value = BlockNumberList>;
 
+    /// Stores the numbers of the blocks in which an address is the sender of a call, the creator
+    /// of a contract or a self-destructed contract.
+    ///
+    /// Only populated by the optional `IndexCallTraces` stage. The shards have the same layout as
+    /// the shards of [`AccountsHistory`], with the last shard keyed by `u64::MAX`.
+    table CallFromIndex<Key = ShardedKey<Address>, Value = BlockNumberList>;
+
+    /// Stores the numbers of the blocks in which an address is the recipient of a call, a created
+    /// contract, the refund address of a self-destruct or the beneficiary of a block or ommer.
+    ///
+    /// Only populated by the optional `IndexCallTraces` stage. The shards have the same layout as
+    /// the shards of [`AccountsHistory`], with the last shard keyed by `u64::MAX`.
+    table CallToIndex<Key = ShardedKey<Address>, Value = BlockNumberList>;
+
+    /// Stores the `from` addresses of the call traces of the blocks that were executed by the
+    /// execution stage, until they're added to [`CallFromIndex`].
+    ///
+    /// Only populated if the optional `IndexCallTraces` stage is enabled.
+    table BlockCallFromAddresses<Key = BlockNumber, Value = Address, SubKey = Address>;
+
+    /// Stores the `to` addresses of the call traces of the blocks that were executed by the
+    /// execution stage, until they're added to [`CallToIndex`]. Every recorded block has at least
+    /// its beneficiary.
+    ///
+    /// Only populated if the optional `IndexCallTraces` stage is enabled.
+    table BlockCallToAddresses<Key = BlockNumber, Value = Address, SubKey = Address>;
+
     /// Stores the state of an account before a certain transaction changed it.
     /// Change on state can be: account is created, selfdestructed, touched while empty
     /// or changed balance,nonce.
@@ -376,6 +404,11 @@ tables! {
 
     /// Stores the history of client versions that have accessed the database with write privileges by unix timestamp in seconds.
     table VersionHistory<Key = u64, Value = ClientVersion>;
+
+    /// Stores the progress of the schema migrations of the database by migration version.
+    ///
+    /// See [`migration`](crate::migration) for the migrations run by `reth db migrate`.
+    table Migrations<Key = u64, Value = MigrationCheckpoint>;
 }
 
 // Alias types.
@@ -0,0 +1,16 @@
+//! Migration progress model.
+
+use reth_codecs::{main_codec, Compact};
+use reth_primitives::Bytes;
+
+/// The progress of a migration of the database schema, stored by migration version.
+#[main_codec]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MigrationCheckpoint {
+    /// The number of entries of the source table that were migrated.
+    pub entries: u64,
+    /// Whether all entries were migrated.
+    pub finished: bool,
+    /// The raw key of the last migrated entry of the source table, if any.
+    pub last_key: Option<Bytes>,
+}
@@ -6,6 +6,7 @@ use crate::{
     table::{Decode, Encode},
     DatabaseError,
 };
+use reth_codecs::derive_arbitrary;
 use reth_primitives::BlockNumber;
 use serde::{Deserialize, Serialize};
 
@@ -18,6 +19,7 @@ pub const NUM_OF_INDICES_IN_SHARD: usize = 2_000;
 /// `Address | 200` -> data is from block 0 to 200.
 ///
 /// `Address | 300` -> data is from block 201 to 300.
+#[derive_arbitrary]
 #[derive(Debug, Default, Clone, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
 pub struct ShardedKey<T> {
     /// The key for this type.
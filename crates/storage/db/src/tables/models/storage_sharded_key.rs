@@ -5,6 +5,7 @@ use crate::{
     DatabaseError,
 };
 use derive_more::AsRef;
+use reth_codecs::derive_arbitrary;
 use reth_primitives::{Address, BlockNumber, B256};
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +20,7 @@ pub const NUM_OF_INDICES_IN_SHARD: usize = 2_000;
 /// `Address | Storagekey | 200` -> data is from transition 0 to 200.
 ///
 /// `Address | StorageKey | 300` -> data is from transition 201 to 300.
+#[derive_arbitrary]
 #[derive(
     Debug, Default, Clone, Eq, Ord, PartialOrd, PartialEq, AsRef, Serialize, Deserialize, Hash,
 )]
@@ -154,3 +154,29 @@ fn test_freelist() {
     freelist = env.freelist().unwrap();
     assert!(freelist > 0);
 }
+
+#[test]
+fn test_copy() {
+    let dir = tempdir().unwrap();
+    let env = Environment::builder().open(dir.path()).unwrap();
+
+    let tx = env.begin_rw_txn().expect("begin_rw_txn");
+    let db = tx.open_db(None).unwrap();
+    for i in 0..64u64 {
+        tx.put(db.dbi(), i.to_be_bytes(), i.to_be_bytes(), WriteFlags::default()).unwrap();
+    }
+    tx.commit().expect("tx.commit");
+
+    for compact in [false, true] {
+        let dest = tempdir().unwrap();
+        env.copy(&dest.path().join("mdbx.dat"), compact).unwrap();
+
+        let copy = Environment::builder().open(dest.path()).unwrap();
+        assert_eq!(copy.stat().unwrap().entries(), 64);
+    }
+
+    // the destination must not exist
+    let dest = tempdir().unwrap();
+    std::fs::write(dest.path().join("mdbx.dat"), []).unwrap();
+    assert!(env.copy(&dest.path().join("mdbx.dat"), true).is_err());
+}
@@ -67,6 +67,19 @@ impl<'a, H: NippyJarHeader> NippyJarCursor<'a, H> {
         self.row = 0;
     }
 
+    /// Hints the OS to read ahead the values of the given range of rows, because they're about to
+    /// be read in order.
+    ///
+    /// Rows beyond the end of the jar are ignored.
+    pub fn prefetch_rows(&self, rows: Range<usize>) -> Result<(), NippyJarError> {
+        let end = rows.end.min(self.jar.rows);
+        if rows.start >= end {
+            return Ok(())
+        }
+        let columns = self.jar.columns;
+        self.reader.prefetch(rows.start * columns..end * columns)
+    }
+
     /// Returns a row, searching it by a key used during [`NippyJar::prepare_index`].
     ///
     /// **May return false positives.**
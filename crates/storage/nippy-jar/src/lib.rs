@@ -10,6 +10,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use memmap2::Mmap;
+use reth_primitives::static_file::AccessPattern;
 use serde::{Deserialize, Serialize};
 use std::{
     error::Error as StdError,
@@ -252,6 +253,16 @@ impl<H: NippyJarHeader> NippyJar<H> {
         Ok(obj)
     }
 
+    /// Deserializes [`Self`] from the contents of its configuration file, with the given data file
+    /// path, like [`Self::load`].
+    ///
+    /// **The user must ensure the header type matches the one used during the jar's creation.**
+    pub fn load_from_config(path: &Path, config: &[u8]) -> Result<Self, NippyJarError> {
+        let mut obj: Self = bincode::deserialize(config)?;
+        obj.path = path.to_path_buf();
+        Ok(obj)
+    }
+
     /// Loads filters into memory.
     pub fn load_filters(&mut self) -> Result<(), NippyJarError> {
         // Read the offsets lists located at the index file.
@@ -545,13 +556,73 @@ impl DataReader {
     pub fn size(&self) -> usize {
         self.data_mmap.len()
     }
+
+    /// Hints the OS how the data and offsets are going to be accessed, e.g. to stop reading ahead
+    /// pages that random lookups never use.
+    ///
+    /// The hint applies to every cursor sharing this reader. It's a no-op on non-unix platforms.
+    pub fn advise(&self, pattern: AccessPattern) -> Result<(), NippyJarError> {
+        #[cfg(unix)]
+        {
+            let advice = match pattern {
+                AccessPattern::Normal => memmap2::Advice::Normal,
+                AccessPattern::Random => memmap2::Advice::Random,
+                AccessPattern::Sequential => memmap2::Advice::Sequential,
+            };
+            self.data_mmap.advise(advice)?;
+            self.offset_mmap.advise(advice)?;
+        }
+        #[cfg(not(unix))]
+        let _ = pattern;
+
+        Ok(())
+    }
+
+    /// Hints the OS to read ahead the values of the given range of value indices and their offsets,
+    /// because they're about to be read.
+    ///
+    /// Indices beyond the end of the file are ignored. It's a no-op on non-unix platforms.
+    pub fn prefetch(&self, indices: Range<usize>) -> Result<(), NippyJarError> {
+        let offset_size = self.offset_size as usize;
+        // the offset of a value is stored after the byte of the offset size
+        let has_offset = |index: usize| 1 + (index + 1) * offset_size <= self.offset_mmap.len();
+        if indices.is_empty() || !has_offset(indices.start) {
+            return Ok(())
+        }
+
+        let data_start = self.offset(indices.start) as usize;
+        let data_end =
+            if has_offset(indices.end) { self.offset(indices.end) as usize } else { self.size() };
+        let offsets_start = 1 + indices.start * offset_size;
+        let offsets_end = (1 + indices.end * offset_size).min(self.offset_mmap.len());
+
+        #[cfg(unix)]
+        {
+            if data_start < data_end {
+                self.data_mmap.advise_range(
+                    memmap2::Advice::WillNeed,
+                    data_start,
+                    data_end - data_start,
+                )?;
+            }
+            self.offset_mmap.advise_range(
+                memmap2::Advice::WillNeed,
+                offsets_start,
+                offsets_end - offsets_start,
+            )?;
+        }
+        #[cfg(not(unix))]
+        let _ = (data_start, data_end, offsets_start, offsets_end);
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use rand::{rngs::SmallRng, seq::SliceRandom, RngCore, SeedableRng};
-    use std::{collections::HashSet, fs::OpenOptions};
+    use std::{collections::HashSet, fs::OpenOptions, sync::Arc};
 
     type ColumnResults<T> = Vec<ColumnResult<T>>;
     type ColumnValues = Vec<Vec<u8>>;
@@ -1041,6 +1112,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_access_hints() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let num_columns = 2;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        NippyJar::new_without_header(num_columns, file_path.path())
+            .freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows)
+            .unwrap();
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        let reader = Arc::new(loaded_nippy.open_data_reader().unwrap());
+        let mut cursor = NippyJarCursor::with_reader(&loaded_nippy, reader.clone()).unwrap();
+
+        for pattern in [AccessPattern::Random, AccessPattern::Sequential, AccessPattern::Normal] {
+            reader.advise(pattern).unwrap();
+        }
+        // ranges that reach or start beyond the end of the jar are clamped
+        cursor.prefetch_rows(0..10).unwrap();
+        cursor.prefetch_rows(90..200).unwrap();
+        cursor.prefetch_rows(200..300).unwrap();
+        reader.prefetch(0..0).unwrap();
+
+        // the hints don't change the data
+        let mut row_index = 0;
+        while let Some(row) = cursor.next_row().unwrap() {
+            assert_eq!((row[0], row[1]), (col1[row_index].as_slice(), col2[row_index].as_slice()));
+            row_index += 1;
+        }
+        assert_eq!(row_index, num_rows as usize);
+    }
+
     #[test]
     fn test_writer() {
         let (col1, col2) = test_data(None);
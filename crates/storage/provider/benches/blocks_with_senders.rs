@@ -0,0 +1,60 @@
+#![allow(missing_docs)]
+use criterion::{criterion_group, criterion_main, Criterion};
+use reth_interfaces::test_utils::generators::{self, random_block_range};
+use reth_primitives::{StaticFileSegment, B256};
+use reth_provider::{
+    providers::StaticFileWriter, test_utils::create_test_provider_factory, BlockReader,
+    TransactionVariant,
+};
+
+/// The number of blocks that are read.
+const RANGE: u64 = 1000;
+
+/// Compares reading the blocks of a range with their senders one block at a time against reading
+/// the whole range at once.
+fn blocks_with_senders(c: &mut Criterion) {
+    let factory = create_test_provider_factory();
+    let mut rng = generators::rng();
+    let blocks = random_block_range(&mut rng, 0..=RANGE, B256::ZERO, 0..50);
+
+    let provider = factory.provider_rw().unwrap();
+    for block in blocks {
+        provider.insert_historical_block(block.try_seal_with_senders().unwrap(), None).unwrap();
+    }
+    provider
+        .static_file_provider()
+        .latest_writer(StaticFileSegment::Headers)
+        .unwrap()
+        .commit()
+        .unwrap();
+    provider.commit().unwrap();
+
+    let mut group = c.benchmark_group("Blocks with senders");
+    group.sample_size(10);
+
+    group.bench_function("block_with_senders", |b| {
+        b.iter(|| {
+            let provider = factory.provider().unwrap();
+            (1..=RANGE)
+                .map(|number| {
+                    provider.block_with_senders(number.into(), TransactionVariant::NoHash).unwrap()
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+
+    group.bench_function("blocks_with_senders_range", |b| {
+        b.iter(|| {
+            factory
+                .provider()
+                .unwrap()
+                .blocks_with_senders_range(1..=RANGE, TransactionVariant::NoHash)
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, blocks_with_senders);
+criterion_main!(benches);
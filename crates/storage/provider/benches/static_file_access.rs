@@ -0,0 +1,79 @@
+#![allow(missing_docs)]
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::seq::SliceRandom;
+use reth_interfaces::test_utils::generators::{self, random_header_range};
+use reth_primitives::{static_file::AccessPattern, StaticFileSegment, B256, U256};
+use reth_provider::{
+    providers::{StaticFileProvider, StaticFileWriter},
+    HeaderProvider,
+};
+
+/// The number of headers that are written to the static files.
+const HEADERS: u64 = 10_000;
+
+/// The number of headers that are read by a range walk.
+const RANGE: u64 = 1_000;
+
+/// Compares point lookups and range walks of headers with the access patterns the OS can be
+/// advised of for the mapped static files.
+///
+/// The static files are in the page cache after the first iteration, so this only measures the
+/// overhead of the hints. Their effect on cold reads requires dropping the page cache between
+/// iterations, e.g. with `echo 1 > /proc/sys/vm/drop_caches`.
+fn static_file_access(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let mut rng = generators::rng();
+    let headers = random_header_range(&mut rng, 0..HEADERS, B256::ZERO);
+    {
+        let provider = StaticFileProvider::new(dir.path()).unwrap();
+        let mut writer = provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        for header in headers {
+            let hash = header.hash();
+            writer.append_header(header.unseal(), U256::ZERO, hash).unwrap();
+        }
+        writer.commit().unwrap();
+    }
+
+    let mut numbers = (0..HEADERS).collect::<Vec<_>>();
+    numbers.shuffle(&mut rng);
+
+    let mut group = c.benchmark_group("Static file access");
+    group.sample_size(10);
+
+    for access_pattern in [
+        None,
+        Some(AccessPattern::Normal),
+        Some(AccessPattern::Random),
+        Some(AccessPattern::Sequential),
+    ] {
+        let name = access_pattern
+            .map_or("default".to_string(), |access_pattern| access_pattern.as_ref().to_string());
+        let mut provider = StaticFileProvider::new(dir.path()).unwrap();
+        if let Some(access_pattern) = access_pattern {
+            provider = provider.with_access_pattern(access_pattern);
+        }
+
+        group.bench_function(format!("{name}/header_by_number"), |b| {
+            b.iter(|| {
+                numbers
+                    .iter()
+                    .map(|number| provider.header_by_number(*number).unwrap())
+                    .collect::<Vec<_>>()
+            })
+        });
+
+        group.bench_function(format!("{name}/headers_range"), |b| {
+            b.iter(|| {
+                (0..HEADERS)
+                    .step_by(RANGE as usize)
+                    .map(|start| provider.headers_range(start..start + RANGE).unwrap())
+                    .collect::<Vec<_>>()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, static_file_access);
+criterion_main!(benches);
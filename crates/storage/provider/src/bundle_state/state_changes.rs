@@ -45,7 +45,8 @@ impl StateChanges {
         tracing::trace!(target: "provider::bundle_state", len = self.0.contracts.len(), "Writing bytecodes");
         let mut bytecodes_cursor = tx.cursor_write::<tables::Bytecodes>()?;
         for (hash, bytecode) in self.0.contracts.into_iter() {
-            bytecodes_cursor.upsert(hash, Bytecode(bytecode))?;
+            // the bytecodes that weren't created during execution, e.g. of the genesis, are raw
+            bytecodes_cursor.upsert(hash, Bytecode(bytecode).into_analysed())?;
         }
 
         // Write new storage state and wipe storage if needed.
@@ -1,17 +1,26 @@
 use crate::{
-    providers::{state::latest::LatestStateProvider, StaticFileProvider},
+    providers::{state::latest::LatestStateProvider, HistoryShardCache, StaticFileProvider},
     to_range,
     traits::{BlockSource, ReceiptProvider},
-    BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode,
-    ProviderError, PruneCheckpointReader, StageCheckpointReader, StateProviderBox,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    BlockHashReader, BlockNumReader, BlockReader, CallTraceIndexReader, ChainSpecProvider,
+    DatabaseMetadataReader, DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, HeaderSyncGap,
+    HeaderSyncGapProvider, HeaderSyncMode, ProviderError, PruneCheckpointReader,
+    StageCheckpointReader, StateProviderBox, StaticFileProviderFactory, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
+};
+use reth_db::{
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue},
+    init_db,
+    models::StoredBlockBodyIndices,
+    transaction::DbTx,
+    DatabaseEnv,
 };
-use reth_db::{database::Database, init_db, models::StoredBlockBodyIndices, DatabaseEnv};
 use reth_evm::ConfigureEvmEnv;
 use reth_interfaces::{provider::ProviderResult, RethError, RethResult};
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
+    static_file::{AccessPattern, Compression},
     Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, ChainInfo,
     ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders,
     SealedHeader, StaticFileSegment, TransactionMeta, TransactionSigned, TransactionSignedNoHash,
@@ -19,6 +28,7 @@ use reth_primitives::{
 };
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
+    collections::HashSet,
     ops::{RangeBounds, RangeInclusive},
     path::{Path, PathBuf},
     sync::Arc,
@@ -42,6 +52,8 @@ pub struct ProviderFactory<DB> {
     chain_spec: Arc<ChainSpec>,
     /// Static File Provider
     static_file_provider: StaticFileProvider,
+    /// Cache of the history shards resolved by the historical state providers.
+    history_cache: HistoryShardCache,
 }
 
 impl<DB> ProviderFactory<DB> {
@@ -55,6 +67,7 @@ impl<DB> ProviderFactory<DB> {
             db,
             chain_spec,
             static_file_provider: StaticFileProvider::new(static_files_path)?,
+            history_cache: HistoryShardCache::default(),
         })
     }
 
@@ -64,6 +77,47 @@ impl<DB> ProviderFactory<DB> {
         self
     }
 
+    /// Sets the compression of newly created static files of the given segments.
+    pub fn with_static_files_compression(
+        mut self,
+        compression: impl IntoIterator<Item = (StaticFileSegment, Compression)>,
+    ) -> Self {
+        for (segment, compression) in compression {
+            self.static_file_provider =
+                self.static_file_provider.with_compression(segment, compression);
+        }
+        self
+    }
+
+    /// Sets the access pattern the OS is advised of for the mapped static files, see
+    /// [StaticFileProvider::with_access_pattern].
+    pub fn with_static_files_access_pattern(
+        mut self,
+        access_pattern: Option<AccessPattern>,
+    ) -> Self {
+        if let Some(access_pattern) = access_pattern {
+            self.static_file_provider =
+                self.static_file_provider.with_access_pattern(access_pattern);
+        }
+        self
+    }
+
+    /// Indexes the transactions of the static files by hash, see
+    /// [StaticFileProvider::with_transaction_hash_index].
+    pub fn with_static_files_transaction_hash_index(mut self, enabled: bool) -> Self {
+        if enabled {
+            self.static_file_provider = self.static_file_provider.with_transaction_hash_index();
+        }
+        self
+    }
+
+    /// Sets the number of accounts and storage slots whose history shards are cached, see
+    /// [HistoryShardCache].
+    pub fn with_history_cache_capacity(mut self, capacity: u32) -> Self {
+        self.history_cache = HistoryShardCache::new(capacity);
+        self
+    }
+
     /// Returns reference to the underlying database.
     pub fn db_ref(&self) -> &DB {
         &self.db
@@ -94,6 +148,7 @@ impl ProviderFactory<DatabaseEnv> {
             db: init_db(path, args).map_err(|e| RethError::Custom(e.to_string()))?,
             chain_spec,
             static_file_provider: StaticFileProvider::new(static_files_path)?,
+            history_cache: HistoryShardCache::default(),
         })
     }
 }
@@ -104,11 +159,10 @@ impl<DB: Database> ProviderFactory<DB> {
     /// [`BlockHashReader`]. This may fail if the inner read database transaction fails to open.
     #[track_caller]
     pub fn provider(&self) -> ProviderResult<DatabaseProviderRO<DB>> {
-        Ok(DatabaseProvider::new(
-            self.db.tx()?,
-            self.chain_spec.clone(),
-            self.static_file_provider.clone(),
-        ))
+        let tx = self.db.tx()?;
+        let history_cache = self.history_cache.reader(tx.snapshot_id());
+        Ok(DatabaseProvider::new(tx, self.chain_spec.clone(), self.static_file_provider.clone())
+            .with_history_cache(history_cache))
     }
 
     /// Returns a provider with a created `DbTxMut` inside, which allows fetching and updating
@@ -338,6 +392,32 @@ impl<DB: Database> BlockReader for ProviderFactory<DB> {
     ) -> ProviderResult<Vec<BlockWithSenders>> {
         self.provider()?.block_with_senders_range(range)
     }
+
+    fn blocks_with_senders_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        transaction_kind: TransactionVariant,
+    ) -> ProviderResult<Vec<BlockWithSenders>> {
+        self.provider()?.blocks_with_senders_range(range, transaction_kind)
+    }
+
+    fn block_with_senders_and_receipts(
+        &self,
+        number: BlockNumber,
+    ) -> ProviderResult<Option<(SealedBlock, Vec<Address>, Vec<Receipt>)>> {
+        self.provider()?.block_with_senders_and_receipts(number)
+    }
+
+    fn block_bodies_range<F, R>(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        assemble_body: F,
+    ) -> ProviderResult<Vec<R>>
+    where
+        F: FnMut(BlockNumber, Vec<TransactionSignedNoHash>, Option<Withdrawals>) -> R,
+    {
+        self.provider()?.block_bodies_range(range, assemble_body)
+    }
 }
 
 impl<DB: Database> TransactionsProvider for ProviderFactory<DB> {
@@ -547,6 +627,17 @@ where
     }
 }
 
+impl<DB: Database> CallTraceIndexReader for ProviderFactory<DB> {
+    fn call_trace_blocks(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        from: &HashSet<Address>,
+        to: &HashSet<Address>,
+    ) -> ProviderResult<Option<Vec<BlockNumber>>> {
+        self.provider()?.call_trace_blocks(range, from, to)
+    }
+}
+
 impl<DB: Database> PruneCheckpointReader for ProviderFactory<DB> {
     fn get_prune_checkpoint(
         &self,
@@ -556,34 +647,57 @@ impl<DB: Database> PruneCheckpointReader for ProviderFactory<DB> {
     }
 }
 
+impl<DB: Database> DatabaseMetadataReader for ProviderFactory<DB> {
+    fn database_metadata(&self) -> DatabaseMetadataValue {
+        self.db.metadata()
+    }
+}
+
+impl<DB> StaticFileProviderFactory for ProviderFactory<DB> {
+    fn static_file_provider(&self) -> StaticFileProvider {
+        self.static_file_provider.clone()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::ProviderFactory;
     use crate::{
         providers::StaticFileWriter, test_utils::create_test_provider_factory, BlockHashReader,
-        BlockNumReader, BlockWriter, HeaderSyncGapProvider, HeaderSyncMode, TransactionsProvider,
+        BlockNumReader, BlockReader, BlockWriter, CallTraceIndexReader, HeaderSyncGapProvider,
+        HeaderSyncMode, HistoryWriter, PruneCheckpointWriter, StageCheckpointWriter,
+        TransactionVariant, TransactionsProvider,
     };
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
     use rand::Rng;
     use reth_db::{
+        database::Database,
         mdbx::DatabaseArguments,
         tables,
-        test_utils::{create_test_static_files_dir, ERROR_TEMPDIR},
+        test_utils::{create_test_static_files_dir, TempDatabase, ERROR_TEMPDIR},
+        transaction::{DbTx, DbTxMut},
+        DatabaseEnv,
     };
     use reth_interfaces::{
         provider::ProviderError,
         test_utils::{
             generators,
-            generators::{random_block, random_header},
+            generators::{random_block, random_block_range, random_header},
         },
         RethError,
     };
     use reth_primitives::{
-        hex_literal::hex, ChainSpecBuilder, PruneMode, PruneModes, SealedBlock, StaticFileSegment,
-        TxNumber, B256, U256,
+        hex_literal::hex,
+        stage::{StageCheckpoint, StageId},
+        Address, ChainSpecBuilder, PruneCheckpoint, PruneMode, PruneModes, PruneSegment,
+        SealedBlock, StaticFileSegment, TxNumber, B256, U256,
+    };
+    use std::{
+        collections::{BTreeMap, BTreeSet, HashSet},
+        ops::RangeInclusive,
+        sync::Arc,
     };
-    use std::{ops::RangeInclusive, sync::Arc};
     use tokio::sync::watch;
 
     #[test]
@@ -612,6 +726,24 @@ mod tests {
         provider.block_hash(0).unwrap();
     }
 
+    #[test]
+    fn history_cache_snapshot_changes_on_commit() {
+        let factory = create_test_provider_factory();
+        let snapshot_id = factory.provider().unwrap().tx_ref().snapshot_id();
+        assert!(snapshot_id.is_some());
+        assert_eq!(factory.provider().unwrap().tx_ref().snapshot_id(), snapshot_id);
+
+        // a write that bypasses the providers creates a new snapshot as well, so readers opened
+        // after it don't use the history shards cached by older readers
+        factory
+            .db_ref()
+            .update(|tx| tx.put::<tables::CanonicalHeaders>(0, B256::ZERO))
+            .unwrap()
+            .unwrap();
+        let provider = factory.provider().unwrap();
+        assert_ne!(provider.tx_ref().snapshot_id(), snapshot_id);
+    }
+
     #[test]
     fn provider_factory_with_database_path() {
         let chain_spec = ChainSpecBuilder::mainnet().build();
@@ -711,6 +843,92 @@ mod tests {
         }
     }
 
+    /// Inserts the blocks and moves the transactions of the blocks up to `static_file_tip` from
+    /// the database to the static files.
+    fn insert_blocks_across_static_files(
+        factory: &ProviderFactory<Arc<TempDatabase<DatabaseEnv>>>,
+        blocks: &[SealedBlock],
+        static_file_tip: usize,
+    ) {
+        let provider = factory.provider_rw().unwrap();
+        for block in blocks {
+            provider
+                .insert_historical_block(block.clone().try_seal_with_senders().unwrap(), None)
+                .unwrap();
+        }
+        provider
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+
+        let mut writer =
+            provider.static_file_provider().latest_writer(StaticFileSegment::Transactions).unwrap();
+        let mut tx_num = 0;
+        for block in &blocks[..=static_file_tip] {
+            for transaction in &block.body {
+                writer.append_transaction(tx_num, transaction.clone().into()).unwrap();
+                provider.tx_ref().delete::<tables::Transactions>(tx_num, None).unwrap();
+                tx_num += 1;
+            }
+            writer.increment_block(StaticFileSegment::Transactions, block.number).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        provider.commit().unwrap();
+    }
+
+    #[test]
+    fn block_bodies_range_across_static_files() {
+        let factory = create_test_provider_factory();
+        let mut rng = generators::rng();
+        let blocks = random_block_range(&mut rng, 0..=9, B256::ZERO, 1..3);
+        insert_blocks_across_static_files(&factory, &blocks, 4);
+
+        let provider = factory.provider().unwrap();
+        let bodies = provider
+            .block_bodies_range(2..=7, |number, transactions, withdrawals| {
+                let transactions =
+                    transactions.into_iter().map(|tx| tx.with_hash()).collect::<Vec<_>>();
+                (number, transactions, withdrawals)
+            })
+            .unwrap();
+        let expected = blocks[2..=7]
+            .iter()
+            .map(|block| (block.number, block.body.clone(), block.withdrawals.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(bodies, expected);
+    }
+
+    #[test]
+    fn blocks_with_senders_range_across_static_files() {
+        let factory = create_test_provider_factory();
+        let mut rng = generators::rng();
+        // some of the blocks have no transactions
+        let blocks = random_block_range(&mut rng, 0..=9, B256::ZERO, 0..3);
+        insert_blocks_across_static_files(&factory, &blocks, 4);
+
+        // the senders of pruned transactions are recovered
+        let provider = factory.provider_rw().unwrap();
+        for tx_num in provider.block_body_indices(6).unwrap().unwrap().tx_num_range() {
+            provider.tx_ref().delete::<tables::TransactionSenders>(tx_num, None).unwrap();
+        }
+        provider.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        for kind in [TransactionVariant::NoHash, TransactionVariant::WithHash] {
+            let expected = (2..=7)
+                .map(|number| provider.block_with_senders(number.into(), kind).unwrap().unwrap())
+                .collect::<Vec<_>>();
+            assert_eq!(provider.blocks_with_senders_range(2..=7, kind).unwrap(), expected);
+        }
+        assert_eq!(
+            provider.blocks_with_senders_range(8..=12, TransactionVariant::WithHash).unwrap().len(),
+            2
+        );
+    }
+
     #[test]
     fn header_sync_gap_lookup() {
         let factory = create_test_provider_factory();
@@ -743,4 +961,64 @@ mod tests {
         assert_eq!(gap.local_head, head);
         assert_eq!(gap.target.tip(), consensus_tip.into());
     }
+
+    #[test]
+    fn call_trace_blocks() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let (a, b) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let none = HashSet::new();
+
+        // Not indexed
+        assert_eq!(provider.call_trace_blocks(1..=10, &HashSet::from([a]), &none).unwrap(), None);
+
+        provider
+            .insert_call_trace_index(
+                BTreeMap::from([(a, vec![2, 5, 9])]),
+                BTreeMap::from([(b, vec![3, 5])]),
+            )
+            .unwrap();
+        provider.save_stage_checkpoint(StageId::IndexCallTraces, StageCheckpoint::new(8)).unwrap();
+
+        // The blocks above the checkpoint are not indexed
+        assert_eq!(
+            provider.call_trace_blocks(1..=10, &HashSet::from([a]), &none).unwrap(),
+            Some(vec![2, 5, 9, 10])
+        );
+        assert_eq!(
+            provider.call_trace_blocks(1..=10, &HashSet::from([a]), &HashSet::from([b])).unwrap(),
+            Some(vec![2, 3, 5, 9, 10])
+        );
+        assert_eq!(
+            provider.call_trace_blocks(1..=8, &none, &HashSet::from([a])).unwrap(),
+            Some(vec![])
+        );
+
+        // Unwind the index of `a` from block 5
+        provider.unwind_call_trace_indices(5, &BTreeSet::from([a]), &BTreeSet::new()).unwrap();
+        assert_eq!(
+            provider.call_trace_blocks(1..=8, &HashSet::from([a]), &none).unwrap(),
+            Some(vec![2])
+        );
+
+        // The pruned blocks are not indexed
+        provider
+            .save_prune_checkpoint(
+                PruneSegment::CallTraces,
+                PruneCheckpoint {
+                    block_number: Some(3),
+                    tx_number: None,
+                    prune_mode: PruneMode::Before(4),
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            provider.call_trace_blocks(1..=8, &HashSet::from([a]), &none).unwrap(),
+            Some(vec![1, 2, 3])
+        );
+        assert_eq!(
+            provider.call_trace_blocks(1..=8, &none, &HashSet::from([b])).unwrap(),
+            Some(vec![1, 2, 3, 5])
+        );
+    }
 }
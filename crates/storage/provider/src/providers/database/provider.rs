@@ -1,16 +1,19 @@
 use crate::{
     bundle_state::{BundleStateInit, BundleStateWithReceipts, HashedStateChanges, RevertsInit},
-    providers::{database::metrics, static_file::StaticFileWriter, StaticFileProvider},
+    providers::{
+        database::metrics, state::history_cache::HistoryShardCacheHandle,
+        static_file::StaticFileWriter, StaticFileProvider,
+    },
     to_range,
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
     },
     AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
-    Chain, EvmEnvProvider, HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
-    HeaderSyncMode, HistoricalStateProvider, HistoryWriter, LatestStateProvider,
-    OriginalValuesKnown, ProviderError, PruneCheckpointReader, PruneCheckpointWriter,
-    StageCheckpointReader, StateProviderBox, StatsReader, StorageReader, TransactionVariant,
-    TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
+    CallTraceIndexReader, Chain, EvmEnvProvider, HashingWriter, HeaderProvider, HeaderSyncGap,
+    HeaderSyncGapProvider, HeaderSyncMode, HistoricalStateProvider, HistoryWriter,
+    LatestStateProvider, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
+    PruneCheckpointWriter, StageCheckpointReader, StateProviderBox, StatsReader, StorageReader,
+    TransactionVariant, TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
 };
 use itertools::{izip, Itertools};
 use reth_db::{
@@ -21,6 +24,7 @@ use reth_db::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
         ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
     },
+    static_file::TransactionSenderMask,
     table::{Table, TableRow},
     tables,
     transaction::{DbTx, DbTxMut},
@@ -105,6 +109,8 @@ pub struct DatabaseProvider<TX> {
     chain_spec: Arc<ChainSpec>,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Cache of the history shards of the factory of this provider.
+    history_cache: Option<HistoryShardCacheHandle>,
 }
 
 impl<TX> DatabaseProvider<TX> {
@@ -112,6 +118,13 @@ impl<TX> DatabaseProvider<TX> {
     pub fn static_file_provider(&self) -> &StaticFileProvider {
         &self.static_file_provider
     }
+
+    /// Sets the history shard cache that's used by the historical state providers of a read-only
+    /// provider.
+    pub(crate) fn with_history_cache(mut self, history_cache: HistoryShardCacheHandle) -> Self {
+        self.history_cache = Some(history_cache);
+        self
+    }
 }
 
 impl<TX: DbTxMut> DatabaseProvider<TX> {
@@ -121,7 +134,7 @@ impl<TX: DbTxMut> DatabaseProvider<TX> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider }
+        Self { tx, chain_spec, static_file_provider, history_cache: None }
     }
 }
 
@@ -186,7 +199,8 @@ impl<TX: DbTx + 'static> DatabaseProvider<TX> {
             self.get_prune_checkpoint(PruneSegment::StorageHistory)?;
 
         let mut state_provider =
-            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider);
+            HistoricalStateProvider::new(self.tx, block_number, self.static_file_provider)
+                .with_history_cache(self.history_cache);
 
         // If we pruned account or storage history, we can't return state on every historical block.
         // Instead, we should cap it at the latest prune checkpoint for corresponding prune segment.
@@ -298,7 +312,7 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider }
+        Self { tx, chain_spec, static_file_provider, history_cache: None }
     }
 
     /// Consume `DbTx` or `DbTxMut`.
@@ -316,6 +330,34 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         &self.tx
     }
 
+    /// Collects the blocks of the range from the call trace index of every address.
+    fn call_trace_index_blocks<T>(
+        &self,
+        addresses: &HashSet<Address>,
+        range: &RangeInclusive<BlockNumber>,
+        blocks: &mut BTreeSet<BlockNumber>,
+    ) -> ProviderResult<()>
+    where
+        T: Table<Key = ShardedKey<Address>, Value = BlockNumberList>,
+    {
+        let mut cursor = self.tx.cursor_read::<T>()?;
+        for &address in addresses {
+            // The first shard that may contain the start of the range is the one with the lowest
+            // highest block number above it.
+            for entry in cursor.walk(Some(ShardedKey::new(address, *range.start())))? {
+                let (sharded_key, list) = entry?;
+                if sharded_key.key != address {
+                    break
+                }
+                blocks.extend(list.iter().filter(|block| range.contains(block)));
+                if sharded_key.highest_block_number >= *range.end() {
+                    break
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Return full table as Vec
     pub fn table<T: Table>(&self) -> Result<Vec<KeyValue<T>>, DatabaseError>
     where
@@ -354,6 +396,38 @@ impl<TX: DbTx> DatabaseProvider<TX> {
             |_| true,
         )
     }
+
+    /// Returns the senders of the transaction range alongside their transaction numbers, reading
+    /// from the transaction senders static files first and from the database afterwards.
+    ///
+    /// Senders that are missing from both, e.g. because they were pruned, are skipped.
+    fn senders_by_tx_range_with_cursor<C>(
+        &self,
+        range: impl RangeBounds<TxNumber>,
+        cursor: &mut C,
+    ) -> ProviderResult<Vec<(TxNumber, Address)>>
+    where
+        C: DbCursorRO<tables::TransactionSenders>,
+    {
+        self.static_file_provider.get_range_with_static_file_or_database(
+            StaticFileSegment::TransactionSenders,
+            to_range(range),
+            |static_file, range, _| {
+                static_file.fetch_range_with_predicate(
+                    StaticFileSegment::TransactionSenders,
+                    range,
+                    |cursor, number| {
+                        Ok(cursor
+                            .get_one::<TransactionSenderMask<Address>>(number.into())?
+                            .map(|sender| (number, sender)))
+                    },
+                    |_| true,
+                )
+            },
+            |range, _| Ok(cursor.walk_range(range)?.collect::<Result<Vec<_>, _>>()?),
+            |_| true,
+        )
+    }
 }
 
 impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
@@ -1017,6 +1091,36 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         }
         Ok(())
     }
+
+    /// Removes the blocks starting from `first_block` from the call trace index of every address.
+    fn unwind_call_trace_index<T>(
+        &self,
+        first_block: BlockNumber,
+        addresses: &BTreeSet<Address>,
+    ) -> ProviderResult<()>
+    where
+        T: Table<Key = ShardedKey<Address>, Value = BlockNumberList>,
+    {
+        let mut cursor = self.tx.cursor_write::<T>()?;
+        for &address in addresses {
+            let partial_shard = unwind_history_shards::<_, T, _>(
+                &mut cursor,
+                ShardedKey::last(address),
+                first_block,
+                |sharded_key| sharded_key.key == address,
+            )?;
+
+            // Check the last returned partial shard.
+            // If it's not empty, the shard needs to be reinserted.
+            if !partial_shard.is_empty() {
+                cursor.insert(
+                    ShardedKey::last(address),
+                    BlockNumberList::new_pre_sorted(partial_shard),
+                )?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl<TX: DbTx> AccountReader for DatabaseProvider<TX> {
@@ -1084,6 +1188,21 @@ impl<TX: DbTx> ChangeSetReader for DatabaseProvider<TX> {
             })
             .collect()
     }
+
+    fn storage_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(Address, StorageEntry)>> {
+        let range = block_number..=block_number;
+        self.tx
+            .cursor_read::<tables::StorageChangeSets>()?
+            .walk_range(BlockNumberAddress::range(range))?
+            .map(|result| -> ProviderResult<_> {
+                let (BlockNumberAddress((_, address)), storage_before) = result?;
+                Ok((address, storage_before))
+            })
+            .collect()
+    }
 }
 
 impl<TX: DbTx> HeaderSyncGapProvider for DatabaseProvider<TX> {
@@ -1315,29 +1434,11 @@ impl<Tx: DbTx> DatabaseProvider<Tx> {
             // we skip the block.
             if let Some((_, block_body_indices)) = block_body_cursor.seek_exact(header.number)? {
                 let tx_range = block_body_indices.tx_num_range();
-
-                // If we are past shanghai, then all blocks should have a withdrawal list,
-                // even if empty
-                let withdrawals =
-                    if self.chain_spec.is_shanghai_active_at_timestamp(header.timestamp) {
-                        Some(
-                            withdrawals_cursor
-                                .seek_exact(header.number)?
-                                .map(|(_, w)| w.withdrawals)
-                                .unwrap_or_default(),
-                        )
-                    } else {
-                        None
-                    };
-                let ommers =
-                    if self.chain_spec.final_paris_total_difficulty(header.number).is_some() {
-                        Vec::new()
-                    } else {
-                        ommers_cursor
-                            .seek_exact(header.number)?
-                            .map(|(_, o)| o.ommers)
-                            .unwrap_or_default()
-                    };
+                let (ommers, withdrawals) = self.ommers_and_withdrawals_with_cursors(
+                    &header,
+                    &mut ommers_cursor,
+                    &mut withdrawals_cursor,
+                )?;
                 if let Ok(b) = assemble_block(tx_range, header, ommers, withdrawals) {
                     blocks.push(b);
                 }
@@ -1345,6 +1446,32 @@ impl<Tx: DbTx> DatabaseProvider<Tx> {
         }
         Ok(blocks)
     }
+
+    /// Returns the ommers and withdrawals of the block with the given header.
+    fn ommers_and_withdrawals_with_cursors(
+        &self,
+        header: &Header,
+        ommers_cursor: &mut impl DbCursorRO<tables::BlockOmmers>,
+        withdrawals_cursor: &mut impl DbCursorRO<tables::BlockWithdrawals>,
+    ) -> ProviderResult<(Vec<Header>, Option<Withdrawals>)> {
+        // If we are past shanghai, then all blocks should have a withdrawal list, even if empty
+        let withdrawals = if self.chain_spec.is_shanghai_active_at_timestamp(header.timestamp) {
+            Some(
+                withdrawals_cursor
+                    .seek_exact(header.number)?
+                    .map(|(_, w)| w.withdrawals)
+                    .unwrap_or_default(),
+            )
+        } else {
+            None
+        };
+        let ommers = if self.chain_spec.final_paris_total_difficulty(header.number).is_some() {
+            Vec::new()
+        } else {
+            ommers_cursor.seek_exact(header.number)?.map(|(_, o)| o.ommers).unwrap_or_default()
+        };
+        Ok((ommers, withdrawals))
+    }
 }
 
 impl<TX: DbTx> BlockReader for DatabaseProvider<TX> {
@@ -1488,45 +1615,172 @@ impl<TX: DbTx> BlockReader for DatabaseProvider<TX> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<Vec<BlockWithSenders>> {
-        let mut tx_cursor = self.tx.cursor_read::<tables::Transactions>()?;
-        let mut senders_cursor = self.tx.cursor_read::<tables::TransactionSenders>()?;
+        self.blocks_with_senders_range(range, TransactionVariant::WithHash)
+    }
 
-        self.process_block_range(range, |tx_range, header, ommers, withdrawals| {
-            let (body, senders) = if tx_range.is_empty() {
-                (Vec::new(), Vec::new())
-            } else {
-                let body = self
-                    .transactions_by_tx_range_with_cursor(tx_range.clone(), &mut tx_cursor)?
-                    .into_iter()
-                    .map(Into::into)
-                    .collect::<Vec<TransactionSigned>>();
-                // fetch senders from the senders table
-                let known_senders =
-                    senders_cursor
-                        .walk_range(tx_range.clone())?
-                        .collect::<Result<HashMap<_, _>, _>>()?;
-
-                let mut senders = Vec::with_capacity(body.len());
-                for (tx_num, tx) in tx_range.zip(body.iter()) {
-                    match known_senders.get(&tx_num) {
-                        None => {
-                            // recover the sender from the transaction if not found
-                            let sender = tx
-                                .recover_signer_unchecked()
-                                .ok_or_else(|| ProviderError::SenderRecoveryError)?;
-                            senders.push(sender);
-                        }
-                        Some(sender) => senders.push(*sender),
+    fn blocks_with_senders_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        transaction_kind: TransactionVariant,
+    ) -> ProviderResult<Vec<BlockWithSenders>> {
+        if range.is_empty() {
+            return Ok(Vec::new())
+        }
+
+        let body_indices = self
+            .tx
+            .cursor_read::<tables::BlockBodyIndices>()?
+            .walk_range(range.clone())?
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+        let (Some(first), Some(last)) =
+            (body_indices.values().next(), body_indices.values().next_back())
+        else {
+            return Ok(Vec::new())
+        };
+
+        // The transactions of consecutive blocks are stored consecutively, so the transactions and
+        // senders of the whole range are read at once, from the static files and the database.
+        let tx_range = first.first_tx_num()..last.next_tx_num();
+        let (transactions, senders) = if tx_range.is_empty() {
+            (Vec::new(), Vec::new())
+        } else {
+            (
+                self.transactions_by_tx_range_with_cursor(
+                    tx_range.clone(),
+                    &mut self.tx.cursor_read::<tables::Transactions>()?,
+                )?,
+                self.senders_by_tx_range_with_cursor(
+                    tx_range.clone(),
+                    &mut self.tx.cursor_read::<tables::TransactionSenders>()?,
+                )?,
+            )
+        };
+        let mut transactions = transactions.into_iter();
+        let mut senders = senders.into_iter().peekable();
+        let mut next_tx_num = tx_range.start;
+
+        let headers = self.headers_range(range)?;
+        let mut ommers_cursor = self.tx.cursor_read::<tables::BlockOmmers>()?;
+        let mut withdrawals_cursor = self.tx.cursor_read::<tables::BlockWithdrawals>()?;
+
+        let mut blocks = Vec::with_capacity(headers.len());
+        for header in headers {
+            // Blocks without body indices are skipped, see `process_block_range`.
+            let Some(block_body_indices) = body_indices.get(&header.number) else { continue };
+            let tx_range = block_body_indices.tx_num_range();
+
+            // skip the transactions of blocks without a header
+            if tx_range.start > next_tx_num {
+                transactions.nth((tx_range.start - next_tx_num - 1) as usize);
+            }
+            next_tx_num = tx_range.end;
+
+            let body = transactions
+                .by_ref()
+                .take(tx_range.clone().count())
+                .map(|tx| match transaction_kind {
+                    TransactionVariant::NoHash => TransactionSigned {
+                        // Caller explicitly asked for no hash, so we don't calculate it
+                        hash: B256::ZERO,
+                        signature: tx.signature,
+                        transaction: tx.transaction,
+                    },
+                    TransactionVariant::WithHash => tx.with_hash(),
+                })
+                .collect::<Vec<_>>();
+            if body.len() as u64 != block_body_indices.tx_count() {
+                // the transactions of the block are not available
+                continue
+            }
+
+            let mut block_senders = Vec::with_capacity(body.len());
+            for (tx_num, tx) in tx_range.zip(body.iter()) {
+                while senders.next_if(|(number, _)| *number < tx_num).is_some() {}
+                match senders.next_if(|(number, _)| *number == tx_num) {
+                    Some((_, sender)) => block_senders.push(sender),
+                    None => {
+                        // recover the sender from the transaction if not found, e.g. if pruned
+                        let sender = tx
+                            .recover_signer_unchecked()
+                            .ok_or(ProviderError::SenderRecoveryError)?;
+                        block_senders.push(sender);
                     }
                 }
+            }
+
+            let (ommers, withdrawals) = self.ommers_and_withdrawals_with_cursors(
+                &header,
+                &mut ommers_cursor,
+                &mut withdrawals_cursor,
+            )?;
+            blocks.push(BlockWithSenders {
+                block: Block { header, body, ommers, withdrawals },
+                senders: block_senders,
+            });
+        }
+        Ok(blocks)
+    }
+
+    fn block_bodies_range<F, R>(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        mut assemble_body: F,
+    ) -> ProviderResult<Vec<R>>
+    where
+        F: FnMut(BlockNumber, Vec<TransactionSignedNoHash>, Option<Withdrawals>) -> R,
+    {
+        let mut block_body_cursor = self.tx.cursor_read::<tables::BlockBodyIndices>()?;
+        let mut tx_cursor = self.tx.cursor_read::<tables::Transactions>()?;
+        let mut withdrawals_cursor = self.tx.cursor_read::<tables::BlockWithdrawals>()?;
 
-                (body, senders)
+        let mut entries = Vec::new();
+        for entry in block_body_cursor.walk_range(range)? {
+            let (number, block_body_indices) = entry?;
+            let withdrawals = withdrawals_cursor.seek_exact(number)?.map(|(_, stored)| stored);
+            entries.push((number, block_body_indices, withdrawals));
+        }
+
+        // Empty withdrawal lists are not stored, so the headers are only needed to tell apart
+        // blocks past shanghai without withdrawals from blocks before shanghai. The headers of the
+        // blocks without stored withdrawals are read at once.
+        let mut without_withdrawals = entries
+            .iter()
+            .filter(|(_, _, withdrawals)| withdrawals.is_none())
+            .map(|(number, _, _)| *number);
+        let timestamps: HashMap<BlockNumber, u64> =
+            match (without_withdrawals.next(), without_withdrawals.last()) {
+                (Some(first), last) => self
+                    .headers_range(first..=last.unwrap_or(first))?
+                    .into_iter()
+                    .map(|header| (header.number, header.timestamp))
+                    .collect(),
+                (None, _) => HashMap::new(),
             };
 
-            Block { header, body, ommers, withdrawals }
-                .try_with_senders_unchecked(senders)
-                .map_err(|_| ProviderError::SenderRecoveryError)
-        })
+        let mut bodies = Vec::with_capacity(entries.len());
+        for (number, block_body_indices, withdrawals) in entries {
+            let tx_range = block_body_indices.tx_num_range();
+            let transactions = if tx_range.is_empty() {
+                Vec::new()
+            } else {
+                self.transactions_by_tx_range_with_cursor(tx_range, &mut tx_cursor)?
+            };
+
+            let withdrawals = match withdrawals {
+                Some(stored) => Some(stored.withdrawals),
+                None => {
+                    let timestamp = timestamps
+                        .get(&number)
+                        .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
+                    self.chain_spec
+                        .is_shanghai_active_at_timestamp(*timestamp)
+                        .then(Withdrawals::default)
+                }
+            };
+
+            bodies.push(assemble_body(number, transactions, withdrawals));
+        }
+        Ok(bodies)
     }
 }
 
@@ -1601,7 +1855,12 @@ impl<TX: DbTx> TransactionsProviderExt for DatabaseProvider<TX> {
 
 impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
     fn transaction_id(&self, tx_hash: TxHash) -> ProviderResult<Option<TxNumber>> {
-        Ok(self.tx.get::<tables::TransactionHashNumbers>(tx_hash)?)
+        if let Some(id) = self.tx.get::<tables::TransactionHashNumbers>(tx_hash)? {
+            return Ok(Some(id))
+        }
+        // The entries of pruned transactions may still be found by the hash index of the static
+        // files, if it's enabled.
+        self.static_file_provider.transaction_id_by_hash_index(tx_hash)
     }
 
     fn transaction_by_id(&self, id: TxNumber) -> ProviderResult<Option<TransactionSigned>> {
@@ -1749,11 +2008,32 @@ impl<TX: DbTx> TransactionsProvider for DatabaseProvider<TX> {
         &self,
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Address>> {
-        self.cursor_read_collect::<tables::TransactionSenders>(range).map_err(Into::into)
+        Ok(self
+            .senders_by_tx_range_with_cursor(
+                range,
+                &mut self.tx.cursor_read::<tables::TransactionSenders>()?,
+            )?
+            .into_iter()
+            .map(|(_, sender)| sender)
+            .collect())
     }
 
     fn transaction_sender(&self, id: TxNumber) -> ProviderResult<Option<Address>> {
-        Ok(self.tx.get::<tables::TransactionSenders>(id)?)
+        self.static_file_provider.get_with_static_file_or_database(
+            StaticFileSegment::TransactionSenders,
+            id,
+            |static_file| {
+                static_file
+                    .get_segment_provider_from_transaction(
+                        StaticFileSegment::TransactionSenders,
+                        id,
+                        None,
+                    )?
+                    .cursor()?
+                    .get_one::<TransactionSenderMask<Address>>(id.into())
+            },
+            || Ok(self.tx.get::<tables::TransactionSenders>(id)?),
+        )
     }
 }
 
@@ -2370,6 +2650,25 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
 
         Ok(())
     }
+
+    fn unwind_call_trace_indices(
+        &self,
+        first_block: BlockNumber,
+        from: &BTreeSet<Address>,
+        to: &BTreeSet<Address>,
+    ) -> ProviderResult<()> {
+        self.unwind_call_trace_index::<tables::CallFromIndex>(first_block, from)?;
+        self.unwind_call_trace_index::<tables::CallToIndex>(first_block, to)
+    }
+
+    fn insert_call_trace_index(
+        &self,
+        from: BTreeMap<Address, Vec<u64>>,
+        to: BTreeMap<Address, Vec<u64>>,
+    ) -> ProviderResult<()> {
+        self.append_history_index::<_, tables::CallFromIndex>(from, ShardedKey::new)?;
+        self.append_history_index::<_, tables::CallToIndex>(to, ShardedKey::new)
+    }
 }
 
 impl<TX: DbTxMut + DbTx> BlockExecutionWriter for DatabaseProvider<TX> {
@@ -2461,6 +2760,17 @@ impl<TX: DbTxMut + DbTx> BlockExecutionWriter for DatabaseProvider<TX> {
             // Update pipeline progress
             if let Some(fork_number) = unwind_to {
                 self.update_pipeline_stages(fork_number, true)?;
+
+                // The optional call trace index is not one of the pipeline stages. Its index of the
+                // unwound blocks is left in place, but it's not used above the checkpoint.
+                if let Some(checkpoint) = self.get_stage_checkpoint(StageId::IndexCallTraces)? {
+                    if checkpoint.block_number > fork_number {
+                        self.save_stage_checkpoint(
+                            StageId::IndexCallTraces,
+                            StageCheckpoint::new(fork_number),
+                        )?;
+                    }
+                }
             }
         }
 
@@ -2654,6 +2964,37 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> CallTraceIndexReader for DatabaseProvider<TX> {
+    fn call_trace_blocks(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        from: &HashSet<Address>,
+        to: &HashSet<Address>,
+    ) -> ProviderResult<Option<Vec<BlockNumber>>> {
+        let Some(checkpoint) = self.get_stage_checkpoint(StageId::IndexCallTraces)? else {
+            return Ok(None)
+        };
+        let lowest_indexed_block = self
+            .get_prune_checkpoint(PruneSegment::CallTraces)?
+            .and_then(|checkpoint| checkpoint.block_number)
+            .map_or(0, |block_number| block_number + 1);
+        let indexed =
+            lowest_indexed_block.max(*range.start())..=checkpoint.block_number.min(*range.end());
+        if indexed.is_empty() {
+            return Ok(Some(range.collect()))
+        }
+
+        // Blocks that are not indexed may contain the call traces of any address.
+        let mut blocks = (*range.start()..*indexed.start())
+            .chain(*indexed.end() + 1..=*range.end())
+            .collect::<BTreeSet<_>>();
+        self.call_trace_index_blocks::<tables::CallFromIndex>(from, &indexed, &mut blocks)?;
+        self.call_trace_index_blocks::<tables::CallToIndex>(to, &indexed, &mut blocks)?;
+
+        Ok(Some(blocks.into_iter().collect()))
+    }
+}
+
 impl<TX: DbTx> PruneCheckpointReader for DatabaseProvider<TX> {
     fn get_prune_checkpoint(
         &self,
@@ -1,13 +1,15 @@
 use crate::{
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    BlockSource, BlockchainTreePendingStateProvider, BundleStateDataProvider, CanonChainTracker,
-    CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, ProviderError, PruneCheckpointReader,
-    ReceiptProvider, ReceiptProviderIdExt, StageCheckpointReader, StateProviderBox,
-    StateProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    BlockSource, BlockchainTreePendingStateProvider, BundleStateDataProvider, CallTraceIndexReader,
+    CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider,
+    ChangeSetReader, DatabaseMetadataReader, DatabaseProviderFactory, EvmEnvProvider,
+    HeaderProvider, ProviderError, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
+    StageCheckpointReader, StateProviderBox, StateProviderFactory, StaticFileProviderFactory,
+    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::{
     database::Database,
+    database_metrics::DatabaseMetadataValue,
     models::{AccountBeforeTx, StoredBlockBodyIndices},
 };
 use reth_evm::ConfigureEvmEnv;
@@ -25,16 +27,16 @@ use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumHash, BlockNumber,
     BlockNumberOrTag, BlockWithSenders, ChainInfo, ChainSpec, Header, PruneCheckpoint,
-    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StorageEntry,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    Withdrawals, B256, U256,
 };
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
     collections::{BTreeMap, HashSet},
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use tracing::trace;
 
@@ -50,6 +52,7 @@ pub use static_file::{
 mod state;
 pub use state::{
     historical::{HistoricalStateProvider, HistoricalStateProviderRef},
+    history_cache::{HistoryShardCache, DEFAULT_HISTORY_SHARD_CACHE_CAPACITY},
     latest::{LatestStateProvider, LatestStateProviderRef},
 };
 
@@ -296,6 +299,9 @@ where
 
     /// Returns the block with senders with matching number or hash from database.
     ///
+    /// A hash also matches a block of the tree, e.g. a block of a side chain, if the database has
+    /// no such block. The transactions of a block of the tree always have their hashes.
+    ///
     /// **NOTE: If [TransactionVariant::NoHash] is provided then the transactions have invalid
     /// hashes, since they would need to be calculated on the spot, and we want fast querying.**
     ///
@@ -305,7 +311,12 @@ where
         id: BlockHashOrNumber,
         transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        self.database.block_with_senders(id, transaction_kind)
+        let block = self.database.block_with_senders(id, transaction_kind)?;
+        if block.is_some() {
+            return Ok(block)
+        }
+        let BlockHashOrNumber::Hash(hash) = id else { return Ok(None) };
+        Ok(self.tree.block_with_senders_by_hash(hash).map(SealedBlockWithSenders::unseal))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -318,6 +329,32 @@ where
     ) -> ProviderResult<Vec<BlockWithSenders>> {
         self.database.block_with_senders_range(range)
     }
+
+    fn blocks_with_senders_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        transaction_kind: TransactionVariant,
+    ) -> ProviderResult<Vec<BlockWithSenders>> {
+        self.database.blocks_with_senders_range(range, transaction_kind)
+    }
+
+    fn block_with_senders_and_receipts(
+        &self,
+        number: BlockNumber,
+    ) -> ProviderResult<Option<(SealedBlock, Vec<Address>, Vec<Receipt>)>> {
+        self.database.block_with_senders_and_receipts(number)
+    }
+
+    fn block_bodies_range<F, R>(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        assemble_body: F,
+    ) -> ProviderResult<Vec<R>>
+    where
+        F: FnMut(BlockNumber, Vec<TransactionSignedNoHash>, Option<Withdrawals>) -> R,
+    {
+        self.database.block_bodies_range(range, assemble_body)
+    }
 }
 
 impl<DB, Tree> TransactionsProvider for BlockchainProvider<DB, Tree>
@@ -472,10 +509,54 @@ where
     }
 }
 
+impl<DB, Tree> BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: BlockchainTreeViewer,
+{
+    /// Returns the header of the block to fill the env of, which is either a block of the
+    /// database or a block of the tree, e.g. a block of a side chain.
+    fn env_header(&self, at: BlockHashOrNumber) -> ProviderResult<Header> {
+        if let Some(header) = self.database.header_by_hash_or_number(at)? {
+            return Ok(header)
+        }
+        let BlockHashOrNumber::Hash(hash) = at else {
+            return Err(ProviderError::HeaderNotFound(at))
+        };
+        self.tree
+            .header_by_hash(hash)
+            .map(|header| header.unseal())
+            .ok_or(ProviderError::HeaderNotFound(at))
+    }
+
+    /// Returns the total difficulty of the block of the given header.
+    ///
+    /// The total difficulty of a block of the tree is the total difficulty of its canonical
+    /// ancestor plus the difficulties of the blocks of the tree up to the block.
+    fn env_total_difficulty(&self, header: &Header) -> ProviderResult<U256> {
+        if let Some(total_difficulty) = self.database.header_td(&header.hash_slow())? {
+            return Ok(total_difficulty)
+        }
+        let mut total_difficulty = header.difficulty;
+        let mut parent_hash = header.parent_hash;
+        loop {
+            if let Some(parent_total_difficulty) = self.database.header_td(&parent_hash)? {
+                return Ok(total_difficulty + parent_total_difficulty)
+            }
+            let parent = self
+                .tree
+                .header_by_hash(parent_hash)
+                .ok_or(ProviderError::HeaderNotFound(parent_hash.into()))?;
+            total_difficulty += parent.difficulty;
+            parent_hash = parent.parent_hash;
+        }
+    }
+}
+
 impl<DB, Tree> EvmEnvProvider for BlockchainProvider<DB, Tree>
 where
     DB: Database,
-    Tree: Send + Sync,
+    Tree: BlockchainTreeViewer,
 {
     fn fill_env_at<EvmConfig>(
         &self,
@@ -487,7 +568,8 @@ where
     where
         EvmConfig: ConfigureEvmEnv,
     {
-        self.database.provider()?.fill_env_at(cfg, block_env, at, evm_config)
+        let header = self.env_header(at)?;
+        self.fill_env_with_header(cfg, block_env, &header, evm_config)
     }
 
     fn fill_env_with_header<EvmConfig>(
@@ -495,12 +577,20 @@ where
         cfg: &mut CfgEnvWithHandlerCfg,
         block_env: &mut BlockEnv,
         header: &Header,
-        evm_config: EvmConfig,
+        _evm_config: EvmConfig,
     ) -> ProviderResult<()>
     where
         EvmConfig: ConfigureEvmEnv,
     {
-        self.database.provider()?.fill_env_with_header(cfg, block_env, header, evm_config)
+        let total_difficulty = self.env_total_difficulty(header)?;
+        EvmConfig::fill_cfg_and_block_env(
+            cfg,
+            block_env,
+            &self.database.chain_spec(),
+            header,
+            total_difficulty,
+        );
+        Ok(())
     }
 
     fn fill_block_env_at(
@@ -508,7 +598,8 @@ where
         block_env: &mut BlockEnv,
         at: BlockHashOrNumber,
     ) -> ProviderResult<()> {
-        self.database.provider()?.fill_block_env_at(block_env, at)
+        let header = self.env_header(at)?;
+        self.fill_block_env_with_header(block_env, &header)
     }
 
     fn fill_block_env_with_header(
@@ -528,19 +619,37 @@ where
     where
         EvmConfig: ConfigureEvmEnv,
     {
-        self.database.provider()?.fill_cfg_env_at(cfg, at, evm_config)
+        let header = self.env_header(at)?;
+        self.fill_cfg_env_with_header(cfg, &header, evm_config)
     }
 
     fn fill_cfg_env_with_header<EvmConfig>(
         &self,
         cfg: &mut CfgEnvWithHandlerCfg,
         header: &Header,
-        evm_config: EvmConfig,
+        _evm_config: EvmConfig,
     ) -> ProviderResult<()>
     where
         EvmConfig: ConfigureEvmEnv,
     {
-        self.database.provider()?.fill_cfg_env_with_header(cfg, header, evm_config)
+        let total_difficulty = self.env_total_difficulty(header)?;
+        EvmConfig::fill_cfg_env(cfg, &self.database.chain_spec(), header, total_difficulty);
+        Ok(())
+    }
+}
+
+impl<DB, Tree> CallTraceIndexReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn call_trace_blocks(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        from: &HashSet<Address>,
+        to: &HashSet<Address>,
+    ) -> ProviderResult<Option<Vec<BlockNumber>>> {
+        self.database.provider()?.call_trace_blocks(range, from, to)
     }
 }
 
@@ -557,6 +666,22 @@ where
     }
 }
 
+impl<DB, Tree> DatabaseMetadataReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn database_metadata(&self) -> DatabaseMetadataValue {
+        self.database.database_metadata()
+    }
+}
+
+impl<DB, Tree> StaticFileProviderFactory for BlockchainProvider<DB, Tree> {
+    fn static_file_provider(&self) -> StaticFileProvider {
+        self.database.static_file_provider()
+    }
+}
+
 impl<DB, Tree> ChainSpecProvider for BlockchainProvider<DB, Tree>
 where
     DB: Send + Sync,
@@ -649,6 +774,18 @@ where
     DB: Send + Sync,
     Tree: BlockchainTreeEngine,
 {
+    fn insert_block_without_senders(
+        &self,
+        block: SealedBlock,
+        validation_kind: BlockValidationKind,
+    ) -> Result<InsertPayloadOk, InsertBlockError> {
+        self.tree.insert_block_without_senders(block, validation_kind)
+    }
+
+    fn on_payload_validated(&self, block_hash: BlockHash, elapsed: Duration) {
+        self.tree.on_payload_validated(block_hash, elapsed)
+    }
+
     fn buffer_block(&self, block: SealedBlockWithSenders) -> Result<(), InsertBlockError> {
         self.tree.buffer_block(block)
     }
@@ -901,6 +1038,13 @@ where
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
         self.database.provider()?.account_block_changeset(block_number)
     }
+
+    fn storage_block_changeset(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(Address, StorageEntry)>> {
+        self.database.provider()?.storage_block_changeset(block_number)
+    }
 }
 
 impl<DB, Tree> AccountReader for BlockchainProvider<DB, Tree>
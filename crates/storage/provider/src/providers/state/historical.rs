@@ -1,5 +1,11 @@
 use crate::{
-    providers::{state::macros::delegate_provider_impls, StaticFileProvider},
+    providers::{
+        state::{
+            history_cache::{CachedShard, HistoryKey, HistoryShardCacheHandle},
+            macros::delegate_provider_impls,
+        },
+        StaticFileProvider,
+    },
     AccountReader, BlockHashReader, ProviderError, StateProvider, StateRootProvider,
 };
 use reth_db::{
@@ -17,7 +23,7 @@ use reth_primitives::{
 };
 use reth_trie::{updates::TrieUpdates, HashedPostState};
 use revm::db::BundleState;
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -40,6 +46,8 @@ pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Cache of the resolved history shards, if the transaction is read-only.
+    history_cache: Option<&'b HistoryShardCacheHandle>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -57,7 +65,13 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            history_cache: None,
+        }
     }
 
     /// Create new StateProvider for historical block number and lowest block numbers at which
@@ -68,7 +82,22 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         lowest_available_blocks: LowestAvailableBlocks,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks, static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks,
+            static_file_provider,
+            history_cache: None,
+        }
+    }
+
+    /// Resolves the history shards through the given cache.
+    pub(crate) fn with_history_cache(
+        mut self,
+        history_cache: Option<&'b HistoryShardCacheHandle>,
+    ) -> Self {
+        self.history_cache = history_cache;
+        self
     }
 
     /// Lookup an account in the AccountsHistory table
@@ -81,7 +110,9 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         let history_key = ShardedKey::new(address, self.block_number);
         self.history_info::<tables::AccountsHistory, _>(
             history_key,
+            HistoryKey::Account(address),
             |key| key.key == address,
+            |key| key.highest_block_number,
             self.lowest_available_blocks.account_history_block_number,
         )
     }
@@ -100,7 +131,9 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         let history_key = StorageShardedKey::new(address, storage_key, self.block_number);
         self.history_info::<tables::StoragesHistory, _>(
             history_key,
+            HistoryKey::Storage(address, storage_key),
             |key| key.address == address && key.sharded_key.key == storage_key,
+            |key| key.sharded_key.highest_block_number,
             self.lowest_available_blocks.storage_history_block_number,
         )
     }
@@ -134,66 +167,121 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         Ok(HashedPostState::from_revert_range(self.tx, self.block_number..=tip)?)
     }
 
+    /// Resolves the history shard of the key at the block of the provider, from the cache if
+    /// possible, and looks up in which changeset or state the value at the block is.
     fn history_info<T, K>(
         &self,
         key: K,
+        cache_key: HistoryKey,
         key_filter: impl Fn(&K) -> bool,
+        highest_block_number: impl Fn(&K) -> BlockNumber,
         lowest_available_block_number: Option<BlockNumber>,
     ) -> ProviderResult<HistoryInfo>
     where
         T: Table<Key = K, Value = BlockNumberList>,
     {
+        if let Some(shard) =
+            self.history_cache.and_then(|cache| cache.get(cache_key, self.block_number))
+        {
+            let is_first_shard = shard.previous_highest_block_number.is_none();
+            return self.chunk_history_info(
+                &shard.chunk,
+                || Ok(is_first_shard),
+                lowest_available_block_number,
+            )
+        }
+
         let mut cursor = self.tx.cursor_read::<T>()?;
 
         // Lookup the history chunk in the history index. If they key does not appear in the
         // index, the first chunk for the next key will be returned so we filter out chunks that
         // have a different key.
-        if let Some(chunk) = cursor.seek(key)?.filter(|(key, _)| key_filter(key)).map(|x| x.1 .0) {
-            // Get the rank of the first entry before or equal to our block.
-            let mut rank = chunk.rank(self.block_number);
-
-            // Adjust the rank, so that we have the rank of the first entry strictly before our
-            // block (not equal to it).
-            if rank.checked_sub(1).and_then(|rank| chunk.select(rank)) == Some(self.block_number) {
-                rank -= 1
-            };
-
-            let block_number = chunk.select(rank);
-
-            // If our block is before the first entry in the index chunk and this first entry
-            // doesn't equal to our block, it might be before the first write ever. To check, we
-            // look at the previous entry and check if the key is the same.
-            // This check is worth it, the `cursor.prev()` check is rarely triggered (the if will
-            // short-circuit) and when it passes we save a full seek into the changeset/plain state
-            // table.
-            if rank == 0 &&
-                block_number != Some(self.block_number) &&
-                !cursor.prev()?.is_some_and(|(key, _)| key_filter(&key))
-            {
-                if let (Some(_), Some(block_number)) = (lowest_available_block_number, block_number)
-                {
-                    // The key may have been written, but due to pruning we may not have changesets
-                    // and history, so we need to make a changeset lookup.
-                    Ok(HistoryInfo::InChangeset(block_number))
-                } else {
-                    // The key is written to, but only after our block.
-                    Ok(HistoryInfo::NotYetWritten)
-                }
-            } else if let Some(block_number) = block_number {
-                // The chunk contains an entry for a write after our block, return it.
+        let Some((shard_key, chunk)) = cursor.seek(key)?.filter(|(key, _)| key_filter(key)) else {
+            return Ok(if lowest_available_block_number.is_some() {
+                // The key may have been written, but due to pruning we may not have changesets
+                // and history, so we need to make a plain state lookup.
+                HistoryInfo::MaybeInPlainState
+            } else {
+                // The key has not been written to at all.
+                HistoryInfo::NotYetWritten
+            })
+        };
+
+        let Some(cache) = self.history_cache else {
+            // Whether there's a previous chunk is only checked if needed, see
+            // `chunk_history_info`.
+            return self.chunk_history_info(
+                &chunk,
+                || Ok(!cursor.prev()?.is_some_and(|(key, _)| key_filter(&key))),
+                lowest_available_block_number,
+            )
+        };
+
+        // The boundary to the previous chunk is cached alongside the chunk, so later lookups of
+        // the key at blocks that resolve to this chunk don't need to seek.
+        let previous_highest_block_number = cursor
+            .prev()?
+            .filter(|(key, _)| key_filter(key))
+            .map(|(key, _)| highest_block_number(&key));
+        let info = self.chunk_history_info(
+            &chunk,
+            || Ok(previous_highest_block_number.is_none()),
+            lowest_available_block_number,
+        )?;
+        cache.insert(
+            cache_key,
+            Arc::new(CachedShard {
+                previous_highest_block_number,
+                highest_block_number: highest_block_number(&shard_key),
+                chunk,
+            }),
+        );
+        Ok(info)
+    }
+
+    /// Looks up in which changeset or state the value at the block of the provider is, given the
+    /// history chunk of the key that the block resolves to.
+    ///
+    /// `is_first_chunk` returns whether the chunk is the first chunk of the key.
+    fn chunk_history_info(
+        &self,
+        chunk: &BlockNumberList,
+        is_first_chunk: impl FnOnce() -> ProviderResult<bool>,
+        lowest_available_block_number: Option<BlockNumber>,
+    ) -> ProviderResult<HistoryInfo> {
+        // Get the rank of the first entry before or equal to our block.
+        let mut rank = chunk.rank(self.block_number);
+
+        // Adjust the rank, so that we have the rank of the first entry strictly before our
+        // block (not equal to it).
+        if rank.checked_sub(1).and_then(|rank| chunk.select(rank)) == Some(self.block_number) {
+            rank -= 1
+        };
+
+        let block_number = chunk.select(rank);
+
+        // If our block is before the first entry in the index chunk and this first entry
+        // doesn't equal to our block, it might be before the first write ever. To check, we
+        // look at the previous chunk and check if the key is the same.
+        // This check is worth it, the previous chunk check is rarely triggered (the if will
+        // short-circuit) and when it passes we save a full seek into the changeset/plain state
+        // table.
+        if rank == 0 && block_number != Some(self.block_number) && is_first_chunk()? {
+            if let (Some(_), Some(block_number)) = (lowest_available_block_number, block_number) {
+                // The key may have been written, but due to pruning we may not have changesets
+                // and history, so we need to make a changeset lookup.
                 Ok(HistoryInfo::InChangeset(block_number))
             } else {
-                // The chunk does not contain an entry for a write after our block. This can only
-                // happen if this is the last chunk and so we need to look in the plain state.
-                Ok(HistoryInfo::InPlainState)
+                // The key is written to, but only after our block.
+                Ok(HistoryInfo::NotYetWritten)
             }
-        } else if lowest_available_block_number.is_some() {
-            // The key may have been written, but due to pruning we may not have changesets and
-            // history, so we need to make a plain state lookup.
-            Ok(HistoryInfo::MaybeInPlainState)
+        } else if let Some(block_number) = block_number {
+            // The chunk contains an entry for a write after our block, return it.
+            Ok(HistoryInfo::InChangeset(block_number))
         } else {
-            // The key has not been written to at all.
-            Ok(HistoryInfo::NotYetWritten)
+            // The chunk does not contain an entry for a write after our block. This can only
+            // happen if this is the last chunk and so we need to look in the plain state.
+            Ok(HistoryInfo::InPlainState)
         }
     }
 }
@@ -309,8 +397,17 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
     }
 
     /// Get account and storage proofs.
-    fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
-        Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+    fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof> {
+        let mut proofs = self.proofs(&[(address, keys.to_vec())])?;
+        Ok(proofs.remove(0))
+    }
+
+    /// Generates the proofs against the state root of the historical block by applying the
+    /// reverts of all later blocks on top of the trie tables.
+    fn proofs(&self, targets: &[(Address, Vec<B256>)]) -> ProviderResult<Vec<AccountProof>> {
+        self.revert_state()?
+            .account_proofs(self.tx, targets)
+            .map_err(|err| ProviderError::Database(err.into()))
     }
 }
 
@@ -326,6 +423,8 @@ pub struct HistoricalStateProvider<TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Cache of the resolved history shards, if the transaction is read-only.
+    history_cache: Option<HistoryShardCacheHandle>,
 }
 
 impl<TX: DbTx> HistoricalStateProvider<TX> {
@@ -335,7 +434,22 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            history_cache: None,
+        }
+    }
+
+    /// Resolves the history shards through the given cache.
+    pub(crate) fn with_history_cache(
+        mut self,
+        history_cache: Option<HistoryShardCacheHandle>,
+    ) -> Self {
+        self.history_cache = history_cache;
+        self
     }
 
     /// Set the lowest block number at which the account history is available.
@@ -365,6 +479,7 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
             self.lowest_available_blocks,
             self.static_file_provider.clone(),
         )
+        .with_history_cache(self.history_cache.as_ref())
     }
 }
 
@@ -402,7 +517,10 @@ impl LowestAvailableBlocks {
 #[cfg(test)]
 mod tests {
     use crate::{
-        providers::state::historical::{HistoryInfo, LowestAvailableBlocks},
+        providers::state::{
+            historical::{HistoryInfo, LowestAvailableBlocks},
+            history_cache::{HistoryKey, HistoryShardCache},
+        },
         test_utils::create_test_provider_factory,
         AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
     };
@@ -413,7 +531,8 @@ mod tests {
         BlockNumberList,
     };
     use reth_interfaces::provider::ProviderError;
-    use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
+    use reth_primitives::{address, b256, keccak256, Account, Address, StorageEntry, B256, U256};
+    use reth_trie::{proof::Proof, StateRoot};
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -656,6 +775,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn history_provider_proof() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+
+        let hashed_address = keccak256(ADDRESS);
+        let other_slot = B256::with_last_byte(2);
+        let acc_at2 = Account { nonce: 1, balance: U256::from(10), bytecode_hash: None };
+        let acc_plain = Account { nonce: 2, balance: U256::from(5), bytecode_hash: None };
+        let higher_acc = Account { nonce: 4, balance: U256::ZERO, bytecode_hash: None };
+
+        // the state and trie at the start of block 2
+        tx.put::<tables::HashedAccounts>(hashed_address, acc_at2).unwrap();
+        tx.put::<tables::HashedAccounts>(keccak256(HIGHER_ADDRESS), higher_acc).unwrap();
+        for (slot, value) in [(STORAGE, 1), (other_slot, 3)] {
+            tx.put::<tables::HashedStorages>(
+                hashed_address,
+                StorageEntry { key: keccak256(slot), value: U256::from(value) },
+            )
+            .unwrap();
+        }
+        let (root_at2, updates) = StateRoot::from_tx(&tx).root_with_updates().unwrap();
+        updates.flush(&tx).unwrap();
+
+        // the proof generated from the trie tables while they still hold the state of block 2
+        let expected = Proof::new(&tx).account_proof(ADDRESS, &[STORAGE, other_slot]).unwrap();
+        assert_eq!(keccak256(&expected.proof[0]), root_at2);
+
+        // the changes of block 2
+        tx.put::<tables::CanonicalHeaders>(2, B256::with_last_byte(2)).unwrap();
+        tx.put::<tables::AccountChangeSets>(
+            2,
+            AccountBeforeTx { address: ADDRESS, info: Some(acc_at2) },
+        )
+        .unwrap();
+        tx.put::<tables::StorageChangeSets>(
+            (2, ADDRESS).into(),
+            StorageEntry { key: STORAGE, value: U256::from(1) },
+        )
+        .unwrap();
+        tx.put::<tables::HashedAccounts>(hashed_address, acc_plain).unwrap();
+        tx.delete::<tables::HashedStorages>(
+            hashed_address,
+            Some(StorageEntry { key: keccak256(STORAGE), value: U256::from(1) }),
+        )
+        .unwrap();
+        tx.put::<tables::HashedStorages>(
+            hashed_address,
+            StorageEntry { key: keccak256(STORAGE), value: U256::from(2) },
+        )
+        .unwrap();
+        let (root_plain, updates) = StateRoot::incremental_root_with_updates(&tx, 2..=2).unwrap();
+        updates.flush(&tx).unwrap();
+        assert_ne!(root_at2, root_plain);
+        tx.commit().unwrap();
+
+        let tx = factory.provider().unwrap().into_tx();
+        let proof = HistoricalStateProviderRef::new(&tx, 2, static_file_provider)
+            .proof(ADDRESS, &[STORAGE, other_slot])
+            .unwrap();
+
+        // the proof is against the state root of block 2, not the one of the trie tables
+        assert_eq!(keccak256(&proof.proof[0]), root_at2);
+        assert_eq!(proof.info, Some(acc_at2));
+        assert_eq!(keccak256(&proof.storage_proofs[0].proof[0]), proof.storage_root);
+        assert_eq!(proof.storage_proofs[0].value, U256::from(1));
+        assert_eq!(proof.storage_proofs[1].value, U256::from(3));
+
+        // all nodes of the account and storage proofs match the ones of the historical trie
+        assert_eq!(proof, expected);
+    }
+
     #[test]
     fn history_provider_unavailable() {
         let factory = create_test_provider_factory();
@@ -716,4 +908,115 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    #[test]
+    fn history_provider_lookups_with_cache() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+
+        // two shards with a boundary at block 7
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey { key: ADDRESS, highest_block_number: 7 },
+            BlockNumberList::new([3, 7]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey { key: ADDRESS, highest_block_number: u64::MAX },
+            BlockNumberList::new([10, 15]).unwrap(),
+        )
+        .unwrap();
+        // the remaining shard of a partially pruned history
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey { key: HIGHER_ADDRESS, highest_block_number: u64::MAX },
+            BlockNumberList::new([12]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StoragesHistory>(
+            StorageShardedKey::new(ADDRESS, STORAGE, 8),
+            BlockNumberList::new([4, 8]).unwrap(),
+        )
+        .unwrap();
+        tx.put::<tables::StoragesHistory>(
+            StorageShardedKey::new(ADDRESS, STORAGE, u64::MAX),
+            BlockNumberList::new([9]).unwrap(),
+        )
+        .unwrap();
+        tx.commit().unwrap();
+
+        let static_file_provider = factory.static_file_provider();
+        let cache = HistoryShardCache::new(10);
+        let tx = factory.provider().unwrap().into_tx();
+        let pruned = LowestAvailableBlocks {
+            account_history_block_number: Some(5),
+            storage_history_block_number: Some(5),
+        };
+        for lowest_available_blocks in [LowestAvailableBlocks::default(), pruned] {
+            let history_cache = cache.reader(tx.snapshot_id());
+            // the lookups are repeated in both directions, so they're also resolved from shards
+            // that were cached by lookups at blocks on the other side of a shard boundary
+            for block_number in (0..=20).chain((0..=20).rev()) {
+                let provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
+                    &tx,
+                    block_number,
+                    lowest_available_blocks,
+                    static_file_provider.clone(),
+                );
+                let cached_provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
+                    &tx,
+                    block_number,
+                    lowest_available_blocks,
+                    static_file_provider.clone(),
+                )
+                .with_history_cache(Some(&history_cache));
+
+                for address in [ADDRESS, HIGHER_ADDRESS] {
+                    assert_eq!(
+                        cached_provider.account_history_lookup(address),
+                        provider.account_history_lookup(address),
+                        "account {address} at block {block_number}"
+                    );
+                }
+                assert_eq!(
+                    cached_provider.storage_history_lookup(ADDRESS, STORAGE),
+                    provider.storage_history_lookup(ADDRESS, STORAGE),
+                    "storage at block {block_number}"
+                );
+            }
+            assert!(history_cache.get(HistoryKey::Account(ADDRESS), 7).is_some());
+            assert!(history_cache.get(HistoryKey::Account(ADDRESS), 8).is_some());
+        }
+
+        // blocks on the shard boundary resolve to the lower shard
+        let history_cache = cache.reader(tx.snapshot_id());
+        for (block_number, expected) in [(7, 7), (8, 10)] {
+            let provider =
+                HistoricalStateProviderRef::new(&tx, block_number, static_file_provider.clone())
+                    .with_history_cache(Some(&history_cache));
+            assert_eq!(
+                provider.account_history_lookup(ADDRESS),
+                Ok(HistoryInfo::InChangeset(expected))
+            );
+            assert_eq!(
+                provider.account_history_lookup(ADDRESS),
+                Ok(HistoryInfo::InChangeset(expected))
+            );
+        }
+
+        // the partially pruned history is looked up in the changesets
+        let provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
+            &tx,
+            6,
+            pruned,
+            static_file_provider,
+        )
+        .with_history_cache(Some(&history_cache));
+        assert_eq!(
+            provider.account_history_lookup(HIGHER_ADDRESS),
+            Ok(HistoryInfo::InChangeset(12))
+        );
+        assert_eq!(
+            provider.account_history_lookup(HIGHER_ADDRESS),
+            Ok(HistoryInfo::InChangeset(12))
+        );
+    }
 }
@@ -0,0 +1,273 @@
+//! Cache of the history shards that were resolved by historical state lookups.
+//!
+//! Historical lookups of the same account or storage slot at nearby blocks usually resolve to
+//! the same shard of [tables::AccountsHistory](reth_db::tables::AccountsHistory) or
+//! [tables::StoragesHistory](reth_db::tables::StoragesHistory). The resolved shards are cached
+//! with the boundaries of the block range they're resolved for, so a lookup of a cached shard
+//! skips the seek of the history cursor.
+//!
+//! The cache is shared by all providers of a [ProviderFactory](crate::ProviderFactory). The cached
+//! shards are tagged with the snapshot of the database they were read from, and read-only
+//! providers only use the shards of the snapshot of their own transaction. Every committed write
+//! creates a new snapshot, no matter which transaction or process committed it, so providers never
+//! see the shards of another snapshot. The shards of older snapshots are dropped once a provider
+//! of a newer snapshot caches a shard.
+
+use parking_lot::Mutex;
+use reth_db::BlockNumberList;
+use reth_metrics::{metrics::Counter, Metrics};
+use reth_primitives::{Address, BlockNumber, B256};
+use schnellru::{ByLength, LruMap};
+use std::{fmt, sync::Arc};
+
+/// The default number of accounts and storage slots whose history shards are cached.
+pub const DEFAULT_HISTORY_SHARD_CACHE_CAPACITY: u32 = 10_000;
+
+/// The maximum number of shards that are cached per account or storage slot.
+const MAX_SHARDS_PER_KEY: usize = 4;
+
+/// The key of an account or a storage slot in the history tables.
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub(crate) enum HistoryKey {
+    /// An account of [tables::AccountsHistory](reth_db::tables::AccountsHistory).
+    Account(Address),
+    /// A storage slot of [tables::StoragesHistory](reth_db::tables::StoragesHistory).
+    Storage(Address, B256),
+}
+
+/// A history shard of an account or storage slot.
+#[derive(Debug)]
+pub(crate) struct CachedShard {
+    /// The highest block number of the previous shard of the key, or `None` if this is the first
+    /// shard of the key.
+    pub(crate) previous_highest_block_number: Option<BlockNumber>,
+    /// The highest block number of the shard, [u64::MAX] for the last shard of the key.
+    pub(crate) highest_block_number: BlockNumber,
+    /// The block numbers of the changesets of the key in this shard.
+    pub(crate) chunk: BlockNumberList,
+}
+
+impl CachedShard {
+    /// Returns true if a lookup of the key at the given block resolves to this shard, i.e. if the
+    /// block is above the previous shard and not above the highest block number of this shard.
+    fn contains(&self, block_number: BlockNumber) -> bool {
+        self.previous_highest_block_number.map_or(true, |previous| previous < block_number) &&
+            block_number <= self.highest_block_number
+    }
+}
+
+/// A shared, bounded cache of history shards, see the [module docs](self).
+#[derive(Clone)]
+pub struct HistoryShardCache {
+    inner: Arc<HistoryShardCacheInner>,
+}
+
+impl HistoryShardCache {
+    /// Creates a cache of the history shards of up to `capacity` accounts and storage slots.
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            inner: Arc::new(HistoryShardCacheInner {
+                shards: Mutex::new(CachedShards {
+                    snapshot_id: 0,
+                    shards: LruMap::new(ByLength::new(capacity)),
+                }),
+                account_metrics: HistoryShardCacheMetrics::new_with_labels(&[(
+                    "table",
+                    "AccountsHistory",
+                )]),
+                storage_metrics: HistoryShardCacheMetrics::new_with_labels(&[(
+                    "table",
+                    "StoragesHistory",
+                )]),
+            }),
+        }
+    }
+
+    /// Returns a handle for a read-only transaction that reads the snapshot with the given id, see
+    /// [DbTx::snapshot_id](reth_db::transaction::DbTx::snapshot_id).
+    ///
+    /// The handle doesn't use the cache if the id of the snapshot is unknown.
+    pub(crate) fn reader(&self, snapshot_id: Option<u64>) -> HistoryShardCacheHandle {
+        HistoryShardCacheHandle { cache: self.clone(), snapshot_id }
+    }
+
+    fn metrics(&self, key: &HistoryKey) -> &HistoryShardCacheMetrics {
+        match key {
+            HistoryKey::Account(_) => &self.inner.account_metrics,
+            HistoryKey::Storage(..) => &self.inner.storage_metrics,
+        }
+    }
+}
+
+impl Default for HistoryShardCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_SHARD_CACHE_CAPACITY)
+    }
+}
+
+impl fmt::Debug for HistoryShardCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let shards = self.inner.shards.lock();
+        f.debug_struct("HistoryShardCache")
+            .field("snapshot_id", &shards.snapshot_id)
+            .field("len", &shards.shards.len())
+            .finish_non_exhaustive()
+    }
+}
+
+struct HistoryShardCacheInner {
+    shards: Mutex<CachedShards>,
+    account_metrics: HistoryShardCacheMetrics,
+    storage_metrics: HistoryShardCacheMetrics,
+}
+
+struct CachedShards {
+    /// The id of the snapshot of the database the cached shards were read from.
+    snapshot_id: u64,
+    /// The most recently resolved shards of every key, ordered by their block numbers.
+    shards: LruMap<HistoryKey, Vec<Arc<CachedShard>>, ByLength>,
+}
+
+/// The access of a database provider to the [HistoryShardCache] of its factory.
+#[derive(Debug, Clone)]
+pub(crate) struct HistoryShardCacheHandle {
+    cache: HistoryShardCache,
+    /// The id of the snapshot of the database the read-only transaction reads, or `None` if the
+    /// cache is not used.
+    snapshot_id: Option<u64>,
+}
+
+impl HistoryShardCacheHandle {
+    /// Returns the cached shard that a lookup of the key at the given block resolves to.
+    pub(crate) fn get(
+        &self,
+        key: HistoryKey,
+        block_number: BlockNumber,
+    ) -> Option<Arc<CachedShard>> {
+        let snapshot_id = self.snapshot_id?;
+        let shard = {
+            let mut shards = self.cache.inner.shards.lock();
+            if shards.snapshot_id != snapshot_id {
+                return None
+            }
+            shards
+                .shards
+                .get(&key)
+                .and_then(|shards| shards.iter().find(|shard| shard.contains(block_number)))
+                .cloned()
+        };
+
+        let metrics = self.cache.metrics(&key);
+        if shard.is_some() {
+            metrics.hits.increment(1);
+        } else {
+            metrics.misses.increment(1);
+        }
+        shard
+    }
+
+    /// Caches a shard that a lookup of the key resolved to.
+    pub(crate) fn insert(&self, key: HistoryKey, shard: Arc<CachedShard>) {
+        let Some(snapshot_id) = self.snapshot_id else { return };
+        let mut shards = self.cache.inner.shards.lock();
+        if snapshot_id < shards.snapshot_id {
+            // the shard may be outdated
+            return
+        }
+        if snapshot_id > shards.snapshot_id {
+            // the cached shards may be outdated
+            shards.snapshot_id = snapshot_id;
+            shards.shards.clear();
+        }
+
+        if let Some(cached) = shards.shards.get(&key) {
+            if let Err(index) = cached.binary_search_by_key(&shard.highest_block_number, |cached| {
+                cached.highest_block_number
+            }) {
+                cached.insert(index, shard);
+                if cached.len() > MAX_SHARDS_PER_KEY {
+                    // evict the shard that's the farthest from the inserted one
+                    if index < cached.len() / 2 {
+                        cached.pop();
+                    } else {
+                        cached.remove(0);
+                    }
+                }
+            }
+        } else {
+            shards.shards.insert(key, vec![shard]);
+        }
+    }
+}
+
+/// Metrics of the [HistoryShardCache] for one history table.
+#[derive(Metrics)]
+#[metrics(scope = "storage.providers.history_cache")]
+struct HistoryShardCacheMetrics {
+    /// The number of history lookups that were resolved from the cache.
+    hits: Counter,
+    /// The number of history lookups that had to seek the history table.
+    misses: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shard(previous: Option<BlockNumber>, highest: BlockNumber) -> Arc<CachedShard> {
+        Arc::new(CachedShard {
+            previous_highest_block_number: previous,
+            highest_block_number: highest,
+            chunk: BlockNumberList::new([highest]).unwrap(),
+        })
+    }
+
+    #[test]
+    fn shard_boundaries() {
+        let cache = HistoryShardCache::new(10);
+        let reader = cache.reader(Some(1));
+        let key = HistoryKey::Account(Address::ZERO);
+
+        reader.insert(key, shard(None, 10));
+        reader.insert(key, shard(Some(10), 20));
+        assert_eq!(reader.get(key, 0).unwrap().highest_block_number, 10);
+        // a block on the boundary belongs to the lower shard
+        assert_eq!(reader.get(key, 10).unwrap().highest_block_number, 10);
+        assert_eq!(reader.get(key, 11).unwrap().highest_block_number, 20);
+        assert!(reader.get(key, 21).is_none());
+        assert!(reader.get(HistoryKey::Storage(Address::ZERO, B256::ZERO), 0).is_none());
+
+        // at most a few shards are kept per key
+        for highest in [30, 40, 50] {
+            reader.insert(key, shard(Some(highest - 10), highest));
+        }
+        assert!(reader.get(key, 5).is_none());
+        assert!(reader.get(key, 45).is_some());
+    }
+
+    #[test]
+    fn shards_of_one_snapshot() {
+        let cache = HistoryShardCache::new(10);
+        let key = HistoryKey::Account(Address::ZERO);
+        let old_reader = cache.reader(Some(1));
+
+        // transactions with an unknown snapshot don't use the cache
+        let untracked = cache.reader(None);
+        untracked.insert(key, shard(None, 10));
+        assert!(untracked.get(key, 5).is_none());
+
+        old_reader.insert(key, shard(None, 10));
+        assert!(old_reader.get(key, 5).is_some());
+
+        // a reader of a newer snapshot, i.e. after a commit, doesn't see the older shards
+        let reader = cache.reader(Some(2));
+        assert!(reader.get(key, 5).is_none());
+        reader.insert(key, shard(None, 20));
+        assert_eq!(reader.get(key, 5).unwrap().highest_block_number, 20);
+
+        // and the shards of older snapshots are neither used nor cached anymore
+        assert!(old_reader.get(key, 5).is_none());
+        old_reader.insert(key, shard(None, 10));
+        assert_eq!(reader.get(key, 5).unwrap().highest_block_number, 20);
+    }
+}
@@ -1,5 +1,5 @@
 use super::{
-    metrics::{StaticFileProviderMetrics, StaticFileProviderOperation},
+    metrics::{thread_major_page_faults, StaticFileProviderMetrics, StaticFileProviderOperation},
     LoadedJarRef,
 };
 use crate::{
@@ -16,9 +16,10 @@ use reth_primitives::{
     TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, B256, U256,
 };
 use std::{
-    ops::{Deref, RangeBounds},
+    ops::{Deref, Range, RangeBounds},
     sync::Arc,
 };
+use tracing::debug;
 
 /// Provider over a specific `NippyJar` and range.
 #[derive(Debug)]
@@ -28,6 +29,21 @@ pub struct StaticFileJarProvider<'a> {
     /// Another kind of static file segment to help query data from the main one.
     auxiliary_jar: Option<Box<Self>>,
     metrics: Option<Arc<StaticFileProviderMetrics>>,
+    /// The major page faults of the current thread when metrics were enabled. The page faults
+    /// until the provider is dropped are attributed to its segment.
+    major_page_faults: Option<u64>,
+    /// Whether the rows of range walks are read ahead, see [`Self::range_cursor`].
+    prefetch_ranges: bool,
+}
+
+impl<'a> Drop for StaticFileJarProvider<'a> {
+    fn drop(&mut self) {
+        if let (Some(metrics), Some(start)) = (&self.metrics, self.major_page_faults) {
+            if let Some(end) = thread_major_page_faults() {
+                metrics.record_major_page_faults(self.segment(), end.saturating_sub(start));
+            }
+        }
+    }
 }
 
 impl<'a> Deref for StaticFileJarProvider<'a> {
@@ -39,7 +55,13 @@ impl<'a> Deref for StaticFileJarProvider<'a> {
 
 impl<'a> From<LoadedJarRef<'a>> for StaticFileJarProvider<'a> {
     fn from(value: LoadedJarRef<'a>) -> Self {
-        StaticFileJarProvider { jar: value, auxiliary_jar: None, metrics: None }
+        StaticFileJarProvider {
+            jar: value,
+            auxiliary_jar: None,
+            metrics: None,
+            major_page_faults: None,
+            prefetch_ranges: false,
+        }
     }
 }
 
@@ -62,6 +84,34 @@ impl<'a> StaticFileJarProvider<'a> {
         Ok(result)
     }
 
+    /// Provides a cursor to read the given range of `BlockNumber` or `TxNumber` in order.
+    ///
+    /// If enabled with [`Self::with_range_prefetch`], the OS is asked to read the rows of the range
+    /// ahead, which is otherwise disabled if the static files are accessed at random.
+    pub fn range_cursor<'b>(&'b self, range: Range<u64>) -> ProviderResult<StaticFileCursor<'a>>
+    where
+        'b: 'a,
+    {
+        let cursor = self.cursor()?;
+        if self.prefetch_ranges && !range.is_empty() {
+            if let Err(err) = cursor.prefetch(range) {
+                debug!(
+                    target: "provider::static_file",
+                    segment = ?self.segment(),
+                    %err,
+                    "Failed to prefetch rows"
+                );
+            } else if let Some(metrics) = &self.metrics {
+                metrics.record_segment_operation(
+                    self.segment(),
+                    StaticFileProviderOperation::Prefetch,
+                    None,
+                );
+            }
+        }
+        Ok(cursor)
+    }
+
     /// Adds a new auxiliary static file to help query data from the main one
     pub fn with_auxiliary(mut self, auxiliary_jar: StaticFileJarProvider<'a>) -> Self {
         self.auxiliary_jar = Some(Box::new(auxiliary_jar));
@@ -69,8 +119,18 @@ impl<'a> StaticFileJarProvider<'a> {
     }
 
     /// Enables metrics on the provider.
+    ///
+    /// The major page faults of the current thread while the provider is in use are recorded
+    /// for its segment, on Linux.
     pub fn with_metrics(mut self, metrics: Arc<StaticFileProviderMetrics>) -> Self {
         self.metrics = Some(metrics);
+        self.major_page_faults = thread_major_page_faults();
+        self
+    }
+
+    /// Reads the rows of range walks ahead, see [`Self::range_cursor`].
+    pub fn with_range_prefetch(mut self) -> Self {
+        self.prefetch_ranges = true;
         self
     }
 }
@@ -103,7 +163,7 @@ impl<'a> HeaderProvider for StaticFileJarProvider<'a> {
     fn headers_range(&self, range: impl RangeBounds<BlockNumber>) -> ProviderResult<Vec<Header>> {
         let range = to_range(range);
 
-        let mut cursor = self.cursor()?;
+        let mut cursor = self.range_cursor(range.clone())?;
         let mut headers = Vec::with_capacity((range.end - range.start) as usize);
 
         for num in range.start..range.end {
@@ -129,7 +189,7 @@ impl<'a> HeaderProvider for StaticFileJarProvider<'a> {
     ) -> ProviderResult<Vec<SealedHeader>> {
         let range = to_range(range);
 
-        let mut cursor = self.cursor()?;
+        let mut cursor = self.range_cursor(range.clone())?;
         let mut headers = Vec::with_capacity((range.end - range.start) as usize);
 
         for number in range.start..range.end {
@@ -157,7 +217,7 @@ impl<'a> BlockHashReader for StaticFileJarProvider<'a> {
         start: BlockNumber,
         end: BlockNumber,
     ) -> ProviderResult<Vec<B256>> {
-        let mut cursor = self.cursor()?;
+        let mut cursor = self.range_cursor(start..end)?;
         let mut hashes = Vec::with_capacity((end - start) as usize);
 
         for number in start..end {
@@ -260,7 +320,7 @@ impl<'a> TransactionsProvider for StaticFileJarProvider<'a> {
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<reth_primitives::TransactionSignedNoHash>> {
         let range = to_range(range);
-        let mut cursor = self.cursor()?;
+        let mut cursor = self.range_cursor(range.clone())?;
         let mut txes = Vec::with_capacity((range.end - range.start) as usize);
 
         for num in range {
@@ -315,7 +375,7 @@ impl<'a> ReceiptProvider for StaticFileJarProvider<'a> {
         range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Receipt>> {
         let range = to_range(range);
-        let mut cursor = self.cursor()?;
+        let mut cursor = self.range_cursor(range.clone())?;
         let mut receipts = Vec::with_capacity((range.end - range.start) as usize);
 
         for num in range {
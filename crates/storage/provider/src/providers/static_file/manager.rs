@@ -1,6 +1,8 @@
 use super::{
-    metrics::StaticFileProviderMetrics, LoadedJar, StaticFileJarProvider, StaticFileProviderRW,
-    StaticFileProviderRWRefMut, BLOCKS_PER_STATIC_FILE,
+    metrics::StaticFileProviderMetrics,
+    tx_hash_index::{tx_hash_index_path, TxHashIndex},
+    LoadedJar, StaticFileJarProvider, StaticFileProviderRW, StaticFileProviderRWRefMut,
+    BLOCKS_PER_STATIC_FILE,
 };
 use crate::{
     to_range, BlockHashReader, BlockNumReader, BlockReader, BlockSource, HeaderProvider,
@@ -8,7 +10,7 @@ use crate::{
     TransactionsProviderExt, WithdrawalsProvider,
 };
 use dashmap::{mapref::entry::Entry as DashMapEntry, DashMap};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use reth_db::{
     codecs::CompactU256,
     models::StoredBlockBodyIndices,
@@ -20,7 +22,10 @@ use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_nippy_jar::NippyJar;
 use reth_primitives::{
     keccak256,
-    static_file::{find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive},
+    static_file::{
+        find_fixed_range, AccessPattern, Compression, HighestStaticFiles, SegmentHeader,
+        SegmentRangeInclusive,
+    },
     Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, ChainInfo, Header,
     Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment, TransactionMeta,
     TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
@@ -32,13 +37,22 @@ use std::{
     path::{Path, PathBuf},
     sync::{mpsc, Arc},
 };
-use tracing::warn;
+use tracing::{debug, warn};
 
 /// Alias type for a map that can be queried for block ranges from a transaction
 /// segment respectively. It uses `TxNumber` to represent the transaction end of a static file
 /// range.
 type SegmentRanges = HashMap<StaticFileSegment, BTreeMap<TxNumber, SegmentRangeInclusive>>;
 
+/// The number of transactions whose hashes are computed at once when indexing a static file, see
+/// [`StaticFileProvider::update_transaction_hash_index`].
+const TX_HASH_INDEX_BATCH_SIZE: u64 = 100_000;
+
+/// The maximum number of transactions that were appended to a static file since the last update of
+/// its transaction hash index, which are scanned on a lookup by hash, see
+/// [`StaticFileProvider::transaction_id_by_hash_index`].
+const TX_HASH_INDEX_MAX_UNINDEXED_SCAN: u64 = 1_000;
+
 /// [`StaticFileProvider`] manages all existing [`StaticFileJarProvider`].
 #[derive(Debug, Default, Clone)]
 pub struct StaticFileProvider(pub(crate) Arc<StaticFileProviderInner>);
@@ -66,6 +80,8 @@ pub struct StaticFileProviderInner {
     /// Maintains a map which allows for concurrent access to different `NippyJars`, over different
     /// segments and ranges.
     map: DashMap<(BlockNumber, StaticFileSegment), LoadedJar>,
+    /// Min static file block for each segment
+    static_files_min_block: RwLock<HashMap<StaticFileSegment, u64>>,
     /// Max static file block for each segment
     static_files_max_block: RwLock<HashMap<StaticFileSegment, u64>>,
     /// Available static file block ranges on disk indexed by max transactions.
@@ -77,6 +93,20 @@ pub struct StaticFileProviderInner {
     load_filters: bool,
     /// Maintains a map of StaticFile writers for each [`StaticFileSegment`]
     writers: DashMap<StaticFileSegment, StaticFileProviderRW>,
+    /// Compression of newly created static files, for the segments that don't use the default.
+    /// See [`StaticFileProviderInner::compression`].
+    compression: HashMap<StaticFileSegment, Compression>,
+    /// Access pattern the OS is advised of for the mapped static files. If not set, the OS reads
+    /// ahead with its defaults.
+    access_pattern: Option<AccessPattern>,
+    /// Whether the transactions are indexed by hash, so they can be looked up after the
+    /// `TransactionHashNumbers` table is pruned.
+    tx_hash_index: bool,
+    /// The loaded transaction hash indexes, by the end of the fixed block range of their static
+    /// file. [None] if the static file isn't indexed.
+    tx_hash_indexes: DashMap<BlockNumber, Option<Arc<TxHashIndex>>>,
+    /// Held by the update of the transaction hash indexes, so only one update runs at a time.
+    tx_hash_index_update: Mutex<()>,
     metrics: Option<Arc<StaticFileProviderMetrics>>,
 }
 
@@ -86,15 +116,44 @@ impl StaticFileProviderInner {
         let provider = Self {
             map: Default::default(),
             writers: Default::default(),
+            static_files_min_block: Default::default(),
             static_files_max_block: Default::default(),
             static_files_tx_index: Default::default(),
             path: path.as_ref().to_path_buf(),
             load_filters: false,
+            compression: Default::default(),
+            access_pattern: None,
+            tx_hash_index: false,
+            tx_hash_indexes: Default::default(),
+            tx_hash_index_update: Default::default(),
             metrics: None,
         };
 
         Ok(provider)
     }
+
+    /// Returns the compression of newly created static files of the segment.
+    ///
+    /// Defaults to [`Compression::Lz4`] for headers. Transactions and receipts are uncompressed by
+    /// default, since their values are already compressed with zstd by their encoding, and so are
+    /// transaction senders, since addresses don't compress.
+    ///
+    /// Existing static files keep the compression they were created with, which is stored in
+    /// their configuration, so static files of the same segment can use different compressions.
+    pub fn compression(&self, segment: StaticFileSegment) -> Compression {
+        self.compression.get(&segment).copied().unwrap_or(match segment {
+            StaticFileSegment::Headers => Compression::Lz4,
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::TransactionSenders => Compression::Uncompressed,
+        })
+    }
+
+    /// Returns true if the rows of range walks are read ahead explicitly, which is the case if the
+    /// mappings are advised as [`AccessPattern::Random`].
+    fn prefetch_ranges(&self) -> bool {
+        self.access_pattern == Some(AccessPattern::Random)
+    }
 }
 
 impl StaticFileProvider {
@@ -106,6 +165,41 @@ impl StaticFileProvider {
         Self(Arc::new(provider))
     }
 
+    /// Sets the compression of newly created static files of the segment.
+    ///
+    /// [`Compression::ZstdWithDictionary`] is not supported, since static files are written
+    /// incrementally and there's no data to train the dictionary on when they're created.
+    pub fn with_compression(self, segment: StaticFileSegment, compression: Compression) -> Self {
+        let mut provider =
+            Arc::try_unwrap(self.0).expect("should be called when initializing only");
+        provider.compression.insert(segment, compression);
+        Self(Arc::new(provider))
+    }
+
+    /// Sets the access pattern the OS is advised of for the mapped static files.
+    ///
+    /// By default, no access pattern is advised. With [`AccessPattern::Random`], e.g. for nodes
+    /// serving many point lookups by hash, nothing is read ahead except the rows of range walks,
+    /// which are prefetched explicitly.
+    pub fn with_access_pattern(self, access_pattern: AccessPattern) -> Self {
+        let mut provider =
+            Arc::try_unwrap(self.0).expect("should be called when initializing only");
+        provider.access_pattern = Some(access_pattern);
+        Self(Arc::new(provider))
+    }
+
+    /// Indexes the transactions of the static files by hash, see
+    /// [`StaticFileProvider::update_transaction_hash_index`].
+    ///
+    /// The indexes are consulted by [`StaticFileProvider::transaction_id_by_hash_index`], which
+    /// finds the transactions whose entries of `TransactionHashNumbers` are pruned.
+    pub fn with_transaction_hash_index(self) -> Self {
+        let mut provider =
+            Arc::try_unwrap(self.0).expect("should be called when initializing only");
+        provider.tx_hash_index = true;
+        Self(Arc::new(provider))
+    }
+
     /// Enables metrics on the [`StaticFileProvider`].
     pub fn with_metrics(self) -> Self {
         let mut provider =
@@ -253,6 +347,14 @@ impl StaticFileProvider {
         };
 
         jar.delete().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        if segment == StaticFileSegment::Transactions {
+            self.tx_hash_indexes.remove(&fixed_block_range.end());
+            let index_path =
+                tx_hash_index_path(&self.path.join(segment.filename(&fixed_block_range)));
+            if index_path.exists() {
+                reth_primitives::fs::remove_file(index_path)?;
+            }
+        }
 
         let mut segment_max_block = None;
         if fixed_block_range.start() > 0 {
@@ -284,12 +386,15 @@ impl StaticFileProvider {
                 jar.load_filters().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
             }
 
-            self.map.entry(key).insert(LoadedJar::new(jar)?).downgrade().into()
+            self.map.entry(key).insert(LoadedJar::new(jar, self.access_pattern)?).downgrade().into()
         };
 
         if let Some(metrics) = &self.metrics {
             provider = provider.with_metrics(metrics.clone());
         }
+        if self.prefetch_ranges() {
+            provider = provider.with_range_prefetch();
+        }
         Ok(provider)
     }
 
@@ -345,6 +450,7 @@ impl StaticFileProvider {
         segment: StaticFileSegment,
         segment_max_block: Option<BlockNumber>,
     ) -> ProviderResult<()> {
+        let mut min_block = self.static_files_min_block.write();
         let mut max_block = self.static_files_max_block.write();
         let mut tx_index = self.static_files_tx_index.write();
 
@@ -391,21 +497,34 @@ impl StaticFileProvider {
                     if jar.user_header().expected_block_start() == 0 &&
                         matches!(
                             segment,
-                            StaticFileSegment::Receipts | StaticFileSegment::Transactions
+                            StaticFileSegment::Receipts |
+                                StaticFileSegment::Transactions |
+                                StaticFileSegment::TransactionSenders
                         )
                     {
                         tx_index.remove(&segment);
                     }
                 }
 
+                // The first static file of the segment might start at any block
+                let block_start = jar.user_header().expected_block_start();
+                min_block
+                    .entry(segment)
+                    .and_modify(|min_block| *min_block = (*min_block).min(block_start))
+                    .or_insert(block_start);
+
                 // Update the cached provider.
-                self.map.insert((fixed_range.end(), segment), LoadedJar::new(jar)?);
+                self.map.insert(
+                    (fixed_range.end(), segment),
+                    LoadedJar::new(jar, self.access_pattern)?,
+                );
 
                 // Delete any cached provider that no longer has an associated jar.
                 self.map.retain(|(end, seg), _| !(*seg == segment && *end > fixed_range.end()));
             }
             None => {
                 tx_index.remove(&segment);
+                min_block.remove(&segment);
                 max_block.remove(&segment);
             }
         };
@@ -415,6 +534,7 @@ impl StaticFileProvider {
 
     /// Initializes the inner transaction and block index
     pub fn initialize_index(&self) -> ProviderResult<()> {
+        let mut min_block = self.static_files_min_block.write();
         let mut max_block = self.static_files_max_block.write();
         let mut tx_index = self.static_files_tx_index.write();
 
@@ -423,6 +543,11 @@ impl StaticFileProvider {
         for (segment, ranges) in
             iter_static_files(&self.path).map_err(|e| ProviderError::NippyJar(e.to_string()))?
         {
+            // Update first block for each segment
+            if let Some((block_range, _)) = ranges.first() {
+                min_block.insert(segment, block_range.start());
+            }
+
             // Update last block for each segment
             if let Some((block_range, _)) = ranges.last() {
                 max_block.insert(segment, block_range.end());
@@ -448,6 +573,11 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Gets the lowest static file block if it exists for a static file segment.
+    pub fn get_lowest_static_file_block(&self, segment: StaticFileSegment) -> Option<BlockNumber> {
+        self.static_files_min_block.read().get(&segment).copied()
+    }
+
     /// Gets the highest static file block if it exists for a static file segment.
     pub fn get_highest_static_file_block(&self, segment: StaticFileSegment) -> Option<BlockNumber> {
         self.static_files_max_block.read().get(&segment).copied()
@@ -467,6 +597,8 @@ impl StaticFileProvider {
             headers: self.get_highest_static_file_block(StaticFileSegment::Headers),
             receipts: self.get_highest_static_file_block(StaticFileSegment::Receipts),
             transactions: self.get_highest_static_file_block(StaticFileSegment::Transactions),
+            transaction_senders: self
+                .get_highest_static_file_block(StaticFileSegment::TransactionSenders),
         }
     }
 
@@ -493,6 +625,187 @@ impl StaticFileProvider {
         Ok(None)
     }
 
+    /// Returns the number of the transaction with the given hash from the transaction hash indexes
+    /// of the static files, or [None] if the transactions aren't indexed by hash.
+    ///
+    /// The static files are probed from the newest to the oldest, see
+    /// [`StaticFileProvider::indexed_transaction_id`]. The transactions that aren't indexed yet,
+    /// e.g. during the initial indexing, are only found if at most
+    /// [`TX_HASH_INDEX_MAX_UNINDEXED_SCAN`] transactions of their static file aren't indexed.
+    pub fn transaction_id_by_hash_index(&self, hash: TxHash) -> ProviderResult<Option<TxNumber>> {
+        if !self.tx_hash_index {
+            return Ok(None)
+        }
+
+        self.find_static_file(StaticFileSegment::Transactions, |jar_provider| {
+            self.indexed_transaction_id(&jar_provider, hash)
+        })
+    }
+
+    /// Returns the number of the transaction with the given hash in the transactions static file,
+    /// using its transaction hash index.
+    ///
+    /// Every candidate of the index is checked against the hash of its transaction, so only a
+    /// matching transaction is returned. The transactions that were appended since the last update
+    /// of the index are scanned, unless there are more than [`TX_HASH_INDEX_MAX_UNINDEXED_SCAN`]
+    /// of them. A static file without an index is a miss.
+    fn indexed_transaction_id(
+        &self,
+        jar_provider: &StaticFileJarProvider<'_>,
+        hash: TxHash,
+    ) -> ProviderResult<Option<TxNumber>> {
+        let Some(tx_range) = jar_provider.user_header().tx_range().copied() else {
+            return Ok(None)
+        };
+        let fixed_block_range = find_fixed_range(jar_provider.user_header().expected_block_start());
+        let Some(index) = self.get_tx_hash_index(&fixed_block_range)? else { return Ok(None) };
+
+        let indexed = tx_range.start()..=tx_range.end().min(index.next_tx().saturating_sub(1));
+        for tx_number in index.candidates(&hash).into_iter().filter(|num| indexed.contains(num)) {
+            if jar_provider
+                .transaction_by_id_no_hash(tx_number)?
+                .is_some_and(|transaction| transaction.hash() == hash)
+            {
+                return Ok(Some(tx_number))
+            }
+        }
+
+        let unindexed = index.next_tx().max(tx_range.start())..=tx_range.end();
+        if unindexed.end().saturating_sub(*unindexed.start()) >= TX_HASH_INDEX_MAX_UNINDEXED_SCAN {
+            return Ok(None)
+        }
+        let mut cursor = jar_provider.cursor()?;
+        for tx_number in unindexed {
+            if cursor
+                .get_one::<TransactionMask<TransactionSignedNoHash>>(tx_number.into())?
+                .is_some_and(|transaction| transaction.hash() == hash)
+            {
+                return Ok(Some(tx_number))
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Indexes the transactions of the static files by hash, if enabled by
+    /// [`StaticFileProvider::with_transaction_hash_index`].
+    ///
+    /// Every transactions static file has its own index, which is extended with the transactions
+    /// that were appended to the static file since the last update, so existing static files are
+    /// indexed on the first update. The progress is persisted in batches, so an interrupted update
+    /// is resumed. The index of a static file that was truncated is rebuilt.
+    ///
+    /// If another update is in progress, e.g. the initial indexing, this returns immediately: the
+    /// transactions are indexed by that update or the next one, and lookups only scan a small tail
+    /// of the transactions that aren't indexed yet in the meantime.
+    ///
+    /// Returns the number of newly indexed transactions.
+    pub fn update_transaction_hash_index(&self) -> ProviderResult<u64> {
+        if !self.tx_hash_index {
+            return Ok(0)
+        }
+        let Some(_lock) = self.tx_hash_index_update.try_lock() else {
+            debug!(target: "provider::static_file", "Transaction hash index update in progress");
+            return Ok(0)
+        };
+
+        let mut indexed = 0;
+        for fixed_block_range in self.transaction_static_files() {
+            let jar_provider = self
+                .get_or_create_jar_provider(StaticFileSegment::Transactions, &fixed_block_range)?;
+            let Some(tx_range) = jar_provider.user_header().tx_range().copied() else { continue };
+            let path = tx_hash_index_path(jar_provider.data_path());
+            drop(jar_provider);
+
+            let mut index = match self.get_tx_hash_index(&fixed_block_range) {
+                Ok(index) => index,
+                Err(err) => {
+                    warn!(
+                        target: "provider::static_file",
+                        ?path,
+                        %err,
+                        "Rebuilding the invalid transaction hash index"
+                    );
+                    None
+                }
+            };
+            // If the indexed transactions were unwound from the static file, their numbers may
+            // have been reused by the transactions appended since.
+            if let Some(existing) = &index {
+                let last_tx = existing.next_tx().saturating_sub(1);
+                let is_valid = existing.next_tx() <= tx_range.end() + 1 &&
+                    (last_tx < tx_range.start() ||
+                        self.transaction_by_id_no_hash(last_tx)?
+                            .is_some_and(|tx| existing.is_last_indexed(&tx.hash())));
+                if !is_valid {
+                    debug!(
+                        target: "provider::static_file",
+                        ?path,
+                        "Rebuilding the transaction hash index of a truncated static file"
+                    );
+                    index = None;
+                }
+            }
+
+            let mut next_tx = index.as_ref().map_or(tx_range.start(), |index| index.next_tx());
+            if index.is_none() && path.exists() {
+                reth_primitives::fs::remove_file(&path)?;
+            }
+            while next_tx <= tx_range.end() {
+                let batch_end = (next_tx + TX_HASH_INDEX_BATCH_SIZE).min(tx_range.end() + 1);
+                let hashes = self.transaction_hashes_by_range(next_tx..batch_end)?;
+                index = Some(Arc::new(TxHashIndex::write(
+                    &path,
+                    index.as_deref(),
+                    &hashes,
+                    batch_end,
+                )?));
+                self.tx_hash_indexes.insert(fixed_block_range.end(), index.clone());
+
+                indexed += batch_end - next_tx;
+                next_tx = batch_end;
+                debug!(
+                    target: "provider::static_file",
+                    ?path,
+                    next_tx,
+                    "Indexed transaction hashes"
+                );
+            }
+        }
+
+        Ok(indexed)
+    }
+
+    /// Returns the fixed block ranges of the transactions static files, from the oldest to the
+    /// newest.
+    fn transaction_static_files(&self) -> Vec<SegmentRangeInclusive> {
+        self.static_files_tx_index
+            .read()
+            .get(&StaticFileSegment::Transactions)
+            .map(|index| {
+                index.values().map(|block_range| find_fixed_range(block_range.start())).collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the transaction hash index of the transactions static file with the given fixed
+    /// block range, loading it on first use.
+    fn get_tx_hash_index(
+        &self,
+        fixed_block_range: &SegmentRangeInclusive,
+    ) -> ProviderResult<Option<Arc<TxHashIndex>>> {
+        if let Some(index) = self.tx_hash_indexes.get(&fixed_block_range.end()) {
+            return Ok(index.clone())
+        }
+
+        let path = tx_hash_index_path(
+            &self.path.join(StaticFileSegment::Transactions.filename(fixed_block_range)),
+        );
+        let index = TxHashIndex::load(&path)?.map(Arc::new);
+        self.tx_hash_indexes.insert(fixed_block_range.end(), index.clone());
+        Ok(index)
+    }
+
     /// Fetches data within a specified range across multiple static files.
     ///
     /// This function iteratively retrieves data using `get_fn` for each item in the given range.
@@ -513,14 +826,17 @@ impl StaticFileProvider {
             StaticFileSegment::Headers => {
                 self.get_segment_provider_from_block(segment, start, None)
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::TransactionSenders => {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
 
         let mut result = Vec::with_capacity((range.end - range.start).min(100) as usize);
+        let end = range.end;
         let mut provider = get_provider(range.start)?;
-        let mut cursor = provider.cursor()?;
+        let mut cursor = provider.range_cursor(range.clone())?;
 
         // advances number in range
         'outer: for number in range {
@@ -556,7 +872,7 @@ impl StaticFileProvider {
                             return Err(err)
                         }
                         provider = get_provider(number)?;
-                        cursor = provider.cursor()?;
+                        cursor = provider.range_cursor(number..end)?;
                         retrying = true;
                     }
                 }
@@ -583,7 +899,9 @@ impl StaticFileProvider {
             StaticFileSegment::Headers => {
                 self.get_segment_provider_from_block(segment, start, None)
             }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::TransactionSenders => {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -628,9 +946,9 @@ impl StaticFileProvider {
         // If there is, check the maximum block or transaction number of the segment.
         let static_file_upper_bound = match segment {
             StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::TransactionSenders => self.get_highest_static_file_tx(segment),
         };
 
         if static_file_upper_bound
@@ -670,9 +988,9 @@ impl StaticFileProvider {
         // If there is, check the maximum block or transaction number of the segment.
         if let Some(static_file_upper_bound) = match segment {
             StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+            StaticFileSegment::Transactions |
+            StaticFileSegment::Receipts |
+            StaticFileSegment::TransactionSenders => self.get_highest_static_file_tx(segment),
         } {
             if block_or_tx_range.start <= static_file_upper_bound {
                 let end = block_or_tx_range.end.min(static_file_upper_bound + 1);
@@ -932,6 +1250,9 @@ impl TransactionsProviderExt for StaticFileProvider {
 
 impl TransactionsProvider for StaticFileProvider {
     fn transaction_id(&self, tx_hash: TxHash) -> ProviderResult<Option<TxNumber>> {
+        if self.tx_hash_index {
+            return self.transaction_id_by_hash_index(tx_hash)
+        }
         self.find_static_file(StaticFileSegment::Transactions, |jar_provider| {
             let mut cursor = jar_provider.cursor()?;
             if cursor
@@ -960,6 +1281,13 @@ impl TransactionsProvider for StaticFileProvider {
     }
 
     fn transaction_by_hash(&self, hash: TxHash) -> ProviderResult<Option<TransactionSigned>> {
+        if self.tx_hash_index {
+            return Ok(self
+                .transaction_id_by_hash_index(hash)?
+                .map(|num| self.transaction_by_id(num))
+                .transpose()?
+                .flatten())
+        }
         self.find_static_file(StaticFileSegment::Transactions, |jar_provider| {
             Ok(jar_provider
                 .cursor()?
@@ -1110,6 +1438,26 @@ impl BlockReader for StaticFileProvider {
     ) -> ProviderResult<Vec<BlockWithSenders>> {
         Err(ProviderError::UnsupportedProvider)
     }
+
+    fn blocks_with_senders_range(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+        _transaction_kind: TransactionVariant,
+    ) -> ProviderResult<Vec<BlockWithSenders>> {
+        Err(ProviderError::UnsupportedProvider)
+    }
+
+    fn block_bodies_range<F, R>(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+        _assemble_body: F,
+    ) -> ProviderResult<Vec<R>>
+    where
+        F: FnMut(BlockNumber, Vec<TransactionSignedNoHash>, Option<Withdrawals>) -> R,
+    {
+        // Required data not present in static_files
+        Err(ProviderError::UnsupportedProvider)
+    }
 }
 
 impl WithdrawalsProvider for StaticFileProvider {
@@ -1146,6 +1494,10 @@ impl StatsReader for StaticFileProvider {
                 .get_highest_static_file_tx(StaticFileSegment::Transactions)
                 .map(|txs| txs + 1)
                 .unwrap_or_default() as usize),
+            tables::TransactionSenders::NAME => Ok(self
+                .get_highest_static_file_tx(StaticFileSegment::TransactionSenders)
+                .map(|txs| txs + 1)
+                .unwrap_or_default() as usize),
             _ => Err(ProviderError::UnsupportedProvider),
         }
     }
@@ -60,6 +60,15 @@ impl StaticFileProviderMetrics {
             .set(entries as f64);
     }
 
+    /// Records the major page faults that occurred while reading the static files of the segment.
+    pub(crate) fn record_major_page_faults(&self, segment: StaticFileSegment, faults: u64) {
+        self.segments
+            .get(&segment)
+            .expect("segment metrics should exist")
+            .major_page_faults_total
+            .increment(faults);
+    }
+
     pub(crate) fn record_segment_operation(
         &self,
         segment: StaticFileSegment,
@@ -85,6 +94,7 @@ impl StaticFileProviderMetrics {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
 pub(crate) enum StaticFileProviderOperation {
     InitCursor,
+    Prefetch,
     OpenWriter,
     Append,
     Prune,
@@ -96,6 +106,7 @@ impl StaticFileProviderOperation {
     const fn as_str(&self) -> &'static str {
         match self {
             Self::InitCursor => "init-cursor",
+            Self::Prefetch => "prefetch",
             Self::OpenWriter => "open-writer",
             Self::Append => "append",
             Self::Prune => "prune",
@@ -115,6 +126,23 @@ pub(crate) struct StaticFileSegmentMetrics {
     files: Gauge,
     /// The number of entries for a static file segment
     entries: Gauge,
+    /// The number of major page faults while reading a static file segment, i.e. the reads that
+    /// had to wait for the disk. Only reported on Linux.
+    major_page_faults_total: Counter,
+}
+
+/// Returns the number of major page faults of the current thread so far, if the platform reports
+/// them.
+pub(crate) fn thread_major_page_faults() -> Option<u64> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut usage = std::mem::MaybeUninit::<libc::rusage>::zeroed();
+        // SAFETY: the pointer is valid for writes of a `rusage`, which is initialized on success
+        if unsafe { libc::getrusage(libc::RUSAGE_THREAD, usage.as_mut_ptr()) } == 0 {
+            return Some(unsafe { usage.assume_init() }.ru_majflt as u64)
+        }
+    }
+    None
 }
 
 #[derive(Metrics)]
@@ -9,10 +9,16 @@ pub use writer::{StaticFileProviderRW, StaticFileProviderRWRefMut};
 
 mod metrics;
 
+mod tx_hash_index;
+
 use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_nippy_jar::NippyJar;
-use reth_primitives::{static_file::SegmentHeader, StaticFileSegment};
+use reth_primitives::{
+    static_file::{AccessPattern, SegmentHeader},
+    StaticFileSegment,
+};
 use std::{ops::Deref, sync::Arc};
+use tracing::warn;
 
 const BLOCKS_PER_STATIC_FILE: u64 = 500_000;
 
@@ -27,9 +33,22 @@ pub struct LoadedJar {
 }
 
 impl LoadedJar {
-    fn new(jar: NippyJar<SegmentHeader>) -> ProviderResult<Self> {
+    fn new(
+        jar: NippyJar<SegmentHeader>,
+        access_pattern: Option<AccessPattern>,
+    ) -> ProviderResult<Self> {
         match jar.open_data_reader() {
             Ok(data_reader) => {
+                // The advice is only a hint, so the static file is still readable without it.
+                let advised = access_pattern.map(|pattern| data_reader.advise(pattern));
+                if let Some(Err(err)) = advised {
+                    warn!(
+                        target: "provider::static_file",
+                        ?access_pattern,
+                        %err,
+                        "Failed to advise the OS of the access pattern"
+                    );
+                }
                 let mmap_handle = Arc::new(data_reader);
                 Ok(Self { jar, mmap_handle })
             }
@@ -57,7 +76,10 @@ impl Deref for LoadedJar {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{test_utils::create_test_provider_factory, HeaderProvider};
+    use crate::{
+        test_utils::create_test_provider_factory, HeaderProvider, ReceiptProvider,
+        TransactionsProvider,
+    };
     use rand::seq::SliceRandom;
     use reth_db::{
         cursor::DbCursorRO,
@@ -65,8 +87,12 @@ mod tests {
         transaction::{DbTx, DbTxMut},
         CanonicalHeaders, HeaderNumbers, HeaderTerminalDifficulties, Headers, RawTable,
     };
-    use reth_interfaces::test_utils::generators::{self, random_header_range};
-    use reth_primitives::{static_file::find_fixed_range, BlockNumber, B256, U256};
+    use reth_interfaces::test_utils::generators::{self, random_header_range, random_signed_tx};
+    use reth_nippy_jar::compression::Compressors;
+    use reth_primitives::{
+        static_file::{find_fixed_range, Compression},
+        BlockNumber, Receipt, B256, U256,
+    };
 
     #[test]
     fn test_snap() {
@@ -176,4 +202,102 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_segment_compression() {
+        let static_files_path = tempfile::tempdir().unwrap();
+        let segment = StaticFileSegment::Receipts;
+        let receipts = (0..10)
+            .map(|cumulative_gas_used| Receipt { cumulative_gas_used, ..Default::default() })
+            .collect::<Vec<_>>();
+
+        // The file is created with the compression configured at the time, and appending to it
+        // later on keeps using it.
+        for (compression, tx_range) in [(Compression::Zstd, 0..5), (Compression::Lz4, 5..10)] {
+            let provider = StaticFileProvider::new(static_files_path.path())
+                .unwrap()
+                .with_compression(segment, compression);
+            let mut writer = provider.latest_writer(segment).unwrap();
+            for tx_num in tx_range {
+                writer.increment_block(segment, tx_num).unwrap();
+                writer.append_receipt(tx_num, receipts[tx_num as usize].clone()).unwrap();
+            }
+            writer.commit().unwrap();
+        }
+
+        let provider = StaticFileProvider::new(static_files_path.path()).unwrap();
+        let jar_provider = provider.get_segment_provider_from_block(segment, 0, None).unwrap();
+        assert!(matches!(jar_provider.compressor(), Some(Compressors::Zstd(_))));
+        assert_eq!(provider.receipts_by_tx_range(0..10).unwrap(), receipts);
+    }
+
+    #[test]
+    fn test_transaction_hash_index() {
+        let static_files_path = tempfile::tempdir().unwrap();
+        let segment = StaticFileSegment::Transactions;
+        let mut rng = generators::rng();
+        let transactions = (0..10).map(|_| random_signed_tx(&mut rng)).collect::<Vec<_>>();
+
+        let provider = StaticFileProvider::new(static_files_path.path())
+            .unwrap()
+            .with_transaction_hash_index();
+        let mut writer = provider.latest_writer(segment).unwrap();
+        for tx_num in 0..6 {
+            writer.increment_block(segment, tx_num).unwrap();
+            writer
+                .append_transaction(tx_num, transactions[tx_num as usize].clone().into())
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        // The static file isn't indexed yet.
+        assert_eq!(provider.transaction_id(transactions[0].hash).unwrap(), None);
+
+        assert_eq!(provider.update_transaction_hash_index().unwrap(), 6);
+        assert_eq!(provider.update_transaction_hash_index().unwrap(), 0);
+        for (tx_num, transaction) in transactions[..6].iter().enumerate() {
+            assert_eq!(provider.transaction_id(transaction.hash).unwrap(), Some(tx_num as u64));
+        }
+        assert_eq!(provider.transaction_id(transactions[6].hash).unwrap(), None);
+
+        // The last two transactions are unwound, and their numbers are reused by other
+        // transactions.
+        let mut writer = provider.latest_writer(segment).unwrap();
+        writer.prune_transactions(2, 3).unwrap();
+        for tx_num in 4..8 {
+            writer.increment_block(segment, tx_num).unwrap();
+            writer
+                .append_transaction(tx_num, transactions[tx_num as usize + 2].clone().into())
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        assert_eq!(provider.update_transaction_hash_index().unwrap(), 8);
+        let provider = StaticFileProvider::new(static_files_path.path())
+            .unwrap()
+            .with_transaction_hash_index();
+        for transaction in &transactions[4..6] {
+            assert_eq!(provider.transaction_id(transaction.hash).unwrap(), None);
+        }
+        for (tx_num, transaction) in transactions[6..].iter().enumerate() {
+            assert_eq!(provider.transaction_id(transaction.hash).unwrap(), Some(tx_num as u64 + 4));
+        }
+
+        // The transactions appended since the last update are found by scanning them.
+        let appended = (0..2).map(|_| random_signed_tx(&mut rng)).collect::<Vec<_>>();
+        let mut writer = provider.latest_writer(segment).unwrap();
+        for (tx_num, transaction) in (8..).zip(&appended) {
+            writer.increment_block(segment, tx_num).unwrap();
+            writer.append_transaction(tx_num, transaction.clone().into()).unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        for (tx_num, transaction) in (8..).zip(&appended) {
+            assert_eq!(provider.transaction_id(transaction.hash).unwrap(), Some(tx_num));
+        }
+        assert_eq!(provider.update_transaction_hash_index().unwrap(), 2);
+    }
 }
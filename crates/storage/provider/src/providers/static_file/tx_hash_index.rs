@@ -0,0 +1,257 @@
+use memmap2::{Mmap, MmapMut};
+use reth_interfaces::provider::{ProviderError, ProviderResult};
+use reth_primitives::{fs::FsPathError, TxHash, TxNumber};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+/// The extension of the transaction hash index of a transactions static file.
+pub(crate) const TX_HASH_INDEX_EXTENSION: &str = "txhash";
+
+/// Identifies the format of a transaction hash index file.
+const MAGIC: &[u8; 8] = b"rethtxh1";
+
+/// Size of the header: the magic, the capacity, the number of entries, the next transaction
+/// number and the key of the last indexed transaction.
+const HEADER_SIZE: usize = 40;
+
+/// Size of a slot: the key derived from the hash, and the transaction number.
+const SLOT_SIZE: usize = 16;
+
+/// The capacity of a new index.
+const MIN_CAPACITY: u64 = 1024;
+
+/// Returns the path of the transaction hash index of the static file at the given path.
+pub(crate) fn tx_hash_index_path(static_file: &Path) -> PathBuf {
+    static_file.with_extension(TX_HASH_INDEX_EXTENSION)
+}
+
+/// Index of the transactions of a static file by their hashes, which is used to look up the
+/// transactions whose entries of `TransactionHashNumbers` are pruned.
+///
+/// The index is an open addressing hash table, which is stored in a file next to the static file
+/// and rewritten as the static file grows. Once it's half full, it's rewritten with twice the
+/// capacity. Only the first 8 bytes of every hash are stored, so the transaction numbers
+/// returned by a lookup are candidates that must be checked against the hash of the transaction.
+///
+/// The transactions of the static file below [`TxHashIndex::next_tx`] are indexed. The key of the
+/// last indexed transaction is kept to detect that the static file was truncated and appended to
+/// again, see [`TxHashIndex::is_last_indexed`].
+#[derive(Debug)]
+pub(crate) struct TxHashIndex {
+    mmap: Mmap,
+    capacity: u64,
+    len: u64,
+    next_tx: TxNumber,
+    last_key: u64,
+}
+
+impl TxHashIndex {
+    /// Loads the index at the given path, if it exists.
+    pub(crate) fn load(path: &Path) -> ProviderResult<Option<Self>> {
+        if !path.exists() {
+            return Ok(None)
+        }
+
+        let file = File::open(path).map_err(|err| FsPathError::open(err, path))?;
+        // SAFETY: the index is never modified once it's written, `TxHashIndex::write` moves a new
+        // file over it.
+        let mmap = unsafe { Mmap::map(&file) }.map_err(|err| FsPathError::read(err, path))?;
+
+        let invalid = || {
+            ProviderError::FsPathError(format!("invalid transaction hash index {}", path.display()))
+        };
+        if mmap.len() < HEADER_SIZE || &mmap[..8] != MAGIC {
+            return Err(invalid())
+        }
+        let [capacity, len, next_tx, last_key] = read_header(&mmap);
+        if !capacity.is_power_of_two() || mmap.len() as u64 != slots_offset(capacity) {
+            return Err(invalid())
+        }
+
+        Ok(Some(Self { mmap, capacity, len, next_tx, last_key }))
+    }
+
+    /// Returns the number of the first transaction that is not indexed yet.
+    pub(crate) const fn next_tx(&self) -> TxNumber {
+        self.next_tx
+    }
+
+    /// Returns true if the given hash may be the hash of the last indexed transaction, the one
+    /// before [`TxHashIndex::next_tx`].
+    pub(crate) fn is_last_indexed(&self, hash: &TxHash) -> bool {
+        slot_key(hash) == self.last_key
+    }
+
+    /// Returns the numbers of the transactions that may have the given hash.
+    pub(crate) fn candidates(&self, hash: &TxHash) -> Vec<TxNumber> {
+        let key = slot_key(hash);
+        let mut candidates = Vec::new();
+        let mut slot = key & (self.capacity - 1);
+        // The table is never more than half full, so the probing ends at an empty slot.
+        for _ in 0..self.capacity {
+            let (slot_key, tx_number) = read_slot(&self.mmap[HEADER_SIZE..], slot);
+            if slot_key == 0 {
+                break
+            }
+            if slot_key == key && !candidates.contains(&tx_number) {
+                candidates.push(tx_number);
+            }
+            slot = (slot + 1) & (self.capacity - 1);
+        }
+        candidates
+    }
+
+    /// Adds the hashes of transactions to the index at the given path, and sets the number of the
+    /// first transaction that is not indexed to `next_tx`.
+    ///
+    /// The entries of the `existing` index and the hashes are written to a new index, which is
+    /// moved over the existing one, so the index that is mapped by readers is never modified. The
+    /// capacity is doubled if the existing index doesn't have enough of it.
+    pub(crate) fn write(
+        path: &Path,
+        existing: Option<&Self>,
+        hashes: &[(TxHash, TxNumber)],
+        next_tx: TxNumber,
+    ) -> ProviderResult<Self> {
+        let len = existing.map_or(0, |index| index.len) + hashes.len() as u64;
+        let last_key = hashes
+            .last()
+            .map(|(hash, _)| slot_key(hash))
+            .or(existing.map(|index| index.last_key))
+            .unwrap_or_default();
+
+        let capacity = match existing {
+            Some(index) if len * 2 <= index.capacity => index.capacity,
+            _ => (len * 2).next_power_of_two().max(MIN_CAPACITY),
+        };
+
+        let tmp_path = path.with_extension(format!("{TX_HASH_INDEX_EXTENSION}.tmp"));
+        let file =
+            File::create(&tmp_path).map_err(|err| FsPathError::create_file(err, &tmp_path))?;
+        file.set_len(slots_offset(capacity)).map_err(|err| FsPathError::write(err, &tmp_path))?;
+
+        let mut mmap = map_mut(&file, &tmp_path)?;
+        let slots = &mut mmap[HEADER_SIZE..];
+        match existing {
+            Some(index) if index.capacity == capacity => {
+                slots.copy_from_slice(&index.mmap[HEADER_SIZE..]);
+            }
+            Some(index) => {
+                for slot in 0..index.capacity {
+                    let (key, tx_number) = read_slot(&index.mmap[HEADER_SIZE..], slot);
+                    if key != 0 {
+                        insert(slots, capacity, key, tx_number);
+                    }
+                }
+            }
+            None => {}
+        }
+        for (hash, tx_number) in hashes {
+            insert(slots, capacity, slot_key(hash), *tx_number);
+        }
+        write_header(&mut mmap, [capacity, len, next_tx, last_key]);
+        mmap.flush().map_err(|err| FsPathError::write(err, &tmp_path))?;
+        drop(mmap);
+
+        reth_primitives::fs::rename(&tmp_path, path)?;
+
+        Self::load(path)?.ok_or_else(|| {
+            ProviderError::FsPathError(format!("missing transaction hash index {}", path.display()))
+        })
+    }
+}
+
+/// Maps the file for writing.
+fn map_mut(file: &File, path: &Path) -> ProviderResult<MmapMut> {
+    // SAFETY: the file is a new index that is only mapped by the writer until it's moved over the
+    // existing index.
+    unsafe { MmapMut::map_mut(file) }.map_err(|err| FsPathError::open(err, path).into())
+}
+
+/// Returns the offset of the end of the slots of an index with the given capacity.
+const fn slots_offset(capacity: u64) -> u64 {
+    HEADER_SIZE as u64 + capacity * SLOT_SIZE as u64
+}
+
+/// Returns the key of the slot of a hash, which is never zero since that marks an empty slot.
+fn slot_key(hash: &TxHash) -> u64 {
+    u64::from_le_bytes(hash[..8].try_into().expect("hash has 32 bytes")).max(1)
+}
+
+/// Reads the capacity, the number of entries, the next transaction number and the key of the last
+/// indexed transaction.
+fn read_header(mmap: &[u8]) -> [u64; 4] {
+    [8, 16, 24, 32].map(|offset| read_u64(mmap, offset))
+}
+
+/// Writes the magic, the capacity, the number of entries, the next transaction number and the key
+/// of the last indexed transaction.
+fn write_header(mmap: &mut [u8], values: [u64; 4]) {
+    mmap[..8].copy_from_slice(MAGIC);
+    for (offset, value) in [8, 16, 24, 32].into_iter().zip(values) {
+        mmap[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn read_slot(slots: &[u8], slot: u64) -> (u64, TxNumber) {
+    let offset = slot as usize * SLOT_SIZE;
+    (read_u64(slots, offset), read_u64(slots, offset + 8))
+}
+
+/// Inserts the key into the first empty slot, starting from the slot of the key.
+fn insert(slots: &mut [u8], capacity: u64, key: u64, tx_number: TxNumber) {
+    let mut slot = key & (capacity - 1);
+    while read_slot(slots, slot).0 != 0 {
+        slot = (slot + 1) & (capacity - 1);
+    }
+    let offset = slot as usize * SLOT_SIZE;
+    slots[offset..offset + 8].copy_from_slice(&key.to_le_bytes());
+    slots[offset + 8..offset + 16].copy_from_slice(&tx_number.to_le_bytes());
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().expect("slice has 8 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::B256;
+
+    #[test]
+    fn write_and_extend_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = tx_hash_index_path(&dir.path().join("static_file_transactions_0_499999"));
+        assert!(TxHashIndex::load(&path).unwrap().is_none());
+
+        let hashes = (0..600).map(|tx| (B256::random(), tx)).collect::<Vec<_>>();
+        let index = TxHashIndex::write(&path, None, &hashes[..100], 100).unwrap();
+        assert_eq!((index.capacity, index.len, index.next_tx()), (MIN_CAPACITY, 100, 100));
+
+        // extended with the same capacity, the previous index is left as it was
+        let previous = index;
+        let index = TxHashIndex::write(&path, Some(&previous), &hashes[100..500], 500).unwrap();
+        assert_eq!((index.capacity, index.len, index.next_tx()), (MIN_CAPACITY, 500, 500));
+        assert_eq!((previous.len, previous.next_tx()), (100, 100));
+        assert!(previous.candidates(&hashes[100].0).is_empty());
+        assert!(previous.candidates(&hashes[99].0).contains(&99));
+
+        // rewritten with twice the capacity
+        let index = TxHashIndex::write(&path, Some(&index), &hashes[500..], 600).unwrap();
+        assert_eq!((index.capacity, index.len, index.next_tx()), (2 * MIN_CAPACITY, 600, 600));
+
+        let index = TxHashIndex::load(&path).unwrap().unwrap();
+        assert!(index.is_last_indexed(&hashes[599].0));
+        for (hash, tx) in &hashes {
+            assert!(index.candidates(hash).contains(tx));
+        }
+        assert!(index.candidates(&B256::random()).is_empty());
+
+        // only the first 8 bytes of the hash are compared
+        let mut colliding = hashes[0].0;
+        colliding[31] ^= 1;
+        assert_eq!(index.candidates(&colliding), vec![0]);
+    }
+}
@@ -9,9 +9,9 @@ use reth_db::codecs::CompactU256;
 use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_nippy_jar::{NippyJar, NippyJarError, NippyJarWriter};
 use reth_primitives::{
-    static_file::{find_fixed_range, SegmentHeader, SegmentRangeInclusive},
-    BlockHash, BlockNumber, Header, Receipt, StaticFileSegment, TransactionSignedNoHash, TxNumber,
-    U256,
+    static_file::{find_fixed_range, Compression, SegmentHeader, SegmentRangeInclusive},
+    Address, BlockHash, BlockNumber, Header, Receipt, StaticFileSegment, TransactionSignedNoHash,
+    TxNumber, U256,
 };
 use std::{
     path::{Path, PathBuf},
@@ -71,7 +71,8 @@ impl StaticFileProviderRW {
             ),
             Err(ProviderError::MissingStaticFileBlock(_, _)) => {
                 let path = static_file_provider.directory().join(segment.filename(&block_range));
-                (create_jar(segment, &path, block_range), path)
+                let compression = static_file_provider.compression(segment);
+                (create_jar(segment, &path, block_range, compression)?, path)
             }
             Err(err) => return Err(err),
         };
@@ -267,7 +268,9 @@ impl StaticFileProviderRW {
                 StaticFileSegment::Headers => {
                     self.writer.user_header().block_len().unwrap_or_default()
                 }
-                StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+                StaticFileSegment::Transactions |
+                StaticFileSegment::Receipts |
+                StaticFileSegment::TransactionSenders => {
                     self.writer.user_header().tx_len().unwrap_or_default()
                 }
             };
@@ -337,6 +340,26 @@ impl StaticFileProviderRW {
         Ok(())
     }
 
+    /// Appends column that was already encoded with [`Compact::to_compact`] to static file.
+    fn append_encoded_column(&mut self, column: &[u8]) -> ProviderResult<()> {
+        self.writer
+            .append_column(Some(Ok(column)))
+            .map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Increments the transaction range of a tx number-based static file, starting it at `tx_num`
+    /// if it's empty.
+    fn increment_tx(&mut self, segment: StaticFileSegment, tx_num: TxNumber) {
+        debug_assert!(self.writer.user_header().segment() == segment);
+
+        if self.writer.user_header().tx_range().is_none() {
+            self.writer.user_header_mut().set_tx_range(tx_num, tx_num);
+        } else {
+            self.writer.user_header_mut().increment_tx();
+        }
+    }
+
     /// Appends to tx number-based static file.
     ///
     /// Returns the current [`TxNumber`] as seen in the static file.
@@ -346,15 +369,38 @@ impl StaticFileProviderRW {
         tx_num: TxNumber,
         value: V,
     ) -> ProviderResult<TxNumber> {
-        debug_assert!(self.writer.user_header().segment() == segment);
+        self.increment_tx(segment, tx_num);
+        self.append_column(value)?;
 
-        if self.writer.user_header().tx_range().is_none() {
-            self.writer.user_header_mut().set_tx_range(tx_num, tx_num);
-        } else {
-            self.writer.user_header_mut().increment_tx();
-        }
+        Ok(self.writer.user_header().tx_end().expect("qed"))
+    }
 
-        self.append_column(value)?;
+    /// Appends an already encoded value to tx number-based static file.
+    ///
+    /// It **DOES NOT CALL** `increment_block()`, it should be handled elsewhere. The value must be
+    /// encoded with [`Compact::to_compact`], so the written file is the same as if the value was
+    /// appended with [`Self::append_transaction`] or [`Self::append_receipt`]. This allows
+    /// encoding and compressing the values in parallel, while appending them in order.
+    ///
+    /// Returns the current [`TxNumber`] as seen in the static file.
+    pub fn append_encoded_with_tx_number(
+        &mut self,
+        segment: StaticFileSegment,
+        tx_num: TxNumber,
+        value: &[u8],
+    ) -> ProviderResult<TxNumber> {
+        let start = Instant::now();
+
+        self.increment_tx(segment, tx_num);
+        self.append_encoded_column(value)?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                segment,
+                StaticFileProviderOperation::Append,
+                Some(start.elapsed()),
+            );
+        }
 
         Ok(self.writer.user_header().tx_end().expect("qed"))
     }
@@ -444,6 +490,33 @@ impl StaticFileProviderRW {
         Ok(result)
     }
 
+    /// Appends transaction sender to static file.
+    ///
+    /// It **DOES NOT** call `increment_block()`, it should be handled elsewhere. There might be
+    /// empty blocks and this function wouldn't be called.
+    ///
+    /// Returns the current [`TxNumber`] as seen in the static file.
+    pub fn append_transaction_sender(
+        &mut self,
+        tx_num: TxNumber,
+        sender: Address,
+    ) -> ProviderResult<TxNumber> {
+        let start = Instant::now();
+
+        let result =
+            self.append_with_tx_number(StaticFileSegment::TransactionSenders, tx_num, sender)?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                StaticFileSegment::TransactionSenders,
+                StaticFileProviderOperation::Append,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(result)
+    }
+
     /// Removes the last `number` of transactions from static files.
     ///
     /// # Note
@@ -498,6 +571,33 @@ impl StaticFileProviderRW {
         Ok(())
     }
 
+    /// Prunes `to_delete` number of transaction senders from static_files.
+    ///
+    /// # Note
+    /// Commits to the configuration file at the end.
+    pub fn prune_transaction_senders(
+        &mut self,
+        to_delete: u64,
+        last_block: BlockNumber,
+    ) -> ProviderResult<()> {
+        let start = Instant::now();
+
+        let segment = StaticFileSegment::TransactionSenders;
+        debug_assert!(self.writer.user_header().segment() == segment);
+
+        self.truncate(segment, to_delete, Some(last_block))?;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.record_segment_operation(
+                StaticFileSegment::TransactionSenders,
+                StaticFileProviderOperation::Prune,
+                Some(start.elapsed()),
+            );
+        }
+
+        Ok(())
+    }
+
     /// Prunes `to_delete` number of headers from static_files.
     ///
     /// # Note
@@ -556,18 +656,25 @@ fn create_jar(
     segment: StaticFileSegment,
     path: &Path,
     expected_block_range: SegmentRangeInclusive,
-) -> NippyJar<SegmentHeader> {
-    let mut jar = NippyJar::new(
+    compression: Compression,
+) -> ProviderResult<NippyJar<SegmentHeader>> {
+    let jar = NippyJar::new(
         segment.columns(),
         path,
         SegmentHeader::new(expected_block_range, None, None, segment),
     );
 
-    // Transaction and Receipt already have the compression scheme used natively in its encoding.
-    // (zstd-dictionary)
-    if segment.is_headers() {
-        jar = jar.with_lz4();
-    }
-
-    jar
+    // The compression is stored in the jar configuration, so readers of this file decompress it
+    // accordingly regardless of the configured compression.
+    Ok(match compression {
+        Compression::Lz4 => jar.with_lz4(),
+        Compression::Zstd => jar.with_zstd(false, 0),
+        Compression::ZstdWithDictionary => {
+            return Err(ProviderError::NippyJar(format!(
+                "can't create {segment} static file with a zstd dictionary, since there are no \
+                 rows to train it on"
+            )))
+        }
+        Compression::Uncompressed => jar,
+    })
 }
@@ -4,8 +4,8 @@ use crate::{
 };
 use parking_lot::Mutex;
 use reth_interfaces::executor::BlockExecutionError;
-use reth_primitives::{BlockNumber, BlockWithSenders, PruneModes, Receipt, U256};
-use std::sync::Arc;
+use reth_primitives::{Address, BlockNumber, BlockWithSenders, PruneModes, Receipt, U256};
+use std::{collections::HashSet, sync::Arc};
 /// Test executor with mocked result.
 #[derive(Debug)]
 pub struct TestExecutor(pub Option<BundleStateWithReceipts>);
@@ -24,6 +24,29 @@ impl BlockExecutor for TestExecutor {
         Ok(())
     }
 
+    fn execute_block(
+        &mut self,
+        _block: &BlockWithSenders,
+        _total_difficulty: U256,
+    ) -> Result<Vec<Receipt>, BlockExecutionError> {
+        if self.0.is_none() {
+            return Err(BlockExecutionError::UnavailableForTest)
+        }
+        Ok(Vec::new())
+    }
+
+    fn save_receipts(&mut self, _receipts: Vec<Receipt>) -> Result<(), BlockExecutionError> {
+        Ok(())
+    }
+
+    fn verify_receipts(
+        &mut self,
+        _block: &BlockWithSenders,
+        _receipts: &[Receipt],
+    ) -> Result<bool, BlockExecutionError> {
+        Ok(false)
+    }
+
     fn execute_transactions(
         &mut self,
         _block: &BlockWithSenders,
@@ -45,6 +68,12 @@ impl PrunableBlockExecutor for TestExecutor {
     fn set_tip(&mut self, _tip: BlockNumber) {}
 
     fn set_prune_modes(&mut self, _prune_modes: PruneModes) {}
+
+    fn record_call_addresses(&mut self) {}
+
+    fn take_call_addresses(&mut self) -> Option<(HashSet<Address>, HashSet<Address>)> {
+        None
+    }
 }
 
 /// Executor factory with pre-set execution results.
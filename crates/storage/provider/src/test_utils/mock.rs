@@ -1,8 +1,10 @@
 use crate::{
+    providers::StaticFileProvider,
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    BundleStateDataProvider, ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider,
-    ReceiptProviderIdExt, StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider,
+    BundleStateDataProvider, CallTraceIndexReader, ChainSpecProvider, ChangeSetReader,
+    EvmEnvProvider, HeaderProvider, PruneCheckpointReader, ReceiptProviderIdExt, StateProvider,
+    StateProviderBox, StateProviderFactory, StateRootProvider, StaticFileProviderFactory,
     TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use parking_lot::Mutex;
@@ -11,10 +13,10 @@ use reth_evm::ConfigureEvmEnv;
 use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_primitives::{
     keccak256, trie::AccountProof, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId,
-    BlockNumber, BlockWithSenders, Bytecode, Bytes, ChainInfo, ChainSpec, Header, Receipt,
-    SealedBlock, SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    BlockNumber, BlockWithSenders, Bytecode, Bytes, ChainInfo, ChainSpec, Header, PruneCheckpoint,
+    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StorageEntry,
+    StorageKey, StorageValue, TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash,
+    TxNumber, Withdrawal, Withdrawals, B256, U256,
 };
 use reth_trie::updates::TrieUpdates;
 use revm::{
@@ -22,7 +24,7 @@ use revm::{
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
 };
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
 };
@@ -38,6 +40,10 @@ pub struct MockEthProvider {
     pub accounts: Arc<Mutex<HashMap<Address, ExtendedAccount>>>,
     /// Local chain spec
     pub chain_spec: Arc<ChainSpec>,
+    /// Local prune checkpoint store
+    pub prune_checkpoints: Arc<Mutex<HashMap<PruneSegment, PruneCheckpoint>>>,
+    /// Local receipt store, by the hash of the block
+    pub receipts: Arc<Mutex<HashMap<B256, Vec<Receipt>>>>,
 }
 
 impl Default for MockEthProvider {
@@ -47,6 +53,8 @@ impl Default for MockEthProvider {
             headers: Default::default(),
             accounts: Default::default(),
             chain_spec: Arc::new(reth_primitives::ChainSpecBuilder::mainnet().build()),
+            prune_checkpoints: Default::default(),
+            receipts: Default::default(),
         }
     }
 }
@@ -126,6 +134,16 @@ impl MockEthProvider {
             self.add_account(address, account)
         }
     }
+
+    /// Add prune checkpoint of the segment to local prune checkpoint store
+    pub fn add_prune_checkpoint(&self, segment: PruneSegment, checkpoint: PruneCheckpoint) {
+        self.prune_checkpoints.lock().insert(segment, checkpoint);
+    }
+
+    /// Add the receipts of the block to local receipt store
+    pub fn add_receipts(&self, hash: B256, receipts: Vec<Receipt>) {
+        self.receipts.lock().insert(hash, receipts);
+    }
 }
 
 impl HeaderProvider for MockEthProvider {
@@ -352,9 +370,29 @@ impl ReceiptProvider for MockEthProvider {
 
     fn receipts_by_tx_range(
         &self,
-        _range: impl RangeBounds<TxNumber>,
+        range: impl RangeBounds<TxNumber>,
     ) -> ProviderResult<Vec<Receipt>> {
-        Ok(vec![])
+        let blocks = self.blocks.lock();
+        let receipts = self.receipts.lock();
+        // receipts are numbered like the transactions of the blocks, missing receipts are skipped
+        let receipts = blocks
+            .iter()
+            .flat_map(|(hash, block)| {
+                let receipts = receipts.get(hash);
+                (0..block.body.len())
+                    .map(move |idx| receipts.and_then(|receipts| receipts.get(idx)))
+            })
+            .enumerate()
+            .filter_map(|(tx_number, receipt)| {
+                if range.contains(&(tx_number as TxNumber)) {
+                    receipt.cloned()
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        Ok(receipts)
     }
 }
 
@@ -462,16 +500,25 @@ impl BlockReader for MockEthProvider {
         Ok(None)
     }
 
-    fn block_body_indices(&self, _num: u64) -> ProviderResult<Option<StoredBlockBodyIndices>> {
+    fn block_body_indices(&self, num: u64) -> ProviderResult<Option<StoredBlockBodyIndices>> {
+        let lock = self.blocks.lock();
+        let mut first_tx_num: TxNumber = 0;
+        for block in lock.values() {
+            if block.header.number == num {
+                let tx_count = block.body.len() as u64;
+                return Ok(Some(StoredBlockBodyIndices { first_tx_num, tx_count }))
+            }
+            first_tx_num += block.body.len() as TxNumber;
+        }
         Ok(None)
     }
 
     fn block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        Ok(None)
+        Ok(self.block(id)?.and_then(|block| block.with_recovered_senders()))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -490,6 +537,32 @@ impl BlockReader for MockEthProvider {
     ) -> ProviderResult<Vec<BlockWithSenders>> {
         Ok(vec![])
     }
+
+    fn blocks_with_senders_range(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+        _transaction_kind: TransactionVariant,
+    ) -> ProviderResult<Vec<BlockWithSenders>> {
+        Ok(vec![])
+    }
+
+    fn block_bodies_range<F, R>(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        mut assemble_body: F,
+    ) -> ProviderResult<Vec<R>>
+    where
+        F: FnMut(BlockNumber, Vec<TransactionSignedNoHash>, Option<Withdrawals>) -> R,
+    {
+        Ok(self
+            .block_range(range)?
+            .into_iter()
+            .map(|block| {
+                let transactions = block.body.into_iter().map(Into::into).collect();
+                assemble_body(block.number, transactions, block.withdrawals)
+            })
+            .collect())
+    }
 }
 
 impl BlockReaderIdExt for MockEthProvider {
@@ -686,4 +759,38 @@ impl ChangeSetReader for MockEthProvider {
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
         Ok(Vec::default())
     }
+
+    fn storage_block_changeset(
+        &self,
+        _block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(Address, StorageEntry)>> {
+        Ok(Vec::default())
+    }
+}
+
+impl CallTraceIndexReader for MockEthProvider {
+    fn call_trace_blocks(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+        _from: &HashSet<Address>,
+        _to: &HashSet<Address>,
+    ) -> ProviderResult<Option<Vec<BlockNumber>>> {
+        Ok(None)
+    }
+}
+
+impl PruneCheckpointReader for MockEthProvider {
+    fn get_prune_checkpoint(
+        &self,
+        segment: PruneSegment,
+    ) -> ProviderResult<Option<PruneCheckpoint>> {
+        let lock = self.prune_checkpoints.lock();
+        Ok(lock.get(&segment).copied())
+    }
+}
+
+impl StaticFileProviderFactory for MockEthProvider {
+    fn static_file_provider(&self) -> StaticFileProvider {
+        StaticFileProvider::default()
+    }
 }
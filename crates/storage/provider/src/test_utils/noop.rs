@@ -1,12 +1,17 @@
 use crate::{
+    providers::StaticFileProvider,
     traits::{BlockSource, ReceiptProvider},
     AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
-    ReceiptProviderIdExt, StageCheckpointReader, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, TransactionVariant, TransactionsProvider,
+    CallTraceIndexReader, ChainSpecProvider, ChangeSetReader, DatabaseMetadataReader,
+    EvmEnvProvider, HeaderProvider, PruneCheckpointReader, ReceiptProviderIdExt,
+    StageCheckpointReader, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRootProvider, StaticFileProviderFactory, TransactionVariant, TransactionsProvider,
     WithdrawalsProvider,
 };
-use reth_db::models::{AccountBeforeTx, StoredBlockBodyIndices};
+use reth_db::{
+    database_metrics::DatabaseMetadataValue,
+    models::{AccountBeforeTx, StoredBlockBodyIndices},
+};
 use reth_evm::ConfigureEvmEnv;
 use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
@@ -14,7 +19,7 @@ use reth_primitives::{
     trie::AccountProof,
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber, BlockWithSenders,
     Bytecode, ChainInfo, ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock,
-    SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
+    SealedBlockWithSenders, SealedHeader, StorageEntry, StorageKey, StorageValue, TransactionMeta,
     TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
     MAINNET, U256,
 };
@@ -24,6 +29,7 @@ use revm::{
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
 };
 use std::{
+    collections::HashSet,
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
 };
@@ -123,6 +129,25 @@ impl BlockReader for NoopProvider {
     ) -> ProviderResult<Vec<BlockWithSenders>> {
         Ok(vec![])
     }
+
+    fn blocks_with_senders_range(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+        _transaction_kind: TransactionVariant,
+    ) -> ProviderResult<Vec<BlockWithSenders>> {
+        Ok(vec![])
+    }
+
+    fn block_bodies_range<F, R>(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+        _assemble_body: F,
+    ) -> ProviderResult<Vec<R>>
+    where
+        F: FnMut(BlockNumber, Vec<TransactionSignedNoHash>, Option<Withdrawals>) -> R,
+    {
+        Ok(vec![])
+    }
 }
 
 impl BlockReaderIdExt for NoopProvider {
@@ -291,6 +316,13 @@ impl ChangeSetReader for NoopProvider {
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
         Ok(Vec::default())
     }
+
+    fn storage_block_changeset(
+        &self,
+        _block_number: BlockNumber,
+    ) -> ProviderResult<Vec<(Address, StorageEntry)>> {
+        Ok(Vec::default())
+    }
 }
 
 impl StateRootProvider for NoopProvider {
@@ -448,6 +480,17 @@ impl WithdrawalsProvider for NoopProvider {
     }
 }
 
+impl CallTraceIndexReader for NoopProvider {
+    fn call_trace_blocks(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+        _from: &HashSet<Address>,
+        _to: &HashSet<Address>,
+    ) -> ProviderResult<Option<Vec<BlockNumber>>> {
+        Ok(None)
+    }
+}
+
 impl PruneCheckpointReader for NoopProvider {
     fn get_prune_checkpoint(
         &self,
@@ -456,3 +499,15 @@ impl PruneCheckpointReader for NoopProvider {
         Ok(None)
     }
 }
+
+impl DatabaseMetadataReader for NoopProvider {
+    fn database_metadata(&self) -> DatabaseMetadataValue {
+        DatabaseMetadataValue::default()
+    }
+}
+
+impl StaticFileProviderFactory for NoopProvider {
+    fn static_file_provider(&self) -> StaticFileProvider {
+        StaticFileProvider::default()
+    }
+}
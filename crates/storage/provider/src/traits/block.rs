@@ -6,8 +6,9 @@ use auto_impl::auto_impl;
 use reth_db::models::StoredBlockBodyIndices;
 use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
-    Block, BlockHashOrNumber, BlockId, BlockNumber, BlockNumberOrTag, BlockWithSenders, ChainSpec,
-    Header, PruneModes, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, B256,
+    Address, Block, BlockHashOrNumber, BlockId, BlockNumber, BlockNumberOrTag, BlockWithSenders,
+    ChainSpec, Header, PruneModes, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader,
+    TransactionSignedNoHash, Withdrawals, B256,
 };
 use reth_trie::{updates::TrieUpdates, HashedPostState};
 use std::ops::RangeInclusive;
@@ -142,6 +143,64 @@ pub trait BlockReader:
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<Vec<BlockWithSenders>>;
+
+    /// Returns all blocks in the given inclusive range with the senders of their transactions.
+    ///
+    /// Unlike calling [BlockReader::block_with_senders] for every block, this reads the body
+    /// indices of the range once and the transactions and senders of the whole range at once.
+    /// The `transaction_kind` parameter determines whether the hashes of the transactions are
+    /// computed.
+    ///
+    /// Note: returns only available blocks
+    fn blocks_with_senders_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        transaction_kind: TransactionVariant,
+    ) -> ProviderResult<Vec<BlockWithSenders>>;
+
+    /// Assembles the bodies of all blocks in the given inclusive range from their transactions and
+    /// withdrawals.
+    ///
+    /// Unlike [BlockReader::block_range], this doesn't read the ommers of the blocks or compute
+    /// the hashes of the transactions, and every body is passed to `assemble_body` as soon as it
+    /// is read, alongside its block number.
+    ///
+    /// Note: returns only available blocks
+    fn block_bodies_range<F, R>(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        assemble_body: F,
+    ) -> ProviderResult<Vec<R>>
+    where
+        F: FnMut(BlockNumber, Vec<TransactionSignedNoHash>, Option<Withdrawals>) -> R;
+
+    /// Returns the block with the given number without its ommers and withdrawals, the senders of
+    /// its transactions and its receipts, each read with a single lookup of the transaction range
+    /// of the block.
+    ///
+    /// The senders are empty if they're pruned, and there are fewer receipts than transactions if
+    /// the receipts are pruned. Providers that open a database transaction per call read all of it
+    /// with a single one, so the parts of the block are consistent with each other.
+    ///
+    /// Returns `None` if the block isn't stored.
+    fn block_with_senders_and_receipts(
+        &self,
+        number: BlockNumber,
+    ) -> ProviderResult<Option<(SealedBlock, Vec<Address>, Vec<Receipt>)>> {
+        let Some(body_indices) = self.block_body_indices(number)? else { return Ok(None) };
+        let Some(header) = self.sealed_header(number)? else { return Ok(None) };
+
+        let tx_range = body_indices.tx_num_range();
+        let receipts = self.receipts_by_tx_range(tx_range.clone())?;
+        let body = self
+            .transactions_by_tx_range(tx_range.clone())?
+            .into_iter()
+            .map(TransactionSignedNoHash::with_hash)
+            .collect();
+        let senders = self.senders_by_tx_range(tx_range)?;
+
+        Ok(Some((SealedBlock { header, body, ..Default::default() }, senders, receipts)))
+    }
 }
 
 /// Trait extension for `BlockReader`, for types that implement `BlockId` conversion.
@@ -0,0 +1,20 @@
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{Address, BlockNumber};
+use std::{collections::HashSet, ops::RangeInclusive};
+
+/// The trait for fetching the blocks that contain the call traces of addresses, from the index of
+/// the optional IndexCallTraces stage.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait CallTraceIndexReader: Send + Sync {
+    /// Returns the blocks of the range, in ascending order, that may contain a call trace from one
+    /// of the `from` addresses or to one of the `to` addresses.
+    ///
+    /// The blocks of the range that are not indexed, because they are above the checkpoint of the
+    /// stage or were pruned, are always returned. Returns `None` if the index is not built.
+    fn call_trace_blocks(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        from: &HashSet<Address>,
+        to: &HashSet<Address>,
+    ) -> ProviderResult<Option<Vec<BlockNumber>>>;
+}
@@ -2,8 +2,8 @@
 
 use crate::{bundle_state::BundleStateWithReceipts, StateProvider};
 use reth_interfaces::executor::BlockExecutionError;
-use reth_primitives::{BlockNumber, BlockWithSenders, PruneModes, Receipt, U256};
-use std::time::Duration;
+use reth_primitives::{Address, BlockNumber, BlockWithSenders, PruneModes, Receipt, U256};
+use std::{collections::HashSet, time::Duration};
 use tracing::debug;
 
 /// Executor factory that would create the EVM with particular state provider.
@@ -36,6 +36,34 @@ pub trait BlockExecutor {
         total_difficulty: U256,
     ) -> Result<(), Self::Error>;
 
+    /// Executes the entire block without verifying or saving its receipts, and returns them.
+    ///
+    /// This will update the state of the executor with the changes made by the block, like
+    /// [execute_and_verify_receipt](BlockExecutor::execute_and_verify_receipt). The receipts can be
+    /// verified with [verify_receipts](BlockExecutor::verify_receipts), and have to be saved with
+    /// [save_receipts](BlockExecutor::save_receipts) before the next block is executed.
+    fn execute_block(
+        &mut self,
+        block: &BlockWithSenders,
+        total_difficulty: U256,
+    ) -> Result<Vec<Receipt>, Self::Error>;
+
+    /// Verifies the receipts root and the logs bloom of the block against the receipts of its
+    /// execution.
+    ///
+    /// Returns false if the receipts of the block can't be verified, e.g. before Byzantium, when
+    /// receipts contained the intermediate state root instead of the status.
+    fn verify_receipts(
+        &mut self,
+        block: &BlockWithSenders,
+        receipts: &[Receipt],
+    ) -> Result<bool, Self::Error>;
+
+    /// Saves the receipts of the block that was executed last with
+    /// [execute_block](BlockExecutor::execute_block), pruning them according to the prune modes of
+    /// the executor.
+    fn save_receipts(&mut self, receipts: Vec<Receipt>) -> Result<(), Self::Error>;
+
     /// Runs the provided transactions and commits their state to the run-time database.
     ///
     /// The returned [BundleStateWithReceipts] can be used to persist the changes to disk, and
@@ -69,6 +97,13 @@ pub trait PrunableBlockExecutor: BlockExecutor {
 
     /// Set prune modes.
     fn set_prune_modes(&mut self, prune_modes: PruneModes);
+
+    /// Record the `from` and `to` addresses of the call traces of the executed blocks.
+    fn record_call_addresses(&mut self);
+
+    /// Takes the `from` and `to` addresses of the call traces that were recorded since the last
+    /// call, or `None` if they're not recorded.
+    fn take_call_addresses(&mut self) -> Option<(HashSet<Address>, HashSet<Address>)>;
 }
 
 /// Block execution statistics. Contains duration of each step of block execution.
@@ -1,8 +1,10 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    DatabaseProviderFactory, EvmEnvProvider, StateProviderFactory,
+    AccountReader, BlockReaderIdExt, CallTraceIndexReader, CanonStateSubscriptions,
+    ChainSpecProvider, ChangeSetReader, DatabaseMetadataReader, DatabaseProviderFactory,
+    EvmEnvProvider, PruneCheckpointReader, StageCheckpointReader, StateProviderFactory,
+    StaticFileProviderFactory,
 };
 use reth_db::database::Database;
 
@@ -15,6 +17,11 @@ pub trait FullProvider<DB: Database>:
     + EvmEnvProvider
     + ChainSpecProvider
     + ChangeSetReader
+    + PruneCheckpointReader
+    + StageCheckpointReader
+    + CallTraceIndexReader
+    + StaticFileProviderFactory
+    + DatabaseMetadataReader
     + CanonStateSubscriptions
     + Clone
     + Unpin
@@ -30,6 +37,11 @@ impl<T, DB: Database> FullProvider<DB> for T where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + PruneCheckpointReader
+        + StageCheckpointReader
+        + CallTraceIndexReader
+        + StaticFileProviderFactory
+        + DatabaseMetadataReader
         + CanonStateSubscriptions
         + Clone
         + Unpin
@@ -3,7 +3,7 @@ use reth_db::models::BlockNumberAddress;
 use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{Address, BlockNumber, B256};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     ops::{Range, RangeInclusive},
 };
 
@@ -40,4 +40,20 @@ pub trait HistoryWriter: Send + Sync {
 
     /// Read account/storage changesets and update account/storage history indices.
     fn update_history_indices(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()>;
+
+    /// Unwind the call trace indices of the given `from` and `to` addresses, removing the blocks
+    /// starting from `first_block`.
+    fn unwind_call_trace_indices(
+        &self,
+        first_block: BlockNumber,
+        from: &BTreeSet<Address>,
+        to: &BTreeSet<Address>,
+    ) -> ProviderResult<()>;
+
+    /// Insert call trace indices to database. Used inside IndexCallTraces stage
+    fn insert_call_trace_index(
+        &self,
+        from: BTreeMap<Address, Vec<u64>>,
+        to: BTreeMap<Address, Vec<u64>>,
+    ) -> ProviderResult<()>;
 }
@@ -69,14 +69,20 @@ pub use hashing::HashingWriter;
 mod history;
 pub use history::HistoryWriter;
 
+mod call_trace_index;
+pub use call_trace_index::CallTraceIndexReader;
+
 mod prune_checkpoint;
 pub use prune_checkpoint::{PruneCheckpointReader, PruneCheckpointWriter};
 
 mod database_provider;
 pub use database_provider::DatabaseProviderFactory;
 
+mod static_file_provider;
+pub use static_file_provider::StaticFileProviderFactory;
+
 mod stats;
-pub use stats::StatsReader;
+pub use stats::{DatabaseMetadataReader, StatsReader};
 
 mod full;
 pub use full::FullProvider;
@@ -26,6 +26,13 @@ pub trait StateProvider: BlockHashReader + AccountReader + StateRootProvider + S
     /// Get account and storage proofs.
     fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof>;
 
+    /// Get account and storage proofs of multiple accounts.
+    ///
+    /// Returns one proof per target, in the order of the targets.
+    fn proofs(&self, targets: &[(Address, Vec<B256>)]) -> ProviderResult<Vec<AccountProof>> {
+        targets.iter().map(|(address, keys)| self.proof(*address, keys)).collect()
+    }
+
     /// Get account code by its address.
     ///
     /// Returns `None` if the account doesn't exist or account is not a contract
@@ -104,12 +111,19 @@ pub trait StateProviderFactory: BlockIdReader + Send + Sync {
 
     /// Returns a [StateProvider] indexed by the given [BlockId].
     ///
-    /// Note: if a number or hash is provided this will __only__ look at historical(canonical)
-    /// state.
+    /// Note: if a number is provided this will __only__ look at historical(canonical) state. A hash
+    /// also resolves to the state of a non-canonical block, unless the block id requires a
+    /// canonical block, see [EIP-1898](https://eips.ethereum.org/EIPS/eip-1898).
     fn state_by_block_id(&self, block_id: BlockId) -> ProviderResult<StateProviderBox> {
         match block_id {
             BlockId::Number(block_number) => self.state_by_block_number_or_tag(block_number),
-            BlockId::Hash(block_hash) => self.history_by_block_hash(block_hash.into()),
+            BlockId::Hash(rpc_block_hash) => {
+                if rpc_block_hash.require_canonical.unwrap_or(false) {
+                    self.history_by_block_hash(rpc_block_hash.block_hash)
+                } else {
+                    self.state_by_block_hash(rpc_block_hash.block_hash)
+                }
+            }
         }
     }
 
@@ -0,0 +1,8 @@
+use crate::providers::StaticFileProvider;
+
+/// Static file provider factory.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait StaticFileProviderFactory {
+    /// Returns the provider of the static files.
+    fn static_file_provider(&self) -> StaticFileProvider;
+}
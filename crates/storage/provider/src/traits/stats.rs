@@ -1,4 +1,4 @@
-use reth_db::table::Table;
+use reth_db::{database_metrics::DatabaseMetadataValue, table::Table};
 use reth_interfaces::provider::ProviderResult;
 
 /// The trait for fetching provider statistics.
@@ -8,3 +8,11 @@ pub trait StatsReader: Send + Sync {
     /// route to different data sources other than [Table].
     fn count_entries<T: Table>(&self) -> ProviderResult<usize>;
 }
+
+/// The trait for fetching the metadata of the database, like the size of its used pages and its
+/// freelist.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait DatabaseMetadataReader: Send + Sync {
+    /// Returns the metadata of the database.
+    fn database_metadata(&self) -> DatabaseMetadataValue;
+}
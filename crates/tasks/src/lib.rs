@@ -13,8 +13,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use crate::{
-    metrics::{IncCounterOnDrop, TaskExecutorMetrics},
-    shutdown::{signal, GracefulShutdown, GracefulShutdownGuard, Shutdown, Signal},
+    metrics::{IncCounterOnDrop, ShutdownMetrics, TaskExecutorMetrics},
+    shutdown::{signal, GracefulShutdown, GracefulShutdownGuard, Shutdown, ShutdownPhase, Signal},
 };
 use dyn_clone::DynClone;
 use futures_util::{
@@ -36,7 +36,7 @@ use tokio::{
     sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
     task::JoinHandle,
 };
-use tracing::{debug, error};
+use tracing::{debug, error, info, warn};
 use tracing_futures::Instrument;
 
 pub mod metrics;
@@ -155,14 +155,17 @@ pub struct TaskManager {
     panicked_tasks_tx: UnboundedSender<PanickedTaskError>,
     /// Listens for panicked tasks
     panicked_tasks_rx: UnboundedReceiver<PanickedTaskError>,
-    /// The [Signal] to fire when all tasks should be shutdown.
+    /// The [Signal]s to fire for the [ShutdownPhase]s, in order.
     ///
-    /// This is fired when dropped.
-    signal: Option<Signal>,
-    /// Receiver of the shutdown signal.
+    /// These are fired when dropped.
+    signals: Vec<Signal>,
+    /// Receiver of the shutdown signal of all regular tasks, which is fired with the signal of
+    /// [ShutdownPhase::Persist].
     on_shutdown: Shutdown,
-    /// How many [GracefulShutdown] tasks are currently active
-    graceful_tasks: Arc<AtomicUsize>,
+    /// The shutdown signals of the [ShutdownPhase]s, in order.
+    phases: Arc<[PhaseShutdown]>,
+    /// Metrics of the graceful shutdown.
+    metrics: ShutdownMetrics,
 }
 
 // === impl TaskManager ===
@@ -181,14 +184,22 @@ impl TaskManager {
     /// Create a new instance connected to the given handle's tokio runtime.
     pub fn new(handle: Handle) -> Self {
         let (panicked_tasks_tx, panicked_tasks_rx) = unbounded_channel();
-        let (signal, on_shutdown) = signal();
+        let (signals, phases): (Vec<_>, Vec<_>) = ShutdownPhase::ALL
+            .iter()
+            .map(|_| {
+                let (signal, on_shutdown) = signal();
+                (signal, PhaseShutdown { on_shutdown, graceful_tasks: Default::default() })
+            })
+            .unzip();
+        let on_shutdown = phases[ShutdownPhase::Persist.index()].on_shutdown.clone();
         Self {
             handle,
             panicked_tasks_tx,
             panicked_tasks_rx,
-            signal: Some(signal),
+            signals,
             on_shutdown,
-            graceful_tasks: Arc::new(AtomicUsize::new(0)),
+            phases: phases.into(),
+            metrics: Default::default(),
         }
     }
 
@@ -200,7 +211,7 @@ impl TaskManager {
             on_shutdown: self.on_shutdown.clone(),
             panicked_tasks_tx: self.panicked_tasks_tx.clone(),
             metrics: Default::default(),
-            graceful_tasks: Arc::clone(&self.graceful_tasks),
+            phases: Arc::clone(&self.phases),
         }
     }
 
@@ -216,17 +227,33 @@ impl TaskManager {
         self.do_graceful_shutdown(Some(timeout))
     }
 
+    /// Fires the shutdown signals of the [ShutdownPhase]s in order, and awaits until all
+    /// [GracefulShutdown] tasks of a phase are shutdown before the next phase is started.
+    ///
+    /// If the timeout elapses, the signals of the remaining phases are fired without waiting for
+    /// their tasks.
     fn do_graceful_shutdown(self, timeout: Option<std::time::Duration>) -> bool {
-        drop(self.signal);
         let when = timeout.map(|t| std::time::Instant::now() + t);
-        while self.graceful_tasks.load(Ordering::Relaxed) > 0 {
-            if when.map(|when| std::time::Instant::now() > when).unwrap_or(false) {
-                debug!("graceful shutdown timed out");
-                return false
+        for (phase, signal) in ShutdownPhase::ALL.into_iter().zip(self.signals) {
+            let graceful_tasks = &self.phases[phase.index()].graceful_tasks;
+            let tasks = graceful_tasks.load(Ordering::Relaxed);
+            self.metrics.phase.set(phase as u8 as f64);
+            self.metrics.remaining_tasks.set(tasks as f64);
+            info!(target: "reth::tasks", %phase, tasks, "Starting shutdown phase");
+
+            drop(signal);
+            while graceful_tasks.load(Ordering::Relaxed) > 0 {
+                if when.map(|when| std::time::Instant::now() > when).unwrap_or(false) {
+                    let remaining_tasks = graceful_tasks.load(Ordering::Relaxed);
+                    self.metrics.remaining_tasks.set(remaining_tasks as f64);
+                    warn!(target: "reth::tasks", %phase, remaining_tasks, "Graceful shutdown timed out, aborting remaining tasks");
+                    return false
+                }
+                std::hint::spin_loop();
             }
-            std::hint::spin_loop();
         }
 
+        self.metrics.remaining_tasks.set(0.0);
         debug!("gracefully shut down");
         true
     }
@@ -289,8 +316,8 @@ pub struct TaskExecutor {
     panicked_tasks_tx: UnboundedSender<PanickedTaskError>,
     // Task Executor Metrics
     metrics: TaskExecutorMetrics,
-    /// How many [GracefulShutdown] tasks are currently active
-    graceful_tasks: Arc<AtomicUsize>,
+    /// The shutdown signals of the [ShutdownPhase]s, in order.
+    phases: Arc<[PhaseShutdown]>,
 }
 
 // === impl TaskExecutor ===
@@ -306,6 +333,18 @@ impl TaskExecutor {
         &self.on_shutdown
     }
 
+    /// Returns a [GracefulShutdown] signal that fires when the given [ShutdownPhase] starts.
+    ///
+    /// The phase does not complete and the next phase is not started before the signal, or the
+    /// guard it resolves to, is dropped.
+    pub fn graceful_shutdown_signal(&self, phase: ShutdownPhase) -> GracefulShutdown {
+        let phase = &self.phases[phase.index()];
+        GracefulShutdown::new(
+            phase.on_shutdown.clone(),
+            GracefulShutdownGuard::new(Arc::clone(&phase.graceful_tasks)),
+        )
+    }
+
     /// Spawns a future on the tokio runtime depending on the [TaskKind]
     fn spawn_on_rt<F>(&self, fut: F, task_kind: TaskKind) -> JoinHandle<()>
     where
@@ -492,14 +531,49 @@ impl TaskExecutor {
         name: &'static str,
         f: impl FnOnce(GracefulShutdown) -> F,
     ) -> JoinHandle<()>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.spawn_critical_with_graceful_shutdown_phase(name, ShutdownPhase::Persist, f)
+    }
+
+    /// This spawns a critical task onto the runtime, which is shut down in the given
+    /// [ShutdownPhase].
+    ///
+    /// If this task panics, the [TaskManager] is notified.
+    /// The [TaskManager] will wait until the given future has completed before starting the next
+    /// phase of the shutdown.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn t(executor: reth_tasks::TaskExecutor) {
+    /// use reth_tasks::shutdown::ShutdownPhase;
+    ///
+    /// executor.spawn_critical_with_graceful_shutdown_phase(
+    ///     "rpc shutdown",
+    ///     ShutdownPhase::Rpc,
+    ///     |shutdown| async move {
+    ///         // await the start of the phase
+    ///         let guard = shutdown.await;
+    ///         // stop accepting new requests
+    ///         // allow the next phase to start
+    ///         drop(guard);
+    ///     },
+    /// );
+    /// # }
+    /// ```
+    pub fn spawn_critical_with_graceful_shutdown_phase<F>(
+        &self,
+        name: &'static str,
+        phase: ShutdownPhase,
+        f: impl FnOnce(GracefulShutdown) -> F,
+    ) -> JoinHandle<()>
     where
         F: Future<Output = ()> + Send + 'static,
     {
         let panicked_tasks_tx = self.panicked_tasks_tx.clone();
-        let on_shutdown = GracefulShutdown::new(
-            self.on_shutdown.clone(),
-            GracefulShutdownGuard::new(Arc::clone(&self.graceful_tasks)),
-        );
+        let on_shutdown = self.graceful_shutdown_signal(phase);
         let fut = f(on_shutdown);
 
         // wrap the task in catch unwind
@@ -542,10 +616,7 @@ impl TaskExecutor {
     where
         F: Future<Output = ()> + Send + 'static,
     {
-        let on_shutdown = GracefulShutdown::new(
-            self.on_shutdown.clone(),
-            GracefulShutdownGuard::new(Arc::clone(&self.graceful_tasks)),
-        );
+        let on_shutdown = self.graceful_shutdown_signal(ShutdownPhase::Persist);
         let fut = f(on_shutdown);
 
         self.handle.spawn(fut)
@@ -624,6 +695,15 @@ impl TaskSpawnerExt for TaskExecutor {
     }
 }
 
+/// The shutdown signal of a [ShutdownPhase] and the number of its active [GracefulShutdown]s.
+#[derive(Debug)]
+struct PhaseShutdown {
+    /// Receiver of the shutdown signal of the phase.
+    on_shutdown: Shutdown,
+    /// How many [GracefulShutdown] tasks of the phase are currently active
+    graceful_tasks: Arc<AtomicUsize>,
+}
+
 /// Determines how a task is spawned
 enum TaskKind {
     /// Spawn the task to the default executor [Handle::spawn]
@@ -771,4 +851,78 @@ mod tests {
         manager.graceful_shutdown_with_timeout(timeout);
         assert!(!val.load(Ordering::Relaxed));
     }
+
+    #[test]
+    fn test_manager_graceful_shutdown_phases() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let handle = runtime.handle().clone();
+        let manager = TaskManager::new(handle);
+        let executor = manager.executor();
+
+        // spawn the tasks in reverse order, every task takes a while to shut down
+        let phases = Arc::new(std::sync::Mutex::new(Vec::new()));
+        for phase in ShutdownPhase::ALL.into_iter().rev() {
+            let phases = phases.clone();
+            executor.spawn_critical_with_graceful_shutdown_phase(
+                "grace",
+                phase,
+                move |shutdown| async move {
+                    let _guard = shutdown.await;
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    phases.lock().unwrap().push(phase);
+                },
+            );
+        }
+
+        // regular tasks are shut down with the persist phase
+        let persisted = phases.clone();
+        executor.spawn_with_signal(|shutdown| async move {
+            shutdown.await;
+            persisted.lock().unwrap().push(ShutdownPhase::Persist);
+        });
+
+        manager.graceful_shutdown();
+        assert_eq!(
+            *phases.lock().unwrap(),
+            vec![
+                ShutdownPhase::Rpc,
+                ShutdownPhase::Engine,
+                ShutdownPhase::Pipeline,
+                ShutdownPhase::StaticFiles,
+                ShutdownPhase::Persist,
+                ShutdownPhase::Persist,
+                ShutdownPhase::Database,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_manager_graceful_shutdown_phase_timeout() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let handle = runtime.handle().clone();
+        let manager = TaskManager::new(handle);
+        let executor = manager.executor();
+
+        let timeout = Duration::from_millis(500);
+        let database = executor.graceful_shutdown_signal(ShutdownPhase::Database);
+        let database_fired = Arc::new(AtomicBool::new(true));
+        let database_fired2 = database_fired.clone();
+        let database2 = database.clone();
+        executor.spawn_critical_with_graceful_shutdown_phase(
+            "stuck",
+            ShutdownPhase::Engine,
+            |shutdown| async move {
+                let _guard = shutdown.await;
+                tokio::time::sleep(timeout / 2).await;
+                database_fired2.store(database2.is_fired(), Ordering::Relaxed);
+                tokio::time::sleep(timeout * 3).await;
+            },
+        );
+
+        assert!(!manager.graceful_shutdown_with_timeout(timeout));
+        // the later phases are not started while the engine phase is stuck
+        assert!(!database_fired.load(Ordering::Relaxed));
+        // but they are fired once the timeout elapsed
+        assert!(database.is_fired());
+    }
 }
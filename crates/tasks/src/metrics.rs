@@ -2,7 +2,10 @@
 
 use core::fmt;
 
-use reth_metrics::{metrics::Counter, Metrics};
+use reth_metrics::{
+    metrics::{Counter, Gauge},
+    Metrics,
+};
 
 /// Task Executor Metrics
 #[derive(Metrics, Clone)]
@@ -30,6 +33,17 @@ impl TaskExecutorMetrics {
     }
 }
 
+/// Graceful shutdown metrics
+#[derive(Metrics, Clone)]
+#[metrics(scope = "executor.shutdown")]
+pub struct ShutdownMetrics {
+    /// The current [phase](crate::shutdown::ShutdownPhase) of the graceful shutdown, or zero if
+    /// the shutdown has not started
+    pub(crate) phase: Gauge,
+    /// Number of graceful tasks of the current phase that have not completed yet
+    pub(crate) remaining_tasks: Gauge,
+}
+
 /// Helper type for increasing counters even if a task fails
 pub struct IncCounterOnDrop(Counter);
 
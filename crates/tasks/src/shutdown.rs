@@ -5,6 +5,7 @@ use futures_util::{
     FutureExt,
 };
 use std::{
+    fmt,
     future::Future,
     pin::Pin,
     sync::{atomic::AtomicUsize, Arc},
@@ -25,6 +26,11 @@ impl GracefulShutdown {
     pub(crate) fn new(shutdown: Shutdown, guard: GracefulShutdownGuard) -> Self {
         Self { shutdown, guard: Some(guard) }
     }
+
+    /// Returns true if the shutdown event has been fired, without releasing the guard.
+    pub fn is_fired(&self) -> bool {
+        self.shutdown.is_fired()
+    }
 }
 
 impl Future for GracefulShutdown {
@@ -68,6 +74,13 @@ impl Drop for GracefulShutdownGuard {
 #[derive(Debug, Clone)]
 pub struct Shutdown(Shared<oneshot::Receiver<()>>);
 
+impl Shutdown {
+    /// Returns true if the shutdown event has been fired.
+    pub fn is_fired(&self) -> bool {
+        self.clone().now_or_never().is_some()
+    }
+}
+
 impl Future for Shutdown {
     type Output = ();
 
@@ -98,6 +111,62 @@ pub fn signal() -> (Signal, Shutdown) {
     (Signal(sender), Shutdown(receiver.shared()))
 }
 
+/// The phases of a graceful shutdown of the [TaskManager](crate::TaskManager), in order.
+///
+/// The phases are started one after another: a phase starts once all [GracefulShutdown]s of the
+/// previous phase completed. Tasks are assigned to a phase when they are spawned by the
+/// [TaskExecutor](crate::TaskExecutor).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShutdownPhase {
+    /// Stop accepting new RPC requests and engine API messages.
+    Rpc = 1,
+    /// Let the in-flight engine API messages complete.
+    Engine = 2,
+    /// Stop the pipeline at the next stage boundary.
+    Pipeline = 3,
+    /// Commit the static file writers.
+    StaticFiles = 4,
+    /// Persist the state of the components, like the transaction pool and the peers.
+    ///
+    /// This is the phase of the tasks that are spawned with a [GracefulShutdown] signal without a
+    /// phase. The [Shutdown] signal of all other tasks is fired when it starts.
+    Persist = 5,
+    /// Release the database.
+    ///
+    /// This is the phase of the tasks that use the database until all other phases completed. The
+    /// database itself is closed once the last task that holds it is dropped, after this phase.
+    Database = 6,
+}
+
+impl ShutdownPhase {
+    /// All phases, in order.
+    pub const ALL: [Self; 6] =
+        [Self::Rpc, Self::Engine, Self::Pipeline, Self::StaticFiles, Self::Persist, Self::Database];
+
+    /// Returns the index of the phase in [ShutdownPhase::ALL].
+    pub(crate) const fn index(&self) -> usize {
+        *self as usize - 1
+    }
+
+    /// Returns the name of the phase.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rpc => "rpc",
+            Self::Engine => "engine",
+            Self::Pipeline => "pipeline",
+            Self::StaticFiles => "static_files",
+            Self::Persist => "persist",
+            Self::Database => "database",
+        }
+    }
+}
+
+impl fmt::Display for ShutdownPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +208,15 @@ mod tests {
         join_all(tasks).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_is_fired() {
+        let (signal, shutdown) = signal();
+        assert!(!shutdown.is_fired());
+        signal.fire();
+        assert!(shutdown.is_fired());
+        assert!(shutdown.clone().is_fired());
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_drop_signal_from_thread() {
         let (signal, shutdown) = signal();
@@ -43,18 +43,7 @@ impl LogFormat {
         color: Option<String>,
         file_writer: Option<NonBlocking>,
     ) -> BoxedLayer<Registry> {
-        let ansi = if let Some(color) = color {
-            std::env::var("RUST_LOG_STYLE").map(|val| val != "never").unwrap_or(color != "never")
-        } else {
-            false
-        };
-        let target = std::env::var("RUST_LOG_TARGET")
-            // `RUST_LOG_TARGET` always overrides default behaviour
-            .map(|val| val != "0")
-            .unwrap_or_else(|_|
-                // If `RUST_LOG_TARGET` is not set, show target in logs only if the max enabled
-                // level is higher than INFO (DEBUG, TRACE)
-                filter.max_level_hint().map_or(true, |max_level| max_level > tracing::Level::INFO));
+        let (ansi, target) = ansi_and_target(&filter, color);
 
         match self {
             LogFormat::Json => {
@@ -79,6 +68,52 @@ impl LogFormat {
             }
         }
     }
+
+    /// Applies the specified logging format to create a new layer that writes to stderr.
+    pub(crate) fn apply_to_stderr(
+        &self,
+        filter: EnvFilter,
+        color: Option<String>,
+    ) -> BoxedLayer<Registry> {
+        let (ansi, target) = ansi_and_target(&filter, color);
+
+        match self {
+            LogFormat::Json => tracing_subscriber::fmt::layer()
+                .json()
+                .with_ansi(ansi)
+                .with_target(target)
+                .with_writer(std::io::stderr)
+                .with_filter(filter)
+                .boxed(),
+            LogFormat::LogFmt => tracing_logfmt::builder()
+                .layer_with_writer(std::io::stderr)
+                .with_filter(filter)
+                .boxed(),
+            LogFormat::Terminal => tracing_subscriber::fmt::layer()
+                .with_ansi(ansi)
+                .with_target(target)
+                .with_writer(std::io::stderr)
+                .with_filter(filter)
+                .boxed(),
+        }
+    }
+}
+
+/// Returns whether ANSI color codes are emitted and whether the targets are shown in the logs.
+fn ansi_and_target(filter: &EnvFilter, color: Option<String>) -> (bool, bool) {
+    let ansi = if let Some(color) = color {
+        std::env::var("RUST_LOG_STYLE").map(|val| val != "never").unwrap_or(color != "never")
+    } else {
+        false
+    };
+    let target = std::env::var("RUST_LOG_TARGET")
+        // `RUST_LOG_TARGET` always overrides default behaviour
+        .map(|val| val != "0")
+        .unwrap_or_else(|_|
+            // If `RUST_LOG_TARGET` is not set, show target in logs only if the max enabled
+            // level is higher than INFO (DEBUG, TRACE)
+            filter.max_level_hint().map_or(true, |max_level| max_level > tracing::Level::INFO));
+    (ansi, target)
 }
 
 impl Display for LogFormat {
@@ -65,6 +65,7 @@ impl Layers {
     /// * `directive` - Directive for the default logging level.
     /// * `filter` - Additional filter directives as a string.
     /// * `color` - Optional color configuration for the log messages.
+    /// * `stderr` - Whether the log messages are written to stderr instead of stdout.
     ///
     /// # Returns
     /// An `eyre::Result<()>` indicating the success or failure of the operation.
@@ -74,9 +75,14 @@ impl Layers {
         default_directive: Directive,
         filters: &str,
         color: Option<String>,
+        stderr: bool,
     ) -> eyre::Result<()> {
         let filter = build_env_filter(Some(default_directive), filters)?;
-        let layer = format.apply(filter, color, None);
+        let layer = if stderr {
+            format.apply_to_stderr(filter, color)
+        } else {
+            format.apply(filter, color, None)
+        };
         self.inner.push(layer.boxed());
         Ok(())
     }
@@ -69,6 +69,7 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 #[derive(Debug, Clone)]
 pub struct RethTracer {
     stdout: LayerInfo,
+    stdout_to_stderr: bool,
     journald: Option<String>,
     file: Option<(LayerInfo, FileInfo)>,
 }
@@ -79,7 +80,7 @@ impl RethTracer {
     ///  Initializes with default stdout layer configuration.
     ///  Journald and file layers are not set by default.
     pub fn new() -> Self {
-        Self { stdout: LayerInfo::default(), journald: None, file: None }
+        Self { stdout: LayerInfo::default(), stdout_to_stderr: false, journald: None, file: None }
     }
 
     ///  Sets a custom configuration for the stdout layer.
@@ -91,6 +92,12 @@ impl RethTracer {
         self
     }
 
+    ///  Writes the stdout layer to stderr instead, e.g. when stdout carries the command output.
+    pub fn with_stdout_to_stderr(mut self) -> Self {
+        self.stdout_to_stderr = true;
+        self
+    }
+
     ///  Sets the journald layer filter.
     ///
     ///  # Arguments
@@ -198,6 +205,7 @@ impl Tracer for RethTracer {
             self.stdout.default_directive.parse()?,
             &self.stdout.filters,
             self.stdout.color,
+            self.stdout_to_stderr,
         )?;
 
         if let Some(config) = self.journald {
@@ -8,7 +8,10 @@ use std::{
     fmt,
     sync::atomic::{AtomicUsize, Ordering},
 };
-pub use tracker::{BlobStoreCanonTracker, BlobStoreUpdates};
+pub use tracker::{
+    BlobStoreCanonTracker, BlobStoreUpdates, MissingBlobSidecars, MissingBlobSidecarsTracker,
+    MAX_BLOB_SIDECAR_REFETCH_ATTEMPTS,
+};
 
 pub mod disk;
 mod mem;
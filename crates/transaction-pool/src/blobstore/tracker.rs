@@ -2,7 +2,11 @@
 
 use reth_primitives::{BlockNumber, B256};
 use reth_provider::chain::ChainBlocks;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+
+/// The maximum number of times the sidecar of a reorged blob transaction is requested from peers
+/// before the transaction is dropped.
+pub const MAX_BLOB_SIDECAR_REFETCH_ATTEMPTS: u8 = 5;
 
 /// The type that is used to track canonical blob transactions.
 #[derive(Debug, Default, Eq, PartialEq)]
@@ -76,6 +80,79 @@ pub enum BlobStoreUpdates {
     Finalized(Vec<B256>),
 }
 
+/// The type that is used to track reorged blob transactions whose sidecars are missing from the
+/// blob store.
+///
+/// These transactions can't be re-injected into the pool, so their sidecars are requested from
+/// peers until the transactions are back in the pool, or until
+/// [`MAX_BLOB_SIDECAR_REFETCH_ATTEMPTS`] is reached.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct MissingBlobSidecarsTracker {
+    /// The number of times the sidecar of each transaction was requested.
+    attempts: HashMap<B256, u8>,
+}
+
+impl MissingBlobSidecarsTracker {
+    /// Starts tracking the given blob transactions, if not tracked yet.
+    pub fn add_transactions(&mut self, tx_hashes: impl IntoIterator<Item = B256>) {
+        for tx_hash in tx_hashes {
+            self.attempts.entry(tx_hash).or_default();
+        }
+    }
+
+    /// Stops tracking the given transactions, for example because they were mined.
+    pub fn remove_transactions<'a>(&mut self, tx_hashes: impl IntoIterator<Item = &'a B256>) {
+        for tx_hash in tx_hashes {
+            self.attempts.remove(tx_hash);
+        }
+    }
+
+    /// Returns the number of tracked transactions.
+    pub fn len(&self) -> usize {
+        self.attempts.len()
+    }
+
+    /// Returns `true` if no transactions are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.attempts.is_empty()
+    }
+
+    /// Invoked when a new block was processed.
+    ///
+    /// Transactions for which `is_recovered` returns `true` are back in the pool and are no longer
+    /// tracked, as are the transactions that reached the maximum number of attempts. The sidecars
+    /// of all other transactions should be requested again.
+    pub fn on_new_block(&mut self, is_recovered: impl Fn(&B256) -> bool) -> MissingBlobSidecars {
+        let mut update = MissingBlobSidecars::default();
+        self.attempts.retain(|tx_hash, attempts| {
+            if is_recovered(tx_hash) {
+                update.recovered.push(*tx_hash);
+                return false
+            }
+            if *attempts >= MAX_BLOB_SIDECAR_REFETCH_ATTEMPTS {
+                update.exhausted.push(*tx_hash);
+                return false
+            }
+            *attempts += 1;
+            update.to_request.push(*tx_hash);
+            true
+        });
+        update
+    }
+}
+
+/// The transactions tracked by the [`MissingBlobSidecarsTracker`], after a new block was
+/// processed.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct MissingBlobSidecars {
+    /// Transactions whose sidecars should be requested from peers.
+    pub to_request: Vec<B256>,
+    /// Transactions that are back in the pool.
+    pub recovered: Vec<B256>,
+    /// Transactions whose sidecars couldn't be fetched within the maximum number of attempts.
+    pub exhausted: Vec<B256>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,4 +175,33 @@ mod tests {
             BlobStoreUpdates::Finalized(block2.into_iter().chain(block3).collect::<Vec<_>>())
         );
     }
+
+    #[test]
+    fn test_missing_sidecars_tracker() {
+        let mut tracker = MissingBlobSidecarsTracker::default();
+
+        let recovered = B256::random();
+        let missing = B256::random();
+        let mined = B256::random();
+        tracker.add_transactions([recovered, missing, mined]);
+        tracker.remove_transactions(&[mined]);
+        assert_eq!(tracker.len(), 2);
+
+        let update = tracker.on_new_block(|_| false);
+        assert_eq!(update.to_request.len(), 2);
+
+        let update = tracker.on_new_block(|tx_hash| *tx_hash == recovered);
+        assert_eq!(update.recovered, vec![recovered]);
+        assert_eq!(update.to_request, vec![missing]);
+
+        // re-adding a tracked transaction doesn't reset its attempts
+        tracker.add_transactions([missing]);
+        for _ in 2..MAX_BLOB_SIDECAR_REFETCH_ATTEMPTS {
+            assert_eq!(tracker.on_new_block(|_| false).to_request, vec![missing]);
+        }
+        let update = tracker.on_new_block(|_| false);
+        assert!(update.to_request.is_empty());
+        assert_eq!(update.exhausted, vec![missing]);
+        assert!(tracker.is_empty());
+    }
 }
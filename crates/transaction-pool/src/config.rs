@@ -31,8 +31,19 @@ pub struct PoolConfig {
     pub blob_limit: SubPoolLimit,
     /// Max number of executable transaction slots guaranteed per account
     pub max_account_slots: usize,
+    /// Max number of transactions of a single sender in the pool.
+    ///
+    /// Unlike `max_account_slots`, this is a hard limit that also applies to local transactions.
+    pub max_txs_per_sender: Option<usize>,
+    /// Max number of transactions in the pool per [TransactionOrigin].
+    pub origin_limits: OriginLimits,
     /// Price bump (in %) for the transaction pool underpriced check.
     pub price_bumps: PriceBumpConfig,
+    /// Minimum effective tip a new transaction must pay at the base fee of the pending block.
+    ///
+    /// This is enforced by the validator, see
+    /// [EthTransactionValidatorBuilder](crate::validate::EthTransactionValidatorBuilder).
+    pub minimum_priority_fee: Option<u128>,
     /// How to handle locally received transactions:
     /// [TransactionOrigin::Local](crate::TransactionOrigin).
     pub local_transactions_config: LocalTransactionConfig,
@@ -57,12 +68,62 @@ impl Default for PoolConfig {
             queued_limit: Default::default(),
             blob_limit: Default::default(),
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_txs_per_sender: None,
+            origin_limits: Default::default(),
             price_bumps: Default::default(),
+            minimum_priority_fee: None,
             local_transactions_config: Default::default(),
         }
     }
 }
 
+/// The settings of the [PoolConfig] that can be changed while the pool is running, see
+/// [TransactionPool::update_config](crate::TransactionPool::update_config).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolConfigUpdate {
+    /// Max number of transactions of a single sender in the pool.
+    pub max_txs_per_sender: Option<usize>,
+    /// Max number of transactions in the pool per [TransactionOrigin].
+    pub origin_limits: OriginLimits,
+    /// Minimum effective tip a new transaction must pay at the base fee of the pending block.
+    pub minimum_priority_fee: Option<u128>,
+}
+
+impl From<&PoolConfig> for PoolConfigUpdate {
+    fn from(config: &PoolConfig) -> Self {
+        Self {
+            max_txs_per_sender: config.max_txs_per_sender,
+            origin_limits: config.origin_limits,
+            minimum_priority_fee: config.minimum_priority_fee,
+        }
+    }
+}
+
+/// The minimum effective tip a new transaction must pay at the base fee of the pending block, see
+/// [TransactionPool::priority_fee_floor](crate::TransactionPool::priority_fee_floor).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PriorityFeeFloor {
+    /// The configured minimum priority fee, see [PoolConfig::minimum_priority_fee].
+    pub minimum: Option<u128>,
+    /// The floor adjusted by the maintenance task based on the fullness of recent blocks, see
+    /// [PriorityFeeFloorConfig](crate::maintain::PriorityFeeFloorConfig).
+    pub dynamic: Option<u128>,
+    /// The floor set manually, e.g. with `miner_setGasPrice`, which replaces both the configured
+    /// and the dynamic floor.
+    pub manual: Option<u128>,
+}
+
+impl PriorityFeeFloor {
+    /// Returns the floor that's enforced: the manual floor if set, otherwise the higher of the
+    /// configured and the dynamic floor.
+    pub fn effective(&self) -> Option<u128> {
+        self.manual.or(match (self.minimum, self.dynamic) {
+            (Some(minimum), Some(dynamic)) => Some(minimum.max(dynamic)),
+            (minimum, dynamic) => minimum.or(dynamic),
+        })
+    }
+}
+
 /// Size limits for a sub-pool.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SubPoolLimit {
@@ -95,6 +156,32 @@ impl Default for SubPoolLimit {
     }
 }
 
+/// Limits for the number of transactions in the pool per [TransactionOrigin].
+///
+/// If the limit of an origin is reached, a new transaction of this origin evicts the transaction
+/// with the lowest priority fee of the same origin, if that fee is lower.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OriginLimits {
+    /// Max number of [TransactionOrigin::Local] transactions.
+    pub local: Option<usize>,
+    /// Max number of [TransactionOrigin::External] transactions.
+    pub external: Option<usize>,
+    /// Max number of [TransactionOrigin::Private] transactions.
+    pub private: Option<usize>,
+}
+
+impl OriginLimits {
+    /// Returns the limit for the given origin, if any.
+    #[inline]
+    pub const fn limit(&self, origin: TransactionOrigin) -> Option<usize> {
+        match origin {
+            TransactionOrigin::Local => self.local,
+            TransactionOrigin::External => self.external,
+            TransactionOrigin::Private => self.private,
+        }
+    }
+}
+
 /// Price bump config (in %) for the transaction pool underpriced check.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct PriceBumpConfig {
@@ -139,6 +226,9 @@ pub struct LocalTransactionConfig {
     pub local_addresses: HashSet<Address>,
     /// Flag indicating whether local transactions should be propagated.
     pub propagate_local_transactions: bool,
+    /// Whether the minimum priority fee of the validator also applies to local transactions,
+    /// which are exempt from it by default.
+    pub enforce_minimum_priority_fee: bool,
 }
 
 impl Default for LocalTransactionConfig {
@@ -147,6 +237,7 @@ impl Default for LocalTransactionConfig {
             no_exemptions: false,
             local_addresses: HashSet::default(),
             propagate_local_transactions: true,
+            enforce_minimum_priority_fee: false,
         }
     }
 }
@@ -1,5 +1,6 @@
 //! Transaction pool errors
 
+use crate::TransactionOrigin;
 use reth_primitives::{Address, BlobTransactionValidationError, InvalidTransactionError, TxHash};
 
 /// Transaction pool result type.
@@ -49,6 +50,15 @@ pub enum PoolErrorKind {
     /// Thrown when the number of unique transactions of a sender exceeded the slot capacity.
     #[error("rejected due to {0} being identified as a spammer")]
     SpammerExceededCapacity(Address),
+    /// Thrown when the sender already has the maximum number of transactions in the pool, see
+    /// [PoolConfig::max_txs_per_sender](crate::PoolConfig::max_txs_per_sender).
+    #[error("sender {0} exceeded the limit of transactions in the pool")]
+    SenderTransactionsLimitExceeded(Address),
+    /// Thrown when the pool already holds the maximum number of transactions of the origin and no
+    /// transaction with a lower priority fee could be evicted, see
+    /// [PoolConfig::origin_limits](crate::PoolConfig::origin_limits).
+    #[error("limit of {0:?} transactions in the pool exceeded")]
+    OriginLimitExceeded(TransactionOrigin),
     /// Thrown when a new transaction is added to the pool, but then immediately discarded to
     /// respect the size limits of the pool.
     #[error("transaction discarded outright due to pool size constraints")]
@@ -115,6 +125,15 @@ impl PoolError {
                 // (pool lags behind) and old transaction still occupy a slot in the pool
                 false
             }
+            PoolErrorKind::SenderTransactionsLimitExceeded(_) => {
+                // the sender hit the configured limit, which is a local policy, and the same
+                // reasoning as for the slot capacity applies
+                false
+            }
+            PoolErrorKind::OriginLimitExceeded(_) => {
+                // valid tx but rejected due to the configured limits of the origin
+                false
+            }
             PoolErrorKind::DiscardedOnInsert => {
                 // valid tx but dropped due to size constraints
                 false
@@ -191,6 +210,13 @@ pub enum InvalidPoolTransactionError {
     /// Thrown if the transaction's fee is below the minimum fee
     #[error("transaction underpriced")]
     Underpriced,
+    /// Thrown if the effective tip of the transaction at the base fee of the pending block is
+    /// below the configured minimum priority fee
+    #[error("transaction effective tip below minimum priority fee {minimum_priority_fee}")]
+    PriorityFeeBelowMinimum {
+        /// The configured minimum priority fee.
+        minimum_priority_fee: u128,
+    },
     /// Thrown if the transaction's would require an account to be overdrawn
     #[error("transaction overdraws from account")]
     Overdraft,
@@ -253,7 +279,8 @@ impl InvalidPoolTransactionError {
             InvalidPoolTransactionError::ExceedsGasLimit(_, _) => true,
             InvalidPoolTransactionError::ExceedsMaxInitCodeSize(_, _) => true,
             InvalidPoolTransactionError::OversizedData(_, _) => true,
-            InvalidPoolTransactionError::Underpriced => {
+            InvalidPoolTransactionError::Underpriced |
+            InvalidPoolTransactionError::PriorityFeeBelowMinimum { .. } => {
                 // local setting
                 false
             }
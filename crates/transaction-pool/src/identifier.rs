@@ -1,6 +1,6 @@
 use fnv::FnvHashMap;
 use reth_primitives::Address;
-use std::collections::HashMap;
+use std::{collections::HashMap, fmt};
 
 /// An internal mapping of addresses.
 ///
@@ -68,6 +68,12 @@ impl From<u64> for SenderId {
     }
 }
 
+impl fmt::Display for SenderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 /// A unique identifier of a transaction of a Sender.
 ///
 /// This serves as an identifier for dependencies of a transaction:
@@ -161,9 +161,10 @@ use tracing::{instrument, trace};
 pub use crate::{
     blobstore::{BlobStore, BlobStoreError},
     config::{
-        LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
-        REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
-        TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+        LocalTransactionConfig, OriginLimits, PoolConfig, PoolConfigUpdate, PriceBumpConfig,
+        PriorityFeeFloor, SubPoolLimit, DEFAULT_PRICE_BUMP, REPLACE_BLOB_PRICE_BUMP,
+        TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+        TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
     ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
@@ -173,8 +174,9 @@ pub use crate::{
     },
     traits::*,
     validate::{
-        EthTransactionValidator, TransactionValidationOutcome, TransactionValidationTaskExecutor,
-        TransactionValidator, ValidPoolTransaction,
+        EthTransactionValidator, KnownAccount, TransactionConditional,
+        TransactionValidationOutcome, TransactionValidationTaskExecutor, TransactionValidator,
+        ValidPoolTransaction,
     },
 };
 
@@ -228,6 +230,8 @@ where
     }
 
     /// Get the config the pool was configured with.
+    ///
+    /// This doesn't include the changes of [TransactionPool::update_config].
     pub fn config(&self) -> &PoolConfig {
         self.inner().config()
     }
@@ -332,6 +336,18 @@ where
         self.pool.block_info()
     }
 
+    fn update_config(&self, update: PoolConfigUpdate) {
+        self.pool.update_config(update)
+    }
+
+    fn priority_fee_floor(&self) -> PriorityFeeFloor {
+        self.pool.priority_fee_floor()
+    }
+
+    fn set_priority_fee_floor_override(&self, floor: Option<u128>) {
+        self.pool.update_priority_fee_floor(|current| current.manual = floor)
+    }
+
     async fn add_transaction_and_subscribe(
         &self,
         origin: TransactionOrigin,
@@ -364,6 +380,20 @@ where
         self.pool.add_transactions(origin, validated.into_iter().map(|(_, tx)| tx))
     }
 
+    async fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let (_, tx) = self.validate(origin, transaction).await;
+        let mut results = self.pool.add_transactions_with_conditionals(
+            origin,
+            std::iter::once((tx, Some(Box::new(conditional)))),
+        );
+        results.pop().expect("result length is the same as the input")
+    }
+
     fn transaction_event_listener(&self, tx_hash: TxHash) -> Option<TransactionEvents> {
         self.pool.add_transaction_event_listener(tx_hash)
     }
@@ -380,6 +410,10 @@ where
         self.pool.add_blob_sidecar_listener()
     }
 
+    fn missing_blob_sidecars_listener(&self) -> Receiver<TxHash> {
+        self.pool.add_missing_blob_sidecar_listener()
+    }
+
     fn new_transactions_listener_for(
         &self,
         kind: TransactionListenerKind,
@@ -450,6 +484,10 @@ where
         self.pool.all_transactions()
     }
 
+    fn transactions_page(&self, query: TransactionsQuery) -> TransactionsPage<Self::Transaction> {
+        self.pool.transactions_page(&query)
+    }
+
     fn remove_transactions(
         &self,
         hashes: Vec<TxHash>,
@@ -535,6 +573,10 @@ where
         self.pool.set_block_info(info)
     }
 
+    fn set_dynamic_priority_fee_floor(&self, floor: Option<u128>) {
+        self.pool.update_priority_fee_floor(|current| current.dynamic = floor)
+    }
+
     fn on_canonical_state_change(&self, update: CanonicalStateUpdate<'_>) {
         self.pool.on_canonical_state_change(update);
     }
@@ -554,6 +596,10 @@ where
     fn cleanup_blobs(&self) {
         self.pool.cleanup_blobs()
     }
+
+    fn request_missing_blob_sidecars(&self, tx_hashes: Vec<TxHash>) {
+        self.pool.request_missing_blob_sidecars(tx_hashes)
+    }
 }
 
 impl<V, T: TransactionOrdering, S> Clone for Pool<V, T, S> {
@@ -1,11 +1,13 @@
 //! Support for maintaining the state of the transaction pool
 
 use crate::{
-    blobstore::{BlobStoreCanonTracker, BlobStoreUpdates},
+    blobstore::{
+        BlobStoreCanonTracker, BlobStoreUpdates, MissingBlobSidecars, MissingBlobSidecarsTracker,
+    },
     error::PoolError,
     metrics::MaintainPoolMetrics,
     traits::{CanonicalStateUpdate, ChangedAccount, TransactionPool, TransactionPoolExt},
-    BlockInfo,
+    BlockInfo, TransactionOrigin,
 };
 use futures_util::{
     future::{BoxFuture, Fuse, FusedFuture},
@@ -13,19 +15,20 @@ use futures_util::{
 };
 use reth_primitives::{
     fs::FsPathError, Address, BlockHash, BlockNumber, BlockNumberOrTag,
-    FromRecoveredPooledTransaction, FromRecoveredTransaction, IntoRecoveredTransaction,
-    PooledTransactionsElementEcRecovered, TransactionSigned,
+    FromRecoveredPooledTransaction, FromRecoveredTransaction, PooledTransactionsElement,
+    PooledTransactionsElementEcRecovered, SealedBlock,
 };
 use reth_provider::{
-    BlockReaderIdExt, BundleStateWithReceipts, CanonStateNotification, ChainSpecProvider,
-    ProviderError, StateProviderFactory,
+    chain::ChainBlocks, BlockReaderIdExt, BundleStateWithReceipts, CanonStateNotification,
+    ChainSpecProvider, ProviderError, StateProviderFactory,
 };
 use reth_tasks::TaskSpawner;
 use std::{
     borrow::Borrow,
-    collections::HashSet,
+    collections::{HashSet, VecDeque},
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
+    time::Duration,
 };
 use tokio::sync::oneshot;
 use tracing::{debug, error, info, trace, warn};
@@ -42,25 +45,207 @@ pub struct MaintainPoolConfig {
     ///
     /// Default: 100
     pub max_reload_accounts: usize,
+    /// Adjusts the floor of the effective tip of new transactions based on the fullness of recent
+    /// blocks, if set.
+    ///
+    /// Default: disabled
+    pub priority_fee_floor: Option<PriorityFeeFloorConfig>,
 }
 
 impl Default for MaintainPoolConfig {
     fn default() -> Self {
-        Self { max_update_depth: 64, max_reload_accounts: 100 }
+        Self { max_update_depth: 64, max_reload_accounts: 100, priority_fee_floor: None }
     }
 }
 
+/// Settings of the controller that adjusts the floor of the effective tip of new transactions
+/// based on the fullness of recent blocks, see
+/// [TransactionPoolExt::set_dynamic_priority_fee_floor].
+///
+/// While the recent blocks are full, the floor moves towards the lowest tip that was still included
+/// in them, and while they're mostly empty, it moves back towards `min`. In between the two
+/// thresholds the floor is kept, so it doesn't oscillate around a single target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriorityFeeFloorConfig {
+    /// The lowest floor in wei.
+    pub min: u128,
+    /// The highest floor in wei.
+    pub max: u128,
+    /// The number of recent blocks whose gas usage and tips are considered.
+    ///
+    /// Default: 10
+    pub window: usize,
+    /// The gas usage of the recent blocks, in percent of their gas limit, at or above which the
+    /// floor is raised.
+    ///
+    /// Default: 90
+    pub raise_threshold: u64,
+    /// The gas usage of the recent blocks, in percent of their gas limit, at or below which the
+    /// floor is lowered.
+    ///
+    /// Default: 50
+    pub lower_threshold: u64,
+    /// The share of the distance to its target, in percent, that the floor moves per block.
+    ///
+    /// Default: 25
+    pub adjustment: u128,
+}
+
+impl PriorityFeeFloorConfig {
+    /// Creates the settings of a floor between `min` and `max` with the default thresholds.
+    pub const fn new(min: u128, max: u128) -> Self {
+        Self { min, max, window: 10, raise_threshold: 90, lower_threshold: 50, adjustment: 25 }
+    }
+}
+
+/// Adjusts the floor of the effective tip of new transactions based on the gas usage and the
+/// lowest included tips of recent canonical blocks, see [PriorityFeeFloorConfig].
+#[derive(Debug)]
+pub struct PriorityFeeFloorController {
+    config: PriorityFeeFloorConfig,
+    /// The gas usage and the lowest included tip of the recent blocks, oldest first.
+    blocks: VecDeque<BlockFees>,
+    /// The current floor.
+    floor: u128,
+}
+
+/// The gas usage and the lowest included tip of a block.
+#[derive(Debug, Clone, Copy)]
+struct BlockFees {
+    gas_used: u64,
+    gas_limit: u64,
+    lowest_tip: Option<u128>,
+}
+
+impl PriorityFeeFloorController {
+    /// Creates a new controller whose floor starts at the configured minimum.
+    pub fn new(mut config: PriorityFeeFloorConfig) -> Self {
+        config.max = config.max.max(config.min);
+        config.window = config.window.max(1);
+        Self { blocks: VecDeque::with_capacity(config.window), floor: config.min, config }
+    }
+
+    /// Returns the current floor.
+    pub const fn floor(&self) -> u128 {
+        self.floor
+    }
+
+    /// Observes a new canonical block and returns the new floor if it changed.
+    pub fn on_block(&mut self, block: &SealedBlock) -> Option<u128> {
+        // transactions without a tip at the base fee, like deposit transactions, are ignored
+        let lowest_tip = block
+            .body
+            .iter()
+            .filter_map(|tx| tx.effective_tip_per_gas(block.base_fee_per_gas))
+            .min();
+        self.on_block_fees(BlockFees {
+            gas_used: block.gas_used,
+            gas_limit: block.gas_limit,
+            lowest_tip,
+        })
+    }
+
+    fn on_block_fees(&mut self, fees: BlockFees) -> Option<u128> {
+        if self.blocks.len() == self.config.window {
+            self.blocks.pop_front();
+        }
+        self.blocks.push_back(fees);
+
+        let PriorityFeeFloorConfig {
+            min, max, raise_threshold, lower_threshold, adjustment, ..
+        } = self.config;
+        let gas_used = self.blocks.iter().map(|block| block.gas_used as u128).sum::<u128>();
+        let gas_limit = self.blocks.iter().map(|block| block.gas_limit as u128).sum::<u128>();
+        if gas_limit == 0 {
+            return None
+        }
+        let fullness = gas_used * 100 / gas_limit;
+
+        let floor = if fullness >= raise_threshold as u128 {
+            // the lowest tips that were still included are the price of inclusion
+            let target = self.median_lowest_tip()?.clamp(min, max);
+            if target <= self.floor {
+                return None
+            }
+            self.floor + ((target - self.floor) * adjustment / 100).max(1)
+        } else if fullness <= lower_threshold as u128 {
+            if self.floor <= min {
+                return None
+            }
+            self.floor - ((self.floor - min) * adjustment / 100).max(1)
+        } else {
+            return None
+        };
+
+        self.floor = floor;
+        Some(floor)
+    }
+
+    /// Returns the median of the lowest included tips of the recent blocks.
+    fn median_lowest_tip(&self) -> Option<u128> {
+        let mut tips = self.blocks.iter().filter_map(|block| block.lowest_tip).collect::<Vec<_>>();
+        tips.sort_unstable();
+        tips.get(tips.len() / 2).copied()
+    }
+}
+
+/// Default interval at which the transactions backup file is written: 1 hour
+pub const DEFAULT_TXS_BACKUP_FLUSH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Default maximum size of the transactions backup file: 64 MB
+pub const DEFAULT_MAX_TXS_BACKUP_SIZE: usize = 64 * 1024 * 1024;
+
+/// The length of the header of an entry of the transactions backup file: the origin byte and the
+/// big-endian `u32` length of the transaction.
+const TXS_BACKUP_ENTRY_HEADER_LEN: usize = 1 + 4;
+
 /// Settings for local transaction backup task
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct LocalTransactionBackupConfig {
     /// Path to transactions backup file
     pub transactions_path: Option<PathBuf>,
+    /// Interval at which the transactions are written to the backup file, in addition to the
+    /// graceful shutdown.
+    ///
+    /// If `None`, the transactions are only written on graceful shutdown.
+    pub flush_interval: Option<Duration>,
+    /// Maximum size of the transactions backup file in bytes.
+    ///
+    /// Transactions that don't fit are not written, prioritizing local transactions over pending
+    /// transactions received from the network.
+    pub max_size: usize,
 }
 
 impl LocalTransactionBackupConfig {
     /// Receive path to transactions backup and return initialized config
     pub const fn with_local_txs_backup(transactions_path: PathBuf) -> Self {
-        Self { transactions_path: Some(transactions_path) }
+        Self {
+            transactions_path: Some(transactions_path),
+            flush_interval: Some(DEFAULT_TXS_BACKUP_FLUSH_INTERVAL),
+            max_size: DEFAULT_MAX_TXS_BACKUP_SIZE,
+        }
+    }
+
+    /// Sets the interval at which the transactions are written to the backup file.
+    pub fn with_flush_interval(mut self, flush_interval: Option<Duration>) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Sets the maximum size of the transactions backup file in bytes.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+}
+
+impl Default for LocalTransactionBackupConfig {
+    fn default() -> Self {
+        Self {
+            transactions_path: None,
+            flush_interval: Some(DEFAULT_TXS_BACKUP_FLUSH_INTERVAL),
+            max_size: DEFAULT_MAX_TXS_BACKUP_SIZE,
+        }
     }
 }
 
@@ -100,7 +285,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
     Tasks: TaskSpawner + 'static,
 {
     let metrics = MaintainPoolMetrics::default();
-    let MaintainPoolConfig { max_update_depth, max_reload_accounts, .. } = config;
+    let MaintainPoolConfig { max_update_depth, max_reload_accounts, priority_fee_floor } = config;
     // ensure the pool points to latest state
     if let Ok(Some(latest)) = client.header_by_number_or_tag(BlockNumberOrTag::Latest) {
         let latest = latest.seal_slow();
@@ -116,9 +301,18 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
         pool.set_block_info(info);
     }
 
+    // adjusts the floor of the effective tip of new transactions to the recent blocks
+    let mut priority_fee_floor = priority_fee_floor.map(PriorityFeeFloorController::new);
+    if let Some(controller) = &priority_fee_floor {
+        pool.set_dynamic_priority_fee_floor(Some(controller.floor()));
+    }
+
     // keeps track of mined blob transaction so we can clean finalized transactions
     let mut blob_store_tracker = BlobStoreCanonTracker::default();
 
+    // keeps track of reorged blob transactions whose sidecars need to be fetched from peers
+    let mut missing_blob_sidecars = MissingBlobSidecarsTracker::default();
+
     // keeps track of the latest finalized block
     let mut last_finalized_block =
         FinalizedBlockTracker::new(client.finalized_block_number().ok().flatten());
@@ -308,6 +502,9 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 // all transactions mined in the new chain
                 let new_mined_transactions: HashSet<_> = new_blocks.transaction_hashes().collect();
 
+                // reorged blob transactions whose sidecars are no longer in the blob store
+                let mut missing_sidecars = Vec::new();
+
                 // update the pool then re-inject the pruned transactions
                 // find all transactions that were mined in the old chain but not in the new chain
                 let pruned_old_transactions = old_blocks
@@ -320,7 +517,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                             // been validated previously, we still need the blob in order to
                             // accurately set the transaction's
                             // encoded-length which is propagated over the network.
-                            pool.get_blob(tx.hash)
+                            let tx_hash = tx.hash;
+                            let pooled = pool.get_blob(tx_hash)
                                 .ok()
                                 .flatten()
                                 .and_then(|sidecar| {
@@ -331,7 +529,12 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                                 })
                                 .map(
                                     <P as TransactionPool>::Transaction::from_recovered_pooled_transaction,
-                                )
+                                );
+                            if pooled.is_none() {
+                                // the sidecar needs to be fetched from peers
+                                missing_sidecars.push(tx_hash);
+                            }
+                            pooled
                         } else {
                             Some(<P as TransactionPool>::Transaction::from_recovered_transaction(
                                 tx,
@@ -360,6 +563,15 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 metrics.inc_reinserted_transactions(pruned_old_transactions.len());
                 let _ = pool.add_external_transactions(pruned_old_transactions).await;
 
+                if let Some(controller) = &mut priority_fee_floor {
+                    update_priority_fee_floor(&pool, controller, &new_blocks);
+                }
+
+                // blob transactions without sidecars can only be re-injected once the sidecars
+                // were fetched from peers
+                missing_blob_sidecars.remove_transactions(&new_mined_transactions);
+                missing_blob_sidecars.add_transactions(missing_sidecars);
+
                 // keep track of new mined blob transactions
                 blob_store_tracker.add_new_chain_blocks(&new_blocks);
             }
@@ -410,7 +622,8 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     changed_accounts.push(acc);
                 }
 
-                let mined_transactions = blocks.transaction_hashes().collect();
+                let mined_transactions: Vec<_> = blocks.transaction_hashes().collect();
+                missing_blob_sidecars.remove_transactions(&mined_transactions);
 
                 // check if the range of the commit is canonical with the pool's block
                 if first_block.parent_hash != pool_info.last_seen_block_hash {
@@ -430,10 +643,42 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                 };
                 pool.on_canonical_state_change(update);
 
+                if let Some(controller) = &mut priority_fee_floor {
+                    update_priority_fee_floor(&pool, controller, &blocks);
+                }
+
                 // keep track of mined blob transactions
                 blob_store_tracker.add_new_chain_blocks(&blocks);
             }
         }
+
+        // request the missing sidecars of reorged blob transactions again until the transactions
+        // are back in the pool, or the attempts are exhausted
+        if !missing_blob_sidecars.is_empty() {
+            let MissingBlobSidecars { to_request, recovered, exhausted } =
+                missing_blob_sidecars.on_new_block(|tx_hash| pool.contains(tx_hash));
+            if !exhausted.is_empty() {
+                debug!(target: "txpool", ?exhausted, "dropping blob txs with missing sidecars");
+            }
+            metrics.inc_blob_sidecar_refetches(to_request.len(), recovered.len(), exhausted.len());
+            metrics.set_missing_blob_sidecars_len(missing_blob_sidecars.len());
+            if !to_request.is_empty() {
+                pool.request_missing_blob_sidecars(to_request);
+            }
+        }
+    }
+}
+
+/// Feeds the new canonical blocks to the controller and passes the adjusted floor to the pool.
+fn update_priority_fee_floor<P: TransactionPoolExt>(
+    pool: &P,
+    controller: &mut PriorityFeeFloorController,
+    blocks: &ChainBlocks<'_>,
+) {
+    let floor = blocks.iter().filter_map(|(_, block)| controller.on_block(&block.block)).last();
+    if let Some(floor) = floor {
+        debug!(target: "txpool", floor, "adjusted the priority fee floor");
+        pool.set_dynamic_priority_fee_floor(Some(floor));
     }
 }
 
@@ -559,9 +804,116 @@ fn changed_accounts_iter(
         .map(|(address, acc)| ChangedAccount { address, nonce: acc.nonce, balance: acc.balance })
 }
 
-/// Loads transactions from a file, decodes them from the RLP format, and inserts them
-/// into the transaction pool on node boot up.
-/// The file is removed after the transactions have been successfully processed.
+/// Encodes the transactions of the pool that should survive a restart into the format of the
+/// transactions backup file.
+///
+/// Every entry consists of the [TransactionOrigin] as a single byte, the length of the transaction
+/// as a big-endian `u32` and the EIP-2718 encoded [PooledTransactionsElement], which includes the
+/// sidecar of blob transactions.
+///
+/// Local and private transactions are written first, followed by the pending transactions received
+/// from the network, until the file reaches the maximum size.
+///
+/// Returns the encoded transactions, the number of written transactions and the number of
+/// transactions that didn't fit.
+fn encode_transactions_backup<P>(pool: &P, max_size: usize) -> (Vec<u8>, usize, usize)
+where
+    P: TransactionPool,
+{
+    let transactions = pool
+        .get_local_transactions()
+        .into_iter()
+        .chain(pool.get_private_transactions())
+        .chain(pool.pending_transactions().into_iter().filter(|tx| tx.origin.is_external()))
+        .collect::<Vec<_>>();
+
+    let mut buf = Vec::new();
+    let mut num_txs = 0;
+    for tx in &transactions {
+        let Some(pooled) = pool.get_pooled_transaction_element(*tx.hash()) else { continue };
+        let encoded = pooled.envelope_encoded();
+        if buf.len() + TXS_BACKUP_ENTRY_HEADER_LEN + encoded.len() > max_size {
+            break
+        }
+
+        buf.push(origin_to_byte(tx.origin));
+        buf.extend_from_slice(&(encoded.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&encoded);
+        num_txs += 1;
+    }
+
+    (buf, num_txs, transactions.len() - num_txs)
+}
+
+/// Decodes the entries of a transactions backup file, see [encode_transactions_backup].
+///
+/// Entries that can't be decoded are logged and skipped, a truncated entry ends the file.
+fn decode_transactions_backup(
+    mut data: &[u8],
+) -> Vec<(TransactionOrigin, PooledTransactionsElementEcRecovered)> {
+    let mut transactions = Vec::new();
+    while !data.is_empty() {
+        if data.len() < TXS_BACKUP_ENTRY_HEADER_LEN {
+            warn!(target: "txpool", remaining = data.len(), "Transactions backup is truncated, skipping remaining bytes");
+            break
+        }
+        let origin = origin_from_byte(data[0]);
+        let len = u32::from_be_bytes(data[1..TXS_BACKUP_ENTRY_HEADER_LEN].try_into().unwrap());
+        data = &data[TXS_BACKUP_ENTRY_HEADER_LEN..];
+
+        let len = len as usize;
+        if data.len() < len {
+            warn!(target: "txpool", remaining = data.len(), len, "Transactions backup is truncated, skipping remaining bytes");
+            break
+        }
+        let (mut entry, rest) = data.split_at(len);
+        data = rest;
+
+        let Some(origin) = origin else {
+            warn!(target: "txpool", "Skipping transaction with unknown origin in transactions backup");
+            continue
+        };
+        let tx = match PooledTransactionsElement::decode_enveloped(&mut entry) {
+            Ok(tx) => tx,
+            Err(err) => {
+                warn!(target: "txpool", %err, "Skipping transaction that failed to decode in transactions backup");
+                continue
+            }
+        };
+        match tx.try_into_ecrecovered() {
+            Ok(tx) => transactions.push((origin, tx)),
+            Err(tx) => {
+                warn!(target: "txpool", hash = ?tx.hash(), "Skipping transaction with invalid signature in transactions backup");
+            }
+        }
+    }
+    transactions
+}
+
+/// Returns the byte the [TransactionOrigin] is encoded as in the transactions backup file.
+const fn origin_to_byte(origin: TransactionOrigin) -> u8 {
+    match origin {
+        TransactionOrigin::Local => 0,
+        TransactionOrigin::External => 1,
+        TransactionOrigin::Private => 2,
+    }
+}
+
+/// Returns the [TransactionOrigin] encoded as the given byte in the transactions backup file.
+const fn origin_from_byte(byte: u8) -> Option<TransactionOrigin> {
+    match byte {
+        0 => Some(TransactionOrigin::Local),
+        1 => Some(TransactionOrigin::External),
+        2 => Some(TransactionOrigin::Private),
+        _ => None,
+    }
+}
+
+/// Loads transactions from a file, decodes them and inserts them into the transaction pool on node
+/// boot up.
+///
+/// The transactions go through the regular validation of the pool, so transactions that became
+/// invalid while the node was stopped are dropped.
 async fn load_and_reinsert_transactions<P>(
     pool: P,
     file_path: &Path,
@@ -580,46 +932,72 @@ where
         return Ok(())
     }
 
-    let txs_signed: Vec<TransactionSigned> = alloy_rlp::Decodable::decode(&mut data.as_slice())?;
+    let mut transactions_by_origin: [(TransactionOrigin, Vec<P::Transaction>); 3] = [
+        (TransactionOrigin::Local, Vec::new()),
+        (TransactionOrigin::Private, Vec::new()),
+        (TransactionOrigin::External, Vec::new()),
+    ];
+    for (origin, tx) in decode_transactions_backup(&data) {
+        if let Some((_, txs)) = transactions_by_origin.iter_mut().find(|(o, _)| *o == origin) {
+            txs.push(<P::Transaction>::from_recovered_pooled_transaction(tx));
+        }
+    }
 
-    let pool_transactions = txs_signed
-        .into_iter()
-        .filter_map(|tx| tx.try_ecrecovered().map(<P::Transaction>::from_recovered_transaction))
-        .collect::<Vec<_>>();
-    let outcome = pool.add_transactions(crate::TransactionOrigin::Local, pool_transactions).await;
+    let mut num_txs = 0;
+    let mut num_discarded = 0;
+    for (origin, transactions) in transactions_by_origin {
+        if transactions.is_empty() {
+            continue
+        }
 
-    info!(target: "txpool", txs_file =?file_path, num_txs=%outcome.len(), "Successfully reinserted local transactions from file");
-    reth_primitives::fs::remove_file(file_path)?;
+        let outcome = pool.add_transactions(origin, transactions).await;
+        let num_added = outcome.iter().filter(|res| res.is_ok()).count();
+        num_txs += num_added;
+        num_discarded += outcome.len() - num_added;
+    }
+
+    info!(target: "txpool", txs_file =?file_path, num_txs, num_discarded, "Successfully reinserted transactions from file");
     Ok(())
 }
 
-fn save_local_txs_backup<P>(pool: P, file_path: &Path)
+/// Writes the transactions of the pool to the backup file, replacing the previous backup.
+///
+/// The transactions are written to a temporary file first, so the previous backup stays intact
+/// if the node crashes while writing.
+fn save_local_txs_backup<P>(pool: &P, file_path: &Path, max_size: usize)
 where
     P: TransactionPool,
 {
-    let local_transactions = pool.get_local_transactions();
-    if local_transactions.is_empty() {
-        trace!(target: "txpool", "no local transactions to save");
+    let (buf, num_txs, num_skipped) = encode_transactions_backup(pool, max_size);
+    if num_skipped > 0 {
+        warn!(target: "txpool", num_skipped, max_size, "Transactions backup exceeds the maximum size, skipping transactions");
+    }
+    if num_txs == 0 {
+        trace!(target: "txpool", "no transactions to save");
+        // Remove the previous backup, since it's stale
+        if file_path.exists() {
+            if let Err(err) = reth_primitives::fs::remove_file(file_path) {
+                warn!(target: "txpool", %err, txs_file=?file_path, "Failed to remove transactions file");
+            }
+        }
         return
     }
 
-    let local_transactions = local_transactions
-        .into_iter()
-        .map(|tx| tx.to_recovered_transaction().into_signed())
-        .collect::<Vec<_>>();
-
-    let num_txs = local_transactions.len();
-    let mut buf = Vec::new();
-    alloy_rlp::encode_list(&local_transactions, &mut buf);
-    info!(target: "txpool", txs_file =?file_path, num_txs=%num_txs, "Saving current local transactions");
-    let parent_dir = file_path.parent().map(std::fs::create_dir_all).transpose();
+    info!(target: "txpool", txs_file =?file_path, num_txs=%num_txs, "Saving current transactions");
+    let tmp_path = file_path.with_extension("tmp");
+    let res = file_path
+        .parent()
+        .map(reth_primitives::fs::create_dir_all)
+        .transpose()
+        .and_then(|_| reth_primitives::fs::write(&tmp_path, buf))
+        .and_then(|_| reth_primitives::fs::rename(&tmp_path, file_path));
 
-    match parent_dir.map(|_| reth_primitives::fs::write(file_path, buf)) {
+    match res {
         Ok(_) => {
-            info!(target: "txpool", txs_file=?file_path, "Wrote local transactions to file");
+            info!(target: "txpool", txs_file=?file_path, "Wrote transactions to file");
         }
         Err(err) => {
-            warn!(target: "txpool", %err, txs_file=?file_path, "Failed to write local transactions to file");
+            warn!(target: "txpool", %err, txs_file=?file_path, "Failed to write transactions to file");
         }
     }
 }
@@ -638,8 +1016,9 @@ pub enum TransactionsBackupError {
     Pool(#[from] PoolError),
 }
 
-/// Task which manages saving local transactions to the persistent file in case of shutdown.
-/// Reloads the transactions from the file on the boot up and inserts them into the pool.
+/// Task which manages saving local and pending transactions to the persistent file periodically
+/// and in case of shutdown. Reloads the transactions from the file on the boot up and inserts them
+/// into the pool.
 pub async fn backup_local_transactions_task<P>(
     shutdown: reth_tasks::shutdown::GracefulShutdown,
     pool: P,
@@ -647,19 +1026,42 @@ pub async fn backup_local_transactions_task<P>(
 ) where
     P: TransactionPool + Clone,
 {
-    let Some(transactions_path) = config.transactions_path else {
+    let LocalTransactionBackupConfig { transactions_path, flush_interval, max_size } = config;
+    let Some(transactions_path) = transactions_path else {
         // nothing to do
         return
     };
 
     if let Err(err) = load_and_reinsert_transactions(pool.clone(), &transactions_path).await {
-        error!(target: "txpool", "{}", err)
+        // the backup is kept as is, saving the pool would replace it with the transactions that
+        // are in the pool without the ones that couldn't be reloaded
+        error!(target: "txpool", %err, "Failed to reload the transactions backup, not saving it");
+        return
     }
-
-    let graceful_guard = shutdown.await;
+    // replace the loaded file with the transactions that are still valid
+    save_local_txs_backup(&pool, &transactions_path, max_size);
+
+    let graceful_guard = match flush_interval {
+        Some(flush_interval) => {
+            let mut interval = tokio::time::interval_at(
+                tokio::time::Instant::now() + flush_interval,
+                flush_interval,
+            );
+            let mut shutdown = std::pin::pin!(shutdown);
+            loop {
+                tokio::select! {
+                    guard = &mut shutdown => break guard,
+                    _ = interval.tick() => {
+                        save_local_txs_backup(&pool, &transactions_path, max_size);
+                    }
+                }
+            }
+        }
+        None => shutdown.await,
+    };
 
     // write transactions to disk
-    save_local_txs_backup(pool, &transactions_path);
+    save_local_txs_backup(&pool, &transactions_path, max_size);
 
     drop(graceful_guard)
 }
@@ -683,7 +1085,35 @@ mod tests {
         assert!(changed_acc.eq(&ChangedAccountEntry(copy)));
     }
 
-    const EXTENSION: &str = "rlp";
+    #[test]
+    fn priority_fee_floor_controller() {
+        let block = |gas_used, lowest_tip| BlockFees { gas_used, gas_limit: 100, lowest_tip };
+        let mut controller = PriorityFeeFloorController::new(PriorityFeeFloorConfig {
+            window: 2,
+            ..PriorityFeeFloorConfig::new(10, 1_000)
+        });
+        assert_eq!(controller.floor(), 10);
+
+        // full blocks move the floor towards the lowest included tip, capped at the maximum
+        assert_eq!(controller.on_block_fees(block(100, Some(410))), Some(110));
+        assert_eq!(controller.on_block_fees(block(95, Some(5_000))), Some(332));
+        assert_eq!(controller.on_block_fees(block(100, Some(5_000))), Some(499));
+
+        // the floor is kept between the thresholds
+        assert_eq!(controller.on_block_fees(block(40, None)), None);
+        assert_eq!(controller.on_block_fees(block(70, None)), None);
+        assert_eq!(controller.floor(), 499);
+
+        // mostly empty blocks move the floor back towards the minimum
+        assert_eq!(controller.on_block_fees(block(0, None)), Some(377));
+        for _ in 0..100 {
+            controller.on_block_fees(block(0, None));
+        }
+        assert_eq!(controller.floor(), 10);
+        assert_eq!(controller.on_block_fees(block(0, None)), None);
+    }
+
+    const EXTENSION: &str = "bin";
     const FILENAME: &str = "test_transactions_backup";
 
     #[tokio::test(flavor = "multi_thread")]
@@ -729,10 +1159,37 @@ mod tests {
 
         let data = fs::read(transactions_path).unwrap();
 
-        let txs: Vec<TransactionSigned> =
-            alloy_rlp::Decodable::decode(&mut data.as_slice()).unwrap();
+        let txs = decode_transactions_backup(&data);
         assert_eq!(txs.len(), 1);
+        assert_eq!(txs[0].0, TransactionOrigin::Local);
+        assert_eq!(txs[0].1.hash(), tx_to_cmp.hash());
 
         temp_dir.close().unwrap();
     }
+
+    #[test]
+    fn decode_corrupt_txs_backup() {
+        let tx_bytes = hex!("02f87201830655c2808505ef61f08482565f94388c818ca8b9251b393131c08a736a67ccb192978801049e39c4b5b1f580c001a01764ace353514e8abdfb92446de356b260e3c1225b73fc4c8876a6258d12a129a04f02294aa61ca7676061cd99f29275491218b4754b46a0248e5e42bc5091f507");
+        let entry = |origin: u8, tx: &[u8]| {
+            let mut entry = vec![origin];
+            entry.extend_from_slice(&(tx.len() as u32).to_be_bytes());
+            entry.extend_from_slice(tx);
+            entry
+        };
+
+        let mut data = entry(origin_to_byte(TransactionOrigin::Local), &tx_bytes);
+        // undecodable transaction
+        data.extend(entry(origin_to_byte(TransactionOrigin::Local), &[0xff; 16]));
+        // unknown origin
+        data.extend(entry(0xff, &tx_bytes));
+        data.extend(entry(origin_to_byte(TransactionOrigin::External), &tx_bytes));
+        // truncated entry
+        data.extend(&entry(origin_to_byte(TransactionOrigin::Local), &tx_bytes)[..10]);
+
+        let txs = decode_transactions_backup(&data);
+        assert_eq!(
+            txs.into_iter().map(|(origin, _)| origin).collect::<Vec<_>>(),
+            vec![TransactionOrigin::Local, TransactionOrigin::External]
+        );
+    }
 }
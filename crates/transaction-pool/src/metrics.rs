@@ -41,6 +41,9 @@ pub struct TxPoolMetrics {
 
     /// How often the pool was updated after the canonical state changed
     pub(crate) performed_state_updates: Counter,
+
+    /// The minimum effective tip in wei that's enforced for new transactions
+    pub(crate) priority_fee_floor: Gauge,
 }
 
 /// Transaction pool blobstore metrics
@@ -70,6 +73,15 @@ pub struct MaintainPoolMetrics {
     pub(crate) reinserted_transactions: Counter,
     /// Number of transactions finalized blob transactions we were tracking.
     pub(crate) deleted_tracked_finalized_blobs: Counter,
+    /// Number of reorged blob transactions whose sidecars are missing from the blob store.
+    pub(crate) missing_blob_sidecars: Gauge,
+    /// Number of requests to fetch the sidecar of a reorged blob transaction from peers.
+    pub(crate) blob_sidecar_refetch_requests: Counter,
+    /// Number of reorged blob transactions that were re-fetched from peers.
+    pub(crate) blob_sidecar_refetch_successes: Counter,
+    /// Number of reorged blob transactions that were dropped, because their sidecars couldn't be
+    /// re-fetched from peers.
+    pub(crate) blob_sidecar_refetch_failures: Counter,
 }
 
 impl MaintainPoolMetrics {
@@ -88,6 +100,23 @@ impl MaintainPoolMetrics {
         self.deleted_tracked_finalized_blobs.increment(count as u64);
     }
 
+    #[inline]
+    pub(crate) fn set_missing_blob_sidecars_len(&self, count: usize) {
+        self.missing_blob_sidecars.set(count as f64);
+    }
+
+    #[inline]
+    pub(crate) fn inc_blob_sidecar_refetches(
+        &self,
+        requests: usize,
+        successes: usize,
+        failures: usize,
+    ) {
+        self.blob_sidecar_refetch_requests.increment(requests as u64);
+        self.blob_sidecar_refetch_successes.increment(successes as u64);
+        self.blob_sidecar_refetch_failures.increment(failures as u64);
+    }
+
     #[inline]
     pub(crate) fn inc_drift(&self) {
         self.drift_count.increment(1);
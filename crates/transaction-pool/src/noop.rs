@@ -12,9 +12,10 @@ use crate::{
     },
     validate::ValidTransaction,
     AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPooledTransaction,
-    NewTransactionEvent, PoolResult, PoolSize, PoolTransaction, PooledTransactionsElement,
-    PropagatedTransactions, TransactionEvents, TransactionOrigin, TransactionPool,
-    TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
+    NewTransactionEvent, PoolConfigUpdate, PoolResult, PoolSize, PoolTransaction,
+    PooledTransactionsElement, PriorityFeeFloor, PropagatedTransactions, TransactionConditional,
+    TransactionEvents, TransactionOrigin, TransactionPool, TransactionValidationOutcome,
+    TransactionValidator, TransactionsPage, TransactionsQuery, ValidPoolTransaction,
 };
 use reth_eth_wire::HandleMempoolData;
 use reth_primitives::{Address, BlobTransactionSidecar, TxHash, U256};
@@ -45,6 +46,14 @@ impl TransactionPool for NoopTransactionPool {
         }
     }
 
+    fn update_config(&self, _update: PoolConfigUpdate) {}
+
+    fn priority_fee_floor(&self) -> PriorityFeeFloor {
+        Default::default()
+    }
+
+    fn set_priority_fee_floor_override(&self, _floor: Option<u128>) {}
+
     async fn add_transaction_and_subscribe(
         &self,
         _origin: TransactionOrigin,
@@ -77,6 +86,16 @@ impl TransactionPool for NoopTransactionPool {
             .collect()
     }
 
+    async fn add_conditional_transaction(
+        &self,
+        _origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        _conditional: TransactionConditional,
+    ) -> PoolResult<TxHash> {
+        let hash = *transaction.hash();
+        Err(PoolError::other(hash, Box::new(NoopInsertError::new(transaction))))
+    }
+
     fn transaction_event_listener(&self, _tx_hash: TxHash) -> Option<TransactionEvents> {
         None
     }
@@ -100,6 +119,10 @@ impl TransactionPool for NoopTransactionPool {
         mpsc::channel(1).1
     }
 
+    fn missing_blob_sidecars_listener(&self) -> Receiver<TxHash> {
+        mpsc::channel(1).1
+    }
+
     fn new_transactions_listener_for(
         &self,
         _kind: TransactionListenerKind,
@@ -173,6 +196,10 @@ impl TransactionPool for NoopTransactionPool {
         AllPoolTransactions::default()
     }
 
+    fn transactions_page(&self, _query: TransactionsQuery) -> TransactionsPage<Self::Transaction> {
+        TransactionsPage::default()
+    }
+
     fn remove_transactions(
         &self,
         _hashes: Vec<TxHash>,
@@ -32,6 +32,8 @@ pub enum FullTransactionEvent<T: PoolTransaction> {
     Discarded(TxHash),
     /// Transaction became invalid indefinitely.
     Invalid(TxHash),
+    /// Transaction expired because its conditions can no longer be satisfied by any future block.
+    Expired(TxHash),
     /// Transaction was propagated to peers.
     Propagated(Arc<Vec<PropagateKind>>),
 }
@@ -49,6 +51,7 @@ impl<T: PoolTransaction> Clone for FullTransactionEvent<T> {
             }
             Self::Discarded(hash) => Self::Discarded(*hash),
             Self::Invalid(hash) => Self::Invalid(*hash),
+            Self::Expired(hash) => Self::Expired(*hash),
             Self::Propagated(propagated) => Self::Propagated(Arc::clone(propagated)),
         }
     }
@@ -72,6 +75,8 @@ pub enum TransactionEvent {
     Discarded,
     /// Transaction became invalid indefinitely.
     Invalid,
+    /// Transaction expired because its conditions can no longer be satisfied by any future block.
+    Expired,
     /// Transaction was propagated to peers.
     Propagated(Arc<Vec<PropagateKind>>),
 }
@@ -84,7 +89,8 @@ impl TransactionEvent {
             self,
             TransactionEvent::Replaced(_) |
                 TransactionEvent::Mined(_) |
-                TransactionEvent::Discarded
+                TransactionEvent::Discarded |
+                TransactionEvent::Expired
         )
     }
 }
@@ -10,7 +10,10 @@ use reth_primitives::{TxHash, B256};
 use std::{
     collections::{hash_map::Entry, HashMap},
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
 };
 use tokio::sync::mpsc::{
@@ -51,12 +54,20 @@ impl Stream for TransactionEvents {
 #[must_use = "streams do nothing unless polled"]
 pub struct AllTransactionsEvents<T: PoolTransaction> {
     pub(crate) events: Receiver<FullTransactionEvent<T>>,
+    /// The number of events that were dropped because the channel was full.
+    missed: Option<Arc<AtomicU64>>,
 }
 
 impl<T: PoolTransaction> AllTransactionsEvents<T> {
     /// Create a new instance of this stream.
     pub const fn new(events: Receiver<FullTransactionEvent<T>>) -> Self {
-        Self { events }
+        Self { events, missed: None }
+    }
+
+    /// Returns the number of events that were dropped since the last call, because this stream
+    /// wasn't polled fast enough and its channel was full.
+    pub fn take_missed(&self) -> u64 {
+        self.missed.as_ref().map_or(0, |missed| missed.swap(0, Ordering::Relaxed))
     }
 }
 
@@ -128,8 +139,9 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
     /// Create a new subscription for all transactions.
     pub(crate) fn subscribe_all(&mut self) -> AllTransactionsEvents<T> {
         let (tx, rx) = tokio::sync::mpsc::channel(TX_POOL_EVENT_CHANNEL_SIZE);
-        self.all_events_broadcaster.senders.push(tx);
-        AllTransactionsEvents::new(rx)
+        let missed = Arc::new(AtomicU64::new(0));
+        self.all_events_broadcaster.senders.push((tx, Arc::clone(&missed)));
+        AllTransactionsEvents { events: rx, missed: Some(missed) }
     }
 
     /// Notify listeners about a transaction that was added to the pending queue.
@@ -172,6 +184,11 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
         self.broadcast_event(tx, TransactionEvent::Discarded, FullTransactionEvent::Discarded(*tx));
     }
 
+    /// Notify listeners about a transaction that expired.
+    pub(crate) fn expired(&mut self, tx: &TxHash) {
+        self.broadcast_event(tx, TransactionEvent::Expired, FullTransactionEvent::Expired(*tx));
+    }
+
     /// Notify listeners that the transaction was mined
     pub(crate) fn mined(&mut self, tx: &TxHash, block_hash: B256) {
         self.broadcast_event(
@@ -187,8 +204,9 @@ impl<T: PoolTransaction> PoolEventBroadcast<T> {
 /// This mimics [tokio::sync::broadcast] but uses separate channels.
 #[derive(Debug)]
 struct AllPoolEventsBroadcaster<T: PoolTransaction> {
-    /// Corresponding sender half(s) for event listener channel
-    senders: Vec<Sender<FullTransactionEvent<T>>>,
+    /// Corresponding sender half(s) for event listener channel, with the number of events the
+    /// listener missed because its channel was full
+    senders: Vec<(Sender<FullTransactionEvent<T>>, Arc<AtomicU64>)>,
 }
 
 impl<T: PoolTransaction> Default for AllPoolEventsBroadcaster<T> {
@@ -200,8 +218,12 @@ impl<T: PoolTransaction> Default for AllPoolEventsBroadcaster<T> {
 impl<T: PoolTransaction> AllPoolEventsBroadcaster<T> {
     // Broadcast an event to all listeners. Dropped listeners are silently evicted.
     fn broadcast(&mut self, event: FullTransactionEvent<T>) {
-        self.senders.retain(|sender| match sender.try_send(event.clone()) {
-            Ok(_) | Err(TrySendError::Full(_)) => true,
+        self.senders.retain(|(sender, missed)| match sender.try_send(event.clone()) {
+            Ok(_) => true,
+            Err(TrySendError::Full(_)) => {
+                missed.fetch_add(1, Ordering::Relaxed);
+                true
+            }
             Err(TrySendError::Closed(_)) => false,
         })
     }
@@ -77,8 +77,9 @@ use crate::{
         AllPoolTransactions, BestTransactionsAttributes, BlockInfo, NewTransactionEvent, PoolSize,
         PoolTransaction, PropagatedTransactions, TransactionOrigin,
     },
-    validate::{TransactionValidationOutcome, ValidPoolTransaction},
-    CanonicalStateUpdate, ChangedAccount, PoolConfig, TransactionOrdering, TransactionValidator,
+    validate::{TransactionConditional, TransactionValidationOutcome, ValidPoolTransaction},
+    CanonicalStateUpdate, ChangedAccount, PoolConfig, PoolConfigUpdate, PriorityFeeFloor,
+    TransactionOrdering, TransactionValidator,
 };
 use best::BestTransactions;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard};
@@ -100,7 +101,10 @@ use crate::{
     blobstore::BlobStore,
     metrics::BlobStoreMetrics,
     pool::txpool::UpdateOutcome,
-    traits::{GetPooledTransactionLimit, NewBlobSidecar, TransactionListenerKind},
+    traits::{
+        GetPooledTransactionLimit, NewBlobSidecar, TransactionListenerKind, TransactionsPage,
+        TransactionsQuery,
+    },
     validate::ValidTransaction,
 };
 pub use best::BestTransactionFilter;
@@ -123,6 +127,7 @@ mod update;
 const PENDING_TX_LISTENER_BUFFER_SIZE: usize = 2048;
 const NEW_TX_LISTENER_BUFFER_SIZE: usize = 1024;
 const BLOB_SIDECAR_LISTENER_BUFFER_SIZE: usize = 512;
+const MISSING_BLOB_SIDECAR_LISTENER_BUFFER_SIZE: usize = 512;
 
 /// Transaction pool internals.
 pub struct PoolInner<V, T, S>
@@ -147,6 +152,8 @@ where
     transaction_listener: Mutex<Vec<TransactionListener<T::Transaction>>>,
     /// Listener for new blob transaction sidecars added to the pool.
     blob_transaction_sidecar_listener: Mutex<Vec<BlobTransactionSidecarListener>>,
+    /// Listeners for blob transactions whose sidecars are missing and should be fetched.
+    missing_blob_sidecar_listener: Mutex<Vec<mpsc::Sender<TxHash>>>,
     /// Metrics for the blob store
     blob_store_metrics: BlobStoreMetrics,
 }
@@ -169,6 +176,7 @@ where
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
             blob_transaction_sidecar_listener: Default::default(),
+            missing_blob_sidecar_listener: Default::default(),
             config,
             blob_store,
             blob_store_metrics: Default::default(),
@@ -194,6 +202,28 @@ where
         self.pool.write().set_block_info(info)
     }
 
+    /// Replaces the limits of the pool and the minimum priority fee of the validator.
+    pub(crate) fn update_config(&self, update: PoolConfigUpdate) {
+        let mut pool = self.pool.write();
+        pool.update_config(update);
+        self.validator.on_new_minimum_priority_fee(pool.priority_fee_floor().effective());
+    }
+
+    /// Returns the minimum effective tip of new transactions.
+    pub(crate) fn priority_fee_floor(&self) -> PriorityFeeFloor {
+        self.get_pool_data().priority_fee_floor()
+    }
+
+    /// Updates the minimum effective tip of new transactions and passes the floor that's enforced
+    /// afterwards to the validator.
+    pub(crate) fn update_priority_fee_floor(&self, f: impl FnOnce(&mut PriorityFeeFloor)) {
+        let mut pool = self.pool.write();
+        let floor = pool.update_priority_fee_floor(f);
+        // the validator is updated while holding the lock, so concurrent updates are applied in
+        // the same order
+        self.validator.on_new_minimum_priority_fee(floor);
+    }
+
     /// Returns the internal `SenderId` for this address
     pub(crate) fn get_sender_id(&self, addr: Address) -> SenderId {
         self.identifiers.write().sender_id_or_create(addr)
@@ -221,6 +251,8 @@ where
     }
 
     /// Get the config the pool was configured with.
+    ///
+    /// This doesn't include the changes of [PoolInner::update_config].
     pub const fn config(&self) -> &PoolConfig {
         &self.config
     }
@@ -258,6 +290,14 @@ where
         rx
     }
 
+    /// Adds a new listener that gets notified about eip4844 transactions whose sidecars are
+    /// missing and should be fetched from peers.
+    pub fn add_missing_blob_sidecar_listener(&self) -> mpsc::Receiver<TxHash> {
+        let (sender, rx) = mpsc::channel(MISSING_BLOB_SIDECAR_LISTENER_BUFFER_SIZE);
+        self.missing_blob_sidecar_listener.lock().push(sender);
+        rx
+    }
+
     /// If the pool contains the transaction, this adds a new listener that gets notified about
     /// transaction events.
     pub(crate) fn add_transaction_event_listener(
@@ -377,14 +417,18 @@ where
         let changed_senders = self.changed_senders(changed_accounts.into_iter());
 
         // update the pool
-        let outcome = self.pool.write().on_canonical_state_change(
-            block_info,
-            mined_transactions,
-            changed_senders,
-        );
+        let outcome = {
+            let mut pool = self.pool.write();
+            let mut outcome =
+                pool.on_canonical_state_change(block_info, mined_transactions, changed_senders);
+            // evict the transactions whose conditions can't be satisfied by any later block
+            outcome.expired =
+                pool.remove_exceeded_conditional_transactions(new_tip.number, new_tip.timestamp);
+            outcome
+        };
 
         // This will discard outdated transactions based on the account's nonce
-        self.delete_discarded_blobs(outcome.discarded.iter());
+        self.delete_discarded_blobs(outcome.discarded.iter().chain(&outcome.expired));
 
         // notify listeners about updates
         self.notify_on_new_state(outcome);
@@ -409,12 +453,13 @@ where
 
     /// Add a single validated transaction into the pool.
     ///
-    /// Note: this is only used internally by [`Self::add_transactions()`], all new transaction(s)
-    /// come in through that function, either as a batch or `std::iter::once`.
+    /// Note: this is only used internally by [`Self::add_transactions_with_conditionals()`], all
+    /// new transaction(s) come in through that function, either as a batch or `std::iter::once`.
     fn add_transaction(
         &self,
         origin: TransactionOrigin,
         tx: TransactionValidationOutcome<T::Transaction>,
+        conditional: Option<Box<TransactionConditional>>,
     ) -> PoolResult<TxHash> {
         match tx {
             TransactionValidationOutcome::Valid {
@@ -444,9 +489,26 @@ where
                     propagate,
                     timestamp: Instant::now(),
                     origin,
+                    conditional,
+                };
+
+                let (added, evicted) = {
+                    let mut pool = self.pool.write();
+                    // check the limit of the origin before inserting the transaction, but only
+                    // evict once the insert succeeded and increased the count of the origin
+                    let origin = tx.origin;
+                    let candidate = pool.origin_eviction_candidate(&tx)?;
+                    let added = pool.add_transaction(tx, balance, state_nonce)?;
+                    let evicted =
+                        candidate.and_then(|candidate| pool.evict_for_origin(origin, candidate));
+                    (added, evicted)
                 };
 
-                let added = self.pool.write().add_transaction(tx, balance, state_nonce)?;
+                if let Some(evicted) = evicted {
+                    self.event_listener.write().discarded(evicted.hash());
+                    self.delete_discarded_blobs(std::iter::once(&evicted));
+                }
+
                 let hash = *added.hash();
 
                 // transaction was successfully inserted into the pool
@@ -512,8 +574,28 @@ where
         origin: TransactionOrigin,
         transactions: impl IntoIterator<Item = TransactionValidationOutcome<T::Transaction>>,
     ) -> Vec<PoolResult<TxHash>> {
-        let mut added =
-            transactions.into_iter().map(|tx| self.add_transaction(origin, tx)).collect::<Vec<_>>();
+        self.add_transactions_with_conditionals(
+            origin,
+            transactions.into_iter().map(|tx| (tx, None)),
+        )
+    }
+
+    /// Adds all transactions in the iterator to the pool, together with the conditions a block
+    /// must satisfy to include them, returning a list of results.
+    pub fn add_transactions_with_conditionals(
+        &self,
+        origin: TransactionOrigin,
+        transactions: impl IntoIterator<
+            Item = (
+                TransactionValidationOutcome<T::Transaction>,
+                Option<Box<TransactionConditional>>,
+            ),
+        >,
+    ) -> Vec<PoolResult<TxHash>> {
+        let mut added = transactions
+            .into_iter()
+            .map(|(tx, conditional)| self.add_transaction(origin, tx, conditional))
+            .collect::<Vec<_>>();
 
         // If at least one transaction was added successfully, then we enforce the pool size limits.
         let discarded =
@@ -597,6 +679,28 @@ where
         })
     }
 
+    /// Notifies all missing blob sidecar listeners about the given transactions.
+    pub(crate) fn request_missing_blob_sidecars(&self, tx_hashes: Vec<TxHash>) {
+        let mut listeners = self.missing_blob_sidecar_listener.lock();
+        listeners.retain_mut(|sender| {
+            for tx_hash in &tx_hashes {
+                match sender.try_send(*tx_hash) {
+                    Ok(()) => {}
+                    Err(mpsc::error::TrySendError::Full(_)) => {
+                        debug!(
+                            target: "txpool",
+                            "[{:?}] failed to send missing blob sidecar; channel full",
+                            tx_hash,
+                        );
+                        return true
+                    }
+                    Err(mpsc::error::TrySendError::Closed(_)) => return false,
+                }
+            }
+            true
+        })
+    }
+
     /// Notifies transaction listeners about changes once a block was processed.
     fn notify_on_new_state(&self, outcome: OnNewCanonicalStateOutcome<T::Transaction>) {
         // notify about promoted pending transactions
@@ -614,7 +718,8 @@ where
             })
         }
 
-        let OnNewCanonicalStateOutcome { mined, promoted, discarded, block_hash } = outcome;
+        let OnNewCanonicalStateOutcome { mined, promoted, discarded, expired, block_hash } =
+            outcome;
 
         // broadcast specific transaction events
         let mut listener = self.event_listener.write();
@@ -622,6 +727,7 @@ where
         mined.iter().for_each(|tx| listener.mined(tx, block_hash));
         promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
         discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
+        expired.iter().for_each(|tx| listener.expired(tx.hash()));
     }
 
     /// Fire events for the newly added transaction if there are any.
@@ -679,6 +785,22 @@ where
         }
     }
 
+    /// Returns a page of the transactions matching the query.
+    pub(crate) fn transactions_page(
+        &self,
+        query: &TransactionsQuery,
+    ) -> TransactionsPage<T::Transaction> {
+        let sender = match query.sender {
+            Some(sender) => match self.identifiers.read().sender_id(&sender) {
+                Some(sender) => Some(sender),
+                // the pool never contained a transaction of the sender
+                None => return TransactionsPage::default(),
+            },
+            None => None,
+        };
+        self.get_pool_data().transactions_page(query, sender)
+    }
+
     /// Removes and returns all matching transactions from the pool.
     pub(crate) fn remove_transactions(
         &self,
@@ -1099,6 +1221,8 @@ pub(crate) struct OnNewCanonicalStateOutcome<T: PoolTransaction> {
     pub(crate) promoted: Vec<Arc<ValidPoolTransaction<T>>>,
     /// transaction that were discarded during the update
     pub(crate) discarded: Vec<Arc<ValidPoolTransaction<T>>>,
+    /// Conditional transactions that can't be included anymore and their descendants.
+    pub(crate) expired: Vec<Arc<ValidPoolTransaction<T>>>,
 }
 
 impl<T: PoolTransaction> OnNewCanonicalStateOutcome<T> {
@@ -1203,6 +1327,7 @@ mod tests {
                         },
                         propagate: true,
                     },
+                    None,
                 )
                 .unwrap();
 
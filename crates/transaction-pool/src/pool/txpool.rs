@@ -1,7 +1,10 @@
 //! The internal transaction pool implementation.
 
 use crate::{
-    config::{LocalTransactionConfig, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER},
+    config::{
+        LocalTransactionConfig, PoolConfigUpdate, PriorityFeeFloor,
+        TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+    },
     error::{Eip4844PoolTransactionError, InvalidPoolTransactionError, PoolError, PoolErrorKind},
     identifier::{SenderId, TransactionId},
     metrics::{AllTransactionsMetrics, TxPoolMetrics},
@@ -14,9 +17,12 @@ use crate::{
         update::{Destination, PoolUpdate},
         AddedPendingTransaction, AddedTransaction, OnNewCanonicalStateOutcome,
     },
-    traits::{BestTransactionsAttributes, BlockInfo, PoolSize},
+    traits::{
+        BestTransactionsAttributes, BlockInfo, PoolSize, TransactionsCursor, TransactionsPage,
+        TransactionsQuery,
+    },
     PoolConfig, PoolResult, PoolTransaction, PriceBumpConfig, TransactionOrdering,
-    ValidPoolTransaction, U256,
+    TransactionOrigin, ValidPoolTransaction, U256,
 };
 use fnv::FnvHashMap;
 use itertools::Itertools;
@@ -29,10 +35,11 @@ use reth_primitives::{
 use smallvec::SmallVec;
 use std::{
     cmp::Ordering,
-    collections::{btree_map::Entry, hash_map, BTreeMap, HashMap, HashSet},
+    collections::{btree_map::Entry, hash_map, BTreeMap, BTreeSet, HashMap, HashSet},
     fmt,
-    ops::Bound::{Excluded, Unbounded},
+    ops::Bound::{self, Excluded, Included, Unbounded},
     sync::Arc,
+    time::Instant,
 };
 use tracing::trace;
 
@@ -72,6 +79,13 @@ pub struct TxPool<T: TransactionOrdering> {
     blob_pool: BlobTransactions<T::Transaction>,
     /// All transactions in the pool.
     all_transactions: AllTransactions<T::Transaction>,
+    /// The minimum effective tip of new transactions.
+    priority_fee_floor: PriorityFeeFloor,
+    /// When the enforced priority fee floor was last raised.
+    ///
+    /// The transactions that were added before are checked against the floor once they're
+    /// promoted to the pending sub-pool, see [Self::process_updates].
+    priority_fee_floor_raised_at: Option<Instant>,
     /// Transaction pool metrics
     metrics: TxPoolMetrics,
 }
@@ -88,6 +102,11 @@ impl<T: TransactionOrdering> TxPool<T> {
             basefee_pool: Default::default(),
             blob_pool: Default::default(),
             all_transactions: AllTransactions::new(&config),
+            priority_fee_floor: PriorityFeeFloor {
+                minimum: config.minimum_priority_fee,
+                ..Default::default()
+            },
+            priority_fee_floor_raised_at: None,
             config,
             metrics: Default::default(),
         }
@@ -234,6 +253,65 @@ impl<T: TransactionOrdering> TxPool<T> {
         }
     }
 
+    /// Replaces the transaction limits of the pool.
+    ///
+    /// The limits are enforced when transactions are added, so transactions that are already in
+    /// the pool are kept even if they exceed the new limits.
+    pub fn update_config(&mut self, update: PoolConfigUpdate) {
+        let PoolConfigUpdate { max_txs_per_sender, origin_limits, minimum_priority_fee } = update;
+        self.config.max_txs_per_sender = max_txs_per_sender;
+        self.config.origin_limits = origin_limits;
+        self.config.minimum_priority_fee = minimum_priority_fee;
+        self.all_transactions.max_txs_per_sender = max_txs_per_sender;
+        self.update_priority_fee_floor(|floor| floor.minimum = minimum_priority_fee);
+    }
+
+    /// Returns the minimum effective tip of new transactions.
+    pub const fn priority_fee_floor(&self) -> PriorityFeeFloor {
+        self.priority_fee_floor
+    }
+
+    /// Updates the minimum effective tip of new transactions and returns the floor that's enforced
+    /// afterwards.
+    ///
+    /// The transactions that are already in the pool aren't rescanned if the floor is raised.
+    /// Instead, they're checked against the floor once they're promoted to the pending sub-pool.
+    pub fn update_priority_fee_floor(
+        &mut self,
+        f: impl FnOnce(&mut PriorityFeeFloor),
+    ) -> Option<u128> {
+        let previous = self.priority_fee_floor.effective();
+        f(&mut self.priority_fee_floor);
+        let floor = self.priority_fee_floor.effective();
+        if floor.unwrap_or_default() > previous.unwrap_or_default() {
+            self.priority_fee_floor_raised_at = Some(Instant::now());
+        }
+        self.metrics.priority_fee_floor.set(floor.unwrap_or_default() as f64);
+        floor
+    }
+
+    /// Returns true if the transaction was added before the enforced priority fee floor was last
+    /// raised, and its effective tip at the pending base fee is below the floor.
+    ///
+    /// Local transactions are exempt unless
+    /// [LocalTransactionConfig::enforce_minimum_priority_fee] is set, like on admission.
+    fn is_below_priority_fee_floor(&self, tx: &ValidPoolTransaction<T::Transaction>) -> bool {
+        let (Some(raised_at), Some(floor)) =
+            (self.priority_fee_floor_raised_at, self.priority_fee_floor.effective())
+        else {
+            return false
+        };
+        let locals = &self.config.local_transactions_config;
+        if tx.timestamp >= raised_at ||
+            (!locals.enforce_minimum_priority_fee && locals.is_local(tx.origin, tx.sender()))
+        {
+            return false
+        }
+        tx.transaction
+            .effective_tip_per_gas(self.all_transactions.pending_fees.base_fee)
+            .map_or(true, |tip| tip < floor)
+    }
+
     /// Sets the current block info for the pool.
     ///
     /// This will also apply updates to the pool based on the new base fee
@@ -374,6 +452,45 @@ impl<T: TransactionOrdering> TxPool<T> {
         txs.into_iter().filter_map(|tx| self.get(&tx))
     }
 
+    /// Returns a page of the transactions matching the query, see
+    /// [TransactionPool::transactions_page](crate::TransactionPool::transactions_page).
+    ///
+    /// The given sender is the id of the query's sender, if any.
+    pub(crate) fn transactions_page(
+        &self,
+        query: &TransactionsQuery,
+        sender: Option<SenderId>,
+    ) -> TransactionsPage<T::Transaction> {
+        let mut start: Bound<TransactionId> =
+            query.cursor.map_or(Unbounded, |cursor| Excluded(cursor.0));
+        if let Some(sender) = sender {
+            // skip to the first transaction of the sender
+            let first = TransactionId::new(sender, 0);
+            if query.cursor.map_or(true, |cursor| cursor.0 < first) {
+                start = Included(first);
+            }
+        }
+
+        let pending_base_fee = self.all_transactions.pending_fees.base_fee;
+        let mut page = TransactionsPage::default();
+        let mut last_id = None;
+        for (id, tx) in self.all_transactions.txs.range((start, Unbounded)) {
+            if sender.is_some_and(|sender| id.sender != sender) {
+                break
+            }
+            if !query.matches(tx.subpool, &tx.transaction.transaction, pending_base_fee) {
+                continue
+            }
+            if page.transactions.len() == query.limit {
+                page.next_cursor = last_id.map(TransactionsCursor);
+                break
+            }
+            page.transactions.push((tx.subpool, Arc::clone(&tx.transaction)));
+            last_id = Some(*id);
+        }
+        page
+    }
+
     /// Returns all transactions sent from the given sender.
     pub(crate) fn get_transactions_by_sender(
         &self,
@@ -424,7 +541,47 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         self.metrics.performed_state_updates.increment(1);
 
-        OnNewCanonicalStateOutcome { block_hash, mined: mined_transactions, promoted, discarded }
+        OnNewCanonicalStateOutcome {
+            block_hash,
+            mined: mined_transactions,
+            promoted,
+            discarded,
+            expired: Vec::new(),
+        }
+    }
+
+    /// Removes all transactions whose conditions can't be satisfied by any block after the block
+    /// with the given number and timestamp, because their block number or timestamp range passed.
+    ///
+    /// This also removes the descendants of the removed transactions, which would otherwise have a
+    /// nonce gap.
+    pub(crate) fn remove_exceeded_conditional_transactions(
+        &mut self,
+        block_number: u64,
+        timestamp: u64,
+    ) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let exceeded =
+            self.all_transactions.exceeded_conditional_transactions(block_number, timestamp);
+
+        let mut removed = Vec::new();
+        for id in exceeded {
+            if let Some(tx) = self.remove_transaction(&id) {
+                removed.push(tx);
+                self.remove_descendants(&id, &mut removed);
+            }
+        }
+
+        if !removed.is_empty() {
+            trace!(
+                target: "txpool",
+                count = removed.len(),
+                block_number,
+                "Removed transactions with exceeded conditions"
+            );
+            self.update_size_metrics();
+        }
+
+        removed
     }
 
     /// Update sub-pools size metrics.
@@ -441,6 +598,62 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.metrics.total_transactions.set(stats.total as f64);
     }
 
+    /// Returns the transaction to evict for the given transaction if the pool already holds the
+    /// maximum number of transactions of its origin, see [PoolConfig::origin_limits].
+    ///
+    /// The candidate is the transaction of the same origin with the lowest priority fee, if that
+    /// fee is lower than the fee of the new transaction. Only the transaction with the highest
+    /// nonce of a sender can be evicted, so that the eviction never creates a nonce gap below the
+    /// retained transactions of the sender. Replacements of a transaction of the same origin
+    /// don't increase the number of transactions and don't require an eviction.
+    ///
+    /// This doesn't remove the candidate, see [Self::evict_for_origin].
+    ///
+    /// Returns an error if the limit is reached and there is no transaction to evict.
+    pub(crate) fn origin_eviction_candidate(
+        &self,
+        tx: &ValidPoolTransaction<T::Transaction>,
+    ) -> PoolResult<Option<TxHash>> {
+        let Some(limit) = self.config.origin_limits.limit(tx.origin) else { return Ok(None) };
+        if self.all_transactions.origin_tx_count(tx.origin) < limit ||
+            self.all_transactions
+                .txs
+                .get(tx.id())
+                .is_some_and(|existing| existing.transaction.origin == tx.origin)
+        {
+            return Ok(None)
+        }
+
+        self.all_transactions
+            .lowest_fee_evictable(tx.origin, tx.sender_id())
+            .filter(|evicted| evicted.priority_fee_or_price() < tx.priority_fee_or_price())
+            .map(|evicted| Some(*evicted.hash()))
+            .ok_or_else(|| {
+                PoolError::new(*tx.hash(), PoolErrorKind::OriginLimitExceeded(tx.origin))
+            })
+    }
+
+    /// Evicts the candidate returned by [Self::origin_eviction_candidate] if the pool holds more
+    /// than the maximum number of transactions of the given origin.
+    ///
+    /// This must be called after the new transaction was inserted, so that nothing is evicted if
+    /// the insert failed or didn't increase the number of transactions of the origin.
+    ///
+    /// Returns the evicted transaction.
+    pub(crate) fn evict_for_origin(
+        &mut self,
+        origin: TransactionOrigin,
+        candidate: TxHash,
+    ) -> Option<Arc<ValidPoolTransaction<T::Transaction>>> {
+        let limit = self.config.origin_limits.limit(origin)?;
+        if self.all_transactions.origin_tx_count(origin) <= limit {
+            return None
+        }
+
+        trace!(target: "txpool", evicted = ?candidate, ?origin, "Evicting transaction for limit");
+        self.remove_transactions(vec![candidate]).pop()
+    }
+
     /// Adds the transaction into the pool.
     ///
     /// This pool consists of four sub-pools: `Queued`, `Pending`, `BaseFee`, and `Blob`.
@@ -529,6 +742,12 @@ impl<T: TransactionOrdering> TxPool<T> {
                             PoolErrorKind::SpammerExceededCapacity(transaction.sender()),
                         ))
                     }
+                    InsertErr::ExceededSenderTransactionsLimit { transaction } => {
+                        Err(PoolError::new(
+                            *transaction.hash(),
+                            PoolErrorKind::SenderTransactionsLimitExceeded(transaction.sender()),
+                        ))
+                    }
                     InsertErr::TxGasLimitMoreThanAvailableBlockGas {
                         transaction,
                         block_gas_limit,
@@ -589,6 +808,34 @@ impl<T: TransactionOrdering> TxPool<T> {
                 }
             }
         }
+
+        // Promoted transactions that were added before the priority fee floor was raised are
+        // re-checked against the new floor. The updates are applied first, so the removal of their
+        // descendants, which can't be executed without them, sees the consistent state.
+        let below_floor = outcome
+            .promoted
+            .iter()
+            .filter(|tx| self.is_below_priority_fee_floor(tx))
+            .map(|tx| *tx.id())
+            .collect::<Vec<_>>();
+        if !below_floor.is_empty() {
+            let mut discarded = Vec::new();
+            for id in below_floor {
+                if let Some(tx) = self.remove_transaction(&id) {
+                    discarded.push(tx);
+                    self.remove_descendants(&id, &mut discarded);
+                }
+            }
+            trace!(
+                target: "txpool",
+                count = discarded.len(),
+                "discarded promoted transactions below the priority fee floor"
+            );
+            self.metrics.removed_transactions.increment(discarded.len() as u64);
+            let discarded_hashes = discarded.iter().map(|tx| *tx.hash()).collect::<HashSet<_>>();
+            outcome.promoted.retain(|tx| !discarded_hashes.contains(tx.hash()));
+            outcome.discarded.extend(discarded);
+        }
         outcome
     }
 
@@ -752,8 +999,9 @@ impl<T: TransactionOrdering> TxPool<T> {
 
     /// Ensures that the transactions in the sub-pools are within the given bounds.
     ///
-    /// If the current size exceeds the given bounds, the worst transactions are evicted from the
-    /// pool and returned.
+    /// If the current size exceeds the given bounds, the external transactions with the lowest
+    /// priority fee that can be removed without creating a nonce gap are evicted first. If the
+    /// sub-pool still exceeds its bounds, the worst transactions of the sub-pool are evicted.
     ///
     /// This returns all transactions that were removed from the entire pool.
     pub(crate) fn discard_worst(&mut self) -> Vec<Arc<ValidPoolTransaction<T::Transaction>>> {
@@ -761,10 +1009,23 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         // Helper macro that discards the worst transactions for the pools
         macro_rules! discard_worst {
-            ($this:ident, $removed:ident, [$($limit:ident => $pool:ident),* $(,)*]) => {
+            (
+                $this:ident,
+                $removed:ident,
+                [$($limit:ident => $pool:ident => $subpool:expr),* $(,)*]
+            ) => {
                 $ (
                 while $this.$pool.exceeds(&$this.config.$limit)
                     {
+                        // prefer the cheapest external transactions, which have no descendants
+                        if let Some(id) = $this.all_transactions.lowest_fee_evictable_in_subpool(
+                            TransactionOrigin::External,
+                            $subpool,
+                        ) {
+                            $removed.extend($this.remove_transaction(&id));
+                            continue
+                        }
+
                         trace!(
                             target: "txpool",
                             "discarding transactions from {}, limit: {:?}, curr size: {}, curr len: {}",
@@ -807,10 +1068,10 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         discard_worst!(
             self, removed, [
-                pending_limit => pending_pool,
-                basefee_limit => basefee_pool,
-                blob_limit    => blob_pool,
-                queued_limit  => queued_pool,
+                pending_limit => pending_pool => SubPool::Pending,
+                basefee_limit => basefee_pool => SubPool::BaseFee,
+                blob_limit    => blob_pool    => SubPool::Blob,
+                queued_limit  => queued_pool  => SubPool::Queued,
             ]
         );
 
@@ -898,12 +1159,25 @@ pub(crate) struct AllTransactions<T: PoolTransaction> {
     block_gas_limit: u64,
     /// Max number of executable transaction slots guaranteed per account
     max_account_slots: usize,
+    /// Max number of transactions of a single sender, regardless of the origin
+    max_txs_per_sender: Option<usize>,
     /// _All_ transactions identified by their hash.
     by_hash: HashMap<TxHash, Arc<ValidPoolTransaction<T>>>,
     /// _All_ transaction in the pool sorted by their sender and nonce pair.
     txs: BTreeMap<TransactionId, PoolInternalTransaction<T>>,
     /// Tracks the number of transactions by sender that are currently in the pool.
     tx_counter: FnvHashMap<SenderId, usize>,
+    /// Tracks the number of transactions by origin that are currently in the pool.
+    origin_counter: FnvHashMap<TransactionOrigin, usize>,
+    /// The origin, priority fee and id of the transaction with the highest nonce of each sender.
+    last_by_sender: FnvHashMap<SenderId, (TransactionOrigin, u128, TransactionId)>,
+    /// The transactions with the highest nonce of their sender by origin, ordered by their
+    /// priority fee.
+    ///
+    /// These can be evicted without creating a nonce gap, see [Self::lowest_fee_evictable].
+    evictable_by_origin: FnvHashMap<TransactionOrigin, BTreeSet<(u128, TransactionId)>>,
+    /// _All_ transactions with conditions, identified by their hash.
+    conditional_txs: HashSet<TxHash>,
     /// The current block number the pool keeps track of.
     last_seen_block_number: u64,
     /// The current block hash the pool keeps track of.
@@ -923,6 +1197,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
     fn new(config: &PoolConfig) -> Self {
         Self {
             max_account_slots: config.max_account_slots,
+            max_txs_per_sender: config.max_txs_per_sender,
             price_bumps: config.price_bumps,
             local_transactions_config: config.local_transactions_config.clone(),
             ..Default::default()
@@ -973,6 +1248,95 @@ impl<T: PoolTransaction> AllTransactions<T> {
         }
     }
 
+    /// Increments the transaction counter for the origin
+    fn origin_inc(&mut self, origin: TransactionOrigin) {
+        *self.origin_counter.entry(origin).or_default() += 1;
+    }
+
+    /// Decrements the transaction counter for the origin
+    fn origin_decr(&mut self, origin: TransactionOrigin) {
+        if let Some(count) = self.origin_counter.get_mut(&origin) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    /// Returns the ids of all transactions whose conditions can't be satisfied by any block after
+    /// the block with the given number and timestamp.
+    pub(crate) fn exceeded_conditional_transactions(
+        &self,
+        block_number: u64,
+        timestamp: u64,
+    ) -> Vec<TransactionId> {
+        self.conditional_txs
+            .iter()
+            .filter_map(|hash| self.by_hash.get(hash))
+            .filter(|tx| {
+                tx.conditional.as_ref().is_some_and(|conditional| {
+                    conditional.has_exceeded_block_attributes(block_number, timestamp)
+                })
+            })
+            .map(|tx| tx.transaction_id)
+            .collect()
+    }
+
+    /// Returns the number of transactions of the given origin in the pool.
+    pub(crate) fn origin_tx_count(&self, origin: TransactionOrigin) -> usize {
+        self.origin_counter.get(&origin).copied().unwrap_or_default()
+    }
+
+    /// Returns the transaction of the given origin with the lowest priority fee that can be
+    /// removed without creating a nonce gap, ignoring the transactions of the given sender.
+    ///
+    /// Only the transaction with the highest nonce of a sender qualifies, since removing any other
+    /// transaction would leave the following transactions of the sender with a nonce gap.
+    pub(crate) fn lowest_fee_evictable(
+        &self,
+        origin: TransactionOrigin,
+        sender: SenderId,
+    ) -> Option<&Arc<ValidPoolTransaction<T>>> {
+        // there's at most one entry per sender, so this skips at most one entry
+        let (_, id) =
+            self.evictable_by_origin.get(&origin)?.iter().find(|(_, id)| id.sender != sender)?;
+        self.txs.get(id).map(|tx| &tx.transaction)
+    }
+
+    /// Returns the id of the transaction of the given origin in the given subpool with the lowest
+    /// priority fee that can be removed without creating a nonce gap.
+    pub(crate) fn lowest_fee_evictable_in_subpool(
+        &self,
+        origin: TransactionOrigin,
+        subpool: SubPool,
+    ) -> Option<TransactionId> {
+        self.evictable_by_origin
+            .get(&origin)?
+            .iter()
+            .find(|(_, id)| self.txs.get(id).is_some_and(|tx| tx.subpool == subpool))
+            .map(|(_, id)| *id)
+    }
+
+    /// Updates the index of the evictable transactions after the transactions of the given sender
+    /// changed.
+    fn update_evictable(&mut self, sender: SenderId) {
+        if let Some((origin, fee, id)) = self.last_by_sender.remove(&sender) {
+            if let hash_map::Entry::Occupied(mut entry) = self.evictable_by_origin.entry(origin) {
+                entry.get_mut().remove(&(fee, id));
+                if entry.get().is_empty() {
+                    entry.remove();
+                }
+            }
+        }
+
+        let last = self
+            .txs
+            .range((sender.start_bound(), Included(TransactionId::new(sender, u64::MAX))))
+            .next_back();
+        if let Some((id, tx)) = last {
+            let (origin, fee) = (tx.transaction.origin, tx.transaction.priority_fee_or_price());
+            self.evictable_by_origin.entry(origin).or_default().insert((fee, *id));
+            self.last_by_sender.insert(sender, (origin, fee, *id));
+        }
+    }
+
     /// Updates the block specific info
     fn set_block_info(&mut self, block_info: BlockInfo) {
         let BlockInfo {
@@ -1241,8 +1605,11 @@ impl<T: PoolTransaction> AllTransactions<T> {
     ) -> Option<(Arc<ValidPoolTransaction<T>>, SubPool)> {
         let tx = self.by_hash.remove(tx_hash)?;
         let internal = self.txs.remove(&tx.transaction_id)?;
-        // decrement the counter for the sender.
+        // decrement the counters for the sender and the origin.
         self.tx_decr(tx.sender_id());
+        self.origin_decr(tx.origin);
+        self.update_evictable(tx.sender_id());
+        self.conditional_txs.remove(tx_hash);
         self.update_size_metrics();
         Some((tx, internal.subpool))
     }
@@ -1258,8 +1625,11 @@ impl<T: PoolTransaction> AllTransactions<T> {
     ) -> Option<(Arc<ValidPoolTransaction<T>>, SubPool)> {
         let internal = self.txs.remove(id)?;
 
-        // decrement the counter for the sender.
+        // decrement the counters for the sender and the origin.
         self.tx_decr(internal.transaction.sender_id());
+        self.origin_decr(internal.transaction.origin);
+        self.update_evictable(internal.transaction.sender_id());
+        self.conditional_txs.remove(internal.transaction.hash());
 
         let result =
             self.by_hash.remove(internal.transaction.hash()).map(|tx| (tx, internal.subpool));
@@ -1287,6 +1657,8 @@ impl<T: PoolTransaction> AllTransactions<T> {
     /// Additional checks for a new transaction.
     ///
     /// This will enforce all additional rules in the context of this pool, such as:
+    ///   - Sender limit: reject new transactions, including local ones, from a sender that already
+    ///     has the configured maximum of transactions in the pool.
     ///   - Spam protection: reject new non-local transaction from a sender that exhausted its slot
     ///     capacity.
     ///   - Gas limit: reject transactions if they exceed a block's maximum gas.
@@ -1296,6 +1668,18 @@ impl<T: PoolTransaction> AllTransactions<T> {
         &self,
         transaction: ValidPoolTransaction<T>,
     ) -> Result<ValidPoolTransaction<T>, InsertErr<T>> {
+        if let Some(max_txs_per_sender) = self.max_txs_per_sender {
+            let current_txs =
+                self.tx_counter.get(&transaction.sender_id()).copied().unwrap_or_default();
+            // replacements don't increase the number of transactions of the sender
+            if current_txs >= max_txs_per_sender &&
+                !self.txs.contains_key(&transaction.transaction_id)
+            {
+                return Err(InsertErr::ExceededSenderTransactionsLimit {
+                    transaction: Arc::new(transaction),
+                })
+            }
+        }
         if !self.local_transactions_config.is_local(transaction.origin, transaction.sender()) {
             let current_txs =
                 self.tx_counter.get(&transaction.sender_id()).copied().unwrap_or_default();
@@ -1550,6 +1934,7 @@ impl<T: PoolTransaction> AllTransactions<T> {
                 let new_transaction = pool_tx.transaction.clone();
                 let replaced = entry.insert(pool_tx);
                 self.by_hash.remove(replaced.transaction.hash());
+                self.conditional_txs.remove(replaced.transaction.hash());
                 self.by_hash.insert(new_hash, new_transaction);
                 // also remove the hash
                 replaced_tx = Some((replaced.transaction, replaced.subpool));
@@ -1636,10 +2021,23 @@ impl<T: PoolTransaction> AllTransactions<T> {
             }
         }
 
-        // If this wasn't a replacement transaction we need to update the counter.
-        if replaced_tx.is_none() {
-            self.tx_inc(inserted_tx_id.sender);
+        if transaction.conditional.is_some() {
+            self.conditional_txs.insert(*transaction.hash());
+        }
+
+        // If this wasn't a replacement transaction we need to update the counters.
+        match &replaced_tx {
+            None => {
+                self.tx_inc(inserted_tx_id.sender);
+                self.origin_inc(transaction.origin);
+            }
+            Some((replaced, _)) if replaced.origin != transaction.origin => {
+                self.origin_decr(replaced.origin);
+                self.origin_inc(transaction.origin);
+            }
+            Some(_) => {}
         }
+        self.update_evictable(inserted_tx_id.sender);
 
         self.update_size_metrics();
 
@@ -1660,6 +2058,11 @@ impl<T: PoolTransaction> AllTransactions<T> {
     #[cfg(any(test, feature = "test-utils"))]
     pub(crate) fn assert_invariants(&self) {
         assert_eq!(self.by_hash.len(), self.txs.len(), "by_hash.len() != txs.len()");
+        assert_eq!(
+            self.last_by_sender.len(),
+            self.evictable_by_origin.values().map(BTreeSet::len).sum::<usize>(),
+            "last_by_sender.len() != evictable_by_origin.len()"
+        );
     }
 }
 
@@ -1677,11 +2080,16 @@ impl<T: PoolTransaction> Default for AllTransactions<T> {
     fn default() -> Self {
         Self {
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
+            max_txs_per_sender: None,
             minimal_protocol_basefee: MIN_PROTOCOL_BASE_FEE,
             block_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
             by_hash: Default::default(),
             txs: Default::default(),
             tx_counter: Default::default(),
+            origin_counter: Default::default(),
+            last_by_sender: Default::default(),
+            evictable_by_origin: Default::default(),
+            conditional_txs: Default::default(),
             last_seen_block_number: Default::default(),
             last_seen_block_hash: Default::default(),
             pending_fees: Default::default(),
@@ -1732,6 +2140,8 @@ pub(crate) enum InsertErr<T: PoolTransaction> {
     ///
     /// The sender can be considered a spammer at this point.
     ExceededSenderTransactionsCapacity { transaction: Arc<ValidPoolTransaction<T>> },
+    /// Sender already has the configured maximum of transactions in the pool.
+    ExceededSenderTransactionsLimit { transaction: Arc<ValidPoolTransaction<T>> },
     /// Transaction gas limit exceeds block's gas limit
     TxGasLimitMoreThanAvailableBlockGas {
         transaction: Arc<ValidPoolTransaction<T>>,
@@ -1848,13 +2258,15 @@ impl SenderInfo {
 
 #[cfg(test)]
 mod tests {
-    use reth_primitives::{address, TxType};
+    use reth_primitives::{address, TxType, LEGACY_TX_TYPE_ID};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
 
     use super::*;
     use crate::{
         test_utils::{MockOrdering, MockTransaction, MockTransactionFactory, MockTransactionSet},
         traits::TransactionOrigin,
-        SubPoolLimit,
+        validate::{KnownAccount, TransactionConditional},
+        OriginLimits, SubPoolLimit,
     };
 
     #[test]
@@ -2577,6 +2989,369 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn transactions_page() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip1559();
+        let sender = f.validated(tx.clone()).transaction_id.sender;
+        for tx in [tx.clone(), tx.next(), tx.next().next(), MockTransaction::legacy()] {
+            pool.add_transaction(f.validated(tx), on_chain_balance, on_chain_nonce).unwrap();
+        }
+
+        let mut query = TransactionsQuery { limit: 2, ..Default::default() };
+        let page = pool.transactions_page(&query, None);
+        assert_eq!(page.transactions.len(), 2);
+        let cursor = page.next_cursor.unwrap();
+        assert_eq!(cursor.to_string().parse::<TransactionsCursor>(), Ok(cursor));
+
+        query.cursor = Some(cursor);
+        let page = pool.transactions_page(&query, None);
+        assert_eq!(page.transactions.len(), 2);
+        assert_eq!(page.next_cursor, None);
+
+        // filter by sender, continuing after the first page
+        let page = pool.transactions_page(&query, Some(sender));
+        assert_eq!(page.transactions.len(), 1);
+        assert_eq!(page.transactions[0].1.nonce(), 2);
+
+        // filter by type and sub-pool
+        let legacy_sub_pool = pool
+            .all_transactions
+            .txs
+            .values()
+            .find(|tx| tx.transaction.transaction.is_legacy())
+            .unwrap()
+            .subpool;
+        let query = TransactionsQuery {
+            tx_type: Some(LEGACY_TX_TYPE_ID),
+            sub_pool: Some(legacy_sub_pool),
+            limit: 10,
+            ..Default::default()
+        };
+        let page = pool.transactions_page(&query, None);
+        assert_eq!(page.transactions.len(), 1);
+        assert!(page.transactions[0].1.transaction.is_legacy());
+    }
+
+    #[test]
+    fn rejects_sender_over_limit() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = AllTransactions::<MockTransaction> {
+            max_txs_per_sender: Some(2),
+            ..Default::default()
+        };
+
+        // the limit also applies to local transactions
+        let tx = MockTransaction::eip1559();
+        pool.insert_tx(
+            f.validated_with_origin(TransactionOrigin::Local, tx.clone()),
+            on_chain_balance,
+            on_chain_nonce,
+        )
+        .unwrap();
+        pool.insert_tx(
+            f.validated_with_origin(TransactionOrigin::Local, tx.next()),
+            on_chain_balance,
+            on_chain_nonce,
+        )
+        .unwrap();
+
+        let err = pool
+            .insert_tx(
+                f.validated_with_origin(TransactionOrigin::Local, tx.next().next()),
+                on_chain_balance,
+                on_chain_nonce,
+            )
+            .unwrap_err();
+        assert!(matches!(err, InsertErr::ExceededSenderTransactionsLimit { .. }));
+
+        // replacements are still accepted
+        let replacement = tx.rng_hash().inc_price_by(10);
+        pool.insert_tx(
+            f.validated_with_origin(TransactionOrigin::Local, replacement),
+            on_chain_balance,
+            on_chain_nonce,
+        )
+        .unwrap();
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn update_limits() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip1559();
+        pool.add_transaction(f.validated(tx.clone()), on_chain_balance, on_chain_nonce).unwrap();
+        pool.add_transaction(f.validated(tx.next()), on_chain_balance, on_chain_nonce).unwrap();
+
+        // the transactions over the new limit are kept, but no new transaction is accepted
+        pool.update_config(PoolConfigUpdate { max_txs_per_sender: Some(1), ..Default::default() });
+        assert_eq!(pool.len(), 2);
+        let err = pool
+            .add_transaction(f.validated(tx.next().next()), on_chain_balance, on_chain_nonce)
+            .unwrap_err();
+        assert!(matches!(err.kind, PoolErrorKind::SenderTransactionsLimitExceeded(_)));
+
+        // the pool holds the maximum number of external transactions, so the next one either
+        // evicts a transaction or is rejected
+        let other = f.validated(MockTransaction::eip1559());
+        pool.update_config(PoolConfigUpdate {
+            origin_limits: OriginLimits { external: Some(2), ..Default::default() },
+            ..Default::default()
+        });
+        assert!(!matches!(pool.origin_eviction_candidate(&other), Ok(None)));
+    }
+
+    #[test]
+    fn recheck_promoted_transactions_against_raised_priority_fee_floor() {
+        let on_chain_balance = U256::from(1_000_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        // queued transactions with a nonce gap and an effective tip of 10
+        let a0 = MockTransaction::eip1559().with_max_fee(1_000).with_priority_fee(100);
+        let a1 = f.validated(a0.next().with_priority_fee(10));
+        let a2 = f.validated(a1.transaction.next());
+        for tx in [a1, a2] {
+            pool.add_transaction(tx, on_chain_balance, on_chain_nonce).unwrap();
+        }
+
+        assert_eq!(pool.update_priority_fee_floor(|floor| floor.dynamic = Some(50)), Some(50));
+        assert_eq!(pool.priority_fee_floor().effective(), Some(50));
+
+        // a transaction that was added after the floor was raised isn't re-checked
+        let b0 = MockTransaction::eip1559().with_max_fee(1_000).with_priority_fee(100);
+        let b1 = f.validated(b0.next().with_priority_fee(10));
+        pool.add_transaction(b1, on_chain_balance, on_chain_nonce).unwrap();
+        let AddedTransaction::Pending(added) =
+            pool.add_transaction(f.validated(b0), on_chain_balance, on_chain_nonce).unwrap()
+        else {
+            panic!("transaction should be pending")
+        };
+        assert_eq!((added.promoted.len(), added.discarded.len()), (1, 0));
+
+        // filling the nonce gap promotes the queued transactions, which are below the new floor
+        let AddedTransaction::Pending(added) =
+            pool.add_transaction(f.validated(a0), on_chain_balance, on_chain_nonce).unwrap()
+        else {
+            panic!("transaction should be pending")
+        };
+        assert!(added.promoted.is_empty());
+        assert_eq!(added.discarded.len(), 2);
+        assert_eq!(pool.len(), 3);
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn origin_limit_evicts_lowest_fee_without_nonce_gap() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig {
+            origin_limits: OriginLimits { external: Some(3), ..Default::default() },
+            ..Default::default()
+        };
+        let mut pool = TxPool::new(MockOrdering::default(), config);
+
+        // the cheapest transaction is followed by another transaction of the same sender, so it
+        // can't be evicted without creating a nonce gap
+        let a0 = f.validated(MockTransaction::eip1559().with_gas_price(10));
+        let a1 = f.validated(a0.transaction.next().with_gas_price(100));
+        let b0 = f.validated(MockTransaction::eip1559().with_gas_price(50));
+        for tx in [&a0, &a1, &b0] {
+            assert!(pool.origin_eviction_candidate(tx).unwrap().is_none());
+            pool.add_transaction(tx.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        }
+        assert_eq!(pool.all_transactions.origin_tx_count(TransactionOrigin::External), 3);
+
+        // local transactions are not affected by the limit of external transactions
+        let local = f.validated_with_origin(TransactionOrigin::Local, MockTransaction::eip1559());
+        assert!(pool.origin_eviction_candidate(&local).unwrap().is_none());
+
+        // nothing is evicted before the new transaction was inserted
+        let c0 = f.validated(MockTransaction::eip1559().with_gas_price(60));
+        let candidate = pool.origin_eviction_candidate(&c0).unwrap().unwrap();
+        assert_eq!(candidate, *b0.hash());
+        assert!(pool.contains(b0.hash()));
+        pool.add_transaction(c0.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        let evicted = pool.evict_for_origin(TransactionOrigin::External, candidate).unwrap();
+        assert_eq!(evicted.hash(), b0.hash());
+        assert!(pool.contains(a0.hash()));
+        assert!(pool.contains(a1.hash()));
+
+        // no transaction that can be evicted has a lower fee
+        let d0 = f.validated(MockTransaction::eip1559().with_gas_price(20));
+        let err = pool.origin_eviction_candidate(&d0).unwrap_err();
+        assert!(matches!(
+            err.kind,
+            PoolErrorKind::OriginLimitExceeded(TransactionOrigin::External)
+        ));
+
+        // a transaction never evicts the transactions of its own sender
+        let a2 = f.validated(a1.transaction.next().with_gas_price(200));
+        assert_eq!(pool.origin_eviction_candidate(&a2).unwrap(), Some(*c0.hash()));
+        assert!(pool.contains(a1.hash()));
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn discard_worst_evicts_lowest_fee_external_first() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let pending_limit = SubPoolLimit::new(3, usize::MAX);
+        let mut pool = TxPool::new(
+            MockOrdering::default(),
+            PoolConfig { pending_limit, ..Default::default() },
+        );
+
+        // the cheapest transaction is followed by another transaction of the same sender
+        let a0 = f.validated(MockTransaction::eip1559().with_gas_price(10));
+        let a1 = f.validated(a0.transaction.next().with_gas_price(30));
+        let b0 = f.validated(MockTransaction::eip1559().with_gas_price(20));
+        let local = f.validated_with_origin(
+            TransactionOrigin::Local,
+            MockTransaction::eip1559().with_gas_price(1),
+        );
+        for tx in [&a0, &a1, &b0, &local] {
+            pool.add_transaction(tx.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        }
+        assert_eq!(pool.pending_pool.len(), 4);
+
+        // the external transaction with the lowest fee that leaves no nonce gap is evicted
+        let removed = pool.discard_worst();
+        assert_eq!(removed.iter().map(|tx| *tx.hash()).collect::<Vec<_>>(), vec![*b0.hash()]);
+        assert!(pool.contains(local.hash()));
+
+        // the sender's last transaction becomes evictable once it has the highest nonce
+        let c0 = f.validated(MockTransaction::eip1559().with_gas_price(40));
+        pool.add_transaction(c0.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        let removed = pool.discard_worst();
+        assert_eq!(removed.iter().map(|tx| *tx.hash()).collect::<Vec<_>>(), vec![*a1.hash()]);
+        assert!(pool.contains(a0.hash()));
+        assert!(pool.contains(c0.hash()));
+        assert!(pool.contains(local.hash()));
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn origin_limit_evicts_only_if_the_origin_grows() {
+        let on_chain_balance = U256::from(1_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let config = PoolConfig {
+            origin_limits: OriginLimits { external: Some(2), ..Default::default() },
+            ..Default::default()
+        };
+        let mut pool = TxPool::new(MockOrdering::default(), config);
+
+        let a0 = f.validated(MockTransaction::eip1559().with_gas_price(10));
+        let b0 = f.validated(MockTransaction::eip1559().with_gas_price(50));
+        for tx in [&a0, &b0] {
+            pool.add_transaction(tx.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        }
+
+        // a replacement of a transaction of the same origin needs no eviction
+        let b0_replacement = f.validated(b0.transaction.clone().rng_hash().with_gas_price(100));
+        assert!(pool.origin_eviction_candidate(&b0_replacement).unwrap().is_none());
+
+        // the count of the origin didn't grow, so the candidate is kept
+        let c0 = f.validated(MockTransaction::eip1559().with_gas_price(60));
+        let candidate = pool.origin_eviction_candidate(&c0).unwrap().unwrap();
+        assert_eq!(candidate, *a0.hash());
+        pool.add_transaction(b0_replacement, on_chain_balance, on_chain_nonce).unwrap();
+        assert!(pool.evict_for_origin(TransactionOrigin::External, candidate).is_none());
+        assert!(pool.contains(a0.hash()));
+        assert_eq!(pool.all_transactions.origin_tx_count(TransactionOrigin::External), 2);
+
+        // the index follows the highest nonce of each sender
+        let a1 = f.validated(a0.transaction.next().with_gas_price(70));
+        pool.add_transaction(a1.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        let lowest = |pool: &TxPool<MockOrdering>| {
+            pool.all_transactions
+                .lowest_fee_evictable(TransactionOrigin::External, c0.sender_id())
+                .map(|tx| *tx.hash())
+        };
+        assert_eq!(lowest(&pool), Some(*a1.hash()));
+        pool.remove_transactions(vec![*a1.hash()]);
+        assert_eq!(lowest(&pool), Some(*a0.hash()));
+        pool.assert_invariants();
+    }
+
+    #[test]
+    fn conditional_transaction_satisfied() {
+        let mut f = MockTransactionFactory::default();
+        let address = Address::random();
+        let slot = B256::with_last_byte(1);
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage([(slot, U256::from(2))]),
+        );
+
+        let mut tx = f.validated(MockTransaction::eip1559());
+        assert!(tx.is_conditional_satisfied(1, 0, &provider).unwrap());
+
+        tx.conditional = Some(Box::new(TransactionConditional {
+            known_accounts: HashMap::from([(
+                address,
+                KnownAccount::StorageSlots(HashMap::from([(slot, B256::with_last_byte(2))])),
+            )]),
+            block_number_min: Some(10),
+            ..Default::default()
+        }));
+        assert!(!tx.is_conditional_satisfied(9, 0, &provider).unwrap());
+        assert!(tx.is_conditional_satisfied(10, 0, &provider).unwrap());
+
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage([(slot, U256::from(3))]),
+        );
+        assert!(!tx.is_conditional_satisfied(10, 0, &provider).unwrap());
+    }
+
+    #[test]
+    fn remove_exceeded_conditional_transactions() {
+        let on_chain_balance = U256::from(10_000);
+        let on_chain_nonce = 0;
+        let mut f = MockTransactionFactory::default();
+        let mut pool = TxPool::new(MockOrdering::default(), Default::default());
+
+        let tx = MockTransaction::eip1559();
+        let mut conditional = f.validated(tx.clone());
+        conditional.conditional = Some(Box::new(TransactionConditional {
+            block_number_max: Some(10),
+            ..Default::default()
+        }));
+        let descendant = f.validated(tx.next());
+        let other = f.validated(MockTransaction::eip1559());
+        for tx in [&conditional, &descendant, &other] {
+            pool.add_transaction(tx.clone(), on_chain_balance, on_chain_nonce).unwrap();
+        }
+
+        assert!(pool.remove_exceeded_conditional_transactions(9, 0).is_empty());
+
+        // the descendant is removed as well, since it would have a nonce gap
+        let removed = pool.remove_exceeded_conditional_transactions(10, 0);
+        assert_eq!(
+            removed.iter().map(|tx| *tx.hash()).collect::<Vec<_>>(),
+            vec![*conditional.hash(), *descendant.hash()]
+        );
+        assert!(pool.contains(other.hash()));
+        assert!(pool.all_transactions.conditional_txs.is_empty());
+        pool.assert_invariants();
+    }
+
     #[test]
     fn reject_tx_over_gas_limit() {
         let on_chain_balance = U256::from(1_000);
@@ -1074,6 +1074,7 @@ impl MockTransactionFactory {
             transaction,
             timestamp: Instant::now(),
             origin,
+            conditional: None,
         }
     }
 
@@ -3,9 +3,10 @@
 use crate::{
     blobstore::BlobStoreError,
     error::PoolResult,
+    identifier::TransactionId,
     pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
-    validate::ValidPoolTransaction,
-    AllTransactionsEvents,
+    validate::{TransactionConditional, ValidPoolTransaction},
+    AllTransactionsEvents, PoolConfigUpdate, PriorityFeeFloor,
 };
 use futures_util::{ready, Stream};
 use reth_eth_wire::HandleMempoolData;
@@ -22,7 +23,9 @@ use std::{
     collections::{HashMap, HashSet},
     fmt,
     future::Future,
+    num::ParseIntError,
     pin::Pin,
+    str::FromStr,
     sync::Arc,
     task::{Context, Poll},
 };
@@ -49,6 +52,24 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// This tracks the block that the pool has last seen.
     fn block_info(&self) -> BlockInfo;
 
+    /// Replaces the transaction limits of the pool and the minimum priority fee of new
+    /// transactions.
+    ///
+    /// The new settings only apply to new transactions, the transactions that are already in the
+    /// pool are kept even if they exceed the new limits.
+    fn update_config(&self, update: PoolConfigUpdate);
+
+    /// Returns the minimum effective tip a new transaction must pay at the base fee of the pending
+    /// block.
+    fn priority_fee_floor(&self) -> PriorityFeeFloor;
+
+    /// Sets the floor of the effective tip of new transactions manually, which replaces the
+    /// configured and the dynamic floor until it's cleared with `None`.
+    ///
+    /// The transactions that are already in the pool are only checked against a raised floor once
+    /// they're promoted to the pending sub-pool.
+    fn set_priority_fee_floor_override(&self, floor: Option<u128>);
+
     /// Imports an _external_ transaction.
     ///
     /// This is intended to be used by the network to insert incoming transactions received over the
@@ -105,6 +126,21 @@ pub trait TransactionPool: Send + Sync + Clone {
         transactions: Vec<Self::Transaction>,
     ) -> impl Future<Output = Vec<PoolResult<TxHash>>> + Send;
 
+    /// Adds an _unvalidated_ transaction into the pool, together with the conditions a block must
+    /// satisfy to include it.
+    ///
+    /// The conditions are not checked by the pool, except for evicting the transaction once its
+    /// block number or timestamp range passed. Block builders must check them via
+    /// [ValidPoolTransaction::is_conditional_satisfied].
+    ///
+    /// Consumer: RPC
+    fn add_conditional_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+        conditional: TransactionConditional,
+    ) -> impl Future<Output = PoolResult<TxHash>> + Send;
+
     /// Returns a new transaction change event stream for the given transaction.
     ///
     /// Returns `None` if the transaction is not in the pool.
@@ -137,6 +173,13 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// commitments/proofs) for eip-4844 transactions inserted into the pool
     fn blob_transaction_sidecars_listener(&self) -> Receiver<NewBlobSidecar>;
 
+    /// Returns a new [Receiver] that yields hashes of eip-4844 transactions that are missing from
+    /// the pool because their sidecars are unavailable, for example after a reorg, and that should
+    /// be fetched from peers.
+    ///
+    /// Consumer: P2P
+    fn missing_blob_sidecars_listener(&self) -> Receiver<TxHash>;
+
     /// Returns a new stream that yields new valid transactions added to the pool
     /// depending on the given [TransactionListenerKind] argument.
     fn new_transactions_listener_for(
@@ -286,6 +329,15 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// Consumer: RPC
     fn all_transactions(&self) -> AllPoolTransactions<Self::Transaction>;
 
+    /// Returns a page of the transactions in the pool that match the given query, in the order of
+    /// their senders and nonces.
+    ///
+    /// Only the matching transactions are collected, so this can be used to serve large pools in
+    /// multiple requests.
+    ///
+    /// Consumer: RPC
+    fn transactions_page(&self, query: TransactionsQuery) -> TransactionsPage<Self::Transaction>;
+
     /// Removes all transactions corresponding to the given hashes.
     ///
     /// Also removes all _dependent_ transactions.
@@ -390,6 +442,11 @@ pub trait TransactionPoolExt: TransactionPool {
     /// Sets the current block info for the pool.
     fn set_block_info(&self, info: BlockInfo);
 
+    /// Sets the floor of the effective tip of new transactions that's adjusted based on the
+    /// fullness of recent blocks, see
+    /// [PriorityFeeFloorConfig](crate::maintain::PriorityFeeFloorConfig).
+    fn set_dynamic_priority_fee_floor(&self, floor: Option<u128>);
+
     /// Event listener for when the pool needs to be updated.
     ///
     /// Implementers need to update the pool accordingly:
@@ -418,6 +475,11 @@ pub trait TransactionPoolExt: TransactionPool {
 
     /// Maintenance function to cleanup blobs that are no longer needed.
     fn cleanup_blobs(&self);
+
+    /// Notifies the [missing blob sidecars
+    /// listeners](TransactionPool::missing_blob_sidecars_listener) that the given blob
+    /// transactions should be fetched from peers.
+    fn request_missing_blob_sidecars(&self, tx_hashes: Vec<TxHash>);
 }
 
 /// Determines what kind of new transactions should be emitted by a stream of transactions.
@@ -472,6 +534,79 @@ impl<T: PoolTransaction> Default for AllPoolTransactions<T> {
     }
 }
 
+/// A query for the transactions in the pool, see [TransactionPool::transactions_page].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionsQuery {
+    /// Only include transactions of this sender.
+    pub sender: Option<Address>,
+    /// Only include transactions in this sub-pool.
+    pub sub_pool: Option<SubPool>,
+    /// Only include transactions of this type.
+    pub tx_type: Option<u8>,
+    /// Only include transactions whose effective tip at the pending block's base fee is at least
+    /// this value.
+    pub min_tip: Option<u128>,
+    /// Only include transactions that come after this cursor, returned by a previous page.
+    pub cursor: Option<TransactionsCursor>,
+    /// The maximum number of transactions in the page.
+    pub limit: usize,
+}
+
+impl TransactionsQuery {
+    /// Returns `true` if the transaction in the given sub-pool matches the filters of the query.
+    pub(crate) fn matches<T: PoolTransaction>(
+        &self,
+        sub_pool: SubPool,
+        transaction: &T,
+        pending_base_fee: u64,
+    ) -> bool {
+        self.sub_pool.map_or(true, |expected| expected == sub_pool) &&
+            self.tx_type.map_or(true, |tx_type| tx_type == transaction.tx_type()) &&
+            self.min_tip.map_or(true, |min_tip| {
+                transaction
+                    .effective_tip_per_gas(pending_base_fee)
+                    .is_some_and(|tip| tip >= min_tip)
+            })
+    }
+}
+
+/// The position of a transaction in the pool, after which a [TransactionsQuery] continues.
+///
+/// The cursor is only meaningful to the pool it was returned by, and can be formatted as and
+/// parsed from an opaque string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionsCursor(pub(crate) TransactionId);
+
+impl fmt::Display for TransactionsCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.0.sender, self.0.nonce)
+    }
+}
+
+impl FromStr for TransactionsCursor {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (sender, nonce) = s.split_once('-').unwrap_or((s, ""));
+        Ok(Self(TransactionId::new(sender.parse::<u64>()?.into(), nonce.parse()?)))
+    }
+}
+
+/// A page of the transactions in the pool, see [TransactionPool::transactions_page].
+#[derive(Debug, Clone)]
+pub struct TransactionsPage<T: PoolTransaction> {
+    /// The matching transactions, with the sub-pool they are currently in.
+    pub transactions: Vec<(SubPool, Arc<ValidPoolTransaction<T>>)>,
+    /// The cursor to query the next page with, if there are more matching transactions.
+    pub next_cursor: Option<TransactionsCursor>,
+}
+
+impl<T: PoolTransaction> Default for TransactionsPage<T> {
+    fn default() -> Self {
+        Self { transactions: Default::default(), next_cursor: None }
+    }
+}
+
 /// Represents a transaction that was propagated over the network.
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct PropagatedTransactions(pub HashMap<TxHash, Vec<PropagateKind>>);
@@ -537,7 +672,7 @@ pub struct NewBlobSidecar {
 ///
 /// Depending on where the transaction was picked up, it affects how the transaction is handled
 /// internally, e.g. limits for simultaneous transaction of one sender.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum TransactionOrigin {
     /// Transaction is coming from a local source.
     Local,
@@ -0,0 +1,143 @@
+//! Conditions of transactions that may only be included in blocks satisfying them.
+
+use reth_primitives::{Address, BlockNumber, B256, U256};
+use reth_provider::{ProviderError, StateProvider};
+use std::collections::HashMap;
+
+/// The conditions a block must satisfy to include a transaction.
+///
+/// See also <https://notes.ethereum.org/@yoav/SkaX2lS9j>
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionConditional {
+    /// The expected storage of accounts in the parent state of the block.
+    pub known_accounts: HashMap<Address, KnownAccount>,
+    /// The minimum number of the block.
+    pub block_number_min: Option<BlockNumber>,
+    /// The maximum number of the block.
+    pub block_number_max: Option<BlockNumber>,
+    /// The minimum timestamp of the block.
+    pub timestamp_min: Option<u64>,
+    /// The maximum timestamp of the block.
+    pub timestamp_max: Option<u64>,
+}
+
+impl TransactionConditional {
+    /// Returns the number of storage slots that are checked for all known accounts.
+    ///
+    /// An expected storage root counts as a single slot.
+    pub fn storage_slots(&self) -> usize {
+        self.known_accounts
+            .values()
+            .map(|account| match account {
+                KnownAccount::StorageRoot(_) => 1,
+                KnownAccount::StorageSlots(slots) => slots.len(),
+            })
+            .sum()
+    }
+
+    /// Returns whether a block with the given number and timestamp satisfies the block number and
+    /// timestamp ranges.
+    pub fn matches_block_attributes(&self, number: BlockNumber, timestamp: u64) -> bool {
+        let in_range = |value: u64, min: Option<u64>, max: Option<u64>| {
+            min.map_or(true, |min| min <= value) && max.map_or(true, |max| value <= max)
+        };
+        in_range(number, self.block_number_min, self.block_number_max) &&
+            in_range(timestamp, self.timestamp_min, self.timestamp_max)
+    }
+
+    /// Returns whether no block after the block with the given number and timestamp can satisfy
+    /// the block number and timestamp ranges anymore.
+    pub fn has_exceeded_block_attributes(&self, number: BlockNumber, timestamp: u64) -> bool {
+        self.block_number_max.is_some_and(|max| max <= number) ||
+            self.timestamp_max.is_some_and(|max| max <= timestamp)
+    }
+
+    /// Returns whether the storage of the known accounts has the expected root or slot values in
+    /// the given state.
+    pub fn known_accounts_match(&self, state: &impl StateProvider) -> Result<bool, ProviderError> {
+        for (address, account) in &self.known_accounts {
+            match account {
+                KnownAccount::StorageRoot(expected) => {
+                    if state.proof(*address, &[])?.storage_root != *expected {
+                        return Ok(false)
+                    }
+                }
+                KnownAccount::StorageSlots(slots) => {
+                    for (slot, expected) in slots {
+                        let value = state.storage(*address, *slot)?.unwrap_or_default();
+                        if value != U256::from_be_bytes(expected.0) {
+                            return Ok(false)
+                        }
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// The expected storage of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KnownAccount {
+    /// The expected root of the storage trie of the account.
+    StorageRoot(B256),
+    /// The expected values of storage slots of the account.
+    StorageSlots(HashMap<B256, B256>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{constants::EMPTY_ROOT_HASH, trie::AccountProof};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+
+    #[test]
+    fn block_attributes() {
+        let conditional = TransactionConditional {
+            block_number_min: Some(10),
+            block_number_max: Some(20),
+            timestamp_max: Some(1_000),
+            ..Default::default()
+        };
+        assert!(!conditional.matches_block_attributes(9, 0));
+        assert!(conditional.matches_block_attributes(10, 0));
+        assert!(conditional.matches_block_attributes(20, 1_000));
+        assert!(!conditional.matches_block_attributes(20, 1_001));
+
+        assert!(!conditional.has_exceeded_block_attributes(19, 999));
+        assert!(conditional.has_exceeded_block_attributes(20, 999));
+        assert!(conditional.has_exceeded_block_attributes(19, 1_000));
+    }
+
+    #[test]
+    fn known_accounts() {
+        let address = Address::random();
+        let slot = B256::with_last_byte(1);
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            address,
+            ExtendedAccount::new(0, U256::ZERO).extend_storage([(slot, U256::from(2))]),
+        );
+
+        let slots = KnownAccount::StorageSlots(HashMap::from([(slot, B256::with_last_byte(2))]));
+        let mut conditional = TransactionConditional {
+            known_accounts: HashMap::from([(address, slots)]),
+            ..Default::default()
+        };
+        assert_eq!(conditional.storage_slots(), 1);
+        assert!(conditional.known_accounts_match(&provider).unwrap());
+
+        let slots = KnownAccount::StorageSlots(HashMap::from([(slot, B256::with_last_byte(3))]));
+        conditional.known_accounts.insert(address, slots);
+        assert!(!conditional.known_accounts_match(&provider).unwrap());
+
+        // the mock provider returns the default proof for every account
+        let root = AccountProof::default().storage_root;
+        assert_ne!(root, EMPTY_ROOT_HASH);
+        conditional.known_accounts.insert(address, KnownAccount::StorageRoot(root));
+        assert!(conditional.known_accounts_match(&provider).unwrap());
+
+        conditional.known_accounts.insert(address, KnownAccount::StorageRoot(EMPTY_ROOT_HASH));
+        assert!(!conditional.known_accounts_match(&provider).unwrap());
+    }
+}
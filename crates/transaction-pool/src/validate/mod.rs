@@ -6,15 +6,20 @@ use crate::{
     traits::{PoolTransaction, TransactionOrigin},
 };
 use reth_primitives::{
-    Address, BlobTransactionSidecar, IntoRecoveredTransaction, SealedBlock,
+    Address, BlobTransactionSidecar, BlockNumber, IntoRecoveredTransaction, SealedBlock,
     TransactionSignedEcRecovered, TxHash, B256, U256,
 };
+use reth_provider::{ProviderError, StateProvider};
 use std::{fmt, future::Future, time::Instant};
 
+mod conditional;
 mod constants;
 mod eth;
 mod task;
 
+/// The conditions a block must satisfy to include a transaction.
+pub use conditional::{KnownAccount, TransactionConditional};
+
 /// A `TransactionValidator` implementation that validates ethereum transaction.
 pub use eth::*;
 
@@ -206,6 +211,11 @@ pub trait TransactionValidator: Send + Sync {
     ///
     /// This can be used to update fork specific values (timestamp).
     fn on_new_head_block(&self, _new_tip_block: &SealedBlock) {}
+
+    /// Invoked when the minimum effective tip of new transactions changes.
+    ///
+    /// Validators that don't enforce a minimum priority fee can ignore this.
+    fn on_new_minimum_priority_fee(&self, _minimum_priority_fee: Option<u128>) {}
 }
 
 /// A valid transaction in the pool.
@@ -225,6 +235,9 @@ pub struct ValidPoolTransaction<T: PoolTransaction> {
     pub timestamp: Instant,
     /// Where this transaction originated from.
     pub origin: TransactionOrigin,
+    /// The conditions a block must satisfy to include this transaction, if it was submitted via
+    /// `eth_sendRawTransactionConditional`.
+    pub conditional: Option<Box<TransactionConditional>>,
 }
 
 // === impl ValidPoolTransaction ===
@@ -328,6 +341,23 @@ impl<T: PoolTransaction> ValidPoolTransaction<T> {
         self.transaction.size()
     }
 
+    /// Returns whether the block with the given number and timestamp, built on top of the given
+    /// parent state, satisfies the conditions of this transaction.
+    ///
+    /// This is always true for transactions without conditions.
+    pub fn is_conditional_satisfied(
+        &self,
+        block_number: BlockNumber,
+        timestamp: u64,
+        state: &impl StateProvider,
+    ) -> Result<bool, ProviderError> {
+        let Some(conditional) = &self.conditional else { return Ok(true) };
+        if !conditional.matches_block_attributes(block_number, timestamp) {
+            return Ok(false)
+        }
+        conditional.known_accounts_match(state)
+    }
+
     /// EIP-4844 blob transactions and normal transactions are treated as mutually exclusive per
     /// account.
     ///
@@ -354,6 +384,7 @@ impl<T: PoolTransaction + Clone> Clone for ValidPoolTransaction<T> {
             propagate: self.propagate,
             timestamp: self.timestamp,
             origin: self.origin,
+            conditional: self.conditional.clone(),
         }
     }
 }
@@ -211,4 +211,8 @@ where
     fn on_new_head_block(&self, new_tip_block: &SealedBlock) {
         self.validator.on_new_head_block(new_tip_block)
     }
+
+    fn on_new_minimum_priority_fee(&self, minimum_priority_fee: Option<u128>) {
+        self.validator.on_new_minimum_priority_fee(minimum_priority_fee)
+    }
 }
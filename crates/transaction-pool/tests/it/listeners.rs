@@ -41,6 +41,24 @@ async fn txpool_listener_all() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_listener_all_missed() {
+    let txpool = TestPoolBuilder::default();
+    let mut mock_tx_factory = MockTransactionFactory::default();
+
+    let all_tx_events = txpool.all_transactions_event_listener();
+
+    for _ in 0..1100 {
+        let transaction = mock_tx_factory.create_eip1559();
+        let added_result =
+            txpool.add_transaction(TransactionOrigin::External, transaction.transaction).await;
+        assert_matches!(added_result, Ok(_));
+    }
+
+    assert!(all_tx_events.take_missed() > 0);
+    assert_eq!(all_tx_events.take_missed(), 0);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn txpool_listener_propagate_only() {
     let txpool =
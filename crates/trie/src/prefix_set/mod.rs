@@ -161,6 +161,11 @@ impl PrefixSet {
         false
     }
 
+    /// Returns an iterator over the keys of the set in sorted order.
+    pub fn iter(&self) -> std::slice::Iter<'_, Nibbles> {
+        self.keys.iter()
+    }
+
     /// Returns the number of elements in the set.
     pub fn len(&self) -> usize {
         self.keys.len()
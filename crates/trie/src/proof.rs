@@ -1,7 +1,7 @@
 use crate::{
     hashed_cursor::{HashedCursorFactory, HashedStorageCursor},
     node_iter::{AccountNode, AccountNodeIter, StorageNode, StorageNodeIter},
-    prefix_set::PrefixSetMut,
+    prefix_set::{PrefixSetMut, TriePrefixSets},
     trie_cursor::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
     walker::TrieWalker,
 };
@@ -20,18 +20,37 @@ use reth_primitives::{
 /// Proof generator adds the target address and slots to the prefix set, enables the proof retainer
 /// on the hash builder and follows the same algorithm as the state root calculator.
 /// See `StateRoot::root` for more info.
+///
+/// Proofs of a state that has not been committed to the trie tables can be generated by setting
+/// a hashed cursor factory over that state together with the prefix sets of its changes, see
+/// [HashedPostState::account_proofs](crate::HashedPostState::account_proofs).
 #[derive(Debug)]
 pub struct Proof<'a, TX, H> {
     /// A reference to the database transaction.
     tx: &'a TX,
     /// The factory for hashed cursors.
     hashed_cursor_factory: H,
+    /// A set of prefixes that have changed and whose trie nodes must be recomputed.
+    prefix_sets: TriePrefixSets,
 }
 
 impl<'a, TX> Proof<'a, TX, &'a TX> {
     /// Create a new [Proof] instance.
     pub fn new(tx: &'a TX) -> Self {
-        Self { tx, hashed_cursor_factory: tx }
+        Self { tx, hashed_cursor_factory: tx, prefix_sets: TriePrefixSets::default() }
+    }
+}
+
+impl<'a, TX, H> Proof<'a, TX, H> {
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> Proof<'a, TX, HF> {
+        Proof { tx: self.tx, hashed_cursor_factory, prefix_sets: self.prefix_sets }
+    }
+
+    /// Set the changed prefixes.
+    pub fn with_prefix_sets(mut self, prefix_sets: TriePrefixSets) -> Self {
+        self.prefix_sets = prefix_sets;
+        self
     }
 }
 
@@ -55,7 +74,8 @@ where
             DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
 
         // Create the walker.
-        let mut prefix_set = PrefixSetMut::default();
+        let mut prefix_set =
+            PrefixSetMut::from(self.prefix_sets.account_prefix_set.iter().cloned());
         prefix_set.insert(target_nibbles.clone());
         let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
@@ -119,7 +139,11 @@ where
         }
 
         let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
-        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
+        let mut prefix_set = PrefixSetMut::from(target_nibbles.clone());
+        if let Some(changed) = self.prefix_sets.storage_prefix_sets.get(&hashed_address) {
+            changed.iter().cloned().for_each(|nibbles| prefix_set.insert(nibbles));
+        }
+        let prefix_set = prefix_set.freeze();
         let trie_cursor = DatabaseStorageTrieCursor::new(
             self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
             hashed_address,
@@ -164,7 +188,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::StateRoot;
+    use crate::{HashedPostState, HashedStorage, StateRoot};
     use once_cell::sync::Lazy;
     use reth_db::database::Database;
     use reth_interfaces::RethResult;
@@ -441,4 +465,38 @@ mod tests {
         let account_proof = Proof::new(provider.tx_ref()).account_proof(target, &slots).unwrap();
         similar_asserts::assert_eq!(account_proof, expected);
     }
+
+    #[test]
+    fn testspec_post_state_proofs() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        // Change the balance of one account and add a storage slot to another one.
+        let changed = Address::from_str("0x2031f89b3ea8014eb51a78c316e42af3e0d7695f").unwrap();
+        let changed_account = Account { nonce: 1, balance: U256::from(1), bytecode_hash: None };
+        let target = Address::from_str("0x1ed9b1dd266b607ee278726d324b855a093394a6").unwrap();
+        let slot = B256::with_last_byte(1);
+        let post_state = HashedPostState::default()
+            .with_accounts([(keccak256(changed), Some(changed_account))])
+            .with_storages([(
+                keccak256(target),
+                HashedStorage::from_iter(false, [(keccak256(slot), U256::from(2))]),
+            )]);
+
+        let provider = factory.provider().unwrap();
+        let state_root = post_state.state_root(provider.tx_ref()).unwrap();
+        let proofs = post_state
+            .account_proofs(provider.tx_ref(), &[(changed, Vec::new()), (target, vec![slot])])
+            .unwrap();
+
+        assert_eq!(proofs[0].info, Some(changed_account));
+        for proof in &proofs {
+            assert_eq!(keccak256(&proof.proof[0]), state_root);
+        }
+
+        let storage_proof = &proofs[1].storage_proofs[0];
+        assert_eq!(storage_proof.value, U256::from(2));
+        assert_eq!(keccak256(&storage_proof.proof[0]), proofs[1].storage_root);
+    }
 }
@@ -1,6 +1,7 @@
 use crate::{
     hashed_cursor::HashedPostStateCursorFactory,
     prefix_set::{PrefixSetMut, TriePrefixSets},
+    proof::Proof,
     updates::TrieUpdates,
     StateRoot,
 };
@@ -13,8 +14,10 @@ use reth_db::{
 };
 use reth_interfaces::trie::StateRootError;
 use reth_primitives::{
-    keccak256, revm::compat::into_reth_acc, trie::Nibbles, Account, Address, BlockNumber, B256,
-    U256,
+    keccak256,
+    revm::compat::into_reth_acc,
+    trie::{AccountProof, Nibbles},
+    Account, Address, BlockNumber, B256, U256,
 };
 use revm::db::BundleAccount;
 use std::{
@@ -249,6 +252,20 @@ impl HashedPostState {
             .with_prefix_sets(prefix_sets)
             .root_with_updates()
     }
+
+    /// Generates the account and storage proofs of the given addresses and slots against the
+    /// state root of this [HashedPostState] applied on top of the database state.
+    pub fn account_proofs<TX: DbTx>(
+        &self,
+        tx: &TX,
+        targets: &[(Address, Vec<B256>)],
+    ) -> Result<Vec<AccountProof>, StateRootError> {
+        let sorted = self.clone().into_sorted();
+        let proof = Proof::new(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_sets(self.construct_prefix_sets());
+        targets.iter().map(|(address, slots)| proof.account_proof(*address, slots)).collect()
+    }
 }
 
 /// Representation of in-memory hashed storage.
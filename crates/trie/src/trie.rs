@@ -17,7 +17,10 @@ use reth_primitives::{
     trie::{HashBuilder, Nibbles, TrieAccount},
     Address, BlockNumber, B256,
 };
-use std::ops::RangeInclusive;
+use std::{
+    ops::RangeInclusive,
+    time::{Duration, Instant},
+};
 use tracing::{debug, trace};
 
 #[cfg(feature = "metrics")]
@@ -36,6 +39,8 @@ pub struct StateRoot<T, H> {
     previous_state: Option<IntermediateStateRootState>,
     /// The number of updates after which the intermediate progress should be returned.
     threshold: u64,
+    /// The time after which the intermediate progress should be returned.
+    duration_threshold: Option<Duration>,
     #[cfg(feature = "metrics")]
     /// State root metrics.
     metrics: StateRootMetrics,
@@ -57,6 +62,14 @@ impl<T, H> StateRoot<T, H> {
     /// Set the threshold to maximum value so that intermediate progress is not returned.
     pub fn with_no_threshold(mut self) -> Self {
         self.threshold = u64::MAX;
+        self.duration_threshold = None;
+        self
+    }
+
+    /// Set the time after which the intermediate progress is returned, regardless of the number
+    /// of updates.
+    pub fn with_duration_threshold(mut self, duration_threshold: Option<Duration>) -> Self {
+        self.duration_threshold = duration_threshold;
         self
     }
 
@@ -73,6 +86,7 @@ impl<T, H> StateRoot<T, H> {
             hashed_cursor_factory,
             prefix_sets: self.prefix_sets,
             threshold: self.threshold,
+            duration_threshold: self.duration_threshold,
             previous_state: self.previous_state,
             #[cfg(feature = "metrics")]
             metrics: self.metrics,
@@ -86,6 +100,7 @@ impl<T, H> StateRoot<T, H> {
             hashed_cursor_factory: self.hashed_cursor_factory,
             prefix_sets: self.prefix_sets,
             threshold: self.threshold,
+            duration_threshold: self.duration_threshold,
             previous_state: self.previous_state,
             #[cfg(feature = "metrics")]
             metrics: self.metrics,
@@ -102,6 +117,7 @@ impl<'a, TX: DbTx> StateRoot<&'a TX, &'a TX> {
             prefix_sets: TriePrefixSets::default(),
             previous_state: None,
             threshold: 100_000,
+            duration_threshold: None,
             #[cfg(feature = "metrics")]
             metrics: StateRootMetrics::default(),
         }
@@ -211,6 +227,7 @@ where
 
     fn calculate(self, retain_updates: bool) -> Result<StateRootProgress, StateRootError> {
         trace!(target: "trie::state_root", "calculating state root");
+        let started_at = Instant::now();
         let mut tracker = TrieTracker::default();
         let mut trie_updates = TrieUpdates::default();
 
@@ -293,7 +310,10 @@ where
                     let total_updates_len = trie_updates.len() +
                         account_node_iter.walker.updates_len() +
                         hash_builder.updates_len();
-                    if retain_updates && total_updates_len as u64 >= self.threshold {
+                    let threshold_reached = total_updates_len as u64 >= self.threshold ||
+                        self.duration_threshold
+                            .is_some_and(|duration| started_at.elapsed() >= duration);
+                    if retain_updates && threshold_reached {
                         let (walker_stack, walker_updates) = account_node_iter.walker.split();
                         let (hash_builder, hash_builder_updates) = hash_builder.split();
 
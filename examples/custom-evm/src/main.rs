@@ -1,12 +1,17 @@
 //! This example shows how to implement a node with a custom EVM
+//!
+//! The custom EVM adds a precompile that is activated at a configurable block, set with the
+//! `MY_PRECOMPILE_ACTIVATION_BLOCK` environment variable. Since the EVM configuration of the node
+//! is used everywhere blocks or calls are executed, the precompile is available in the executed
+//! blocks, the built payloads and the RPC calls alike.
 
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
 use reth::{
-    builder::{node::NodeTypes, NodeBuilder},
+    builder::{NodeBuilder, WithEvmConfig},
     primitives::{
         address,
-        revm_primitives::{CfgEnvWithHandlerCfg, Env, PrecompileResult, TxEnv},
+        revm_primitives::{CfgEnvWithHandlerCfg, PrecompileError, PrecompileResult, TxEnv},
         Address, Bytes, U256,
     },
     revm::{
@@ -19,24 +24,38 @@ use reth::{
 };
 use reth_node_api::{ConfigureEvm, ConfigureEvmEnv};
 use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
-use reth_node_ethereum::{EthEngineTypes, EthEvmConfig, EthereumNode};
-use reth_primitives::{Chain, ChainSpec, Genesis, Header, Transaction};
+use reth_node_ethereum::{EthEvmConfig, EthereumNode};
+use reth_primitives::{BlockNumber, Chain, ChainSpec, Genesis, Header, Transaction};
 use reth_tracing::{RethTracer, Tracer};
 use std::sync::Arc;
 
+/// The address of the custom precompile.
+const MY_PRECOMPILE: Address = address!("0000000000000000000000000000000000000999");
+
+/// The gas cost of a call of the custom precompile.
+const MY_PRECOMPILE_GAS: u64 = 15;
+
 /// Custom EVM configuration
 #[derive(Debug, Clone, Copy, Default)]
 #[non_exhaustive]
-pub struct MyEvmConfig;
+pub struct MyEvmConfig {
+    /// The block at which the custom precompile is activated.
+    activation_block: BlockNumber,
+}
 
 impl MyEvmConfig {
+    /// Creates a new EVM configuration with the custom precompile activated at the given block.
+    pub const fn new(activation_block: BlockNumber) -> Self {
+        Self { activation_block }
+    }
+
     /// Sets the precompiles to the EVM handler
     ///
     /// This will be invoked when the EVM is created via [ConfigureEvm::evm] or
     /// [ConfigureEvm::evm_with_inspector]
     ///
     /// This will use the default mainnet precompiles and add additional precompiles.
-    pub fn set_precompiles<EXT, DB>(handler: &mut EvmHandler<EXT, DB>)
+    pub fn set_precompiles<EXT, DB>(self, handler: &mut EvmHandler<'_, EXT, DB>)
     where
         DB: Database,
     {
@@ -46,18 +65,30 @@ impl MyEvmConfig {
         // install the precompiles
         handler.pre_execution.load_precompiles = Arc::new(move || {
             let mut precompiles = Precompiles::new(PrecompileSpecId::from_spec_id(spec_id)).clone();
-            precompiles.inner.insert(
-                address!("0000000000000000000000000000000000000999"),
-                Precompile::Env(Self::my_precompile),
-            );
+            precompiles.inner.insert(MY_PRECOMPILE, Precompile::Standard(my_precompile));
             precompiles.into()
         });
+
+        // the block is only known once the precompiles are loaded, so the precompile is removed
+        // again before its activation, including the warm access of precompiles
+        let activation_block = self.activation_block;
+        let load_accounts = handler.pre_execution.load_accounts.clone();
+        handler.pre_execution.load_accounts = Arc::new(move |context| {
+            if context.evm.env.block.number < U256::from(activation_block) {
+                context.evm.precompiles.remove(&MY_PRECOMPILE);
+                context.evm.journaled_state.warm_preloaded_addresses.remove(&MY_PRECOMPILE);
+            }
+            load_accounts(context)
+        });
     }
+}
 
-    /// A custom precompile that does nothing
-    fn my_precompile(_data: &Bytes, _gas: u64, _env: &Env) -> PrecompileResult {
-        Ok((0, Bytes::new()))
+/// A custom precompile that returns its input.
+fn my_precompile(data: &Bytes, gas: u64) -> PrecompileResult {
+    if gas < MY_PRECOMPILE_GAS {
+        return Err(PrecompileError::OutOfGas)
     }
+    Ok((MY_PRECOMPILE_GAS, data.clone()))
 }
 
 impl ConfigureEvmEnv for MyEvmConfig {
@@ -82,10 +113,11 @@ impl ConfigureEvmEnv for MyEvmConfig {
 
 impl ConfigureEvm for MyEvmConfig {
     fn evm<'a, DB: Database + 'a>(&self, db: DB) -> Evm<'a, (), DB> {
+        let config = *self;
         EvmBuilder::default()
             .with_db(db)
             // add additional precompiles
-            .append_handler_register(MyEvmConfig::set_precompiles)
+            .append_handler_register_box(Box::new(move |handler| config.set_precompiles(handler)))
             .build()
     }
 
@@ -94,31 +126,17 @@ impl ConfigureEvm for MyEvmConfig {
         DB: Database + 'a,
         I: GetInspector<DB>,
     {
+        let config = *self;
         EvmBuilder::default()
             .with_db(db)
             .with_external_context(inspector)
             // add additional precompiles
-            .append_handler_register(MyEvmConfig::set_precompiles)
+            .append_handler_register_box(Box::new(move |handler| config.set_precompiles(handler)))
             .append_handler_register(inspector_handle_register)
             .build()
     }
 }
 
-#[derive(Debug, Clone, Default)]
-#[non_exhaustive]
-struct MyCustomNode;
-
-/// Configure the node types
-impl NodeTypes for MyCustomNode {
-    type Primitives = ();
-    type Engine = EthEngineTypes;
-    type Evm = MyEvmConfig;
-
-    fn evm_config(&self) -> Self::Evm {
-        Self::Evm::default()
-    }
-}
-
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     let _guard = RethTracer::new().init()?;
@@ -138,10 +156,18 @@ async fn main() -> eyre::Result<()> {
     let node_config =
         NodeConfig::test().with_rpc(RpcServerArgs::default().with_http()).with_chain(spec);
 
+    let activation_block = std::env::var("MY_PRECOMPILE_ACTIVATION_BLOCK")
+        .ok()
+        .map(|block| block.parse::<BlockNumber>())
+        .transpose()?
+        .unwrap_or(2);
+
+    // the ethereum node, with the custom evm in place of the default one
+    let node = WithEvmConfig::new(EthereumNode::default(), MyEvmConfig::new(activation_block));
+
     let handle = NodeBuilder::new(node_config)
         .testing_node(tasks.executor())
-        .with_types(MyCustomNode::default())
-        .with_components(EthereumNode::components())
+        .node(node)
         .launch()
         .await
         .unwrap();
@@ -150,3 +176,62 @@ async fn main() -> eyre::Result<()> {
 
     handle.node_exit_future.await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth::{
+        primitives::{
+            keccak256,
+            revm_primitives::{AccountInfo, Bytecode, ExecutionResult, TransactTo},
+        },
+        revm::db::{CacheDB, EmptyDB},
+    };
+
+    /// The address of a contract that calls the custom precompile without arguments.
+    const CALLER_CONTRACT: Address = address!("0000000000000000000000000000000000001000");
+
+    /// Executes a call of the given address at the given block.
+    fn call(to: Address, data: Bytes, block: u64) -> ExecutionResult {
+        // PUSH1 0 (x5), PUSH2 0x0999, GAS, CALL, STOP
+        let code = Bytes::from_static(&[
+            0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x61, 0x09, 0x99, 0x5a,
+            0xf1, 0x00,
+        ]);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            CALLER_CONTRACT,
+            AccountInfo {
+                code_hash: keccak256(&code),
+                code: Some(Bytecode::new_raw(code)),
+                ..Default::default()
+            },
+        );
+
+        let mut evm = MyEvmConfig::new(2).evm(db);
+        evm.block_mut().number = U256::from(block);
+        *evm.tx_mut() = TxEnv {
+            transact_to: TransactTo::Call(to),
+            data,
+            gas_limit: 100_000,
+            ..Default::default()
+        };
+        evm.transact().unwrap().result
+    }
+
+    #[test]
+    fn precompile_activated_at_block() {
+        let data = Bytes::from_static(&[1, 2, 3]);
+        assert_eq!(call(MY_PRECOMPILE, data.clone(), 1).output(), Some(&Bytes::new()));
+        assert_eq!(call(MY_PRECOMPILE, data.clone(), 2).output(), Some(&data));
+    }
+
+    #[test]
+    fn precompile_not_warm_before_activation() {
+        // a call of a cold account costs 2600 gas, a call of a warm precompile 100 gas plus the
+        // gas of the precompile
+        let before = call(CALLER_CONTRACT, Bytes::new(), 1).gas_used();
+        let after = call(CALLER_CONTRACT, Bytes::new(), 2).gas_used();
+        assert_eq!(before - after, 2600 - 100 - MY_PRECOMPILE_GAS);
+    }
+}
@@ -238,6 +238,7 @@ where
         let payload_job_config = BasicPayloadJobGeneratorConfig::default()
             .interval(conf.interval())
             .deadline(conf.deadline())
+            .rebuild_on_tx(conf.rebuild_on_tx())
             .max_payload_tasks(conf.max_payload_tasks())
             .extradata(conf.extradata_bytes())
             .max_gas_limit(conf.max_gas_limit());
@@ -276,7 +277,8 @@ where
         &self,
         args: BuildArguments<Pool, Client, Self::Attributes, Self::BuiltPayload>,
     ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
-        let BuildArguments { client, pool, cached_reads, config, cancel, best_payload } = args;
+        let BuildArguments { client, pool, cached_reads, config, cancel, best_payload, strategy } =
+            args;
         let PayloadConfig {
             initialized_block_env,
             initialized_cfg,
@@ -302,10 +304,12 @@ where
             },
             cancel,
             best_payload,
+            strategy,
         })
     }
 
     fn build_empty_payload(
+        &self,
         client: &Client,
         config: PayloadConfig<Self::Attributes>,
     ) -> Result<Self::BuiltPayload, PayloadBuilderError> {
@@ -317,8 +321,10 @@ where
             attributes,
             chain_spec,
         } = config;
-        <reth_ethereum_payload_builder::EthereumPayloadBuilder  as PayloadBuilder<Pool,Client>>  ::build_empty_payload(client,
-                                                                                                                       PayloadConfig { initialized_block_env, initialized_cfg, parent_block, extra_data, attributes: attributes.0, chain_spec }
+        <reth_ethereum_payload_builder::EthereumPayloadBuilder as PayloadBuilder<Pool, Client>>::build_empty_payload(
+            &reth_ethereum_payload_builder::EthereumPayloadBuilder::default(),
+            client,
+            PayloadConfig { initialized_block_env, initialized_cfg, parent_block, extra_data, attributes: attributes.0, chain_spec }
         )
     }
 }
@@ -46,6 +46,7 @@ where
         let payload_job_config = BasicPayloadJobGeneratorConfig::default()
             .interval(conf.interval())
             .deadline(conf.deadline())
+            .rebuild_on_tx(conf.rebuild_on_tx())
             .max_payload_tasks(conf.max_payload_tasks())
             .extradata(conf.extradata_bytes())
             .max_gas_limit(conf.max_gas_limit());
@@ -0,0 +1,83 @@
+//! Example of how to install a custom rpc namespace that is only enabled if it's selected.
+//!
+//! Run with
+//!
+//! ```not_rust
+//! cargo run -p custom-rpc-namespace -- node --http --http.api eth,myapp
+//! ```
+//!
+//! This installs an additional RPC method `myapp_blockTransactionCount` that can be queried via [cast](https://github.com/foundry-rs/foundry)
+//!
+//! ```sh
+//! cast rpc myapp_blockTransactionCount 1
+//! ```
+//!
+//! The namespace is not installed on the HTTP transport if `myapp` is not in `--http.api`, unless
+//! all modules are selected with `--http.api all`. It's always installed on IPC.
+
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::{error::INTERNAL_ERROR_CODE, ErrorObject},
+};
+use reth::{
+    cli::Cli, primitives::BlockNumber, providers::BlockReader, rpc::builder::RethRpcModule,
+};
+use reth_node_ethereum::EthereumNode;
+
+fn main() {
+    Cli::parse_args()
+        .run(|builder, _| async move {
+            let handle = builder
+                .node(EthereumNode::default())
+                .extend_rpc_modules(|ctx| {
+                    // the provider gives access to the database of the node
+                    let provider = ctx.provider().clone();
+                    let ext = MyAppExt { provider };
+
+                    // merge the namespace into the transports that select the `myapp` module
+                    ctx.modules.merge_if_module_configured(
+                        RethRpcModule::Other("myapp"),
+                        ext.into_rpc(),
+                    )?;
+
+                    Ok(())
+                })
+                .launch()
+                .await?;
+
+            handle.wait_for_node_exit().await
+        })
+        .unwrap();
+}
+
+/// trait interface for a custom rpc namespace: `myapp`
+///
+/// This defines an additional namespace where all methods are configured as trait functions.
+#[rpc(server, namespace = "myapp")]
+pub trait MyAppExtApi {
+    /// Returns the number of transactions in the block with the given number, or `None` if the
+    /// block is not known.
+    #[method(name = "blockTransactionCount")]
+    fn block_transaction_count(&self, number: BlockNumber) -> RpcResult<Option<u64>>;
+}
+
+/// The type that implements the `myapp` rpc namespace trait
+pub struct MyAppExt<Provider> {
+    provider: Provider,
+}
+
+impl<Provider> MyAppExtApiServer for MyAppExt<Provider>
+where
+    Provider: BlockReader + 'static,
+{
+    fn block_transaction_count(&self, number: BlockNumber) -> RpcResult<Option<u64>> {
+        let indices = self
+            .provider
+            .block_body_indices(number)
+            .map_err(|err| ErrorObject::owned(INTERNAL_ERROR_CODE, err.to_string(), None::<()>))?;
+        Ok(indices.map(|indices| indices.tx_count))
+    }
+}